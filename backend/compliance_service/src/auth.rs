@@ -0,0 +1,111 @@
+//! Caller authentication for officer-only and investor self-service endpoints.
+//!
+//! The main API issues HS256 bearer tokens signed with the shared JWT secret; this service
+//! verifies them and checks the role claim.
+
+use ethers::types::Address;
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Roles allowed to read and write compliance case records
+const OFFICER_ROLES: [&str; 3] = ["ComplianceOfficer", "Admin", "PlatformAdmin"];
+
+#[derive(Error, Debug, PartialEq)]
+pub enum AuthError {
+    #[error("Missing or invalid bearer token")]
+    Unauthenticated,
+    #[error("Role {0} may not access compliance records")]
+    Forbidden(String),
+}
+
+/// Claims this service reads from the main API's tokens
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallerClaims {
+    pub sub: String,
+    pub role: String,
+    pub exp: usize,
+}
+
+/// An authenticated compliance officer or admin
+#[derive(Debug, Clone, PartialEq)]
+pub struct Officer {
+    pub user_id: String,
+    pub role: String,
+}
+
+/// A signed-in wallet, for endpoints scoped to the caller's own records
+#[derive(Debug, Clone, PartialEq)]
+pub struct Investor {
+    pub wallet: Address,
+}
+
+fn verify(token: &str, secret: &str) -> Result<CallerClaims, AuthError> {
+    decode::<CallerClaims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &Validation::new(Algorithm::HS256),
+    )
+    .map(|data| data.claims)
+    .map_err(|_| AuthError::Unauthenticated)
+}
+
+/// Verify `token` against `secret` and require an officer or admin role
+pub fn authorize_officer(token: &str, secret: &str) -> Result<Officer, AuthError> {
+    let claims = verify(token, secret)?;
+
+    if !OFFICER_ROLES.contains(&claims.role.as_str()) {
+        return Err(AuthError::Forbidden(claims.role));
+    }
+    Ok(Officer { user_id: claims.sub, role: claims.role })
+}
+
+/// Verify `token` against `secret` and take the caller's wallet from its subject.
+///
+/// Wallet sign-in tokens carry the wallet address as `sub`; tokens for any other kind of
+/// subject cannot be scoped to a wallet and are refused.
+pub fn authorize_investor(token: &str, secret: &str) -> Result<Investor, AuthError> {
+    let claims = verify(token, secret)?;
+    let wallet = claims.sub.parse::<Address>().map_err(|_| AuthError::Unauthenticated)?;
+    Ok(Investor { wallet })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+
+    fn token(role: &str, secret: &str) -> String {
+        token_for("user-1", role, secret)
+    }
+
+    fn token_for(sub: &str, role: &str, secret: &str) -> String {
+        let claims = CallerClaims {
+            sub: sub.to_string(),
+            role: role.to_string(),
+            exp: (chrono::Utc::now().timestamp() + 600) as usize,
+        };
+        encode(&Header::new(Algorithm::HS256), &claims, &EncodingKey::from_secret(secret.as_bytes())).unwrap()
+    }
+
+    #[test]
+    fn test_only_officers_and_admins_are_authorized() {
+        let officer = authorize_officer(&token("ComplianceOfficer", "secret"), "secret").unwrap();
+        assert_eq!(officer.user_id, "user-1");
+        assert!(authorize_officer(&token("Admin", "secret"), "secret").is_ok());
+
+        assert_eq!(authorize_officer(&token("Investor", "secret"), "secret"), Err(AuthError::Forbidden("Investor".to_string())));
+        assert_eq!(authorize_officer(&token("Admin", "other"), "secret"), Err(AuthError::Unauthenticated));
+        assert_eq!(authorize_officer("not-a-token", "secret"), Err(AuthError::Unauthenticated));
+    }
+
+    #[test]
+    fn test_investors_are_scoped_to_their_token_wallet() {
+        let wallet = "0x00000000000000000000000000000000000000a1";
+        let investor = authorize_investor(&token_for(wallet, "Investor", "secret"), "secret").unwrap();
+        assert_eq!(investor.wallet, wallet.parse::<Address>().unwrap());
+
+        assert_eq!(authorize_investor(&token_for("user-1", "Investor", "secret"), "secret"), Err(AuthError::Unauthenticated));
+        assert_eq!(authorize_investor(&token_for(wallet, "Investor", "other"), "secret"), Err(AuthError::Unauthenticated));
+    }
+}
@@ -0,0 +1,263 @@
+//! Ultimate beneficial owners of institutional investors.
+//!
+//! Owners at or above the ownership threshold are recorded on the institution's profile and
+//! screened individually against the sanctions lists and the PEP provider. The institution
+//! stands as flagged as its worst owner; register changes only screen the owners they add.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use crate::sanctions::ScreeningResult;
+use crate::{ComplianceError, Violation, ViolationSeverity};
+
+/// Ownership at which an owner must be recorded and screened, unless configured otherwise
+pub const DEFAULT_OWNERSHIP_THRESHOLD_PERCENT: Decimal = dec!(25);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeneficialOwner {
+    pub full_name: String,
+    pub date_of_birth: NaiveDate,
+    /// ISO 3166-1 alpha-2 country code
+    pub nationality: String,
+    pub ownership_percent: Decimal,
+}
+
+impl BeneficialOwner {
+    /// Identifies the person across register updates; ownership and nationality may change
+    pub fn key(&self) -> String {
+        format!("{}:{}", self.full_name.trim().to_lowercase(), self.date_of_birth)
+    }
+}
+
+/// Check a submitted register before anything is screened or stored
+pub fn validate(owners: &[BeneficialOwner], today: NaiveDate) -> Result<(), ComplianceError> {
+    let mut keys = HashSet::new();
+    let mut total = Decimal::ZERO;
+    for owner in owners {
+        if owner.full_name.trim().is_empty() {
+            return Err(ComplianceError::InvalidInput("Beneficial owner name is required".to_string()));
+        }
+        if owner.nationality.len() != 2 || !owner.nationality.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(ComplianceError::InvalidInput(format!(
+                "Nationality of {} must be an ISO 3166-1 alpha-2 code", owner.full_name
+            )));
+        }
+        if owner.date_of_birth >= today {
+            return Err(ComplianceError::InvalidInput(format!("Date of birth of {} must be in the past", owner.full_name)));
+        }
+        if owner.ownership_percent <= Decimal::ZERO || owner.ownership_percent > dec!(100) {
+            return Err(ComplianceError::InvalidInput(format!(
+                "Ownership of {} must be above 0 and at most 100 percent", owner.full_name
+            )));
+        }
+        if !keys.insert(owner.key()) {
+            return Err(ComplianceError::InvalidInput(format!("{} is listed more than once", owner.full_name)));
+        }
+        total += owner.ownership_percent;
+    }
+    if total > dec!(100) {
+        return Err(ComplianceError::InvalidInput(format!("Ownership adds up to {} percent", total)));
+    }
+    Ok(())
+}
+
+/// Owners the institution must record, with nationality normalised
+pub fn reportable(owners: Vec<BeneficialOwner>, threshold: Decimal) -> Vec<BeneficialOwner> {
+    owners.into_iter()
+        .filter(|owner| owner.ownership_percent >= threshold)
+        .map(|owner| BeneficialOwner {
+            full_name: owner.full_name.trim().to_string(),
+            nationality: owner.nationality.to_uppercase(),
+            ..owner
+        })
+        .collect()
+}
+
+/// Outcome of screening one owner, ordered from least to most severe
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum UboStatus {
+    Clear,
+    Pep,
+    Sanctioned,
+}
+
+impl UboStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UboStatus::Clear => "clear",
+            UboStatus::Pep => "pep",
+            UboStatus::Sanctioned => "sanctioned",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "clear" => Some(UboStatus::Clear),
+            "pep" => Some(UboStatus::Pep),
+            "sanctioned" => Some(UboStatus::Sanctioned),
+            _ => None,
+        }
+    }
+}
+
+/// A recorded owner with the result of their last screening
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UboScreening {
+    #[serde(flatten)]
+    pub owner: BeneficialOwner,
+    pub status: UboStatus,
+    pub sanctions: ScreeningResult,
+    /// PEP provider details on a match
+    pub pep: Option<String>,
+    pub screened_at: DateTime<Utc>,
+}
+
+impl UboScreening {
+    pub fn new(owner: BeneficialOwner, sanctions: ScreeningResult, pep: Option<String>, screened_at: DateTime<Utc>) -> Self {
+        let status = if sanctions.is_sanctioned {
+            UboStatus::Sanctioned
+        } else if pep.is_some() {
+            UboStatus::Pep
+        } else {
+            UboStatus::Clear
+        };
+        Self { owner, status, sanctions, pep, screened_at }
+    }
+}
+
+/// The institution's standing across its owners; Clear without any
+pub fn worst(screenings: &[UboScreening]) -> UboStatus {
+    screenings.iter().map(|screening| screening.status).max().unwrap_or(UboStatus::Clear)
+}
+
+/// What a new register changes about the recorded one
+#[derive(Debug, Clone, Default)]
+pub struct RegisterChanges {
+    /// Not yet screened
+    pub added: Vec<BeneficialOwner>,
+    pub removed: Vec<UboScreening>,
+    /// Recorded owners whose ownership or nationality changed; their screening stands
+    pub updated: Vec<BeneficialOwner>,
+}
+
+impl RegisterChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.updated.is_empty()
+    }
+}
+
+pub fn diff(current: &[UboScreening], next: &[BeneficialOwner]) -> RegisterChanges {
+    let recorded: HashMap<String, &UboScreening> = current.iter()
+        .map(|screening| (screening.owner.key(), screening))
+        .collect();
+    let kept: HashSet<String> = next.iter().map(BeneficialOwner::key).collect();
+
+    let mut changes = RegisterChanges::default();
+    for owner in next {
+        match recorded.get(&owner.key()) {
+            None => changes.added.push(owner.clone()),
+            Some(screening) if screening.owner != *owner => changes.updated.push(owner.clone()),
+            Some(_) => {}
+        }
+    }
+    changes.removed = current.iter()
+        .filter(|screening| !kept.contains(&screening.owner.key()))
+        .cloned()
+        .collect();
+    changes
+}
+
+/// Violations a compliance check raises for the institution's owners
+pub fn violations(screenings: &[UboScreening]) -> Vec<Violation> {
+    screenings.iter()
+        .filter(|screening| screening.status == UboStatus::Sanctioned)
+        .map(|screening| Violation {
+            violation_type: "UBO_SANCTIONS_HIT".to_string(),
+            description: format!(
+                "Beneficial owner {} ({}%) found on sanctions list: {:?}",
+                screening.owner.full_name, screening.owner.ownership_percent, screening.sanctions.lists
+            ),
+            severity: ViolationSeverity::Critical,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owner(name: &str, ownership_percent: Decimal) -> BeneficialOwner {
+        BeneficialOwner {
+            full_name: name.to_string(),
+            date_of_birth: NaiveDate::from_ymd_opt(1970, 5, 17).unwrap(),
+            nationality: "gb".to_string(),
+            ownership_percent,
+        }
+    }
+
+    fn screened(owner: BeneficialOwner, sanctioned: bool, pep: Option<&str>) -> UboScreening {
+        let sanctions = ScreeningResult {
+            is_sanctioned: sanctioned,
+            lists: if sanctioned { vec!["UN".to_string()] } else { vec![] },
+            match_score: if sanctioned { 100.0 } else { 0.0 },
+            screened_at: Utc::now(),
+            details: None,
+        };
+        UboScreening::new(owner, sanctions, pep.map(str::to_string), Utc::now())
+    }
+
+    #[test]
+    fn test_clear_institution_takes_the_status_of_a_sanctioned_owner() {
+        let today = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let submitted = vec![
+            owner("Jane Holder", dec!(40)),
+            owner("UN Sanctioned Individual", dec!(30)),
+            owner("Minor Investor", dec!(10)),
+        ];
+        validate(&submitted, today).unwrap();
+
+        let owners = reportable(submitted, DEFAULT_OWNERSHIP_THRESHOLD_PERCENT);
+        assert_eq!(owners.len(), 2);
+        assert_eq!(owners[1].nationality, "GB");
+
+        let screenings = vec![
+            screened(owners[0].clone(), false, Some("Minister of finance")),
+            screened(owners[1].clone(), true, None),
+        ];
+        assert_eq!(worst(&screenings), UboStatus::Sanctioned);
+        assert_eq!(worst(&screenings[..1]), UboStatus::Pep);
+        assert_eq!(worst(&[]), UboStatus::Clear);
+
+        let found = violations(&screenings);
+        assert_eq!(found.len(), 1);
+        assert!(found[0].description.contains("UN Sanctioned Individual (30%)"));
+    }
+
+    #[test]
+    fn test_register_changes_screen_only_new_owners() {
+        let current = vec![
+            screened(owner("Jane Holder", dec!(40)), false, None),
+            screened(owner("Former Partner", dec!(30)), false, None),
+        ];
+        let next = vec![owner("jane holder ", dec!(45)), owner("New Partner", dec!(30))];
+
+        let changes = diff(&current, &next);
+        assert_eq!(changes.added, vec![owner("New Partner", dec!(30))]);
+        assert_eq!(changes.updated.len(), 1);
+        assert_eq!(changes.removed[0].owner.full_name, "Former Partner");
+        assert!(diff(&current, &current.iter().map(|s| s.owner.clone()).collect::<Vec<_>>()).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_registers_are_rejected() {
+        let today = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        assert!(validate(&[owner("A", dec!(60)), owner("B", dec!(50))], today).is_err());
+        assert!(validate(&[owner("A", dec!(30)), owner(" a", dec!(30))], today).is_err());
+        assert!(validate(&[owner("A", dec!(0))], today).is_err());
+        assert!(validate(&[BeneficialOwner { nationality: "GBR".to_string(), ..owner("A", dec!(30)) }], today).is_err());
+        assert!(validate(&[owner("A", dec!(30))], NaiveDate::from_ymd_opt(1960, 1, 1).unwrap()).is_err());
+    }
+}
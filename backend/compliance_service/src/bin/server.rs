@@ -2,24 +2,38 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 use axum::{
     extract::{Path, State, Query},
-    http::StatusCode,
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    middleware,
     response::{IntoResponse, Json},
-    routing::{get, post},
-    Router,
+    routing::{get, post, put},
+    Extension, Router,
 };
 use compliance_service::{
     ComplianceService, ComplianceReport, ComplianceCheck, InvestorProfile,
     config::Config,
     kyc::{KycParams, KycResult},
+    kyc_attempts::{KycRetryStatus, ManualReview, ReviewStatus, ReviewResolution},
     sanctions::ScreeningResult,
-    tax::{Transaction, TransactionType, TaxReport, Form1099},
+    tax::{Transaction, TransactionType, TaxReport, Form1099, ImportedLot, LotImportReport, parse_lots_csv},
+    passport::{SignedPassport, PassportVerification, PassportRevocation},
+    documents::{InvestorDocument, DocumentSubmission, DocumentReplacement, ExpiringDocument},
+    monitoring::{AmlAlert, AlertStatus, AlertResolution, MonitoringRun},
+    identity_registry::IdentitySyncRun,
+    offboarding::{Offboarding, OffboardingRequest, OffboardingBlocker},
+    communications::{Communication, NewCommunication, TimelineEntry, CaseDetail, CommunicationBody},
+    auth::{AuthError, Investor, Officer},
+    screening::{OverdueProfile, RescreeningRun},
+    self_service::{SelfServiceStatus, HistoryEntry},
+    beneficial_owners::{BeneficialOwner, UboScreening},
 };
 use ethers::types::Address;
+use quantera_service_auth::{require_service_key, PgServiceKeyStore, Scope, ServiceCaller, ServiceGuard, ServiceKeys};
+use quantera_types::{ErrorEnvelope, Pii};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tokio::net::TcpListener;
-use tracing::{info, error};
+use tracing::{info, warn, error};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 use uuid::Uuid;
 
@@ -35,13 +49,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .init();
     
     info!("Starting Compliance Service v2.0.0-alpha");
+    if !quantera_types::redaction_salt_from_env() {
+        warn!("{} is not set; redacted log fields are hashed without a salt", quantera_types::REDACTION_SALT_ENV);
+    }
     
     // Load configuration
-    let config = Config::from_env().map_err(|e| {
+    let mut config = Config::from_env().map_err(|e| {
         error!("Configuration error: {}", e);
         e
     })?;
     
+    // Keys and API credentials from SECRETS_PROVIDER override the environment
+    let secrets = quantera_secrets::SecretsConfig::from_env()?.build().await?;
+    config.load_secrets(&secrets).await.map_err(|e| {
+        error!("Failed to load secrets: {}", e);
+        e
+    })?;
+    
     config.validate().map_err(|e| {
         error!("Configuration validation failed: {}", e);
         e
@@ -64,18 +88,65 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .expect("Failed to initialize compliance service")
     );
     
+    // Nightly identity document expiry check
+    service.clone().spawn_document_expiry_job();
+    
+    // Scheduled AML transaction monitoring
+    service.clone().spawn_transaction_monitoring_job();
+    
+    // On-chain identity registry sync, when a registry is configured
+    service.clone().spawn_identity_registry_sync_job();
+    
+    // Nightly sanctions and PEP re-screening of due profiles
+    service.clone().spawn_rescreening_job();
+    
+    // Bulk screening also accepts other services' keys; callers without one need an officer token
+    let service_keys = Arc::new(ServiceKeys::new(Arc::new(PgServiceKeyStore::new(service.db_pool().as_ref().clone()))));
+    let screening_routes = Router::new()
+        .route("/api/v2/compliance/screening/run", post(run_rescreening))
+        .route_layer(middleware::from_fn_with_state(
+            ServiceGuard::new(service_keys, Scope::ComplianceScreen, false),
+            require_service_key,
+        ));
+    
     // Build router
     let app = Router::new()
         .route("/health", get(health_check))
         .route("/api/v2/compliance/check", post(perform_compliance_check))
         .route("/api/v2/compliance/kyc/verify", post(verify_kyc))
         .route("/api/v2/compliance/kyc/status/:id", get(check_kyc_status))
+        .route("/api/v2/compliance/kyc/attempts/:investor_id", get(get_kyc_retry_status))
+        .route("/api/v2/compliance/kyc/reviews", get(get_kyc_reviews))
+        .route("/api/v2/compliance/kyc/reviews/:id/resolve", post(resolve_kyc_review))
         .route("/api/v2/compliance/sanctions/screen", post(screen_sanctions))
         .route("/api/v2/compliance/tax/calculate", post(calculate_tax))
         .route("/api/v2/compliance/tax/1099/:address/:year", get(generate_1099))
+        .route("/api/v2/compliance/tax/lots/import", post(import_tax_lots))
         .route("/api/v2/compliance/documents/upload", post(upload_document))
+        .route("/api/v2/compliance/documents/expiring", get(get_expiring_documents))
+        .route("/api/v2/compliance/documents/:address", get(get_investor_documents).post(submit_investor_document))
         .route("/api/v2/compliance/profile", post(update_profile))
+        .route("/api/v2/compliance/aml/alerts", get(get_aml_alerts))
+        .route("/api/v2/compliance/aml/alerts/:id", get(get_aml_case))
+        .route("/api/v2/compliance/aml/alerts/:id/resolve", post(resolve_aml_alert))
+        .route("/api/v2/compliance/aml/monitoring/run", post(run_transaction_monitoring))
+        .route("/api/v2/compliance/identity-registry/sync", post(run_identity_registry_sync))
+        .route("/api/v2/compliance/screening/overdue", get(get_overdue_screenings))
+        .merge(screening_routes)
+        .route("/api/v2/compliance/investor/:address/offboard", post(offboard_investor))
+        .route("/api/v2/compliance/investor/:address/offboarding", get(get_offboarding))
+        .route("/api/v2/compliance/investor/:address/offboarding/complete", post(complete_offboarding))
+        .route("/api/v2/compliance/investor/:address/reonboard", post(reonboard_investor))
+        .route("/api/v2/compliance/investor/:address/beneficial-owners", get(get_beneficial_owners).put(update_beneficial_owners))
+        .route("/api/v2/compliance/investor/:address/communications", get(get_communications).post(record_communication))
+        .route("/api/v2/compliance/communications/:id/body", get(get_communication_body))
         .route("/api/v2/compliance/stats", get(get_stats))
+        .route("/api/v2/me/compliance/status", get(get_my_compliance_status))
+        .route("/api/v2/me/compliance/history", get(get_my_compliance_history))
+        .route("/api/v2/compliance/passport/verify", post(verify_passport))
+        .route("/api/v2/compliance/passport/revocations", get(get_passport_revocations))
+        .route("/api/v2/compliance/passport/:address", post(generate_passport))
+        .route("/api/v2/compliance/passport/:address/revoke", post(revoke_passports))
         .with_state(AppState { service });
     
     // Start server
@@ -171,6 +242,56 @@ async fn check_kyc_status(
     })))
 }
 
+async fn get_kyc_retry_status(
+    State(state): State<AppState>,
+    Path(investor_id): Path<String>,
+) -> Result<Json<KycRetryStatus>, ErrorResponse> {
+    let status = state.service
+        .kyc_retry_status(&investor_id)
+        .await
+        .map_err(|e| ErrorResponse::internal(format!("Failed to load KYC attempts: {}", e)))?;
+    
+    Ok(Json(status))
+}
+
+#[derive(Deserialize)]
+struct KycReviewsQuery {
+    status: Option<String>,
+}
+
+async fn get_kyc_reviews(
+    State(state): State<AppState>,
+    Query(query): Query<KycReviewsQuery>,
+) -> Result<Json<Vec<ManualReview>>, ErrorResponse> {
+    let status = query.status
+        .map(|s| s.parse::<ReviewStatus>())
+        .transpose()
+        .map_err(ErrorResponse::bad_request)?;
+    
+    let reviews = state.service
+        .get_kyc_reviews(status)
+        .await
+        .map_err(|e| ErrorResponse::internal(format!("Failed to load KYC reviews: {}", e)))?;
+    
+    Ok(Json(reviews))
+}
+
+async fn resolve_kyc_review(
+    State(state): State<AppState>,
+    Path(review_id): Path<Uuid>,
+    Json(resolution): Json<ReviewResolution>,
+) -> Result<Json<ManualReview>, ErrorResponse> {
+    let review = state.service
+        .resolve_kyc_review(review_id, resolution)
+        .await
+        .map_err(|e| match e {
+            compliance_service::ComplianceError::InvalidInput(msg) => ErrorResponse::bad_request(msg),
+            other => ErrorResponse::internal(format!("Failed to resolve KYC review: {}", other)),
+        })?;
+    
+    Ok(Json(review))
+}
+
 #[derive(Deserialize)]
 struct SanctionsScreenRequest {
     address: String,
@@ -252,6 +373,38 @@ async fn generate_1099(
     return Err(ErrorResponse::internal("Form generation service temporarily unavailable"))
 }
 
+#[derive(Deserialize)]
+struct TaxLotImportRequest {
+    investor_address: String,
+    /// Raw CSV with header `asset,quantity,acquisition_date,cost_basis,source`
+    csv: Option<String>,
+    lots: Option<Vec<ImportedLot>>,
+}
+
+async fn import_tax_lots(
+    State(state): State<AppState>,
+    Json(req): Json<TaxLotImportRequest>,
+) -> Result<Json<LotImportReport>, ErrorResponse> {
+    let investor = req.investor_address.parse::<Address>()
+        .map_err(|_| ErrorResponse::bad_request("Invalid investor address"))?;
+    
+    let lots = match (req.csv, req.lots) {
+        (Some(csv), None) => parse_lots_csv(&csv).map_err(|e| ErrorResponse::bad_request(e.to_string()))?,
+        (None, Some(lots)) => lots,
+        _ => return Err(ErrorResponse::bad_request("Provide exactly one of csv or lots")),
+    };
+    
+    let report = state.service
+        .import_tax_lots(investor, lots)
+        .await
+        .map_err(|e| match e {
+            compliance_service::ComplianceError::InvalidInput(msg) => ErrorResponse::bad_request(msg),
+            e => ErrorResponse::internal(format!("Tax lot import failed: {}", e)),
+        })?;
+    
+    Ok(Json(report))
+}
+
 #[derive(Deserialize)]
 struct DocumentUploadRequest {
     document_data: String, // Base64 encoded
@@ -277,6 +430,63 @@ async fn upload_document(
     })))
 }
 
+#[derive(Deserialize)]
+struct ExpiringDocumentsQuery {
+    within_days: Option<i64>,
+}
+
+async fn get_expiring_documents(
+    State(state): State<AppState>,
+    Query(query): Query<ExpiringDocumentsQuery>,
+) -> Result<Json<Vec<ExpiringDocument>>, ErrorResponse> {
+    let within_days = query.within_days.unwrap_or(60);
+    if within_days < 0 {
+        return Err(ErrorResponse::bad_request("within_days cannot be negative"));
+    }
+    
+    let documents = state.service
+        .get_expiring_documents(within_days)
+        .await
+        .map_err(|e| ErrorResponse::internal(format!("Failed to load expiring documents: {}", e)))?;
+    
+    Ok(Json(documents))
+}
+
+async fn get_investor_documents(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+) -> Result<Json<Vec<InvestorDocument>>, ErrorResponse> {
+    let investor = address.parse::<Address>()
+        .map_err(|_| ErrorResponse::bad_request("Invalid address"))?;
+    
+    let documents = state.service
+        .get_investor_documents(investor)
+        .await
+        .map_err(|e| ErrorResponse::internal(format!("Failed to load documents: {}", e)))?;
+    
+    Ok(Json(documents))
+}
+
+async fn submit_investor_document(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+    Json(submission): Json<DocumentSubmission>,
+) -> Result<Json<DocumentReplacement>, ErrorResponse> {
+    let investor = address.parse::<Address>()
+        .map_err(|_| ErrorResponse::bad_request("Invalid address"))?;
+    
+    let replacement = state.service
+        .submit_investor_document(investor, submission)
+        .await
+        .map_err(|e| match e {
+            compliance_service::ComplianceError::InvalidInput(msg) => ErrorResponse::bad_request(msg),
+            compliance_service::ComplianceError::KycVerificationFailed(msg) => ErrorResponse::bad_request(msg),
+            e => ErrorResponse::internal(format!("Document submission failed: {}", e)),
+        })?;
+    
+    Ok(Json(replacement))
+}
+
 async fn update_profile(
     State(state): State<AppState>,
     Json(profile): Json<InvestorProfile>,
@@ -304,11 +514,416 @@ async fn get_stats(
     Ok(Json(json!(stats)))
 }
 
+async fn generate_passport(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+) -> Result<Json<SignedPassport>, ErrorResponse> {
+    let investor = address.parse::<Address>()
+        .map_err(|_| ErrorResponse::bad_request("Invalid address"))?;
+    
+    let passport = state.service
+        .generate_compliance_passport(investor)
+        .await
+        .map_err(|e| match e {
+            compliance_service::ComplianceError::InvalidInput(msg) => ErrorResponse::bad_request(msg),
+            e => ErrorResponse::internal(format!("Passport generation failed: {}", e)),
+        })?;
+    
+    Ok(Json(passport))
+}
+
+async fn verify_passport(
+    State(state): State<AppState>,
+    Json(passport): Json<SignedPassport>,
+) -> Result<Json<PassportVerification>, ErrorResponse> {
+    let verification = state.service
+        .verify_compliance_passport(&passport)
+        .await;
+    
+    Ok(Json(verification))
+}
+
+async fn get_passport_revocations(
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, ErrorResponse> {
+    let revocations = state.service
+        .get_passport_revocations()
+        .await;
+    
+    Ok(Json(json!({
+        "signer": format!("{:?}", state.service.passport_signer_address()),
+        "revocations": revocations,
+        "generated_at": chrono::Utc::now()
+    })))
+}
+
+#[derive(Deserialize)]
+struct RevokePassportRequest {
+    reason: String,
+}
+
+async fn revoke_passports(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+    Json(req): Json<RevokePassportRequest>,
+) -> Result<Json<Vec<PassportRevocation>>, ErrorResponse> {
+    let investor = address.parse::<Address>()
+        .map_err(|_| ErrorResponse::bad_request("Invalid address"))?;
+    
+    let revocations = state.service
+        .revoke_compliance_passports(investor, &req.reason)
+        .await
+        .map_err(|e| ErrorResponse::internal(format!("Passport revocation failed: {}", e)))?;
+    
+    Ok(Json(revocations))
+}
+
+#[derive(Deserialize)]
+struct AmlAlertsQuery {
+    status: Option<String>,
+}
+
+async fn get_aml_alerts(
+    State(state): State<AppState>,
+    Query(query): Query<AmlAlertsQuery>,
+) -> Result<Json<Vec<AmlAlert>>, ErrorResponse> {
+    let status = query.status
+        .map(|s| s.parse::<AlertStatus>())
+        .transpose()
+        .map_err(ErrorResponse::bad_request)?;
+    
+    let alerts = state.service
+        .get_aml_alerts(status)
+        .await
+        .map_err(|e| ErrorResponse::internal(format!("Failed to load AML alerts: {}", e)))?;
+    
+    Ok(Json(alerts))
+}
+
+async fn resolve_aml_alert(
+    State(state): State<AppState>,
+    Path(alert_id): Path<Uuid>,
+    Json(resolution): Json<AlertResolution>,
+) -> Result<Json<AmlAlert>, ErrorResponse> {
+    let alert = state.service
+        .resolve_aml_alert(alert_id, resolution)
+        .await
+        .map_err(|e| match e {
+            compliance_service::ComplianceError::InvalidInput(msg) => ErrorResponse::bad_request(msg),
+            other => ErrorResponse::internal(format!("Failed to resolve AML alert: {}", other)),
+        })?;
+    
+    Ok(Json(alert))
+}
+
+async fn run_transaction_monitoring(
+    State(state): State<AppState>,
+) -> Result<Json<MonitoringRun>, ErrorResponse> {
+    let run = state.service
+        .run_transaction_monitoring(chrono::Utc::now())
+        .await
+        .map_err(|e| ErrorResponse::internal(format!("Transaction monitoring failed: {}", e)))?;
+    
+    Ok(Json(run))
+}
+
+async fn run_rescreening(
+    State(state): State<AppState>,
+    caller: Option<Extension<ServiceCaller>>,
+    headers: HeaderMap,
+) -> Result<Json<RescreeningRun>, ErrorResponse> {
+    let requested_by = match caller {
+        Some(Extension(caller)) => format!("service {}", caller.service),
+        None => format!("officer {}", officer(&state, &headers)?.user_id),
+    };
+    info!("Re-screening run requested by {}", requested_by);
+    
+    let run = state.service
+        .run_rescreening(chrono::Utc::now())
+        .await
+        .map_err(|e| ErrorResponse::internal(format!("Re-screening failed: {}", e)))?;
+    
+    Ok(Json(run))
+}
+
+async fn get_overdue_screenings(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<OverdueProfile>>, ErrorResponse> {
+    officer(&state, &headers)?;
+    
+    let overdue = state.service
+        .get_overdue_screenings(chrono::Utc::now())
+        .await
+        .map_err(|e| ErrorResponse::internal(format!("Failed to load overdue screenings: {}", e)))?;
+    
+    Ok(Json(overdue))
+}
+
+async fn run_identity_registry_sync(
+    State(state): State<AppState>,
+) -> Result<Json<IdentitySyncRun>, ErrorResponse> {
+    let run = state.service
+        .run_identity_registry_sync(chrono::Utc::now())
+        .await
+        .map_err(|e| ErrorResponse::internal(format!("Identity registry sync failed: {}", e)))?;
+    
+    Ok(Json(run))
+}
+
+async fn offboard_investor(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+    Json(req): Json<OffboardingRequest>,
+) -> Result<Json<Offboarding>, ErrorResponse> {
+    let investor = address.parse::<Address>()
+        .map_err(|_| ErrorResponse::bad_request("Invalid address"))?;
+    
+    let offboarding = state.service
+        .initiate_offboarding(investor, req)
+        .await
+        .map_err(offboarding_error)?;
+    
+    Ok(Json(offboarding))
+}
+
+async fn get_offboarding(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+) -> Result<Json<Offboarding>, ErrorResponse> {
+    let investor = address.parse::<Address>()
+        .map_err(|_| ErrorResponse::bad_request("Invalid address"))?;
+    
+    let offboarding = state.service
+        .get_offboarding(investor)
+        .await
+        .map_err(offboarding_error)?
+        .ok_or_else(|| ErrorResponse::not_found(format!("No offboarding for {:?}", investor)))?;
+    
+    Ok(Json(offboarding))
+}
+
+async fn complete_offboarding(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(address): Path<String>,
+) -> Result<Json<Offboarding>, ErrorResponse> {
+    let officer = officer(&state, &headers)?;
+    let investor = address.parse::<Address>()
+        .map_err(|_| ErrorResponse::bad_request("Invalid address"))?;
+    
+    let offboarding = state.service
+        .complete_offboarding(investor, &officer.user_id)
+        .await
+        .map_err(offboarding_error)?;
+    
+    Ok(Json(offboarding))
+}
+
+async fn reonboard_investor(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(address): Path<String>,
+) -> Result<StatusCode, ErrorResponse> {
+    let officer = officer(&state, &headers)?;
+    let investor = address.parse::<Address>()
+        .map_err(|_| ErrorResponse::bad_request("Invalid address"))?;
+    
+    state.service
+        .reonboard_investor(investor, &officer.user_id)
+        .await
+        .map_err(offboarding_error)?;
+    
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize)]
+struct BeneficialOwnersRequest {
+    /// The complete register; owners left out are removed
+    owners: Vec<BeneficialOwner>,
+}
+
+async fn get_beneficial_owners(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(address): Path<String>,
+) -> Result<Json<Vec<UboScreening>>, ErrorResponse> {
+    officer(&state, &headers)?;
+    let institution = address.parse::<Address>()
+        .map_err(|_| ErrorResponse::bad_request("Invalid address"))?;
+    
+    let owners = state.service
+        .get_beneficial_owners(institution)
+        .await
+        .map_err(|e| ErrorResponse::internal(format!("Failed to load beneficial owners: {}", e)))?;
+    
+    Ok(Json(owners))
+}
+
+async fn update_beneficial_owners(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(address): Path<String>,
+    Json(req): Json<BeneficialOwnersRequest>,
+) -> Result<Json<Vec<UboScreening>>, ErrorResponse> {
+    let officer = officer(&state, &headers)?;
+    let institution = address.parse::<Address>()
+        .map_err(|_| ErrorResponse::bad_request("Invalid address"))?;
+    
+    let owners = state.service
+        .update_beneficial_owners(institution, req.owners, &officer.user_id)
+        .await
+        .map_err(|e| match e {
+            compliance_service::ComplianceError::InvalidInput(msg) => ErrorResponse::bad_request(msg),
+            other => ErrorResponse::internal(format!("Failed to update beneficial owners: {}", other)),
+        })?;
+    
+    Ok(Json(owners))
+}
+
+fn offboarding_error(e: compliance_service::ComplianceError) -> ErrorResponse {
+    match e {
+        compliance_service::ComplianceError::InvalidInput(msg) => ErrorResponse::bad_request(msg),
+        compliance_service::ComplianceError::OffboardingBlocked(blockers) => ErrorResponse::offboarding_blocked(&blockers),
+        other => ErrorResponse::internal(format!("Offboarding failed: {}", other)),
+    }
+}
+
+fn bearer_token(headers: &HeaderMap) -> Result<&str, ErrorResponse> {
+    headers.get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or_else(|| ErrorResponse::unauthorized(AuthError::Unauthenticated.to_string()))
+}
+
+fn auth_error(e: AuthError) -> ErrorResponse {
+    match e {
+        AuthError::Unauthenticated => ErrorResponse::unauthorized(e.to_string()),
+        AuthError::Forbidden(_) => ErrorResponse::forbidden(e.to_string()),
+    }
+}
+
+/// Authenticated officer or admin making the request
+fn officer(state: &AppState, headers: &HeaderMap) -> Result<Officer, ErrorResponse> {
+    state.service.authorize_officer(bearer_token(headers)?).map_err(auth_error)
+}
+
+/// Signed-in wallet making the request; self-service routes take no address, so they
+/// can only ever return this wallet's records
+fn investor(state: &AppState, headers: &HeaderMap) -> Result<Investor, ErrorResponse> {
+    state.service.authorize_investor(bearer_token(headers)?).map_err(auth_error)
+}
+
+async fn get_my_compliance_status(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<SelfServiceStatus>, ErrorResponse> {
+    let investor = investor(&state, &headers)?;
+    
+    let status = state.service
+        .get_self_service_status(investor.wallet)
+        .await
+        .map_err(|e| {
+            error!(investor = %Pii::debug(investor.wallet), error = %e, "Failed to load compliance status");
+            ErrorResponse::internal("Failed to load compliance status")
+        })?;
+    
+    Ok(Json(status))
+}
+
+async fn get_my_compliance_history(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<HistoryEntry>>, ErrorResponse> {
+    let investor = investor(&state, &headers)?;
+    
+    let history = state.service
+        .get_self_service_history(investor.wallet)
+        .await
+        .map_err(|e| {
+            error!(investor = %Pii::debug(investor.wallet), error = %e, "Failed to load compliance history");
+            ErrorResponse::internal("Failed to load compliance history")
+        })?;
+    
+    Ok(Json(history))
+}
+
+async fn get_aml_case(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(alert_id): Path<Uuid>,
+) -> Result<Json<CaseDetail>, ErrorResponse> {
+    officer(&state, &headers)?;
+    
+    let case = state.service
+        .get_aml_case(alert_id)
+        .await
+        .map_err(|e| ErrorResponse::internal(format!("Failed to load AML case: {}", e)))?
+        .ok_or_else(|| ErrorResponse::not_found(format!("No AML case {}", alert_id)))?;
+    
+    Ok(Json(case))
+}
+
+async fn get_communications(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(address): Path<String>,
+) -> Result<Json<Vec<TimelineEntry>>, ErrorResponse> {
+    officer(&state, &headers)?;
+    let investor = address.parse::<Address>()
+        .map_err(|_| ErrorResponse::bad_request("Invalid address"))?;
+    
+    let entries = state.service
+        .get_communications(investor)
+        .await
+        .map_err(|e| ErrorResponse::internal(format!("Failed to load communications: {}", e)))?;
+    
+    Ok(Json(entries))
+}
+
+async fn record_communication(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(address): Path<String>,
+    Json(entry): Json<NewCommunication>,
+) -> Result<Json<Communication>, ErrorResponse> {
+    let author = officer(&state, &headers)?;
+    let investor = address.parse::<Address>()
+        .map_err(|_| ErrorResponse::bad_request("Invalid address"))?;
+    
+    let communication = state.service
+        .record_communication(investor, &author, entry)
+        .await
+        .map_err(|e| match e {
+            compliance_service::ComplianceError::InvalidInput(msg) => ErrorResponse::bad_request(msg),
+            other => ErrorResponse::internal(format!("Failed to record communication: {}", other)),
+        })?;
+    
+    Ok(Json(communication))
+}
+
+async fn get_communication_body(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(communication_id): Path<Uuid>,
+) -> Result<Json<CommunicationBody>, ErrorResponse> {
+    let reader = officer(&state, &headers)?;
+    
+    let body = state.service
+        .get_communication_body(communication_id, &reader)
+        .await
+        .map_err(|e| ErrorResponse::internal(format!("Failed to retrieve communication body: {}", e)))?
+        .ok_or_else(|| ErrorResponse::not_found(format!("No body for communication {}", communication_id)))?;
+    
+    Ok(Json(body))
+}
+
 // ============ Error Handling ============
 
 struct ErrorResponse {
     code: StatusCode,
     message: String,
+    details: Option<serde_json::Value>,
 }
 
 impl ErrorResponse {
@@ -316,6 +931,40 @@ impl ErrorResponse {
         Self {
             code: StatusCode::BAD_REQUEST,
             message: msg.into(),
+            details: None,
+        }
+    }
+    
+    fn unauthorized(msg: impl Into<String>) -> Self {
+        Self {
+            code: StatusCode::UNAUTHORIZED,
+            message: msg.into(),
+            details: None,
+        }
+    }
+    
+    fn forbidden(msg: impl Into<String>) -> Self {
+        Self {
+            code: StatusCode::FORBIDDEN,
+            message: msg.into(),
+            details: None,
+        }
+    }
+    
+    fn not_found(msg: impl Into<String>) -> Self {
+        Self {
+            code: StatusCode::NOT_FOUND,
+            message: msg.into(),
+            details: None,
+        }
+    }
+    
+    /// Offboarding refused, listing everything still open
+    fn offboarding_blocked(blockers: &[OffboardingBlocker]) -> Self {
+        Self {
+            code: StatusCode::CONFLICT,
+            message: format!("Offboarding is blocked by {} open items", blockers.len()),
+            details: Some(json!({ "blockers": blockers })),
         }
     }
     
@@ -323,18 +972,16 @@ impl ErrorResponse {
         Self {
             code: StatusCode::INTERNAL_SERVER_ERROR,
             message: msg.into(),
+            details: None,
         }
     }
 }
 
 impl IntoResponse for ErrorResponse {
     fn into_response(self) -> axum::response::Response {
-        (
-            self.code,
-            Json(json!({
-                "error": self.message,
-                "timestamp": chrono::Utc::now()
-            }))
-        ).into_response()
+        // Existing clients read the message from `error`, so it is kept there as well
+        let mut envelope = ErrorEnvelope::new(&self.message, &self.message, self.code.as_u16());
+        envelope.details = self.details;
+        (self.code, Json(envelope)).into_response()
     }
 }
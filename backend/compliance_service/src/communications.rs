@@ -0,0 +1,293 @@
+use ethers::types::Address;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use uuid::Uuid;
+use crate::monitoring::AmlAlert;
+
+/// Longest body accepted for one entry
+pub const MAX_BODY_BYTES: usize = 64 * 1024;
+
+const MAX_SUBJECT_CHARS: usize = 200;
+
+// ============ Entries ============
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Channel {
+    Email,
+    Phone,
+    Letter,
+    Portal,
+    Meeting,
+    /// Written by the service itself; not available to officers
+    System,
+}
+
+impl Channel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Channel::Email => "email",
+            Channel::Phone => "phone",
+            Channel::Letter => "letter",
+            Channel::Portal => "portal",
+            Channel::Meeting => "meeting",
+            Channel::System => "system",
+        }
+    }
+}
+
+impl std::str::FromStr for Channel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "email" => Ok(Channel::Email),
+            "phone" => Ok(Channel::Phone),
+            "letter" => Ok(Channel::Letter),
+            "portal" => Ok(Channel::Portal),
+            "meeting" => Ok(Channel::Meeting),
+            "system" => Ok(Channel::System),
+            other => Err(format!("Unknown communication channel: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Inbound,
+    Outbound,
+    /// A note for the file rather than contact with the investor
+    Internal,
+}
+
+impl Direction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Inbound => "inbound",
+            Direction::Outbound => "outbound",
+            Direction::Internal => "internal",
+        }
+    }
+}
+
+impl std::str::FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "inbound" => Ok(Direction::Inbound),
+            "outbound" => Ok(Direction::Outbound),
+            "internal" => Ok(Direction::Internal),
+            other => Err(format!("Unknown communication direction: {}", other)),
+        }
+    }
+}
+
+/// One immutable entry in an investor's communication log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Communication {
+    pub communication_id: Uuid,
+    pub investor: Address,
+    /// AML case the entry belongs to
+    pub case_id: Option<Uuid>,
+    pub channel: Channel,
+    pub direction: Direction,
+    pub subject: String,
+    /// Encrypted body on IPFS; None for system entries
+    pub body_ipfs_hash: Option<String>,
+    pub author: String,
+    /// Entry this one corrects
+    pub corrects: Option<Uuid>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Entry submitted by an officer; the author is the authenticated caller
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewCommunication {
+    pub case_id: Option<Uuid>,
+    pub channel: Channel,
+    pub direction: Direction,
+    pub subject: String,
+    pub body: String,
+    pub corrects: Option<Uuid>,
+}
+
+impl NewCommunication {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.channel == Channel::System {
+            return Err("The system channel is reserved for automatic entries".to_string());
+        }
+        if self.subject.trim().is_empty() || self.subject.chars().count() > MAX_SUBJECT_CHARS {
+            return Err(format!("Subject must be 1 to {} characters", MAX_SUBJECT_CHARS));
+        }
+        if self.body.trim().is_empty() || self.body.len() > MAX_BODY_BYTES {
+            return Err(format!("Body must be 1 to {} bytes", MAX_BODY_BYTES));
+        }
+        Ok(())
+    }
+}
+
+/// Check that the case and corrected entry an entry points to belong to `investor`.
+///
+/// Entries are never edited, so a correction must reference an existing entry of the same
+/// investor; it may not move the record onto another investor's file.
+pub fn check_links(
+    investor: Address,
+    entry: &NewCommunication,
+    case: Option<&AmlAlert>,
+    original: Option<&Communication>,
+) -> Result<(), String> {
+    match (entry.case_id, case) {
+        (Some(case_id), None) => return Err(format!("No AML case {}", case_id)),
+        (Some(_), Some(case)) if case.investor != investor => {
+            return Err(format!("AML case {} belongs to another investor", case.alert_id));
+        }
+        _ => {}
+    }
+    match (entry.corrects, original) {
+        (Some(id), None) => Err(format!("No communication {} to correct", id)),
+        (Some(_), Some(original)) if original.investor != investor => {
+            Err(format!("Communication {} belongs to another investor", original.communication_id))
+        }
+        _ => Ok(()),
+    }
+}
+
+// ============ Timeline ============
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    #[serde(flatten)]
+    pub communication: Communication,
+    /// Later entries correcting this one
+    pub corrected_by: Vec<Uuid>,
+}
+
+/// Entries oldest first, each listing the entries that correct it
+pub fn timeline(mut entries: Vec<Communication>) -> Vec<TimelineEntry> {
+    entries.sort_by_key(|entry| entry.recorded_at);
+
+    let mut corrections: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for entry in &entries {
+        if let Some(original) = entry.corrects {
+            corrections.entry(original).or_default().push(entry.communication_id);
+        }
+    }
+
+    entries.into_iter()
+        .map(|communication| TimelineEntry {
+            corrected_by: corrections.remove(&communication.communication_id).unwrap_or_default(),
+            communication,
+        })
+        .collect()
+}
+
+/// An AML case with its communication timeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaseDetail {
+    pub alert: AmlAlert,
+    pub communications: Vec<TimelineEntry>,
+}
+
+/// A decrypted entry body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommunicationBody {
+    pub communication_id: Uuid,
+    pub body: String,
+    /// Gateway the encrypted body was retrieved from
+    pub served_by: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use rust_decimal::Decimal;
+    use crate::monitoring::{AlertStatus, AmlRule};
+
+    fn entry(corrects: Option<Uuid>, case_id: Option<Uuid>) -> NewCommunication {
+        NewCommunication {
+            case_id,
+            channel: Channel::Email,
+            direction: Direction::Outbound,
+            subject: "Requested updated proof of address".to_string(),
+            body: "Emailed client requesting updated proof of address".to_string(),
+            corrects,
+        }
+    }
+
+    fn recorded(investor: Address, at: DateTime<Utc>, corrects: Option<Uuid>) -> Communication {
+        Communication {
+            communication_id: Uuid::new_v4(),
+            investor,
+            case_id: None,
+            channel: Channel::Email,
+            direction: Direction::Outbound,
+            subject: "Requested updated proof of address".to_string(),
+            body_ipfs_hash: Some("bafkreibeataq6c3dvlxkkv35gusz2sd4orl5y56mt2fh2b7o6e5f2guoka".to_string()),
+            author: "officer-1".to_string(),
+            corrects,
+            recorded_at: at,
+        }
+    }
+
+    fn case(investor: Address) -> AmlAlert {
+        let now = Utc::now();
+        AmlAlert {
+            alert_id: Uuid::new_v4(),
+            investor,
+            jurisdiction: "US".to_string(),
+            rule: AmlRule::Structuring,
+            severity: AmlRule::Structuring.severity(),
+            status: AlertStatus::Open,
+            description: "Structured deposits".to_string(),
+            transaction_ids: Vec::new(),
+            total_amount: Decimal::ZERO,
+            window_start: now - Duration::days(3),
+            window_end: now,
+            created_at: now,
+            resolved_by: None,
+            resolution_notes: None,
+            resolved_at: None,
+        }
+    }
+
+    #[test]
+    fn test_corrections_append_and_leave_original_intact() {
+        let investor = Address::repeat_byte(0x11);
+        let now = Utc::now();
+        let original = recorded(investor, now - Duration::hours(2), None);
+        let correction = recorded(investor, now, Some(original.communication_id));
+
+        assert!(check_links(investor, &entry(Some(original.communication_id), None), None, Some(&original)).is_ok());
+        // A correction cannot reach into another investor's file or an entry that does not exist
+        let other = Address::repeat_byte(0x22);
+        assert!(check_links(other, &entry(Some(original.communication_id), None), None, Some(&original)).is_err());
+        assert!(check_links(investor, &entry(Some(Uuid::new_v4()), None), None, None).is_err());
+
+        let timeline = timeline(vec![correction.clone(), original.clone()]);
+        assert_eq!(timeline.len(), 2);
+        assert_eq!(timeline[0].communication.communication_id, original.communication_id);
+        assert_eq!(timeline[0].communication.subject, original.subject);
+        assert_eq!(timeline[0].corrected_by, vec![correction.communication_id]);
+        assert!(timeline[1].corrected_by.is_empty());
+    }
+
+    #[test]
+    fn test_case_linkage_requires_the_investors_own_case() {
+        let investor = Address::repeat_byte(0x11);
+        let own = case(investor);
+        let foreign = case(Address::repeat_byte(0x22));
+
+        assert!(check_links(investor, &entry(None, Some(own.alert_id)), Some(&own), None).is_ok());
+        assert!(check_links(investor, &entry(None, Some(foreign.alert_id)), Some(&foreign), None).is_err());
+        assert!(check_links(investor, &entry(None, Some(Uuid::new_v4())), None, None).is_err());
+
+        let mut system = entry(None, Some(own.alert_id));
+        system.channel = Channel::System;
+        assert!(system.validate().is_err());
+        assert!(entry(None, None).validate().is_ok());
+    }
+}
@@ -1,3 +1,5 @@
+use quantera_secrets::{SecretRef, SecretStore};
+use rust_decimal::Decimal;
 use serde::Deserialize;
 use std::env;
 use thiserror::Error;
@@ -24,21 +26,67 @@ pub struct Config {
     pub jumio_api_key: Option<String>,
     pub jumio_api_secret: Option<String>,
     pub onfido_api_token: Option<String>,
+    /// Onfido API base, e.g. a regional endpoint or a test fixture; defaults to the public API
+    pub onfido_api_url: Option<String>,
+    pub kyc_max_attempts_per_provider: u32,
+    pub kyc_attempt_window_days: i64,
+    pub kyc_retry_cooldown_hours: i64,
     
     // Sanctions APIs
     pub ofac_api_key: Option<String>,
     pub un_sanctions_api_key: Option<String>,
     
+    // Periodic re-screening
+    pub pep_screening_api_url: Option<String>,
+    pub pep_screening_api_key: Option<String>,
+    pub rescreening_batch_size: usize,
+    /// Provider calls per minute across a re-screening run
+    pub rescreening_rate_per_minute: u32,
+    /// Ownership percentage at which an institution's beneficial owners are recorded and screened
+    pub ubo_ownership_threshold_percent: Decimal,
+    
     // IPFS
     pub ipfs_api_url: String,
+    /// Fallback gateways tried in order after the API node when retrieving documents
+    pub ipfs_gateway_urls: Vec<String>,
+    pub ipfs_gateway_timeout_secs: u64,
+    /// Pinning Service API endpoint holding a replica of every upload
+    pub ipfs_replica_pin_url: Option<String>,
+    pub ipfs_replica_pin_token: Option<String>,
     pub encryption_key: Vec<u8>,
     
     // Service
     pub http_port: u16,
+    /// Secret the main API signs bearer tokens with; officer endpoints reject every caller when unset
+    pub jwt_secret: Option<String>,
     pub log_level: String,
     
     // Tax
     pub tax_api_key: Option<String>,
+    
+    // Compliance passports
+    pub passport_signing_key: String,
+    pub passport_validity_days: i64,
+    
+    // Identity documents
+    pub document_reinstate_within_days: i64,
+    
+    // Transaction monitoring
+    pub aml_rules_path: Option<String>,
+    pub aml_monitoring_interval_secs: u64,
+    
+    // Identity registry sync
+    pub identity_registry_address: Option<String>,
+    pub identity_registry_signer_key: Option<String>,
+    pub identity_sync_interval_secs: u64,
+    pub identity_sync_batch_size: usize,
+    
+    // Offboarding
+    /// Service reporting an investor's open orders, trades, margin calls and holdings
+    pub position_service_url: Option<String>,
+    pub position_service_api_key: Option<String>,
+    /// Years an offboarded investor's documents are held
+    pub document_retention_years: u32,
 }
 
 impl Config {
@@ -60,6 +108,13 @@ impl Config {
             return Err(ConfigError::Invalid("Encryption key must be 32 bytes".to_string()));
         }
         
+        // Passports signed with a generated key cannot be verified after a restart
+        let passport_signing_key = env::var("PASSPORT_SIGNING_KEY")
+            .unwrap_or_else(|_| {
+                tracing::warn!("PASSPORT_SIGNING_KEY not set, generating an ephemeral signing key");
+                hex::encode(generate_encryption_key())
+            });
+        
         Ok(Self {
             database_url: env::var("DATABASE_URL")
                 .map_err(|_| ConfigError::NotFound("DATABASE_URL".to_string()))?,
@@ -73,24 +128,138 @@ impl Config {
             jumio_api_key: env::var("JUMIO_API_KEY").ok(),
             jumio_api_secret: env::var("JUMIO_API_SECRET").ok(),
             onfido_api_token: env::var("ONFIDO_API_TOKEN").ok(),
+            onfido_api_url: env::var("ONFIDO_API_URL").ok().filter(|url| !url.is_empty()),
+            kyc_max_attempts_per_provider: env::var("KYC_MAX_ATTEMPTS_PER_PROVIDER")
+                .unwrap_or_else(|_| "2".to_string())
+                .parse()
+                .map_err(|_| ConfigError::Invalid("Invalid KYC_MAX_ATTEMPTS_PER_PROVIDER".to_string()))?,
+            kyc_attempt_window_days: env::var("KYC_ATTEMPT_WINDOW_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .map_err(|_| ConfigError::Invalid("Invalid KYC_ATTEMPT_WINDOW_DAYS".to_string()))?,
+            kyc_retry_cooldown_hours: env::var("KYC_RETRY_COOLDOWN_HOURS")
+                .unwrap_or_else(|_| "24".to_string())
+                .parse()
+                .map_err(|_| ConfigError::Invalid("Invalid KYC_RETRY_COOLDOWN_HOURS".to_string()))?,
             
             ofac_api_key: env::var("OFAC_API_KEY").ok(),
             un_sanctions_api_key: env::var("UN_SANCTIONS_API_KEY").ok(),
             
+            pep_screening_api_url: env::var("PEP_SCREENING_API_URL").ok().filter(|url| !url.is_empty()),
+            pep_screening_api_key: env::var("PEP_SCREENING_API_KEY").ok(),
+            rescreening_batch_size: env::var("RESCREENING_BATCH_SIZE")
+                .unwrap_or_else(|_| "500".to_string())
+                .parse()
+                .map_err(|_| ConfigError::Invalid("Invalid RESCREENING_BATCH_SIZE".to_string()))?,
+            rescreening_rate_per_minute: env::var("RESCREENING_RATE_PER_MINUTE")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .map_err(|_| ConfigError::Invalid("Invalid RESCREENING_RATE_PER_MINUTE".to_string()))?,
+            ubo_ownership_threshold_percent: env::var("UBO_OWNERSHIP_THRESHOLD_PERCENT")
+                .unwrap_or_else(|_| "25".to_string())
+                .parse()
+                .map_err(|_| ConfigError::Invalid("Invalid UBO_OWNERSHIP_THRESHOLD_PERCENT".to_string()))?,
+            
             ipfs_api_url: env::var("IPFS_API_URL")
                 .unwrap_or_else(|_| "http://localhost:5001".to_string()),
+            ipfs_gateway_urls: env::var("IPFS_GATEWAY_URLS")
+                .unwrap_or_default()
+                .split(',')
+                .map(|url| url.trim().to_string())
+                .filter(|url| !url.is_empty())
+                .collect(),
+            ipfs_gateway_timeout_secs: env::var("IPFS_GATEWAY_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .map_err(|_| ConfigError::Invalid("Invalid IPFS_GATEWAY_TIMEOUT_SECS".to_string()))?,
+            ipfs_replica_pin_url: env::var("IPFS_REPLICA_PIN_URL").ok().filter(|url| !url.is_empty()),
+            ipfs_replica_pin_token: env::var("IPFS_REPLICA_PIN_TOKEN").ok(),
             encryption_key,
             
             http_port: env::var("HTTP_PORT")
                 .unwrap_or_else(|_| "8002".to_string())
                 .parse()
                 .map_err(|_| ConfigError::Invalid("Invalid HTTP_PORT".to_string()))?,
+            jwt_secret: env::var("JWT_SECRET").ok().filter(|secret| !secret.is_empty()),
             log_level: env::var("LOG_LEVEL").unwrap_or_else(|_| "info".to_string()),
             
             tax_api_key: env::var("TAX_API_KEY").ok(),
+            
+            passport_signing_key,
+            passport_validity_days: env::var("PASSPORT_VALIDITY_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .map_err(|_| ConfigError::Invalid("Invalid PASSPORT_VALIDITY_DAYS".to_string()))?,
+            
+            document_reinstate_within_days: env::var("DOCUMENT_REINSTATE_WITHIN_DAYS")
+                .unwrap_or_else(|_| "90".to_string())
+                .parse()
+                .map_err(|_| ConfigError::Invalid("Invalid DOCUMENT_REINSTATE_WITHIN_DAYS".to_string()))?,
+            
+            aml_rules_path: env::var("AML_RULES_PATH").ok().filter(|path| !path.is_empty()),
+            aml_monitoring_interval_secs: env::var("AML_MONITORING_INTERVAL_SECS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .map_err(|_| ConfigError::Invalid("Invalid AML_MONITORING_INTERVAL_SECS".to_string()))?,
+            
+            identity_registry_address: env::var("IDENTITY_REGISTRY_ADDRESS").ok().filter(|v| !v.is_empty()),
+            identity_registry_signer_key: env::var("IDENTITY_REGISTRY_SIGNER_KEY").ok().filter(|v| !v.is_empty()),
+            identity_sync_interval_secs: env::var("IDENTITY_SYNC_INTERVAL_SECS")
+                .unwrap_or_else(|_| "900".to_string())
+                .parse()
+                .map_err(|_| ConfigError::Invalid("Invalid IDENTITY_SYNC_INTERVAL_SECS".to_string()))?,
+            identity_sync_batch_size: env::var("IDENTITY_SYNC_BATCH_SIZE")
+                .unwrap_or_else(|_| "25".to_string())
+                .parse()
+                .map_err(|_| ConfigError::Invalid("Invalid IDENTITY_SYNC_BATCH_SIZE".to_string()))?,
+            
+            position_service_url: env::var("POSITION_SERVICE_URL").ok().filter(|url| !url.is_empty()),
+            position_service_api_key: env::var("POSITION_SERVICE_API_KEY").ok(),
+            document_retention_years: env::var("DOCUMENT_RETENTION_YEARS")
+                .unwrap_or_else(|_| "7".to_string())
+                .parse()
+                .map_err(|_| ConfigError::Invalid("Invalid DOCUMENT_RETENTION_YEARS".to_string()))?,
         })
     }
     
+    /// Replace credentials with the values held by the secrets provider. Secrets the
+    /// provider does not have keep their environment values.
+    pub async fn load_secrets(&mut self, secrets: &SecretStore) -> Result<(), ConfigError> {
+        let lookup = |name: &'static str| async move {
+            secrets.get_optional(&SecretRef::new(name)).await
+                .map(|value| value.map(|v| v.expose().to_string()))
+                .map_err(|e| ConfigError::Invalid(format!("{}: {}", name, e)))
+        };
+        
+        if let Some(key) = lookup("ENCRYPTION_KEY").await? {
+            let key = hex::decode(&key)
+                .map_err(|e| ConfigError::Invalid(format!("Invalid encryption key: {}", e)))?;
+            if key.len() != 32 {
+                return Err(ConfigError::Invalid("Encryption key must be 32 bytes".to_string()));
+            }
+            self.encryption_key = key;
+        }
+        if let Some(key) = lookup("PASSPORT_SIGNING_KEY").await? {
+            self.passport_signing_key = key;
+        }
+        
+        for (name, field) in [
+            ("JUMIO_API_KEY", &mut self.jumio_api_key),
+            ("JUMIO_API_SECRET", &mut self.jumio_api_secret),
+            ("ONFIDO_API_TOKEN", &mut self.onfido_api_token),
+            ("OFAC_API_KEY", &mut self.ofac_api_key),
+            ("PEP_SCREENING_API_KEY", &mut self.pep_screening_api_key),
+            ("JWT_SECRET", &mut self.jwt_secret),
+            ("IDENTITY_REGISTRY_SIGNER_KEY", &mut self.identity_registry_signer_key),
+            ("POSITION_SERVICE_API_KEY", &mut self.position_service_api_key),
+        ] {
+            if let Some(value) = lookup(name).await? {
+                *field = Some(value);
+            }
+        }
+        Ok(())
+    }
+    
     pub fn validate(&self) -> Result<(), ConfigError> {
         if self.database_url.is_empty() {
             return Err(ConfigError::Invalid("DATABASE_URL is empty".to_string()));
@@ -104,6 +273,82 @@ impl Config {
             return Err(ConfigError::Invalid("Invalid COMPLIANCE_ENGINE_ADDRESS".to_string()));
         }
         
+        if self.passport_validity_days <= 0 {
+            return Err(ConfigError::Invalid("PASSPORT_VALIDITY_DAYS must be positive".to_string()));
+        }
+        
+        if self.kyc_max_attempts_per_provider == 0 {
+            return Err(ConfigError::Invalid("KYC_MAX_ATTEMPTS_PER_PROVIDER must be at least 1".to_string()));
+        }
+        
+        if self.kyc_attempt_window_days <= 0 {
+            return Err(ConfigError::Invalid("KYC_ATTEMPT_WINDOW_DAYS must be positive".to_string()));
+        }
+        
+        if self.kyc_retry_cooldown_hours < 0 {
+            return Err(ConfigError::Invalid("KYC_RETRY_COOLDOWN_HOURS cannot be negative".to_string()));
+        }
+        
+        if self.ipfs_gateway_timeout_secs == 0 {
+            return Err(ConfigError::Invalid("IPFS_GATEWAY_TIMEOUT_SECS must be positive".to_string()));
+        }
+        
+        if self.ipfs_replica_pin_url.is_some() && self.ipfs_replica_pin_token.is_none() {
+            return Err(ConfigError::Invalid("IPFS_REPLICA_PIN_TOKEN is required with IPFS_REPLICA_PIN_URL".to_string()));
+        }
+        
+        if self.document_reinstate_within_days < 0 {
+            return Err(ConfigError::Invalid("DOCUMENT_REINSTATE_WITHIN_DAYS cannot be negative".to_string()));
+        }
+        
+        if self.aml_monitoring_interval_secs == 0 {
+            return Err(ConfigError::Invalid("AML_MONITORING_INTERVAL_SECS must be positive".to_string()));
+        }
+        
+        if self.identity_sync_interval_secs == 0 {
+            return Err(ConfigError::Invalid("IDENTITY_SYNC_INTERVAL_SECS must be positive".to_string()));
+        }
+        
+        if self.rescreening_batch_size == 0 {
+            return Err(ConfigError::Invalid("RESCREENING_BATCH_SIZE must be at least 1".to_string()));
+        }
+        
+        if self.rescreening_rate_per_minute == 0 {
+            return Err(ConfigError::Invalid("RESCREENING_RATE_PER_MINUTE must be at least 1".to_string()));
+        }
+        
+        if self.ubo_ownership_threshold_percent <= Decimal::ZERO || self.ubo_ownership_threshold_percent > Decimal::ONE_HUNDRED {
+            return Err(ConfigError::Invalid("UBO_OWNERSHIP_THRESHOLD_PERCENT must be above 0 and at most 100".to_string()));
+        }
+        
+        if self.pep_screening_api_url.is_some() && self.pep_screening_api_key.is_none() {
+            return Err(ConfigError::Invalid("PEP_SCREENING_API_KEY is required with PEP_SCREENING_API_URL".to_string()));
+        }
+        
+        if self.identity_sync_batch_size == 0 {
+            return Err(ConfigError::Invalid("IDENTITY_SYNC_BATCH_SIZE must be at least 1".to_string()));
+        }
+        
+        if self.position_service_url.is_some() && self.position_service_api_key.is_none() {
+            return Err(ConfigError::Invalid("POSITION_SERVICE_API_KEY is required with POSITION_SERVICE_URL".to_string()));
+        }
+        
+        if self.document_retention_years == 0 {
+            return Err(ConfigError::Invalid("DOCUMENT_RETENTION_YEARS must be at least 1".to_string()));
+        }
+        
+        if self.pep_screening_api_url.is_none() {
+            tracing::warn!("PEP_SCREENING_API_URL not set. Re-screening will check sanctions lists only.");
+        }
+        
+        if self.position_service_url.is_none() {
+            tracing::warn!("POSITION_SERVICE_URL not set. Investors cannot be offboarded.");
+        }
+        
+        if self.jwt_secret.is_none() {
+            tracing::warn!("JWT_SECRET not set. Officer endpoints will reject every request.");
+        }
+        
         // Warn if no KYC providers configured
         if self.jumio_api_key.is_none() && self.onfido_api_token.is_none() {
             tracing::warn!("No KYC providers configured. KYC verification will fail.");
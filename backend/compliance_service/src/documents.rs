@@ -0,0 +1,275 @@
+use ethers::types::Address;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+use crate::kyc::KycStatus;
+
+// ============ Document Metadata ============
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentType {
+    Passport,
+    NationalId,
+    DriversLicense,
+    ResidencePermit,
+    UtilityBill,
+    BankStatement,
+}
+
+impl DocumentType {
+    /// Document types that prove identity
+    pub const IDENTITY: [DocumentType; 4] = [
+        DocumentType::Passport,
+        DocumentType::NationalId,
+        DocumentType::DriversLicense,
+        DocumentType::ResidencePermit,
+    ];
+
+    /// Identity documents gate KYC status; proof-of-address documents do not
+    pub fn is_identity(&self) -> bool {
+        Self::IDENTITY.contains(self)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DocumentType::Passport => "passport",
+            DocumentType::NationalId => "national_id",
+            DocumentType::DriversLicense => "drivers_license",
+            DocumentType::ResidencePermit => "residence_permit",
+            DocumentType::UtilityBill => "utility_bill",
+            DocumentType::BankStatement => "bank_statement",
+        }
+    }
+}
+
+impl std::str::FromStr for DocumentType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "passport" => Ok(DocumentType::Passport),
+            "national_id" => Ok(DocumentType::NationalId),
+            "drivers_license" => Ok(DocumentType::DriversLicense),
+            "residence_permit" => Ok(DocumentType::ResidencePermit),
+            "utility_bill" => Ok(DocumentType::UtilityBill),
+            "bank_statement" => Ok(DocumentType::BankStatement),
+            other => Err(format!("Unknown document type: {}", other)),
+        }
+    }
+}
+
+/// Metadata of a KYC document held for an investor; the document itself lives encrypted on IPFS
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvestorDocument {
+    pub document_id: Uuid,
+    pub investor: Address,
+    pub document_type: DocumentType,
+    pub issue_date: DateTime<Utc>,
+    /// Proof-of-address documents usually carry no expiry
+    pub expiry_date: Option<DateTime<Utc>>,
+    pub ipfs_hash: String,
+    /// Provider verification that accepted this document
+    pub verification_id: Option<String>,
+    pub recorded_at: DateTime<Utc>,
+    /// Set when a newer document of the same type replaces this one
+    pub superseded_at: Option<DateTime<Utc>>,
+}
+
+impl InvestorDocument {
+    pub fn is_active(&self) -> bool {
+        self.superseded_at.is_none()
+    }
+}
+
+/// New document submitted for an investor, typically replacing an expiring one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentSubmission {
+    pub document_type: DocumentType,
+    pub issue_date: DateTime<Utc>,
+    pub expiry_date: Option<DateTime<Utc>>,
+    pub ipfs_hash: String,
+}
+
+// ============ Expiry Tracking ============
+
+/// Notification stages for an identity document approaching expiry
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpiryStage {
+    Within60Days,
+    Within30Days,
+    Expired,
+}
+
+impl ExpiryStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExpiryStage::Within60Days => "within_60_days",
+            ExpiryStage::Within30Days => "within_30_days",
+            ExpiryStage::Expired => "expired",
+        }
+    }
+}
+
+/// Stage of a document expiring at `expiry`; a document is expired from its expiry instant on
+pub fn expiry_stage(expiry: DateTime<Utc>, now: DateTime<Utc>) -> Option<ExpiryStage> {
+    if now >= expiry {
+        Some(ExpiryStage::Expired)
+    } else if expiry - now <= Duration::days(30) {
+        Some(ExpiryStage::Within30Days)
+    } else if expiry - now <= Duration::days(60) {
+        Some(ExpiryStage::Within60Days)
+    } else {
+        None
+    }
+}
+
+/// Identity document flagged by the expiry sweep, as listed for compliance officers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpiringDocument {
+    pub document: InvestorDocument,
+    pub stage: ExpiryStage,
+    pub days_remaining: i64,
+    pub kyc_status: KycStatus,
+}
+
+/// Result of one run of the nightly document expiry job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentExpirySweep {
+    /// Stages reached for the first time in this run
+    pub newly_flagged: Vec<ExpiringDocument>,
+    /// Investors whose KYC status was downgraded to Expired
+    pub downgraded: Vec<Address>,
+    pub run_at: DateTime<Utc>,
+}
+
+/// Result of submitting a replacement document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentReplacement {
+    pub document: InvestorDocument,
+    pub kyc_status: KycStatus,
+    /// True when the replacement restored an expired status without a full re-KYC
+    pub reinstated: bool,
+    /// Set when the investor must go through full KYC again
+    pub reason: Option<String>,
+}
+
+// ============ Policy ============
+
+/// KYC status implied by the KYC check expiry and the investor's current identity documents.
+///
+/// Investors without any recorded identity document keep the status of their KYC check.
+pub fn effective_kyc_status(
+    kyc_expiry: DateTime<Utc>,
+    documents: &[InvestorDocument],
+    now: DateTime<Utc>,
+) -> KycStatus {
+    if kyc_expiry <= now {
+        return KycStatus::Expired;
+    }
+
+    let mut identity = documents.iter()
+        .filter(|doc| doc.is_active() && doc.document_type.is_identity())
+        .peekable();
+
+    if identity.peek().is_none() {
+        return KycStatus::Completed;
+    }
+
+    let any_valid = identity.any(|doc| match doc.expiry_date {
+        Some(expiry) => expiry > now,
+        None => true,
+    });
+
+    if any_valid {
+        KycStatus::Completed
+    } else {
+        KycStatus::Expired
+    }
+}
+
+/// Whether a verified replacement document may restore an expired KYC status.
+///
+/// Allowed while the KYC check itself is still valid and the previous identity document
+/// lapsed no longer than `reinstate_within_days` ago; otherwise full re-KYC is required.
+pub fn replacement_reinstates(
+    kyc_expiry: DateTime<Utc>,
+    lapsed_at: Option<DateTime<Utc>>,
+    reinstate_within_days: i64,
+    now: DateTime<Utc>,
+) -> Result<(), String> {
+    if kyc_expiry <= now {
+        return Err("KYC check has expired, full re-verification required".to_string());
+    }
+
+    match lapsed_at {
+        Some(lapsed_at) if now - lapsed_at > Duration::days(reinstate_within_days) => Err(format!(
+            "Identity document lapsed more than {} days ago, full re-verification required",
+            reinstate_within_days
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn passport(expiry: DateTime<Utc>) -> InvestorDocument {
+        InvestorDocument {
+            document_id: Uuid::new_v4(),
+            investor: Address::repeat_byte(0x22),
+            document_type: DocumentType::Passport,
+            issue_date: expiry - Duration::days(3650),
+            expiry_date: Some(expiry),
+            ipfs_hash: "QmPassport".to_string(),
+            verification_id: Some("scan-1".to_string()),
+            recorded_at: expiry - Duration::days(400),
+            superseded_at: None,
+        }
+    }
+
+    #[test]
+    fn test_expiry_stages() {
+        let now = Utc::now();
+
+        assert_eq!(expiry_stage(now + Duration::days(61), now), None);
+        assert_eq!(expiry_stage(now + Duration::days(60), now), Some(ExpiryStage::Within60Days));
+        assert_eq!(expiry_stage(now + Duration::days(31), now), Some(ExpiryStage::Within60Days));
+        assert_eq!(expiry_stage(now + Duration::days(30), now), Some(ExpiryStage::Within30Days));
+        assert_eq!(expiry_stage(now + Duration::seconds(1), now), Some(ExpiryStage::Within30Days));
+        assert_eq!(expiry_stage(now, now), Some(ExpiryStage::Expired));
+    }
+
+    #[test]
+    fn test_downgrade_at_document_expiry_boundary() {
+        let expiry = Utc::now() + Duration::days(10);
+        let kyc_expiry = expiry + Duration::days(200);
+        let documents = vec![passport(expiry)];
+
+        assert_eq!(effective_kyc_status(kyc_expiry, &documents, expiry - Duration::seconds(1)), KycStatus::Completed);
+        assert_eq!(effective_kyc_status(kyc_expiry, &documents, expiry), KycStatus::Expired);
+
+        // Proof-of-address documents never downgrade KYC on their own
+        let mut bill = passport(expiry);
+        bill.document_type = DocumentType::UtilityBill;
+        assert_eq!(effective_kyc_status(kyc_expiry, &[bill], expiry), KycStatus::Completed);
+
+        // A superseded document no longer counts once its replacement is active
+        let mut old = passport(expiry);
+        old.superseded_at = Some(expiry);
+        let renewed = passport(expiry + Duration::days(3650));
+        assert_eq!(effective_kyc_status(kyc_expiry, &[old, renewed], expiry), KycStatus::Completed);
+    }
+
+    #[test]
+    fn test_replacement_reinstatement_policy() {
+        let now = Utc::now();
+
+        assert!(replacement_reinstates(now + Duration::days(100), Some(now - Duration::days(5)), 90, now).is_ok());
+        assert!(replacement_reinstates(now + Duration::days(100), Some(now - Duration::days(91)), 90, now).is_err());
+        assert!(replacement_reinstates(now - Duration::days(1), Some(now - Duration::days(5)), 90, now).is_err());
+        assert!(replacement_reinstates(now + Duration::days(100), None, 90, now).is_ok());
+    }
+}
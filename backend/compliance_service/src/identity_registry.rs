@@ -0,0 +1,579 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use ethers::abi::{self, Token};
+use ethers::prelude::*;
+use ethers::utils::{id, keccak256};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tracing::warn;
+use quantera_types::{BlockTag, ChainHeads, FinalityPolicy};
+use crate::{ComplianceError, InvestorProfile, kyc::KycStatus, monitoring::AmlStatus};
+
+/// Identities pushed per registry transaction
+pub const DEFAULT_IDENTITY_BATCH_SIZE: usize = 25;
+
+// ============ Claims ============
+
+/// ISO 3166-1 numeric code for a jurisdiction's alpha-2 code, as stored on-chain by ERC-3643 registries
+pub fn country_code(jurisdiction: &str) -> Option<u16> {
+    let code = match jurisdiction.trim().to_ascii_uppercase().as_str() {
+        "US" => 840,
+        "GB" | "UK" => 826,
+        "CA" => 124,
+        "DE" => 276,
+        "FR" => 250,
+        "IE" => 372,
+        "LU" => 442,
+        "NL" => 528,
+        "BE" => 56,
+        "ES" => 724,
+        "IT" => 380,
+        "PT" => 620,
+        "AT" => 40,
+        "CH" => 756,
+        "LI" => 438,
+        "SE" => 752,
+        "DK" => 208,
+        "NO" => 578,
+        "FI" => 246,
+        "PL" => 616,
+        "SG" => 702,
+        "HK" => 344,
+        "JP" => 392,
+        "KR" => 410,
+        "AU" => 36,
+        "NZ" => 554,
+        "AE" => 784,
+        "BH" => 48,
+        "SA" => 682,
+        "IL" => 376,
+        "BR" => 76,
+        "MX" => 484,
+        "ZA" => 710,
+        "IN" => 356,
+        "KY" => 136,
+        "BM" => 60,
+        "VG" => 92,
+        "JE" => 832,
+        "GG" => 831,
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Whether a profile may hold registry-gated tokens
+pub fn is_approved(profile: &InvestorProfile, now: DateTime<Utc>) -> bool {
+    profile.kyc_status == KycStatus::Completed
+        && !matches!(profile.aml_status, AmlStatus::Flagged | AmlStatus::Blocked)
+        && !profile.sanctioned
+        && profile.kyc_expiry > now
+}
+
+/// Commitment to the KYC claims a wallet is registered under
+///
+/// Covers only the fields the registry's compliance checks depend on, ABI-encoded in a
+/// fixed order, so re-saving a profile without changing them keeps the same hash.
+pub fn claims_hash(profile: &InvestorProfile) -> H256 {
+    let encoded = abi::encode(&[
+        Token::Address(profile.address),
+        Token::String(profile.jurisdiction.trim().to_ascii_uppercase()),
+        Token::Uint(U256::from(profile.kyc_level)),
+        Token::Uint(U256::from(profile.accreditation_level)),
+        Token::String(profile.kyc_status.as_str().to_string()),
+        Token::String(profile.aml_status.as_str().to_string()),
+        Token::Bool(profile.sanctioned),
+        Token::Bool(profile.pep),
+        Token::Uint(U256::from(profile.kyc_expiry.timestamp().max(0) as u64)),
+    ]);
+    H256::from(keccak256(encoded))
+}
+
+/// What the registry should hold for one wallet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentityRecord {
+    pub wallet: Address,
+    pub country: u16,
+    pub claims_hash: H256,
+}
+
+impl IdentityRecord {
+    pub fn from_profile(profile: &InvestorProfile) -> Result<Self, ComplianceError> {
+        let country = country_code(&profile.jurisdiction)
+            .ok_or_else(|| ComplianceError::InvalidInput(format!("No ISO country code for jurisdiction {}", profile.jurisdiction)))?;
+        Ok(Self { wallet: profile.address, country, claims_hash: claims_hash(profile) })
+    }
+
+    /// Hash of the registry entry itself; equal hashes mean the chain matches our records
+    pub fn state_hash(&self) -> H256 {
+        H256::from(keccak256(abi::encode(&[
+            Token::Address(self.wallet),
+            Token::Uint(U256::from(self.country)),
+            Token::FixedBytes(self.claims_hash.as_bytes().to_vec()),
+        ])))
+    }
+}
+
+// ============ Sync planning ============
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdentityOpKind {
+    Register,
+    Update,
+    Remove,
+}
+
+impl IdentityOpKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IdentityOpKind::Register => "register",
+            IdentityOpKind::Update => "update",
+            IdentityOpKind::Remove => "remove",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IdentityOp {
+    Register(IdentityRecord),
+    Update(IdentityRecord),
+    Remove(Address),
+}
+
+impl IdentityOp {
+    pub fn wallet(&self) -> Address {
+        match self {
+            IdentityOp::Register(record) | IdentityOp::Update(record) => record.wallet,
+            IdentityOp::Remove(wallet) => *wallet,
+        }
+    }
+
+    pub fn kind(&self) -> IdentityOpKind {
+        match self {
+            IdentityOp::Register(_) => IdentityOpKind::Register,
+            IdentityOp::Update(_) => IdentityOpKind::Update,
+            IdentityOp::Remove(_) => IdentityOpKind::Remove,
+        }
+    }
+
+    /// Registry state hash once the operation has been mined; None once removed
+    pub fn resulting_state(&self) -> Option<H256> {
+        match self {
+            IdentityOp::Register(record) | IdentityOp::Update(record) => Some(record.state_hash()),
+            IdentityOp::Remove(_) => None,
+        }
+    }
+}
+
+/// Our view of one wallet's registry entry
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncedIdentity {
+    /// State hash last written or observed on-chain; None when the wallet is not registered
+    pub state_hash: Option<H256>,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+}
+
+/// Registry changes needed to match the investor profiles.
+///
+/// Approved profiles are registered; registered profiles whose claims changed are
+/// updated, including ones no longer approved, so the registry sees the new status.
+/// Wallets are only removed once offboarded. Profiles already in sync produce nothing.
+pub fn plan_identity_changes(
+    profiles: &[InvestorProfile],
+    synced: &HashMap<Address, SyncedIdentity>,
+    offboarded: &HashSet<Address>,
+    now: DateTime<Utc>,
+) -> (Vec<IdentityOp>, Vec<(Address, ComplianceError)>) {
+    let mut ops = Vec::new();
+    let mut rejected = Vec::new();
+
+    for profile in profiles {
+        let current = synced.get(&profile.address).and_then(|s| s.state_hash);
+
+        if offboarded.contains(&profile.address) {
+            if current.is_some() {
+                ops.push(IdentityOp::Remove(profile.address));
+            }
+            continue;
+        }
+
+        if current.is_none() && !is_approved(profile, now) {
+            continue;
+        }
+
+        let record = match IdentityRecord::from_profile(profile) {
+            Ok(record) => record,
+            Err(e) => {
+                rejected.push((profile.address, e));
+                continue;
+            }
+        };
+        match current {
+            None => ops.push(IdentityOp::Register(record)),
+            Some(hash) if hash != record.state_hash() => ops.push(IdentityOp::Update(record)),
+            Some(_) => {}
+        }
+    }
+
+    (ops, rejected)
+}
+
+// ============ Registry ============
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegistryEvent {
+    pub wallet: Address,
+    /// None when the identity was removed
+    pub record: Option<IdentityRecord>,
+    pub block_number: u64,
+}
+
+/// ERC-3643 identity registry as seen by the sync job
+#[async_trait]
+pub trait IdentityRegistry: Send + Sync {
+    /// Apply operations of a single kind in one transaction; all or nothing
+    async fn submit_batch(&self, kind: IdentityOpKind, ops: &[IdentityOp]) -> Result<(), ComplianceError>;
+
+    async fn submit(&self, op: &IdentityOp) -> Result<(), ComplianceError>;
+
+    /// Identity events from `from_block` to the chain head, and the head block scanned to
+    async fn events_since(&self, from_block: u64) -> Result<(Vec<RegistryEvent>, u64), ComplianceError>;
+}
+
+#[derive(Debug, Default)]
+pub struct BatchOutcome {
+    pub succeeded: Vec<IdentityOp>,
+    pub failed: Vec<(IdentityOp, String)>,
+}
+
+/// Push operations in batches of `batch_size` per kind.
+///
+/// A batch that reverts is replayed one identity at a time, so a single bad entry
+/// only fails itself.
+pub async fn push_identity_changes(
+    registry: &dyn IdentityRegistry,
+    ops: Vec<IdentityOp>,
+    batch_size: usize,
+) -> BatchOutcome {
+    let mut outcome = BatchOutcome::default();
+    let mut by_kind: Vec<(IdentityOpKind, Vec<IdentityOp>)> = Vec::new();
+    for op in ops {
+        match by_kind.iter_mut().find(|(kind, _)| *kind == op.kind()) {
+            Some((_, group)) => group.push(op),
+            None => by_kind.push((op.kind(), vec![op])),
+        }
+    }
+
+    for (kind, group) in by_kind {
+        for batch in group.chunks(batch_size.max(1)) {
+            match registry.submit_batch(kind, batch).await {
+                Ok(()) => outcome.succeeded.extend_from_slice(batch),
+                Err(e) => {
+                    warn!("Identity {} batch of {} failed, retrying individually: {}", kind.as_str(), batch.len(), e);
+                    for op in batch {
+                        match registry.submit(op).await {
+                            Ok(()) => outcome.succeeded.push(*op),
+                            Err(e) => outcome.failed.push((*op, e.to_string())),
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    outcome
+}
+
+/// Fold a batch outcome into the synced state; failed entries keep their previous hash so the next run retries them
+pub fn apply_outcome(synced: &mut HashMap<Address, SyncedIdentity>, outcome: &BatchOutcome) {
+    for op in &outcome.succeeded {
+        synced.insert(op.wallet(), SyncedIdentity { state_hash: op.resulting_state(), attempts: 0, last_error: None });
+    }
+    for (op, error) in &outcome.failed {
+        let entry = synced.entry(op.wallet()).or_default();
+        entry.attempts += 1;
+        entry.last_error = Some(error.clone());
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdentitySyncFailure {
+    pub wallet: Address,
+    pub error: String,
+}
+
+/// Result of one push of profile changes to the registry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdentitySyncRun {
+    pub run_at: DateTime<Utc>,
+    pub registered: usize,
+    pub updated: usize,
+    pub removed: usize,
+    pub failed: Vec<IdentitySyncFailure>,
+}
+
+/// Identity registry contract reached through an ethers signer
+pub struct EthersIdentityRegistry {
+    client: Arc<SignerMiddleware<Provider<Http>, LocalWallet>>,
+    address: Address,
+    /// Events are read only up to the confirmed head so reorged-out changes are never applied
+    finality: FinalityPolicy,
+}
+
+impl EthersIdentityRegistry {
+    pub fn new(provider: Provider<Http>, wallet: LocalWallet, address: Address, finality: FinalityPolicy) -> Self {
+        Self { client: Arc::new(SignerMiddleware::new(provider, wallet)), address, finality }
+    }
+
+    async fn confirmed_head(&self) -> Result<u64, ComplianceError> {
+        let failed = |e: String| ComplianceError::EthereumError(format!("Failed to get block number: {}", e));
+        let mut heads = ChainHeads {
+            latest: self.client.get_block_number().await.map_err(|e| failed(e.to_string()))?.as_u64(),
+            ..Default::default()
+        };
+        for tag in self.finality.tags() {
+            let block_number = match tag {
+                BlockTag::Safe => BlockNumber::Safe,
+                BlockTag::Finalized => BlockNumber::Finalized,
+            };
+            let number = self.client.get_block(block_number).await
+                .map_err(|e| failed(e.to_string()))?
+                .and_then(|block| block.number)
+                .ok_or_else(|| failed(format!("node returned no {} block", tag.as_str())))?
+                .as_u64();
+            match tag {
+                BlockTag::Safe => heads.safe = Some(number),
+                BlockTag::Finalized => heads.finalized = Some(number),
+            }
+        }
+        // Nothing is confirmed yet on a chain shorter than the confirmation depth
+        Ok(self.finality.confirmed_head(&heads).unwrap_or(0))
+    }
+
+    async fn send(&self, signature: &str, args: Vec<Token>) -> Result<(), ComplianceError> {
+        let mut calldata = id(signature).to_vec();
+        calldata.extend(abi::encode(&args));
+
+        let tx: TypedTransaction = TransactionRequest::new().to(self.address).data(calldata).into();
+        let receipt = self.client.send_transaction(tx, None).await
+            .map_err(|e| ComplianceError::EthereumError(format!("{} failed: {}", signature, e)))?
+            .await
+            .map_err(|e| ComplianceError::EthereumError(format!("{} not mined: {}", signature, e)))?;
+
+        match receipt {
+            Some(receipt) if receipt.status == Some(U64::from(1)) => Ok(()),
+            _ => Err(ComplianceError::EthereumError(format!("{} reverted", signature))),
+        }
+    }
+}
+
+fn record_tokens(ops: &[IdentityOp]) -> Vec<Token> {
+    let records: Vec<IdentityRecord> = ops.iter()
+        .filter_map(|op| match op {
+            IdentityOp::Register(record) | IdentityOp::Update(record) => Some(*record),
+            IdentityOp::Remove(_) => None,
+        })
+        .collect();
+    vec![
+        Token::Array(records.iter().map(|r| Token::Address(r.wallet)).collect()),
+        Token::Array(records.iter().map(|r| Token::Uint(U256::from(r.country))).collect()),
+        Token::Array(records.iter().map(|r| Token::FixedBytes(r.claims_hash.as_bytes().to_vec())).collect()),
+    ]
+}
+
+#[async_trait]
+impl IdentityRegistry for EthersIdentityRegistry {
+    async fn submit_batch(&self, kind: IdentityOpKind, ops: &[IdentityOp]) -> Result<(), ComplianceError> {
+        match kind {
+            IdentityOpKind::Register => self.send("batchRegisterIdentity(address[],uint16[],bytes32[])", record_tokens(ops)).await,
+            IdentityOpKind::Update => self.send("batchUpdateIdentity(address[],uint16[],bytes32[])", record_tokens(ops)).await,
+            IdentityOpKind::Remove => {
+                let wallets = ops.iter().map(|op| Token::Address(op.wallet())).collect();
+                self.send("batchDeleteIdentity(address[])", vec![Token::Array(wallets)]).await
+            }
+        }
+    }
+
+    async fn submit(&self, op: &IdentityOp) -> Result<(), ComplianceError> {
+        let record_args = |r: &IdentityRecord| vec![
+            Token::Address(r.wallet),
+            Token::Uint(U256::from(r.country)),
+            Token::FixedBytes(r.claims_hash.as_bytes().to_vec()),
+        ];
+        match op {
+            IdentityOp::Register(record) => self.send("registerIdentity(address,uint16,bytes32)", record_args(record)).await,
+            IdentityOp::Update(record) => self.send("updateIdentity(address,uint16,bytes32)", record_args(record)).await,
+            IdentityOp::Remove(wallet) => self.send("deleteIdentity(address)", vec![Token::Address(*wallet)]).await,
+        }
+    }
+
+    async fn events_since(&self, from_block: u64) -> Result<(Vec<RegistryEvent>, u64), ComplianceError> {
+        let head = self.confirmed_head().await?;
+        if from_block > head {
+            return Ok((Vec::new(), head));
+        }
+
+        let stored = H256::from(keccak256("IdentityStored(address,uint16,bytes32)"));
+        let updated = H256::from(keccak256("IdentityUpdated(address,uint16,bytes32)"));
+        let removed = H256::from(keccak256("IdentityRemoved(address)"));
+        let filter = Filter::new()
+            .address(self.address)
+            .from_block(from_block)
+            .to_block(head)
+            .topic0(vec![stored, updated, removed]);
+        let logs = self.client.get_logs(&filter).await
+            .map_err(|e| ComplianceError::EthereumError(format!("Failed to read identity registry events: {}", e)))?;
+
+        let mut events = Vec::new();
+        for log in logs {
+            let (Some(topic), Some(wallet_topic)) = (log.topics.first(), log.topics.get(1)) else { continue };
+            let wallet = Address::from(*wallet_topic);
+            let block_number = log.block_number.map(|b| b.as_u64()).unwrap_or(head);
+
+            let record = if *topic == removed {
+                None
+            } else {
+                let decoded = abi::decode(&[abi::ParamType::Uint(16), abi::ParamType::FixedBytes(32)], &log.data)
+                    .map_err(|e| ComplianceError::EthereumError(format!("Malformed identity event: {}", e)))?;
+                match (&decoded[0], &decoded[1]) {
+                    (Token::Uint(country), Token::FixedBytes(hash)) => Some(IdentityRecord {
+                        wallet,
+                        country: country.low_u32() as u16,
+                        claims_hash: H256::from_slice(hash),
+                    }),
+                    _ => continue,
+                }
+            };
+            events.push(RegistryEvent { wallet, record, block_number });
+        }
+
+        Ok((events, head))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use rust_decimal::Decimal;
+    use tokio::sync::Mutex;
+
+    fn profile(byte: u8, jurisdiction: &str) -> InvestorProfile {
+        let now = Utc::now();
+        InvestorProfile {
+            address: Address::repeat_byte(byte),
+            jurisdiction: jurisdiction.to_string(),
+            kyc_level: 2,
+            kyc_expiry: DateTime::from_timestamp(4_102_444_800, 0).unwrap(),
+            kyc_status: KycStatus::Completed,
+            aml_status: AmlStatus::Clear,
+            investment_blocked: false,
+            accreditation_level: 1,
+            risk_score: 20,
+            total_invested: Decimal::new(5_000, 0),
+            documents_ipfs: vec!["QmDoc".to_string()],
+            last_check: now,
+            pep: false,
+            sanctioned: false,
+        }
+    }
+
+    /// Registry that rejects any batch containing a failing wallet, and that wallet on its own
+    struct FlakyRegistry {
+        failing: HashSet<Address>,
+        batches: Mutex<Vec<Vec<Address>>>,
+    }
+
+    #[async_trait]
+    impl IdentityRegistry for FlakyRegistry {
+        async fn submit_batch(&self, _kind: IdentityOpKind, ops: &[IdentityOp]) -> Result<(), ComplianceError> {
+            self.batches.lock().await.push(ops.iter().map(|op| op.wallet()).collect());
+            if ops.iter().any(|op| self.failing.contains(&op.wallet())) {
+                return Err(ComplianceError::EthereumError("execution reverted".into()));
+            }
+            Ok(())
+        }
+
+        async fn submit(&self, op: &IdentityOp) -> Result<(), ComplianceError> {
+            if self.failing.contains(&op.wallet()) {
+                return Err(ComplianceError::EthereumError("execution reverted".into()));
+            }
+            Ok(())
+        }
+
+        async fn events_since(&self, from_block: u64) -> Result<(Vec<RegistryEvent>, u64), ComplianceError> {
+            Ok((Vec::new(), from_block))
+        }
+    }
+
+    #[test]
+    fn test_claims_hash_is_stable() {
+        let original = profile(0x11, "US");
+
+        // Fields outside the claims do not move the hash
+        let mut resaved = original.clone();
+        resaved.risk_score = 75;
+        resaved.total_invested = Decimal::new(1_000_000, 0);
+        resaved.documents_ipfs.push("QmOther".to_string());
+        resaved.last_check = original.last_check + Duration::days(3);
+        resaved.jurisdiction = " us ".to_string();
+        assert_eq!(claims_hash(&original), claims_hash(&resaved));
+        assert_eq!(
+            IdentityRecord::from_profile(&original).unwrap().state_hash(),
+            IdentityRecord::from_profile(&resaved).unwrap().state_hash(),
+        );
+
+        // Claims do
+        let mut downgraded = original.clone();
+        downgraded.kyc_status = KycStatus::Expired;
+        assert_ne!(claims_hash(&original), claims_hash(&downgraded));
+        assert_ne!(claims_hash(&original), claims_hash(&profile(0x12, "US")));
+
+        assert_eq!(IdentityRecord::from_profile(&original).unwrap().country, 840);
+        assert!(IdentityRecord::from_profile(&profile(0x13, "ZZ")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_batch_retries_only_failed_entries() {
+        let now = Utc::now();
+        let profiles = vec![profile(0x01, "US"), profile(0x02, "GB"), profile(0x03, "SG"), profile(0x04, "DE")];
+        let registry = FlakyRegistry {
+            failing: [Address::repeat_byte(0x02)].into_iter().collect(),
+            batches: Mutex::new(Vec::new()),
+        };
+        let mut synced = HashMap::new();
+        let offboarded = HashSet::new();
+
+        let (ops, rejected) = plan_identity_changes(&profiles, &synced, &offboarded, now);
+        assert!(rejected.is_empty());
+        assert_eq!(ops.len(), 4);
+
+        let outcome = push_identity_changes(&registry, ops, 2).await;
+        assert_eq!(outcome.succeeded.len(), 3);
+        assert_eq!(outcome.failed.len(), 1);
+        apply_outcome(&mut synced, &outcome);
+        assert_eq!(synced[&Address::repeat_byte(0x02)].attempts, 1);
+        assert!(synced[&Address::repeat_byte(0x02)].state_hash.is_none());
+
+        // Only the failed wallet is pushed again
+        registry.batches.lock().await.clear();
+        let (ops, _) = plan_identity_changes(&profiles, &synced, &offboarded, now);
+        assert_eq!(ops, vec![IdentityOp::Register(IdentityRecord::from_profile(&profiles[1]).unwrap())]);
+        let outcome = push_identity_changes(&registry, ops, 2).await;
+        apply_outcome(&mut synced, &outcome);
+        assert_eq!(*registry.batches.lock().await, vec![vec![Address::repeat_byte(0x02)]]);
+        assert_eq!(synced[&Address::repeat_byte(0x02)].attempts, 2);
+
+        // Removal only follows offboarding
+        let mut lapsed = profiles.clone();
+        lapsed[0].kyc_status = KycStatus::Expired;
+        let (ops, _) = plan_identity_changes(&lapsed, &synced, &offboarded, now);
+        assert!(matches!(ops.as_slice(), [IdentityOp::Update(_), IdentityOp::Register(_)]));
+
+        let offboarded: HashSet<Address> = [Address::repeat_byte(0x01)].into_iter().collect();
+        let (ops, _) = plan_identity_changes(&lapsed, &synced, &offboarded, now);
+        assert_eq!(ops[0], IdentityOp::Remove(Address::repeat_byte(0x01)));
+    }
+}
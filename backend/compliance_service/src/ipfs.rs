@@ -3,14 +3,241 @@ use aes_gcm::{
     Aes256Gcm, Nonce,
 };
 use anyhow::Result;
+use async_trait::async_trait;
 use base64;
+use chrono::{DateTime, Utc};
 use futures::TryStreamExt;
-use ipfs_api_backend_hyper::{IpfsApi, IpfsClient as HyperIpfsClient, TryFromUri};
+use ipfs_api_backend_hyper::{request::Add, IpfsApi, IpfsClient as HyperIpfsClient, TryFromUri};
 use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::io::Cursor;
-use tracing::{info, debug, error};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tracing::{info, debug, warn, error};
+
+/// Uploads are added as CIDv1 with raw leaves in chunks of this size, so any document up
+/// to it is a single raw block whose CID is the hash of its bytes
+pub const MAX_VERIFIABLE_BYTES: usize = 1024 * 1024;
+
+/// Chunk size of a default `ipfs add`, the most a CIDv0 single-block file holds
+const DEFAULT_CHUNK_BYTES: usize = 256 * 1024;
+
+const CODEC_RAW: u8 = 0x55;
+const CODEC_DAG_PB: u8 = 0x70;
+const MULTIHASH_SHA2_256: [u8; 2] = [0x12, 0x20];
+
+// ============ Content Verification ============
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CidCheck {
+    Match,
+    /// The bytes do not hash to the CID
+    Mismatch,
+    /// The CID's format or block layout cannot be re-derived from the bytes alone
+    Unsupported(String),
+}
+
+/// Re-hash `content` the way `ipfs add` would and compare it with `cid`.
+///
+/// Handles sha2-256 CIDv1 raw blocks and single-block UnixFS files (CIDv0 or CIDv1 dag-pb).
+pub fn check_cid(cid: &str, content: &[u8]) -> CidCheck {
+    let (codec, digest) = match parse_cid(cid) {
+        Ok(parsed) => parsed,
+        Err(reason) => return CidCheck::Unsupported(reason),
+    };
+
+    let actual = match codec {
+        CODEC_RAW => Sha256::digest(content),
+        CODEC_DAG_PB if content.len() <= DEFAULT_CHUNK_BYTES => Sha256::digest(unixfs_file_node(content)),
+        CODEC_DAG_PB => return CidCheck::Unsupported(format!("{} spans several blocks", cid)),
+        other => return CidCheck::Unsupported(format!("codec 0x{:x} of {}", other, cid)),
+    };
+
+    if actual.as_slice() == digest.as_slice() { CidCheck::Match } else { CidCheck::Mismatch }
+}
+
+/// CIDv1 of a single raw block
+pub fn raw_cid(content: &[u8]) -> String {
+    let mut bytes = vec![0x01, CODEC_RAW];
+    bytes.extend_from_slice(&MULTIHASH_SHA2_256);
+    bytes.extend_from_slice(&Sha256::digest(content));
+    format!("b{}", data_encoding::BASE32_NOPAD.encode(&bytes).to_lowercase())
+}
+
+/// Codec and sha2-256 digest of a CIDv0 (base58btc) or base32 CIDv1
+fn parse_cid(cid: &str) -> std::result::Result<(u8, Vec<u8>), String> {
+    let (codec, multihash) = if cid.len() == 46 && cid.starts_with("Qm") {
+        let bytes = bs58::decode(cid).into_vec().map_err(|e| format!("invalid CIDv0 {}: {}", cid, e))?;
+        (CODEC_DAG_PB, bytes)
+    } else if let Some(encoded) = cid.strip_prefix('b') {
+        let bytes = data_encoding::BASE32_NOPAD.decode(encoded.to_uppercase().as_bytes())
+            .map_err(|e| format!("invalid CIDv1 {}: {}", cid, e))?;
+        match bytes.as_slice() {
+            [0x01, codec, rest @ ..] if *codec < 0x80 => (*codec, rest.to_vec()),
+            _ => return Err(format!("unsupported CID version in {}", cid)),
+        }
+    } else {
+        return Err(format!("unsupported CID encoding {}", cid));
+    };
+
+    match multihash.as_slice() {
+        [0x12, 0x20, digest @ ..] if digest.len() == 32 => Ok((codec, digest.to_vec())),
+        _ => Err(format!("{} is not a sha2-256 CID", cid)),
+    }
+}
+
+/// dag-pb node of a UnixFS file held in a single block
+fn unixfs_file_node(content: &[u8]) -> Vec<u8> {
+    let mut unixfs = vec![0x08, 0x02]; // Type = File
+    if !content.is_empty() {
+        unixfs.push(0x12);
+        unixfs.extend(varint(content.len() as u64));
+        unixfs.extend_from_slice(content);
+    }
+    unixfs.push(0x18);
+    unixfs.extend(varint(content.len() as u64));
+
+    let mut node = vec![0x0a];
+    node.extend(varint(unixfs.len() as u64));
+    node.extend(unixfs);
+    node
+}
+
+fn varint(mut value: u64) -> Vec<u8> {
+    let mut out = Vec::new();
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return out;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+// ============ Gateways and Pinning ============
+
+/// A source of IPFS content, tried in order on retrieval
+#[async_trait]
+pub trait IpfsGateway: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn fetch(&self, cid: &str) -> Result<Vec<u8>>;
+}
+
+/// The node's own API (`cat`)
+pub struct ApiGateway {
+    name: String,
+    client: HyperIpfsClient,
+}
+
+impl ApiGateway {
+    pub fn new(api_url: &str) -> Result<Self> {
+        Ok(Self { name: api_url.to_string(), client: HyperIpfsClient::from_str(api_url)? })
+    }
+}
+
+#[async_trait]
+impl IpfsGateway for ApiGateway {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn fetch(&self, cid: &str) -> Result<Vec<u8>> {
+        let mut stream = Box::pin(self.client.cat(cid).map_err(|e| anyhow::anyhow!("IPFS download failed: {}", e)));
+        let mut data = Vec::new();
+        while let Some(chunk) = stream.try_next().await? {
+            data.extend_from_slice(&chunk);
+        }
+        Ok(data)
+    }
+}
+
+/// A public or dedicated HTTP gateway, e.g. `https://ipfs.io/ipfs`
+pub struct HttpGateway {
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl HttpGateway {
+    pub fn new(base_url: &str) -> Self {
+        Self { base_url: base_url.trim_end_matches('/').to_string(), client: reqwest::Client::new() }
+    }
+}
+
+#[async_trait]
+impl IpfsGateway for HttpGateway {
+    fn name(&self) -> &str {
+        &self.base_url
+    }
+
+    async fn fetch(&self, cid: &str) -> Result<Vec<u8>> {
+        let response = self.client.get(format!("{}/{}", self.base_url, cid)).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("{} responded {}", self.base_url, response.status()));
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+}
+
+/// A remote pinning service holding a replica of uploaded content
+#[async_trait]
+pub trait PinningService: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn pin(&self, cid: &str, name: &str) -> Result<()>;
+}
+
+/// A service implementing the IPFS Pinning Service API (`POST /pins`)
+pub struct RemotePinningService {
+    endpoint: String,
+    token: String,
+    client: reqwest::Client,
+}
+
+impl RemotePinningService {
+    pub fn new(endpoint: &str, token: &str) -> Self {
+        Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl PinningService for RemotePinningService {
+    fn name(&self) -> &str {
+        &self.endpoint
+    }
+
+    async fn pin(&self, cid: &str, name: &str) -> Result<()> {
+        let response = self.client.post(format!("{}/pins", self.endpoint))
+            .bearer_auth(&self.token)
+            .json(&serde_json::json!({ "cid": cid, "name": name }))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("{} responded {}", self.endpoint, response.status()));
+        }
+        Ok(())
+    }
+}
+
+/// Which gateway served a retrieval and which were passed over, for the audit trail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Retrieval {
+    pub cid: String,
+    pub served_by: String,
+    /// Gateways that returned content not matching the CID
+    pub tampered: Vec<String>,
+    /// Gateways that errored or timed out
+    pub unavailable: Vec<String>,
+    pub retrieved_at: DateTime<Utc>,
+}
 
 // ============ IPFS Client ============
 
@@ -18,6 +245,11 @@ pub struct IpfsClient {
     client: HyperIpfsClient,
     cipher: Aes256Gcm,
     encryption_key: Vec<u8>,
+    /// Primary API first, then fallback gateways
+    gateways: Vec<Arc<dyn IpfsGateway>>,
+    gateway_timeout: Duration,
+    replica: Option<Arc<dyn PinningService>>,
+    tamper_alerts: AtomicU64,
 }
 
 impl IpfsClient {
@@ -38,9 +270,35 @@ impl IpfsClient {
             client,
             cipher,
             encryption_key,
+            gateways: vec![Arc::new(ApiGateway::new(api_url)?)],
+            gateway_timeout: Duration::from_secs(10),
+            replica: None,
+            tamper_alerts: AtomicU64::new(0),
         })
     }
     
+    /// Fall back to these gateways, in order, when the primary API cannot serve content
+    pub fn with_gateways(mut self, gateways: Vec<Arc<dyn IpfsGateway>>) -> Self {
+        self.gateways.extend(gateways);
+        self
+    }
+    
+    pub fn with_gateway_timeout(mut self, timeout: Duration) -> Self {
+        self.gateway_timeout = timeout;
+        self
+    }
+    
+    /// Also pin uploads to a secondary pinning service
+    pub fn with_replica(mut self, replica: Arc<dyn PinningService>) -> Self {
+        self.replica = Some(replica);
+        self
+    }
+    
+    /// Retrievals where a gateway served content not matching its CID
+    pub fn tamper_alert_count(&self) -> u64 {
+        self.tamper_alerts.load(Ordering::Relaxed)
+    }
+    
     /// Upload encrypted data to IPFS
     pub async fn upload_encrypted(&self, data: Vec<u8>) -> Result<String> {
         debug!("Encrypting {} bytes of data for IPFS upload", data.len());
@@ -83,39 +341,17 @@ impl IpfsClient {
         // Serialize to JSON
         let json_data = serde_json::to_vec(&document)?;
         
-        // Upload to IPFS
-        let res = self.client
-            .add(Cursor::new(json_data))
-            .await
-            .map_err(|e| anyhow::anyhow!("IPFS upload failed: {}", e))?;
-        
-        let hash = res.hash;
-        
-        // Pin the content
-        self.client
-            .pin_add(&hash, false)
-            .await
-            .map_err(|e| anyhow::anyhow!("IPFS pinning failed: {}", e))?;
-        
+        let hash = self.add_and_pin(json_data).await?;
         info!("Document uploaded to IPFS: {}", hash);
         
         Ok(hash)
     }
     
-    /// Download and decrypt data from IPFS
-    pub async fn download_encrypted(&self, hash: &str) -> Result<Vec<u8>> {
+    /// Download and decrypt data from IPFS, returning the gateway that served it
+    pub async fn download_encrypted(&self, hash: &str) -> Result<(Vec<u8>, Retrieval)> {
         debug!("Downloading encrypted document from IPFS: {}", hash);
         
-        // Download from IPFS
-        let stream = self.client
-            .cat(hash)
-            .map_err(|e| anyhow::anyhow!("IPFS download failed: {}", e));
-            
-        let mut data = Vec::new();
-        let mut stream = Box::pin(stream);
-        while let Some(chunk) = stream.try_next().await? {
-            data.extend_from_slice(&chunk);
-        }
+        let (data, retrieval) = self.fetch_verified(hash).await?;
         
         // Parse JSON
         let document: EncryptedDocument = serde_json::from_slice(&data)?;
@@ -145,21 +381,96 @@ impl IpfsClient {
             return Err(anyhow::anyhow!("Checksum verification failed"));
         }
         
-        info!("Document downloaded and decrypted successfully");
+        info!("Document {} downloaded from {} and decrypted successfully", hash, retrieval.served_by);
+        
+        Ok((plaintext, retrieval))
+    }
+    
+    /// Fetch content whose bytes hash to `cid`, trying each gateway in order.
+    ///
+    /// A gateway returning other bytes is treated as tampering: it is skipped, an alert is
+    /// raised and the next gateway is tried.
+    pub async fn fetch_verified(&self, cid: &str) -> Result<(Vec<u8>, Retrieval)> {
+        let mut tampered = Vec::new();
+        let mut unavailable = Vec::new();
+        
+        for gateway in &self.gateways {
+            let data = match tokio::time::timeout(self.gateway_timeout, gateway.fetch(cid)).await {
+                Ok(Ok(data)) => data,
+                Ok(Err(e)) => {
+                    warn!("IPFS gateway {} failed to serve {}: {}", gateway.name(), cid, e);
+                    unavailable.push(gateway.name().to_string());
+                    continue;
+                }
+                Err(_) => {
+                    warn!("IPFS gateway {} timed out serving {}", gateway.name(), cid);
+                    unavailable.push(gateway.name().to_string());
+                    continue;
+                }
+            };
+            
+            match check_cid(cid, &data) {
+                CidCheck::Match => {
+                    return Ok((data, Retrieval {
+                        cid: cid.to_string(),
+                        served_by: gateway.name().to_string(),
+                        tampered,
+                        unavailable,
+                        retrieved_at: Utc::now(),
+                    }));
+                }
+                CidCheck::Mismatch => {
+                    self.tamper_alerts.fetch_add(1, Ordering::Relaxed);
+                    error!("SECURITY ALERT: IPFS gateway {} served content for {} that does not match the CID", gateway.name(), cid);
+                    tampered.push(gateway.name().to_string());
+                }
+                CidCheck::Unsupported(reason) => {
+                    return Err(anyhow::anyhow!("Cannot verify IPFS content: {}", reason));
+                }
+            }
+        }
         
-        Ok(plaintext)
+        Err(anyhow::anyhow!(
+            "No IPFS gateway served verified content for {} (tampered: {:?}, unavailable: {:?})",
+            cid, tampered, unavailable
+        ))
     }
     
     /// Upload unencrypted public data to IPFS
     pub async fn upload_public(&self, data: Vec<u8>) -> Result<String> {
         debug!("Uploading {} bytes of public data to IPFS", data.len());
         
+        let hash = self.add_and_pin(data).await?;
+        info!("Public document uploaded to IPFS: {}", hash);
+        
+        Ok(hash)
+    }
+    
+    /// Add content as a single raw block, pin it on the primary and replicate the pin
+    async fn add_and_pin(&self, data: Vec<u8>) -> Result<String> {
+        if data.len() > MAX_VERIFIABLE_BYTES {
+            return Err(anyhow::anyhow!(
+                "Document of {} bytes exceeds the {} byte limit for verifiable storage", data.len(), MAX_VERIFIABLE_BYTES
+            ));
+        }
+        let expected = raw_cid(&data);
+        
+        let chunker = format!("size-{}", MAX_VERIFIABLE_BYTES);
+        let options = Add {
+            cid_version: Some(1),
+            raw_leaves: Some(true),
+            chunker: Some(&chunker),
+            ..Default::default()
+        };
         let res = self.client
-            .add(Cursor::new(data))
+            .add_with_options(Cursor::new(data), options)
             .await
             .map_err(|e| anyhow::anyhow!("IPFS upload failed: {}", e))?;
         
         let hash = res.hash;
+        if hash != expected {
+            return Err(anyhow::anyhow!("IPFS node returned CID {} for content hashing to {}", hash, expected));
+        }
         
         // Pin the content
         self.client
@@ -167,7 +478,12 @@ impl IpfsClient {
             .await
             .map_err(|e| anyhow::anyhow!("IPFS pinning failed: {}", e))?;
         
-        info!("Public document uploaded to IPFS: {}", hash);
+        // The primary pin is authoritative; a failed replica is retried by re-uploading
+        if let Some(replica) = &self.replica {
+            if let Err(e) = replica.pin(&hash, &format!("quantera-{}", hash)).await {
+                warn!("Replicating pin of {} to {} failed: {}", hash, replica.name(), e);
+            }
+        }
         
         Ok(hash)
     }
@@ -267,3 +583,72 @@ pub enum DocumentType {
     ComplianceReport,
     Other(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedGateway {
+        name: &'static str,
+        content: Option<Vec<u8>>,
+    }
+
+    #[async_trait]
+    impl IpfsGateway for FixedGateway {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn fetch(&self, _cid: &str) -> Result<Vec<u8>> {
+            self.content.clone().ok_or_else(|| anyhow::anyhow!("not found"))
+        }
+    }
+
+    fn client(gateways: Vec<Arc<dyn IpfsGateway>>) -> IpfsClient {
+        let mut client = IpfsClient::new("http://localhost:5001", vec![7u8; 32]).unwrap();
+        client.gateways = gateways;
+        client
+    }
+
+    #[test]
+    fn test_cid_verification_matches_ipfs_add() {
+        let content = b"hello world\n";
+        assert_eq!(check_cid("QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o", content), CidCheck::Match);
+        assert_eq!(raw_cid(content), "bafkreifjjcie6lypi6ny7amxnfftagclbuxndqonfipmb64f2km2devei4");
+        assert_eq!(check_cid(&raw_cid(content), content), CidCheck::Match);
+        assert_eq!(raw_cid(b"quantera"), "bafkreibeataq6c3dvlxkkv35gusz2sd4orl5y56mt2fh2b7o6e5f2guoka");
+
+        assert_eq!(check_cid(&raw_cid(content), b"hello world!"), CidCheck::Mismatch);
+        assert!(matches!(check_cid("zdj7W", content), CidCheck::Unsupported(_)));
+    }
+
+    #[tokio::test]
+    async fn test_tampered_gateway_is_skipped_for_the_next() {
+        let content = b"compliance report".to_vec();
+        let cid = raw_cid(&content);
+        let client = client(vec![
+            Arc::new(FixedGateway { name: "primary", content: None }),
+            Arc::new(FixedGateway { name: "evil", content: Some(b"forged report".to_vec()) }),
+            Arc::new(FixedGateway { name: "honest", content: Some(content.clone()) }),
+        ]);
+
+        let (data, retrieval) = client.fetch_verified(&cid).await.unwrap();
+        assert_eq!(data, content);
+        assert_eq!(retrieval.served_by, "honest");
+        assert_eq!(retrieval.tampered, vec!["evil".to_string()]);
+        assert_eq!(retrieval.unavailable, vec!["primary".to_string()]);
+        assert_eq!(client.tamper_alert_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retrieval_fails_when_every_gateway_is_tampered() {
+        let cid = raw_cid(b"compliance report");
+        let client = client(vec![
+            Arc::new(FixedGateway { name: "a", content: Some(b"forged".to_vec()) }),
+            Arc::new(FixedGateway { name: "b", content: Some(b"also forged".to_vec()) }),
+        ]);
+
+        assert!(client.fetch_verified(&cid).await.is_err());
+        assert_eq!(client.tamper_alert_count(), 2);
+    }
+}
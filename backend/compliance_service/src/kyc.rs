@@ -6,6 +6,7 @@ use anyhow::Result;
 use chrono::{DateTime, Utc};
 use reqwest::{Client, StatusCode};
 use tracing::{info, warn, error};
+use quantera_types::Pii;
 use uuid::Uuid;
 
 // ============ KYC Provider Trait ============
@@ -45,14 +46,8 @@ pub struct KycCheck {
     pub details: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum KycStatus {
-    Pending,
-    InProgress,
-    Completed,
-    Failed,
-    Expired,
-}
+/// Shared with the backend, which also reports NotStarted, UnderReview and Rejected
+pub use quantera_types::KycStatus;
 
 // ============ Jumio Client Implementation ============
 
@@ -116,7 +111,7 @@ impl JumioClient {
 #[async_trait]
 impl KycProvider for JumioClient {
     async fn verify_identity(&self, params: KycParams) -> Result<KycResult> {
-        info!("Initiating Jumio KYC verification for investor: {}", params.investor_id);
+        info!(investor_id = %Pii::new(&params.investor_id), "Initiating Jumio KYC verification");
         
         // Create verification request
         let verification_id = Uuid::new_v4().to_string();
@@ -266,12 +261,18 @@ impl OnfidoClient {
                 .unwrap(),
         }
     }
+    
+    /// Send requests to `base_url` instead of the public API
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
 }
 
 #[async_trait]
 impl KycProvider for OnfidoClient {
     async fn verify_identity(&self, params: KycParams) -> Result<KycResult> {
-        info!("Initiating Onfido KYC verification for investor: {}", params.investor_id);
+        info!(investor_id = %Pii::new(&params.investor_id), "Initiating Onfido KYC verification");
         
         // Create applicant
         let applicant_body = serde_json::json!({
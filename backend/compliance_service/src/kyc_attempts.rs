@@ -0,0 +1,369 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+use crate::kyc::{KycCheck, KycResult};
+
+// ============ Attempts ============
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AttemptOutcome {
+    Verified,
+    /// The provider reviewed the investor and declined; counts against the retry limit
+    Rejected,
+    /// The provider could not be reached or errored; does not count against the investor
+    ProviderError,
+}
+
+impl AttemptOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttemptOutcome::Verified => "verified",
+            AttemptOutcome::Rejected => "rejected",
+            AttemptOutcome::ProviderError => "provider_error",
+        }
+    }
+}
+
+impl std::str::FromStr for AttemptOutcome {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "verified" => Ok(AttemptOutcome::Verified),
+            "rejected" => Ok(AttemptOutcome::Rejected),
+            "provider_error" => Ok(AttemptOutcome::ProviderError),
+            other => Err(format!("Unknown KYC attempt outcome: {}", other)),
+        }
+    }
+}
+
+/// One call to a KYC provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KycAttempt {
+    pub attempt_id: Uuid,
+    pub investor_id: String,
+    pub provider: String,
+    pub outcome: AttemptOutcome,
+    pub reason: Option<String>,
+    /// The provider's own verification or scan reference
+    pub reference_id: Option<String>,
+    pub attempted_at: DateTime<Utc>,
+}
+
+// ============ Retry Policy ============
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Rejections allowed per provider within the window
+    pub max_attempts_per_provider: u32,
+    pub window: Duration,
+    /// Wait after a rejection before the next attempt
+    pub cooldown: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts_per_provider: 2,
+            window: Duration::days(30),
+            cooldown: Duration::hours(24),
+        }
+    }
+}
+
+/// What a new verification request may do
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RetryStep {
+    /// Providers to try, in order; later ones are only used if earlier ones error
+    Attempt(Vec<String>),
+    CoolingDown { until: DateTime<Utc> },
+    /// Every provider has rejected the investor the maximum number of times
+    Exhausted,
+}
+
+impl RetryPolicy {
+    /// Next step for an investor given their attempt history and the configured providers,
+    /// in preference order. Providers with fewer rejections go first, and the provider that
+    /// rejected most recently goes last, so a retry rotates to the other provider.
+    pub fn next_step(&self, attempts: &[KycAttempt], providers: &[String], now: DateTime<Utc>) -> RetryStep {
+        let rejections: Vec<&KycAttempt> = attempts.iter()
+            .filter(|attempt| attempt.outcome == AttemptOutcome::Rejected && attempt.attempted_at > now - self.window)
+            .collect();
+
+        let last_rejection = rejections.iter().max_by_key(|attempt| attempt.attempted_at);
+        if let Some(last) = last_rejection {
+            let until = last.attempted_at + self.cooldown;
+            if now < until {
+                return RetryStep::CoolingDown { until };
+            }
+        }
+
+        let rejected_by = |provider: &str| rejections.iter().filter(|attempt| attempt.provider == provider).count() as u32;
+        let mut eligible: Vec<(usize, &String)> = providers.iter()
+            .enumerate()
+            .filter(|(_, provider)| rejected_by(provider) < self.max_attempts_per_provider)
+            .collect();
+        if eligible.is_empty() {
+            return RetryStep::Exhausted;
+        }
+
+        let last_provider = last_rejection.map(|attempt| attempt.provider.as_str());
+        eligible.sort_by_key(|(order, provider)| (rejected_by(provider), Some(provider.as_str()) == last_provider, *order));
+        RetryStep::Attempt(eligible.into_iter().map(|(_, provider)| provider.clone()).collect())
+    }
+
+    /// Investor-facing retry standing
+    pub fn status(
+        &self,
+        investor_id: &str,
+        attempts: Vec<KycAttempt>,
+        providers: &[String],
+        manual_review: Option<ManualReview>,
+        now: DateTime<Utc>,
+    ) -> KycRetryStatus {
+        let rejections_in_window = |provider: &String| attempts.iter()
+            .filter(|attempt| attempt.provider == *provider && attempt.outcome == AttemptOutcome::Rejected)
+            .filter(|attempt| attempt.attempted_at > now - self.window)
+            .count() as u32;
+        let attempts_remaining = providers.iter()
+            .map(|provider| self.max_attempts_per_provider.saturating_sub(rejections_in_window(provider)))
+            .sum();
+
+        let (cooldown_until, next_provider) = match self.next_step(&attempts, providers, now) {
+            RetryStep::Attempt(order) => (None, order.into_iter().next()),
+            RetryStep::CoolingDown { until } => (Some(until), None),
+            RetryStep::Exhausted => (None, None),
+        };
+
+        KycRetryStatus {
+            investor_id: investor_id.to_string(),
+            verified: attempts.iter().any(|attempt| attempt.outcome == AttemptOutcome::Verified)
+                || manual_review.as_ref().map_or(false, |review| review.status == ReviewStatus::Approved),
+            attempts_remaining,
+            cooldown_until,
+            next_provider,
+            manual_review,
+            attempts,
+        }
+    }
+}
+
+/// Unverified result returned without contacting a provider, e.g. during a cooldown
+pub fn deferred_result(reason: String, now: DateTime<Utc>) -> KycResult {
+    KycResult {
+        verification_id: format!("deferred:{}", Uuid::new_v4()),
+        verified: false,
+        kyc_level: 0,
+        reason: Some(reason),
+        checks: Vec::new(),
+        timestamp: now,
+        expiry: now,
+    }
+}
+
+/// Retry standing shown to the investor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KycRetryStatus {
+    pub investor_id: String,
+    pub verified: bool,
+    pub attempts_remaining: u32,
+    pub cooldown_until: Option<DateTime<Utc>>,
+    pub next_provider: Option<String>,
+    pub manual_review: Option<ManualReview>,
+    /// Newest first
+    pub attempts: Vec<KycAttempt>,
+}
+
+// ============ Manual Review ============
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewStatus {
+    Pending,
+    /// Officer verified the investor despite provider rejections
+    Approved,
+    Rejected,
+}
+
+impl ReviewStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewStatus::Pending => "pending",
+            ReviewStatus::Approved => "approved",
+            ReviewStatus::Rejected => "rejected",
+        }
+    }
+}
+
+impl std::str::FromStr for ReviewStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(ReviewStatus::Pending),
+            "approved" => Ok(ReviewStatus::Approved),
+            "rejected" => Ok(ReviewStatus::Rejected),
+            other => Err(format!("Unknown KYC review status: {}", other)),
+        }
+    }
+}
+
+/// Escalation opened when an investor runs out of provider attempts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManualReview {
+    pub review_id: Uuid,
+    pub investor_id: String,
+    pub status: ReviewStatus,
+    pub opened_at: DateTime<Utc>,
+    pub resolved_by: Option<String>,
+    pub justification: Option<String>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+/// Officer decision on a pending review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewResolution {
+    pub officer: String,
+    /// Approved or Rejected
+    pub status: ReviewStatus,
+    /// Required: the documented basis for the decision
+    pub justification: String,
+}
+
+impl ReviewResolution {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.status == ReviewStatus::Pending {
+            return Err("Resolution must approve or reject the review".to_string());
+        }
+        if self.officer.trim().is_empty() {
+            return Err("Resolving officer is required".to_string());
+        }
+        if self.justification.trim().len() < 20 {
+            return Err("Justification must document the basis for the decision".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl ManualReview {
+    /// KYC result standing in for the provider checks once an officer has approved the investor
+    pub fn override_result(&self, validity: Duration) -> Option<KycResult> {
+        if self.status != ReviewStatus::Approved {
+            return None;
+        }
+        let resolved_at = self.resolved_at.unwrap_or(self.opened_at);
+        Some(KycResult {
+            verification_id: format!("manual-review:{}", self.review_id),
+            verified: true,
+            kyc_level: 1,
+            reason: self.justification.clone(),
+            checks: vec![KycCheck {
+                check_type: "manual_review".to_string(),
+                passed: true,
+                details: self.resolved_by.as_ref().map(|officer| format!("Approved by {}", officer)),
+            }],
+            timestamp: resolved_at,
+            expiry: resolved_at + validity,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn providers() -> Vec<String> {
+        vec!["jumio".to_string(), "onfido".to_string()]
+    }
+
+    fn rejected(provider: &str, at: DateTime<Utc>) -> KycAttempt {
+        KycAttempt {
+            attempt_id: Uuid::new_v4(),
+            investor_id: "investor-1".to_string(),
+            provider: provider.to_string(),
+            outcome: AttemptOutcome::Rejected,
+            reason: Some("Document unreadable".to_string()),
+            reference_id: None,
+            attempted_at: at,
+        }
+    }
+
+    #[test]
+    fn test_rejections_rotate_provider_then_exhaust() {
+        let policy = RetryPolicy { max_attempts_per_provider: 1, window: Duration::days(30), cooldown: Duration::hours(1) };
+        let start = Utc::now() - Duration::days(1);
+        let mut attempts = Vec::new();
+
+        assert_eq!(policy.next_step(&attempts, &providers(), start), RetryStep::Attempt(providers()));
+
+        attempts.push(rejected("jumio", start));
+        assert_eq!(
+            policy.next_step(&attempts, &providers(), start + Duration::minutes(30)),
+            RetryStep::CoolingDown { until: start + Duration::hours(1) }
+        );
+        assert_eq!(
+            policy.next_step(&attempts, &providers(), start + Duration::hours(1)),
+            RetryStep::Attempt(vec!["onfido".to_string()])
+        );
+
+        attempts.push(rejected("onfido", start + Duration::hours(2)));
+        let later = start + Duration::hours(4);
+        assert_eq!(policy.next_step(&attempts, &providers(), later), RetryStep::Exhausted);
+
+        let status = policy.status("investor-1", attempts.clone(), &providers(), None, later);
+        assert_eq!(status.attempts_remaining, 0);
+        assert_eq!(status.next_provider, None);
+        assert!(!status.verified);
+
+        // Rejections age out of the window
+        assert_eq!(
+            policy.next_step(&attempts, &providers(), start + Duration::days(31)),
+            RetryStep::Attempt(providers())
+        );
+    }
+
+    #[test]
+    fn test_provider_errors_do_not_use_up_attempts() {
+        let policy = RetryPolicy::default();
+        let now = Utc::now();
+        let error = KycAttempt { outcome: AttemptOutcome::ProviderError, ..rejected("jumio", now) };
+
+        assert_eq!(policy.next_step(&[error.clone()], &providers(), now), RetryStep::Attempt(providers()));
+        assert_eq!(policy.status("investor-1", vec![error], &providers(), None, now).attempts_remaining, 4);
+    }
+
+    #[test]
+    fn test_approved_review_overrides_provider_rejections() {
+        let resolution = ReviewResolution {
+            officer: "officer-1".to_string(),
+            status: ReviewStatus::Approved,
+            justification: "ok".to_string(),
+        };
+        assert!(resolution.validate().is_err());
+
+        let now = Utc::now();
+        let mut review = ManualReview {
+            review_id: Uuid::new_v4(),
+            investor_id: "investor-1".to_string(),
+            status: ReviewStatus::Pending,
+            opened_at: now,
+            resolved_by: None,
+            justification: None,
+            resolved_at: None,
+        };
+        assert!(review.override_result(Duration::days(365)).is_none());
+
+        review.status = ReviewStatus::Approved;
+        review.resolved_by = Some("officer-1".to_string());
+        review.justification = Some("Passport verified in person against notarised copy".to_string());
+        review.resolved_at = Some(now);
+        let result = review.override_result(Duration::days(365)).unwrap();
+        assert!(result.verified);
+        assert_eq!(result.expiry, now + Duration::days(365));
+
+        let policy = RetryPolicy { max_attempts_per_provider: 1, ..RetryPolicy::default() };
+        let attempts = vec![rejected("jumio", now), rejected("onfido", now)];
+        assert!(policy.status("investor-1", attempts, &providers(), Some(review), now).verified);
+    }
+}
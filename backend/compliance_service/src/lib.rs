@@ -6,8 +6,13 @@
 //! - Real-time sanctions screening
 //! - Multi-jurisdiction tax calculation
 //! - Encrypted document storage on IPFS
+//! - AML transaction monitoring
+//! - Investor communication log linked to AML cases
+//! - Sanctions and PEP re-screening on a risk-tiered cadence
+//! - Investor offboarding with position wind-down and document retention holds
+//! - Investor self-service compliance status and task list
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use ethers::prelude::*;
@@ -29,18 +34,70 @@ use aes_gcm::{
 use rand::RngCore;
 use sha2::{Sha256, Digest};
 use strsim::levenshtein;
+use quantera_types::{FinalityPolicy, Pii};
 
 pub mod config;
 pub mod kyc;
 pub mod sanctions;
 pub mod tax;
 pub mod ipfs;
+pub mod passport;
+pub mod documents;
+pub mod monitoring;
+pub mod kyc_attempts;
+pub mod identity_registry;
+pub mod repository;
+pub mod communications;
+pub mod auth;
+pub mod screening;
+pub mod offboarding;
+pub mod self_service;
+pub mod beneficial_owners;
 
 use config::Config;
 use kyc::{KycProvider, KycParams, KycResult, KycStatus, JumioClient, OnfidoClient};
 use sanctions::{SanctionsScreener, SanctionedEntity, ScreeningResult};
-use tax::{TaxCalculator, TaxReport, Transaction, Form1099, WashSaleReport};
+use tax::{TaxCalculator, TaxReport, Transaction, Form1099, WashSaleReport, ImportedLot, LotImportReport};
 use ipfs::IpfsClient;
+use passport::{
+    PassportSigner, CompliancePassport, SignedPassport, PassportVerification, PassportRevocation,
+    KycAttestation, SanctionsAttestation, AccreditationAttestation,
+};
+use documents::{
+    DocumentType, InvestorDocument, DocumentSubmission, DocumentReplacement, DocumentExpirySweep, ExpiringDocument,
+    ExpiryStage, expiry_stage, effective_kyc_status, replacement_reinstates,
+};
+use monitoring::{
+    AmlStatus, AmlRule, AmlAlert, AlertStatus, AlertResolution, MonitoringRules, MonitoredTransaction,
+    MonitoringRun, evaluate_investor, status_after_resolution,
+};
+use kyc_attempts::{
+    AttemptOutcome, KycAttempt, RetryPolicy, RetryStep, KycRetryStatus, ManualReview, ReviewStatus,
+    ReviewResolution, deferred_result,
+};
+use identity_registry::{
+    IdentityRegistry, EthersIdentityRegistry, IdentityOp, IdentitySyncRun, IdentitySyncFailure,
+    SyncedIdentity, plan_identity_changes, push_identity_changes, apply_outcome,
+};
+use repository::ComplianceAuditEntry;
+use communications::{
+    Channel, Direction, Communication, NewCommunication, TimelineEntry, CaseDetail, CommunicationBody,
+    check_links, timeline,
+};
+use auth::{AuthError, Investor, Officer, authorize_investor, authorize_officer};
+use screening::{
+    PepScreener, HttpPepScreener, ScreeningSchedule, OverdueProfile, ScreeningHit, RescreeningRun,
+    overdue, new_hits,
+};
+use offboarding::{
+    Offboarding, OffboardingRequest, OffboardingStage, OffboardingBlocker, RemainingHolding, PositionSnapshot,
+    PositionSource, HttpPositionSource, initiation_blockers, completion_blockers, remaining_holdings, investment_block_violation,
+    retention_until,
+};
+use self_service::{SelfServiceStatus, HistoryEntry, CheckRecord, build_status, build_history};
+use beneficial_owners::{BeneficialOwner, UboScreening, UboStatus};
+use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
+use std::num::NonZeroU32;
 
 // ============ Error Types ============
 
@@ -85,6 +142,9 @@ pub enum ComplianceError {
     #[error("Invalid input: {0}")]
     InvalidInput(String),
     
+    #[error("Offboarding blocked by {} open items", .0.len())]
+    OffboardingBlocked(Vec<OffboardingBlocker>),
+    
     #[error("Internal error: {0}")]
     InternalError(String),
 }
@@ -119,6 +179,15 @@ pub struct InvestorProfile {
     pub jurisdiction: String,
     pub kyc_level: u8,
     pub kyc_expiry: DateTime<Utc>,
+    /// Maintained by the document expiry job; not written by profile updates
+    #[serde(default = "default_profile_kyc_status")]
+    pub kyc_status: KycStatus,
+    /// Maintained by transaction monitoring and officer review; not written by profile updates
+    #[serde(default = "default_profile_aml_status")]
+    pub aml_status: AmlStatus,
+    /// Set when offboarding is initiated; not written by profile updates
+    #[serde(default)]
+    pub investment_blocked: bool,
     pub accreditation_level: u8,
     pub risk_score: u32,
     pub total_invested: Decimal,
@@ -128,6 +197,14 @@ pub struct InvestorProfile {
     pub sanctioned: bool,
 }
 
+fn default_profile_kyc_status() -> KycStatus {
+    KycStatus::Completed
+}
+
+fn default_profile_aml_status() -> AmlStatus {
+    AmlStatus::Clear
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComplianceReport {
     pub report_id: Uuid,
@@ -140,6 +217,9 @@ pub struct ComplianceReport {
     pub tax_implications: Option<TaxReport>,
     pub violations: Vec<Violation>,
     pub recommendations: Vec<String>,
+    /// Last screening of each recorded beneficial owner; empty for individual investors
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub beneficial_owners: Vec<UboScreening>,
     pub generated_at: DateTime<Utc>,
     pub ipfs_hash: Option<String>,
 }
@@ -172,6 +252,15 @@ pub struct ComplianceService {
     tax_calculator: Arc<TaxCalculator>,
     ipfs_client: Arc<IpfsClient>,
     compliance_engine_address: Address,
+    passport_signer: Arc<PassportSigner>,
+    passport_revocations: Arc<RwLock<HashMap<Uuid, PassportRevocation>>>,
+    monitoring_rules: Arc<MonitoringRules>,
+    identity_registry: Option<Arc<dyn IdentityRegistry>>,
+    pep_screener: Option<Arc<dyn PepScreener>>,
+    /// Open orders, trades, margin calls and holdings checked before offboarding
+    position_source: Option<Arc<dyn PositionSource>>,
+    /// Paces sanctions and PEP provider calls during re-screening
+    screening_limiter: Arc<DefaultDirectRateLimiter>,
 }
 
 impl ComplianceService {
@@ -212,10 +301,11 @@ impl ComplianceService {
         }
         
         if let Some(onfido_token) = config.onfido_api_token.clone() {
-            kyc_providers.insert(
-                "onfido".to_string(),
-                Box::new(OnfidoClient::new(onfido_token)),
-            );
+            let mut onfido = OnfidoClient::new(onfido_token);
+            if let Some(url) = &config.onfido_api_url {
+                onfido = onfido.with_base_url(url);
+            }
+            kyc_providers.insert("onfido".to_string(), Box::new(onfido));
         }
         
         // Initialize sanctions screener
@@ -228,10 +318,66 @@ impl ComplianceService {
         let tax_calculator = TaxCalculator::new(Arc::new(db.clone()));
         
         // Initialize IPFS client
-        let ipfs_client = IpfsClient::new(
+        let mut ipfs_client = IpfsClient::new(
             &config.ipfs_api_url,
             config.encryption_key.clone(),
+        )?
+        .with_gateways(config.ipfs_gateway_urls.iter()
+            .map(|url| Arc::new(ipfs::HttpGateway::new(url)) as Arc<dyn ipfs::IpfsGateway>)
+            .collect())
+        .with_gateway_timeout(std::time::Duration::from_secs(config.ipfs_gateway_timeout_secs));
+        if let (Some(url), Some(token)) = (&config.ipfs_replica_pin_url, &config.ipfs_replica_pin_token) {
+            ipfs_client = ipfs_client.with_replica(Arc::new(ipfs::RemotePinningService::new(url, token)));
+        }
+        
+        // Initialize passport signer and load revocations so verification stays in memory
+        let passport_signer = PassportSigner::new(
+            &config.passport_signing_key,
+            config.passport_validity_days,
         )?;
+        info!("Compliance passports signed by: {:?}", passport_signer.address());
+        
+        let passport_revocations = Self::load_passport_revocations(&db).await?;
+        
+        // Transaction monitoring thresholds, with per-jurisdiction overrides when configured
+        let monitoring_rules = match &config.aml_rules_path {
+            Some(path) => MonitoringRules::from_file(path).map_err(ComplianceError::ConfigurationError)?,
+            None => MonitoringRules::default(),
+        };
+        
+        // Identity registry sync runs only when both the registry and its signer are configured
+        let identity_registry: Option<Arc<dyn IdentityRegistry>> = match (&config.identity_registry_address, &config.identity_registry_signer_key) {
+            (Some(address), Some(key)) => {
+                let address = address.parse::<Address>()
+                    .map_err(|e| ComplianceError::ConfigurationError(format!("Invalid IDENTITY_REGISTRY_ADDRESS: {}", e)))?;
+                let chain_id = eth_client.get_chainid().await
+                    .map_err(|e| ComplianceError::ConfigurationError(format!("Failed to read chain id: {}", e)))?;
+                let signer = key.parse::<LocalWallet>()
+                    .map_err(|e| ComplianceError::ConfigurationError(format!("Invalid IDENTITY_REGISTRY_SIGNER_KEY: {}", e)))?
+                    .with_chain_id(chain_id.as_u64());
+                let finality = FinalityPolicy::from_env(chain_id.as_u64())
+                    .map_err(|e| ComplianceError::ConfigurationError(e.to_string()))?;
+                info!("Identity registry sync enabled for {:?}, events confirmed at {}", address, finality.confirmed);
+                Some(Arc::new(EthersIdentityRegistry::new(eth_client.clone(), signer, address, finality)))
+            }
+            (None, None) => None,
+            _ => {
+                return Err(ComplianceError::ConfigurationError(
+                    "IDENTITY_REGISTRY_ADDRESS and IDENTITY_REGISTRY_SIGNER_KEY must be set together".to_string()
+                ));
+            }
+        };
+        
+        let pep_screener = match (&config.pep_screening_api_url, &config.pep_screening_api_key) {
+            (Some(url), Some(key)) => Some(Arc::new(HttpPepScreener::new(url, key)) as Arc<dyn PepScreener>),
+            _ => None,
+        };
+        let position_source = match (&config.position_service_url, &config.position_service_api_key) {
+            (Some(url), Some(key)) => Some(Arc::new(HttpPositionSource::new(url, key)) as Arc<dyn PositionSource>),
+            _ => None,
+        };
+        let screening_rate = NonZeroU32::new(config.rescreening_rate_per_minute)
+            .ok_or_else(|| ComplianceError::ConfigurationError("RESCREENING_RATE_PER_MINUTE must be at least 1".to_string()))?;
         
         info!("Compliance Service initialized successfully");
         
@@ -245,6 +391,13 @@ impl ComplianceService {
             tax_calculator,
             ipfs_client: Arc::new(ipfs_client),
             compliance_engine_address,
+            passport_signer: Arc::new(passport_signer),
+            passport_revocations: Arc::new(RwLock::new(passport_revocations)),
+            monitoring_rules: Arc::new(monitoring_rules),
+            identity_registry,
+            pep_screener,
+            position_source,
+            screening_limiter: Arc::new(RateLimiter::direct(Quota::per_minute(screening_rate))),
         })
     }
     
@@ -256,21 +409,26 @@ impl ComplianceService {
         amount: Decimal,
         asset_address: Option<Address>,
     ) -> Result<ComplianceReport, ComplianceError> {
-        info!("Performing compliance check for investor: {:?}", investor_address);
+        info!(investor = %Pii::debug(investor_address), "Performing compliance check");
         
         let report_id = Uuid::new_v4();
         let mut violations = Vec::new();
         let mut recommendations = Vec::new();
         
+        let profile = self.get_investor_profile(investor_address).await?;
+        let investment_blocked = profile.as_ref().map_or(false, |profile| profile.investment_blocked);
+        
         // 1. Check cache first
         let cache_key = format!("compliance:{}:{}", investor_address, jurisdiction);
         let mut cache = self.cache.write().await;
         
         if let Ok(cached) = cache.get::<_, String>(&cache_key).await {
             if let Ok(report) = serde_json::from_str::<ComplianceReport>(&cached) {
-                // Check if cache is still valid (24 hours)
+                // Check if cache is still valid (24 hours). Reports cached before offboarding
+                // blocked or re-onboarding unblocked the investor are stale immediately.
                 let age = Utc::now() - report.generated_at;
-                if age.num_hours() < 24 {
+                let cached_blocked = report.violations.iter().any(|v| v.violation_type == "INVESTMENT_BLOCKED");
+                if age.num_hours() < 24 && cached_blocked == investment_blocked {
                     info!("Returning cached compliance report");
                     return Ok(report);
                 }
@@ -279,7 +437,7 @@ impl ComplianceService {
         
         // 2. KYC Verification
         let kyc_params = KycParams {
-            investor_id: investor_address.to_string(),
+            investor_id: self_service::investor_id(investor_address),
             document_type: "passport".to_string(),
             country: jurisdiction.to_string(),
             metadata: HashMap::new(),
@@ -295,19 +453,40 @@ impl ComplianceService {
             });
         }
         
+        if let Some(profile) = &profile {
+            // Identity document lapses downgrade KYC even when the check itself has not expired
+            if profile.kyc_status == KycStatus::Expired {
+                violations.push(Violation {
+                    violation_type: "KYC_EXPIRED".to_string(),
+                    description: "KYC status is expired; identity document must be replaced".to_string(),
+                    severity: ViolationSeverity::Critical,
+                });
+            }
+            violations.extend(investment_block_violation(profile));
+        }
+        
         // 3. Sanctions Screening
         let sanctions_result = self.sanctions_screener
             .screen_address(investor_address)
             .await?;
         
+        // A manual check counts as a screening and restarts the re-screening clock
+        self.mark_screened(investor_address, sanctions_result.screened_at).await?;
+        
         if sanctions_result.is_sanctioned {
             violations.push(Violation {
                 violation_type: "SANCTIONS_HIT".to_string(),
                 description: format!("Found on sanctions list: {:?}", sanctions_result.lists),
                 severity: ViolationSeverity::Critical,
             });
+            
+            self.revoke_compliance_passports(investor_address, "Sanctions screening hit").await?;
         }
         
+        // An institution is only as clear as its beneficial owners, screened when its register changed
+        let beneficial_owners = self.get_beneficial_owners(investor_address).await?;
+        violations.extend(beneficial_owners::violations(&beneficial_owners));
+        
         // 4. Tax Calculation (if applicable)
         let tax_implications = if amount > dec!(0) {
             let transaction = Transaction {
@@ -350,6 +529,12 @@ impl ComplianceService {
                 recommendations.push("Enhanced KYC verification recommended".to_string());
             }
         }
+        for screening in beneficial_owners.iter().filter(|screening| screening.status == UboStatus::Pep) {
+            recommendations.push(format!(
+                "Enhanced due diligence recommended for politically exposed beneficial owner {}",
+                screening.owner.full_name
+            ));
+        }
         
         // Create report
         let report = ComplianceReport {
@@ -363,6 +548,7 @@ impl ComplianceService {
             tax_implications,
             violations: violations.clone(),
             recommendations,
+            beneficial_owners,
             generated_at: Utc::now(),
             ipfs_hash: None,
         };
@@ -374,119 +560,297 @@ impl ComplianceService {
         let mut final_report = report.clone();
         final_report.ipfs_hash = Some(ipfs_hash.clone());
         
-        // Cache the report
-        let report_str = serde_json::to_string(&final_report)?;
-        let _: () = cache.set_ex(&cache_key, report_str.as_str(), 86400).await?; // 24 hour TTL
+        // Report and its audit entry commit together; the cache is only filled once they have
+        let mut tx = self.db.begin().await?;
+        repository::save_compliance_report(&mut tx, &final_report, &ComplianceAuditEntry {
+            event_type: "COMPLIANCE_CHECK".to_string(),
+            entity_type: "investor".to_string(),
+            entity_id: format!("{:?}", investor_address),
+            actor: None,
+            action: "report_generated".to_string(),
+            details: serde_json::json!({
+                "report_id": final_report.report_id,
+                "jurisdiction": final_report.jurisdiction,
+                "violations": violations.len(),
+                "ipfs_hash": ipfs_hash,
+            }),
+        }).await?;
+        tx.commit().await?;
         
-        // Store in database
-        self.store_compliance_report(&final_report).await?;
+        // A cache miss only costs a recomputation, so a failed write does not fail the check
+        let report_str = serde_json::to_string(&final_report)?;
+        if let Err(e) = cache.set_ex::<_, _, ()>(&cache_key, report_str.as_str(), 86400).await { // 24 hour TTL
+            warn!("Failed to cache compliance report {}: {}", final_report.report_id, e);
+        }
         
         info!("Compliance check completed. Violations: {}, IPFS: {}", violations.len(), ipfs_hash);
         
         Ok(final_report)
     }
     
-    /// Verify KYC using available providers with fallback
+    /// Verify KYC under the retry policy, rotating between providers and recording every
+    /// attempt. An officer-approved manual review stands in for provider verification.
     pub async fn verify_kyc(&self, params: KycParams) -> Result<KycResult, ComplianceError> {
-        // Try primary provider (Jumio)
-        if let Some(jumio) = self.kyc_providers.get("jumio") {
-            match jumio.verify_identity(params.clone()).await {
-                Ok(result) if result.verified => return Ok(result),
-                Ok(result) => {
-                    warn!("Jumio verification failed, trying Onfido: {:?}", result.reason);
-                }
-                Err(e) => {
-                    error!("Jumio error: {}, trying Onfido", e);
-                }
+        let now = Utc::now();
+        let investor_id = params.investor_id.clone();
+        let providers = self.kyc_provider_order();
+        if providers.is_empty() {
+            return Err(ComplianceError::KycVerificationFailed("No KYC providers available".to_string()));
+        }
+        
+        if let Some(review) = self.latest_kyc_review(&investor_id).await? {
+            if let Some(result) = review.override_result(chrono::Duration::days(365)) {
+                return Ok(result);
+            }
+            if review.status == ReviewStatus::Pending {
+                return Ok(deferred_result(format!("KYC is awaiting manual review {}", review.review_id), now));
             }
         }
         
-        // Fallback to Onfido
-        if let Some(onfido) = self.kyc_providers.get("onfido") {
-            match onfido.verify_identity(params).await {
-                Ok(result) => return Ok(result),
+        let attempts = self.get_kyc_attempts(&investor_id).await?;
+        let order = match self.kyc_retry_policy().next_step(&attempts, &providers, now) {
+            RetryStep::Attempt(order) => order,
+            RetryStep::CoolingDown { until } => {
+                return Ok(deferred_result(format!("KYC can be retried after {}", until), now));
+            }
+            RetryStep::Exhausted => {
+                let review = self.open_kyc_review(&investor_id).await?;
+                return Ok(deferred_result(
+                    format!("KYC attempts exhausted; escalated to manual review {}", review.review_id),
+                    now,
+                ));
+            }
+        };
+        
+        // A rejection ends the request and the next retry rotates provider; errors fail over now
+        let mut last_error = None;
+        for name in order {
+            let Some(provider) = self.kyc_providers.get(&name) else { continue };
+            match provider.verify_identity(params.clone()).await {
+                Ok(result) => {
+                    let outcome = if result.verified { AttemptOutcome::Verified } else { AttemptOutcome::Rejected };
+                    if !result.verified {
+                        warn!(provider = %name, investor_id = %Pii::new(&investor_id), reason = ?result.reason, "KYC rejected");
+                    }
+                    self.record_kyc_attempt(&investor_id, &name, outcome, result.reason.as_deref(), Some(&result.verification_id)).await?;
+                    return Ok(result);
+                }
                 Err(e) => {
-                    error!("Onfido error: {}", e);
-                    return Err(ComplianceError::KycVerificationFailed(format!("All providers failed: {}", e)));
+                    error!("{} error: {}, trying next provider", name, e);
+                    self.record_kyc_attempt(&investor_id, &name, AttemptOutcome::ProviderError, Some(&e.to_string()), None).await?;
+                    last_error = Some(e.to_string());
                 }
             }
         }
         
-        Err(ComplianceError::KycVerificationFailed("No KYC providers available".to_string()))
+        Err(ComplianceError::KycVerificationFailed(format!(
+            "All providers failed: {}", last_error.unwrap_or_default()
+        )))
     }
     
-    /// Update investor profile in database and on-chain
-    pub async fn update_investor_profile(
+    fn kyc_retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts_per_provider: self.config.kyc_max_attempts_per_provider,
+            window: chrono::Duration::days(self.config.kyc_attempt_window_days),
+            cooldown: chrono::Duration::hours(self.config.kyc_retry_cooldown_hours),
+        }
+    }
+    
+    /// Configured providers in preference order
+    fn kyc_provider_order(&self) -> Vec<String> {
+        ["jumio", "onfido"].iter()
+            .filter(|name| self.kyc_providers.contains_key(**name))
+            .map(|name| name.to_string())
+            .collect()
+    }
+    
+    async fn record_kyc_attempt(
         &self,
-        profile: InvestorProfile,
+        investor_id: &str,
+        provider: &str,
+        outcome: AttemptOutcome,
+        reason: Option<&str>,
+        reference_id: Option<&str>,
     ) -> Result<(), ComplianceError> {
-        // Update database
         sqlx::query(
             r#"
-            INSERT INTO investor_profiles (
-                address, jurisdiction, kyc_level, kyc_expiry, 
-                accreditation_level, risk_score, total_invested,
-                documents_ipfs, last_check, pep, sanctioned
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
-            ON CONFLICT (address) DO UPDATE SET
-                jurisdiction = $2, kyc_level = $3, kyc_expiry = $4,
-                accreditation_level = $5, risk_score = $6, total_invested = $7,
-                documents_ipfs = $8, last_check = $9, pep = $10, sanctioned = $11,
-                updated_at = NOW()
+            INSERT INTO kyc_verification_attempts (attempt_id, investor_id, provider, outcome, reason, reference_id)
+            VALUES ($1, $2, $3, $4, $5, $6)
             "#
         )
-        .bind(profile.address.as_bytes())
-        .bind(&profile.jurisdiction)
-        .bind(profile.kyc_level as i16)
-        .bind(profile.kyc_expiry)
-        .bind(profile.accreditation_level as i16)
-        .bind(profile.risk_score as i32)
-        .bind(profile.total_invested.to_string())
-        .bind(&profile.documents_ipfs)
-        .bind(profile.last_check)
-        .bind(profile.pep)
-        .bind(profile.sanctioned)
+        .bind(Uuid::new_v4())
+        .bind(investor_id)
+        .bind(provider)
+        .bind(outcome.as_str())
+        .bind(reason)
+        .bind(reference_id)
         .execute(self.db.as_ref())
         .await?;
         
-        // Update on-chain if needed
-        // TODO: Call AutomatedComplianceEngine.setInvestorProfile()
-        
-        info!("Updated investor profile for: {:?}", profile.address);
         Ok(())
     }
     
-    /// Store compliance report in database
-    async fn store_compliance_report(
-        &self,
-        report: &ComplianceReport,
-    ) -> Result<(), ComplianceError> {
-        let violations_json = serde_json::to_value(&report.violations)?;
-        let recommendations_json = serde_json::to_value(&report.recommendations)?;
+    /// An investor's KYC attempts, newest first
+    pub async fn get_kyc_attempts(&self, investor_id: &str) -> Result<Vec<KycAttempt>, ComplianceError> {
+        let rows = sqlx::query_as::<_, KycAttemptRow>(
+            r#"
+            SELECT attempt_id, investor_id, provider, outcome, reason, reference_id, attempted_at
+            FROM kyc_verification_attempts
+            WHERE investor_id = $1
+            ORDER BY attempted_at DESC
+            "#
+        )
+        .bind(investor_id)
+        .fetch_all(self.db.as_ref())
+        .await?;
         
-        sqlx::query(
+        rows.into_iter().map(kyc_attempt_from_row).collect()
+    }
+    
+    async fn latest_kyc_review(&self, investor_id: &str) -> Result<Option<ManualReview>, ComplianceError> {
+        let row = sqlx::query_as::<_, KycReviewRow>(
             r#"
-            INSERT INTO compliance_reports (
-                report_id, investor_address, asset_address, amount,
-                jurisdiction, kyc_verified, sanctions_passed,
-                violations, recommendations, ipfs_hash, generated_at
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
-            "#
-        )
-        .bind(&report.report_id)
-        .bind(report.investor.as_bytes())
-        .bind(report.asset.map(|a| a.as_bytes().to_vec()))
-        .bind(report.amount.to_string())
-        .bind(&report.jurisdiction)
-        .bind(report.kyc_result.verified)
-        .bind(!report.sanctions_result.is_sanctioned)
-        .bind(violations_json)
-        .bind(recommendations_json)
-        .bind(report.ipfs_hash.as_deref())
-        .bind(report.generated_at)
-        .execute(self.db.as_ref())
+            SELECT review_id, investor_id, status, opened_at, resolved_by, justification, resolved_at
+            FROM kyc_manual_reviews
+            WHERE investor_id = $1
+            ORDER BY opened_at DESC
+            LIMIT 1
+            "#
+        )
+        .bind(investor_id)
+        .fetch_optional(self.db.as_ref())
+        .await?;
+        
+        row.map(kyc_review_from_row).transpose()
+    }
+    
+    /// The investor's pending review, opening one if there is none
+    async fn open_kyc_review(&self, investor_id: &str) -> Result<ManualReview, ComplianceError> {
+        let inserted = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO kyc_manual_reviews (review_id, investor_id)
+            VALUES ($1, $2)
+            ON CONFLICT (investor_id) WHERE status = 'pending' DO NOTHING
+            RETURNING review_id
+            "#
+        )
+        .bind(Uuid::new_v4())
+        .bind(investor_id)
+        .fetch_optional(self.db.as_ref())
+        .await?;
+        
+        if let Some(review_id) = inserted {
+            warn!(review_id = %review_id, investor_id = %Pii::new(&investor_id), "[AUDIT] KYC manual review opened: provider attempts exhausted");
+        }
+        
+        self.latest_kyc_review(investor_id).await?
+            .ok_or_else(|| ComplianceError::InternalError(format!("KYC review for {} not found", investor_id)))
+    }
+    
+    /// Attempts remaining, cooldown end and review state for the investor-facing status view
+    pub async fn kyc_retry_status(&self, investor_id: &str) -> Result<KycRetryStatus, ComplianceError> {
+        let attempts = self.get_kyc_attempts(investor_id).await?;
+        let review = self.latest_kyc_review(investor_id).await?;
+        Ok(self.kyc_retry_policy().status(investor_id, attempts, &self.kyc_provider_order(), review, Utc::now()))
+    }
+    
+    /// KYC manual reviews for compliance officers, newest first
+    pub async fn get_kyc_reviews(
+        &self,
+        status: Option<ReviewStatus>,
+    ) -> Result<Vec<ManualReview>, ComplianceError> {
+        let rows = sqlx::query_as::<_, KycReviewRow>(
+            r#"
+            SELECT review_id, investor_id, status, opened_at, resolved_by, justification, resolved_at
+            FROM kyc_manual_reviews
+            WHERE $1::TEXT IS NULL OR status = $1
+            ORDER BY opened_at DESC
+            "#
+        )
+        .bind(status.map(|s| s.as_str()))
+        .fetch_all(self.db.as_ref())
         .await?;
         
+        rows.into_iter().map(kyc_review_from_row).collect()
+    }
+    
+    /// Record an officer's decision on a pending KYC review. Approval overrides the
+    /// provider rejections for this investor until the officer's result expires.
+    pub async fn resolve_kyc_review(
+        &self,
+        review_id: Uuid,
+        resolution: ReviewResolution,
+    ) -> Result<ManualReview, ComplianceError> {
+        resolution.validate().map_err(ComplianceError::InvalidInput)?;
+        
+        let mut tx = self.db.begin().await?;
+        let row = sqlx::query_as::<_, KycReviewRow>(
+            r#"
+            UPDATE kyc_manual_reviews
+            SET status = $2, resolved_by = $3, justification = $4, resolved_at = NOW()
+            WHERE review_id = $1 AND status = 'pending'
+            RETURNING review_id, investor_id, status, opened_at, resolved_by, justification, resolved_at
+            "#
+        )
+        .bind(review_id)
+        .bind(resolution.status.as_str())
+        .bind(&resolution.officer)
+        .bind(&resolution.justification)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| ComplianceError::InvalidInput(format!("No pending KYC review {}", review_id)))?;
+        let review = kyc_review_from_row(row)?;
+        
+        repository::insert_audit_entry(&mut tx, &ComplianceAuditEntry {
+            event_type: "KYC_REVIEW_RESOLVED".to_string(),
+            entity_type: "kyc_manual_review".to_string(),
+            entity_id: review_id.to_string(),
+            actor: Some(resolution.officer.clone()),
+            action: resolution.status.as_str().to_string(),
+            details: serde_json::json!({
+                "investor_id": review.investor_id,
+                "justification": resolution.justification,
+            }),
+        }).await?;
+        tx.commit().await?;
+        
+        info!(
+            review_id = %review_id,
+            investor_id = %Pii::new(&review.investor_id),
+            status = resolution.status.as_str(),
+            officer = %resolution.officer,
+            "[AUDIT] KYC review resolved"
+        );
+        Ok(review)
+    }
+    
+    /// Update investor profile in database and on-chain
+    pub async fn update_investor_profile(
+        &self,
+        profile: InvestorProfile,
+    ) -> Result<(), ComplianceError> {
+        // Profile and audit entry are written atomically
+        let mut tx = self.db.begin().await?;
+        repository::save_investor_profile(&mut tx, &profile, &ComplianceAuditEntry {
+            event_type: "PROFILE_UPDATED".to_string(),
+            entity_type: "investor_profile".to_string(),
+            entity_id: format!("{:?}", profile.address),
+            actor: None,
+            action: "upsert".to_string(),
+            details: serde_json::json!({
+                "jurisdiction": profile.jurisdiction,
+                "kyc_level": profile.kyc_level,
+                "accreditation_level": profile.accreditation_level,
+                "pep": profile.pep,
+                "sanctioned": profile.sanctioned,
+            }),
+        }).await?;
+        tx.commit().await?;
+        
+        // Update on-chain if needed
+        // TODO: Call AutomatedComplianceEngine.setInvestorProfile()
+        
+        info!(investor = %Pii::debug(profile.address), "Updated investor profile");
         Ok(())
     }
     
@@ -500,7 +864,7 @@ impl ComplianceService {
         // TODO: Implement actual contract call to AutomatedComplianceEngine
         // For now, return mock result
         
-        debug!("Checking on-chain compliance for investor: {:?}", investor);
+        debug!(investor = %Pii::debug(investor), "Checking on-chain compliance");
         
         // Simulate contract call
         let amount_wei = amount.to_string().parse::<f64>().unwrap_or(0.0) * 1e18;
@@ -513,28 +877,2231 @@ impl ComplianceService {
         }
     }
     
-    /// Generate compliance statistics
-    pub async fn get_compliance_stats(&self) -> Result<HashMap<String, serde_json::Value>, ComplianceError> {
-        let mut stats = HashMap::new();
+    /// Load an investor profile from the database
+    pub async fn get_investor_profile(
+        &self,
+        investor: Address,
+    ) -> Result<Option<InvestorProfile>, ComplianceError> {
+        let row = sqlx::query_as::<_, ProfileRow>(
+            r#"
+            SELECT address, jurisdiction, kyc_level, kyc_expiry, accreditation_level, risk_score,
+                   total_invested::TEXT, documents_ipfs, last_check, pep, sanctioned, kyc_status, aml_status,
+                   investment_blocked
+            FROM investor_profiles
+            WHERE address = $1
+            "#
+        )
+        .bind(investor.as_bytes())
+        .fetch_optional(self.db.as_ref())
+        .await?;
         
-        // Get total checks today
-        let today_count: i64 = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(*) FROM compliance_reports WHERE generated_at > NOW() - INTERVAL '1 day'"
+        Ok(row.map(profile_from_row))
+    }
+    
+    /// Every investor profile, for jobs that mirror profiles elsewhere
+    async fn load_investor_profiles(&self) -> Result<Vec<InvestorProfile>, ComplianceError> {
+        let rows = sqlx::query_as::<_, ProfileRow>(
+            r#"
+            SELECT address, jurisdiction, kyc_level, kyc_expiry, accreditation_level, risk_score,
+                   total_invested::TEXT, documents_ipfs, last_check, pep, sanctioned, kyc_status, aml_status,
+                   investment_blocked
+            FROM investor_profiles
+            ORDER BY address
+            "#
         )
-        .fetch_one(self.db.as_ref())
-        .await
-        .unwrap_or(0);
+        .fetch_all(self.db.as_ref())
+        .await?;
         
-        stats.insert("checks_today".to_string(), serde_json::json!(today_count));
+        Ok(rows.into_iter().map(profile_from_row).collect())
+    }
+    
+    /// Issue a signed compliance passport for an investor
+    ///
+    /// The passport attests KYC, sanctions/PEP and accreditation standing without
+    /// including any documents. Sanctioned investors are refused and any passports
+    /// previously issued to them are revoked.
+    pub async fn generate_compliance_passport(
+        &self,
+        investor: Address,
+    ) -> Result<SignedPassport, ComplianceError> {
+        info!(investor = %Pii::debug(investor), "Generating compliance passport");
         
-        // Get violation breakdown - temporarily disabled for Phase 1 (requires runtime query mapping)
-        // TODO: Re-enable with proper sqlx prepared queries or runtime mapping
-        let violations: Vec<(String, i64)> = Vec::new();
+        let profile = self.get_investor_profile(investor).await?
+            .ok_or_else(|| ComplianceError::InvalidInput(format!("No investor profile for {:?}", investor)))?;
         
-        stats.insert("violations_7d".to_string(), serde_json::json!(violations));
+        // Screen again so the attestation reflects current lists
+        let screening = self.sanctions_screener
+            .screen_address(investor)
+            .await?;
         
-        Ok(stats)
+        if screening.is_sanctioned || profile.sanctioned {
+            self.revoke_compliance_passports(investor, "Sanctions screening hit").await?;
+            return Err(ComplianceError::InvalidInput("Passport cannot be issued to a sanctioned investor".to_string()));
+        }
+        
+        let now = Utc::now();
+        if profile.kyc_expiry <= now {
+            return Err(ComplianceError::InvalidInput("KYC has expired, re-verification required".to_string()));
+        }
+        
+        if profile.kyc_status == KycStatus::Expired {
+            return Err(ComplianceError::InvalidInput("Identity document has expired, replacement required".to_string()));
+        }
+        
+        // Passport freshness never outlives the underlying KYC
+        let expires_at = std::cmp::min(now + self.passport_signer.validity(), profile.kyc_expiry);
+        
+        let passport = CompliancePassport {
+            passport_id: Uuid::new_v4(),
+            investor,
+            jurisdiction: profile.jurisdiction.clone(),
+            kyc: KycAttestation {
+                level: profile.kyc_level,
+                expiry: profile.kyc_expiry,
+            },
+            sanctions: SanctionsAttestation {
+                sanctioned: false,
+                pep: profile.pep,
+                screened_at: screening.screened_at,
+                list_versions: self.sanctions_screener.list_versions().await,
+            },
+            accreditation: AccreditationAttestation {
+                accredited: profile.accreditation_level > 0,
+                level: profile.accreditation_level,
+            },
+            issuer: self.passport_signer.address(),
+            issued_at: now,
+            expires_at,
+        };
+        
+        let signed = self.passport_signer.sign(passport).await?;
+        
+        sqlx::query(
+            r#"
+            INSERT INTO compliance_passports (
+                passport_id, investor_address, issued_at, expires_at, signature
+            ) VALUES ($1, $2, $3, $4, $5)
+            "#
+        )
+        .bind(signed.passport.passport_id)
+        .bind(investor.as_bytes())
+        .bind(signed.passport.issued_at)
+        .bind(signed.passport.expires_at)
+        .bind(&signed.signature)
+        .execute(self.db.as_ref())
+        .await?;
+        
+        info!(passport_id = %signed.passport.passport_id, investor = %Pii::debug(investor), "[AUDIT] Compliance passport issued");
+        
+        Ok(signed)
+    }
+    
+    /// Verify a passport's signature, freshness window and revocation status
+    ///
+    /// Uses only the signing key and the in-memory revocation list, no database access.
+    pub async fn verify_compliance_passport(&self, signed: &SignedPassport) -> PassportVerification {
+        let mut verification = self.passport_signer.verify(signed, Utc::now());
+        
+        if let Some(revocation) = self.passport_revocations.read().await.get(&signed.passport.passport_id) {
+            verification.revoked = true;
+            verification.valid = false;
+            verification.reason = Some(format!("Passport revoked: {}", revocation.reason));
+        }
+        
+        verification
+    }
+    
+    /// Revoke all unexpired passports issued to an investor
+    pub async fn revoke_compliance_passports(
+        &self,
+        investor: Address,
+        reason: &str,
+    ) -> Result<Vec<PassportRevocation>, ComplianceError> {
+        let passport_ids: Vec<Uuid> = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            UPDATE compliance_passports
+            SET revoked_at = NOW(), revocation_reason = $2
+            WHERE investor_address = $1 AND revoked_at IS NULL AND expires_at > NOW()
+            RETURNING passport_id
+            "#
+        )
+        .bind(investor.as_bytes())
+        .bind(reason)
+        .fetch_all(self.db.as_ref())
+        .await?;
+        
+        let now = Utc::now();
+        let revocations: Vec<PassportRevocation> = passport_ids.into_iter()
+            .map(|passport_id| PassportRevocation {
+                passport_id,
+                investor,
+                reason: reason.to_string(),
+                revoked_at: now,
+            })
+            .collect();
+        
+        if !revocations.is_empty() {
+            let mut cache = self.passport_revocations.write().await;
+            for revocation in &revocations {
+                cache.insert(revocation.passport_id, revocation.clone());
+            }
+            warn!(revoked = revocations.len(), investor = %Pii::debug(investor), reason = %reason, "[AUDIT] Compliance passports revoked");
+        }
+        
+        Ok(revocations)
+    }
+    
+    /// Published revocation list for third-party verifiers
+    pub async fn get_passport_revocations(&self) -> Vec<PassportRevocation> {
+        let mut revocations: Vec<PassportRevocation> = self.passport_revocations.read().await
+            .values()
+            .cloned()
+            .collect();
+        revocations.sort_by(|a, b| b.revoked_at.cmp(&a.revoked_at));
+        revocations
+    }
+    
+    /// Address of the key that signs compliance passports
+    pub fn passport_signer_address(&self) -> Address {
+        self.passport_signer.address()
+    }
+    
+    async fn load_passport_revocations(
+        db: &PgPool,
+    ) -> Result<HashMap<Uuid, PassportRevocation>, ComplianceError> {
+        let rows = sqlx::query_as::<_, (Uuid, Vec<u8>, Option<String>, DateTime<Utc>)>(
+            r#"
+            SELECT passport_id, investor_address, revocation_reason, revoked_at
+            FROM compliance_passports
+            WHERE revoked_at IS NOT NULL
+            "#
+        )
+        .fetch_all(db)
+        .await?;
+        
+        Ok(rows.into_iter()
+            .map(|(passport_id, investor, reason, revoked_at)| {
+                (passport_id, PassportRevocation {
+                    passport_id,
+                    investor: Address::from_slice(&investor),
+                    reason: reason.unwrap_or_default(),
+                    revoked_at,
+                })
+            })
+            .collect())
+    }
+    
+    /// Documents on file for an investor, newest first
+    pub async fn get_investor_documents(
+        &self,
+        investor: Address,
+    ) -> Result<Vec<InvestorDocument>, ComplianceError> {
+        let rows = sqlx::query_as::<_, DocumentRow>(
+            r#"
+            SELECT document_id, investor_address, document_type, issue_date, expiry_date,
+                   ipfs_hash, verification_id, recorded_at, superseded_at
+            FROM investor_documents
+            WHERE investor_address = $1
+            ORDER BY recorded_at DESC
+            "#
+        )
+        .bind(investor.as_bytes())
+        .fetch_all(self.db.as_ref())
+        .await?;
+        
+        rows.into_iter().map(document_from_row).collect()
+    }
+    
+    /// Record a new document for an investor after verifying it with a KYC provider
+    ///
+    /// Supersedes the active document of the same type. When the investor's KYC status was
+    /// downgraded because an identity document lapsed, a verified replacement restores it
+    /// without a full re-KYC as long as the lapse is within policy.
+    pub async fn submit_investor_document(
+        &self,
+        investor: Address,
+        submission: DocumentSubmission,
+    ) -> Result<DocumentReplacement, ComplianceError> {
+        let profile = self.get_investor_profile(investor).await?
+            .ok_or_else(|| ComplianceError::InvalidInput(format!("No investor profile for {:?}", investor)))?;
+        
+        let now = Utc::now();
+        match submission.expiry_date {
+            Some(expiry) if expiry <= submission.issue_date => {
+                return Err(ComplianceError::InvalidInput("Document expiry must be after its issue date".to_string()));
+            }
+            Some(expiry) if expiry <= now => {
+                return Err(ComplianceError::InvalidInput("Document has already expired".to_string()));
+            }
+            None if submission.document_type.is_identity() => {
+                return Err(ComplianceError::InvalidInput("Identity documents require an expiry date".to_string()));
+            }
+            _ => {}
+        }
+        
+        let mut metadata = HashMap::new();
+        metadata.insert("ipfs_hash".to_string(), submission.ipfs_hash.clone());
+        
+        let verification = self.verify_kyc(KycParams {
+            investor_id: investor.to_string(),
+            document_type: submission.document_type.as_str().to_string(),
+            country: profile.jurisdiction.clone(),
+            metadata,
+        }).await?;
+        
+        if !verification.verified {
+            return Err(ComplianceError::KycVerificationFailed(
+                verification.reason.unwrap_or_else(|| "Document was not accepted by the provider".to_string())
+            ));
+        }
+        
+        // Latest expiry among the identity documents being replaced, if they have lapsed
+        let lapsed_at = self.get_investor_documents(investor).await?
+            .into_iter()
+            .filter(|doc| doc.is_active() && doc.document_type == submission.document_type)
+            .filter_map(|doc| doc.expiry_date)
+            .filter(|expiry| *expiry <= now)
+            .max();
+        
+        let document = InvestorDocument {
+            document_id: Uuid::new_v4(),
+            investor,
+            document_type: submission.document_type,
+            issue_date: submission.issue_date,
+            expiry_date: submission.expiry_date,
+            ipfs_hash: submission.ipfs_hash,
+            verification_id: Some(verification.verification_id),
+            recorded_at: now,
+            superseded_at: None,
+        };
+        
+        let mut tx = self.db.begin().await?;
+        
+        sqlx::query(
+            r#"
+            UPDATE investor_documents
+            SET superseded_at = $3
+            WHERE investor_address = $1 AND document_type = $2 AND superseded_at IS NULL
+            "#
+        )
+        .bind(investor.as_bytes())
+        .bind(document.document_type.as_str())
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+        
+        sqlx::query(
+            r#"
+            INSERT INTO investor_documents (
+                document_id, investor_address, document_type, issue_date, expiry_date,
+                ipfs_hash, verification_id, recorded_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#
+        )
+        .bind(document.document_id)
+        .bind(investor.as_bytes())
+        .bind(document.document_type.as_str())
+        .bind(document.issue_date)
+        .bind(document.expiry_date)
+        .bind(&document.ipfs_hash)
+        .bind(document.verification_id.as_deref())
+        .bind(document.recorded_at)
+        .execute(&mut *tx)
+        .await?;
+        
+        tx.commit().await?;
+        
+        info!(document_id = %document.document_id, document_type = document.document_type.as_str(), investor = %Pii::debug(investor), "[AUDIT] Investor document recorded");
+        
+        let mut kyc_status = profile.kyc_status;
+        let mut reinstated = false;
+        let mut reason = None;
+        
+        if kyc_status == KycStatus::Expired && document.document_type.is_identity() {
+            match replacement_reinstates(profile.kyc_expiry, lapsed_at, self.config.document_reinstate_within_days, now) {
+                Ok(()) => {
+                    self.set_kyc_status(&profile, KycStatus::Completed).await?;
+                    kyc_status = KycStatus::Completed;
+                    reinstated = true;
+                    info!(investor = %Pii::debug(investor), document_id = %document.document_id, "[AUDIT] KYC status restored by replacement document");
+                }
+                Err(message) => {
+                    warn!(investor = %Pii::debug(investor), reason = %message, "[AUDIT] Replacement document does not restore KYC");
+                    reason = Some(message);
+                }
+            }
+        }
+        
+        Ok(DocumentReplacement {
+            document,
+            kyc_status,
+            reinstated,
+            reason,
+        })
     }
+    
+    /// Flag identity documents expiring within 60/30/0 days and downgrade lapsed KYC
+    ///
+    /// Each stage is flagged once per document. Investors whose only active identity
+    /// documents have expired are set to `Expired` and their passports revoked, even if
+    /// the KYC check itself has not yet expired.
+    pub async fn run_document_expiry_check(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<DocumentExpirySweep, ComplianceError> {
+        let expiring = self.load_expiring_documents(now, 60).await?;
+        let mut newly_flagged = Vec::new();
+        let mut lapsed_investors = Vec::new();
+        
+        for flagged in expiring {
+            let inserted = sqlx::query_scalar::<_, i64>(
+                r#"
+                INSERT INTO document_expiry_notices (document_id, stage, flagged_at)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (document_id, stage) DO NOTHING
+                RETURNING id
+                "#
+            )
+            .bind(flagged.document.document_id)
+            .bind(flagged.stage.as_str())
+            .bind(now)
+            .fetch_optional(self.db.as_ref())
+            .await?;
+            
+            if flagged.stage == ExpiryStage::Expired && !lapsed_investors.contains(&flagged.document.investor) {
+                lapsed_investors.push(flagged.document.investor);
+            }
+            
+            if inserted.is_some() {
+                warn!(
+                    "[AUDIT] {} document {} for {:?} flagged: {}",
+                    flagged.document.document_type.as_str(),
+                    flagged.document.document_id,
+                    flagged.document.investor,
+                    flagged.stage.as_str(),
+                );
+                self.record_system_communication(
+                    flagged.document.investor,
+                    None,
+                    Direction::Internal,
+                    format!(
+                        "KYC document expiry notice: {} {}",
+                        flagged.document.document_type.as_str(),
+                        flagged.stage.as_str(),
+                    ),
+                ).await;
+                newly_flagged.push(flagged);
+            }
+        }
+        
+        let mut downgraded = Vec::new();
+        for investor in lapsed_investors {
+            let profile = match self.get_investor_profile(investor).await? {
+                Some(profile) if profile.kyc_status != KycStatus::Expired => profile,
+                _ => continue,
+            };
+            
+            let documents = self.get_investor_documents(investor).await?;
+            if effective_kyc_status(profile.kyc_expiry, &documents, now) != KycStatus::Expired {
+                continue;
+            }
+            
+            self.set_kyc_status(&profile, KycStatus::Expired).await?;
+            self.revoke_compliance_passports(investor, "Identity document expired").await?;
+            warn!(investor = %Pii::debug(investor), "[AUDIT] KYC status downgraded to Expired: identity document lapsed");
+            downgraded.push(investor);
+        }
+        
+        info!(
+            "Document expiry check complete: {} newly flagged, {} downgraded",
+            newly_flagged.len(),
+            downgraded.len()
+        );
+        
+        Ok(DocumentExpirySweep {
+            newly_flagged,
+            downgraded,
+            run_at: now,
+        })
+    }
+    
+    /// Identity documents expiring within `within_days` (or already expired) for compliance officers
+    pub async fn get_expiring_documents(
+        &self,
+        within_days: i64,
+    ) -> Result<Vec<ExpiringDocument>, ComplianceError> {
+        self.load_expiring_documents(Utc::now(), within_days).await
+    }
+    
+    /// Run the document expiry check every night
+    pub fn spawn_document_expiry_job(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = self.run_document_expiry_check(Utc::now()).await {
+                    error!("Document expiry check failed: {}", e);
+                }
+                tokio::time::sleep(tokio::time::Duration::from_secs(86400)).await; // 24 hours
+            }
+        })
+    }
+    
+    async fn load_expiring_documents(
+        &self,
+        now: DateTime<Utc>,
+        within_days: i64,
+    ) -> Result<Vec<ExpiringDocument>, ComplianceError> {
+        let identity_types: Vec<&str> = DocumentType::IDENTITY.iter().map(|t| t.as_str()).collect();
+        
+        let rows = sqlx::query_as::<_, (Uuid, Vec<u8>, String, DateTime<Utc>, Option<DateTime<Utc>>, String, Option<String>, DateTime<Utc>, Option<DateTime<Utc>>, String)>(
+            r#"
+            SELECT d.document_id, d.investor_address, d.document_type, d.issue_date, d.expiry_date,
+                   d.ipfs_hash, d.verification_id, d.recorded_at, d.superseded_at, p.kyc_status
+            FROM investor_documents d
+            JOIN investor_profiles p ON p.address = d.investor_address
+            WHERE d.superseded_at IS NULL
+              AND d.document_type = ANY($1)
+              AND d.expiry_date <= $2
+            ORDER BY d.expiry_date ASC
+            "#
+        )
+        .bind(&identity_types)
+        .bind(now + chrono::Duration::days(within_days))
+        .fetch_all(self.db.as_ref())
+        .await?;
+        
+        let mut expiring = Vec::new();
+        for row in rows {
+            let kyc_status = row.9.parse().unwrap_or(KycStatus::Pending);
+            let document = document_from_row((row.0, row.1, row.2, row.3, row.4, row.5, row.6, row.7, row.8))?;
+            let expiry = match document.expiry_date {
+                Some(expiry) => expiry,
+                None => continue,
+            };
+            
+            if let Some(stage) = expiry_stage(expiry, now) {
+                expiring.push(ExpiringDocument {
+                    days_remaining: (expiry - now).num_days(),
+                    stage,
+                    kyc_status,
+                    document,
+                });
+            }
+        }
+        
+        Ok(expiring)
+    }
+    
+    async fn set_kyc_status(
+        &self,
+        profile: &InvestorProfile,
+        status: KycStatus,
+    ) -> Result<(), ComplianceError> {
+        sqlx::query(
+            "UPDATE investor_profiles SET kyc_status = $2, updated_at = NOW() WHERE address = $1"
+        )
+        .bind(profile.address.as_bytes())
+        .bind(status.as_str())
+        .execute(self.db.as_ref())
+        .await?;
+        
+        // Cached compliance reports were produced under the previous status
+        let cache_key = format!("compliance:{}:{}", profile.address, profile.jurisdiction);
+        let mut cache = self.cache.write().await;
+        let _: () = cache.del(&cache_key).await?;
+        
+        Ok(())
+    }
+    
+    /// Evaluate the AML monitoring rules over recent completed transactions
+    ///
+    /// Each triggered rule opens an alert for the investor (at most one open alert per
+    /// rule) and raises their risk score; severe rules also put a Clear investor under
+    /// review. Every rule window scanned is recorded for audit.
+    pub async fn run_transaction_monitoring(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<MonitoringRun, ComplianceError> {
+        let run_id = Uuid::new_v4();
+        let since = now - self.monitoring_rules.max_lookback();
+        
+        let rows = sqlx::query_as::<_, (Uuid, Vec<u8>, String, String, String, DateTime<Utc>)>(
+            r#"
+            SELECT t.id, p.address, p.jurisdiction, t.transaction_type, t.total_value::TEXT, t.timestamp
+            FROM portfolio_transactions t
+            JOIN investor_profiles p ON p.address = decode(substr(lower(t.wallet_address), 3), 'hex')
+            WHERE t.status = 'completed' AND t.timestamp > $1 AND t.timestamp <= $2
+            ORDER BY t.timestamp ASC
+            "#
+        )
+        .bind(since)
+        .bind(now)
+        .fetch_all(self.db.as_ref())
+        .await?;
+        
+        let mut by_investor: HashMap<Address, (String, Vec<MonitoredTransaction>)> = HashMap::new();
+        for (id, address, jurisdiction, transaction_type, amount, timestamp) in rows {
+            let investor = Address::from_slice(&address);
+            by_investor.entry(investor)
+                .or_insert_with(|| (jurisdiction, Vec::new()))
+                .1
+                .push(MonitoredTransaction {
+                    id,
+                    investor,
+                    transaction_type,
+                    amount: amount.parse().unwrap_or_default(),
+                    timestamp,
+                });
+        }
+        
+        let mut evaluations = 0;
+        let mut alerts = Vec::new();
+        let mut under_review = Vec::new();
+        
+        for (investor, (jurisdiction, transactions)) in &by_investor {
+            let previous_activity = sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
+                r#"
+                SELECT MAX(timestamp) FROM portfolio_transactions
+                WHERE lower(wallet_address) = $1 AND status = 'completed' AND timestamp <= $2
+                "#
+            )
+            .bind(format!("{:?}", investor))
+            .bind(since)
+            .fetch_one(self.db.as_ref())
+            .await?;
+            
+            let thresholds = self.monitoring_rules.thresholds(jurisdiction);
+            for evaluation in evaluate_investor(transactions, previous_activity, thresholds, now) {
+                evaluations += 1;
+                
+                let alert = match &evaluation.hit {
+                    Some(hit) => self.open_aml_alert(*investor, jurisdiction, &evaluation, hit, now).await?,
+                    None => None,
+                };
+                
+                sqlx::query(
+                    r#"
+                    INSERT INTO aml_rule_evaluations (
+                        run_id, investor_address, jurisdiction, rule, window_start, window_end,
+                        transactions_scanned, triggered, alert_id
+                    ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                    "#
+                )
+                .bind(run_id)
+                .bind(investor.as_bytes())
+                .bind(jurisdiction)
+                .bind(evaluation.rule.as_str())
+                .bind(evaluation.window_start)
+                .bind(evaluation.window_end)
+                .bind(evaluation.transactions_scanned as i32)
+                .bind(evaluation.hit.is_some())
+                .bind(alert.as_ref().map(|a: &AmlAlert| a.alert_id))
+                .execute(self.db.as_ref())
+                .await?;
+                
+                let Some(alert) = alert else { continue };
+                
+                sqlx::query(
+                    "UPDATE investor_profiles SET risk_score = LEAST(100, risk_score + $2), updated_at = NOW() WHERE address = $1"
+                )
+                .bind(investor.as_bytes())
+                .bind(alert.rule.risk_score_increase() as i32)
+                .execute(self.db.as_ref())
+                .await?;
+                
+                if alert.rule.is_severe() {
+                    let moved = sqlx::query(
+                        "UPDATE investor_profiles SET aml_status = $2, updated_at = NOW() WHERE address = $1 AND aml_status = $3"
+                    )
+                    .bind(investor.as_bytes())
+                    .bind(AmlStatus::UnderReview.as_str())
+                    .bind(AmlStatus::Clear.as_str())
+                    .execute(self.db.as_ref())
+                    .await?;
+                    
+                    if moved.rows_affected() > 0 {
+                        warn!(investor = %Pii::debug(investor), rule = alert.rule.as_str(), alert_id = %alert.alert_id, "[AUDIT] AML status set to UnderReview");
+                        under_review.push(*investor);
+                    }
+                }
+                
+                alerts.push(alert);
+            }
+        }
+        
+        sqlx::query(
+            r#"
+            INSERT INTO aml_monitoring_runs (run_id, run_at, window_from, investors_scanned, evaluations, alerts_created)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#
+        )
+        .bind(run_id)
+        .bind(now)
+        .bind(since)
+        .bind(by_investor.len() as i32)
+        .bind(evaluations as i32)
+        .bind(alerts.len() as i32)
+        .execute(self.db.as_ref())
+        .await?;
+        
+        info!(
+            "Transaction monitoring run {} complete: {} investors, {} evaluations, {} alerts",
+            run_id, by_investor.len(), evaluations, alerts.len()
+        );
+        
+        Ok(MonitoringRun {
+            run_id,
+            run_at: now,
+            investors_scanned: by_investor.len(),
+            evaluations,
+            alerts,
+            under_review,
+        })
+    }
+    
+    /// Open an alert for a triggered rule, unless one is already open for the investor and rule
+    async fn open_aml_alert(
+        &self,
+        investor: Address,
+        jurisdiction: &str,
+        evaluation: &monitoring::RuleEvaluation,
+        hit: &monitoring::RuleHit,
+        now: DateTime<Utc>,
+    ) -> Result<Option<AmlAlert>, ComplianceError> {
+        let alert = AmlAlert {
+            alert_id: Uuid::new_v4(),
+            investor,
+            jurisdiction: jurisdiction.to_string(),
+            rule: evaluation.rule,
+            severity: evaluation.rule.severity(),
+            status: AlertStatus::Open,
+            description: hit.description.clone(),
+            transaction_ids: hit.transaction_ids.clone(),
+            total_amount: hit.total_amount,
+            window_start: evaluation.window_start,
+            window_end: evaluation.window_end,
+            created_at: now,
+            resolved_by: None,
+            resolution_notes: None,
+            resolved_at: None,
+        };
+        
+        self.insert_aml_alert(alert).await
+    }
+    
+    /// Store a new open alert; None when one is already open for the investor and rule
+    async fn insert_aml_alert(&self, alert: AmlAlert) -> Result<Option<AmlAlert>, ComplianceError> {
+        let inserted = sqlx::query_scalar::<_, i64>(
+            r#"
+            INSERT INTO aml_alerts (
+                alert_id, investor_address, jurisdiction, rule, severity, status, description,
+                transaction_ids, total_amount, window_start, window_end, created_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9::NUMERIC, $10, $11, $12)
+            ON CONFLICT (investor_address, rule) WHERE status = 'open' DO NOTHING
+            RETURNING id
+            "#
+        )
+        .bind(alert.alert_id)
+        .bind(alert.investor.as_bytes())
+        .bind(&alert.jurisdiction)
+        .bind(alert.rule.as_str())
+        .bind(format!("{:?}", alert.severity))
+        .bind(alert.status.as_str())
+        .bind(&alert.description)
+        .bind(&alert.transaction_ids)
+        .bind(alert.total_amount.to_string())
+        .bind(alert.window_start)
+        .bind(alert.window_end)
+        .bind(alert.created_at)
+        .fetch_optional(self.db.as_ref())
+        .await?;
+        
+        if inserted.is_none() {
+            return Ok(None);
+        }
+        
+        warn!(alert_id = %alert.alert_id, investor = %Pii::debug(alert.investor), rule = alert.rule.as_str(), "[AUDIT] AML alert opened");
+        Ok(Some(alert))
+    }
+    
+    /// AML alerts for compliance officers, newest first
+    pub async fn get_aml_alerts(
+        &self,
+        status: Option<AlertStatus>,
+    ) -> Result<Vec<AmlAlert>, ComplianceError> {
+        let rows = sqlx::query_as::<_, AmlAlertRow>(
+            r#"
+            SELECT alert_id, investor_address, jurisdiction, rule, status, description, transaction_ids,
+                   total_amount::TEXT, window_start, window_end, created_at, resolved_by, resolution_notes, resolved_at
+            FROM aml_alerts
+            WHERE $1::TEXT IS NULL OR status = $1
+            ORDER BY created_at DESC
+            "#
+        )
+        .bind(status.map(|s| s.as_str()))
+        .fetch_all(self.db.as_ref())
+        .await?;
+        
+        rows.into_iter().map(aml_alert_from_row).collect()
+    }
+    
+    /// Record an officer's decision on an open alert and update the investor's AML status
+    pub async fn resolve_aml_alert(
+        &self,
+        alert_id: Uuid,
+        resolution: AlertResolution,
+    ) -> Result<AmlAlert, ComplianceError> {
+        if resolution.status == AlertStatus::Open {
+            return Err(ComplianceError::InvalidInput("Resolution must dismiss or confirm the alert".to_string()));
+        }
+        if resolution.officer.trim().is_empty() {
+            return Err(ComplianceError::InvalidInput("Resolving officer is required".to_string()));
+        }
+        
+        let row = sqlx::query_as::<_, AmlAlertRow>(
+            r#"
+            UPDATE aml_alerts
+            SET status = $2, resolved_by = $3, resolution_notes = $4, resolved_at = NOW()
+            WHERE alert_id = $1 AND status = 'open'
+            RETURNING alert_id, investor_address, jurisdiction, rule, status, description, transaction_ids,
+                      total_amount::TEXT, window_start, window_end, created_at, resolved_by, resolution_notes, resolved_at
+            "#
+        )
+        .bind(alert_id)
+        .bind(resolution.status.as_str())
+        .bind(&resolution.officer)
+        .bind(&resolution.notes)
+        .fetch_optional(self.db.as_ref())
+        .await?
+        .ok_or_else(|| ComplianceError::InvalidInput(format!("No open AML alert {}", alert_id)))?;
+        let alert = aml_alert_from_row(row)?;
+        
+        let open_rules = sqlx::query_scalar::<_, String>(
+            "SELECT rule FROM aml_alerts WHERE investor_address = $1 AND status = 'open'"
+        )
+        .bind(alert.investor.as_bytes())
+        .fetch_all(self.db.as_ref())
+        .await?;
+        let severe_open = open_rules.iter()
+            .filter(|rule| rule.parse::<AmlRule>().map(|r| r.is_severe()).unwrap_or(true))
+            .count();
+        
+        if let Some(profile) = self.get_investor_profile(alert.investor).await? {
+            let status = status_after_resolution(profile.aml_status, resolution.status, severe_open);
+            if status != profile.aml_status {
+                sqlx::query("UPDATE investor_profiles SET aml_status = $2, updated_at = NOW() WHERE address = $1")
+                    .bind(alert.investor.as_bytes())
+                    .bind(status.as_str())
+                    .execute(self.db.as_ref())
+                    .await?;
+            }
+            info!(
+                alert_id = %alert_id,
+                status = resolution.status.as_str(),
+                officer = %resolution.officer,
+                investor = %Pii::debug(alert.investor),
+                aml_status = status.as_str(),
+                "[AUDIT] AML alert resolved"
+            );
+        }
+        
+        self.record_system_communication(
+            alert.investor,
+            Some(alert_id),
+            Direction::Internal,
+            format!("AML case {} by {}", resolution.status.as_str(), resolution.officer),
+        ).await;
+        
+        Ok(alert)
+    }
+    
+    /// A single AML alert
+    pub async fn get_aml_alert(&self, alert_id: Uuid) -> Result<Option<AmlAlert>, ComplianceError> {
+        let row = sqlx::query_as::<_, AmlAlertRow>(
+            r#"
+            SELECT alert_id, investor_address, jurisdiction, rule, status, description, transaction_ids,
+                   total_amount::TEXT, window_start, window_end, created_at, resolved_by, resolution_notes, resolved_at
+            FROM aml_alerts
+            WHERE alert_id = $1
+            "#
+        )
+        .bind(alert_id)
+        .fetch_optional(self.db.as_ref())
+        .await?;
+        
+        row.map(aml_alert_from_row).transpose()
+    }
+    
+    /// An AML case with every communication linked to it
+    pub async fn get_aml_case(&self, alert_id: Uuid) -> Result<Option<CaseDetail>, ComplianceError> {
+        let alert = match self.get_aml_alert(alert_id).await? {
+            Some(alert) => alert,
+            None => return Ok(None),
+        };
+        
+        let rows = sqlx::query_as::<_, CommunicationRow>(
+            r#"
+            SELECT communication_id, investor_address, case_id, channel, direction, subject, body_ipfs_hash,
+                   author, corrects, recorded_at
+            FROM investor_communications
+            WHERE case_id = $1
+            "#
+        )
+        .bind(alert_id)
+        .fetch_all(self.db.as_ref())
+        .await?;
+        let communications = rows.into_iter().map(communication_from_row).collect::<Result<Vec<_>, _>>()?;
+        
+        Ok(Some(CaseDetail { alert, communications: timeline(communications) }))
+    }
+    
+    /// Pool shared with the service key store
+    pub fn db_pool(&self) -> Arc<PgPool> {
+        self.db.clone()
+    }
+    
+    /// Check a bearer token from the main API and require an officer or admin role
+    pub fn authorize_officer(&self, token: &str) -> Result<Officer, AuthError> {
+        let secret = self.config.jwt_secret.as_deref().ok_or(AuthError::Unauthenticated)?;
+        authorize_officer(token, secret)
+    }
+    
+    /// Check a bearer token from the main API and take the caller's wallet from it
+    pub fn authorize_investor(&self, token: &str) -> Result<Investor, AuthError> {
+        let secret = self.config.jwt_secret.as_deref().ok_or(AuthError::Unauthenticated)?;
+        authorize_investor(token, secret)
+    }
+    
+    /// The wallet's own KYC, document and accreditation standing with its outstanding tasks;
+    /// wallets without a profile get the tasks that create one
+    pub async fn get_self_service_status(&self, wallet: Address) -> Result<SelfServiceStatus, ComplianceError> {
+        let profile = self.get_investor_profile(wallet).await?;
+        let documents = self.get_investor_documents(wallet).await?;
+        Ok(build_status(wallet, profile.as_ref(), &documents, Utc::now()))
+    }
+    
+    /// The wallet's own past checks and their outcomes, newest first
+    pub async fn get_self_service_history(&self, wallet: Address) -> Result<Vec<HistoryEntry>, ComplianceError> {
+        let attempts = self.get_kyc_attempts(&self_service::investor_id(wallet)).await?;
+        let checks = self.get_check_records(wallet).await?;
+        let documents = self.get_investor_documents(wallet).await?;
+        Ok(build_history(wallet, &attempts, &checks, &documents))
+    }
+    
+    async fn get_check_records(&self, wallet: Address) -> Result<Vec<CheckRecord>, ComplianceError> {
+        let rows = sqlx::query_as::<_, CheckRecordRow>(
+            r#"
+            SELECT report_id, investor_address, kyc_verified AND sanctions_passed
+                   AND COALESCE(jsonb_array_length(violations), 0) = 0,
+                   generated_at
+            FROM compliance_reports
+            WHERE investor_address = $1
+            ORDER BY generated_at DESC
+            "#
+        )
+        .bind(wallet.as_bytes())
+        .fetch_all(self.db.as_ref())
+        .await?;
+        
+        Ok(rows.into_iter().map(check_record_from_row).collect())
+    }
+    
+    /// Append an officer's entry to an investor's communication log, storing the body
+    /// encrypted on IPFS
+    pub async fn record_communication(
+        &self,
+        investor: Address,
+        author: &Officer,
+        entry: NewCommunication,
+    ) -> Result<Communication, ComplianceError> {
+        entry.validate().map_err(ComplianceError::InvalidInput)?;
+        
+        let case = match entry.case_id {
+            Some(case_id) => self.get_aml_alert(case_id).await?,
+            None => None,
+        };
+        let original = match entry.corrects {
+            Some(id) => self.get_communication(id).await?,
+            None => None,
+        };
+        check_links(investor, &entry, case.as_ref(), original.as_ref()).map_err(ComplianceError::InvalidInput)?;
+        
+        let body_ipfs_hash = self.ipfs_client
+            .upload_encrypted(entry.body.into_bytes())
+            .await
+            .map_err(|e| ComplianceError::IpfsStorageError(e.to_string()))?;
+        
+        let communication = Communication {
+            communication_id: Uuid::new_v4(),
+            investor,
+            case_id: entry.case_id,
+            channel: entry.channel,
+            direction: entry.direction,
+            subject: entry.subject,
+            body_ipfs_hash: Some(body_ipfs_hash),
+            author: author.user_id.clone(),
+            corrects: entry.corrects,
+            recorded_at: Utc::now(),
+        };
+        
+        let mut tx = self.db.begin().await?;
+        Self::insert_communication(&mut tx, &communication).await?;
+        repository::insert_audit_entry(&mut tx, &ComplianceAuditEntry {
+            event_type: "COMMUNICATION_RECORDED".to_string(),
+            entity_type: "investor_communication".to_string(),
+            entity_id: communication.communication_id.to_string(),
+            actor: Some(author.user_id.clone()),
+            action: if communication.corrects.is_some() { "correct" } else { "append" }.to_string(),
+            details: serde_json::json!({
+                "investor": format!("{:?}", investor),
+                "case_id": communication.case_id,
+                "corrects": communication.corrects,
+                "channel": communication.channel.as_str(),
+                "body_ipfs_hash": communication.body_ipfs_hash,
+            }),
+        }).await?;
+        tx.commit().await?;
+        
+        info!(
+            communication_id = %communication.communication_id,
+            investor = %Pii::debug(investor),
+            author = %author.user_id,
+            role = %author.role,
+            "[AUDIT] Communication recorded"
+        );
+        Ok(communication)
+    }
+    
+    /// An investor's communication log, oldest first
+    pub async fn get_communications(&self, investor: Address) -> Result<Vec<TimelineEntry>, ComplianceError> {
+        let rows = sqlx::query_as::<_, CommunicationRow>(
+            r#"
+            SELECT communication_id, investor_address, case_id, channel, direction, subject, body_ipfs_hash,
+                   author, corrects, recorded_at
+            FROM investor_communications
+            WHERE investor_address = $1
+            "#
+        )
+        .bind(investor.as_bytes())
+        .fetch_all(self.db.as_ref())
+        .await?;
+        
+        let entries = rows.into_iter().map(communication_from_row).collect::<Result<Vec<_>, _>>()?;
+        Ok(timeline(entries))
+    }
+    
+    /// Retrieve and decrypt an entry's body, recording which officer read it
+    pub async fn get_communication_body(
+        &self,
+        communication_id: Uuid,
+        reader: &Officer,
+    ) -> Result<Option<CommunicationBody>, ComplianceError> {
+        let hash = match self.get_communication(communication_id).await?.and_then(|c| c.body_ipfs_hash) {
+            Some(hash) => hash,
+            None => return Ok(None),
+        };
+        
+        let (body, retrieval) = self.ipfs_client
+            .download_encrypted(&hash)
+            .await
+            .map_err(|e| ComplianceError::IpfsStorageError(e.to_string()))?;
+        let body = String::from_utf8(body)
+            .map_err(|_| ComplianceError::InternalError(format!("Communication {} body is not UTF-8", communication_id)))?;
+        
+        let mut conn = self.db.acquire().await?;
+        repository::insert_audit_entry(&mut conn, &ComplianceAuditEntry {
+            event_type: "COMMUNICATION_READ".to_string(),
+            entity_type: "investor_communication".to_string(),
+            entity_id: communication_id.to_string(),
+            actor: Some(reader.user_id.clone()),
+            action: "read".to_string(),
+            details: serde_json::json!({ "retrieval": retrieval }),
+        }).await?;
+        
+        Ok(Some(CommunicationBody { communication_id, body, served_by: retrieval.served_by }))
+    }
+    
+    async fn get_communication(&self, communication_id: Uuid) -> Result<Option<Communication>, ComplianceError> {
+        let row = sqlx::query_as::<_, CommunicationRow>(
+            r#"
+            SELECT communication_id, investor_address, case_id, channel, direction, subject, body_ipfs_hash,
+                   author, corrects, recorded_at
+            FROM investor_communications
+            WHERE communication_id = $1
+            "#
+        )
+        .bind(communication_id)
+        .fetch_optional(self.db.as_ref())
+        .await?;
+        
+        row.map(communication_from_row).transpose()
+    }
+    
+    /// Log an action the service took itself. Failures are logged rather than failing the
+    /// action, which has already happened.
+    async fn record_system_communication(
+        &self,
+        investor: Address,
+        case_id: Option<Uuid>,
+        direction: Direction,
+        subject: String,
+    ) {
+        let communication = Communication {
+            communication_id: Uuid::new_v4(),
+            investor,
+            case_id,
+            channel: Channel::System,
+            direction,
+            subject,
+            body_ipfs_hash: None,
+            author: "system".to_string(),
+            corrects: None,
+            recorded_at: Utc::now(),
+        };
+        
+        let result = match self.db.acquire().await {
+            Ok(mut conn) => Self::insert_communication(&mut conn, &communication).await,
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = result {
+            error!(communication_id = %communication.communication_id, investor = %Pii::debug(investor), error = %e, "Failed to log system communication");
+        }
+    }
+    
+    async fn insert_communication(
+        conn: &mut sqlx::PgConnection,
+        communication: &Communication,
+    ) -> Result<(), ComplianceError> {
+        sqlx::query(
+            r#"
+            INSERT INTO investor_communications
+                (communication_id, investor_address, case_id, channel, direction, subject, body_ipfs_hash,
+                 author, corrects, recorded_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            "#
+        )
+        .bind(communication.communication_id)
+        .bind(communication.investor.as_bytes())
+        .bind(communication.case_id)
+        .bind(communication.channel.as_str())
+        .bind(communication.direction.as_str())
+        .bind(&communication.subject)
+        .bind(&communication.body_ipfs_hash)
+        .bind(&communication.author)
+        .bind(communication.corrects)
+        .bind(communication.recorded_at)
+        .execute(conn)
+        .await?;
+        
+        Ok(())
+    }
+    
+    /// Run transaction monitoring on the configured interval
+    pub fn spawn_transaction_monitoring_job(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let every = tokio::time::Duration::from_secs(self.config.aml_monitoring_interval_secs);
+            loop {
+                if let Err(e) = self.run_transaction_monitoring(Utc::now()).await {
+                    error!("Transaction monitoring run failed: {}", e);
+                }
+                tokio::time::sleep(every).await;
+            }
+        })
+    }
+    
+    /// Profiles whose sanctions and PEP re-screening is due, never-screened and longest overdue first
+    pub async fn get_overdue_screenings(&self, now: DateTime<Utc>) -> Result<Vec<OverdueProfile>, ComplianceError> {
+        Ok(overdue(&self.load_screening_schedules().await?, now))
+    }
+    
+    /// Re-screen due profiles against the sanctions lists and the PEP provider
+    ///
+    /// At most RESCREENING_BATCH_SIZE profiles are screened per run, longest overdue first, with
+    /// provider calls paced by the re-screening rate limit. A new hit flags the profile, raises its
+    /// risk score and opens a case; a sanctions hit also revokes the investor's passports. Screened
+    /// profiles restart their clock, while provider failures leave them due for the next run.
+    pub async fn run_rescreening(&self, now: DateTime<Utc>) -> Result<RescreeningRun, ComplianceError> {
+        let run_id = Uuid::new_v4();
+        let due = overdue(&self.load_screening_schedules().await?, now);
+        let batch = due.len().min(self.config.rescreening_batch_size);
+        
+        let mut screened = 0;
+        let mut failed = 0;
+        let mut hits = Vec::new();
+        
+        for scheduled in &due[..batch] {
+            let investor = scheduled.investor;
+            
+            self.screening_limiter.until_ready().await;
+            let sanctions = match self.sanctions_screener.screen_address(investor).await {
+                Ok(result) => result,
+                Err(e) => {
+                    warn!(investor = %Pii::debug(investor), error = %e, "Sanctions re-screening failed");
+                    failed += 1;
+                    continue;
+                }
+            };
+            let pep = match &self.pep_screener {
+                Some(screener) => match screener.screen(investor).await {
+                    Ok(pep) => pep,
+                    Err(e) => {
+                        warn!(investor = %Pii::debug(investor), error = %e, "PEP re-screening failed");
+                        failed += 1;
+                        continue;
+                    }
+                },
+                None => None,
+            };
+            
+            let Some(profile) = self.get_investor_profile(investor).await? else { continue };
+            
+            let mut new = Vec::new();
+            for rule in new_hits(profile.sanctioned, profile.pep, sanctions.is_sanctioned, pep.is_some()) {
+                let details = match rule {
+                    AmlRule::SanctionsMatch => sanctions.details.clone()
+                        .unwrap_or_else(|| format!("Listed on {}", sanctions.lists.join(", "))),
+                    _ => pep.clone().unwrap_or_default(),
+                };
+                let alert = self.open_screening_alert(
+                    investor,
+                    &profile.jurisdiction,
+                    rule,
+                    &details,
+                    scheduled.last_screened_at.unwrap_or(now),
+                    now,
+                ).await?;
+                new.push(ScreeningHit { investor, rule, details, alert_id: alert.map(|a| a.alert_id) });
+            }
+            
+            // Flags, risk score and the restarted clock are written with the audit entry
+            let risk_increase: u32 = new.iter().map(|hit| hit.rule.risk_score_increase()).sum();
+            let mut tx = self.db.begin().await?;
+            sqlx::query(
+                r#"
+                UPDATE investor_profiles
+                SET sanctioned = sanctioned OR $2, pep = pep OR $3, risk_score = LEAST(100, risk_score + $4),
+                    last_screened_at = $5, updated_at = NOW()
+                WHERE address = $1
+                "#
+            )
+            .bind(investor.as_bytes())
+            .bind(sanctions.is_sanctioned)
+            .bind(pep.is_some())
+            .bind(risk_increase as i32)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+            if !new.is_empty() {
+                repository::insert_audit_entry(&mut tx, &ComplianceAuditEntry {
+                    event_type: "RESCREENING_HIT".to_string(),
+                    entity_type: "investor".to_string(),
+                    entity_id: format!("{:?}", investor),
+                    actor: None,
+                    action: "flagged".to_string(),
+                    details: serde_json::json!({
+                        "run_id": run_id,
+                        "rules": new.iter().map(|hit| hit.rule.as_str()).collect::<Vec<_>>(),
+                        "alert_ids": new.iter().filter_map(|hit| hit.alert_id).collect::<Vec<_>>(),
+                    }),
+                }).await?;
+            }
+            tx.commit().await?;
+            screened += 1;
+            
+            if new.is_empty() {
+                continue;
+            }
+            
+            let rules: Vec<AmlRule> = new.iter().map(|hit| hit.rule).collect();
+            self.escalate_screening_hits(investor, &profile.jurisdiction, &rules, "Sanctions re-screening hit").await?;
+            
+            for hit in &new {
+                warn!(rule = hit.rule.as_str(), investor = %Pii::debug(investor), "[AUDIT] Re-screening hit");
+            }
+            hits.extend(new);
+        }
+        
+        sqlx::query(
+            r#"
+            INSERT INTO screening_runs (run_id, run_at, profiles_due, screened, failed, hits)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#
+        )
+        .bind(run_id)
+        .bind(now)
+        .bind(due.len() as i32)
+        .bind(screened as i32)
+        .bind(failed as i32)
+        .bind(hits.len() as i32)
+        .execute(self.db.as_ref())
+        .await?;
+        
+        info!(
+            "Re-screening run {} complete: {} due, {} screened, {} failed, {} deferred, {} hits",
+            run_id, due.len(), screened, failed, due.len() - batch, hits.len()
+        );
+        
+        Ok(RescreeningRun {
+            run_id,
+            run_at: now,
+            due: due.len(),
+            screened,
+            failed,
+            deferred: due.len() - batch,
+            hits,
+        })
+    }
+    
+    /// Run re-screening every night
+    pub fn spawn_rescreening_job(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = self.run_rescreening(Utc::now()).await {
+                    error!("Re-screening run failed: {}", e);
+                }
+                tokio::time::sleep(tokio::time::Duration::from_secs(86400)).await; // 24 hours
+            }
+        })
+    }
+    
+    async fn load_screening_schedules(&self) -> Result<Vec<ScreeningSchedule>, ComplianceError> {
+        let rows = sqlx::query_as::<_, (Vec<u8>, String, i32, Option<DateTime<Utc>>)>(
+            "SELECT address, jurisdiction, risk_score, last_screened_at FROM investor_profiles ORDER BY address"
+        )
+        .fetch_all(self.db.as_ref())
+        .await?;
+        
+        Ok(rows.into_iter()
+            .map(|(address, jurisdiction, risk_score, last_screened_at)| ScreeningSchedule {
+                investor: Address::from_slice(&address),
+                jurisdiction,
+                risk_score: risk_score.max(0) as u32,
+                last_screened_at,
+            })
+            .collect())
+    }
+    
+    async fn mark_screened(&self, investor: Address, at: DateTime<Utc>) -> Result<(), ComplianceError> {
+        sqlx::query("UPDATE investor_profiles SET last_screened_at = $2 WHERE address = $1")
+            .bind(investor.as_bytes())
+            .bind(at)
+            .execute(self.db.as_ref())
+            .await?;
+        Ok(())
+    }
+    
+    /// Case for a sanctions or PEP screening hit; None when one is already open for the rule
+    async fn open_screening_alert(
+        &self,
+        investor: Address,
+        jurisdiction: &str,
+        rule: AmlRule,
+        description: &str,
+        window_start: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<Option<AmlAlert>, ComplianceError> {
+        self.insert_aml_alert(AmlAlert {
+            alert_id: Uuid::new_v4(),
+            investor,
+            jurisdiction: jurisdiction.to_string(),
+            rule,
+            severity: rule.severity(),
+            status: AlertStatus::Open,
+            description: description.to_string(),
+            transaction_ids: Vec::new(),
+            total_amount: Decimal::ZERO,
+            window_start,
+            window_end: now,
+            created_at: now,
+            resolved_by: None,
+            resolution_notes: None,
+            resolved_at: None,
+        }).await
+    }
+    
+    /// Put a newly flagged profile under review, revoke its passports on a sanctions hit and
+    /// drop cached compliance reports, which predate the hit
+    async fn escalate_screening_hits(
+        &self,
+        investor: Address,
+        jurisdiction: &str,
+        rules: &[AmlRule],
+        revocation_reason: &str,
+    ) -> Result<(), ComplianceError> {
+        if rules.iter().any(|rule| rule.is_severe()) {
+            sqlx::query(
+                "UPDATE investor_profiles SET aml_status = $2, updated_at = NOW() WHERE address = $1 AND aml_status = $3"
+            )
+            .bind(investor.as_bytes())
+            .bind(AmlStatus::UnderReview.as_str())
+            .bind(AmlStatus::Clear.as_str())
+            .execute(self.db.as_ref())
+            .await?;
+        }
+        if rules.contains(&AmlRule::SanctionsMatch) {
+            self.revoke_compliance_passports(investor, revocation_reason).await?;
+        }
+        
+        let cache_key = format!("compliance:{}:{}", investor, jurisdiction);
+        let mut cache = self.cache.write().await;
+        let _: () = cache.del(&cache_key).await?;
+        Ok(())
+    }
+    
+    /// Recorded beneficial owners of an institution with their last screening, largest holder first
+    pub async fn get_beneficial_owners(&self, institution: Address) -> Result<Vec<UboScreening>, ComplianceError> {
+        let rows = sqlx::query_as::<_, (String, chrono::NaiveDate, String, String, String, serde_json::Value, Option<String>, DateTime<Utc>)>(
+            r#"
+            SELECT full_name, date_of_birth, nationality, ownership_percent::TEXT, status, sanctions, pep_details, screened_at
+            FROM beneficial_owners
+            WHERE institution_address = $1
+            ORDER BY ownership_percent DESC, full_name
+            "#
+        )
+        .bind(institution.as_bytes())
+        .fetch_all(self.db.as_ref())
+        .await?;
+        
+        rows.into_iter()
+            .map(|(full_name, date_of_birth, nationality, ownership_percent, status, sanctions, pep, screened_at)| {
+                Ok(UboScreening {
+                    owner: BeneficialOwner {
+                        full_name,
+                        date_of_birth,
+                        nationality,
+                        ownership_percent: ownership_percent.parse()
+                            .map_err(|e| ComplianceError::InternalError(format!("Invalid stored ownership: {}", e)))?,
+                    },
+                    status: UboStatus::parse(&status)
+                        .ok_or_else(|| ComplianceError::InternalError(format!("Unknown beneficial owner status {}", status)))?,
+                    sanctions: serde_json::from_value(sanctions)?,
+                    pep,
+                    screened_at,
+                })
+            })
+            .collect()
+    }
+    
+    /// Replace an institution's register of beneficial owners
+    ///
+    /// Owners below UBO_OWNERSHIP_THRESHOLD_PERCENT are not recorded. Only owners the register
+    /// adds are screened, each through the sanctions lists and the PEP provider; recorded owners
+    /// keep their screening. The institution is flagged as its worst owner, opening a case for a
+    /// new hit and revoking its passports on a sanctions hit. Flags are not lifted when a flagged
+    /// owner is removed; that is left to officer review of the case.
+    pub async fn update_beneficial_owners(
+        &self,
+        institution: Address,
+        owners: Vec<BeneficialOwner>,
+        requested_by: &str,
+    ) -> Result<Vec<UboScreening>, ComplianceError> {
+        let now = Utc::now();
+        beneficial_owners::validate(&owners, now.date_naive())?;
+        let owners = beneficial_owners::reportable(owners, self.config.ubo_ownership_threshold_percent);
+        let profile = self.get_investor_profile(institution).await?
+            .ok_or_else(|| ComplianceError::InvalidInput(format!("No investor profile for {:?}", institution)))?;
+        
+        let current = self.get_beneficial_owners(institution).await?;
+        let changes = beneficial_owners::diff(&current, &owners);
+        if changes.is_empty() {
+            return Ok(current);
+        }
+        
+        // Screening happens before anything is written, so a provider failure leaves the register as it was
+        let mut added = Vec::with_capacity(changes.added.len());
+        for owner in &changes.added {
+            self.screening_limiter.until_ready().await;
+            let sanctions = self.sanctions_screener.screen_name(&owner.full_name).await?;
+            let pep = match &self.pep_screener {
+                Some(screener) => screener.screen_person(owner).await?,
+                None => None,
+            };
+            added.push(UboScreening::new(owner.clone(), sanctions, pep, now));
+        }
+        
+        let mut register: Vec<UboScreening> = current.iter()
+            .filter(|screening| !changes.removed.iter().any(|removed| removed.owner.key() == screening.owner.key()))
+            .map(|screening| {
+                let owner = changes.updated.iter()
+                    .find(|owner| owner.key() == screening.owner.key())
+                    .unwrap_or(&screening.owner);
+                UboScreening { owner: owner.clone(), ..screening.clone() }
+            })
+            .chain(added.iter().cloned())
+            .collect();
+        register.sort_by(|a, b| b.owner.ownership_percent.cmp(&a.owner.ownership_percent)
+            .then_with(|| a.owner.full_name.cmp(&b.owner.full_name)));
+        
+        let worst = beneficial_owners::worst(&register);
+        let sanctioned = worst == UboStatus::Sanctioned;
+        let pep = register.iter().any(|screening| screening.pep.is_some());
+        let rules = new_hits(profile.sanctioned, profile.pep, sanctioned, pep);
+        let risk_increase: u32 = rules.iter().map(|rule| rule.risk_score_increase()).sum();
+        
+        // Register, flags and audit entry are written together
+        let mut tx = self.db.begin().await?;
+        for screening in &changes.removed {
+            sqlx::query("DELETE FROM beneficial_owners WHERE institution_address = $1 AND owner_key = $2")
+                .bind(institution.as_bytes())
+                .bind(screening.owner.key())
+                .execute(&mut *tx)
+                .await?;
+        }
+        for owner in &changes.updated {
+            sqlx::query(
+                r#"
+                UPDATE beneficial_owners
+                SET full_name = $3, nationality = $4, ownership_percent = $5::NUMERIC, updated_at = NOW()
+                WHERE institution_address = $1 AND owner_key = $2
+                "#
+            )
+            .bind(institution.as_bytes())
+            .bind(owner.key())
+            .bind(&owner.full_name)
+            .bind(&owner.nationality)
+            .bind(owner.ownership_percent.to_string())
+            .execute(&mut *tx)
+            .await?;
+        }
+        for screening in &added {
+            sqlx::query(
+                r#"
+                INSERT INTO beneficial_owners (
+                    institution_address, owner_key, full_name, date_of_birth, nationality, ownership_percent,
+                    status, sanctions, pep_details, screened_at
+                ) VALUES ($1, $2, $3, $4, $5, $6::NUMERIC, $7, $8, $9, $10)
+                "#
+            )
+            .bind(institution.as_bytes())
+            .bind(screening.owner.key())
+            .bind(&screening.owner.full_name)
+            .bind(screening.owner.date_of_birth)
+            .bind(&screening.owner.nationality)
+            .bind(screening.owner.ownership_percent.to_string())
+            .bind(screening.status.as_str())
+            .bind(serde_json::to_value(&screening.sanctions)?)
+            .bind(&screening.pep)
+            .bind(screening.screened_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query(
+            r#"
+            UPDATE investor_profiles
+            SET sanctioned = sanctioned OR $2, pep = pep OR $3, risk_score = LEAST(100, risk_score + $4), updated_at = NOW()
+            WHERE address = $1
+            "#
+        )
+        .bind(institution.as_bytes())
+        .bind(sanctioned)
+        .bind(pep)
+        .bind(risk_increase as i32)
+        .execute(&mut *tx)
+        .await?;
+        repository::insert_audit_entry(&mut tx, &ComplianceAuditEntry {
+            event_type: "UBO_REGISTER_UPDATED".to_string(),
+            entity_type: "investor".to_string(),
+            entity_id: format!("{:?}", institution),
+            actor: Some(requested_by.to_string()),
+            action: "updated".to_string(),
+            details: serde_json::json!({
+                "added": added.iter()
+                    .map(|screening| serde_json::json!({
+                        "name": screening.owner.full_name,
+                        "ownership_percent": screening.owner.ownership_percent,
+                        "status": screening.status.as_str(),
+                    }))
+                    .collect::<Vec<_>>(),
+                "removed": changes.removed.iter().map(|screening| &screening.owner.full_name).collect::<Vec<_>>(),
+                "updated": changes.updated.iter().map(|owner| &owner.full_name).collect::<Vec<_>>(),
+                "worst_status": worst.as_str(),
+            }),
+        }).await?;
+        tx.commit().await?;
+        
+        for rule in &rules {
+            let details = register.iter()
+                .filter(|screening| match rule {
+                    AmlRule::SanctionsMatch => screening.status == UboStatus::Sanctioned,
+                    _ => screening.pep.is_some(),
+                })
+                .map(|screening| format!("Beneficial owner {} ({}%)", screening.owner.full_name, screening.owner.ownership_percent))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let alert = self.open_screening_alert(institution, &profile.jurisdiction, *rule, &details, now, now).await?;
+            // Owner names stay in the alert; the log carries only the institution's digest
+            warn!(
+                rule = rule.as_str(),
+                institution = %Pii::debug(institution),
+                alert_id = ?alert.map(|a| a.alert_id),
+                "[AUDIT] Beneficial owner screening hit"
+            );
+        }
+        if !rules.is_empty() {
+            self.escalate_screening_hits(institution, &profile.jurisdiction, &rules, "Beneficial owner sanctions hit").await?;
+        } else {
+            // The cached report lists the previous register
+            let cache_key = format!("compliance:{}:{}", institution, profile.jurisdiction);
+            let mut cache = self.cache.write().await;
+            let _: () = cache.del(&cache_key).await?;
+        }
+        
+        info!(
+            institution = %Pii::debug(institution),
+            requested_by = %requested_by,
+            added = added.len(),
+            removed = changes.removed.len(),
+            updated = changes.updated.len(),
+            "Beneficial owners updated"
+        );
+        Ok(register)
+    }
+    
+    /// Start offboarding an investor. Refused with every blocker while the investor has open
+    /// orders, unsettled trades, pending margin calls or open AML cases; otherwise new
+    /// investments are blocked at once and the remaining holdings are tracked as they wind down.
+    pub async fn initiate_offboarding(
+        &self,
+        investor: Address,
+        request: OffboardingRequest,
+    ) -> Result<Offboarding, ComplianceError> {
+        if request.reason.trim().is_empty() || request.requested_by.trim().is_empty() {
+            return Err(ComplianceError::InvalidInput("Offboarding requires a reason and requester".to_string()));
+        }
+        
+        // Repeated requests keep the original record
+        if let Some(existing) = self.load_offboarding(investor).await? {
+            return Ok(existing);
+        }
+        if self.get_investor_profile(investor).await?.is_none() {
+            return Err(ComplianceError::InvalidInput(format!("No investor profile for {:?}", investor)));
+        }
+        
+        let (positions, open_cases) = self.offboarding_positions(investor).await?;
+        let blockers = initiation_blockers(&positions, &open_cases);
+        if !blockers.is_empty() {
+            return Err(ComplianceError::OffboardingBlocked(blockers));
+        }
+        let holdings = remaining_holdings(&positions);
+        
+        // The block and the offboarding record commit together
+        let mut tx = self.db.begin().await?;
+        sqlx::query(
+            r#"
+            INSERT INTO investor_offboarding (investor_address, reason, requested_by, stage, remaining_holdings, wind_down_checked_at)
+            VALUES ($1, $2, $3, 'winding_down', $4, NOW())
+            "#
+        )
+        .bind(investor.as_bytes())
+        .bind(&request.reason)
+        .bind(&request.requested_by)
+        .bind(serde_json::to_value(&holdings)?)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("UPDATE investor_profiles SET investment_blocked = TRUE, updated_at = NOW() WHERE address = $1")
+            .bind(investor.as_bytes())
+            .execute(&mut *tx)
+            .await?;
+        repository::insert_audit_entry(&mut tx, &ComplianceAuditEntry {
+            event_type: "OFFBOARDING_INITIATED".to_string(),
+            entity_type: "investor_profile".to_string(),
+            entity_id: format!("{:?}", investor),
+            actor: Some(request.requested_by.clone()),
+            action: "block_investments".to_string(),
+            details: serde_json::json!({
+                "reason": request.reason,
+                "remaining_holdings": holdings.len(),
+            }),
+        }).await?;
+        tx.commit().await?;
+        
+        self.revoke_compliance_passports(investor, "Investor offboarding").await?;
+        warn!(investor = %Pii::debug(investor), requested_by = %request.requested_by, reason = %request.reason, "[AUDIT] Investor offboarding initiated");
+        self.record_system_communication(
+            investor,
+            None,
+            Direction::Internal,
+            format!("Offboarding initiated by {}", request.requested_by),
+        ).await;
+        
+        self.load_offboarding(investor).await?
+            .ok_or_else(|| ComplianceError::InternalError(format!("Offboarding of {:?} was not recorded", investor)))
+    }
+    
+    /// Offboarding record of an investor; while winding down, the remaining holdings are
+    /// refreshed from the position service
+    pub async fn get_offboarding(&self, investor: Address) -> Result<Option<Offboarding>, ComplianceError> {
+        let Some(offboarding) = self.load_offboarding(investor).await? else {
+            return Ok(None);
+        };
+        if offboarding.stage != OffboardingStage::WindingDown {
+            return Ok(Some(offboarding));
+        }
+        
+        let (positions, _) = self.offboarding_positions(investor).await?;
+        self.store_remaining_holdings(investor, &remaining_holdings(&positions)).await?;
+        self.load_offboarding(investor).await
+    }
+    
+    /// Finish a wind-down: the investor is marked Offboarded, KYC must be redone before any
+    /// re-onboarding, retention holds start on their documents, and the identity registry
+    /// sync is authorized to remove the wallet. Refused with every blocker while anything
+    /// is still open or held.
+    pub async fn complete_offboarding(
+        &self,
+        investor: Address,
+        completed_by: &str,
+    ) -> Result<Offboarding, ComplianceError> {
+        let offboarding = self.load_offboarding(investor).await?
+            .ok_or_else(|| ComplianceError::InvalidInput(format!("Offboarding of {:?} has not been initiated", investor)))?;
+        if offboarding.stage == OffboardingStage::Offboarded {
+            return Ok(offboarding);
+        }
+        
+        let (positions, open_cases) = self.offboarding_positions(investor).await?;
+        let blockers = completion_blockers(&positions, &open_cases);
+        if !blockers.is_empty() {
+            self.store_remaining_holdings(investor, &remaining_holdings(&positions)).await?;
+            return Err(ComplianceError::OffboardingBlocked(blockers));
+        }
+        
+        let now = Utc::now();
+        let retain_until = retention_until(now, self.config.document_retention_years);
+        let mut tx = self.db.begin().await?;
+        sqlx::query(
+            r#"
+            UPDATE investor_offboarding
+            SET stage = 'offboarded', offboarded_at = $2, remaining_holdings = '[]', wind_down_checked_at = $2
+            WHERE investor_address = $1
+            "#
+        )
+        .bind(investor.as_bytes())
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("UPDATE investor_profiles SET kyc_status = $2, updated_at = NOW() WHERE address = $1")
+            .bind(investor.as_bytes())
+            .bind(KycStatus::Pending.as_str())
+            .execute(&mut *tx)
+            .await?;
+        // A hold never shortens one already running from an earlier offboarding
+        let held = sqlx::query(
+            r#"
+            INSERT INTO document_retention_holds (document_id, investor_address, held_from, retain_until)
+            SELECT document_id, investor_address, $2, $3 FROM investor_documents WHERE investor_address = $1
+            ON CONFLICT (document_id) DO UPDATE
+            SET retain_until = GREATEST(document_retention_holds.retain_until, EXCLUDED.retain_until)
+            "#
+        )
+        .bind(investor.as_bytes())
+        .bind(now)
+        .bind(retain_until)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        repository::insert_audit_entry(&mut tx, &ComplianceAuditEntry {
+            event_type: "OFFBOARDING_COMPLETED".to_string(),
+            entity_type: "investor_profile".to_string(),
+            entity_id: format!("{:?}", investor),
+            actor: Some(completed_by.to_string()),
+            action: "offboard".to_string(),
+            details: serde_json::json!({
+                "documents_held": held,
+                "retain_until": retain_until,
+            }),
+        }).await?;
+        tx.commit().await?;
+        
+        warn!(investor = %Pii::debug(investor), completed_by = %completed_by, documents_held = held, retain_until = %retain_until, "[AUDIT] Investor offboarded");
+        self.record_system_communication(
+            investor,
+            None,
+            Direction::Internal,
+            format!("Offboarding completed by {}", completed_by),
+        ).await;
+        
+        self.load_offboarding(investor).await?
+            .ok_or_else(|| ComplianceError::InternalError(format!("Offboarding of {:?} was not recorded", investor)))
+    }
+    
+    /// Take back an offboarded investor. The old profile is not simply reactivated: a KYC
+    /// verification made after the offboarding is required. Retention holds stay in place.
+    pub async fn reonboard_investor(&self, investor: Address, requested_by: &str) -> Result<(), ComplianceError> {
+        let offboarding = self.load_offboarding(investor).await?
+            .ok_or_else(|| ComplianceError::InvalidInput(format!("Investor {:?} has not been offboarded", investor)))?;
+        let offboarded_at = match (offboarding.stage, offboarding.offboarded_at) {
+            (OffboardingStage::Offboarded, Some(at)) => at,
+            _ => return Err(ComplianceError::InvalidInput(
+                "Offboarding is still winding down; it must complete before re-onboarding".to_string()
+            )),
+        };
+        
+        let fresh_kyc = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM kyc_verification_attempts
+                WHERE investor_id = $1 AND outcome = 'verified' AND attempted_at > $2
+            )
+            "#
+        )
+        .bind(format!("{:?}", investor))
+        .bind(offboarded_at)
+        .fetch_one(self.db.as_ref())
+        .await?;
+        if !fresh_kyc {
+            return Err(ComplianceError::InvalidInput(
+                "Re-onboarding requires a KYC verification completed after offboarding".to_string()
+            ));
+        }
+        
+        let mut tx = self.db.begin().await?;
+        sqlx::query("DELETE FROM investor_offboarding WHERE investor_address = $1")
+            .bind(investor.as_bytes())
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "UPDATE investor_profiles SET investment_blocked = FALSE, kyc_status = $2, updated_at = NOW() WHERE address = $1"
+        )
+        .bind(investor.as_bytes())
+        .bind(KycStatus::Completed.as_str())
+        .execute(&mut *tx)
+        .await?;
+        repository::insert_audit_entry(&mut tx, &ComplianceAuditEntry {
+            event_type: "INVESTOR_REONBOARDED".to_string(),
+            entity_type: "investor_profile".to_string(),
+            entity_id: format!("{:?}", investor),
+            actor: Some(requested_by.to_string()),
+            action: "reonboard".to_string(),
+            details: serde_json::json!({
+                "offboarded_at": offboarded_at,
+                "offboarding_reason": offboarding.reason,
+            }),
+        }).await?;
+        tx.commit().await?;
+        
+        warn!(investor = %Pii::debug(investor), requested_by = %requested_by, "[AUDIT] Investor re-onboarded after fresh KYC");
+        Ok(())
+    }
+    
+    /// Positions from the position service and the investor's open AML cases
+    async fn offboarding_positions(&self, investor: Address) -> Result<(PositionSnapshot, Vec<Uuid>), ComplianceError> {
+        let source = self.position_source.as_ref()
+            .ok_or_else(|| ComplianceError::ConfigurationError(
+                "POSITION_SERVICE_URL is not configured; open positions cannot be checked".to_string()
+            ))?;
+        let positions = source.positions(investor).await
+            .map_err(|e| ComplianceError::InternalError(format!("Position service failed for {:?}: {}", investor, e)))?;
+        
+        let open_cases = sqlx::query_scalar::<_, Uuid>(
+            "SELECT alert_id FROM aml_alerts WHERE investor_address = $1 AND status = 'open' ORDER BY created_at"
+        )
+        .bind(investor.as_bytes())
+        .fetch_all(self.db.as_ref())
+        .await?;
+        
+        Ok((positions, open_cases))
+    }
+    
+    async fn load_offboarding(&self, investor: Address) -> Result<Option<Offboarding>, ComplianceError> {
+        let row = sqlx::query_as::<_, OffboardingRow>(
+            r#"
+            SELECT investor_address, reason, requested_by, requested_at, stage, remaining_holdings,
+                   wind_down_checked_at, offboarded_at, registry_removed_at
+            FROM investor_offboarding
+            WHERE investor_address = $1
+            "#
+        )
+        .bind(investor.as_bytes())
+        .fetch_optional(self.db.as_ref())
+        .await?;
+        
+        row.map(offboarding_from_row).transpose()
+    }
+    
+    async fn store_remaining_holdings(&self, investor: Address, holdings: &[RemainingHolding]) -> Result<(), ComplianceError> {
+        sqlx::query(
+            "UPDATE investor_offboarding SET remaining_holdings = $2, wind_down_checked_at = NOW() WHERE investor_address = $1"
+        )
+        .bind(investor.as_bytes())
+        .bind(serde_json::to_value(holdings)?)
+        .execute(self.db.as_ref())
+        .await?;
+        Ok(())
+    }
+    
+    /// Push investor profile changes to the on-chain identity registry
+    pub async fn run_identity_registry_sync(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<IdentitySyncRun, ComplianceError> {
+        let registry = self.identity_registry.as_ref()
+            .ok_or_else(|| ComplianceError::ConfigurationError("Identity registry is not configured".to_string()))?;
+        
+        let profiles = self.load_investor_profiles().await?;
+        let mut synced = self.load_synced_identities().await?;
+        let offboarded: HashSet<Address> = sqlx::query_scalar::<_, Vec<u8>>(
+            "SELECT investor_address FROM investor_offboarding WHERE stage = 'offboarded' AND registry_removed_at IS NULL"
+        )
+        .fetch_all(self.db.as_ref())
+        .await?
+        .into_iter()
+        .map(|bytes| Address::from_slice(&bytes))
+        .collect();
+        
+        let (ops, rejected) = plan_identity_changes(&profiles, &synced, &offboarded, now);
+        let outcome = push_identity_changes(registry.as_ref(), ops, self.config.identity_sync_batch_size).await;
+        apply_outcome(&mut synced, &outcome);
+        
+        let mut run = IdentitySyncRun { run_at: now, registered: 0, updated: 0, removed: 0, failed: Vec::new() };
+        for op in &outcome.succeeded {
+            self.store_synced_identity(op.wallet(), &synced[&op.wallet()], now).await?;
+            match op {
+                IdentityOp::Register(_) => run.registered += 1,
+                IdentityOp::Update(_) => run.updated += 1,
+                IdentityOp::Remove(wallet) => {
+                    run.removed += 1;
+                    sqlx::query("UPDATE investor_offboarding SET registry_removed_at = $2 WHERE investor_address = $1")
+                        .bind(wallet.as_bytes())
+                        .bind(now)
+                        .execute(self.db.as_ref())
+                        .await?;
+                    warn!(investor = %Pii::debug(wallet), "[AUDIT] Removed offboarded investor from identity registry");
+                }
+            }
+        }
+        for (op, error) in &outcome.failed {
+            self.store_synced_identity(op.wallet(), &synced[&op.wallet()], now).await?;
+            run.failed.push(IdentitySyncFailure { wallet: op.wallet(), error: error.clone() });
+        }
+        // Profiles that cannot be mapped to a registry entry are retried like failed submissions
+        for (wallet, error) in rejected {
+            let entry = synced.entry(wallet).or_default();
+            entry.attempts += 1;
+            entry.last_error = Some(error.to_string());
+            self.store_synced_identity(wallet, entry, now).await?;
+            run.failed.push(IdentitySyncFailure { wallet, error: error.to_string() });
+        }
+        
+        info!(
+            "Identity registry sync: {} registered, {} updated, {} removed, {} failed",
+            run.registered, run.updated, run.removed, run.failed.len()
+        );
+        Ok(run)
+    }
+    
+    /// Correct stored registry state from identity events, so drift is repaired on the next sync.
+    /// Returns the number of wallets whose on-chain state differed from ours.
+    pub async fn reconcile_identity_registry(&self) -> Result<usize, ComplianceError> {
+        let registry = self.identity_registry.as_ref()
+            .ok_or_else(|| ComplianceError::ConfigurationError("Identity registry is not configured".to_string()))?;
+        
+        let last_block: Option<i64> = sqlx::query_scalar("SELECT last_block FROM identity_registry_cursor WHERE id = 1")
+            .fetch_optional(self.db.as_ref())
+            .await?;
+        let (events, head) = registry.events_since(last_block.map(|b| b as u64 + 1).unwrap_or(0)).await?;
+        
+        // Only the latest event per wallet matters
+        let mut observed: HashMap<Address, Option<H256>> = HashMap::new();
+        for event in events {
+            observed.insert(event.wallet, event.record.map(|record| record.state_hash()));
+        }
+        
+        let synced = self.load_synced_identities().await?;
+        let mut drifted = 0;
+        for (wallet, state_hash) in observed {
+            if synced.get(&wallet).and_then(|s| s.state_hash) == state_hash {
+                continue;
+            }
+            drifted += 1;
+            sqlx::query(
+                r#"
+                INSERT INTO identity_registry_state (investor_address, state_hash)
+                VALUES ($1, $2)
+                ON CONFLICT (investor_address) DO UPDATE SET state_hash = $2, updated_at = NOW()
+                "#
+            )
+            .bind(wallet.as_bytes())
+            .bind(state_hash.map(|h| h.as_bytes().to_vec()))
+            .execute(self.db.as_ref())
+            .await?;
+            warn!(investor = %Pii::debug(wallet), on_chain_state = ?state_hash, "[AUDIT] Identity registry drift");
+        }
+        
+        sqlx::query(
+            r#"
+            INSERT INTO identity_registry_cursor (id, last_block) VALUES (1, $1)
+            ON CONFLICT (id) DO UPDATE SET last_block = $1
+            "#
+        )
+        .bind(head as i64)
+        .execute(self.db.as_ref())
+        .await?;
+        
+        Ok(drifted)
+    }
+    
+    async fn load_synced_identities(&self) -> Result<HashMap<Address, SyncedIdentity>, ComplianceError> {
+        let rows = sqlx::query_as::<_, (Vec<u8>, Option<Vec<u8>>, i32, Option<String>)>(
+            "SELECT investor_address, state_hash, attempts, last_error FROM identity_registry_state"
+        )
+        .fetch_all(self.db.as_ref())
+        .await?;
+        
+        Ok(rows.into_iter()
+            .map(|(wallet, state_hash, attempts, last_error)| {
+                (Address::from_slice(&wallet), SyncedIdentity {
+                    state_hash: state_hash.map(|h| H256::from_slice(&h)),
+                    attempts: attempts as u32,
+                    last_error,
+                })
+            })
+            .collect())
+    }
+    
+    async fn store_synced_identity(
+        &self,
+        wallet: Address,
+        state: &SyncedIdentity,
+        now: DateTime<Utc>,
+    ) -> Result<(), ComplianceError> {
+        let synced_at = (state.attempts == 0).then_some(now);
+        sqlx::query(
+            r#"
+            INSERT INTO identity_registry_state (investor_address, state_hash, attempts, last_error, last_attempt_at, synced_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (investor_address) DO UPDATE SET
+                state_hash = $2, attempts = $3, last_error = $4, last_attempt_at = $5,
+                synced_at = COALESCE($6, identity_registry_state.synced_at), updated_at = NOW()
+            "#
+        )
+        .bind(wallet.as_bytes())
+        .bind(state.state_hash.map(|h| h.as_bytes().to_vec()))
+        .bind(state.attempts as i32)
+        .bind(&state.last_error)
+        .bind(now)
+        .bind(synced_at)
+        .execute(self.db.as_ref())
+        .await?;
+        Ok(())
+    }
+    
+    /// Reconcile and sync the identity registry on the configured interval; a no-op without a registry
+    pub fn spawn_identity_registry_sync_job(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            if self.identity_registry.is_none() {
+                return;
+            }
+            let every = tokio::time::Duration::from_secs(self.config.identity_sync_interval_secs);
+            loop {
+                if let Err(e) = self.reconcile_identity_registry().await {
+                    error!("Identity registry reconciliation failed: {}", e);
+                }
+                if let Err(e) = self.run_identity_registry_sync(Utc::now()).await {
+                    error!("Identity registry sync failed: {}", e);
+                }
+                tokio::time::sleep(every).await;
+            }
+        })
+    }
+    
+    /// Import an investor's historical tax lots, reconciling against on-chain holdings
+    pub async fn import_tax_lots(
+        &self,
+        investor: Address,
+        lots: Vec<ImportedLot>,
+    ) -> Result<LotImportReport, ComplianceError> {
+        let mut balances = HashMap::new();
+        for lot in &lots {
+            if !balances.contains_key(&lot.asset) {
+                let balance = self.get_token_balance(investor, lot.asset).await?;
+                balances.insert(lot.asset, balance);
+            }
+        }
+        
+        self.tax_calculator.import_lots(investor, lots, &balances).await
+    }
+    
+    /// ERC-20 balance of `holder`, scaled to whole tokens (18 decimals)
+    async fn get_token_balance(&self, holder: Address, token: Address) -> Result<Decimal, ComplianceError> {
+        // balanceOf(address)
+        let mut calldata = vec![0x70, 0xa0, 0x82, 0x31];
+        calldata.extend_from_slice(&[0u8; 12]);
+        calldata.extend_from_slice(holder.as_bytes());
+        
+        let tx: TypedTransaction = TransactionRequest::new()
+            .to(token)
+            .data(calldata)
+            .into();
+        
+        let result = self.eth_client.call(&tx, None).await
+            .map_err(|e| ComplianceError::EthereumError(format!("balanceOf failed for {:?}: {}", token, e)))?;
+        
+        if result.len() < 32 {
+            return Err(ComplianceError::EthereumError(format!("Unexpected balanceOf response from {:?}", token)));
+        }
+        
+        let raw = U256::from_big_endian(&result[..32]);
+        if raw > U256::from(u128::MAX) {
+            return Err(ComplianceError::EthereumError(format!("Balance overflow for {:?}", token)));
+        }
+        
+        Decimal::try_from_i128_with_scale(raw.as_u128() as i128, 18)
+            .map_err(|e| ComplianceError::EthereumError(format!("Balance out of range for {:?}: {}", token, e)))
+    }
+    
+    /// Generate compliance statistics
+    pub async fn get_compliance_stats(&self) -> Result<HashMap<String, serde_json::Value>, ComplianceError> {
+        let mut stats = HashMap::new();
+        
+        // Get total checks today
+        let today_count: i64 = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM compliance_reports WHERE generated_at > NOW() - INTERVAL '1 day'"
+        )
+        .fetch_one(self.db.as_ref())
+        .await
+        .unwrap_or(0);
+        
+        stats.insert("checks_today".to_string(), serde_json::json!(today_count));
+        
+        // Profiles past their re-screening date
+        let screening_backlog = overdue(&self.load_screening_schedules().await?, Utc::now()).len();
+        stats.insert("screening_backlog".to_string(), serde_json::json!(screening_backlog));
+        
+        // Get violation breakdown - temporarily disabled for Phase 1 (requires runtime query mapping)
+        // TODO: Re-enable with proper sqlx prepared queries or runtime mapping
+        let violations: Vec<(String, i64)> = Vec::new();
+        
+        stats.insert("violations_7d".to_string(), serde_json::json!(violations));
+        
+        Ok(stats)
+    }
+}
+
+type ProfileRow = (Vec<u8>, String, i16, Option<DateTime<Utc>>, i16, i32, Option<String>, Option<Vec<String>>, DateTime<Utc>, bool, bool, String, String, bool);
+
+fn profile_from_row(row: ProfileRow) -> InvestorProfile {
+    InvestorProfile {
+        address: Address::from_slice(&row.0),
+        jurisdiction: row.1,
+        kyc_level: row.2 as u8,
+        kyc_expiry: row.3.unwrap_or(row.8),
+        kyc_status: row.11.parse().unwrap_or(KycStatus::Pending),
+        aml_status: row.12.parse().unwrap_or(AmlStatus::UnderReview),
+        investment_blocked: row.13,
+        accreditation_level: row.4 as u8,
+        risk_score: row.5 as u32,
+        total_invested: row.6.and_then(|v| v.parse().ok()).unwrap_or_default(),
+        documents_ipfs: row.7.unwrap_or_default(),
+        last_check: row.8,
+        pep: row.9,
+        sanctioned: row.10,
+    }
+}
+
+type OffboardingRow = (Vec<u8>, String, String, DateTime<Utc>, String, serde_json::Value, Option<DateTime<Utc>>, Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+fn offboarding_from_row(row: OffboardingRow) -> Result<Offboarding, ComplianceError> {
+    Ok(Offboarding {
+        investor: Address::from_slice(&row.0),
+        reason: row.1,
+        requested_by: row.2,
+        requested_at: row.3,
+        stage: row.4.parse().map_err(ComplianceError::InternalError)?,
+        remaining_holdings: serde_json::from_value(row.5)?,
+        wind_down_checked_at: row.6,
+        offboarded_at: row.7,
+        registry_removed_at: row.8,
+    })
+}
+
+type DocumentRow = (Uuid, Vec<u8>, String, DateTime<Utc>, Option<DateTime<Utc>>, String, Option<String>, DateTime<Utc>, Option<DateTime<Utc>>);
+
+fn document_from_row(row: DocumentRow) -> Result<InvestorDocument, ComplianceError> {
+    Ok(InvestorDocument {
+        document_id: row.0,
+        investor: Address::from_slice(&row.1),
+        document_type: row.2.parse().map_err(ComplianceError::InternalError)?,
+        issue_date: row.3,
+        expiry_date: row.4,
+        ipfs_hash: row.5,
+        verification_id: row.6,
+        recorded_at: row.7,
+        superseded_at: row.8,
+    })
+}
+
+type AmlAlertRow = (Uuid, Vec<u8>, String, String, String, String, Vec<Uuid>, String, DateTime<Utc>, DateTime<Utc>, DateTime<Utc>, Option<String>, Option<String>, Option<DateTime<Utc>>);
+
+fn aml_alert_from_row(row: AmlAlertRow) -> Result<AmlAlert, ComplianceError> {
+    let rule: AmlRule = row.3.parse().map_err(ComplianceError::InternalError)?;
+    Ok(AmlAlert {
+        alert_id: row.0,
+        investor: Address::from_slice(&row.1),
+        jurisdiction: row.2,
+        severity: rule.severity(),
+        rule,
+        status: row.4.parse().map_err(ComplianceError::InternalError)?,
+        description: row.5,
+        transaction_ids: row.6,
+        total_amount: row.7.parse().unwrap_or_default(),
+        window_start: row.8,
+        window_end: row.9,
+        created_at: row.10,
+        resolved_by: row.11,
+        resolution_notes: row.12,
+        resolved_at: row.13,
+    })
+}
+
+type CommunicationRow = (Uuid, Vec<u8>, Option<Uuid>, String, String, String, Option<String>, String, Option<Uuid>, DateTime<Utc>);
+
+fn communication_from_row(row: CommunicationRow) -> Result<Communication, ComplianceError> {
+    Ok(Communication {
+        communication_id: row.0,
+        investor: Address::from_slice(&row.1),
+        case_id: row.2,
+        channel: row.3.parse().map_err(ComplianceError::InternalError)?,
+        direction: row.4.parse().map_err(ComplianceError::InternalError)?,
+        subject: row.5,
+        body_ipfs_hash: row.6,
+        author: row.7,
+        corrects: row.8,
+        recorded_at: row.9,
+    })
+}
+
+type CheckRecordRow = (Uuid, Vec<u8>, bool, DateTime<Utc>);
+
+fn check_record_from_row(row: CheckRecordRow) -> CheckRecord {
+    let (report_id, investor, passed, generated_at) = row;
+    CheckRecord { report_id, investor: Address::from_slice(&investor), passed, generated_at }
+}
+
+type KycAttemptRow = (Uuid, String, String, String, Option<String>, Option<String>, DateTime<Utc>);
+
+fn kyc_attempt_from_row(row: KycAttemptRow) -> Result<KycAttempt, ComplianceError> {
+    Ok(KycAttempt {
+        attempt_id: row.0,
+        investor_id: row.1,
+        provider: row.2,
+        outcome: row.3.parse().map_err(ComplianceError::InternalError)?,
+        reason: row.4,
+        reference_id: row.5,
+        attempted_at: row.6,
+    })
+}
+
+type KycReviewRow = (Uuid, String, String, DateTime<Utc>, Option<String>, Option<String>, Option<DateTime<Utc>>);
+
+fn kyc_review_from_row(row: KycReviewRow) -> Result<ManualReview, ComplianceError> {
+    Ok(ManualReview {
+        review_id: row.0,
+        investor_id: row.1,
+        status: row.2.parse().map_err(ComplianceError::InternalError)?,
+        opened_at: row.3,
+        resolved_by: row.4,
+        justification: row.5,
+        resolved_at: row.6,
+    })
 }
 
 // Helper struct for stats query
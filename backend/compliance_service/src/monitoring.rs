@@ -0,0 +1,635 @@
+use ethers::types::Address;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use uuid::Uuid;
+use crate::ViolationSeverity;
+
+// ============ AML Status ============
+
+/// Ongoing AML standing of an investor, maintained by transaction monitoring and officer review.
+/// Shared with the backend, which also sets Blocked and RequiresEnhancedDueDiligence.
+pub use quantera_types::AmlStatus;
+
+// ============ Rules ============
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AmlRule {
+    /// Many transactions just below the reporting threshold
+    Structuring,
+    /// Funds bought in and sold or transferred out again within a short window
+    RapidInOut,
+    /// Large activity on an account that had been inactive for months
+    DormantReactivation,
+    /// Repeated transactions in exact round amounts
+    RoundAmountClustering,
+    /// Periodic re-screening found the investor on a sanctions list
+    SanctionsMatch,
+    /// Periodic re-screening found the investor to be a politically exposed person
+    PepMatch,
+}
+
+impl AmlRule {
+    pub const ALL: [AmlRule; 6] = [
+        AmlRule::Structuring,
+        AmlRule::RapidInOut,
+        AmlRule::DormantReactivation,
+        AmlRule::RoundAmountClustering,
+        AmlRule::SanctionsMatch,
+        AmlRule::PepMatch,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AmlRule::Structuring => "structuring",
+            AmlRule::RapidInOut => "rapid_in_out",
+            AmlRule::DormantReactivation => "dormant_reactivation",
+            AmlRule::RoundAmountClustering => "round_amount_clustering",
+            AmlRule::SanctionsMatch => "sanctions_match",
+            AmlRule::PepMatch => "pep_match",
+        }
+    }
+
+    pub fn severity(&self) -> ViolationSeverity {
+        match self {
+            AmlRule::Structuring | AmlRule::RapidInOut => ViolationSeverity::High,
+            AmlRule::DormantReactivation | AmlRule::RoundAmountClustering | AmlRule::PepMatch => ViolationSeverity::Medium,
+            AmlRule::SanctionsMatch => ViolationSeverity::Critical,
+        }
+    }
+
+    /// Severe rules put the investor under review until an officer resolves the alert
+    pub fn is_severe(&self) -> bool {
+        matches!(self.severity(), ViolationSeverity::High | ViolationSeverity::Critical)
+    }
+
+    /// Added to the investor's risk score (0-100) when the rule triggers
+    pub fn risk_score_increase(&self) -> u32 {
+        match self {
+            AmlRule::Structuring => 25,
+            AmlRule::RapidInOut => 20,
+            AmlRule::DormantReactivation => 10,
+            AmlRule::RoundAmountClustering => 10,
+            AmlRule::SanctionsMatch => 50,
+            AmlRule::PepMatch => 15,
+        }
+    }
+}
+
+impl std::str::FromStr for AmlRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AmlRule::ALL.into_iter()
+            .find(|rule| rule.as_str() == s)
+            .ok_or_else(|| format!("Unknown AML rule: {}", s))
+    }
+}
+
+/// Rule thresholds for one jurisdiction; amounts are in USD
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RuleThresholds {
+    /// Cash-equivalent reporting threshold structuring stays below
+    pub reporting_threshold: Decimal,
+    /// Fraction below the threshold counted as "just below" (0.1 = within 10%)
+    pub structuring_margin: Decimal,
+    pub structuring_min_count: usize,
+    pub structuring_window_hours: i64,
+
+    pub rapid_window_hours: i64,
+    /// Minimum inflow before rapid in-out is considered
+    pub rapid_min_inflow: Decimal,
+    /// Share of the inflow leaving again within the window
+    pub rapid_out_ratio: Decimal,
+
+    /// Inactivity before an account counts as dormant
+    pub dormant_days: i64,
+    /// Window of new activity examined on a dormant account
+    pub dormant_activity_hours: i64,
+    pub dormant_min_amount: Decimal,
+
+    /// Amounts that are exact multiples of this unit are round
+    pub round_unit: Decimal,
+    pub round_min_count: usize,
+    /// Share of the window's transactions that must be round
+    pub round_min_share: Decimal,
+    pub round_window_hours: i64,
+}
+
+impl Default for RuleThresholds {
+    fn default() -> Self {
+        Self {
+            reporting_threshold: dec!(10000),
+            structuring_margin: dec!(0.1),
+            structuring_min_count: 3,
+            structuring_window_hours: 72,
+
+            rapid_window_hours: 48,
+            rapid_min_inflow: dec!(5000),
+            rapid_out_ratio: dec!(0.8),
+
+            dormant_days: 180,
+            dormant_activity_hours: 72,
+            dormant_min_amount: dec!(10000),
+
+            round_unit: dec!(1000),
+            round_min_count: 5,
+            round_min_share: dec!(0.8),
+            round_window_hours: 168,
+        }
+    }
+}
+
+impl RuleThresholds {
+    /// Longest window any rule looks back over, excluding the dormancy period itself
+    pub fn lookback(&self) -> Duration {
+        Duration::hours(
+            self.structuring_window_hours
+                .max(self.rapid_window_hours)
+                .max(self.dormant_activity_hours)
+                .max(self.round_window_hours),
+        )
+    }
+}
+
+/// Rule thresholds with per-jurisdiction overrides
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct MonitoringRules {
+    pub default: RuleThresholds,
+    /// Keyed by jurisdiction code as stored on investor profiles
+    pub jurisdictions: HashMap<String, RuleThresholds>,
+}
+
+impl MonitoringRules {
+    /// Load rules from a JSON file of the form `{"default": {...}, "jurisdictions": {"US": {...}}}`
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read AML rules {}: {}", path, e))?;
+        serde_json::from_str(&json)
+            .map_err(|e| format!("Invalid AML rules {}: {}", path, e))
+    }
+
+    pub fn thresholds(&self, jurisdiction: &str) -> &RuleThresholds {
+        self.jurisdictions.get(jurisdiction).unwrap_or(&self.default)
+    }
+
+    pub fn max_lookback(&self) -> Duration {
+        self.jurisdictions.values()
+            .map(RuleThresholds::lookback)
+            .fold(self.default.lookback(), Duration::max)
+    }
+}
+
+// ============ Evaluation ============
+
+/// Completed investor transaction as seen by the monitoring rules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitoredTransaction {
+    pub id: Uuid,
+    pub investor: Address,
+    /// buy, sell, yield, transfer or retirement
+    pub transaction_type: String,
+    /// Total value in USD
+    pub amount: Decimal,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl MonitoredTransaction {
+    fn is_inflow(&self) -> bool {
+        self.transaction_type == "buy"
+    }
+
+    fn is_outflow(&self) -> bool {
+        matches!(self.transaction_type.as_str(), "sell" | "transfer")
+    }
+}
+
+/// Pattern that triggered a rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleHit {
+    pub description: String,
+    pub transaction_ids: Vec<Uuid>,
+    pub total_amount: Decimal,
+}
+
+/// One rule evaluated over one window, kept as the evaluation audit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleEvaluation {
+    pub rule: AmlRule,
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    pub transactions_scanned: usize,
+    pub hit: Option<RuleHit>,
+}
+
+/// Evaluate every rule for one investor over windows ending at `now`.
+///
+/// `transactions` must cover at least the thresholds' lookback; `previous_activity` is the
+/// last transaction before the earliest of them, used to detect dormancy.
+pub fn evaluate_investor(
+    transactions: &[MonitoredTransaction],
+    previous_activity: Option<DateTime<Utc>>,
+    thresholds: &RuleThresholds,
+    now: DateTime<Utc>,
+) -> Vec<RuleEvaluation> {
+    let mut sorted: Vec<&MonitoredTransaction> = transactions.iter()
+        .filter(|tx| tx.timestamp <= now)
+        .collect();
+    sorted.sort_by_key(|tx| tx.timestamp);
+
+    vec![
+        structuring(&sorted, thresholds, now),
+        rapid_in_out(&sorted, thresholds, now),
+        dormant_reactivation(&sorted, previous_activity, thresholds, now),
+        round_amount_clustering(&sorted, thresholds, now),
+    ]
+}
+
+fn window<'a>(
+    transactions: &[&'a MonitoredTransaction],
+    start: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Vec<&'a MonitoredTransaction> {
+    transactions.iter()
+        .copied()
+        .filter(|tx| tx.timestamp > start && tx.timestamp <= now)
+        .collect()
+}
+
+fn hit(description: String, transactions: &[&MonitoredTransaction]) -> Option<RuleHit> {
+    Some(RuleHit {
+        description,
+        transaction_ids: transactions.iter().map(|tx| tx.id).collect(),
+        total_amount: transactions.iter().map(|tx| tx.amount).sum(),
+    })
+}
+
+fn structuring(transactions: &[&MonitoredTransaction], thresholds: &RuleThresholds, now: DateTime<Utc>) -> RuleEvaluation {
+    let start = now - Duration::hours(thresholds.structuring_window_hours);
+    let scanned = window(transactions, start, now);
+
+    let floor = thresholds.reporting_threshold * (Decimal::ONE - thresholds.structuring_margin);
+    let just_below: Vec<&MonitoredTransaction> = scanned.iter()
+        .copied()
+        .filter(|tx| tx.amount >= floor && tx.amount < thresholds.reporting_threshold)
+        .collect();
+
+    RuleEvaluation {
+        rule: AmlRule::Structuring,
+        window_start: start,
+        window_end: now,
+        transactions_scanned: scanned.len(),
+        hit: if just_below.len() >= thresholds.structuring_min_count {
+            hit(format!(
+                "{} transactions between {} and {} within {}h",
+                just_below.len(), floor, thresholds.reporting_threshold, thresholds.structuring_window_hours
+            ), &just_below)
+        } else {
+            None
+        },
+    }
+}
+
+fn rapid_in_out(transactions: &[&MonitoredTransaction], thresholds: &RuleThresholds, now: DateTime<Utc>) -> RuleEvaluation {
+    let start = now - Duration::hours(thresholds.rapid_window_hours);
+    let scanned = window(transactions, start, now);
+
+    let inflows: Vec<&MonitoredTransaction> = scanned.iter().copied().filter(|tx| tx.is_inflow()).collect();
+    let inflow: Decimal = inflows.iter().map(|tx| tx.amount).sum();
+
+    // Only outflows after the first inflow count as the same funds leaving again
+    let outflows: Vec<&MonitoredTransaction> = match inflows.first() {
+        Some(first_in) => scanned.iter()
+            .copied()
+            .filter(|tx| tx.is_outflow() && tx.timestamp >= first_in.timestamp)
+            .collect(),
+        None => Vec::new(),
+    };
+    let outflow: Decimal = outflows.iter().map(|tx| tx.amount).sum();
+
+    let triggered = inflow >= thresholds.rapid_min_inflow
+        && outflow >= inflow * thresholds.rapid_out_ratio;
+
+    RuleEvaluation {
+        rule: AmlRule::RapidInOut,
+        window_start: start,
+        window_end: now,
+        transactions_scanned: scanned.len(),
+        hit: if triggered {
+            let flow: Vec<&MonitoredTransaction> = inflows.into_iter().chain(outflows).collect();
+            hit(format!(
+                "{} in and {} out within {}h",
+                inflow, outflow, thresholds.rapid_window_hours
+            ), &flow)
+        } else {
+            None
+        },
+    }
+}
+
+fn dormant_reactivation(
+    transactions: &[&MonitoredTransaction],
+    previous_activity: Option<DateTime<Utc>>,
+    thresholds: &RuleThresholds,
+    now: DateTime<Utc>,
+) -> RuleEvaluation {
+    let activity_start = now - Duration::hours(thresholds.dormant_activity_hours);
+    let activity = window(transactions, activity_start, now);
+
+    let last_before = transactions.iter()
+        .filter(|tx| tx.timestamp <= activity_start)
+        .map(|tx| tx.timestamp)
+        .max()
+        .or(previous_activity);
+
+    let mut evaluation = RuleEvaluation {
+        rule: AmlRule::DormantReactivation,
+        window_start: last_before.unwrap_or(activity_start),
+        window_end: now,
+        transactions_scanned: activity.len(),
+        hit: None,
+    };
+
+    // New accounts have no dormancy to break
+    let (Some(last_before), Some(first_active)) = (last_before, activity.first()) else {
+        return evaluation;
+    };
+
+    let idle = first_active.timestamp - last_before;
+    let amount: Decimal = activity.iter().map(|tx| tx.amount).sum();
+    if idle >= Duration::days(thresholds.dormant_days) && amount >= thresholds.dormant_min_amount {
+        evaluation.hit = hit(format!(
+            "{} moved after {} days inactive",
+            amount, idle.num_days()
+        ), &activity);
+    }
+    evaluation
+}
+
+fn round_amount_clustering(transactions: &[&MonitoredTransaction], thresholds: &RuleThresholds, now: DateTime<Utc>) -> RuleEvaluation {
+    let start = now - Duration::hours(thresholds.round_window_hours);
+    let scanned = window(transactions, start, now);
+
+    let round: Vec<&MonitoredTransaction> = scanned.iter()
+        .copied()
+        .filter(|tx| tx.amount > Decimal::ZERO && (tx.amount % thresholds.round_unit).is_zero())
+        .collect();
+
+    let share = if scanned.is_empty() {
+        Decimal::ZERO
+    } else {
+        Decimal::from(round.len()) / Decimal::from(scanned.len())
+    };
+
+    RuleEvaluation {
+        rule: AmlRule::RoundAmountClustering,
+        window_start: start,
+        window_end: now,
+        transactions_scanned: scanned.len(),
+        hit: if round.len() >= thresholds.round_min_count && share >= thresholds.round_min_share {
+            hit(format!(
+                "{} of {} transactions in multiples of {} within {}h",
+                round.len(), scanned.len(), thresholds.round_unit, thresholds.round_window_hours
+            ), &round)
+        } else {
+            None
+        },
+    }
+}
+
+// ============ Alerts and Cases ============
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertStatus {
+    Open,
+    /// Officer found the activity legitimate
+    Dismissed,
+    /// Officer confirmed the activity as suspicious
+    Confirmed,
+}
+
+impl AlertStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertStatus::Open => "open",
+            AlertStatus::Dismissed => "dismissed",
+            AlertStatus::Confirmed => "confirmed",
+        }
+    }
+}
+
+impl std::str::FromStr for AlertStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "open" => Ok(AlertStatus::Open),
+            "dismissed" => Ok(AlertStatus::Dismissed),
+            "confirmed" => Ok(AlertStatus::Confirmed),
+            other => Err(format!("Unknown AML alert status: {}", other)),
+        }
+    }
+}
+
+/// Alert raised by a monitoring rule, worked as a case by a compliance officer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AmlAlert {
+    pub alert_id: Uuid,
+    pub investor: Address,
+    pub jurisdiction: String,
+    pub rule: AmlRule,
+    pub severity: ViolationSeverity,
+    pub status: AlertStatus,
+    pub description: String,
+    pub transaction_ids: Vec<Uuid>,
+    pub total_amount: Decimal,
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub resolved_by: Option<String>,
+    pub resolution_notes: Option<String>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+/// Officer decision on an open alert
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertResolution {
+    pub officer: String,
+    /// Dismissed or Confirmed
+    pub status: AlertStatus,
+    pub notes: String,
+}
+
+/// AML status after an officer resolves an alert
+///
+/// A confirmed alert flags the investor. A dismissal clears an investor under review
+/// only once no severe alert remains open.
+pub fn status_after_resolution(current: AmlStatus, resolution: AlertStatus, severe_alerts_open: usize) -> AmlStatus {
+    match (resolution, current) {
+        (AlertStatus::Confirmed, _) => AmlStatus::Flagged,
+        (_, AmlStatus::UnderReview) if severe_alerts_open == 0 => AmlStatus::Clear,
+        (_, current) => current,
+    }
+}
+
+/// Result of one scheduled monitoring run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitoringRun {
+    pub run_id: Uuid,
+    pub run_at: DateTime<Utc>,
+    pub investors_scanned: usize,
+    pub evaluations: usize,
+    pub alerts: Vec<AmlAlert>,
+    /// Investors moved to UnderReview by this run
+    pub under_review: Vec<Address>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tx(transaction_type: &str, amount: Decimal, at: DateTime<Utc>) -> MonitoredTransaction {
+        MonitoredTransaction {
+            id: Uuid::new_v4(),
+            investor: Address::repeat_byte(0x33),
+            transaction_type: transaction_type.to_string(),
+            amount,
+            timestamp: at,
+        }
+    }
+
+    fn triggered(evaluations: &[RuleEvaluation]) -> Vec<AmlRule> {
+        evaluations.iter().filter(|e| e.hit.is_some()).map(|e| e.rule).collect()
+    }
+
+    #[test]
+    fn test_structuring() {
+        let now = Utc::now();
+        let thresholds = RuleThresholds::default();
+
+        let structured = vec![
+            tx("buy", dec!(9500), now - Duration::hours(60)),
+            tx("buy", dec!(9800), now - Duration::hours(30)),
+            tx("buy", dec!(9950), now - Duration::hours(2)),
+        ];
+        let evaluations = evaluate_investor(&structured, None, &thresholds, now);
+        assert_eq!(triggered(&evaluations), vec![AmlRule::Structuring]);
+        assert_eq!(evaluations[0].hit.as_ref().unwrap().total_amount, dec!(29250));
+
+        // Amounts at or well below the threshold, or spread beyond the window, do not count
+        let ordinary = vec![
+            tx("buy", dec!(10000), now - Duration::hours(10)),
+            tx("buy", dec!(4200), now - Duration::hours(8)),
+            tx("buy", dec!(9500), now - Duration::hours(5)),
+            tx("buy", dec!(9700), now - Duration::hours(100)),
+            tx("buy", dec!(9600), now - Duration::hours(1)),
+        ];
+        assert!(triggered(&evaluate_investor(&ordinary, None, &thresholds, now)).is_empty());
+    }
+
+    #[test]
+    fn test_rapid_in_out() {
+        let now = Utc::now();
+        let thresholds = RuleThresholds::default();
+
+        let flipped = vec![
+            tx("buy", dec!(25000), now - Duration::hours(40)),
+            tx("sell", dec!(12000), now - Duration::hours(20)),
+            tx("transfer", dec!(9000), now - Duration::hours(3)),
+        ];
+        assert_eq!(triggered(&evaluate_investor(&flipped, None, &thresholds, now)), vec![AmlRule::RapidInOut]);
+
+        // Held position, or outflows that precede the inflow, do not trigger
+        let held = vec![
+            tx("sell", dec!(24000), now - Duration::hours(45)),
+            tx("buy", dec!(25000), now - Duration::hours(40)),
+            tx("sell", dec!(3000), now - Duration::hours(3)),
+        ];
+        assert!(triggered(&evaluate_investor(&held, None, &thresholds, now)).is_empty());
+
+        // Small flows stay below the minimum inflow
+        let small = vec![
+            tx("buy", dec!(2000), now - Duration::hours(10)),
+            tx("sell", dec!(2000), now - Duration::hours(5)),
+        ];
+        assert!(triggered(&evaluate_investor(&small, None, &thresholds, now)).is_empty());
+    }
+
+    #[test]
+    fn test_dormant_reactivation() {
+        let now = Utc::now();
+        let thresholds = RuleThresholds::default();
+
+        let awakened = vec![tx("buy", dec!(50000), now - Duration::hours(6))];
+        let evaluations = evaluate_investor(&awakened, Some(now - Duration::days(400)), &thresholds, now);
+        assert_eq!(triggered(&evaluations), vec![AmlRule::DormantReactivation]);
+
+        // The audit records the whole dormancy window that was scanned
+        let dormant = &evaluations[2];
+        assert_eq!(dormant.window_start, now - Duration::days(400));
+
+        // Regularly active accounts and brand-new accounts are not dormant
+        let active = vec![
+            tx("buy", dec!(1000), now - Duration::days(20)),
+            tx("buy", dec!(50000), now - Duration::hours(6)),
+        ];
+        assert!(triggered(&evaluate_investor(&active, Some(now - Duration::days(400)), &thresholds, now)).is_empty());
+        assert!(triggered(&evaluate_investor(&awakened, None, &thresholds, now)).is_empty());
+
+        // A small top-up after dormancy stays under the amount threshold
+        let small = vec![tx("buy", dec!(500), now - Duration::hours(6))];
+        assert!(triggered(&evaluate_investor(&small, Some(now - Duration::days(400)), &thresholds, now)).is_empty());
+    }
+
+    #[test]
+    fn test_round_amount_clustering() {
+        let now = Utc::now();
+        let thresholds = RuleThresholds::default();
+
+        let round: Vec<MonitoredTransaction> = (1..=5)
+            .map(|i| tx("buy", Decimal::from(i * 2000), now - Duration::hours(i * 20)))
+            .collect();
+        assert_eq!(triggered(&evaluate_investor(&round, None, &thresholds, now)), vec![AmlRule::RoundAmountClustering]);
+
+        // Round amounts mixed with ordinary ones fall below the required share
+        let mut mixed = round.clone();
+        mixed.extend((1..=3).map(|i| tx("buy", dec!(1234.56) * Decimal::from(i), now - Duration::hours(i * 5))));
+        assert!(triggered(&evaluate_investor(&mixed, None, &thresholds, now)).is_empty());
+
+        let few: Vec<MonitoredTransaction> = round.into_iter().take(4).collect();
+        assert!(triggered(&evaluate_investor(&few, None, &thresholds, now)).is_empty());
+    }
+
+    #[test]
+    fn test_jurisdiction_thresholds() {
+        let now = Utc::now();
+        let mut rules = MonitoringRules::default();
+        rules.jurisdictions.insert("SG".to_string(), RuleThresholds {
+            reporting_threshold: dec!(20000),
+            ..RuleThresholds::default()
+        });
+
+        let transactions = vec![
+            tx("buy", dec!(19000), now - Duration::hours(30)),
+            tx("buy", dec!(19500), now - Duration::hours(20)),
+            tx("buy", dec!(18500), now - Duration::hours(10)),
+        ];
+
+        assert_eq!(triggered(&evaluate_investor(&transactions, None, rules.thresholds("SG"), now)), vec![AmlRule::Structuring]);
+        assert!(triggered(&evaluate_investor(&transactions, None, rules.thresholds("US"), now)).is_empty());
+    }
+
+    #[test]
+    fn test_status_after_resolution() {
+        assert_eq!(status_after_resolution(AmlStatus::UnderReview, AlertStatus::Dismissed, 0), AmlStatus::Clear);
+        assert_eq!(status_after_resolution(AmlStatus::UnderReview, AlertStatus::Dismissed, 1), AmlStatus::UnderReview);
+        assert_eq!(status_after_resolution(AmlStatus::UnderReview, AlertStatus::Confirmed, 0), AmlStatus::Flagged);
+        assert_eq!(status_after_resolution(AmlStatus::Flagged, AlertStatus::Dismissed, 0), AmlStatus::Flagged);
+    }
+}
@@ -0,0 +1,253 @@
+//! Investor offboarding.
+//!
+//! Initiation is refused while the investor has open orders, unsettled trades, pending
+//! margin calls or open compliance cases. Once initiated, new investments are blocked and
+//! the remaining holdings are wound down; completion marks the profile Offboarded and starts
+//! the retention holds on the investor's documents.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Months, Utc};
+use ethers::types::Address;
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::{InvestorProfile, Violation, ViolationSeverity};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OffboardingStage {
+    /// Investments are blocked; remaining holdings are being redeemed or transferred out
+    WindingDown,
+    Offboarded,
+}
+
+impl OffboardingStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OffboardingStage::WindingDown => "winding_down",
+            OffboardingStage::Offboarded => "offboarded",
+        }
+    }
+}
+
+impl std::str::FromStr for OffboardingStage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "winding_down" => Ok(OffboardingStage::WindingDown),
+            "offboarded" => Ok(OffboardingStage::Offboarded),
+            other => Err(format!("Unknown offboarding stage: {}", other)),
+        }
+    }
+}
+
+/// Offboarding request; completing it authorizes removing the investor from the registry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OffboardingRequest {
+    pub reason: String,
+    pub requested_by: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Offboarding {
+    pub investor: Address,
+    pub reason: String,
+    pub requested_by: String,
+    pub requested_at: DateTime<Utc>,
+    pub stage: OffboardingStage,
+    /// Holdings still to be wound down, as of `wind_down_checked_at`
+    pub remaining_holdings: Vec<RemainingHolding>,
+    pub wind_down_checked_at: Option<DateTime<Utc>>,
+    pub offboarded_at: Option<DateTime<Utc>>,
+    pub registry_removed_at: Option<DateTime<Utc>>,
+}
+
+/// A token balance the investor still has to redeem or transfer out
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemainingHolding {
+    pub token: Address,
+    pub balance: Decimal,
+}
+
+/// What an investor still has open with the trading platform
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PositionSnapshot {
+    pub open_orders: u32,
+    pub unsettled_trades: u32,
+    pub pending_margin_calls: u32,
+    #[serde(default)]
+    pub holdings: Vec<RemainingHolding>,
+}
+
+/// Source of an investor's orders, trades, margin calls and holdings
+#[async_trait]
+pub trait PositionSource: Send + Sync {
+    async fn positions(&self, investor: Address) -> Result<PositionSnapshot>;
+}
+
+/// Platform position service reached over HTTP
+pub struct HttpPositionSource {
+    base_url: String,
+    api_key: String,
+    client: Client,
+}
+
+impl HttpPositionSource {
+    pub fn new(base_url: &str, api_key: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+            client: Client::builder()
+                .timeout(std::time::Duration::from_secs(30))
+                .build()
+                .unwrap(),
+        }
+    }
+}
+
+#[async_trait]
+impl PositionSource for HttpPositionSource {
+    async fn positions(&self, investor: Address) -> Result<PositionSnapshot> {
+        Ok(self.client
+            .get(format!("{}/v1/investors/{:?}/positions", self.base_url, investor))
+            .bearer_auth(&self.api_key)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+}
+
+/// Why an investor cannot be offboarded yet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OffboardingBlocker {
+    OpenOrders { count: u32 },
+    UnsettledTrades { count: u32 },
+    PendingMarginCalls { count: u32 },
+    OpenComplianceCases { alert_ids: Vec<Uuid> },
+    /// Only blocks completion; initiation starts the wind-down of these
+    RemainingHoldings { holdings: Vec<RemainingHolding> },
+}
+
+/// Every reason offboarding cannot be initiated, so they can all be cleared at once
+pub fn initiation_blockers(positions: &PositionSnapshot, open_cases: &[Uuid]) -> Vec<OffboardingBlocker> {
+    let mut blockers = Vec::new();
+    if positions.open_orders > 0 {
+        blockers.push(OffboardingBlocker::OpenOrders { count: positions.open_orders });
+    }
+    if positions.unsettled_trades > 0 {
+        blockers.push(OffboardingBlocker::UnsettledTrades { count: positions.unsettled_trades });
+    }
+    if positions.pending_margin_calls > 0 {
+        blockers.push(OffboardingBlocker::PendingMarginCalls { count: positions.pending_margin_calls });
+    }
+    if !open_cases.is_empty() {
+        blockers.push(OffboardingBlocker::OpenComplianceCases { alert_ids: open_cases.to_vec() });
+    }
+    blockers
+}
+
+/// Initiation blockers plus any holdings not yet wound down
+pub fn completion_blockers(positions: &PositionSnapshot, open_cases: &[Uuid]) -> Vec<OffboardingBlocker> {
+    let mut blockers = initiation_blockers(positions, open_cases);
+    let holdings = remaining_holdings(positions);
+    if !holdings.is_empty() {
+        blockers.push(OffboardingBlocker::RemainingHoldings { holdings });
+    }
+    blockers
+}
+
+/// Holdings with a balance left
+pub fn remaining_holdings(positions: &PositionSnapshot) -> Vec<RemainingHolding> {
+    positions.holdings.iter()
+        .filter(|holding| holding.balance > Decimal::ZERO)
+        .cloned()
+        .collect()
+}
+
+/// Violation every compliance check raises for an investor whose investments are blocked
+pub fn investment_block_violation(profile: &InvestorProfile) -> Option<Violation> {
+    profile.investment_blocked.then(|| Violation {
+        violation_type: "INVESTMENT_BLOCKED".to_string(),
+        description: "Investor is being offboarded; new investments are blocked".to_string(),
+        severity: ViolationSeverity::Critical,
+    })
+}
+
+/// End of the regulatory hold on an offboarded investor's documents
+pub fn retention_until(offboarded_at: DateTime<Utc>, retention_years: u32) -> DateTime<Utc> {
+    offboarded_at.checked_add_months(Months::new(retention_years * 12))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{kyc::KycStatus, monitoring::AmlStatus};
+
+    fn profile() -> InvestorProfile {
+        InvestorProfile {
+            address: Address::repeat_byte(0x01),
+            jurisdiction: "US".to_string(),
+            kyc_level: 2,
+            kyc_expiry: Utc::now(),
+            kyc_status: KycStatus::Completed,
+            aml_status: AmlStatus::Clear,
+            investment_blocked: false,
+            accreditation_level: 1,
+            risk_score: 10,
+            total_invested: Decimal::ZERO,
+            documents_ipfs: Vec::new(),
+            last_check: Utc::now(),
+            pep: false,
+            sanctioned: false,
+        }
+    }
+
+    #[test]
+    fn test_blockers_are_reported_together() {
+        let case = Uuid::new_v4();
+        let positions = PositionSnapshot {
+            open_orders: 2,
+            unsettled_trades: 1,
+            pending_margin_calls: 1,
+            holdings: vec![RemainingHolding { token: Address::repeat_byte(0x70), balance: Decimal::from(5) }],
+        };
+
+        assert_eq!(initiation_blockers(&positions, &[case]), vec![
+            OffboardingBlocker::OpenOrders { count: 2 },
+            OffboardingBlocker::UnsettledTrades { count: 1 },
+            OffboardingBlocker::PendingMarginCalls { count: 1 },
+            OffboardingBlocker::OpenComplianceCases { alert_ids: vec![case] },
+        ]);
+
+        // Holdings alone do not block initiation, only completion
+        let winding_down = PositionSnapshot { holdings: positions.holdings.clone(), ..Default::default() };
+        assert!(initiation_blockers(&winding_down, &[]).is_empty());
+        assert_eq!(completion_blockers(&winding_down, &[]), vec![
+            OffboardingBlocker::RemainingHoldings { holdings: positions.holdings },
+        ]);
+        let wound_down = PositionSnapshot {
+            holdings: vec![RemainingHolding { token: Address::repeat_byte(0x70), balance: Decimal::ZERO }],
+            ..Default::default()
+        };
+        assert!(completion_blockers(&wound_down, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_investments_blocked_from_initiation() {
+        let mut profile = profile();
+        assert!(investment_block_violation(&profile).is_none());
+
+        // Initiation sets the flag; the next check fails without waiting for completion
+        profile.investment_blocked = true;
+        let violation = investment_block_violation(&profile).unwrap();
+        assert_eq!(violation.violation_type, "INVESTMENT_BLOCKED");
+        assert!(matches!(violation.severity, ViolationSeverity::Critical));
+    }
+}
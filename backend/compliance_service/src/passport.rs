@@ -0,0 +1,221 @@
+use std::collections::BTreeMap;
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::{Address, Signature};
+use serde::{Deserialize, Serialize};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use sha2::{Sha256, Digest};
+use uuid::Uuid;
+
+// ============ Passport Documents ============
+
+/// Portable attestation of an investor's compliance standing.
+///
+/// Contains statuses and dates only; raw KYC documents and other PII are never included.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CompliancePassport {
+    pub passport_id: Uuid,
+    pub investor: Address,
+    pub jurisdiction: String,
+    pub kyc: KycAttestation,
+    pub sanctions: SanctionsAttestation,
+    pub accreditation: AccreditationAttestation,
+    pub issuer: Address,
+    pub issued_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KycAttestation {
+    pub level: u8,
+    pub expiry: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SanctionsAttestation {
+    pub sanctioned: bool,
+    pub pep: bool,
+    pub screened_at: DateTime<Utc>,
+    /// List name to the time that list was last refreshed
+    pub list_versions: BTreeMap<String, DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AccreditationAttestation {
+    pub accredited: bool,
+    pub level: u8,
+}
+
+/// Passport JSON with a detached signature from the service signing key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedPassport {
+    pub passport: CompliancePassport,
+    /// Hex encoded secp256k1 signature over the SHA-256 digest of the passport JSON
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PassportVerification {
+    pub passport_id: Uuid,
+    pub valid: bool,
+    pub signature_valid: bool,
+    pub fresh: bool,
+    pub revoked: bool,
+    pub reason: Option<String>,
+    pub verified_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PassportRevocation {
+    pub passport_id: Uuid,
+    pub investor: Address,
+    pub reason: String,
+    pub revoked_at: DateTime<Utc>,
+}
+
+// ============ Passport Signer ============
+
+pub struct PassportSigner {
+    wallet: LocalWallet,
+    validity: Duration,
+}
+
+impl PassportSigner {
+    pub fn new(signing_key: &str, validity_days: i64) -> Result<Self> {
+        let wallet = signing_key.trim_start_matches("0x").parse::<LocalWallet>()
+            .map_err(|e| anyhow::anyhow!("Invalid passport signing key: {}", e))?;
+
+        Ok(Self {
+            wallet,
+            validity: Duration::days(validity_days),
+        })
+    }
+
+    /// Address third parties use to check passport signatures
+    pub fn address(&self) -> Address {
+        self.wallet.address()
+    }
+
+    /// How long a newly issued passport stays fresh
+    pub fn validity(&self) -> Duration {
+        self.validity
+    }
+
+    /// Sign a passport, producing the JSON document and its detached signature
+    pub async fn sign(&self, passport: CompliancePassport) -> Result<SignedPassport> {
+        let digest = passport_digest(&passport)?;
+        let signature = self.wallet.sign_message(digest).await
+            .map_err(|e| anyhow::anyhow!("Failed to sign passport: {}", e))?;
+
+        Ok(SignedPassport {
+            passport,
+            signature: hex::encode(signature.to_vec()),
+        })
+    }
+
+    /// Verify signature and freshness of a passport.
+    ///
+    /// Revocation is checked by the caller against the published revocation list.
+    pub fn verify(&self, signed: &SignedPassport, now: DateTime<Utc>) -> PassportVerification {
+        let signature_valid = verify_signature(signed, self.address());
+        let fresh = signed.passport.issued_at <= now && now < signed.passport.expires_at;
+
+        let reason = if !signature_valid {
+            Some("Signature does not match the service signing key".to_string())
+        } else if !fresh {
+            Some(format!("Passport is outside its validity window (expires {})", signed.passport.expires_at))
+        } else {
+            None
+        };
+
+        PassportVerification {
+            passport_id: signed.passport.passport_id,
+            valid: signature_valid && fresh,
+            signature_valid,
+            fresh,
+            revoked: false,
+            reason,
+            verified_at: now,
+        }
+    }
+}
+
+fn passport_digest(passport: &CompliancePassport) -> Result<[u8; 32]> {
+    let json = serde_json::to_vec(passport)?;
+    Ok(Sha256::digest(&json).into())
+}
+
+fn verify_signature(signed: &SignedPassport, signer: Address) -> bool {
+    let digest = match passport_digest(&signed.passport) {
+        Ok(digest) => digest,
+        Err(_) => return false,
+    };
+
+    let signature = match hex::decode(signed.signature.trim_start_matches("0x"))
+        .ok()
+        .and_then(|bytes| Signature::try_from(bytes.as_slice()).ok())
+    {
+        Some(signature) => signature,
+        None => return false,
+    };
+
+    signature.verify(digest, signer).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+    fn sample_passport(signer: &PassportSigner, issued_at: DateTime<Utc>) -> CompliancePassport {
+        let mut list_versions = BTreeMap::new();
+        list_versions.insert("OFAC".to_string(), issued_at);
+        list_versions.insert("UN".to_string(), issued_at);
+
+        CompliancePassport {
+            passport_id: Uuid::new_v4(),
+            investor: Address::repeat_byte(0x11),
+            jurisdiction: "US".to_string(),
+            kyc: KycAttestation { level: 2, expiry: issued_at + Duration::days(365) },
+            sanctions: SanctionsAttestation {
+                sanctioned: false,
+                pep: false,
+                screened_at: issued_at,
+                list_versions,
+            },
+            accreditation: AccreditationAttestation { accredited: true, level: 1 },
+            issuer: signer.address(),
+            issued_at,
+            expires_at: issued_at + signer.validity(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_passport_round_trip() {
+        let signer = PassportSigner::new(TEST_KEY, 30).unwrap();
+        let now = Utc::now();
+        let signed = signer.sign(sample_passport(&signer, now)).await.unwrap();
+
+        let verification = signer.verify(&signed, now + Duration::days(1));
+        assert!(verification.valid);
+
+        let stale = signer.verify(&signed, now + Duration::days(31));
+        assert!(stale.signature_valid);
+        assert!(!stale.fresh);
+        assert!(!stale.valid);
+    }
+
+    #[tokio::test]
+    async fn test_tampered_passport_rejected() {
+        let signer = PassportSigner::new(TEST_KEY, 30).unwrap();
+        let now = Utc::now();
+        let mut signed = signer.sign(sample_passport(&signer, now)).await.unwrap();
+
+        signed.passport.kyc.level = 3;
+
+        let verification = signer.verify(&signed, now);
+        assert!(!verification.signature_valid);
+        assert!(!verification.valid);
+    }
+}
@@ -0,0 +1,217 @@
+//! Transaction-scoped writes.
+//!
+//! Each function takes a connection rather than the pool, so a handler that writes
+//! more than once opens a transaction, passes `&mut tx` to every write including
+//! its audit entry, and commits once; any error drops the transaction and rolls
+//! back the earlier writes.
+
+use serde_json::Value;
+use sqlx::PgConnection;
+use crate::{ComplianceError, ComplianceReport, InvestorProfile};
+
+/// Row in compliance_audit_log
+#[derive(Debug, Clone)]
+pub struct ComplianceAuditEntry {
+    pub event_type: String,
+    pub entity_type: String,
+    pub entity_id: String,
+    pub actor: Option<String>,
+    pub action: String,
+    pub details: Value,
+}
+
+pub async fn insert_audit_entry(
+    conn: &mut PgConnection,
+    entry: &ComplianceAuditEntry,
+) -> Result<(), ComplianceError> {
+    sqlx::query(
+        r#"
+        INSERT INTO compliance_audit_log (event_type, entity_type, entity_id, actor, action, details)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#
+    )
+    .bind(&entry.event_type)
+    .bind(&entry.entity_type)
+    .bind(&entry.entity_id)
+    .bind(&entry.actor)
+    .bind(&entry.action)
+    .bind(&entry.details)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn upsert_investor_profile(
+    conn: &mut PgConnection,
+    profile: &InvestorProfile,
+) -> Result<(), ComplianceError> {
+    sqlx::query(
+        r#"
+        INSERT INTO investor_profiles (
+            address, jurisdiction, kyc_level, kyc_expiry,
+            accreditation_level, risk_score, total_invested,
+            documents_ipfs, last_check, pep, sanctioned
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        ON CONFLICT (address) DO UPDATE SET
+            jurisdiction = $2, kyc_level = $3, kyc_expiry = $4,
+            accreditation_level = $5, risk_score = $6, total_invested = $7,
+            documents_ipfs = $8, last_check = $9, pep = $10, sanctioned = $11,
+            updated_at = NOW()
+        "#
+    )
+    .bind(profile.address.as_bytes())
+    .bind(&profile.jurisdiction)
+    .bind(profile.kyc_level as i16)
+    .bind(profile.kyc_expiry)
+    .bind(profile.accreditation_level as i16)
+    .bind(profile.risk_score as i32)
+    .bind(profile.total_invested.to_string())
+    .bind(&profile.documents_ipfs)
+    .bind(profile.last_check)
+    .bind(profile.pep)
+    .bind(profile.sanctioned)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn insert_compliance_report(
+    conn: &mut PgConnection,
+    report: &ComplianceReport,
+) -> Result<(), ComplianceError> {
+    let violations_json = serde_json::to_value(&report.violations)?;
+    let recommendations_json = serde_json::to_value(&report.recommendations)?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO compliance_reports (
+            report_id, investor_address, asset_address, amount,
+            jurisdiction, kyc_verified, sanctions_passed,
+            violations, recommendations, ipfs_hash, generated_at
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        "#
+    )
+    .bind(&report.report_id)
+    .bind(report.investor.as_bytes())
+    .bind(report.asset.map(|a| a.as_bytes().to_vec()))
+    .bind(report.amount.to_string())
+    .bind(&report.jurisdiction)
+    .bind(report.kyc_result.verified)
+    .bind(!report.sanctions_result.is_sanctioned && !report.beneficial_owners.iter().any(|ubo| ubo.sanctions.is_sanctioned))
+    .bind(violations_json)
+    .bind(recommendations_json)
+    .bind(report.ipfs_hash.as_deref())
+    .bind(report.generated_at)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Profile write and its audit entry, both on the caller's transaction
+pub async fn save_investor_profile(
+    conn: &mut PgConnection,
+    profile: &InvestorProfile,
+    audit: &ComplianceAuditEntry,
+) -> Result<(), ComplianceError> {
+    upsert_investor_profile(&mut *conn, profile).await?;
+    insert_audit_entry(conn, audit).await
+}
+
+/// Report write and its audit entry, both on the caller's transaction
+pub async fn save_compliance_report(
+    conn: &mut PgConnection,
+    report: &ComplianceReport,
+    audit: &ComplianceAuditEntry,
+) -> Result<(), ComplianceError> {
+    insert_compliance_report(&mut *conn, report).await?;
+    insert_audit_entry(conn, audit).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use ethers::types::Address;
+    use rust_decimal::Decimal;
+    use sqlx::PgPool;
+    use crate::{kyc::KycStatus, monitoring::AmlStatus};
+
+    /// Migrated database from TEST_DATABASE_URL; these tests are skipped without one
+    async fn test_pool() -> Option<PgPool> {
+        let url = std::env::var("TEST_DATABASE_URL").ok()?;
+        Some(PgPool::connect(&url).await.expect("TEST_DATABASE_URL is unreachable"))
+    }
+
+    fn profile(address: Address) -> InvestorProfile {
+        InvestorProfile {
+            address,
+            jurisdiction: "US".to_string(),
+            kyc_level: 2,
+            kyc_expiry: Utc::now(),
+            kyc_status: KycStatus::Completed,
+            aml_status: AmlStatus::Clear,
+            investment_blocked: false,
+            accreditation_level: 1,
+            risk_score: 10,
+            total_invested: Decimal::ZERO,
+            documents_ipfs: Vec::new(),
+            last_check: Utc::now(),
+            pep: false,
+            sanctioned: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_audit_write_rolls_back_profile() {
+        let Some(db) = test_pool().await else { return };
+        let address = Address::random();
+        // event_type is VARCHAR(50), so the audit insert fails after the profile insert succeeded
+        let audit = ComplianceAuditEntry {
+            event_type: "x".repeat(51),
+            entity_type: "investor_profile".to_string(),
+            entity_id: format!("{:?}", address),
+            actor: None,
+            action: "update".to_string(),
+            details: Value::Null,
+        };
+
+        let mut tx = db.begin().await.unwrap();
+        assert!(save_investor_profile(&mut tx, &profile(address), &audit).await.is_err());
+        drop(tx);
+
+        let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM investor_profiles WHERE address = $1")
+            .bind(address.as_bytes())
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(stored, 0);
+    }
+
+    #[tokio::test]
+    async fn test_committed_profile_includes_audit_entry() {
+        let Some(db) = test_pool().await else { return };
+        let address = Address::random();
+        let audit = ComplianceAuditEntry {
+            event_type: "PROFILE_UPDATED".to_string(),
+            entity_type: "investor_profile".to_string(),
+            entity_id: format!("{:?}", address),
+            actor: Some("test".to_string()),
+            action: "update".to_string(),
+            details: Value::Null,
+        };
+
+        let mut tx = db.begin().await.unwrap();
+        save_investor_profile(&mut tx, &profile(address), &audit).await.unwrap();
+        tx.commit().await.unwrap();
+
+        let audited: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM compliance_audit_log WHERE entity_id = $1")
+            .bind(&audit.entity_id)
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(audited, 1);
+    }
+}
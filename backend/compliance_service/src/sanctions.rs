@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use ethers::types::Address;
@@ -8,6 +8,7 @@ use redis::aio::ConnectionManager;
 use redis::AsyncCommands;
 use reqwest::Client;
 use tracing::{info, warn, error};
+use quantera_types::Pii;
 use strsim::levenshtein;
 use chrono::{DateTime, Utc};
 
@@ -118,7 +119,7 @@ impl SanctionsScreener {
         let result_str = serde_json::to_string(&result)?;
         let _: () = cache.set_ex(&cache_key, result_str.as_str(), 86400).await?;
         
-        info!("Address screening completed: {:?}, sanctioned: {}", address, result.is_sanctioned);
+        info!(address = %Pii::debug(address), sanctioned = result.is_sanctioned, "Address screening completed");
         
         Ok(result)
     }
@@ -223,8 +224,7 @@ impl SanctionsScreener {
         let result_str = serde_json::to_string(&result)?;
         let _: () = cache.set_ex(&cache_key, result_str.as_str(), 86400).await?;
         
-        info!("Name screening completed: {}, sanctioned: {}, score: {}", 
-              name, result.is_sanctioned, result.match_score);
+        info!(name = %Pii::new(name), sanctioned = result.is_sanctioned, score = result.match_score, "Name screening completed");
         
         Ok(result)
     }
@@ -300,6 +300,16 @@ impl SanctionsScreener {
         Ok(())
     }
     
+    /// Versions of the loaded lists, identified by their last refresh time
+    pub async fn list_versions(&self) -> BTreeMap<String, DateTime<Utc>> {
+        let last_update = *self.last_update.read().await;
+        
+        let mut versions = BTreeMap::new();
+        versions.insert("OFAC".to_string(), last_update);
+        versions.insert("UN".to_string(), last_update);
+        versions
+    }
+    
     /// Get statistics about sanctions screening
     pub async fn get_stats(&self) -> SanctionsStats {
         let ofac_count = self.ofac_list.read().await.len();
@@ -0,0 +1,271 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use ethers::types::Address;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use anyhow::Result;
+use crate::beneficial_owners::BeneficialOwner;
+use crate::monitoring::AmlRule;
+
+// ============ Cadence ============
+
+/// Re-screening band derived from the investor's 0-100 risk score
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskTier {
+    Low,
+    Medium,
+    High,
+}
+
+impl RiskTier {
+    pub fn for_score(risk_score: u32) -> Self {
+        match risk_score {
+            70.. => RiskTier::High,
+            40..=69 => RiskTier::Medium,
+            _ => RiskTier::Low,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RiskTier::Low => "low",
+            RiskTier::Medium => "medium",
+            RiskTier::High => "high",
+        }
+    }
+
+    /// Time allowed between screenings: monthly for high risk, quarterly for medium, annually for low
+    pub fn cadence(&self) -> Duration {
+        match self {
+            RiskTier::High => Duration::days(30),
+            RiskTier::Medium => Duration::days(90),
+            RiskTier::Low => Duration::days(365),
+        }
+    }
+}
+
+/// When a profile was last screened, with what its cadence depends on
+///
+/// The due date is derived rather than stored, so a risk score change moves it immediately.
+#[derive(Debug, Clone)]
+pub struct ScreeningSchedule {
+    pub investor: Address,
+    pub jurisdiction: String,
+    pub risk_score: u32,
+    /// Last sanctions and PEP screening, scheduled or manual
+    pub last_screened_at: Option<DateTime<Utc>>,
+}
+
+impl ScreeningSchedule {
+    pub fn tier(&self) -> RiskTier {
+        RiskTier::for_score(self.risk_score)
+    }
+
+    /// None for profiles never screened, which are due immediately
+    pub fn next_screening_due(&self) -> Option<DateTime<Utc>> {
+        self.last_screened_at.map(|at| at + self.tier().cadence())
+    }
+
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.next_screening_due().map_or(true, |due| due <= now)
+    }
+}
+
+/// A profile whose re-screening is due, for compliance officers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverdueProfile {
+    pub investor: Address,
+    pub jurisdiction: String,
+    pub risk_score: u32,
+    pub tier: RiskTier,
+    pub last_screened_at: Option<DateTime<Utc>>,
+    pub next_screening_due: Option<DateTime<Utc>>,
+    /// Unset for profiles never screened
+    pub days_overdue: Option<i64>,
+}
+
+/// Profiles due for re-screening, never-screened first and then the longest overdue
+pub fn overdue(schedules: &[ScreeningSchedule], now: DateTime<Utc>) -> Vec<OverdueProfile> {
+    let mut due: Vec<OverdueProfile> = schedules.iter()
+        .filter(|schedule| schedule.is_due(now))
+        .map(|schedule| {
+            let next_screening_due = schedule.next_screening_due();
+            OverdueProfile {
+                investor: schedule.investor,
+                jurisdiction: schedule.jurisdiction.clone(),
+                risk_score: schedule.risk_score,
+                tier: schedule.tier(),
+                last_screened_at: schedule.last_screened_at,
+                next_screening_due,
+                days_overdue: next_screening_due.map(|due| (now - due).num_days()),
+            }
+        })
+        .collect();
+    due.sort_by_key(|profile| profile.next_screening_due);
+    due
+}
+
+/// Rules to open cases for: only hits the profile was not already flagged for
+pub fn new_hits(already_sanctioned: bool, already_pep: bool, sanctioned: bool, pep: bool) -> Vec<AmlRule> {
+    let mut hits = Vec::new();
+    if sanctioned && !already_sanctioned {
+        hits.push(AmlRule::SanctionsMatch);
+    }
+    if pep && !already_pep {
+        hits.push(AmlRule::PepMatch);
+    }
+    hits
+}
+
+// ============ PEP Screening ============
+
+/// Politically exposed person lookups; Some(details) on a match
+#[async_trait]
+pub trait PepScreener: Send + Sync {
+    async fn screen(&self, investor: Address) -> Result<Option<String>>;
+
+    /// Look up a natural person, such as an institution's beneficial owner
+    async fn screen_person(&self, person: &BeneficialOwner) -> Result<Option<String>>;
+}
+
+/// PEP screening provider reached over HTTP
+pub struct HttpPepScreener {
+    base_url: String,
+    api_key: String,
+    client: Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct PepResponse {
+    pep: bool,
+    #[serde(default)]
+    details: Option<String>,
+}
+
+impl HttpPepScreener {
+    pub fn new(base_url: &str, api_key: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+            client: Client::builder()
+                .timeout(std::time::Duration::from_secs(30))
+                .build()
+                .unwrap(),
+        }
+    }
+}
+
+#[async_trait]
+impl PepScreener for HttpPepScreener {
+    async fn screen(&self, investor: Address) -> Result<Option<String>> {
+        let response: PepResponse = self.client
+            .get(format!("{}/v1/wallets/{:?}", self.base_url, investor))
+            .bearer_auth(&self.api_key)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(response.pep.then(|| response.details.unwrap_or_else(|| "Politically exposed person".to_string())))
+    }
+
+    async fn screen_person(&self, person: &BeneficialOwner) -> Result<Option<String>> {
+        let response: PepResponse = self.client
+            .get(format!("{}/v1/persons", self.base_url))
+            .query(&[
+                ("name", person.full_name.clone()),
+                ("date_of_birth", person.date_of_birth.to_string()),
+                ("nationality", person.nationality.clone()),
+            ])
+            .bearer_auth(&self.api_key)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(response.pep.then(|| response.details.unwrap_or_else(|| "Politically exposed person".to_string())))
+    }
+}
+
+// ============ Runs ============
+
+/// A new sanctions or PEP finding and the case opened for it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScreeningHit {
+    pub investor: Address,
+    pub rule: AmlRule,
+    pub details: String,
+    /// Unset when a case for the same rule was already open
+    pub alert_id: Option<Uuid>,
+}
+
+/// Outcome of one scheduled re-screening batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RescreeningRun {
+    pub run_id: Uuid,
+    pub run_at: DateTime<Utc>,
+    /// Profiles due when the run started
+    pub due: usize,
+    pub screened: usize,
+    /// Provider errors; these stay due and are retried by the next run
+    pub failed: usize,
+    /// Due profiles beyond the batch size, left for the next run
+    pub deferred: usize,
+    pub hits: Vec<ScreeningHit>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(byte: u8, risk_score: u32, last_screened_at: Option<DateTime<Utc>>) -> ScreeningSchedule {
+        ScreeningSchedule {
+            investor: Address::repeat_byte(byte),
+            jurisdiction: "US".to_string(),
+            risk_score,
+            last_screened_at,
+        }
+    }
+
+    #[test]
+    fn test_cadence_follows_risk_tier() {
+        let now = Utc::now();
+        let screened = now - Duration::days(45);
+
+        assert!(schedule(1, 85, Some(screened)).is_due(now));
+        assert!(!schedule(2, 50, Some(screened)).is_due(now));
+        assert!(!schedule(3, 10, Some(screened)).is_due(now));
+        assert!(schedule(3, 10, Some(now - Duration::days(366))).is_due(now));
+        assert_eq!(schedule(4, 40, Some(screened)).next_screening_due(), Some(screened + Duration::days(90)));
+        assert!(schedule(5, 0, None).is_due(now));
+    }
+
+    #[test]
+    fn test_backlog_lists_due_profiles_longest_overdue_first() {
+        let now = Utc::now();
+        let schedules = vec![
+            schedule(1, 90, Some(now - Duration::days(31))),
+            schedule(2, 5, Some(now - Duration::days(100))),
+            schedule(3, 5, Some(now - Duration::days(400))),
+            schedule(4, 55, None),
+        ];
+
+        let backlog = overdue(&schedules, now);
+
+        let investors: Vec<Address> = backlog.iter().map(|p| p.investor).collect();
+        assert_eq!(investors, vec![Address::repeat_byte(4), Address::repeat_byte(3), Address::repeat_byte(1)]);
+        assert_eq!(backlog[1].days_overdue, Some(35));
+        assert_eq!(backlog[2].tier, RiskTier::High);
+    }
+
+    #[test]
+    fn test_only_new_hits_open_cases() {
+        assert_eq!(new_hits(false, false, true, true), vec![AmlRule::SanctionsMatch, AmlRule::PepMatch]);
+        assert_eq!(new_hits(true, false, true, false), Vec::<AmlRule>::new());
+        assert_eq!(new_hits(false, true, false, true), Vec::<AmlRule>::new());
+    }
+}
@@ -0,0 +1,384 @@
+//! Investor self-service view of their own compliance standing.
+//!
+//! Everything here is built for the authenticated wallet only and is redacted for the
+//! investor: sanctions and accreditation are reported as pass/fail, and PEP flags, AML
+//! status, provider names, rejection reasons and case notes never leave the service.
+
+use chrono::{DateTime, Duration, Utc};
+use ethers::types::Address;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::documents::{DocumentType, InvestorDocument};
+use crate::kyc::KycStatus;
+use crate::kyc_attempts::{AttemptOutcome, KycAttempt};
+use crate::InvestorProfile;
+
+/// Where the investor portal serves the pages tasks link to
+const PORTAL_PATH: &str = "/account/compliance";
+
+/// Documents and KYC checks this close to expiry get a renewal task
+const RENEWAL_NOTICE_DAYS: i64 = 60;
+
+/// Redacted outcome of a check the investor may not see the detail of
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStanding {
+    Pass,
+    Fail,
+}
+
+impl CheckStanding {
+    fn from_passed(passed: bool) -> Self {
+        if passed { CheckStanding::Pass } else { CheckStanding::Fail }
+    }
+}
+
+/// A document requirement no active, unexpired document on file satisfies
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MissingDocument {
+    /// `identity` or `proof_of_address`
+    pub category: String,
+    /// Any one of these satisfies the requirement
+    pub accepted: Vec<DocumentType>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskPriority {
+    /// Investing is blocked until this is done
+    Blocking,
+    /// Needed to complete the profile
+    Required,
+    /// Not blocking yet, but will be at `due`
+    Upcoming,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskKind {
+    ContactCompliance,
+    CompleteProfile,
+    CompleteKyc,
+    UploadIdentityDocument,
+    UploadProofOfAddress,
+    AccreditationQuestionnaire,
+    ReplaceIdentityDocument,
+    RenewKyc,
+}
+
+/// Something the investor has to do, with a link to the portal page that does it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ComplianceTask {
+    pub kind: TaskKind,
+    pub priority: TaskPriority,
+    pub title: String,
+    pub link: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due: Option<DateTime<Utc>>,
+}
+
+impl ComplianceTask {
+    fn new(kind: TaskKind, priority: TaskPriority, title: &str, page: &str) -> Self {
+        Self { kind, priority, title: title.to_string(), link: format!("{}/{}", PORTAL_PATH, page), due: None }
+    }
+
+    fn due(mut self, due: DateTime<Utc>) -> Self {
+        self.due = Some(due);
+        self
+    }
+}
+
+/// The investor's own compliance standing and what is left to do.
+///
+/// Wallets without a profile get `profile_complete: false` and the tasks that create one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfServiceStatus {
+    pub wallet: Address,
+    pub profile_complete: bool,
+    pub kyc_level: Option<u8>,
+    pub kyc_status: KycStatus,
+    pub kyc_expiry: Option<DateTime<Utc>>,
+    pub missing_documents: Vec<MissingDocument>,
+    /// Questionnaire the investor has started or still has to answer
+    pub outstanding_questionnaire: Option<String>,
+    pub sanctions: Option<CheckStanding>,
+    pub accreditation: Option<CheckStanding>,
+    /// Most urgent first
+    pub tasks: Vec<ComplianceTask>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryKind {
+    IdentityVerification,
+    ComplianceCheck,
+    DocumentSubmitted,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryOutcome {
+    Passed,
+    Failed,
+    /// The check could not be completed, e.g. the provider was unavailable
+    Incomplete,
+    Accepted,
+}
+
+/// One past check, without the officer-level detail behind its outcome
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HistoryEntry {
+    pub kind: HistoryKind,
+    pub outcome: HistoryOutcome,
+    /// The submitted document type, for document entries
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub document_type: Option<DocumentType>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Stored outcome of a compliance check, as kept in `compliance_reports`
+#[derive(Debug, Clone)]
+pub struct CheckRecord {
+    pub report_id: Uuid,
+    pub investor: Address,
+    pub passed: bool,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// KYC attempts are keyed by the full lowercase wallet address
+pub fn investor_id(wallet: Address) -> String {
+    format!("{:?}", wallet)
+}
+
+fn identity_satisfied(documents: &[&InvestorDocument], now: DateTime<Utc>) -> bool {
+    documents.iter().any(|doc| doc.document_type.is_identity() && doc.expiry_date.map_or(true, |expiry| expiry > now))
+}
+
+/// Status of `wallet` from its profile and documents; records of any other wallet are ignored
+pub fn build_status(
+    wallet: Address,
+    profile: Option<&InvestorProfile>,
+    documents: &[InvestorDocument],
+    now: DateTime<Utc>,
+) -> SelfServiceStatus {
+    let profile = profile.filter(|profile| profile.address == wallet);
+    let active: Vec<&InvestorDocument> = documents.iter()
+        .filter(|doc| doc.investor == wallet && doc.is_active())
+        .collect();
+    let renewal_notice = now + Duration::days(RENEWAL_NOTICE_DAYS);
+    let mut tasks = Vec::new();
+
+    let mut missing_documents = Vec::new();
+    if !identity_satisfied(&active, now) {
+        missing_documents.push(MissingDocument { category: "identity".to_string(), accepted: DocumentType::IDENTITY.to_vec() });
+        tasks.push(ComplianceTask::new(TaskKind::UploadIdentityDocument, TaskPriority::Blocking, "Upload a valid identity document", "documents/identity"));
+    } else if let Some(expiry) = active.iter()
+        .filter(|doc| doc.document_type.is_identity())
+        .filter_map(|doc| doc.expiry_date)
+        .filter(|expiry| *expiry > now && *expiry <= renewal_notice)
+        .max()
+    {
+        // Only when every unexpired identity document runs out soon
+        if !active.iter().any(|doc| doc.document_type.is_identity() && doc.expiry_date.map_or(true, |e| e > renewal_notice)) {
+            tasks.push(ComplianceTask::new(TaskKind::ReplaceIdentityDocument, TaskPriority::Upcoming, "Replace your identity document before it expires", "documents/identity").due(expiry));
+        }
+    }
+    if !active.iter().any(|doc| !doc.document_type.is_identity()) {
+        missing_documents.push(MissingDocument {
+            category: "proof_of_address".to_string(),
+            accepted: vec![DocumentType::UtilityBill, DocumentType::BankStatement],
+        });
+        tasks.push(ComplianceTask::new(TaskKind::UploadProofOfAddress, TaskPriority::Required, "Upload a proof of address", "documents/address"));
+    }
+
+    let Some(profile) = profile else {
+        tasks.push(ComplianceTask::new(TaskKind::CompleteProfile, TaskPriority::Blocking, "Complete your investor profile", "profile"));
+        tasks.push(ComplianceTask::new(TaskKind::CompleteKyc, TaskPriority::Blocking, "Verify your identity", "kyc"));
+        tasks.push(ComplianceTask::new(TaskKind::AccreditationQuestionnaire, TaskPriority::Required, "Answer the accreditation questionnaire", "accreditation"));
+        tasks.sort_by_key(|task| task.priority);
+        return SelfServiceStatus {
+            wallet,
+            profile_complete: false,
+            kyc_level: None,
+            kyc_status: KycStatus::NotStarted,
+            kyc_expiry: None,
+            missing_documents,
+            outstanding_questionnaire: Some("accreditation".to_string()),
+            sanctions: None,
+            accreditation: None,
+            tasks,
+        };
+    };
+
+    if profile.sanctioned || profile.investment_blocked {
+        tasks.push(ComplianceTask::new(TaskKind::ContactCompliance, TaskPriority::Blocking, "Contact the compliance team about your account", "contact"));
+    }
+    if profile.kyc_level == 0 || profile.kyc_status != KycStatus::Completed || profile.kyc_expiry <= now {
+        tasks.push(ComplianceTask::new(TaskKind::CompleteKyc, TaskPriority::Blocking, "Verify your identity", "kyc"));
+    } else if profile.kyc_expiry <= renewal_notice {
+        tasks.push(ComplianceTask::new(TaskKind::RenewKyc, TaskPriority::Upcoming, "Renew your identity verification", "kyc").due(profile.kyc_expiry));
+    }
+    let accredited = profile.accreditation_level > 0;
+    if !accredited {
+        tasks.push(ComplianceTask::new(TaskKind::AccreditationQuestionnaire, TaskPriority::Required, "Answer the accreditation questionnaire", "accreditation"));
+    }
+    tasks.sort_by_key(|task| task.priority);
+
+    SelfServiceStatus {
+        wallet,
+        profile_complete: missing_documents.is_empty() && accredited && profile.kyc_level > 0,
+        kyc_level: Some(profile.kyc_level),
+        kyc_status: profile.kyc_status,
+        kyc_expiry: Some(profile.kyc_expiry),
+        missing_documents,
+        outstanding_questionnaire: (!accredited).then(|| "accreditation".to_string()),
+        sanctions: Some(CheckStanding::from_passed(!profile.sanctioned)),
+        accreditation: Some(CheckStanding::from_passed(accredited)),
+        tasks,
+    }
+}
+
+/// Past checks of `wallet`, newest first; records of any other wallet are ignored
+pub fn build_history(
+    wallet: Address,
+    attempts: &[KycAttempt],
+    checks: &[CheckRecord],
+    documents: &[InvestorDocument],
+) -> Vec<HistoryEntry> {
+    let id = investor_id(wallet);
+    let mut history: Vec<HistoryEntry> = attempts.iter()
+        .filter(|attempt| attempt.investor_id == id)
+        .map(|attempt| HistoryEntry {
+            kind: HistoryKind::IdentityVerification,
+            outcome: match attempt.outcome {
+                AttemptOutcome::Verified => HistoryOutcome::Passed,
+                AttemptOutcome::Rejected => HistoryOutcome::Failed,
+                AttemptOutcome::ProviderError => HistoryOutcome::Incomplete,
+            },
+            document_type: None,
+            occurred_at: attempt.attempted_at,
+        })
+        .chain(checks.iter().filter(|check| check.investor == wallet).map(|check| HistoryEntry {
+            kind: HistoryKind::ComplianceCheck,
+            outcome: if check.passed { HistoryOutcome::Passed } else { HistoryOutcome::Failed },
+            document_type: None,
+            occurred_at: check.generated_at,
+        }))
+        .chain(documents.iter().filter(|doc| doc.investor == wallet).map(|doc| HistoryEntry {
+            kind: HistoryKind::DocumentSubmitted,
+            outcome: HistoryOutcome::Accepted,
+            document_type: Some(doc.document_type),
+            occurred_at: doc.recorded_at,
+        }))
+        .collect();
+    history.sort_by(|a, b| b.occurred_at.cmp(&a.occurred_at));
+    history
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitoring::AmlStatus;
+    use rust_decimal::Decimal;
+
+    fn profile(wallet: Address, now: DateTime<Utc>) -> InvestorProfile {
+        InvestorProfile {
+            address: wallet,
+            jurisdiction: "US".to_string(),
+            kyc_level: 2,
+            kyc_expiry: now + Duration::days(30),
+            kyc_status: KycStatus::Completed,
+            aml_status: AmlStatus::Clear,
+            investment_blocked: false,
+            accreditation_level: 0,
+            risk_score: 10,
+            total_invested: Decimal::ZERO,
+            documents_ipfs: vec![],
+            last_check: now,
+            pep: true,
+            sanctioned: false,
+        }
+    }
+
+    fn document(wallet: Address, document_type: DocumentType, expiry: Option<DateTime<Utc>>, now: DateTime<Utc>) -> InvestorDocument {
+        InvestorDocument {
+            document_id: Uuid::new_v4(),
+            investor: wallet,
+            document_type,
+            issue_date: now - Duration::days(400),
+            expiry_date: expiry,
+            ipfs_hash: "ipfs://doc".to_string(),
+            verification_id: Some("provider-ref".to_string()),
+            recorded_at: now - Duration::days(10),
+            superseded_at: None,
+        }
+    }
+
+    #[test]
+    fn test_incomplete_profile_lists_the_tasks_to_complete_it() {
+        let now = Utc::now();
+        let wallet = Address::from_low_u64_be(1);
+        let other = Address::from_low_u64_be(2);
+
+        // Another wallet's profile and documents do not count for this one
+        let status = build_status(wallet, Some(&profile(other, now)), &[document(other, DocumentType::Passport, None, now)], now);
+        assert!(!status.profile_complete);
+        assert_eq!(status.kyc_level, None);
+        assert_eq!(status.sanctions, None);
+        assert_eq!(status.missing_documents.len(), 2);
+        let kinds: Vec<TaskKind> = status.tasks.iter().map(|task| task.kind).collect();
+        assert_eq!(kinds[..3], [TaskKind::UploadIdentityDocument, TaskKind::CompleteProfile, TaskKind::CompleteKyc]);
+        assert!(status.tasks.iter().all(|task| task.link.starts_with(PORTAL_PATH)));
+
+        let documents = [
+            document(wallet, DocumentType::Passport, Some(now + Duration::days(20)), now),
+            document(wallet, DocumentType::UtilityBill, None, now),
+        ];
+        let status = build_status(wallet, Some(&profile(wallet, now)), &documents, now);
+        assert!(status.missing_documents.is_empty());
+        assert_eq!(status.sanctions, Some(CheckStanding::Pass));
+        assert_eq!(status.accreditation, Some(CheckStanding::Fail));
+        assert_eq!(status.outstanding_questionnaire.as_deref(), Some("accreditation"));
+        let kinds: Vec<TaskKind> = status.tasks.iter().map(|task| task.kind).collect();
+        assert_eq!(kinds, [TaskKind::AccreditationQuestionnaire, TaskKind::ReplaceIdentityDocument, TaskKind::RenewKyc]);
+
+        // PEP status is never part of the investor's view
+        assert!(!serde_json::to_string(&status).unwrap().contains("pep"));
+    }
+
+    #[test]
+    fn test_history_only_contains_the_callers_records() {
+        let now = Utc::now();
+        let wallet = Address::from_low_u64_be(1);
+        let other = Address::from_low_u64_be(2);
+        let attempt = |investor: Address, outcome, minutes| KycAttempt {
+            attempt_id: Uuid::new_v4(),
+            investor_id: investor_id(investor),
+            provider: "jumio".to_string(),
+            outcome,
+            reason: Some("Document tampering suspected".to_string()),
+            reference_id: None,
+            attempted_at: now - Duration::minutes(minutes),
+        };
+        let attempts = [
+            attempt(wallet, AttemptOutcome::Rejected, 30),
+            attempt(other, AttemptOutcome::Verified, 20),
+            attempt(wallet, AttemptOutcome::Verified, 10),
+        ];
+        let checks = [
+            CheckRecord { report_id: Uuid::new_v4(), investor: other, passed: false, generated_at: now },
+            CheckRecord { report_id: Uuid::new_v4(), investor: wallet, passed: true, generated_at: now - Duration::minutes(5) },
+        ];
+        let documents = [document(other, DocumentType::Passport, None, now)];
+
+        let history = build_history(wallet, &attempts, &checks, &documents);
+        let entries: Vec<(HistoryKind, HistoryOutcome)> = history.iter().map(|entry| (entry.kind, entry.outcome)).collect();
+        assert_eq!(entries, [
+            (HistoryKind::ComplianceCheck, HistoryOutcome::Passed),
+            (HistoryKind::IdentityVerification, HistoryOutcome::Passed),
+            (HistoryKind::IdentityVerification, HistoryOutcome::Failed),
+        ]);
+        let json = serde_json::to_string(&history).unwrap();
+        assert!(!json.contains("jumio") && !json.contains("tampering"));
+    }
+}
@@ -1,13 +1,15 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use ethers::types::Address;
 use serde::{Deserialize, Serialize};
 use anyhow::Result;
-use chrono::{DateTime, Utc, Datelike};
+use chrono::{DateTime, NaiveDate, Utc, Datelike};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use sqlx::PgPool;
 use tracing::{info, warn};
+use quantera_types::Pii;
+use uuid::Uuid;
 
 // ============ Tax Calculator ============
 
@@ -95,8 +97,15 @@ impl TaxCalculator {
                 format!("Unknown jurisdiction: {}", jurisdiction)
             ))?;
         
-        // Get cost basis
-        let cost_basis = self.get_cost_basis(transaction.investor, transaction.asset).await?;
+        // Record acquisitions as platform-native lots so later sales have a basis
+        if matches!(transaction.transaction_type, TransactionType::Buy) {
+            if let Some(asset) = transaction.asset {
+                self.record_platform_lot(&transaction, asset).await?;
+            }
+        }
+        
+        // Get cost basis from imported and platform-native lots
+        let cost_basis = self.get_cost_basis(&transaction).await?;
         
         // Calculate gains/losses
         let proceeds = transaction.amount;
@@ -162,7 +171,7 @@ impl TaxCalculator {
         investor: Address,
         year: u32,
     ) -> Result<Form1099, crate::ComplianceError> {
-        info!("Generating Form 1099 for investor {:?} for year {}", investor, year);
+        info!(investor = %Pii::debug(investor), year, "Generating Form 1099");
         
         // Get all transactions for the year
         let transactions = self.get_yearly_transactions(investor, year).await?;
@@ -266,20 +275,207 @@ impl TaxCalculator {
         })
     }
     
-    /// Get cost basis for an investor's position
-    async fn get_cost_basis(
+    /// Import historical lots for an investor, superseding any previous import.
+    ///
+    /// `onchain_balances` holds the investor's actual holdings per asset; imported
+    /// quantities above those holdings are reported as warnings, not rejected.
+    pub async fn import_lots(
         &self,
         investor: Address,
-        asset: Option<Address>,
+        lots: Vec<ImportedLot>,
+        onchain_balances: &HashMap<Address, Decimal>,
+    ) -> Result<LotImportReport, crate::ComplianceError> {
+        let now = Utc::now();
+        
+        let errors = validate_imported_lots(&lots, now);
+        if !errors.is_empty() {
+            return Err(crate::ComplianceError::InvalidInput(errors.join("; ")));
+        }
+        
+        let mut tx = self.db.begin().await?;
+        
+        // Supersede the active import and deactivate its lots
+        let superseded: Option<(Uuid, i32)> = sqlx::query_as(
+            r#"
+            UPDATE tax_lot_imports SET superseded_at = $2
+            WHERE investor_address = $1 AND superseded_at IS NULL
+            RETURNING import_id, version
+            "#
+        )
+        .bind(investor.as_bytes())
+        .bind(now)
+        .fetch_optional(&mut *tx)
+        .await?;
+        
+        if let Some((previous_import, _)) = superseded {
+            sqlx::query("UPDATE tax_lots SET active = false WHERE import_id = $1")
+                .bind(previous_import)
+                .execute(&mut *tx)
+                .await?;
+        }
+        
+        let import_id = Uuid::new_v4();
+        let version = superseded.map(|(_, version)| version + 1).unwrap_or(1);
+        
+        sqlx::query(
+            r#"
+            INSERT INTO tax_lot_imports (import_id, investor_address, version, lot_count, imported_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#
+        )
+        .bind(import_id)
+        .bind(investor.as_bytes())
+        .bind(version)
+        .bind(lots.len() as i32)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+        
+        for lot in &lots {
+            sqlx::query(
+                r#"
+                INSERT INTO tax_lots (
+                    lot_id, investor_address, asset_address, quantity, remaining_quantity,
+                    cost_basis, acquisition_date, source, origin, import_id, active
+                ) VALUES ($1, $2, $3, $4::NUMERIC, $4::NUMERIC, $5::NUMERIC, $6, $7, 'imported', $8, true)
+                "#
+            )
+            .bind(Uuid::new_v4())
+            .bind(investor.as_bytes())
+            .bind(lot.asset.as_bytes())
+            .bind(lot.quantity.to_string())
+            .bind(lot.cost_basis.to_string())
+            .bind(lot.acquisition_date)
+            .bind(&lot.source)
+            .bind(import_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        
+        tx.commit().await?;
+        
+        let warnings = reconcile_lots(&lots, onchain_balances);
+        for warning in &warnings {
+            warn!("Imported lots exceed holdings for {:?}: imported {}, on-chain {}", 
+                  warning.asset, warning.imported_quantity, warning.onchain_quantity);
+        }
+        
+        info!(lots = lots.len(), investor = %Pii::debug(investor), version, "[AUDIT] Imported tax lots");
+        
+        Ok(LotImportReport {
+            import_id,
+            investor,
+            version,
+            lots_imported: lots.len(),
+            superseded_import: superseded.map(|(id, _)| id),
+            reconciliation_warnings: warnings,
+            imported_at: now,
+        })
+    }
+    
+    /// Record a platform-native acquisition as an open lot
+    async fn record_platform_lot(
+        &self,
+        transaction: &Transaction,
+        asset: Address,
+    ) -> Result<(), crate::ComplianceError> {
+        let quantity = transaction.quantity();
+        
+        sqlx::query(
+            r#"
+            INSERT INTO tax_lots (
+                lot_id, investor_address, asset_address, quantity, remaining_quantity,
+                cost_basis, acquisition_date, source, origin, import_id, active
+            ) VALUES ($1, $2, $3, $4::NUMERIC, $4::NUMERIC, $5::NUMERIC, $6, 'quantera', 'platform', NULL, true)
+            "#
+        )
+        .bind(Uuid::new_v4())
+        .bind(transaction.investor.as_bytes())
+        .bind(asset.as_bytes())
+        .bind(quantity.to_string())
+        .bind(transaction.amount.to_string())
+        .bind(transaction.timestamp)
+        .execute(self.db.as_ref())
+        .await?;
+        
+        Ok(())
+    }
+    
+    /// Active lots (imported and platform-native) for an investor and asset, oldest first
+    async fn get_open_lots(
+        &self,
+        investor: Address,
+        asset: Address,
+    ) -> Result<Vec<TaxLot>, crate::ComplianceError> {
+        let rows = sqlx::query_as::<_, (Uuid, String, String, String, DateTime<Utc>, String, String)>(
+            r#"
+            SELECT lot_id, quantity::TEXT, remaining_quantity::TEXT, cost_basis::TEXT,
+                   acquisition_date, source, origin
+            FROM tax_lots
+            WHERE investor_address = $1 AND asset_address = $2
+              AND active = true AND remaining_quantity > 0
+            ORDER BY acquisition_date ASC
+            "#
+        )
+        .bind(investor.as_bytes())
+        .bind(asset.as_bytes())
+        .fetch_all(self.db.as_ref())
+        .await?;
+        
+        Ok(rows.into_iter()
+            .map(|(lot_id, quantity, remaining, cost_basis, acquisition_date, source, origin)| TaxLot {
+                lot_id,
+                investor,
+                asset,
+                quantity: quantity.parse().unwrap_or_default(),
+                remaining_quantity: remaining.parse().unwrap_or_default(),
+                cost_basis: cost_basis.parse().unwrap_or_default(),
+                acquisition_date,
+                source,
+                imported: origin == "imported",
+            })
+            .collect())
+    }
+    
+    /// Get cost basis for a disposal using FIFO over open lots
+    async fn get_cost_basis(
+        &self,
+        transaction: &Transaction,
     ) -> Result<CostBasis, crate::ComplianceError> {
-        // In production, this would fetch from database
-        // Using mock data for now
+        let asset = match transaction.asset {
+            Some(asset) if !matches!(transaction.transaction_type, TransactionType::Buy) => asset,
+            // Acquisitions and asset-less transactions have no realized basis
+            _ => return Ok(CostBasis {
+                investor: transaction.investor,
+                asset: transaction.asset,
+                total_cost: transaction.amount,
+                acquisition_date: transaction.timestamp,
+                method: CostBasisMethod::Fifo,
+            }),
+        };
+        
+        let lots = self.get_open_lots(transaction.investor, asset).await?;
+        let allocation = fifo_allocate(&lots, transaction.quantity());
+        
+        if allocation.unmatched_quantity > dec!(0) {
+            warn!("No lots cover {} units of {:?} sold by {:?}; treating as zero basis",
+                  allocation.unmatched_quantity, asset, transaction.investor);
+        }
+        
+        // Consume the matched lots
+        for (lot_id, consumed) in &allocation.consumed {
+            sqlx::query("UPDATE tax_lots SET remaining_quantity = remaining_quantity - $2::NUMERIC WHERE lot_id = $1")
+                .bind(lot_id)
+                .bind(consumed.to_string())
+                .execute(self.db.as_ref())
+                .await?;
+        }
         
         Ok(CostBasis {
-            investor,
-            asset,
-            total_cost: dec!(10000),
-            acquisition_date: Utc::now() - chrono::Duration::days(400),
+            investor: transaction.investor,
+            asset: Some(asset),
+            total_cost: allocation.total_cost,
+            acquisition_date: allocation.earliest_acquisition.unwrap_or(transaction.timestamp),
             method: CostBasisMethod::Fifo,
         })
     }
@@ -374,6 +570,195 @@ impl Transaction {
     pub fn transaction_id(&self) -> String {
         format!("{:?}-{}", self.investor, self.timestamp.timestamp())
     }
+    
+    /// Units transacted, derived from value and unit price
+    pub fn quantity(&self) -> Decimal {
+        if self.price > dec!(0) {
+            self.amount / self.price
+        } else {
+            self.amount
+        }
+    }
+}
+
+// ============ Tax Lots ============
+
+/// Historical lot supplied by an investor migrating from another platform
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedLot {
+    pub asset: Address,
+    pub quantity: Decimal,
+    pub acquisition_date: DateTime<Utc>,
+    /// Total cost of the lot
+    pub cost_basis: Decimal,
+    /// Platform or broker the lot was acquired on
+    pub source: String,
+}
+
+/// Open lot in the lot store
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxLot {
+    pub lot_id: Uuid,
+    pub investor: Address,
+    pub asset: Address,
+    pub quantity: Decimal,
+    pub remaining_quantity: Decimal,
+    pub cost_basis: Decimal,
+    pub acquisition_date: DateTime<Utc>,
+    pub source: String,
+    pub imported: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LotImportReport {
+    pub import_id: Uuid,
+    pub investor: Address,
+    pub version: i32,
+    pub lots_imported: usize,
+    pub superseded_import: Option<Uuid>,
+    pub reconciliation_warnings: Vec<ReconciliationWarning>,
+    pub imported_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconciliationWarning {
+    pub asset: Address,
+    pub imported_quantity: Decimal,
+    pub onchain_quantity: Decimal,
+}
+
+/// Parse lots from CSV with header `asset,quantity,acquisition_date,cost_basis,source`.
+///
+/// Dates may be RFC 3339 timestamps or plain `YYYY-MM-DD` dates.
+pub fn parse_lots_csv(csv: &str) -> Result<Vec<ImportedLot>, crate::ComplianceError> {
+    let mut lines = csv.lines().map(str::trim).filter(|line| !line.is_empty());
+    
+    let header: Vec<String> = lines.next()
+        .ok_or_else(|| crate::ComplianceError::InvalidInput("CSV is empty".to_string()))?
+        .split(',')
+        .map(|column| column.trim().to_lowercase())
+        .collect();
+    
+    let column = |name: &str| header.iter().position(|c| c == name)
+        .ok_or_else(|| crate::ComplianceError::InvalidInput(format!("CSV missing column: {}", name)));
+    let asset_idx = column("asset")?;
+    let quantity_idx = column("quantity")?;
+    let date_idx = column("acquisition_date")?;
+    let cost_idx = column("cost_basis")?;
+    let source_idx = column("source")?;
+    
+    let mut lots = Vec::new();
+    for (row, line) in lines.enumerate() {
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let field = |idx: usize| fields.get(idx).copied()
+            .ok_or_else(|| crate::ComplianceError::InvalidInput(format!("Row {}: missing field", row + 1)));
+        let invalid = |what: &str| crate::ComplianceError::InvalidInput(format!("Row {}: invalid {}", row + 1, what));
+        
+        let date_str = field(date_idx)?;
+        let acquisition_date = DateTime::parse_from_rfc3339(date_str)
+            .map(|d| d.with_timezone(&Utc))
+            .or_else(|_| NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
+                .map(|d| d.and_hms_opt(0, 0, 0).unwrap().and_utc()))
+            .map_err(|_| invalid("acquisition_date"))?;
+        
+        lots.push(ImportedLot {
+            asset: field(asset_idx)?.parse().map_err(|_| invalid("asset"))?,
+            quantity: field(quantity_idx)?.parse().map_err(|_| invalid("quantity"))?,
+            acquisition_date,
+            cost_basis: field(cost_idx)?.parse().map_err(|_| invalid("cost_basis"))?,
+            source: field(source_idx)?.to_string(),
+        });
+    }
+    
+    Ok(lots)
+}
+
+/// Validate imported lots, returning one message per problem
+pub fn validate_imported_lots(lots: &[ImportedLot], now: DateTime<Utc>) -> Vec<String> {
+    let mut errors = Vec::new();
+    
+    if lots.is_empty() {
+        errors.push("No lots supplied".to_string());
+    }
+    
+    for (idx, lot) in lots.iter().enumerate() {
+        if lot.quantity <= dec!(0) {
+            errors.push(format!("Lot {}: quantity must be positive", idx + 1));
+        }
+        if lot.cost_basis < dec!(0) {
+            errors.push(format!("Lot {}: cost basis cannot be negative", idx + 1));
+        }
+        if lot.acquisition_date > now {
+            errors.push(format!("Lot {}: acquisition date is in the future", idx + 1));
+        }
+        if lot.source.trim().is_empty() {
+            errors.push(format!("Lot {}: source is required", idx + 1));
+        }
+    }
+    
+    errors
+}
+
+/// Compare imported quantities per asset against on-chain holdings
+pub fn reconcile_lots(
+    lots: &[ImportedLot],
+    onchain_balances: &HashMap<Address, Decimal>,
+) -> Vec<ReconciliationWarning> {
+    let mut imported: BTreeMap<Address, Decimal> = BTreeMap::new();
+    for lot in lots {
+        *imported.entry(lot.asset).or_insert(dec!(0)) += lot.quantity;
+    }
+    
+    imported.into_iter()
+        .filter_map(|(asset, imported_quantity)| {
+            let onchain_quantity = onchain_balances.get(&asset).copied().unwrap_or(dec!(0));
+            (imported_quantity > onchain_quantity).then(|| ReconciliationWarning {
+                asset,
+                imported_quantity,
+                onchain_quantity,
+            })
+        })
+        .collect()
+}
+
+/// Lots consumed by a disposal
+#[derive(Debug, Clone)]
+pub struct LotAllocation {
+    pub consumed: Vec<(Uuid, Decimal)>,
+    pub total_cost: Decimal,
+    pub earliest_acquisition: Option<DateTime<Utc>>,
+    pub unmatched_quantity: Decimal,
+}
+
+/// Allocate a disposal against lots in FIFO order (lots must be sorted oldest first)
+pub fn fifo_allocate(lots: &[TaxLot], quantity: Decimal) -> LotAllocation {
+    let mut remaining = quantity;
+    let mut allocation = LotAllocation {
+        consumed: Vec::new(),
+        total_cost: dec!(0),
+        earliest_acquisition: None,
+        unmatched_quantity: dec!(0),
+    };
+    
+    for lot in lots {
+        if remaining <= dec!(0) {
+            break;
+        }
+        if lot.remaining_quantity <= dec!(0) || lot.quantity <= dec!(0) {
+            continue;
+        }
+        
+        let take = remaining.min(lot.remaining_quantity);
+        let unit_cost = lot.cost_basis / lot.quantity;
+        
+        allocation.consumed.push((lot.lot_id, take));
+        allocation.total_cost += take * unit_cost;
+        allocation.earliest_acquisition.get_or_insert(lot.acquisition_date);
+        remaining -= take;
+    }
+    
+    allocation.unmatched_quantity = remaining.max(dec!(0));
+    allocation
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -478,3 +863,73 @@ struct TaxTransaction {
     is_long_term: bool,
     wash_sale: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn lot(quantity: Decimal, cost_basis: Decimal, days_ago: i64) -> TaxLot {
+        TaxLot {
+            lot_id: Uuid::new_v4(),
+            investor: Address::repeat_byte(1),
+            asset: Address::repeat_byte(2),
+            quantity,
+            remaining_quantity: quantity,
+            cost_basis,
+            acquisition_date: Utc::now() - chrono::Duration::days(days_ago),
+            source: "test".to_string(),
+            imported: true,
+        }
+    }
+    
+    #[test]
+    fn test_parse_and_validate_csv() {
+        let csv = "asset,quantity,acquisition_date,cost_basis,source\n\
+                   0x0202020202020202020202020202020202020202,10,2023-01-15,1000,coinbase\n\
+                   0x0202020202020202020202020202020202020202,-1,2999-01-01,50,kraken\n";
+        
+        let lots = parse_lots_csv(csv).unwrap();
+        assert_eq!(lots.len(), 2);
+        assert_eq!(lots[0].quantity, dec!(10));
+        assert_eq!(lots[0].source, "coinbase");
+        
+        let errors = validate_imported_lots(&lots, Utc::now());
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().all(|e| e.starts_with("Lot 2")));
+    }
+    
+    #[test]
+    fn test_reconciliation_flags_excess_imports() {
+        let asset = Address::repeat_byte(2);
+        let lots = vec![ImportedLot {
+            asset,
+            quantity: dec!(15),
+            acquisition_date: Utc::now(),
+            cost_basis: dec!(100),
+            source: "kraken".to_string(),
+        }];
+        
+        let mut balances = HashMap::new();
+        balances.insert(asset, dec!(10));
+        let warnings = reconcile_lots(&lots, &balances);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].onchain_quantity, dec!(10));
+        
+        balances.insert(asset, dec!(15));
+        assert!(reconcile_lots(&lots, &balances).is_empty());
+    }
+    
+    #[test]
+    fn test_fifo_allocation_spans_lots() {
+        let lots = vec![lot(dec!(10), dec!(100), 400), lot(dec!(10), dec!(300), 100)];
+        
+        let allocation = fifo_allocate(&lots, dec!(15));
+        assert_eq!(allocation.total_cost, dec!(250));
+        assert_eq!(allocation.consumed.len(), 2);
+        assert_eq!(allocation.earliest_acquisition, Some(lots[0].acquisition_date));
+        assert_eq!(allocation.unmatched_quantity, dec!(0));
+        
+        let oversold = fifo_allocate(&lots, dec!(25));
+        assert_eq!(oversold.unmatched_quantity, dec!(5));
+    }
+}
@@ -0,0 +1,204 @@
+use std::time::Duration;
+use alloy_primitives::H256;
+use async_trait::async_trait;
+use quantera_types::{BlockTag, ChainHeads, Finality, FinalityPolicy, FinalityStatus};
+use tracing::debug;
+use crate::{Error, TransactionBackend, TransactionReceipt};
+
+/// Block heights finality is judged against.
+///
+/// EthereumClient is the production implementation; tests can substitute a mock provider.
+#[async_trait]
+pub trait ChainHeadSource: Send + Sync {
+    async fn latest_block(&self) -> Result<u64, Error>;
+
+    /// Number of the block the node reports for `tag`; an error when the node does not
+    /// support the tag
+    async fn tagged_block(&self, tag: BlockTag) -> Result<u64, Error>;
+}
+
+/// Current heads, fetching only the tags the policy uses
+pub async fn chain_heads(source: &dyn ChainHeadSource, policy: &FinalityPolicy) -> Result<ChainHeads, Error> {
+    let mut heads = ChainHeads { latest: source.latest_block().await?, ..Default::default() };
+    for tag in policy.tags() {
+        let block = source.tagged_block(tag).await?;
+        match tag {
+            BlockTag::Safe => heads.safe = Some(block),
+            BlockTag::Finalized => heads.finalized = Some(block),
+        }
+    }
+    Ok(heads)
+}
+
+/// What a recheck of a recorded transaction found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FinalityCheck {
+    /// Mined in this block, with the status the policy gives it
+    Observed { status: FinalityStatus, block_number: u64, block_hash: H256 },
+    /// No receipt; the transaction is unmined or its block was reorged out
+    Missing,
+    /// The receipt now names a different block than the one recorded as Confirmed
+    Reorged { recorded: String, observed: H256 },
+}
+
+/// Recheck a transaction against its recorded finality. Missing and Reorged results for a
+/// Confirmed record mean the record should be downgraded.
+pub async fn check_finality(
+    backend: &dyn TransactionBackend,
+    heads: &dyn ChainHeadSource,
+    policy: &FinalityPolicy,
+    tx_hash: H256,
+    recorded: &Finality,
+) -> Result<FinalityCheck, Error> {
+    let Some(receipt) = backend.receipt(tx_hash).await? else {
+        return Ok(FinalityCheck::Missing);
+    };
+    let observed = format!("{:?}", receipt.block_hash);
+    if let Some(recorded_hash) = &recorded.block_hash {
+        if recorded.status == FinalityStatus::Confirmed && !recorded_hash.eq_ignore_ascii_case(&observed) {
+            return Ok(FinalityCheck::Reorged { recorded: recorded_hash.clone(), observed: receipt.block_hash });
+        }
+    }
+    let heads = chain_heads(heads, policy).await?;
+    Ok(FinalityCheck::Observed {
+        status: policy.status(receipt.block_number, &heads),
+        block_number: receipt.block_number,
+        block_hash: receipt.block_hash,
+    })
+}
+
+/// Wait until a mined transaction is at least Confirmed under the policy. The receipt is
+/// re-read each poll so a transaction reorged into another block is followed there; the
+/// receipt returned is the one it was confirmed in.
+pub async fn wait_for_confirmation(
+    backend: &dyn TransactionBackend,
+    heads: &dyn ChainHeadSource,
+    policy: &FinalityPolicy,
+    mut receipt: TransactionReceipt,
+    poll_interval: Duration,
+) -> Result<(TransactionReceipt, FinalityStatus), Error> {
+    loop {
+        let status = policy.status(receipt.block_number, &chain_heads(heads, policy).await?);
+        if status >= FinalityStatus::Confirmed {
+            return Ok((receipt, status));
+        }
+        debug!("Transaction {} in block {} is not yet confirmed", receipt.transaction_hash, receipt.block_number);
+
+        tokio::time::sleep(poll_interval).await;
+        if let Some(current) = backend.receipt(receipt.transaction_hash).await? {
+            receipt = current;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use alloy_primitives::{Address, U256};
+    use quantera_types::FinalityRule;
+    use crate::TransactionParams;
+
+    /// Chain whose head advances one block per head lookup; tags trail the head
+    struct MockChain {
+        latest: Mutex<u64>,
+        safe_lag: Option<u64>,
+        finalized_lag: Option<u64>,
+        block: Mutex<(u64, H256)>,
+        head_reads: Mutex<usize>,
+    }
+
+    impl MockChain {
+        fn new(latest: u64, safe_lag: Option<u64>, finalized_lag: Option<u64>) -> Self {
+            Self {
+                latest: Mutex::new(latest),
+                safe_lag,
+                finalized_lag,
+                block: Mutex::new((100, H256::repeat_byte(0xbb))),
+                head_reads: Mutex::new(0),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ChainHeadSource for MockChain {
+        async fn latest_block(&self) -> Result<u64, Error> {
+            *self.head_reads.lock().unwrap() += 1;
+            let mut latest = self.latest.lock().unwrap();
+            *latest += 1;
+            Ok(*latest)
+        }
+
+        async fn tagged_block(&self, tag: BlockTag) -> Result<u64, Error> {
+            let lag = match tag {
+                BlockTag::Safe => self.safe_lag,
+                BlockTag::Finalized => self.finalized_lag,
+            };
+            let lag = lag.ok_or_else(|| Error::ProviderError(format!("{} tag not supported", tag.as_str())))?;
+            Ok(self.latest.lock().unwrap().saturating_sub(lag))
+        }
+    }
+
+    #[async_trait]
+    impl TransactionBackend for MockChain {
+        fn sender(&self) -> Address {
+            Address::repeat_byte(0xaa)
+        }
+
+        async fn broadcast(&self, _params: &TransactionParams) -> Result<H256, Error> {
+            unimplemented!()
+        }
+
+        async fn receipt(&self, hash: H256) -> Result<Option<TransactionReceipt>, Error> {
+            let (block_number, block_hash) = *self.block.lock().unwrap();
+            Ok(Some(TransactionReceipt {
+                transaction_hash: hash,
+                block_number,
+                block_hash,
+                contract_address: None,
+                gas_used: U256::from(50_000u64),
+                status: true,
+                logs: Vec::new(),
+            }))
+        }
+
+        async fn mined_nonce(&self) -> Result<u64, Error> {
+            Ok(0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_waits_for_confirmation_count() {
+        // Mined in block 100 with the head at 100: 64 confirmations are 63 more blocks
+        let chain = MockChain::new(99, None, None);
+        let policy = FinalityPolicy { confirmed: FinalityRule::Depth(64), finalized: FinalityRule::Depth(256) };
+        let receipt = chain.receipt(H256::repeat_byte(1)).await.unwrap().unwrap();
+
+        let (receipt, status) = wait_for_confirmation(&chain, &chain, &policy, receipt, Duration::from_millis(1)).await.unwrap();
+        assert_eq!(status, FinalityStatus::Confirmed);
+        assert_eq!(receipt.block_number, 100);
+        assert_eq!(*chain.latest.lock().unwrap(), 163);
+        assert_eq!(*chain.head_reads.lock().unwrap(), 64);
+    }
+
+    #[tokio::test]
+    async fn test_finalized_tag_and_reorg_detection() {
+        // The finalized tag trails the head by 64 blocks; safe is not used by this policy
+        let chain = MockChain::new(170, None, Some(64));
+        let policy = FinalityPolicy { confirmed: FinalityRule::Depth(12), finalized: FinalityRule::Tag(BlockTag::Finalized) };
+        let tx = H256::repeat_byte(1);
+
+        let mut recorded = Finality::default();
+        let check = check_finality(&chain, &chain, &policy, tx, &recorded).await.unwrap();
+        assert_eq!(check, FinalityCheck::Observed { status: FinalityStatus::Finalized, block_number: 100, block_hash: H256::repeat_byte(0xbb) });
+
+        // A Confirmed record whose block was replaced is reported as reorged
+        recorded.observe(FinalityStatus::Confirmed, 100, &format!("{:?}", H256::repeat_byte(0xcc))).unwrap();
+        let check = check_finality(&chain, &chain, &policy, tx, &recorded).await.unwrap();
+        assert!(matches!(check, FinalityCheck::Reorged { observed, .. } if observed == H256::repeat_byte(0xbb)));
+
+        // Nodes without the tag fail the lookup rather than reporting Pending forever
+        let untagged = MockChain::new(170, None, None);
+        assert!(check_finality(&untagged, &untagged, &policy, tx, &Finality::default()).await.is_err());
+    }
+}
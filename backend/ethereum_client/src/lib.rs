@@ -2,11 +2,39 @@ use alloy_primitives::{Address, U256, H256};
 use alloy_provider::Provider;
 use alloy_signer::LocalWallet;
 use alloy_contract::{Tokenize, Token, FromEvent};
+use quantera_secrets::{SecretRef, SecretsProvider};
+use quantera_types::{BlockTag, Finality, FinalityPolicy};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 use tracing::{info, error, warn, debug};
 
+pub mod metrics;
+
+pub use metrics::{MetricsRecorder, NoopMetricsRecorder};
+use metrics::{CallContext, instrument_call};
+
+pub mod pending;
+
+pub use pending::{
+    PendingKind,
+    PendingTransaction,
+    PendingTransactionTracker,
+    ReplacementOutcome,
+    StallPolicy,
+    TransactionBackend,
+    TransactionParams,
+    MIN_FEE_BUMP_PERCENT,
+};
+
+pub mod finality;
+
+pub use finality::{
+    ChainHeadSource,
+    FinalityCheck,
+};
+
 /// Custom error type for EthereumClient operations
 #[derive(Debug, Error)]
 pub enum Error {
@@ -38,6 +66,23 @@ pub enum Error {
     InvalidState(String),
 }
 
+impl Error {
+    /// Short variant name used as the `error` metric label
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::ProviderError(_) => "provider",
+            Error::WalletError(_) => "wallet",
+            Error::ContractError(_) => "contract",
+            Error::EncodingError(_) => "encoding",
+            Error::TransactionError(_) => "transaction",
+            Error::BlobDataError(_) => "blob_data",
+            Error::SmartAccountError(_) => "smart_account",
+            Error::BLSSignatureError(_) => "bls_signature",
+            Error::InvalidState(_) => "invalid_state",
+        }
+    }
+}
+
 /// Transaction receipt returned after sending transactions
 #[derive(Debug, Clone)]
 pub struct TransactionReceipt {
@@ -61,26 +106,57 @@ pub struct Log {
     pub log_index: u32,
 }
 
+/// Outcome of simulating a transaction with eth_call before broadcasting it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimulationOutcome {
+    /// The call executed successfully and returned the given data
+    Success(Vec<u8>),
+    /// The call reverted; the reason is decoded from the revert data when possible
+    Reverted(String),
+}
+
+impl SimulationOutcome {
+    /// Whether the simulated call would revert
+    pub fn is_revert(&self) -> bool {
+        matches!(self, SimulationOutcome::Reverted(_))
+    }
+}
+
 /// Client for interacting with Ethereum blockchain
 pub struct EthereumClient {
     provider: Arc<Provider>,
     wallet: LocalWallet,
     chain_id: u64,
     supports_pectra: bool,
+    endpoint: String,
+    metrics: Arc<dyn MetricsRecorder>,
+    slow_call_threshold: Duration,
+    pending: PendingTransactionTracker,
+    stall_policy: Option<StallPolicy>,
+    finality_policy: FinalityPolicy,
 }
 
 impl EthereumClient {
-    /// Create a new EthereumClient
-    pub async fn new(rpc_url: &str, private_key: &str, chain_id: u64) -> Result<Self, Error> {
+    /// Create a new EthereumClient signing with the private key `signer_key` resolves to.
+    /// The key is read once; the plaintext is zeroized as soon as the wallet is built.
+    pub async fn new(
+        rpc_url: &str,
+        signer_key: &SecretRef,
+        secrets: &dyn SecretsProvider,
+        chain_id: u64,
+    ) -> Result<Self, Error> {
         info!("Initializing EthereumClient with chain_id: {}", chain_id);
         
         // Initialize provider
         let provider = Provider::try_from(rpc_url)
             .map_err(|e| Error::ProviderError(e.to_string()))?;
         
-        // Initialize wallet from private key
-        let wallet = LocalWallet::from_private_key_hex(private_key)
+        // Initialize wallet from the private key held by the secrets provider
+        let private_key = secrets.fetch(signer_key).await
+            .map_err(|e| Error::WalletError(format!("Cannot read signer key {}: {}", signer_key, e)))?;
+        let wallet = LocalWallet::from_private_key_hex(private_key.expose())
             .map_err(|e| Error::WalletError(format!("Failed to create wallet: {}", e)))?;
+        drop(private_key);
         
         // Check if the network supports Pectra
         let supports_pectra = Self::check_pectra_support(&provider).await
@@ -93,9 +169,57 @@ impl EthereumClient {
             wallet,
             chain_id,
             supports_pectra,
+            endpoint: metrics::endpoint_label(rpc_url),
+            metrics: Arc::new(NoopMetricsRecorder),
+            slow_call_threshold: metrics::DEFAULT_SLOW_CALL_THRESHOLD,
+            pending: PendingTransactionTracker::default(),
+            stall_policy: None,
+            finality_policy: FinalityPolicy::for_chain(chain_id),
         })
     }
     
+    /// Record per-method request, error and latency metrics with the given recorder
+    pub fn with_metrics(mut self, metrics: Arc<dyn MetricsRecorder>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+    
+    /// Log calls slower than `threshold` at warn level
+    pub fn with_slow_call_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_call_threshold = threshold;
+        self
+    }
+    
+    /// Speed up contract transactions that stay unmined past the policy's stall timeout
+    pub fn with_stall_policy(mut self, policy: StallPolicy) -> Self {
+        self.stall_policy = Some(policy);
+        self
+    }
+    
+    /// Judge receipts against this policy instead of the chain's default
+    pub fn with_finality_policy(mut self, policy: FinalityPolicy) -> Self {
+        self.finality_policy = policy;
+        self
+    }
+    
+    pub fn finality_policy(&self) -> &FinalityPolicy {
+        &self.finality_policy
+    }
+    
+    /// Span and metric context for a call; only the selector is kept, never the calldata
+    fn call_context(&self, method: &'static str, target: Address, selector: Option<String>) -> CallContext<'_> {
+        CallContext {
+            method,
+            endpoint: &self.endpoint,
+            target,
+            selector,
+        }
+    }
+    
+    fn selector_label(function: &str) -> Option<String> {
+        Self::get_function_selector(function).ok().map(|selector| format!("0x{}", hex::encode(selector)))
+    }
+    
     /// Check if the connected network supports Pectra EIPs
     async fn check_pectra_support(provider: &Provider) -> Result<bool, Error> {
         // Try to detect EIP-7702 support (smart accounts)
@@ -163,101 +287,249 @@ impl EthereumClient {
     pub async fn call_contract<T: Tokenize>(&self, address: Address, function: &str, args: Vec<Token>) -> Result<T, Error> {
         debug!("Calling contract function: {} at {}", function, address);
         
-        // Encode function call
-        let calldata = Self::encode_function_call(function, args)
-            .map_err(|e| Error::EncodingError(e))?;
-        
-        // Call contract
-        let result = self.provider.call(
-            address,
-            calldata.clone(),
-            None, // Block number (latest)
-        ).await.map_err(|e| Error::ContractError(format!("Contract call failed: {}", e)))?;
-        
-        // Decode result
-        let decoded = T::from_tokens(&Token::decode(&result)
-            .map_err(|e| Error::EncodingError(format!("Failed to decode result: {}", e)))?)
-            .map_err(|e| Error::EncodingError(format!("Failed to convert from tokens: {}", e)))?;
-        
-        Ok(decoded)
+        let context = self.call_context("call_contract", address, Self::selector_label(function));
+        instrument_call(self.metrics.as_ref(), self.slow_call_threshold, context, async {
+            // Encode function call
+            let calldata = Self::encode_function_call(function, args)
+                .map_err(|e| Error::EncodingError(e))?;
+            
+            // Call contract
+            let result = self.provider.call(
+                address,
+                calldata.clone(),
+                None, // Block number (latest)
+            ).await.map_err(|e| Error::ContractError(format!("Contract call failed: {}", e)))?;
+            
+            // Decode result
+            let decoded = T::from_tokens(&Token::decode(&result)
+                .map_err(|e| Error::EncodingError(format!("Failed to decode result: {}", e)))?)
+                .map_err(|e| Error::EncodingError(format!("Failed to convert from tokens: {}", e)))?;
+            
+            Ok(decoded)
+        }).await
     }
     
     /// Send a transaction to a contract
     pub async fn send_transaction(&self, address: Address, function: &str, args: Vec<Token>) -> Result<TransactionReceipt, Error> {
         info!("Sending transaction to: {} function: {}", address, function);
         
+        let context = self.call_context("send_transaction", address, Self::selector_label(function));
+        instrument_call(self.metrics.as_ref(), self.slow_call_threshold, context, async {
+            // Encode function call
+            let calldata = Self::encode_function_call(function, args)
+                .map_err(|e| Error::EncodingError(e))?;
+            
+            // Fix the nonce and fee up front so the transaction can be replaced if it stalls
+            let params = TransactionParams {
+                to: Some(address),
+                data: calldata,
+                value: U256::ZERO,
+                nonce: self.next_nonce().await?,
+                gas_limit: None,
+                gas_price: self.gas_price().await?,
+            };
+            
+            // Sign and send transaction
+            let tx_hash = self.broadcast(&params).await?;
+            self.pending.record(tx_hash, params, PendingKind::Original);
+            
+            // Wait for this transaction or a replacement to be mined, then for the chain's confirmations
+            let receipt = self.pending.wait(self, tx_hash, self.stall_policy).await?;
+            let receipt = self.wait_for_confirmation(receipt).await?;
+            
+            if !receipt.status {
+                return Err(Error::TransactionError("Transaction reverted".to_string()));
+            }
+            
+            info!("Transaction successful: {}", tx_hash);
+            
+            Ok(receipt)
+        }).await
+    }
+    
+    /// Simulate a contract transaction with eth_call using the same calldata and sender
+    ///
+    /// Nothing is signed or broadcast. A revert is reported as `SimulationOutcome::Reverted`
+    /// rather than an error so callers can decide whether to proceed.
+    pub async fn simulate_transaction(&self, address: Address, function: &str, args: Vec<Token>) -> Result<SimulationOutcome, Error> {
+        debug!("Simulating transaction to: {} function: {}", address, function);
+        
         // Encode function call
         let calldata = Self::encode_function_call(function, args)
             .map_err(|e| Error::EncodingError(e))?;
         
-        // Sign transaction
-        let tx_request = self.wallet.sign_transaction(
+        // Call contract from the wallet address so access control checks match the real send
+        let result = self.provider.call_from(
+            self.wallet.address(),
+            address,
             calldata,
-            Some(address),
-            self.chain_id,
-            None, // nonce
-            None, // value
-            None, // gas limit
-            None, // gas price
-        ).map_err(|e| Error::TransactionError(format!("Failed to sign transaction: {}", e)))?;
-        
-        // Send transaction
-        let tx_hash = self.provider.send_raw_transaction(tx_request)
-            .await
-            .map_err(|e| Error::TransactionError(format!("Failed to send transaction: {}", e)))?;
-        
-        // Wait for transaction receipt
-        let receipt = self.wait_for_transaction_receipt(tx_hash).await?;
+            None, // Block number (latest)
+        ).await;
         
-        if !receipt.status {
-            return Err(Error::TransactionError("Transaction reverted".to_string()));
+        match result {
+            Ok(data) => Ok(SimulationOutcome::Success(data)),
+            Err(e) => {
+                let reason = e.revert_data()
+                    .and_then(|data| decode_revert_reason(&data))
+                    .unwrap_or_else(|| e.to_string());
+                Ok(SimulationOutcome::Reverted(reason))
+            }
         }
-        
-        info!("Transaction successful: {}", tx_hash);
-        
-        Ok(receipt)
     }
     
     /// Get events emitted by a contract
     pub async fn get_events<T: FromEvent>(&self, address: Address, event: &str, from_block: u64) -> Result<Vec<T>, Error> {
         debug!("Getting events: {} from block {}", event, from_block);
         
-        // Get event signature
-        let event_signature = Self::get_event_signature(event)
-            .map_err(|e| Error::EncodingError(e))?;
-        
-        // Create filter
-        let filter = self.provider.new_filter()
-            .address(address)
-            .event_signature(event_signature)
-            .from_block(from_block);
-        
-        // Get logs
-        let logs = filter.logs()
-            .await
-            .map_err(|e| Error::ContractError(format!("Failed to get logs: {}", e)))?;
-        
-        // Parse events
-        let mut events = Vec::new();
-        for log in logs {
-            match T::from_log(log) {
-                Ok(event) => events.push(event),
-                Err(e) => warn!("Failed to parse event: {}", e),
+        let topic = Self::get_event_signature(event).ok().map(|signature| format!("{:?}", signature));
+        let context = self.call_context("get_events", address, topic);
+        instrument_call(self.metrics.as_ref(), self.slow_call_threshold, context, async {
+            // Get event signature
+            let event_signature = Self::get_event_signature(event)
+                .map_err(|e| Error::EncodingError(e))?;
+            
+            // Create filter
+            let filter = self.provider.new_filter()
+                .address(address)
+                .event_signature(event_signature)
+                .from_block(from_block);
+            
+            // Get logs
+            let logs = filter.logs()
+                .await
+                .map_err(|e| Error::ContractError(format!("Failed to get logs: {}", e)))?;
+            
+            // Parse events
+            let mut events = Vec::new();
+            for log in logs {
+                match T::from_log(log) {
+                    Ok(event) => events.push(event),
+                    Err(e) => warn!("Failed to parse event: {}", e),
+                }
             }
-        }
-        
-        Ok(events)
+            
+            Ok(events)
+        }).await
+    }
+    
+    /// Raw logs of one event emitted by `address` in `from_block..=to_block`, in chain order
+    pub async fn get_logs(&self, address: Address, event: &str, from_block: u64, to_block: u64) -> Result<Vec<Log>, Error> {
+        debug!("Getting logs: {} in blocks {}..={}", event, from_block, to_block);
+        
+        let topic = Self::get_event_signature(event).ok().map(|signature| format!("{:?}", signature));
+        let context = self.call_context("get_logs", address, topic);
+        instrument_call(self.metrics.as_ref(), self.slow_call_threshold, context, async {
+            let event_signature = Self::get_event_signature(event)
+                .map_err(|e| Error::EncodingError(e))?;
+            
+            let filter = self.provider.new_filter()
+                .address(address)
+                .event_signature(event_signature)
+                .from_block(from_block)
+                .to_block(to_block);
+            
+            let logs = filter.logs()
+                .await
+                .map_err(|e| Error::ContractError(format!("Failed to get logs: {}", e)))?;
+            
+            Ok(logs.into_iter().map(|log| Log {
+                address: log.address,
+                topics: log.topics,
+                data: log.data,
+                block_number: log.block_number,
+                transaction_hash: log.transaction_hash,
+                log_index: log.log_index,
+            }).collect())
+        }).await
     }
     
     /// Get account balance
     pub async fn get_balance(&self, address: Address) -> Result<U256, Error> {
         debug!("Getting balance for: {}", address);
         
-        let balance = self.provider.get_balance(address, None)
+        let context = self.call_context("get_balance", address, None);
+        instrument_call(self.metrics.as_ref(), self.slow_call_threshold, context, async {
+            self.provider.get_balance(address, None)
+                .await
+                .map_err(|e| Error::ProviderError(format!("Failed to get balance: {}", e)))
+        }).await
+    }
+    
+    /// Get the deployed bytecode at an address; empty for accounts without code
+    pub async fn get_code(&self, address: Address) -> Result<Vec<u8>, Error> {
+        debug!("Getting code for: {}", address);
+
+        let context = self.call_context("get_code", address, None);
+        instrument_call(self.metrics.as_ref(), self.slow_call_threshold, context, async {
+            let result = self.provider.request::<_, String>(
+                "eth_getCode",
+                [format!("{:?}", address), "latest".to_string()]
+            ).await.map_err(|e| Error::ProviderError(format!("Failed to get code: {}", e)))?;
+
+            hex::decode(result.strip_prefix("0x").unwrap_or(&result))
+                .map_err(|e| Error::EncodingError(format!("Failed to decode code: {}", e)))
+        }).await
+    }
+
+    /// Call a view function without decoding the result, e.g. to check that a contract responds
+    pub async fn call_raw(&self, address: Address, function: &str, args: Vec<Token>) -> Result<Vec<u8>, Error> {
+        let context = self.call_context("call_raw", address, Self::selector_label(function));
+        instrument_call(self.metrics.as_ref(), self.slow_call_threshold, context, async {
+            let calldata = Self::encode_function_call(function, args)
+                .map_err(|e| Error::EncodingError(e))?;
+
+            let result = self.provider.call(address, calldata, None)
+                .await
+                .map_err(|e| Error::ContractError(format!("Contract call failed: {}", e)))?;
+
+            Ok(result.to_vec())
+        }).await
+    }
+
+    /// Get the latest block number
+    pub async fn get_block_number(&self) -> Result<u64, Error> {
+        self.provider.get_block_number()
             .await
-            .map_err(|e| Error::ProviderError(format!("Failed to get balance: {}", e)))?;
+            .map_err(|e| Error::ProviderError(format!("Failed to get block number: {}", e)))
+    }
+    
+    /// Timestamp of a block, in seconds since the epoch
+    pub async fn get_block_timestamp(&self, block_number: u64) -> Result<u64, Error> {
+        let block = self.provider.request::<_, serde_json::Value>(
+            "eth_getBlockByNumber",
+            serde_json::json!([format!("0x{:x}", block_number), false])
+        ).await.map_err(|e| Error::ProviderError(format!("Failed to get block {}: {}", block_number, e)))?;
+        
+        block.get("timestamp")
+            .and_then(|timestamp| timestamp.as_str())
+            .and_then(|timestamp| u64::from_str_radix(timestamp.trim_start_matches("0x"), 16).ok())
+            .ok_or_else(|| Error::ProviderError(format!("Block {} has no timestamp", block_number)))
+    }
+    
+    /// Highest block that is Confirmed under the finality policy; event syncs read up to here
+    pub async fn get_confirmed_block_number(&self) -> Result<u64, Error> {
+        let heads = finality::chain_heads(self, &self.finality_policy).await?;
+        Ok(self.finality_policy.confirmed_head(&heads).unwrap_or(0))
+    }
+    
+    /// Recheck a recorded transaction's finality, e.g. to advance it to Finalized or to
+    /// detect that its block was reorged out
+    pub async fn check_finality(&self, tx_hash: H256, recorded: &Finality) -> Result<FinalityCheck, Error> {
+        finality::check_finality(self, self, &self.finality_policy, tx_hash, recorded).await
+    }
+    
+    /// Estimate gas for a contract transaction sent from the client wallet
+    pub async fn estimate_gas(&self, address: Address, function: &str, args: Vec<Token>) -> Result<U256, Error> {
+        debug!("Estimating gas for: {} function: {}", address, function);
         
-        Ok(balance)
+        let calldata = Self::encode_function_call(function, args)
+            .map_err(|e| Error::EncodingError(e))?;
+        
+        self.provider.estimate_gas(
+            self.wallet.address(),
+            address,
+            calldata,
+        ).await.map_err(|e| Error::ProviderError(format!("Failed to estimate gas: {}", e)))
     }
     
     /// Get historical block hash (EIP-2935)
@@ -410,10 +682,61 @@ impl EthereumClient {
         Ok(result)
     }
     
+    /// Transactions sent by this client that have not been seen mined
+    pub async fn list_pending(&self) -> Result<Vec<PendingTransaction>, Error> {
+        self.pending.refresh(self).await?;
+        Ok(self.pending.list_pending())
+    }
+    
+    /// Re-send a pending transaction at the same nonce with its fee raised by `fee_bump_percent`
+    pub async fn speed_up(&self, tx_hash: H256, fee_bump_percent: u32) -> Result<ReplacementOutcome, Error> {
+        self.pending.speed_up(self, tx_hash, fee_bump_percent).await
+    }
+    
+    /// Replace a pending transaction with a zero-value transfer to self at the same nonce
+    pub async fn cancel(&self, tx_hash: H256) -> Result<ReplacementOutcome, Error> {
+        self.pending.cancel(self, tx_hash).await
+    }
+    
     // Helper methods
     
-    /// Wait for transaction receipt
+    /// Next nonce for the wallet, counting transactions still in the mempool
+    async fn next_nonce(&self) -> Result<u64, Error> {
+        self.provider.request::<_, u64>(
+            "eth_getTransactionCount",
+            [format!("{:?}", self.wallet.address()), "pending".to_string()]
+        ).await.map_err(|e| Error::ProviderError(format!("Failed to get nonce: {}", e)))
+    }
+    
+    /// Current network gas price
+    async fn gas_price(&self) -> Result<U256, Error> {
+        self.provider.request::<_, U256>("eth_gasPrice", Vec::<String>::new())
+            .await
+            .map_err(|e| Error::ProviderError(format!("Failed to get gas price: {}", e)))
+    }
+    
+    /// Wait for transaction receipt, then until the block is Confirmed under the finality policy
     async fn wait_for_transaction_receipt(&self, tx_hash: H256) -> Result<TransactionReceipt, Error> {
+        let receipt = self.fetch_transaction_receipt(tx_hash).await?;
+        self.wait_for_confirmation(receipt).await
+    }
+    
+    async fn wait_for_confirmation(&self, receipt: TransactionReceipt) -> Result<TransactionReceipt, Error> {
+        let (receipt, status) = finality::wait_for_confirmation(
+            self,
+            self,
+            &self.finality_policy,
+            receipt,
+            CONFIRMATION_POLL_INTERVAL,
+        ).await?;
+        debug!("Transaction {} is {:?} in block {}", receipt.transaction_hash, status, receipt.block_number);
+        Ok(receipt)
+    }
+    
+    /// Receipt as soon as the provider returns it, without waiting for confirmations.
+    ///
+    /// Blocks until the provider returns the receipt; see `TransactionBackend::receipt` for polling.
+    async fn fetch_transaction_receipt(&self, tx_hash: H256) -> Result<TransactionReceipt, Error> {
         let receipt = self.provider.get_transaction_receipt(tx_hash)
             .await
             .map_err(|e| Error::TransactionError(format!("Failed to get transaction receipt: {}", e)))?;
@@ -437,7 +760,7 @@ impl EthereumClient {
     }
     
     /// Encode function call with selector and arguments
-    fn encode_function_call(function: &str, args: Vec<Token>) -> Result<Vec<u8>, String> {
+    pub fn encode_function_call(function: &str, args: Vec<Token>) -> Result<Vec<u8>, String> {
         // Calculate function selector
         let selector = Self::get_function_selector(function)
             .map_err(|e| format!("Failed to get function selector: {}", e))?;
@@ -474,6 +797,107 @@ impl EthereumClient {
     }
 }
 
+/// Receipt lookups give up after this long so a pending transaction reads as `None`
+const RECEIPT_POLL_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Time between head lookups while waiting for confirmations
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[async_trait::async_trait]
+impl TransactionBackend for EthereumClient {
+    fn sender(&self) -> Address {
+        self.wallet.address()
+    }
+    
+    async fn broadcast(&self, params: &TransactionParams) -> Result<H256, Error> {
+        let tx_request = self.wallet.sign_transaction(
+            params.data.clone(),
+            params.to,
+            self.chain_id,
+            Some(params.nonce),
+            Some(params.value),
+            params.gas_limit,
+            Some(params.gas_price),
+        ).map_err(|e| Error::TransactionError(format!("Failed to sign transaction: {}", e)))?;
+        
+        self.provider.send_raw_transaction(tx_request)
+            .await
+            .map_err(|e| Error::TransactionError(format!("Failed to send transaction: {}", e)))
+    }
+    
+    async fn receipt(&self, hash: H256) -> Result<Option<TransactionReceipt>, Error> {
+        match tokio::time::timeout(RECEIPT_POLL_TIMEOUT, self.fetch_transaction_receipt(hash)).await {
+            Ok(receipt) => receipt.map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+    
+    async fn mined_nonce(&self) -> Result<u64, Error> {
+        self.provider.request::<_, u64>(
+            "eth_getTransactionCount",
+            [format!("{:?}", self.wallet.address()), "latest".to_string()]
+        ).await.map_err(|e| Error::ProviderError(format!("Failed to get nonce: {}", e)))
+    }
+}
+
+#[async_trait::async_trait]
+impl ChainHeadSource for EthereumClient {
+    async fn latest_block(&self) -> Result<u64, Error> {
+        self.get_block_number().await
+    }
+    
+    async fn tagged_block(&self, tag: BlockTag) -> Result<u64, Error> {
+        let block = self.provider.request::<_, serde_json::Value>(
+            "eth_getBlockByNumber",
+            serde_json::json!([tag.as_str(), false])
+        ).await.map_err(|e| Error::ProviderError(format!("Failed to get {} block: {}", tag.as_str(), e)))?;
+        
+        block.get("number")
+            .and_then(|number| number.as_str())
+            .and_then(|number| u64::from_str_radix(number.trim_start_matches("0x"), 16).ok())
+            .ok_or_else(|| Error::ProviderError(format!("Node does not report a {} block", tag.as_str())))
+    }
+}
+
+/// Decode a Solidity revert payload into a human readable reason
+///
+/// Supports `Error(string)` and `Panic(uint256)` payloads. Returns `None` for custom
+/// errors or malformed data.
+pub fn decode_revert_reason(data: &[u8]) -> Option<String> {
+    const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+    const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+    
+    if data.len() < 4 {
+        return None;
+    }
+    
+    let (selector, payload) = data.split_at(4);
+    
+    if selector == ERROR_SELECTOR {
+        // ABI layout: offset (32 bytes), length (32 bytes), utf-8 bytes
+        if payload.len() < 64 {
+            return None;
+        }
+        let length = U256::from_be_slice(&payload[32..64]);
+        let length = usize::try_from(length).ok()?;
+        let end = 64usize.checked_add(length)?;
+        if payload.len() < end {
+            return None;
+        }
+        return String::from_utf8(payload[64..end].to_vec()).ok();
+    }
+    
+    if selector == PANIC_SELECTOR {
+        if payload.len() < 32 {
+            return None;
+        }
+        let code = U256::from_be_slice(&payload[0..32]);
+        return Some(format!("Panic(0x{:x})", code));
+    }
+    
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -481,13 +905,41 @@ mod tests {
     #[tokio::test]
     async fn test_new_client() {
         // This is a basic test to ensure the struct can be created
+        std::env::set_var("TEST_SIGNER_KEY", "0x0000000000000000000000000000000000000000000000000000000000000001");
         let result = EthereumClient::new(
             "http://localhost:8545",
-            "0x0000000000000000000000000000000000000000000000000000000000000001",
+            &SecretRef::new("TEST_SIGNER_KEY"),
+            &quantera_secrets::EnvProvider,
             1,
         ).await;
         
         assert!(result.is_ok());
+        
+        let missing = EthereumClient::new("http://localhost:8545", &SecretRef::new("UNSET_SIGNER_KEY"), &quantera_secrets::EnvProvider, 1).await;
+        assert!(matches!(missing, Err(Error::WalletError(_))));
+    }
+    
+    #[test]
+    fn test_decode_revert_reason_error_string() {
+        let reason = "Treasury already matured";
+        let mut data = vec![0x08, 0xc3, 0x79, 0xa0];
+        data.extend_from_slice(&U256::from(32).to_be_bytes::<32>());
+        data.extend_from_slice(&U256::from(reason.len()).to_be_bytes::<32>());
+        let mut padded = reason.as_bytes().to_vec();
+        padded.resize(32, 0);
+        data.extend_from_slice(&padded);
+        
+        assert_eq!(decode_revert_reason(&data), Some(reason.to_string()));
+    }
+    
+    #[test]
+    fn test_decode_revert_reason_panic_and_garbage() {
+        let mut data = vec![0x4e, 0x48, 0x7b, 0x71];
+        data.extend_from_slice(&U256::from(0x11).to_be_bytes::<32>());
+        assert_eq!(decode_revert_reason(&data), Some("Panic(0x11)".to_string()));
+        
+        assert_eq!(decode_revert_reason(&[0xde, 0xad]), None);
+        assert_eq!(decode_revert_reason(&[0xde, 0xad, 0xbe, 0xef, 0x00]), None);
     }
     
     // More comprehensive tests would require a local Ethereum node
@@ -0,0 +1,458 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use alloy_primitives::{Address, H256, U256};
+use async_trait::async_trait;
+use tracing::{info, warn};
+use crate::{Error, TransactionReceipt};
+
+/// Nodes reject same-nonce replacements that raise the fee by less than this
+pub const MIN_FEE_BUMP_PERCENT: u32 = 10;
+
+/// Gas limit of a plain value transfer, used for cancellations
+const TRANSFER_GAS_LIMIT: u64 = 21_000;
+
+/// Everything needed to sign a transaction at a fixed nonce
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionParams {
+    pub to: Option<Address>,
+    pub data: Vec<u8>,
+    pub value: U256,
+    pub nonce: u64,
+    /// Estimated by the provider when unset
+    pub gas_limit: Option<U256>,
+    pub gas_price: U256,
+}
+
+/// Why a transaction was sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PendingKind {
+    Original,
+    SpeedUp,
+    Cancel,
+}
+
+/// A broadcast transaction that has not been seen mined
+#[derive(Debug, Clone)]
+pub struct PendingTransaction {
+    pub hash: H256,
+    pub params: TransactionParams,
+    pub kind: PendingKind,
+    pub submitted_at: SystemTime,
+}
+
+/// Signing, broadcasting and receipt lookup for the tracker.
+///
+/// EthereumClient is the production implementation; tests can substitute a mock provider.
+#[async_trait]
+pub trait TransactionBackend: Send + Sync {
+    /// Address transactions are sent from
+    fn sender(&self) -> Address;
+
+    /// Sign and broadcast at the given nonce, returning the transaction hash
+    async fn broadcast(&self, params: &TransactionParams) -> Result<H256, Error>;
+
+    /// Receipt of a mined transaction, or `None` while it is pending
+    async fn receipt(&self, hash: H256) -> Result<Option<TransactionReceipt>, Error>;
+
+    /// Number of the sender's transactions mined so far, i.e. the next unmined nonce
+    async fn mined_nonce(&self) -> Result<u64, Error>;
+}
+
+/// Result of a speed-up or cancel
+#[derive(Debug, Clone)]
+pub enum ReplacementOutcome {
+    /// The replacement was broadcast; whichever transaction at the nonce mines first wins
+    Replaced {
+        original: H256,
+        replacement: H256,
+        gas_price: U256,
+    },
+    /// A transaction at this nonce mined before the replacement could be sent.
+    /// The receipt is missing when the nonce advanced but the receipt is not yet visible.
+    AlreadyMined {
+        nonce: u64,
+        receipt: Option<TransactionReceipt>,
+    },
+}
+
+/// When to replace a stalled transaction automatically
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StallPolicy {
+    /// Time since the last broadcast at a nonce before it is sped up
+    pub stall_timeout: Duration,
+    pub fee_bump_percent: u32,
+    /// Speed-ups per transaction before waiting without further replacement
+    pub max_replacements: u32,
+    pub poll_interval: Duration,
+}
+
+impl Default for StallPolicy {
+    fn default() -> Self {
+        Self {
+            stall_timeout: Duration::from_secs(180),
+            fee_bump_percent: 20,
+            max_replacements: 3,
+            poll_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Records submitted transactions by hash so stalled ones can be sped up or cancelled.
+///
+/// Transactions sharing a nonce form one group: the original and its replacements. A group
+/// is forgotten as soon as any of its transactions is seen mined.
+#[derive(Debug, Default)]
+pub struct PendingTransactionTracker {
+    pending: Mutex<HashMap<H256, PendingTransaction>>,
+}
+
+impl PendingTransactionTracker {
+    pub fn record(&self, hash: H256, params: TransactionParams, kind: PendingKind) {
+        self.pending.lock().unwrap().insert(hash, PendingTransaction {
+            hash,
+            params,
+            kind,
+            submitted_at: SystemTime::now(),
+        });
+    }
+
+    /// Tracked transactions by nonce, oldest submission first
+    pub fn list_pending(&self) -> Vec<PendingTransaction> {
+        let mut pending: Vec<_> = self.pending.lock().unwrap().values().cloned().collect();
+        pending.sort_by_key(|tx| (tx.params.nonce, tx.submitted_at));
+        pending
+    }
+
+    pub fn get(&self, hash: H256) -> Option<PendingTransaction> {
+        self.pending.lock().unwrap().get(&hash).cloned()
+    }
+
+    /// Hashes sharing a nonce, oldest first
+    fn group(&self, nonce: u64) -> Vec<PendingTransaction> {
+        self.list_pending().into_iter().filter(|tx| tx.params.nonce == nonce).collect()
+    }
+
+    fn forget_nonce(&self, nonce: u64) {
+        self.pending.lock().unwrap().retain(|_, tx| tx.params.nonce != nonce);
+    }
+
+    /// Receipt of whichever transaction at `hash`'s nonce has mined, forgetting the group
+    pub async fn confirm(&self, backend: &dyn TransactionBackend, hash: H256) -> Result<Option<TransactionReceipt>, Error> {
+        let Some(tracked) = self.get(hash) else {
+            return backend.receipt(hash).await;
+        };
+
+        for tx in self.group(tracked.params.nonce) {
+            if let Some(receipt) = backend.receipt(tx.hash).await? {
+                self.forget_nonce(tracked.params.nonce);
+                return Ok(Some(receipt));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Drop groups whose nonce has been mined
+    pub async fn refresh(&self, backend: &dyn TransactionBackend) -> Result<(), Error> {
+        let mined_nonce = backend.mined_nonce().await?;
+        self.pending.lock().unwrap().retain(|_, tx| tx.params.nonce >= mined_nonce);
+        Ok(())
+    }
+
+    /// Re-sign `hash` at the same nonce with the fee raised by `fee_bump_percent`
+    pub async fn speed_up(
+        &self,
+        backend: &dyn TransactionBackend,
+        hash: H256,
+        fee_bump_percent: u32,
+    ) -> Result<ReplacementOutcome, Error> {
+        if fee_bump_percent < MIN_FEE_BUMP_PERCENT {
+            return Err(Error::TransactionError(format!(
+                "Fee bump of {}% is below the {}% nodes accept for replacements",
+                fee_bump_percent, MIN_FEE_BUMP_PERCENT
+            )));
+        }
+
+        let tracked = self.get(hash)
+            .ok_or_else(|| Error::InvalidState(format!("Transaction {} is not pending", hash)))?;
+        let params = TransactionParams {
+            gas_price: self.bumped_gas_price(tracked.params.nonce, fee_bump_percent),
+            ..tracked.params.clone()
+        };
+
+        self.replace(backend, tracked, params, PendingKind::SpeedUp).await
+    }
+
+    /// Replace `hash` with a zero-value transfer to the sender at the same nonce
+    pub async fn cancel(&self, backend: &dyn TransactionBackend, hash: H256) -> Result<ReplacementOutcome, Error> {
+        let tracked = self.get(hash)
+            .ok_or_else(|| Error::InvalidState(format!("Transaction {} is not pending", hash)))?;
+        let params = TransactionParams {
+            to: Some(backend.sender()),
+            data: Vec::new(),
+            value: U256::ZERO,
+            nonce: tracked.params.nonce,
+            gas_limit: Some(U256::from(TRANSFER_GAS_LIMIT)),
+            gas_price: self.bumped_gas_price(tracked.params.nonce, MIN_FEE_BUMP_PERCENT),
+        };
+
+        self.replace(backend, tracked, params, PendingKind::Cancel).await
+    }
+
+    /// Bump over the highest fee already offered at the nonce, so the replacement outbids every sibling
+    fn bumped_gas_price(&self, nonce: u64, fee_bump_percent: u32) -> U256 {
+        let highest = self.group(nonce).iter().map(|tx| tx.params.gas_price).max().unwrap_or_default();
+        let bumped = highest * U256::from(100 + fee_bump_percent) / U256::from(100);
+        bumped.max(highest + U256::from(1))
+    }
+
+    async fn replace(
+        &self,
+        backend: &dyn TransactionBackend,
+        original: PendingTransaction,
+        params: TransactionParams,
+        kind: PendingKind,
+    ) -> Result<ReplacementOutcome, Error> {
+        let nonce = original.params.nonce;
+
+        // The original (or an earlier replacement) may have mined while it looked stalled
+        if let Some(outcome) = self.mined_outcome(backend, original.hash, nonce).await? {
+            return Ok(outcome);
+        }
+
+        match backend.broadcast(&params).await {
+            Ok(replacement) => {
+                info!("Replaced transaction {} with {} ({:?}) at nonce {}", original.hash, replacement, kind, nonce);
+                let gas_price = params.gas_price;
+                self.record(replacement, params, kind);
+                Ok(ReplacementOutcome::Replaced { original: original.hash, replacement, gas_price })
+            }
+            Err(e) => {
+                // A nonce that mined between the check and the broadcast fails as "nonce too low"
+                if let Some(outcome) = self.mined_outcome(backend, original.hash, nonce).await? {
+                    return Ok(outcome);
+                }
+                warn!("Replacement of {} at nonce {} failed: {}", original.hash, nonce, e);
+                Err(e)
+            }
+        }
+    }
+
+    async fn mined_outcome(
+        &self,
+        backend: &dyn TransactionBackend,
+        hash: H256,
+        nonce: u64,
+    ) -> Result<Option<ReplacementOutcome>, Error> {
+        if let Some(receipt) = self.confirm(backend, hash).await? {
+            return Ok(Some(ReplacementOutcome::AlreadyMined { nonce, receipt: Some(receipt) }));
+        }
+        if backend.mined_nonce().await? > nonce {
+            self.forget_nonce(nonce);
+            return Ok(Some(ReplacementOutcome::AlreadyMined { nonce, receipt: None }));
+        }
+        Ok(None)
+    }
+
+    /// Wait for `hash` or one of its replacements to mine.
+    ///
+    /// With a stall policy, the latest transaction at the nonce is sped up each time it goes
+    /// `stall_timeout` without mining, up to `max_replacements` times.
+    pub async fn wait(
+        &self,
+        backend: &dyn TransactionBackend,
+        hash: H256,
+        policy: Option<StallPolicy>,
+    ) -> Result<TransactionReceipt, Error> {
+        let poll_interval = policy.map(|p| p.poll_interval).unwrap_or(StallPolicy::default().poll_interval);
+        let nonce = self.get(hash).map(|tx| tx.params.nonce);
+        let mut latest = hash;
+        let mut replacements = 0;
+        let mut last_broadcast = Instant::now();
+
+        loop {
+            if let Some(receipt) = self.confirm(backend, latest).await? {
+                return Ok(receipt);
+            }
+
+            if let Some(policy) = policy {
+                if last_broadcast.elapsed() >= policy.stall_timeout && replacements < policy.max_replacements {
+                    warn!("Transaction {} stalled for {:?}; speeding up", latest, policy.stall_timeout);
+                    match self.speed_up(backend, latest, policy.fee_bump_percent).await? {
+                        ReplacementOutcome::Replaced { replacement, .. } => {
+                            latest = replacement;
+                            replacements += 1;
+                            last_broadcast = Instant::now();
+                        }
+                        ReplacementOutcome::AlreadyMined { receipt: Some(receipt), .. } => return Ok(receipt),
+                        ReplacementOutcome::AlreadyMined { receipt: None, nonce } => {
+                            return Err(Error::TransactionError(format!(
+                                "Nonce {} mined but no tracked transaction has a receipt; an untracked transaction may have used it",
+                                nonce
+                            )));
+                        }
+                    }
+                }
+            } else if let Some(nonce) = nonce {
+                if backend.mined_nonce().await? > nonce && self.confirm(backend, latest).await?.is_none() {
+                    self.forget_nonce(nonce);
+                    return Err(Error::TransactionError(format!("Nonce {} was used by another transaction", nonce)));
+                }
+            }
+
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Provider that mines nothing until told to, then mines a chosen hash
+    #[derive(Default)]
+    struct MockProvider {
+        broadcasts: Mutex<Vec<TransactionParams>>,
+        mined: Mutex<Option<H256>>,
+        mined_nonce: Mutex<u64>,
+        /// Mine each replacement as soon as it is broadcast
+        mine_replacements: bool,
+    }
+
+    impl MockProvider {
+        fn mine(&self, hash: H256, nonce: u64) {
+            *self.mined.lock().unwrap() = Some(hash);
+            *self.mined_nonce.lock().unwrap() = nonce + 1;
+        }
+    }
+
+    #[async_trait]
+    impl TransactionBackend for MockProvider {
+        fn sender(&self) -> Address {
+            Address::repeat_byte(0xaa)
+        }
+
+        async fn broadcast(&self, params: &TransactionParams) -> Result<H256, Error> {
+            if *self.mined_nonce.lock().unwrap() > params.nonce {
+                return Err(Error::TransactionError("nonce too low".into()));
+            }
+            let mut broadcasts = self.broadcasts.lock().unwrap();
+            broadcasts.push(params.clone());
+            let hash = H256::repeat_byte(broadcasts.len() as u8);
+            drop(broadcasts);
+
+            if self.mine_replacements {
+                self.mine(hash, params.nonce);
+            }
+            Ok(hash)
+        }
+
+        async fn receipt(&self, hash: H256) -> Result<Option<TransactionReceipt>, Error> {
+            Ok((*self.mined.lock().unwrap() == Some(hash)).then(|| receipt(hash)))
+        }
+
+        async fn mined_nonce(&self) -> Result<u64, Error> {
+            Ok(*self.mined_nonce.lock().unwrap())
+        }
+    }
+
+    fn receipt(hash: H256) -> TransactionReceipt {
+        TransactionReceipt {
+            transaction_hash: hash,
+            block_number: 100,
+            block_hash: H256::repeat_byte(0xbb),
+            contract_address: None,
+            gas_used: U256::from(50_000u64),
+            status: true,
+            logs: Vec::new(),
+        }
+    }
+
+    fn params(nonce: u64) -> TransactionParams {
+        TransactionParams {
+            to: Some(Address::repeat_byte(0x01)),
+            data: vec![0xde, 0xad],
+            value: U256::ZERO,
+            nonce,
+            gas_limit: None,
+            gas_price: U256::from(1_000u64),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stalled_transaction_is_sped_up_and_replacement_mines() {
+        let provider = MockProvider { mine_replacements: true, ..Default::default() };
+        let tracker = PendingTransactionTracker::default();
+        let original = H256::repeat_byte(0xf0);
+        tracker.record(original, params(7), PendingKind::Original);
+
+        let policy = StallPolicy {
+            stall_timeout: Duration::from_millis(20),
+            fee_bump_percent: 25,
+            max_replacements: 2,
+            poll_interval: Duration::from_millis(5),
+        };
+        let receipt = tracker.wait(&provider, original, Some(policy)).await.unwrap();
+
+        let broadcasts = provider.broadcasts.lock().unwrap().clone();
+        assert_eq!(broadcasts.len(), 1);
+        assert_eq!(broadcasts[0].nonce, 7);
+        assert_eq!(broadcasts[0].data, vec![0xde, 0xad]);
+        assert_eq!(broadcasts[0].gas_price, U256::from(1_250u64));
+        assert_eq!(receipt.transaction_hash, H256::repeat_byte(1));
+        assert!(tracker.list_pending().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_replacement_race_reports_original_mined() {
+        let provider = MockProvider::default();
+        let tracker = PendingTransactionTracker::default();
+        let original = H256::repeat_byte(0xf0);
+
+        // The original's receipt is visible: nothing is sent
+        tracker.record(original, params(3), PendingKind::Original);
+        provider.mine(original, 3);
+        match tracker.cancel(&provider, original).await.unwrap() {
+            ReplacementOutcome::AlreadyMined { nonce, receipt } => {
+                assert_eq!(nonce, 3);
+                assert_eq!(receipt.unwrap().transaction_hash, original);
+            }
+            other => panic!("expected AlreadyMined, got {:?}", other),
+        }
+        assert!(provider.broadcasts.lock().unwrap().is_empty());
+        assert!(tracker.list_pending().is_empty());
+
+        // The nonce advanced but the receipt is not visible yet
+        tracker.record(original, params(4), PendingKind::Original);
+        *provider.mined.lock().unwrap() = None;
+        *provider.mined_nonce.lock().unwrap() = 5;
+        assert!(matches!(
+            tracker.speed_up(&provider, original, 20).await.unwrap(),
+            ReplacementOutcome::AlreadyMined { nonce: 4, receipt: None }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_outbids_earlier_speed_up() {
+        let provider = MockProvider::default();
+        let tracker = PendingTransactionTracker::default();
+        let original = H256::repeat_byte(0xf0);
+        tracker.record(original, params(9), PendingKind::Original);
+
+        assert!(tracker.speed_up(&provider, original, 5).await.is_err());
+
+        let ReplacementOutcome::Replaced { replacement: sped_up, gas_price, .. } =
+            tracker.speed_up(&provider, original, 50).await.unwrap() else { panic!("expected replacement") };
+        assert_eq!(gas_price, U256::from(1_500u64));
+
+        let ReplacementOutcome::Replaced { gas_price, .. } = tracker.cancel(&provider, sped_up).await.unwrap()
+            else { panic!("expected replacement") };
+        assert_eq!(gas_price, U256::from(1_650u64));
+
+        let cancel = provider.broadcasts.lock().unwrap()[1].clone();
+        assert_eq!(cancel.to, Some(provider.sender()));
+        assert!(cancel.data.is_empty());
+        assert_eq!(cancel.value, U256::ZERO);
+        assert_eq!(tracker.list_pending().len(), 3);
+    }
+}
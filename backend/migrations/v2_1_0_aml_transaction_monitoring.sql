@@ -0,0 +1,54 @@
+-- Quantera v2.1.0 AML Transaction Monitoring
+-- Rule-based detection over completed portfolio transactions, with alerts worked by compliance officers
+
+ALTER TABLE investor_profiles
+    ADD COLUMN IF NOT EXISTS aml_status VARCHAR(20) NOT NULL DEFAULT 'Clear';
+
+CREATE TABLE IF NOT EXISTS aml_monitoring_runs (
+    id BIGSERIAL PRIMARY KEY,
+    run_id UUID NOT NULL UNIQUE,
+    run_at TIMESTAMPTZ NOT NULL,
+    window_from TIMESTAMPTZ NOT NULL,
+    investors_scanned INTEGER NOT NULL,
+    evaluations INTEGER NOT NULL,
+    alerts_created INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS aml_alerts (
+    id BIGSERIAL PRIMARY KEY,
+    alert_id UUID NOT NULL UNIQUE,
+    investor_address BYTEA NOT NULL,
+    jurisdiction VARCHAR(10) NOT NULL,
+    rule VARCHAR(40) NOT NULL,
+    severity VARCHAR(20) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'open' CHECK (status IN ('open', 'dismissed', 'confirmed')),
+    description TEXT NOT NULL,
+    transaction_ids UUID[] NOT NULL,
+    total_amount NUMERIC(38, 8) NOT NULL,
+    window_start TIMESTAMPTZ NOT NULL,
+    window_end TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_by VARCHAR(255),
+    resolution_notes TEXT,
+    resolved_at TIMESTAMPTZ
+);
+
+-- At most one open case per investor and rule; repeat hits while it is open are not duplicated
+CREATE UNIQUE INDEX IF NOT EXISTS idx_aml_alerts_open
+    ON aml_alerts(investor_address, rule) WHERE status = 'open';
+CREATE INDEX IF NOT EXISTS idx_aml_alerts_status ON aml_alerts(status, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS aml_rule_evaluations (
+    id BIGSERIAL PRIMARY KEY,
+    run_id UUID NOT NULL,
+    investor_address BYTEA NOT NULL,
+    jurisdiction VARCHAR(10) NOT NULL,
+    rule VARCHAR(40) NOT NULL,
+    window_start TIMESTAMPTZ NOT NULL,
+    window_end TIMESTAMPTZ NOT NULL,
+    transactions_scanned INTEGER NOT NULL,
+    triggered BOOLEAN NOT NULL,
+    alert_id UUID REFERENCES aml_alerts(alert_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_aml_rule_evaluations_investor ON aml_rule_evaluations(investor_address, window_end DESC);
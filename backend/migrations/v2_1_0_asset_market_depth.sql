@@ -0,0 +1,19 @@
+-- Quantera v2.1.0 Asset Market Depth
+-- Traded volume and order book depth per asset, written by the market data ingestion job and
+-- read by liquidity scoring when LIQUIDITY_SOURCE=database
+
+CREATE TABLE IF NOT EXISTS asset_market_depth (
+    asset_address VARCHAR(42) NOT NULL,
+    observed_at TIMESTAMPTZ NOT NULL,
+    -- Units traded over the 24 hours before observed_at
+    daily_volume NUMERIC(38, 18) NOT NULL CHECK (daily_volume >= 0),
+    -- Units the book absorbs within 2% of mid, below and above
+    bid_depth NUMERIC(38, 18) NOT NULL CHECK (bid_depth >= 0),
+    ask_depth NUMERIC(38, 18) NOT NULL CHECK (ask_depth >= 0),
+    -- Venue or feed the observation came from
+    source VARCHAR(50) NOT NULL,
+    PRIMARY KEY (asset_address, observed_at, source)
+);
+
+CREATE INDEX IF NOT EXISTS idx_asset_market_depth_latest
+    ON asset_market_depth (asset_address, observed_at DESC);
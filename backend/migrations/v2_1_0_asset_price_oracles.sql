@@ -0,0 +1,19 @@
+-- Quantera v2.1.0 Asset Price Oracles
+-- Aggregated oracle prices with confidence, and a log of disputed aggregations
+
+ALTER TABLE asset_price_history ADD COLUMN IF NOT EXISTS confidence NUMERIC(5, 4)
+    CHECK (confidence >= 0 AND confidence <= 1);
+ALTER TABLE asset_price_history ADD COLUMN IF NOT EXISTS source_names TEXT[];
+
+-- Aggregations where sources disagreed; these prices are never written to the history
+CREATE TABLE IF NOT EXISTS asset_price_disputes (
+    id BIGSERIAL PRIMARY KEY,
+    asset_address VARCHAR(66) NOT NULL,
+    reference_price NUMERIC(38, 18) NOT NULL,
+    quotes JSONB NOT NULL,
+    failures JSONB NOT NULL DEFAULT '[]',
+    observed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_asset_price_disputes_asset
+    ON asset_price_disputes(asset_address, observed_at DESC);
@@ -0,0 +1,25 @@
+-- Quantera v2.1.0 Audit Log Pagination
+-- Persist secure API audit entries and support keyset pagination with server-side filters
+
+ALTER TABLE audit_log
+    ADD COLUMN IF NOT EXISTS actor VARCHAR(255),
+    ADD COLUMN IF NOT EXISTS resource VARCHAR(255);
+
+-- Keyset order: created_at DESC, id DESC, within a tenant or across all tenants
+CREATE INDEX IF NOT EXISTS idx_audit_log_tenant_keyset ON audit_log(tenant_id, created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_audit_log_keyset ON audit_log(created_at DESC, id DESC);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_actor ON audit_log(actor, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_audit_log_resource_prefix ON audit_log(resource text_pattern_ops);
+
+-- Entries past AUDIT_LOG_RETENTION_DAYS (default 365) are never returned
+CREATE OR REPLACE FUNCTION purge_audit_log(retention_days INTEGER DEFAULT 365)
+RETURNS BIGINT AS $$
+DECLARE
+    deleted BIGINT;
+BEGIN
+    DELETE FROM audit_log WHERE created_at < NOW() - make_interval(days => retention_days);
+    GET DIAGNOSTICS deleted = ROW_COUNT;
+    RETURN deleted;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,38 @@
+-- Quantera v2.1.0 Auth Challenge Hardening
+-- Bind challenges to the requesting client and clean up used challenges
+
+ALTER TABLE auth_challenges
+    ADD COLUMN IF NOT EXISTS fingerprint VARCHAR(64),
+    ADD COLUMN IF NOT EXISTS client_ip VARCHAR(64);
+
+-- Outstanding-challenge caps count unexpired, unused rows per wallet and per IP
+CREATE INDEX IF NOT EXISTS idx_auth_challenges_outstanding_wallet
+    ON auth_challenges(wallet_address, expires_at) WHERE used = false;
+CREATE INDEX IF NOT EXISTS idx_auth_challenges_outstanding_ip
+    ON auth_challenges(client_ip, expires_at) WHERE used = false;
+
+CREATE OR REPLACE FUNCTION cleanup_expired_auth_data()
+RETURNS TABLE (
+    challenges_deleted INTEGER,
+    sessions_deleted INTEGER
+)
+LANGUAGE plpgsql
+AS $$
+DECLARE
+    v_challenges_deleted INTEGER;
+    v_sessions_deleted INTEGER;
+BEGIN
+    -- Used challenges can never be replayed, expired ones never verified
+    DELETE FROM auth_challenges
+    WHERE used = TRUE OR expires_at < NOW();
+    GET DIAGNOSTICS v_challenges_deleted = ROW_COUNT;
+
+    -- Delete revoked or expired sessions older than 24 hours
+    DELETE FROM auth_sessions
+    WHERE (is_revoked = TRUE OR expires_at < NOW())
+    AND created_at < NOW() - INTERVAL '24 hours';
+    GET DIAGNOSTICS v_sessions_deleted = ROW_COUNT;
+
+    RETURN QUERY SELECT v_challenges_deleted, v_sessions_deleted;
+END;
+$$;
@@ -0,0 +1,12 @@
+-- Quantera v2.1.0 Auth Session Devices
+-- Record the device behind each session and support per-user concurrent session caps
+
+ALTER TABLE auth_sessions
+    ADD COLUMN IF NOT EXISTS user_agent_hash VARCHAR(64),
+    ADD COLUMN IF NOT EXISTS ip_address VARCHAR(64),
+    ADD COLUMN IF NOT EXISTS device_label VARCHAR(128),
+    ADD COLUMN IF NOT EXISTS revoked_at TIMESTAMPTZ;
+
+-- Active-session listing and cap enforcement read a user's unrevoked sessions by age
+CREATE INDEX IF NOT EXISTS idx_auth_sessions_user_active
+    ON auth_sessions(user_id, created_at DESC) WHERE is_revoked = false;
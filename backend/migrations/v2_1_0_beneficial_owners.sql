@@ -0,0 +1,20 @@
+-- Quantera v2.1.0 Beneficial Owners
+-- Ultimate beneficial owners of institutional investors and their individual sanctions and PEP screening
+
+CREATE TABLE IF NOT EXISTS beneficial_owners (
+    institution_address BYTEA NOT NULL REFERENCES investor_profiles(address),
+    -- Lowercased name and date of birth; identifies the person across register updates
+    owner_key VARCHAR(300) NOT NULL,
+    full_name VARCHAR(255) NOT NULL,
+    date_of_birth DATE NOT NULL,
+    nationality CHAR(2) NOT NULL,
+    ownership_percent NUMERIC(7, 4) NOT NULL CHECK (ownership_percent > 0 AND ownership_percent <= 100),
+    status VARCHAR(20) NOT NULL CHECK (status IN ('clear', 'pep', 'sanctioned')),
+    sanctions JSONB NOT NULL,
+    pep_details TEXT,
+    screened_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (institution_address, owner_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_beneficial_owners_status ON beneficial_owners(status) WHERE status <> 'clear';
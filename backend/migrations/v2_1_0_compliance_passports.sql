@@ -0,0 +1,16 @@
+-- Quantera v2.1.0 Compliance Passports
+-- Issued passports and their revocation state
+
+CREATE TABLE IF NOT EXISTS compliance_passports (
+    id BIGSERIAL PRIMARY KEY,
+    passport_id UUID NOT NULL UNIQUE,
+    investor_address BYTEA NOT NULL,
+    issued_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    signature TEXT NOT NULL,
+    revoked_at TIMESTAMPTZ,
+    revocation_reason TEXT
+);
+
+CREATE INDEX idx_compliance_passports_investor ON compliance_passports(investor_address);
+CREATE INDEX idx_compliance_passports_revoked_at ON compliance_passports(revoked_at) WHERE revoked_at IS NOT NULL;
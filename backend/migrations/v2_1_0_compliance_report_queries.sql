@@ -0,0 +1,19 @@
+-- Quantera v2.1.0 Compliance Report Queries
+-- Indexes behind the filtered, cursor-paginated compliance reports listing
+
+-- Keyset pages: (generated_at, id) and (amount, id) in either direction, per tenant
+CREATE INDEX IF NOT EXISTS idx_compliance_reports_tenant_generated
+    ON compliance_reports(tenant_id, generated_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_compliance_reports_tenant_amount
+    ON compliance_reports(tenant_id, (COALESCE(amount, 0)) DESC, id DESC);
+
+-- An investor's own reports, newest first
+CREATE INDEX IF NOT EXISTS idx_compliance_reports_investor_generated
+    ON compliance_reports(investor_address, generated_at DESC, id DESC);
+
+CREATE INDEX IF NOT EXISTS idx_compliance_reports_jurisdiction_generated
+    ON compliance_reports(jurisdiction, generated_at DESC);
+
+-- violation_type filter: violations @> '[{"violation_type": "..."}]'
+CREATE INDEX IF NOT EXISTS idx_compliance_reports_violations
+    ON compliance_reports USING GIN (violations jsonb_path_ops);
@@ -0,0 +1,31 @@
+-- Quantera v2.1.0 Consistency Checks
+-- History of cross-store consistency runs, so drift between Postgres, Redis, the chain and IPFS can be trended
+
+CREATE TABLE IF NOT EXISTS consistency_runs (
+    run_id UUID PRIMARY KEY,
+    trigger VARCHAR(16) NOT NULL CHECK (trigger IN ('schedule', 'admin', 'cli')),
+    started_at TIMESTAMPTZ NOT NULL,
+    finished_at TIMESTAMPTZ NOT NULL,
+    report JSONB NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_consistency_runs_started
+    ON consistency_runs (started_at DESC);
+
+CREATE TABLE IF NOT EXISTS consistency_findings (
+    id BIGSERIAL PRIMARY KEY,
+    run_id UUID NOT NULL REFERENCES consistency_runs(run_id) ON DELETE CASCADE,
+    check_name VARCHAR(64) NOT NULL,
+    code VARCHAR(64) NOT NULL,
+    severity VARCHAR(16) NOT NULL CHECK (severity IN ('info', 'warning', 'critical')),
+    subject TEXT NOT NULL,
+    detail TEXT NOT NULL,
+    found_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_consistency_findings_run
+    ON consistency_findings (run_id);
+
+-- Trend queries count findings per check over time
+CREATE INDEX IF NOT EXISTS idx_consistency_findings_check_time
+    ON consistency_findings (check_name, found_at DESC);
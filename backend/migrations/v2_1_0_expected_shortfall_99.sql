@@ -0,0 +1,4 @@
+-- Quantera v2.1.0 Expected Shortfall at 99%
+-- expected_shortfall holds the 95% shortfall; both are read off the distribution that produced the VaR
+
+ALTER TABLE risk_metrics ADD COLUMN IF NOT EXISTS es_99 NUMERIC(20, 8);
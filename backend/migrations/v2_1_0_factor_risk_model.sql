@@ -0,0 +1,18 @@
+-- Quantera v2.1.0 Factor Risk Model
+-- Per-asset factor loadings and the factor covariance used to decompose portfolio risk
+
+-- Loading of each asset on each factor; assets without rows are treated as idiosyncratic
+CREATE TABLE IF NOT EXISTS factor_exposures (
+    asset_address VARCHAR(42) NOT NULL,
+    factor VARCHAR(50) NOT NULL,
+    loading NUMERIC(38, 18) NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (asset_address, factor)
+);
+
+-- Factor models saved through the admin API; the latest row is the active model
+CREATE TABLE IF NOT EXISTS factor_model_versions (
+    id BIGSERIAL PRIMARY KEY,
+    model JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
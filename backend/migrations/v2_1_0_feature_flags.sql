@@ -0,0 +1,14 @@
+-- Quantera v2.1.0 Feature Flags
+-- Runtime overrides of the configured flags, toggled through the admin API
+
+CREATE TABLE IF NOT EXISTS feature_flag_overrides (
+    id BIGSERIAL PRIMARY KEY,
+    flag VARCHAR(100) NOT NULL,
+    scope_type VARCHAR(10) NOT NULL CHECK (scope_type IN ('global', 'tenant', 'role')),
+    -- Tenant id or role name; empty for global overrides
+    scope_value VARCHAR(100) NOT NULL DEFAULT '',
+    enabled BOOLEAN NOT NULL,
+    updated_by VARCHAR(255) NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (flag, scope_type, scope_value)
+);
@@ -0,0 +1,9 @@
+-- Quantera v2.1.0 Issuer Concentration Limits
+-- Issuer of each holding, so compliance checks can count exposure per issuer; unknown for existing rows
+
+ALTER TABLE portfolio_holdings
+    ADD COLUMN IF NOT EXISTS issuer VARCHAR(255);
+
+CREATE INDEX IF NOT EXISTS idx_portfolio_holdings_wallet_issuer
+    ON portfolio_holdings (wallet_address, issuer)
+    WHERE issuer IS NOT NULL;
@@ -0,0 +1,31 @@
+-- Quantera v2.1.0 Identity Registry Sync
+-- Mirrors approved investor profiles into the on-chain ERC-3643 identity registry
+
+CREATE TABLE IF NOT EXISTS identity_registry_state (
+    investor_address BYTEA PRIMARY KEY,
+    -- keccak256(wallet, country, claims_hash) last written or observed on-chain; NULL when not registered
+    state_hash BYTEA,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    last_attempt_at TIMESTAMPTZ,
+    synced_at TIMESTAMPTZ,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_identity_registry_state_failed
+    ON identity_registry_state(last_attempt_at) WHERE attempts > 0;
+
+-- Last registry block scanned for identity events during reconciliation
+CREATE TABLE IF NOT EXISTS identity_registry_cursor (
+    id SMALLINT PRIMARY KEY DEFAULT 1 CHECK (id = 1),
+    last_block BIGINT NOT NULL
+);
+
+-- Offboarded investors; the only route by which a wallet is removed from the registry
+CREATE TABLE IF NOT EXISTS investor_offboarding (
+    investor_address BYTEA PRIMARY KEY,
+    reason TEXT NOT NULL,
+    requested_by VARCHAR(255) NOT NULL,
+    requested_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    registry_removed_at TIMESTAMPTZ
+);
@@ -0,0 +1,58 @@
+-- Quantera v2.1.0 Institution Statements
+-- Position ledger of prime brokerage institutions and the monthly statements built from it
+
+CREATE TABLE IF NOT EXISTS institution_ledger_entries (
+    id BIGSERIAL PRIMARY KEY,
+    institution_id VARCHAR(255) NOT NULL,
+    entry_type VARCHAR(20) NOT NULL CHECK (entry_type IN ('deposit', 'withdrawal', 'buy', 'sell', 'fee', 'interest', 'yield')),
+    asset_id VARCHAR(66),
+    quantity NUMERIC(38, 18) NOT NULL DEFAULT 0 CHECK (quantity >= 0),
+    price NUMERIC(38, 18) NOT NULL DEFAULT 0 CHECK (price >= 0),
+    -- Cash value of the entry; its direction follows from entry_type
+    amount NUMERIC(38, 18) NOT NULL CHECK (amount >= 0),
+    effective_at TIMESTAMPTZ NOT NULL,
+    -- Later than effective_at for late-arriving corrections
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (entry_type NOT IN ('buy', 'sell') OR asset_id IS NOT NULL)
+);
+
+CREATE INDEX IF NOT EXISTS idx_institution_ledger_entries_effective
+    ON institution_ledger_entries(institution_id, effective_at);
+CREATE INDEX IF NOT EXISTS idx_institution_ledger_entries_recorded
+    ON institution_ledger_entries(institution_id, recorded_at);
+
+-- One row per statement version; amendments reference the version they supersede
+CREATE TABLE IF NOT EXISTS institution_statements (
+    id BIGSERIAL PRIMARY KEY,
+    statement_id UUID NOT NULL UNIQUE,
+    institution_id VARCHAR(255) NOT NULL,
+    -- First day of the statement month
+    period DATE NOT NULL CHECK (EXTRACT(DAY FROM period) = 1),
+    version INTEGER NOT NULL CHECK (version >= 1),
+    amends UUID REFERENCES institution_statements(statement_id),
+    -- Ledger entries recorded after this are not reflected
+    ledger_cutoff TIMESTAMPTZ NOT NULL,
+    content_hash VARCHAR(64) NOT NULL,
+    statement JSONB NOT NULL,
+    pdf BYTEA NOT NULL,
+    generated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (institution_id, period, version),
+    CHECK ((version = 1) = (amends IS NULL))
+);
+
+CREATE INDEX IF NOT EXISTS idx_institution_statements_institution
+    ON institution_statements(institution_id, period DESC, version DESC);
+
+-- Statements are immutable; corrections produce an amended version
+CREATE OR REPLACE FUNCTION reject_statement_change()
+RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'institution_statements are immutable; generate an amended statement instead';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS institution_statements_immutable ON institution_statements;
+CREATE TRIGGER institution_statements_immutable
+    BEFORE UPDATE OR DELETE ON institution_statements
+    FOR EACH ROW
+    EXECUTE FUNCTION reject_statement_change();
@@ -0,0 +1,36 @@
+-- Quantera v2.1.0 Investor Communications
+-- Append-only log of outreach to investors, optionally linked to an AML case
+
+CREATE TABLE IF NOT EXISTS investor_communications (
+    id BIGSERIAL PRIMARY KEY,
+    communication_id UUID NOT NULL UNIQUE,
+    investor_address BYTEA NOT NULL,
+    case_id UUID REFERENCES aml_alerts(alert_id),
+    channel VARCHAR(20) NOT NULL CHECK (channel IN ('email', 'phone', 'letter', 'portal', 'meeting', 'system')),
+    direction VARCHAR(20) NOT NULL CHECK (direction IN ('inbound', 'outbound', 'internal')),
+    subject VARCHAR(200) NOT NULL,
+    -- Encrypted body on IPFS; system entries carry only a subject
+    body_ipfs_hash VARCHAR(128),
+    author VARCHAR(255) NOT NULL,
+    corrects UUID REFERENCES investor_communications(communication_id),
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_investor_communications_investor
+    ON investor_communications(investor_address, recorded_at);
+CREATE INDEX IF NOT EXISTS idx_investor_communications_case
+    ON investor_communications(case_id, recorded_at) WHERE case_id IS NOT NULL;
+
+-- Entries are immutable; corrections are new entries referencing the original
+CREATE OR REPLACE FUNCTION reject_communication_change()
+RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'investor_communications entries are immutable; record a correction instead';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS investor_communications_immutable ON investor_communications;
+CREATE TRIGGER investor_communications_immutable
+    BEFORE UPDATE OR DELETE ON investor_communications
+    FOR EACH ROW
+    EXECUTE FUNCTION reject_communication_change();
@@ -0,0 +1,32 @@
+-- Quantera v2.1.0 Investor Document Expiry
+-- KYC document metadata, expiry notices and document-driven KYC status
+
+ALTER TABLE investor_profiles
+    ADD COLUMN IF NOT EXISTS kyc_status VARCHAR(20) NOT NULL DEFAULT 'Completed';
+
+CREATE INDEX IF NOT EXISTS idx_investor_profiles_kyc_status ON investor_profiles(kyc_status);
+
+CREATE TABLE IF NOT EXISTS investor_documents (
+    id BIGSERIAL PRIMARY KEY,
+    document_id UUID NOT NULL UNIQUE,
+    investor_address BYTEA NOT NULL,
+    document_type VARCHAR(30) NOT NULL,
+    issue_date TIMESTAMPTZ NOT NULL,
+    expiry_date TIMESTAMPTZ,
+    ipfs_hash TEXT NOT NULL,
+    verification_id VARCHAR(255),
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    superseded_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_investor_documents_investor ON investor_documents(investor_address);
+CREATE INDEX idx_investor_documents_active_expiry ON investor_documents(expiry_date) WHERE superseded_at IS NULL;
+
+-- One notice per document and stage (within_60_days, within_30_days, expired)
+CREATE TABLE IF NOT EXISTS document_expiry_notices (
+    id BIGSERIAL PRIMARY KEY,
+    document_id UUID NOT NULL REFERENCES investor_documents(document_id),
+    stage VARCHAR(20) NOT NULL,
+    flagged_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (document_id, stage)
+);
@@ -0,0 +1,30 @@
+-- Quantera v2.1.0 Investor Offboarding
+-- Position wind-down before offboarding, the investment block and document retention holds
+
+-- Set when offboarding is initiated; every compliance check fails while it is set
+ALTER TABLE investor_profiles
+    ADD COLUMN IF NOT EXISTS investment_blocked BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Rows recorded before the wind-down existed were offboarded when requested
+ALTER TABLE investor_offboarding
+    ADD COLUMN IF NOT EXISTS stage VARCHAR(20) NOT NULL DEFAULT 'offboarded'
+        CHECK (stage IN ('winding_down', 'offboarded')),
+    ADD COLUMN IF NOT EXISTS remaining_holdings JSONB NOT NULL DEFAULT '[]',
+    ADD COLUMN IF NOT EXISTS wind_down_checked_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS offboarded_at TIMESTAMPTZ;
+
+UPDATE investor_offboarding SET offboarded_at = requested_at WHERE stage = 'offboarded' AND offboarded_at IS NULL;
+
+ALTER TABLE investor_offboarding ALTER COLUMN stage SET DEFAULT 'winding_down';
+
+CREATE INDEX IF NOT EXISTS idx_investor_offboarding_stage ON investor_offboarding(stage);
+
+-- Documents of offboarded investors are kept until retain_until; nothing may purge them earlier
+CREATE TABLE IF NOT EXISTS document_retention_holds (
+    document_id UUID PRIMARY KEY REFERENCES investor_documents(document_id),
+    investor_address BYTEA NOT NULL,
+    held_from TIMESTAMPTZ NOT NULL,
+    retain_until TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_document_retention_holds_until ON document_retention_holds(retain_until);
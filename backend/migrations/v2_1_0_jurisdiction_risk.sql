@@ -0,0 +1,15 @@
+-- Quantera v2.1.0 Jurisdiction Risk
+-- Country risk tiers from FATF listings and the Basel AML Index, refreshed from JURISDICTION_RISK_SOURCE
+
+CREATE TABLE IF NOT EXISTS jurisdiction_risk (
+    country_code CHAR(2) PRIMARY KEY,
+    fatf_status VARCHAR(30) NOT NULL,
+    basel_aml_score DOUBLE PRECISION,
+    -- Platform policy taking precedence over the FATF and Basel data
+    tier_override VARCHAR(20),
+    -- standard, high (enhanced due diligence) or blacklisted
+    tier VARCHAR(20) NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_jurisdiction_risk_tier ON jurisdiction_risk(tier);
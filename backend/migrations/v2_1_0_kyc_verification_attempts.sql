@@ -0,0 +1,34 @@
+-- Quantera v2.1.0 KYC Verification Attempts
+-- Per-provider attempt history driving the retry policy, and manual-review escalations
+
+CREATE TABLE IF NOT EXISTS kyc_verification_attempts (
+    id BIGSERIAL PRIMARY KEY,
+    attempt_id UUID NOT NULL UNIQUE,
+    investor_id VARCHAR(255) NOT NULL,
+    provider VARCHAR(40) NOT NULL,
+    outcome VARCHAR(20) NOT NULL CHECK (outcome IN ('verified', 'rejected', 'provider_error')),
+    reason TEXT,
+    reference_id VARCHAR(255),
+    attempted_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_kyc_verification_attempts_investor
+    ON kyc_verification_attempts(investor_id, attempted_at DESC);
+
+CREATE TABLE IF NOT EXISTS kyc_manual_reviews (
+    id BIGSERIAL PRIMARY KEY,
+    review_id UUID NOT NULL UNIQUE,
+    investor_id VARCHAR(255) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'approved', 'rejected')),
+    opened_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_by VARCHAR(255),
+    justification TEXT,
+    resolved_at TIMESTAMPTZ
+);
+
+-- At most one pending review per investor; further exhausted attempts reuse it
+CREATE UNIQUE INDEX IF NOT EXISTS idx_kyc_manual_reviews_pending
+    ON kyc_manual_reviews(investor_id) WHERE status = 'pending';
+
+CREATE INDEX IF NOT EXISTS idx_kyc_manual_reviews_investor
+    ON kyc_manual_reviews(investor_id, opened_at DESC);
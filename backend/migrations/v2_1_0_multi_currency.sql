@@ -0,0 +1,11 @@
+-- Quantera v2.1.0 Multi-Currency
+-- ISO 4217 currency of holdings, transactions and yield distributions; existing rows are in US dollars
+
+ALTER TABLE portfolio_holdings
+    ADD COLUMN IF NOT EXISTS currency CHAR(3) NOT NULL DEFAULT 'USD' CHECK (currency ~ '^[A-Z]{3}$');
+
+ALTER TABLE portfolio_transactions
+    ADD COLUMN IF NOT EXISTS currency CHAR(3) NOT NULL DEFAULT 'USD' CHECK (currency ~ '^[A-Z]{3}$');
+
+ALTER TABLE yield_distributions
+    ADD COLUMN IF NOT EXISTS currency CHAR(3) NOT NULL DEFAULT 'USD' CHECK (currency ~ '^[A-Z]{3}$');
@@ -0,0 +1,18 @@
+-- Quantera v2.1.0 Periodic Re-screening
+-- Sanctions and PEP re-screening on a cadence set by each investor's risk tier
+
+-- Set by scheduled re-screening and by manual compliance checks; NULL until first screened
+ALTER TABLE investor_profiles
+    ADD COLUMN IF NOT EXISTS last_screened_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_investor_profiles_last_screened ON investor_profiles(last_screened_at NULLS FIRST);
+
+CREATE TABLE IF NOT EXISTS screening_runs (
+    id BIGSERIAL PRIMARY KEY,
+    run_id UUID NOT NULL UNIQUE,
+    run_at TIMESTAMPTZ NOT NULL,
+    profiles_due INTEGER NOT NULL,
+    screened INTEGER NOT NULL,
+    failed INTEGER NOT NULL,
+    hits INTEGER NOT NULL
+);
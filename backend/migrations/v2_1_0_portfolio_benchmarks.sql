@@ -0,0 +1,10 @@
+-- Quantera v2.1.0 Portfolio Benchmarks
+-- Benchmark each portfolio's beta and alpha are measured against; portfolios without a row use RISK_BENCHMARK
+
+CREATE TABLE IF NOT EXISTS portfolio_benchmarks (
+    portfolio_address VARCHAR(42) PRIMARY KEY,
+    -- 'asset' for an asset in asset_price_history, 'benchmark' for an index in benchmark_price_history
+    series_kind VARCHAR(16) NOT NULL CHECK (series_kind IN ('asset', 'benchmark')),
+    series_key VARCHAR(50) NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,49 @@
+-- Quantera v2.1.0 Position Entry Prices
+-- Acquisition history and weighted-average cost basis per portfolio asset
+
+-- Daily asset prices, used to value transfers in from unknown sources
+CREATE TABLE IF NOT EXISTS asset_price_history (
+    asset_address VARCHAR(42) NOT NULL,
+    price_date DATE NOT NULL,
+    price NUMERIC(38, 18) NOT NULL CHECK (price >= 0),
+    source VARCHAR(50) NOT NULL DEFAULT 'oracle',
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (asset_address, price_date)
+);
+
+-- Portfolios whose acquisitions are synced, with the last block ingested
+CREATE TABLE IF NOT EXISTS acquisition_watchlist (
+    portfolio_address VARCHAR(42) PRIMARY KEY,
+    last_synced_block BIGINT,
+    added_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Ingested transfer and purchase events
+CREATE TABLE IF NOT EXISTS position_acquisitions (
+    id BIGSERIAL PRIMARY KEY,
+    portfolio_address VARCHAR(42) NOT NULL,
+    asset_address VARCHAR(42) NOT NULL,
+    tx_hash VARCHAR(66) NOT NULL,
+    log_index BIGINT NOT NULL,
+    block_number BIGINT NOT NULL,
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('purchase', 'transfer_in', 'disposal')),
+    amount NUMERIC(38, 18) NOT NULL,
+    price NUMERIC(38, 18),
+    provenance VARCHAR(20) CHECK (provenance IN ('purchase', 'price_history', 'unpriced')),
+    occurred_at TIMESTAMPTZ NOT NULL,
+    UNIQUE (portfolio_address, tx_hash, log_index)
+);
+
+CREATE INDEX IF NOT EXISTS idx_position_acquisitions_asset
+    ON position_acquisitions(portfolio_address, asset_address, block_number);
+
+-- Weighted-average entry price of each open holding
+CREATE TABLE IF NOT EXISTS position_cost_basis (
+    portfolio_address VARCHAR(42) NOT NULL,
+    asset_address VARCHAR(42) NOT NULL,
+    amount NUMERIC(38, 18) NOT NULL,
+    entry_price NUMERIC(38, 18) NOT NULL,
+    provenance VARCHAR(20) NOT NULL CHECK (provenance IN ('purchase', 'price_history', 'unpriced')),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (portfolio_address, asset_address)
+);
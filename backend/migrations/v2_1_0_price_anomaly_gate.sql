@@ -0,0 +1,28 @@
+-- Quantera v2.1.0 Price Anomaly Gate
+-- Ingested prices that break from recent history are quarantined until reviewed
+
+-- Quarantined points stay in the history so consumers skip them explicitly
+ALTER TABLE asset_price_history ADD COLUMN IF NOT EXISTS quarantined BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX IF NOT EXISTS idx_asset_price_history_quarantined
+    ON asset_price_history(asset_address, price_date) WHERE quarantined;
+
+CREATE TABLE IF NOT EXISTS asset_price_quarantine (
+    quarantine_id UUID PRIMARY KEY,
+    asset_address VARCHAR(42) NOT NULL,
+    price_date DATE NOT NULL,
+    price NUMERIC(38, 18) NOT NULL CHECK (price >= 0),
+    -- Why the observation was held back, with the reference price and limit
+    anomaly JSONB NOT NULL,
+    source_names TEXT[] NOT NULL DEFAULT '{}',
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'approved', 'discarded')),
+    -- Sources of a later aggregation that agreed with the price; approval needs two
+    confirmed_by TEXT[] NOT NULL DEFAULT '{}',
+    confirmed_at TIMESTAMPTZ,
+    reviewed_by VARCHAR(255),
+    reviewed_at TIMESTAMPTZ,
+    observed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_asset_price_quarantine_pending
+    ON asset_price_quarantine(asset_address, observed_at) WHERE status = 'pending';
@@ -0,0 +1,11 @@
+-- Quantera v2.1.0 Price Backfill
+-- Daily benchmark index history, filled alongside asset prices by the historical backfill
+
+CREATE TABLE IF NOT EXISTS benchmark_price_history (
+    benchmark VARCHAR(50) NOT NULL,
+    price_date DATE NOT NULL,
+    price NUMERIC(38, 18) NOT NULL CHECK (price >= 0),
+    source VARCHAR(50) NOT NULL DEFAULT 'backfill',
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (benchmark, price_date)
+);
@@ -0,0 +1,27 @@
+-- Quantera v2.1.0 Risk Alert Deduplication
+-- One row per open alert with occurrence tracking and resolution state
+
+ALTER TABLE risk_alerts
+    ADD COLUMN IF NOT EXISTS limit_name VARCHAR(50),
+    ADD COLUMN IF NOT EXISTS status VARCHAR(20) NOT NULL DEFAULT 'Open' CHECK (status IN ('Open', 'Resolved')),
+    ADD COLUMN IF NOT EXISTS occurrence_count INTEGER NOT NULL DEFAULT 1,
+    ADD COLUMN IF NOT EXISTS last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ADD COLUMN IF NOT EXISTS resolved_at TIMESTAMPTZ;
+
+-- At most one open alert per portfolio, alert type and limit
+CREATE UNIQUE INDEX IF NOT EXISTS idx_risk_alerts_open_key
+    ON risk_alerts(portfolio_address, alert_type, limit_name)
+    WHERE status = 'Open';
+
+CREATE INDEX IF NOT EXISTS idx_risk_alerts_status ON risk_alerts(portfolio_address, status, last_seen_at DESC);
+
+-- Occurrence history for each alert
+CREATE TABLE IF NOT EXISTS risk_alert_occurrences (
+    id BIGSERIAL PRIMARY KEY,
+    alert_id UUID NOT NULL REFERENCES risk_alerts(id) ON DELETE CASCADE,
+    metric_value NUMERIC(20, 8),
+    severity VARCHAR(20) NOT NULL,
+    observed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_risk_alert_occurrences_alert ON risk_alert_occurrences(alert_id, observed_at DESC);
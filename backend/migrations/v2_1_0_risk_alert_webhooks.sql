@@ -0,0 +1,33 @@
+-- Quantera v2.1.0 Risk Alert Webhooks
+-- Endpoints that receive a portfolio's risk alerts, and deliveries given up after every
+-- retry failed
+
+CREATE TABLE IF NOT EXISTS risk_alert_webhooks (
+    id UUID PRIMARY KEY,
+    portfolio_address VARCHAR(42) NOT NULL,
+    url TEXT NOT NULL,
+    -- HMAC-SHA256 key the body is signed with in X-Signature
+    secret TEXT NOT NULL,
+    -- Alert severities delivered: Info, Warning and/or Critical
+    severities TEXT[] NOT NULL CHECK (cardinality(severities) > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_risk_alert_webhooks_portfolio ON risk_alert_webhooks (portfolio_address);
+
+-- Kept after the webhook is removed, for the record of what was not delivered
+CREATE TABLE IF NOT EXISTS risk_webhook_dead_letters (
+    delivery_id UUID PRIMARY KEY,
+    webhook_id UUID NOT NULL,
+    portfolio_address VARCHAR(42) NOT NULL,
+    alert_id UUID NOT NULL,
+    url TEXT NOT NULL,
+    attempts INTEGER NOT NULL,
+    -- Status of the last response; NULL when it timed out or could not connect
+    last_status INTEGER,
+    last_error TEXT NOT NULL,
+    payload JSONB NOT NULL,
+    failed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_risk_webhook_dead_letters_portfolio ON risk_webhook_dead_letters (portfolio_address, failed_at DESC);
@@ -0,0 +1,14 @@
+-- Quantera v2.1.0 Risk Limits
+-- Per-portfolio overrides of the limits monitor_risk_limits checks; limits a portfolio does
+-- not override keep the service defaults
+
+CREATE TABLE IF NOT EXISTS risk_limits (
+    portfolio_address VARCHAR(42) NOT NULL,
+    -- max_var_95, max_var_99, max_drawdown, max_concentration, max_leverage or max_volatility
+    limit_name VARCHAR(50) NOT NULL,
+    limit_value NUMERIC(38, 18) NOT NULL CHECK (limit_value > 0),
+    -- Severity a breach alerts at before escalation
+    severity VARCHAR(20) NOT NULL CHECK (severity IN ('Info', 'Warning', 'Critical')),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (portfolio_address, limit_name)
+);
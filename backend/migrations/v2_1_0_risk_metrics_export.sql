@@ -0,0 +1,5 @@
+-- Quantera v2.1.0 Risk Metrics Export
+-- Keep the correlation matrix of each calculation for bulk CSV/Parquet exports
+
+ALTER TABLE risk_metrics
+    ADD COLUMN IF NOT EXISTS correlation_matrix JSONB;
@@ -0,0 +1,33 @@
+-- Quantera v2.1.0 Service API Keys
+-- Scoped keys for calls between services, and the audit of calls made with them
+
+-- Only a SHA-256 hash of each secret is stored; a rotated-out key keeps working until expires_at
+CREATE TABLE IF NOT EXISTS service_api_keys (
+    key_id UUID PRIMARY KEY,
+    service VARCHAR(100) NOT NULL,
+    scopes TEXT[] NOT NULL CHECK (cardinality(scopes) > 0),
+    secret_hash CHAR(64) NOT NULL,
+    rate_limit_per_minute INTEGER NOT NULL CHECK (rate_limit_per_minute > 0),
+    created_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_service_api_keys_service
+    ON service_api_keys(service, created_at);
+
+-- Every request made with a service key, attributed to the calling service
+CREATE TABLE IF NOT EXISTS service_call_audit (
+    id BIGSERIAL PRIMARY KEY,
+    key_id UUID NOT NULL REFERENCES service_api_keys(key_id),
+    service VARCHAR(100) NOT NULL,
+    scope VARCHAR(50) NOT NULL,
+    method VARCHAR(10) NOT NULL,
+    path TEXT NOT NULL,
+    status INTEGER NOT NULL,
+    called_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_service_call_audit_service
+    ON service_call_audit(service, called_at);
@@ -0,0 +1,10 @@
+-- Quantera v2.1.0 Stablecoin Depeg Monitoring
+-- Stablecoins managed through the admin API, on top of the configured STABLECOIN_PEGS
+
+-- An inactive row removes a configured stablecoin from monitoring
+CREATE TABLE IF NOT EXISTS stablecoin_pegs (
+    asset_address VARCHAR(42) PRIMARY KEY,
+    peg NUMERIC(38, 18) NOT NULL CHECK (peg > 0),
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
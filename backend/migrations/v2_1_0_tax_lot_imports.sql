@@ -0,0 +1,34 @@
+-- Quantera v2.1.0 Tax Lot Imports
+-- Opening lots imported from other platforms plus platform-native acquisitions
+
+CREATE TABLE IF NOT EXISTS tax_lot_imports (
+    id BIGSERIAL PRIMARY KEY,
+    import_id UUID NOT NULL UNIQUE,
+    investor_address BYTEA NOT NULL,
+    version INTEGER NOT NULL,
+    lot_count INTEGER NOT NULL,
+    imported_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    superseded_at TIMESTAMPTZ,
+    UNIQUE (investor_address, version)
+);
+
+-- At most one active import per investor
+CREATE UNIQUE INDEX idx_tax_lot_imports_active ON tax_lot_imports(investor_address) WHERE superseded_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS tax_lots (
+    id BIGSERIAL PRIMARY KEY,
+    lot_id UUID NOT NULL UNIQUE,
+    investor_address BYTEA NOT NULL,
+    asset_address BYTEA NOT NULL,
+    quantity NUMERIC(78, 18) NOT NULL CHECK (quantity > 0),
+    remaining_quantity NUMERIC(78, 18) NOT NULL CHECK (remaining_quantity >= 0),
+    cost_basis NUMERIC(78, 18) NOT NULL CHECK (cost_basis >= 0),
+    acquisition_date TIMESTAMPTZ NOT NULL,
+    source VARCHAR(100) NOT NULL,
+    origin VARCHAR(20) NOT NULL CHECK (origin IN ('imported', 'platform')),
+    import_id UUID REFERENCES tax_lot_imports(import_id),
+    active BOOLEAN NOT NULL DEFAULT TRUE
+);
+
+CREATE INDEX idx_tax_lots_open ON tax_lots(investor_address, asset_address, acquisition_date) WHERE active AND remaining_quantity > 0;
+CREATE INDEX idx_tax_lots_import ON tax_lots(import_id);
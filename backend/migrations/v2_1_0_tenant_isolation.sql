@@ -0,0 +1,39 @@
+-- Quantera v2.1.0 Tenant Isolation
+-- Issuer tenants for white-label deployments; existing rows are backfilled into the default tenant
+
+CREATE TABLE IF NOT EXISTS tenants (
+    tenant_id VARCHAR(64) PRIMARY KEY,
+    name VARCHAR(200) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+INSERT INTO tenants (tenant_id, name) VALUES ('default', 'Default Tenant')
+ON CONFLICT (tenant_id) DO NOTHING;
+
+-- ADD COLUMN with a constant default backfills every existing row
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS tenant_id VARCHAR(64) NOT NULL DEFAULT 'default' REFERENCES tenants(tenant_id);
+
+ALTER TABLE tradefinance_assets
+    ADD COLUMN IF NOT EXISTS tenant_id VARCHAR(64) NOT NULL DEFAULT 'default' REFERENCES tenants(tenant_id);
+
+ALTER TABLE investor_profiles
+    ADD COLUMN IF NOT EXISTS tenant_id VARCHAR(64) NOT NULL DEFAULT 'default' REFERENCES tenants(tenant_id);
+
+ALTER TABLE compliance_reports
+    ADD COLUMN IF NOT EXISTS tenant_id VARCHAR(64) NOT NULL DEFAULT 'default' REFERENCES tenants(tenant_id);
+
+ALTER TABLE audit_log
+    ADD COLUMN IF NOT EXISTS tenant_id VARCHAR(64) NOT NULL DEFAULT 'default' REFERENCES tenants(tenant_id);
+
+ALTER TABLE compliance_audit_log
+    ADD COLUMN IF NOT EXISTS tenant_id VARCHAR(64) NOT NULL DEFAULT 'default' REFERENCES tenants(tenant_id);
+
+CREATE INDEX IF NOT EXISTS idx_users_tenant ON users(tenant_id);
+CREATE INDEX IF NOT EXISTS idx_tradefinance_assets_tenant ON tradefinance_assets(tenant_id);
+CREATE INDEX IF NOT EXISTS idx_investor_profiles_tenant ON investor_profiles(tenant_id);
+CREATE INDEX IF NOT EXISTS idx_compliance_reports_tenant ON compliance_reports(tenant_id);
+CREATE INDEX IF NOT EXISTS idx_audit_log_tenant ON audit_log(tenant_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_compliance_audit_log_tenant ON compliance_audit_log(tenant_id, created_at DESC);
+
+COMMENT ON TABLE tenants IS 'Issuer tenants; every tenant-owned row references one';
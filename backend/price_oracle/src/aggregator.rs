@@ -0,0 +1,386 @@
+// Median aggregation across price sources
+use crate::{
+    ChainlinkOracle,
+    FeedReader,
+    HttpPriceOracle,
+    ManualOverrideStore,
+    OracleError,
+    PriceOracle,
+    PriceQuote,
+};
+use chrono::{DateTime, Utc};
+use futures::future::join_all;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Deviation thresholds, in basis points of the median
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AggregatorConfig {
+    /// Quotes further than this from the median are discarded
+    pub outlier_threshold_bps: u32,
+    /// Any quote further than this from the median marks the price as disputed
+    pub dispute_threshold_bps: u32,
+}
+
+impl Default for AggregatorConfig {
+    fn default() -> Self {
+        Self {
+            outlier_threshold_bps: 100,
+            dispute_threshold_bps: 500,
+        }
+    }
+}
+
+impl AggregatorConfig {
+    pub fn validate(&self) -> Result<(), OracleError> {
+        if self.outlier_threshold_bps == 0 {
+            return Err(OracleError::Config("Outlier threshold must be at least 1 bps".into()));
+        }
+        if self.dispute_threshold_bps < self.outlier_threshold_bps {
+            return Err(OracleError::Config("Dispute threshold must not be below the outlier threshold".into()));
+        }
+        Ok(())
+    }
+}
+
+/// A source that errored while being queried
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceFailure {
+    pub source: String,
+    pub error: String,
+}
+
+/// Consensus price for an asset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AggregatedPrice {
+    pub asset: String,
+    pub price: Decimal,
+    /// 0 to 1: the share of responding sources that agree, less their spread
+    pub confidence: Decimal,
+    /// Quotes the price was computed from
+    pub sources: Vec<PriceQuote>,
+    /// Quotes dropped as outliers, or shadowed by a manual override
+    pub discarded: Vec<PriceQuote>,
+    pub failed: Vec<SourceFailure>,
+    /// Sources disagree beyond the dispute threshold, or no majority agrees
+    pub disputed: bool,
+    /// The price is an operator's manual override
+    pub overridden: bool,
+    pub aggregated_at: DateTime<Utc>,
+}
+
+impl AggregatedPrice {
+    /// Disputed prices may be shown but must not be written on-chain automatically
+    pub fn allows_automatic_update(&self) -> bool {
+        !self.disputed
+    }
+}
+
+/// Queries every configured oracle for an asset and reduces the quotes to one price
+pub struct OracleAggregator {
+    oracles: Vec<Arc<dyn PriceOracle>>,
+    overrides: Arc<ManualOverrideStore>,
+    config: AggregatorConfig,
+}
+
+impl OracleAggregator {
+    pub fn new(oracles: Vec<Arc<dyn PriceOracle>>, config: AggregatorConfig) -> Self {
+        Self {
+            oracles,
+            overrides: Arc::new(ManualOverrideStore::default()),
+            config,
+        }
+    }
+
+    pub fn with_overrides(mut self, overrides: Arc<ManualOverrideStore>) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
+    /// Build from environment:
+    ///   PRICE_API_SOURCES            name=url pairs, comma separated
+    ///   CHAINLINK_FEEDS              asset=feed pairs, comma separated (needs a feed reader)
+    ///   CHAINLINK_MAX_AGE_SECS       rounds older than this are rejected (default 86400)
+    ///   PRICE_OVERRIDES_PATH         JSON file of manual overrides keyed by asset
+    ///   PRICE_OUTLIER_THRESHOLD_BPS  default 100
+    ///   PRICE_DISPUTE_THRESHOLD_BPS  default 500
+    pub fn from_env(feed_reader: Option<Arc<dyn FeedReader>>) -> Result<Self, OracleError> {
+        let mut oracles: Vec<Arc<dyn PriceOracle>> = Vec::new();
+
+        for (name, url) in env_pairs("PRICE_API_SOURCES")? {
+            oracles.push(Arc::new(HttpPriceOracle::new(&name, &url, Duration::from_secs(5))?));
+        }
+
+        let feeds: HashMap<String, String> = env_pairs("CHAINLINK_FEEDS")?.into_iter().collect();
+        match feed_reader {
+            Some(reader) if !feeds.is_empty() => {
+                let max_age = Duration::from_secs(env_number("CHAINLINK_MAX_AGE_SECS", 86_400)?);
+                oracles.push(Arc::new(ChainlinkOracle::new(reader, feeds, max_age)));
+            }
+            None if !feeds.is_empty() => warn!("CHAINLINK_FEEDS is set but this service has no feed reader; ignoring"),
+            _ => {}
+        }
+
+        let overrides = match std::env::var("PRICE_OVERRIDES_PATH").ok().filter(|path| !path.is_empty()) {
+            Some(path) => ManualOverrideStore::from_file(&path)?,
+            None => ManualOverrideStore::default(),
+        };
+
+        let config = AggregatorConfig {
+            outlier_threshold_bps: env_number("PRICE_OUTLIER_THRESHOLD_BPS", 100)? as u32,
+            dispute_threshold_bps: env_number("PRICE_DISPUTE_THRESHOLD_BPS", 500)? as u32,
+        };
+        config.validate()?;
+
+        info!("Price aggregator configured with {} sources", oracles.len());
+        Ok(Self::new(oracles, config).with_overrides(Arc::new(overrides)))
+    }
+
+    pub fn overrides(&self) -> Arc<ManualOverrideStore> {
+        self.overrides.clone()
+    }
+
+    pub fn config(&self) -> AggregatorConfig {
+        self.config
+    }
+
+    /// Query every oracle for `asset` and aggregate the quotes
+    pub async fn price(&self, asset: &str) -> Result<AggregatedPrice, OracleError> {
+        let results = join_all(self.oracles.iter().map(|oracle| async move {
+            (oracle.name().to_string(), oracle.quote(asset).await)
+        })).await;
+
+        let mut quotes = Vec::new();
+        let mut failed = Vec::new();
+        for (source, result) in results {
+            match result {
+                Ok(Some(quote)) => quotes.push(quote),
+                Ok(None) => {}
+                Err(e) => {
+                    warn!("Price source {} failed for {}: {}", source, asset, e);
+                    failed.push(SourceFailure { source, error: e.to_string() });
+                }
+            }
+        }
+
+        if let Some(quote) = self.overrides.quote(asset).await? {
+            return Ok(AggregatedPrice {
+                asset: asset.to_string(),
+                price: quote.price,
+                confidence: Decimal::ONE,
+                sources: vec![quote],
+                discarded: quotes,
+                failed,
+                disputed: false,
+                overridden: true,
+                aggregated_at: Utc::now(),
+            });
+        }
+
+        self.aggregate(asset, quotes, failed)
+    }
+
+    /// Reduce quotes to a median price, discarding outliers and flagging disputes
+    pub fn aggregate(
+        &self,
+        asset: &str,
+        quotes: Vec<PriceQuote>,
+        failed: Vec<SourceFailure>,
+    ) -> Result<AggregatedPrice, OracleError> {
+        if quotes.is_empty() {
+            return Err(OracleError::NoPrice(asset.to_string()));
+        }
+
+        let reference = median(quotes.iter().map(|q| q.price).collect());
+        let max_deviation = quotes.iter()
+            .map(|q| deviation_bps(q.price, reference))
+            .max()
+            .unwrap_or(Decimal::ZERO);
+
+        let outlier_threshold = Decimal::from(self.config.outlier_threshold_bps);
+        let total = quotes.len();
+        let (sources, discarded): (Vec<_>, Vec<_>) = quotes.into_iter()
+            .partition(|q| deviation_bps(q.price, reference) <= outlier_threshold);
+
+        // Without a strict majority there is no consensus to fall back on
+        let consensus = sources.len() * 2 > total;
+        let disputed = !consensus || max_deviation > Decimal::from(self.config.dispute_threshold_bps);
+
+        let price = if consensus {
+            median(sources.iter().map(|q| q.price).collect())
+        } else {
+            reference
+        };
+
+        let confidence = if disputed {
+            Decimal::ZERO
+        } else {
+            let coverage = Decimal::from(sources.len()) / Decimal::from(total + failed.len());
+            let spread = sources.iter().map(|q| deviation_bps(q.price, price)).sum::<Decimal>()
+                / Decimal::from(sources.len());
+            (coverage * (Decimal::ONE - spread / outlier_threshold)).max(Decimal::ZERO).round_dp(4)
+        };
+
+        if disputed {
+            warn!(
+                "Price for {} is disputed: {} of {} sources agree, max deviation {} bps",
+                asset, sources.len(), total, max_deviation.round_dp(1)
+            );
+        }
+
+        Ok(AggregatedPrice {
+            asset: asset.to_string(),
+            price,
+            confidence,
+            sources,
+            discarded,
+            failed,
+            disputed,
+            overridden: false,
+            aggregated_at: Utc::now(),
+        })
+    }
+}
+
+fn median(mut prices: Vec<Decimal>) -> Decimal {
+    prices.sort();
+    let mid = prices.len() / 2;
+    if prices.len() % 2 == 0 {
+        (prices[mid - 1] + prices[mid]) / Decimal::TWO
+    } else {
+        prices[mid]
+    }
+}
+
+/// Absolute distance from `reference`, in basis points
+fn deviation_bps(price: Decimal, reference: Decimal) -> Decimal {
+    if reference.is_zero() {
+        return Decimal::MAX;
+    }
+    ((price - reference).abs() / reference) * Decimal::from(10_000)
+}
+
+fn env_pairs(name: &str) -> Result<Vec<(String, String)>, OracleError> {
+    let Ok(value) = std::env::var(name) else { return Ok(Vec::new()) };
+
+    value.split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| pair.split_once('=')
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .ok_or_else(|| OracleError::Config(format!("{} entries must be key=value, got {}", name, pair))))
+        .collect()
+}
+
+pub(crate) fn env_number(name: &str, default: u64) -> Result<u64, OracleError> {
+    match std::env::var(name) {
+        Ok(value) => value.parse::<u64>()
+            .map_err(|_| OracleError::Config(format!("{} must be a non-negative integer", name))),
+        Err(_) => Ok(default),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ManualOverride;
+    use async_trait::async_trait;
+
+    struct Fixed(&'static str, Option<Decimal>);
+
+    #[async_trait]
+    impl PriceOracle for Fixed {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        async fn quote(&self, _asset: &str) -> Result<Option<PriceQuote>, OracleError> {
+            match self.1 {
+                Some(price) => Ok(Some(PriceQuote { source: self.0.to_string(), price, observed_at: Utc::now() })),
+                None => Err(OracleError::Source { oracle: self.0.to_string(), message: "timeout".into() }),
+            }
+        }
+    }
+
+    fn aggregator(prices: &[(&'static str, Option<Decimal>)]) -> OracleAggregator {
+        OracleAggregator::new(
+            prices.iter().map(|(name, price)| Arc::new(Fixed(name, *price)) as Arc<dyn PriceOracle>).collect(),
+            AggregatorConfig { outlier_threshold_bps: 100, dispute_threshold_bps: 500 },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_outlier_is_discarded_from_median() {
+        let prices = aggregator(&[
+            ("chainlink", Some(Decimal::new(10000, 2))),
+            ("api-a", Some(Decimal::new(10020, 2))),
+            ("api-b", Some(Decimal::new(10300, 2))),
+            ("api-c", None),
+        ]);
+
+        let price = prices.price("UST10Y").await.unwrap();
+        assert!(!price.disputed);
+        assert!(price.allows_automatic_update());
+        assert_eq!(price.price, Decimal::new(10010, 2));
+        assert_eq!(price.sources.len(), 2);
+        assert_eq!(price.discarded[0].source, "api-b");
+        assert_eq!(price.failed[0].source, "api-c");
+        assert!(price.confidence > Decimal::ZERO && price.confidence < Decimal::new(5, 1));
+    }
+
+    #[tokio::test]
+    async fn test_disagreement_beyond_hard_threshold_is_disputed() {
+        // One source far off: outvoted, but still beyond the dispute threshold
+        let prices = aggregator(&[
+            ("chainlink", Some(Decimal::new(10000, 2))),
+            ("api-a", Some(Decimal::new(10010, 2))),
+            ("api-b", Some(Decimal::new(12000, 2))),
+        ]);
+        let price = prices.price("UST10Y").await.unwrap();
+        assert!(price.disputed);
+        assert!(!price.allows_automatic_update());
+        assert_eq!(price.confidence, Decimal::ZERO);
+
+        // Two sources apart by more than the outlier threshold: no majority agrees
+        let prices = aggregator(&[
+            ("chainlink", Some(Decimal::new(10000, 2))),
+            ("api-a", Some(Decimal::new(10300, 2))),
+        ]);
+        let price = prices.price("UST10Y").await.unwrap();
+        assert!(price.disputed);
+        assert_eq!(price.price, Decimal::new(10150, 2));
+
+        let prices = aggregator(&[("api-a", None)]);
+        assert!(matches!(prices.price("UST10Y").await, Err(OracleError::NoPrice(_))));
+    }
+
+    #[tokio::test]
+    async fn test_manual_override_takes_precedence() {
+        let overrides = Arc::new(ManualOverrideStore::default());
+        overrides.set("UST10Y", ManualOverride {
+            price: Decimal::new(9950, 2),
+            set_by: "ops".into(),
+            reason: "feed halted".into(),
+            set_at: Utc::now(),
+            expires_at: None,
+        }).unwrap();
+
+        let prices = aggregator(&[
+            ("chainlink", Some(Decimal::new(10000, 2))),
+            ("api-a", Some(Decimal::new(12000, 2))),
+        ]).with_overrides(overrides.clone());
+
+        let price = prices.price("UST10Y").await.unwrap();
+        assert!(price.overridden);
+        assert!(!price.disputed);
+        assert_eq!(price.price, Decimal::new(9950, 2));
+        assert_eq!(price.discarded.len(), 2);
+
+        overrides.clear("UST10Y");
+        assert!(prices.price("UST10Y").await.unwrap().disputed);
+    }
+}
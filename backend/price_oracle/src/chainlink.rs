@@ -0,0 +1,118 @@
+// Chainlink aggregator feeds
+use crate::{OracleError, PriceOracle, PriceQuote};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Answer of a feed's `latestRoundData()`, with the feed's `decimals()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundData {
+    pub answer: i128,
+    pub decimals: u8,
+    pub updated_at: u64,
+}
+
+/// Reads Chainlink aggregator contracts.
+///
+/// Each service implements this for its own Ethereum client so the oracle layer does
+/// not depend on a particular RPC library.
+#[async_trait]
+pub trait FeedReader: Send + Sync {
+    async fn latest_round(&self, feed: &str) -> Result<RoundData, String>;
+}
+
+/// Prices from Chainlink feeds, one feed contract per asset
+pub struct ChainlinkOracle {
+    reader: Arc<dyn FeedReader>,
+    feeds: HashMap<String, String>,
+    max_age: Duration,
+}
+
+impl ChainlinkOracle {
+    pub fn new(reader: Arc<dyn FeedReader>, feeds: HashMap<String, String>, max_age: Duration) -> Self {
+        Self { reader, feeds, max_age }
+    }
+
+    /// Scale a raw answer by the feed's decimals, rejecting non-positive or stale rounds
+    fn to_quote(&self, asset: &str, round: RoundData, now: DateTime<Utc>) -> Result<PriceQuote, OracleError> {
+        if round.answer <= 0 || round.decimals > 28 {
+            return Err(OracleError::Source {
+                oracle: self.name().to_string(),
+                message: format!("Invalid answer {} with {} decimals for {}", round.answer, round.decimals, asset),
+            });
+        }
+
+        let updated_at = DateTime::<Utc>::from_timestamp(round.updated_at as i64, 0).unwrap_or_default();
+        let max_age = chrono::Duration::from_std(self.max_age).unwrap_or_else(|_| chrono::Duration::days(36_500));
+        if now - updated_at > max_age {
+            return Err(OracleError::Stale {
+                oracle: self.name().to_string(),
+                asset: asset.to_string(),
+                updated_at,
+            });
+        }
+
+        Ok(PriceQuote {
+            source: self.name().to_string(),
+            price: Decimal::from_i128_with_scale(round.answer, round.decimals as u32).normalize(),
+            observed_at: updated_at,
+        })
+    }
+}
+
+#[async_trait]
+impl PriceOracle for ChainlinkOracle {
+    fn name(&self) -> &str {
+        "chainlink"
+    }
+
+    async fn quote(&self, asset: &str) -> Result<Option<PriceQuote>, OracleError> {
+        let Some(feed) = self.feeds.get(asset) else { return Ok(None) };
+
+        let round = self.reader.latest_round(feed).await
+            .map_err(|message| OracleError::Source { oracle: self.name().to_string(), message })?;
+
+        self.to_quote(asset, round, Utc::now()).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedFeed(RoundData);
+
+    #[async_trait]
+    impl FeedReader for FixedFeed {
+        async fn latest_round(&self, _feed: &str) -> Result<RoundData, String> {
+            Ok(self.0)
+        }
+    }
+
+    fn oracle(round: RoundData) -> ChainlinkOracle {
+        ChainlinkOracle::new(
+            Arc::new(FixedFeed(round)),
+            HashMap::from([("UST10Y".to_string(), "0xfeed".to_string())]),
+            Duration::from_secs(3600),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_scales_answer_and_rejects_stale_rounds() {
+        let now = Utc::now().timestamp() as u64;
+
+        let fresh = oracle(RoundData { answer: 9_950_000_000, decimals: 8, updated_at: now });
+        let quote = fresh.quote("UST10Y").await.unwrap().expect("covered asset");
+        assert_eq!(quote.price, Decimal::new(995, 1));
+        assert!(fresh.quote("UST2Y").await.unwrap().is_none());
+
+        let stale = oracle(RoundData { answer: 9_950_000_000, decimals: 8, updated_at: now - 7200 });
+        assert!(matches!(stale.quote("UST10Y").await, Err(OracleError::Stale { .. })));
+
+        let negative = oracle(RoundData { answer: -1, decimals: 8, updated_at: now });
+        assert!(matches!(negative.quote("UST10Y").await, Err(OracleError::Source { .. })));
+    }
+}
@@ -0,0 +1,235 @@
+// Foreign exchange rates for valuing amounts in a reporting currency
+use crate::{aggregator::env_number, OracleAggregator, OracleError};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use quantera_types::Currency;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+
+/// Units of `quote` per unit of `base`, as observed by `source`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FxRate {
+    pub base: Currency,
+    pub quote: Currency,
+    pub rate: Decimal,
+    pub source: String,
+    pub observed_at: DateTime<Utc>,
+}
+
+impl FxRate {
+    /// `EUR/USD` style name of the pair
+    pub fn pair(&self) -> String {
+        format!("{}/{}", self.base, self.quote)
+    }
+
+    fn inverse(&self) -> Option<FxRate> {
+        if self.rate.is_zero() {
+            return None;
+        }
+        Some(FxRate {
+            base: self.quote,
+            quote: self.base,
+            rate: Decimal::ONE / self.rate,
+            source: format!("{} (inverted)", self.source),
+            observed_at: self.observed_at,
+        })
+    }
+}
+
+/// A source of exchange rates
+#[async_trait]
+pub trait FxRateProvider: Send + Sync {
+    /// Rate from `base` to `quote`, or `None` when this source does not quote the pair
+    async fn rate(&self, base: Currency, quote: Currency) -> Result<Option<FxRate>, OracleError>;
+}
+
+/// Rates from the price aggregator, quoted under keys like `USDEUR` (euros per dollar)
+#[async_trait]
+impl FxRateProvider for OracleAggregator {
+    async fn rate(&self, base: Currency, quote: Currency) -> Result<Option<FxRate>, OracleError> {
+        let key = format!("{}{}", base, quote);
+        let price = match self.price(&key).await {
+            Ok(price) => price,
+            Err(OracleError::NoPrice(_)) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        if price.disputed {
+            return Err(OracleError::Source { oracle: "aggregator".to_string(), message: format!("price sources disagree on {}", key) });
+        }
+        // A consensus rate is only as fresh as its oldest input
+        let observed_at = price.sources.iter().map(|quote| quote.observed_at).min().unwrap_or(price.aggregated_at);
+        let source = price.sources.iter().map(|quote| quote.source.as_str()).collect::<Vec<_>>().join(",");
+        Ok(Some(FxRate { base, quote, rate: price.price, source, observed_at }))
+    }
+}
+
+/// An amount that could not be converted into the reporting currency
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnconvertedAmount {
+    pub currency: Currency,
+    pub amount: Decimal,
+    pub reason: String,
+}
+
+/// A total in a reporting currency, with the rates it was converted at.
+///
+/// Amounts in currencies without a usable rate are left out of `total` and listed in
+/// `unconverted`; they are never counted at par.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportingTotal {
+    pub currency: Currency,
+    pub total: Decimal,
+    pub rates: Vec<FxRate>,
+    pub unconverted: Vec<UnconvertedAmount>,
+}
+
+impl ReportingTotal {
+    pub fn is_complete(&self) -> bool {
+        self.unconverted.is_empty()
+    }
+}
+
+/// Exchange rates from a provider, cached for a short time.
+///
+/// A missing direct rate is derived from the inverse pair. Rates observed longer ago than
+/// `max_age` are refused rather than used.
+pub struct FxRateService {
+    provider: Arc<dyn FxRateProvider>,
+    cache: RwLock<HashMap<(Currency, Currency), (FxRate, DateTime<Utc>)>>,
+    cache_ttl: Duration,
+    max_age: Duration,
+}
+
+impl FxRateService {
+    pub fn new(provider: Arc<dyn FxRateProvider>, cache_ttl: Duration, max_age: Duration) -> Self {
+        Self { provider, cache: RwLock::new(HashMap::new()), cache_ttl, max_age }
+    }
+
+    /// Build from environment:
+    ///   FX_RATE_CACHE_SECS    how long a fetched rate is reused (default 60)
+    ///   FX_RATE_MAX_AGE_SECS  rates observed longer ago than this are refused (default 3600)
+    pub fn from_env(provider: Arc<dyn FxRateProvider>) -> Result<Self, OracleError> {
+        let cache_ttl = Duration::seconds(env_number("FX_RATE_CACHE_SECS", 60)? as i64);
+        let max_age = Duration::seconds(env_number("FX_RATE_MAX_AGE_SECS", 3_600)? as i64);
+        Ok(Self::new(provider, cache_ttl, max_age))
+    }
+
+    /// Units of `quote` per unit of `base`
+    pub async fn rate(&self, base: Currency, quote: Currency) -> Result<FxRate, OracleError> {
+        let now = Utc::now();
+        if base == quote {
+            return Ok(FxRate { base, quote, rate: Decimal::ONE, source: "identity".to_string(), observed_at: now });
+        }
+        let cached = self.cache.read().ok().and_then(|cache| cache.get(&(base, quote)).cloned());
+        if let Some((rate, fetched_at)) = cached {
+            if now - fetched_at < self.cache_ttl {
+                return Ok(rate);
+            }
+        }
+
+        let rate = match self.provider.rate(base, quote).await? {
+            Some(rate) => rate,
+            None => self.provider.rate(quote, base).await?
+                .and_then(|inverse| inverse.inverse())
+                .ok_or_else(|| OracleError::NoPrice(format!("{}/{}", base, quote)))?,
+        };
+        if now - rate.observed_at > self.max_age {
+            return Err(OracleError::Stale { oracle: rate.source.clone(), asset: rate.pair(), updated_at: rate.observed_at });
+        }
+        if let Ok(mut cache) = self.cache.write() {
+            cache.insert((base, quote), (rate.clone(), now));
+        }
+        Ok(rate)
+    }
+
+    /// Sum `amounts` in `reporting`, converting each currency once at its current rate
+    pub async fn total(&self, amounts: impl IntoIterator<Item = (Currency, Decimal)>, reporting: Currency) -> ReportingTotal {
+        let mut by_currency: BTreeMap<Currency, Decimal> = BTreeMap::new();
+        for (currency, amount) in amounts {
+            *by_currency.entry(currency).or_insert(Decimal::ZERO) += amount;
+        }
+
+        let mut total = ReportingTotal { currency: reporting, total: Decimal::ZERO, rates: Vec::new(), unconverted: Vec::new() };
+        for (currency, amount) in by_currency {
+            if currency == reporting {
+                total.total += amount;
+                continue;
+            }
+            match self.rate(currency, reporting).await {
+                Ok(rate) => {
+                    total.total += amount * rate.rate;
+                    total.rates.push(rate);
+                }
+                Err(e) => total.unconverted.push(UnconvertedAmount { currency, amount, reason: e.to_string() }),
+            }
+        }
+        total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Quotes only the listed pairs; counts lookups
+    struct Quoted(Vec<(Currency, Currency, Decimal, DateTime<Utc>)>, AtomicUsize);
+
+    #[async_trait]
+    impl FxRateProvider for Quoted {
+        async fn rate(&self, base: Currency, quote: Currency) -> Result<Option<FxRate>, OracleError> {
+            self.1.fetch_add(1, Ordering::SeqCst);
+            Ok(self.0.iter()
+                .find(|(b, q, _, _)| *b == base && *q == quote)
+                .map(|(_, _, rate, observed_at)| FxRate { base, quote, rate: *rate, source: "test".to_string(), observed_at: *observed_at }))
+        }
+    }
+
+    fn service(quotes: Vec<(Currency, Currency, Decimal, DateTime<Utc>)>) -> (FxRateService, Arc<Quoted>) {
+        let provider = Arc::new(Quoted(quotes, AtomicUsize::new(0)));
+        (FxRateService::new(provider.clone(), Duration::seconds(60), Duration::hours(1)), provider)
+    }
+
+    #[tokio::test]
+    async fn test_mixed_currencies_total_with_rates_disclosed() {
+        let now = Utc::now();
+        let jpy: Currency = "JPY".parse().unwrap();
+        let (fx, provider) = service(vec![(Currency::EUR, Currency::USD, Decimal::new(108, 2), now)]);
+
+        let amounts = vec![
+            (Currency::USD, Decimal::from(1_000)),
+            (Currency::EUR, Decimal::from(500)),
+            (Currency::EUR, Decimal::from(250)),
+            (jpy, Decimal::from(100_000)),
+        ];
+        let usd = fx.total(amounts.clone(), Currency::USD).await;
+        assert_eq!(usd.total, Decimal::from(1_810));
+        assert_eq!(usd.rates.len(), 1);
+        assert_eq!(usd.rates[0].pair(), "EUR/USD");
+        // No yen rate: reported on its own, not counted at par
+        assert_eq!(usd.unconverted, vec![UnconvertedAmount { currency: jpy, amount: Decimal::from(100_000), reason: "No price source quoted JPY/USD".to_string() }]);
+        assert!(!usd.is_complete());
+
+        // The inverse pair serves euro reporting; 1000 / 1.08 = 925.925...
+        let eur = fx.total(amounts, Currency::EUR).await;
+        assert_eq!(eur.total.round_dp(2), Decimal::new(167593, 2));
+        assert_eq!(eur.rates[0].source, "test (inverted)");
+
+        // EUR/USD came from the cache the second time round
+        let lookups = provider.1.load(Ordering::SeqCst);
+        fx.rate(Currency::EUR, Currency::USD).await.unwrap();
+        assert_eq!(provider.1.load(Ordering::SeqCst), lookups);
+    }
+
+    #[tokio::test]
+    async fn test_stale_rates_are_refused() {
+        let (fx, _) = service(vec![(Currency::EUR, Currency::USD, Decimal::new(108, 2), Utc::now() - Duration::hours(2))]);
+        assert!(matches!(fx.rate(Currency::EUR, Currency::USD).await, Err(OracleError::Stale { .. })));
+
+        let total = fx.total(vec![(Currency::EUR, Decimal::from(10))], Currency::USD).await;
+        assert_eq!(total.total, Decimal::ZERO);
+        assert_eq!(total.unconverted.len(), 1);
+    }
+}
@@ -0,0 +1,73 @@
+// HTTP price API source
+use crate::{OracleError, PriceOracle, PriceQuote};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Prices from a JSON API answering `GET {base_url}/{asset}` with `{"price": ..., "timestamp": ...}`.
+///
+/// The price may be a string or a number; the optional timestamp is unix seconds. A 404
+/// means the API does not cover the asset.
+pub struct HttpPriceOracle {
+    name: String,
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl HttpPriceOracle {
+    pub fn new(name: &str, base_url: &str, timeout: Duration) -> Result<Self, OracleError> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| OracleError::Config(format!("HTTP client for {}: {}", name, e)))?;
+
+        Ok(Self {
+            name: name.to_string(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client,
+        })
+    }
+
+    fn error(&self, message: String) -> OracleError {
+        OracleError::Source { oracle: self.name.clone(), message }
+    }
+}
+
+#[async_trait]
+impl PriceOracle for HttpPriceOracle {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn quote(&self, asset: &str) -> Result<Option<PriceQuote>, OracleError> {
+        let response = self.client.get(format!("{}/{}", self.base_url, asset))
+            .send()
+            .await
+            .map_err(|e| self.error(e.to_string()))?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let body: serde_json::Value = response.error_for_status()
+            .map_err(|e| self.error(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| self.error(format!("Invalid response for {}: {}", asset, e)))?;
+
+        let price = match &body["price"] {
+            serde_json::Value::String(price) => Decimal::from_str(price).ok(),
+            serde_json::Value::Number(price) => Decimal::from_str(&price.to_string()).ok(),
+            _ => None,
+        }
+            .filter(|price| *price > Decimal::ZERO)
+            .ok_or_else(|| self.error(format!("Missing or invalid price for {}", asset)))?;
+
+        let observed_at = body["timestamp"].as_i64()
+            .and_then(|secs| DateTime::<Utc>::from_timestamp(secs, 0))
+            .unwrap_or_else(Utc::now);
+
+        Ok(Some(PriceQuote { source: self.name.clone(), price, observed_at }))
+    }
+}
@@ -0,0 +1,81 @@
+// Asset price oracle adapters with median aggregation
+//
+// Every configured oracle is queried for an asset; quotes too far from the median are
+// discarded and the median of the rest is returned with a confidence score. Sources that
+// disagree beyond a hard threshold mark the price as disputed, which callers must treat
+// as blocking automatic on-chain updates.
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+mod aggregator;
+pub use aggregator::{
+    AggregatedPrice,
+    AggregatorConfig,
+    OracleAggregator,
+    SourceFailure,
+};
+
+mod chainlink;
+pub use chainlink::{
+    ChainlinkOracle,
+    FeedReader,
+    RoundData,
+};
+
+mod fx;
+pub use fx::{
+    FxRate,
+    FxRateProvider,
+    FxRateService,
+    ReportingTotal,
+    UnconvertedAmount,
+};
+
+mod http;
+pub use http::HttpPriceOracle;
+
+mod manual;
+pub use manual::{
+    ManualOverride,
+    ManualOverrideStore,
+};
+
+/// Errors from price sources and aggregation
+#[derive(Debug, Error)]
+pub enum OracleError {
+    #[error("Price source {oracle} failed: {message}")]
+    Source { oracle: String, message: String },
+
+    #[error("Stale price from {oracle} for {asset}: last updated {updated_at}")]
+    Stale { oracle: String, asset: String, updated_at: DateTime<Utc> },
+
+    #[error("No price source quoted {0}")]
+    NoPrice(String),
+
+    #[error("Invalid oracle configuration: {0}")]
+    Config(String),
+}
+
+/// One source's price for an asset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceQuote {
+    pub source: String,
+    pub price: Decimal,
+    pub observed_at: DateTime<Utc>,
+}
+
+/// A source of asset prices.
+///
+/// Assets are identified by the caller's own key (an address, a token id, an asset id);
+/// each oracle maps the keys it knows about to its feeds.
+#[async_trait]
+pub trait PriceOracle: Send + Sync {
+    /// Name reported in quotes and failures
+    fn name(&self) -> &str;
+
+    /// Current price, or `None` when this source does not cover the asset
+    async fn quote(&self, asset: &str) -> Result<Option<PriceQuote>, OracleError>;
+}
@@ -0,0 +1,90 @@
+// Operator-set price overrides
+use crate::{OracleError, PriceOracle, PriceQuote};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// A price set by an operator, e.g. while a feed is broken or an asset is unlisted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManualOverride {
+    pub price: Decimal,
+    pub set_by: String,
+    pub reason: String,
+    #[serde(default = "Utc::now")]
+    pub set_at: DateTime<Utc>,
+    /// Overrides without an expiry stay until cleared
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl ManualOverride {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.map_or(true, |expires_at| now < expires_at)
+    }
+}
+
+/// Manual price overrides by asset.
+///
+/// The aggregator gives an active override precedence over every other source.
+#[derive(Debug, Default)]
+pub struct ManualOverrideStore {
+    overrides: RwLock<HashMap<String, ManualOverride>>,
+}
+
+impl ManualOverrideStore {
+    /// Load overrides from a JSON object keyed by asset
+    pub fn from_file(path: &str) -> Result<Self, OracleError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| OracleError::Config(format!("Cannot read price overrides {}: {}", path, e)))?;
+        let overrides: HashMap<String, ManualOverride> = serde_json::from_str(&contents)
+            .map_err(|e| OracleError::Config(format!("Invalid price overrides {}: {}", path, e)))?;
+
+        if let Some((asset, _)) = overrides.iter().find(|(_, o)| o.price <= Decimal::ZERO) {
+            return Err(OracleError::Config(format!("Override price for {} must be positive", asset)));
+        }
+
+        Ok(Self { overrides: RwLock::new(overrides) })
+    }
+
+    pub fn set(&self, asset: &str, manual_override: ManualOverride) -> Result<(), OracleError> {
+        if manual_override.price <= Decimal::ZERO {
+            return Err(OracleError::Config(format!("Override price for {} must be positive", asset)));
+        }
+        self.overrides.write().unwrap().insert(asset.to_string(), manual_override);
+        Ok(())
+    }
+
+    pub fn clear(&self, asset: &str) -> Option<ManualOverride> {
+        self.overrides.write().unwrap().remove(asset)
+    }
+
+    /// The asset's override if it has not expired
+    pub fn active(&self, asset: &str) -> Option<ManualOverride> {
+        self.overrides.read().unwrap()
+            .get(asset)
+            .filter(|o| o.is_active(Utc::now()))
+            .cloned()
+    }
+
+    pub fn list(&self) -> HashMap<String, ManualOverride> {
+        self.overrides.read().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl PriceOracle for ManualOverrideStore {
+    fn name(&self) -> &str {
+        "manual"
+    }
+
+    async fn quote(&self, asset: &str) -> Result<Option<PriceQuote>, OracleError> {
+        Ok(self.active(asset).map(|o| PriceQuote {
+            source: self.name().to_string(),
+            price: o.price,
+            observed_at: o.set_at,
+        }))
+    }
+}
@@ -0,0 +1,106 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Why a string is not a wallet address
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AddressError {
+    #[error("Wallet address must start with 0x")]
+    MissingPrefix,
+    #[error("Wallet address must be 42 characters")]
+    Length,
+    #[error("Wallet address contains invalid characters")]
+    InvalidHex,
+}
+
+/// A 20-byte account address, carried as a 0x-prefixed hex string.
+///
+/// Any letter case is accepted, including EIP-55 checksummed input; output is always
+/// lowercase, which is what every stack already emits and stores.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WalletAddress([u8; 20]);
+
+impl WalletAddress {
+    pub const fn new(bytes: [u8; 20]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 20] {
+        &self.0
+    }
+}
+
+impl FromStr for WalletAddress {
+    type Err = AddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s.strip_prefix("0x").ok_or(AddressError::MissingPrefix)?;
+        if s.len() != 42 {
+            return Err(AddressError::Length);
+        }
+        let mut bytes = [0u8; 20];
+        hex::decode_to_slice(digits, &mut bytes).map_err(|_| AddressError::InvalidHex)?;
+        Ok(Self(bytes))
+    }
+}
+
+impl fmt::Display for WalletAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{}", hex::encode(self.0))
+    }
+}
+
+impl Serialize for WalletAddress {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for WalletAddress {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl From<[u8; 20]> for WalletAddress {
+    fn from(bytes: [u8; 20]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<alloy_primitives::Address> for WalletAddress {
+    fn from(address: alloy_primitives::Address) -> Self {
+        Self(address.into_array())
+    }
+}
+
+impl From<WalletAddress> for alloy_primitives::Address {
+    fn from(address: WalletAddress) -> Self {
+        alloy_primitives::Address::from(address.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksummed_input_round_trips_lowercase() {
+        let address: WalletAddress = serde_json::from_str("\"0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed\"").unwrap();
+
+        let json = serde_json::to_string(&address).unwrap();
+
+        assert_eq!(json, "\"0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed\"");
+        assert_eq!(serde_json::from_str::<WalletAddress>(&json).unwrap(), address);
+        assert_eq!(alloy_primitives::Address::from(address).into_array(), *address.as_bytes());
+    }
+
+    #[test]
+    fn test_malformed_addresses_are_rejected() {
+        assert_eq!("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".parse::<WalletAddress>(), Err(AddressError::MissingPrefix));
+        assert_eq!("0x5aaeb6".parse::<WalletAddress>(), Err(AddressError::Length));
+        assert_eq!("0xzzaeb6053f3e94c9b9a09f33669435e7ef1beaed".parse::<WalletAddress>(), Err(AddressError::InvalidHex));
+    }
+}
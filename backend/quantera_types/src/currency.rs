@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// ISO 4217 alphabetic currency code, such as `USD` or `EUR`.
+///
+/// Records written before currencies were tracked are in US dollars, so that is the default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Currency([u8; 3]);
+
+impl Currency {
+    pub const USD: Currency = Currency(*b"USD");
+    pub const EUR: Currency = Currency(*b"EUR");
+
+    pub fn as_str(&self) -> &str {
+        // Only ever built from three ASCII letters
+        std::str::from_utf8(&self.0).unwrap_or("???")
+    }
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        Currency::USD
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = String;
+
+    /// Case-insensitive; codes are stored in uppercase
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s.trim().to_ascii_uppercase();
+        match code.as_bytes() {
+            &[a, b, c] if code.bytes().all(|byte| byte.is_ascii_uppercase()) => Ok(Currency([a, b, c])),
+            _ => Err(format!("invalid currency code {:?} (expected three letters, such as USD)", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Currency> for String {
+    fn from(currency: Currency) -> Self {
+        currency.as_str().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_parse_and_round_trip() {
+        assert_eq!("eur".parse::<Currency>().unwrap(), Currency::EUR);
+        assert_eq!(serde_json::to_string(&Currency::EUR).unwrap(), "\"EUR\"");
+        assert_eq!(serde_json::from_str::<Currency>("\"gbp\"").unwrap().to_string(), "GBP");
+        assert!("US".parse::<Currency>().is_err());
+        assert!("US1".parse::<Currency>().is_err());
+        assert!(serde_json::from_str::<Currency>("\"EURO\"").is_err());
+        assert_eq!(Currency::default(), Currency::USD);
+    }
+}
@@ -0,0 +1,93 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Error body returned by every API stack.
+///
+/// A superset of the shapes the stacks produced before sharing it: the backend's
+/// `{error, message, code}`, the secure API's request id and timestamp, and the treasury
+/// service's details and resumable attempt id. Optional parts are omitted when unset, so
+/// each stack's existing fields serialize exactly as before.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorEnvelope {
+    /// Machine-readable error kind, e.g. NOT_FOUND
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub error: String,
+    pub message: String,
+    /// HTTP status code
+    pub code: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// Free text from the treasury service, structured data from the secure API
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+    /// Failed treasury creation attempt, resumable by id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attempt_id: Option<String>,
+}
+
+impl ErrorEnvelope {
+    /// A new error stamped with the current time and a fresh request id
+    pub fn new(error: &str, message: &str, code: u16) -> Self {
+        Self {
+            error: error.to_string(),
+            message: message.to_string(),
+            code,
+            timestamp: Some(Utc::now()),
+            request_id: Some(Uuid::new_v4().to_string()),
+            details: None,
+            attempt_id: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: impl Into<serde_json::Value>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+
+    pub fn with_attempt_id(mut self, attempt_id: impl Into<String>) -> Self {
+        self.attempt_id = Some(attempt_id.into());
+        self
+    }
+
+    pub fn unauthorized() -> Self {
+        Self::new("UNAUTHORIZED", "Authentication required", 401)
+    }
+
+    pub fn forbidden() -> Self {
+        Self::new("FORBIDDEN", "Insufficient permissions", 403)
+    }
+
+    pub fn rate_limited() -> Self {
+        Self::new("RATE_LIMITED", "Too many requests", 429)
+    }
+
+    pub fn validation_error(message: &str) -> Self {
+        Self::new("VALIDATION_ERROR", message, 400)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unset_parts_are_omitted() {
+        let envelope = ErrorEnvelope {
+            error: String::new(),
+            message: "Resource not found".to_string(),
+            code: 404,
+            timestamp: None,
+            request_id: None,
+            details: Some("Treasury not found".into()),
+            attempt_id: None,
+        };
+
+        let json = serde_json::to_value(&envelope).unwrap();
+
+        assert_eq!(json, serde_json::json!({"message": "Resource not found", "code": 404, "details": "Treasury not found"}));
+        assert_eq!(serde_json::from_value::<ErrorEnvelope>(json).unwrap(), envelope);
+    }
+}
@@ -0,0 +1,317 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// How settled an on-chain operation is.
+///
+/// Pending operations are unmined or too shallow to rely on. Confirmed ones meet the
+/// chain's confirmation rule but can still be reorged out; Finalized ones cannot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum FinalityStatus {
+    #[default]
+    Pending,
+    Confirmed,
+    Finalized,
+}
+
+impl FinalityStatus {
+    /// Whether a reorg can still remove the operation
+    pub fn is_reorgable(&self) -> bool {
+        *self != FinalityStatus::Finalized
+    }
+}
+
+/// Block tags of the execution API, reported by nodes of proof-of-stake chains
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockTag {
+    Safe,
+    Finalized,
+}
+
+impl BlockTag {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BlockTag::Safe => "safe",
+            BlockTag::Finalized => "finalized",
+        }
+    }
+}
+
+/// When a block has reached one level of finality
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FinalityRule {
+    /// At least this many blocks deep, counting the block itself
+    Depth(u64),
+    /// At or below the block the node reports for the tag
+    Tag(BlockTag),
+}
+
+impl FinalityRule {
+    fn reached(&self, block: u64, heads: &ChainHeads) -> bool {
+        self.highest(heads).map_or(false, |highest| block <= highest)
+    }
+
+    /// Highest block meeting the rule
+    fn highest(&self, heads: &ChainHeads) -> Option<u64> {
+        match self {
+            FinalityRule::Depth(depth) => (heads.latest + 1).checked_sub(*depth),
+            FinalityRule::Tag(BlockTag::Safe) => heads.safe,
+            FinalityRule::Tag(BlockTag::Finalized) => heads.finalized,
+        }
+    }
+}
+
+impl FromStr for FinalityRule {
+    type Err = FinalityError;
+
+    /// A block count, or `safe` / `finalized` for the tag
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "safe" => Ok(FinalityRule::Tag(BlockTag::Safe)),
+            "finalized" => Ok(FinalityRule::Tag(BlockTag::Finalized)),
+            depth => depth.parse::<u64>().ok()
+                .filter(|depth| *depth > 0)
+                .map(FinalityRule::Depth)
+                .ok_or_else(|| FinalityError::InvalidPolicy(format!(
+                    "{} is not a positive block count, safe or finalized", s
+                ))),
+        }
+    }
+}
+
+impl fmt::Display for FinalityRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FinalityRule::Depth(depth) => write!(f, "{} blocks", depth),
+            FinalityRule::Tag(tag) => f.write_str(tag.as_str()),
+        }
+    }
+}
+
+/// Chain heads a finality decision is made against; tags are None when not fetched
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChainHeads {
+    pub latest: u64,
+    pub safe: Option<u64>,
+    pub finalized: Option<u64>,
+}
+
+/// When operations on one chain count as Confirmed and as Finalized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FinalityPolicy {
+    pub confirmed: FinalityRule,
+    pub finalized: FinalityRule,
+}
+
+impl FinalityPolicy {
+    /// Defaults for known chains. Ethereum and the rollups settling to it report safe and
+    /// finalized blocks; Polygon PoS reorgs deeper and is judged by depth, and Avalanche
+    /// finalizes blocks on acceptance.
+    pub fn for_chain(chain_id: u64) -> Self {
+        use FinalityRule::{Depth, Tag};
+        let (confirmed, finalized) = match chain_id {
+            // Ethereum, Sepolia, Optimism, Base, Arbitrum
+            1 | 11155111 | 10 | 8453 | 42161 => (Tag(BlockTag::Safe), Tag(BlockTag::Finalized)),
+            // Polygon PoS, Amoy
+            137 | 80002 => (Depth(64), Depth(256)),
+            // Avalanche C-Chain
+            43114 => (Depth(1), Depth(1)),
+            // BNB Smart Chain
+            56 => (Depth(15), Tag(BlockTag::Finalized)),
+            _ => (Depth(12), Depth(64)),
+        };
+        Self { confirmed, finalized }
+    }
+
+    /// The chain's defaults, with `FINALITY_CONFIRMED` and `FINALITY_FINALIZED` overriding
+    /// either rule as a block count, `safe` or `finalized`
+    pub fn from_env(chain_id: u64) -> Result<Self, FinalityError> {
+        let mut policy = Self::for_chain(chain_id);
+        if let Ok(rule) = std::env::var("FINALITY_CONFIRMED") {
+            policy.confirmed = rule.parse()?;
+        }
+        if let Ok(rule) = std::env::var("FINALITY_FINALIZED") {
+            policy.finalized = rule.parse()?;
+        }
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    pub fn validate(&self) -> Result<(), FinalityError> {
+        for rule in [self.confirmed, self.finalized] {
+            if rule == FinalityRule::Depth(0) {
+                return Err(FinalityError::InvalidPolicy("Depth must be at least one block".to_string()));
+            }
+        }
+        match (self.confirmed, self.finalized) {
+            (FinalityRule::Depth(confirmed), FinalityRule::Depth(finalized)) if finalized < confirmed => {
+                Err(FinalityError::InvalidPolicy(format!(
+                    "Finalized depth {} is shallower than confirmed depth {}", finalized, confirmed
+                )))
+            }
+            (FinalityRule::Tag(BlockTag::Finalized), FinalityRule::Tag(BlockTag::Safe)) => {
+                Err(FinalityError::InvalidPolicy("Confirmed cannot require the finalized tag when finalized uses safe".to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Tags the node must be asked for to apply this policy
+    pub fn tags(&self) -> Vec<BlockTag> {
+        let mut tags = Vec::new();
+        for rule in [self.confirmed, self.finalized] {
+            if let FinalityRule::Tag(tag) = rule {
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
+        }
+        tags
+    }
+
+    /// Finality of an operation mined in `block`
+    pub fn status(&self, block: u64, heads: &ChainHeads) -> FinalityStatus {
+        if self.finalized.reached(block, heads) {
+            FinalityStatus::Finalized
+        } else if self.confirmed.reached(block, heads) {
+            FinalityStatus::Confirmed
+        } else {
+            FinalityStatus::Pending
+        }
+    }
+
+    /// Highest Confirmed block, up to which event scanners read; None when no block is
+    /// Confirmed yet
+    pub fn confirmed_head(&self, heads: &ChainHeads) -> Option<u64> {
+        self.confirmed.highest(heads).map(|highest| highest.min(heads.latest))
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum FinalityError {
+    #[error("Invalid finality policy: {0}")]
+    InvalidPolicy(String),
+    #[error("Finalized operations cannot be downgraded")]
+    AlreadyFinal,
+    #[error("Only Confirmed operations can be downgraded, this one is {0:?}")]
+    NotConfirmed(FinalityStatus),
+    #[error("Operation moved from block {recorded} to {observed}; downgrade it before recording the new block")]
+    Reorged { recorded: String, observed: String },
+}
+
+/// Finality of one recorded operation and the block it was last seen in
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Finality {
+    pub status: FinalityStatus,
+    pub block_number: Option<u64>,
+    /// Hex block hash, compared on later checks to detect reorgs
+    pub block_hash: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl Finality {
+    /// Record the operation in a block with the status the policy gives it. The status never
+    /// moves backwards here; a Confirmed operation seen in a different block was reorged and
+    /// must be downgraded first. Returns whether anything changed.
+    pub fn observe(&mut self, status: FinalityStatus, block_number: u64, block_hash: &str) -> Result<bool, FinalityError> {
+        if let Some(recorded) = &self.block_hash {
+            if self.status >= FinalityStatus::Confirmed && !recorded.eq_ignore_ascii_case(block_hash) {
+                return Err(FinalityError::Reorged { recorded: recorded.clone(), observed: block_hash.to_string() });
+            }
+        }
+        let status = status.max(self.status);
+        if status == self.status && self.block_number == Some(block_number) {
+            return Ok(false);
+        }
+        self.status = status;
+        self.block_number = Some(block_number);
+        self.block_hash = Some(block_hash.to_string());
+        self.updated_at = Some(Utc::now());
+        Ok(true)
+    }
+
+    /// Return a Confirmed operation to Pending after its block was reorged out, forgetting
+    /// the block. The returned record of who did this and why belongs in the owner's audit trail.
+    pub fn downgrade(&mut self, reason: &str, actor: &str) -> Result<FinalityDowngrade, FinalityError> {
+        match self.status {
+            FinalityStatus::Finalized => Err(FinalityError::AlreadyFinal),
+            FinalityStatus::Pending => Err(FinalityError::NotConfirmed(self.status)),
+            FinalityStatus::Confirmed => {
+                let now = Utc::now();
+                let downgrade = FinalityDowngrade {
+                    from: self.status,
+                    block_number: self.block_number,
+                    block_hash: self.block_hash.clone(),
+                    reason: reason.to_string(),
+                    actor: actor.to_string(),
+                    downgraded_at: now,
+                };
+                *self = Finality { updated_at: Some(now), ..Finality::default() };
+                Ok(downgrade)
+            }
+        }
+    }
+}
+
+/// A Confirmed operation returned to Pending, kept with the record it applied to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FinalityDowngrade {
+    pub from: FinalityStatus,
+    /// Block the operation had been confirmed in
+    pub block_number: Option<u64>,
+    pub block_hash: Option<String>,
+    pub reason: String,
+    pub actor: String,
+    pub downgraded_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_depth_and_tag_rules() {
+        let heads = ChainHeads { latest: 1_000, safe: Some(968), finalized: Some(936) };
+
+        let polygon = FinalityPolicy::for_chain(137);
+        assert_eq!(polygon.status(1_000, &heads), FinalityStatus::Pending);
+        assert_eq!(polygon.status(937, &heads), FinalityStatus::Confirmed);
+        assert_eq!(polygon.status(745, &heads), FinalityStatus::Finalized);
+        assert_eq!(polygon.confirmed_head(&heads), Some(937));
+
+        let ethereum = FinalityPolicy::for_chain(1);
+        assert_eq!(ethereum.tags(), vec![BlockTag::Safe, BlockTag::Finalized]);
+        assert_eq!(ethereum.status(969, &heads), FinalityStatus::Pending);
+        assert_eq!(ethereum.status(968, &heads), FinalityStatus::Confirmed);
+        assert_eq!(ethereum.status(936, &heads), FinalityStatus::Finalized);
+        // Without the tags nothing is Confirmed
+        assert_eq!(ethereum.status(1, &ChainHeads { latest: 1_000, ..Default::default() }), FinalityStatus::Pending);
+
+        assert_eq!("finalized".parse::<FinalityRule>().unwrap(), FinalityRule::Tag(BlockTag::Finalized));
+        assert_eq!("12".parse::<FinalityRule>().unwrap(), FinalityRule::Depth(12));
+        assert!("0".parse::<FinalityRule>().is_err());
+        assert!(FinalityPolicy { confirmed: FinalityRule::Depth(64), finalized: FinalityRule::Depth(8) }.validate().is_err());
+    }
+
+    #[test]
+    fn test_reorg_requires_downgrade() {
+        let mut finality = Finality::default();
+        assert!(finality.observe(FinalityStatus::Confirmed, 500, "0xaa").unwrap());
+        assert!(!finality.observe(FinalityStatus::Confirmed, 500, "0xAA").unwrap());
+
+        assert!(matches!(finality.observe(FinalityStatus::Confirmed, 501, "0xbb"), Err(FinalityError::Reorged { .. })));
+        let downgrade = finality.downgrade("block 500 reorged", "finality-monitor").unwrap();
+        assert_eq!((downgrade.from, downgrade.block_number), (FinalityStatus::Confirmed, Some(500)));
+        assert_eq!(downgrade.block_hash.as_deref(), Some("0xaa"));
+        assert_eq!(finality.status, FinalityStatus::Pending);
+        assert_eq!(finality.block_hash, None);
+        assert_eq!(finality.downgrade("again", "ops").unwrap_err(), FinalityError::NotConfirmed(FinalityStatus::Pending));
+
+        assert!(finality.observe(FinalityStatus::Finalized, 501, "0xbb").unwrap());
+        assert_eq!(finality.downgrade("late", "ops").unwrap_err(), FinalityError::AlreadyFinal);
+    }
+}
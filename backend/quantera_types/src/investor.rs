@@ -0,0 +1,168 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Normalized spelling for status lookups: case-insensitive, underscores ignored
+fn normalize(s: &str) -> String {
+    s.chars().filter(|c| *c != '_').flat_map(char::to_lowercase).collect()
+}
+
+/// KYC progress of an investor.
+///
+/// Serialized in PascalCase, as both stacks always have. The backend's API also accepted
+/// snake_case, which is kept as an alias. The compliance service never reports NotStarted,
+/// UnderReview or Rejected, and the backend never reports Pending or Failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum KycStatus {
+    #[serde(alias = "not_started")]
+    NotStarted,
+    #[serde(alias = "pending")]
+    Pending,
+    #[serde(alias = "in_progress")]
+    InProgress,
+    /// Waiting on a compliance officer
+    #[serde(alias = "under_review")]
+    UnderReview,
+    #[serde(alias = "completed")]
+    Completed,
+    /// The verification provider could not verify the investor
+    #[serde(alias = "failed")]
+    Failed,
+    /// An officer declined the investor
+    #[serde(alias = "rejected")]
+    Rejected,
+    #[serde(alias = "expired")]
+    Expired,
+}
+
+impl KycStatus {
+    const ALL: [KycStatus; 8] = [
+        KycStatus::NotStarted,
+        KycStatus::Pending,
+        KycStatus::InProgress,
+        KycStatus::UnderReview,
+        KycStatus::Completed,
+        KycStatus::Failed,
+        KycStatus::Rejected,
+        KycStatus::Expired,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KycStatus::NotStarted => "NotStarted",
+            KycStatus::Pending => "Pending",
+            KycStatus::InProgress => "InProgress",
+            KycStatus::UnderReview => "UnderReview",
+            KycStatus::Completed => "Completed",
+            KycStatus::Failed => "Failed",
+            KycStatus::Rejected => "Rejected",
+            KycStatus::Expired => "Expired",
+        }
+    }
+}
+
+impl FromStr for KycStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let wanted = normalize(s);
+        Self::ALL
+            .into_iter()
+            .find(|status| normalize(status.as_str()) == wanted)
+            .ok_or_else(|| format!("Unknown KYC status: {}", s))
+    }
+}
+
+/// Ongoing AML standing of an investor.
+///
+/// The compliance service derives Clear, UnderReview and Flagged from transaction
+/// monitoring; Blocked and RequiresEnhancedDueDiligence are set through the backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AmlStatus {
+    #[serde(alias = "clear")]
+    Clear,
+    /// A severe monitoring alert is awaiting officer review
+    #[serde(alias = "under_review")]
+    UnderReview,
+    /// An officer confirmed suspicious activity
+    #[serde(alias = "flagged")]
+    Flagged,
+    #[serde(alias = "blocked")]
+    Blocked,
+    #[serde(alias = "requires_enhanced_due_diligence")]
+    RequiresEnhancedDueDiligence,
+}
+
+impl AmlStatus {
+    const ALL: [AmlStatus; 5] = [
+        AmlStatus::Clear,
+        AmlStatus::UnderReview,
+        AmlStatus::Flagged,
+        AmlStatus::Blocked,
+        AmlStatus::RequiresEnhancedDueDiligence,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AmlStatus::Clear => "Clear",
+            AmlStatus::UnderReview => "UnderReview",
+            AmlStatus::Flagged => "Flagged",
+            AmlStatus::Blocked => "Blocked",
+            AmlStatus::RequiresEnhancedDueDiligence => "RequiresEnhancedDueDiligence",
+        }
+    }
+}
+
+impl FromStr for AmlStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let wanted = normalize(s);
+        Self::ALL
+            .into_iter()
+            .find(|status| normalize(status.as_str()) == wanted)
+            .ok_or_else(|| format!("Unknown AML status: {}", s))
+    }
+}
+
+/// Identity verification state of a platform user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum VerificationStatus {
+    #[serde(alias = "unverified")]
+    Unverified,
+    #[serde(alias = "pending")]
+    Pending,
+    #[serde(alias = "verified")]
+    Verified,
+    #[serde(alias = "rejected")]
+    Rejected,
+    #[serde(alias = "suspended")]
+    Suspended,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statuses_round_trip_in_pascal_case() {
+        for status in KycStatus::ALL {
+            let json = serde_json::to_string(&status).unwrap();
+            assert_eq!(json, format!("\"{}\"", status.as_str()));
+            assert_eq!(serde_json::from_str::<KycStatus>(&json).unwrap(), status);
+            assert_eq!(status.as_str().parse::<KycStatus>().unwrap(), status);
+        }
+        for status in AmlStatus::ALL {
+            let json = serde_json::to_string(&status).unwrap();
+            assert_eq!(json, format!("\"{}\"", status.as_str()));
+            assert_eq!(serde_json::from_str::<AmlStatus>(&json).unwrap(), status);
+        }
+    }
+
+    #[test]
+    fn test_backend_query_spellings_parse() {
+        assert_eq!("not_started".parse::<KycStatus>().unwrap(), KycStatus::NotStarted);
+        assert_eq!("underreview".parse::<KycStatus>().unwrap(), KycStatus::UnderReview);
+        assert_eq!("REQUIRES_ENHANCED_DUE_DILIGENCE".parse::<AmlStatus>().unwrap(), AmlStatus::RequiresEnhancedDueDiligence);
+        assert!("cleared".parse::<AmlStatus>().is_err());
+    }
+}
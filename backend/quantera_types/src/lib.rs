@@ -0,0 +1,70 @@
+// Wire types shared by the Quantera API stacks and services
+//
+// The axum backend, the warp treasury service and the compliance service all exchange
+// these types as JSON. Keeping one definition here stops the stacks from drifting apart;
+// any change to a serialized name must stay readable by clients of every stack, so old
+// spellings are kept as serde aliases rather than renamed away.
+
+mod address;
+pub use address::{
+    AddressError,
+    WalletAddress,
+};
+
+mod currency;
+pub use currency::Currency;
+
+mod error;
+pub use error::ErrorEnvelope;
+
+mod finality;
+pub use finality::{
+    BlockTag,
+    ChainHeads,
+    Finality,
+    FinalityDowngrade,
+    FinalityError,
+    FinalityPolicy,
+    FinalityRule,
+    FinalityStatus,
+};
+
+mod investor;
+pub use investor::{
+    AmlStatus,
+    KycStatus,
+    VerificationStatus,
+};
+
+mod pagination;
+pub use pagination::{
+    clamp_range,
+    Page,
+    Pagination,
+    PaginationError,
+    SortDirection,
+    SortKey,
+    SortSpec,
+    DEFAULT_PER_PAGE,
+    MAX_PER_PAGE,
+};
+
+mod redact;
+pub use redact::{
+    redaction_salt_from_env,
+    set_redaction_salt,
+    unredacted,
+    Pii,
+    REDACTION_SALT_ENV,
+};
+
+mod treasury;
+pub use treasury::{
+    TreasuryMetadata,
+    TreasuryOverview,
+    TreasuryStatus,
+    TreasuryType,
+};
+
+// Serde helpers for token ids and U256 amounts, used with #[serde(with = "...")]
+pub mod wire;
@@ -0,0 +1,294 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::ops::Range;
+use thiserror::Error;
+
+/// Rows per page when a listing does not ask for a page size
+pub const DEFAULT_PER_PAGE: u32 = 20;
+
+/// Largest page any listing returns; larger requests are clamped to it
+pub const MAX_PER_PAGE: u32 = 100;
+
+/// Prefix of a decoded cursor, so that arbitrary hex is not mistaken for one
+const CURSOR_PREFIX: &str = "offset:";
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PaginationError {
+    #[error("page must be 1 or more")]
+    InvalidPage,
+    #[error("per_page must be 1 or more")]
+    InvalidPerPage,
+    #[error("page and cursor cannot be combined")]
+    PageAndCursor,
+    #[error("cursor is not valid")]
+    InvalidCursor,
+    #[error("cannot sort by {field}; sortable fields are {}", allowed.join(", "))]
+    UnknownSortField { field: String, allowed: Vec<&'static str> },
+    #[error("sort must list fields, each optionally prefixed with - or suffixed with :asc or :desc")]
+    InvalidSort,
+}
+
+impl PaginationError {
+    /// Machine-readable error kind for the API error body
+    pub fn code(&self) -> &'static str {
+        match self {
+            PaginationError::UnknownSortField { .. } | PaginationError::InvalidSort => "INVALID_SORT",
+            _ => "INVALID_PAGINATION",
+        }
+    }
+}
+
+/// Validated position and size of one page of a listing.
+///
+/// Built from a page number or from the `next_cursor` of a previous page. A page past the
+/// end of a listing is valid and simply empty, so clients can stop on an empty page or on a
+/// missing cursor alike.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pagination {
+    offset: usize,
+    per_page: u32,
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Self { offset: 0, per_page: DEFAULT_PER_PAGE }
+    }
+}
+
+impl Pagination {
+    /// From `page`/`per_page` or `cursor`/`per_page` query parameters; `page` is 1-based
+    pub fn new(page: Option<u32>, per_page: Option<u32>, cursor: Option<&str>) -> Result<Self, PaginationError> {
+        let per_page = match per_page {
+            Some(0) => return Err(PaginationError::InvalidPerPage),
+            Some(per_page) => per_page.min(MAX_PER_PAGE),
+            None => DEFAULT_PER_PAGE,
+        };
+        let offset = match (page, cursor) {
+            (Some(_), Some(_)) => return Err(PaginationError::PageAndCursor),
+            (Some(0), None) => return Err(PaginationError::InvalidPage),
+            (Some(page), None) => (page as usize - 1).saturating_mul(per_page as usize),
+            (None, Some(cursor)) => decode_cursor(cursor)?,
+            (None, None) => 0,
+        };
+        Ok(Self { offset, per_page })
+    }
+
+    /// From the `offset`/`limit` parameters older listings accept
+    pub fn from_offset(offset: usize, limit: usize) -> Result<Self, PaginationError> {
+        if limit == 0 {
+            return Err(PaginationError::InvalidPerPage);
+        }
+        Ok(Self { offset, per_page: limit.min(MAX_PER_PAGE as usize) as u32 })
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn per_page(&self) -> u32 {
+        self.per_page
+    }
+
+    /// 1-based page number; a cursor taken with another page size lands on the page it starts in
+    pub fn page(&self) -> u32 {
+        u32::try_from(self.offset / self.per_page as usize + 1).unwrap_or(u32::MAX)
+    }
+
+    /// Indexes of this page within `len` rows, empty past the end
+    pub fn range(&self, len: usize) -> Range<usize> {
+        clamp_range(len, self.offset, self.per_page as usize)
+    }
+
+    pub fn slice<'a, T>(&self, items: &'a [T]) -> &'a [T] {
+        &items[self.range(items.len())]
+    }
+
+    /// This page of a fully loaded listing
+    pub fn paginate<T>(&self, mut items: Vec<T>) -> Page<T> {
+        let total_count = items.len();
+        let range = self.range(total_count);
+        items.truncate(range.end);
+        items.drain(..range.start);
+        self.page_of(items, total_count)
+    }
+
+    /// Envelope for rows already limited to this page, e.g. by the database
+    pub fn page_of<T>(&self, data: Vec<T>, total_count: usize) -> Page<T> {
+        let per_page = self.per_page as usize;
+        let next = self.offset.saturating_add(per_page);
+        Page {
+            data,
+            total_count,
+            page: self.page(),
+            per_page: self.per_page,
+            total_pages: u32::try_from(total_count.div_ceil(per_page)).unwrap_or(u32::MAX),
+            next_cursor: (next < total_count).then(|| encode_cursor(next)),
+        }
+    }
+}
+
+/// `count` indexes from `start`, cut short at `len`
+pub fn clamp_range(len: usize, start: usize, count: usize) -> Range<usize> {
+    let start = start.min(len);
+    start..start.saturating_add(count).min(len)
+}
+
+fn encode_cursor(offset: usize) -> String {
+    hex::encode(format!("{}{}", CURSOR_PREFIX, offset))
+}
+
+fn decode_cursor(cursor: &str) -> Result<usize, PaginationError> {
+    let bytes = hex::decode(cursor).map_err(|_| PaginationError::InvalidCursor)?;
+    String::from_utf8(bytes).ok()
+        .and_then(|decoded| decoded.strip_prefix(CURSOR_PREFIX)?.parse().ok())
+        .ok_or(PaginationError::InvalidCursor)
+}
+
+/// One page of a listing, with what a client needs to fetch the rest
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub data: Vec<T>,
+    pub total_count: usize,
+    pub page: u32,
+    pub per_page: u32,
+    pub total_pages: u32,
+    /// Cursor of the following page; absent on the last page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            data: self.data.into_iter().map(f).collect(),
+            total_count: self.total_count,
+            page: self.page,
+            per_page: self.per_page,
+            total_pages: self.total_pages,
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortKey {
+    /// One of the listing's sortable fields
+    pub field: &'static str,
+    pub direction: SortDirection,
+}
+
+/// Sort order of a listing, limited to the fields that listing allows.
+///
+/// Written as comma-separated fields, each descending when prefixed with `-` or suffixed
+/// with `:desc`, e.g. `-created_at,name` or `created_at:desc,name:asc`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortSpec(Vec<SortKey>);
+
+impl SortSpec {
+    /// The requested order, or `default` when none was requested; an empty default keeps
+    /// the listing in the order it was loaded
+    pub fn parse(requested: Option<&str>, default: &str, allowed: &[&'static str]) -> Result<Self, PaginationError> {
+        let value = requested.map(str::trim).filter(|value| !value.is_empty()).unwrap_or(default);
+        let mut keys = Vec::new();
+        if value.is_empty() {
+            return Ok(Self(keys));
+        }
+        for term in value.split(',').map(str::trim) {
+            let (name, direction) = match term.split_once(':') {
+                Some((name, "asc")) => (name, SortDirection::Asc),
+                Some((name, "desc")) => (name, SortDirection::Desc),
+                Some(_) => return Err(PaginationError::InvalidSort),
+                None => match term.strip_prefix('-') {
+                    Some(name) => (name, SortDirection::Desc),
+                    None => (term, SortDirection::Asc),
+                },
+            };
+            if name.is_empty() {
+                return Err(PaginationError::InvalidSort);
+            }
+            let field = allowed.iter()
+                .find(|field| **field == name)
+                .ok_or_else(|| PaginationError::UnknownSortField { field: name.to_string(), allowed: allowed.to_vec() })?;
+            if !keys.iter().any(|key: &SortKey| key.field == *field) {
+                keys.push(SortKey { field, direction });
+            }
+        }
+        Ok(Self(keys))
+    }
+
+    pub fn keys(&self) -> &[SortKey] {
+        &self.0
+    }
+
+    /// Stable sort by each key in turn; `compare` orders two rows ascending by one field
+    pub fn sort<T>(&self, items: &mut [T], compare: impl Fn(&str, &T, &T) -> Ordering) {
+        items.sort_by(|a, b| {
+            self.0.iter()
+                .map(|key| match key.direction {
+                    SortDirection::Asc => compare(key.field, a, b),
+                    SortDirection::Desc => compare(key.field, b, a),
+                })
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_out_of_range_pages_are_empty_and_cursors_walk_the_listing() {
+        let items: Vec<u32> = (0..45).collect();
+
+        let last = Pagination::new(Some(3), Some(20), None).unwrap().paginate(items.clone());
+        assert_eq!(last.data, (40..45).collect::<Vec<_>>());
+        assert_eq!((last.total_pages, last.next_cursor), (3, None));
+
+        // Well past the end, including page numbers whose offset would overflow
+        let beyond = Pagination::new(Some(9), Some(20), None).unwrap().paginate(items.clone());
+        assert!(beyond.data.is_empty());
+        assert_eq!((beyond.page, beyond.total_count, beyond.total_pages), (9, 45, 3));
+        assert!(Pagination::new(Some(u32::MAX), Some(MAX_PER_PAGE), None).unwrap().slice(&items).is_empty());
+        assert_eq!(Pagination::new(Some(0), None, None), Err(PaginationError::InvalidPage));
+        assert_eq!(Pagination::new(None, Some(0), None), Err(PaginationError::InvalidPerPage));
+        assert_eq!(Pagination::new(None, Some(1000), None).unwrap().per_page(), MAX_PER_PAGE);
+
+        let first = Pagination::new(None, Some(20), None).unwrap().paginate(items.clone());
+        let cursor = first.next_cursor.unwrap();
+        let second = Pagination::new(None, Some(20), Some(&cursor)).unwrap().paginate(items);
+        assert_eq!((second.page, second.data[0]), (2, 20));
+        assert_eq!(Pagination::new(Some(2), None, Some(&cursor)), Err(PaginationError::PageAndCursor));
+        assert_eq!(Pagination::new(None, None, Some("zz")), Err(PaginationError::InvalidCursor));
+        assert_eq!(Pagination::new(None, None, Some(&hex::encode("42"))), Err(PaginationError::InvalidCursor));
+    }
+
+    #[test]
+    fn test_sort_is_limited_to_allowed_fields() {
+        const FIELDS: &[&str] = &["name", "created_at"];
+        let spec = SortSpec::parse(Some("-created_at,name"), "name", FIELDS).unwrap();
+        let mut rows = vec![("b", 1), ("a", 2), ("c", 2)];
+        spec.sort(&mut rows, |field, a, b| match field {
+            "name" => a.0.cmp(b.0),
+            _ => a.1.cmp(&b.1),
+        });
+        assert_eq!(rows, vec![("a", 2), ("c", 2), ("b", 1)]);
+
+        assert_eq!(SortSpec::parse(None, "created_at:desc", FIELDS).unwrap().keys(), &[SortKey {
+            field: "created_at",
+            direction: SortDirection::Desc,
+        }]);
+        let err = SortSpec::parse(Some("data_hash"), "name", FIELDS).unwrap_err();
+        assert_eq!(err.code(), "INVALID_SORT");
+        assert_eq!(err.to_string(), "cannot sort by data_hash; sortable fields are name, created_at");
+        assert_eq!(SortSpec::parse(Some("name:up"), "name", FIELDS), Err(PaginationError::InvalidSort));
+    }
+}
@@ -0,0 +1,123 @@
+// Redaction of personal data in log output
+//
+// Wallet addresses, investor ids, names and emails must not reach the logs in the clear.
+// Log them as structured fields wrapped in `Pii`, which keeps only a salted hash prefix:
+// the same value gives the same digest across services sharing the salt, so one
+// investor's lines can still be followed, but the value cannot be read back or looked up
+// without the salt. Correlation ids (request, review, alert, order and passport ids) are
+// not personal data and are logged as they are.
+//
+//     warn!(investor = %Pii::debug(&investor), alert_id = %alert.alert_id, "AML alert opened");
+//
+// Logging a raw value needs `unredacted`, which clippy.toml denies; a call site has to
+// carry an `#[allow(clippy::disallowed_methods)]` with its justification for review.
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Hex characters of the digest shown in logs
+const DIGEST_HEX_LEN: usize = 12;
+
+/// Environment variable every service reads its redaction salt from
+pub const REDACTION_SALT_ENV: &str = "LOG_REDACTION_SALT";
+
+static SALT: OnceLock<String> = OnceLock::new();
+
+/// Set the deployment's redaction salt, normally from LOG_REDACTION_SALT at startup.
+///
+/// Only the first call takes effect; returns false if a salt was already set. Without a
+/// salt digests are still one-way for names, but short inputs like wallet addresses can
+/// be matched by hashing candidates, so every deployment should set one.
+pub fn set_redaction_salt(salt: impl Into<String>) -> bool {
+    SALT.set(salt.into()).is_ok()
+}
+
+/// Set the redaction salt from LOG_REDACTION_SALT; returns false if it is unset or empty
+pub fn redaction_salt_from_env() -> bool {
+    match std::env::var(REDACTION_SALT_ENV) {
+        Ok(salt) if !salt.is_empty() => {
+            set_redaction_salt(salt);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// A personal value reduced to a salted digest for logging. The raw value is not kept.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Pii {
+    digest: String,
+}
+
+impl Pii {
+    /// Redact a value by its Display form. Case is ignored so checksummed and lowercase
+    /// spellings of an address give the same digest.
+    pub fn new(value: impl fmt::Display) -> Self {
+        Self::from_canonical(&value.to_string())
+    }
+
+    /// Redact a value by its Debug form, for ethers addresses whose Display is abbreviated
+    pub fn debug(value: impl fmt::Debug) -> Self {
+        Self::from_canonical(&format!("{:?}", value))
+    }
+
+    fn from_canonical(value: &str) -> Self {
+        let salt = SALT.get().map(String::as_str).unwrap_or_default();
+        let mut hasher = Sha256::new();
+        hasher.update(salt.as_bytes());
+        hasher.update([0u8]);
+        hasher.update(value.trim().to_lowercase().as_bytes());
+        let mut digest = hex::encode(hasher.finalize());
+        digest.truncate(DIGEST_HEX_LEN);
+        Self { digest }
+    }
+}
+
+impl fmt::Display for Pii {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pii:{}", self.digest)
+    }
+}
+
+impl fmt::Debug for Pii {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Pass a personal value to a log line in the clear. Denied by clippy.toml: only for
+/// cases the data handling policy allows, with the allow attribute explaining which.
+pub fn unredacted<T>(value: T) -> T {
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redacted_output_never_contains_the_value() {
+        let values = [
+            "0x7a16ff8270133f063aab6c9977183d9e72835428",
+            "investor-4821",
+            "Maria Fernanda Oliveira",
+            "maria.oliveira@example.com",
+        ];
+        for value in values {
+            let redacted = Pii::new(value);
+            for output in [redacted.to_string(), format!("{:?}", redacted), format!("{:>60}", redacted)] {
+                assert!(!output.to_lowercase().contains(&value.to_lowercase()), "{}", output);
+                assert!(value.split(' ').all(|part| !output.contains(part)), "{}", output);
+            }
+            assert_eq!(redacted.to_string().len(), "pii:".len() + DIGEST_HEX_LEN);
+        }
+    }
+
+    #[test]
+    fn test_digest_is_stable_and_case_insensitive() {
+        let checksummed = Pii::new("0x7A16fF8270133F063aAb6C9977183D9e72835428");
+        assert_eq!(checksummed, Pii::new("0x7a16ff8270133f063aab6c9977183d9e72835428"));
+        assert_eq!(Pii::debug(42u64), Pii::new(42u64));
+        assert_ne!(Pii::new("investor-1"), Pii::new("investor-2"));
+    }
+}
@@ -0,0 +1,146 @@
+use alloy_primitives::{Address, U256};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use crate::Currency;
+
+/// Treasury types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TreasuryType {
+    #[serde(alias = "tbill")]
+    TBill,
+    #[serde(alias = "tnote")]
+    TNote,
+    #[serde(alias = "tbond")]
+    TBond,
+}
+
+impl TreasuryType {
+    /// Classify by original term: bills mature within a year, notes within ten
+    pub fn for_term(issuance_date: u64, maturity_date: u64) -> Self {
+        const YEAR_SECS: u64 = 365 * 24 * 60 * 60;
+        match maturity_date.saturating_sub(issuance_date) {
+            term if term <= YEAR_SECS => TreasuryType::TBill,
+            term if term <= 10 * YEAR_SECS => TreasuryType::TNote,
+            _ => TreasuryType::TBond,
+        }
+    }
+}
+
+impl FromStr for TreasuryType {
+    type Err = String;
+
+    /// Case-insensitive, as query strings and the admin CLI spell it in lowercase
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "tbill" => Ok(TreasuryType::TBill),
+            "tnote" => Ok(TreasuryType::TNote),
+            "tbond" => Ok(TreasuryType::TBond),
+            other => Err(format!("unknown treasury type {} (expected tbill, tnote or tbond)", other)),
+        }
+    }
+}
+
+/// Treasury status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TreasuryStatus {
+    #[serde(alias = "active")]
+    Active,
+    #[serde(alias = "matured")]
+    Matured,
+    #[serde(alias = "redeemed")]
+    Redeemed,
+}
+
+impl FromStr for TreasuryStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "active" => Ok(TreasuryStatus::Active),
+            "matured" => Ok(TreasuryStatus::Matured),
+            "redeemed" => Ok(TreasuryStatus::Redeemed),
+            other => Err(format!("unknown status {} (expected active, matured or redeemed)", other)),
+        }
+    }
+}
+
+/// Treasury overview for listing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TreasuryOverview {
+    #[serde(with = "crate::wire::token_id")]
+    pub token_id: [u8; 32],
+    pub token_address: Address,
+    pub name: String,
+    pub symbol: String,
+    pub treasury_type: TreasuryType,
+    #[serde(with = "crate::wire::u256_decimal")]
+    pub current_price: U256,
+    pub yield_rate: u64,
+    pub maturity_date: u64,
+    pub status: TreasuryStatus,
+    /// Why the treasury's metadata was rejected; name and symbol are withheld when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validation_error: Option<String>,
+}
+
+/// Treasury metadata, as pinned at the token's metadata URI
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TreasuryMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub description: String,
+    pub issuer_name: String,
+    pub treasury_type: TreasuryType,
+    pub face_value: String,
+    /// Currency of the face value and of yield payments; documents pinned before
+    /// currencies were recorded are in US dollars
+    #[serde(default)]
+    pub currency: Currency,
+    pub issuance_date: u64,
+    pub maturity_date: u64,
+    pub yield_rate: u64,
+    pub image_uri: Option<String>,
+    pub external_url: Option<String>,
+    pub additional_details: Option<serde_json::Value>,
+    /// Tranche salt mixed into the token id, for instruments registered more than once
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tranche: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overview_round_trips() {
+        let overview = TreasuryOverview {
+            token_id: [7u8; 32],
+            token_address: Address::from([0xab; 20]),
+            name: "13 Week Bill".to_string(),
+            symbol: "TB13".to_string(),
+            treasury_type: TreasuryType::TBill,
+            current_price: U256::from(985_000_000u64),
+            yield_rate: 520,
+            maturity_date: 1_767_225_600,
+            status: TreasuryStatus::Active,
+            validation_error: None,
+        };
+
+        let json = serde_json::to_value(&overview).unwrap();
+
+        assert_eq!(json["token_id"], format!("0x{}", "07".repeat(32)));
+        assert_eq!(json["current_price"], "985000000");
+        assert_eq!(json["treasury_type"], "TBill");
+        assert_eq!(json["status"], "Active");
+        assert!(json.get("validation_error").is_none());
+        assert_eq!(serde_json::from_value::<TreasuryOverview>(json).unwrap(), overview);
+    }
+
+    #[test]
+    fn test_lowercase_spellings_are_accepted() {
+        assert_eq!(serde_json::from_str::<TreasuryType>("\"tnote\"").unwrap(), TreasuryType::TNote);
+        assert_eq!("TBOND".parse::<TreasuryType>().unwrap(), TreasuryType::TBond);
+        assert_eq!("matured".parse::<TreasuryStatus>().unwrap(), TreasuryStatus::Matured);
+        assert!("tstrip".parse::<TreasuryType>().is_err());
+    }
+}
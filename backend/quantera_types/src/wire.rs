@@ -0,0 +1,231 @@
+//! Serde helpers for on-chain identifiers and amounts.
+//!
+//! Token ids are written as 0x-prefixed 64-character hex and U256 amounts as decimal
+//! strings, so JSON clients never lose precision or have to decode byte arrays. For one
+//! release the readers also accept the old forms: token ids as 32-number arrays or bare
+//! hex, and amounts as 0x hex strings or JSON numbers.
+//!
+//! ```ignore
+//! #[serde(with = "quantera_types::wire::token_id")]
+//! pub token_id: [u8; 32],
+//! #[serde(with = "quantera_types::wire::u256_decimal")]
+//! pub price: U256,
+//! ```
+
+use alloy_primitives::U256;
+use serde::de::{self, SeqAccess, Visitor};
+use std::fmt;
+
+/// 0x-prefixed lowercase hex of a token id
+pub fn format_token_id(id: &[u8; 32]) -> String {
+    format!("0x{}", hex::encode(id))
+}
+
+/// Token id from hex, with or without the 0x prefix
+pub fn parse_token_id(s: &str) -> Result<[u8; 32], String> {
+    let digits = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")).unwrap_or(s);
+    if digits.len() != 64 {
+        return Err(format!("token id must be 64 hex characters, got {}", digits.len()));
+    }
+    let mut id = [0u8; 32];
+    hex::decode_to_slice(digits, &mut id).map_err(|e| format!("invalid token id: {}", e))?;
+    Ok(id)
+}
+
+/// U256 from a decimal string, or a 0x-prefixed hex string as previously serialized
+pub fn parse_u256(s: &str) -> Result<U256, String> {
+    let s = s.trim();
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(digits) => U256::from_str_radix(digits, 16),
+        None => U256::from_str_radix(s, 10),
+    }
+    .map_err(|e| format!("invalid amount {}: {}", s, e))
+}
+
+struct TokenIdVisitor;
+
+impl<'de> Visitor<'de> for TokenIdVisitor {
+    type Value = [u8; 32];
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a 0x-prefixed 64-character hex token id or an array of 32 bytes")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        parse_token_id(v).map_err(E::custom)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut id = [0u8; 32];
+        for (i, byte) in id.iter_mut().enumerate() {
+            *byte = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<de::IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(33, &self));
+        }
+        Ok(id)
+    }
+}
+
+struct U256Visitor;
+
+impl<'de> Visitor<'de> for U256Visitor {
+    type Value = U256;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a decimal string, a 0x-prefixed hex string or a non-negative integer")
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(U256::from(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        u64::try_from(v)
+            .map(U256::from)
+            .map_err(|_| E::invalid_value(de::Unexpected::Signed(v), &self))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        parse_u256(v).map_err(E::custom)
+    }
+}
+
+/// `[u8; 32]` token ids as 0x-prefixed hex
+pub mod token_id {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(id: &[u8; 32], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format_token_id(id))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; 32], D::Error> {
+        deserializer.deserialize_any(TokenIdVisitor)
+    }
+}
+
+/// `Option<[u8; 32]>` token ids as 0x-prefixed hex or null
+pub mod option_token_id {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct Wrapped(#[serde(with = "super::token_id")] [u8; 32]);
+
+    pub fn serialize<S: Serializer>(id: &Option<[u8; 32]>, serializer: S) -> Result<S::Ok, S::Error> {
+        id.map(Wrapped).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<[u8; 32]>, D::Error> {
+        Ok(Option::<Wrapped>::deserialize(deserializer)?.map(|Wrapped(id)| id))
+    }
+}
+
+/// U256 amounts as decimal strings
+pub mod u256_decimal {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &U256, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<U256, D::Error> {
+        deserializer.deserialize_any(U256Visitor)
+    }
+}
+
+/// `Option<U256>` amounts as decimal strings or null
+pub mod option_u256_decimal {
+    use alloy_primitives::U256;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct Wrapped(#[serde(with = "super::u256_decimal")] U256);
+
+    pub fn serialize<S: Serializer>(value: &Option<U256>, serializer: S) -> Result<S::Ok, S::Error> {
+        value.map(Wrapped).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<U256>, D::Error> {
+        Ok(Option::<Wrapped>::deserialize(deserializer)?.map(|Wrapped(value)| value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Sample {
+        #[serde(with = "token_id")]
+        token_id: [u8; 32],
+        #[serde(with = "u256_decimal")]
+        price: U256,
+        #[serde(default, with = "option_token_id")]
+        partition: Option<[u8; 32]>,
+        #[serde(default, with = "option_u256_decimal")]
+        average_price: Option<U256>,
+    }
+
+    #[test]
+    fn test_round_trips_through_new_format() {
+        let mut id = [0u8; 32];
+        id[0] = 0xab;
+        id[31] = 0x01;
+        let values = [U256::ZERO, U256::from(1u64), U256::from(u64::MAX), U256::MAX];
+
+        for price in values {
+            for partition in [None, Some([0xffu8; 32]), Some([0u8; 32])] {
+                let sample = Sample { token_id: id, price, partition, average_price: Some(price) };
+                let json = serde_json::to_value(&sample).unwrap();
+
+                assert_eq!(json["token_id"], format!("0xab{}01", "00".repeat(30)));
+                assert_eq!(json["price"], price.to_string());
+                assert_eq!(json["average_price"], price.to_string());
+                assert_eq!(serde_json::from_value::<Sample>(json).unwrap(), sample);
+            }
+        }
+
+        let empty = Sample { token_id: id, price: U256::ZERO, partition: None, average_price: None };
+        let json = serde_json::to_value(&empty).unwrap();
+        assert!(json["partition"].is_null() && json["average_price"].is_null());
+        assert_eq!(serde_json::from_value::<Sample>(json).unwrap(), empty);
+    }
+
+    #[test]
+    fn test_old_formats_still_parse() {
+        let bytes: Vec<u8> = (1..=32).collect();
+        let old = json!({
+            "token_id": bytes,
+            "price": "0x3ab5e840",
+            "partition": hex::encode([7u8; 32]),
+            "average_price": 985000000u64,
+        });
+
+        let sample: Sample = serde_json::from_value(old).unwrap();
+
+        assert_eq!(sample.token_id.to_vec(), bytes);
+        assert_eq!(sample.price, U256::from(985_000_000u64));
+        assert_eq!(sample.partition, Some([7u8; 32]));
+        assert_eq!(sample.average_price, Some(U256::from(985_000_000u64)));
+    }
+
+    #[test]
+    fn test_malformed_values_are_rejected() {
+        let id = format_token_id(&[1u8; 32]);
+        for bad in [
+            json!({ "token_id": "0x1234", "price": "1" }),
+            json!({ "token_id": vec![1u8; 31], "price": "1" }),
+            json!({ "token_id": vec![1u8; 33], "price": "1" }),
+            json!({ "token_id": format!("0x{}", "zz".repeat(32)), "price": "1" }),
+            json!({ "token_id": id, "price": "-1" }),
+            json!({ "token_id": id, "price": -1 }),
+            json!({ "token_id": id, "price": "1.5" }),
+        ] {
+            assert!(serde_json::from_value::<Sample>(bad.clone()).is_err(), "{} parsed", bad);
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! Payloads captured from each stack before the types were shared.
+//!
+//! Every fixture must still deserialize, and re-serializing must reproduce it exactly;
+//! a failure here means a client of one of the stacks would break. Legacy fixtures hold
+//! payloads in a superseded format, which must still parse for one release.
+
+use quantera_types::{
+    AmlStatus, ErrorEnvelope, KycStatus, TreasuryMetadata, TreasuryOverview, TreasuryStatus,
+    TreasuryType, VerificationStatus,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+fn fixture(name: &str) -> Value {
+    let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+/// Deserializes the captured payload and checks it serializes back unchanged
+fn assert_round_trip<T: Serialize + DeserializeOwned>(captured: Value) -> T {
+    let parsed: T = serde_json::from_value(captured.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&parsed).unwrap(), captured);
+    parsed
+}
+
+#[test]
+fn test_treasury_listing_payload() {
+    let listing: Vec<TreasuryOverview> = assert_round_trip(fixture("treasury_overview_list.json"));
+
+    assert_eq!(listing[0].treasury_type, TreasuryType::TBill);
+    assert_eq!(listing[0].token_id[31], 32);
+    assert_eq!(listing[0].current_price.to::<u64>(), 985_000_000);
+    assert_eq!(listing[1].status, TreasuryStatus::Matured);
+    assert!(listing[1].validation_error.is_some());
+}
+
+#[test]
+fn test_legacy_treasury_listing_still_parses() {
+    // Token ids as byte arrays and prices as 0x hex, as serialized before v2.1.0
+    let legacy: Vec<TreasuryOverview> =
+        serde_json::from_value(fixture("treasury_overview_list_legacy.json")).unwrap();
+    let current: Vec<TreasuryOverview> =
+        serde_json::from_value(fixture("treasury_overview_list.json")).unwrap();
+
+    assert_eq!(legacy, current);
+}
+
+#[test]
+fn test_treasury_metadata_payload() {
+    let metadata: TreasuryMetadata = assert_round_trip(fixture("treasury_metadata.json"));
+
+    assert_eq!(metadata.treasury_type, TreasuryType::TNote);
+    assert_eq!(metadata.tranche.as_deref(), Some("2025-A"));
+}
+
+#[test]
+fn test_error_payloads_from_every_stack() {
+    let treasury: ErrorEnvelope = assert_round_trip(fixture("treasury_service_error.json"));
+    assert_eq!(treasury.code, 409);
+    assert!(treasury.attempt_id.is_some());
+
+    let backend: ErrorEnvelope = assert_round_trip(fixture("backend_api_error.json"));
+    assert_eq!(backend.error, "INVALID_ASSET_TYPE");
+
+    let secure: ErrorEnvelope = assert_round_trip(fixture("secure_api_error.json"));
+    assert_eq!(secure.details.unwrap()["max_length"], 1000);
+}
+
+#[test]
+fn test_investor_status_payloads_from_every_stack() {
+    #[derive(Serialize, Deserialize)]
+    struct Statuses {
+        kyc_status: KycStatus,
+        aml_status: AmlStatus,
+    }
+
+    let captured = fixture("investor_statuses.json");
+    let backend: Vec<Statuses> = assert_round_trip(captured["backend"].clone());
+    let compliance: Vec<Statuses> = assert_round_trip(captured["compliance_service"].clone());
+    let treasury: Vec<VerificationStatus> = assert_round_trip(captured["treasury_service"].clone());
+
+    assert_eq!(backend[1].aml_status, AmlStatus::RequiresEnhancedDueDiligence);
+    assert_eq!(compliance[1].kyc_status, KycStatus::Failed);
+    assert_eq!(treasury[4], VerificationStatus::Suspended);
+}
@@ -0,0 +1,547 @@
+// Per-position entry prices from on-chain acquisition history
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use ethers::abi::{parse_abi, Token};
+use ethers::contract::BaseContract;
+use ethers::prelude::*;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tracing::{info, warn};
+use quantera_types::Pii;
+use crate::ethereum_client::{EthereumClient, Address};
+use crate::RiskServiceError;
+
+/// ERC-20 transfer event of the watched assets
+pub const TRANSFER_EVENT: &str = "Transfer(address,address,uint256)";
+
+/// TradingModule fill event; a transfer in the same transaction is a purchase
+pub const TRADE_EXECUTED_EVENT: &str = "TradeExecuted(bytes32,bytes32,bytes32,bytes32,bool)";
+
+/// Blocks requested per log query
+const SYNC_BLOCK_RANGE: u64 = 5_000;
+
+/// Token amounts and trade prices are 18-decimal fixed point
+const TOKEN_DECIMALS: u32 = 18;
+
+/// Where the price behind an entry price came from, from most to least reliable
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceProvenance {
+    /// Fill price of a trade
+    #[default]
+    Purchase,
+    /// Price history on the date of a transfer in from an unknown source
+    PriceHistory,
+    /// Transfer in with no price available; carried at the existing average
+    Unpriced,
+}
+
+impl PriceProvenance {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PriceProvenance::Purchase => "purchase",
+            PriceProvenance::PriceHistory => "price_history",
+            PriceProvenance::Unpriced => "unpriced",
+        }
+    }
+}
+
+impl FromStr for PriceProvenance {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "purchase" => Ok(PriceProvenance::Purchase),
+            "price_history" => Ok(PriceProvenance::PriceHistory),
+            "unpriced" => Ok(PriceProvenance::Unpriced),
+            other => Err(format!("Unknown price provenance: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AcquisitionKind {
+    /// Bought through the trading module at a known price
+    Purchase { price: Decimal },
+    /// Received from elsewhere, priced from history when available
+    TransferIn { price: Option<Decimal> },
+    /// Sold or transferred out
+    Disposal,
+}
+
+/// One change to a portfolio's holding of an asset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcquisitionEvent {
+    pub portfolio: Address,
+    pub asset: Address,
+    pub kind: AcquisitionKind,
+    pub amount: Decimal,
+    pub block_number: u64,
+    pub log_index: u64,
+    pub tx_hash: H256,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Weighted-average entry price of a holding
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CostBasis {
+    pub amount: Decimal,
+    pub entry_price: Decimal,
+    /// Least reliable provenance among the lots still held
+    pub provenance: PriceProvenance,
+}
+
+impl CostBasis {
+    /// Acquisitions move the average price; disposals reduce the amount at the same average
+    pub fn apply(&mut self, kind: &AcquisitionKind, amount: Decimal) {
+        match kind {
+            AcquisitionKind::Purchase { price } => self.add(amount, *price, PriceProvenance::Purchase),
+            AcquisitionKind::TransferIn { price: Some(price) } => self.add(amount, *price, PriceProvenance::PriceHistory),
+            AcquisitionKind::TransferIn { price: None } => {
+                let price = self.entry_price;
+                self.add(amount, price, PriceProvenance::Unpriced);
+            }
+            AcquisitionKind::Disposal => {
+                self.amount = (self.amount - amount).max(Decimal::ZERO);
+                if self.amount.is_zero() {
+                    *self = Self::default();
+                }
+            }
+        }
+    }
+
+    fn add(&mut self, amount: Decimal, price: Decimal, provenance: PriceProvenance) {
+        if amount <= Decimal::ZERO {
+            return;
+        }
+
+        let total = self.amount + amount;
+        self.entry_price = (self.amount * self.entry_price + amount * price) / total;
+        self.amount = total;
+        self.provenance = self.provenance.max(provenance);
+    }
+}
+
+/// Apply events to per-asset cost bases in chain order
+pub fn apply_events(bases: &mut HashMap<Address, CostBasis>, events: &[AcquisitionEvent]) {
+    let mut ordered: Vec<&AcquisitionEvent> = events.iter().collect();
+    ordered.sort_by_key(|event| (event.block_number, event.log_index));
+
+    for event in ordered {
+        bases.entry(event.asset).or_default().apply(&event.kind, event.amount);
+    }
+}
+
+/// Convert an 18-decimal on-chain amount
+pub fn from_base_units(value: U256) -> Result<Decimal, RiskServiceError> {
+    Decimal::from_scientific(&format!("{}e-{}", value, TOKEN_DECIMALS))
+        .map(|d| d.normalize())
+        .map_err(|e| RiskServiceError::CalculationError(format!("Amount {} out of range: {}", value, e)))
+}
+
+/// Latest accepted price of an asset on or before `date`; quarantined prices are skipped
+pub async fn price_on(db: &PgPool, asset: Address, date: NaiveDate) -> Result<Option<Decimal>, RiskServiceError> {
+    let row: Option<(String,)> = sqlx::query_as(r#"
+        SELECT price::text FROM asset_price_history
+        WHERE asset_address = $1 AND price_date <= $2 AND NOT quarantined
+        ORDER BY price_date DESC
+        LIMIT 1
+    "#)
+        .bind(format!("{:?}", asset))
+        .bind(date)
+        .fetch_optional(db)
+        .await?;
+
+    row.map(|(price,)| parse_decimal(&price)).transpose()
+}
+
+/// Most recent price of an asset
+pub async fn latest_price(db: &PgPool, asset: Address) -> Result<Option<Decimal>, RiskServiceError> {
+    price_on(db, asset, Utc::now().date_naive()).await
+}
+
+/// Persisted cost bases of a portfolio's open holdings
+pub async fn load_cost_basis(db: &PgPool, portfolio: Address) -> Result<HashMap<Address, CostBasis>, RiskServiceError> {
+    let rows: Vec<(String, String, String, String)> = sqlx::query_as(r#"
+        SELECT asset_address, amount::text, entry_price::text, provenance
+        FROM position_cost_basis
+        WHERE portfolio_address = $1 AND amount > 0
+    "#)
+        .bind(format!("{:?}", portfolio))
+        .fetch_all(db)
+        .await?;
+
+    rows.into_iter()
+        .map(|(asset, amount, entry_price, provenance)| {
+            let asset = asset.parse::<Address>()
+                .map_err(|e| RiskServiceError::CalculationError(format!("Invalid asset address {}: {}", asset, e)))?;
+            Ok((asset, CostBasis {
+                amount: parse_decimal(&amount)?,
+                entry_price: parse_decimal(&entry_price)?,
+                provenance: provenance.parse().map_err(RiskServiceError::CalculationError)?,
+            }))
+        })
+        .collect()
+}
+
+pub(crate) fn parse_decimal(value: &str) -> Result<Decimal, RiskServiceError> {
+    Decimal::from_str(value)
+        .map_err(|e| RiskServiceError::CalculationError(format!("Invalid decimal {}: {}", value, e)))
+}
+
+/// Ingests transfer and purchase events for watched portfolios and keeps their cost bases
+pub struct AcquisitionTracker {
+    eth_client: Arc<EthereumClient>,
+    db: Arc<PgPool>,
+    trading_module: Option<Address>,
+}
+
+impl AcquisitionTracker {
+    pub fn new(eth_client: Arc<EthereumClient>, db: Arc<PgPool>, trading_module: Option<Address>) -> Self {
+        Self { eth_client, db, trading_module }
+    }
+
+    /// Start tracking a portfolio; returns false if it was already watched
+    pub async fn watch(&self, portfolio: Address) -> Result<bool, RiskServiceError> {
+        let result = sqlx::query(r#"
+            INSERT INTO acquisition_watchlist (portfolio_address) VALUES ($1)
+            ON CONFLICT (portfolio_address) DO NOTHING
+        "#)
+            .bind(format!("{:?}", portfolio))
+            .execute(&*self.db)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Whether a portfolio is already tracked
+    pub async fn is_watched(&self, portfolio: Address) -> Result<bool, RiskServiceError> {
+        let row: Option<(i32,)> = sqlx::query_as("SELECT 1 FROM acquisition_watchlist WHERE portfolio_address = $1")
+            .bind(format!("{:?}", portfolio))
+            .fetch_optional(&*self.db)
+            .await?;
+
+        Ok(row.is_some())
+    }
+
+    /// Whether a portfolio's history has been synced at least once; a watched portfolio whose
+    /// backfill failed is not, and has no cost bases yet
+    pub async fn is_backfilled(&self, portfolio: Address) -> Result<bool, RiskServiceError> {
+        let row: Option<(i32,)> = sqlx::query_as(
+            "SELECT 1 FROM acquisition_watchlist WHERE portfolio_address = $1 AND last_synced_block IS NOT NULL"
+        )
+            .bind(format!("{:?}", portfolio))
+            .fetch_optional(&*self.db)
+            .await?;
+
+        Ok(row.is_some())
+    }
+
+    /// Sync every watched portfolio, returning the number of events ingested
+    pub async fn sync_all(&self) -> Result<usize, RiskServiceError> {
+        let portfolios: Vec<(String,)> = sqlx::query_as("SELECT portfolio_address FROM acquisition_watchlist")
+            .fetch_all(&*self.db)
+            .await?;
+
+        let mut ingested = 0;
+        for (portfolio,) in portfolios {
+            match portfolio.parse::<Address>() {
+                Ok(address) => ingested += self.sync_portfolio(address).await?,
+                Err(e) => warn!(portfolio = %Pii::new(&portfolio), error = %e, "Skipping invalid watched portfolio"),
+            }
+        }
+        Ok(ingested)
+    }
+
+    /// Ingest events since the last synced block and update the portfolio's cost bases
+    pub async fn sync_portfolio(&self, portfolio: Address) -> Result<usize, RiskServiceError> {
+        let last_synced: Option<(i64,)> = sqlx::query_as(
+            "SELECT last_synced_block FROM acquisition_watchlist WHERE portfolio_address = $1"
+        )
+            .bind(format!("{:?}", portfolio))
+            .fetch_optional(&*self.db)
+            .await?;
+        let mut from_block = last_synced.map(|(block,)| block as u64 + 1).unwrap_or(0);
+        let head = self.eth_client.confirmed_block_number().await.map_err(eth_error)?;
+
+        let mut events = Vec::new();
+        while from_block <= head {
+            let to_block = (from_block + SYNC_BLOCK_RANGE - 1).min(head);
+            events.extend(self.fetch_events(portfolio, from_block, to_block).await?);
+            from_block = to_block + 1;
+        }
+
+        let mut bases = load_cost_basis(&self.db, portfolio).await?;
+        apply_events(&mut bases, &events);
+        self.persist(portfolio, &events, &bases, head).await?;
+
+        if !events.is_empty() {
+            info!(events = events.len(), portfolio = %Pii::debug(portfolio), "Ingested acquisition events");
+        }
+        Ok(events.len())
+    }
+
+    async fn fetch_events(&self, portfolio: Address, from_block: u64, to_block: u64) -> Result<Vec<AcquisitionEvent>, RiskServiceError> {
+        let portfolio_topic = H256::from(portfolio);
+        let transfers = Filter::new()
+            .event(TRANSFER_EVENT)
+            .from_block(from_block)
+            .to_block(to_block);
+
+        let incoming = self.eth_client.get_events(&transfers.clone().topic2(portfolio_topic)).await.map_err(eth_error)?;
+        let outgoing = self.eth_client.get_events(&transfers.topic1(portfolio_topic)).await.map_err(eth_error)?;
+        let trades = self.trades_by_tx(from_block, to_block).await?;
+
+        let mut block_times = HashMap::new();
+        let mut events = Vec::with_capacity(incoming.len() + outgoing.len());
+
+        for (log, is_incoming) in incoming.into_iter().map(|log| (log, true)).chain(outgoing.into_iter().map(|log| (log, false))) {
+            let (Some(block_number), Some(tx_hash)) = (log.block_number, log.transaction_hash) else {
+                continue; // pending log
+            };
+            let block_number = block_number.as_u64();
+            let amount = from_base_units(U256::from_big_endian(&log.data))?;
+
+            let timestamp = match block_times.get(&block_number) {
+                Some(timestamp) => *timestamp,
+                None => {
+                    let secs = self.eth_client.block_timestamp(block_number).await.map_err(eth_error)?.unwrap_or_default();
+                    let timestamp = Utc.timestamp_opt(secs as i64, 0).single().unwrap_or_else(Utc::now);
+                    block_times.insert(block_number, timestamp);
+                    timestamp
+                }
+            };
+
+            let kind = if !is_incoming {
+                AcquisitionKind::Disposal
+            } else if let Some(price) = self.purchase_price(trades.get(&tx_hash), portfolio).await? {
+                AcquisitionKind::Purchase { price }
+            } else {
+                AcquisitionKind::TransferIn { price: price_on(&self.db, log.address, timestamp.date_naive()).await? }
+            };
+
+            events.push(AcquisitionEvent {
+                portfolio,
+                asset: log.address,
+                kind,
+                amount,
+                block_number,
+                log_index: log.log_index.map(|i| i.as_u64()).unwrap_or_default(),
+                tx_hash,
+                timestamp,
+            });
+        }
+
+        Ok(events)
+    }
+
+    /// Trade ids executed in each transaction of the block range
+    async fn trades_by_tx(&self, from_block: u64, to_block: u64) -> Result<HashMap<H256, H256>, RiskServiceError> {
+        let Some(trading_module) = self.trading_module else {
+            return Ok(HashMap::new());
+        };
+
+        let filter = Filter::new()
+            .address(trading_module)
+            .event(TRADE_EXECUTED_EVENT)
+            .from_block(from_block)
+            .to_block(to_block);
+        let logs = self.eth_client.get_events(&filter).await.map_err(eth_error)?;
+
+        Ok(logs.into_iter()
+            .filter_map(|log| Some((log.transaction_hash?, *log.topics.get(1)?)))
+            .collect())
+    }
+
+    /// Fill price of a trade in which the portfolio was the buyer
+    async fn purchase_price(&self, trade_id: Option<&H256>, portfolio: Address) -> Result<Option<Decimal>, RiskServiceError> {
+        let (Some(trading_module), Some(trade_id)) = (self.trading_module, trade_id) else {
+            return Ok(None);
+        };
+
+        let abi = BaseContract::from(parse_abi(&[
+            "function getTradeDetails(bytes32) view returns ((bytes32,bytes32,address,address,uint256,uint256,uint256,bool))",
+        ]).map_err(|e| RiskServiceError::EthereumError(e.to_string()))?);
+        let calldata = abi.encode("getTradeDetails", *trade_id)
+            .map_err(|e| RiskServiceError::EthereumError(e.to_string()))?;
+        let output = self.eth_client.call(trading_module, calldata).await.map_err(eth_error)?;
+
+        let trade = abi.decode_output_raw("getTradeDetails", output)
+            .map_err(|e| RiskServiceError::EthereumError(e.to_string()))?;
+        let Some(Token::Tuple(fields)) = trade.into_iter().next() else {
+            return Err(RiskServiceError::EthereumError("Unexpected getTradeDetails output".to_string()));
+        };
+
+        match (fields.get(2), fields.get(5)) {
+            (Some(Token::Address(buyer)), Some(Token::Uint(price))) if *buyer == portfolio => Ok(Some(from_base_units(*price)?)),
+            _ => Ok(None),
+        }
+    }
+
+    async fn persist(
+        &self,
+        portfolio: Address,
+        events: &[AcquisitionEvent],
+        bases: &HashMap<Address, CostBasis>,
+        synced_block: u64,
+    ) -> Result<(), RiskServiceError> {
+        let portfolio_key = format!("{:?}", portfolio);
+        let mut tx = self.db.begin().await?;
+
+        for event in events {
+            let (kind, price, provenance) = match &event.kind {
+                AcquisitionKind::Purchase { price } => ("purchase", Some(*price), Some(PriceProvenance::Purchase)),
+                AcquisitionKind::TransferIn { price: Some(price) } => ("transfer_in", Some(*price), Some(PriceProvenance::PriceHistory)),
+                AcquisitionKind::TransferIn { price: None } => ("transfer_in", None, Some(PriceProvenance::Unpriced)),
+                AcquisitionKind::Disposal => ("disposal", None, None),
+            };
+
+            sqlx::query(r#"
+                INSERT INTO position_acquisitions (
+                    portfolio_address, asset_address, tx_hash, log_index, block_number,
+                    kind, amount, price, provenance, occurred_at
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7::numeric, $8::numeric, $9, $10)
+                ON CONFLICT (portfolio_address, tx_hash, log_index) DO NOTHING
+            "#)
+                .bind(&portfolio_key)
+                .bind(format!("{:?}", event.asset))
+                .bind(format!("{:?}", event.tx_hash))
+                .bind(event.log_index as i64)
+                .bind(event.block_number as i64)
+                .bind(kind)
+                .bind(event.amount.to_string())
+                .bind(price.map(|p| p.to_string()))
+                .bind(provenance.map(|p| p.as_str()))
+                .bind(event.timestamp)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        for (asset, basis) in bases {
+            sqlx::query(r#"
+                INSERT INTO position_cost_basis (portfolio_address, asset_address, amount, entry_price, provenance, updated_at)
+                VALUES ($1, $2, $3::numeric, $4::numeric, $5, NOW())
+                ON CONFLICT (portfolio_address, asset_address) DO UPDATE SET
+                    amount = EXCLUDED.amount,
+                    entry_price = EXCLUDED.entry_price,
+                    provenance = EXCLUDED.provenance,
+                    updated_at = EXCLUDED.updated_at
+            "#)
+                .bind(&portfolio_key)
+                .bind(format!("{:?}", asset))
+                .bind(basis.amount.to_string())
+                .bind(basis.entry_price.to_string())
+                .bind(basis.provenance.as_str())
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query(r#"
+            INSERT INTO acquisition_watchlist (portfolio_address, last_synced_block) VALUES ($1, $2)
+            ON CONFLICT (portfolio_address) DO UPDATE SET last_synced_block = EXCLUDED.last_synced_block
+        "#)
+            .bind(&portfolio_key)
+            .bind(synced_block as i64)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+fn eth_error(e: ProviderError) -> RiskServiceError {
+    RiskServiceError::EthereumError(e.to_string())
+}
+
+/// Periodically sync all watched portfolios
+pub fn spawn_acquisition_sync(tracker: Arc<AcquisitionTracker>, every: std::time::Duration) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(every);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = tracker.sync_all().await {
+                tracing::error!("Acquisition sync failed: {}", e);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn event(asset: Address, block_number: u64, log_index: u64, kind: AcquisitionKind, amount: Decimal) -> AcquisitionEvent {
+        AcquisitionEvent {
+            portfolio: Address::repeat_byte(0x01),
+            asset,
+            kind,
+            amount,
+            block_number,
+            log_index,
+            tx_hash: H256::repeat_byte(block_number as u8),
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_weighted_average_over_split_purchases() {
+        let asset = Address::repeat_byte(0xaa);
+        let other = Address::repeat_byte(0xbb);
+
+        // Deliberately out of order; events are applied by block and log index
+        let events = vec![
+            event(asset, 12, 0, AcquisitionKind::Purchase { price: dec!(110) }, dec!(200)),
+            event(asset, 10, 3, AcquisitionKind::Purchase { price: dec!(100) }, dec!(100)),
+            event(other, 11, 0, AcquisitionKind::Purchase { price: dec!(5) }, dec!(10)),
+            event(asset, 13, 1, AcquisitionKind::Disposal, dec!(150)),
+            event(asset, 14, 0, AcquisitionKind::Purchase { price: dec!(90) }, dec!(50)),
+        ];
+
+        let mut bases = HashMap::new();
+        apply_events(&mut bases, &events);
+
+        // (100 * 100 + 200 * 110) / 300 = 106.666..., unchanged by the disposal of 150,
+        // then (150 * 106.666... + 50 * 90) / 200 = 102.5
+        let basis = &bases[&asset];
+        assert_eq!(basis.amount, dec!(200));
+        assert_eq!(basis.entry_price.round_dp(8), dec!(102.5));
+        assert_eq!(basis.provenance, PriceProvenance::Purchase);
+
+        assert_eq!(bases[&other].entry_price, dec!(5));
+    }
+
+    #[test]
+    fn test_transfer_in_provenance() {
+        let asset = Address::repeat_byte(0xaa);
+        let events = vec![
+            event(asset, 1, 0, AcquisitionKind::Purchase { price: dec!(100) }, dec!(100)),
+            event(asset, 2, 0, AcquisitionKind::TransferIn { price: Some(dec!(120)) }, dec!(100)),
+        ];
+
+        let mut bases = HashMap::new();
+        apply_events(&mut bases, &events);
+        assert_eq!(bases[&asset].entry_price, dec!(110));
+        assert_eq!(bases[&asset].provenance, PriceProvenance::PriceHistory);
+
+        // Unpriced transfers keep the average but are flagged
+        apply_events(&mut bases, &[event(asset, 3, 0, AcquisitionKind::TransferIn { price: None }, dec!(50))]);
+        assert_eq!(bases[&asset].amount, dec!(250));
+        assert_eq!(bases[&asset].entry_price, dec!(110));
+        assert_eq!(bases[&asset].provenance, PriceProvenance::Unpriced);
+
+        // Closing the position resets it
+        apply_events(&mut bases, &[event(asset, 4, 0, AcquisitionKind::Disposal, dec!(250))]);
+        assert_eq!(bases[&asset], CostBasis::default());
+    }
+
+    #[test]
+    fn test_from_base_units() {
+        assert_eq!(from_base_units(U256::from(1_500_000_000_000_000_000u128)).unwrap(), dec!(1.5));
+        assert_eq!(from_base_units(U256::zero()).unwrap(), Decimal::ZERO);
+    }
+}
@@ -0,0 +1,451 @@
+// Risk alert deduplication, auto-resolution and escalation
+use std::collections::HashMap;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tracing::warn;
+use uuid::Uuid;
+use crate::{RiskAlert, AlertType, AlertSeverity, AlertStatus, RiskServiceError};
+use crate::acquisitions::parse_decimal;
+use crate::ethereum_client::Address;
+
+/// Identity of an alert: one open alert per portfolio, alert type and limit
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AlertKey {
+    pub portfolio: Address,
+    pub alert_type: AlertType,
+    pub limit: String,
+}
+
+/// Outcome of checking one metric against one limit in a monitoring cycle
+#[derive(Debug, Clone)]
+pub struct LimitEvaluation {
+    pub alert_type: AlertType,
+    pub limit: String,
+    pub base_severity: AlertSeverity,
+    pub metric_value: Decimal,
+    pub threshold: Decimal,
+    pub message: String,
+    pub breached: bool,
+}
+
+/// Controls when open alerts resolve and escalate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertPolicy {
+    /// Consecutive in-limit cycles before an open alert auto-resolves
+    pub resolve_after_cycles: u32,
+    /// Breach magnitude steps (metric / threshold); each step crossed raises severity one level
+    pub escalation_steps: Vec<Decimal>,
+    /// Minimum seconds between notifications of an alert that stays open at the same severity
+    pub notify_cooldown_secs: u64,
+}
+
+impl Default for AlertPolicy {
+    fn default() -> Self {
+        Self {
+            resolve_after_cycles: 3,
+            escalation_steps: vec![Decimal::new(15, 1), Decimal::from(2)],
+            notify_cooldown_secs: 3600,
+        }
+    }
+}
+
+impl AlertPolicy {
+    /// Severity for a breach of the given magnitude, starting from the limit's base severity
+    pub fn severity_for(&self, base: AlertSeverity, metric_value: Decimal, threshold: Decimal) -> AlertSeverity {
+        if threshold <= Decimal::ZERO {
+            return base;
+        }
+
+        let magnitude = metric_value / threshold;
+        let steps = self.escalation_steps.iter()
+            .filter(|step| magnitude >= **step)
+            .count();
+
+        (0..steps).fold(base, |severity, _| severity.escalate())
+    }
+
+    /// Whether an alert last notified at `last_notified` is due to be notified again
+    pub fn cooldown_elapsed(&self, last_notified: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+        last_notified.map_or(true, |at| now - at >= Duration::seconds(self.notify_cooldown_secs as i64))
+    }
+}
+
+/// Single observation of a breach
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertOccurrence {
+    pub timestamp: DateTime<Utc>,
+    pub metric_value: Decimal,
+    pub severity: AlertSeverity,
+}
+
+/// Alert with its deduplication state and occurrence history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedAlert {
+    pub alert: RiskAlert,
+    pub within_limit_streak: u32,
+    pub history: Vec<AlertOccurrence>,
+    /// When the alert was last persisted and pushed to clients
+    #[serde(default)]
+    pub last_notified: Option<DateTime<Utc>>,
+    /// Set when this change should be persisted and pushed: the alert opened, escalated
+    /// or resolved, or the cooldown since its last notification has passed
+    #[serde(skip)]
+    pub notify: bool,
+}
+
+impl TrackedAlert {
+    fn mark_notified(&mut self, now: DateTime<Utc>) {
+        self.notify = true;
+        self.last_notified = Some(now);
+    }
+}
+
+/// In-memory view of open and resolved alerts
+#[derive(Debug, Default)]
+pub struct AlertTracker {
+    policy: AlertPolicy,
+    open: HashMap<AlertKey, TrackedAlert>,
+    resolved: Vec<TrackedAlert>,
+}
+
+impl AlertTracker {
+    pub fn new(policy: AlertPolicy) -> Self {
+        Self {
+            policy,
+            open: HashMap::new(),
+            resolved: Vec::new(),
+        }
+    }
+
+    /// Apply one monitoring cycle for a portfolio.
+    ///
+    /// Returns every alert whose state changed (opened, refreshed or resolved); only those
+    /// flagged `notify` need persisting and pushing, the rest are refreshes within the cooldown.
+    pub fn record_cycle(
+        &mut self,
+        portfolio: Address,
+        evaluations: Vec<LimitEvaluation>,
+        now: DateTime<Utc>,
+    ) -> Vec<TrackedAlert> {
+        let mut changed = Vec::new();
+
+        for evaluation in evaluations {
+            let key = AlertKey {
+                portfolio,
+                alert_type: evaluation.alert_type.clone(),
+                limit: evaluation.limit.clone(),
+            };
+
+            if evaluation.breached {
+                let severity = self.policy.severity_for(
+                    evaluation.base_severity.clone(),
+                    evaluation.metric_value,
+                    evaluation.threshold,
+                );
+
+                let tracked = self.open.entry(key).or_insert_with(|| TrackedAlert {
+                    alert: RiskAlert {
+                        id: Uuid::new_v4(),
+                        portfolio,
+                        alert_type: evaluation.alert_type.clone(),
+                        severity: severity.clone(),
+                        message: evaluation.message.clone(),
+                        metric_value: evaluation.metric_value,
+                        threshold: evaluation.threshold,
+                        timestamp: now,
+                        limit: evaluation.limit.clone(),
+                        status: AlertStatus::Open,
+                        occurrence_count: 0,
+                        first_seen: now,
+                        resolved_at: None,
+                    },
+                    within_limit_streak: 0,
+                    history: Vec::new(),
+                    last_notified: None,
+                    notify: false,
+                });
+
+                // Severity only ratchets up while the alert stays open
+                let escalated = severity > tracked.alert.severity;
+                if escalated {
+                    tracked.alert.severity = severity.clone();
+                }
+                tracked.alert.message = evaluation.message;
+                tracked.alert.metric_value = evaluation.metric_value;
+                tracked.alert.threshold = evaluation.threshold;
+                tracked.alert.timestamp = now;
+                tracked.alert.occurrence_count += 1;
+                tracked.within_limit_streak = 0;
+                tracked.history.push(AlertOccurrence {
+                    timestamp: now,
+                    metric_value: evaluation.metric_value,
+                    severity,
+                });
+
+                tracked.notify = false;
+                if escalated || self.policy.cooldown_elapsed(tracked.last_notified, now) {
+                    tracked.mark_notified(now);
+                }
+                changed.push(tracked.clone());
+            } else if let Some(tracked) = self.open.get_mut(&key) {
+                tracked.within_limit_streak += 1;
+                tracked.alert.metric_value = evaluation.metric_value;
+
+                if tracked.within_limit_streak >= self.policy.resolve_after_cycles {
+                    let mut resolved = self.open.remove(&key).expect("open alert present");
+                    resolved.alert.status = AlertStatus::Resolved;
+                    resolved.alert.resolved_at = Some(now);
+                    resolved.mark_notified(now);
+                    self.resolved.push(resolved.clone());
+                    changed.push(resolved);
+                }
+            }
+        }
+
+        changed
+    }
+
+    /// Resolve every portfolio's open alert on a limit without waiting for in-limit
+    /// cycles, for conditions that clear at a known moment (e.g. a stablecoin back on its peg)
+    pub fn resolve_all(&mut self, alert_type: AlertType, limit: &str, now: DateTime<Utc>) -> Vec<TrackedAlert> {
+        let keys: Vec<AlertKey> = self.open.keys()
+            .filter(|key| key.alert_type == alert_type && key.limit == limit)
+            .cloned()
+            .collect();
+
+        keys.into_iter()
+            .filter_map(|key| self.open.remove(&key))
+            .map(|mut resolved| {
+                resolved.alert.status = AlertStatus::Resolved;
+                resolved.alert.resolved_at = Some(now);
+                resolved.mark_notified(now);
+                self.resolved.push(resolved.clone());
+                resolved
+            })
+            .collect()
+    }
+
+    /// Take back alerts a previous run left open, so later breaches refresh them instead
+    /// of opening duplicates. They were last notified when last persisted.
+    pub fn restore(&mut self, alerts: Vec<RiskAlert>) {
+        for alert in alerts {
+            let key = AlertKey {
+                portfolio: alert.portfolio,
+                alert_type: alert.alert_type.clone(),
+                limit: alert.limit.clone(),
+            };
+            self.open.insert(key, TrackedAlert {
+                last_notified: Some(alert.timestamp),
+                alert,
+                within_limit_streak: 0,
+                history: Vec::new(),
+                notify: false,
+            });
+        }
+    }
+
+    /// Open alerts for a portfolio
+    pub fn open_alerts(&self, portfolio: Address) -> Vec<TrackedAlert> {
+        let mut alerts: Vec<TrackedAlert> = self.open.values()
+            .filter(|tracked| tracked.alert.portfolio == portfolio)
+            .cloned()
+            .collect();
+        alerts.sort_by(|a, b| b.alert.timestamp.cmp(&a.alert.timestamp));
+        alerts
+    }
+
+    /// Resolved alerts for a portfolio, most recent first
+    pub fn resolved_alerts(&self, portfolio: Address) -> Vec<TrackedAlert> {
+        self.resolved.iter()
+            .rev()
+            .filter(|tracked| tracked.alert.portfolio == portfolio)
+            .cloned()
+            .collect()
+    }
+}
+
+pub(crate) fn parse_severity(value: &str) -> Option<AlertSeverity> {
+    match value {
+        "Info" => Some(AlertSeverity::Info),
+        "Warning" => Some(AlertSeverity::Warning),
+        "Critical" => Some(AlertSeverity::Critical),
+        _ => None,
+    }
+}
+
+fn parse_alert_type(value: &str) -> Option<AlertType> {
+    match value {
+        "VaRBreach" => Some(AlertType::VaRBreach),
+        "DrawdownLimit" => Some(AlertType::DrawdownLimit),
+        "ConcentrationRisk" => Some(AlertType::ConcentrationRisk),
+        "LiquidityWarning" => Some(AlertType::LiquidityWarning),
+        "VolatilitySpike" => Some(AlertType::VolatilitySpike),
+        "DepegRisk" => Some(AlertType::DepegRisk),
+        "LeverageLimit" => Some(AlertType::LeverageLimit),
+        _ => None,
+    }
+}
+
+/// Alerts persisted as open, across all portfolios
+pub async fn load_open(db: &PgPool) -> Result<Vec<RiskAlert>, RiskServiceError> {
+    let rows: Vec<(Uuid, String, String, Option<String>, String, String, Option<String>, Option<String>, i32, DateTime<Utc>, DateTime<Utc>)> =
+        sqlx::query_as(r#"
+            SELECT id, portfolio_address, alert_type, limit_name, severity, message,
+                   metric_value::text, threshold::text, occurrence_count, created_at, last_seen_at
+            FROM risk_alerts
+            WHERE status = 'Open'
+        "#)
+        .fetch_all(db)
+        .await?;
+
+    let mut alerts = Vec::with_capacity(rows.len());
+    for (id, portfolio, alert_type, limit, severity, message, metric_value, threshold, occurrence_count, first_seen, last_seen) in rows {
+        let (Ok(portfolio), Some(alert_type), Some(limit), Some(severity)) =
+            (portfolio.parse::<Address>(), parse_alert_type(&alert_type), limit, parse_severity(&severity))
+        else {
+            warn!("Skipping unreadable open alert {}", id);
+            continue;
+        };
+        alerts.push(RiskAlert {
+            id,
+            portfolio,
+            alert_type,
+            severity,
+            message,
+            metric_value: metric_value.as_deref().map(parse_decimal).transpose()?.unwrap_or_default(),
+            threshold: threshold.as_deref().map(parse_decimal).transpose()?.unwrap_or_default(),
+            timestamp: last_seen,
+            limit,
+            status: AlertStatus::Open,
+            occurrence_count: occurrence_count.max(0) as u32,
+            first_seen,
+            resolved_at: None,
+        });
+    }
+    Ok(alerts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn var_breach(metric_value: Decimal, breached: bool) -> LimitEvaluation {
+        LimitEvaluation {
+            alert_type: AlertType::VaRBreach,
+            limit: "max_var_95".to_string(),
+            base_severity: AlertSeverity::Warning,
+            metric_value,
+            threshold: Decimal::new(10, 2),
+            message: format!("VaR (95%) exceeds limit: {} > 0.10", metric_value),
+            breached,
+        }
+    }
+
+    #[test]
+    fn test_persistent_breach_deduplicated() {
+        let mut tracker = AlertTracker::new(AlertPolicy::default());
+        let portfolio = Address::repeat_byte(1);
+        let start = Utc::now();
+
+        let mut ids = Vec::new();
+        for cycle in 0..5 {
+            let changed = tracker.record_cycle(
+                portfolio,
+                vec![var_breach(Decimal::new(12, 2), true)],
+                start + Duration::minutes(cycle),
+            );
+            assert_eq!(changed.len(), 1);
+            ids.push(changed[0].alert.id);
+        }
+
+        let open = tracker.open_alerts(portfolio);
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].alert.occurrence_count, 5);
+        assert_eq!(open[0].history.len(), 5);
+        assert!(ids.iter().all(|id| *id == ids[0]));
+    }
+
+    #[test]
+    fn test_auto_resolve_after_consecutive_cycles() {
+        let mut tracker = AlertTracker::new(AlertPolicy { resolve_after_cycles: 2, ..AlertPolicy::default() });
+        let portfolio = Address::repeat_byte(2);
+        let now = Utc::now();
+
+        tracker.record_cycle(portfolio, vec![var_breach(Decimal::new(12, 2), true)], now);
+        tracker.record_cycle(portfolio, vec![var_breach(Decimal::new(8, 2), false)], now);
+        // Breach again resets the streak
+        tracker.record_cycle(portfolio, vec![var_breach(Decimal::new(12, 2), true)], now);
+        tracker.record_cycle(portfolio, vec![var_breach(Decimal::new(8, 2), false)], now);
+        assert_eq!(tracker.open_alerts(portfolio).len(), 1);
+
+        let changed = tracker.record_cycle(portfolio, vec![var_breach(Decimal::new(8, 2), false)], now);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].alert.status, AlertStatus::Resolved);
+        assert!(tracker.open_alerts(portfolio).is_empty());
+        assert_eq!(tracker.resolved_alerts(portfolio)[0].alert.occurrence_count, 2);
+    }
+
+    #[test]
+    fn test_severity_escalates_with_breach_magnitude() {
+        let mut tracker = AlertTracker::new(AlertPolicy::default());
+        let portfolio = Address::repeat_byte(3);
+        let now = Utc::now();
+
+        let changed = tracker.record_cycle(portfolio, vec![var_breach(Decimal::new(11, 2), true)], now);
+        assert_eq!(changed[0].alert.severity, AlertSeverity::Warning);
+
+        // 0.16 / 0.10 = 1.6 crosses the first step
+        let changed = tracker.record_cycle(portfolio, vec![var_breach(Decimal::new(16, 2), true)], now);
+        assert_eq!(changed[0].alert.severity, AlertSeverity::Critical);
+
+        // Shrinking breach keeps the escalated severity
+        let changed = tracker.record_cycle(portfolio, vec![var_breach(Decimal::new(11, 2), true)], now);
+        assert_eq!(changed[0].alert.severity, AlertSeverity::Critical);
+    }
+
+    #[test]
+    fn test_refreshes_within_cooldown_are_not_notified() {
+        let mut tracker = AlertTracker::new(AlertPolicy::default());
+        let portfolio = Address::repeat_byte(4);
+        let start = Utc::now();
+        let breach = |tracker: &mut AlertTracker, value: i64, minutes: i64| {
+            tracker.record_cycle(portfolio, vec![var_breach(Decimal::new(value, 2), true)], start + Duration::minutes(minutes))
+        };
+
+        // Opened: notified
+        assert!(breach(&mut tracker, 12, 0)[0].notify);
+        // Still breached within the hour: refreshed but quiet
+        let changed = breach(&mut tracker, 12, 30);
+        assert!(!changed[0].notify);
+        assert_eq!(changed[0].alert.occurrence_count, 2);
+        // Escalation is always notified and restarts the cooldown
+        assert!(breach(&mut tracker, 16, 40)[0].notify);
+        assert!(!breach(&mut tracker, 16, 90)[0].notify);
+        // Cooldown elapsed
+        assert!(breach(&mut tracker, 16, 100)[0].notify);
+
+        let mut resolved = Vec::new();
+        for cycle in 0..3 {
+            resolved = tracker.record_cycle(portfolio, vec![var_breach(Decimal::new(8, 2), false)], start + Duration::minutes(101 + cycle));
+        }
+        assert_eq!(resolved[0].alert.status, AlertStatus::Resolved);
+        assert!(resolved[0].notify);
+    }
+
+    #[test]
+    fn test_restored_alert_keeps_identity() {
+        let mut tracker = AlertTracker::new(AlertPolicy::default());
+        let portfolio = Address::repeat_byte(5);
+        let now = Utc::now();
+        let previous = tracker.record_cycle(portfolio, vec![var_breach(Decimal::new(12, 2), true)], now - Duration::minutes(10));
+
+        let mut restarted = AlertTracker::new(AlertPolicy::default());
+        restarted.restore(vec![previous[0].alert.clone()]);
+        let changed = restarted.record_cycle(portfolio, vec![var_breach(Decimal::new(12, 2), true)], now);
+        assert_eq!(changed[0].alert.id, previous[0].alert.id);
+        assert_eq!(changed[0].alert.occurrence_count, 2);
+        assert!(!changed[0].notify);
+    }
+}
@@ -0,0 +1,451 @@
+// Anomaly gate between the oracle aggregator and the asset price history
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use price_oracle::AggregatedPrice;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::BTreeSet;
+use uuid::Uuid;
+use crate::acquisitions::parse_decimal;
+use crate::{DecimalExt, RiskServiceError};
+
+/// When a daily observation is held back for review instead of entering the history
+#[derive(Debug, Clone)]
+pub struct AnomalyConfig {
+    /// Largest move from the last accepted price, in multiples of recent daily volatility
+    pub max_move_sigmas: Decimal,
+    /// Days of accepted history the volatility is estimated from
+    pub lookback_days: i64,
+    /// Fewer returns than this in the lookback and moves are not checked
+    pub min_observations: usize,
+    /// Volatility floor, so a flat series does not quarantine every tick
+    pub min_volatility: Decimal,
+    /// How close a confirming source must quote to the quarantined price, in basis points
+    pub confirmation_tolerance_bps: u32,
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        Self {
+            max_move_sigmas: dec!(6),
+            lookback_days: 30,
+            min_observations: 10,
+            min_volatility: dec!(0.005),
+            confirmation_tolerance_bps: 100,
+        }
+    }
+}
+
+/// One day of an asset's price history
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceObservation {
+    pub date: NaiveDate,
+    pub price: Decimal,
+    /// Held for review; not a price any consumer may use
+    pub quarantined: bool,
+}
+
+/// Why an observation was quarantined
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum Anomaly {
+    /// Observed for a day before the latest one already on record
+    OutOfOrder { latest: NaiveDate },
+    /// Moved further from the last accepted price than recent volatility allows
+    Spike { reference: Decimal, change: Decimal, volatility: Decimal, limit: Decimal },
+}
+
+impl std::fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Anomaly::OutOfOrder { latest } => write!(f, "observation predates the latest price of {}", latest),
+            Anomaly::Spike { reference, change, limit, .. } => {
+                write!(f, "move of {} from {} exceeds the limit of {}", change, reference, limit)
+            }
+        }
+    }
+}
+
+/// Accepted prices in date order, skipping quarantined points, and how many were skipped.
+///
+/// A return is then taken across a skipped day rather than through the suspect price.
+pub fn accepted_prices(history: &[PriceObservation]) -> (Vec<Decimal>, usize) {
+    let prices: Vec<Decimal> = history.iter().filter(|o| !o.quarantined).map(|o| o.price).collect();
+    let skipped = history.len() - prices.len();
+    (prices, skipped)
+}
+
+fn daily_volatility(prices: &[Decimal]) -> Option<(Decimal, usize)> {
+    let returns: Vec<Decimal> = prices.windows(2)
+        .filter(|pair| pair[0] > Decimal::ZERO)
+        .map(|pair| pair[1] / pair[0] - Decimal::ONE)
+        .collect();
+    if returns.len() < 2 {
+        return None;
+    }
+    let n = Decimal::from(returns.len());
+    let mean = returns.iter().sum::<Decimal>() / n;
+    let variance = returns.iter().map(|r| (r - mean) * (r - mean)).sum::<Decimal>() / (n - Decimal::ONE);
+    Some((variance.sqrt_approx()?, returns.len()))
+}
+
+/// Whether the observation of `price` for `date` must be quarantined, given the asset's
+/// recent history in date order
+pub fn check(config: &AnomalyConfig, history: &[PriceObservation], date: NaiveDate, price: Decimal) -> Option<Anomaly> {
+    if let Some(latest) = history.iter().map(|o| o.date).max() {
+        if date < latest {
+            return Some(Anomaly::OutOfOrder { latest });
+        }
+    }
+
+    let earlier: Vec<PriceObservation> = history.iter().filter(|o| o.date < date).cloned().collect();
+    let (prices, _) = accepted_prices(&earlier);
+    let reference = *prices.last()?;
+    if reference <= Decimal::ZERO {
+        return None;
+    }
+    let (volatility, observations) = daily_volatility(&prices)?;
+    if observations < config.min_observations {
+        return None;
+    }
+
+    let volatility = volatility.max(config.min_volatility);
+    let limit = volatility * config.max_move_sigmas;
+    let change = (price / reference - Decimal::ONE).abs();
+    (change > limit).then_some(Anomaly::Spike { reference, change, volatility, limit })
+}
+
+/// Sources in `fresh` quoting within tolerance of `price`; two or more confirm it
+pub fn confirming_sources(config: &AnomalyConfig, price: Decimal, fresh: &AggregatedPrice) -> Vec<String> {
+    if fresh.disputed || fresh.overridden || price <= Decimal::ZERO {
+        return Vec::new();
+    }
+    let tolerance = Decimal::from(config.confirmation_tolerance_bps) / Decimal::from(10_000);
+    fresh.sources.iter()
+        .filter(|quote| ((quote.price - price) / price).abs() <= tolerance)
+        .map(|quote| quote.source.clone())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuarantineStatus {
+    Pending,
+    Approved,
+    Discarded,
+}
+
+impl QuarantineStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            QuarantineStatus::Pending => "pending",
+            QuarantineStatus::Approved => "approved",
+            QuarantineStatus::Discarded => "discarded",
+        }
+    }
+
+    fn parse(status: &str) -> Result<Self, RiskServiceError> {
+        match status {
+            "pending" => Ok(QuarantineStatus::Pending),
+            "approved" => Ok(QuarantineStatus::Approved),
+            "discarded" => Ok(QuarantineStatus::Discarded),
+            other => Err(RiskServiceError::CalculationError(format!("Unknown quarantine status {}", other))),
+        }
+    }
+}
+
+/// An observation held back from the price history until an operator reviews it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantinedPrice {
+    pub quarantine_id: Uuid,
+    pub asset: String,
+    pub price_date: NaiveDate,
+    pub price: Decimal,
+    pub anomaly: Anomaly,
+    pub source_names: Vec<String>,
+    pub status: QuarantineStatus,
+    /// Sources of a later aggregation that agreed with the price
+    pub confirmed_by: Vec<String>,
+    pub confirmed_at: Option<DateTime<Utc>>,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub observed_at: DateTime<Utc>,
+}
+
+impl QuarantinedPrice {
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed_by.len() >= 2
+    }
+}
+
+/// History of `asset` from `lookback_days` before `date` onwards, quarantined points included
+pub async fn recent_history(db: &PgPool, asset: &str, date: NaiveDate, lookback_days: i64) -> Result<Vec<PriceObservation>, RiskServiceError> {
+    let rows: Vec<(NaiveDate, String, bool)> = sqlx::query_as(r#"
+        SELECT price_date, price::text, quarantined FROM asset_price_history
+        WHERE asset_address = $1 AND price_date >= $2
+        ORDER BY price_date
+    "#)
+        .bind(asset)
+        .bind(date - Duration::days(lookback_days))
+        .fetch_all(db)
+        .await?;
+
+    rows.into_iter()
+        .map(|(date, price, quarantined)| Ok(PriceObservation { date, price: parse_decimal(&price)?, quarantined }))
+        .collect()
+}
+
+/// Record a quarantined observation. Its history row is flagged so consumers skip it; an
+/// accepted price already recorded for the day is kept.
+pub async fn quarantine(db: &PgPool, asset: &str, date: NaiveDate, price: &AggregatedPrice, anomaly: Anomaly) -> Result<QuarantinedPrice, RiskServiceError> {
+    let quarantined = QuarantinedPrice {
+        quarantine_id: Uuid::new_v4(),
+        asset: asset.to_string(),
+        price_date: date,
+        price: price.price,
+        anomaly,
+        source_names: price.sources.iter().map(|q| q.source.clone()).collect(),
+        status: QuarantineStatus::Pending,
+        confirmed_by: Vec::new(),
+        confirmed_at: None,
+        reviewed_by: None,
+        reviewed_at: None,
+        observed_at: price.aggregated_at,
+    };
+    let anomaly = serde_json::to_value(&quarantined.anomaly)
+        .map_err(|e| RiskServiceError::CalculationError(e.to_string()))?;
+
+    let mut tx = db.begin().await?;
+    sqlx::query(r#"
+        INSERT INTO asset_price_quarantine
+            (quarantine_id, asset_address, price_date, price, anomaly, source_names, status, observed_at)
+        VALUES ($1, $2, $3, $4::numeric, $5, $6, 'pending', $7)
+    "#)
+        .bind(quarantined.quarantine_id)
+        .bind(asset)
+        .bind(date)
+        .bind(price.price.to_string())
+        .bind(anomaly)
+        .bind(&quarantined.source_names)
+        .bind(quarantined.observed_at)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(r#"
+        INSERT INTO asset_price_history (asset_address, price_date, price, source, confidence, source_names, quarantined, recorded_at)
+        VALUES ($1, $2, $3::numeric, 'oracle', $4::numeric, $5, TRUE, NOW())
+        ON CONFLICT (asset_address, price_date) DO UPDATE SET
+            price = EXCLUDED.price,
+            confidence = EXCLUDED.confidence,
+            source_names = EXCLUDED.source_names,
+            recorded_at = NOW()
+        WHERE asset_price_history.quarantined
+    "#)
+        .bind(asset)
+        .bind(date)
+        .bind(price.price.to_string())
+        .bind(price.confidence.to_string())
+        .bind(&quarantined.source_names)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(quarantined)
+}
+
+const QUARANTINE_COLUMNS: &str = r#"
+    quarantine_id, asset_address, price_date, price::text, anomaly::text, source_names, status,
+    confirmed_by, confirmed_at, reviewed_by, reviewed_at, observed_at
+"#;
+
+type QuarantineRow = (
+    Uuid, String, NaiveDate, String, String, Vec<String>, String,
+    Vec<String>, Option<DateTime<Utc>>, Option<String>, Option<DateTime<Utc>>, DateTime<Utc>,
+);
+
+fn from_row(row: QuarantineRow) -> Result<QuarantinedPrice, RiskServiceError> {
+    let (quarantine_id, asset, price_date, price, anomaly, source_names, status, confirmed_by, confirmed_at, reviewed_by, reviewed_at, observed_at) = row;
+    Ok(QuarantinedPrice {
+        quarantine_id,
+        asset,
+        price_date,
+        price: parse_decimal(&price)?,
+        anomaly: serde_json::from_str(&anomaly)
+            .map_err(|e| RiskServiceError::CalculationError(format!("Invalid quarantine reason: {}", e)))?,
+        source_names,
+        status: QuarantineStatus::parse(&status)?,
+        confirmed_by,
+        confirmed_at,
+        reviewed_by,
+        reviewed_at,
+        observed_at,
+    })
+}
+
+/// Observations awaiting review, oldest first; `asset` narrows them to one asset
+pub async fn pending(db: &PgPool, asset: Option<&str>) -> Result<Vec<QuarantinedPrice>, RiskServiceError> {
+    let rows: Vec<QuarantineRow> = sqlx::query_as(&format!(
+        "SELECT {} FROM asset_price_quarantine
+         WHERE status = 'pending' AND ($1::text IS NULL OR asset_address = $1)
+         ORDER BY observed_at",
+        QUARANTINE_COLUMNS
+    ))
+        .bind(asset)
+        .fetch_all(db)
+        .await?;
+    rows.into_iter().map(from_row).collect()
+}
+
+pub async fn get(db: &PgPool, quarantine_id: Uuid) -> Result<Option<QuarantinedPrice>, RiskServiceError> {
+    let row: Option<QuarantineRow> = sqlx::query_as(&format!(
+        "SELECT {} FROM asset_price_quarantine WHERE quarantine_id = $1",
+        QUARANTINE_COLUMNS
+    ))
+        .bind(quarantine_id)
+        .fetch_optional(db)
+        .await?;
+    row.map(from_row).transpose()
+}
+
+pub async fn record_confirmation(db: &PgPool, quarantine_id: Uuid, sources: &[String]) -> Result<(), RiskServiceError> {
+    sqlx::query(r#"
+        UPDATE asset_price_quarantine SET confirmed_by = $2, confirmed_at = NOW()
+        WHERE quarantine_id = $1 AND status = 'pending'
+    "#)
+        .bind(quarantine_id)
+        .bind(sources)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// Close a pending review. Approval makes the price a regular history point; discarding
+/// drops its history row unless an accepted price has since replaced it.
+pub async fn resolve(db: &PgPool, quarantined: &QuarantinedPrice, status: QuarantineStatus, reviewer: &str) -> Result<(), RiskServiceError> {
+    let mut tx = db.begin().await?;
+    let updated = sqlx::query(r#"
+        UPDATE asset_price_quarantine SET status = $2, reviewed_by = $3, reviewed_at = NOW()
+        WHERE quarantine_id = $1 AND status = 'pending'
+    "#)
+        .bind(quarantined.quarantine_id)
+        .bind(status.as_str())
+        .bind(reviewer)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    if updated == 0 {
+        return Err(RiskServiceError::InvalidInput(format!("{} has already been reviewed", quarantined.quarantine_id)));
+    }
+
+    match status {
+        QuarantineStatus::Approved => {
+            sqlx::query(r#"
+                INSERT INTO asset_price_history (asset_address, price_date, price, source, source_names, quarantined, recorded_at)
+                VALUES ($1, $2, $3::numeric, 'oracle', $4, FALSE, NOW())
+                ON CONFLICT (asset_address, price_date) DO UPDATE SET
+                    price = EXCLUDED.price,
+                    source = EXCLUDED.source,
+                    source_names = EXCLUDED.source_names,
+                    quarantined = FALSE,
+                    recorded_at = NOW()
+            "#)
+                .bind(&quarantined.asset)
+                .bind(quarantined.price_date)
+                .bind(quarantined.price.to_string())
+                .bind(&quarantined.source_names)
+                .execute(&mut *tx)
+                .await?;
+        }
+        QuarantineStatus::Discarded => {
+            sqlx::query("DELETE FROM asset_price_history WHERE asset_address = $1 AND price_date = $2 AND quarantined")
+                .bind(&quarantined.asset)
+                .bind(quarantined.price_date)
+                .execute(&mut *tx)
+                .await?;
+        }
+        QuarantineStatus::Pending => {}
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::factors::return_variance;
+    use price_oracle::PriceQuote;
+
+    fn day(n: i64) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 9, 1).unwrap() + Duration::days(n)
+    }
+
+    /// A month around 100 moving under 1% a day
+    fn calm_history() -> Vec<PriceObservation> {
+        (0..30)
+            .map(|n| PriceObservation {
+                date: day(n),
+                price: dec!(100) + Decimal::from(n % 3) * dec!(0.6),
+                quarantined: false,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_spike_is_quarantined_and_excluded_from_returns() {
+        let config = AnomalyConfig::default();
+        let mut history = calm_history();
+
+        assert_eq!(check(&config, &history, day(30), dec!(101)), None);
+        let anomaly = check(&config, &history, day(30), dec!(1000)).unwrap();
+        assert!(matches!(anomaly, Anomaly::Spike { reference, .. } if reference == dec!(101.2)));
+
+        // The spike is recorded as quarantined; the next day is checked against the last accepted price
+        history.push(PriceObservation { date: day(30), price: dec!(1000), quarantined: true });
+        history.push(PriceObservation { date: day(31), price: dec!(100.6), quarantined: false });
+        assert_eq!(check(&config, &history, day(32), dec!(100)), None);
+
+        let (prices, skipped) = accepted_prices(&history);
+        assert_eq!(skipped, 1);
+        assert!(!prices.contains(&dec!(1000)));
+        let clean = return_variance(&prices).unwrap();
+        let poisoned = return_variance(&history.iter().map(|o| o.price).collect::<Vec<_>>()).unwrap();
+        assert!(clean < dec!(0.0001));
+        assert!(poisoned > dec!(1));
+    }
+
+    #[test]
+    fn test_out_of_order_and_thin_history() {
+        let config = AnomalyConfig::default();
+        let history = calm_history();
+        assert_eq!(check(&config, &history, day(20), dec!(100)), Some(Anomaly::OutOfOrder { latest: day(29) }));
+
+        // Too few returns to judge a move by
+        assert_eq!(check(&config, &history[..5], day(5), dec!(1000)), None);
+    }
+
+    #[test]
+    fn test_confirmation_needs_two_agreeing_sources() {
+        let config = AnomalyConfig::default();
+        let quote = |source: &str, price: Decimal| PriceQuote { source: source.to_string(), price, observed_at: Utc::now() };
+        let mut fresh = AggregatedPrice {
+            asset: "0xasset".to_string(),
+            price: dec!(1000),
+            confidence: dec!(1),
+            sources: vec![quote("chainlink", dec!(1000)), quote("chainlink", dec!(1001)), quote("pyth", dec!(950))],
+            discarded: Vec::new(),
+            failed: Vec::new(),
+            disputed: false,
+            overridden: false,
+            aggregated_at: Utc::now(),
+        };
+        assert_eq!(confirming_sources(&config, dec!(1000), &fresh), vec!["chainlink".to_string()]);
+
+        fresh.sources.push(quote("redstone", dec!(1005)));
+        assert_eq!(confirming_sources(&config, dec!(1000), &fresh).len(), 2);
+
+        fresh.disputed = true;
+        assert!(confirming_sources(&config, dec!(1000), &fresh).is_empty());
+    }
+}
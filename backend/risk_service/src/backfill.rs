@@ -0,0 +1,904 @@
+// Historical daily price backfill for assets and benchmarks
+//
+// New deployments start with empty price histories, so risk calculations have nothing to
+// work with until the daily ingestion has run for weeks. A backfill pulls the history from
+// the configured price feed page by page, writes each page as it arrives and resumes every
+// series from its last stored date, so an interrupted run can simply be started again.
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tokio::sync::RwLock;
+use tracing::{info, warn, error};
+use uuid::Uuid;
+use crate::ethereum_client::Address;
+use crate::RiskServiceError;
+
+/// Source recorded for backfilled rows; live oracle prices are never overwritten by them
+pub const BACKFILL_SOURCE: &str = "backfill";
+
+// ============ Provider ============
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyPrice {
+    pub date: NaiveDate,
+    pub price: Decimal,
+}
+
+/// One page of a provider's daily history, in ascending date order
+#[derive(Debug, Clone, Default)]
+pub struct PricePage {
+    pub prices: Vec<DailyPrice>,
+    /// Opaque cursor for the next page; `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProviderError {
+    /// The provider asked us to slow down, optionally saying for how long
+    RateLimited { retry_after: Option<Duration> },
+    Failed(String),
+}
+
+impl std::fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ProviderError::RateLimited { retry_after: Some(wait) } => write!(f, "Rate limited for {:?}", wait),
+            ProviderError::RateLimited { retry_after: None } => write!(f, "Rate limited"),
+            ProviderError::Failed(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ProviderError {}
+
+/// A source of historical daily prices.
+///
+/// Pages must be returned in ascending date order: each page is stored before the next
+/// is requested, and an interrupted backfill resumes after the last stored date.
+#[async_trait]
+pub trait PriceFeedProvider: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Daily prices of `key` between `from` and `to` inclusive
+    async fn daily_prices(
+        &self,
+        key: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<PricePage, ProviderError>;
+}
+
+/// Prices from a JSON API answering
+/// `GET {base_url}/{key}/daily?from=YYYY-MM-DD&to=YYYY-MM-DD&limit=N[&cursor=...]` with
+/// `{"prices": [{"date": "...", "price": ...}], "next_cursor": ...}`.
+///
+/// A 429 response is reported as rate limiting, honouring `Retry-After` seconds.
+pub struct HttpPriceFeedProvider {
+    base_url: String,
+    api_key: Option<String>,
+    client: reqwest::Client,
+}
+
+impl HttpPriceFeedProvider {
+    pub fn new(base_url: &str, api_key: Option<String>, timeout: Duration) -> Result<Self, RiskServiceError> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| RiskServiceError::PriceFeedError(format!("HTTP client: {}", e)))?;
+
+        Ok(Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            client,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct HttpPricePage {
+    prices: Vec<HttpDailyPrice>,
+    next_cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct HttpDailyPrice {
+    date: NaiveDate,
+    price: serde_json::Value,
+}
+
+#[async_trait]
+impl PriceFeedProvider for HttpPriceFeedProvider {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    async fn daily_prices(
+        &self,
+        key: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<PricePage, ProviderError> {
+        let mut query = vec![
+            ("from", from.to_string()),
+            ("to", to.to_string()),
+            ("limit", limit.to_string()),
+        ];
+        if let Some(cursor) = cursor {
+            query.push(("cursor", cursor.to_string()));
+        }
+
+        let mut request = self.client.get(format!("{}/{}/daily", self.base_url, key)).query(&query);
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+        let response = request.send().await.map_err(|e| ProviderError::Failed(e.to_string()))?;
+
+        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response.headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|secs| secs.trim().parse::<u64>().ok())
+                .map(Duration::from_secs);
+            return Err(ProviderError::RateLimited { retry_after });
+        }
+        let page: HttpPricePage = response.error_for_status()
+            .map_err(|e| ProviderError::Failed(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| ProviderError::Failed(format!("Invalid response for {}: {}", key, e)))?;
+
+        let prices = page.prices.into_iter()
+            .map(|row| {
+                let price = match &row.price {
+                    serde_json::Value::String(price) => Decimal::from_str(price).ok(),
+                    serde_json::Value::Number(price) => Decimal::from_str(&price.to_string()).ok(),
+                    _ => None,
+                };
+                price
+                    .filter(|price| *price >= Decimal::ZERO)
+                    .map(|price| DailyPrice { date: row.date, price })
+                    .ok_or_else(|| ProviderError::Failed(format!("Invalid price for {} on {}", key, row.date)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(PricePage { prices, next_cursor: page.next_cursor })
+    }
+}
+
+// ============ Throttling ============
+
+/// Spacing between provider requests, widened on rate limiting and narrowed again as
+/// requests succeed
+#[derive(Debug, Clone)]
+pub struct Throttle {
+    min_interval: Duration,
+    max_interval: Duration,
+    interval: Duration,
+}
+
+impl Throttle {
+    pub fn new(min_interval: Duration, max_interval: Duration) -> Self {
+        Self { min_interval, max_interval: max_interval.max(min_interval), interval: min_interval }
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    pub async fn wait(&self) {
+        if !self.interval.is_zero() {
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+
+    /// Back off: at least double, and never less than the provider asked for
+    pub fn rate_limited(&mut self, retry_after: Option<Duration>) {
+        let doubled = (self.interval * 2).max(self.min_interval).max(Duration::from_millis(100));
+        self.interval = doubled.max(retry_after.unwrap_or_default()).min(self.max_interval);
+    }
+
+    /// Speed back up by a quarter per successful request
+    pub fn succeeded(&mut self) {
+        self.interval = (self.interval * 3 / 4).max(self.min_interval);
+    }
+}
+
+// ============ Continuity ============
+
+/// A stretch with no prices longer than the configured limit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceGap {
+    /// Last date with a price before the gap
+    pub after: NaiveDate,
+    /// First date with a price after the gap
+    pub before: NaiveDate,
+    pub days: i64,
+}
+
+/// Gaps between consecutive dates longer than `max_gap_days`; `previous` is the last date
+/// already stored, so gaps across a resumed run are caught too
+pub fn find_gaps(previous: Option<NaiveDate>, dates: &[NaiveDate], max_gap_days: i64) -> Vec<PriceGap> {
+    let mut gaps = Vec::new();
+    let mut last = previous;
+    for &date in dates {
+        if let Some(after) = last {
+            let days = (date - after).num_days();
+            if days > max_gap_days {
+                gaps.push(PriceGap { after, before: date, days });
+            }
+        }
+        last = Some(date);
+    }
+    gaps
+}
+
+// ============ Storage ============
+
+/// A price history to fill: an asset's prices or a benchmark index
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "key", rename_all = "snake_case")]
+pub enum PriceSeries {
+    Asset(String),
+    Benchmark(String),
+}
+
+impl PriceSeries {
+    pub fn asset(address: Address) -> Self {
+        PriceSeries::Asset(format!("{:?}", address))
+    }
+
+    /// Key the provider knows the series by
+    pub fn key(&self) -> &str {
+        match self {
+            PriceSeries::Asset(key) | PriceSeries::Benchmark(key) => key,
+        }
+    }
+}
+
+impl std::fmt::Display for PriceSeries {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PriceSeries::Asset(key) => write!(f, "asset {}", key),
+            PriceSeries::Benchmark(key) => write!(f, "benchmark {}", key),
+        }
+    }
+}
+
+#[async_trait]
+pub trait PriceHistoryStore: Send + Sync {
+    /// Latest date stored for the series
+    async fn last_date(&self, series: &PriceSeries) -> Result<Option<NaiveDate>, RiskServiceError>;
+
+    /// Write prices; rewriting a date already backfilled is a no-op
+    async fn write(&self, series: &PriceSeries, prices: &[DailyPrice]) -> Result<(), RiskServiceError>;
+}
+
+/// `asset_price_history` for assets and `benchmark_price_history` for benchmarks
+pub struct PgPriceHistoryStore {
+    db: Arc<PgPool>,
+}
+
+impl PgPriceHistoryStore {
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl PriceHistoryStore for PgPriceHistoryStore {
+    async fn last_date(&self, series: &PriceSeries) -> Result<Option<NaiveDate>, RiskServiceError> {
+        let query = match series {
+            PriceSeries::Asset(_) => "SELECT MAX(price_date) FROM asset_price_history WHERE asset_address = $1",
+            PriceSeries::Benchmark(_) => "SELECT MAX(price_date) FROM benchmark_price_history WHERE benchmark = $1",
+        };
+        let last: Option<NaiveDate> = sqlx::query_scalar(query).bind(series.key()).fetch_one(&*self.db).await?;
+        Ok(last)
+    }
+
+    async fn write(&self, series: &PriceSeries, prices: &[DailyPrice]) -> Result<(), RiskServiceError> {
+        // Oracle and manual prices are better than a backfilled one for the same day
+        let query = match series {
+            PriceSeries::Asset(_) => r#"
+                INSERT INTO asset_price_history (asset_address, price_date, price, source, recorded_at)
+                SELECT $1, d, p::numeric, $4, NOW() FROM UNNEST($2::date[], $3::text[]) AS t(d, p)
+                ON CONFLICT (asset_address, price_date) DO UPDATE SET
+                    price = EXCLUDED.price,
+                    recorded_at = NOW()
+                WHERE asset_price_history.source = $4
+            "#,
+            PriceSeries::Benchmark(_) => r#"
+                INSERT INTO benchmark_price_history (benchmark, price_date, price, source, recorded_at)
+                SELECT $1, d, p::numeric, $4, NOW() FROM UNNEST($2::date[], $3::text[]) AS t(d, p)
+                ON CONFLICT (benchmark, price_date) DO UPDATE SET
+                    price = EXCLUDED.price,
+                    recorded_at = NOW()
+                WHERE benchmark_price_history.source = $4
+            "#,
+        };
+
+        sqlx::query(query)
+            .bind(series.key())
+            .bind(prices.iter().map(|p| p.date).collect::<Vec<_>>())
+            .bind(prices.iter().map(|p| p.price.to_string()).collect::<Vec<_>>())
+            .bind(BACKFILL_SOURCE)
+            .execute(&*self.db)
+            .await?;
+        Ok(())
+    }
+}
+
+// ============ Backfill ============
+
+#[derive(Debug, Clone)]
+pub struct BackfillConfig {
+    /// Prices requested per page
+    pub page_size: usize,
+    /// Gaps between prices longer than this are reported (weekends and holidays are normal)
+    pub max_gap_days: i64,
+    pub min_request_interval: Duration,
+    pub max_request_interval: Duration,
+    /// Consecutive rate-limited requests tolerated before a series is given up
+    pub max_rate_limit_retries: u32,
+}
+
+impl Default for BackfillConfig {
+    fn default() -> Self {
+        Self {
+            page_size: 365,
+            max_gap_days: 4,
+            min_request_interval: Duration::from_millis(250),
+            max_request_interval: Duration::from_secs(60),
+            max_rate_limit_retries: 8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BackfillProgress {
+    pub series_total: usize,
+    pub series_done: usize,
+    pub days_total: i64,
+    pub days_done: i64,
+    pub percent_complete: f64,
+    /// Estimated seconds left, once there is progress to extrapolate from
+    pub eta_secs: Option<u64>,
+}
+
+impl BackfillProgress {
+    fn update(&mut self, started: Instant) {
+        if self.days_total <= 0 {
+            self.percent_complete = 100.0;
+            self.eta_secs = Some(0);
+            return;
+        }
+        let done = self.days_done.min(self.days_total) as f64;
+        let total = self.days_total as f64;
+        self.percent_complete = (done / total * 1000.0).round() / 10.0;
+        self.eta_secs = (done > 0.0).then(|| (started.elapsed().as_secs_f64() * (total - done) / done).round() as u64);
+    }
+}
+
+/// What a backfill did for one series
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeriesReport {
+    pub series: PriceSeries,
+    /// Last date already stored when the run started
+    pub resumed_after: Option<NaiveDate>,
+    pub prices_written: usize,
+    pub last_date: Option<NaiveDate>,
+    pub gaps: Vec<PriceGap>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BackfillReport {
+    pub series: Vec<SeriesReport>,
+}
+
+impl BackfillReport {
+    pub fn failed(&self) -> usize {
+        self.series.iter().filter(|s| s.error.is_some()).count()
+    }
+}
+
+pub struct Backfiller {
+    provider: Arc<dyn PriceFeedProvider>,
+    store: Arc<dyn PriceHistoryStore>,
+    config: BackfillConfig,
+}
+
+impl Backfiller {
+    pub fn new(provider: Arc<dyn PriceFeedProvider>, store: Arc<dyn PriceHistoryStore>, config: BackfillConfig) -> Self {
+        Self { provider, store, config }
+    }
+
+    /// Backfill daily prices from `from` up to yesterday
+    pub async fn backfill<F>(&self, series: &[PriceSeries], from: NaiveDate, on_progress: F) -> BackfillReport
+    where
+        F: FnMut(&BackfillProgress),
+    {
+        let to = Utc::now().date_naive().pred_opt().unwrap_or(from);
+        self.backfill_until(series, from, to, on_progress).await
+    }
+
+    /// Backfill daily prices between `from` and `to` inclusive, resuming each series after
+    /// its last stored date. One series failing does not stop the others.
+    pub async fn backfill_until<F>(&self, series: &[PriceSeries], from: NaiveDate, to: NaiveDate, mut on_progress: F) -> BackfillReport
+    where
+        F: FnMut(&BackfillProgress),
+    {
+        let started = Instant::now();
+        let mut throttle = Throttle::new(self.config.min_request_interval, self.config.max_request_interval);
+        let mut progress = BackfillProgress { series_total: series.len(), ..Default::default() };
+        let mut report = BackfillReport::default();
+
+        // Work out where each series resumes so progress covers only what is left
+        let mut plans = Vec::with_capacity(series.len());
+        for s in series {
+            match self.store.last_date(s).await {
+                Ok(last) => {
+                    let start = last.and_then(|d| d.succ_opt()).map_or(from, |d| d.max(from));
+                    progress.days_total += days_between(start, to);
+                    plans.push((s, last, start, None));
+                }
+                Err(e) => plans.push((s, None, from, Some(e.to_string()))),
+            }
+        }
+        progress.update(started);
+        on_progress(&progress);
+
+        for (s, last, start, error) in plans {
+            let mut result = SeriesReport {
+                series: s.clone(),
+                resumed_after: last,
+                prices_written: 0,
+                last_date: last,
+                gaps: Vec::new(),
+                error,
+            };
+            let days = days_between(start, to);
+            let days_before = progress.days_done;
+
+            if result.error.is_none() && start <= to {
+                let outcome = self.backfill_series(&mut result, start, to, &mut throttle, |covered| {
+                    progress.days_done = days_before + covered;
+                    progress.update(started);
+                    on_progress(&progress);
+                }).await;
+                if let Err(e) = outcome {
+                    warn!("Backfill of {} stopped: {}", s, e);
+                    result.error = Some(e.to_string());
+                }
+            }
+            for gap in &result.gaps {
+                warn!("{} has no prices for {} days between {} and {}", s, gap.days, gap.after, gap.before);
+            }
+
+            progress.days_done = days_before + days;
+            progress.series_done += 1;
+            progress.update(started);
+            on_progress(&progress);
+            report.series.push(result);
+        }
+
+        report
+    }
+
+    async fn backfill_series<F>(
+        &self,
+        result: &mut SeriesReport,
+        start: NaiveDate,
+        to: NaiveDate,
+        throttle: &mut Throttle,
+        mut on_page: F,
+    ) -> Result<(), RiskServiceError>
+    where
+        F: FnMut(i64),
+    {
+        let mut cursor: Option<String> = None;
+        let mut retries = 0;
+
+        loop {
+            throttle.wait().await;
+            let page = match self.provider
+                .daily_prices(result.series.key(), start, to, cursor.as_deref(), self.config.page_size)
+                .await
+            {
+                Ok(page) => page,
+                Err(ProviderError::RateLimited { retry_after }) => {
+                    retries += 1;
+                    if retries > self.config.max_rate_limit_retries {
+                        return Err(RiskServiceError::PriceFeedError(format!(
+                            "{} still rate limiting after {} retries", self.provider.name(), retries - 1
+                        )));
+                    }
+                    throttle.rate_limited(retry_after);
+                    continue;
+                }
+                Err(ProviderError::Failed(message)) => {
+                    return Err(RiskServiceError::PriceFeedError(format!("{}: {}", self.provider.name(), message)));
+                }
+            };
+            retries = 0;
+            throttle.succeeded();
+
+            // Only dates after the last stored one, so a resumed run never steps backwards
+            let mut prices: Vec<_> = page.prices.into_iter()
+                .filter(|p| p.date >= start && p.date <= to && result.last_date.map_or(true, |last| p.date > last))
+                .collect();
+            prices.sort_by_key(|p| p.date);
+            prices.dedup_by_key(|p| p.date);
+
+            if !prices.is_empty() {
+                self.store.write(&result.series, &prices).await?;
+                let dates: Vec<_> = prices.iter().map(|p| p.date).collect();
+                result.gaps.extend(find_gaps(result.last_date, &dates, self.config.max_gap_days));
+                result.prices_written += prices.len();
+                result.last_date = dates.last().copied();
+                on_page(days_between(start, result.last_date.unwrap_or(start)));
+            }
+
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => return Ok(()),
+            }
+        }
+    }
+}
+
+/// Days from `from` to `to` inclusive, or zero when `to` is earlier
+fn days_between(from: NaiveDate, to: NaiveDate) -> i64 {
+    ((to - from).num_days() + 1).max(0)
+}
+
+// ============ Job Runner ============
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackfillRequest {
+    /// Assets to backfill; with no assets and no benchmarks, every held asset
+    #[serde(default)]
+    pub assets: Vec<Address>,
+    #[serde(default)]
+    pub benchmarks: Vec<String>,
+    pub from: NaiveDate,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BackfillJobStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackfillJob {
+    pub job_id: Uuid,
+    pub status: BackfillJobStatus,
+    pub progress: BackfillProgress,
+    pub report: Option<BackfillReport>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Runs backfills in the background and tracks their progress
+pub struct BackfillManager {
+    db: Arc<PgPool>,
+    backfiller: Arc<Backfiller>,
+    jobs: Arc<RwLock<HashMap<Uuid, BackfillJob>>>,
+}
+
+impl BackfillManager {
+    pub fn new(db: Arc<PgPool>, provider: Arc<dyn PriceFeedProvider>, config: BackfillConfig) -> Self {
+        let store = Arc::new(PgPriceHistoryStore::new(db.clone()));
+        Self {
+            db,
+            backfiller: Arc::new(Backfiller::new(provider, store, config)),
+            jobs: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub fn backfiller(&self) -> Arc<Backfiller> {
+        self.backfiller.clone()
+    }
+
+    /// The requested series, or every asset held in a tracked portfolio
+    pub async fn series_for(&self, request: &BackfillRequest) -> Result<Vec<PriceSeries>, RiskServiceError> {
+        let mut series: Vec<_> = request.assets.iter().map(|a| PriceSeries::asset(*a)).collect();
+        series.extend(request.benchmarks.iter().map(|b| PriceSeries::Benchmark(b.clone())));
+        if !series.is_empty() {
+            return Ok(series);
+        }
+
+        let held: Vec<(String,)> = sqlx::query_as(
+            "SELECT DISTINCT asset_address FROM position_cost_basis WHERE amount > 0 ORDER BY asset_address"
+        )
+            .fetch_all(&*self.db)
+            .await?;
+        Ok(held.into_iter().map(|(asset,)| PriceSeries::Asset(asset)).collect())
+    }
+
+    /// Queue a backfill; poll `job` for its progress
+    pub async fn submit(&self, request: BackfillRequest) -> Result<BackfillJob, RiskServiceError> {
+        if request.from >= Utc::now().date_naive() {
+            return Err(RiskServiceError::InvalidInput("from must be in the past".to_string()));
+        }
+        if request.benchmarks.iter().any(|b| b.trim().is_empty() || b.len() > 50) {
+            return Err(RiskServiceError::InvalidInput("Benchmark names must be 1 to 50 characters".to_string()));
+        }
+        let series = self.series_for(&request).await?;
+        if series.is_empty() {
+            return Err(RiskServiceError::InvalidInput("No assets to backfill".to_string()));
+        }
+
+        let job = BackfillJob {
+            job_id: Uuid::new_v4(),
+            status: BackfillJobStatus::Pending,
+            progress: BackfillProgress { series_total: series.len(), ..Default::default() },
+            report: None,
+            error: None,
+            created_at: Utc::now(),
+            completed_at: None,
+        };
+        self.jobs.write().await.insert(job.job_id, job.clone());
+
+        let backfiller = self.backfiller.clone();
+        let jobs = self.jobs.clone();
+        let job_id = job.job_id;
+
+        tokio::spawn(async move {
+            if let Some(job) = jobs.write().await.get_mut(&job_id) {
+                job.status = BackfillJobStatus::Running;
+            }
+
+            // Progress is published from the callback without holding the lock across awaits
+            let latest = Arc::new(std::sync::Mutex::new(BackfillProgress::default()));
+            let publisher = {
+                let (jobs, latest) = (jobs.clone(), latest.clone());
+                tokio::spawn(async move {
+                    loop {
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                        let progress = latest.lock().map(|p| p.clone()).unwrap_or_default();
+                        if let Some(job) = jobs.write().await.get_mut(&job_id) {
+                            job.progress = progress;
+                        }
+                    }
+                })
+            };
+
+            let report = backfiller.backfill(&series, request.from, |progress| {
+                if let Ok(mut latest) = latest.lock() {
+                    *latest = progress.clone();
+                }
+            }).await;
+            publisher.abort();
+
+            let mut jobs = jobs.write().await;
+            let Some(job) = jobs.get_mut(&job_id) else { return };
+            job.completed_at = Some(Utc::now());
+            job.progress = latest.lock().map(|p| p.clone()).unwrap_or_default();
+
+            if report.failed() == report.series.len() {
+                error!("Price backfill {} failed for every series", job_id);
+                job.status = BackfillJobStatus::Failed;
+                job.error = Some("No series could be backfilled".to_string());
+            } else {
+                info!(
+                    "Price backfill {} completed: {} series, {} failed",
+                    job_id, report.series.len(), report.failed()
+                );
+                job.status = BackfillJobStatus::Completed;
+            }
+            job.report = Some(report);
+        });
+
+        Ok(job)
+    }
+
+    pub async fn job(&self, job_id: Uuid) -> Option<BackfillJob> {
+        self.jobs.read().await.get(&job_id).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    /// Serves fixed histories in pages of at most `limit`, with scripted rate limits and failures
+    struct MockProvider {
+        history: HashMap<String, Vec<DailyPrice>>,
+        /// Calls that answer 429 (1-based)
+        rate_limited_calls: Vec<usize>,
+        /// Calls that fail outright (1-based)
+        failing_calls: Vec<usize>,
+        calls: Mutex<Vec<(NaiveDate, Option<String>)>>,
+    }
+
+    impl MockProvider {
+        fn new(key: &str, dates: &[NaiveDate]) -> Self {
+            let prices = dates.iter().enumerate()
+                .map(|(i, d)| DailyPrice { date: *d, price: Decimal::from(100 + i as i64) })
+                .collect();
+            Self {
+                history: HashMap::from([(key.to_string(), prices)]),
+                rate_limited_calls: Vec::new(),
+                failing_calls: Vec::new(),
+                calls: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl PriceFeedProvider for MockProvider {
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        async fn daily_prices(
+            &self,
+            key: &str,
+            from: NaiveDate,
+            to: NaiveDate,
+            cursor: Option<&str>,
+            limit: usize,
+        ) -> Result<PricePage, ProviderError> {
+            let call = {
+                let mut calls = self.calls.lock().unwrap();
+                calls.push((from, cursor.map(str::to_string)));
+                calls.len()
+            };
+            if self.rate_limited_calls.contains(&call) {
+                return Err(ProviderError::RateLimited { retry_after: Some(Duration::from_millis(1)) });
+            }
+            if self.failing_calls.contains(&call) {
+                return Err(ProviderError::Failed("connection reset".to_string()));
+            }
+
+            let matching: Vec<_> = self.history.get(key).into_iter().flatten()
+                .filter(|p| p.date >= from && p.date <= to)
+                .cloned()
+                .collect();
+            let offset: usize = cursor.map_or(0, |c| c.parse().unwrap());
+            let prices: Vec<_> = matching.iter().skip(offset).take(limit).cloned().collect();
+            let next = offset + prices.len();
+            Ok(PricePage {
+                prices,
+                next_cursor: (next < matching.len()).then(|| next.to_string()),
+            })
+        }
+    }
+
+    #[derive(Default)]
+    struct MemoryStore {
+        series: Mutex<HashMap<PriceSeries, BTreeMap<NaiveDate, Decimal>>>,
+        writes: Mutex<usize>,
+    }
+
+    #[async_trait]
+    impl PriceHistoryStore for MemoryStore {
+        async fn last_date(&self, series: &PriceSeries) -> Result<Option<NaiveDate>, RiskServiceError> {
+            Ok(self.series.lock().unwrap().get(series).and_then(|prices| prices.keys().next_back().copied()))
+        }
+
+        async fn write(&self, series: &PriceSeries, prices: &[DailyPrice]) -> Result<(), RiskServiceError> {
+            *self.writes.lock().unwrap() += 1;
+            let mut all = self.series.lock().unwrap();
+            let stored = all.entry(series.clone()).or_default();
+            for p in prices {
+                stored.insert(p.date, p.price);
+            }
+            Ok(())
+        }
+    }
+
+    fn config() -> BackfillConfig {
+        BackfillConfig {
+            page_size: 3,
+            max_gap_days: 4,
+            min_request_interval: Duration::ZERO,
+            max_request_interval: Duration::from_millis(5),
+            max_rate_limit_retries: 2,
+        }
+    }
+
+    fn weekdays(from: &str, to: &str) -> Vec<NaiveDate> {
+        use chrono::Datelike;
+        date(from).iter_days()
+            .take_while(|d| *d <= date(to))
+            .filter(|d| d.weekday().number_from_monday() <= 5)
+            .collect()
+    }
+
+    #[test]
+    fn test_gaps_longer_than_the_limit_are_flagged() {
+        let dates = [date("2024-01-05"), date("2024-01-08"), date("2024-01-20"), date("2024-01-21")];
+
+        // Friday to Monday is a normal weekend
+        assert_eq!(find_gaps(None, &dates, 4), vec![
+            PriceGap { after: date("2024-01-08"), before: date("2024-01-20"), days: 12 },
+        ]);
+        // The last stored date counts too
+        assert_eq!(find_gaps(Some(date("2023-12-01")), &dates[..1], 4)[0].days, 35);
+        assert!(find_gaps(None, &dates, 12).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_backfill_pages_through_history_and_reports_gaps() {
+        let series = PriceSeries::Benchmark("SPX".to_string());
+        // Two weeks of weekdays with the second week missing entirely
+        let mut dates = weekdays("2024-01-01", "2024-01-05");
+        dates.extend(weekdays("2024-01-15", "2024-01-19"));
+        let mut provider = MockProvider::new("SPX", &dates);
+        provider.rate_limited_calls = vec![2];
+        let provider = Arc::new(provider);
+        let store = Arc::new(MemoryStore::default());
+
+        let backfiller = Backfiller::new(provider.clone(), store.clone(), config());
+        let mut updates = Vec::new();
+        let report = backfiller.backfill_until(&[series.clone()], date("2024-01-01"), date("2024-01-19"), |p| {
+            updates.push(p.clone())
+        }).await;
+
+        let result = &report.series[0];
+        assert_eq!(result.error, None);
+        assert_eq!(result.prices_written, 10);
+        assert_eq!(result.gaps, vec![PriceGap { after: date("2024-01-05"), before: date("2024-01-15"), days: 10 }]);
+        assert_eq!(store.series.lock().unwrap()[&series].len(), 10);
+
+        // Four pages of three plus the retried rate-limited request
+        assert_eq!(provider.calls.lock().unwrap().len(), 5);
+        assert!(updates.windows(2).all(|w| w[0].percent_complete <= w[1].percent_complete));
+        let last = updates.last().unwrap();
+        assert_eq!((last.percent_complete, last.series_done, last.eta_secs), (100.0, 1, Some(0)));
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_backfill_resumes_from_last_stored_date() {
+        let series = PriceSeries::Asset("0x00000000000000000000000000000000000000aa".to_string());
+        let dates = weekdays("2024-02-01", "2024-02-29");
+        let store = Arc::new(MemoryStore::default());
+
+        let mut failing = MockProvider::new(series.key(), &dates);
+        failing.failing_calls = vec![3];
+        let backfiller = Backfiller::new(Arc::new(failing), store.clone(), config());
+        let report = backfiller.backfill_until(&[series.clone()], date("2024-02-01"), date("2024-02-29"), |_| {}).await;
+        assert!(report.series[0].error.as_deref().unwrap().contains("connection reset"));
+        assert_eq!(report.series[0].prices_written, 6);
+        assert_eq!(report.series[0].last_date, Some(date("2024-02-08")));
+
+        let provider = Arc::new(MockProvider::new(series.key(), &dates));
+        let backfiller = Backfiller::new(provider.clone(), store.clone(), config());
+        let report = backfiller.backfill_until(&[series.clone()], date("2024-02-01"), date("2024-02-29"), |_| {}).await;
+
+        let result = &report.series[0];
+        assert_eq!(result.error, None);
+        assert_eq!(result.resumed_after, Some(date("2024-02-08")));
+        assert_eq!(result.prices_written, dates.len() - 6);
+        assert!(result.gaps.is_empty());
+        assert_eq!(provider.calls.lock().unwrap()[0], (date("2024-02-09"), None));
+        assert_eq!(store.series.lock().unwrap()[&series].keys().copied().collect::<Vec<_>>(), dates);
+
+        // Nothing left to fetch once complete
+        let writes = *store.writes.lock().unwrap();
+        let report = backfiller.backfill_until(&[series.clone()], date("2024-02-01"), date("2024-02-29"), |_| {}).await;
+        assert_eq!(report.series[0].prices_written, 0);
+        assert_eq!(*store.writes.lock().unwrap(), writes);
+    }
+}
@@ -0,0 +1,68 @@
+// Benchmarks that portfolio beta and alpha are measured against
+use crate::backfill::PriceSeries;
+use crate::ethereum_client::Address;
+use crate::RiskServiceError;
+use sqlx::PgPool;
+
+/// Longest benchmark index name `benchmark_price_history` stores
+const MAX_NAME_LEN: usize = 50;
+
+/// A benchmark from configuration: an asset address, or the name of an index in
+/// `benchmark_price_history`
+pub fn parse(value: &str) -> Result<PriceSeries, RiskServiceError> {
+    let value = value.trim();
+    if value.starts_with("0x") {
+        let asset = value.parse::<Address>()
+            .map_err(|e| RiskServiceError::InvalidInput(format!("Invalid benchmark asset {}: {}", value, e)))?;
+        return Ok(PriceSeries::asset(asset));
+    }
+    validate(&PriceSeries::Benchmark(value.to_string()))
+}
+
+/// The benchmark in canonical form, once its key can be stored
+pub fn validate(benchmark: &PriceSeries) -> Result<PriceSeries, RiskServiceError> {
+    match benchmark {
+        PriceSeries::Asset(asset) => Ok(PriceSeries::asset(asset.parse::<Address>()
+            .map_err(|e| RiskServiceError::InvalidInput(format!("Invalid benchmark asset {}: {}", asset, e)))?)),
+        PriceSeries::Benchmark(name) if name.is_empty() || name.len() > MAX_NAME_LEN => Err(RiskServiceError::InvalidInput(
+            format!("Benchmark name must be 1 to {} characters", MAX_NAME_LEN),
+        )),
+        PriceSeries::Benchmark(name) => Ok(PriceSeries::Benchmark(name.clone())),
+    }
+}
+
+/// Benchmark set for a portfolio, if any
+pub async fn load(db: &PgPool, portfolio: Address) -> Result<Option<PriceSeries>, RiskServiceError> {
+    let row: Option<(String, String)> = sqlx::query_as(
+        "SELECT series_kind, series_key FROM portfolio_benchmarks WHERE portfolio_address = $1"
+    )
+        .bind(format!("{:?}", portfolio))
+        .fetch_optional(db)
+        .await?;
+
+    Ok(row.map(|(kind, key)| match kind.as_str() {
+        "asset" => PriceSeries::Asset(key),
+        _ => PriceSeries::Benchmark(key),
+    }))
+}
+
+pub async fn save(db: &PgPool, portfolio: Address, benchmark: &PriceSeries) -> Result<(), RiskServiceError> {
+    let kind = match benchmark {
+        PriceSeries::Asset(_) => "asset",
+        PriceSeries::Benchmark(_) => "benchmark",
+    };
+    sqlx::query(r#"
+        INSERT INTO portfolio_benchmarks (portfolio_address, series_kind, series_key, updated_at)
+        VALUES ($1, $2, $3, NOW())
+        ON CONFLICT (portfolio_address) DO UPDATE SET
+            series_kind = EXCLUDED.series_kind,
+            series_key = EXCLUDED.series_key,
+            updated_at = NOW()
+    "#)
+        .bind(format!("{:?}", portfolio))
+        .bind(kind)
+        .bind(benchmark.key())
+        .execute(db)
+        .await?;
+    Ok(())
+}
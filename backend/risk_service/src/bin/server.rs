@@ -1,25 +1,47 @@
 use std::sync::Arc;
 use std::net::SocketAddr;
 use axum::{
+    body::Body,
     extract::{Path, Query, State},
-    http::StatusCode,
-    response::{IntoResponse, Json},
-    routing::{get, post},
+    http::{header, StatusCode},
+    middleware,
+    response::{IntoResponse, Json, Response},
+    routing::{delete, get, post, put},
     Router,
 };
+use std::collections::HashMap;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use risk_service::{RiskService, RiskMetrics, MarketScenario, ScenarioOutcome, RiskAlert};
+use risk_service::{RiskService, RiskServiceError, RiskMetrics, VaRMethod, MarketScenario, ScenarioOutcome, RiskAlert, AlertSeverity, AlertStatus, PublicationStatus};
+use risk_service::limits::RiskLimit;
+use risk_service::webhooks::{DeadLetter, WebhookSubscription};
+use risk_service::factors::{FactorExposure, FactorModel};
+use risk_service::alerts::TrackedAlert;
+use risk_service::rebalance::{RebalancePlan, RebalanceTarget, DEFAULT_MIN_TRADE_VALUE};
+use risk_service::what_if::{HypotheticalTrade, WhatIfReport};
+use risk_service::pre_trade::PreTradeEvaluation;
+use risk_service::export::{ExportJob, ExportManager, ExportRequest, ExportStore};
 use risk_service::ethereum_client::{EthereumClient, Address};
 use risk_service::websocket::WebSocketServer;
 use risk_service::config::Config;
+use risk_service::prices::{spawn_price_ingestion, PriceIngestor};
+use risk_service::anomaly::QuarantinedPrice;
+use risk_service::depeg::{DepegAlerter, PegStatus};
+use risk_service::backfill::{BackfillJob, BackfillManager, BackfillProgress, BackfillRequest, HttpPriceFeedProvider, PriceFeedProvider, PriceSeries};
+use price_oracle::{FeedReader, OracleAggregator};
+use quantera_service_auth::{require_service_key, PgServiceKeyStore, Scope, ServiceGuard, ServiceKeys};
 use tokio::net::TcpListener;
-use tracing::{info, error};
+use tracing::{info, warn, error};
 use tracing_subscriber;
 use uuid::Uuid;
 
 #[derive(Clone)]
 struct AppState {
     risk_service: Arc<RiskService>,
+    exports: Arc<ExportManager>,
+    prices: Arc<PriceIngestor>,
+    /// Absent when no historical price feed is configured
+    backfills: Option<Arc<BackfillManager>>,
 }
 
 #[derive(Deserialize)]
@@ -27,12 +49,79 @@ struct PortfolioQuery {
     address: String,
 }
 
+#[derive(Deserialize)]
+struct RiskQuery {
+    /// `monte_carlo`, `historical` or `parametric`; defaults to VAR_METHOD
+    var_method: Option<VaRMethod>,
+}
+
+#[derive(Deserialize)]
+struct AlertHistoryQuery {
+    status: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ExposureQuery {
+    asset: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ExposureUpdate {
+    loadings: HashMap<String, Decimal>,
+}
+
+#[derive(Deserialize)]
+struct PegUpdate {
+    peg: Decimal,
+}
+
+#[derive(Deserialize)]
+struct RiskLimitUpdate {
+    value: Decimal,
+    /// Defaults to the metric's own severity
+    severity: Option<AlertSeverity>,
+}
+
+#[derive(Deserialize)]
+struct WebhookRegistration {
+    url: String,
+    secret: String,
+    severities: Vec<AlertSeverity>,
+}
+
+#[derive(Deserialize)]
+struct PriceReview {
+    reviewer: String,
+}
+
+#[derive(Deserialize)]
+struct DownloadQuery {
+    expires: i64,
+    signature: String,
+}
+
 #[derive(Deserialize)]
 struct ScenarioRequest {
     portfolio_address: String,
     scenarios: Vec<MarketScenario>,
 }
 
+#[derive(Deserialize)]
+struct RebalanceRequest {
+    target: RebalanceTarget,
+    #[serde(default = "default_min_trade_value")]
+    min_trade_value: Decimal,
+}
+
+fn default_min_trade_value() -> Decimal {
+    DEFAULT_MIN_TRADE_VALUE
+}
+
+#[derive(Deserialize)]
+struct WhatIfRequest {
+    trades: Vec<HypotheticalTrade>,
+}
+
 #[derive(Serialize)]
 struct ApiResponse<T> {
     success: bool,
@@ -64,6 +153,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing_subscriber::fmt::init();
     
     info!("Starting Risk Service v2.0.0-alpha");
+    if !quantera_types::redaction_salt_from_env() {
+        warn!("{} is not set; redacted log fields are hashed without a salt", quantera_types::REDACTION_SALT_ENV);
+    }
     
     // Load and validate configuration
     let config = Config::from_env().map_err(|e| {
@@ -78,37 +170,171 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         std::io::Error::new(std::io::ErrorKind::Other, e)
     })?;
     
+    // `risk_service_server backfill ...` runs a one-off price backfill instead of the server
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(command) = args.first() {
+        return match command.as_str() {
+            "backfill" => run_backfill_command(&config, &args[1..]).await,
+            other => Err(format!("Unknown command: {} (expected backfill)", other).into()),
+        };
+    }
+    
     let risk_engine_address = config.risk_engine_address
         .parse::<Address>()
         .expect("Invalid risk engine address");
     
-    // Initialize Ethereum client
-    let eth_client = Arc::new(
-        EthereumClient::new(&config.eth_rpc_url)
-            .await
-            .expect("Failed to connect to Ethereum")
+    // Initialize Ethereum client, signing risk grade publications if a key is configured
+    let mut eth_client = EthereumClient::new(&config.eth_rpc_url)
+        .await
+        .expect("Failed to connect to Ethereum");
+    if let Some(key) = &config.risk_publisher_private_key {
+        eth_client = eth_client.with_signer(key).await.expect("Invalid risk publisher key");
+    }
+    let eth_client = Arc::new(eth_client);
+    
+    // Price sources for held assets, with Chainlink feeds read through the same client
+    let price_aggregator = Arc::new(
+        OracleAggregator::from_env(Some(eth_client.clone() as Arc<dyn FeedReader>))
+            .expect("Invalid price oracle configuration")
     );
     
     // Initialize Risk Service
-    let risk_service = Arc::new(
-        RiskService::new(
-            eth_client,
-            &config.database_url,
-            &config.redis_url,
-            risk_engine_address,
-        )
-        .await
-        .expect("Failed to initialize Risk Service")
+    let mut risk_service = RiskService::new(
+        eth_client.clone(),
+        &config.database_url,
+        &config.redis_url,
+        risk_engine_address,
+    )
+    .await
+    .expect("Failed to initialize Risk Service")
+    .with_alert_policy(config.alert_policy())
+    .with_broadcast_capacity(config.ws_broadcast_capacity)
+    .with_pre_trade_budget(std::time::Duration::from_millis(config.pre_trade_budget_ms))
+    .with_correlation_min_observations(config.correlation_min_observations)
+    .with_var_method(config.var_method)
+    .with_monte_carlo_simulations(config.monte_carlo_simulations)
+    .with_price_history_days(config.price_history_days)
+    .with_depeg_monitor(config.depeg_monitor().expect("Invalid stablecoin configuration"))
+    .with_webhook_config(config.webhook_config())
+    .expect("Failed to create webhook client");
+    
+    if let Some(benchmark) = &config.benchmark {
+        risk_service = risk_service.with_default_benchmark(benchmark.clone());
+    }
+    let liquidity_model = config.liquidity_model(eth_client.clone(), risk_service.db_pool())
+        .expect("Invalid liquidity configuration");
+    risk_service = risk_service.with_liquidity_model(liquidity_model);
+    if let Some(trading_module) = &config.trading_module_address {
+        risk_service = risk_service.with_trading_module(
+            trading_module.parse::<Address>().expect("Invalid trading module address")
+        );
+    }
+    if config.risk_publisher_private_key.is_some() {
+        risk_service = risk_service.with_publication(config.publication_policy());
+    }
+    if let Some(path) = &config.factor_model_path {
+        risk_service = risk_service.with_factor_model(
+            FactorModel::from_file(path).expect("Invalid factor model file")
+        );
+    }
+    let risk_service = Arc::new(risk_service);
+    
+    // A model saved through the admin API overrides the configured seed
+    risk_service.restore_factor_model().await.expect("Failed to restore factor model");
+    risk_service.restore_stablecoin_pegs().await.expect("Failed to restore stablecoin pegs");
+    let open_alerts = risk_service.restore_open_alerts().await.expect("Failed to restore open alerts");
+    info!("Restored {} open risk alerts", open_alerts);
+    
+    // Watched portfolios pick up new transfers and purchases between risk requests
+    risk_service::acquisitions::spawn_acquisition_sync(
+        risk_service.acquisitions(),
+        std::time::Duration::from_secs(config.acquisition_sync_interval_secs),
     );
     
-    let app_state = AppState { risk_service: risk_service.clone() };
+    // Oracle prices feed valuations through the asset price history, and stablecoin
+    // prices the depeg monitor; anomalous prices wait in quarantine for review
+    let price_ingestor = Arc::new(
+        PriceIngestor::new(price_aggregator, risk_service.db_pool())
+            .with_depeg_monitor(risk_service.depeg_monitor(), risk_service.clone() as Arc<dyn DepegAlerter>)
+            .with_anomaly_config(config.anomaly_config())
+    );
+    spawn_price_ingestion(
+        price_ingestor.clone(),
+        std::time::Duration::from_secs(config.price_ingestion_interval_secs),
+    );
+    
+    // Export download links stay valid across restarts only with a configured key
+    let signing_key = match &config.export_signing_key {
+        Some(key) => key.as_bytes().to_vec(),
+        None => {
+            use rand::Rng;
+            rand::thread_rng().gen::<[u8; 32]>().to_vec()
+        }
+    };
+    let export_store = ExportStore::new(&config.export_dir, signing_key)
+        .expect("Failed to create export directory");
+    let exports = Arc::new(ExportManager::new(
+        risk_service.db_pool(),
+        export_store,
+        config.export_max_rows,
+        chrono::Duration::seconds(config.export_url_ttl_secs),
+    ));
+    
+    let backfills = price_feed_provider(&config)?.map(|provider| {
+        Arc::new(BackfillManager::new(risk_service.db_pool(), provider, config.backfill_config()))
+    });
+    
+    let app_state = AppState { risk_service: risk_service.clone(), exports, prices: price_ingestor, backfills };
     
     // Build router
-    let app = Router::new()
-        .route("/health", get(health_check))
+    // Other services call in with X-Service-Key; without SERVICE_KEYS_REQUIRED, callers
+    // presenting no key are still served so existing clients keep working
+    let service_keys = Arc::new(ServiceKeys::new(Arc::new(PgServiceKeyStore::new(risk_service.db_pool().as_ref().clone()))));
+    let service_guard = |scope| middleware::from_fn_with_state(
+        ServiceGuard::new(service_keys.clone(), scope, config.service_keys_required),
+        require_service_key,
+    );
+    
+    let read_routes = Router::new()
         .route("/api/v2/risk/portfolio/:address", get(get_portfolio_risk))
+        .route("/api/v2/risk/portfolio/:address/publication", get(get_publication_status))
         .route("/api/v2/risk/scenarios/:address", post(run_scenarios))
+        .route("/api/v2/risk/builtin-scenarios", get(list_builtin_scenarios))
+        .route("/api/v2/risk/rebalance/:address", post(suggest_rebalance))
+        .route("/api/v2/risk/portfolios/:address/what-if", post(what_if_trades))
+        .route("/api/v2/risk/portfolios/:address/pre-trade", post(evaluate_pre_trade))
         .route("/api/v2/risk/alerts/:address", get(get_risk_alerts))
+        .route("/api/v2/risk/alerts/:address/history", get(get_alert_history))
+        .route("/api/v2/risk/export", post(create_export))
+        .route("/api/v2/risk/export/:job_id", get(get_export_status))
+        .route_layer(service_guard(Scope::RiskRead));
+    
+    let admin_routes = Router::new()
+        .route("/api/v2/risk/admin/factors/model", get(get_factor_model).put(update_factor_model))
+        .route("/api/v2/risk/admin/factors/exposures", get(get_factor_exposures))
+        .route("/api/v2/risk/admin/factors/exposures/:asset", put(set_factor_exposures).delete(delete_factor_exposures))
+        .route("/api/v2/risk/admin/stablecoins", get(get_stablecoin_pegs))
+        .route("/api/v2/risk/admin/stablecoins/:asset", put(set_stablecoin_peg).delete(delete_stablecoin_peg))
+        .route("/api/v2/risk/admin/portfolios/:address/benchmark", get(get_portfolio_benchmark).put(set_portfolio_benchmark))
+        .route("/api/v2/risk/admin/portfolios/:address/limits", get(get_risk_limits))
+        .route("/api/v2/risk/admin/portfolios/:address/limits/:name", put(set_risk_limit).delete(delete_risk_limit))
+        .route("/api/v2/risk/admin/portfolios/:address/webhooks", get(list_webhooks).post(register_webhook))
+        .route("/api/v2/risk/admin/portfolios/:address/webhooks/:id", delete(delete_webhook))
+        .route("/api/v2/risk/admin/portfolios/:address/webhook-dead-letters", get(get_webhook_dead_letters))
+        .route("/api/v2/risk/admin/prices/quarantine", get(get_quarantined_prices))
+        .route("/api/v2/risk/admin/prices/quarantine/:id/approve", post(approve_quarantined_price))
+        .route("/api/v2/risk/admin/prices/quarantine/:id/discard", post(discard_quarantined_price))
+        .route("/api/v2/risk/admin/websocket/stats", get(get_broadcast_stats))
+        .route("/api/v2/risk/admin/backfill", post(start_backfill))
+        .route("/api/v2/risk/admin/backfill/:job_id", get(get_backfill_status))
+        .route_layer(service_guard(Scope::RiskAdmin));
+    
+    let app = Router::new()
+        .route("/health", get(health_check))
+        .merge(read_routes)
+        .merge(admin_routes)
+        // Signed download links authorize themselves
+        .route("/api/v2/risk/export/:job_id/download", get(download_export))
         // WebSocket endpoint disabled for now
         // .route("/api/v2/risk/ws", get(websocket_handler))
         .with_state(app_state);
@@ -146,12 +372,78 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// The configured historical price feed, if any
+fn price_feed_provider(config: &Config) -> Result<Option<Arc<dyn PriceFeedProvider>>, Box<dyn std::error::Error>> {
+    let Some(url) = &config.price_feed_url else { return Ok(None) };
+    let provider: Arc<dyn PriceFeedProvider> = Arc::new(
+        HttpPriceFeedProvider::new(url, config.price_feed_api_key.clone(), std::time::Duration::from_secs(30))?
+    );
+    Ok(Some(provider))
+}
+
+/// `backfill --from YYYY-MM-DD [--benchmark NAME]... [ASSET_ADDRESS]...`; with no assets or
+/// benchmarks every held asset is backfilled
+fn parse_backfill_args(args: &[String]) -> Result<BackfillRequest, String> {
+    let mut from = None;
+    let mut assets = Vec::new();
+    let mut benchmarks = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--from" => {
+                let value = args.next().ok_or("--from needs a date")?;
+                from = Some(chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                    .map_err(|_| format!("Invalid --from date {} (expected YYYY-MM-DD)", value))?);
+            }
+            "--benchmark" => benchmarks.push(args.next().ok_or("--benchmark needs a name")?.clone()),
+            asset => assets.push(asset.parse::<Address>().map_err(|e| format!("Invalid asset address {}: {}", asset, e))?),
+        }
+    }
+    Ok(BackfillRequest { assets, benchmarks, from: from.ok_or("--from is required")? })
+}
+
+async fn run_backfill_command(config: &Config, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let request = parse_backfill_args(args)?;
+    let provider = price_feed_provider(config)?.ok_or("PRICE_FEED_URL must be set to run a backfill")?;
+    let db = Arc::new(sqlx::PgPool::connect(&config.database_url).await?);
+    
+    let manager = BackfillManager::new(db, provider, config.backfill_config());
+    let series = manager.series_for(&request).await?;
+    info!("Backfilling {} price series from {}", series.len(), request.from);
+    
+    let mut logged = -1.0;
+    let report = manager.backfiller().backfill(&series, request.from, |progress: &BackfillProgress| {
+        if progress.percent_complete.floor() > logged {
+            logged = progress.percent_complete.floor();
+            info!(
+                "Backfill {:.1}% complete ({}/{} series), ETA {}",
+                progress.percent_complete,
+                progress.series_done,
+                progress.series_total,
+                progress.eta_secs.map_or("unknown".to_string(), |secs| format!("{}s", secs)),
+            );
+        }
+    }).await;
+    
+    for result in &report.series {
+        match &result.error {
+            Some(e) => error!("{}: {} prices written, stopped: {}", result.series, result.prices_written, e),
+            None => info!("{}: {} prices written, {} gaps", result.series, result.prices_written, result.gaps.len()),
+        }
+    }
+    if report.failed() > 0 {
+        return Err(format!("{} of {} series did not complete; rerun to resume them", report.failed(), report.series.len()).into());
+    }
+    Ok(())
+}
+
 async fn health_check() -> impl IntoResponse {
     Json(ApiResponse::success("Risk Service v2.0.0-alpha - Healthy"))
 }
 
 async fn get_portfolio_risk(
     Path(address): Path<String>,
+    Query(query): Query<RiskQuery>,
     State(state): State<AppState>,
 ) -> impl IntoResponse {
     let portfolio_address = match address.parse::<Address>() {
@@ -164,7 +456,8 @@ async fn get_portfolio_risk(
         }
     };
     
-    match state.risk_service.calculate_portfolio_risk(portfolio_address).await {
+    let var_method = query.var_method.unwrap_or_else(|| state.risk_service.var_method());
+    match state.risk_service.calculate_portfolio_risk(portfolio_address, var_method).await {
         Ok(metrics) => {
             (StatusCode::OK, Json(ApiResponse::success(metrics)))
         }
@@ -178,6 +471,32 @@ async fn get_portfolio_risk(
     }
 }
 
+async fn get_publication_status(
+    Path(address): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let portfolio_address = match address.parse::<Address>() {
+        Ok(addr) => addr,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<PublicationStatus>::error(format!("Invalid address: {}", e)))
+            );
+        }
+    };
+    
+    match state.risk_service.publication_status(portfolio_address).await {
+        Ok(status) => (StatusCode::OK, Json(ApiResponse::success(status))),
+        Err(e) => {
+            error!("Failed to read risk grade publication: {}", e);
+            (
+                StatusCode::BAD_GATEWAY,
+                Json(ApiResponse::error(format!("Failed to read risk grade publication: {}", e)))
+            )
+        }
+    }
+}
+
 async fn run_scenarios(
     Path(address): Path<String>,
     State(state): State<AppState>,
@@ -197,13 +516,75 @@ async fn run_scenarios(
         Ok(outcomes) => {
             (StatusCode::OK, Json(ApiResponse::success(outcomes)))
         }
+        Err(e) => factor_error("Failed to run scenarios", e),
+    }
+}
+
+/// Named scenarios that can be posted to the scenarios endpoint as they are
+async fn list_builtin_scenarios(State(state): State<AppState>) -> impl IntoResponse {
+    Json(ApiResponse::success(state.risk_service.list_builtin_scenarios()))
+}
+
+async fn suggest_rebalance(
+    Path(address): Path<String>,
+    State(state): State<AppState>,
+    Json(request): Json<RebalanceRequest>,
+) -> impl IntoResponse {
+    let portfolio_address = match address.parse::<Address>() {
+        Ok(addr) => addr,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<RebalancePlan>::error(format!("Invalid address: {}", e)))
+            );
+        }
+    };
+    
+    match state.risk_service.suggest_rebalance(portfolio_address, request.target, request.min_trade_value).await {
+        Ok(plan) => (StatusCode::OK, Json(ApiResponse::success(plan))),
+        Err(e) => factor_error("Failed to suggest rebalance", e),
+    }
+}
+
+async fn what_if_trades(
+    Path(address): Path<String>,
+    State(state): State<AppState>,
+    Json(request): Json<WhatIfRequest>,
+) -> impl IntoResponse {
+    let portfolio_address = match address.parse::<Address>() {
+        Ok(addr) => addr,
         Err(e) => {
-            error!("Failed to run scenarios: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::error(format!("Failed to run scenarios: {}", e)))
-            )
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<WhatIfReport>::error(format!("Invalid address: {}", e)))
+            );
+        }
+    };
+    
+    match state.risk_service.what_if(portfolio_address, request.trades).await {
+        Ok(report) => (StatusCode::OK, Json(ApiResponse::success(report))),
+        Err(e) => factor_error("Failed to evaluate trades", e),
+    }
+}
+
+async fn evaluate_pre_trade(
+    Path(address): Path<String>,
+    State(state): State<AppState>,
+    Json(trade): Json<HypotheticalTrade>,
+) -> impl IntoResponse {
+    let portfolio_address = match address.parse::<Address>() {
+        Ok(addr) => addr,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<PreTradeEvaluation>::error(format!("Invalid address: {}", e)))
+            );
         }
+    };
+    
+    match state.risk_service.evaluate_pre_trade(portfolio_address, trade).await {
+        Ok(evaluation) => (StatusCode::OK, Json(ApiResponse::success(evaluation))),
+        Err(e) => factor_error("Failed to evaluate trade", e),
     }
 }
 
@@ -235,6 +616,496 @@ async fn get_risk_alerts(
     }
 }
 
+async fn get_alert_history(
+    Path(address): Path<String>,
+    Query(query): Query<AlertHistoryQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let portfolio_address = match address.parse::<Address>() {
+        Ok(addr) => addr,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<Vec<TrackedAlert>>::error(format!("Invalid address: {}", e)))
+            );
+        }
+    };
+    
+    let status = match query.status.as_deref() {
+        None | Some("all") => None,
+        Some("open") => Some(AlertStatus::Open),
+        Some("resolved") => Some(AlertStatus::Resolved),
+        Some(other) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(format!("Invalid status filter: {} (expected open, resolved or all)", other)))
+            );
+        }
+    };
+    
+    let alerts = state.risk_service.get_alerts(portfolio_address, status).await;
+    (StatusCode::OK, Json(ApiResponse::success(alerts)))
+}
+
+async fn get_broadcast_stats(State(state): State<AppState>) -> impl IntoResponse {
+    Json(ApiResponse::success(state.risk_service.broadcast_stats()))
+}
+
+async fn get_factor_model(State(state): State<AppState>) -> impl IntoResponse {
+    Json(ApiResponse::success(state.risk_service.factor_model().await))
+}
+
+async fn update_factor_model(
+    State(state): State<AppState>,
+    Json(model): Json<FactorModel>,
+) -> impl IntoResponse {
+    match state.risk_service.update_factor_model(model).await {
+        Ok(model) => (StatusCode::OK, Json(ApiResponse::success(model))),
+        Err(e) => factor_error("Failed to update factor model", e),
+    }
+}
+
+async fn get_factor_exposures(
+    Query(query): Query<ExposureQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let asset = match query.asset.as_deref().map(str::parse::<Address>).transpose() {
+        Ok(asset) => asset,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<Vec<FactorExposure>>::error(format!("Invalid address: {}", e)))
+            );
+        }
+    };
+    
+    match state.risk_service.get_factor_exposures(asset).await {
+        Ok(exposures) => (StatusCode::OK, Json(ApiResponse::success(exposures))),
+        Err(e) => factor_error("Failed to get factor exposures", e),
+    }
+}
+
+async fn set_factor_exposures(
+    Path(asset): Path<String>,
+    State(state): State<AppState>,
+    Json(update): Json<ExposureUpdate>,
+) -> impl IntoResponse {
+    let asset = match asset.parse::<Address>() {
+        Ok(addr) => addr,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<Vec<FactorExposure>>::error(format!("Invalid address: {}", e)))
+            );
+        }
+    };
+    
+    match state.risk_service.set_factor_exposures(asset, update.loadings).await {
+        Ok(exposures) => (StatusCode::OK, Json(ApiResponse::success(exposures))),
+        Err(e) => factor_error("Failed to set factor exposures", e),
+    }
+}
+
+async fn delete_factor_exposures(
+    Path(asset): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let asset = match asset.parse::<Address>() {
+        Ok(addr) => addr,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<()>::error(format!("Invalid address: {}", e)))
+            );
+        }
+    };
+    
+    match state.risk_service.delete_factor_exposures(asset).await {
+        Ok(()) => (StatusCode::OK, Json(ApiResponse::success(()))),
+        Err(e) => factor_error("Failed to delete factor exposures", e),
+    }
+}
+
+/// Watched stablecoins with their current deviation and depeg state
+async fn get_stablecoin_pegs(State(state): State<AppState>) -> impl IntoResponse {
+    Json(ApiResponse::success(state.risk_service.stablecoin_pegs()))
+}
+
+async fn set_stablecoin_peg(
+    Path(asset): Path<String>,
+    State(state): State<AppState>,
+    Json(update): Json<PegUpdate>,
+) -> impl IntoResponse {
+    let asset = match asset.parse::<Address>() {
+        Ok(addr) => addr,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<PegStatus>::error(format!("Invalid address: {}", e)))
+            );
+        }
+    };
+    
+    match state.risk_service.set_stablecoin_peg(asset, update.peg).await {
+        Ok(status) => (StatusCode::OK, Json(ApiResponse::success(status))),
+        Err(e) => factor_error("Failed to set stablecoin peg", e),
+    }
+}
+
+async fn delete_stablecoin_peg(
+    Path(asset): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let asset = match asset.parse::<Address>() {
+        Ok(addr) => addr,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<()>::error(format!("Invalid address: {}", e)))
+            );
+        }
+    };
+    
+    match state.risk_service.remove_stablecoin_peg(asset).await {
+        Ok(()) => (StatusCode::OK, Json(ApiResponse::success(()))),
+        Err(e) => factor_error("Failed to remove stablecoin peg", e),
+    }
+}
+
+/// Benchmark the portfolio's beta and alpha are measured against; null when none is configured
+async fn get_portfolio_benchmark(
+    Path(address): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let portfolio = match address.parse::<Address>() {
+        Ok(addr) => addr,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<Option<PriceSeries>>::error(format!("Invalid address: {}", e)))
+            );
+        }
+    };
+    
+    match state.risk_service.portfolio_benchmark(portfolio).await {
+        Ok(benchmark) => (StatusCode::OK, Json(ApiResponse::success(benchmark))),
+        Err(e) => factor_error("Failed to read portfolio benchmark", e),
+    }
+}
+
+/// Body is `{"kind": "asset", "key": "0x..."}` or `{"kind": "benchmark", "key": "<index name>"}`
+async fn set_portfolio_benchmark(
+    Path(address): Path<String>,
+    State(state): State<AppState>,
+    Json(benchmark): Json<PriceSeries>,
+) -> impl IntoResponse {
+    let portfolio = match address.parse::<Address>() {
+        Ok(addr) => addr,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<PriceSeries>::error(format!("Invalid address: {}", e)))
+            );
+        }
+    };
+    
+    match state.risk_service.set_portfolio_benchmark(portfolio, benchmark).await {
+        Ok(benchmark) => (StatusCode::OK, Json(ApiResponse::success(benchmark))),
+        Err(e) => factor_error("Failed to set portfolio benchmark", e),
+    }
+}
+
+/// Limits the portfolio is monitored against, its own and the defaults it keeps
+async fn get_risk_limits(
+    Path(address): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let portfolio = match address.parse::<Address>() {
+        Ok(addr) => addr,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<Vec<RiskLimit>>::error(format!("Invalid address: {}", e)))
+            );
+        }
+    };
+    
+    match state.risk_service.list_risk_limits(portfolio).await {
+        Ok(limits) => (StatusCode::OK, Json(ApiResponse::success(limits))),
+        Err(e) => factor_error("Failed to list risk limits", e),
+    }
+}
+
+/// Body is `{"value": "0.15", "severity": "Critical"}`; severity is optional
+async fn set_risk_limit(
+    Path((address, name)): Path<(String, String)>,
+    State(state): State<AppState>,
+    Json(update): Json<RiskLimitUpdate>,
+) -> impl IntoResponse {
+    let portfolio = match address.parse::<Address>() {
+        Ok(addr) => addr,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<RiskLimit>::error(format!("Invalid address: {}", e)))
+            );
+        }
+    };
+    
+    match state.risk_service.set_risk_limit(portfolio, &name, update.value, update.severity).await {
+        Ok(limit) => (StatusCode::OK, Json(ApiResponse::success(limit))),
+        Err(e) => factor_error("Failed to set risk limit", e),
+    }
+}
+
+async fn delete_risk_limit(
+    Path((address, name)): Path<(String, String)>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let portfolio = match address.parse::<Address>() {
+        Ok(addr) => addr,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<()>::error(format!("Invalid address: {}", e)))
+            );
+        }
+    };
+    
+    match state.risk_service.delete_risk_limit(portfolio, &name).await {
+        Ok(true) => (StatusCode::OK, Json(ApiResponse::success(()))),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error(format!("Portfolio has not set limit {}", name)))
+        ),
+        Err(e) => factor_error("Failed to remove risk limit", e),
+    }
+}
+
+/// Body is `{"url": "https://...", "secret": "...", "severities": ["Warning", "Critical"]}`
+async fn register_webhook(
+    Path(address): Path<String>,
+    State(state): State<AppState>,
+    Json(registration): Json<WebhookRegistration>,
+) -> impl IntoResponse {
+    let portfolio = match address.parse::<Address>() {
+        Ok(addr) => addr,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<WebhookSubscription>::error(format!("Invalid address: {}", e)))
+            );
+        }
+    };
+    
+    match state.risk_service.register_webhook(portfolio, &registration.url, &registration.secret, &registration.severities).await {
+        Ok(subscription) => (StatusCode::CREATED, Json(ApiResponse::success(subscription))),
+        Err(e) => factor_error("Failed to register webhook", e),
+    }
+}
+
+async fn list_webhooks(
+    Path(address): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let portfolio = match address.parse::<Address>() {
+        Ok(addr) => addr,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<Vec<WebhookSubscription>>::error(format!("Invalid address: {}", e)))
+            );
+        }
+    };
+    
+    match state.risk_service.list_webhooks(portfolio).await {
+        Ok(subscriptions) => (StatusCode::OK, Json(ApiResponse::success(subscriptions))),
+        Err(e) => factor_error("Failed to list webhooks", e),
+    }
+}
+
+async fn delete_webhook(
+    Path((address, id)): Path<(String, Uuid)>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let portfolio = match address.parse::<Address>() {
+        Ok(addr) => addr,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<()>::error(format!("Invalid address: {}", e)))
+            );
+        }
+    };
+    
+    match state.risk_service.delete_webhook(portfolio, id).await {
+        Ok(true) => (StatusCode::OK, Json(ApiResponse::success(()))),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error(format!("Portfolio has no webhook {}", id)))
+        ),
+        Err(e) => factor_error("Failed to remove webhook", e),
+    }
+}
+
+/// Alert deliveries given up after every attempt failed, newest first
+async fn get_webhook_dead_letters(
+    Path(address): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let portfolio = match address.parse::<Address>() {
+        Ok(addr) => addr,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<Vec<DeadLetter>>::error(format!("Invalid address: {}", e)))
+            );
+        }
+    };
+    
+    match state.risk_service.webhook_dead_letters(portfolio).await {
+        Ok(dead_letters) => (StatusCode::OK, Json(ApiResponse::success(dead_letters))),
+        Err(e) => factor_error("Failed to list webhook dead letters", e),
+    }
+}
+
+/// Ingested prices held back by the anomaly gate, oldest first
+async fn get_quarantined_prices(State(state): State<AppState>) -> impl IntoResponse {
+    match state.prices.quarantined().await {
+        Ok(prices) => (StatusCode::OK, Json(ApiResponse::success(prices))),
+        Err(e) => factor_error("Failed to list quarantined prices", e),
+    }
+}
+
+/// Accept a quarantined price once a second oracle source confirms it
+async fn approve_quarantined_price(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Json(review): Json<PriceReview>,
+) -> impl IntoResponse {
+    if review.reviewer.trim().is_empty() {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::<QuarantinedPrice>::error("A reviewer is required".to_string())));
+    }
+    match state.prices.approve_quarantined(id, review.reviewer.trim()).await {
+        Ok(price) => (StatusCode::OK, Json(ApiResponse::success(price))),
+        Err(e) => factor_error("Failed to approve quarantined price", e),
+    }
+}
+
+async fn discard_quarantined_price(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Json(review): Json<PriceReview>,
+) -> impl IntoResponse {
+    if review.reviewer.trim().is_empty() {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::<QuarantinedPrice>::error("A reviewer is required".to_string())));
+    }
+    match state.prices.discard_quarantined(id, review.reviewer.trim()).await {
+        Ok(price) => (StatusCode::OK, Json(ApiResponse::success(price))),
+        Err(e) => factor_error("Failed to discard quarantined price", e),
+    }
+}
+
+/// Invalid input (rejected models, unknown factors, bad targets) is a client error; anything else is logged
+fn factor_error<T>(context: &str, e: RiskServiceError) -> (StatusCode, Json<ApiResponse<T>>) {
+    match e {
+        RiskServiceError::InvalidInput(msg) => (StatusCode::BAD_REQUEST, Json(ApiResponse::error(msg))),
+        e => {
+            error!("{}: {}", context, e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::error(format!("{}: {}", context, e))))
+        }
+    }
+}
+
+async fn start_backfill(
+    State(state): State<AppState>,
+    Json(request): Json<BackfillRequest>,
+) -> impl IntoResponse {
+    let Some(backfills) = &state.backfills else {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse::<BackfillJob>::error("No historical price feed is configured".to_string()))
+        );
+    };
+    
+    match backfills.submit(request).await {
+        Ok(job) => (StatusCode::ACCEPTED, Json(ApiResponse::success(job))),
+        Err(e) => factor_error("Failed to start backfill", e),
+    }
+}
+
+async fn get_backfill_status(
+    Path(job_id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match state.backfills.as_ref() {
+        Some(backfills) => match backfills.job(job_id).await {
+            Some(job) => (StatusCode::OK, Json(ApiResponse::success(job))),
+            None => (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<BackfillJob>::error(format!("Backfill job not found: {}", job_id)))
+            ),
+        },
+        None => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse::error("No historical price feed is configured".to_string()))
+        ),
+    }
+}
+
+async fn create_export(
+    State(state): State<AppState>,
+    Json(request): Json<ExportRequest>,
+) -> impl IntoResponse {
+    match state.exports.submit(request).await {
+        Ok(job) => (StatusCode::ACCEPTED, Json(ApiResponse::success(job))),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ApiResponse::error(e.to_string()))),
+    }
+}
+
+async fn get_export_status(
+    Path(job_id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match state.exports.job(job_id).await {
+        Some(job) => (StatusCode::OK, Json(ApiResponse::success(job))),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<ExportJob>::error(format!("Export job not found: {}", job_id)))
+        ),
+    }
+}
+
+async fn download_export(
+    Path(job_id): Path<Uuid>,
+    Query(query): Query<DownloadQuery>,
+    State(state): State<AppState>,
+) -> Response {
+    let Some((path, format)) = state.exports.download(job_id, query.expires, &query.signature).await else {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ApiResponse::<()>::error("Download link is invalid or has expired".to_string()))
+        ).into_response();
+    };
+    
+    match tokio::fs::File::open(&path).await {
+        Ok(file) => (
+            [
+                (header::CONTENT_TYPE, format.content_type().to_string()),
+                (header::CONTENT_DISPOSITION, format!("attachment; filename=\"risk-export-{}.{}\"", job_id, format.extension())),
+            ],
+            Body::from_stream(tokio_util::io::ReaderStream::new(file)),
+        ).into_response(),
+        Err(e) => {
+            error!("Failed to open export {}: {}", job_id, e);
+            (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("Export file is no longer available".to_string()))
+            ).into_response()
+        }
+    }
+}
+
 /* Temporarily disabled WebSocket handlers
 async fn websocket_handler(
     ws: WebSocketUpgrade,
@@ -244,20 +1115,16 @@ async fn websocket_handler(
 }
 
 async fn handle_websocket(socket: axum::extract::ws::WebSocket, state: AppState) {
-    let (tx, mut rx) = tokio::sync::mpsc::channel::<RiskMetrics>(100);
-    let client_id = Uuid::new_v4();
-    
-    // Register client
-    state.risk_service.register_websocket_client(client_id, tx);
+    let mut updates = state.risk_service.subscribe_risk_updates();
     
     // Handle socket
     let (mut sender, mut receiver) = socket.split();
     
     // Spawn task to send updates
     let send_task = tokio::spawn(async move {
-        while let Some(metrics) = rx.recv().await {
-            if let Ok(msg) = serde_json::to_string(&metrics) {
-                let _ = sender.send(axum::extract::ws::Message::Text(msg)).await;
+        while let Ok(payload) = updates.next().await {
+            if sender.send(axum::extract::ws::Message::Text(payload.to_string())).await.is_err() {
+                break;
             }
         }
     });
@@ -268,7 +1135,6 @@ async fn handle_websocket(socket: axum::extract::ws::WebSocket, state: AppState)
     }
     
     // Clean up
-    state.risk_service.unregister_websocket_client(client_id);
     send_task.abort();
 }
 */
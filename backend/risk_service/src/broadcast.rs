@@ -0,0 +1,232 @@
+// Fan-out of risk updates to WebSocket clients
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use serde::Serialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{error, warn};
+use quantera_types::Pii;
+use crate::{RiskAlert, RiskMetrics};
+
+/// Updates buffered per client before a slow client is dropped
+pub const DEFAULT_BROADCAST_CAPACITY: usize = 64;
+
+#[derive(Default)]
+struct BroadcastCounters {
+    published: AtomicU64,
+    serialization_failures: AtomicU64,
+    lagged_messages: AtomicU64,
+    dropped_clients: AtomicU64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BroadcastStats {
+    pub subscribers: usize,
+    pub published: u64,
+    pub serialization_failures: u64,
+    /// Updates skipped by clients that fell behind, summed over all dropped clients
+    pub lagged_messages: u64,
+    pub dropped_clients: u64,
+}
+
+/// Serializes each update once and hands the same payload to every subscriber.
+///
+/// Publishing never waits on clients: a client that falls more than the channel
+/// capacity behind is disconnected instead of slowing everyone else down.
+pub struct RiskBroadcaster {
+    sender: broadcast::Sender<Arc<str>>,
+    counters: Arc<BroadcastCounters>,
+}
+
+impl RiskBroadcaster {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self { sender, counters: Arc::new(BroadcastCounters::default()) }
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Serialize and fan out an update; returns the number of subscribers it reached
+    pub fn publish(&self, metrics: &RiskMetrics) -> usize {
+        match serde_json::to_string(metrics) {
+            Ok(json) => self.send(json),
+            Err(e) => {
+                self.counters.serialization_failures.fetch_add(1, Ordering::Relaxed);
+                error!(portfolio = %Pii::debug(metrics.portfolio_address), error = %e, "Failed to serialize risk update");
+                0
+            }
+        }
+    }
+
+    /// Fan out an alert change, wrapped as `{"alert": ...}` to tell it apart from metric updates
+    pub fn publish_alert(&self, alert: &RiskAlert) -> usize {
+        match serde_json::to_string(&serde_json::json!({ "alert": alert })) {
+            Ok(json) => self.send(json),
+            Err(e) => {
+                self.counters.serialization_failures.fetch_add(1, Ordering::Relaxed);
+                error!(alert_id = %alert.id, portfolio = %Pii::debug(alert.portfolio), error = %e, "Failed to serialize alert");
+                0
+            }
+        }
+    }
+
+    fn send(&self, json: String) -> usize {
+        self.counters.published.fetch_add(1, Ordering::Relaxed);
+        // Sending only fails when nobody is subscribed
+        self.sender.send(json.into()).unwrap_or(0)
+    }
+
+    pub fn subscribe(&self) -> RiskSubscription {
+        RiskSubscription {
+            receiver: self.sender.subscribe(),
+            counters: self.counters.clone(),
+        }
+    }
+
+    pub fn stats(&self) -> BroadcastStats {
+        BroadcastStats {
+            subscribers: self.subscriber_count(),
+            published: self.counters.published.load(Ordering::Relaxed),
+            serialization_failures: self.counters.serialization_failures.load(Ordering::Relaxed),
+            lagged_messages: self.counters.lagged_messages.load(Ordering::Relaxed),
+            dropped_clients: self.counters.dropped_clients.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Default for RiskBroadcaster {
+    fn default() -> Self {
+        Self::new(DEFAULT_BROADCAST_CAPACITY)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubscriptionEnded {
+    /// The client missed this many updates and should be disconnected
+    Lagged(u64),
+    /// The broadcaster was dropped
+    Closed,
+}
+
+/// One client's view of the update stream
+pub struct RiskSubscription {
+    receiver: broadcast::Receiver<Arc<str>>,
+    counters: Arc<BroadcastCounters>,
+}
+
+impl RiskSubscription {
+    /// Next serialized update. A lagging client is counted as dropped and gets no further updates.
+    pub async fn next(&mut self) -> Result<Arc<str>, SubscriptionEnded> {
+        match self.receiver.recv().await {
+            Ok(payload) => Ok(payload),
+            Err(RecvError::Lagged(missed)) => {
+                self.counters.lagged_messages.fetch_add(missed, Ordering::Relaxed);
+                self.counters.dropped_clients.fetch_add(1, Ordering::Relaxed);
+                warn!("Dropping WebSocket client that fell {} risk updates behind", missed);
+                Err(SubscriptionEnded::Lagged(missed))
+            }
+            Err(RecvError::Closed) => Err(SubscriptionEnded::Closed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RiskGrade, VaRMethod};
+    use crate::ethereum_client::Address;
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
+
+    fn metrics(assets: usize) -> RiskMetrics {
+        RiskMetrics {
+            portfolio_address: Address::repeat_byte(1),
+            var_95: Decimal::new(5, 2),
+            var_99: Decimal::new(8, 2),
+            var_method: VaRMethod::MonteCarlo,
+            expected_shortfall: Decimal::new(9, 2),
+            es_99: Decimal::new(12, 2),
+            sharpe_ratio: Decimal::ONE,
+            sortino_ratio: Decimal::ONE,
+            max_drawdown: Decimal::new(12, 2),
+            beta: Some(Decimal::ONE),
+            alpha: Some(Decimal::ZERO),
+            volatility: Decimal::new(15, 2),
+            correlation_matrix: vec![vec![Decimal::new(5, 1); assets]; assets],
+            liquidity_scores: HashMap::new(),
+            liquidity_details: HashMap::new(),
+            concentration_risk: Decimal::new(2, 1),
+            leverage_ratio: Decimal::ONE,
+            risk_grade: RiskGrade::B,
+            factor_risk_contributions: Vec::new(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_payload_serialized_once_and_shared() {
+        let broadcaster = RiskBroadcaster::new(4);
+        let mut first = broadcaster.subscribe();
+        let mut second = broadcaster.subscribe();
+
+        assert_eq!(broadcaster.publish(&metrics(3)), 2);
+
+        let a = first.next().await.unwrap();
+        let b = second.next().await.unwrap();
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(a.contains("correlation_matrix"));
+
+        drop(broadcaster);
+        assert_eq!(first.next().await, Err(SubscriptionEnded::Closed));
+    }
+
+    #[tokio::test]
+    async fn test_slow_subscribers_do_not_delay_publishing() {
+        const SUBSCRIBERS: usize = 500;
+        const UPDATES: usize = 200;
+
+        let broadcaster = RiskBroadcaster::new(DEFAULT_BROADCAST_CAPACITY);
+        let update = metrics(50);
+
+        // Half the clients keep up, the other half never read
+        let fast: Vec<_> = (0..SUBSCRIBERS / 2)
+            .map(|_| {
+                let mut subscription = broadcaster.subscribe();
+                tokio::spawn(async move {
+                    let mut received = 0;
+                    while subscription.next().await.is_ok() {
+                        received += 1;
+                    }
+                    received
+                })
+            })
+            .collect();
+        let mut slow: Vec<_> = (0..SUBSCRIBERS / 2).map(|_| broadcaster.subscribe()).collect();
+
+        let mut slowest = Duration::ZERO;
+        for _ in 0..UPDATES {
+            let started = Instant::now();
+            broadcaster.publish(&update);
+            slowest = slowest.max(started.elapsed());
+            tokio::task::yield_now().await;
+        }
+        assert!(slowest < Duration::from_millis(100), "publish took {:?}", slowest);
+
+        for subscription in slow.iter_mut() {
+            let missed = UPDATES - DEFAULT_BROADCAST_CAPACITY;
+            assert_eq!(subscription.next().await, Err(SubscriptionEnded::Lagged(missed as u64)));
+        }
+
+        let stats = broadcaster.stats();
+        assert_eq!(stats.published, UPDATES as u64);
+        assert_eq!(stats.dropped_clients, (SUBSCRIBERS / 2) as u64);
+
+        drop(broadcaster);
+        for handle in fast {
+            assert_eq!(handle.await.unwrap(), UPDATES);
+        }
+    }
+}
@@ -2,6 +2,20 @@
 use std::env;
 use serde::Deserialize;
 use tracing::info;
+use rust_decimal::Decimal;
+use crate::alerts::AlertPolicy;
+use crate::anomaly::AnomalyConfig;
+use crate::backfill::{BackfillConfig, PriceSeries};
+use crate::depeg::{DepegConfig, DepegMonitor};
+use crate::ethereum_client::{Address, EthereumClient};
+use crate::history::MIN_OBSERVATIONS;
+use crate::liquidity::{AmmDepthSource, DbMarketDepthSource, LiquidityConfig, LiquidityModel, MarketDepthSource};
+use crate::publication::PublicationPolicy;
+use crate::webhooks::WebhookConfig;
+use crate::VaRMethod;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
@@ -13,6 +27,56 @@ pub struct Config {
     pub log_level: String,
     pub http_port: u16,
     pub ws_port: u16,
+    pub alert_resolve_after_cycles: u32,
+    pub alert_escalation_steps: Vec<Decimal>,
+    pub alert_notify_cooldown_secs: u64,
+    pub webhook_timeout_ms: u64,
+    pub webhook_max_attempts: u32,
+    pub webhook_initial_backoff_ms: u64,
+    pub webhook_max_backoff_secs: u64,
+    pub export_dir: String,
+    pub export_max_rows: u64,
+    pub export_url_ttl_secs: i64,
+    pub export_signing_key: Option<String>,
+    pub trading_module_address: Option<String>,
+    pub acquisition_sync_interval_secs: u64,
+    pub factor_model_path: Option<String>,
+    pub price_ingestion_interval_secs: u64,
+    pub ws_broadcast_capacity: usize,
+    pub price_feed_url: Option<String>,
+    pub price_feed_api_key: Option<String>,
+    pub backfill_page_size: usize,
+    pub backfill_max_gap_days: i64,
+    pub backfill_min_request_interval_ms: u64,
+    pub risk_publisher_private_key: Option<String>,
+    pub risk_publish_var_threshold_bps: u32,
+    pub risk_publish_daily_cap: u32,
+    pub pre_trade_budget_ms: u64,
+    pub correlation_min_observations: usize,
+    pub var_method: VaRMethod,
+    pub monte_carlo_simulations: usize,
+    pub price_history_days: usize,
+    /// Benchmark of portfolios without their own: an asset address or a benchmark index name
+    pub benchmark: Option<PriceSeries>,
+    /// Stablecoin address and peg pairs
+    pub stablecoin_pegs: Vec<(String, Decimal)>,
+    pub depeg_threshold_bps: u32,
+    pub depeg_sustain_secs: i64,
+    pub depeg_stressed_volatility: Decimal,
+    pub price_anomaly_max_sigmas: Decimal,
+    pub price_anomaly_lookback_days: i64,
+    pub price_anomaly_min_volatility: Decimal,
+    pub price_confirmation_tolerance_bps: u32,
+    /// `database` for the ingested `asset_market_depth` table, `amm` for on-chain pools
+    pub liquidity_source: String,
+    /// Asset address and AMM pool address pairs
+    pub liquidity_amm_pools: Vec<(String, String)>,
+    pub liquidity_amm_blocks_per_day: u64,
+    pub liquidity_max_age_hours: i64,
+    pub liquidity_participation_rate: Decimal,
+    pub liquidity_max_days: Decimal,
+    /// Reject calls without a service key instead of serving them unauthenticated
+    pub service_keys_required: bool,
 }
 
 impl Config {
@@ -45,6 +109,206 @@ impl Config {
             .parse::<u16>()
             .map_err(|_| "WS_PORT must be a valid port number")?;
         
+        let alert_resolve_after_cycles = env::var("ALERT_RESOLVE_AFTER_CYCLES")
+            .unwrap_or_else(|_| "3".to_string())
+            .parse::<u32>()
+            .map_err(|_| "ALERT_RESOLVE_AFTER_CYCLES must be a positive integer")?;
+        let alert_escalation_steps = env::var("ALERT_ESCALATION_STEPS")
+            .unwrap_or_else(|_| "1.5,2.0".to_string())
+            .split(',')
+            .map(|step| step.trim().parse::<Decimal>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| "ALERT_ESCALATION_STEPS must be a comma-separated list of decimals")?;
+        let alert_notify_cooldown_secs = env::var("ALERT_NOTIFY_COOLDOWN_SECS")
+            .unwrap_or_else(|_| "3600".to_string())
+            .parse::<u64>()
+            .map_err(|_| "ALERT_NOTIFY_COOLDOWN_SECS must be a non-negative integer")?;
+        
+        // Alert webhooks: a slow endpoint times out per request and is retried with backoff
+        let webhook_timeout_ms = env::var("WEBHOOK_TIMEOUT_MS")
+            .unwrap_or_else(|_| "5000".to_string())
+            .parse::<u64>()
+            .map_err(|_| "WEBHOOK_TIMEOUT_MS must be a positive integer")?;
+        let webhook_max_attempts = env::var("WEBHOOK_MAX_ATTEMPTS")
+            .unwrap_or_else(|_| "5".to_string())
+            .parse::<u32>()
+            .map_err(|_| "WEBHOOK_MAX_ATTEMPTS must be a positive integer")?;
+        let webhook_initial_backoff_ms = env::var("WEBHOOK_INITIAL_BACKOFF_MS")
+            .unwrap_or_else(|_| "1000".to_string())
+            .parse::<u64>()
+            .map_err(|_| "WEBHOOK_INITIAL_BACKOFF_MS must be a non-negative integer")?;
+        let webhook_max_backoff_secs = env::var("WEBHOOK_MAX_BACKOFF_SECS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse::<u64>()
+            .map_err(|_| "WEBHOOK_MAX_BACKOFF_SECS must be a non-negative integer")?;
+        
+        let export_dir = env::var("EXPORT_DIR")
+            .unwrap_or_else(|_| std::env::temp_dir().join("quantera-risk-exports").to_string_lossy().into_owned());
+        let export_max_rows = env::var("EXPORT_MAX_ROWS")
+            .unwrap_or_else(|_| "1000000".to_string())
+            .parse::<u64>()
+            .map_err(|_| "EXPORT_MAX_ROWS must be a positive integer")?;
+        let export_url_ttl_secs = env::var("EXPORT_URL_TTL_SECS")
+            .unwrap_or_else(|_| "3600".to_string())
+            .parse::<i64>()
+            .map_err(|_| "EXPORT_URL_TTL_SECS must be a positive integer")?;
+        let export_signing_key = env::var("EXPORT_SIGNING_KEY").ok().filter(|key| !key.is_empty());
+        
+        let trading_module_address = env::var("TRADING_MODULE_ADDRESS").ok().filter(|address| !address.is_empty());
+        let acquisition_sync_interval_secs = env::var("ACQUISITION_SYNC_INTERVAL_SECS")
+            .unwrap_or_else(|_| "300".to_string())
+            .parse::<u64>()
+            .map_err(|_| "ACQUISITION_SYNC_INTERVAL_SECS must be a positive integer")?;
+        
+        let factor_model_path = env::var("FACTOR_MODEL_PATH").ok().filter(|path| !path.is_empty());
+        
+        let price_ingestion_interval_secs = env::var("PRICE_INGESTION_INTERVAL_SECS")
+            .unwrap_or_else(|_| "900".to_string())
+            .parse::<u64>()
+            .map_err(|_| "PRICE_INGESTION_INTERVAL_SECS must be a positive integer")?;
+        
+        let ws_broadcast_capacity = env::var("WS_BROADCAST_CAPACITY")
+            .unwrap_or_else(|_| "64".to_string())
+            .parse::<usize>()
+            .map_err(|_| "WS_BROADCAST_CAPACITY must be a positive integer")?;
+        
+        let price_feed_url = env::var("PRICE_FEED_URL").ok().filter(|url| !url.is_empty());
+        let price_feed_api_key = env::var("PRICE_FEED_API_KEY").ok().filter(|key| !key.is_empty());
+        let backfill_page_size = env::var("BACKFILL_PAGE_SIZE")
+            .unwrap_or_else(|_| "365".to_string())
+            .parse::<usize>()
+            .map_err(|_| "BACKFILL_PAGE_SIZE must be a positive integer")?;
+        let backfill_max_gap_days = env::var("BACKFILL_MAX_GAP_DAYS")
+            .unwrap_or_else(|_| "4".to_string())
+            .parse::<i64>()
+            .map_err(|_| "BACKFILL_MAX_GAP_DAYS must be a positive integer")?;
+        let backfill_min_request_interval_ms = env::var("BACKFILL_MIN_REQUEST_INTERVAL_MS")
+            .unwrap_or_else(|_| "250".to_string())
+            .parse::<u64>()
+            .map_err(|_| "BACKFILL_MIN_REQUEST_INTERVAL_MS must be a non-negative integer")?;
+        
+        // Risk grades are published on chain only with a key holding the RiskEngine risk manager role
+        let risk_publisher_private_key = env::var("RISK_PUBLISHER_PRIVATE_KEY").ok().filter(|key| !key.is_empty());
+        let risk_publish_var_threshold_bps = env::var("RISK_PUBLISH_VAR_THRESHOLD_BPS")
+            .unwrap_or_else(|_| "25".to_string())
+            .parse::<u32>()
+            .map_err(|_| "RISK_PUBLISH_VAR_THRESHOLD_BPS must be a non-negative integer")?;
+        let risk_publish_daily_cap = env::var("RISK_PUBLISH_DAILY_CAP")
+            .unwrap_or_else(|_| "4".to_string())
+            .parse::<u32>()
+            .map_err(|_| "RISK_PUBLISH_DAILY_CAP must be a positive integer")?;
+        
+        // Pre-trade evaluations that take longer degrade to warn so order placement never stalls
+        let pre_trade_budget_ms = env::var("PRE_TRADE_BUDGET_MS")
+            .unwrap_or_else(|_| "250".to_string())
+            .parse::<u64>()
+            .map_err(|_| "PRE_TRADE_BUDGET_MS must be a positive integer")?;
+        
+        // Asset pairs with fewer shared return days get zero correlation rather than a noisy one
+        let correlation_min_observations = env::var("CORRELATION_MIN_OBSERVATIONS")
+            .unwrap_or_else(|_| "20".to_string())
+            .parse::<usize>()
+            .map_err(|_| "CORRELATION_MIN_OBSERVATIONS must be a positive integer")?;
+        
+        // VaR method when a request does not pick one
+        let var_method = env::var("VAR_METHOD")
+            .unwrap_or_else(|_| "monte_carlo".to_string())
+            .parse::<VaRMethod>()
+            .map_err(|_| "VAR_METHOD must be monte_carlo, historical or parametric")?;
+        
+        let monte_carlo_simulations = env::var("MONTE_CARLO_SIMULATIONS")
+            .unwrap_or_else(|_| "10000".to_string())
+            .parse::<usize>()
+            .map_err(|_| "MONTE_CARLO_SIMULATIONS must be a positive integer")?;
+        
+        // Daily prices per asset behind returns, VaR and drawdown
+        let price_history_days = env::var("PRICE_HISTORY_DAYS")
+            .unwrap_or_else(|_| "100".to_string())
+            .parse::<usize>()
+            .map_err(|_| "PRICE_HISTORY_DAYS must be a positive integer")?;
+        
+        // Portfolios without a benchmark of their own report no beta or alpha unless this is set
+        let benchmark = env::var("RISK_BENCHMARK").ok()
+            .filter(|value| !value.trim().is_empty())
+            .map(|value| crate::benchmark::parse(&value))
+            .transpose()
+            .map_err(|e| format!("RISK_BENCHMARK: {}", e))?;
+        
+        // Stablecoins watched for depegs, as `address=peg` pairs; more can be added through the admin API
+        let stablecoin_pegs = env::var("STABLECOIN_PEGS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (address, peg) = entry.split_once('=').ok_or(())?;
+                Ok((address.trim().to_string(), peg.trim().parse::<Decimal>().map_err(|_| ())?))
+            })
+            .collect::<Result<Vec<_>, ()>>()
+            .map_err(|_| "STABLECOIN_PEGS must be a comma-separated list of address=peg pairs")?;
+        let depeg_threshold_bps = env::var("DEPEG_THRESHOLD_BPS")
+            .unwrap_or_else(|_| "100".to_string())
+            .parse::<u32>()
+            .map_err(|_| "DEPEG_THRESHOLD_BPS must be a positive integer")?;
+        let depeg_sustain_secs = env::var("DEPEG_SUSTAIN_SECS")
+            .unwrap_or_else(|_| "1800".to_string())
+            .parse::<i64>()
+            .map_err(|_| "DEPEG_SUSTAIN_SECS must be a non-negative integer")?;
+        let depeg_stressed_volatility = env::var("DEPEG_STRESSED_VOLATILITY")
+            .unwrap_or_else(|_| "0.10".to_string())
+            .parse::<Decimal>()
+            .map_err(|_| "DEPEG_STRESSED_VOLATILITY must be a decimal daily volatility")?;
+        
+        // Ingested prices moving further than this from recent history are quarantined for review
+        let price_anomaly_max_sigmas = env::var("PRICE_ANOMALY_MAX_SIGMAS")
+            .unwrap_or_else(|_| "6".to_string())
+            .parse::<Decimal>()
+            .map_err(|_| "PRICE_ANOMALY_MAX_SIGMAS must be a decimal multiple of daily volatility")?;
+        let price_anomaly_lookback_days = env::var("PRICE_ANOMALY_LOOKBACK_DAYS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse::<i64>()
+            .map_err(|_| "PRICE_ANOMALY_LOOKBACK_DAYS must be a positive integer")?;
+        let price_anomaly_min_volatility = env::var("PRICE_ANOMALY_MIN_VOLATILITY")
+            .unwrap_or_else(|_| "0.005".to_string())
+            .parse::<Decimal>()
+            .map_err(|_| "PRICE_ANOMALY_MIN_VOLATILITY must be a decimal daily volatility")?;
+        let price_confirmation_tolerance_bps = env::var("PRICE_CONFIRMATION_TOLERANCE_BPS")
+            .unwrap_or_else(|_| "100".to_string())
+            .parse::<u32>()
+            .map_err(|_| "PRICE_CONFIRMATION_TOLERANCE_BPS must be a non-negative integer")?;
+        
+        // Liquidity is scored from ingested market depth or read from AMM pools on chain
+        let liquidity_source = env::var("LIQUIDITY_SOURCE").unwrap_or_else(|_| "database".to_string());
+        let liquidity_amm_pools = env::var("LIQUIDITY_AMM_POOLS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| entry.split_once('=').map(|(asset, pool)| (asset.trim().to_string(), pool.trim().to_string())))
+            .collect::<Option<Vec<_>>>()
+            .ok_or("LIQUIDITY_AMM_POOLS must be a comma-separated list of asset=pool pairs")?;
+        let liquidity_amm_blocks_per_day = env::var("LIQUIDITY_AMM_BLOCKS_PER_DAY")
+            .unwrap_or_else(|_| "7200".to_string())
+            .parse::<u64>()
+            .map_err(|_| "LIQUIDITY_AMM_BLOCKS_PER_DAY must be a positive integer")?;
+        let liquidity_max_age_hours = env::var("LIQUIDITY_MAX_AGE_HOURS")
+            .unwrap_or_else(|_| crate::liquidity::DEFAULT_MAX_AGE_HOURS.to_string())
+            .parse::<i64>()
+            .map_err(|_| "LIQUIDITY_MAX_AGE_HOURS must be a positive integer")?;
+        let liquidity_participation_rate = env::var("LIQUIDITY_PARTICIPATION_RATE")
+            .unwrap_or_else(|_| "0.2".to_string())
+            .parse::<Decimal>()
+            .map_err(|_| "LIQUIDITY_PARTICIPATION_RATE must be a decimal share of daily volume")?;
+        let liquidity_max_days = env::var("LIQUIDITY_MAX_DAYS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse::<Decimal>()
+            .map_err(|_| "LIQUIDITY_MAX_DAYS must be a decimal number of days")?;
+        
+        let service_keys_required = env::var("SERVICE_KEYS_REQUIRED")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse::<bool>()
+            .map_err(|_| "SERVICE_KEYS_REQUIRED must be true or false")?;
+        
         let config = Config {
             database_url,
             redis_url,
@@ -54,6 +318,51 @@ impl Config {
             log_level,
             http_port,
             ws_port,
+            alert_resolve_after_cycles,
+            alert_escalation_steps,
+            alert_notify_cooldown_secs,
+            webhook_timeout_ms,
+            webhook_max_attempts,
+            webhook_initial_backoff_ms,
+            webhook_max_backoff_secs,
+            export_dir,
+            export_max_rows,
+            export_url_ttl_secs,
+            export_signing_key,
+            trading_module_address,
+            acquisition_sync_interval_secs,
+            factor_model_path,
+            price_ingestion_interval_secs,
+            ws_broadcast_capacity,
+            price_feed_url,
+            price_feed_api_key,
+            backfill_page_size,
+            backfill_max_gap_days,
+            backfill_min_request_interval_ms,
+            risk_publisher_private_key,
+            risk_publish_var_threshold_bps,
+            risk_publish_daily_cap,
+            pre_trade_budget_ms,
+            correlation_min_observations,
+            var_method,
+            monte_carlo_simulations,
+            price_history_days,
+            benchmark,
+            stablecoin_pegs,
+            depeg_threshold_bps,
+            depeg_sustain_secs,
+            depeg_stressed_volatility,
+            price_anomaly_max_sigmas,
+            price_anomaly_lookback_days,
+            price_anomaly_min_volatility,
+            price_confirmation_tolerance_bps,
+            liquidity_source,
+            liquidity_amm_pools,
+            liquidity_amm_blocks_per_day,
+            liquidity_max_age_hours,
+            liquidity_participation_rate,
+            liquidity_max_days,
+            service_keys_required,
         };
         
         info!("Configuration loaded successfully");
@@ -86,6 +395,231 @@ impl Config {
             return Err("RISK_ENGINE_ADDRESS must be a valid Ethereum address (0x followed by 40 hex characters)".to_string());
         }
         
+        if self.alert_resolve_after_cycles == 0 {
+            return Err("ALERT_RESOLVE_AFTER_CYCLES must be at least 1".to_string());
+        }
+        
+        if self.alert_escalation_steps.iter().any(|step| *step <= Decimal::ONE) {
+            return Err("ALERT_ESCALATION_STEPS must all be greater than 1.0".to_string());
+        }
+        
+        if self.export_max_rows == 0 {
+            return Err("EXPORT_MAX_ROWS must be at least 1".to_string());
+        }
+        
+        if self.export_url_ttl_secs <= 0 {
+            return Err("EXPORT_URL_TTL_SECS must be at least 1".to_string());
+        }
+        
+        if let Some(address) = &self.trading_module_address {
+            if !address.starts_with("0x") || address.len() != 42 {
+                return Err("TRADING_MODULE_ADDRESS must be a valid Ethereum address (0x followed by 40 hex characters)".to_string());
+            }
+        }
+        
+        if self.acquisition_sync_interval_secs == 0 {
+            return Err("ACQUISITION_SYNC_INTERVAL_SECS must be at least 1".to_string());
+        }
+        
+        if self.price_ingestion_interval_secs == 0 {
+            return Err("PRICE_INGESTION_INTERVAL_SECS must be at least 1".to_string());
+        }
+        
+        if self.ws_broadcast_capacity == 0 {
+            return Err("WS_BROADCAST_CAPACITY must be at least 1".to_string());
+        }
+        
+        if let Some(url) = &self.price_feed_url {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err("PRICE_FEED_URL must start with http:// or https://".to_string());
+            }
+        }
+        
+        if self.backfill_page_size == 0 {
+            return Err("BACKFILL_PAGE_SIZE must be at least 1".to_string());
+        }
+        
+        if self.backfill_max_gap_days < 1 {
+            return Err("BACKFILL_MAX_GAP_DAYS must be at least 1".to_string());
+        }
+        
+        if self.pre_trade_budget_ms == 0 {
+            return Err("PRE_TRADE_BUDGET_MS must be at least 1".to_string());
+        }
+        
+        if self.webhook_timeout_ms == 0 || self.webhook_max_attempts == 0 {
+            return Err("WEBHOOK_TIMEOUT_MS and WEBHOOK_MAX_ATTEMPTS must be at least 1".to_string());
+        }
+        
+        if self.correlation_min_observations < 2 {
+            return Err("CORRELATION_MIN_OBSERVATIONS must be at least 2".to_string());
+        }
+        
+        if self.monte_carlo_simulations == 0 {
+            return Err("MONTE_CARLO_SIMULATIONS must be at least 1".to_string());
+        }
+        
+        if self.price_history_days < MIN_OBSERVATIONS {
+            return Err(format!("PRICE_HISTORY_DAYS must be at least {}", MIN_OBSERVATIONS));
+        }
+        
+        if self.risk_publish_daily_cap == 0 {
+            return Err("RISK_PUBLISH_DAILY_CAP must be at least 1".to_string());
+        }
+        
+        for (address, peg) in &self.stablecoin_pegs {
+            if !address.starts_with("0x") || address.len() != 42 {
+                return Err(format!("STABLECOIN_PEGS address {} must be 0x followed by 40 hex characters", address));
+            }
+            if *peg <= Decimal::ZERO {
+                return Err(format!("STABLECOIN_PEGS peg of {} must be positive", address));
+            }
+        }
+        
+        if self.depeg_threshold_bps == 0 {
+            return Err("DEPEG_THRESHOLD_BPS must be at least 1".to_string());
+        }
+        
+        if self.depeg_sustain_secs < 0 {
+            return Err("DEPEG_SUSTAIN_SECS cannot be negative".to_string());
+        }
+        
+        if self.price_anomaly_max_sigmas <= Decimal::ZERO {
+            return Err("PRICE_ANOMALY_MAX_SIGMAS must be positive".to_string());
+        }
+        
+        if self.price_anomaly_lookback_days < 2 {
+            return Err("PRICE_ANOMALY_LOOKBACK_DAYS must be at least 2".to_string());
+        }
+        
+        if self.price_anomaly_min_volatility < Decimal::ZERO {
+            return Err("PRICE_ANOMALY_MIN_VOLATILITY must not be negative".to_string());
+        }
+        
+        if self.depeg_stressed_volatility <= Decimal::ZERO {
+            return Err("DEPEG_STRESSED_VOLATILITY must be positive".to_string());
+        }
+        
+        match self.liquidity_source.as_str() {
+            "database" => {}
+            "amm" if self.liquidity_amm_pools.is_empty() => {
+                return Err("LIQUIDITY_AMM_POOLS must list asset=pool pairs when LIQUIDITY_SOURCE is amm".to_string());
+            }
+            "amm" => {}
+            _ => return Err("LIQUIDITY_SOURCE must be database or amm".to_string()),
+        }
+        
+        for (asset, pool) in &self.liquidity_amm_pools {
+            for address in [asset, pool] {
+                if !address.starts_with("0x") || address.len() != 42 {
+                    return Err(format!("LIQUIDITY_AMM_POOLS address {} must be 0x followed by 40 hex characters", address));
+                }
+            }
+        }
+        
+        if self.liquidity_amm_blocks_per_day == 0 {
+            return Err("LIQUIDITY_AMM_BLOCKS_PER_DAY must be at least 1".to_string());
+        }
+        
+        if self.liquidity_max_age_hours < 1 {
+            return Err("LIQUIDITY_MAX_AGE_HOURS must be at least 1".to_string());
+        }
+        
+        if self.liquidity_participation_rate <= Decimal::ZERO || self.liquidity_participation_rate > Decimal::ONE {
+            return Err("LIQUIDITY_PARTICIPATION_RATE must be above 0 and at most 1".to_string());
+        }
+        
+        if self.liquidity_max_days <= Decimal::ONE {
+            return Err("LIQUIDITY_MAX_DAYS must be greater than 1".to_string());
+        }
+        
         Ok(())
     }
+    
+    /// Alert deduplication and escalation policy
+    pub fn alert_policy(&self) -> AlertPolicy {
+        let mut escalation_steps = self.alert_escalation_steps.clone();
+        escalation_steps.sort();
+        
+        AlertPolicy {
+            resolve_after_cycles: self.alert_resolve_after_cycles,
+            escalation_steps,
+            notify_cooldown_secs: self.alert_notify_cooldown_secs,
+        }
+    }
+    
+    /// Timeout and retry schedule of alert webhook deliveries
+    pub fn webhook_config(&self) -> WebhookConfig {
+        WebhookConfig {
+            timeout: std::time::Duration::from_millis(self.webhook_timeout_ms),
+            max_attempts: self.webhook_max_attempts,
+            initial_backoff: std::time::Duration::from_millis(self.webhook_initial_backoff_ms),
+            max_backoff: std::time::Duration::from_secs(self.webhook_max_backoff_secs),
+        }
+    }
+    
+    /// Change threshold and daily cap for on-chain risk grade publication
+    pub fn publication_policy(&self) -> PublicationPolicy {
+        PublicationPolicy {
+            var_threshold_bps: self.risk_publish_var_threshold_bps,
+            daily_cap: self.risk_publish_daily_cap,
+        }
+    }
+    
+    /// Depeg monitor watching the configured stablecoins
+    pub fn depeg_monitor(&self) -> Result<DepegMonitor, String> {
+        let monitor = DepegMonitor::new(DepegConfig {
+            threshold_bps: self.depeg_threshold_bps,
+            sustain: chrono::Duration::seconds(self.depeg_sustain_secs),
+            stressed_volatility: self.depeg_stressed_volatility,
+        });
+        for (address, peg) in &self.stablecoin_pegs {
+            let asset = address.parse::<Address>()
+                .map_err(|e| format!("Invalid STABLECOIN_PEGS address {}: {}", address, e))?;
+            monitor.set_peg(asset, *peg).map_err(|e| e.to_string())?;
+        }
+        Ok(monitor)
+    }
+    
+    /// Anomaly gate applied to ingested oracle prices
+    pub fn anomaly_config(&self) -> AnomalyConfig {
+        AnomalyConfig {
+            max_move_sigmas: self.price_anomaly_max_sigmas,
+            lookback_days: self.price_anomaly_lookback_days,
+            min_volatility: self.price_anomaly_min_volatility,
+            confirmation_tolerance_bps: self.price_confirmation_tolerance_bps,
+            ..AnomalyConfig::default()
+        }
+    }
+    
+    /// Liquidity model reading market data from the configured source
+    pub fn liquidity_model(&self, eth_client: Arc<EthereumClient>, db: Arc<PgPool>) -> Result<LiquidityModel, String> {
+        let source: Arc<dyn MarketDepthSource> = match self.liquidity_source.as_str() {
+            "amm" => {
+                let mut pools = HashMap::new();
+                for (asset, pool) in &self.liquidity_amm_pools {
+                    let asset = asset.parse::<Address>().map_err(|e| format!("Invalid LIQUIDITY_AMM_POOLS asset {}: {}", asset, e))?;
+                    let pool = pool.parse::<Address>().map_err(|e| format!("Invalid LIQUIDITY_AMM_POOLS pool {}: {}", pool, e))?;
+                    pools.insert(asset, pool);
+                }
+                Arc::new(AmmDepthSource::new(eth_client, pools, self.liquidity_amm_blocks_per_day))
+            }
+            _ => Arc::new(DbMarketDepthSource::new(db, chrono::Duration::hours(self.liquidity_max_age_hours))),
+        };
+        Ok(LiquidityModel::new(source, LiquidityConfig {
+            participation_rate: self.liquidity_participation_rate,
+            max_days_to_liquidate: self.liquidity_max_days,
+            ..LiquidityConfig::default()
+        }))
+    }
+    
+    /// Paging, gap detection and request pacing for historical price backfills
+    pub fn backfill_config(&self) -> BackfillConfig {
+        BackfillConfig {
+            page_size: self.backfill_page_size,
+            max_gap_days: self.backfill_max_gap_days,
+            min_request_interval: std::time::Duration::from_millis(self.backfill_min_request_interval_ms),
+            ..BackfillConfig::default()
+        }
+    }
 }
@@ -0,0 +1,294 @@
+// Stablecoin depeg detection on ingested oracle prices
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use crate::acquisitions::parse_decimal;
+use crate::alerts::LimitEvaluation;
+use crate::ethereum_client::Address;
+use crate::{AlertSeverity, AlertType, RiskServiceError};
+
+/// When a deviation from peg counts as a depeg and how it is stressed in VaR
+#[derive(Debug, Clone)]
+pub struct DepegConfig {
+    /// Deviation from peg, in basis points, beyond which the asset is off its band
+    pub threshold_bps: u32,
+    /// How long the price must stay off its band before the asset is treated as depegged
+    pub sustain: Duration,
+    /// Daily volatility used for a depegged asset in VaR instead of its usual input
+    pub stressed_volatility: Decimal,
+}
+
+impl Default for DepegConfig {
+    fn default() -> Self {
+        Self {
+            threshold_bps: 100,
+            sustain: Duration::minutes(30),
+            stressed_volatility: dec!(0.10),
+        }
+    }
+}
+
+impl DepegConfig {
+    /// Band half-width as a fraction of the peg
+    pub fn threshold(&self) -> Decimal {
+        Decimal::from(self.threshold_bps) / Decimal::from(10_000)
+    }
+}
+
+/// A stablecoin, its peg and where its price stands against it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PegStatus {
+    pub asset: Address,
+    pub peg: Decimal,
+    pub last_price: Option<Decimal>,
+    /// |price - peg| / peg at the last observation
+    pub deviation: Option<Decimal>,
+    /// First observation of the current excursion off the band
+    pub off_band_since: Option<DateTime<Utc>>,
+    /// Set while the asset is treated as depegged
+    pub depegged_since: Option<DateTime<Utc>>,
+}
+
+/// Change in an asset's depeg state caused by a price observation
+#[derive(Debug, Clone, PartialEq)]
+pub enum DepegTransition {
+    Depegged { asset: Address, peg: Decimal, price: Decimal, deviation: Decimal },
+    Recovered { asset: Address, peg: Decimal, price: Decimal },
+}
+
+impl DepegTransition {
+    pub fn asset(&self) -> Address {
+        match self {
+            DepegTransition::Depegged { asset, .. } | DepegTransition::Recovered { asset, .. } => *asset,
+        }
+    }
+}
+
+/// Alert limit for a depeg of `asset`; one open alert per portfolio and stablecoin
+pub fn depeg_limit(asset: Address) -> String {
+    format!("depeg:{:?}", asset)
+}
+
+/// Breach raised on every portfolio holding an asset that has depegged
+pub fn depeg_evaluation(config: &DepegConfig, asset: Address, peg: Decimal, price: Decimal, deviation: Decimal) -> LimitEvaluation {
+    LimitEvaluation {
+        alert_type: AlertType::DepegRisk,
+        limit: depeg_limit(asset),
+        base_severity: AlertSeverity::Critical,
+        metric_value: deviation,
+        threshold: config.threshold(),
+        message: format!("Stablecoin {:?} trading at {} against a peg of {} ({:.2}% off)", asset, price, peg, deviation * Decimal::from(100)),
+        breached: true,
+    }
+}
+
+/// Tracks stablecoin prices against their pegs.
+///
+/// An asset is depegged once its price has stayed off the band for the configured
+/// window, and recovers on the first observation back within the band.
+#[derive(Debug, Default)]
+pub struct DepegMonitor {
+    config: DepegConfig,
+    pegs: RwLock<HashMap<Address, PegStatus>>,
+}
+
+impl DepegMonitor {
+    pub fn new(config: DepegConfig) -> Self {
+        Self { config, pegs: RwLock::new(HashMap::new()) }
+    }
+
+    pub fn config(&self) -> &DepegConfig {
+        &self.config
+    }
+
+    /// Watch `asset` against `peg`, keeping its state if it was already watched at that peg
+    pub fn set_peg(&self, asset: Address, peg: Decimal) -> Result<PegStatus, RiskServiceError> {
+        if peg <= Decimal::ZERO {
+            return Err(RiskServiceError::InvalidInput(format!("Peg of {:?} must be positive", asset)));
+        }
+        let mut pegs = self.pegs.write().expect("depeg monitor lock poisoned");
+        let status = pegs.entry(asset).or_insert_with(|| PegStatus {
+            asset,
+            peg,
+            last_price: None,
+            deviation: None,
+            off_band_since: None,
+            depegged_since: None,
+        });
+        if status.peg != peg {
+            *status = PegStatus { asset, peg, last_price: None, deviation: None, off_band_since: None, depegged_since: None };
+        }
+        Ok(status.clone())
+    }
+
+    /// Stop watching `asset`; returns its last state
+    pub fn remove_peg(&self, asset: Address) -> Option<PegStatus> {
+        self.pegs.write().expect("depeg monitor lock poisoned").remove(&asset)
+    }
+
+    /// Every watched stablecoin
+    pub fn pegs(&self) -> Vec<PegStatus> {
+        let mut pegs: Vec<PegStatus> = self.pegs.read().expect("depeg monitor lock poisoned").values().cloned().collect();
+        pegs.sort_by_key(|status| status.asset);
+        pegs
+    }
+
+    /// Record an oracle price; returns the transition it caused, if any
+    pub fn observe(&self, asset: Address, price: Decimal, now: DateTime<Utc>) -> Option<DepegTransition> {
+        let mut pegs = self.pegs.write().expect("depeg monitor lock poisoned");
+        let status = pegs.get_mut(&asset)?;
+        let deviation = (price - status.peg).abs() / status.peg;
+        status.last_price = Some(price);
+        status.deviation = Some(deviation);
+
+        if deviation <= self.config.threshold() {
+            status.off_band_since = None;
+            return status.depegged_since.take()
+                .map(|_| DepegTransition::Recovered { asset, peg: status.peg, price });
+        }
+
+        let off_band_since = *status.off_band_since.get_or_insert(now);
+        if status.depegged_since.is_none() && now - off_band_since >= self.config.sustain {
+            status.depegged_since = Some(now);
+            return Some(DepegTransition::Depegged { asset, peg: status.peg, price, deviation });
+        }
+        None
+    }
+
+    /// Stressed VaR volatility of each currently depegged asset
+    pub fn volatility_overrides(&self) -> HashMap<Address, Decimal> {
+        self.pegs.read().expect("depeg monitor lock poisoned")
+            .values()
+            .filter(|status| status.depegged_since.is_some())
+            .map(|status| (status.asset, self.config.stressed_volatility))
+            .collect()
+    }
+}
+
+/// Receives depeg transitions from price ingestion
+#[async_trait]
+pub trait DepegAlerter: Send + Sync {
+    async fn depeg_changed(&self, transition: &DepegTransition) -> Result<(), RiskServiceError>;
+}
+
+/// Pegs added or removed through the admin API; a removed row disables a configured peg
+pub async fn load_saved_pegs(db: &PgPool) -> Result<Vec<(Address, Option<Decimal>)>, RiskServiceError> {
+    let rows: Vec<(String, String, bool)> = sqlx::query_as(
+        "SELECT asset_address, peg::text, active FROM stablecoin_pegs"
+    )
+        .fetch_all(db)
+        .await?;
+
+    rows.into_iter()
+        .map(|(asset, peg, active)| {
+            let asset = asset.parse::<Address>()
+                .map_err(|e| RiskServiceError::CalculationError(format!("Invalid stablecoin address {}: {}", asset, e)))?;
+            Ok((asset, if active { Some(parse_decimal(&peg)?) } else { None }))
+        })
+        .collect()
+}
+
+/// Store a peg, or with `None` record that the asset is no longer a watched stablecoin
+pub async fn save_peg(db: &PgPool, asset: Address, peg: Option<Decimal>) -> Result<(), RiskServiceError> {
+    sqlx::query(r#"
+        INSERT INTO stablecoin_pegs (asset_address, peg, active, updated_at)
+        VALUES ($1, $2::numeric, $3, NOW())
+        ON CONFLICT (asset_address) DO UPDATE SET
+            peg = EXCLUDED.peg,
+            active = EXCLUDED.active,
+            updated_at = NOW()
+    "#)
+        .bind(format!("{:?}", asset))
+        .bind(peg.unwrap_or(Decimal::ONE).to_string())
+        .bind(peg.is_some())
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::{AlertPolicy, AlertTracker};
+    use crate::metrics::{simulate_portfolio_returns, TailRisk};
+    use crate::{AlertStatus, PortfolioPosition};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn position(asset: Address, price: Decimal) -> PortfolioPosition {
+        PortfolioPosition {
+            asset,
+            amount: Decimal::from(1000),
+            current_price: price,
+            entry_price: Decimal::ONE,
+            unrealized_pnl: Decimal::ZERO,
+            entry_price_provenance: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_sustained_five_percent_depeg_alerts_and_stresses_var() {
+        let monitor = DepegMonitor::new(DepegConfig::default());
+        let (usdc, other) = (Address::repeat_byte(0xaa), Address::repeat_byte(0xbb));
+        let portfolio = Address::repeat_byte(1);
+        monitor.set_peg(usdc, Decimal::ONE).unwrap();
+        let start = Utc::now();
+
+        assert_eq!(monitor.observe(usdc, dec!(0.95), start), None);
+        assert_eq!(monitor.observe(usdc, dec!(0.95), start + Duration::minutes(15)), None);
+        let transition = monitor.observe(usdc, dec!(0.95), start + Duration::minutes(30)).unwrap();
+        let DepegTransition::Depegged { peg, price, deviation, .. } = transition else { panic!("expected a depeg") };
+        assert_eq!(deviation, dec!(0.05));
+        // Still off the band: no repeated transition
+        assert_eq!(monitor.observe(usdc, dec!(0.94), start + Duration::minutes(45)), None);
+
+        let mut tracker = AlertTracker::new(AlertPolicy::default());
+        let evaluation = depeg_evaluation(monitor.config(), usdc, peg, price, deviation);
+        let changed = tracker.record_cycle(portfolio, vec![evaluation], start + Duration::minutes(30));
+        assert_eq!(changed[0].alert.alert_type, AlertType::DepegRisk);
+        assert_eq!(changed[0].alert.limit, depeg_limit(usdc));
+
+        // The stablecoin barely moved before the depeg; the override stresses it in the simulation
+        let positions = vec![position(usdc, dec!(0.95)), position(other, Decimal::ONE)];
+        let returns: Vec<Vec<Decimal>> = (0..60)
+            .map(|day| vec![Decimal::new(day % 3 - 1, 5), Decimal::new((day * 7) % 11 - 5, 3)])
+            .collect();
+        let var_95 = |overrides: &HashMap<Address, Decimal>| {
+            let simulated = simulate_portfolio_returns(&mut StdRng::seed_from_u64(7), &returns, &positions, overrides, 1000).unwrap();
+            TailRisk::from_sorted(&simulated).unwrap().var_95
+        };
+        let (calm_var, stressed_var) = (var_95(&HashMap::new()), var_95(&monitor.volatility_overrides()));
+        assert!(stressed_var > calm_var);
+
+        // Back within the band clears the override and resolves the alert at once
+        let recovered = monitor.observe(usdc, dec!(0.998), start + Duration::hours(2)).unwrap();
+        assert!(matches!(recovered, DepegTransition::Recovered { .. }));
+        assert!(monitor.volatility_overrides().is_empty());
+        let resolved = tracker.resolve_all(AlertType::DepegRisk, &depeg_limit(usdc), start + Duration::hours(2));
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].alert.status, AlertStatus::Resolved);
+        assert!(tracker.open_alerts(portfolio).is_empty());
+    }
+
+    #[test]
+    fn test_brief_excursion_restarts_the_window() {
+        let monitor = DepegMonitor::new(DepegConfig::default());
+        let asset = Address::repeat_byte(0xcc);
+        monitor.set_peg(asset, Decimal::ONE).unwrap();
+        let start = Utc::now();
+
+        assert_eq!(monitor.observe(asset, dec!(0.97), start), None);
+        assert_eq!(monitor.observe(asset, dec!(0.995), start + Duration::minutes(20)), None);
+        assert_eq!(monitor.observe(asset, dec!(0.97), start + Duration::minutes(40)), None);
+        assert!(monitor.volatility_overrides().is_empty());
+        assert!(monitor.observe(asset, dec!(0.97), start + Duration::minutes(70)).is_some());
+
+        assert!(monitor.set_peg(asset, Decimal::ZERO).is_err());
+        assert_eq!(monitor.observe(Address::repeat_byte(0xdd), dec!(0.5), start), None);
+    }
+}
@@ -1,23 +1,97 @@
 // Mock Ethereum Client for testing
 use ethers::prelude::*;
 use std::sync::Arc;
+use quantera_types::{BlockTag, ChainHeads, FinalityPolicy};
 
 pub type Address = H160;
 
 #[derive(Clone)]
 pub struct EthereumClient {
     provider: Arc<Provider<Http>>,
+    /// Present when a signing key is configured; needed to send transactions
+    signer: Option<Arc<SignerMiddleware<Provider<Http>, LocalWallet>>>,
+    /// Confirmation rule for the provider's chain, with any environment overrides
+    finality: FinalityPolicy,
 }
 
 impl EthereumClient {
     pub async fn new(url: &str) -> Result<Self, Box<dyn std::error::Error>> {
         let provider = Provider::<Http>::try_from(url)?;
+        let finality = FinalityPolicy::from_env(provider.get_chainid().await?.as_u64())?;
         Ok(Self {
             provider: Arc::new(provider),
+            signer: None,
+            finality,
         })
     }
     
+    /// Sign transactions with the given hex private key, on the provider's chain
+    pub async fn with_signer(mut self, private_key: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let chain_id = self.provider.get_chainid().await?.as_u64();
+        let wallet = private_key.trim_start_matches("0x").parse::<LocalWallet>()?.with_chain_id(chain_id);
+        self.signer = Some(Arc::new(SignerMiddleware::new((*self.provider).clone(), wallet)));
+        Ok(self)
+    }
+    
     pub fn provider(&self) -> &Provider<Http> {
         &self.provider
     }
+    
+    /// Logs matching `filter`
+    pub async fn get_events(&self, filter: &Filter) -> Result<Vec<Log>, ProviderError> {
+        self.provider.get_logs(filter).await
+    }
+    
+    /// Latest block number
+    pub async fn block_number(&self) -> Result<u64, ProviderError> {
+        Ok(self.provider.get_block_number().await?.as_u64())
+    }
+    
+    /// Highest block the chain's finality policy treats as confirmed; event scanners read up
+    /// to here so reorged-out events are never ingested
+    pub async fn confirmed_block_number(&self) -> Result<u64, ProviderError> {
+        let mut heads = ChainHeads { latest: self.block_number().await?, ..Default::default() };
+        for tag in self.finality.tags() {
+            let block_number = match tag {
+                BlockTag::Safe => BlockNumber::Safe,
+                BlockTag::Finalized => BlockNumber::Finalized,
+            };
+            let number = self.provider.get_block(block_number).await?
+                .and_then(|block| block.number)
+                .ok_or_else(|| ProviderError::CustomError(format!("node returned no {} block", tag.as_str())))?
+                .as_u64();
+            match tag {
+                BlockTag::Safe => heads.safe = Some(number),
+                BlockTag::Finalized => heads.finalized = Some(number),
+            }
+        }
+        Ok(self.finality.confirmed_head(&heads).unwrap_or(0))
+    }
+    
+    /// Timestamp of a block, in seconds
+    pub async fn block_timestamp(&self, block_number: u64) -> Result<Option<u64>, ProviderError> {
+        Ok(self.provider.get_block(block_number).await?.map(|block| block.timestamp.as_u64()))
+    }
+    
+    /// Read-only contract call with pre-encoded calldata
+    pub async fn call(&self, to: Address, data: Bytes) -> Result<Bytes, ProviderError> {
+        let tx: ethers::types::transaction::eip2718::TypedTransaction = TransactionRequest::new().to(to).data(data).into();
+        self.provider.call(&tx, None).await
+    }
+    
+    /// Send a signed transaction and wait for it to be mined; reverted transactions are errors
+    pub async fn send_transaction(&self, to: Address, data: Bytes) -> Result<TransactionReceipt, String> {
+        let signer = self.signer.as_ref().ok_or("No signing key configured")?;
+        let pending = signer.send_transaction(TransactionRequest::new().to(to).data(data), None)
+            .await
+            .map_err(|e| e.to_string())?;
+        let receipt = pending.await
+            .map_err(|e| e.to_string())?
+            .ok_or("Transaction was dropped from the mempool")?;
+        
+        if receipt.status != Some(U64::from(1)) {
+            return Err(format!("Transaction {:?} reverted", receipt.transaction_hash));
+        }
+        Ok(receipt)
+    }
 }
@@ -0,0 +1,678 @@
+// Bulk risk metrics export to CSV and Parquet for quant teams
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use arrow::array::{ArrayRef, Float64Builder, Int32Builder, StringBuilder, TimestampMicrosecondBuilder};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use chrono::{DateTime, Duration, Utc};
+use futures::stream::{Stream, StreamExt};
+use hmac::{Hmac, Mac};
+use parquet::arrow::ArrowWriter;
+use parquet::file::metadata::KeyValue;
+use parquet::file::properties::WriterProperties;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sqlx::PgPool;
+use tokio::sync::RwLock;
+use tracing::{info, error};
+use uuid::Uuid;
+use crate::ethereum_client::Address;
+use crate::RiskServiceError;
+
+/// Rows fetched from Postgres and written per batch
+pub const EXPORT_BATCH_SIZE: usize = 5_000;
+
+/// Column naming conventions, written as `#` comment lines ahead of the CSV header and as
+/// `quantera.column_notes` key-value metadata in Parquet files
+pub const COLUMN_NOTES: &[&str] = &[
+    "Quantera risk metrics export; one row per stored risk calculation, ordered by portfolio_address then timestamp",
+    "timestamp: calculation time in UTC (RFC 3339 in CSV, microsecond timestamp in Parquet)",
+    "var_95, var_99, expected_shortfall: loss amounts in portfolio value units; other metrics are ratios",
+    "corr_<i>_<j>: correlation between positions i and j of the calculation (0-based, position order), upper triangle only (i < j); empty when the portfolio held fewer positions",
+];
+
+// ============ Requests & Jobs ============
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Parquet,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Parquet => "application/vnd.apache.parquet",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportRequest {
+    pub portfolios: Vec<Address>,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub format: ExportFormat,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExportJobStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportJob {
+    pub job_id: Uuid,
+    pub status: ExportJobStatus,
+    pub format: ExportFormat,
+    pub rows_written: u64,
+    /// Set when the row cap cut the export short
+    pub truncated: bool,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Signed download link, issued on completion
+    pub download_url: Option<String>,
+    pub download_expires_at: Option<DateTime<Utc>>,
+}
+
+/// Outcome of writing one export file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportSummary {
+    pub rows_written: u64,
+    pub truncated: bool,
+}
+
+// ============ Rows ============
+
+/// One stored risk calculation as exported
+#[derive(Debug, Clone, Default)]
+pub struct ExportRow {
+    pub portfolio_address: String,
+    pub timestamp: DateTime<Utc>,
+    pub var_95: Option<f64>,
+    pub var_99: Option<f64>,
+    pub expected_shortfall: Option<f64>,
+    pub sharpe_ratio: Option<f64>,
+    pub sortino_ratio: Option<f64>,
+    pub max_drawdown: Option<f64>,
+    pub beta: Option<f64>,
+    pub alpha: Option<f64>,
+    pub volatility: Option<f64>,
+    pub liquidity_score: Option<i32>,
+    pub concentration_risk: Option<f64>,
+    pub leverage_ratio: Option<f64>,
+    pub risk_grade: Option<String>,
+    pub correlation_matrix: Vec<Vec<f64>>,
+}
+
+impl ExportRow {
+    /// Correlation of positions `i` and `j`, if the matrix covers them
+    fn correlation(&self, i: usize, j: usize) -> Option<f64> {
+        self.correlation_matrix.get(i).and_then(|row| row.get(j)).copied()
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct MetricsRow {
+    portfolio_address: String,
+    timestamp: DateTime<Utc>,
+    var_95: Option<f64>,
+    var_99: Option<f64>,
+    expected_shortfall: Option<f64>,
+    sharpe_ratio: Option<f64>,
+    sortino_ratio: Option<f64>,
+    max_drawdown: Option<f64>,
+    beta: Option<f64>,
+    alpha: Option<f64>,
+    volatility: Option<f64>,
+    liquidity_score: Option<i32>,
+    concentration_risk: Option<f64>,
+    leverage_ratio: Option<f64>,
+    risk_grade: Option<String>,
+    correlation_matrix: Option<String>,
+}
+
+impl From<MetricsRow> for ExportRow {
+    fn from(row: MetricsRow) -> Self {
+        let correlation_matrix = row.correlation_matrix
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+
+        Self {
+            portfolio_address: row.portfolio_address,
+            timestamp: row.timestamp,
+            var_95: row.var_95,
+            var_99: row.var_99,
+            expected_shortfall: row.expected_shortfall,
+            sharpe_ratio: row.sharpe_ratio,
+            sortino_ratio: row.sortino_ratio,
+            max_drawdown: row.max_drawdown,
+            beta: row.beta,
+            alpha: row.alpha,
+            volatility: row.volatility,
+            liquidity_score: row.liquidity_score,
+            concentration_risk: row.concentration_risk,
+            leverage_ratio: row.leverage_ratio,
+            risk_grade: row.risk_grade,
+            correlation_matrix,
+        }
+    }
+}
+
+const METRIC_COLUMNS: [&str; 11] = [
+    "var_95", "var_99", "expected_shortfall", "sharpe_ratio", "sortino_ratio",
+    "max_drawdown", "beta", "alpha", "volatility", "concentration_risk", "leverage_ratio",
+];
+
+fn metric_values(row: &ExportRow) -> [Option<f64>; 11] {
+    [
+        row.var_95, row.var_99, row.expected_shortfall, row.sharpe_ratio, row.sortino_ratio,
+        row.max_drawdown, row.beta, row.alpha, row.volatility, row.concentration_risk, row.leverage_ratio,
+    ]
+}
+
+/// Upper-triangle position pairs for a correlation matrix of the given dimension
+fn correlation_pairs(dimension: usize) -> Vec<(usize, usize)> {
+    (0..dimension)
+        .flat_map(|i| (i + 1..dimension).map(move |j| (i, j)))
+        .collect()
+}
+
+/// Column names of an export whose largest correlation matrix has the given dimension
+pub fn export_columns(correlation_dimension: usize) -> Vec<String> {
+    let mut columns = vec!["portfolio_address".to_string(), "timestamp".to_string()];
+    columns.extend(METRIC_COLUMNS.iter().map(|c| c.to_string()));
+    columns.push("liquidity_score".to_string());
+    columns.push("risk_grade".to_string());
+    columns.extend(correlation_pairs(correlation_dimension).into_iter().map(|(i, j)| format!("corr_{}_{}", i, j)));
+    columns
+}
+
+// ============ Writers ============
+
+trait ExportWriter: Send {
+    fn write_batch(&mut self, rows: &[ExportRow]) -> Result<(), RiskServiceError>;
+    fn finish(self: Box<Self>) -> Result<(), RiskServiceError>;
+}
+
+fn export_error(e: impl std::fmt::Display) -> RiskServiceError {
+    RiskServiceError::ExportError(e.to_string())
+}
+
+struct CsvExportWriter {
+    writer: csv::Writer<File>,
+    correlation_pairs: Vec<(usize, usize)>,
+}
+
+impl CsvExportWriter {
+    fn create(path: &Path, correlation_dimension: usize) -> Result<Self, RiskServiceError> {
+        let mut file = File::create(path).map_err(export_error)?;
+        for note in COLUMN_NOTES {
+            std::io::Write::write_all(&mut file, format!("# {}\n", note).as_bytes()).map_err(export_error)?;
+        }
+
+        let mut writer = csv::Writer::from_writer(file);
+        writer.write_record(export_columns(correlation_dimension)).map_err(export_error)?;
+
+        Ok(Self {
+            writer,
+            correlation_pairs: correlation_pairs(correlation_dimension),
+        })
+    }
+}
+
+impl ExportWriter for CsvExportWriter {
+    fn write_batch(&mut self, rows: &[ExportRow]) -> Result<(), RiskServiceError> {
+        let optional = |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+
+        for row in rows {
+            let mut record = vec![row.portfolio_address.clone(), row.timestamp.to_rfc3339()];
+            record.extend(metric_values(row).into_iter().map(optional));
+            record.push(row.liquidity_score.map(|v| v.to_string()).unwrap_or_default());
+            record.push(row.risk_grade.clone().unwrap_or_default());
+            record.extend(self.correlation_pairs.iter().map(|&(i, j)| optional(row.correlation(i, j))));
+            self.writer.write_record(&record).map_err(export_error)?;
+        }
+
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<(), RiskServiceError> {
+        self.writer.flush().map_err(export_error)
+    }
+}
+
+struct ParquetExportWriter {
+    writer: ArrowWriter<File>,
+    schema: SchemaRef,
+    correlation_pairs: Vec<(usize, usize)>,
+}
+
+impl ParquetExportWriter {
+    fn create(path: &Path, correlation_dimension: usize) -> Result<Self, RiskServiceError> {
+        let columns = export_columns(correlation_dimension);
+        let fields: Vec<Field> = columns.iter().map(|name| match name.as_str() {
+            "portfolio_address" => Field::new(name, DataType::Utf8, false),
+            "timestamp" => Field::new(name, DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())), false),
+            "liquidity_score" => Field::new(name, DataType::Int32, true),
+            "risk_grade" => Field::new(name, DataType::Utf8, true),
+            _ => Field::new(name, DataType::Float64, true),
+        }).collect();
+        let schema = Arc::new(Schema::new(fields));
+
+        let properties = WriterProperties::builder()
+            .set_key_value_metadata(Some(vec![KeyValue::new(
+                "quantera.column_notes".to_string(),
+                COLUMN_NOTES.join("\n"),
+            )]))
+            .build();
+
+        let file = File::create(path).map_err(export_error)?;
+        let writer = ArrowWriter::try_new(file, schema.clone(), Some(properties)).map_err(export_error)?;
+
+        Ok(Self {
+            writer,
+            schema,
+            correlation_pairs: correlation_pairs(correlation_dimension),
+        })
+    }
+}
+
+impl ExportWriter for ParquetExportWriter {
+    fn write_batch(&mut self, rows: &[ExportRow]) -> Result<(), RiskServiceError> {
+        let mut addresses = StringBuilder::new();
+        let mut timestamps = TimestampMicrosecondBuilder::new().with_timezone("UTC");
+        let mut metrics: Vec<Float64Builder> = METRIC_COLUMNS.iter().map(|_| Float64Builder::new()).collect();
+        let mut liquidity = Int32Builder::new();
+        let mut grades = StringBuilder::new();
+        let mut correlations: Vec<Float64Builder> = self.correlation_pairs.iter().map(|_| Float64Builder::new()).collect();
+
+        for row in rows {
+            addresses.append_value(&row.portfolio_address);
+            timestamps.append_value(row.timestamp.timestamp_micros());
+            for (builder, value) in metrics.iter_mut().zip(metric_values(row)) {
+                builder.append_option(value);
+            }
+            liquidity.append_option(row.liquidity_score);
+            grades.append_option(row.risk_grade.as_deref());
+            for (builder, &(i, j)) in correlations.iter_mut().zip(&self.correlation_pairs) {
+                builder.append_option(row.correlation(i, j));
+            }
+        }
+
+        let mut arrays: Vec<ArrayRef> = vec![Arc::new(addresses.finish()), Arc::new(timestamps.finish())];
+        arrays.extend(metrics.iter_mut().map(|b| Arc::new(b.finish()) as ArrayRef));
+        arrays.push(Arc::new(liquidity.finish()));
+        arrays.push(Arc::new(grades.finish()));
+        arrays.extend(correlations.iter_mut().map(|b| Arc::new(b.finish()) as ArrayRef));
+
+        let batch = RecordBatch::try_new(self.schema.clone(), arrays).map_err(export_error)?;
+        self.writer.write(&batch).map_err(export_error)
+    }
+
+    fn finish(self: Box<Self>) -> Result<(), RiskServiceError> {
+        self.writer.close().map_err(export_error)?;
+        Ok(())
+    }
+}
+
+/// Write rows from `rows` to `path` in batches, stopping after `max_rows`.
+///
+/// `correlation_dimension` fixes the number of correlation columns; matrices of smaller
+/// portfolios leave the remaining columns empty.
+pub async fn write_export<S>(
+    rows: S,
+    format: ExportFormat,
+    path: &Path,
+    correlation_dimension: usize,
+    max_rows: u64,
+) -> Result<ExportSummary, RiskServiceError>
+where
+    S: Stream<Item = Result<ExportRow, RiskServiceError>>,
+{
+    let mut writer: Box<dyn ExportWriter> = match format {
+        ExportFormat::Csv => Box::new(CsvExportWriter::create(path, correlation_dimension)?),
+        ExportFormat::Parquet => Box::new(ParquetExportWriter::create(path, correlation_dimension)?),
+    };
+
+    let mut rows = std::pin::pin!(rows);
+    let mut batch = Vec::with_capacity(EXPORT_BATCH_SIZE);
+    let mut summary = ExportSummary { rows_written: 0, truncated: false };
+
+    while let Some(row) = rows.next().await {
+        if summary.rows_written + batch.len() as u64 >= max_rows {
+            summary.truncated = true;
+            break;
+        }
+
+        batch.push(row?);
+        if batch.len() == EXPORT_BATCH_SIZE {
+            writer.write_batch(&batch)?;
+            summary.rows_written += batch.len() as u64;
+            batch.clear();
+        }
+    }
+
+    if !batch.is_empty() {
+        writer.write_batch(&batch)?;
+        summary.rows_written += batch.len() as u64;
+    }
+
+    writer.finish()?;
+    Ok(summary)
+}
+
+// ============ Storage ============
+
+/// Local directory holding finished exports, with HMAC-signed expiring download links
+pub struct ExportStore {
+    root: PathBuf,
+    signing_key: Vec<u8>,
+}
+
+impl ExportStore {
+    pub fn new(root: impl Into<PathBuf>, signing_key: impl Into<Vec<u8>>) -> Result<Self, RiskServiceError> {
+        let root = root.into();
+        std::fs::create_dir_all(&root).map_err(export_error)?;
+        Ok(Self { root, signing_key: signing_key.into() })
+    }
+
+    pub fn path_for(&self, job_id: Uuid, format: ExportFormat) -> PathBuf {
+        self.root.join(format!("{}.{}", job_id, format.extension()))
+    }
+
+    fn signature(&self, job_id: Uuid, expires: i64) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.signing_key)
+            .expect("HMAC accepts keys of any length");
+        mac.update(format!("{}:{}", job_id, expires).as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    /// Download path for the job's file, valid until `expires_at`
+    pub fn signed_url(&self, job_id: Uuid, expires_at: DateTime<Utc>) -> String {
+        let expires = expires_at.timestamp();
+        format!(
+            "/api/v2/risk/export/{}/download?expires={}&signature={}",
+            job_id, expires, self.signature(job_id, expires)
+        )
+    }
+
+    /// Whether a download link is authentic and not yet expired
+    pub fn verify(&self, job_id: Uuid, expires: i64, signature: &str, now: DateTime<Utc>) -> bool {
+        if now.timestamp() > expires {
+            return false;
+        }
+
+        let Ok(signature) = hex::decode(signature) else {
+            return false;
+        };
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.signing_key)
+            .expect("HMAC accepts keys of any length");
+        mac.update(format!("{}:{}", job_id, expires).as_bytes());
+        mac.verify_slice(&signature).is_ok()
+    }
+}
+
+// ============ Job Runner ============
+
+/// Runs export jobs in the background and tracks their status
+pub struct ExportManager {
+    db: Arc<PgPool>,
+    store: Arc<ExportStore>,
+    jobs: Arc<RwLock<HashMap<Uuid, ExportJob>>>,
+    max_rows: u64,
+    url_ttl: Duration,
+}
+
+impl ExportManager {
+    pub fn new(db: Arc<PgPool>, store: ExportStore, max_rows: u64, url_ttl: Duration) -> Self {
+        Self {
+            db,
+            store: Arc::new(store),
+            jobs: Arc::new(RwLock::new(HashMap::new())),
+            max_rows,
+            url_ttl,
+        }
+    }
+
+    /// Queue an export; poll `job` for its status
+    pub async fn submit(&self, request: ExportRequest) -> Result<ExportJob, RiskServiceError> {
+        if request.portfolios.is_empty() {
+            return Err(RiskServiceError::ExportError("At least one portfolio is required".to_string()));
+        }
+        if request.from >= request.to {
+            return Err(RiskServiceError::ExportError("from must be before to".to_string()));
+        }
+
+        let job = ExportJob {
+            job_id: Uuid::new_v4(),
+            status: ExportJobStatus::Pending,
+            format: request.format,
+            rows_written: 0,
+            truncated: false,
+            error: None,
+            created_at: Utc::now(),
+            completed_at: None,
+            download_url: None,
+            download_expires_at: None,
+        };
+        self.jobs.write().await.insert(job.job_id, job.clone());
+
+        let db = self.db.clone();
+        let store = self.store.clone();
+        let jobs = self.jobs.clone();
+        let (job_id, max_rows, url_ttl) = (job.job_id, self.max_rows, self.url_ttl);
+
+        tokio::spawn(async move {
+            if let Some(job) = jobs.write().await.get_mut(&job_id) {
+                job.status = ExportJobStatus::Running;
+            }
+
+            let path = store.path_for(job_id, request.format);
+            let result = run_export(&db, &request, &path, max_rows).await;
+
+            let mut jobs = jobs.write().await;
+            let Some(job) = jobs.get_mut(&job_id) else { return };
+            job.completed_at = Some(Utc::now());
+
+            match result {
+                Ok(summary) => {
+                    let expires_at = Utc::now() + url_ttl;
+                    job.status = ExportJobStatus::Completed;
+                    job.rows_written = summary.rows_written;
+                    job.truncated = summary.truncated;
+                    job.download_url = Some(store.signed_url(job_id, expires_at));
+                    job.download_expires_at = Some(expires_at);
+                    info!("Risk export {} completed with {} rows", job_id, summary.rows_written);
+                }
+                Err(e) => {
+                    error!("Risk export {} failed: {}", job_id, e);
+                    job.status = ExportJobStatus::Failed;
+                    job.error = Some(e.to_string());
+                    let _ = std::fs::remove_file(&path);
+                }
+            }
+        });
+
+        Ok(job)
+    }
+
+    pub async fn job(&self, job_id: Uuid) -> Option<ExportJob> {
+        self.jobs.read().await.get(&job_id).cloned()
+    }
+
+    /// File and format of a completed export if the download link is valid
+    pub async fn download(&self, job_id: Uuid, expires: i64, signature: &str) -> Option<(PathBuf, ExportFormat)> {
+        if !self.store.verify(job_id, expires, signature, Utc::now()) {
+            return None;
+        }
+
+        let job = self.job(job_id).await?;
+        (job.status == ExportJobStatus::Completed).then(|| (self.store.path_for(job_id, job.format), job.format))
+    }
+}
+
+async fn run_export(
+    db: &PgPool,
+    request: &ExportRequest,
+    path: &Path,
+    max_rows: u64,
+) -> Result<ExportSummary, RiskServiceError> {
+    // Addresses are stored in their Debug form by store_risk_metrics
+    let portfolios: Vec<String> = request.portfolios.iter().map(|p| format!("{:?}", p)).collect();
+
+    let dimension: Option<i32> = sqlx::query_scalar(
+        r#"
+        SELECT MAX(jsonb_array_length(correlation_matrix))
+        FROM risk_metrics
+        WHERE portfolio_address = ANY($1) AND timestamp >= $2 AND timestamp < $3
+        "#
+    )
+    .bind(&portfolios)
+    .bind(request.from)
+    .bind(request.to)
+    .fetch_one(db)
+    .await?;
+
+    let rows = sqlx::query_as::<_, MetricsRow>(
+        r#"
+        SELECT portfolio_address, timestamp,
+               var_95::float8 AS var_95, var_99::float8 AS var_99,
+               expected_shortfall::float8 AS expected_shortfall,
+               sharpe_ratio::float8 AS sharpe_ratio, sortino_ratio::float8 AS sortino_ratio,
+               max_drawdown::float8 AS max_drawdown, beta::float8 AS beta, alpha::float8 AS alpha,
+               volatility::float8 AS volatility, liquidity_score,
+               concentration_risk::float8 AS concentration_risk, leverage_ratio::float8 AS leverage_ratio,
+               risk_grade, correlation_matrix::text AS correlation_matrix
+        FROM risk_metrics
+        WHERE portfolio_address = ANY($1) AND timestamp >= $2 AND timestamp < $3
+        ORDER BY portfolio_address, timestamp
+        LIMIT $4
+        "#
+    )
+    .bind(&portfolios)
+    .bind(request.from)
+    .bind(request.to)
+    // One extra row tells the writer the cap was hit
+    .bind(max_rows as i64 + 1)
+    .fetch(db)
+    .map(|row| row.map(ExportRow::from).map_err(RiskServiceError::from));
+
+    write_export(rows, request.format, path, dimension.unwrap_or(0).max(0) as usize, max_rows).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Array;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    fn rows(count: usize) -> Vec<Result<ExportRow, RiskServiceError>> {
+        (0..count).map(|i| Ok(ExportRow {
+            portfolio_address: "0x00000000000000000000000000000000000000aa".to_string(),
+            timestamp: Utc::now() - Duration::hours(count as i64 - i as i64),
+            var_95: Some(1_000.0 + i as f64),
+            var_99: Some(1_500.0),
+            sharpe_ratio: Some(1.2),
+            liquidity_score: Some(80),
+            risk_grade: Some("B".to_string()),
+            // Every other calculation covered a third position
+            correlation_matrix: if i % 2 == 0 {
+                vec![vec![1.0, 0.3, 0.1], vec![0.3, 1.0, -0.2], vec![0.1, -0.2, 1.0]]
+            } else {
+                vec![vec![1.0, 0.4], vec![0.4, 1.0]]
+            },
+            ..Default::default()
+        })).collect()
+    }
+
+    fn temp_path(format: ExportFormat) -> PathBuf {
+        std::env::temp_dir().join(format!("risk-export-test-{}.{}", Uuid::new_v4(), format.extension()))
+    }
+
+    #[tokio::test]
+    async fn test_csv_export_round_trip() {
+        let path = temp_path(ExportFormat::Csv);
+        let summary = write_export(futures::stream::iter(rows(7)), ExportFormat::Csv, &path, 3, 100).await.unwrap();
+        assert_eq!(summary, ExportSummary { rows_written: 7, truncated: false });
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.lines().take(COLUMN_NOTES.len()).all(|line| line.starts_with("# ")));
+
+        let mut reader = csv::ReaderBuilder::new().comment(Some(b'#')).from_path(&path).unwrap();
+        let headers = reader.headers().unwrap().clone();
+        assert_eq!(headers.iter().collect::<Vec<_>>(), export_columns(3));
+
+        let records: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+        assert_eq!(records.len(), 7);
+        let column = |name: &str| headers.iter().position(|h| h == name).unwrap();
+        assert!(records[0][column("var_95")].parse::<f64>().is_ok());
+        assert_eq!(records[0][column("corr_1_2")].parse::<f64>().unwrap(), -0.2);
+        assert_eq!(&records[1][column("corr_1_2")], "");
+        assert_eq!(records[1][column("liquidity_score")].parse::<i32>().unwrap(), 80);
+        assert!(DateTime::parse_from_rfc3339(&records[0][column("timestamp")]).is_ok());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_parquet_export_round_trip_with_row_cap() {
+        let path = temp_path(ExportFormat::Parquet);
+        let summary = write_export(futures::stream::iter(rows(12)), ExportFormat::Parquet, &path, 3, 10).await.unwrap();
+        assert_eq!(summary, ExportSummary { rows_written: 10, truncated: true });
+
+        let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap()).unwrap();
+        let notes = builder.metadata().file_metadata().key_value_metadata().unwrap()
+            .iter()
+            .find(|kv| kv.key == "quantera.column_notes")
+            .and_then(|kv| kv.value.clone())
+            .unwrap();
+        assert!(notes.contains("corr_<i>_<j>"));
+
+        let schema = builder.schema().clone();
+        assert_eq!(schema.field_with_name("portfolio_address").unwrap().data_type(), &DataType::Utf8);
+        assert_eq!(schema.field_with_name("timestamp").unwrap().data_type(), &DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())));
+        assert_eq!(schema.field_with_name("var_95").unwrap().data_type(), &DataType::Float64);
+        assert_eq!(schema.field_with_name("liquidity_score").unwrap().data_type(), &DataType::Int32);
+        assert_eq!(schema.field_with_name("corr_0_1").unwrap().data_type(), &DataType::Float64);
+        assert!(schema.field_with_name("corr_0_0").is_err());
+
+        let batches: Vec<RecordBatch> = builder.build().unwrap().map(Result::unwrap).collect();
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 10);
+
+        let corr = batches[0].column_by_name("corr_1_2").unwrap();
+        assert!(corr.is_valid(0));
+        assert!(corr.is_null(1));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_signed_download_url_expires() {
+        let store = ExportStore::new(std::env::temp_dir().join("risk-export-test-store"), b"secret".to_vec()).unwrap();
+        let job_id = Uuid::new_v4();
+        let now = Utc::now();
+        let expires = (now + Duration::minutes(5)).timestamp();
+        let signature = store.signature(job_id, expires);
+
+        assert!(store.verify(job_id, expires, &signature, now));
+        assert!(!store.verify(job_id, expires, &signature, now + Duration::minutes(6)));
+        assert!(!store.verify(job_id, expires + 60, &signature, now));
+        assert!(!store.verify(Uuid::new_v4(), expires, &signature, now));
+    }
+}
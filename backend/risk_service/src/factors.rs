@@ -0,0 +1,433 @@
+// Factor model and factor-based decomposition of portfolio variance
+use std::collections::{HashMap, HashSet};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tracing::debug;
+use crate::ethereum_client::Address;
+use crate::acquisitions::parse_decimal;
+use crate::anomaly;
+use crate::RiskServiceError;
+
+/// Bucket for asset-specific risk, including every asset without factor loadings
+pub const IDIOSYNCRATIC_BUCKET: &str = "idiosyncratic";
+
+/// Daily return variance assumed for an asset with too little price history (2% daily volatility)
+pub const DEFAULT_ASSET_VARIANCE: Decimal = dec!(0.0004);
+
+/// Daily price history used to estimate an asset's return variance
+pub const VARIANCE_LOOKBACK_DAYS: i32 = 90;
+
+/// Fewest daily returns needed before the estimated variance is used
+const MIN_RETURN_OBSERVATIONS: usize = 20;
+
+/// Named factors and the covariance of their daily returns
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactorModel {
+    pub factors: Vec<String>,
+    pub covariance: Vec<Vec<Decimal>>,
+}
+
+impl Default for FactorModel {
+    /// Rates, credit, crypto beta and FX with indicative daily covariances
+    fn default() -> Self {
+        Self {
+            factors: vec!["rates".into(), "credit".into(), "crypto_beta".into(), "fx".into()],
+            covariance: vec![
+                vec![dec!(0.00001), dec!(-0.000006), dec!(0), dec!(0.000002)],
+                vec![dec!(-0.000006), dec!(0.00004), dec!(0.00005), dec!(0)],
+                vec![dec!(0), dec!(0.00005), dec!(0.0016), dec!(0.00001)],
+                vec![dec!(0.000002), dec!(0), dec!(0.00001), dec!(0.000025)],
+            ],
+        }
+    }
+}
+
+impl FactorModel {
+    pub fn new(factors: Vec<String>, covariance: Vec<Vec<Decimal>>) -> Result<Self, RiskServiceError> {
+        let model = Self { factors, covariance };
+        model.validate()?;
+        Ok(model)
+    }
+
+    /// Load a model seed from a JSON file
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read factor model {}: {}", path, e))?;
+        let model: Self = serde_json::from_str(&json)
+            .map_err(|e| format!("Invalid factor model {}: {}", path, e))?;
+        model.validate().map_err(|e| e.to_string())?;
+        Ok(model)
+    }
+
+    /// Factor names must be unique and the covariance square, symmetric and non-negative on the diagonal
+    pub fn validate(&self) -> Result<(), RiskServiceError> {
+        let invalid = |msg: String| Err(RiskServiceError::InvalidInput(msg));
+
+        if self.factors.is_empty() {
+            return invalid("Factor model needs at least one factor".into());
+        }
+        let mut seen = HashSet::new();
+        for factor in &self.factors {
+            if factor.is_empty() || factor == IDIOSYNCRATIC_BUCKET {
+                return invalid(format!("Invalid factor name {:?}", factor));
+            }
+            if !seen.insert(factor) {
+                return invalid(format!("Duplicate factor {}", factor));
+            }
+        }
+
+        let n = self.factors.len();
+        if self.covariance.len() != n || self.covariance.iter().any(|row| row.len() != n) {
+            return invalid(format!("Covariance must be {}x{} to match the factors", n, n));
+        }
+        for i in 0..n {
+            if self.covariance[i][i] < Decimal::ZERO {
+                return invalid(format!("Negative variance for factor {}", self.factors[i]));
+            }
+            for j in (i + 1)..n {
+                if self.covariance[i][j] != self.covariance[j][i] {
+                    return invalid(format!("Covariance is not symmetric at {}/{}", self.factors[i], self.factors[j]));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn index(&self, factor: &str) -> Option<usize> {
+        self.factors.iter().position(|f| f == factor)
+    }
+
+    /// Loadings as a vector in factor order; loadings on factors not in the model are ignored
+    fn loading_vector(&self, loadings: &HashMap<String, Decimal>) -> Vec<Decimal> {
+        let mut vector = vec![Decimal::ZERO; self.factors.len()];
+        for (factor, loading) in loadings {
+            if let Some(k) = self.index(factor) {
+                vector[k] = *loading;
+            }
+        }
+        vector
+    }
+
+    fn has_loadings(&self, loadings: &HashMap<String, Decimal>) -> bool {
+        loadings.iter().any(|(factor, loading)| !loading.is_zero() && self.index(factor).is_some())
+    }
+
+    /// b'Fb for a loading vector
+    fn quadratic_form(&self, b: &[Decimal]) -> Decimal {
+        let fb = self.covariance_times(b);
+        b.iter().zip(&fb).map(|(x, y)| x * y).sum()
+    }
+
+    fn covariance_times(&self, b: &[Decimal]) -> Vec<Decimal> {
+        self.covariance.iter()
+            .map(|row| row.iter().zip(b).map(|(c, x)| c * x).sum())
+            .collect()
+    }
+}
+
+/// Loading of an asset on one factor, as stored in factor_exposures
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FactorExposure {
+    pub asset: Address,
+    pub factor: String,
+    pub loading: Decimal,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Share of portfolio variance attributed to a factor or the idiosyncratic bucket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactorRiskContribution {
+    pub factor: String,
+    /// Portfolio loading on the factor; zero for the idiosyncratic bucket
+    pub exposure: Decimal,
+    pub variance: Decimal,
+    /// Fraction of total variance; negative for hedging factors
+    pub share: Decimal,
+}
+
+/// One holding as input to the decomposition
+#[derive(Debug, Clone)]
+pub struct AssetRisk {
+    pub weight: Decimal,
+    /// Total daily return variance of the asset
+    pub variance: Decimal,
+    /// Empty for unmapped assets
+    pub loadings: HashMap<String, Decimal>,
+}
+
+/// Attribute portfolio variance to factors, with the remainder in the idiosyncratic bucket.
+///
+/// Factor contributions are the Euler allocation b_k (Fb)_k of the portfolio loading b, so
+/// they sum to b'Fb. Each mapped asset adds its variance not explained by its loadings to the
+/// idiosyncratic bucket; unmapped assets add all of theirs. Specific returns are assumed
+/// uncorrelated, so the contributions sum to the model's total portfolio variance.
+pub fn decompose(model: &FactorModel, assets: &[AssetRisk]) -> Vec<FactorRiskContribution> {
+    let mut exposure = vec![Decimal::ZERO; model.factors.len()];
+    let mut idiosyncratic = Decimal::ZERO;
+
+    for asset in assets {
+        let weight_sq = asset.weight * asset.weight;
+        if model.has_loadings(&asset.loadings) {
+            let b = model.loading_vector(&asset.loadings);
+            for (total, loading) in exposure.iter_mut().zip(&b) {
+                *total += asset.weight * loading;
+            }
+            let specific = (asset.variance - model.quadratic_form(&b)).max(Decimal::ZERO);
+            idiosyncratic += weight_sq * specific;
+        } else {
+            idiosyncratic += weight_sq * asset.variance;
+        }
+    }
+
+    let f_exposure = model.covariance_times(&exposure);
+    let mut contributions: Vec<FactorRiskContribution> = model.factors.iter()
+        .enumerate()
+        .map(|(k, factor)| FactorRiskContribution {
+            factor: factor.clone(),
+            exposure: exposure[k],
+            variance: exposure[k] * f_exposure[k],
+            share: Decimal::ZERO,
+        })
+        .collect();
+    contributions.push(FactorRiskContribution {
+        factor: IDIOSYNCRATIC_BUCKET.to_string(),
+        exposure: Decimal::ZERO,
+        variance: idiosyncratic,
+        share: Decimal::ZERO,
+    });
+
+    let total: Decimal = contributions.iter().map(|c| c.variance).sum();
+    if !total.is_zero() {
+        for contribution in &mut contributions {
+            contribution.share = contribution.variance / total;
+        }
+    }
+
+    contributions
+}
+
+/// Asset covariance matrix BFB' + D implied by the model.
+///
+/// Off-diagonal entries come from shared factor loadings only; the diagonal is each asset's
+/// total variance, or its factor variance if that is larger.
+pub fn asset_covariance(model: &FactorModel, assets: &[AssetRisk]) -> Vec<Vec<Decimal>> {
+    let b: Vec<Vec<Decimal>> = assets.iter().map(|a| model.loading_vector(&a.loadings)).collect();
+    let fb: Vec<Vec<Decimal>> = b.iter().map(|loadings| model.covariance_times(loadings)).collect();
+
+    assets.iter().enumerate()
+        .map(|(i, asset)| {
+            (0..assets.len())
+                .map(|j| {
+                    let cov: Decimal = b[i].iter().zip(&fb[j]).map(|(x, y)| x * y).sum();
+                    if i == j { asset.variance.max(cov) } else { cov }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Factor loadings keyed by asset
+pub async fn load_exposures(db: &PgPool, asset: Option<Address>) -> Result<Vec<FactorExposure>, RiskServiceError> {
+    let rows: Vec<(String, String, String, DateTime<Utc>)> = sqlx::query_as(r#"
+        SELECT asset_address, factor, loading::text, updated_at
+        FROM factor_exposures
+        WHERE $1::text IS NULL OR asset_address = $1
+        ORDER BY asset_address, factor
+    "#)
+        .bind(asset.map(|a| format!("{:?}", a)))
+        .fetch_all(db)
+        .await?;
+
+    rows.into_iter()
+        .map(|(asset, factor, loading, updated_at)| {
+            Ok(FactorExposure {
+                asset: asset.parse::<Address>()
+                    .map_err(|e| RiskServiceError::CalculationError(format!("Invalid asset address {}: {}", asset, e)))?,
+                factor,
+                loading: parse_decimal(&loading)?,
+                updated_at,
+            })
+        })
+        .collect()
+}
+
+/// Replace all loadings of an asset
+pub async fn replace_exposures(
+    db: &PgPool,
+    asset: Address,
+    loadings: &HashMap<String, Decimal>,
+) -> Result<(), RiskServiceError> {
+    let mut tx = db.begin().await?;
+
+    sqlx::query("DELETE FROM factor_exposures WHERE asset_address = $1")
+        .bind(format!("{:?}", asset))
+        .execute(&mut *tx)
+        .await?;
+
+    for (factor, loading) in loadings {
+        sqlx::query(r#"
+            INSERT INTO factor_exposures (asset_address, factor, loading, updated_at)
+            VALUES ($1, $2, $3::numeric, NOW())
+        "#)
+            .bind(format!("{:?}", asset))
+            .bind(factor)
+            .bind(loading.to_string())
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Latest factor model saved through the admin API
+pub async fn load_saved_model(db: &PgPool) -> Result<Option<FactorModel>, RiskServiceError> {
+    let row: Option<(String,)> = sqlx::query_as(
+        "SELECT model::text FROM factor_model_versions ORDER BY id DESC LIMIT 1"
+    )
+        .fetch_optional(db)
+        .await?;
+
+    row.map(|(json,)| {
+        serde_json::from_str(&json)
+            .map_err(|e| RiskServiceError::CalculationError(format!("Invalid saved factor model: {}", e)))
+    }).transpose()
+}
+
+pub async fn save_model(db: &PgPool, model: &FactorModel) -> Result<(), RiskServiceError> {
+    let json = serde_json::to_string(model)
+        .map_err(|e| RiskServiceError::CalculationError(format!("Failed to encode factor model: {}", e)))?;
+
+    sqlx::query("INSERT INTO factor_model_versions (model) VALUES ($1::jsonb)")
+        .bind(json)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// Sample variance of an asset's daily returns, or None with too little history.
+/// Quarantined prices are skipped, so no return is taken through them.
+pub async fn load_return_variance(db: &PgPool, asset: Address) -> Result<Option<Decimal>, RiskServiceError> {
+    let history = anomaly::recent_history(db, &format!("{:?}", asset), Utc::now().date_naive(), i64::from(VARIANCE_LOOKBACK_DAYS)).await?;
+    let (prices, skipped) = anomaly::accepted_prices(&history);
+    if skipped > 0 {
+        debug!("Skipping {} quarantined prices of {:?} in its return variance", skipped, asset);
+    }
+    Ok(return_variance(&prices))
+}
+
+pub(crate) fn return_variance(prices: &[Decimal]) -> Option<Decimal> {
+    let returns: Vec<Decimal> = prices.windows(2)
+        .filter(|pair| pair[0] > Decimal::ZERO)
+        .map(|pair| pair[1] / pair[0] - Decimal::ONE)
+        .collect();
+    if returns.len() < MIN_RETURN_OBSERVATIONS {
+        return None;
+    }
+
+    let n = Decimal::from(returns.len());
+    let mean = returns.iter().sum::<Decimal>() / n;
+    let sum_sq: Decimal = returns.iter().map(|r| (r - mean) * (r - mean)).sum();
+    Some(sum_sq / (n - Decimal::ONE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn toy_model() -> FactorModel {
+        FactorModel::new(
+            vec!["rates".into(), "crypto_beta".into()],
+            vec![
+                vec![dec!(0.04), dec!(0.01)],
+                vec![dec!(0.01), dec!(0.09)],
+            ],
+        ).unwrap()
+    }
+
+    fn asset(weight: Decimal, variance: Decimal, loadings: &[(&str, Decimal)]) -> AssetRisk {
+        AssetRisk {
+            weight,
+            variance,
+            loadings: loadings.iter().map(|(f, l)| (f.to_string(), *l)).collect(),
+        }
+    }
+
+    /// w'Σw with Σ = BFB' + D built out in full
+    fn full_covariance_variance(model: &FactorModel, assets: &[AssetRisk]) -> Decimal {
+        let b: Vec<Vec<Decimal>> = assets.iter().map(|a| model.loading_vector(&a.loadings)).collect();
+        let mut total = Decimal::ZERO;
+        for (i, ai) in assets.iter().enumerate() {
+            for (j, aj) in assets.iter().enumerate() {
+                let mut cov = model.quadratic_form_between(&b[i], &b[j]);
+                if i == j {
+                    cov = ai.variance.max(cov);
+                }
+                total += ai.weight * aj.weight * cov;
+            }
+        }
+        total
+    }
+
+    impl FactorModel {
+        fn quadratic_form_between(&self, x: &[Decimal], y: &[Decimal]) -> Decimal {
+            let fy = self.covariance_times(y);
+            x.iter().zip(&fy).map(|(a, b)| a * b).sum()
+        }
+    }
+
+    #[test]
+    fn test_contributions_sum_to_total_variance() {
+        let model = toy_model();
+        let assets = vec![
+            asset(dec!(0.5), dec!(0.1), &[("rates", dec!(1.0)), ("crypto_beta", dec!(0.5))]),
+            asset(dec!(0.3), dec!(0.2), &[("crypto_beta", dec!(1.2))]),
+            asset(dec!(0.2), dec!(0.05), &[]),
+        ];
+
+        let contributions = decompose(&model, &assets);
+        let names: Vec<&str> = contributions.iter().map(|c| c.factor.as_str()).collect();
+        assert_eq!(names, vec!["rates", "crypto_beta", IDIOSYNCRATIC_BUCKET]);
+
+        let sum: Decimal = contributions.iter().map(|c| c.variance).sum();
+        let expected = full_covariance_variance(&model, &assets);
+        assert!((sum - expected).abs() < dec!(0.000000001), "{} != {}", sum, expected);
+
+        let shares: Decimal = contributions.iter().map(|c| c.share).sum();
+        assert!((shares - Decimal::ONE).abs() < dec!(0.000000001));
+
+        // Portfolio loadings are weight-averaged
+        assert_eq!(contributions[0].exposure, dec!(0.5));
+        assert_eq!(contributions[1].exposure, dec!(0.61));
+    }
+
+    #[test]
+    fn test_unmapped_assets_fall_into_idiosyncratic_bucket() {
+        let model = toy_model();
+        // Loadings on factors outside the model count as unmapped
+        let assets = vec![
+            asset(dec!(0.6), dec!(0.04), &[]),
+            asset(dec!(0.4), dec!(0.09), &[("fx", dec!(1))]),
+        ];
+
+        let contributions = decompose(&model, &assets);
+        let idiosyncratic = contributions.iter().find(|c| c.factor == IDIOSYNCRATIC_BUCKET).unwrap();
+        assert_eq!(idiosyncratic.variance, dec!(0.36) * dec!(0.04) + dec!(0.16) * dec!(0.09));
+        assert_eq!(idiosyncratic.share, Decimal::ONE);
+        assert!(contributions.iter().filter(|c| c.factor != IDIOSYNCRATIC_BUCKET).all(|c| c.variance.is_zero()));
+    }
+
+    #[test]
+    fn test_model_validation() {
+        assert!(FactorModel::default().validate().is_ok());
+        assert!(FactorModel::new(vec!["a".into(), "b".into()], vec![vec![dec!(1), dec!(0)]]).is_err());
+        assert!(FactorModel::new(vec!["a".into(), "b".into()], vec![vec![dec!(1), dec!(0.1)], vec![dec!(0.2), dec!(1)]]).is_err());
+        assert!(FactorModel::new(vec!["a".into(), "a".into()], vec![vec![dec!(1), dec!(0)], vec![dec!(0), dec!(1)]]).is_err());
+        assert!(FactorModel::new(vec![IDIOSYNCRATIC_BUCKET.into()], vec![vec![dec!(1)]]).is_err());
+        assert!(FactorModel::new(vec!["a".into()], vec![vec![dec!(-1)]]).is_err());
+    }
+}
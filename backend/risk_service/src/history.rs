@@ -0,0 +1,223 @@
+// Daily price history behind return, VaR, drawdown and beta calculations
+use crate::acquisitions::parse_decimal;
+use crate::backfill::PriceSeries;
+use crate::ethereum_client::Address;
+use crate::RiskServiceError;
+use chrono::{DateTime, NaiveDate, Utc};
+use redis::aio::ConnectionManager;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use std::collections::{BTreeSet, HashMap};
+use tokio::sync::RwLock;
+
+/// Daily prices loaded per asset unless configured otherwise
+pub const DEFAULT_HISTORY_DAYS: usize = 100;
+
+/// Prices an asset needs before its returns are trusted
+pub const MIN_OBSERVATIONS: usize = 30;
+
+/// Seconds a loaded history stays cached; new prices are picked up after at most this long
+pub const CACHE_TTL_SECS: u64 = 60;
+
+/// Source of prices written through `record`
+pub const RECORDED_SOURCE: &str = "recorded";
+
+/// Source of prices bulk-loaded through `import`
+pub const IMPORT_SOURCE: &str = "import";
+
+/// Accepted daily prices of one asset or benchmark, oldest first
+pub type DailyHistory = Vec<(NaiveDate, Decimal)>;
+
+fn cache_key(series: &PriceSeries, days: usize) -> String {
+    match series {
+        PriceSeries::Asset(asset) => format!("risk:prices:{}:{}", asset, days),
+        PriceSeries::Benchmark(name) => format!("risk:benchmark_prices:{}:{}", name, days),
+    }
+}
+
+fn validate_price(asset: Address, price: Decimal) -> Result<(), RiskServiceError> {
+    if price <= Decimal::ZERO {
+        return Err(RiskServiceError::InvalidInput(format!("Price of {:?} must be positive", asset)));
+    }
+    Ok(())
+}
+
+/// Record the price of `asset` for the day of `at`; a later price for the same day replaces it
+pub async fn record(db: &PgPool, asset: Address, price: Decimal, at: DateTime<Utc>) -> Result<(), RiskServiceError> {
+    validate_price(asset, price)?;
+    sqlx::query(r#"
+        INSERT INTO asset_price_history (asset_address, price_date, price, source, recorded_at)
+        VALUES ($1, $2, $3::numeric, $4, NOW())
+        ON CONFLICT (asset_address, price_date) DO UPDATE SET
+            price = EXCLUDED.price,
+            source = EXCLUDED.source,
+            quarantined = FALSE,
+            recorded_at = NOW()
+    "#)
+        .bind(format!("{:?}", asset))
+        .bind(at.date_naive())
+        .bind(price.to_string())
+        .bind(RECORDED_SOURCE)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// Bulk-load price series in one transaction, returning the number of prices written.
+///
+/// Oracle and recorded prices are better than an imported one for the same day, so only
+/// earlier imports are overwritten; of several imported prices for one day the last wins.
+pub async fn import(db: &PgPool, series: &[(Address, Vec<(DateTime<Utc>, Decimal)>)]) -> Result<u64, RiskServiceError> {
+    let mut tx = db.begin().await?;
+    let mut written = 0;
+    for (asset, prices) in series {
+        let mut daily: HashMap<NaiveDate, Decimal> = HashMap::new();
+        for (at, price) in prices {
+            validate_price(*asset, *price)?;
+            daily.insert(at.date_naive(), *price);
+        }
+        if daily.is_empty() {
+            continue;
+        }
+
+        let (dates, prices): (Vec<NaiveDate>, Vec<String>) = daily.into_iter().map(|(date, price)| (date, price.to_string())).unzip();
+        written += sqlx::query(r#"
+            INSERT INTO asset_price_history (asset_address, price_date, price, source, recorded_at)
+            SELECT $1, d, p::numeric, $4, NOW() FROM UNNEST($2::date[], $3::text[]) AS t(d, p)
+            ON CONFLICT (asset_address, price_date) DO UPDATE SET
+                price = EXCLUDED.price,
+                recorded_at = NOW()
+            WHERE asset_price_history.source = $4
+        "#)
+            .bind(format!("{:?}", asset))
+            .bind(dates)
+            .bind(prices)
+            .bind(IMPORT_SOURCE)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+    }
+    tx.commit().await?;
+    Ok(written)
+}
+
+/// The last `days` accepted daily prices of a series; quarantined asset prices are skipped
+pub async fn load(db: &PgPool, series: &PriceSeries, days: usize) -> Result<DailyHistory, RiskServiceError> {
+    let query = match series {
+        PriceSeries::Asset(_) => r#"
+            SELECT price_date, price::text FROM asset_price_history
+            WHERE asset_address = $1 AND NOT quarantined
+            ORDER BY price_date DESC
+            LIMIT $2
+        "#,
+        PriceSeries::Benchmark(_) => r#"
+            SELECT price_date, price::text FROM benchmark_price_history
+            WHERE benchmark = $1
+            ORDER BY price_date DESC
+            LIMIT $2
+        "#,
+    };
+    let rows: Vec<(NaiveDate, String)> = sqlx::query_as(query)
+        .bind(series.key())
+        .bind(days as i64)
+        .fetch_all(db)
+        .await?;
+
+    rows.into_iter().rev()
+        .map(|(date, price)| Ok((date, parse_decimal(&price)?)))
+        .collect()
+}
+
+/// `load`, served from Redis while a recent load is cached
+pub async fn load_cached(
+    db: &PgPool,
+    cache: &RwLock<ConnectionManager>,
+    series: &PriceSeries,
+    days: usize,
+) -> Result<DailyHistory, RiskServiceError> {
+    let key = cache_key(series, days);
+    let cached: Option<String> = redis::cmd("GET").arg(&key).query_async(&mut *cache.write().await).await?;
+    if let Some(history) = cached.and_then(|value| decode(&value)) {
+        return Ok(history);
+    }
+
+    let history = load(db, series, days).await?;
+    redis::cmd("SET")
+        .arg(&key)
+        .arg(encode(&history))
+        .arg("EX")
+        .arg(CACHE_TTL_SECS)
+        .query_async::<_, ()>(&mut *cache.write().await)
+        .await?;
+    Ok(history)
+}
+
+/// Drop the cached history of a series so its next load sees newly written prices
+pub async fn invalidate(cache: &RwLock<ConnectionManager>, series: &PriceSeries, days: usize) -> Result<(), RiskServiceError> {
+    redis::cmd("DEL").arg(cache_key(series, days)).query_async::<_, ()>(&mut *cache.write().await).await?;
+    Ok(())
+}
+
+fn encode(history: &DailyHistory) -> String {
+    let entries: Vec<(NaiveDate, String)> = history.iter().map(|(date, price)| (*date, price.to_string())).collect();
+    serde_json::to_string(&entries).unwrap_or_default()
+}
+
+fn decode(value: &str) -> Option<DailyHistory> {
+    let entries: Vec<(NaiveDate, String)> = serde_json::from_str(value).ok()?;
+    entries.into_iter().map(|(date, price)| Some((date, price.parse().ok()?))).collect()
+}
+
+/// Days every asset was priced, oldest first, with one price per asset.
+///
+/// Any asset with fewer than `MIN_OBSERVATIONS` prices makes the whole history insufficient,
+/// rather than letting the other assets' days stand in for it.
+pub fn align(histories: &[DailyHistory]) -> Result<Vec<(NaiveDate, Vec<Decimal>)>, RiskServiceError> {
+    if histories.is_empty() || histories.iter().any(|history| history.len() < MIN_OBSERVATIONS) {
+        return Err(RiskServiceError::InsufficientData);
+    }
+
+    let mut shared: BTreeSet<NaiveDate> = histories[0].iter().map(|(date, _)| *date).collect();
+    for history in &histories[1..] {
+        let dates: BTreeSet<NaiveDate> = history.iter().map(|(date, _)| *date).collect();
+        shared.retain(|date| dates.contains(date));
+    }
+
+    let by_date: Vec<HashMap<NaiveDate, Decimal>> = histories.iter()
+        .map(|history| history.iter().copied().collect())
+        .collect();
+    Ok(shared.into_iter()
+        .map(|date| (date, by_date.iter().map(|prices| prices[&date]).collect()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use rust_decimal_macros::dec;
+
+    fn history(first: NaiveDate, days: i64, skip_every: Option<i64>) -> DailyHistory {
+        (0..days)
+            .filter(|day| skip_every.map_or(true, |every| day % every != 0))
+            .map(|day| (first + Duration::days(day), dec!(100) + Decimal::from(day)))
+            .collect()
+    }
+
+    #[test]
+    fn test_align_keeps_days_every_asset_was_priced() {
+        let first = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let daily = history(first, 60, None);
+        let gappy = history(first, 60, Some(5));
+
+        let rows = align(&[daily, gappy.clone()]).unwrap();
+        assert_eq!(rows.len(), gappy.len());
+        assert_eq!(rows[0], (first + Duration::days(1), vec![dec!(101), dec!(101)]));
+
+        // One asset short of history makes the portfolio's history insufficient
+        let short = history(first, 10, None);
+        assert!(matches!(align(&[gappy.clone(), short]), Err(RiskServiceError::InsufficientData)));
+
+        assert_eq!(decode(&encode(&gappy)), Some(gappy));
+    }
+}
@@ -26,21 +26,56 @@ impl DecimalExt for Decimal {
         Some(Decimal::try_from(f.sqrt()).unwrap_or(Decimal::ZERO))
     }
 }
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use uuid::Uuid;
 use anyhow::Result;
 use thiserror::Error;
 use tracing::{info, warn, error};
+use quantera_types::Pii;
 use ndarray::{Array1, Array2};
 use rand::prelude::*;
-use statrs::distribution::{Normal, ContinuousCDF};
+use statrs::distribution::ContinuousCDF;
 use statrs::statistics::Statistics;
 use redis::aio::ConnectionManager;
 use sqlx::{PgPool, postgres::PgPoolOptions};
 pub mod ethereum_client;
 pub mod websocket;
 pub mod config;
+pub mod alerts;
+pub mod export;
+pub mod acquisitions;
+pub mod factors;
+pub mod prices;
+pub mod broadcast;
+pub mod rebalance;
+pub mod what_if;
+pub mod metrics;
+pub mod backfill;
+pub mod publication;
+pub mod pre_trade;
+pub mod depeg;
+pub mod anomaly;
+pub mod history;
+pub mod benchmark;
+pub mod liquidity;
+pub mod limits;
+pub mod webhooks;
+pub mod scenarios;
 use ethereum_client::{EthereumClient, Address};
+use alerts::{AlertTracker, AlertPolicy, TrackedAlert};
+use acquisitions::{AcquisitionTracker, PriceProvenance};
+use broadcast::{BroadcastStats, RiskBroadcaster, RiskSubscription};
+use rebalance::{RebalanceHolding, RebalancePlan, RebalanceTarget, RiskBaseline};
+use what_if::{HypotheticalTrade, WhatIfHolding, WhatIfReport};
+use pre_trade::{PreTradeEvaluation, DEFAULT_PRE_TRADE_BUDGET};
+use publication::{PublicationPolicy, PublishOutcome, PublishedAttestation, OnChainAttestation, RiskAttestation, RiskPublisher};
+use backfill::PriceSeries;
+use factors::{AssetRisk, FactorExposure, FactorModel, FactorRiskContribution, DEFAULT_ASSET_VARIANCE};
+use depeg::{DepegAlerter, DepegMonitor, DepegTransition, PegStatus};
+use liquidity::{DbMarketDepthSource, LiquidityAssessment, LiquidityConfig, LiquidityModel};
+use limits::RiskLimit;
+use webhooks::{DeadLetter, WebhookConfig, WebhookDispatcher, WebhookSubscription};
+use metrics::{CovarianceStress, TailRisk};
 use futures::stream::StreamExt;
 
 #[derive(Error, Debug)]
@@ -62,6 +97,48 @@ pub enum RiskServiceError {
     
     #[error("Ethereum client error: {0}")]
     EthereumError(String),
+    
+    #[error("Export error: {0}")]
+    ExportError(String),
+    
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+    
+    #[error("Price oracle error: {0}")]
+    PriceOracle(#[from] price_oracle::OracleError),
+    
+    #[error("Price feed error: {0}")]
+    PriceFeedError(String),
+    
+    #[error("Webhook error: {0}")]
+    WebhookError(String),
+}
+
+/// How VaR and Expected Shortfall are calculated
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VaRMethod {
+    /// Correlated normal daily returns simulated at the history's means and covariance,
+    /// with depegged stablecoins at a stressed volatility
+    #[default]
+    MonteCarlo,
+    /// Quantiles of the portfolio's own value-weighted return history
+    Historical,
+    /// Normal quantiles at the mean and standard deviation of that history
+    Parametric,
+}
+
+impl std::str::FromStr for VaRMethod {
+    type Err = RiskServiceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "monte_carlo" => Ok(VaRMethod::MonteCarlo),
+            "historical" => Ok(VaRMethod::Historical),
+            "parametric" => Ok(VaRMethod::Parametric),
+            other => Err(RiskServiceError::InvalidInput(format!("Unknown VaR method {}", other))),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,22 +146,36 @@ pub struct RiskMetrics {
     pub portfolio_address: Address,
     pub var_95: Decimal,          // 95% Value at Risk
     pub var_99: Decimal,          // 99% Value at Risk
+    #[serde(default)]
+    pub var_method: VaRMethod,
+    /// Mean loss beyond `var_95`, from the same distribution
     pub expected_shortfall: Decimal,
+    /// Mean loss beyond `var_99`
+    #[serde(default)]
+    pub es_99: Decimal,
     pub sharpe_ratio: Decimal,
     pub sortino_ratio: Decimal,
     pub max_drawdown: Decimal,
-    pub beta: Decimal,
-    pub alpha: Decimal,
+    /// Against the portfolio's benchmark; `None` when no benchmark is configured
+    pub beta: Option<Decimal>,
+    /// Mean daily return beyond what beta explains
+    pub alpha: Option<Decimal>,
     pub volatility: Decimal,
     pub correlation_matrix: Vec<Vec<Decimal>>,
     pub liquidity_scores: HashMap<Address, u8>,
+    /// Volume, depth and days to liquidate behind each liquidity score
+    #[serde(default)]
+    pub liquidity_details: HashMap<Address, LiquidityAssessment>,
     pub concentration_risk: Decimal,
     pub leverage_ratio: Decimal,
     pub risk_grade: RiskGrade,
+    /// Portfolio variance attributed to each factor and the idiosyncratic bucket
+    #[serde(default)]
+    pub factor_risk_contributions: Vec<FactorRiskContribution>,
     pub timestamp: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum RiskGrade {
     A,  // Low risk
     B,  // Medium-low risk
@@ -93,6 +184,57 @@ pub enum RiskGrade {
     F,  // High risk
 }
 
+impl RiskGrade {
+    /// Grade from VaR, Sharpe ratio and drawdown, each scored 0-100 and averaged
+    pub fn from_metrics(var: Decimal, sharpe: Decimal, drawdown: Decimal) -> Self {
+        let var_score = if var < dec!(0.02) {
+            100
+        } else if var < dec!(0.05) {
+            75
+        } else if var < dec!(0.10) {
+            50
+        } else if var < dec!(0.15) {
+            25
+        } else {
+            0
+        };
+    
+        let sharpe_score = if sharpe > Decimal::from(2) {
+            100
+        } else if sharpe > Decimal::ONE {
+            75
+        } else if sharpe > dec!(0.5) {
+            50
+        } else if sharpe > Decimal::ZERO {
+            25
+        } else {
+            0
+        };
+    
+        let dd_score = if drawdown < dec!(0.05) {
+            100
+        } else if drawdown < dec!(0.10) {
+            75
+        } else if drawdown < dec!(0.20) {
+            50
+        } else if drawdown < dec!(0.30) {
+            25
+        } else {
+            0
+        };
+    
+        let avg_score = (var_score + sharpe_score + dd_score) / 3;
+    
+        match avg_score {
+            80.. => RiskGrade::A,
+            60..=79 => RiskGrade::B,
+            40..=59 => RiskGrade::C,
+            20..=39 => RiskGrade::D,
+            _ => RiskGrade::F,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PortfolioPosition {
     pub asset: Address,
@@ -100,22 +242,38 @@ pub struct PortfolioPosition {
     pub current_price: Decimal,
     pub entry_price: Decimal,
     pub unrealized_pnl: Decimal,
+    /// Where the entry price came from; anything but `Purchase` is an estimate
+    #[serde(default)]
+    pub entry_price_provenance: PriceProvenance,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketScenario {
     pub name: String,
+    /// Relative price change per asset; -0.5 halves the price
     pub price_shocks: HashMap<Address, Decimal>,
+    /// Relative price change of assets missing from `price_shocks`
+    #[serde(default)]
+    pub default_shock: Decimal,
+    /// Factor on every asset's volatility when VaR is re-run
     pub volatility_multiplier: Decimal,
+    /// Share of the way each correlation is moved toward 1, from 0 to 1
     pub correlation_adjustment: Decimal,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScenarioOutcome {
     pub scenario: MarketScenario,
+    /// Relative change in portfolio value at the shocked prices
     pub portfolio_value_change: Decimal,
+    /// Stressed minus baseline 95% VaR
     pub var_impact: Decimal,
+    /// Share of the portfolio's observed daily returns as bad as the value change
     pub probability: Decimal,
+    pub baseline_value: Decimal,
+    pub stressed_value: Decimal,
+    pub baseline_var_95: Decimal,
+    pub stressed_var_95: Decimal,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -127,31 +285,91 @@ pub struct RiskAlert {
     pub message: String,
     pub metric_value: Decimal,
     pub threshold: Decimal,
-    pub timestamp: DateTime<Utc>,
+    pub timestamp: DateTime<Utc>,      // Last time the breach was observed
+    pub limit: String,
+    pub status: AlertStatus,
+    pub occurrence_count: u32,
+    pub first_seen: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum AlertType {
     VaRBreach,
     DrawdownLimit,
     ConcentrationRisk,
     LiquidityWarning,
     VolatilitySpike,
+    /// A held stablecoin has stayed off its peg
+    DepegRisk,
+    LeverageLimit,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum AlertSeverity {
     Info,
     Warning,
     Critical,
 }
 
+impl AlertSeverity {
+    /// Next severity level up, saturating at Critical
+    pub fn escalate(self) -> Self {
+        match self {
+            AlertSeverity::Info => AlertSeverity::Warning,
+            AlertSeverity::Warning | AlertSeverity::Critical => AlertSeverity::Critical,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlertStatus {
+    Open,
+    Resolved,
+}
+
 pub struct RiskService {
     eth_client: Arc<EthereumClient>,
     db: Arc<PgPool>,
     cache: Arc<RwLock<ConnectionManager>>,
     risk_engine_address: Address,
-    websocket_clients: Arc<RwLock<HashMap<Uuid, tokio::sync::mpsc::Sender<RiskMetrics>>>>,
+    broadcaster: Arc<RiskBroadcaster>,
+    alert_tracker: Arc<RwLock<AlertTracker>>,
+    acquisitions: Arc<AcquisitionTracker>,
+    factor_model: Arc<RwLock<FactorModel>>,
+    /// Publishes risk grades to the RiskEngine; off unless a signing key is configured
+    publisher: Option<Arc<RiskPublisher>>,
+    /// Time a pre-trade evaluation may take before it degrades to Warn
+    pre_trade_budget: std::time::Duration,
+    /// Stablecoins watched for depegs; depegged ones get a stressed VaR volatility
+    depeg: Arc<DepegMonitor>,
+    /// Shared return days below which an asset pair's correlation is reported as zero
+    correlation_min_observations: usize,
+    /// VaR method for calculations that do not ask for one
+    var_method: VaRMethod,
+    /// Scenarios drawn per Monte Carlo VaR
+    monte_carlo_simulations: usize,
+    /// Fixed seed for reproducible Monte Carlo VaR; fresh entropy when unset
+    monte_carlo_seed: Option<u64>,
+    /// Daily prices per asset behind returns, VaR and drawdown
+    price_history_days: usize,
+    /// Benchmark of portfolios that have none of their own
+    default_benchmark: Option<PriceSeries>,
+    /// Scores positions against their assets' traded volume and depth
+    liquidity: Arc<LiquidityModel>,
+    /// Posts notified alerts to the portfolio's subscribed endpoints
+    webhooks: Arc<WebhookDispatcher>,
+}
+
+/// Risk grade publication state of a portfolio
+#[derive(Debug, Clone, Serialize)]
+pub struct PublicationStatus {
+    pub portfolio_address: Address,
+    pub enabled: bool,
+    /// Last attestation this service published or found on chain
+    pub last_published: Option<PublishedAttestation>,
+    /// What the RiskEngine holds now
+    pub on_chain: Option<OnChainAttestation>,
 }
 
 impl RiskService {
@@ -172,19 +390,423 @@ impl RiskService {
         let conn = ConnectionManager::new(client).await?;
         let cache = Arc::new(RwLock::new(conn));
         
+        let db = Arc::new(db);
+        let acquisitions = Arc::new(AcquisitionTracker::new(eth_client.clone(), db.clone(), None));
+        let liquidity = Arc::new(LiquidityModel::new(
+            Arc::new(DbMarketDepthSource::new(db.clone(), chrono::Duration::hours(liquidity::DEFAULT_MAX_AGE_HOURS))),
+            LiquidityConfig::default(),
+        ));
+        let webhooks = Arc::new(WebhookDispatcher::new(db.clone(), WebhookConfig::default())?);
+        
         Ok(Self {
             eth_client,
-            db: Arc::new(db),
+            db,
             cache,
             risk_engine_address,
-            websocket_clients: Arc::new(RwLock::new(HashMap::new())),
+            broadcaster: Arc::new(RiskBroadcaster::default()),
+            alert_tracker: Arc::new(RwLock::new(AlertTracker::new(AlertPolicy::default()))),
+            acquisitions,
+            factor_model: Arc::new(RwLock::new(FactorModel::default())),
+            publisher: None,
+            pre_trade_budget: DEFAULT_PRE_TRADE_BUDGET,
+            depeg: Arc::new(DepegMonitor::default()),
+            correlation_min_observations: metrics::DEFAULT_MIN_CORRELATION_OBSERVATIONS,
+            var_method: VaRMethod::default(),
+            monte_carlo_simulations: metrics::DEFAULT_MONTE_CARLO_SIMULATIONS,
+            monte_carlo_seed: None,
+            price_history_days: history::DEFAULT_HISTORY_DAYS,
+            default_benchmark: None,
+            liquidity,
+            webhooks,
+        })
+    }
+    
+    /// Database pool shared with the export job runner
+    pub fn db_pool(&self) -> Arc<PgPool> {
+        self.db.clone()
+    }
+    
+    /// Replace the alert deduplication and escalation policy
+    pub fn with_alert_policy(mut self, policy: AlertPolicy) -> Self {
+        self.alert_tracker = Arc::new(RwLock::new(AlertTracker::new(policy)));
+        self
+    }
+    
+    /// Price purchases from TradingModule fills; without it every acquisition is a transfer
+    pub fn with_trading_module(mut self, trading_module: Address) -> Self {
+        self.acquisitions = Arc::new(AcquisitionTracker::new(self.eth_client.clone(), self.db.clone(), Some(trading_module)));
+        self
+    }
+    
+    /// Acquisition tracker shared with the background sync
+    pub fn acquisitions(&self) -> Arc<AcquisitionTracker> {
+        self.acquisitions.clone()
+    }
+    
+    /// Updates buffered per WebSocket client before a lagging client is dropped
+    pub fn with_broadcast_capacity(mut self, capacity: usize) -> Self {
+        self.broadcaster = Arc::new(RiskBroadcaster::new(capacity));
+        self
+    }
+    
+    /// Replace the factor model used for risk decomposition until a saved one is restored
+    pub fn with_factor_model(mut self, model: FactorModel) -> Self {
+        self.factor_model = Arc::new(RwLock::new(model));
+        self
+    }
+    
+    /// Publish risk grades of calculated portfolios to the RiskEngine contract. The
+    /// Ethereum client must have a signing key with the contract's risk manager role.
+    pub fn with_publication(mut self, policy: PublicationPolicy) -> Self {
+        self.publisher = Some(Arc::new(RiskPublisher::new(self.eth_client.clone(), self.risk_engine_address, policy)));
+        self
+    }
+    
+    /// Latency budget for pre-trade evaluations
+    pub fn with_pre_trade_budget(mut self, budget: std::time::Duration) -> Self {
+        self.pre_trade_budget = budget;
+        self
+    }
+    
+    /// VaR method for monitoring, rebalancing, what-if and subscription calculations
+    pub fn with_var_method(mut self, method: VaRMethod) -> Self {
+        self.var_method = method;
+        self
+    }
+    
+    pub fn var_method(&self) -> VaRMethod {
+        self.var_method
+    }
+    
+    /// Scenarios drawn per Monte Carlo VaR
+    pub fn with_monte_carlo_simulations(mut self, num_simulations: usize) -> Self {
+        self.monte_carlo_simulations = num_simulations;
+        self
+    }
+    
+    /// Draw Monte Carlo scenarios from a fixed seed so VaR is reproducible, e.g. in tests
+    pub fn with_monte_carlo_seed(mut self, seed: u64) -> Self {
+        self.monte_carlo_seed = Some(seed);
+        self
+    }
+    
+    /// Daily prices per asset loaded for risk calculations
+    pub fn with_price_history_days(mut self, days: usize) -> Self {
+        self.price_history_days = days;
+        self
+    }
+    
+    /// Benchmark for the beta and alpha of portfolios without one of their own
+    pub fn with_default_benchmark(mut self, benchmark: PriceSeries) -> Self {
+        self.default_benchmark = Some(benchmark);
+        self
+    }
+    
+    /// Score liquidity with this model instead of market data from `asset_market_depth`
+    pub fn with_liquidity_model(mut self, model: LiquidityModel) -> Self {
+        self.liquidity = Arc::new(model);
+        self
+    }
+    
+    /// Timeout and retry schedule of alert webhook deliveries
+    pub fn with_webhook_config(mut self, config: WebhookConfig) -> Result<Self, RiskServiceError> {
+        self.webhooks = Arc::new(WebhookDispatcher::new(self.db.clone(), config)?);
+        Ok(self)
+    }
+    
+    /// Measure a portfolio's beta and alpha against `benchmark` from now on
+    pub async fn set_portfolio_benchmark(&self, portfolio: Address, benchmark: PriceSeries) -> Result<PriceSeries, RiskServiceError> {
+        let benchmark = benchmark::validate(&benchmark)?;
+        benchmark::save(&self.db, portfolio, &benchmark).await?;
+        info!(portfolio = %Pii::debug(portfolio), benchmark = %benchmark, "Portfolio benchmark set");
+        Ok(benchmark)
+    }
+    
+    /// Benchmark a portfolio's beta and alpha are measured against, if any
+    pub async fn portfolio_benchmark(&self, portfolio: Address) -> Result<Option<PriceSeries>, RiskServiceError> {
+        Ok(benchmark::load(&self.db, portfolio).await?.or_else(|| self.default_benchmark.clone()))
+    }
+    
+    /// Set a portfolio's limit on one metric, breaches alerting at `severity` or the metric's default
+    pub async fn set_risk_limit(
+        &self,
+        portfolio: Address,
+        name: &str,
+        value: Decimal,
+        severity: Option<AlertSeverity>,
+    ) -> Result<RiskLimit, RiskServiceError> {
+        let metric = limits::validate(name, value)?;
+        let limit = RiskLimit {
+            name: metric.name().to_string(),
+            value,
+            severity: severity.unwrap_or_else(|| metric.default_severity()),
+            custom: true,
+            updated_at: Some(Utc::now()),
+        };
+        limits::save(&self.db, portfolio, &limit).await?;
+        limits::invalidate(&self.cache, portfolio).await?;
+        info!(portfolio = %Pii::debug(portfolio), limit = %limit.name, value = %limit.value, severity = ?limit.severity, "Risk limit set");
+        Ok(limit)
+    }
+    
+    /// Drop a portfolio's own limit, reverting to the default where there is one.
+    /// Returns false when the portfolio had not set the limit.
+    pub async fn delete_risk_limit(&self, portfolio: Address, name: &str) -> Result<bool, RiskServiceError> {
+        let deleted = limits::delete(&self.db, portfolio, name).await?;
+        if deleted {
+            limits::invalidate(&self.cache, portfolio).await?;
+            info!(portfolio = %Pii::debug(portfolio), limit = %name, "Risk limit removed");
+        }
+        Ok(deleted)
+    }
+    
+    /// Send the portfolio's alerts at the given severities to `url`, signed with `secret`
+    pub async fn register_webhook(
+        &self,
+        portfolio: Address,
+        url: &str,
+        secret: &str,
+        severities: &[AlertSeverity],
+    ) -> Result<WebhookSubscription, RiskServiceError> {
+        let severities = webhooks::validate(url, secret, severities)?;
+        let subscription = WebhookSubscription {
+            id: Uuid::new_v4(),
+            portfolio,
+            url: url.to_string(),
+            secret: secret.to_string(),
+            severities,
+            created_at: Utc::now(),
+        };
+        webhooks::save(&self.db, &subscription).await?;
+        info!(portfolio = %Pii::debug(portfolio), webhook_id = %subscription.id, severities = ?subscription.severities, "Alert webhook registered");
+        Ok(subscription)
+    }
+    
+    pub async fn list_webhooks(&self, portfolio: Address) -> Result<Vec<WebhookSubscription>, RiskServiceError> {
+        webhooks::load(&self.db, portfolio).await
+    }
+    
+    /// Stop sending alerts to a webhook; false when the portfolio has none with this id
+    pub async fn delete_webhook(&self, portfolio: Address, id: Uuid) -> Result<bool, RiskServiceError> {
+        let deleted = webhooks::delete(&self.db, portfolio, id).await?;
+        if deleted {
+            info!(portfolio = %Pii::debug(portfolio), webhook_id = %id, "Alert webhook removed");
+        }
+        Ok(deleted)
+    }
+    
+    /// Alert deliveries to the portfolio's webhooks that failed every attempt
+    pub async fn webhook_dead_letters(&self, portfolio: Address) -> Result<Vec<DeadLetter>, RiskServiceError> {
+        webhooks::load_dead_letters(&self.db, portfolio).await
+    }
+    
+    /// Limits monitoring checks the portfolio against, its own and the defaults it keeps
+    pub async fn list_risk_limits(&self, portfolio: Address) -> Result<Vec<RiskLimit>, RiskServiceError> {
+        limits::load_cached(&self.db, &self.cache, portfolio).await
+    }
+    
+    /// Record an asset's price for the day of `at`
+    pub async fn record_price(&self, asset: Address, price: Decimal, at: DateTime<Utc>) -> Result<(), RiskServiceError> {
+        history::record(&self.db, asset, price, at).await?;
+        history::invalidate(&self.cache, &PriceSeries::asset(asset), self.price_history_days).await
+    }
+    
+    /// Bulk-load price history, e.g. from a vendor export; returns the number of prices written
+    pub async fn backfill_price_history(
+        &self,
+        series: Vec<(Address, Vec<(DateTime<Utc>, Decimal)>)>,
+    ) -> Result<u64, RiskServiceError> {
+        let written = history::import(&self.db, &series).await?;
+        for (asset, _) in &series {
+            history::invalidate(&self.cache, &PriceSeries::asset(*asset), self.price_history_days).await?;
+        }
+        info!("Imported {} prices for {} assets", written, series.len());
+        Ok(written)
+    }
+    
+    /// Daily prices of `assets` on the days all of them were priced, oldest first, one column per asset
+    pub async fn price_history(&self, assets: &[Address]) -> Result<Vec<Vec<Decimal>>, RiskServiceError> {
+        Ok(self.dated_price_history(assets).await?.into_iter().map(|(_, prices)| prices).collect())
+    }
+    
+    async fn dated_price_history(&self, assets: &[Address]) -> Result<Vec<(NaiveDate, Vec<Decimal>)>, RiskServiceError> {
+        let mut histories = Vec::with_capacity(assets.len());
+        for asset in assets {
+            histories.push(history::load_cached(&self.db, &self.cache, &PriceSeries::asset(*asset), self.price_history_days).await?);
+        }
+        history::align(&histories)
+    }
+    
+    /// Report zero correlation for asset pairs with fewer shared return days than this
+    pub fn with_correlation_min_observations(mut self, min_observations: usize) -> Self {
+        self.correlation_min_observations = min_observations;
+        self
+    }
+    
+    /// Watch stablecoins for depegs with this monitor and its configured pegs
+    pub fn with_depeg_monitor(mut self, monitor: DepegMonitor) -> Self {
+        self.depeg = Arc::new(monitor);
+        self
+    }
+    
+    /// Depeg monitor fed by price ingestion
+    pub fn depeg_monitor(&self) -> Arc<DepegMonitor> {
+        self.depeg.clone()
+    }
+    
+    /// Apply pegs added or removed through the admin API over the configured ones
+    pub async fn restore_stablecoin_pegs(&self) -> Result<(), RiskServiceError> {
+        for (asset, peg) in depeg::load_saved_pegs(&self.db).await? {
+            match peg {
+                Some(peg) => { self.depeg.set_peg(asset, peg)?; }
+                None => { self.depeg.remove_peg(asset); }
+            }
+        }
+        Ok(())
+    }
+    
+    /// Watched stablecoins and where each stands against its peg
+    pub fn stablecoin_pegs(&self) -> Vec<PegStatus> {
+        self.depeg.pegs()
+    }
+    
+    /// Watch an asset as a stablecoin pegged at `peg`
+    pub async fn set_stablecoin_peg(&self, asset: Address, peg: Decimal) -> Result<PegStatus, RiskServiceError> {
+        if peg <= Decimal::ZERO {
+            return Err(RiskServiceError::InvalidInput(format!("Peg of {:?} must be positive", asset)));
+        }
+        depeg::save_peg(&self.db, asset, Some(peg)).await?;
+        self.depeg.set_peg(asset, peg)
+    }
+    
+    /// Stop watching an asset as a stablecoin, resolving its open depeg alerts
+    pub async fn remove_stablecoin_peg(&self, asset: Address) -> Result<(), RiskServiceError> {
+        depeg::save_peg(&self.db, asset, None).await?;
+        if let Some(status) = self.depeg.remove_peg(asset) {
+            if status.depegged_since.is_some() {
+                let price = status.last_price.unwrap_or(status.peg);
+                self.depeg_changed(&DepegTransition::Recovered { asset, peg: status.peg, price }).await?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Last published and current on-chain risk grade of a portfolio
+    pub async fn publication_status(&self, portfolio: Address) -> Result<PublicationStatus, RiskServiceError> {
+        let Some(publisher) = &self.publisher else {
+            return Ok(PublicationStatus { portfolio_address: portfolio, enabled: false, last_published: None, on_chain: None });
+        };
+        
+        Ok(PublicationStatus {
+            portfolio_address: portfolio,
+            enabled: true,
+            last_published: publisher.last_published(portfolio).await,
+            on_chain: publisher.read_on_chain(portfolio).await?,
         })
     }
     
-    /// Calculate comprehensive risk assessment for a portfolio
+    /// Factor model currently used for risk decomposition
+    pub async fn factor_model(&self) -> FactorModel {
+        self.factor_model.read().await.clone()
+    }
+    
+    /// Validate, persist and apply a new factor model
+    pub async fn update_factor_model(&self, model: FactorModel) -> Result<FactorModel, RiskServiceError> {
+        model.validate()?;
+        factors::save_model(&self.db, &model).await?;
+        *self.factor_model.write().await = model.clone();
+        info!("Factor model updated: {} factors", model.factors.len());
+        Ok(model)
+    }
+    
+    /// Load the most recently saved factor model, keeping the current one if none was saved
+    pub async fn restore_factor_model(&self) -> Result<(), RiskServiceError> {
+        if let Some(model) = factors::load_saved_model(&self.db).await? {
+            model.validate()?;
+            *self.factor_model.write().await = model;
+        }
+        Ok(())
+    }
+    
+    /// Stored factor loadings, for one asset or all of them
+    pub async fn get_factor_exposures(&self, asset: Option<Address>) -> Result<Vec<FactorExposure>, RiskServiceError> {
+        factors::load_exposures(&self.db, asset).await
+    }
+    
+    /// Replace an asset's factor loadings; every factor must exist in the current model
+    pub async fn set_factor_exposures(
+        &self,
+        asset: Address,
+        loadings: HashMap<String, Decimal>,
+    ) -> Result<Vec<FactorExposure>, RiskServiceError> {
+        {
+            let model = self.factor_model.read().await;
+            if let Some(unknown) = loadings.keys().find(|factor| !model.factors.contains(*factor)) {
+                return Err(RiskServiceError::InvalidInput(format!("Unknown factor {}", unknown)));
+            }
+        }
+        
+        factors::replace_exposures(&self.db, asset, &loadings).await?;
+        self.get_factor_exposures(Some(asset)).await
+    }
+    
+    /// Remove an asset's loadings so it falls into the idiosyncratic bucket
+    pub async fn delete_factor_exposures(&self, asset: Address) -> Result<(), RiskServiceError> {
+        factors::replace_exposures(&self.db, asset, &HashMap::new()).await
+    }
+    
+    /// VaR at `confidence` from the empirical distribution of the portfolio's daily
+    /// returns, weighting each asset by its position's market value
+    pub fn calculate_var_historical(
+        &self,
+        returns: &[Vec<Decimal>],
+        positions: &[PortfolioPosition],
+        confidence: f64,
+    ) -> Result<Decimal, RiskServiceError> {
+        metrics::historical_var(&metrics::portfolio_returns(returns, positions)?, confidence)
+    }
+    
+    /// 95% and 99% VaR and Expected Shortfall by `method`
+    fn calculate_tail_risk(
+        &self,
+        method: VaRMethod,
+        returns: &[Vec<Decimal>],
+        positions: &[PortfolioPosition],
+    ) -> Result<TailRisk, RiskServiceError> {
+        match method {
+            VaRMethod::MonteCarlo => {
+                // Depegged stablecoins are simulated at a stressed volatility
+                let mut rng = match self.monte_carlo_seed {
+                    Some(seed) => StdRng::seed_from_u64(seed),
+                    None => StdRng::from_entropy(),
+                };
+                let simulated = metrics::simulate_portfolio_returns(
+                    &mut rng, returns, positions, &self.depeg.volatility_overrides(), self.monte_carlo_simulations,
+                )?;
+                TailRisk::from_sorted(&simulated)
+            }
+            VaRMethod::Historical => {
+                let mut portfolio_returns = metrics::portfolio_returns(returns, positions)?;
+                portfolio_returns.sort();
+                TailRisk::from_sorted(&portfolio_returns)
+            }
+            VaRMethod::Parametric => {
+                let portfolio_returns = metrics::portfolio_returns(returns, positions)?;
+                Ok(TailRisk {
+                    var_95: metrics::parametric_var(&portfolio_returns, 0.95)?,
+                    var_99: metrics::parametric_var(&portfolio_returns, 0.99)?,
+                    es_95: metrics::parametric_expected_shortfall(&portfolio_returns, 0.95)?,
+                    es_99: metrics::parametric_expected_shortfall(&portfolio_returns, 0.99)?,
+                })
+            }
+        }
+    }
+    
+    /// Calculate comprehensive risk assessment for a portfolio, with VaR by `var_method`
     pub async fn calculate_portfolio_risk(
         &self,
         portfolio_address: Address,
+        var_method: VaRMethod,
     ) -> Result<RiskMetrics, RiskServiceError> {
         // Fetch portfolio positions from on-chain
         let positions = self.fetch_portfolio_positions(portfolio_address).await?;
@@ -194,56 +816,65 @@ impl RiskService {
         }
         
         // Fetch historical price data
-        let price_history = self.fetch_price_history(&positions).await?;
+        let (dates, price_history): (Vec<NaiveDate>, Vec<Vec<Decimal>>) =
+            self.fetch_price_history(&positions).await?.into_iter().unzip();
         
-        if price_history.len() < 30 {
+        // Every asset has enough prices, but they may share too few days
+        if price_history.len() < history::MIN_OBSERVATIONS {
             return Err(RiskServiceError::InsufficientData);
         }
         
         // Calculate returns
-        let returns = self.calculate_returns(&price_history);
-        
-        // Calculate VaR using Monte Carlo simulation
-        let (var_95, var_99) = self.calculate_var_monte_carlo(&returns, &positions, 10000).await?;
+        let assets: Vec<Address> = positions.iter().map(|p| p.asset).collect();
+        let returns = metrics::daily_returns(&price_history, &assets)?;
         
-        // Calculate Expected Shortfall (CVaR)
-        let expected_shortfall = self.calculate_expected_shortfall(&returns, var_95);
+        // Calculate VaR and Expected Shortfall (CVaR) from one distribution
+        let TailRisk { var_95, var_99, es_95: expected_shortfall, es_99 } =
+            self.calculate_tail_risk(var_method, &returns, &positions)?;
         
         // Calculate correlation matrix
-        let correlation_matrix = self.calculate_correlation_matrix(&returns);
+        let correlation_matrix = metrics::correlation_matrix(&returns, self.correlation_min_observations)?;
         
         // Calculate Sharpe ratio
-        let sharpe_ratio = self.calculate_sharpe_ratio(&returns);
+        let sharpe_ratio = metrics::sharpe_ratio(&returns)?;
         
         // Calculate Sortino ratio
-        let sortino_ratio = self.calculate_sortino_ratio(&returns);
+        let sortino_ratio = metrics::sortino_ratio(&returns)?;
         
         // Calculate maximum drawdown
-        let max_drawdown = self.calculate_max_drawdown(&price_history);
+        let max_drawdown = metrics::max_drawdown(&price_history, &assets)?;
         
         // Calculate beta and alpha
-        let (beta, alpha) = self.calculate_beta_alpha(&returns).await?;
+        let (beta, alpha) = self.calculate_beta_alpha(portfolio_address, &dates, &returns, &positions).await?;
         
         // Calculate volatility
-        let volatility = self.calculate_volatility(&returns);
+        let volatility = metrics::volatility(&returns)?;
         
-        // Assess liquidity
-        let liquidity_scores = self.assess_liquidity(&positions).await?;
+        // Assess liquidity against each asset's traded volume and depth
+        let liquidity_details = self.liquidity.assess(&positions).await?;
+        let liquidity_scores = liquidity_details.iter()
+            .map(|(asset, assessment)| (*asset, assessment.score))
+            .collect();
         
         // Calculate concentration risk
-        let concentration_risk = self.calculate_concentration_risk(&positions);
+        let concentration_risk = metrics::concentration_risk(&positions)?;
         
         // Calculate leverage ratio
         let leverage_ratio = self.calculate_leverage_ratio(&positions);
         
         // Determine risk grade
-        let risk_grade = self.determine_risk_grade(var_95, sharpe_ratio, max_drawdown);
+        let risk_grade = RiskGrade::from_metrics(var_95, sharpe_ratio, max_drawdown);
+        
+        // Attribute variance to factors
+        let factor_risk_contributions = self.decompose_factor_risk(&positions).await?;
         
         let metrics = RiskMetrics {
             portfolio_address,
             var_95,
             var_99,
+            var_method,
             expected_shortfall,
+            es_99,
             sharpe_ratio,
             sortino_ratio,
             max_drawdown,
@@ -252,9 +883,11 @@ impl RiskService {
             volatility,
             correlation_matrix,
             liquidity_scores,
+            liquidity_details,
             concentration_risk,
             leverage_ratio,
             risk_grade,
+            factor_risk_contributions,
             timestamp: Utc::now(),
         };
         
@@ -264,376 +897,354 @@ impl RiskService {
         // Cache results in Redis
         self.cache_risk_metrics(&metrics).await?;
         
-        // Send real-time updates via WebSocket
-        self.broadcast_risk_update(&metrics).await;
+        // Fan out to WebSocket clients off the calculation path
+        self.broadcast_risk_update(&metrics);
+        
+        // On-chain publication happens after the metrics are stored and never fails the calculation
+        self.publish_risk_grade(&metrics);
         
         Ok(metrics)
     }
     
-    /// Predict risk under various market scenarios
+    /// Revalue a portfolio and re-run its VaR under each scenario, best outcome first
     pub async fn predict_risk_scenarios(
         &self,
         portfolio_address: Address,
         scenarios: Vec<MarketScenario>,
     ) -> Result<Vec<ScenarioOutcome>, RiskServiceError> {
-        let mut outcomes = Vec::new();
-        
-        for scenario in scenarios {
-            let outcome = self.run_scenario_simulation(portfolio_address, &scenario).await?;
-            outcomes.push(outcome);
+        for scenario in &scenarios {
+            scenarios::validate(scenario)?;
         }
         
-        outcomes.sort_by(|a, b| b.portfolio_value_change.partial_cmp(&a.portfolio_value_change).unwrap());
-        
-        Ok(outcomes)
-    }
-    
-    /// Monitor risk limits and generate alerts
-    pub async fn monitor_risk_limits(
-        &self,
-        portfolio_address: Address,
-    ) -> Result<Vec<RiskAlert>, RiskServiceError> {
-        let metrics = self.calculate_portfolio_risk(portfolio_address).await?;
-        let limits = self.fetch_risk_limits(portfolio_address).await?;
-        let mut alerts = Vec::new();
-        
-        // Check VaR limits
-        if let Some(var_limit) = limits.get("max_var_95") {
-            if metrics.var_95 > *var_limit {
-                alerts.push(RiskAlert {
-                    id: Uuid::new_v4(),
-                    portfolio: portfolio_address,
-                    alert_type: AlertType::VaRBreach,
-                    severity: AlertSeverity::Critical,
-                    message: format!("VaR (95%) exceeds limit: {} > {}", metrics.var_95, var_limit),
-                    metric_value: metrics.var_95,
-                    threshold: *var_limit,
-                    timestamp: Utc::now(),
-                });
-            }
+        let positions = self.fetch_portfolio_positions(portfolio_address).await?;
+        if positions.is_empty() {
+            return Err(RiskServiceError::PortfolioNotFound(format!("{:?}", portfolio_address)));
         }
-        
-        // Check drawdown limits
-        if let Some(dd_limit) = limits.get("max_drawdown") {
-            if metrics.max_drawdown > *dd_limit {
-                alerts.push(RiskAlert {
-                    id: Uuid::new_v4(),
-                    portfolio: portfolio_address,
-                    alert_type: AlertType::DrawdownLimit,
-                    severity: AlertSeverity::Warning,
-                    message: format!("Maximum drawdown exceeds limit: {} > {}", metrics.max_drawdown, dd_limit),
-                    metric_value: metrics.max_drawdown,
-                    threshold: *dd_limit,
-                    timestamp: Utc::now(),
-                });
-            }
+        let (_, price_history): (Vec<NaiveDate>, Vec<Vec<Decimal>>) =
+            self.fetch_price_history(&positions).await?.into_iter().unzip();
+        if price_history.len() < history::MIN_OBSERVATIONS {
+            return Err(RiskServiceError::InsufficientData);
         }
+        let assets: Vec<Address> = positions.iter().map(|p| p.asset).collect();
+        let returns = metrics::daily_returns(&price_history, &assets)?;
         
-        // Check concentration risk
-        if metrics.concentration_risk > Decimal::from_str("0.4").unwrap() {
-            alerts.push(RiskAlert {
-                id: Uuid::new_v4(),
-                portfolio: portfolio_address,
-                alert_type: AlertType::ConcentrationRisk,
-                severity: AlertSeverity::Warning,
-                message: format!("High concentration risk: {}", metrics.concentration_risk),
-                metric_value: metrics.concentration_risk,
-                threshold: Decimal::from_str("0.4").unwrap(),
-                timestamp: Utc::now(),
-            });
-        }
+        // Baseline and scenarios draw the same Monte Carlo scenarios, so VaR differences
+        // come from the stress alone
+        let seed = self.monte_carlo_seed.unwrap_or_else(|| thread_rng().gen());
+        let baseline_var_95 = self.scenario_var_95(seed, &returns, &positions, CovarianceStress::NONE)?;
+        let observed_returns = metrics::portfolio_returns(&returns, &positions)?;
         
-        // Store alerts
-        for alert in &alerts {
-            self.store_alert(alert).await?;
-        }
+        let mut outcomes = scenarios.iter()
+            .map(|scenario| self.run_scenario_simulation(seed, &returns, &positions, &observed_returns, baseline_var_95, scenario))
+            .collect::<Result<Vec<_>, _>>()?;
+        
+        outcomes.sort_by(|a, b| b.portfolio_value_change.cmp(&a.portfolio_value_change));
         
-        Ok(alerts)
+        Ok(outcomes)
     }
     
-    // Private helper methods
-    
-    async fn fetch_portfolio_positions(&self, portfolio: Address) -> Result<Vec<PortfolioPosition>, RiskServiceError> {
-        // In production, fetch from blockchain
-        // For now, return mock data
-        Ok(vec![
-            PortfolioPosition {
-                asset: Address::random(),
-                amount: Decimal::from(1000),
-                current_price: Decimal::from(100),
-                entry_price: Decimal::from(95),
-                unrealized_pnl: Decimal::from(5000),
-            },
-        ])
-    }
-    
-    async fn fetch_price_history(&self, _positions: &[PortfolioPosition]) -> Result<Vec<Vec<Decimal>>, RiskServiceError> {
-        // Fetch from database or external API
-        // Mock implementation
-        let mut rng = thread_rng();
-        let mut history = Vec::new();
-        
-        for _ in 0..100 {
-            let mut day_prices = Vec::new();
-            for _ in 0..5 {
-                let price = Decimal::from(100) + Decimal::from(rng.gen_range(-10..10));
-                day_prices.push(price);
-            }
-            history.push(day_prices);
-        }
-        
-        Ok(history)
+    /// Named scenarios to run through `predict_risk_scenarios`
+    pub fn list_builtin_scenarios(&self) -> Vec<MarketScenario> {
+        scenarios::builtin_scenarios()
     }
     
-    fn calculate_returns(&self, price_history: &[Vec<Decimal>]) -> Vec<Vec<Decimal>> {
-        let mut returns = Vec::new();
+    /// Advisory trades that bring a portfolio back within a risk target.
+    ///
+    /// Candidates are simulated against a fresh risk assessment; nothing is executed.
+    /// Trades smaller than `min_trade_value` are never suggested.
+    pub async fn suggest_rebalance(
+        &self,
+        portfolio_address: Address,
+        target: RebalanceTarget,
+        min_trade_value: Decimal,
+    ) -> Result<RebalancePlan, RiskServiceError> {
+        target.validate()?;
+        if min_trade_value < Decimal::ZERO {
+            return Err(RiskServiceError::InvalidInput("Minimum trade size cannot be negative".into()));
+        }
         
-        for i in 1..price_history.len() {
-            let mut day_returns = Vec::new();
-            for j in 0..price_history[i].len() {
-                let return_val = (price_history[i][j] - price_history[i-1][j]) / price_history[i-1][j];
-                day_returns.push(return_val);
-            }
-            returns.push(day_returns);
+        let metrics = self.calculate_portfolio_risk(portfolio_address, self.var_method).await?;
+        let positions = self.fetch_portfolio_positions(portfolio_address).await?;
+        let total_value: Decimal = positions.iter().map(|p| p.amount * p.current_price).sum();
+        if total_value.is_zero() {
+            return Err(RiskServiceError::InsufficientData);
         }
         
-        returns
+        let model = self.factor_model().await;
+        let assets = self.asset_risks(&positions, total_value).await?;
+        let holdings: Vec<RebalanceHolding> = positions.iter().zip(assets)
+            .map(|(position, risk)| RebalanceHolding {
+                asset: position.asset,
+                amount: position.amount,
+                price: position.current_price,
+                liquidity_score: metrics.liquidity_scores.get(&position.asset).copied().unwrap_or(50),
+                variance: risk.variance,
+                loadings: risk.loadings,
+            })
+            .collect();
+        
+        Ok(rebalance::suggest(
+            portfolio_address,
+            &model,
+            &holdings,
+            &RiskBaseline::from(&metrics),
+            &target,
+            min_trade_value,
+        ))
     }
     
-    async fn calculate_var_monte_carlo(
+    /// Before/after risk of a portfolio with hypothetical trades overlaid.
+    ///
+    /// Uses the latest cached assessment and the stored price history; nothing is
+    /// ingested, persisted or broadcast. Assets new to the portfolio without price
+    /// history are reported as unmodeled and left out of the weights.
+    pub async fn what_if(
         &self,
-        returns: &[Vec<Decimal>],
-        _positions: &[PortfolioPosition],
-        num_simulations: usize,
-    ) -> Result<(Decimal, Decimal), RiskServiceError> {
-        let mut rng = thread_rng();
-        let mut simulated_returns = Vec::new();
-        
-        // Calculate mean and standard deviation of returns
-        let mean = Decimal::from(0); // Simplified
-        let std_dev = Decimal::from_str("0.02").unwrap(); // 2% daily volatility
+        portfolio_address: Address,
+        trades: Vec<HypotheticalTrade>,
+    ) -> Result<WhatIfReport, RiskServiceError> {
+        what_if::validate_trades(&trades)?;
         
-        // Run Monte Carlo simulations
-        for _ in 0..num_simulations {
-            let normal = Normal::new(0.0, std_dev.to_f64_lossy()).unwrap();
-            let simulated = Decimal::try_from(normal.sample(&mut rng)).unwrap_or(Decimal::ZERO);
-            simulated_returns.push(mean + simulated);
+        let metrics = match self.cached_risk_metrics(portfolio_address).await {
+            Some(metrics) => metrics,
+            None => self.calculate_portfolio_risk(portfolio_address, self.var_method).await?,
+        };
+        let positions = self.fetch_portfolio_positions(portfolio_address).await?;
+        if positions.is_empty() {
+            return Err(RiskServiceError::PortfolioNotFound(format!("{:?}", portfolio_address)));
         }
         
-        // Sort returns
-        simulated_returns.sort();
-        
-        // Calculate VaR at 95% and 99% confidence levels
-        let index_95 = (num_simulations as f64 * 0.05) as usize;
-        let index_99 = (num_simulations as f64 * 0.01) as usize;
+        let mut loadings: HashMap<Address, HashMap<String, Decimal>> = HashMap::new();
+        for exposure in self.get_factor_exposures(None).await? {
+            loadings.entry(exposure.asset).or_default().insert(exposure.factor, exposure.loading);
+        }
         
-        let var_95 = simulated_returns[index_95].abs();
-        let var_99 = simulated_returns[index_99].abs();
+        // Held assets fall back to the default variance, as in the assessment itself
+        let mut holdings = Vec::with_capacity(positions.len() + trades.len());
+        for position in &positions {
+            let variance = factors::load_return_variance(&self.db, position.asset).await?
+                .unwrap_or(DEFAULT_ASSET_VARIANCE);
+            holdings.push(WhatIfHolding {
+                asset: position.asset,
+                amount: position.amount,
+                price: position.current_price,
+                variance: Some(variance),
+                loadings: loadings.remove(&position.asset).unwrap_or_default(),
+            });
+        }
+        for trade in &trades {
+            if holdings.iter().any(|h| h.asset == trade.asset) {
+                continue;
+            }
+            holdings.push(WhatIfHolding {
+                asset: trade.asset,
+                amount: Decimal::ZERO,
+                price: trade.price,
+                variance: factors::load_return_variance(&self.db, trade.asset).await?,
+                loadings: loadings.remove(&trade.asset).unwrap_or_default(),
+            });
+        }
         
-        Ok((var_95, var_99))
+        let model = self.factor_model().await;
+        what_if::evaluate(portfolio_address, &model, &holdings, &metrics, &trades)
     }
     
-    fn calculate_expected_shortfall(&self, returns: &[Vec<Decimal>], var_95: Decimal) -> Decimal {
-        let mut losses_beyond_var = Vec::new();
+    /// Decide whether a proposed trade may be placed for a portfolio.
+    ///
+    /// Overlays the trade as in `what_if` and checks the result against the portfolio's
+    /// risk limits. Portfolios that are not watched are allowed without evaluation, and
+    /// evaluations that overrun the latency budget degrade to Warn with `timed_out` set.
+    pub async fn evaluate_pre_trade(
+        &self,
+        portfolio_address: Address,
+        trade: HypotheticalTrade,
+    ) -> Result<PreTradeEvaluation, RiskServiceError> {
+        what_if::validate_trades(std::slice::from_ref(&trade))?;
         
-        for day_returns in returns {
-            for ret in day_returns {
-                if *ret < -var_95 {
-                    losses_beyond_var.push(ret.abs());
-                }
+        let evaluation = async {
+            if !self.acquisitions.is_watched(portfolio_address).await? {
+                return Ok(PreTradeEvaluation::unwatched(portfolio_address, trade.clone()));
             }
-        }
+            let report = self.what_if(portfolio_address, vec![trade.clone()]).await?;
+            let limits = self.fetch_risk_limits(portfolio_address).await?;
+            Ok::<_, RiskServiceError>(PreTradeEvaluation::from_report(&report, trade.clone(), &limits))
+        };
+        let evaluation = pre_trade::within_budget(self.pre_trade_budget, portfolio_address, trade.clone(), evaluation).await?;
         
-        if losses_beyond_var.is_empty() {
-            return var_95;
+        if evaluation.timed_out {
+            warn!(portfolio = %Pii::debug(portfolio_address), budget = ?self.pre_trade_budget, "Pre-trade evaluation exceeded its budget; degraded to warn");
+        } else {
+            info!("Pre-trade evaluation {} for {:?}: {:?} in {}ms",
+                evaluation.evaluation_id, portfolio_address, evaluation.decision, evaluation.elapsed_ms);
         }
-        
-        let sum: Decimal = losses_beyond_var.iter().sum();
-        sum / Decimal::from(losses_beyond_var.len())
+        Ok(evaluation)
     }
     
-    fn calculate_correlation_matrix(&self, returns: &[Vec<Decimal>]) -> Vec<Vec<Decimal>> {
-        let num_assets = returns[0].len();
-        let mut matrix = vec![vec![Decimal::ZERO; num_assets]; num_assets];
+    /// Monitor risk limits and generate alerts
+    ///
+    /// Repeated breaches of the same limit refresh a single open alert instead of
+    /// creating a new one each cycle. Returns the alerts currently open for the portfolio.
+    pub async fn monitor_risk_limits(
+        &self,
+        portfolio_address: Address,
+    ) -> Result<Vec<RiskAlert>, RiskServiceError> {
+        let metrics = self.calculate_portfolio_risk(portfolio_address, self.var_method).await?;
+        // Every limit the portfolio sets, over the defaults it leaves in place
+        let limits = limits::load_cached(&self.db, &self.cache, portfolio_address).await?;
+        let mut evaluations = limits::evaluate(&limits, &metrics);
+        
+        // Warn on positions that cannot be liquidated on any estimate; resolved once volume data arrives
+        evaluations.extend(metrics.liquidity_details.values().map(liquidity::liquidity_evaluation));
+        
+        let (changed, open) = {
+            let mut tracker = self.alert_tracker.write().await;
+            let changed = tracker.record_cycle(portfolio_address, evaluations, Utc::now());
+            (changed, tracker.open_alerts(portfolio_address))
+        };
         
-        for i in 0..num_assets {
-            for j in 0..num_assets {
-                if i == j {
-                    matrix[i][j] = Decimal::ONE;
-                } else {
-                    // Calculate correlation between asset i and j
-                    let corr = self.calculate_correlation(returns, i, j);
-                    matrix[i][j] = corr;
-                    matrix[j][i] = corr;
-                }
-            }
-        }
+        // Persist and push opened, escalated and resolved alerts; refreshes wait out the cooldown
+        self.notify_alerts(&changed).await?;
         
-        matrix
+        Ok(open.into_iter().map(|tracked| tracked.alert).collect())
     }
     
-    fn calculate_correlation(&self, returns: &[Vec<Decimal>], i: usize, j: usize) -> Decimal {
-        // Simplified correlation calculation
-        Decimal::from_str("0.5").unwrap()
+    /// Alerts currently open for a portfolio, most recently seen first
+    pub async fn list_active_alerts(&self, portfolio_address: Address) -> Vec<RiskAlert> {
+        self.alert_tracker.read().await
+            .open_alerts(portfolio_address)
+            .into_iter()
+            .map(|tracked| tracked.alert)
+            .collect()
     }
     
-    fn calculate_sharpe_ratio(&self, returns: &[Vec<Decimal>]) -> Decimal {
-        // Calculate average return
-        let mut total_return = Decimal::ZERO;
-        let mut count = 0;
-        
-        for day_returns in returns {
-            for ret in day_returns {
-                total_return += ret;
-                count += 1;
-            }
-        }
-        
-        let avg_return = total_return / Decimal::from(count);
-        
-        // Calculate standard deviation
-        let mut variance_sum = Decimal::ZERO;
-        for day_returns in returns {
-            for ret in day_returns {
-                let diff = ret - avg_return;
-                variance_sum += diff * diff;
-            }
-        }
-        
-        let std_dev = (variance_sum / Decimal::from(count)).sqrt_approx().unwrap_or(Decimal::ONE);
-        
-        // Assume risk-free rate of 2% annually (0.0079% daily)
-        let risk_free_rate = Decimal::from_str("0.000079").unwrap();
-        
-        // Sharpe ratio = (return - risk_free_rate) / std_dev
-        if std_dev > Decimal::ZERO {
-            (avg_return - risk_free_rate) / std_dev
-        } else {
-            Decimal::ZERO
-        }
+    /// Reload alerts left open by a previous run, so a breach that persists across a
+    /// restart keeps refreshing the same alert
+    pub async fn restore_open_alerts(&self) -> Result<usize, RiskServiceError> {
+        let open = alerts::load_open(&self.db).await?;
+        let count = open.len();
+        self.alert_tracker.write().await.restore(open);
+        Ok(count)
     }
     
-    fn calculate_sortino_ratio(&self, returns: &[Vec<Decimal>]) -> Decimal {
-        // Similar to Sharpe but only considers downside volatility
-        let mut total_return = Decimal::ZERO;
-        let mut count = 0;
-        let mut downside_variance = Decimal::ZERO;
-        let mut downside_count = 0;
-        
-        for day_returns in returns {
-            for ret in day_returns {
-                total_return += ret;
-                count += 1;
-                
-                if *ret < Decimal::ZERO {
-                    downside_variance += ret * ret;
-                    downside_count += 1;
-                }
-            }
-        }
-        
-        let avg_return = total_return / Decimal::from(count);
-        
-        if downside_count > 0 {
-            let downside_deviation = (downside_variance / Decimal::from(downside_count)).sqrt_approx().unwrap_or(Decimal::ONE);
-            let risk_free_rate = Decimal::from_str("0.000079").unwrap();
-            
-            if downside_deviation > Decimal::ZERO {
-                (avg_return - risk_free_rate) / downside_deviation
-            } else {
-                Decimal::ZERO
+    /// Query tracked alerts with their occurrence history
+    pub async fn get_alerts(
+        &self,
+        portfolio_address: Address,
+        status: Option<AlertStatus>,
+    ) -> Vec<TrackedAlert> {
+        let tracker = self.alert_tracker.read().await;
+        
+        match status {
+            Some(AlertStatus::Open) => tracker.open_alerts(portfolio_address),
+            Some(AlertStatus::Resolved) => tracker.resolved_alerts(portfolio_address),
+            None => {
+                let mut alerts = tracker.open_alerts(portfolio_address);
+                alerts.extend(tracker.resolved_alerts(portfolio_address));
+                alerts
             }
-        } else {
-            Decimal::from(100) // No downside risk
         }
     }
     
-    fn calculate_max_drawdown(&self, price_history: &[Vec<Decimal>]) -> Decimal {
-        let mut max_drawdown = Decimal::ZERO;
+    // Private helper methods
+    
+    async fn fetch_portfolio_positions(&self, portfolio: Address) -> Result<Vec<PortfolioPosition>, RiskServiceError> {
+        // Newly seen portfolios are backfilled now; watched ones are kept current by the sync job.
+        // A backfill that failed is retried rather than read as an empty portfolio.
+        self.acquisitions.watch(portfolio).await?;
+        if !self.acquisitions.is_backfilled(portfolio).await? {
+            self.acquisitions.sync_portfolio(portfolio).await?;
+        }
         
-        for asset_idx in 0..price_history[0].len() {
-            let mut peak = price_history[0][asset_idx];
+        let mut positions = Vec::new();
+        for (asset, basis) in acquisitions::load_cost_basis(&self.db, portfolio).await? {
+            let current_price = acquisitions::latest_price(&self.db, asset).await?
+                .unwrap_or(basis.entry_price);
             
-            for day_prices in price_history {
-                let current = day_prices[asset_idx];
-                
-                if current > peak {
-                    peak = current;
-                }
-                
-                let drawdown = (peak - current) / peak;
-                if drawdown > max_drawdown {
-                    max_drawdown = drawdown;
-                }
-            }
+            positions.push(PortfolioPosition {
+                asset,
+                amount: basis.amount,
+                current_price,
+                entry_price: basis.entry_price,
+                unrealized_pnl: (current_price - basis.entry_price) * basis.amount,
+                entry_price_provenance: basis.provenance,
+            });
         }
         
-        max_drawdown
+        Ok(positions)
     }
     
-    async fn calculate_beta_alpha(&self, _returns: &[Vec<Decimal>]) -> Result<(Decimal, Decimal), RiskServiceError> {
-        // Calculate beta and alpha against market benchmark
-        // Simplified implementation
-        let beta = Decimal::ONE; // Market neutral
-        let alpha = Decimal::from_str("0.02").unwrap(); // 2% alpha
-        
-        Ok((beta, alpha))
+    async fn fetch_price_history(&self, positions: &[PortfolioPosition]) -> Result<Vec<(NaiveDate, Vec<Decimal>)>, RiskServiceError> {
+        let assets: Vec<Address> = positions.iter().map(|p| p.asset).collect();
+        self.dated_price_history(&assets).await
     }
     
-    fn calculate_volatility(&self, returns: &[Vec<Decimal>]) -> Decimal {
-        let mut variance_sum = Decimal::ZERO;
-        let mut count = 0;
+    /// Beta and alpha of the portfolio's value-weighted returns against its benchmark.
+    ///
+    /// `dates` are the days of the price history `returns` were taken from.
+    async fn calculate_beta_alpha(
+        &self,
+        portfolio: Address,
+        dates: &[NaiveDate],
+        returns: &[Vec<Decimal>],
+        positions: &[PortfolioPosition],
+    ) -> Result<(Option<Decimal>, Option<Decimal>), RiskServiceError> {
+        let Some(benchmark) = self.portfolio_benchmark(portfolio).await? else {
+            return Ok((None, None));
+        };
         
-        for day_returns in returns {
-            for ret in day_returns {
-                variance_sum += ret * ret;
-                count += 1;
-            }
+        let portfolio_returns = metrics::portfolio_returns(returns, positions)?;
+        if portfolio_returns.len() + 1 != dates.len() {
+            return Err(RiskServiceError::CalculationError("Portfolio returns do not line up with price history days".to_string()));
         }
+        let dated: Vec<(NaiveDate, NaiveDate, Decimal)> = dates.windows(2).zip(portfolio_returns)
+            .map(|(days, ret)| (days[0], days[1], ret))
+            .collect();
         
-        if count > 0 {
-            // Annualize volatility (252 trading days)
-            let daily_vol = (variance_sum / Decimal::from(count)).sqrt_approx().unwrap_or(Decimal::ZERO);
-            daily_vol * Decimal::from(252).sqrt_approx().unwrap_or(Decimal::ONE)
-        } else {
-            Decimal::ZERO
-        }
+        let benchmark_prices = history::load_cached(&self.db, &self.cache, &benchmark, self.price_history_days).await?;
+        let (beta, alpha) = metrics::beta_alpha(&dated, &benchmark_prices, history::MIN_OBSERVATIONS)?;
+        Ok((Some(beta), Some(alpha)))
     }
     
-    async fn assess_liquidity(&self, positions: &[PortfolioPosition]) -> Result<HashMap<Address, u8>, RiskServiceError> {
-        let mut scores = HashMap::new();
-        
-        for position in positions {
-            // Simplified liquidity scoring
-            let score = if position.amount > Decimal::from(10000) {
-                50 // Low liquidity for large positions
-            } else if position.amount > Decimal::from(1000) {
-                75 // Medium liquidity
-            } else {
-                95 // High liquidity for small positions
-            };
-            
-            scores.insert(position.asset, score);
+    /// Factor decomposition of the portfolio, weighting positions by market value
+    async fn decompose_factor_risk(
+        &self,
+        positions: &[PortfolioPosition],
+    ) -> Result<Vec<FactorRiskContribution>, RiskServiceError> {
+        let model = self.factor_model().await;
+        let values = positions.iter().map(metrics::position_value).collect::<Result<Vec<_>, _>>()?;
+        let total_value = values.iter().try_fold(Decimal::ZERO, |sum, value| sum.checked_add(*value))
+            .ok_or_else(|| RiskServiceError::CalculationError("Overflow computing portfolio value".to_string()))?;
+        if total_value.is_zero() {
+            return Ok(Vec::new());
         }
         
-        Ok(scores)
+        let assets = self.asset_risks(positions, total_value).await?;
+        Ok(factors::decompose(&model, &assets))
     }
     
-    fn calculate_concentration_risk(&self, positions: &[PortfolioPosition]) -> Decimal {
-        let total_value: Decimal = positions.iter()
-            .map(|p| p.amount * p.current_price)
-            .sum();
-        
-        if total_value == Decimal::ZERO {
-            return Decimal::ZERO;
+    /// Weight, return variance and factor loadings of each position, in position order
+    async fn asset_risks(
+        &self,
+        positions: &[PortfolioPosition],
+        total_value: Decimal,
+    ) -> Result<Vec<AssetRisk>, RiskServiceError> {
+        let mut loadings: HashMap<Address, HashMap<String, Decimal>> = HashMap::new();
+        for exposure in self.get_factor_exposures(None).await? {
+            loadings.entry(exposure.asset).or_default().insert(exposure.factor, exposure.loading);
         }
         
-        let max_position = positions.iter()
-            .map(|p| p.amount * p.current_price)
-            .max()
-            .unwrap_or(Decimal::ZERO);
+        let mut assets = Vec::with_capacity(positions.len());
+        for position in positions {
+            let variance = factors::load_return_variance(&self.db, position.asset).await?
+                .unwrap_or(DEFAULT_ASSET_VARIANCE);
+            assets.push(AssetRisk {
+                weight: metrics::position_value(position)? / total_value,
+                variance,
+                loadings: loadings.remove(&position.asset).unwrap_or_default(),
+            });
+        }
         
-        max_position / total_value
+        Ok(assets)
     }
     
     fn calculate_leverage_ratio(&self, positions: &[PortfolioPosition]) -> Decimal {
@@ -642,79 +1253,38 @@ impl RiskService {
         Decimal::ONE
     }
     
-    fn determine_risk_grade(&self, var: Decimal, sharpe: Decimal, drawdown: Decimal) -> RiskGrade {
-        let var_score = if var < Decimal::from_str("0.02").unwrap() {
-            100
-        } else if var < Decimal::from_str("0.05").unwrap() {
-            75
-        } else if var < Decimal::from_str("0.10").unwrap() {
-            50
-        } else if var < Decimal::from_str("0.15").unwrap() {
-            25
-        } else {
-            0
-        };
-        
-        let sharpe_score = if sharpe > Decimal::from(2) {
-            100
-        } else if sharpe > Decimal::ONE {
-            75
-        } else if sharpe > Decimal::from_str("0.5").unwrap() {
-            50
-        } else if sharpe > Decimal::ZERO {
-            25
-        } else {
-            0
-        };
-        
-        let dd_score = if drawdown < Decimal::from_str("0.05").unwrap() {
-            100
-        } else if drawdown < Decimal::from_str("0.10").unwrap() {
-            75
-        } else if drawdown < Decimal::from_str("0.20").unwrap() {
-            50
-        } else if drawdown < Decimal::from_str("0.30").unwrap() {
-            25
-        } else {
-            0
-        };
-        
-        let avg_score = (var_score + sharpe_score + dd_score) / 3;
-        
-        match avg_score {
-            80..=100 => RiskGrade::A,
-            60..=79 => RiskGrade::B,
-            40..=59 => RiskGrade::C,
-            20..=39 => RiskGrade::D,
-            _ => RiskGrade::F,
-        }
-    }
-    
     async fn store_risk_metrics(&self, metrics: &RiskMetrics) -> Result<(), RiskServiceError> {
         let query = r#"
             INSERT INTO risk_metrics (
-                portfolio_address, timestamp, var_95, var_99,
-                sharpe_ratio, max_drawdown, beta, volatility,
-                liquidity_score, concentration_risk, risk_grade
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                portfolio_address, timestamp, var_95, var_99, expected_shortfall, es_99,
+                sharpe_ratio, max_drawdown, beta, alpha, volatility,
+                liquidity_score, concentration_risk, risk_grade, correlation_matrix
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15::jsonb)
         "#;
         
         let liquidity_avg = metrics.liquidity_scores.values()
             .map(|&v| v as i32)
             .sum::<i32>() / metrics.liquidity_scores.len().max(1) as i32;
+        let correlation: Vec<Vec<f64>> = metrics.correlation_matrix.iter()
+            .map(|row| row.iter().map(|v| v.to_f64_lossy()).collect())
+            .collect();
         
         sqlx::query(query)
             .bind(format!("{:?}", metrics.portfolio_address))
             .bind(metrics.timestamp)
             .bind(metrics.var_95.to_f64_lossy())
             .bind(metrics.var_99.to_f64_lossy())
+            .bind(metrics.expected_shortfall.to_f64_lossy())
+            .bind(metrics.es_99.to_f64_lossy())
             .bind(metrics.sharpe_ratio.to_f64_lossy())
             .bind(metrics.max_drawdown.to_f64_lossy())
-            .bind(metrics.beta.to_f64_lossy())
+            .bind(metrics.beta.map(|beta| beta.to_f64_lossy()))
+            .bind(metrics.alpha.map(|alpha| alpha.to_f64_lossy()))
             .bind(metrics.volatility.to_f64_lossy())
             .bind(liquidity_avg)
             .bind(metrics.concentration_risk.to_f64_lossy())
             .bind(format!("{:?}", metrics.risk_grade))
+            .bind(serde_json::to_string(&correlation).unwrap_or_default())
             .execute(&*self.db)
             .await?;
         
@@ -724,7 +1294,8 @@ impl RiskService {
     async fn cache_risk_metrics(&self, metrics: &RiskMetrics) -> Result<(), RiskServiceError> {
         let mut cache = self.cache.write().await;
         let key = format!("risk:portfolio:{:?}", metrics.portfolio_address);
-        let value = serde_json::to_string(metrics).unwrap();
+        let value = serde_json::to_string(metrics)
+            .map_err(|e| RiskServiceError::CalculationError(format!("Cannot serialize risk metrics: {}", e)))?;
         
         redis::cmd("SET")
             .arg(&key)
@@ -737,61 +1308,218 @@ impl RiskService {
         Ok(())
     }
     
-    async fn broadcast_risk_update(&self, metrics: &RiskMetrics) {
-        let clients = self.websocket_clients.read().await;
-        for (_id, sender) in clients.iter() {
-            let _ = sender.send(metrics.clone()).await;
+    /// Assessment cached by the last risk calculation, if it has not expired
+    async fn cached_risk_metrics(&self, portfolio: Address) -> Option<RiskMetrics> {
+        let mut cache = self.cache.write().await;
+        let key = format!("risk:portfolio:{:?}", portfolio);
+        let value: Option<String> = redis::cmd("GET")
+            .arg(&key)
+            .query_async(&mut *cache)
+            .await
+            .ok()?;
+        value.and_then(|v| serde_json::from_str(&v).ok())
+    }
+    
+    fn broadcast_risk_update(&self, metrics: &RiskMetrics) {
+        if self.broadcaster.subscriber_count() == 0 {
+            return;
         }
+        let broadcaster = self.broadcaster.clone();
+        let metrics = metrics.clone();
+        tokio::spawn(async move {
+            broadcaster.publish(&metrics);
+        });
     }
     
-    async fn run_scenario_simulation(
+    fn publish_risk_grade(&self, metrics: &RiskMetrics) {
+        let Some(publisher) = self.publisher.clone() else { return };
+        let attestation = RiskAttestation::from_metrics(metrics);
+        tokio::spawn(async move {
+            let portfolio = attestation.portfolio;
+            match publisher.publish(attestation).await {
+                Ok(PublishOutcome::Published(published)) => info!(
+                    portfolio = %Pii::debug(portfolio),
+                    grade = ?published.attestation.grade,
+                    tx_hash = ?published.tx_hash,
+                    verified = published.verified,
+                    "Published risk grade"
+                ),
+                Ok(_) => {}
+                Err(e) => warn!(portfolio = %Pii::debug(portfolio), error = %e, "Risk grade not published"),
+            }
+        });
+    }
+    
+    fn run_scenario_simulation(
         &self,
-        portfolio: Address,
+        seed: u64,
+        returns: &[Vec<Decimal>],
+        positions: &[PortfolioPosition],
+        observed_returns: &[Decimal],
+        baseline_var_95: Decimal,
         scenario: &MarketScenario,
     ) -> Result<ScenarioOutcome, RiskServiceError> {
-        // Stress test portfolio under scenario
-        // Simplified implementation
-        let portfolio_value_change = Decimal::from_str("-0.05").unwrap(); // 5% loss
-        let var_impact = Decimal::from_str("0.02").unwrap(); // 2% increase in VaR
-        let probability = Decimal::from_str("0.15").unwrap(); // 15% probability
+        let stressed = scenarios::shocked_positions(positions, scenario)?;
+        let baseline_value = scenarios::total_value(positions)?;
+        let stressed_value = scenarios::total_value(&stressed)?;
+        let portfolio_value_change = (stressed_value - baseline_value)
+            .checked_div(baseline_value)
+            .ok_or(RiskServiceError::InsufficientData)?;
+        
+        // A portfolio shocked to nothing has nothing left to lose
+        let stressed_var_95 = if stressed_value.is_zero() {
+            Decimal::ZERO
+        } else {
+            self.scenario_var_95(seed, returns, &stressed, scenarios::covariance_stress(scenario))?
+        };
         
         Ok(ScenarioOutcome {
             scenario: scenario.clone(),
             portfolio_value_change,
-            var_impact,
-            probability,
+            var_impact: stressed_var_95 - baseline_var_95,
+            probability: scenarios::observed_frequency(observed_returns, portfolio_value_change),
+            baseline_value,
+            stressed_value,
+            baseline_var_95,
+            stressed_var_95,
         })
     }
     
-    async fn fetch_risk_limits(&self, _portfolio: Address) -> Result<HashMap<String, Decimal>, RiskServiceError> {
-        // Fetch from database or smart contract
-        let mut limits = HashMap::new();
-        limits.insert("max_var_95".to_string(), Decimal::from_str("0.10").unwrap());
-        limits.insert("max_drawdown".to_string(), Decimal::from_str("0.20").unwrap());
-        Ok(limits)
+    /// Monte Carlo 95% VaR of the positions in a stressed market
+    fn scenario_var_95(
+        &self,
+        seed: u64,
+        returns: &[Vec<Decimal>],
+        positions: &[PortfolioPosition],
+        stress: CovarianceStress,
+    ) -> Result<Decimal, RiskServiceError> {
+        let simulated = metrics::simulate_stressed_portfolio_returns(
+            &mut StdRng::seed_from_u64(seed), returns, positions, &self.depeg.volatility_overrides(), stress, self.monte_carlo_simulations,
+        )?;
+        Ok(TailRisk::from_sorted(&simulated)?.var_95)
+    }
+    
+    async fn fetch_risk_limits(&self, portfolio: Address) -> Result<HashMap<String, Decimal>, RiskServiceError> {
+        Ok(limits::thresholds(&limits::load_cached(&self.db, &self.cache, portfolio).await?))
     }
     
-    async fn store_alert(&self, _alert: &RiskAlert) -> Result<(), RiskServiceError> {
-        // Store in database
+    async fn notify_alerts(&self, changed: &[TrackedAlert]) -> Result<(), RiskServiceError> {
+        for tracked in changed.iter().filter(|tracked| tracked.notify) {
+            self.store_alert(tracked).await?;
+            if self.broadcaster.subscriber_count() > 0 {
+                self.broadcaster.publish_alert(&tracked.alert);
+            }
+            self.webhooks.dispatch(tracked.alert.clone());
+        }
         Ok(())
     }
     
-    pub async fn register_websocket_client(&self, client_id: Uuid, sender: tokio::sync::mpsc::Sender<RiskMetrics>) {
-        let mut clients = self.websocket_clients.write().await;
-        clients.insert(client_id, sender);
-        info!("WebSocket client {} registered", client_id);
+    async fn store_alert(&self, tracked: &TrackedAlert) -> Result<(), RiskServiceError> {
+        let alert = &tracked.alert;
+        
+        // One row per alert, refreshed on every occurrence
+        sqlx::query(r#"
+            INSERT INTO risk_alerts (
+                id, portfolio_address, alert_type, limit_name, severity, message,
+                metric_value, threshold, status, occurrence_count,
+                created_at, last_seen_at, resolved_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            ON CONFLICT (id) DO UPDATE SET
+                severity = EXCLUDED.severity,
+                message = EXCLUDED.message,
+                metric_value = EXCLUDED.metric_value,
+                threshold = EXCLUDED.threshold,
+                status = EXCLUDED.status,
+                occurrence_count = EXCLUDED.occurrence_count,
+                last_seen_at = EXCLUDED.last_seen_at,
+                resolved_at = EXCLUDED.resolved_at
+        "#)
+            .bind(alert.id)
+            .bind(format!("{:?}", alert.portfolio))
+            .bind(format!("{:?}", alert.alert_type))
+            .bind(&alert.limit)
+            .bind(format!("{:?}", alert.severity))
+            .bind(&alert.message)
+            .bind(alert.metric_value.to_f64_lossy())
+            .bind(alert.threshold.to_f64_lossy())
+            .bind(format!("{:?}", alert.status))
+            .bind(alert.occurrence_count as i32)
+            .bind(alert.first_seen)
+            .bind(alert.timestamp)
+            .bind(alert.resolved_at)
+            .execute(&*self.db)
+            .await?;
+        
+        // Record the latest occurrence for the history view
+        if alert.status == AlertStatus::Open {
+            if let Some(occurrence) = tracked.history.last() {
+                sqlx::query(r#"
+                    INSERT INTO risk_alert_occurrences (alert_id, metric_value, severity, observed_at)
+                    VALUES ($1, $2, $3, $4)
+                "#)
+                    .bind(alert.id)
+                    .bind(occurrence.metric_value.to_f64_lossy())
+                    .bind(format!("{:?}", occurrence.severity))
+                    .bind(occurrence.timestamp)
+                    .execute(&*self.db)
+                    .await?;
+            }
+        }
+        
+        Ok(())
     }
     
-    pub async fn unregister_websocket_client(&self, client_id: Uuid) {
-        let mut clients = self.websocket_clients.write().await;
-        clients.remove(&client_id);
-        info!("WebSocket client {} unregistered", client_id);
+    /// Stream of serialized risk updates for one WebSocket client
+    pub fn subscribe_risk_updates(&self) -> RiskSubscription {
+        self.broadcaster.subscribe()
     }
     
-    pub async fn get_client_sender(&self, client_id: Uuid) -> Option<tokio::sync::mpsc::Sender<RiskMetrics>> {
-        let clients = self.websocket_clients.read().await;
-        clients.get(&client_id).cloned()
+    /// Subscriber, lag and drop counters of the WebSocket fan-out
+    pub fn broadcast_stats(&self) -> BroadcastStats {
+        self.broadcaster.stats()
     }
 }
 
-use rust_decimal::prelude::FromStr;
\ No newline at end of file
+#[async_trait::async_trait]
+impl DepegAlerter for RiskService {
+    /// Raise a depeg alert on every watched portfolio holding the asset, or resolve the
+    /// asset's open depeg alerts once it is back on its peg
+    async fn depeg_changed(&self, transition: &DepegTransition) -> Result<(), RiskServiceError> {
+        let asset = transition.asset();
+        let now = Utc::now();
+        
+        let changed = match transition {
+            DepegTransition::Depegged { peg, price, deviation, .. } => {
+                let holders: Vec<(String,)> = sqlx::query_as(r#"
+                    SELECT b.portfolio_address FROM position_cost_basis b
+                    JOIN acquisition_watchlist w ON w.portfolio_address = b.portfolio_address
+                    WHERE b.asset_address = $1 AND b.amount > 0
+                "#)
+                    .bind(format!("{:?}", asset))
+                    .fetch_all(&*self.db)
+                    .await?;
+                
+                let mut tracker = self.alert_tracker.write().await;
+                let mut changed = Vec::new();
+                for (portfolio,) in holders {
+                    let Ok(portfolio) = portfolio.parse::<Address>() else {
+                        warn!(portfolio = %Pii::new(&portfolio), "Skipping invalid watched portfolio");
+                        continue;
+                    };
+                    let evaluation = depeg::depeg_evaluation(self.depeg.config(), asset, *peg, *price, *deviation);
+                    changed.extend(tracker.record_cycle(portfolio, vec![evaluation], now));
+                }
+                warn!("Stablecoin {:?} depegged at {} (peg {}); alerted {} portfolios", asset, price, peg, changed.len());
+                changed
+            }
+            DepegTransition::Recovered { price, peg, .. } => {
+                let resolved = self.alert_tracker.write().await
+                    .resolve_all(AlertType::DepegRisk, &depeg::depeg_limit(asset), now);
+                info!("Stablecoin {:?} back on its peg {} at {}; resolved {} alerts", asset, peg, price, resolved.len());
+                resolved
+            }
+        };
+        
+        self.notify_alerts(&changed).await
+    }
+}
@@ -0,0 +1,335 @@
+// Per-portfolio risk limits checked on every monitoring cycle
+//
+// Each limit caps one metric of the portfolio's RiskMetrics. Portfolios start from the
+// default limits; operators override a limit's threshold and alert severity per portfolio,
+// and deleting the override falls back to the default, or to no limit where there is none.
+use chrono::{DateTime, Utc};
+use redis::aio::ConnectionManager;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::{BTreeMap, HashMap};
+use tokio::sync::RwLock;
+use crate::acquisitions::parse_decimal;
+use crate::alerts::{self, LimitEvaluation};
+use crate::ethereum_client::Address;
+use crate::{AlertSeverity, AlertType, RiskMetrics, RiskServiceError};
+
+/// Seconds a portfolio's loaded limits stay cached; writes invalidate them immediately
+pub const CACHE_TTL_SECS: u64 = 300;
+
+/// A metric a limit can cap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitMetric {
+    Var95,
+    Var99,
+    Drawdown,
+    Concentration,
+    Leverage,
+    Volatility,
+}
+
+impl LimitMetric {
+    pub const ALL: [LimitMetric; 6] = [
+        LimitMetric::Var95,
+        LimitMetric::Var99,
+        LimitMetric::Drawdown,
+        LimitMetric::Concentration,
+        LimitMetric::Leverage,
+        LimitMetric::Volatility,
+    ];
+
+    /// Limit name as stored and used in alerts
+    pub fn name(&self) -> &'static str {
+        match self {
+            LimitMetric::Var95 => "max_var_95",
+            LimitMetric::Var99 => "max_var_99",
+            LimitMetric::Drawdown => "max_drawdown",
+            LimitMetric::Concentration => "max_concentration",
+            LimitMetric::Leverage => "max_leverage",
+            LimitMetric::Volatility => "max_volatility",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|metric| metric.name() == name)
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            LimitMetric::Var95 => "VaR (95%)",
+            LimitMetric::Var99 => "VaR (99%)",
+            LimitMetric::Drawdown => "Maximum drawdown",
+            LimitMetric::Concentration => "Concentration risk",
+            LimitMetric::Leverage => "Leverage ratio",
+            LimitMetric::Volatility => "Volatility",
+        }
+    }
+
+    pub fn alert_type(&self) -> AlertType {
+        match self {
+            LimitMetric::Var95 | LimitMetric::Var99 => AlertType::VaRBreach,
+            LimitMetric::Drawdown => AlertType::DrawdownLimit,
+            LimitMetric::Concentration => AlertType::ConcentrationRisk,
+            LimitMetric::Leverage => AlertType::LeverageLimit,
+            LimitMetric::Volatility => AlertType::VolatilitySpike,
+        }
+    }
+
+    /// Severity of a breach when the limit does not set one
+    pub fn default_severity(&self) -> AlertSeverity {
+        match self {
+            LimitMetric::Var95 | LimitMetric::Var99 => AlertSeverity::Critical,
+            _ => AlertSeverity::Warning,
+        }
+    }
+
+    pub fn value(&self, metrics: &RiskMetrics) -> Decimal {
+        match self {
+            LimitMetric::Var95 => metrics.var_95,
+            LimitMetric::Var99 => metrics.var_99,
+            LimitMetric::Drawdown => metrics.max_drawdown,
+            LimitMetric::Concentration => metrics.concentration_risk,
+            LimitMetric::Leverage => metrics.leverage_ratio,
+            LimitMetric::Volatility => metrics.volatility,
+        }
+    }
+}
+
+/// A threshold on one metric and the severity its breaches start at
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskLimit {
+    pub name: String,
+    pub value: Decimal,
+    pub severity: AlertSeverity,
+    /// False for a default limit the portfolio has not overridden
+    pub custom: bool,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl RiskLimit {
+    fn default_for(metric: LimitMetric, value: Decimal) -> Self {
+        Self { name: metric.name().to_string(), value, severity: metric.default_severity(), custom: false, updated_at: None }
+    }
+}
+
+/// Limits every portfolio has until it overrides them
+pub fn default_limits() -> Vec<RiskLimit> {
+    vec![
+        RiskLimit::default_for(LimitMetric::Var95, dec!(0.10)),
+        RiskLimit::default_for(LimitMetric::Drawdown, dec!(0.20)),
+        RiskLimit::default_for(LimitMetric::Concentration, dec!(0.4)),
+    ]
+}
+
+/// The default limits with a portfolio's overrides applied, by name
+pub fn effective(overrides: Vec<RiskLimit>) -> Vec<RiskLimit> {
+    let mut limits: BTreeMap<String, RiskLimit> = default_limits().into_iter()
+        .map(|limit| (limit.name.clone(), limit))
+        .collect();
+    for limit in overrides {
+        limits.insert(limit.name.clone(), limit);
+    }
+    limits.into_values().collect()
+}
+
+/// The metric a limit name caps, once its threshold is one the metric can take
+pub fn validate(name: &str, value: Decimal) -> Result<LimitMetric, RiskServiceError> {
+    let metric = LimitMetric::parse(name).ok_or_else(|| RiskServiceError::InvalidInput(format!(
+        "Unknown risk limit {}; limits are {}",
+        name,
+        LimitMetric::ALL.iter().map(LimitMetric::name).collect::<Vec<_>>().join(", ")
+    )))?;
+    if value <= Decimal::ZERO {
+        return Err(RiskServiceError::InvalidInput(format!("Limit {} must be positive", name)));
+    }
+    Ok(metric)
+}
+
+/// Check every limit against the metrics
+pub fn evaluate(limits: &[RiskLimit], metrics: &RiskMetrics) -> Vec<LimitEvaluation> {
+    limits.iter()
+        .filter_map(|limit| {
+            let metric = LimitMetric::parse(&limit.name)?;
+            let value = metric.value(metrics);
+            Some(LimitEvaluation {
+                alert_type: metric.alert_type(),
+                limit: limit.name.clone(),
+                base_severity: limit.severity.clone(),
+                metric_value: value,
+                threshold: limit.value,
+                message: format!("{} exceeds limit: {} > {}", metric.label(), value, limit.value),
+                breached: value > limit.value,
+            })
+        })
+        .collect()
+}
+
+/// Threshold of each limit by name
+pub fn thresholds(limits: &[RiskLimit]) -> HashMap<String, Decimal> {
+    limits.iter().map(|limit| (limit.name.clone(), limit.value)).collect()
+}
+
+fn parse_severity(value: &str) -> Result<AlertSeverity, RiskServiceError> {
+    alerts::parse_severity(value)
+        .ok_or_else(|| RiskServiceError::CalculationError(format!("Invalid stored limit severity {}", value)))
+}
+
+/// Limits a portfolio has overridden
+pub async fn load(db: &PgPool, portfolio: Address) -> Result<Vec<RiskLimit>, RiskServiceError> {
+    let rows: Vec<(String, String, String, DateTime<Utc>)> = sqlx::query_as(r#"
+        SELECT limit_name, limit_value::text, severity, updated_at
+        FROM risk_limits
+        WHERE portfolio_address = $1
+        ORDER BY limit_name
+    "#)
+        .bind(format!("{:?}", portfolio))
+        .fetch_all(db)
+        .await?;
+
+    rows.into_iter()
+        .map(|(name, value, severity, updated_at)| Ok(RiskLimit {
+            name,
+            value: parse_decimal(&value)?,
+            severity: parse_severity(&severity)?,
+            custom: true,
+            updated_at: Some(updated_at),
+        }))
+        .collect()
+}
+
+pub async fn save(db: &PgPool, portfolio: Address, limit: &RiskLimit) -> Result<(), RiskServiceError> {
+    sqlx::query(r#"
+        INSERT INTO risk_limits (portfolio_address, limit_name, limit_value, severity, updated_at)
+        VALUES ($1, $2, $3::numeric, $4, NOW())
+        ON CONFLICT (portfolio_address, limit_name) DO UPDATE SET
+            limit_value = EXCLUDED.limit_value,
+            severity = EXCLUDED.severity,
+            updated_at = NOW()
+    "#)
+        .bind(format!("{:?}", portfolio))
+        .bind(&limit.name)
+        .bind(limit.value.to_string())
+        .bind(format!("{:?}", limit.severity))
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// Remove a portfolio's override; false when it had none
+pub async fn delete(db: &PgPool, portfolio: Address, name: &str) -> Result<bool, RiskServiceError> {
+    let result = sqlx::query("DELETE FROM risk_limits WHERE portfolio_address = $1 AND limit_name = $2")
+        .bind(format!("{:?}", portfolio))
+        .bind(name)
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+fn cache_key(portfolio: Address) -> String {
+    format!("risk:limits:{:?}", portfolio)
+}
+
+/// Effective limits of a portfolio, served from Redis while cached
+pub async fn load_cached(db: &PgPool, cache: &RwLock<ConnectionManager>, portfolio: Address) -> Result<Vec<RiskLimit>, RiskServiceError> {
+    let key = cache_key(portfolio);
+    let cached: Option<String> = redis::cmd("GET").arg(&key).query_async(&mut *cache.write().await).await?;
+    if let Some(limits) = cached.and_then(|value| serde_json::from_str(&value).ok()) {
+        return Ok(limits);
+    }
+
+    let limits = effective(load(db, portfolio).await?);
+    redis::cmd("SET")
+        .arg(&key)
+        .arg(serde_json::to_string(&limits).unwrap_or_default())
+        .arg("EX")
+        .arg(CACHE_TTL_SECS)
+        .query_async::<_, ()>(&mut *cache.write().await)
+        .await?;
+    Ok(limits)
+}
+
+/// Drop a portfolio's cached limits so the next load sees a write
+pub async fn invalidate(cache: &RwLock<ConnectionManager>, portfolio: Address) -> Result<(), RiskServiceError> {
+    redis::cmd("DEL").arg(cache_key(portfolio)).query_async::<_, ()>(&mut *cache.write().await).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::{AlertPolicy, AlertTracker};
+    use crate::{AlertStatus, RiskGrade, VaRMethod};
+
+    fn metrics() -> RiskMetrics {
+        RiskMetrics {
+            portfolio_address: Address::repeat_byte(0xaa),
+            var_95: dec!(0.05),
+            var_99: dec!(0.08),
+            var_method: VaRMethod::Historical,
+            expected_shortfall: dec!(0.06),
+            es_99: dec!(0.09),
+            sharpe_ratio: dec!(1.2),
+            sortino_ratio: dec!(1.5),
+            max_drawdown: dec!(0.08),
+            beta: None,
+            alpha: None,
+            volatility: dec!(0.3),
+            correlation_matrix: Vec::new(),
+            liquidity_scores: HashMap::new(),
+            liquidity_details: HashMap::new(),
+            concentration_risk: dec!(0.3),
+            leverage_ratio: dec!(2.5),
+            risk_grade: RiskGrade::B,
+            factor_risk_contributions: Vec::new(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    fn custom(name: &str, value: Decimal, severity: AlertSeverity) -> RiskLimit {
+        validate(name, value).unwrap();
+        RiskLimit { name: name.to_string(), value, severity, custom: true, updated_at: Some(Utc::now()) }
+    }
+
+    #[test]
+    fn test_breached_leverage_limit_raises_an_alert_at_its_severity() {
+        let metrics = metrics();
+        let limits = effective(vec![custom("max_leverage", dec!(2), AlertSeverity::Critical)]);
+        assert_eq!(limits.len(), 4);
+
+        let evaluations = evaluate(&limits, &metrics);
+        assert_eq!(evaluations.iter().filter(|e| e.breached).count(), 1);
+
+        let mut tracker = AlertTracker::new(AlertPolicy::default());
+        let changed = tracker.record_cycle(metrics.portfolio_address, evaluations, metrics.timestamp);
+        assert_eq!(changed.len(), 1);
+        let alert = &changed[0].alert;
+        assert_eq!(alert.alert_type, AlertType::LeverageLimit);
+        assert_eq!(alert.limit, "max_leverage");
+        assert_eq!(alert.severity, AlertSeverity::Critical);
+        assert_eq!((alert.metric_value, alert.threshold), (dec!(2.5), dec!(2)));
+        assert_eq!(alert.message, "Leverage ratio exceeds limit: 2.5 > 2");
+        assert_eq!((alert.status.clone(), alert.occurrence_count), (AlertStatus::Open, 1));
+    }
+
+    #[test]
+    fn test_overrides_replace_defaults_and_unknown_limits_are_rejected() {
+        let limits = effective(vec![
+            custom("max_concentration", dec!(0.25), AlertSeverity::Info),
+            custom("max_volatility", dec!(0.2), AlertSeverity::Warning),
+        ]);
+        let concentration = limits.iter().find(|limit| limit.name == "max_concentration").unwrap();
+        assert_eq!((concentration.value, concentration.custom), (dec!(0.25), true));
+        assert_eq!(thresholds(&limits)["max_var_95"], dec!(0.10));
+
+        let breached: Vec<_> = evaluate(&limits, &metrics()).into_iter().filter(|e| e.breached).collect();
+        assert_eq!(breached.len(), 2);
+        assert_eq!(breached[0].alert_type, AlertType::ConcentrationRisk);
+        assert_eq!(breached[0].base_severity, AlertSeverity::Info);
+        assert_eq!(breached[1].alert_type, AlertType::VolatilitySpike);
+
+        assert!(validate("max_sharpe", dec!(1)).is_err());
+        assert!(validate("max_var_99", Decimal::ZERO).is_err());
+    }
+}
@@ -0,0 +1,434 @@
+// Liquidity of held assets from traded volume and order book or pool depth
+//
+// Each asset's market is read from a configurable source: the `asset_market_depth` table an
+// ingestion job fills, or the reserves and swaps of an on-chain AMM pool. A position scores
+// 0-100 from how many days it takes to sell at a sustainable share of daily volume and how
+// much of it the bid side absorbs within the depth band. Assets without volume data score 0.
+use std::collections::HashMap;
+use std::sync::Arc;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use ethers::abi::{self, ParamType, Token};
+use ethers::prelude::*;
+use ethers::utils::id;
+use rust_decimal::prelude::*;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use crate::acquisitions::{from_base_units, parse_decimal};
+use crate::alerts::LimitEvaluation;
+use crate::ethereum_client::{Address, EthereumClient};
+use crate::{AlertSeverity, AlertType, RiskServiceError};
+
+/// Uniswap V2 style pool swap event
+pub const SWAP_EVENT: &str = "Swap(address,uint256,uint256,uint256,uint256,address)";
+
+/// Price move, as a fraction, within which bid and ask depth are measured
+pub const DEPTH_BAND: Decimal = dec!(0.02);
+
+/// Blocks requested per swap log query
+const LOG_BLOCK_RANGE: u64 = 5_000;
+
+/// Hours after which an ingested observation no longer counts, unless configured otherwise
+pub const DEFAULT_MAX_AGE_HOURS: i64 = 48;
+
+/// Volume and depth of an asset's market, in asset units
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketDepth {
+    pub daily_volume: Decimal,
+    /// Units that can be sold before the price falls by `DEPTH_BAND`
+    pub bid_depth: Decimal,
+    /// Units that can be bought before the price rises by `DEPTH_BAND`
+    pub ask_depth: Decimal,
+    pub observed_at: DateTime<Utc>,
+}
+
+/// Where asset market data comes from
+#[async_trait]
+pub trait MarketDepthSource: Send + Sync {
+    /// Current market of `asset`; `None` when the source has nothing for it
+    async fn depth(&self, asset: Address) -> Result<Option<MarketDepth>, RiskServiceError>;
+}
+
+/// Market data written to `asset_market_depth` by an ingestion job
+pub struct DbMarketDepthSource {
+    db: Arc<PgPool>,
+    /// Older observations are treated as missing
+    max_age: Duration,
+}
+
+impl DbMarketDepthSource {
+    pub fn new(db: Arc<PgPool>, max_age: Duration) -> Self {
+        Self { db, max_age }
+    }
+}
+
+#[async_trait]
+impl MarketDepthSource for DbMarketDepthSource {
+    async fn depth(&self, asset: Address) -> Result<Option<MarketDepth>, RiskServiceError> {
+        let row: Option<(String, String, String, DateTime<Utc>)> = sqlx::query_as(r#"
+            SELECT daily_volume::text, bid_depth::text, ask_depth::text, observed_at
+            FROM asset_market_depth
+            WHERE asset_address = $1 AND observed_at >= $2
+            ORDER BY observed_at DESC
+            LIMIT 1
+        "#)
+            .bind(format!("{:?}", asset))
+            .bind(Utc::now() - self.max_age)
+            .fetch_optional(&*self.db)
+            .await?;
+
+        row.map(|(daily_volume, bid_depth, ask_depth, observed_at)| Ok(MarketDepth {
+            daily_volume: parse_decimal(&daily_volume)?,
+            bid_depth: parse_decimal(&bid_depth)?,
+            ask_depth: parse_decimal(&ask_depth)?,
+            observed_at,
+        }))
+            .transpose()
+    }
+}
+
+/// Calls and logs of AMM pools
+#[async_trait]
+pub trait PoolReader: Send + Sync {
+    async fn read(&self, to: Address, data: Bytes) -> Result<Bytes, String>;
+
+    async fn confirmed_block(&self) -> Result<u64, String>;
+
+    /// Swap logs of `pool` in `from_block..=to_block`
+    async fn swap_logs(&self, pool: Address, from_block: u64, to_block: u64) -> Result<Vec<Log>, String>;
+}
+
+#[async_trait]
+impl PoolReader for EthereumClient {
+    async fn read(&self, to: Address, data: Bytes) -> Result<Bytes, String> {
+        self.call(to, data).await.map_err(|e| e.to_string())
+    }
+
+    async fn confirmed_block(&self) -> Result<u64, String> {
+        self.confirmed_block_number().await.map_err(|e| e.to_string())
+    }
+
+    async fn swap_logs(&self, pool: Address, from_block: u64, to_block: u64) -> Result<Vec<Log>, String> {
+        let filter = Filter::new()
+            .address(pool)
+            .event(SWAP_EVENT)
+            .from_block(from_block)
+            .to_block(to_block);
+        self.get_events(&filter).await.map_err(|e| e.to_string())
+    }
+}
+
+/// Market data of assets traded in constant-product AMM pools: depth from the pool's
+/// reserves, daily volume from its swaps over the last day of blocks
+pub struct AmmDepthSource {
+    reader: Arc<dyn PoolReader>,
+    /// Pool of each asset
+    pools: HashMap<Address, Address>,
+    blocks_per_day: u64,
+}
+
+impl AmmDepthSource {
+    pub fn new(reader: Arc<dyn PoolReader>, pools: HashMap<Address, Address>, blocks_per_day: u64) -> Self {
+        Self { reader, pools, blocks_per_day }
+    }
+
+    async fn call(&self, pool: Address, signature: &str, outputs: &[ParamType]) -> Result<Vec<Token>, RiskServiceError> {
+        let data = self.reader.read(pool, Bytes::from(id(signature).to_vec())).await
+            .map_err(RiskServiceError::EthereumError)?;
+        abi::decode(outputs, &data)
+            .map_err(|e| RiskServiceError::EthereumError(format!("Invalid {} response from pool {:?}: {}", signature, pool, e)))
+    }
+}
+
+#[async_trait]
+impl MarketDepthSource for AmmDepthSource {
+    async fn depth(&self, asset: Address) -> Result<Option<MarketDepth>, RiskServiceError> {
+        let Some(&pool) = self.pools.get(&asset) else { return Ok(None) };
+
+        let token0 = match self.call(pool, "token0()", &[ParamType::Address]).await?.first() {
+            Some(Token::Address(token0)) => *token0,
+            _ => return Err(RiskServiceError::EthereumError(format!("Pool {:?} returned no token0", pool))),
+        };
+        let reserves = self.call(pool, "getReserves()", &[ParamType::Uint(112), ParamType::Uint(112), ParamType::Uint(32)]).await?;
+        let asset_is_token0 = token0 == asset;
+        let reserve = match reserves.get(if asset_is_token0 { 0 } else { 1 }) {
+            Some(Token::Uint(reserve)) => from_base_units(*reserve)?,
+            _ => return Err(RiskServiceError::EthereumError(format!("Pool {:?} returned no reserves", pool))),
+        };
+
+        // Amounts in and out on the asset's side of every swap in the last day
+        let head = self.reader.confirmed_block().await.map_err(RiskServiceError::EthereumError)?;
+        let mut from_block = head.saturating_sub(self.blocks_per_day.saturating_sub(1));
+        let mut traded = U256::zero();
+        while from_block <= head {
+            let to_block = (from_block + LOG_BLOCK_RANGE - 1).min(head);
+            for log in self.reader.swap_logs(pool, from_block, to_block).await.map_err(RiskServiceError::EthereumError)? {
+                let word = |index: usize| log.data.get(index * 32..(index + 1) * 32).map(U256::from_big_endian);
+                let (amount_in, amount_out) = if asset_is_token0 { (word(0), word(2)) } else { (word(1), word(3)) };
+                traded = traded
+                    .saturating_add(amount_in.unwrap_or_default())
+                    .saturating_add(amount_out.unwrap_or_default());
+            }
+            from_block = to_block + 1;
+        }
+
+        let (bid_depth, ask_depth) = constant_product_depth(reserve);
+        Ok(Some(MarketDepth { daily_volume: from_base_units(traded)?, bid_depth, ask_depth, observed_at: Utc::now() }))
+    }
+}
+
+/// Units of an asset with `reserve` in a constant-product pool that can be sold, and bought,
+/// before its price moves by `DEPTH_BAND`
+pub fn constant_product_depth(reserve: Decimal) -> (Decimal, Decimal) {
+    let reserve_f = reserve.to_f64().unwrap_or(0.0);
+    let band = DEPTH_BAND.to_f64().unwrap_or(0.0);
+    let bid = reserve_f * (1.0 / (1.0 - band).sqrt() - 1.0);
+    let ask = reserve_f * (1.0 - 1.0 / (1.0 + band).sqrt());
+    (
+        Decimal::from_f64(bid).unwrap_or(Decimal::ZERO).round_dp(6),
+        Decimal::from_f64(ask).unwrap_or(Decimal::ZERO).round_dp(6),
+    )
+}
+
+/// How positions are scored against their markets
+#[derive(Debug, Clone)]
+pub struct LiquidityConfig {
+    /// Share of daily volume a position can be sold into without moving the market
+    pub participation_rate: Decimal,
+    /// Days to liquidate at or beyond which the volume part of the score is 0
+    pub max_days_to_liquidate: Decimal,
+    /// Weight of bid depth in the score; the rest is days to liquidate
+    pub depth_weight: Decimal,
+}
+
+impl Default for LiquidityConfig {
+    fn default() -> Self {
+        Self {
+            participation_rate: dec!(0.2),
+            max_days_to_liquidate: dec!(30),
+            depth_weight: dec!(0.4),
+        }
+    }
+}
+
+/// Per-asset breakdown behind a liquidity score
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LiquidityAssessment {
+    pub asset: Address,
+    pub score: u8,
+    pub position_amount: Decimal,
+    /// `None` when the source has no data for the asset
+    pub daily_volume: Option<Decimal>,
+    pub bid_depth: Option<Decimal>,
+    pub ask_depth: Option<Decimal>,
+    /// Days to sell the position at the participation rate; `None` without traded volume
+    pub days_to_liquidate: Option<Decimal>,
+    pub observed_at: Option<DateTime<Utc>>,
+}
+
+impl LiquidityAssessment {
+    pub fn has_volume(&self) -> bool {
+        self.daily_volume.is_some_and(|volume| volume > Decimal::ZERO)
+    }
+}
+
+/// Score a position of `amount` units against its asset's market
+pub fn assess(config: &LiquidityConfig, asset: Address, amount: Decimal, market: Option<&MarketDepth>) -> LiquidityAssessment {
+    let mut assessment = LiquidityAssessment {
+        asset,
+        score: 0,
+        position_amount: amount,
+        daily_volume: market.map(|m| m.daily_volume),
+        bid_depth: market.map(|m| m.bid_depth),
+        ask_depth: market.map(|m| m.ask_depth),
+        days_to_liquidate: None,
+        observed_at: market.map(|m| m.observed_at),
+    };
+    let Some(market) = market.filter(|m| m.daily_volume > Decimal::ZERO) else { return assessment };
+
+    let sellable_per_day = market.daily_volume * config.participation_rate;
+    let days = if sellable_per_day > Decimal::ZERO { amount.max(Decimal::ZERO) / sellable_per_day } else { Decimal::MAX };
+    assessment.days_to_liquidate = (days < Decimal::MAX).then(|| days.round_dp(4));
+
+    // 100 within a day, falling on a log scale to 0 at the maximum
+    let days_f = days.to_f64().unwrap_or(f64::MAX);
+    let max_days = config.max_days_to_liquidate.to_f64().unwrap_or(1.0).max(1.0 + f64::EPSILON);
+    let volume_score = if days_f <= 1.0 { 100.0 } else { (100.0 * (1.0 - days_f.ln() / max_days.ln())).max(0.0) };
+    let depth_score = if amount <= Decimal::ZERO {
+        100.0
+    } else {
+        (market.bid_depth / amount).min(Decimal::ONE).to_f64().unwrap_or(0.0).max(0.0) * 100.0
+    };
+
+    let depth_weight = config.depth_weight.to_f64().unwrap_or(0.0).clamp(0.0, 1.0);
+    let score = volume_score * (1.0 - depth_weight) + depth_score * depth_weight;
+    assessment.score = score.round().clamp(0.0, 100.0) as u8;
+    assessment
+}
+
+/// Alert limit for an asset with no liquidity data; one open alert per portfolio and asset
+pub fn liquidity_limit(asset: Address) -> String {
+    format!("liquidity:{:?}", asset)
+}
+
+/// Warning while a held asset has no traded volume data, resolved once it does
+pub fn liquidity_evaluation(assessment: &LiquidityAssessment) -> LimitEvaluation {
+    LimitEvaluation {
+        alert_type: AlertType::LiquidityWarning,
+        limit: liquidity_limit(assessment.asset),
+        base_severity: AlertSeverity::Warning,
+        metric_value: Decimal::from(assessment.score),
+        threshold: Decimal::ZERO,
+        message: format!(
+            "No traded volume data for {:?}; {} units cannot be assessed for liquidation",
+            assessment.asset, assessment.position_amount
+        ),
+        breached: !assessment.has_volume(),
+    }
+}
+
+/// Scores positions against market data from one source
+pub struct LiquidityModel {
+    source: Arc<dyn MarketDepthSource>,
+    config: LiquidityConfig,
+}
+
+impl LiquidityModel {
+    pub fn new(source: Arc<dyn MarketDepthSource>, config: LiquidityConfig) -> Self {
+        Self { source, config }
+    }
+
+    /// Assessment of each asset held, for the total amount held of it
+    pub async fn assess(&self, positions: &[crate::PortfolioPosition]) -> Result<HashMap<Address, LiquidityAssessment>, RiskServiceError> {
+        let mut amounts: HashMap<Address, Decimal> = HashMap::new();
+        for position in positions {
+            *amounts.entry(position.asset).or_default() += position.amount;
+        }
+
+        let mut assessments = HashMap::with_capacity(amounts.len());
+        for (asset, amount) in amounts {
+            let market = self.source.depth(asset).await?;
+            assessments.insert(asset, assess(&self.config, asset, amount, market.as_ref()));
+        }
+        Ok(assessments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    const ASSET: Address = H160([0xa1; 20]);
+    const QUOTE: Address = H160([0xb2; 20]);
+    const POOL: Address = H160([0xc3; 20]);
+
+    fn units(amount: u64) -> U256 {
+        U256::from(amount) * U256::exp10(18)
+    }
+
+    /// A pool of ASSET (token0) against QUOTE with a fixed set of swaps
+    struct MockPool {
+        reserves: (U256, U256),
+        swaps: Vec<(u64, [U256; 4])>,
+        head: u64,
+        queried: Mutex<Vec<(u64, u64)>>,
+    }
+
+    #[async_trait]
+    impl PoolReader for MockPool {
+        async fn read(&self, to: Address, data: Bytes) -> Result<Bytes, String> {
+            assert_eq!(to, POOL);
+            let tokens = if data[..4] == id("token0()") {
+                vec![Token::Address(ASSET)]
+            } else if data[..4] == id("getReserves()") {
+                vec![Token::Uint(self.reserves.0), Token::Uint(self.reserves.1), Token::Uint(U256::from(1_700_000_000u64))]
+            } else {
+                return Err("execution reverted".to_string());
+            };
+            Ok(Bytes::from(abi::encode(&tokens)))
+        }
+
+        async fn confirmed_block(&self) -> Result<u64, String> {
+            Ok(self.head)
+        }
+
+        async fn swap_logs(&self, pool: Address, from_block: u64, to_block: u64) -> Result<Vec<Log>, String> {
+            self.queried.lock().unwrap().push((from_block, to_block));
+            Ok(self.swaps.iter()
+                .filter(|(block, _)| pool == POOL && (from_block..=to_block).contains(block))
+                .map(|(block, amounts)| Log {
+                    address: POOL,
+                    data: Bytes::from(abi::encode(&amounts.iter().map(|a| Token::Uint(*a)).collect::<Vec<_>>())),
+                    block_number: Some(U64::from(*block)),
+                    ..Default::default()
+                })
+                .collect())
+        }
+    }
+
+    fn amm_model(pool: MockPool) -> LiquidityModel {
+        let source = AmmDepthSource::new(Arc::new(pool), HashMap::from([(ASSET, POOL)]), 7_200);
+        LiquidityModel::new(Arc::new(source), LiquidityConfig::default())
+    }
+
+    fn position(asset: Address, amount: Decimal) -> crate::PortfolioPosition {
+        crate::PortfolioPosition {
+            asset,
+            amount,
+            current_price: dec!(2),
+            entry_price: dec!(2),
+            unrealized_pnl: Decimal::ZERO,
+            entry_price_provenance: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_amm_reserves_and_swaps_score_the_position_held() {
+        let pool = MockPool {
+            reserves: (units(1_000_000), units(2_000_000)),
+            swaps: vec![
+                // Before the last day of blocks
+                (1_000, [units(900_000), U256::zero(), U256::zero(), units(1_800_000)]),
+                // 30,000 sold into the pool and 20,000 bought out of it
+                (9_000, [units(30_000), U256::zero(), U256::zero(), units(59_000)]),
+                (9_500, [U256::zero(), units(41_000), units(20_000), U256::zero()]),
+            ],
+            head: 10_000,
+            queried: Mutex::new(Vec::new()),
+        };
+        let model = amm_model(pool);
+
+        let large = model.assess(&[position(ASSET, dec!(60_000)), position(ASSET, dec!(40_000))]).await.unwrap();
+        let large = &large[&ASSET];
+        assert_eq!(large.daily_volume, Some(dec!(50_000)));
+        assert_eq!(large.position_amount, dec!(100_000));
+        // A tenth of a day's volume at 20% participation is ten days
+        assert_eq!(large.days_to_liquidate, Some(dec!(10)));
+        assert!((large.bid_depth.unwrap() - dec!(10152.5)).abs() < dec!(0.1), "{:?}", large.bid_depth);
+        assert!(large.ask_depth.unwrap() < large.bid_depth.unwrap());
+        assert_eq!(large.score, 23);
+
+        let small = &model.assess(&[position(ASSET, dec!(5_000))]).await.unwrap()[&ASSET];
+        assert_eq!((small.score, small.days_to_liquidate), (100, Some(dec!(0.5))));
+        assert!(!liquidity_evaluation(small).breached);
+    }
+
+    #[tokio::test]
+    async fn test_assets_without_volume_score_zero_and_warn() {
+        let idle = MockPool { reserves: (units(1_000), units(1_000)), swaps: vec![], head: 10_000, queried: Mutex::new(Vec::new()) };
+        let model = amm_model(idle);
+
+        let assessments = model.assess(&[position(ASSET, dec!(10)), position(QUOTE, dec!(10))]).await.unwrap();
+        // A pool without swaps has depth but no volume; an asset without a pool has neither
+        assert_eq!(assessments[&ASSET].daily_volume, Some(Decimal::ZERO));
+        assert_eq!(assessments[&QUOTE].daily_volume, None);
+        for assessment in assessments.values() {
+            assert_eq!((assessment.score, assessment.days_to_liquidate), (0, None));
+            let evaluation = liquidity_evaluation(assessment);
+            assert!(evaluation.breached);
+            assert_eq!(evaluation.alert_type, AlertType::LiquidityWarning);
+        }
+    }
+}
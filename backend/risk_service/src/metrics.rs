@@ -0,0 +1,958 @@
+// Portfolio risk statistics over daily price histories
+//
+// Histories are indexed `[day][asset]`. Degenerate input never panics: empty or short
+// histories are `InsufficientData`, and ragged rows, zero or negative prices and overflowing
+// arithmetic are `CalculationError`s naming the asset and day involved.
+use chrono::NaiveDate;
+use ndarray::{Array1, Array2, Axis};
+use rand::Rng;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use rust_decimal_macros::dec;
+use statrs::distribution::{Continuous, ContinuousCDF, Normal};
+use crate::ethereum_client::Address;
+use crate::{DecimalExt, PortfolioPosition, RiskServiceError};
+
+/// Assumed risk-free rate: 2% annually, 0.0079% daily
+const DAILY_RISK_FREE_RATE: Decimal = dec!(0.000079);
+
+/// Trading days per year, for annualizing daily volatility
+const TRADING_DAYS: i64 = 252;
+
+/// Scenarios drawn by the Monte Carlo VaR unless configured otherwise
+pub const DEFAULT_MONTE_CARLO_SIMULATIONS: usize = 10_000;
+
+/// Days two assets must both have returns on before their correlation is computed
+pub const DEFAULT_MIN_CORRELATION_OBSERVATIONS: usize = 20;
+
+/// Outcomes beyond the VaR needed before their mean is trusted as the Expected Shortfall
+pub const MIN_TAIL_OBSERVATIONS: usize = 10;
+
+fn calculation_error(message: String) -> RiskServiceError {
+    RiskServiceError::CalculationError(message)
+}
+
+fn overflow(what: &str) -> RiskServiceError {
+    calculation_error(format!("Overflow computing {}", what))
+}
+
+/// The asset's address, or its column when the history covers more assets than positions
+fn asset_label(assets: &[Address], index: usize) -> String {
+    assets.get(index).map_or_else(|| format!("asset #{}", index), |asset| format!("{:?}", asset))
+}
+
+fn checked_sum<'a>(values: impl IntoIterator<Item = &'a Decimal>, what: &str) -> Result<Decimal, RiskServiceError> {
+    values.into_iter().try_fold(Decimal::ZERO, |sum, value| sum.checked_add(*value).ok_or_else(|| overflow(what)))
+}
+
+fn checked_sum_of_squares<'a>(values: impl IntoIterator<Item = &'a Decimal>, what: &str) -> Result<Decimal, RiskServiceError> {
+    values.into_iter().try_fold(Decimal::ZERO, |sum, value| {
+        value.checked_mul(*value).and_then(|square| sum.checked_add(square)).ok_or_else(|| overflow(what))
+    })
+}
+
+/// Number of assets in the history, once every day is known to price every asset with a
+/// non-negative price
+pub fn validate_history(price_history: &[Vec<Decimal>], assets: &[Address]) -> Result<usize, RiskServiceError> {
+    let width = price_history.first().map(Vec::len).ok_or(RiskServiceError::InsufficientData)?;
+    if width == 0 {
+        return Err(RiskServiceError::InsufficientData);
+    }
+
+    for (day, prices) in price_history.iter().enumerate() {
+        if prices.len() != width {
+            return Err(calculation_error(format!(
+                "Price history on day {} has {} assets, expected {}", day, prices.len(), width
+            )));
+        }
+        if let Some(asset) = prices.iter().position(|price| price.is_sign_negative() && !price.is_zero()) {
+            return Err(calculation_error(format!(
+                "Negative price {} for {} on day {}", prices[asset], asset_label(assets, asset), day
+            )));
+        }
+    }
+    Ok(width)
+}
+
+/// Simple daily returns, one row per day after the first
+pub fn daily_returns(price_history: &[Vec<Decimal>], assets: &[Address]) -> Result<Vec<Vec<Decimal>>, RiskServiceError> {
+    validate_history(price_history, assets)?;
+    if price_history.len() < 2 {
+        return Err(RiskServiceError::InsufficientData);
+    }
+
+    price_history.windows(2).enumerate()
+        .map(|(day, pair)| {
+            pair[0].iter().zip(&pair[1]).enumerate()
+                .map(|(asset, (previous, current))| {
+                    if previous.is_zero() {
+                        return Err(calculation_error(format!(
+                            "Zero price for {} on day {}; cannot compute its return on day {}",
+                            asset_label(assets, asset), day, day + 1
+                        )));
+                    }
+                    current.checked_sub(*previous)
+                        .and_then(|change| change.checked_div(*previous))
+                        .ok_or_else(|| calculation_error(format!(
+                            "Overflow computing return of {} on day {}", asset_label(assets, asset), day + 1
+                        )))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Index of the given lower tail in `len` sorted outcomes; never past the last one
+fn tail_index(len: usize, tail: f64) -> Result<usize, RiskServiceError> {
+    if len == 0 {
+        return Err(RiskServiceError::InsufficientData);
+    }
+    if !(0.0..=1.0).contains(&tail) {
+        return Err(calculation_error(format!("Tail probability {} is not between 0 and 1", tail)));
+    }
+    Ok(((len as f64 * tail) as usize).min(len - 1))
+}
+
+/// Value at the given lower tail of sorted outcomes; the index never leaves the slice
+pub fn tail_quantile(sorted: &[Decimal], tail: f64) -> Result<Decimal, RiskServiceError> {
+    Ok(sorted[tail_index(sorted.len(), tail)?])
+}
+
+/// VaR and Expected Shortfall at 95% and 99%, as losses in fractions of portfolio value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TailRisk {
+    pub var_95: Decimal,
+    pub var_99: Decimal,
+    pub es_95: Decimal,
+    pub es_99: Decimal,
+}
+
+impl TailRisk {
+    /// Read off sorted simulated or historical portfolio returns, so the shortfall averages
+    /// the same outcomes the VaR cuts off
+    pub fn from_sorted(sorted: &[Decimal]) -> Result<Self, RiskServiceError> {
+        Ok(Self {
+            var_95: (-tail_quantile(sorted, 0.05)?).max(Decimal::ZERO),
+            var_99: (-tail_quantile(sorted, 0.01)?).max(Decimal::ZERO),
+            es_95: expected_shortfall(sorted, 0.95)?,
+            es_99: expected_shortfall(sorted, 0.99)?,
+        })
+    }
+}
+
+/// A stressed market for VaR: every volatility scaled by `volatility_multiplier` and every
+/// correlation moved toward 1 by the `correlation_adjustment` share of its distance
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CovarianceStress {
+    pub volatility_multiplier: f64,
+    pub correlation_adjustment: f64,
+}
+
+impl CovarianceStress {
+    /// The market as observed
+    pub const NONE: CovarianceStress = CovarianceStress { volatility_multiplier: 1.0, correlation_adjustment: 0.0 };
+}
+
+/// Simulated daily portfolio returns, sorted from worst to best.
+///
+/// Each scenario draws correlated asset returns from a normal distribution with the sample
+/// means and covariance of `returns`, over the days on which every position has a return,
+/// and weights them by position market value. An asset in `volatility_overrides` keeps its
+/// correlations but is simulated at the overriding daily volatility.
+pub fn simulate_portfolio_returns<R: Rng>(
+    rng: &mut R,
+    returns: &[Vec<Decimal>],
+    positions: &[PortfolioPosition],
+    volatility_overrides: &HashMap<Address, Decimal>,
+    num_simulations: usize,
+) -> Result<Vec<Decimal>, RiskServiceError> {
+    simulate_stressed_portfolio_returns(rng, returns, positions, volatility_overrides, CovarianceStress::NONE, num_simulations)
+}
+
+/// Simulated daily portfolio returns as `simulate_portfolio_returns` draws them, with the
+/// covariance stressed after volatility overrides are applied
+pub fn simulate_stressed_portfolio_returns<R: Rng>(
+    rng: &mut R,
+    returns: &[Vec<Decimal>],
+    positions: &[PortfolioPosition],
+    volatility_overrides: &HashMap<Address, Decimal>,
+    stress: CovarianceStress,
+    num_simulations: usize,
+) -> Result<Vec<Decimal>, RiskServiceError> {
+    if num_simulations == 0 {
+        return Err(calculation_error("VaR needs at least one simulation".to_string()));
+    }
+    let values: Vec<Decimal> = positions.iter().map(position_value).collect::<Result<_, _>>()?;
+    let total_value = checked_sum(&values, "portfolio value")?;
+    if total_value.is_zero() {
+        return Err(RiskServiceError::InsufficientData);
+    }
+    let weights: Array1<f64> = values.iter()
+        .map(|value| value.checked_div(total_value).map(|weight| weight.to_f64_lossy()).ok_or_else(|| overflow("VaR weights")))
+        .collect::<Result<_, _>>()?;
+
+    let (means, mut covariance) = return_moments(returns, positions.len())?;
+    for (index, position) in positions.iter().enumerate() {
+        if let Some(volatility) = volatility_overrides.get(&position.asset) {
+            if volatility.is_sign_negative() {
+                return Err(calculation_error(format!("Negative VaR volatility {} for {:?}", volatility, position.asset)));
+            }
+            set_volatility(&mut covariance, index, volatility.to_f64_lossy());
+        }
+    }
+    stress_covariance(&mut covariance, stress)?;
+    let factor = cholesky(&covariance)?;
+
+    let normal = Normal::new(0.0, 1.0)
+        .map_err(|e| calculation_error(format!("Invalid standard normal: {}", e)))?;
+    let mut shocks = Array1::<f64>::zeros(positions.len());
+    let mut simulated = Vec::with_capacity(num_simulations);
+    for _ in 0..num_simulations {
+        shocks.iter_mut().for_each(|shock| *shock = rng.sample(normal));
+        let asset_returns = &means + &factor.dot(&shocks);
+        let portfolio_return = weights.dot(&asset_returns);
+        simulated.push(Decimal::try_from(portfolio_return).map_err(|_| overflow("simulated portfolio return"))?);
+    }
+    simulated.sort();
+    Ok(simulated)
+}
+
+/// Per-asset mean returns and their sample covariance over the days on which each of the
+/// first `assets` columns has a return
+fn return_moments(returns: &[Vec<Decimal>], assets: usize) -> Result<(Array1<f64>, Array2<f64>), RiskServiceError> {
+    let complete: Vec<&[Decimal]> = returns.iter()
+        .filter(|day| day.len() >= assets)
+        .map(|day| &day[..assets])
+        .collect();
+    if assets == 0 || complete.len() < 2 {
+        return Err(RiskServiceError::InsufficientData);
+    }
+
+    let samples = Array2::from_shape_fn((complete.len(), assets), |(day, asset)| complete[day][asset].to_f64_lossy());
+    let means = samples.mean_axis(Axis(0)).ok_or(RiskServiceError::InsufficientData)?;
+    let centered = &samples - &means;
+    let covariance = centered.t().dot(&centered) / (complete.len() - 1) as f64;
+    if covariance.iter().chain(means.iter()).any(|value| !value.is_finite()) {
+        return Err(overflow("return covariance"));
+    }
+    Ok((means, covariance))
+}
+
+/// Rescale an asset's row and column of `covariance` to the given volatility, keeping its
+/// correlations; an asset that never moved gets the volatility uncorrelated
+fn set_volatility(covariance: &mut Array2<f64>, index: usize, volatility: f64) {
+    let current = covariance[[index, index]].sqrt();
+    if current > 0.0 {
+        let scale = volatility / current;
+        covariance.row_mut(index).mapv_inplace(|value| value * scale);
+        covariance.column_mut(index).mapv_inplace(|value| value * scale);
+    } else {
+        covariance[[index, index]] = volatility * volatility;
+    }
+}
+
+/// Scale volatilities and move correlations toward 1. The stressed correlation matrix is a
+/// blend of the observed one and all ones, so it stays positive semi-definite.
+fn stress_covariance(covariance: &mut Array2<f64>, stress: CovarianceStress) -> Result<(), RiskServiceError> {
+    let CovarianceStress { volatility_multiplier, correlation_adjustment } = stress;
+    if !(volatility_multiplier >= 0.0 && volatility_multiplier.is_finite()) || !(0.0..=1.0).contains(&correlation_adjustment) {
+        return Err(calculation_error(format!(
+            "Invalid stress: volatility x{}, correlation adjustment {}", volatility_multiplier, correlation_adjustment
+        )));
+    }
+
+    let volatilities: Vec<f64> = covariance.diag().iter().map(|variance| variance.max(0.0).sqrt()).collect();
+    for ((row, column), value) in covariance.indexed_iter_mut() {
+        let (row_volatility, column_volatility) = (volatilities[row], volatilities[column]);
+        let correlation = if row == column {
+            1.0
+        } else if row_volatility > 0.0 && column_volatility > 0.0 {
+            let observed = (*value / (row_volatility * column_volatility)).clamp(-1.0, 1.0);
+            observed + correlation_adjustment * (1.0 - observed)
+        } else {
+            // An asset that never moved has no correlation to stress
+            0.0
+        };
+        *value = correlation * row_volatility * column_volatility * volatility_multiplier * volatility_multiplier;
+    }
+    Ok(())
+}
+
+/// Lower-triangular `L` with `L * L^T == matrix` for a symmetric positive semi-definite
+/// matrix. Columns without remaining variance, such as a flat or perfectly collinear
+/// asset, are left zero rather than failing the decomposition.
+fn cholesky(matrix: &Array2<f64>) -> Result<Array2<f64>, RiskServiceError> {
+    let size = matrix.nrows();
+    if matrix.ncols() != size || matrix.iter().any(|value| !value.is_finite()) {
+        return Err(calculation_error("Covariance matrix is not square and finite".to_string()));
+    }
+    let tolerance = matrix.diag().iter().fold(0.0_f64, |max, value| max.max(value.abs())) * 1e-12;
+
+    let mut lower = Array2::<f64>::zeros((size, size));
+    for column in 0..size {
+        let pivot = matrix[[column, column]] - (0..column).map(|k| lower[[column, k]].powi(2)).sum::<f64>();
+        if pivot <= tolerance {
+            continue;
+        }
+        let diagonal = pivot.sqrt();
+        lower[[column, column]] = diagonal;
+        for row in column + 1..size {
+            let dot = (0..column).map(|k| lower[[row, k]] * lower[[column, k]]).sum::<f64>();
+            lower[[row, column]] = (matrix[[row, column]] - dot) / diagonal;
+        }
+    }
+    Ok(lower)
+}
+
+/// Daily portfolio returns, each asset's return weighted by its position's market value.
+///
+/// Rows may be shorter than `positions`: an asset past the end of a day's row had no
+/// return that day, as for an asset added mid-window, and the day is weighted over the
+/// assets that did.
+pub fn portfolio_returns(returns: &[Vec<Decimal>], positions: &[PortfolioPosition]) -> Result<Vec<Decimal>, RiskServiceError> {
+    let values: Vec<Decimal> = positions.iter().map(position_value).collect::<Result<_, _>>()?;
+    if checked_sum(&values, "portfolio value")?.is_zero() {
+        return Err(RiskServiceError::InsufficientData);
+    }
+
+    let mut portfolio = Vec::with_capacity(returns.len());
+    for day in returns {
+        let held = &values[..day.len().min(values.len())];
+        let day_value = checked_sum(held, "portfolio value")?;
+        if day_value.is_zero() {
+            continue;
+        }
+        let weighted: Vec<Decimal> = held.iter().zip(day)
+            .map(|(value, ret)| value.checked_mul(*ret).ok_or_else(|| overflow("portfolio return")))
+            .collect::<Result<_, _>>()?;
+        portfolio.push(checked_sum(&weighted, "portfolio return")?.checked_div(day_value).ok_or_else(|| overflow("portfolio return"))?);
+    }
+    if portfolio.is_empty() {
+        return Err(RiskServiceError::InsufficientData);
+    }
+    Ok(portfolio)
+}
+
+fn tail_probability(confidence: f64) -> Result<f64, RiskServiceError> {
+    if !(confidence > 0.0 && confidence < 1.0) {
+        return Err(calculation_error(format!("VaR confidence {} is not between 0 and 1", confidence)));
+    }
+    Ok(1.0 - confidence)
+}
+
+/// Loss at `confidence` read off the empirical distribution of portfolio returns; zero when
+/// even that tail is a gain
+pub fn historical_var(portfolio_returns: &[Decimal], confidence: f64) -> Result<Decimal, RiskServiceError> {
+    let tail = tail_probability(confidence)?;
+    let mut sorted = portfolio_returns.to_vec();
+    sorted.sort();
+    Ok((-tail_quantile(&sorted, tail)?).max(Decimal::ZERO))
+}
+
+/// Loss at `confidence` of a normal distribution with the portfolio returns' mean and
+/// standard deviation; zero when even that tail is a gain
+pub fn parametric_var(portfolio_returns: &[Decimal], confidence: f64) -> Result<Decimal, RiskServiceError> {
+    let tail = tail_probability(confidence)?;
+    let (mean, std_dev) = mean_and_std_dev(&[portfolio_returns.to_vec()], "parametric VaR")?;
+    let z = Normal::new(0.0, 1.0)
+        .map_err(|e| calculation_error(format!("Invalid standard normal: {}", e)))?
+        .inverse_cdf(tail);
+    let quantile = Decimal::try_from(z)
+        .ok()
+        .and_then(|z| std_dev.checked_mul(z))
+        .and_then(|spread| mean.checked_add(spread))
+        .ok_or_else(|| overflow("parametric VaR"))?;
+    Ok((-quantile).max(Decimal::ZERO))
+}
+
+/// Mean loss over the sorted portfolio returns at or beyond the VaR at `confidence`; zero
+/// when that tail is a gain.
+///
+/// With fewer than `MIN_TAIL_OBSERVATIONS` outcomes in the tail their mean is mostly noise,
+/// so the worst outcome is taken instead. Either way the result is never below the VaR.
+pub fn expected_shortfall(sorted: &[Decimal], confidence: f64) -> Result<Decimal, RiskServiceError> {
+    let tail = &sorted[..=tail_index(sorted.len(), tail_probability(confidence)?)?];
+    let average = if tail.len() < MIN_TAIL_OBSERVATIONS {
+        sorted[0]
+    } else {
+        checked_sum(tail, "expected shortfall")? / Decimal::from(tail.len())
+    };
+    Ok((-average).max(Decimal::ZERO))
+}
+
+/// Expected Shortfall at `confidence` of a normal distribution with the portfolio returns'
+/// mean and standard deviation; zero when that tail is a gain
+pub fn parametric_expected_shortfall(portfolio_returns: &[Decimal], confidence: f64) -> Result<Decimal, RiskServiceError> {
+    let tail = tail_probability(confidence)?;
+    let (mean, std_dev) = mean_and_std_dev(&[portfolio_returns.to_vec()], "parametric expected shortfall")?;
+    let normal = Normal::new(0.0, 1.0)
+        .map_err(|e| calculation_error(format!("Invalid standard normal: {}", e)))?;
+    // Mean of the standard normal below its `tail` quantile
+    let tail_mean = -normal.pdf(normal.inverse_cdf(tail)) / tail;
+    let average = Decimal::try_from(tail_mean)
+        .ok()
+        .and_then(|tail_mean| std_dev.checked_mul(tail_mean))
+        .and_then(|spread| mean.checked_add(spread))
+        .ok_or_else(|| overflow("parametric expected shortfall"))?;
+    Ok((-average).max(Decimal::ZERO))
+}
+
+/// Pearson correlation of the returns of assets `i` and `j` over the days both have one.
+///
+/// `None` when they overlap on fewer than `min_observations` days (at least two), when
+/// either asset's returns do not vary over those days, or when the result is not finite.
+pub fn pearson_correlation(returns: &[Vec<Decimal>], i: usize, j: usize, min_observations: usize) -> Option<Decimal> {
+    let pairs: Vec<(f64, f64)> = returns.iter()
+        .filter_map(|day| Some((day.get(i)?.to_f64_lossy(), day.get(j)?.to_f64_lossy())))
+        .filter(|(x, y)| x.is_finite() && y.is_finite())
+        .collect();
+    if pairs.len() < min_observations.max(2) {
+        return None;
+    }
+
+    let count = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / count;
+    let mean_y = pairs.iter().map(|(_, y)| y).sum::<f64>() / count;
+    let (covariance, variance_x, variance_y) = pairs.iter().fold((0.0, 0.0, 0.0), |(cov, var_x, var_y), (x, y)| {
+        let (dx, dy) = (x - mean_x, y - mean_y);
+        (cov + dx * dy, var_x + dx * dx, var_y + dy * dy)
+    });
+    if variance_x <= 0.0 || variance_y <= 0.0 {
+        return None;
+    }
+
+    let correlation = covariance / (variance_x.sqrt() * variance_y.sqrt());
+    if !correlation.is_finite() {
+        return None;
+    }
+    // Rounding absorbs float error so perfectly (anti-)correlated series give exactly ±1
+    Decimal::try_from(correlation.clamp(-1.0, 1.0)).ok().map(|c| c.round_dp(10))
+}
+
+/// Symmetric matrix of pairwise Pearson correlations with ones on the diagonal.
+///
+/// Pairs without a correlation (see [`pearson_correlation`]) are zero.
+pub fn correlation_matrix(returns: &[Vec<Decimal>], min_observations: usize) -> Result<Vec<Vec<Decimal>>, RiskServiceError> {
+    let num_assets = returns.iter().map(Vec::len).max().ok_or(RiskServiceError::InsufficientData)?;
+
+    let mut matrix = vec![vec![Decimal::ZERO; num_assets]; num_assets];
+    for i in 0..num_assets {
+        matrix[i][i] = Decimal::ONE;
+        for j in (i + 1)..num_assets {
+            let correlation = pearson_correlation(returns, i, j, min_observations).unwrap_or(Decimal::ZERO);
+            matrix[i][j] = correlation;
+            matrix[j][i] = correlation;
+        }
+    }
+    Ok(matrix)
+}
+
+/// Mean and population standard deviation of every return in the history
+fn mean_and_std_dev(returns: &[Vec<Decimal>], what: &str) -> Result<(Decimal, Decimal), RiskServiceError> {
+    let count = returns.iter().map(Vec::len).sum::<usize>();
+    if count == 0 {
+        return Err(RiskServiceError::InsufficientData);
+    }
+    let count = Decimal::from(count);
+
+    let mean = checked_sum(returns.iter().flatten(), what)? / count;
+    let deviations: Vec<Decimal> = returns.iter().flatten()
+        .map(|ret| ret.checked_sub(mean).ok_or_else(|| overflow(what)))
+        .collect::<Result<_, _>>()?;
+    let variance = checked_sum_of_squares(&deviations, what)? / count;
+
+    Ok((mean, variance.sqrt_approx().unwrap_or(Decimal::ONE)))
+}
+
+pub fn sharpe_ratio(returns: &[Vec<Decimal>]) -> Result<Decimal, RiskServiceError> {
+    let (mean, std_dev) = mean_and_std_dev(returns, "Sharpe ratio")?;
+    if std_dev.is_zero() {
+        return Ok(Decimal::ZERO);
+    }
+    mean.checked_sub(DAILY_RISK_FREE_RATE)
+        .and_then(|excess| excess.checked_div(std_dev))
+        .ok_or_else(|| overflow("Sharpe ratio"))
+}
+
+/// Like the Sharpe ratio, penalising only downside volatility
+pub fn sortino_ratio(returns: &[Vec<Decimal>]) -> Result<Decimal, RiskServiceError> {
+    let (mean, _) = mean_and_std_dev(returns, "Sortino ratio")?;
+    let downside: Vec<Decimal> = returns.iter().flatten().copied().filter(|ret| *ret < Decimal::ZERO).collect();
+    if downside.is_empty() {
+        // No downside risk
+        return Ok(Decimal::from(100));
+    }
+
+    let downside_variance = checked_sum_of_squares(&downside, "Sortino ratio")? / Decimal::from(downside.len());
+    let downside_deviation = downside_variance.sqrt_approx().unwrap_or(Decimal::ONE);
+    if downside_deviation.is_zero() {
+        return Ok(Decimal::ZERO);
+    }
+    mean.checked_sub(DAILY_RISK_FREE_RATE)
+        .and_then(|excess| excess.checked_div(downside_deviation))
+        .ok_or_else(|| overflow("Sortino ratio"))
+}
+
+/// Largest peak-to-trough fall of any asset, as a fraction of the peak
+pub fn max_drawdown(price_history: &[Vec<Decimal>], assets: &[Address]) -> Result<Decimal, RiskServiceError> {
+    let width = validate_history(price_history, assets)?;
+    let mut max_drawdown = Decimal::ZERO;
+
+    for asset in 0..width {
+        let mut peak = price_history[0][asset];
+        for (day, prices) in price_history.iter().enumerate() {
+            let current = prices[asset];
+            if current > peak {
+                peak = current;
+            }
+            if peak.is_zero() {
+                return Err(calculation_error(format!(
+                    "Zero price for {} up to day {}; drawdown is undefined", asset_label(assets, asset), day
+                )));
+            }
+
+            let drawdown = (peak - current) / peak;
+            if drawdown > max_drawdown {
+                max_drawdown = drawdown;
+            }
+        }
+    }
+    Ok(max_drawdown)
+}
+
+/// Annualized volatility of daily returns
+pub fn volatility(returns: &[Vec<Decimal>]) -> Result<Decimal, RiskServiceError> {
+    let count = returns.iter().map(Vec::len).sum::<usize>();
+    if count == 0 {
+        return Ok(Decimal::ZERO);
+    }
+
+    let daily_variance = checked_sum_of_squares(returns.iter().flatten(), "volatility")? / Decimal::from(count);
+    let daily_vol = daily_variance.sqrt_approx().unwrap_or(Decimal::ZERO);
+    daily_vol.checked_mul(Decimal::from(TRADING_DAYS).sqrt_approx().unwrap_or(Decimal::ONE))
+        .ok_or_else(|| overflow("volatility"))
+}
+
+/// Beta and daily alpha of the portfolio against a benchmark.
+///
+/// `portfolio_returns` are `(from, to, return)` between consecutive priced days. Each is paired
+/// with the benchmark's return over the same days, so days the benchmark was not priced on
+/// are dropped and a shorter benchmark history truncates to the overlap. Fewer than
+/// `min_observations` pairs are `InsufficientData`.
+pub fn beta_alpha(
+    portfolio_returns: &[(NaiveDate, NaiveDate, Decimal)],
+    benchmark_prices: &[(NaiveDate, Decimal)],
+    min_observations: usize,
+) -> Result<(Decimal, Decimal), RiskServiceError> {
+    let prices: HashMap<NaiveDate, Decimal> = benchmark_prices.iter().copied().collect();
+    let mut pairs = Vec::new();
+    for (from, to, portfolio) in portfolio_returns {
+        let (Some(start), Some(end)) = (prices.get(from), prices.get(to)) else { continue };
+        if *start <= Decimal::ZERO {
+            return Err(calculation_error(format!("Benchmark price {} on {}; returns are undefined", start, from)));
+        }
+        let benchmark = end.checked_div(*start).map(|ratio| ratio - Decimal::ONE).ok_or_else(|| overflow("benchmark return"))?;
+        pairs.push((*portfolio, benchmark));
+    }
+    if pairs.len() < min_observations.max(2) {
+        return Err(RiskServiceError::InsufficientData);
+    }
+
+    let count = Decimal::from(pairs.len());
+    let mean_portfolio = checked_sum(pairs.iter().map(|(p, _)| p), "beta")? / count;
+    let mean_benchmark = checked_sum(pairs.iter().map(|(_, b)| b), "beta")? / count;
+    let mut covariance = Decimal::ZERO;
+    let mut variance = Decimal::ZERO;
+    for (portfolio, benchmark) in &pairs {
+        let (dp, db) = (*portfolio - mean_portfolio, *benchmark - mean_benchmark);
+        covariance = dp.checked_mul(db).and_then(|product| covariance.checked_add(product)).ok_or_else(|| overflow("beta"))?;
+        variance = db.checked_mul(db).and_then(|square| variance.checked_add(square)).ok_or_else(|| overflow("beta"))?;
+    }
+    if variance.is_zero() {
+        return Err(calculation_error("Benchmark returns do not vary; beta is undefined".to_string()));
+    }
+
+    // Sample covariance over sample variance: the n - 1 denominators cancel
+    let beta = covariance.checked_div(variance).ok_or_else(|| overflow("beta"))?;
+    let alpha = beta.checked_mul(mean_benchmark)
+        .and_then(|explained| mean_portfolio.checked_sub(explained))
+        .ok_or_else(|| overflow("alpha"))?;
+    Ok((beta, alpha))
+}
+
+/// Market value of a position
+pub fn position_value(position: &PortfolioPosition) -> Result<Decimal, RiskServiceError> {
+    position.amount.checked_mul(position.current_price)
+        .ok_or_else(|| calculation_error(format!("Overflow valuing position in {:?}", position.asset)))
+}
+
+/// Share of the portfolio held in its largest position
+pub fn concentration_risk(positions: &[PortfolioPosition]) -> Result<Decimal, RiskServiceError> {
+    let values: Vec<Decimal> = positions.iter().map(position_value).collect::<Result<_, _>>()?;
+    let total_value = checked_sum(&values, "portfolio value")?;
+    if total_value.is_zero() {
+        return Ok(Decimal::ZERO);
+    }
+
+    let max_position = values.iter().copied().max().unwrap_or(Decimal::ZERO);
+    max_position.checked_div(total_value).ok_or_else(|| overflow("concentration risk"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RiskGrade;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn asset(n: u8) -> Address {
+        Address::from([n; 20])
+    }
+
+    fn position(amount: Decimal, current_price: Decimal) -> PortfolioPosition {
+        holding(asset(1), amount, current_price)
+    }
+
+    fn holding(asset: Address, amount: Decimal, current_price: Decimal) -> PortfolioPosition {
+        PortfolioPosition {
+            asset,
+            amount,
+            current_price,
+            entry_price: current_price,
+            unrealized_pnl: Decimal::ZERO,
+            entry_price_provenance: Default::default(),
+        }
+    }
+
+    /// Every statistic over one history, as `calculate_portfolio_risk` runs them
+    fn all_metrics(history: &[Vec<Decimal>], assets: &[Address]) -> Result<(), RiskServiceError> {
+        let returns = daily_returns(history, assets)?;
+        let positions: Vec<_> = assets.iter().map(|asset| holding(*asset, Decimal::ONE, Decimal::ONE)).collect();
+        let simulated = simulate_portfolio_returns(&mut StdRng::seed_from_u64(7), &returns, &positions, &HashMap::new(), 100)?;
+        let TailRisk { var_95, var_99, es_95, es_99 } = TailRisk::from_sorted(&simulated)?;
+        assert!(var_99 >= Decimal::ZERO && var_95 >= Decimal::ZERO);
+        assert!(es_95 >= var_95 && es_99 >= var_99);
+        correlation_matrix(&returns, DEFAULT_MIN_CORRELATION_OBSERVATIONS)?;
+        let sharpe = sharpe_ratio(&returns)?;
+        sortino_ratio(&returns)?;
+        let drawdown = max_drawdown(history, assets)?;
+        volatility(&returns)?;
+        RiskGrade::from_metrics(var_95, sharpe, drawdown);
+        Ok(())
+    }
+
+    #[test]
+    fn test_degenerate_histories_are_errors_not_panics() {
+        let (a, b) = (asset(1), asset(2));
+        let huge = Decimal::MAX;
+        let tiny = Decimal::new(1, 28);
+        let cases: Vec<(&str, Vec<Vec<Decimal>>)> = vec![
+            ("empty", vec![]),
+            ("no assets", vec![vec![], vec![]]),
+            ("single observation", vec![vec![dec!(100), dec!(50)]]),
+            ("ragged", vec![vec![dec!(100), dec!(50)], vec![dec!(101)]]),
+            ("zero price", vec![vec![dec!(100), dec!(50)], vec![dec!(100), Decimal::ZERO], vec![dec!(101), dec!(51)]]),
+            ("all zero", vec![vec![Decimal::ZERO; 2]; 3]),
+            ("negative price", vec![vec![dec!(100), dec!(-1)], vec![dec!(100), dec!(50)]]),
+            ("extreme jump", vec![vec![tiny, dec!(50)], vec![huge, dec!(50)], vec![tiny, dec!(50)]]),
+            ("extreme values", vec![vec![huge, huge], vec![huge, huge]]),
+            ("more columns than positions", vec![vec![dec!(1), dec!(2), dec!(3)], vec![dec!(2), dec!(2), Decimal::ZERO], vec![dec!(2); 3]]),
+        ];
+
+        for (name, history) in cases {
+            let result = std::panic::catch_unwind(|| all_metrics(&history, &[a, b]));
+            assert!(result.is_ok(), "{} panicked", name);
+        }
+
+        // Errors say what was wrong and where
+        let zero = vec![vec![dec!(100), dec!(50)], vec![dec!(100), Decimal::ZERO], vec![dec!(101), dec!(51)]];
+        let message = all_metrics(&zero, &[a, b]).unwrap_err().to_string();
+        assert!(message.contains(&format!("{:?}", b)) && message.contains("day 1"), "{}", message);
+        assert!(all_metrics(&zero, &[a]).unwrap_err().to_string().contains("asset #1"));
+        assert!(matches!(all_metrics(&[], &[a]), Err(RiskServiceError::InsufficientData)));
+        assert!(matches!(all_metrics(&[vec![dec!(1)]], &[a]), Err(RiskServiceError::InsufficientData)));
+        assert!(matches!(all_metrics(&[vec![dec!(1)], vec![dec!(-1)]], &[a]), Err(RiskServiceError::CalculationError(_))));
+
+        // A flat series is fine, just riskless
+        let flat = vec![vec![dec!(100)]; 30];
+        all_metrics(&flat, &[a]).unwrap();
+        assert_eq!(max_drawdown(&flat, &[a]).unwrap(), Decimal::ZERO);
+        assert_eq!(sharpe_ratio(&daily_returns(&flat, &[a]).unwrap()).unwrap(), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_quantiles_and_aggregates_stay_in_bounds() {
+        assert!(matches!(tail_quantile(&[], 0.05), Err(RiskServiceError::InsufficientData)));
+        assert_eq!(tail_quantile(&[dec!(-1)], 0.05).unwrap(), dec!(-1));
+        assert_eq!(tail_quantile(&[dec!(-2), dec!(-1)], 1.0).unwrap(), dec!(-1));
+        assert!(tail_quantile(&[dec!(1)], f64::NAN).is_err());
+        let returns = vec![vec![dec!(0.01)], vec![dec!(-0.02)], vec![dec!(0.005)]];
+        let positions = [position(dec!(1), dec!(10))];
+        let var = |returns: &[Vec<Decimal>], overrides: &HashMap<Address, Decimal>, simulations| {
+            simulate_portfolio_returns(&mut StdRng::seed_from_u64(1), returns, &positions, overrides, simulations)
+        };
+        assert!(var(&returns, &HashMap::new(), 0).is_err());
+        assert!(var(&returns, &HashMap::new(), 1).is_ok());
+        assert!(var(&returns, &HashMap::from([(asset(1), dec!(-1))]), 10).is_err());
+        assert!(matches!(var(&returns[..1], &HashMap::new(), 10), Err(RiskServiceError::InsufficientData)));
+
+        assert!(matches!(sharpe_ratio(&[]), Err(RiskServiceError::InsufficientData)));
+        assert!(matches!(sortino_ratio(&[vec![]]), Err(RiskServiceError::InsufficientData)));
+        assert!(matches!(correlation_matrix(&[], 2), Err(RiskServiceError::InsufficientData)));
+        assert_eq!(volatility(&[]).unwrap(), Decimal::ZERO);
+        assert!(volatility(&[vec![Decimal::MAX]]).is_err());
+
+        assert_eq!(concentration_risk(&[]).unwrap(), Decimal::ZERO);
+        assert_eq!(concentration_risk(&[position(Decimal::ZERO, dec!(10))]).unwrap(), Decimal::ZERO);
+        assert_eq!(concentration_risk(&[position(dec!(1), dec!(30)), position(dec!(1), dec!(10))]).unwrap(), dec!(0.75));
+        assert!(concentration_risk(&[position(Decimal::MAX, dec!(2))]).is_err());
+        assert!(concentration_risk(&[position(Decimal::MAX, dec!(1)), position(Decimal::MAX, dec!(1))]).is_err());
+    }
+
+    /// Daily returns of two assets: `b` moves with `a` scaled by `beta` plus its own noise
+    fn paired_returns(rng: &mut StdRng, days: usize, beta: f64) -> Vec<Vec<Decimal>> {
+        let normal = Normal::new(0.0005, 0.01).unwrap();
+        (0..days)
+            .map(|_| {
+                let a: f64 = rng.sample(normal);
+                let b = beta * a + 0.5 * (rng.sample(normal) - 0.0005);
+                vec![Decimal::try_from(a).unwrap(), Decimal::try_from(b).unwrap()]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_monte_carlo_var_matches_portfolio_distribution() {
+        let returns = paired_returns(&mut StdRng::seed_from_u64(3), 250, 0.8);
+        let positions = [holding(asset(1), dec!(300), dec!(2)), holding(asset(2), dec!(100), dec!(4))];
+
+        // The same seed gives the same answer
+        let seeded = |seed| {
+            let simulated = simulate_portfolio_returns(&mut StdRng::seed_from_u64(seed), &returns, &positions, &HashMap::new(), 20_000).unwrap();
+            TailRisk::from_sorted(&simulated).unwrap()
+        };
+        assert_eq!(seeded(11), seeded(11));
+
+        // ... which is the analytic normal VaR of the weighted portfolio, up to sampling error
+        let (means, covariance) = return_moments(&returns, 2).unwrap();
+        let weights = Array1::from(vec![0.6, 0.4]);
+        let mean = weights.dot(&means);
+        let std_dev = weights.dot(&covariance.dot(&weights)).sqrt();
+        let risk = seeded(11);
+        for (var, z) in [(risk.var_95, 1.6449), (risk.var_99, 2.3263)] {
+            let expected = z * std_dev - mean;
+            assert!((var.to_f64_lossy() - expected).abs() < 0.05 * expected, "{} vs {}", var, expected);
+        }
+        // Normal tail means: pdf(z) / tail standard deviations below the mean
+        for (es, z_es) in [(risk.es_95, 2.0627), (risk.es_99, 2.6652)] {
+            let expected = z_es * std_dev - mean;
+            assert!((es.to_f64_lossy() - expected).abs() < 0.05 * expected, "{} vs {}", es, expected);
+        }
+
+        let factor = cholesky(&covariance).unwrap();
+        assert!((factor.dot(&factor.t()) - &covariance).iter().all(|error| error.abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_monte_carlo_var_responds_to_portfolio() {
+        let returns = paired_returns(&mut StdRng::seed_from_u64(5), 250, 1.0);
+        let var_95 = |positions: &[PortfolioPosition], overrides: &HashMap<Address, Decimal>| {
+            let simulated = simulate_portfolio_returns(&mut StdRng::seed_from_u64(9), &returns, positions, overrides, 5_000).unwrap();
+            TailRisk::from_sorted(&simulated).unwrap().var_95
+        };
+        let (a, b) = (asset(1), asset(2));
+        let mostly_a = [holding(a, dec!(90), dec!(1)), holding(b, dec!(10), dec!(1))];
+        let mostly_b = [holding(a, dec!(10), dec!(1)), holding(b, dec!(90), dec!(1))];
+        // `b` has `a`'s moves plus its own, so tilting towards it is riskier
+        assert!(var_95(&mostly_b, &HashMap::new()) > var_95(&mostly_a, &HashMap::new()));
+
+        // A stressed volatility on `a` raises the VaR of a portfolio holding it
+        let stressed = HashMap::from([(a, dec!(0.1))]);
+        assert!(var_95(&mostly_a, &stressed) > var_95(&mostly_a, &HashMap::new()));
+
+        // Offsetting positions cancel out
+        let hedged: Vec<Vec<Decimal>> = returns.iter().map(|day| vec![day[0], -day[0]]).collect();
+        let hedge = [holding(a, dec!(50), dec!(1)), holding(b, dec!(50), dec!(1))];
+        let simulated = simulate_portfolio_returns(&mut StdRng::seed_from_u64(9), &hedged, &hedge, &HashMap::new(), 1_000).unwrap();
+        let hedged_var = TailRisk::from_sorted(&simulated).unwrap().var_95;
+        assert!(hedged_var < dec!(0.0001), "{}", hedged_var);
+    }
+
+    #[test]
+    fn test_covariance_stress_scales_volatility_and_raises_correlation() {
+        let mut covariance = ndarray::arr2(&[[0.04, -0.006], [-0.006, 0.01]]);
+        stress_covariance(&mut covariance, CovarianceStress { volatility_multiplier: 2.0, correlation_adjustment: 0.5 }).unwrap();
+        // Volatilities 0.2 and 0.1 double; the -0.3 correlation moves halfway to 1
+        assert!((covariance[[0, 0]] - 0.16).abs() < 1e-12);
+        assert!((covariance[[1, 1]] - 0.04).abs() < 1e-12);
+        assert!((covariance[[0, 1]] - 0.35 * 0.4 * 0.2).abs() < 1e-12);
+        assert_eq!(covariance[[0, 1]], covariance[[1, 0]]);
+
+        // Fully correlated, two equal positions lose as much as either asset alone
+        let returns: Vec<Vec<Decimal>> = (0..120)
+            .map(|day| vec![Decimal::from((day * 7) % 11) / dec!(100) - dec!(0.05), Decimal::from((day * 5) % 13) / dec!(100) - dec!(0.06)])
+            .collect();
+        let positions = [holding(asset(1), Decimal::ONE, Decimal::ONE), holding(asset(2), Decimal::ONE, Decimal::ONE)];
+        let var_95 = |stress| {
+            let simulated = simulate_stressed_portfolio_returns(&mut StdRng::seed_from_u64(3), &returns, &positions, &HashMap::new(), stress, 5_000).unwrap();
+            TailRisk::from_sorted(&simulated).unwrap().var_95
+        };
+        let baseline = var_95(CovarianceStress::NONE);
+        let correlated = var_95(CovarianceStress { volatility_multiplier: 1.0, correlation_adjustment: 1.0 });
+        let volatile = var_95(CovarianceStress { volatility_multiplier: 3.0, correlation_adjustment: 0.0 });
+        assert!(correlated > baseline, "{} <= {}", correlated, baseline);
+        assert!(volatile > baseline * dec!(2.5), "{} vs {}", volatile, baseline);
+
+        let mut covariance = ndarray::arr2(&[[0.04]]);
+        assert!(stress_covariance(&mut covariance, CovarianceStress { volatility_multiplier: 1.0, correlation_adjustment: 1.5 }).is_err());
+    }
+
+    #[test]
+    fn test_expected_shortfall_is_never_below_var() {
+        let positions = [holding(asset(1), dec!(300), dec!(2)), holding(asset(2), dec!(100), dec!(4))];
+        for seed in 0..20 {
+            let returns = paired_returns(&mut StdRng::seed_from_u64(seed), 120, 0.8);
+            let simulated = simulate_portfolio_returns(&mut StdRng::seed_from_u64(seed), &returns, &positions, &HashMap::new(), 2_000).unwrap();
+            let mut historical = portfolio_returns(&returns, &positions).unwrap();
+            historical.sort();
+            for risk in [TailRisk::from_sorted(&simulated).unwrap(), TailRisk::from_sorted(&historical).unwrap()] {
+                assert!(risk.es_95 >= risk.var_95 && risk.es_99 >= risk.var_99, "seed {}: {:?}", seed, risk);
+            }
+            for confidence in [0.95, 0.99] {
+                assert!(parametric_expected_shortfall(&historical, confidence).unwrap() >= parametric_var(&historical, confidence).unwrap());
+            }
+        }
+
+        // Five outcomes in the 95% tail of 80 are too few to average, so the worst one is used
+        let mut sorted: Vec<Decimal> = (0..80).map(|n| Decimal::new(n - 10, 3)).collect();
+        sorted[0] = dec!(-0.5);
+        let risk = TailRisk::from_sorted(&sorted).unwrap();
+        assert_eq!((risk.var_95, risk.es_95), (dec!(0.006), dec!(0.5)));
+
+        // A tail of gains has no shortfall
+        assert_eq!(expected_shortfall(&[dec!(0.01); 40], 0.95).unwrap(), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_beta_alpha_over_the_benchmark_overlap() {
+        let first = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let day = |n: i64| first + chrono::Duration::days(n);
+
+        // The benchmark alternates +1% / -1%; the portfolio moves 1.5x as much plus 0.1% a day
+        let mut benchmark = vec![(day(0), dec!(100))];
+        for n in 1..60 {
+            let change = if n % 2 == 0 { dec!(1.01) } else { dec!(0.99) };
+            benchmark.push((day(n), benchmark[n as usize - 1].1 * change));
+        }
+        let returns: Vec<(NaiveDate, NaiveDate, Decimal)> = benchmark.windows(2)
+            .map(|pair| (pair[0].0, pair[1].0, dec!(1.5) * (pair[1].1 / pair[0].1 - Decimal::ONE) + dec!(0.001)))
+            .collect();
+
+        let (beta, alpha) = beta_alpha(&returns, &benchmark, 30).unwrap();
+        assert_eq!(beta.round_dp(8), dec!(1.5));
+        assert_eq!(alpha.round_dp(8), dec!(0.001));
+
+        // A benchmark that starts later only shortens the overlap ...
+        let (beta, _) = beta_alpha(&returns, &benchmark[25..], 30).unwrap();
+        assert_eq!(beta.round_dp(8), dec!(1.5));
+        // ... until fewer than the minimum days remain
+        assert!(matches!(beta_alpha(&returns, &benchmark[35..], 30), Err(RiskServiceError::InsufficientData)));
+
+        let flat: Vec<(NaiveDate, Decimal)> = benchmark.iter().map(|(date, _)| (*date, dec!(100))).collect();
+        assert!(matches!(beta_alpha(&returns, &flat, 30), Err(RiskServiceError::CalculationError(_))));
+    }
+
+    #[test]
+    fn test_risk_grade_covers_every_score() {
+        assert_eq!(RiskGrade::from_metrics(dec!(0.01), dec!(3), dec!(0.01)), RiskGrade::A);
+        assert_eq!(RiskGrade::from_metrics(Decimal::MAX, Decimal::MIN, Decimal::MAX), RiskGrade::F);
+        assert_eq!(RiskGrade::from_metrics(Decimal::MIN, Decimal::MAX, Decimal::MIN), RiskGrade::A);
+        assert_eq!(RiskGrade::from_metrics(dec!(0.07), dec!(0.7), dec!(0.15)), RiskGrade::C);
+    }
+
+    #[test]
+    fn test_pearson_correlation_of_known_series() {
+        let columns = |a: &[i64], b: &[i64]| -> Vec<Vec<Decimal>> {
+            a.iter().zip(b).map(|(x, y)| vec![Decimal::new(*x, 2), Decimal::new(*y, 2)]).collect()
+        };
+        let rising = [1, 3, -2, 5, 4, -1];
+
+        let same = columns(&rising, &rising.map(|x| 2 * x + 1));
+        assert_eq!(pearson_correlation(&same, 0, 1, 2), Some(Decimal::ONE));
+        let opposite = columns(&rising, &rising.map(|x| -3 * x));
+        assert_eq!(pearson_correlation(&opposite, 0, 1, 2), Some(Decimal::NEGATIVE_ONE));
+        let unrelated = columns(&[1, -1, 1, -1], &[1, 1, -1, -1]);
+        assert_eq!(pearson_correlation(&unrelated, 0, 1, 2), Some(Decimal::ZERO));
+
+        // Flat assets have no correlation, and neither do pairs with too little overlap
+        let flat = columns(&rising, &[2; 6]);
+        assert_eq!(pearson_correlation(&flat, 0, 1, 2), None);
+        assert_eq!(pearson_correlation(&same, 0, 1, 7), None);
+        assert_eq!(correlation_matrix(&flat, 2).unwrap(), vec![vec![Decimal::ONE, Decimal::ZERO], vec![Decimal::ZERO, Decimal::ONE]]);
+
+        // Days on which an asset has no return only shorten the overlap
+        let mut ragged = same.clone();
+        ragged.push(vec![dec!(0.5)]);
+        assert_eq!(pearson_correlation(&ragged, 0, 1, 6), Some(Decimal::ONE));
+        assert_eq!(pearson_correlation(&ragged, 0, 1, 7), None);
+    }
+
+    #[test]
+    fn test_correlation_matrix_is_symmetric_with_unit_diagonal() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let assets = rng.gen_range(1..6);
+            let days = rng.gen_range(0..40);
+            let returns: Vec<Vec<Decimal>> = (0..days)
+                .map(|_| (0..assets).map(|_| Decimal::new(rng.gen_range(-500..500), 4)).collect())
+                .collect();
+            let min_observations = rng.gen_range(0..30);
+
+            let Ok(matrix) = correlation_matrix(&returns, min_observations) else {
+                assert!(returns.is_empty());
+                continue;
+            };
+            for i in 0..assets {
+                assert_eq!(matrix[i][i], Decimal::ONE);
+                for j in 0..assets {
+                    assert_eq!(matrix[i][j], matrix[j][i]);
+                    assert!(matrix[i][j] >= Decimal::NEGATIVE_ONE && matrix[i][j] <= Decimal::ONE);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_historical_var_matches_hand_computed_quantiles() {
+        let mut a = position(dec!(3), dec!(100));
+        a.asset = asset(1);
+        let mut b = position(dec!(1), dec!(100));
+        b.asset = asset(2);
+
+        // Weights 0.75 / 0.25: day 0 is -0.03 + 0.01 = -0.02, day 1 is -0.015 - 0.015 = -0.03,
+        // the other 18 days gain 0.01
+        let mut returns = vec![vec![dec!(-0.04), dec!(0.04)], vec![dec!(-0.02), dec!(-0.06)]];
+        returns.extend(std::iter::repeat(vec![dec!(0.01), dec!(0.01)]).take(18));
+        let portfolio = portfolio_returns(&returns, &[a.clone(), b.clone()]).unwrap();
+        assert_eq!(portfolio[..3], [dec!(-0.02), dec!(-0.03), dec!(0.01)]);
+
+        // Of 20 sorted outcomes the 5% tail is the 2nd worst and the 1% tail the worst
+        assert_eq!(historical_var(&portfolio, 0.95).unwrap(), dec!(0.02));
+        assert_eq!(historical_var(&portfolio, 0.99).unwrap(), dec!(0.03));
+        assert!(historical_var(&portfolio, 1.0).is_err());
+
+        // An asset added mid-window only weighs in from its first return
+        let mut c = position(dec!(1), dec!(400));
+        c.asset = asset(3);
+        let late = vec![vec![dec!(-0.10), dec!(0.10)], vec![dec!(0.02), dec!(0.02), dec!(-0.10)]];
+        assert_eq!(portfolio_returns(&late, &[a.clone(), b.clone(), c]).unwrap(), vec![dec!(-0.05), dec!(-0.04)]);
+
+        // A single day and a flat history
+        let single = portfolio_returns(&[vec![dec!(-0.05), dec!(0.03)]], &[a.clone(), b.clone()]).unwrap();
+        assert_eq!(historical_var(&single, 0.99).unwrap(), dec!(0.03));
+        let flat = portfolio_returns(&vec![vec![Decimal::ZERO; 2]; 30], &[a.clone(), b]).unwrap();
+        assert_eq!(historical_var(&flat, 0.95).unwrap(), Decimal::ZERO);
+        assert_eq!(parametric_var(&flat, 0.95).unwrap(), Decimal::ZERO);
+        assert!(matches!(portfolio_returns(&[], &[a]), Err(RiskServiceError::InsufficientData)));
+    }
+}
@@ -0,0 +1,217 @@
+// Pre-trade risk limit enforcement for order placement
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::ethereum_client::Address;
+use crate::what_if::{HypotheticalTrade, RiskSnapshot, WhatIfReport};
+use crate::RiskServiceError;
+
+/// Time an evaluation may take before it degrades to Warn
+pub const DEFAULT_PRE_TRADE_BUDGET: Duration = Duration::from_millis(250);
+
+/// What the trading path should do with an order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PreTradeDecision {
+    Allow,
+    Warn,
+    Block,
+}
+
+/// A limit the portfolio would be over after the trade
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BreachedLimit {
+    pub limit: String,
+    pub before: Decimal,
+    pub after: Decimal,
+    pub threshold: Decimal,
+    /// Whether this breach alone blocks the trade
+    pub blocking: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreTradeEvaluation {
+    pub evaluation_id: Uuid,
+    pub portfolio_address: Address,
+    pub trade: HypotheticalTrade,
+    pub decision: PreTradeDecision,
+    pub breached_limits: Vec<BreachedLimit>,
+    /// The budget ran out before the projection finished; the decision degraded to Warn
+    pub timed_out: bool,
+    /// False when the portfolio is not monitored; such trades are allowed unevaluated
+    pub watched: bool,
+    /// Projected risk with the trade applied
+    pub after: Option<RiskSnapshot>,
+    pub elapsed_ms: u64,
+    pub evaluated_at: DateTime<Utc>,
+}
+
+impl PreTradeEvaluation {
+    fn new(portfolio_address: Address, trade: HypotheticalTrade, decision: PreTradeDecision) -> Self {
+        Self {
+            evaluation_id: Uuid::new_v4(),
+            portfolio_address,
+            trade,
+            decision,
+            breached_limits: Vec::new(),
+            timed_out: false,
+            watched: true,
+            after: None,
+            elapsed_ms: 0,
+            evaluated_at: Utc::now(),
+        }
+    }
+
+    /// Allow for a portfolio that is not monitored
+    pub fn unwatched(portfolio_address: Address, trade: HypotheticalTrade) -> Self {
+        Self { watched: false, ..Self::new(portfolio_address, trade, PreTradeDecision::Allow) }
+    }
+
+    /// Decision for the projected report against the portfolio's limits
+    pub fn from_report(report: &WhatIfReport, trade: HypotheticalTrade, limits: &HashMap<String, Decimal>) -> Self {
+        let (decision, breached_limits) = decide(limits, report);
+        Self {
+            breached_limits,
+            after: Some(report.after.clone()),
+            ..Self::new(report.portfolio_address, trade, decision)
+        }
+    }
+}
+
+/// Check the projected metrics against the limits.
+///
+/// A VaR limit breach the trade causes or worsens blocks; other breaches, and trades
+/// that reduce an existing breach, only warn so de-risking orders are never refused.
+pub fn decide(limits: &HashMap<String, Decimal>, report: &WhatIfReport) -> (PreTradeDecision, Vec<BreachedLimit>) {
+    let checks = [
+        ("max_var_95", report.before.var_95, report.after.var_95, true),
+        ("max_var_99", report.before.var_99, report.after.var_99, true),
+        ("max_concentration", report.before.concentration_risk, report.after.concentration_risk, false),
+    ];
+
+    let mut decision = PreTradeDecision::Allow;
+    let mut breached = Vec::new();
+    for (limit, before, after, blocks) in checks {
+        let Some(threshold) = limits.get(limit) else { continue };
+        if after <= *threshold {
+            continue;
+        }
+        let blocking = blocks && after > before;
+        decision = decision.max(if blocking { PreTradeDecision::Block } else { PreTradeDecision::Warn });
+        breached.push(BreachedLimit { limit: limit.to_string(), before, after, threshold: *threshold, blocking });
+    }
+    (decision, breached)
+}
+
+/// Run an evaluation within `budget`, degrading to a timed-out Warn when it overruns
+pub async fn within_budget<F>(
+    budget: Duration,
+    portfolio_address: Address,
+    trade: HypotheticalTrade,
+    evaluation: F,
+) -> Result<PreTradeEvaluation, RiskServiceError>
+where
+    F: Future<Output = Result<PreTradeEvaluation, RiskServiceError>>,
+{
+    let started = Instant::now();
+    let mut evaluation = match tokio::time::timeout(budget, evaluation).await {
+        Ok(evaluation) => evaluation?,
+        Err(_) => PreTradeEvaluation {
+            timed_out: true,
+            ..PreTradeEvaluation::new(portfolio_address, trade, PreTradeDecision::Warn)
+        },
+    };
+    evaluation.elapsed_ms = started.elapsed().as_millis() as u64;
+    Ok(evaluation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::factors::FactorModel;
+    use crate::what_if::{self, WhatIfHolding};
+    use crate::{RiskGrade, RiskMetrics, VaRMethod};
+    use rust_decimal_macros::dec;
+
+    fn metrics() -> RiskMetrics {
+        RiskMetrics {
+            portfolio_address: Address::zero(),
+            var_95: dec!(0.05),
+            var_99: dec!(0.08),
+            var_method: VaRMethod::MonteCarlo,
+            expected_shortfall: dec!(0.06),
+            es_99: dec!(0.08),
+            sharpe_ratio: dec!(1.2),
+            sortino_ratio: dec!(1.5),
+            max_drawdown: dec!(0.08),
+            beta: Some(Decimal::ONE),
+            alpha: Some(Decimal::ZERO),
+            volatility: dec!(0.3),
+            correlation_matrix: Vec::new(),
+            liquidity_scores: HashMap::new(),
+            liquidity_details: HashMap::new(),
+            concentration_risk: dec!(0.5),
+            leverage_ratio: Decimal::ONE,
+            risk_grade: RiskGrade::B,
+            factor_risk_contributions: Vec::new(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    fn report(trade: &HypotheticalTrade) -> WhatIfReport {
+        // Half in a volatile asset, half in a quiet one
+        let holdings = vec![
+            WhatIfHolding { asset: Address::repeat_byte(1), amount: dec!(50), price: dec!(100), variance: Some(dec!(0.0016)), loadings: HashMap::new() },
+            WhatIfHolding { asset: Address::repeat_byte(2), amount: dec!(50), price: dec!(100), variance: Some(dec!(0.0001)), loadings: HashMap::new() },
+        ];
+        what_if::evaluate(Address::zero(), &FactorModel::default(), &holdings, &metrics(), std::slice::from_ref(trade)).unwrap()
+    }
+
+    fn limits() -> HashMap<String, Decimal> {
+        HashMap::from([("max_var_95".to_string(), dec!(0.06))])
+    }
+
+    #[test]
+    fn test_trade_breaching_var_limit_is_blocked() {
+        let trade = HypotheticalTrade { asset: Address::repeat_byte(1), quantity: dec!(100), price: dec!(100) };
+        let evaluation = PreTradeEvaluation::from_report(&report(&trade), trade, &limits());
+
+        assert_eq!(evaluation.decision, PreTradeDecision::Block);
+        assert_eq!(evaluation.breached_limits.len(), 1);
+        let breach = &evaluation.breached_limits[0];
+        assert_eq!(breach.limit, "max_var_95");
+        assert!(breach.blocking && breach.after > breach.threshold);
+
+        // Selling down the volatile asset stays within the limit
+        let trade = HypotheticalTrade { asset: Address::repeat_byte(1), quantity: dec!(-25), price: dec!(100) };
+        let evaluation = PreTradeEvaluation::from_report(&report(&trade), trade, &limits());
+        assert_eq!(evaluation.decision, PreTradeDecision::Allow);
+    }
+
+    #[test]
+    fn test_reducing_an_existing_breach_only_warns() {
+        let trade = HypotheticalTrade { asset: Address::repeat_byte(1), quantity: dec!(-10), price: dec!(100) };
+        let limits = HashMap::from([("max_var_95".to_string(), dec!(0.03))]);
+        let (decision, breached) = decide(&limits, &report(&trade));
+
+        assert_eq!(decision, PreTradeDecision::Warn);
+        assert!(!breached[0].blocking);
+    }
+
+    #[tokio::test]
+    async fn test_overrunning_the_budget_degrades_to_warn() {
+        let trade = HypotheticalTrade { asset: Address::repeat_byte(1), quantity: dec!(1), price: dec!(100) };
+        let slow = async {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            Ok(PreTradeEvaluation::new(Address::zero(), trade.clone(), PreTradeDecision::Block))
+        };
+        let evaluation = within_budget(Duration::from_millis(10), Address::zero(), trade.clone(), slow).await.unwrap();
+
+        assert_eq!(evaluation.decision, PreTradeDecision::Warn);
+        assert!(evaluation.timed_out);
+    }
+}
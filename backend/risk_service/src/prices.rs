@@ -0,0 +1,251 @@
+// Asset price ingestion from the oracle aggregator
+use crate::anomaly::{self, AnomalyConfig, QuarantineStatus, QuarantinedPrice};
+use crate::depeg::{DepegAlerter, DepegMonitor};
+use crate::ethereum_client::{Address, EthereumClient};
+use crate::RiskServiceError;
+use async_trait::async_trait;
+use chrono::Utc;
+use ethers::abi::{self, ParamType, Token};
+use ethers::types::{Bytes, I256};
+use price_oracle::{AggregatedPrice, FeedReader, OracleAggregator, RoundData};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// `latestRoundData()`
+const LATEST_ROUND_DATA: [u8; 4] = [0xfe, 0xaf, 0x96, 0x8c];
+
+/// `decimals()`
+const DECIMALS: [u8; 4] = [0x31, 0x3c, 0xe5, 0x67];
+
+#[async_trait]
+impl FeedReader for EthereumClient {
+    async fn latest_round(&self, feed: &str) -> Result<RoundData, String> {
+        let feed = feed.parse::<Address>().map_err(|e| format!("Invalid feed address {}: {}", feed, e))?;
+
+        let round = self.call(feed, Bytes::from(LATEST_ROUND_DATA.to_vec())).await.map_err(|e| e.to_string())?;
+        let round = abi::decode(
+            &[ParamType::Uint(80), ParamType::Int(256), ParamType::Uint(256), ParamType::Uint(256), ParamType::Uint(80)],
+            &round,
+        ).map_err(|e| format!("Invalid latestRoundData response: {}", e))?;
+
+        let decimals = self.call(feed, Bytes::from(DECIMALS.to_vec())).await.map_err(|e| e.to_string())?;
+        let decimals = abi::decode(&[ParamType::Uint(8)], &decimals)
+            .map_err(|e| format!("Invalid decimals response: {}", e))?;
+
+        match (&round[1], &round[3], &decimals[0]) {
+            (Token::Int(answer), Token::Uint(updated_at), Token::Uint(decimals)) => Ok(RoundData {
+                answer: i128::try_from(I256::from_raw(*answer)).map_err(|_| "Answer out of range".to_string())?,
+                decimals: decimals.low_u32() as u8,
+                updated_at: updated_at.low_u64(),
+            }),
+            _ => Err("Unexpected latestRoundData response".to_string()),
+        }
+    }
+}
+
+/// What became of one asset's aggregated price
+#[derive(Debug, Clone)]
+pub enum Ingested {
+    Recorded(AggregatedPrice),
+    /// Logged to `asset_price_disputes`; the history keeps the last agreed price
+    Disputed(AggregatedPrice),
+    /// Held for operator review; consumers skip it until approved
+    Quarantined(QuarantinedPrice),
+}
+
+/// Writes aggregated prices for held assets into `asset_price_history`.
+///
+/// Disputed prices are logged to `asset_price_disputes` instead, so valuations keep using
+/// the last agreed price until the sources converge or an operator sets an override.
+/// Prices that fail the anomaly gate are quarantined until a second source confirms them
+/// and an operator approves them.
+pub struct PriceIngestor {
+    aggregator: Arc<OracleAggregator>,
+    db: Arc<PgPool>,
+    depeg: Option<(Arc<DepegMonitor>, Arc<dyn DepegAlerter>)>,
+    anomaly: AnomalyConfig,
+}
+
+impl PriceIngestor {
+    pub fn new(aggregator: Arc<OracleAggregator>, db: Arc<PgPool>) -> Self {
+        Self { aggregator, db, depeg: None, anomaly: AnomalyConfig::default() }
+    }
+
+    pub fn with_anomaly_config(mut self, config: AnomalyConfig) -> Self {
+        self.anomaly = config;
+        self
+    }
+
+    /// Check recorded stablecoin prices against their pegs, reporting depegs and recoveries to `alerter`
+    pub fn with_depeg_monitor(mut self, monitor: Arc<DepegMonitor>, alerter: Arc<dyn DepegAlerter>) -> Self {
+        self.depeg = Some((monitor, alerter));
+        self
+    }
+
+    pub fn aggregator(&self) -> Arc<OracleAggregator> {
+        self.aggregator.clone()
+    }
+
+    /// Aggregate and record the price of every asset held in a tracked portfolio
+    pub async fn ingest_all(&self) -> Result<usize, RiskServiceError> {
+        let assets: Vec<(String,)> = sqlx::query_as(
+            "SELECT DISTINCT asset_address FROM position_cost_basis WHERE amount > 0"
+        )
+            .fetch_all(&*self.db)
+            .await?;
+
+        let mut recorded = 0;
+        for (asset,) in assets {
+            let Ok(address) = asset.parse::<Address>() else {
+                warn!("Skipping invalid asset address {}", asset);
+                continue;
+            };
+            match self.ingest(address).await {
+                Ok(Ingested::Recorded(_)) => recorded += 1,
+                Ok(_) => {}
+                Err(e) => warn!("No price recorded for {}: {}", asset, e),
+            }
+        }
+        Ok(recorded)
+    }
+
+    /// Aggregate one asset's price and record it
+    pub async fn ingest(&self, asset: Address) -> Result<Ingested, RiskServiceError> {
+        let key = format!("{:?}", asset);
+        let price = self.aggregator.price(&key).await?;
+
+        if price.disputed {
+            sqlx::query(r#"
+                INSERT INTO asset_price_disputes (asset_address, reference_price, quotes, failures, observed_at)
+                VALUES ($1, $2::numeric, $3, $4, $5)
+            "#)
+                .bind(format!("{:?}", asset))
+                .bind(price.price.to_string())
+                .bind(serde_json::to_value(price.sources.iter().chain(&price.discarded).collect::<Vec<_>>())
+                    .map_err(|e| RiskServiceError::CalculationError(e.to_string()))?)
+                .bind(serde_json::to_value(&price.failed)
+                    .map_err(|e| RiskServiceError::CalculationError(e.to_string()))?)
+                .bind(price.aggregated_at)
+                .execute(&*self.db)
+                .await?;
+            return Ok(Ingested::Disputed(price));
+        }
+
+        // A fresh aggregation may confirm an earlier quarantined price
+        self.confirm_pending(&key, &price).await?;
+
+        let date = price.aggregated_at.date_naive();
+        let history = anomaly::recent_history(&self.db, &key, date, self.anomaly.lookback_days).await?;
+        if let Some(found) = anomaly::check(&self.anomaly, &history, date, price.price) {
+            warn!("Quarantined price {} of {} for {}: {}", price.price, key, date, found);
+            return Ok(Ingested::Quarantined(anomaly::quarantine(&self.db, &key, date, &price, found).await?));
+        }
+
+        let source = if price.overridden { "manual" } else { "oracle" };
+        sqlx::query(r#"
+            INSERT INTO asset_price_history (asset_address, price_date, price, source, confidence, source_names, recorded_at)
+            VALUES ($1, $2, $3::numeric, $4, $5::numeric, $6, NOW())
+            ON CONFLICT (asset_address, price_date) DO UPDATE SET
+                price = EXCLUDED.price,
+                source = EXCLUDED.source,
+                confidence = EXCLUDED.confidence,
+                source_names = EXCLUDED.source_names,
+                quarantined = FALSE,
+                recorded_at = NOW()
+        "#)
+            .bind(&key)
+            .bind(date)
+            .bind(price.price.to_string())
+            .bind(source)
+            .bind(price.confidence.to_string())
+            .bind(price.sources.iter().map(|q| q.source.clone()).collect::<Vec<_>>())
+            .execute(&*self.db)
+            .await?;
+
+        if let Some((monitor, alerter)) = &self.depeg {
+            if let Some(transition) = monitor.observe(asset, price.price, Utc::now()) {
+                // The price stays recorded; a failure to alert is logged, not retried
+                if let Err(e) = alerter.depeg_changed(&transition).await {
+                    warn!("Failed to apply {:?}: {}", transition, e);
+                }
+            }
+        }
+
+        Ok(Ingested::Recorded(price))
+    }
+
+    async fn confirm_pending(&self, asset: &str, fresh: &AggregatedPrice) -> Result<(), RiskServiceError> {
+        for quarantined in anomaly::pending(&self.db, Some(asset)).await? {
+            if quarantined.is_confirmed() {
+                continue;
+            }
+            let sources = anomaly::confirming_sources(&self.anomaly, quarantined.price, fresh);
+            if sources.len() >= 2 {
+                info!("Quarantined price {} of {} confirmed by {:?}", quarantined.quarantine_id, asset, sources);
+                anomaly::record_confirmation(&self.db, quarantined.quarantine_id, &sources).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Quarantined prices awaiting review
+    pub async fn quarantined(&self) -> Result<Vec<QuarantinedPrice>, RiskServiceError> {
+        anomaly::pending(&self.db, None).await
+    }
+
+    /// Accept a quarantined price into the history. Unless an earlier aggregation already
+    /// confirmed it, the oracle aggregator is asked again and two sources must agree.
+    pub async fn approve_quarantined(&self, quarantine_id: Uuid, reviewer: &str) -> Result<QuarantinedPrice, RiskServiceError> {
+        let mut quarantined = self.pending_review(quarantine_id).await?;
+        if !quarantined.is_confirmed() {
+            let fresh = self.aggregator.price(&quarantined.asset).await?;
+            let sources = anomaly::confirming_sources(&self.anomaly, quarantined.price, &fresh);
+            if sources.len() < 2 {
+                return Err(RiskServiceError::InvalidInput(format!(
+                    "Price {} of {} is not confirmed by a second source (current price {})",
+                    quarantined.price, quarantined.asset, fresh.price
+                )));
+            }
+            anomaly::record_confirmation(&self.db, quarantine_id, &sources).await?;
+            quarantined.confirmed_by = sources;
+            quarantined.confirmed_at = Some(Utc::now());
+        }
+
+        anomaly::resolve(&self.db, &quarantined, QuarantineStatus::Approved, reviewer).await?;
+        info!("Quarantined price {} of {} approved by {}", quarantine_id, quarantined.asset, reviewer);
+        Ok(QuarantinedPrice { status: QuarantineStatus::Approved, reviewed_by: Some(reviewer.to_string()), reviewed_at: Some(Utc::now()), ..quarantined })
+    }
+
+    /// Reject a quarantined price for good
+    pub async fn discard_quarantined(&self, quarantine_id: Uuid, reviewer: &str) -> Result<QuarantinedPrice, RiskServiceError> {
+        let quarantined = self.pending_review(quarantine_id).await?;
+        anomaly::resolve(&self.db, &quarantined, QuarantineStatus::Discarded, reviewer).await?;
+        info!("Quarantined price {} of {} discarded by {}", quarantine_id, quarantined.asset, reviewer);
+        Ok(QuarantinedPrice { status: QuarantineStatus::Discarded, reviewed_by: Some(reviewer.to_string()), reviewed_at: Some(Utc::now()), ..quarantined })
+    }
+
+    async fn pending_review(&self, quarantine_id: Uuid) -> Result<QuarantinedPrice, RiskServiceError> {
+        let quarantined = anomaly::get(&self.db, quarantine_id).await?
+            .ok_or_else(|| RiskServiceError::InvalidInput(format!("No quarantined price {}", quarantine_id)))?;
+        if quarantined.status != QuarantineStatus::Pending {
+            return Err(RiskServiceError::InvalidInput(format!("{} has already been reviewed", quarantine_id)));
+        }
+        Ok(quarantined)
+    }
+}
+
+/// Periodically refresh prices of all held assets
+pub fn spawn_price_ingestion(ingestor: Arc<PriceIngestor>, every: std::time::Duration) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(every);
+        loop {
+            ticker.tick().await;
+            match ingestor.ingest_all().await {
+                Ok(recorded) => info!("Recorded oracle prices for {} assets", recorded),
+                Err(e) => tracing::error!("Price ingestion failed: {}", e),
+            }
+        }
+    })
+}
@@ -0,0 +1,440 @@
+// Publication of portfolio risk grades to the on-chain RiskEngine
+//
+// Each calculation yields a compact attestation (grade, VaR in basis points, calculation
+// time). It is only sent when the grade changed or the VaR moved past a threshold since the
+// last publication, and at most a few times a day per portfolio. Publishing runs off the
+// calculation path: a failed transaction is logged and never affects stored metrics.
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use ethers::abi::{self, ParamType, Token};
+use ethers::types::{Bytes, H256, U256};
+use ethers::utils::id;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use tokio::sync::RwLock;
+use tracing::warn;
+use quantera_types::Pii;
+use crate::ethereum_client::{Address, EthereumClient};
+use crate::{RiskGrade, RiskMetrics, RiskServiceError};
+
+const PUBLISH_SIGNATURE: &str = "publishRiskAttestation(address,uint8,uint32,uint64)";
+const READ_SIGNATURE: &str = "riskAttestations(address)";
+
+/// Transactions and calls against the RiskEngine contract
+#[async_trait]
+pub trait RiskEngineGateway: Send + Sync {
+    /// Send a transaction and wait for it to be mined
+    async fn submit(&self, to: Address, data: Bytes) -> Result<H256, String>;
+
+    async fn read(&self, to: Address, data: Bytes) -> Result<Bytes, String>;
+}
+
+#[async_trait]
+impl RiskEngineGateway for EthereumClient {
+    async fn submit(&self, to: Address, data: Bytes) -> Result<H256, String> {
+        self.send_transaction(to, data).await.map(|receipt| receipt.transaction_hash)
+    }
+
+    async fn read(&self, to: Address, data: Bytes) -> Result<Bytes, String> {
+        self.call(to, data).await.map_err(|e| e.to_string())
+    }
+}
+
+/// Grade code stored on chain: 0 = A ... 4 = F
+pub fn grade_code(grade: RiskGrade) -> u8 {
+    match grade {
+        RiskGrade::A => 0,
+        RiskGrade::B => 1,
+        RiskGrade::C => 2,
+        RiskGrade::D => 3,
+        RiskGrade::F => 4,
+    }
+}
+
+pub fn grade_from_code(code: u8) -> Option<RiskGrade> {
+    match code {
+        0 => Some(RiskGrade::A),
+        1 => Some(RiskGrade::B),
+        2 => Some(RiskGrade::C),
+        3 => Some(RiskGrade::D),
+        4 => Some(RiskGrade::F),
+        _ => None,
+    }
+}
+
+/// VaR as a fraction of portfolio value, in whole basis points
+pub fn var_to_bps(var: Decimal) -> u32 {
+    var.abs().checked_mul(Decimal::from(10_000))
+        .and_then(|bps| bps.round().to_u32())
+        .unwrap_or(u32::MAX)
+}
+
+/// What gets published for a portfolio
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RiskAttestation {
+    pub portfolio: Address,
+    pub grade: RiskGrade,
+    pub var_95_bps: u32,
+    pub calculated_at: DateTime<Utc>,
+}
+
+impl RiskAttestation {
+    pub fn from_metrics(metrics: &RiskMetrics) -> Self {
+        Self {
+            portfolio: metrics.portfolio_address,
+            grade: metrics.risk_grade,
+            var_95_bps: var_to_bps(metrics.var_95),
+            calculated_at: metrics.timestamp,
+        }
+    }
+
+    /// Calldata for `publishRiskAttestation`
+    pub fn encode(&self) -> Bytes {
+        let mut data = id(PUBLISH_SIGNATURE).to_vec();
+        data.extend(abi::encode(&[
+            Token::Address(self.portfolio),
+            Token::Uint(U256::from(grade_code(self.grade))),
+            Token::Uint(U256::from(self.var_95_bps)),
+            Token::Uint(U256::from(self.calculated_at.timestamp().max(0) as u64)),
+        ]));
+        Bytes::from(data)
+    }
+}
+
+/// Calldata for the `riskAttestations` getter
+pub fn encode_read(portfolio: Address) -> Bytes {
+    let mut data = id(READ_SIGNATURE).to_vec();
+    data.extend(abi::encode(&[Token::Address(portfolio)]));
+    Bytes::from(data)
+}
+
+/// Attestation as stored on chain
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OnChainAttestation {
+    pub grade: RiskGrade,
+    pub var_95_bps: u32,
+    pub calculated_at: DateTime<Utc>,
+    pub published_at: DateTime<Utc>,
+}
+
+/// Decode the `riskAttestations` getter; `None` when nothing was ever published
+pub fn decode_read(data: &[u8]) -> Result<Option<OnChainAttestation>, String> {
+    let tokens = abi::decode(
+        &[ParamType::Uint(8), ParamType::Uint(32), ParamType::Uint(64), ParamType::Uint(64)],
+        data,
+    ).map_err(|e| format!("Invalid riskAttestations response: {}", e))?;
+
+    let [Token::Uint(grade), Token::Uint(var), Token::Uint(calculated_at), Token::Uint(published_at)] = tokens.as_slice() else {
+        return Err("Unexpected riskAttestations response".to_string());
+    };
+    if published_at.is_zero() {
+        return Ok(None);
+    }
+
+    let timestamp = |value: &U256| DateTime::<Utc>::from_timestamp(value.low_u64() as i64, 0)
+        .ok_or_else(|| format!("Invalid timestamp {}", value));
+    Ok(Some(OnChainAttestation {
+        grade: grade_from_code(grade.low_u32() as u8).ok_or_else(|| format!("Invalid risk grade {}", grade))?,
+        var_95_bps: var.low_u32(),
+        calculated_at: timestamp(calculated_at)?,
+        published_at: timestamp(published_at)?,
+    }))
+}
+
+#[derive(Debug, Clone)]
+pub struct PublicationPolicy {
+    /// VaR change since the last publication that warrants a new one
+    pub var_threshold_bps: u32,
+    /// Publications per portfolio per UTC day
+    pub daily_cap: u32,
+}
+
+impl Default for PublicationPolicy {
+    fn default() -> Self {
+        Self { var_threshold_bps: 25, daily_cap: 4 }
+    }
+}
+
+/// Last publication of a portfolio's attestation
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PublishedAttestation {
+    pub attestation: RiskAttestation,
+    /// None when the state was restored from the chain rather than published by this process
+    pub tx_hash: Option<H256>,
+    pub published_at: DateTime<Utc>,
+    /// Whether reading the contract back returned what was sent
+    pub verified: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum PublishOutcome {
+    Published(PublishedAttestation),
+    /// Grade and VaR are within the threshold of the last publication
+    Unchanged,
+    DailyCapReached,
+    /// Another publication for the portfolio is being sent
+    InFlight,
+}
+
+#[derive(Debug, Clone, Default)]
+struct PortfolioPublications {
+    last: Option<PublishedAttestation>,
+    day: Option<NaiveDate>,
+    published_today: u32,
+}
+
+impl PortfolioPublications {
+    fn published_on(&self, day: NaiveDate) -> u32 {
+        if self.day == Some(day) { self.published_today } else { 0 }
+    }
+}
+
+/// The change-detection gate: publish the first attestation, and later ones whose grade
+/// changed or whose VaR moved by at least the threshold, within the daily cap
+fn gate(policy: &PublicationPolicy, state: &PortfolioPublications, attestation: &RiskAttestation) -> Option<PublishOutcome> {
+    if let Some(last) = &state.last {
+        let moved = last.attestation.var_95_bps.abs_diff(attestation.var_95_bps);
+        if last.attestation.grade == attestation.grade && moved < policy.var_threshold_bps {
+            return Some(PublishOutcome::Unchanged);
+        }
+    }
+    if state.published_on(attestation.calculated_at.date_naive()) >= policy.daily_cap {
+        return Some(PublishOutcome::DailyCapReached);
+    }
+    None
+}
+
+/// Publishes attestations to one RiskEngine and remembers the last one per portfolio
+pub struct RiskPublisher {
+    gateway: Arc<dyn RiskEngineGateway>,
+    risk_engine: Address,
+    policy: PublicationPolicy,
+    portfolios: RwLock<HashMap<Address, PortfolioPublications>>,
+    in_flight: RwLock<HashSet<Address>>,
+}
+
+impl RiskPublisher {
+    pub fn new(gateway: Arc<dyn RiskEngineGateway>, risk_engine: Address, policy: PublicationPolicy) -> Self {
+        Self {
+            gateway,
+            risk_engine,
+            policy,
+            portfolios: RwLock::new(HashMap::new()),
+            in_flight: RwLock::new(HashSet::new()),
+        }
+    }
+
+    /// Current attestation of a portfolio according to the contract
+    pub async fn read_on_chain(&self, portfolio: Address) -> Result<Option<OnChainAttestation>, RiskServiceError> {
+        let data = self.gateway.read(self.risk_engine, encode_read(portfolio)).await
+            .map_err(RiskServiceError::EthereumError)?;
+        decode_read(&data).map_err(RiskServiceError::EthereumError)
+    }
+
+    /// Last attestation published, or found on chain, for the portfolio
+    pub async fn last_published(&self, portfolio: Address) -> Option<PublishedAttestation> {
+        self.portfolios.read().await.get(&portfolio).and_then(|state| state.last.clone())
+    }
+
+    /// Publish the attestation if the change-detection gate and daily cap allow it
+    pub async fn publish(&self, attestation: RiskAttestation) -> Result<PublishOutcome, RiskServiceError> {
+        let portfolio = attestation.portfolio;
+        if !self.in_flight.write().await.insert(portfolio) {
+            return Ok(PublishOutcome::InFlight);
+        }
+        let outcome = self.publish_exclusive(attestation).await;
+        self.in_flight.write().await.remove(&portfolio);
+        outcome
+    }
+
+    async fn publish_exclusive(&self, attestation: RiskAttestation) -> Result<PublishOutcome, RiskServiceError> {
+        let portfolio = attestation.portfolio;
+
+        // After a restart the gate compares against what the contract already holds
+        if !self.portfolios.read().await.contains_key(&portfolio) {
+            let last = self.read_on_chain(portfolio).await?.map(|on_chain| PublishedAttestation {
+                attestation: RiskAttestation {
+                    portfolio,
+                    grade: on_chain.grade,
+                    var_95_bps: on_chain.var_95_bps,
+                    calculated_at: on_chain.calculated_at,
+                },
+                tx_hash: None,
+                published_at: on_chain.published_at,
+                verified: true,
+            });
+            self.portfolios.write().await.entry(portfolio).or_insert(PortfolioPublications { last, ..Default::default() });
+        }
+
+        {
+            let portfolios = self.portfolios.read().await;
+            if let Some(skip) = portfolios.get(&portfolio).and_then(|state| gate(&self.policy, state, &attestation)) {
+                return Ok(skip);
+            }
+        }
+
+        let tx_hash = self.gateway.submit(self.risk_engine, attestation.encode()).await
+            .map_err(|e| RiskServiceError::EthereumError(format!("Publishing risk grade of {:?} failed: {}", portfolio, e)))?;
+
+        // Read back what the contract stored; a mismatch is reported, not retried
+        let verified = match self.read_on_chain(portfolio).await {
+            Ok(Some(on_chain)) => {
+                let matches = on_chain.grade == attestation.grade
+                    && on_chain.var_95_bps == attestation.var_95_bps
+                    && on_chain.calculated_at.timestamp() == attestation.calculated_at.timestamp();
+                if !matches {
+                    warn!(
+                        portfolio = %Pii::debug(portfolio),
+                        on_chain_grade = ?on_chain.grade,
+                        published_grade = ?attestation.grade,
+                        "RiskEngine holds a different attestation after publishing"
+                    );
+                }
+                matches
+            }
+            Ok(None) => {
+                warn!(portfolio = %Pii::debug(portfolio), tx_hash = ?tx_hash, "RiskEngine has no attestation after publishing");
+                false
+            }
+            Err(e) => {
+                warn!(portfolio = %Pii::debug(portfolio), error = %e, "Could not verify risk grade publication");
+                false
+            }
+        };
+
+        let day = attestation.calculated_at.date_naive();
+        let published = PublishedAttestation {
+            attestation,
+            tx_hash: Some(tx_hash),
+            published_at: Utc::now(),
+            verified,
+        };
+        let mut portfolios = self.portfolios.write().await;
+        let state = portfolios.entry(portfolio).or_default();
+        state.published_today = state.published_on(day) + 1;
+        state.day = Some(day);
+        state.last = Some(published.clone());
+
+        Ok(PublishOutcome::Published(published))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use std::sync::Mutex;
+
+    /// Stores whatever is published, optionally corrupting it, and fails on request
+    #[derive(Default)]
+    struct MockGateway {
+        submitted: Mutex<Vec<Bytes>>,
+        stored: Mutex<HashMap<Address, Vec<Token>>>,
+        fail_submit: Mutex<bool>,
+        corrupt_var: Mutex<bool>,
+    }
+
+    #[async_trait]
+    impl RiskEngineGateway for MockGateway {
+        async fn submit(&self, _to: Address, data: Bytes) -> Result<H256, String> {
+            if *self.fail_submit.lock().unwrap() {
+                return Err("nonce too low".to_string());
+            }
+            let params = abi::decode(
+                &[ParamType::Address, ParamType::Uint(8), ParamType::Uint(32), ParamType::Uint(64)],
+                &data[4..],
+            ).map_err(|e| e.to_string())?;
+            let Token::Address(portfolio) = params[0] else { return Err("bad portfolio".to_string()) };
+            let mut stored = vec![params[1].clone(), params[2].clone(), params[3].clone(), Token::Uint(U256::from(1_700_000_100u64))];
+            if *self.corrupt_var.lock().unwrap() {
+                stored[1] = Token::Uint(U256::from(1u64));
+            }
+            self.stored.lock().unwrap().insert(portfolio, stored);
+            self.submitted.lock().unwrap().push(data);
+            Ok(H256::repeat_byte(self.submitted.lock().unwrap().len() as u8))
+        }
+
+        async fn read(&self, _to: Address, data: Bytes) -> Result<Bytes, String> {
+            let Token::Address(portfolio) = abi::decode(&[ParamType::Address], &data[4..]).unwrap()[0] else { unreachable!() };
+            let tokens = self.stored.lock().unwrap().get(&portfolio).cloned()
+                .unwrap_or_else(|| vec![Token::Uint(U256::zero()); 4]);
+            Ok(Bytes::from(abi::encode(&tokens)))
+        }
+    }
+
+    fn attestation(grade: RiskGrade, var_95_bps: u32, calculated_at: DateTime<Utc>) -> RiskAttestation {
+        RiskAttestation { portfolio: Address::repeat_byte(0xaa), grade, var_95_bps, calculated_at }
+    }
+
+    fn publisher(gateway: Arc<MockGateway>, daily_cap: u32) -> RiskPublisher {
+        RiskPublisher::new(gateway, Address::repeat_byte(0x01), PublicationPolicy { var_threshold_bps: 25, daily_cap })
+    }
+
+    #[test]
+    fn test_attestation_encoding() {
+        let at = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        let data = attestation(RiskGrade::D, var_to_bps(Decimal::new(1234, 4)), at).encode();
+
+        assert_eq!(&data[..4], &id("publishRiskAttestation(address,uint8,uint32,uint64)"));
+        assert_eq!(data.len(), 4 + 4 * 32);
+        assert_eq!(&data[4 + 12..4 + 32], Address::repeat_byte(0xaa).as_bytes());
+        assert_eq!(U256::from_big_endian(&data[36..68]), U256::from(3u8));
+        assert_eq!(U256::from_big_endian(&data[68..100]), U256::from(1234u32));
+        assert_eq!(U256::from_big_endian(&data[100..132]), U256::from(at.timestamp() as u64));
+
+        assert_eq!(&encode_read(Address::repeat_byte(0xaa))[..4], &id("riskAttestations(address)"));
+        assert_eq!(decode_read(&abi::encode(&[Token::Uint(U256::zero()); 4])).unwrap(), None);
+        assert!(decode_read(&abi::encode(&[Token::Uint(U256::from(9u8)), Token::Uint(U256::one()), Token::Uint(U256::one()), Token::Uint(U256::one())])).is_err());
+        assert_eq!(var_to_bps(Decimal::new(-5, 2)), 500);
+        assert_eq!(var_to_bps(Decimal::MAX), u32::MAX);
+    }
+
+    #[tokio::test]
+    async fn test_publishes_only_on_material_change_within_daily_cap() {
+        let gateway = Arc::new(MockGateway::default());
+        let publisher = publisher(gateway.clone(), 3);
+        let day = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let at = |minutes: i64| day + Duration::minutes(minutes);
+
+        let first = publisher.publish(attestation(RiskGrade::B, 400, at(0))).await.unwrap();
+        assert!(matches!(&first, PublishOutcome::Published(p) if p.verified && p.tx_hash.is_some()));
+
+        // Same grade, VaR within 25 bps of the last publication
+        assert_eq!(publisher.publish(attestation(RiskGrade::B, 424, at(1))).await.unwrap(), PublishOutcome::Unchanged);
+        assert_eq!(publisher.publish(attestation(RiskGrade::B, 376, at(2))).await.unwrap(), PublishOutcome::Unchanged);
+        // Grade changed; then VaR moved by the threshold
+        assert!(matches!(publisher.publish(attestation(RiskGrade::C, 410, at(3))).await.unwrap(), PublishOutcome::Published(_)));
+        assert!(matches!(publisher.publish(attestation(RiskGrade::C, 435, at(4))).await.unwrap(), PublishOutcome::Published(_)));
+        // Cap of three reached for the day, until the next one
+        assert_eq!(publisher.publish(attestation(RiskGrade::F, 2000, at(5))).await.unwrap(), PublishOutcome::DailyCapReached);
+        assert!(matches!(publisher.publish(attestation(RiskGrade::F, 2000, at(24 * 60))).await.unwrap(), PublishOutcome::Published(_)));
+        assert_eq!(gateway.submitted.lock().unwrap().len(), 4);
+
+        // A restarted publisher picks up the contract's state instead of republishing
+        let restarted = RiskPublisher::new(gateway.clone(), Address::repeat_byte(0x01), PublicationPolicy::default());
+        assert_eq!(restarted.publish(attestation(RiskGrade::F, 2010, at(24 * 60 + 1))).await.unwrap(), PublishOutcome::Unchanged);
+        assert_eq!(restarted.last_published(Address::repeat_byte(0xaa)).await.unwrap().attestation.var_95_bps, 2000);
+    }
+
+    #[tokio::test]
+    async fn test_failed_or_mismatched_publications_are_reported() {
+        let gateway = Arc::new(MockGateway::default());
+        let publisher = publisher(gateway.clone(), 3);
+        let at = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+
+        *gateway.fail_submit.lock().unwrap() = true;
+        assert!(matches!(publisher.publish(attestation(RiskGrade::A, 100, at)).await, Err(RiskServiceError::EthereumError(_))));
+        assert_eq!(publisher.last_published(Address::repeat_byte(0xaa)).await, None);
+
+        *gateway.fail_submit.lock().unwrap() = false;
+        *gateway.corrupt_var.lock().unwrap() = true;
+        let PublishOutcome::Published(published) = publisher.publish(attestation(RiskGrade::A, 100, at)).await.unwrap() else {
+            panic!("expected a publication");
+        };
+        assert!(!published.verified);
+    }
+}
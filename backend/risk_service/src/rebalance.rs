@@ -0,0 +1,492 @@
+// Advisory rebalancing toward a risk target
+use std::collections::HashMap;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use crate::ethereum_client::Address;
+use crate::factors::{self, AssetRisk, FactorModel};
+use crate::{DecimalExt, RiskGrade, RiskMetrics, RiskServiceError};
+
+/// Share of portfolio value sold per step when trimming risk contributors
+pub const TRIM_STEP: Decimal = dec!(0.05);
+
+/// Single-position weight caps tried when no concentration target is given
+pub const DEFAULT_WEIGHT_CAPS: [Decimal; 5] = [dec!(0.50), dec!(0.40), dec!(0.33), dec!(0.25), dec!(0.20)];
+
+/// Smallest trade suggested when the caller does not set one
+pub const DEFAULT_MIN_TRADE_VALUE: Decimal = dec!(1000);
+
+/// Market impact of selling an entire position with a liquidity score of zero
+pub const MAX_IMPACT_BPS: Decimal = dec!(200);
+
+/// What the portfolio should be brought back to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum RebalanceTarget {
+    /// Grade no worse than this one
+    RiskGrade(RiskGrade),
+    /// 95% VaR at or below this fraction of portfolio value
+    MaxVar(Decimal),
+    /// Largest position at or below this share of portfolio value
+    MaxConcentration(Decimal),
+}
+
+impl RebalanceTarget {
+    pub fn validate(&self) -> Result<(), RiskServiceError> {
+        match self {
+            RebalanceTarget::MaxVar(var) if *var <= Decimal::ZERO => {
+                Err(RiskServiceError::InvalidInput("Target VaR must be positive".into()))
+            }
+            RebalanceTarget::MaxConcentration(share) if *share <= Decimal::ZERO || *share > Decimal::ONE => {
+                Err(RiskServiceError::InvalidInput("Target concentration must be in (0, 1]".into()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn is_met(&self, risk: &ProjectedRisk) -> bool {
+        match self {
+            RebalanceTarget::RiskGrade(grade) => risk.risk_grade <= *grade,
+            RebalanceTarget::MaxVar(var) => risk.var_95 <= *var,
+            RebalanceTarget::MaxConcentration(share) => risk.concentration_risk <= *share,
+        }
+    }
+
+    /// How far a projection is from the target; smaller is closer
+    fn shortfall(&self, risk: &ProjectedRisk) -> (u8, Decimal) {
+        match self {
+            RebalanceTarget::RiskGrade(grade) => {
+                let gap = (risk.risk_grade as u8).saturating_sub(*grade as u8);
+                (gap, risk.var_95)
+            }
+            RebalanceTarget::MaxVar(var) => (0, (risk.var_95 - var).max(Decimal::ZERO)),
+            RebalanceTarget::MaxConcentration(share) => (0, (risk.concentration_risk - share).max(Decimal::ZERO)),
+        }
+    }
+}
+
+/// The parts of a risk assessment that trades are projected from
+#[derive(Debug, Clone)]
+pub struct RiskBaseline {
+    pub var_95: Decimal,
+    /// Held constant: suggestions only change weights, not return history
+    pub sharpe_ratio: Decimal,
+    pub max_drawdown: Decimal,
+}
+
+impl From<&RiskMetrics> for RiskBaseline {
+    fn from(metrics: &RiskMetrics) -> Self {
+        Self {
+            var_95: metrics.var_95,
+            sharpe_ratio: metrics.sharpe_ratio,
+            max_drawdown: metrics.max_drawdown,
+        }
+    }
+}
+
+/// One position as input to the engine
+#[derive(Debug, Clone)]
+pub struct RebalanceHolding {
+    pub asset: Address,
+    pub amount: Decimal,
+    pub price: Decimal,
+    /// 0-100, higher is more liquid
+    pub liquidity_score: u8,
+    /// Daily return variance
+    pub variance: Decimal,
+    pub loadings: HashMap<String, Decimal>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TradeSide {
+    Sell,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuggestedTrade {
+    pub asset: Address,
+    pub side: TradeSide,
+    pub amount: Decimal,
+    pub value: Decimal,
+    pub estimated_impact_bps: Decimal,
+    pub estimated_impact_cost: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComponentVar {
+    pub asset: Address,
+    pub weight: Decimal,
+    /// Share of portfolio VaR in the same units as var_95; components sum to var_95
+    pub var_contribution: Decimal,
+}
+
+/// Risk of the portfolio as it is, or as it would be after a set of trades
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectedRisk {
+    pub var_95: Decimal,
+    pub concentration_risk: Decimal,
+    pub risk_grade: RiskGrade,
+    pub component_var: Vec<ComponentVar>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RebalanceStrategy {
+    /// Repeatedly sell from the position contributing most to VaR
+    TrimTopContributors,
+    /// Sell every position down to a maximum weight
+    CapWeights { cap: Decimal },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RebalanceSuggestion {
+    pub rank: usize,
+    pub strategy: RebalanceStrategy,
+    pub trades: Vec<SuggestedTrade>,
+    pub projected: ProjectedRisk,
+    pub meets_target: bool,
+    pub turnover: Decimal,
+    pub estimated_impact_cost: Decimal,
+}
+
+/// Ranked suggestions; when the target is unreachable the first one is the best achievable outcome
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RebalancePlan {
+    pub portfolio_address: Address,
+    pub target: RebalanceTarget,
+    pub min_trade_value: Decimal,
+    pub current: ProjectedRisk,
+    pub target_reachable: bool,
+    pub suggestions: Vec<RebalanceSuggestion>,
+}
+
+/// Projects trades onto a fixed covariance, anchored to the assessed VaR.
+///
+/// Sale proceeds are held as cash: total value is unchanged, cash carries no risk and
+/// does not count as a position for concentration.
+struct Simulator<'a> {
+    holdings: &'a [RebalanceHolding],
+    covariance: Vec<Vec<Decimal>>,
+    baseline: &'a RiskBaseline,
+    total_value: Decimal,
+    base_variance: Decimal,
+}
+
+impl<'a> Simulator<'a> {
+    fn new(model: &FactorModel, holdings: &'a [RebalanceHolding], baseline: &'a RiskBaseline) -> Self {
+        let values: Vec<Decimal> = holdings.iter().map(|h| h.amount * h.price).collect();
+        let total_value: Decimal = values.iter().sum();
+        let assets: Vec<AssetRisk> = holdings.iter()
+            .map(|h| AssetRisk { weight: Decimal::ZERO, variance: h.variance, loadings: h.loadings.clone() })
+            .collect();
+
+        let mut simulator = Self {
+            holdings,
+            covariance: factors::asset_covariance(model, &assets),
+            baseline,
+            total_value,
+            base_variance: Decimal::ZERO,
+        };
+        simulator.base_variance = simulator.variance(&simulator.weights(&values));
+        simulator
+    }
+
+    fn current_values(&self) -> Vec<Decimal> {
+        self.holdings.iter().map(|h| h.amount * h.price).collect()
+    }
+
+    fn weights(&self, values: &[Decimal]) -> Vec<Decimal> {
+        if self.total_value.is_zero() {
+            return vec![Decimal::ZERO; values.len()];
+        }
+        values.iter().map(|v| v / self.total_value).collect()
+    }
+
+    fn covariance_times(&self, weights: &[Decimal]) -> Vec<Decimal> {
+        self.covariance.iter()
+            .map(|row| row.iter().zip(weights).map(|(c, w)| c * w).sum())
+            .collect()
+    }
+
+    fn variance(&self, weights: &[Decimal]) -> Decimal {
+        weights.iter().zip(self.covariance_times(weights)).map(|(w, sw)| w * sw).sum()
+    }
+
+    fn project(&self, values: &[Decimal]) -> ProjectedRisk {
+        let weights = self.weights(values);
+        let sigma_w = self.covariance_times(&weights);
+        let variance: Decimal = weights.iter().zip(&sigma_w).map(|(w, sw)| w * sw).sum();
+
+        // VaR scales with portfolio volatility relative to the assessed portfolio
+        let var_95 = if self.base_variance > Decimal::ZERO {
+            self.baseline.var_95 * (variance / self.base_variance).sqrt_approx().unwrap_or(Decimal::ZERO)
+        } else {
+            Decimal::ZERO
+        };
+
+        // Euler allocation: w_i (Σw)_i / w'Σw of the portfolio VaR
+        let component_var = self.holdings.iter().zip(&weights).zip(&sigma_w)
+            .map(|((holding, weight), sw)| ComponentVar {
+                asset: holding.asset,
+                weight: *weight,
+                var_contribution: if variance > Decimal::ZERO { var_95 * weight * sw / variance } else { Decimal::ZERO },
+            })
+            .collect();
+
+        let concentration_risk = weights.iter().copied().max().unwrap_or(Decimal::ZERO);
+
+        ProjectedRisk {
+            var_95,
+            concentration_risk,
+            risk_grade: RiskGrade::from_metrics(var_95, self.baseline.sharpe_ratio, self.baseline.max_drawdown),
+            component_var,
+        }
+    }
+
+    /// Turn per-position sale values into trades, dropping any below the minimum trade size
+    fn trades(&self, sales: &[Decimal], min_trade_value: Decimal) -> Vec<SuggestedTrade> {
+        self.holdings.iter().zip(sales)
+            .filter(|(holding, sale)| **sale > Decimal::ZERO && **sale >= min_trade_value && holding.price > Decimal::ZERO)
+            .map(|(holding, sale)| {
+                let position_value = holding.amount * holding.price;
+                let participation = (*sale / position_value).min(Decimal::ONE);
+                let illiquidity = Decimal::from(100u8.saturating_sub(holding.liquidity_score.min(100))) / dec!(100);
+                let impact_bps = (MAX_IMPACT_BPS * illiquidity * participation).round_dp(2);
+                SuggestedTrade {
+                    asset: holding.asset,
+                    side: TradeSide::Sell,
+                    amount: *sale / holding.price,
+                    value: *sale,
+                    estimated_impact_bps: impact_bps,
+                    estimated_impact_cost: (*sale * impact_bps / dec!(10000)).round_dp(2),
+                }
+            })
+            .collect()
+    }
+
+    fn values_after(&self, trades: &[SuggestedTrade]) -> Vec<Decimal> {
+        let mut values = self.current_values();
+        for trade in trades {
+            if let Some(i) = self.holdings.iter().position(|h| h.asset == trade.asset) {
+                values[i] -= trade.value;
+            }
+        }
+        values
+    }
+}
+
+/// Evaluate candidate adjustments against `target` and rank them.
+///
+/// Suggestions that meet the target come first, cheapest turnover first; the rest are
+/// ordered by how close they get. Candidates left with no trade at or above
+/// `min_trade_value` are discarded.
+pub fn suggest(
+    portfolio_address: Address,
+    model: &FactorModel,
+    holdings: &[RebalanceHolding],
+    baseline: &RiskBaseline,
+    target: &RebalanceTarget,
+    min_trade_value: Decimal,
+) -> RebalancePlan {
+    let simulator = Simulator::new(model, holdings, baseline);
+    let current = simulator.project(&simulator.current_values());
+
+    let mut plan = RebalancePlan {
+        portfolio_address,
+        target: target.clone(),
+        min_trade_value,
+        current: current.clone(),
+        target_reachable: target.is_met(&current),
+        suggestions: Vec::new(),
+    };
+    if plan.target_reachable || simulator.total_value.is_zero() {
+        return plan;
+    }
+
+    let mut candidates = vec![(RebalanceStrategy::TrimTopContributors, trim_top_contributors(&simulator, target))];
+    let caps: Vec<Decimal> = match target {
+        RebalanceTarget::MaxConcentration(share) => vec![*share],
+        _ => DEFAULT_WEIGHT_CAPS.to_vec(),
+    };
+    for cap in caps.into_iter().filter(|cap| *cap < current.concentration_risk) {
+        candidates.push((RebalanceStrategy::CapWeights { cap }, cap_weights(&simulator, cap)));
+    }
+
+    let mut seen = Vec::new();
+    for (strategy, sales) in candidates {
+        let trades = simulator.trades(&sales, min_trade_value);
+        if trades.is_empty() {
+            continue;
+        }
+        let key: Vec<(Address, Decimal)> = trades.iter().map(|t| (t.asset, t.value)).collect();
+        if seen.contains(&key) {
+            continue;
+        }
+        seen.push(key);
+
+        let projected = simulator.project(&simulator.values_after(&trades));
+        plan.suggestions.push(RebalanceSuggestion {
+            rank: 0,
+            strategy,
+            meets_target: target.is_met(&projected),
+            turnover: trades.iter().map(|t| t.value).sum(),
+            estimated_impact_cost: trades.iter().map(|t| t.estimated_impact_cost).sum(),
+            projected,
+            trades,
+        });
+    }
+
+    plan.suggestions.sort_by(|a, b| {
+        b.meets_target.cmp(&a.meets_target)
+            .then_with(|| {
+                if a.meets_target {
+                    std::cmp::Ordering::Equal
+                } else {
+                    target.shortfall(&a.projected).cmp(&target.shortfall(&b.projected))
+                }
+            })
+            .then_with(|| (a.turnover + a.estimated_impact_cost).cmp(&(b.turnover + b.estimated_impact_cost)))
+    });
+    for (i, suggestion) in plan.suggestions.iter_mut().enumerate() {
+        suggestion.rank = i + 1;
+    }
+    plan.target_reachable = plan.suggestions.first().map_or(false, |s| s.meets_target);
+
+    plan
+}
+
+/// Sell TRIM_STEP of portfolio value at a time from the largest VaR contributor until the target is met
+fn trim_top_contributors(simulator: &Simulator, target: &RebalanceTarget) -> Vec<Decimal> {
+    let step = simulator.total_value * TRIM_STEP;
+    let mut values = simulator.current_values();
+    let mut sales = vec![Decimal::ZERO; values.len()];
+
+    // Enough steps to sell every position outright
+    let max_steps = 20 * values.len();
+    for _ in 0..max_steps {
+        let projected = simulator.project(&values);
+        if target.is_met(&projected) {
+            break;
+        }
+        let top = projected.component_var.iter().enumerate()
+            .filter(|(i, _)| values[*i] > Decimal::ZERO)
+            .max_by(|(_, a), (_, b)| a.var_contribution.cmp(&b.var_contribution))
+            .map(|(i, _)| i);
+        let Some(i) = top else { break };
+
+        let sale = step.min(values[i]);
+        values[i] -= sale;
+        sales[i] += sale;
+    }
+
+    sales
+}
+
+/// Sell each position down to `cap` of portfolio value
+fn cap_weights(simulator: &Simulator, cap: Decimal) -> Vec<Decimal> {
+    let limit = simulator.total_value * cap;
+    simulator.current_values().into_iter()
+        .map(|value| (value - limit).max(Decimal::ZERO))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STABLE: u8 = 1;
+    const CREDIT: u8 = 2;
+    const CRYPTO: u8 = 3;
+
+    /// 70% in a volatile crypto asset, 20% credit, 10% a near-riskless stablecoin
+    fn concentrated_portfolio() -> Vec<RebalanceHolding> {
+        let holding = |byte: u8, amount: Decimal, price: Decimal, liquidity: u8, variance: Decimal, loadings: &[(&str, Decimal)]| {
+            RebalanceHolding {
+                asset: Address::repeat_byte(byte),
+                amount,
+                price,
+                liquidity_score: liquidity,
+                variance,
+                loadings: loadings.iter().map(|(f, l)| (f.to_string(), *l)).collect(),
+            }
+        };
+        vec![
+            holding(CRYPTO, dec!(350), dec!(2000), 50, dec!(0.0016), &[("crypto_beta", dec!(1))]),
+            holding(CREDIT, dec!(2000), dec!(100), 75, dec!(0.00005), &[("credit", dec!(1))]),
+            holding(STABLE, dec!(100000), dec!(1), 95, dec!(0.000001), &[]),
+        ]
+    }
+
+    fn baseline(var_95: Decimal) -> RiskBaseline {
+        RiskBaseline { var_95, sharpe_ratio: dec!(1.5), max_drawdown: dec!(0.08) }
+    }
+
+    fn sold(suggestion: &RebalanceSuggestion, byte: u8) -> Decimal {
+        suggestion.trades.iter()
+            .filter(|t| t.asset == Address::repeat_byte(byte))
+            .map(|t| t.value)
+            .sum()
+    }
+
+    #[test]
+    fn test_concentration_target_caps_largest_position() {
+        let holdings = concentrated_portfolio();
+        let target = RebalanceTarget::MaxConcentration(dec!(0.40));
+        let plan = suggest(Address::zero(), &FactorModel::default(), &holdings, &baseline(dec!(0.06)), &target, dec!(1000));
+
+        assert!((plan.current.concentration_risk - dec!(0.7)).abs() < dec!(0.0001));
+        assert!(plan.target_reachable);
+
+        let best = &plan.suggestions[0];
+        assert_eq!(best.rank, 1);
+        assert!(best.meets_target);
+        assert!(best.projected.concentration_risk <= dec!(0.40));
+        assert!(best.projected.var_95 < plan.current.var_95);
+        assert_eq!(sold(best, CRYPTO), dec!(300000));
+        assert!(best.trades.iter().all(|t| t.value >= dec!(1000) && t.side == TradeSide::Sell));
+        // 200bps scaled by illiquidity (0.5) and the share of the position sold (3/7)
+        assert_eq!(best.trades[0].estimated_impact_bps, dec!(42.86));
+        assert_eq!(best.estimated_impact_cost, dec!(1285.80));
+    }
+
+    #[test]
+    fn test_var_target_trims_top_contributor_first() {
+        let holdings = concentrated_portfolio();
+        let plan = suggest(Address::zero(), &FactorModel::default(), &holdings, &baseline(dec!(0.06)), &RebalanceTarget::MaxVar(dec!(0.03)), dec!(1000));
+
+        // The crypto position carries almost all of the VaR
+        let crypto = &plan.current.component_var[0];
+        assert!(crypto.var_contribution > plan.current.var_95 * dec!(0.9));
+        let components: Decimal = plan.current.component_var.iter().map(|c| c.var_contribution).sum();
+        assert!((components - plan.current.var_95).abs() < dec!(0.0001));
+
+        let trim = plan.suggestions.iter()
+            .find(|s| s.strategy == RebalanceStrategy::TrimTopContributors)
+            .unwrap();
+        assert!(trim.meets_target);
+        assert!(trim.projected.var_95 <= dec!(0.03));
+        assert_eq!(sold(trim, STABLE), Decimal::ZERO);
+        assert!(sold(trim, CRYPTO) > Decimal::ZERO);
+        assert!(plan.suggestions[0].meets_target);
+    }
+
+    #[test]
+    fn test_unreachable_grade_reports_best_achievable() {
+        let holdings = concentrated_portfolio();
+        // Negative Sharpe and a 35% drawdown cap the grade at D whatever is traded
+        let poor = RiskBaseline { var_95: dec!(0.06), sharpe_ratio: dec!(-0.2), max_drawdown: dec!(0.35) };
+        let plan = suggest(Address::zero(), &FactorModel::default(), &holdings, &poor, &RebalanceTarget::RiskGrade(RiskGrade::B), dec!(1000));
+
+        assert_eq!(plan.current.risk_grade, RiskGrade::F);
+        assert!(!plan.target_reachable);
+        assert!(!plan.suggestions.is_empty());
+        assert!(plan.suggestions.iter().all(|s| !s.meets_target));
+        assert_eq!(plan.suggestions[0].projected.risk_grade, RiskGrade::D);
+
+        // Nothing is suggested when every trade is below the minimum size
+        let plan = suggest(Address::zero(), &FactorModel::default(), &holdings, &poor, &RebalanceTarget::RiskGrade(RiskGrade::B), dec!(10000000));
+        assert!(!plan.target_reachable);
+        assert!(plan.suggestions.is_empty());
+    }
+}
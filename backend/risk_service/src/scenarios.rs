@@ -0,0 +1,161 @@
+// Market stress scenarios
+//
+// A scenario shocks asset prices, relative to the current price (-0.5 halves it), and
+// stresses the market VaR is simulated in. Positions are revalued at the shocked prices
+// for the value change, and VaR is re-run over the shocked positions with volatilities
+// scaled and correlations moved toward 1, for comparison with the unstressed VaR.
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use crate::ethereum_client::Address;
+use crate::metrics::{self, CovarianceStress};
+use crate::{DecimalExt, MarketScenario, PortfolioPosition, RiskServiceError};
+
+/// Reject shocks below -100%, non-positive volatility multipliers and correlation
+/// adjustments outside [0, 1]
+pub fn validate(scenario: &MarketScenario) -> Result<(), RiskServiceError> {
+    let invalid = |reason: String| RiskServiceError::InvalidInput(format!("Scenario {}: {}", scenario.name, reason));
+    if let Some((asset, shock)) = scenario.price_shocks.iter().find(|(_, shock)| **shock < Decimal::NEGATIVE_ONE) {
+        return Err(invalid(format!("shock {} on {:?} is below -100%", shock, asset)));
+    }
+    if scenario.default_shock < Decimal::NEGATIVE_ONE {
+        return Err(invalid(format!("default shock {} is below -100%", scenario.default_shock)));
+    }
+    if scenario.volatility_multiplier <= Decimal::ZERO {
+        return Err(invalid("volatility multiplier must be positive".to_string()));
+    }
+    if scenario.correlation_adjustment < Decimal::ZERO || scenario.correlation_adjustment > Decimal::ONE {
+        return Err(invalid("correlation adjustment must be between 0 and 1".to_string()));
+    }
+    Ok(())
+}
+
+/// Relative price change the scenario applies to an asset
+pub fn shock(scenario: &MarketScenario, asset: Address) -> Decimal {
+    scenario.price_shocks.get(&asset).copied().unwrap_or(scenario.default_shock)
+}
+
+/// The positions at the scenario's shocked prices
+pub fn shocked_positions(positions: &[PortfolioPosition], scenario: &MarketScenario) -> Result<Vec<PortfolioPosition>, RiskServiceError> {
+    positions.iter()
+        .map(|position| {
+            let current_price = position.current_price
+                .checked_mul(Decimal::ONE + shock(scenario, position.asset))
+                .ok_or_else(|| RiskServiceError::CalculationError(format!("Overflow shocking the price of {:?}", position.asset)))?;
+            Ok(PortfolioPosition {
+                current_price,
+                unrealized_pnl: (current_price - position.entry_price) * position.amount,
+                ..position.clone()
+            })
+        })
+        .collect()
+}
+
+/// Market value of the positions
+pub fn total_value(positions: &[PortfolioPosition]) -> Result<Decimal, RiskServiceError> {
+    positions.iter().try_fold(Decimal::ZERO, |total, position| {
+        total.checked_add(metrics::position_value(position)?)
+            .ok_or_else(|| RiskServiceError::CalculationError("Overflow summing portfolio value".to_string()))
+    })
+}
+
+/// Covariance stress of the scenario for the VaR simulation
+pub fn covariance_stress(scenario: &MarketScenario) -> CovarianceStress {
+    CovarianceStress {
+        volatility_multiplier: scenario.volatility_multiplier.to_f64_lossy(),
+        correlation_adjustment: scenario.correlation_adjustment.to_f64_lossy(),
+    }
+}
+
+/// Share of observed daily portfolio returns at or below `value_change`
+pub fn observed_frequency(portfolio_returns: &[Decimal], value_change: Decimal) -> Decimal {
+    if portfolio_returns.is_empty() {
+        return Decimal::ZERO;
+    }
+    let at_or_below = portfolio_returns.iter().filter(|ret| **ret <= value_change).count();
+    Decimal::from(at_or_below) / Decimal::from(portfolio_returns.len())
+}
+
+fn builtin(name: &str, default_shock: Decimal, volatility_multiplier: Decimal, correlation_adjustment: Decimal) -> MarketScenario {
+    MarketScenario {
+        name: name.to_string(),
+        price_shocks: HashMap::new(),
+        default_shock,
+        volatility_multiplier,
+        correlation_adjustment,
+    }
+}
+
+/// Named scenarios shipped with the service, calibrated to broad market moves. They shock
+/// every asset alike through `default_shock`; add `price_shocks` for asset-specific moves.
+pub fn builtin_scenarios() -> Vec<MarketScenario> {
+    vec![
+        // Peak-to-trough equity losses of the financial crisis, with correlations converging
+        builtin("2008 crisis", dec!(-0.45), dec!(2.5), dec!(0.6)),
+        // The March 2020 sell-off: a sharper, shorter shock at the highest volatility
+        builtin("2020 pandemic crash", dec!(-0.34), dec!(3.0), dec!(0.5)),
+        // A 200bps parallel rise in rates repricing duration-sensitive holdings
+        builtin("rates +200bps", dec!(-0.12), dec!(1.3), dec!(0.2)),
+        // A prolonged digital asset drawdown, as in 2022
+        builtin("crypto winter", dec!(-0.65), dec!(2.0), dec!(0.4)),
+        // Volatility spikes without a price move, isolating the VaR impact
+        builtin("volatility spike", Decimal::ZERO, dec!(2.0), dec!(0.3)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(asset: Address, amount: Decimal, current_price: Decimal) -> PortfolioPosition {
+        PortfolioPosition {
+            asset,
+            amount,
+            current_price,
+            entry_price: current_price,
+            unrealized_pnl: Decimal::ZERO,
+            entry_price_provenance: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_shocks_revalue_positions_exactly() {
+        let (shocked, other) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let positions = [position(shocked, dec!(10), dec!(100)), position(other, dec!(3), dec!(50))];
+        let scenario = MarketScenario {
+            name: "test".to_string(),
+            price_shocks: HashMap::from([(shocked, dec!(-0.5))]),
+            default_shock: dec!(0.1),
+            volatility_multiplier: Decimal::ONE,
+            correlation_adjustment: Decimal::ZERO,
+        };
+
+        let stressed = shocked_positions(&positions, &scenario).unwrap();
+        assert_eq!(stressed[0].current_price, dec!(50));
+        assert_eq!(stressed[1].current_price, dec!(55));
+        assert_eq!(stressed[0].unrealized_pnl, dec!(-500));
+        // 1,150 revalued to 500 + 165
+        assert_eq!(total_value(&positions).unwrap(), dec!(1150));
+        assert_eq!(total_value(&stressed).unwrap(), dec!(665));
+
+        assert_eq!(observed_frequency(&[dec!(-0.1), dec!(-0.02), dec!(0.01), dec!(0.03)], dec!(-0.02)), dec!(0.5));
+    }
+
+    #[test]
+    fn test_builtin_scenarios_are_valid_and_distinct() {
+        let scenarios = builtin_scenarios();
+        for scenario in &scenarios {
+            validate(scenario).unwrap();
+        }
+        let mut names: Vec<_> = scenarios.iter().map(|scenario| scenario.name.as_str()).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), scenarios.len());
+
+        let mut invalid = scenarios[0].clone();
+        invalid.price_shocks.insert(Address::repeat_byte(1), dec!(-1.5));
+        assert!(matches!(validate(&invalid), Err(RiskServiceError::InvalidInput(_))));
+        let invalid = MarketScenario { correlation_adjustment: dec!(1.2), ..scenarios[0].clone() };
+        assert!(matches!(validate(&invalid), Err(RiskServiceError::InvalidInput(_))));
+    }
+}
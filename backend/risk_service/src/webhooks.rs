@@ -0,0 +1,427 @@
+// Webhook delivery of risk alerts to external systems
+//
+// A portfolio's subscribers receive the alert changes the tracker notifies (opened,
+// escalated, re-sent after the cooldown, resolved) at the severities they chose. Each POST
+// carries the alert as JSON with an HMAC-SHA256 of the body under the subscriber's secret
+// in `X-Signature`, hex encoded. Timeouts and non-2xx responses are retried with
+// exponential backoff; a delivery still failing after the last attempt is dead-lettered.
+// Deliveries run as their own tasks, so a slow endpoint never holds up monitoring.
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+use quantera_types::Pii;
+use crate::alerts::parse_severity;
+use crate::ethereum_client::Address;
+use crate::{AlertSeverity, RiskAlert, RiskServiceError};
+
+/// Header carrying the hex HMAC-SHA256 of the body
+pub const SIGNATURE_HEADER: &str = "X-Signature";
+/// Header carrying the delivery id, unchanged across retries so receivers can deduplicate
+pub const DELIVERY_HEADER: &str = "X-Delivery-Id";
+
+/// Shorter secrets are rejected at registration
+const MIN_SECRET_LEN: usize = 16;
+
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    /// Time one request may take before it counts as a failed attempt
+    pub timeout: Duration,
+    /// Attempts per delivery, the first included
+    pub max_attempts: u32,
+    /// Wait before the first retry; doubles with each further retry
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(5),
+            max_attempts: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+        }
+    }
+}
+
+impl WebhookConfig {
+    /// Wait after the given number of failed attempts
+    fn backoff(&self, failed_attempts: u32) -> Duration {
+        let factor = 2u32.saturating_pow(failed_attempts.saturating_sub(1));
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+/// An endpoint receiving a portfolio's alerts
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookSubscription {
+    pub id: Uuid,
+    pub portfolio: Address,
+    pub url: String,
+    /// Signing secret; never returned by the API
+    #[serde(skip_serializing)]
+    pub secret: String,
+    /// Alert severities delivered to the endpoint
+    pub severities: Vec<AlertSeverity>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl WebhookSubscription {
+    pub fn accepts(&self, severity: &AlertSeverity) -> bool {
+        self.severities.contains(severity)
+    }
+}
+
+/// Body POSTed to a subscriber
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookPayload<'a> {
+    pub delivery_id: Uuid,
+    pub webhook_id: Uuid,
+    pub alert: &'a RiskAlert,
+}
+
+/// A delivery given up after its last attempt
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadLetter {
+    pub delivery_id: Uuid,
+    pub webhook_id: Uuid,
+    pub alert_id: Uuid,
+    pub url: String,
+    pub attempts: u32,
+    /// Status of the last response; none when the last attempt got no response
+    pub last_status: Option<u16>,
+    pub last_error: String,
+    pub payload: serde_json::Value,
+    pub failed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeliveryOutcome {
+    Delivered { attempts: u32 },
+    Failed { attempts: u32, last_status: Option<u16>, last_error: String },
+}
+
+/// Hex HMAC-SHA256 of `body` under `secret`, as sent in `X-Signature`
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Check a registration, returning its severities sorted and deduplicated
+pub fn validate(url: &str, secret: &str, severities: &[AlertSeverity]) -> Result<Vec<AlertSeverity>, RiskServiceError> {
+    let parsed = reqwest::Url::parse(url)
+        .map_err(|e| RiskServiceError::InvalidInput(format!("Invalid webhook URL: {}", e)))?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        return Err(RiskServiceError::InvalidInput("Webhook URL must be an http(s) URL with a host".to_string()));
+    }
+    if secret.len() < MIN_SECRET_LEN {
+        return Err(RiskServiceError::InvalidInput(format!("Webhook secret must be at least {} characters", MIN_SECRET_LEN)));
+    }
+    if severities.is_empty() {
+        return Err(RiskServiceError::InvalidInput("A webhook needs at least one alert severity".to_string()));
+    }
+    let mut severities = severities.to_vec();
+    severities.sort();
+    severities.dedup();
+    Ok(severities)
+}
+
+/// POST a signed body, retrying timeouts and non-2xx responses with backoff
+async fn deliver(client: &reqwest::Client, config: &WebhookConfig, url: &str, secret: &str, delivery_id: Uuid, body: &str) -> DeliveryOutcome {
+    let signature = sign(secret, body.as_bytes());
+    let mut attempts = 0;
+    let mut last_status = None;
+    let mut last_error = String::new();
+
+    while attempts < config.max_attempts.max(1) {
+        if attempts > 0 {
+            tokio::time::sleep(config.backoff(attempts)).await;
+        }
+        attempts += 1;
+
+        let response = client.post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, &signature)
+            .header(DELIVERY_HEADER, delivery_id.to_string())
+            .body(body.to_string())
+            .send()
+            .await;
+        match response {
+            Ok(response) if response.status().is_success() => return DeliveryOutcome::Delivered { attempts },
+            Ok(response) => {
+                last_status = Some(response.status().as_u16());
+                last_error = format!("HTTP {}", response.status());
+            }
+            Err(e) => {
+                last_status = None;
+                last_error = e.to_string();
+            }
+        }
+    }
+
+    DeliveryOutcome::Failed { attempts, last_status, last_error }
+}
+
+/// Sends alert changes to the subscribed endpoints of their portfolio
+pub struct WebhookDispatcher {
+    db: Arc<PgPool>,
+    client: reqwest::Client,
+    config: WebhookConfig,
+}
+
+impl WebhookDispatcher {
+    pub fn new(db: Arc<PgPool>, config: WebhookConfig) -> Result<Self, RiskServiceError> {
+        let client = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .build()
+            .map_err(|e| RiskServiceError::WebhookError(format!("HTTP client: {}", e)))?;
+        Ok(Self { db, client, config })
+    }
+
+    /// Deliver an alert change in the background, one task per subscriber
+    pub fn dispatch(self: &Arc<Self>, alert: RiskAlert) {
+        let dispatcher = self.clone();
+        tokio::spawn(async move {
+            let subscriptions = match load(&dispatcher.db, alert.portfolio).await {
+                Ok(subscriptions) => subscriptions,
+                Err(e) => {
+                    error!(alert_id = %alert.id, portfolio = %Pii::debug(alert.portfolio), error = %e, "Failed to load webhooks for alert");
+                    return;
+                }
+            };
+            let alert = Arc::new(alert);
+            for subscription in subscriptions.into_iter().filter(|subscription| subscription.accepts(&alert.severity)) {
+                let dispatcher = dispatcher.clone();
+                let alert = alert.clone();
+                tokio::spawn(async move { dispatcher.deliver_to(&subscription, &alert).await });
+            }
+        });
+    }
+
+    async fn deliver_to(&self, subscription: &WebhookSubscription, alert: &RiskAlert) {
+        let delivery_id = Uuid::new_v4();
+        let payload = WebhookPayload { delivery_id, webhook_id: subscription.id, alert };
+        let body = match serde_json::to_string(&payload) {
+            Ok(body) => body,
+            Err(e) => {
+                error!(alert_id = %alert.id, webhook_id = %subscription.id, error = %e, "Failed to serialize webhook payload");
+                return;
+            }
+        };
+
+        match deliver(&self.client, &self.config, &subscription.url, &subscription.secret, delivery_id, &body).await {
+            DeliveryOutcome::Delivered { attempts } => {
+                info!(alert_id = %alert.id, webhook_id = %subscription.id, %delivery_id, attempts, "Alert webhook delivered");
+            }
+            DeliveryOutcome::Failed { attempts, last_status, last_error } => {
+                warn!(alert_id = %alert.id, webhook_id = %subscription.id, %delivery_id, attempts, error = %last_error, "Alert webhook failed; dead-lettering");
+                let dead_letter = DeadLetter {
+                    delivery_id,
+                    webhook_id: subscription.id,
+                    alert_id: alert.id,
+                    url: subscription.url.clone(),
+                    attempts,
+                    last_status,
+                    last_error,
+                    payload: serde_json::from_str(&body).unwrap_or_default(),
+                    failed_at: Utc::now(),
+                };
+                if let Err(e) = record_dead_letter(&self.db, subscription.portfolio, &dead_letter).await {
+                    error!(alert_id = %alert.id, webhook_id = %subscription.id, %delivery_id, error = %e, "Failed to record dead-lettered webhook");
+                }
+            }
+        }
+    }
+}
+
+pub async fn save(db: &PgPool, subscription: &WebhookSubscription) -> Result<(), RiskServiceError> {
+    sqlx::query(r#"
+        INSERT INTO risk_alert_webhooks (id, portfolio_address, url, secret, severities, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+    "#)
+        .bind(subscription.id)
+        .bind(format!("{:?}", subscription.portfolio))
+        .bind(&subscription.url)
+        .bind(&subscription.secret)
+        .bind(subscription.severities.iter().map(|severity| format!("{:?}", severity)).collect::<Vec<_>>())
+        .bind(subscription.created_at)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// Webhooks of a portfolio, oldest first
+pub async fn load(db: &PgPool, portfolio: Address) -> Result<Vec<WebhookSubscription>, RiskServiceError> {
+    let rows: Vec<(Uuid, String, String, Vec<String>, DateTime<Utc>)> = sqlx::query_as(r#"
+        SELECT id, url, secret, severities, created_at
+        FROM risk_alert_webhooks
+        WHERE portfolio_address = $1
+        ORDER BY created_at, id
+    "#)
+        .bind(format!("{:?}", portfolio))
+        .fetch_all(db)
+        .await?;
+
+    rows.into_iter()
+        .map(|(id, url, secret, severities, created_at)| Ok(WebhookSubscription {
+            id,
+            portfolio,
+            url,
+            secret,
+            severities: severities.iter()
+                .map(|severity| parse_severity(severity)
+                    .ok_or_else(|| RiskServiceError::CalculationError(format!("Unknown alert severity {}", severity))))
+                .collect::<Result<_, _>>()?,
+            created_at,
+        }))
+        .collect()
+}
+
+/// Remove a portfolio's webhook; false when it has none with this id
+pub async fn delete(db: &PgPool, portfolio: Address, id: Uuid) -> Result<bool, RiskServiceError> {
+    let result = sqlx::query("DELETE FROM risk_alert_webhooks WHERE id = $1 AND portfolio_address = $2")
+        .bind(id)
+        .bind(format!("{:?}", portfolio))
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+async fn record_dead_letter(db: &PgPool, portfolio: Address, dead_letter: &DeadLetter) -> Result<(), RiskServiceError> {
+    sqlx::query(r#"
+        INSERT INTO risk_webhook_dead_letters (
+            delivery_id, webhook_id, portfolio_address, alert_id, url,
+            attempts, last_status, last_error, payload, failed_at
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9::jsonb, $10)
+    "#)
+        .bind(dead_letter.delivery_id)
+        .bind(dead_letter.webhook_id)
+        .bind(format!("{:?}", portfolio))
+        .bind(dead_letter.alert_id)
+        .bind(&dead_letter.url)
+        .bind(dead_letter.attempts as i32)
+        .bind(dead_letter.last_status.map(i32::from))
+        .bind(&dead_letter.last_error)
+        .bind(dead_letter.payload.to_string())
+        .bind(dead_letter.failed_at)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// Deliveries to a portfolio's webhooks that were given up, newest first
+pub async fn load_dead_letters(db: &PgPool, portfolio: Address) -> Result<Vec<DeadLetter>, RiskServiceError> {
+    let rows: Vec<(Uuid, Uuid, Uuid, String, i32, Option<i32>, String, String, DateTime<Utc>)> = sqlx::query_as(r#"
+        SELECT delivery_id, webhook_id, alert_id, url, attempts, last_status, last_error, payload::text, failed_at
+        FROM risk_webhook_dead_letters
+        WHERE portfolio_address = $1
+        ORDER BY failed_at DESC
+    "#)
+        .bind(format!("{:?}", portfolio))
+        .fetch_all(db)
+        .await?;
+
+    Ok(rows.into_iter()
+        .map(|(delivery_id, webhook_id, alert_id, url, attempts, last_status, last_error, payload, failed_at)| DeadLetter {
+            delivery_id,
+            webhook_id,
+            alert_id,
+            url,
+            attempts: attempts as u32,
+            last_status: last_status.map(|status| status as u16),
+            last_error,
+            payload: serde_json::from_str(&payload).unwrap_or_default(),
+            failed_at,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::State;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::Router;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_signature_and_registration_checks() {
+        assert_eq!(
+            sign("key", b"The quick brown fox jumps over the lazy dog"),
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8",
+        );
+
+        let secret = "0123456789abcdef";
+        let severities = validate("https://hooks.example.com/risk", secret, &[AlertSeverity::Critical, AlertSeverity::Warning, AlertSeverity::Critical]).unwrap();
+        assert_eq!(severities, vec![AlertSeverity::Warning, AlertSeverity::Critical]);
+        for (url, secret, severities) in [
+            ("ftp://hooks.example.com", secret, vec![AlertSeverity::Info]),
+            ("not a url", secret, vec![AlertSeverity::Info]),
+            ("https://hooks.example.com", "short", vec![AlertSeverity::Info]),
+            ("https://hooks.example.com", secret, vec![]),
+        ] {
+            assert!(matches!(validate(url, secret, &severities), Err(RiskServiceError::InvalidInput(_))), "{}", url);
+        }
+
+        let config = WebhookConfig::default();
+        assert_eq!(config.backoff(1), Duration::from_secs(1));
+        assert_eq!(config.backoff(3), Duration::from_secs(4));
+        assert_eq!(config.backoff(10), Duration::from_secs(60));
+    }
+
+    /// Responds 503 to the first `failures` requests, then 200, recording each request
+    async fn endpoint(failures: usize) -> (String, Arc<Mutex<Vec<(HeaderMap, String)>>>) {
+        let received: Arc<Mutex<Vec<(HeaderMap, String)>>> = Arc::default();
+        let app = Router::new()
+            .route("/hook", post(move |State(received): State<Arc<Mutex<Vec<(HeaderMap, String)>>>>, headers: HeaderMap, body: String| async move {
+                let mut received = received.lock().unwrap();
+                received.push((headers, body));
+                if received.len() <= failures { StatusCode::SERVICE_UNAVAILABLE } else { StatusCode::OK }
+            }))
+            .with_state(received.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (url, received)
+    }
+
+    #[tokio::test]
+    async fn test_failed_attempts_are_retried_with_the_same_signed_body() {
+        let config = WebhookConfig {
+            timeout: Duration::from_secs(2),
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(50),
+        };
+        let client = reqwest::Client::builder().timeout(config.timeout).build().unwrap();
+        let secret = "0123456789abcdef";
+        let delivery_id = Uuid::new_v4();
+        let body = r#"{"alert":{"severity":"Critical"}}"#;
+
+        let (url, received) = endpoint(2).await;
+        assert_eq!(deliver(&client, &config, &url, secret, delivery_id, body).await, DeliveryOutcome::Delivered { attempts: 3 });
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 3);
+        for (headers, received_body) in received.iter() {
+            assert_eq!(received_body, body);
+            assert_eq!(headers[SIGNATURE_HEADER].to_str().unwrap(), sign(secret, body.as_bytes()));
+            assert_eq!(headers[DELIVERY_HEADER].to_str().unwrap(), delivery_id.to_string());
+        }
+
+        let (url, received) = endpoint(usize::MAX).await;
+        assert_eq!(
+            deliver(&client, &config, &url, secret, delivery_id, body).await,
+            DeliveryOutcome::Failed { attempts: 3, last_status: Some(503), last_error: "HTTP 503 Service Unavailable".to_string() },
+        );
+        assert_eq!(received.lock().unwrap().len(), 3);
+    }
+}
@@ -3,11 +3,14 @@ use std::sync::Arc;
 use std::net::SocketAddr;
 use tokio::net::{TcpListener, TcpStream};
 use tokio_tungstenite::{accept_async, tungstenite::Message};
+use tokio_tungstenite::tungstenite::protocol::{frame::coding::CloseCode, CloseFrame};
 use futures_util::{SinkExt, StreamExt};
 use uuid::Uuid;
 use serde_json;
 use tracing::{info, error, warn};
+use quantera_types::Pii;
 use crate::{RiskService, RiskMetrics};
+use crate::broadcast::SubscriptionEnded;
 
 pub struct WebSocketServer {
     risk_service: Arc<RiskService>,
@@ -53,25 +56,32 @@ async fn handle_connection(
     let (ws_sender, mut ws_receiver) = ws_stream.split();
     let client_id = Uuid::new_v4();
     
-    // Create channel for sending updates to this client
-    let (tx, mut rx) = tokio::sync::mpsc::channel::<RiskMetrics>(100);
+    // Replies to this client's commands; risk updates come from the shared broadcast
     let (cmd_tx, mut cmd_rx) = tokio::sync::mpsc::channel::<Message>(100);
-    
-    // Register the client
-    risk_service.register_websocket_client(client_id, tx).await;
+    let mut updates = risk_service.subscribe_risk_updates();
+    info!("WebSocket client {} subscribed to risk updates", client_id);
     
     // Spawn task to forward risk updates to WebSocket
     let mut ws_sender = ws_sender;
     let send_task = tokio::spawn(async move {
         loop {
             tokio::select! {
-                Some(metrics) = rx.recv() => {
-                    if let Ok(json) = serde_json::to_string(&metrics) {
-                        if ws_sender.send(Message::Text(json)).await.is_err() {
+                update = updates.next() => match update {
+                    Ok(payload) => {
+                        if ws_sender.send(Message::Text(payload.to_string())).await.is_err() {
                             break;
                         }
                     }
-                }
+                    Err(SubscriptionEnded::Lagged(missed)) => {
+                        // Tell the client why so it can reconnect and resubscribe
+                        let _ = ws_sender.send(Message::Close(Some(CloseFrame {
+                            code: CloseCode::Again,
+                            reason: format!("Fell {} risk updates behind", missed).into(),
+                        }))).await;
+                        break;
+                    }
+                    Err(SubscriptionEnded::Closed) => break,
+                },
                 Some(msg) = cmd_rx.recv() => {
                     if ws_sender.send(msg).await.is_err() {
                         break;
@@ -89,19 +99,19 @@ async fn handle_connection(
                 if let Ok(command) = serde_json::from_str::<WebSocketCommand>(&text) {
                     match command {
                         WebSocketCommand::Subscribe { portfolio_address } => {
-                            info!("Client {} subscribed to portfolio {}", client_id, portfolio_address);
+                            info!(client_id = %client_id, portfolio = %Pii::new(&portfolio_address), "Client subscribed to portfolio");
                             // Immediately send current metrics
                             if let Ok(metrics) = risk_service.calculate_portfolio_risk(
-                                portfolio_address.parse().unwrap_or_default()
+                                portfolio_address.parse().unwrap_or_default(),
+                                risk_service.var_method(),
                             ).await {
-                                let tx = risk_service.get_client_sender(client_id).await;
-                                if let Some(tx) = tx {
-                                    let _ = tx.send(metrics).await;
+                                if let Ok(json) = serde_json::to_string(&metrics) {
+                                    let _ = cmd_tx.send(Message::Text(json)).await;
                                 }
                             }
                         }
                         WebSocketCommand::Unsubscribe { portfolio_address } => {
-                            info!("Client {} unsubscribed from portfolio {}", client_id, portfolio_address);
+                            info!(client_id = %client_id, portfolio = %Pii::new(&portfolio_address), "Client unsubscribed from portfolio");
                         }
                         WebSocketCommand::Ping => {
                             // Send pong through command channel
@@ -128,8 +138,8 @@ async fn handle_connection(
         }
     }
     
-    // Cleanup
-    risk_service.unregister_websocket_client(client_id).await;
+    // Cleanup; dropping the forwarding task releases the subscription
+    info!("WebSocket client {} unsubscribed from risk updates", client_id);
     send_task.abort();
     
     Ok(())
@@ -0,0 +1,275 @@
+// Pre-trade risk impact of hypothetical trades
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::ethereum_client::Address;
+use crate::factors::{self, AssetRisk, FactorModel};
+use crate::{DecimalExt, RiskGrade, RiskMetrics, RiskServiceError};
+
+/// Most hypothetical trades accepted in one request
+pub const MAX_WHAT_IF_TRADES: usize = 25;
+
+/// A trade to evaluate; positive quantities buy, negative sell
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HypotheticalTrade {
+    pub asset: Address,
+    pub quantity: Decimal,
+    pub price: Decimal,
+}
+
+/// Reject empty or oversized requests and trades without a quantity or a positive price
+pub fn validate_trades(trades: &[HypotheticalTrade]) -> Result<(), RiskServiceError> {
+    if trades.is_empty() {
+        return Err(RiskServiceError::InvalidInput("At least one trade is required".into()));
+    }
+    if trades.len() > MAX_WHAT_IF_TRADES {
+        return Err(RiskServiceError::InvalidInput(format!("At most {} trades per request", MAX_WHAT_IF_TRADES)));
+    }
+    for trade in trades {
+        if trade.quantity.is_zero() {
+            return Err(RiskServiceError::InvalidInput(format!("Trade in {:?} has no quantity", trade.asset)));
+        }
+        if trade.price <= Decimal::ZERO {
+            return Err(RiskServiceError::InvalidInput(format!("Trade in {:?} needs a positive price", trade.asset)));
+        }
+    }
+    Ok(())
+}
+
+/// A position, held or introduced by a trade, as input to the projection
+#[derive(Debug, Clone)]
+pub struct WhatIfHolding {
+    pub asset: Address,
+    pub amount: Decimal,
+    pub price: Decimal,
+    /// Daily return variance; None when the asset has no price history to model it from
+    pub variance: Option<Decimal>,
+    pub loadings: HashMap<String, Decimal>,
+}
+
+/// Portfolio risk before or after the trades
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskSnapshot {
+    /// Value of modeled positions only
+    pub portfolio_value: Decimal,
+    pub var_95: Decimal,
+    pub var_99: Decimal,
+    pub volatility: Decimal,
+    pub concentration_risk: Decimal,
+    pub risk_grade: RiskGrade,
+    pub weights: HashMap<Address, Decimal>,
+}
+
+/// After minus before
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskDelta {
+    pub portfolio_value: Decimal,
+    pub var_95: Decimal,
+    pub var_99: Decimal,
+    pub volatility: Decimal,
+    pub concentration_risk: Decimal,
+}
+
+/// An asset left out of the after-trade weights because it has no price history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnmodeledAsset {
+    pub asset: Address,
+    pub amount: Decimal,
+    pub value: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WhatIfReport {
+    pub portfolio_address: Address,
+    pub trades: Vec<HypotheticalTrade>,
+    pub before: RiskSnapshot,
+    pub after: RiskSnapshot,
+    pub delta: RiskDelta,
+    pub unmodeled: Vec<UnmodeledAsset>,
+}
+
+/// Overlay `trades` on `holdings` and project the result against the assessed `metrics`.
+///
+/// Covariance comes from the factor model and each asset's variance, as in rebalancing;
+/// VaR and volatility scale with portfolio volatility relative to the assessed portfolio,
+/// and Sharpe and drawdown are held constant for the grade. `holdings` must include a
+/// zero-amount entry for every asset a trade introduces.
+pub fn evaluate(
+    portfolio_address: Address,
+    model: &FactorModel,
+    holdings: &[WhatIfHolding],
+    metrics: &RiskMetrics,
+    trades: &[HypotheticalTrade],
+) -> Result<WhatIfReport, RiskServiceError> {
+    let mut amounts: Vec<Decimal> = holdings.iter().map(|h| h.amount).collect();
+    for trade in trades {
+        let i = holdings.iter().position(|h| h.asset == trade.asset)
+            .ok_or_else(|| RiskServiceError::InvalidInput(format!("No holding entry for {:?}", trade.asset)))?;
+        amounts[i] += trade.quantity;
+    }
+    if let Some((holding, _)) = holdings.iter().zip(&amounts).find(|(_, amount)| **amount < Decimal::ZERO) {
+        return Err(RiskServiceError::InvalidInput(format!("Trades sell more {:?} than is held", holding.asset)));
+    }
+
+    let modeled: Vec<usize> = (0..holdings.len()).filter(|i| holdings[*i].variance.is_some()).collect();
+    let assets: Vec<AssetRisk> = modeled.iter()
+        .map(|i| AssetRisk {
+            weight: Decimal::ZERO,
+            variance: holdings[*i].variance.unwrap_or_default(),
+            loadings: holdings[*i].loadings.clone(),
+        })
+        .collect();
+    let covariance = factors::asset_covariance(model, &assets);
+
+    let current: Vec<Decimal> = holdings.iter().map(|h| h.amount).collect();
+    let (before_value, before_weights) = weights(holdings, &modeled, &current);
+    let (after_value, after_weights) = weights(holdings, &modeled, &amounts);
+    let before_variance = variance(&covariance, &before_weights);
+    let after_variance = variance(&covariance, &after_weights);
+
+    let scale = if before_variance > Decimal::ZERO {
+        (after_variance / before_variance).sqrt_approx().unwrap_or(Decimal::ONE)
+    } else {
+        Decimal::ONE
+    };
+
+    let snapshot = |value: Decimal, weights: &[Decimal], scale: Decimal| {
+        let var_95 = metrics.var_95 * scale;
+        RiskSnapshot {
+            portfolio_value: value,
+            var_95,
+            var_99: metrics.var_99 * scale,
+            volatility: metrics.volatility * scale,
+            concentration_risk: weights.iter().copied().max().unwrap_or(Decimal::ZERO),
+            risk_grade: RiskGrade::from_metrics(var_95, metrics.sharpe_ratio, metrics.max_drawdown),
+            weights: modeled.iter().zip(weights)
+                .filter(|(_, weight)| !weight.is_zero())
+                .map(|(i, weight)| (holdings[*i].asset, *weight))
+                .collect(),
+        }
+    };
+    let before = snapshot(before_value, &before_weights, Decimal::ONE);
+    let after = snapshot(after_value, &after_weights, scale);
+
+    let unmodeled = holdings.iter().zip(&amounts)
+        .filter(|(holding, amount)| holding.variance.is_none() && **amount > Decimal::ZERO)
+        .map(|(holding, amount)| UnmodeledAsset { asset: holding.asset, amount: *amount, value: amount * holding.price })
+        .collect();
+
+    Ok(WhatIfReport {
+        portfolio_address,
+        trades: trades.to_vec(),
+        delta: RiskDelta {
+            portfolio_value: after.portfolio_value - before.portfolio_value,
+            var_95: after.var_95 - before.var_95,
+            var_99: after.var_99 - before.var_99,
+            volatility: after.volatility - before.volatility,
+            concentration_risk: after.concentration_risk - before.concentration_risk,
+        },
+        before,
+        after,
+        unmodeled,
+    })
+}
+
+/// Total value and weights of the modeled positions, in `modeled` order
+fn weights(holdings: &[WhatIfHolding], modeled: &[usize], amounts: &[Decimal]) -> (Decimal, Vec<Decimal>) {
+    let values: Vec<Decimal> = modeled.iter().map(|i| amounts[*i] * holdings[*i].price).collect();
+    let total: Decimal = values.iter().sum();
+    if total.is_zero() {
+        return (total, vec![Decimal::ZERO; values.len()]);
+    }
+    (total, values.iter().map(|v| v / total).collect())
+}
+
+fn variance(covariance: &[Vec<Decimal>], weights: &[Decimal]) -> Decimal {
+    covariance.iter().zip(weights)
+        .map(|(row, wi)| wi * row.iter().zip(weights).map(|(c, wj)| c * wj).sum::<Decimal>())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use rust_decimal_macros::dec;
+
+    fn holding(byte: u8, amount: Decimal, price: Decimal, variance: Option<Decimal>) -> WhatIfHolding {
+        WhatIfHolding { asset: Address::repeat_byte(byte), amount, price, variance, loadings: HashMap::new() }
+    }
+
+    fn trade(byte: u8, quantity: Decimal, price: Decimal) -> HypotheticalTrade {
+        HypotheticalTrade { asset: Address::repeat_byte(byte), quantity, price }
+    }
+
+    fn metrics() -> RiskMetrics {
+        RiskMetrics {
+            portfolio_address: Address::zero(),
+            var_95: dec!(0.05),
+            var_99: dec!(0.08),
+            var_method: crate::VaRMethod::MonteCarlo,
+            expected_shortfall: dec!(0.06),
+            es_99: dec!(0.08),
+            sharpe_ratio: dec!(1.2),
+            sortino_ratio: dec!(1.5),
+            max_drawdown: dec!(0.08),
+            beta: Some(Decimal::ONE),
+            alpha: Some(Decimal::ZERO),
+            volatility: dec!(0.3),
+            correlation_matrix: Vec::new(),
+            liquidity_scores: HashMap::new(),
+            liquidity_details: HashMap::new(),
+            concentration_risk: dec!(0.8),
+            leverage_ratio: Decimal::ONE,
+            risk_grade: RiskGrade::B,
+            factor_risk_contributions: Vec::new(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_diversifying_trade_reduces_concentration_and_var() {
+        // 80% in one volatile asset, 20% in a quiet one
+        let holdings = vec![
+            holding(1, dec!(80), dec!(100), Some(dec!(0.0016))),
+            holding(2, dec!(20), dec!(100), Some(dec!(0.0001))),
+        ];
+        let trades = vec![trade(1, dec!(-30), dec!(100)), trade(2, dec!(30), dec!(100))];
+        let report = evaluate(Address::zero(), &FactorModel::default(), &holdings, &metrics(), &trades).unwrap();
+
+        assert_eq!(report.before.concentration_risk, dec!(0.8));
+        assert_eq!(report.before.var_95, dec!(0.05));
+        assert_eq!(report.after.concentration_risk, dec!(0.5));
+        assert_eq!(report.delta.concentration_risk, dec!(-0.3));
+        assert!(report.delta.var_95 < Decimal::ZERO);
+        assert!(report.after.volatility < report.before.volatility);
+        assert_eq!(report.delta.portfolio_value, Decimal::ZERO);
+        assert!(report.unmodeled.is_empty());
+    }
+
+    #[test]
+    fn test_asset_without_history_is_flagged_and_excluded() {
+        let holdings = vec![
+            holding(1, dec!(100), dec!(10), Some(dec!(0.0004))),
+            holding(9, Decimal::ZERO, dec!(50), None),
+        ];
+        let report = evaluate(Address::zero(), &FactorModel::default(), &holdings, &metrics(), &[trade(9, dec!(20), dec!(50))]).unwrap();
+
+        assert_eq!(report.unmodeled, vec![UnmodeledAsset { asset: Address::repeat_byte(9), amount: dec!(20), value: dec!(1000) }]);
+        assert_eq!(report.after.portfolio_value, dec!(1000));
+        assert!(!report.after.weights.contains_key(&Address::repeat_byte(9)));
+        assert_eq!(report.after.var_95, report.before.var_95);
+    }
+
+    #[test]
+    fn test_oversized_requests_and_oversells_are_rejected() {
+        let trades = vec![trade(1, dec!(1), dec!(1)); MAX_WHAT_IF_TRADES + 1];
+        assert!(matches!(validate_trades(&trades), Err(RiskServiceError::InvalidInput(_))));
+        assert!(validate_trades(&trades[..MAX_WHAT_IF_TRADES]).is_ok());
+        assert!(validate_trades(&[trade(1, dec!(1), Decimal::ZERO)]).is_err());
+
+        let holdings = vec![holding(1, dec!(10), dec!(1), Some(dec!(0.0004)))];
+        let oversell = evaluate(Address::zero(), &FactorModel::default(), &holdings, &metrics(), &[trade(1, dec!(-11), dec!(1))]);
+        assert!(matches!(oversell, Err(RiskServiceError::InvalidInput(_))));
+    }
+}
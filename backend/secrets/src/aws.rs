@@ -0,0 +1,54 @@
+use async_trait::async_trait;
+use aws_sdk_secretsmanager::{error::SdkError, operation::get_secret_value::GetSecretValueError, Client};
+use serde_json::Value;
+use crate::{SecretError, SecretRef, SecretValue, SecretsProvider};
+
+/// AWS Secrets Manager, with credentials and region from the standard AWS environment.
+///
+/// A secret `NAME` is the secret id `{prefix}NAME`, or the id given as its path. When the
+/// secret string is a JSON object, the value is its `NAME` field.
+pub struct AwsSecretsManagerProvider {
+    client: Client,
+    prefix: String,
+}
+
+impl AwsSecretsManagerProvider {
+    pub async fn from_env(prefix: &str) -> Self {
+        let config = aws_config::load_from_env().await;
+        Self { client: Client::new(&config), prefix: prefix.to_string() }
+    }
+
+    fn failed(&self, message: impl Into<String>) -> SecretError {
+        SecretError::Provider { provider: self.name(), message: message.into() }
+    }
+}
+
+#[async_trait]
+impl SecretsProvider for AwsSecretsManagerProvider {
+    fn name(&self) -> &'static str {
+        "aws"
+    }
+
+    async fn fetch(&self, secret: &SecretRef) -> Result<SecretValue, SecretError> {
+        let secret_id = secret.path.clone().unwrap_or_else(|| format!("{}{}", self.prefix, secret.name));
+
+        let output = match self.client.get_secret_value().secret_id(&secret_id).send().await {
+            Ok(output) => output,
+            Err(SdkError::ServiceError(e)) if matches!(e.err(), GetSecretValueError::ResourceNotFoundException(_)) => {
+                return Err(SecretError::NotFound(secret.to_string()));
+            }
+            Err(e) => return Err(self.failed(format!("reading {} failed: {}", secret_id, e))),
+        };
+        let raw = SecretValue::new(output.secret_string()
+            .ok_or_else(|| self.failed(format!("{} has no string value", secret_id)))?);
+
+        // Key/value secrets are stored as a JSON object of fields
+        match serde_json::from_str::<Value>(raw.expose()) {
+            Ok(Value::Object(fields)) => match fields.get(&secret.name) {
+                Some(Value::String(value)) => Ok(SecretValue::new(value.as_str())),
+                _ => Err(SecretError::NotFound(secret.to_string())),
+            },
+            _ => Ok(raw),
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use async_trait::async_trait;
+use crate::{SecretError, SecretRef, SecretValue, SecretsProvider};
+
+/// Secrets read from environment variables named after the secret; paths are ignored
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EnvProvider;
+
+#[async_trait]
+impl SecretsProvider for EnvProvider {
+    fn name(&self) -> &'static str {
+        "env"
+    }
+
+    async fn fetch(&self, secret: &SecretRef) -> Result<SecretValue, SecretError> {
+        match std::env::var(&secret.name) {
+            Ok(value) if !value.is_empty() => Ok(SecretValue::new(value)),
+            _ => Err(SecretError::NotFound(secret.name.clone())),
+        }
+    }
+}
@@ -0,0 +1,133 @@
+// Secrets providers for private keys and API credentials
+//
+// Secrets are looked up by name through one configured provider: environment variables
+// (the default, matching earlier releases), HashiCorp Vault KV v2 or AWS Secrets Manager.
+// Values are cached by a SecretStore, and secrets that can safely change while running,
+// such as token signing secrets, are re-fetched on a schedule. Every value is zeroized
+// when its last copy is dropped.
+use async_trait::async_trait;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+mod env;
+pub use env::EnvProvider;
+
+mod vault;
+pub use vault::VaultProvider;
+
+mod aws;
+pub use aws::AwsSecretsManagerProvider;
+
+mod store;
+pub use store::{
+    RotatingSecret,
+    SecretStore,
+    SecretsConfig,
+    ProviderKind,
+};
+
+#[derive(Debug, Error)]
+pub enum SecretError {
+    #[error("Secret {0} not found")]
+    NotFound(String),
+    #[error("Secrets provider {provider} failed: {message}")]
+    Provider { provider: &'static str, message: String },
+    #[error("Invalid secrets configuration: {0}")]
+    Config(String),
+}
+
+/// A secret's value. Debug output is redacted and the memory is zeroized on drop.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretValue(Arc<Zeroizing<String>>);
+
+impl SecretValue {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(Arc::new(Zeroizing::new(value.into())))
+    }
+
+    /// The plaintext, for handing to a signer or HTTP client; never log it
+    pub fn expose(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl fmt::Debug for SecretValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("SecretValue(***)")
+    }
+}
+
+/// Where to find a secret: a name, optionally qualified with a provider-specific path
+/// as `path#NAME`. Unqualified names use the provider's configured default location.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SecretRef {
+    pub path: Option<String>,
+    pub name: String,
+}
+
+impl SecretRef {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { path: None, name: name.into() }
+    }
+
+    pub fn at(path: impl Into<String>, name: impl Into<String>) -> Self {
+        Self { path: Some(path.into()), name: name.into() }
+    }
+}
+
+impl FromStr for SecretRef {
+    type Err = SecretError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let reference = match s.rsplit_once('#') {
+            Some((path, name)) if !path.is_empty() => SecretRef::at(path, name),
+            Some((_, name)) => SecretRef::new(name),
+            None => SecretRef::new(s),
+        };
+        if reference.name.is_empty() {
+            return Err(SecretError::Config(format!("Secret reference {:?} has no name", s)));
+        }
+        Ok(reference)
+    }
+}
+
+impl fmt::Display for SecretRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{}#{}", path, self.name),
+            None => f.write_str(&self.name),
+        }
+    }
+}
+
+/// A source of secret values
+#[async_trait]
+pub trait SecretsProvider: Send + Sync {
+    /// Short name for errors and logs
+    fn name(&self) -> &'static str;
+
+    /// Current value of a secret, read from the source
+    async fn fetch(&self, secret: &SecretRef) -> Result<SecretValue, SecretError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_references_and_redaction() {
+        assert_eq!("JWT_SECRET".parse::<SecretRef>().unwrap(), SecretRef::new("JWT_SECRET"));
+        assert_eq!("quantera/chain#SIGNER_KEY".parse::<SecretRef>().unwrap(), SecretRef::at("quantera/chain", "SIGNER_KEY"));
+        assert_eq!("#KEY".parse::<SecretRef>().unwrap(), SecretRef::new("KEY"));
+        assert!("quantera/chain#".parse::<SecretRef>().is_err());
+        assert_eq!(SecretRef::at("a/b", "C").to_string(), "a/b#C");
+
+        let value = SecretValue::new("0xdeadbeef");
+        assert_eq!(format!("{:?}", value), "SecretValue(***)");
+        assert_eq!(value.expose(), "0xdeadbeef");
+    }
+}
@@ -0,0 +1,303 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock, Weak};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+use crate::{
+    AwsSecretsManagerProvider, EnvProvider, SecretError, SecretRef, SecretValue, SecretsProvider, VaultProvider,
+};
+
+/// Which provider secrets come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderKind {
+    Env,
+    Vault,
+    Aws,
+}
+
+impl std::str::FromStr for ProviderKind {
+    type Err = SecretError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "env" => Ok(ProviderKind::Env),
+            "vault" => Ok(ProviderKind::Vault),
+            "aws" => Ok(ProviderKind::Aws),
+            other => Err(SecretError::Config(format!("Unknown SECRETS_PROVIDER {}", other))),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SecretsConfig {
+    pub provider: ProviderKind,
+    pub vault_address: Option<String>,
+    pub vault_mount: String,
+    pub vault_path: String,
+    pub aws_prefix: String,
+    /// How long a fetched value is served from cache
+    pub cache_ttl: Duration,
+    /// How often rotatable secrets are re-fetched
+    pub refresh_interval: Duration,
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        Self {
+            provider: ProviderKind::Env,
+            vault_address: None,
+            vault_mount: "secret".to_string(),
+            vault_path: "quantera".to_string(),
+            aws_prefix: "quantera/".to_string(),
+            cache_ttl: Duration::from_secs(300),
+            refresh_interval: Duration::from_secs(900),
+        }
+    }
+}
+
+impl SecretsConfig {
+    pub fn from_env() -> Result<Self, SecretError> {
+        let defaults = Self::default();
+        let seconds = |name: &str, default: Duration| -> Result<Duration, SecretError> {
+            match std::env::var(name) {
+                Ok(value) => value.parse::<u64>()
+                    .map(Duration::from_secs)
+                    .map_err(|_| SecretError::Config(format!("{} must be a number of seconds", name))),
+                Err(_) => Ok(default),
+            }
+        };
+
+        Ok(Self {
+            provider: std::env::var("SECRETS_PROVIDER").map(|p| p.parse()).unwrap_or(Ok(ProviderKind::Env))?,
+            vault_address: std::env::var("VAULT_ADDR").ok().filter(|a| !a.is_empty()),
+            vault_mount: std::env::var("VAULT_KV_MOUNT").unwrap_or(defaults.vault_mount),
+            vault_path: std::env::var("VAULT_SECRET_PATH").unwrap_or(defaults.vault_path),
+            aws_prefix: std::env::var("AWS_SECRETS_PREFIX").unwrap_or(defaults.aws_prefix),
+            cache_ttl: seconds("SECRETS_CACHE_TTL_SECS", defaults.cache_ttl)?,
+            refresh_interval: seconds("SECRETS_REFRESH_SECS", defaults.refresh_interval)?,
+        })
+    }
+
+    /// Store over the configured provider. Vault's token is read from `VAULT_TOKEN`.
+    pub async fn build(&self) -> Result<Arc<SecretStore>, SecretError> {
+        let provider: Arc<dyn SecretsProvider> = match self.provider {
+            ProviderKind::Env => Arc::new(EnvProvider),
+            ProviderKind::Vault => {
+                let address = self.vault_address.as_deref()
+                    .ok_or_else(|| SecretError::Config("VAULT_ADDR is required for the vault provider".into()))?;
+                let token = EnvProvider.fetch(&SecretRef::new("VAULT_TOKEN")).await
+                    .map_err(|_| SecretError::Config("VAULT_TOKEN is required for the vault provider".into()))?;
+                std::env::remove_var("VAULT_TOKEN");
+                Arc::new(VaultProvider::new(address, token, &self.vault_mount, &self.vault_path))
+            }
+            ProviderKind::Aws => Arc::new(AwsSecretsManagerProvider::from_env(&self.aws_prefix).await),
+        };
+        info!("Secrets are read from the {} provider", provider.name());
+        Ok(Arc::new(SecretStore::new(provider, self.cache_ttl).with_refresh_interval(self.refresh_interval)))
+    }
+}
+
+/// Cache in front of a provider; also a provider itself, so it can be passed anywhere one is expected
+pub struct SecretStore {
+    provider: Arc<dyn SecretsProvider>,
+    ttl: Duration,
+    refresh_interval: Duration,
+    cache: RwLock<HashMap<SecretRef, (SecretValue, Instant)>>,
+}
+
+impl SecretStore {
+    pub fn new(provider: Arc<dyn SecretsProvider>, ttl: Duration) -> Self {
+        Self {
+            provider,
+            ttl,
+            refresh_interval: SecretsConfig::default().refresh_interval,
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_refresh_interval(mut self, refresh_interval: Duration) -> Self {
+        self.refresh_interval = refresh_interval;
+        self
+    }
+
+    /// A secret, from cache while it is fresh
+    pub async fn get(&self, secret: &SecretRef) -> Result<SecretValue, SecretError> {
+        let cached = self.cache.read().ok()
+            .and_then(|cache| cache.get(secret).filter(|(_, at)| at.elapsed() < self.ttl).map(|(value, _)| value.clone()));
+        if let Some(value) = cached {
+            return Ok(value);
+        }
+        self.refresh(secret).await
+    }
+
+    /// A secret that may legitimately be unset
+    pub async fn get_optional(&self, secret: &SecretRef) -> Result<Option<SecretValue>, SecretError> {
+        match self.get(secret).await {
+            Ok(value) => Ok(Some(value)),
+            Err(SecretError::NotFound(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Read a secret from the provider, bypassing and then updating the cache
+    pub async fn refresh(&self, secret: &SecretRef) -> Result<SecretValue, SecretError> {
+        let value = self.provider.fetch(secret).await?;
+        if let Ok(mut cache) = self.cache.write() {
+            cache.insert(secret.clone(), (value.clone(), Instant::now()));
+        }
+        Ok(value)
+    }
+
+    /// A secret that is re-fetched every refresh interval for as long as the handle lives.
+    ///
+    /// Only use this where a changed value can take effect mid-flight, e.g. an API key or
+    /// a token signing secret; keys that data or on-chain identity depend on are read once.
+    pub async fn rotating(self: &Arc<Self>, secret: SecretRef) -> Result<RotatingSecret, SecretError> {
+        let handle = RotatingSecret::fixed(self.refresh(&secret).await?);
+        let current: Weak<RwLock<SecretValue>> = Arc::downgrade(&handle.current);
+        let store = self.clone();
+        let interval = self.refresh_interval;
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let Some(current) = current.upgrade() else { break };
+                match store.refresh(&secret).await {
+                    Ok(value) => {
+                        let mut slot = current.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+                        if *slot != value {
+                            info!("Secret {} rotated", secret);
+                            *slot = value;
+                        }
+                    }
+                    // Keep serving the last good value
+                    Err(e) => warn!("Re-fetching secret {} failed: {}", secret, e),
+                }
+            }
+        });
+        Ok(handle)
+    }
+}
+
+#[async_trait]
+impl SecretsProvider for SecretStore {
+    fn name(&self) -> &'static str {
+        self.provider.name()
+    }
+
+    async fn fetch(&self, secret: &SecretRef) -> Result<SecretValue, SecretError> {
+        self.get(secret).await
+    }
+}
+
+/// Latest value of a rotatable secret; cheap to clone
+#[derive(Debug, Clone)]
+pub struct RotatingSecret {
+    current: Arc<RwLock<SecretValue>>,
+}
+
+impl RotatingSecret {
+    /// A value that never rotates, e.g. in tests
+    pub fn fixed(value: SecretValue) -> Self {
+        Self { current: Arc::new(RwLock::new(value)) }
+    }
+
+    pub fn current(&self) -> SecretValue {
+        self.current.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        extract::{Path, State},
+        http::{HeaderMap, StatusCode},
+        routing::get,
+        Json, Router,
+    };
+    use serde_json::{json, Value};
+    use std::sync::Mutex;
+
+    type Entries = Arc<Mutex<HashMap<String, HashMap<String, String>>>>;
+
+    /// Minimal Vault KV v2 read endpoint
+    async fn mock_vault(entries: Entries) -> String {
+        async fn read(
+            State(entries): State<Entries>,
+            Path((mount, path)): Path<(String, String)>,
+            headers: HeaderMap,
+        ) -> Result<Json<Value>, StatusCode> {
+            if headers.get("X-Vault-Token").and_then(|t| t.to_str().ok()) != Some("root-token") {
+                return Err(StatusCode::FORBIDDEN);
+            }
+            let entries = entries.lock().unwrap();
+            let data = entries.get(&format!("{}/{}", mount, path)).ok_or(StatusCode::NOT_FOUND)?;
+            Ok(Json(json!({ "data": { "data": data, "metadata": { "version": 1 } } })))
+        }
+
+        let app = Router::new().route("/v1/:mount/data/*path", get(read)).with_state(entries);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        address
+    }
+
+    fn entry(fields: &[(&str, &str)]) -> HashMap<String, String> {
+        fields.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[tokio::test]
+    async fn test_vault_kv_v2_lookups() {
+        let entries: Entries = Arc::default();
+        entries.lock().unwrap().insert("secret/quantera".into(), entry(&[("JWT_SECRET", "signing-secret")]));
+        entries.lock().unwrap().insert("secret/quantera/chain".into(), entry(&[("SIGNER_KEY", "0xabc")]));
+        let address = mock_vault(entries).await;
+
+        let vault = VaultProvider::new(&address, SecretValue::new("root-token"), "secret", "quantera");
+        assert_eq!(vault.fetch(&SecretRef::new("JWT_SECRET")).await.unwrap().expose(), "signing-secret");
+        assert_eq!(vault.fetch(&"quantera/chain#SIGNER_KEY".parse().unwrap()).await.unwrap().expose(), "0xabc");
+        assert!(matches!(vault.fetch(&SecretRef::new("MISSING")).await, Err(SecretError::NotFound(_))));
+        assert!(matches!(vault.fetch(&SecretRef::at("nowhere", "KEY")).await, Err(SecretError::NotFound(_))));
+
+        let wrong_token = VaultProvider::new(&address, SecretValue::new("stale"), "secret", "quantera");
+        assert!(matches!(
+            wrong_token.fetch(&SecretRef::new("JWT_SECRET")).await,
+            Err(SecretError::Provider { provider: "vault", .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rotation_takes_effect_without_restart() {
+        let entries: Entries = Arc::default();
+        entries.lock().unwrap().insert("secret/quantera".into(), entry(&[("KYC_API_KEY", "key-1")]));
+        let address = mock_vault(entries.clone()).await;
+
+        let vault = Arc::new(VaultProvider::new(&address, SecretValue::new("root-token"), "secret", "quantera"));
+        let store = Arc::new(SecretStore::new(vault, Duration::from_secs(3600)).with_refresh_interval(Duration::from_millis(50)));
+        let key = SecretRef::new("KYC_API_KEY");
+        let rotating = store.rotating(key.clone()).await.unwrap();
+        assert_eq!(rotating.current().expose(), "key-1");
+
+        entries.lock().unwrap().insert("secret/quantera".into(), entry(&[("KYC_API_KEY", "key-2")]));
+        // One-shot reads are served from cache until it expires
+        assert_eq!(store.get(&key).await.unwrap().expose(), "key-1");
+
+        let mut rotated = false;
+        for _ in 0..40 {
+            tokio::time::sleep(Duration::from_millis(25)).await;
+            if rotating.current().expose() == "key-2" {
+                rotated = true;
+                break;
+            }
+        }
+        assert!(rotated, "rotating secret never picked up the new value");
+
+        // A failed re-fetch keeps the last good value
+        entries.lock().unwrap().clear();
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(rotating.current().expose(), "key-2");
+    }
+}
@@ -0,0 +1,79 @@
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+use crate::{SecretError, SecretRef, SecretValue, SecretsProvider};
+
+/// HashiCorp Vault KV version 2, authenticated with a token.
+///
+/// A secret named `NAME` at path `p` is the field `NAME` of the KV entry `p`; unqualified
+/// names are read from the default path.
+pub struct VaultProvider {
+    client: reqwest::Client,
+    address: String,
+    token: SecretValue,
+    mount: String,
+    default_path: String,
+}
+
+#[derive(Deserialize)]
+struct KvResponse {
+    data: KvData,
+}
+
+#[derive(Deserialize)]
+struct KvData {
+    data: HashMap<String, Value>,
+}
+
+impl VaultProvider {
+    pub fn new(address: &str, token: SecretValue, mount: &str, default_path: &str) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            address: address.trim_end_matches('/').to_string(),
+            token,
+            mount: mount.trim_matches('/').to_string(),
+            default_path: default_path.trim_matches('/').to_string(),
+        }
+    }
+
+    fn failed(&self, message: impl Into<String>) -> SecretError {
+        SecretError::Provider { provider: self.name(), message: message.into() }
+    }
+}
+
+#[async_trait]
+impl SecretsProvider for VaultProvider {
+    fn name(&self) -> &'static str {
+        "vault"
+    }
+
+    async fn fetch(&self, secret: &SecretRef) -> Result<SecretValue, SecretError> {
+        let path = secret.path.as_deref().map(|p| p.trim_matches('/')).unwrap_or(&self.default_path);
+        let url = format!("{}/v1/{}/data/{}", self.address, self.mount, path);
+
+        let response = self.client.get(&url)
+            .header("X-Vault-Token", self.token.expose())
+            .send()
+            .await
+            .map_err(|e| self.failed(format!("request for {} failed: {}", path, e)))?;
+        match response.status() {
+            StatusCode::NOT_FOUND => return Err(SecretError::NotFound(secret.to_string())),
+            status if !status.is_success() => return Err(self.failed(format!("reading {} returned {}", path, status))),
+            _ => {}
+        }
+
+        let body: KvResponse = response.json().await
+            .map_err(|e| self.failed(format!("unexpected response for {}: {}", path, e)))?;
+        match body.data.data.get(&secret.name) {
+            Some(Value::String(value)) => Ok(SecretValue::new(value.as_str())),
+            Some(_) => Err(self.failed(format!("{} is not a string", secret))),
+            None => Err(SecretError::NotFound(secret.to_string())),
+        }
+    }
+}
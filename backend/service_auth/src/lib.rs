@@ -0,0 +1,388 @@
+// Scoped API keys for calls between Quantera services
+//
+// A calling service presents its key in the X-Service-Key header, separately from the
+// user JWTs in Authorization. Each key carries the scopes it may use and its own request
+// rate limit, and only a SHA-256 hash of its secret is stored. Rotation issues a new key
+// while the previous one keeps working for a grace period, so a service has at most two
+// live keys and can switch over without downtime.
+use chrono::{DateTime, Duration, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+use tracing::info;
+use uuid::Uuid;
+
+mod store;
+pub use store::{InMemoryServiceKeyStore, PgServiceKeyStore, ServiceCall, ServiceKeyStore};
+
+mod middleware;
+pub use middleware::{require_service_key, ServiceGuard};
+
+/// Header carrying a service key
+pub const SERVICE_KEY_HEADER: &str = "x-service-key";
+
+/// Live keys a service may hold at once: the current one and the one being rotated out
+pub const MAX_LIVE_KEYS: usize = 2;
+
+pub const DEFAULT_RATE_LIMIT_PER_MINUTE: u32 = 600;
+
+const KEY_PREFIX: &str = "qsk_";
+
+/// Permission a service key may carry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Scope {
+    /// Portfolio risk metrics, alerts and scenario analysis
+    #[serde(rename = "risk:read")]
+    RiskRead,
+    /// Risk service administration: factor models, stablecoins, price reviews
+    #[serde(rename = "risk:admin")]
+    RiskAdmin,
+    /// Sanctions screening and bulk re-screening
+    #[serde(rename = "compliance:screen")]
+    ComplianceScreen,
+    #[serde(rename = "compliance:read")]
+    ComplianceRead,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 4] = [Scope::RiskRead, Scope::RiskAdmin, Scope::ComplianceScreen, Scope::ComplianceRead];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::RiskRead => "risk:read",
+            Scope::RiskAdmin => "risk:admin",
+            Scope::ComplianceScreen => "compliance:screen",
+            Scope::ComplianceRead => "compliance:read",
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Scope {
+    type Err = ServiceAuthError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Scope::ALL.into_iter()
+            .find(|scope| scope.as_str() == s)
+            .ok_or_else(|| ServiceAuthError::InvalidRequest(format!("Unknown scope {}", s)))
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ServiceAuthError {
+    /// Missing, malformed, unknown, revoked or expired key
+    #[error("Invalid service key")]
+    Unauthenticated,
+    #[error("Service {service} lacks the {scope} scope")]
+    Forbidden { service: String, scope: Scope },
+    #[error("Rate limit of service {service} exceeded")]
+    RateLimited { service: String, retry_after_secs: i64 },
+    #[error("{0}")]
+    InvalidRequest(String),
+    #[error("Service key {0} not found")]
+    NotFound(String),
+    #[error("Service key store error: {0}")]
+    Store(String),
+}
+
+/// A stored service key; the secret itself is never kept
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceKey {
+    pub key_id: Uuid,
+    pub service: String,
+    pub scopes: Vec<Scope>,
+    #[serde(skip)]
+    pub secret_hash: String,
+    pub rate_limit_per_minute: u32,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    /// Set on the previous key when it is rotated out
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl ServiceKey {
+    pub fn is_live(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at.map_or(true, |expires_at| now < expires_at)
+    }
+
+    pub fn allows(&self, scope: Scope) -> bool {
+        self.scopes.contains(&scope)
+    }
+}
+
+/// A newly issued key; `api_key` is shown once and cannot be recovered
+#[derive(Debug, Clone, Serialize)]
+pub struct IssuedKey {
+    #[serde(flatten)]
+    pub key: ServiceKey,
+    pub api_key: String,
+}
+
+/// The service behind an authenticated request
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServiceCaller {
+    pub service: String,
+    pub key_id: Uuid,
+    pub scopes: Vec<Scope>,
+}
+
+fn hash_secret(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+/// Split `qsk_<key id>.<secret>` into its parts
+fn parse_key(presented: &str) -> Option<(Uuid, &str)> {
+    let (key_id, secret) = presented.strip_prefix(KEY_PREFIX)?.split_once('.')?;
+    if secret.is_empty() {
+        return None;
+    }
+    Some((Uuid::parse_str(key_id).ok()?, secret))
+}
+
+/// Compare hashes without exiting at the first difference
+fn hashes_match(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Requests per key in the current one-minute window
+#[derive(Debug, Default)]
+struct RateWindows {
+    windows: Mutex<HashMap<Uuid, (DateTime<Utc>, u32)>>,
+}
+
+impl RateWindows {
+    /// Seconds until the window resets when the key is over its limit
+    fn check(&self, key_id: Uuid, limit: u32, now: DateTime<Utc>) -> Result<(), i64> {
+        let mut windows = self.windows.lock().expect("rate window lock poisoned");
+        let (started, count) = windows.entry(key_id).or_insert((now, 0));
+        if now - *started >= Duration::minutes(1) {
+            *started = now;
+            *count = 0;
+        }
+        if *count >= limit {
+            return Err((*started + Duration::minutes(1) - now).num_seconds().max(1));
+        }
+        *count += 1;
+        Ok(())
+    }
+}
+
+/// Issues, rotates, revokes and checks service keys
+pub struct ServiceKeys {
+    store: Arc<dyn ServiceKeyStore>,
+    rate_windows: RateWindows,
+}
+
+impl ServiceKeys {
+    pub fn new(store: Arc<dyn ServiceKeyStore>) -> Self {
+        Self { store, rate_windows: RateWindows::default() }
+    }
+
+    pub fn store(&self) -> Arc<dyn ServiceKeyStore> {
+        self.store.clone()
+    }
+
+    fn new_key(service: &str, scopes: Vec<Scope>, rate_limit_per_minute: u32, created_by: &str, now: DateTime<Utc>) -> IssuedKey {
+        let mut secret = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut secret);
+        let secret = hex::encode(secret);
+        let key_id = Uuid::new_v4();
+        IssuedKey {
+            api_key: format!("{}{}.{}", KEY_PREFIX, key_id.simple(), secret),
+            key: ServiceKey {
+                key_id,
+                service: service.to_string(),
+                scopes,
+                secret_hash: hash_secret(&secret),
+                rate_limit_per_minute,
+                created_by: created_by.to_string(),
+                created_at: now,
+                expires_at: None,
+                revoked_at: None,
+            },
+        }
+    }
+
+    async fn live_keys(&self, service: &str, now: DateTime<Utc>) -> Result<Vec<ServiceKey>, ServiceAuthError> {
+        let mut keys: Vec<ServiceKey> = self.store.keys_for(Some(service)).await?
+            .into_iter()
+            .filter(|key| key.is_live(now))
+            .collect();
+        keys.sort_by_key(|key| key.created_at);
+        Ok(keys)
+    }
+
+    /// First key of a service; later keys come from [`ServiceKeys::rotate`]
+    pub async fn issue(
+        &self,
+        service: &str,
+        scopes: Vec<Scope>,
+        rate_limit_per_minute: u32,
+        created_by: &str,
+        now: DateTime<Utc>,
+    ) -> Result<IssuedKey, ServiceAuthError> {
+        let service = service.trim();
+        if service.is_empty() {
+            return Err(ServiceAuthError::InvalidRequest("A service name is required".to_string()));
+        }
+        if scopes.is_empty() {
+            return Err(ServiceAuthError::InvalidRequest("A service key needs at least one scope".to_string()));
+        }
+        if rate_limit_per_minute == 0 {
+            return Err(ServiceAuthError::InvalidRequest("The rate limit must be at least 1 request per minute".to_string()));
+        }
+        if !self.live_keys(service, now).await?.is_empty() {
+            return Err(ServiceAuthError::InvalidRequest(format!("{} already has a live key; rotate it instead", service)));
+        }
+
+        let mut scopes = scopes;
+        scopes.sort();
+        scopes.dedup();
+        let issued = Self::new_key(service, scopes, rate_limit_per_minute, created_by, now);
+        self.store.insert(&issued.key).await?;
+        info!("Issued service key {} for {} by {}", issued.key.key_id, service, created_by);
+        Ok(issued)
+    }
+
+    /// New key for a service with the scopes and rate limit of its current one.
+    ///
+    /// The current key keeps working for `grace`, then expires; any older key still live
+    /// is revoked at once, so the service never holds more than [`MAX_LIVE_KEYS`].
+    pub async fn rotate(&self, service: &str, grace: Duration, created_by: &str, now: DateTime<Utc>) -> Result<IssuedKey, ServiceAuthError> {
+        let live = self.live_keys(service, now).await?;
+        let current = live.last().ok_or_else(|| ServiceAuthError::NotFound(service.to_string()))?;
+
+        let issued = Self::new_key(service, current.scopes.clone(), current.rate_limit_per_minute, created_by, now);
+        self.store.insert(&issued.key).await?;
+
+        let grace_ends = now + grace;
+        if current.expires_at.map_or(true, |expires_at| grace_ends < expires_at) {
+            self.store.set_expiry(current.key_id, grace_ends).await?;
+        }
+        for older in &live[..live.len().saturating_sub(MAX_LIVE_KEYS - 1)] {
+            self.store.revoke(older.key_id, now).await?;
+        }
+        info!("Rotated service key of {} to {} by {}; {} expires at {}", service, issued.key.key_id, created_by, current.key_id, grace_ends);
+        Ok(issued)
+    }
+
+    pub async fn revoke(&self, key_id: Uuid, now: DateTime<Utc>) -> Result<ServiceKey, ServiceAuthError> {
+        let key = self.store.find(key_id).await?.ok_or_else(|| ServiceAuthError::NotFound(key_id.to_string()))?;
+        if key.revoked_at.is_none() {
+            self.store.revoke(key_id, now).await?;
+        }
+        info!("Revoked service key {} of {}", key_id, key.service);
+        Ok(ServiceKey { revoked_at: key.revoked_at.or(Some(now)), ..key })
+    }
+
+    /// Every key, live or not; `service` narrows them to one service
+    pub async fn list(&self, service: Option<&str>) -> Result<Vec<ServiceKey>, ServiceAuthError> {
+        self.store.keys_for(service).await
+    }
+
+    /// The service presenting `api_key`, if the key is live, carries `scope` and is within its rate limit
+    pub async fn authenticate(&self, api_key: &str, scope: Scope, now: DateTime<Utc>) -> Result<ServiceCaller, ServiceAuthError> {
+        let (key_id, secret) = parse_key(api_key).ok_or(ServiceAuthError::Unauthenticated)?;
+        let key = self.store.find(key_id).await?.ok_or(ServiceAuthError::Unauthenticated)?;
+        if !hashes_match(&key.secret_hash, &hash_secret(secret)) || !key.is_live(now) {
+            return Err(ServiceAuthError::Unauthenticated);
+        }
+        if !key.allows(scope) {
+            return Err(ServiceAuthError::Forbidden { service: key.service, scope });
+        }
+        self.rate_windows.check(key_id, key.rate_limit_per_minute, now)
+            .map_err(|retry_after_secs| ServiceAuthError::RateLimited { service: key.service.clone(), retry_after_secs })?;
+
+        Ok(ServiceCaller { service: key.service, key_id, scopes: key.scopes })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys() -> ServiceKeys {
+        ServiceKeys::new(Arc::new(InMemoryServiceKeyStore::default()))
+    }
+
+    #[tokio::test]
+    async fn test_scopes_are_enforced() {
+        let keys = keys();
+        let now = Utc::now();
+        let issued = keys.issue("treasury_service", vec![Scope::RiskRead], 100, "admin", now).await.unwrap();
+
+        let caller = keys.authenticate(&issued.api_key, Scope::RiskRead, now).await.unwrap();
+        assert_eq!(caller.service, "treasury_service");
+        assert_eq!(
+            keys.authenticate(&issued.api_key, Scope::ComplianceScreen, now).await,
+            Err(ServiceAuthError::Forbidden { service: "treasury_service".to_string(), scope: Scope::ComplianceScreen }),
+        );
+
+        // A wrong secret for a real key id, a malformed key and a revoked key are all refused alike
+        let forged = format!("{}.{}", issued.api_key.rsplit_once('.').unwrap().0, "0".repeat(64));
+        assert_eq!(keys.authenticate(&forged, Scope::RiskRead, now).await, Err(ServiceAuthError::Unauthenticated));
+        assert_eq!(keys.authenticate("Bearer abc", Scope::RiskRead, now).await, Err(ServiceAuthError::Unauthenticated));
+        keys.revoke(issued.key.key_id, now).await.unwrap();
+        assert_eq!(keys.authenticate(&issued.api_key, Scope::RiskRead, now).await, Err(ServiceAuthError::Unauthenticated));
+
+        assert!("compliance:screen".parse::<Scope>().is_ok());
+        assert!("compliance:write".parse::<Scope>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rotation_keeps_two_live_keys() {
+        let keys = keys();
+        let now = Utc::now();
+        let first = keys.issue("risk_service", vec![Scope::ComplianceScreen], 100, "admin", now).await.unwrap();
+        assert!(matches!(
+            keys.issue("risk_service", vec![Scope::ComplianceScreen], 100, "admin", now).await,
+            Err(ServiceAuthError::InvalidRequest(_))
+        ));
+
+        // Both keys work during the grace period, only the new one after it
+        let now = now + Duration::minutes(1);
+        let second = keys.rotate("risk_service", Duration::hours(1), "admin", now).await.unwrap();
+        assert_eq!(second.key.scopes, vec![Scope::ComplianceScreen]);
+        assert!(keys.authenticate(&first.api_key, Scope::ComplianceScreen, now).await.is_ok());
+        assert!(keys.authenticate(&second.api_key, Scope::ComplianceScreen, now).await.is_ok());
+        let later = now + Duration::hours(2);
+        assert_eq!(keys.authenticate(&first.api_key, Scope::ComplianceScreen, later).await, Err(ServiceAuthError::Unauthenticated));
+        assert!(keys.authenticate(&second.api_key, Scope::ComplianceScreen, later).await.is_ok());
+
+        // Rotating again within the grace period retires the oldest key at once
+        let now = now + Duration::minutes(1);
+        let third = keys.rotate("risk_service", Duration::hours(1), "admin", now).await.unwrap();
+        assert_eq!(keys.authenticate(&first.api_key, Scope::ComplianceScreen, now).await, Err(ServiceAuthError::Unauthenticated));
+        assert!(keys.authenticate(&second.api_key, Scope::ComplianceScreen, now).await.is_ok());
+        assert!(keys.authenticate(&third.api_key, Scope::ComplianceScreen, now).await.is_ok());
+        assert_eq!(keys.live_keys("risk_service", now).await.unwrap().len(), MAX_LIVE_KEYS);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_is_per_key() {
+        let keys = keys();
+        let now = Utc::now();
+        let limited = keys.issue("reporting", vec![Scope::RiskRead], 2, "admin", now).await.unwrap();
+        let other = keys.issue("treasury_service", vec![Scope::RiskRead], 2, "admin", now).await.unwrap();
+
+        assert!(keys.authenticate(&limited.api_key, Scope::RiskRead, now).await.is_ok());
+        assert!(keys.authenticate(&limited.api_key, Scope::RiskRead, now).await.is_ok());
+        assert!(matches!(
+            keys.authenticate(&limited.api_key, Scope::RiskRead, now).await,
+            Err(ServiceAuthError::RateLimited { retry_after_secs: 60, .. })
+        ));
+        assert!(keys.authenticate(&other.api_key, Scope::RiskRead, now).await.is_ok());
+        assert!(keys.authenticate(&limited.api_key, Scope::RiskRead, now + Duration::minutes(1)).await.is_ok());
+    }
+}
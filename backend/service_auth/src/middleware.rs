@@ -0,0 +1,125 @@
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Utc;
+use quantera_types::ErrorEnvelope;
+use std::sync::Arc;
+use tracing::warn;
+use crate::{Scope, ServiceAuthError, ServiceCall, ServiceKeys, SERVICE_KEY_HEADER};
+
+/// Route layer state: the scope a route needs and whether a service key is mandatory.
+///
+/// When not `required`, requests without an X-Service-Key header pass through untouched so
+/// the route's own authentication (user JWTs) still applies; a key that is presented is
+/// always checked.
+#[derive(Clone)]
+pub struct ServiceGuard {
+    keys: Arc<ServiceKeys>,
+    scope: Scope,
+    required: bool,
+}
+
+impl ServiceGuard {
+    pub fn new(keys: Arc<ServiceKeys>, scope: Scope, required: bool) -> Self {
+        Self { keys, scope, required }
+    }
+}
+
+fn rejection(error: ServiceAuthError) -> Response {
+    let envelope = match &error {
+        ServiceAuthError::Unauthenticated => ErrorEnvelope::unauthorized(),
+        ServiceAuthError::Forbidden { .. } => ErrorEnvelope::new("FORBIDDEN", &error.to_string(), 403),
+        ServiceAuthError::RateLimited { .. } => ErrorEnvelope::new("RATE_LIMITED", &error.to_string(), 429),
+        other => {
+            warn!("Service key check failed: {}", other);
+            ErrorEnvelope::new("INTERNAL_ERROR", "Service key check failed", 500)
+        }
+    };
+    let status = StatusCode::from_u16(envelope.code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let mut response = (status, Json(envelope)).into_response();
+    if let ServiceAuthError::RateLimited { retry_after_secs, .. } = error {
+        if let Ok(value) = HeaderValue::from_str(&retry_after_secs.to_string()) {
+            response.headers_mut().insert(header::RETRY_AFTER, value);
+        }
+    }
+    response
+}
+
+/// Authenticate the X-Service-Key header, expose the [`crate::ServiceCaller`] as a request
+/// extension and audit the call under the calling service
+pub async fn require_service_key(State(guard): State<ServiceGuard>, mut req: Request, next: Next) -> Response {
+    let Some(presented) = req.headers().get(SERVICE_KEY_HEADER) else {
+        if guard.required {
+            return rejection(ServiceAuthError::Unauthenticated);
+        }
+        return next.run(req).await;
+    };
+    let Ok(presented) = presented.to_str() else {
+        return rejection(ServiceAuthError::Unauthenticated);
+    };
+
+    let caller = match guard.keys.authenticate(presented, guard.scope, Utc::now()).await {
+        Ok(caller) => caller,
+        Err(e) => return rejection(e),
+    };
+    let method = req.method().to_string();
+    let path = req.uri().path().to_string();
+    req.extensions_mut().insert(caller.clone());
+
+    let response = next.run(req).await;
+    let call = ServiceCall {
+        key_id: caller.key_id,
+        service: caller.service,
+        scope: guard.scope,
+        method,
+        path,
+        status: response.status().as_u16(),
+        called_at: Utc::now(),
+    };
+    if let Err(e) = guard.keys.store().record_call(&call).await {
+        warn!("Failed to audit {} {} by service {}: {}", call.method, call.path, call.service, e);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InMemoryServiceKeyStore, ServiceCaller};
+    use axum::{body::Body, middleware, routing::get, Extension, Router};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_guard_checks_scope_and_audits_caller() {
+        let store = Arc::new(InMemoryServiceKeyStore::default());
+        let keys = Arc::new(ServiceKeys::new(store.clone()));
+        let reader = keys.issue("reporting", vec![Scope::RiskRead], 10, "admin", Utc::now()).await.unwrap();
+        let screener = keys.issue("onboarding", vec![Scope::ComplianceScreen], 10, "admin", Utc::now()).await.unwrap();
+
+        let app = |required| Router::new()
+            .route("/risk", get(|Extension(caller): Extension<ServiceCaller>| async move { caller.service }))
+            .route_layer(middleware::from_fn_with_state(ServiceGuard::new(keys.clone(), Scope::RiskRead, required), require_service_key));
+        let call = |key: Option<&str>| {
+            let mut req = Request::builder().uri("/risk");
+            if let Some(key) = key {
+                req = req.header(SERVICE_KEY_HEADER, key);
+            }
+            req.body(Body::empty()).unwrap()
+        };
+
+        let ok = app(true).oneshot(call(Some(&reader.api_key))).await.unwrap();
+        assert_eq!(ok.status(), StatusCode::OK);
+        let wrong_scope = app(true).oneshot(call(Some(&screener.api_key))).await.unwrap();
+        assert_eq!(wrong_scope.status(), StatusCode::FORBIDDEN);
+        let missing = app(true).oneshot(call(None)).await.unwrap();
+        assert_eq!(missing.status(), StatusCode::UNAUTHORIZED);
+
+        let calls = store.calls();
+        assert_eq!(calls.len(), 1);
+        assert_eq!((calls[0].service.as_str(), calls[0].path.as_str(), calls[0].status), ("reporting", "/risk", 200));
+    }
+}
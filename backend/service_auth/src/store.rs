@@ -0,0 +1,206 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::sync::Mutex;
+use uuid::Uuid;
+use crate::{Scope, ServiceAuthError, ServiceKey};
+
+/// One request made with a service key, attributed to the calling service
+#[derive(Debug, Clone)]
+pub struct ServiceCall {
+    pub key_id: Uuid,
+    pub service: String,
+    pub scope: Scope,
+    pub method: String,
+    pub path: String,
+    pub status: u16,
+    pub called_at: DateTime<Utc>,
+}
+
+/// Where service keys and their call audit are kept
+#[async_trait]
+pub trait ServiceKeyStore: Send + Sync {
+    async fn insert(&self, key: &ServiceKey) -> Result<(), ServiceAuthError>;
+
+    async fn find(&self, key_id: Uuid) -> Result<Option<ServiceKey>, ServiceAuthError>;
+
+    /// Keys in creation order; `None` returns every service's keys
+    async fn keys_for(&self, service: Option<&str>) -> Result<Vec<ServiceKey>, ServiceAuthError>;
+
+    async fn set_expiry(&self, key_id: Uuid, expires_at: DateTime<Utc>) -> Result<(), ServiceAuthError>;
+
+    async fn revoke(&self, key_id: Uuid, revoked_at: DateTime<Utc>) -> Result<(), ServiceAuthError>;
+
+    async fn record_call(&self, call: &ServiceCall) -> Result<(), ServiceAuthError>;
+}
+
+impl From<sqlx::Error> for ServiceAuthError {
+    fn from(e: sqlx::Error) -> Self {
+        ServiceAuthError::Store(e.to_string())
+    }
+}
+
+/// Keys in `service_api_keys`, calls in `service_call_audit`; shared by every service on the database
+pub struct PgServiceKeyStore {
+    db: PgPool,
+}
+
+impl PgServiceKeyStore {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+}
+
+type KeyRow = (Uuid, String, Vec<String>, String, i32, String, DateTime<Utc>, Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+const KEY_COLUMNS: &str =
+    "key_id, service, scopes, secret_hash, rate_limit_per_minute, created_by, created_at, expires_at, revoked_at";
+
+fn from_row(row: KeyRow) -> Result<ServiceKey, ServiceAuthError> {
+    let (key_id, service, scopes, secret_hash, rate_limit_per_minute, created_by, created_at, expires_at, revoked_at) = row;
+    Ok(ServiceKey {
+        key_id,
+        service,
+        scopes: scopes.iter()
+            .map(|scope| scope.parse())
+            .collect::<Result<_, _>>()
+            .map_err(|e: ServiceAuthError| ServiceAuthError::Store(format!("Stored key {}: {}", key_id, e)))?,
+        secret_hash,
+        rate_limit_per_minute: u32::try_from(rate_limit_per_minute).unwrap_or(0),
+        created_by,
+        created_at,
+        expires_at,
+        revoked_at,
+    })
+}
+
+#[async_trait]
+impl ServiceKeyStore for PgServiceKeyStore {
+    async fn insert(&self, key: &ServiceKey) -> Result<(), ServiceAuthError> {
+        sqlx::query(
+            "INSERT INTO service_api_keys
+                (key_id, service, scopes, secret_hash, rate_limit_per_minute, created_by, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)"
+        )
+        .bind(key.key_id)
+        .bind(&key.service)
+        .bind(key.scopes.iter().map(|scope| scope.as_str().to_string()).collect::<Vec<_>>())
+        .bind(&key.secret_hash)
+        .bind(i32::try_from(key.rate_limit_per_minute).unwrap_or(i32::MAX))
+        .bind(&key.created_by)
+        .bind(key.created_at)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn find(&self, key_id: Uuid) -> Result<Option<ServiceKey>, ServiceAuthError> {
+        let row: Option<KeyRow> = sqlx::query_as(&format!("SELECT {} FROM service_api_keys WHERE key_id = $1", KEY_COLUMNS))
+            .bind(key_id)
+            .fetch_optional(&self.db)
+            .await?;
+        row.map(from_row).transpose()
+    }
+
+    async fn keys_for(&self, service: Option<&str>) -> Result<Vec<ServiceKey>, ServiceAuthError> {
+        let rows: Vec<KeyRow> = sqlx::query_as(&format!(
+            "SELECT {} FROM service_api_keys WHERE ($1::text IS NULL OR service = $1) ORDER BY created_at, key_id",
+            KEY_COLUMNS
+        ))
+        .bind(service)
+        .fetch_all(&self.db)
+        .await?;
+        rows.into_iter().map(from_row).collect()
+    }
+
+    async fn set_expiry(&self, key_id: Uuid, expires_at: DateTime<Utc>) -> Result<(), ServiceAuthError> {
+        sqlx::query("UPDATE service_api_keys SET expires_at = $2 WHERE key_id = $1")
+            .bind(key_id)
+            .bind(expires_at)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn revoke(&self, key_id: Uuid, revoked_at: DateTime<Utc>) -> Result<(), ServiceAuthError> {
+        sqlx::query("UPDATE service_api_keys SET revoked_at = $2 WHERE key_id = $1 AND revoked_at IS NULL")
+            .bind(key_id)
+            .bind(revoked_at)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn record_call(&self, call: &ServiceCall) -> Result<(), ServiceAuthError> {
+        sqlx::query(
+            "INSERT INTO service_call_audit (key_id, service, scope, method, path, status, called_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)"
+        )
+        .bind(call.key_id)
+        .bind(&call.service)
+        .bind(call.scope.as_str())
+        .bind(&call.method)
+        .bind(&call.path)
+        .bind(i32::from(call.status))
+        .bind(call.called_at)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+}
+
+/// Keys and calls held in memory, for tests and single-process deployments
+#[derive(Default)]
+pub struct InMemoryServiceKeyStore {
+    keys: Mutex<Vec<ServiceKey>>,
+    calls: Mutex<Vec<ServiceCall>>,
+}
+
+impl InMemoryServiceKeyStore {
+    pub fn calls(&self) -> Vec<ServiceCall> {
+        self.calls.lock().expect("service call lock poisoned").clone()
+    }
+
+    fn update(&self, key_id: Uuid, change: impl FnOnce(&mut ServiceKey)) {
+        if let Some(key) = self.keys.lock().expect("service key lock poisoned").iter_mut().find(|key| key.key_id == key_id) {
+            change(key);
+        }
+    }
+}
+
+#[async_trait]
+impl ServiceKeyStore for InMemoryServiceKeyStore {
+    async fn insert(&self, key: &ServiceKey) -> Result<(), ServiceAuthError> {
+        self.keys.lock().expect("service key lock poisoned").push(key.clone());
+        Ok(())
+    }
+
+    async fn find(&self, key_id: Uuid) -> Result<Option<ServiceKey>, ServiceAuthError> {
+        Ok(self.keys.lock().expect("service key lock poisoned").iter().find(|key| key.key_id == key_id).cloned())
+    }
+
+    async fn keys_for(&self, service: Option<&str>) -> Result<Vec<ServiceKey>, ServiceAuthError> {
+        Ok(self.keys.lock().expect("service key lock poisoned")
+            .iter()
+            .filter(|key| service.map_or(true, |service| key.service == service))
+            .cloned()
+            .collect())
+    }
+
+    async fn set_expiry(&self, key_id: Uuid, expires_at: DateTime<Utc>) -> Result<(), ServiceAuthError> {
+        self.update(key_id, |key| key.expires_at = Some(expires_at));
+        Ok(())
+    }
+
+    async fn revoke(&self, key_id: Uuid, revoked_at: DateTime<Utc>) -> Result<(), ServiceAuthError> {
+        self.update(key_id, |key| {
+            key.revoked_at.get_or_insert(revoked_at);
+        });
+        Ok(())
+    }
+
+    async fn record_call(&self, call: &ServiceCall) -> Result<(), ServiceAuthError> {
+        self.calls.lock().expect("service call lock poisoned").push(call.clone());
+        Ok(())
+    }
+}
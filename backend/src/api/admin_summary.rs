@@ -0,0 +1,293 @@
+// Dashboard summary aggregated from the asset, compliance, risk and prime brokerage sources
+use chrono::{DateTime, Utc};
+use price_oracle::{FxRateService, ReportingTotal};
+use quantera_types::Currency;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+use crate::compliance::enhanced_compliance_engine::{ComplianceCheckCounts, EnhancedComplianceEngine};
+use crate::feature_flags::{FeatureFlags, FlagStatus};
+use crate::services::multi_chain_asset_service::MultiChainAssetService;
+use crate::services::prime_brokerage_service::PrimeBrokerageService;
+use crate::task_health::{TaskHealth, TaskStatus};
+use crate::tenant::TenantScope;
+
+/// How long a computed summary is served before the sources are queried again
+pub const SUMMARY_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// How long each source may take before its section is reported as timed out
+pub const SOURCE_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionStatus {
+    Ok,
+    Timeout,
+    Error,
+}
+
+/// One source's part of the summary; `data` is absent when the source failed
+#[derive(Debug, Clone, Serialize)]
+pub struct SummarySection<T> {
+    pub status: SectionStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl<T> SummarySection<T> {
+    /// Run a source with the per-source timeout
+    pub async fn collect<F>(source: F, timeout: Duration) -> Self
+    where
+        F: Future<Output = Result<T, String>>,
+    {
+        match tokio::time::timeout(timeout, source).await {
+            Ok(Ok(data)) => Self { status: SectionStatus::Ok, data: Some(data), error: None },
+            Ok(Err(e)) => Self { status: SectionStatus::Error, data: None, error: Some(e) },
+            Err(_) => Self {
+                status: SectionStatus::Timeout,
+                data: None,
+                error: Some(format!("No response within {}ms", timeout.as_millis())),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetSummary {
+    /// Assets under management in the requested reporting currency, with the rates applied
+    pub aum: ReportingTotal,
+    pub treasuries_by_status: HashMap<String, usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RiskAlertSummary {
+    pub open_by_severity: HashMap<String, i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MarginSummary {
+    pub open_margin_calls: usize,
+    pub margin_calls_24h: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AdminSummary {
+    pub generated_at: DateTime<Utc>,
+    pub assets: SummarySection<AssetSummary>,
+    /// Checks and violations since midnight UTC
+    pub compliance_today: SummarySection<ComplianceCheckCounts>,
+    pub risk_alerts: SummarySection<RiskAlertSummary>,
+    pub margin: SummarySection<MarginSummary>,
+    pub background_tasks: Vec<TaskStatus>,
+    pub feature_flags: Vec<FlagStatus>,
+}
+
+/// Query every source concurrently; a slow or failing source only affects its own section
+pub async fn build_summary<A, C, R, M>(
+    assets: A,
+    compliance: C,
+    risk_alerts: R,
+    margin: M,
+    tasks: &TaskHealth,
+    flags: &FeatureFlags,
+    timeout: Duration,
+) -> AdminSummary
+where
+    A: Future<Output = Result<AssetSummary, String>>,
+    C: Future<Output = Result<ComplianceCheckCounts, String>>,
+    R: Future<Output = Result<RiskAlertSummary, String>>,
+    M: Future<Output = Result<MarginSummary, String>>,
+{
+    let (assets, compliance_today, risk_alerts, margin) = tokio::join!(
+        SummarySection::collect(assets, timeout),
+        SummarySection::collect(compliance, timeout),
+        SummarySection::collect(risk_alerts, timeout),
+        SummarySection::collect(margin, timeout),
+    );
+
+    AdminSummary {
+        generated_at: Utc::now(),
+        assets,
+        compliance_today,
+        risk_alerts,
+        margin,
+        background_tasks: tasks.snapshot(),
+        feature_flags: flags.snapshot(),
+    }
+}
+
+/// Asset counts and AUM; assets in a currency with no rate to `reporting` are listed apart
+pub async fn asset_summary(
+    service: &RwLock<MultiChainAssetService>,
+    scope: &TenantScope,
+    fx: &FxRateService,
+    reporting: Currency,
+) -> Result<AssetSummary, String> {
+    let mut locked = Vec::new();
+    let mut treasuries_by_status = HashMap::new();
+
+    let service = service.read().await;
+    for asset in service.get_all_assets(scope) {
+        if let Some(metrics) = service.get_asset_metrics(scope, &asset.asset_id) {
+            let value = Decimal::try_from(metrics.total_value_locked)
+                .map_err(|e| format!("Value locked in {} is not a number: {}", asset.asset_id, e))?;
+            locked.push((asset.currency, value));
+        }
+
+        let status = if asset.deployments.is_empty() {
+            "pending"
+        } else if asset.deployments.values().any(|deployment| deployment.is_active) {
+            "active"
+        } else {
+            "inactive"
+        };
+        *treasuries_by_status.entry(status.to_string()).or_insert(0) += 1;
+    }
+    drop(service);
+
+    Ok(AssetSummary { aum: fx.total(locked, reporting).await, treasuries_by_status })
+}
+
+pub async fn compliance_today(engine: &RwLock<EnhancedComplianceEngine>, scope: &TenantScope) -> Result<ComplianceCheckCounts, String> {
+    let midnight = Utc::now().date_naive().and_hms_opt(0, 0, 0)
+        .expect("midnight is a valid time")
+        .and_utc();
+    Ok(engine.read().await.check_counts_since(scope, midnight))
+}
+
+/// Open alerts written by the risk service
+pub async fn open_risk_alerts(db: &PgPool) -> Result<RiskAlertSummary, String> {
+    let rows: Vec<(String, i64)> = sqlx::query_as(
+        "SELECT severity, COUNT(*) FROM risk_alerts WHERE status = 'Open' GROUP BY severity"
+    )
+    .fetch_all(db)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(RiskAlertSummary { open_by_severity: rows.into_iter().collect() })
+}
+
+pub async fn margin_summary(service: &RwLock<PrimeBrokerageService>) -> Result<MarginSummary, String> {
+    let service = service.read().await;
+    Ok(MarginSummary {
+        open_margin_calls: service.open_margin_call_count(),
+        margin_calls_24h: service.get_prime_brokerage_metrics().margin_calls_24h,
+    })
+}
+
+/// Computed summaries per tenant scope and reporting currency, served for `ttl`
+pub struct AdminSummaryCache {
+    ttl: Duration,
+    entries: RwLock<HashMap<String, (Instant, AdminSummary)>>,
+}
+
+impl AdminSummaryCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, entries: RwLock::new(HashMap::new()) }
+    }
+
+    pub async fn get_or_build<F>(&self, scope: &TenantScope, reporting: Currency, build: F) -> AdminSummary
+    where
+        F: Future<Output = AdminSummary>,
+    {
+        let key = match scope.tenant() {
+            Some(tenant) => format!("{}:{}", tenant, reporting),
+            None => format!("*:{}", reporting),
+        };
+
+        if let Some((built_at, summary)) = self.entries.read().await.get(&key) {
+            if built_at.elapsed() < self.ttl {
+                return summary.clone();
+            }
+        }
+
+        let summary = build.await;
+        self.entries.write().await.insert(key, (Instant::now(), summary.clone()));
+        summary
+    }
+}
+
+impl Default for AdminSummaryCache {
+    fn default() -> Self {
+        Self::new(SUMMARY_CACHE_TTL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aum(total: i64) -> ReportingTotal {
+        ReportingTotal { currency: Currency::USD, total: Decimal::from(total), rates: Vec::new(), unconverted: Vec::new() }
+    }
+
+    #[tokio::test]
+    async fn test_partial_failure_shape() {
+        let tasks = TaskHealth::new();
+        tasks.record_success("auth_challenge_cleanup");
+        tasks.record_failure("jurisdiction_risk_refresh", "source unreachable");
+
+        let summary = build_summary(
+            async { Ok(AssetSummary { aum: aum(1_500_000), treasuries_by_status: HashMap::from([("active".to_string(), 3)]) }) },
+            async { Ok(ComplianceCheckCounts { checks: 12, violations: 2 }) },
+            async { Err::<RiskAlertSummary, _>("connection refused".to_string()) },
+            async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok(MarginSummary { open_margin_calls: 0, margin_calls_24h: 0 })
+            },
+            &tasks,
+            &FeatureFlags::new(HashMap::new()),
+            Duration::from_millis(50),
+        ).await;
+
+        let json = serde_json::to_value(&summary).unwrap();
+
+        assert_eq!(json["assets"]["status"], "ok");
+        assert_eq!(json["assets"]["data"]["aum"]["currency"], "USD");
+        assert_eq!(summary.assets.data.as_ref().unwrap().aum.total, Decimal::from(1_500_000));
+        assert_eq!(json["compliance_today"]["data"]["violations"], 2);
+
+        assert_eq!(json["risk_alerts"]["status"], "error");
+        assert_eq!(json["risk_alerts"]["error"], "connection refused");
+        assert!(json["risk_alerts"].get("data").is_none());
+
+        assert_eq!(json["margin"]["status"], "timeout");
+        assert!(json["margin"].get("data").is_none());
+
+        let task_health: Vec<bool> = summary.background_tasks.iter().map(|task| task.healthy).collect();
+        assert_eq!(task_health, vec![true, false]);
+        assert_eq!(json["feature_flags"][0]["name"], "legacy_login");
+        assert_eq!(json["feature_flags"][0]["enabled"], false);
+    }
+
+    #[tokio::test]
+    async fn test_summary_cached_per_scope() {
+        let cache = AdminSummaryCache::new(Duration::from_secs(30));
+        let tasks = TaskHealth::new();
+        let flags = FeatureFlags::new(HashMap::new());
+        let scope = TenantScope::AllTenants;
+        let build = |total: i64| build_summary(
+            async move { Ok(AssetSummary { aum: aum(total), treasuries_by_status: HashMap::new() }) },
+            async { Ok(ComplianceCheckCounts::default()) },
+            async { Ok(RiskAlertSummary { open_by_severity: HashMap::new() }) },
+            async { Ok(MarginSummary { open_margin_calls: 0, margin_calls_24h: 0 }) },
+            &tasks,
+            &flags,
+            SOURCE_TIMEOUT,
+        );
+
+        let first = cache.get_or_build(&scope, Currency::USD, build(100)).await;
+        let second = cache.get_or_build(&scope, Currency::USD, build(200)).await;
+        let in_euros = cache.get_or_build(&scope, Currency::EUR, build(300)).await;
+        assert_eq!(first.assets.data.unwrap().aum.total, Decimal::from(100));
+        assert_eq!(second.assets.data.unwrap().aum.total, Decimal::from(100));
+        assert_eq!(in_euros.assets.data.unwrap().aum.total, Decimal::from(300));
+    }
+}
@@ -0,0 +1,412 @@
+// Audit log persistence and cursor pagination for the admin endpoint
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::tenant::{TenantId, TenantScope};
+use super::secure_api::AuditLogEntry;
+
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+pub const MAX_PAGE_SIZE: usize = 500;
+
+/// Matching entries are counted up to this many; larger totals are reported as the cap
+pub const TOTAL_ESTIMATE_CAP: u64 = 10_000;
+
+pub const DEFAULT_RETENTION_DAYS: i64 = 365;
+
+/// Days audit entries are kept, from `AUDIT_LOG_RETENTION_DAYS`
+pub fn retention_from_env() -> Duration {
+    let days = std::env::var("AUDIT_LOG_RETENTION_DAYS").ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|v: &i64| *v > 0)
+        .unwrap_or(DEFAULT_RETENTION_DAYS);
+    Duration::days(days)
+}
+
+/// Position after the last entry of a page: entries are ordered by timestamp, then id, descending
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuditLogCursor {
+    pub timestamp: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl AuditLogCursor {
+    pub fn after(entry: &AuditLogEntry) -> Self {
+        Self { timestamp: entry.timestamp, id: entry.id }
+    }
+
+    /// Opaque form handed to clients
+    pub fn encode(&self) -> String {
+        let nanos = self.timestamp.timestamp_nanos_opt().unwrap_or(i64::MAX);
+        hex::encode(format!("{}:{}", nanos, self.id))
+    }
+
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let raw = String::from_utf8(hex::decode(cursor).ok()?).ok()?;
+        let (nanos, id) = raw.split_once(':')?;
+        Some(Self {
+            timestamp: Utc.timestamp_nanos(nanos.parse().ok()?),
+            id: id.parse().ok()?,
+        })
+    }
+
+    /// Whether `entry` comes after this cursor in page order
+    fn precedes(&self, entry: &AuditLogEntry) -> bool {
+        (entry.timestamp, entry.id) < (self.timestamp, self.id)
+    }
+}
+
+/// Query string of the audit log endpoint
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuditLogQuery {
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+    pub user_id: Option<String>,
+    pub action: Option<String>,
+    pub resource_prefix: Option<String>,
+    pub success: Option<bool>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl AuditLogQuery {
+    pub fn page_size(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+    }
+
+    fn matches(&self, entry: &AuditLogEntry) -> bool {
+        self.user_id.as_ref().map_or(true, |user| &entry.user_id == user)
+            && self.action.as_ref().map_or(true, |action| &entry.action == action)
+            && self.resource_prefix.as_ref().map_or(true, |prefix| entry.resource.starts_with(prefix.as_str()))
+            && self.success.map_or(true, |success| entry.success == success)
+            && self.from.map_or(true, |from| entry.timestamp >= from)
+            && self.to.map_or(true, |to| entry.timestamp < to)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditLogPage {
+    pub entries: Vec<AuditLogEntry>,
+    /// Absent on the last page
+    pub next_cursor: Option<String>,
+    /// Matching entries, capped at `TOTAL_ESTIMATE_CAP`
+    pub total_estimate: u64,
+}
+
+impl AuditLogPage {
+    fn empty() -> Self {
+        Self { entries: Vec::new(), next_cursor: None, total_estimate: 0 }
+    }
+}
+
+#[derive(Debug)]
+pub enum AuditLogQueryError {
+    InvalidCursor,
+    Database(sqlx::Error),
+}
+
+impl std::fmt::Display for AuditLogQueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            AuditLogQueryError::InvalidCursor => write!(f, "Invalid cursor"),
+            AuditLogQueryError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+/// Decode the request cursor; `Ok(None)` means the cursor points past the retention window
+fn start_cursor(
+    query: &AuditLogQuery,
+    now: DateTime<Utc>,
+    retention: Duration,
+) -> Result<Option<Option<AuditLogCursor>>, AuditLogQueryError> {
+    match query.cursor.as_deref() {
+        None => Ok(Some(None)),
+        Some(raw) => {
+            let cursor = AuditLogCursor::decode(raw).ok_or(AuditLogQueryError::InvalidCursor)?;
+            if cursor.timestamp < now - retention {
+                Ok(None)
+            } else {
+                Ok(Some(Some(cursor)))
+            }
+        }
+    }
+}
+
+/// One page of in-memory entries visible in `scope`
+pub fn page_entries(
+    entries: &[AuditLogEntry],
+    scope: &TenantScope,
+    query: &AuditLogQuery,
+    now: DateTime<Utc>,
+    retention: Duration,
+) -> Result<AuditLogPage, AuditLogQueryError> {
+    let Some(cursor) = start_cursor(query, now, retention)? else {
+        return Ok(AuditLogPage::empty());
+    };
+    let oldest = now - retention;
+
+    let mut matching: Vec<&AuditLogEntry> = entries.iter()
+        .filter(|entry| entry.timestamp >= oldest && scope.allows(&entry.tenant_id) && query.matches(entry))
+        .collect();
+    let total_estimate = (matching.len() as u64).min(TOTAL_ESTIMATE_CAP);
+
+    matching.sort_unstable_by(|a, b| (b.timestamp, b.id).cmp(&(a.timestamp, a.id)));
+
+    let page_size = query.page_size();
+    let mut page: Vec<AuditLogEntry> = matching.into_iter()
+        .filter(|entry| cursor.map_or(true, |cursor| cursor.precedes(entry)))
+        .take(page_size + 1)
+        .cloned()
+        .collect();
+
+    let next_cursor = if page.len() > page_size {
+        page.truncate(page_size);
+        page.last().map(|entry| AuditLogCursor::after(entry).encode())
+    } else {
+        None
+    };
+
+    Ok(AuditLogPage { entries: page, next_cursor, total_estimate })
+}
+
+// ============ Persistence ============
+
+/// Write one entry; pass a transaction to commit it alongside the writes it records
+pub async fn persist_entry<'e>(db: impl PgExecutor<'e>, entry: &AuditLogEntry) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO audit_log (
+            id, tenant_id, actor, action, resource_type, resource,
+            ip_address, user_agent, success, details, created_at
+        ) VALUES ($1, $2, $3, $4, 'secure_api', $5, $6::inet, $7, $8, $9::jsonb, $10)
+        "#
+    )
+    .bind(entry.id)
+    .bind(entry.tenant_id.as_str())
+    .bind(&entry.user_id)
+    .bind(&entry.action)
+    .bind(&entry.resource)
+    .bind(&entry.ip_address)
+    .bind(&entry.user_agent)
+    .bind(entry.success)
+    .bind(entry.details.to_string())
+    .bind(entry.timestamp)
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+type AuditLogRow = (Uuid, String, Option<String>, String, Option<String>, Option<String>, Option<String>, bool, Option<String>, DateTime<Utc>);
+
+/// One page of persisted entries, using the (tenant_id, created_at, id) keyset indexes
+pub async fn page_persisted(
+    db: &PgPool,
+    scope: &TenantScope,
+    query: &AuditLogQuery,
+    now: DateTime<Utc>,
+    retention: Duration,
+) -> Result<AuditLogPage, AuditLogQueryError> {
+    let Some(cursor) = start_cursor(query, now, retention)? else {
+        return Ok(AuditLogPage::empty());
+    };
+
+    let tenant = scope.tenant().map(|tenant| tenant.as_str().to_string());
+    let resource_pattern = query.resource_prefix.as_ref().map(|prefix| {
+        format!("{}%", prefix.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"))
+    });
+    let filters = r#"
+        created_at >= $1
+          AND ($2::text IS NULL OR tenant_id = $2)
+          AND ($3::text IS NULL OR actor = $3)
+          AND ($4::text IS NULL OR action = $4)
+          AND ($5::text IS NULL OR resource LIKE $5)
+          AND ($6::bool IS NULL OR success = $6)
+          AND ($7::timestamptz IS NULL OR created_at >= $7)
+          AND ($8::timestamptz IS NULL OR created_at < $8)
+    "#;
+
+    let total: i64 = sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM (SELECT 1 FROM audit_log WHERE {} LIMIT {}) capped",
+        filters, TOTAL_ESTIMATE_CAP
+    ))
+    .bind(now - retention)
+    .bind(&tenant)
+    .bind(&query.user_id)
+    .bind(&query.action)
+    .bind(&resource_pattern)
+    .bind(query.success)
+    .bind(query.from)
+    .bind(query.to)
+    .fetch_one(db)
+    .await
+    .map_err(AuditLogQueryError::Database)?;
+
+    let page_size = query.page_size();
+    let rows: Vec<AuditLogRow> = sqlx::query_as(&format!(
+        r#"
+        SELECT id, tenant_id, actor, action, resource, host(ip_address), user_agent, success,
+               details::text, created_at
+        FROM audit_log
+        WHERE {}
+          AND ($9::timestamptz IS NULL OR (created_at, id) < ($9, $10))
+        ORDER BY created_at DESC, id DESC
+        LIMIT $11
+        "#,
+        filters
+    ))
+    .bind(now - retention)
+    .bind(&tenant)
+    .bind(&query.user_id)
+    .bind(&query.action)
+    .bind(&resource_pattern)
+    .bind(query.success)
+    .bind(query.from)
+    .bind(query.to)
+    .bind(cursor.map(|c| c.timestamp))
+    .bind(cursor.map(|c| c.id).unwrap_or_default())
+    .bind(page_size as i64 + 1)
+    .fetch_all(db)
+    .await
+    .map_err(AuditLogQueryError::Database)?;
+
+    let mut entries: Vec<AuditLogEntry> = rows.into_iter()
+        .map(|(id, tenant_id, actor, action, resource, ip_address, user_agent, success, details, created_at)| AuditLogEntry {
+            id,
+            timestamp: created_at,
+            tenant_id: TenantId::new(tenant_id),
+            user_id: actor.unwrap_or_default(),
+            action,
+            resource: resource.unwrap_or_default(),
+            ip_address,
+            user_agent,
+            success,
+            details: details.and_then(|d| serde_json::from_str(&d).ok()).unwrap_or(serde_json::Value::Null),
+        })
+        .collect();
+
+    let next_cursor = if entries.len() > page_size {
+        entries.truncate(page_size);
+        entries.last().map(|entry| AuditLogCursor::after(entry).encode())
+    } else {
+        None
+    };
+
+    Ok(AuditLogPage { entries, next_cursor, total_estimate: total as u64 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// 10k entries over two tenants; every 4 entries share a timestamp to exercise the id tiebreak
+    fn seeded_log(now: DateTime<Utc>) -> Vec<AuditLogEntry> {
+        (0..10_000).map(|i| AuditLogEntry {
+            id: Uuid::new_v4(),
+            timestamp: now - Duration::seconds(i / 4),
+            tenant_id: TenantId::new(if i % 2 == 0 { "tenant-a" } else { "tenant-b" }),
+            user_id: format!("0x{:040x}", i % 7),
+            action: if i % 3 == 0 { "LOGIN" } else { "CREATE_ASSET" }.to_string(),
+            resource: if i % 3 == 0 { "AUTH".to_string() } else { format!("asset-{}", i) },
+            ip_address: None,
+            user_agent: None,
+            success: i % 5 != 0,
+            details: serde_json::Value::Null,
+        }).collect()
+    }
+
+    fn collect_pages(entries: &[AuditLogEntry], scope: &TenantScope, mut query: AuditLogQuery, now: DateTime<Utc>) -> Vec<AuditLogEntry> {
+        let retention = Duration::days(DEFAULT_RETENTION_DAYS);
+        let mut seen = Vec::new();
+        loop {
+            let page = page_entries(entries, scope, &query, now, retention).unwrap();
+            assert!(page.entries.len() <= query.page_size());
+            seen.extend(page.entries);
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(cursor),
+                None => return seen,
+            }
+        }
+    }
+
+    #[test]
+    fn test_pages_are_stable_and_complete() {
+        let now = Utc::now();
+        let mut log = seeded_log(now);
+        let query = AuditLogQuery { limit: Some(MAX_PAGE_SIZE * 4), ..Default::default() };
+
+        let first = page_entries(&log, &TenantScope::AllTenants, &query, now, Duration::days(DEFAULT_RETENTION_DAYS)).unwrap();
+        assert_eq!(first.entries.len(), MAX_PAGE_SIZE);
+        assert_eq!(first.total_estimate, 10_000);
+
+        let all = collect_pages(&log, &TenantScope::AllTenants, query.clone(), now);
+        assert_eq!(all.len(), 10_000);
+        assert_eq!(all.iter().map(|e| e.id).collect::<HashSet<_>>().len(), 10_000);
+        assert!(all.windows(2).all(|w| (w[0].timestamp, w[0].id) > (w[1].timestamp, w[1].id)));
+
+        // Entries logged while paging are newer than any cursor and do not shift later pages
+        let page = page_entries(&log, &TenantScope::AllTenants, &query, now, Duration::days(DEFAULT_RETENTION_DAYS)).unwrap();
+        let cursor = page.next_cursor.clone().unwrap();
+        let expected = page_entries(&log, &TenantScope::AllTenants, &AuditLogQuery { cursor: Some(cursor.clone()), ..query.clone() }, now, Duration::days(DEFAULT_RETENTION_DAYS)).unwrap();
+        log.extend(seeded_log(now + Duration::seconds(1)).into_iter().take(50));
+        let after = page_entries(&log, &TenantScope::AllTenants, &AuditLogQuery { cursor: Some(cursor), ..query }, now, Duration::days(DEFAULT_RETENTION_DAYS)).unwrap();
+        assert_eq!(
+            expected.entries.iter().map(|e| e.id).collect::<Vec<_>>(),
+            after.entries.iter().map(|e| e.id).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_filters_and_tenant_scope() {
+        let now = Utc::now();
+        let log = seeded_log(now);
+        let scope = TenantScope::Tenant(TenantId::new("tenant-a"));
+        let query = AuditLogQuery {
+            limit: Some(250),
+            action: Some("CREATE_ASSET".to_string()),
+            resource_prefix: Some("asset-".to_string()),
+            success: Some(true),
+            from: Some(now - Duration::seconds(1_000)),
+            ..Default::default()
+        };
+
+        let entries = collect_pages(&log, &scope, query, now);
+        assert!(!entries.is_empty());
+        assert!(entries.iter().all(|e| {
+            e.tenant_id.as_str() == "tenant-a"
+                && e.action == "CREATE_ASSET"
+                && e.resource.starts_with("asset-")
+                && e.success
+                && e.timestamp >= now - Duration::seconds(1_000)
+        }));
+        let expected = log.iter().filter(|e| {
+            e.tenant_id.as_str() == "tenant-a" && e.action == "CREATE_ASSET" && e.success
+                && e.timestamp >= now - Duration::seconds(1_000)
+        }).count();
+        assert_eq!(entries.len(), expected);
+    }
+
+    #[test]
+    fn test_stale_cursor_returns_empty_page() {
+        let now = Utc::now();
+        let log = seeded_log(now);
+        let stale = AuditLogCursor { timestamp: now - Duration::days(DEFAULT_RETENTION_DAYS + 1), id: Uuid::new_v4() };
+        let query = AuditLogQuery { cursor: Some(stale.encode()), ..Default::default() };
+
+        let page = page_entries(&log, &TenantScope::AllTenants, &query, now, Duration::days(DEFAULT_RETENTION_DAYS)).unwrap();
+        assert!(page.entries.is_empty());
+        assert!(page.next_cursor.is_none());
+
+        let garbage = AuditLogQuery { cursor: Some("not-a-cursor".to_string()), ..Default::default() };
+        assert!(matches!(
+            page_entries(&log, &TenantScope::AllTenants, &garbage, now, Duration::days(DEFAULT_RETENTION_DAYS)),
+            Err(AuditLogQueryError::InvalidCursor)
+        ));
+
+        let cursor = AuditLogCursor { timestamp: now, id: Uuid::new_v4() };
+        assert_eq!(AuditLogCursor::decode(&cursor.encode()), Some(cursor));
+    }
+}
@@ -0,0 +1,151 @@
+// Auth challenge hardening: outstanding-challenge caps, request fingerprints and cleanup
+use axum::http::HeaderMap;
+use sha2::{Sha256, Digest};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, error};
+use crate::task_health::TaskHealth;
+
+/// Name the cleanup task reports its health under
+pub const CHALLENGE_CLEANUP_TASK: &str = "auth_challenge_cleanup";
+
+/// Limits on unexpired, unused challenges a wallet or client IP may hold at once
+#[derive(Debug, Clone, Copy)]
+pub struct ChallengeLimits {
+    pub per_wallet: i64,
+    pub per_ip: i64,
+}
+
+impl Default for ChallengeLimits {
+    fn default() -> Self {
+        Self {
+            per_wallet: 3,
+            per_ip: 20,
+        }
+    }
+}
+
+impl ChallengeLimits {
+    /// Read `AUTH_CHALLENGE_MAX_PER_WALLET` and `AUTH_CHALLENGE_MAX_PER_IP`, falling back to defaults
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let read = |name: &str, default: i64| {
+            std::env::var(name).ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|v: &i64| *v > 0)
+                .unwrap_or(default)
+        };
+
+        Self {
+            per_wallet: read("AUTH_CHALLENGE_MAX_PER_WALLET", defaults.per_wallet),
+            per_ip: read("AUTH_CHALLENGE_MAX_PER_IP", defaults.per_ip),
+        }
+    }
+
+    /// Whether one more challenge may be issued given the current outstanding counts
+    pub fn allows(&self, wallet_outstanding: i64, ip_outstanding: i64) -> bool {
+        wallet_outstanding < self.per_wallet && ip_outstanding < self.per_ip
+    }
+}
+
+/// Client IP from proxy headers, as used for rate limiting
+pub fn client_ip(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get("X-Forwarded-For")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|s| s.split(',').next())
+        .map(|s| s.trim())
+        .or_else(|| {
+            headers.get("X-Real-IP")
+                .and_then(|h| h.to_str().ok())
+        })
+}
+
+/// Hash of the requesting origin and user agent a challenge is bound to
+pub fn request_fingerprint(headers: &HeaderMap) -> String {
+    let header = |name: &str| headers.get(name).and_then(|h| h.to_str().ok()).unwrap_or("");
+
+    let mut hasher = Sha256::new();
+    hasher.update(header("Origin").as_bytes());
+    hasher.update(b"\n");
+    hasher.update(header("User-Agent").as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Compare a stored fingerprint with the verifying request's without early exit
+pub fn fingerprint_matches(stored: &str, presented: &str) -> bool {
+    stored.len() == presented.len()
+        && stored.bytes().zip(presented.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// Delete expired and used challenges
+pub async fn cleanup_challenges(db: &PgPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        "DELETE FROM auth_challenges WHERE used = true OR expires_at < NOW()"
+    )
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Periodically delete expired and used challenges
+pub fn spawn_challenge_cleanup(db: Arc<PgPool>, health: Arc<TaskHealth>, every: Duration) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(every);
+        loop {
+            ticker.tick().await;
+            match cleanup_challenges(db.as_ref()).await {
+                Ok(deleted) => {
+                    if deleted > 0 {
+                        info!("Deleted {} expired or used auth challenges", deleted);
+                    }
+                    health.record_success(CHALLENGE_CLEANUP_TASK);
+                }
+                Err(e) => {
+                    error!("Auth challenge cleanup failed: {}", e);
+                    health.record_failure(CHALLENGE_CLEANUP_TASK, e);
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(origin: &str, user_agent: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("Origin", origin.parse().unwrap());
+        headers.insert("User-Agent", user_agent.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_outstanding_challenge_cap() {
+        let limits = ChallengeLimits { per_wallet: 3, per_ip: 5 };
+
+        assert!(limits.allows(0, 0));
+        assert!(limits.allows(2, 4));
+        assert!(!limits.allows(3, 0));
+        assert!(!limits.allows(0, 5));
+    }
+
+    #[test]
+    fn test_fingerprint_mismatch() {
+        let issued = request_fingerprint(&headers("https://app.quantera.io", "Mozilla/5.0 (Macintosh)"));
+
+        let same = request_fingerprint(&headers("https://app.quantera.io", "Mozilla/5.0 (Macintosh)"));
+        assert!(fingerprint_matches(&issued, &same));
+
+        let other_agent = request_fingerprint(&headers("https://app.quantera.io", "curl/8.4.0"));
+        assert!(!fingerprint_matches(&issued, &other_agent));
+
+        let other_origin = request_fingerprint(&headers("https://evil.example", "Mozilla/5.0 (Macintosh)"));
+        assert!(!fingerprint_matches(&issued, &other_origin));
+
+        assert!(!fingerprint_matches(&issued, ""));
+    }
+}
@@ -0,0 +1,237 @@
+// Session device tracking, per-user concurrent session caps and self-service revocation
+use axum::http::HeaderMap;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sha2::{Sha256, Digest};
+use sqlx::{PgConnection, PgPool, Row};
+use uuid::Uuid;
+
+use super::auth_challenge::client_ip;
+
+/// Longest device label kept; longer labels and user agents are truncated
+pub const MAX_DEVICE_LABEL_LEN: usize = 128;
+
+/// Concurrent active sessions a user may hold
+#[derive(Debug, Clone, Copy)]
+pub struct SessionLimits {
+    pub per_user: usize,
+}
+
+impl Default for SessionLimits {
+    fn default() -> Self {
+        Self { per_user: 5 }
+    }
+}
+
+impl SessionLimits {
+    /// Read `AUTH_MAX_SESSIONS_PER_USER`, falling back to the default
+    pub fn from_env() -> Self {
+        let per_user = std::env::var("AUTH_MAX_SESSIONS_PER_USER").ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|v: &usize| *v > 0)
+            .unwrap_or(Self::default().per_user);
+
+        Self { per_user }
+    }
+}
+
+/// Device a session was created from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceInfo {
+    pub user_agent_hash: String,
+    pub ip_address: Option<String>,
+    pub label: String,
+}
+
+impl DeviceInfo {
+    /// Label from `X-Device-Label`, else the user agent
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &str| headers.get(name).and_then(|h| h.to_str().ok()).map(str::trim);
+        let user_agent = header("User-Agent").unwrap_or("");
+        let label = header("X-Device-Label")
+            .filter(|label| !label.is_empty())
+            .or(Some(user_agent).filter(|agent| !agent.is_empty()))
+            .unwrap_or("Unknown device");
+
+        Self {
+            user_agent_hash: format!("{:x}", Sha256::digest(user_agent.as_bytes())),
+            ip_address: client_ip(headers).map(str::to_string),
+            label: label.chars().take(MAX_DEVICE_LABEL_LEN).collect(),
+        }
+    }
+}
+
+/// An unrevoked, unexpired session as shown to its owner
+#[derive(Debug, Clone, Serialize)]
+pub struct ActiveSession {
+    pub id: Uuid,
+    pub label: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// The session making this request
+    pub current: bool,
+}
+
+/// Sessions to revoke so that, with one more session, the user stays within `limit`.
+/// `active` is (id, created_at); the oldest sessions go first.
+pub fn sessions_to_evict(active: &[(Uuid, DateTime<Utc>)], limit: usize) -> Vec<Uuid> {
+    let keep = limit.saturating_sub(1);
+    let mut newest_first = active.to_vec();
+    newest_first.sort_by(|a, b| b.1.cmp(&a.1).then(b.0.cmp(&a.0)));
+    newest_first.into_iter().skip(keep).map(|(id, _)| id).collect()
+}
+
+/// Record a new session, revoking the user's oldest sessions beyond the cap.
+/// Returns the new session id and the ids of the sessions it evicted.
+pub async fn create_session(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    token_hash: &str,
+    expires_at: DateTime<Utc>,
+    device: &DeviceInfo,
+    limits: SessionLimits,
+) -> Result<(Uuid, Vec<Uuid>), sqlx::Error> {
+    // Lock the user's active sessions so concurrent logins cannot both slip under the cap
+    let active: Vec<(Uuid, DateTime<Utc>)> = sqlx::query_as(
+        "SELECT id, created_at FROM auth_sessions
+         WHERE user_id = $1 AND is_revoked = false AND expires_at > NOW()
+         FOR UPDATE"
+    )
+    .bind(user_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let evicted = sessions_to_evict(&active, limits.per_user);
+    if !evicted.is_empty() {
+        sqlx::query("UPDATE auth_sessions SET is_revoked = true, revoked_at = NOW() WHERE id = ANY($1)")
+            .bind(&evicted)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    let session_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO auth_sessions (user_id, token_hash, expires_at, user_agent_hash, ip_address, device_label)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         RETURNING id"
+    )
+    .bind(user_id)
+    .bind(token_hash)
+    .bind(expires_at)
+    .bind(&device.user_agent_hash)
+    .bind(device.ip_address.as_deref())
+    .bind(&device.label)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok((session_id, evicted))
+}
+
+/// Owner of the active session with this token hash
+pub async fn session_user(db: &PgPool, token_hash: &str) -> Result<Option<Uuid>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT user_id FROM auth_sessions
+         WHERE token_hash = $1 AND is_revoked = false AND expires_at > NOW()"
+    )
+    .bind(token_hash)
+    .fetch_optional(db)
+    .await
+    .map(Option::flatten)
+}
+
+/// The user's active sessions, newest first, with the one holding `current_token_hash` marked
+pub async fn list_active_sessions(
+    db: &PgPool,
+    user_id: Uuid,
+    current_token_hash: &str,
+) -> Result<Vec<ActiveSession>, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT id, device_label, ip_address, created_at, expires_at, token_hash = $2 AS current
+         FROM auth_sessions
+         WHERE user_id = $1 AND is_revoked = false AND expires_at > NOW()
+         ORDER BY created_at DESC, id DESC"
+    )
+    .bind(user_id)
+    .bind(current_token_hash)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows.into_iter()
+        .map(|row| ActiveSession {
+            id: row.get("id"),
+            label: row.get("device_label"),
+            ip_address: row.get("ip_address"),
+            created_at: row.get("created_at"),
+            expires_at: row.get("expires_at"),
+            current: row.get("current"),
+        })
+        .collect())
+}
+
+/// Revoke one of the user's own sessions; false when no such active session belongs to them
+pub async fn revoke_session(db: &PgPool, user_id: Uuid, session_id: Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE auth_sessions SET is_revoked = true, revoked_at = NOW()
+         WHERE id = $1 AND user_id = $2 AND is_revoked = false"
+    )
+    .bind(session_id)
+    .bind(user_id)
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_cap_evicts_oldest_sessions() {
+        let now = Utc::now();
+        let sessions: Vec<(Uuid, DateTime<Utc>)> = (0..4)
+            .map(|age| (Uuid::new_v4(), now - Duration::hours(age)))
+            .collect();
+
+        // Room for the new session means keeping the three newest of four
+        assert_eq!(sessions_to_evict(&sessions, 4), vec![sessions[3].0]);
+        assert_eq!(sessions_to_evict(&sessions, 2), vec![sessions[1].0, sessions[2].0, sessions[3].0]);
+        assert!(sessions_to_evict(&sessions, 5).is_empty());
+        assert!(sessions_to_evict(&[], 1).is_empty());
+    }
+
+    /// Migrated database from TEST_DATABASE_URL; skipped without one
+    async fn test_pool() -> Option<PgPool> {
+        let url = std::env::var("TEST_DATABASE_URL").ok()?;
+        Some(PgPool::connect(&url).await.expect("TEST_DATABASE_URL is unreachable"))
+    }
+
+    async fn test_user(db: &PgPool) -> Uuid {
+        sqlx::query_scalar("INSERT INTO users (wallet_address) VALUES ($1) RETURNING id")
+            .bind(format!("0x{}", Uuid::new_v4().simple()))
+            .fetch_one(db)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_self_revocation_leaves_other_users_sessions() {
+        let Some(db) = test_pool().await else { return };
+        let (alice, bob) = (test_user(&db).await, test_user(&db).await);
+        let device = DeviceInfo { user_agent_hash: "0".repeat(64), ip_address: None, label: "test".to_string() };
+        let expires = Utc::now() + Duration::hours(1);
+
+        let mut conn = db.acquire().await.unwrap();
+        let (alice_session, _) = create_session(&mut conn, alice, &Uuid::new_v4().to_string(), expires, &device, SessionLimits::default()).await.unwrap();
+        let (bob_session, _) = create_session(&mut conn, bob, &Uuid::new_v4().to_string(), expires, &device, SessionLimits::default()).await.unwrap();
+        drop(conn);
+
+        assert!(!revoke_session(&db, alice, bob_session).await.unwrap());
+        assert!(revoke_session(&db, alice, alice_session).await.unwrap());
+
+        assert!(list_active_sessions(&db, alice, "").await.unwrap().is_empty());
+        let bob_sessions = list_active_sessions(&db, bob, "").await.unwrap();
+        assert_eq!(bob_sessions.iter().map(|s| s.id).collect::<Vec<_>>(), vec![bob_session]);
+    }
+}
@@ -0,0 +1,419 @@
+// Browsing stored compliance reports: filters, sorting and cursor pagination
+use chrono::{DateTime, SecondsFormat, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::tenant::TenantScope;
+use super::secure_api::UserRole;
+
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+pub const MAX_PAGE_SIZE: usize = 200;
+
+/// Listing order; a leading `-` sorts descending
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ReportSort {
+    #[default]
+    #[serde(rename = "-generated_at")]
+    NewestFirst,
+    #[serde(rename = "generated_at")]
+    OldestFirst,
+    #[serde(rename = "-amount")]
+    LargestFirst,
+    #[serde(rename = "amount")]
+    SmallestFirst,
+}
+
+impl ReportSort {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ReportSort::NewestFirst => "-generated_at",
+            ReportSort::OldestFirst => "generated_at",
+            ReportSort::LargestFirst => "-amount",
+            ReportSort::SmallestFirst => "amount",
+        }
+    }
+
+    fn parse(sort: &str) -> Option<Self> {
+        [ReportSort::NewestFirst, ReportSort::OldestFirst, ReportSort::LargestFirst, ReportSort::SmallestFirst]
+            .into_iter()
+            .find(|candidate| candidate.as_str() == sort)
+    }
+
+    /// ORDER BY clause, with the row id breaking ties
+    fn order_by(&self) -> &'static str {
+        match self {
+            ReportSort::NewestFirst => "generated_at DESC, id DESC",
+            ReportSort::OldestFirst => "generated_at ASC, id ASC",
+            ReportSort::LargestFirst => "COALESCE(amount, 0) DESC, id DESC",
+            ReportSort::SmallestFirst => "COALESCE(amount, 0) ASC, id ASC",
+        }
+    }
+
+    /// Keyset condition for rows after the cursor; `$k` is the cursor's sort key as text
+    fn after_cursor(&self, k: usize, id: usize) -> String {
+        match self {
+            ReportSort::NewestFirst => format!("(generated_at, id) < (${}::timestamptz, ${})", k, id),
+            ReportSort::OldestFirst => format!("(generated_at, id) > (${}::timestamptz, ${})", k, id),
+            ReportSort::LargestFirst => format!("(COALESCE(amount, 0), id) < (${}::numeric, ${})", k, id),
+            ReportSort::SmallestFirst => format!("(COALESCE(amount, 0), id) > (${}::numeric, ${})", k, id),
+        }
+    }
+}
+
+/// Position after the last report of a page, in the sort it was issued for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportCursor {
+    pub sort: ReportSort,
+    /// Sort key of the last report: an RFC 3339 timestamp or a decimal amount
+    pub key: String,
+    pub id: i64,
+}
+
+impl ReportCursor {
+    fn after(sort: ReportSort, row: &ReportRow) -> Self {
+        let key = match sort {
+            ReportSort::NewestFirst | ReportSort::OldestFirst => row.generated_at.to_rfc3339_opts(SecondsFormat::Micros, true),
+            ReportSort::LargestFirst | ReportSort::SmallestFirst => row.amount.unwrap_or_default().to_string(),
+        };
+        Self { sort, key, id: row.id }
+    }
+
+    /// Opaque form handed to clients
+    pub fn encode(&self) -> String {
+        hex::encode(format!("{}|{}|{}", self.sort.as_str(), self.key, self.id))
+    }
+
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let raw = String::from_utf8(hex::decode(cursor).ok()?).ok()?;
+        let mut parts = raw.splitn(3, '|');
+        let sort = ReportSort::parse(parts.next()?)?;
+        let key = parts.next()?.to_string();
+        let id = parts.next()?.parse().ok()?;
+        // Keys are bound into SQL casts; reject anything that would not cast cleanly
+        let valid_key = match sort {
+            ReportSort::NewestFirst | ReportSort::OldestFirst => DateTime::parse_from_rfc3339(&key).is_ok(),
+            ReportSort::LargestFirst | ReportSort::SmallestFirst => key.parse::<Decimal>().is_ok(),
+        };
+        valid_key.then_some(Self { sort, key, id })
+    }
+}
+
+/// Query string of the compliance reports listing
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ComplianceReportQuery {
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+    /// Investor wallet address
+    pub investor: Option<String>,
+    pub jurisdiction: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub has_violations: Option<bool>,
+    /// Reports with at least one violation of this type, e.g. `KYC_EXPIRED`
+    pub violation_type: Option<String>,
+    pub kyc_verified: Option<bool>,
+    #[serde(default)]
+    pub sort: ReportSort,
+}
+
+impl ComplianceReportQuery {
+    pub fn page_size(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+    }
+}
+
+/// A report as listed; the detail adds recommendations
+#[derive(Debug, Clone, Serialize)]
+pub struct ComplianceReportSummary {
+    pub report_id: Uuid,
+    pub investor: String,
+    pub asset: Option<String>,
+    pub amount: Option<Decimal>,
+    pub jurisdiction: String,
+    pub kyc_verified: bool,
+    pub sanctions_passed: bool,
+    pub violations: serde_json::Value,
+    pub ipfs_hash: Option<String>,
+    pub generated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComplianceReportDetail {
+    #[serde(flatten)]
+    pub report: ComplianceReportSummary,
+    pub recommendations: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComplianceReportPage {
+    pub reports: Vec<ComplianceReportSummary>,
+    /// Absent on the last page
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum ReportQueryError {
+    InvalidCursor,
+    InvalidFilter(String),
+    /// Investors may only see reports about their own address
+    Forbidden,
+    NotFound,
+    Database(sqlx::Error),
+}
+
+impl std::fmt::Display for ReportQueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ReportQueryError::InvalidCursor => write!(f, "Invalid cursor"),
+            ReportQueryError::InvalidFilter(msg) => write!(f, "Invalid filter: {}", msg),
+            ReportQueryError::Forbidden => write!(f, "Reports of other investors are not accessible"),
+            ReportQueryError::NotFound => write!(f, "Compliance report not found"),
+            ReportQueryError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+/// Roles that see every investor's reports; everyone else sees only their own
+fn sees_all_investors(role: &UserRole) -> bool {
+    matches!(role, UserRole::PlatformAdmin | UserRole::Admin | UserRole::ComplianceOfficer | UserRole::AssetManager)
+}
+
+/// Investor the listing is restricted to, or None for every investor.
+///
+/// Investors get their own address whether or not they ask for it; asking for anyone
+/// else's is refused rather than silently narrowed.
+pub fn investor_filter(role: &UserRole, caller: &str, requested: Option<&str>) -> Result<Option<String>, ReportQueryError> {
+    if sees_all_investors(role) {
+        return Ok(requested.map(str::to_string));
+    }
+    match requested {
+        Some(investor) if !investor.eq_ignore_ascii_case(caller) => Err(ReportQueryError::Forbidden),
+        _ => Ok(Some(caller.to_string())),
+    }
+}
+
+/// Containment value matching reports with a violation of `violation_type`
+pub fn violation_type_filter(violation_type: &str) -> serde_json::Value {
+    serde_json::json!([{ "violation_type": violation_type }])
+}
+
+fn parse_address(address: &str) -> Result<Vec<u8>, ReportQueryError> {
+    hex::decode(address.trim_start_matches("0x")).ok()
+        .filter(|bytes| bytes.len() == 20)
+        .ok_or_else(|| ReportQueryError::InvalidFilter(format!("Invalid investor address: {}", address)))
+}
+
+fn format_address(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+/// Filters shared by the listing; every one is skipped when its parameter is NULL.
+/// The violation type uses jsonb containment so the GIN index on violations applies.
+const FILTERS: &str = r#"
+    ($1::text IS NULL OR tenant_id = $1)
+      AND ($2::bytea IS NULL OR investor_address = $2)
+      AND ($3::text IS NULL OR jurisdiction = $3)
+      AND ($4::timestamptz IS NULL OR generated_at >= $4)
+      AND ($5::timestamptz IS NULL OR generated_at < $5)
+      AND ($6::bool IS NULL OR (COALESCE(jsonb_array_length(violations), 0) > 0) = $6)
+      AND ($7::jsonb IS NULL OR violations @> $7)
+      AND ($8::bool IS NULL OR kyc_verified = $8)
+"#;
+
+const COLUMNS: &str = r#"
+    id, report_id, investor_address, asset_address, amount, jurisdiction,
+    kyc_verified, sanctions_passed, violations::text, recommendations::text, ipfs_hash, generated_at
+"#;
+
+type ReportTuple = (i64, Uuid, Vec<u8>, Option<Vec<u8>>, Option<Decimal>, String, bool, bool, Option<String>, Option<String>, Option<String>, DateTime<Utc>);
+
+struct ReportRow {
+    id: i64,
+    amount: Option<Decimal>,
+    generated_at: DateTime<Utc>,
+    detail: ComplianceReportDetail,
+}
+
+impl From<ReportTuple> for ReportRow {
+    fn from(row: ReportTuple) -> Self {
+        let (id, report_id, investor, asset, amount, jurisdiction, kyc_verified, sanctions_passed, violations, recommendations, ipfs_hash, generated_at) = row;
+        let json = |value: Option<String>| value
+            .and_then(|v| serde_json::from_str(&v).ok())
+            .unwrap_or_else(|| serde_json::Value::Array(Vec::new()));
+        Self {
+            id,
+            amount,
+            generated_at,
+            detail: ComplianceReportDetail {
+                report: ComplianceReportSummary {
+                    report_id,
+                    investor: format_address(&investor),
+                    asset: asset.as_deref().map(format_address),
+                    amount,
+                    jurisdiction,
+                    kyc_verified,
+                    sanctions_passed,
+                    violations: json(violations),
+                    ipfs_hash,
+                    generated_at,
+                },
+                recommendations: json(recommendations),
+            },
+        }
+    }
+}
+
+/// One page of reports visible in `scope`; `investor` comes from `investor_filter`
+pub async fn page_reports(
+    db: &PgPool,
+    scope: &TenantScope,
+    investor: Option<&str>,
+    query: &ComplianceReportQuery,
+) -> Result<ComplianceReportPage, ReportQueryError> {
+    let cursor = match query.cursor.as_deref() {
+        Some(raw) => {
+            let cursor = ReportCursor::decode(raw).ok_or(ReportQueryError::InvalidCursor)?;
+            // A cursor only makes sense in the order it was issued for
+            if cursor.sort != query.sort {
+                return Err(ReportQueryError::InvalidCursor);
+            }
+            Some(cursor)
+        }
+        None => None,
+    };
+    let investor = investor.map(parse_address).transpose()?;
+    let tenant = scope.tenant().map(|tenant| tenant.as_str().to_string());
+
+    let page_size = query.page_size();
+    let rows: Vec<ReportTuple> = sqlx::query_as(&format!(
+        r#"
+        SELECT {}
+        FROM compliance_reports
+        WHERE {}
+          AND ($9::text IS NULL OR {})
+        ORDER BY {}
+        LIMIT $11
+        "#,
+        COLUMNS,
+        FILTERS,
+        query.sort.after_cursor(9, 10),
+        query.sort.order_by()
+    ))
+    .bind(&tenant)
+    .bind(&investor)
+    .bind(&query.jurisdiction)
+    .bind(query.from)
+    .bind(query.to)
+    .bind(query.has_violations)
+    .bind(query.violation_type.as_deref().map(violation_type_filter))
+    .bind(query.kyc_verified)
+    .bind(cursor.as_ref().map(|c| c.key.clone()))
+    .bind(cursor.as_ref().map(|c| c.id).unwrap_or_default())
+    .bind(page_size as i64 + 1)
+    .fetch_all(db)
+    .await
+    .map_err(ReportQueryError::Database)?;
+
+    let mut rows: Vec<ReportRow> = rows.into_iter().map(ReportRow::from).collect();
+    let next_cursor = if rows.len() > page_size {
+        rows.truncate(page_size);
+        rows.last().map(|row| ReportCursor::after(query.sort, row).encode())
+    } else {
+        None
+    };
+
+    Ok(ComplianceReportPage {
+        reports: rows.into_iter().map(|row| row.detail.report).collect(),
+        next_cursor,
+    })
+}
+
+/// Full report by id, if visible in `scope` and, for investors, their own
+pub async fn get_report(
+    db: &PgPool,
+    scope: &TenantScope,
+    investor: Option<&str>,
+    report_id: Uuid,
+) -> Result<ComplianceReportDetail, ReportQueryError> {
+    let tenant = scope.tenant().map(|tenant| tenant.as_str().to_string());
+    let row: Option<ReportTuple> = sqlx::query_as(&format!(
+        "SELECT {} FROM compliance_reports WHERE report_id = $1 AND ($2::text IS NULL OR tenant_id = $2)",
+        COLUMNS
+    ))
+    .bind(report_id)
+    .bind(&tenant)
+    .fetch_optional(db)
+    .await
+    .map_err(ReportQueryError::Database)?;
+
+    let detail = ReportRow::from(row.ok_or(ReportQueryError::NotFound)?).detail;
+    if let Some(investor) = investor {
+        if !detail.report.investor.eq_ignore_ascii_case(&format_address(&parse_address(investor)?)) {
+            return Err(ReportQueryError::Forbidden);
+        }
+    }
+    Ok(detail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// jsonb `@>` for the shapes stored in compliance_reports.violations
+    fn contains(haystack: &serde_json::Value, needle: &serde_json::Value) -> bool {
+        match (haystack, needle) {
+            (serde_json::Value::Array(items), serde_json::Value::Array(wanted)) => {
+                wanted.iter().all(|w| items.iter().any(|item| contains(item, w)))
+            }
+            (serde_json::Value::Object(fields), serde_json::Value::Object(wanted)) => {
+                wanted.iter().all(|(key, w)| fields.get(key).map_or(false, |value| contains(value, w)))
+            }
+            (value, wanted) => value == wanted,
+        }
+    }
+
+    #[test]
+    fn test_violation_type_filter_matches_stored_violations() {
+        // As the compliance service serializes Vec<Violation> into the column
+        let stored = serde_json::json!([
+            { "violation_type": "KYC_EXPIRED", "description": "KYC has expired", "severity": "CRITICAL" },
+            { "violation_type": "INVESTMENT_LIMIT", "description": "Over the limit", "severity": "HIGH" },
+        ]);
+
+        assert!(contains(&stored, &violation_type_filter("KYC_EXPIRED")));
+        assert!(contains(&stored, &violation_type_filter("INVESTMENT_LIMIT")));
+        assert!(!contains(&stored, &violation_type_filter("SANCTIONED")));
+        assert!(!contains(&serde_json::json!([]), &violation_type_filter("KYC_EXPIRED")));
+
+        // The filter is bound as a parameter of the containment clause, never spliced in
+        assert!(FILTERS.contains("violations @> $7"));
+        let injected = violation_type_filter("x\"}]' OR 1=1 --");
+        assert_eq!(injected[0]["violation_type"], "x\"}]' OR 1=1 --");
+    }
+
+    #[test]
+    fn test_investors_only_query_their_own_reports() {
+        let me = "0x00000000000000000000000000000000000000aa";
+        let other = "0x00000000000000000000000000000000000000bb";
+
+        assert_eq!(investor_filter(&UserRole::Investor, me, None).unwrap().as_deref(), Some(me));
+        assert_eq!(investor_filter(&UserRole::Investor, me, Some(&me.to_uppercase().replace("0X", "0x"))).unwrap().as_deref(), Some(me));
+        assert!(matches!(investor_filter(&UserRole::Investor, me, Some(other)), Err(ReportQueryError::Forbidden)));
+        assert!(matches!(investor_filter(&UserRole::ReadOnly, me, Some(other)), Err(ReportQueryError::Forbidden)));
+
+        assert_eq!(investor_filter(&UserRole::ComplianceOfficer, me, None).unwrap(), None);
+        assert_eq!(investor_filter(&UserRole::ComplianceOfficer, me, Some(other)).unwrap().as_deref(), Some(other));
+    }
+
+    #[test]
+    fn test_cursor_round_trip_and_validation() {
+        let cursor = ReportCursor { sort: ReportSort::LargestFirst, key: "1250.50".to_string(), id: 42 };
+        assert_eq!(ReportCursor::decode(&cursor.encode()), Some(cursor));
+
+        let bad_key = ReportCursor { sort: ReportSort::NewestFirst, key: "now(); DROP".to_string(), id: 1 };
+        assert_eq!(ReportCursor::decode(&bad_key.encode()), None);
+        assert_eq!(ReportCursor::decode("zz"), None);
+    }
+}
@@ -1,25 +1,39 @@
 // Module declarations
 pub mod secure_api;
+pub mod auth_challenge;
+pub mod auth_sessions;
+pub mod audit_log;
+pub mod compliance_reports;
+pub mod admin_summary;
+pub mod rate_limit;
 pub mod portfolio_api; // Phase 5
 pub mod tradefinance_api; // Phase 5
 
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{header::ACCEPT_LANGUAGE, HeaderMap, StatusCode},
     response::Json,
     routing::{get, post, put},
     Router,
 };
 use serde::{Deserialize, Serialize};
+use quantera_types::{Pagination, PaginationError, SortSpec};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
-use crate::services::multi_chain_asset_service::{MultiChainAssetService, AssetType, ComplianceStandard};
+use crate::services::multi_chain_asset_service::{
+    MultiChainAssetService, AssetType, ComplianceStandard, DeploymentCostReport,
+    AssetLiquidity, ChainLiquidityError, CrossChainAsset,
+};
+use crate::services::symbol_registry::SymbolError;
+use crate::services::asset_review::ReviewError;
+use crate::tenant::TenantScope;
 use crate::compliance::enhanced_compliance_engine::{
     EnhancedComplianceEngine, InvestorProfile, InvestorType, KYCStatus, AMLStatus, 
     AccreditationStatus, RiskRating, SanctionsStatus, AccessLevel
 };
+use crate::compliance::concentration::InvestmentTarget;
 
 // API State
 #[derive(Clone)]
@@ -38,6 +52,9 @@ pub struct CreateAssetRequest {
     pub regulatory_framework: String,
     pub jurisdiction: String,
     pub total_supply: u128,
+    /// ISO 4217 code; defaults to USD
+    #[serde(default)]
+    pub currency: quantera_types::Currency,
     pub description: Option<String>,
 }
 
@@ -74,6 +91,21 @@ pub struct ComplianceCheckRequest {
     pub asset_type: String,
     pub investment_amount: String, // String to handle large numbers
     pub jurisdiction: String,
+    /// Selects issuer concentration limits
+    #[serde(default)]
+    pub issuer: Option<String>,
+    /// Selects asset class concentration limits
+    #[serde(default)]
+    pub asset_class: Option<String>,
+}
+
+impl ComplianceCheckRequest {
+    pub fn target(&self) -> InvestmentTarget {
+        let mut target = InvestmentTarget::new(&self.asset_type);
+        target.issuer = self.issuer.clone();
+        target.asset_class = self.asset_class.clone();
+        target
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -84,6 +116,7 @@ pub struct ComplianceCheckResponse {
     pub recommendations: Vec<String>,
     pub required_actions: Vec<String>,
     pub estimated_completion_days: Option<i64>,
+    pub locale: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -94,6 +127,7 @@ pub struct ComplianceCheckDto {
     pub message: String,
     pub severity: String,
     pub remediation_steps: Vec<String>,
+    pub message_id: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -139,7 +173,35 @@ pub struct ChainSupportResponse {
 pub struct LiquidityResponse {
     pub asset_id: String,
     pub chain_liquidity: std::collections::HashMap<String, ChainLiquidityDto>,
+    /// Sum over the chains that answered
     pub total_liquidity_usd: f64,
+    /// Some chains failed; their errors are in `errors`
+    pub partial: bool,
+    pub errors: std::collections::HashMap<String, ChainLiquidityError>,
+}
+
+impl LiquidityResponse {
+    fn from_liquidity(asset_id: String, liquidity: AssetLiquidity) -> Self {
+        let chain_liquidity: std::collections::HashMap<String, ChainLiquidityDto> = liquidity.chains.iter()
+            .map(|(chain, liquidity_data)| (
+                format!("{:?}", chain),
+                ChainLiquidityDto {
+                    chain: format!("{:?}", chain),
+                    total_liquidity_usd: liquidity_data.total_liquidity_usd,
+                    available_liquidity_usd: liquidity_data.available_liquidity_usd,
+                    pool_count: liquidity_data.pools.len(),
+                }
+            ))
+            .collect();
+        
+        Self {
+            asset_id,
+            total_liquidity_usd: chain_liquidity.values().map(|chain| chain.total_liquidity_usd).sum(),
+            chain_liquidity,
+            partial: liquidity.is_partial(),
+            errors: liquidity.errors.into_iter().map(|(chain, error)| (format!("{:?}", chain), error)).collect(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -154,36 +216,58 @@ pub struct ChainLiquidityDto {
 pub struct PaginationQuery {
     pub page: Option<u32>,
     pub per_page: Option<u32>,
+    /// `next_cursor` of the previous page, instead of a page number
+    pub cursor: Option<String>,
+    /// Sort order, e.g. `-created_at,name`
+    pub sort: Option<String>,
     pub asset_type: Option<String>,
     pub jurisdiction: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
-pub struct PaginatedResponse<T> {
-    pub data: Vec<T>,
-    pub total_count: usize,
-    pub page: u32,
-    pub per_page: u32,
-    pub total_pages: u32,
+impl PaginationQuery {
+    pub fn pagination(&self) -> Result<Pagination, PaginationError> {
+        Pagination::new(self.page, self.per_page, self.cursor.as_deref())
+    }
 }
 
-#[derive(Debug, Serialize)]
-pub struct ApiError {
-    pub error: String,
-    pub message: String,
-    pub code: u16,
-}
+/// Fields the asset listings can be sorted by
+pub const ASSET_SORT_FIELDS: &[&str] = &["created_at", "name", "symbol", "jurisdiction"];
 
-impl ApiError {
-    pub fn new(error: &str, message: &str, code: u16) -> Self {
-        Self {
-            error: error.to_string(),
-            message: message.to_string(),
-            code,
-        }
+/// Newest assets first unless another order is asked for
+pub const DEFAULT_ASSET_SORT: &str = "-created_at";
+
+/// Ascending order of two assets by one of `ASSET_SORT_FIELDS`
+pub fn compare_assets(field: &str, a: &CrossChainAsset, b: &CrossChainAsset) -> std::cmp::Ordering {
+    match field {
+        "name" => a.name.cmp(&b.name),
+        "symbol" => a.symbol.cmp(&b.symbol),
+        "jurisdiction" => a.jurisdiction.cmp(&b.jurisdiction),
+        _ => a.created_at.cmp(&b.created_at),
     }
 }
 
+/// Bad paging or sort parameters, as a 400 in either API's error body
+pub fn pagination_error(e: PaginationError) -> (StatusCode, Json<ApiError>) {
+    (StatusCode::BAD_REQUEST, Json(ApiError::new(e.code(), &e.to_string(), 400)))
+}
+
+/// Locale of compliance messages; the Accept-Language header is used when absent
+#[derive(Debug, Default, Deserialize)]
+pub struct LocaleQuery {
+    pub locale: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeploymentCostQuery {
+    /// Comma separated chain names; all configured chains when absent
+    pub chains: Option<String>,
+}
+
+pub use quantera_types::Page as PaginatedResponse;
+
+/// Error body of the v1 API, shared with the other stacks
+pub use quantera_types::ErrorEnvelope as ApiError;
+
 // API Routes
 pub fn create_router(state: ApiState) -> Router {
     Router::new()
@@ -193,6 +277,7 @@ pub fn create_router(state: ApiState) -> Router {
         .route("/api/v1/assets/:asset_id", get(get_asset))
         .route("/api/v1/assets/:asset_id/deploy", post(deploy_asset))
         .route("/api/v1/assets/:asset_id/liquidity", get(get_asset_liquidity))
+        .route("/api/v1/assets/:asset_id/deployment-costs", get(get_deployment_costs))
         
         // Compliance Routes
         .route("/api/v1/compliance/check", post(check_compliance))
@@ -214,6 +299,7 @@ pub fn create_router(state: ApiState) -> Router {
 // Asset Management Handlers
 async fn create_asset(
     State(state): State<ApiState>,
+    scope: TenantScope,
     Json(request): Json<CreateAssetRequest>,
 ) -> Result<Json<AssetResponse>, (StatusCode, Json<ApiError>)> {
     let mut service = state.asset_service.write().await;
@@ -225,6 +311,7 @@ async fn create_asset(
         .map_err(|e| (StatusCode::BAD_REQUEST, Json(ApiError::new("INVALID_COMPLIANCE_STANDARD", &e, 400))))?;
     
     let asset_id = service.create_asset(
+        scope.tenant().cloned().unwrap_or_default(),
         request.name.clone(),
         request.symbol.clone(),
         asset_type,
@@ -232,10 +319,16 @@ async fn create_asset(
         request.regulatory_framework.clone(),
         request.jurisdiction.clone(),
         request.total_supply,
+        request.currency,
+        // This router has no caller identity, so any checker may approve its assets
+        "anonymous",
     ).await
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiError::new("CREATION_FAILED", &e.to_string(), 500))))?;
+    .map_err(|e| match e.downcast_ref::<SymbolError>() {
+        Some(SymbolError::Taken { .. } | SymbolError::Reserved { .. }) => (StatusCode::CONFLICT, Json(ApiError::new("SYMBOL_TAKEN", &e.to_string(), 409))),
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiError::new("CREATION_FAILED", &e.to_string(), 500))),
+    })?;
     
-    let asset = service.get_asset(&asset_id)
+    let asset = service.get_asset(&scope, &asset_id)
         .ok_or_else(|| (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiError::new("ASSET_NOT_FOUND", "Created asset not found", 500))))?;
     
     Ok(Json(AssetResponse {
@@ -256,30 +349,26 @@ async fn create_asset(
 
 async fn list_assets(
     State(state): State<ApiState>,
+    scope: TenantScope,
     Query(params): Query<PaginationQuery>,
 ) -> Result<Json<PaginatedResponse<AssetResponse>>, (StatusCode, Json<ApiError>)> {
     let service = state.asset_service.read().await;
+    let pagination = params.pagination().map_err(pagination_error)?;
+    let sort = SortSpec::parse(params.sort.as_deref(), DEFAULT_ASSET_SORT, ASSET_SORT_FIELDS)
+        .map_err(pagination_error)?;
     
-    let page = params.page.unwrap_or(1);
-    let per_page = params.per_page.unwrap_or(20).min(100); // Max 100 per page
-    
-    let assets = if let Some(asset_type) = params.asset_type {
-        let parsed_type = parse_asset_type(&asset_type)
+    let mut assets = if let Some(asset_type) = &params.asset_type {
+        let parsed_type = parse_asset_type(asset_type)
             .map_err(|e| (StatusCode::BAD_REQUEST, Json(ApiError::new("INVALID_ASSET_TYPE", &e, 400))))?;
-        service.get_assets_by_type(&parsed_type)
-    } else if let Some(jurisdiction) = params.jurisdiction {
-        service.get_assets_by_jurisdiction(&jurisdiction)
+        service.get_assets_by_type(&scope, &parsed_type)
+    } else if let Some(jurisdiction) = &params.jurisdiction {
+        service.get_assets_by_jurisdiction(&scope, jurisdiction)
     } else {
-        service.get_all_assets()
+        service.get_all_assets(&scope)
     };
+    sort.sort(&mut assets, |field, a, b| compare_assets(field, a, b));
     
-    let total_count = assets.len();
-    let total_pages = (total_count as f64 / per_page as f64).ceil() as u32;
-    
-    let start = ((page - 1) * per_page) as usize;
-    let end = (start + per_page as usize).min(total_count);
-    
-    let paginated_assets: Vec<AssetResponse> = assets[start..end].iter()
+    Ok(Json(pagination.paginate(assets)
         .map(|asset| AssetResponse {
             asset_id: asset.asset_id.clone(),
             name: asset.name.clone(),
@@ -294,24 +383,17 @@ async fn list_assets(
                 .map(|(k, v)| (format!("{:?}", k), v.contract_address.clone()))
                 .collect(),
         })
-        .collect();
-    
-    Ok(Json(PaginatedResponse {
-        data: paginated_assets,
-        total_count,
-        page,
-        per_page,
-        total_pages,
-    }))
+    ))
 }
 
 async fn get_asset(
     State(state): State<ApiState>,
+    scope: TenantScope,
     Path(asset_id): Path<String>,
 ) -> Result<Json<AssetResponse>, (StatusCode, Json<ApiError>)> {
     let service = state.asset_service.read().await;
     
-    let asset = service.get_asset(&asset_id)
+    let asset = service.get_asset(&scope, &asset_id)
         .ok_or_else(|| (StatusCode::NOT_FOUND, Json(ApiError::new("ASSET_NOT_FOUND", "Asset not found", 404))))?;
     
     Ok(Json(AssetResponse {
@@ -332,12 +414,13 @@ async fn get_asset(
 
 async fn deploy_asset(
     State(state): State<ApiState>,
+    scope: TenantScope,
     Path(asset_id): Path<String>,
     Json(request): Json<DeployAssetRequest>,
 ) -> Result<Json<DeploymentResponse>, (StatusCode, Json<ApiError>)> {
     let mut service = state.asset_service.write().await;
     
-    let asset = service.get_asset(&asset_id)
+    let asset = service.get_asset(&scope, &asset_id)
         .ok_or_else(|| (StatusCode::NOT_FOUND, Json(ApiError::new("ASSET_NOT_FOUND", "Asset not found", 404))))?
         .clone();
     
@@ -349,7 +432,10 @@ async fn deploy_asset(
         .map_err(|e| (StatusCode::BAD_REQUEST, Json(ApiError::new("INVALID_CHAIN", &e, 400))))?;
     
     let deployments = service.deploy_asset_cross_chain(&asset, target_chains).await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiError::new("DEPLOYMENT_FAILED", &e.to_string(), 500))))?;
+        .map_err(|e| match e.downcast_ref::<ReviewError>() {
+            Some(ReviewError::NotApproved(_)) => (StatusCode::CONFLICT, Json(ApiError::new("ASSET_NOT_APPROVED", &e.to_string(), 409))),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiError::new("DEPLOYMENT_FAILED", &e.to_string(), 500))),
+        })?;
     
     let deployment_map: std::collections::HashMap<String, String> = deployments.iter()
         .map(|(k, v)| (format!("{:?}", k), v.clone()))
@@ -364,54 +450,67 @@ async fn deploy_asset(
 
 async fn get_asset_liquidity(
     State(state): State<ApiState>,
+    scope: TenantScope,
     Path(asset_id): Path<String>,
 ) -> Result<Json<LiquidityResponse>, (StatusCode, Json<ApiError>)> {
     let service = state.asset_service.read().await;
     
-    let liquidity = service.get_asset_liquidity_across_chains(&asset_id).await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiError::new("LIQUIDITY_FETCH_FAILED", &e.to_string(), 500))))?;
+    // Chains that fail are reported alongside the ones that answered
+    let liquidity = service.get_asset_liquidity_across_chains(&scope, &asset_id).await
+        .map_err(|_| (StatusCode::NOT_FOUND, Json(ApiError::new("ASSET_NOT_FOUND", "Asset not found", 404))))?;
     
-    let mut total_liquidity = 0.0;
-    let chain_liquidity: std::collections::HashMap<String, ChainLiquidityDto> = liquidity.iter()
-        .map(|(chain, liquidity_data)| {
-            total_liquidity += liquidity_data.total_liquidity_usd;
-            (
-                format!("{:?}", chain),
-                ChainLiquidityDto {
-                    chain: format!("{:?}", chain),
-                    total_liquidity_usd: liquidity_data.total_liquidity_usd,
-                    available_liquidity_usd: liquidity_data.available_liquidity_usd,
-                    pool_count: liquidity_data.pools.len(),
-                }
-            )
-        })
-        .collect();
+    Ok(Json(LiquidityResponse::from_liquidity(asset_id, liquidity)))
+}
+
+async fn get_deployment_costs(
+    State(state): State<ApiState>,
+    scope: TenantScope,
+    Path(asset_id): Path<String>,
+    Query(query): Query<DeploymentCostQuery>,
+) -> Result<Json<DeploymentCostReport>, (StatusCode, Json<ApiError>)> {
+    let chains = parse_chain_list(query.chains.as_deref())
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(ApiError::new("INVALID_CHAIN", &e, 400))))?;
     
-    Ok(Json(LiquidityResponse {
-        asset_id,
-        chain_liquidity,
-        total_liquidity_usd: total_liquidity,
-    }))
+    let service = state.asset_service.read().await;
+    
+    if service.get_asset(&scope, &asset_id).is_none() {
+        return Err((StatusCode::NOT_FOUND, Json(ApiError::new("ASSET_NOT_FOUND", "Asset not found", 404))));
+    }
+    
+    let report = service.estimate_deployment_costs(&scope, &asset_id, chains).await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiError::new("COST_ESTIMATION_FAILED", &e.to_string(), 500))))?;
+    
+    Ok(Json(report))
 }
 
 // Compliance Handlers
 async fn check_compliance(
     State(state): State<ApiState>,
+    scope: TenantScope,
+    Query(locale): Query<LocaleQuery>,
+    headers: HeaderMap,
     Json(request): Json<ComplianceCheckRequest>,
 ) -> Result<Json<ComplianceCheckResponse>, (StatusCode, Json<ApiError>)> {
     let mut engine = state.compliance_engine.write().await;
+    let messages = engine.messages().clone();
+    let locale = messages.negotiate(
+        locale.locale.as_deref(),
+        headers.get(ACCEPT_LANGUAGE).and_then(|value| value.to_str().ok()),
+    );
     
     let investment_amount: u128 = request.investment_amount.parse()
         .map_err(|_| (StatusCode::BAD_REQUEST, Json(ApiError::new("INVALID_AMOUNT", "Invalid investment amount", 400))))?;
     
-    let result = engine.comprehensive_compliance_check(
+    let result = engine.comprehensive_compliance_check_for(
+        &scope,
         &request.investor_id,
-        &request.asset_type,
+        &request.target(),
         investment_amount,
         &request.jurisdiction,
         "api_system", // performed_by - using system identifier for Phase 1
     ).await
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiError::new("COMPLIANCE_CHECK_FAILED", &e.to_string(), 500))))?;
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiError::new("COMPLIANCE_CHECK_FAILED", &e.to_string(), 500))))?
+    .localized(&messages, &locale);
     
     let checks: Vec<ComplianceCheckDto> = result.checks.iter()
         .map(|check| ComplianceCheckDto {
@@ -421,6 +520,7 @@ async fn check_compliance(
             message: check.message.clone(),
             severity: format!("{:?}", check.severity),
             remediation_steps: check.remediation_steps.clone(),
+            message_id: check.message_ref.id.clone(),
         })
         .collect();
     
@@ -431,11 +531,13 @@ async fn check_compliance(
         recommendations: result.recommendations,
         required_actions: result.required_actions,
         estimated_completion_days: result.estimated_completion_time.map(|d| d.num_days()),
+        locale: result.locale,
     }))
 }
 
 async fn create_investor(
     State(state): State<ApiState>,
+    scope: TenantScope,
     Json(request): Json<CreateInvestorRequest>,
 ) -> Result<Json<InvestorResponse>, (StatusCode, Json<ApiError>)> {
     let mut engine = state.compliance_engine.write().await;
@@ -445,6 +547,7 @@ async fn create_investor(
     
     let profile = InvestorProfile {
         investor_id: request.investor_id.clone(),
+        tenant_id: scope.tenant().cloned().unwrap_or_default(),
         jurisdiction: request.jurisdiction.clone(),
         tax_residency: request.tax_residency.clone(),
         investor_type,
@@ -457,6 +560,7 @@ async fn create_investor(
         risk_rating: RiskRating::Medium,
         sanctions_status: SanctionsStatus::Clear,
         cooling_periods: std::collections::HashMap::new(),
+        appropriateness: None,
         // Security fields
         data_hash: String::new(), // Will be generated by update_investor_profile
         access_level: AccessLevel::Standard,
@@ -464,7 +568,7 @@ async fn create_investor(
         last_accessed: chrono::Utc::now(),
     };
     
-    engine.update_investor_profile(request.investor_id.clone(), profile.clone(), "api_system").await
+    engine.update_investor_profile(&scope, request.investor_id.clone(), profile.clone(), "api_system").await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiError::new("PROFILE_CREATION_FAILED", &e.to_string(), 500))))?;
     
     Ok(Json(InvestorResponse {
@@ -482,11 +586,12 @@ async fn create_investor(
 
 async fn get_investor(
     State(state): State<ApiState>,
+    scope: TenantScope,
     Path(investor_id): Path<String>,
 ) -> Result<Json<InvestorResponse>, (StatusCode, Json<ApiError>)> {
     let mut engine = state.compliance_engine.write().await;
     
-    let profile = engine.get_investor_profile(&investor_id, "api_system").await
+    let profile = engine.get_investor_profile(&scope, &investor_id, "api_system").await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiError::new("PROFILE_FETCH_FAILED", &e.to_string(), 500))))?
         .ok_or_else(|| (StatusCode::NOT_FOUND, Json(ApiError::new("INVESTOR_NOT_FOUND", "Investor profile not found", 404))))?;
     
@@ -505,12 +610,13 @@ async fn get_investor(
 
 async fn update_investor(
     State(state): State<ApiState>,
+    scope: TenantScope,
     Path(investor_id): Path<String>,
     Json(request): Json<UpdateInvestorRequest>,
 ) -> Result<Json<InvestorResponse>, (StatusCode, Json<ApiError>)> {
     let mut engine = state.compliance_engine.write().await;
     
-    let mut profile = engine.get_investor_profile(&investor_id, "api_system").await
+    let mut profile = engine.get_investor_profile(&scope, &investor_id, "api_system").await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiError::new("PROFILE_FETCH_FAILED", &e.to_string(), 500))))?
         .ok_or_else(|| (StatusCode::NOT_FOUND, Json(ApiError::new("INVESTOR_NOT_FOUND", "Investor profile not found", 404))))?
         .clone();
@@ -545,7 +651,7 @@ async fn update_investor(
     
     profile.last_updated = chrono::Utc::now();
     
-    engine.update_investor_profile(investor_id.clone(), profile.clone(), "api_system").await
+    engine.update_investor_profile(&scope, investor_id.clone(), profile.clone(), "api_system").await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiError::new("PROFILE_UPDATE_FAILED", &e.to_string(), 500))))?;
     
     Ok(Json(InvestorResponse {
@@ -572,10 +678,11 @@ async fn get_supported_jurisdictions(
 // Chain Support Handlers
 async fn get_supported_chains(
     State(state): State<ApiState>,
+    scope: TenantScope,
 ) -> Result<Json<ChainSupportResponse>, (StatusCode, Json<ApiError>)> {
     let service = state.asset_service.read().await;
     let chains = service.get_supported_chains();
-    let all_assets = service.get_all_assets();
+    let all_assets = service.get_all_assets(&scope);
     
     let active_deployments = all_assets.iter()
         .map(|asset| asset.deployments.len())
@@ -590,13 +697,14 @@ async fn get_supported_chains(
 
 async fn get_chain_assets(
     State(state): State<ApiState>,
+    scope: TenantScope,
     Path(chain_id): Path<String>,
 ) -> Result<Json<Vec<AssetResponse>>, (StatusCode, Json<ApiError>)> {
     let service = state.asset_service.read().await;
     let chain = parse_supported_chain(&chain_id)
         .map_err(|e| (StatusCode::BAD_REQUEST, Json(ApiError::new("INVALID_CHAIN", &e, 400))))?;
     
-    let all_assets = service.get_all_assets();
+    let all_assets = service.get_all_assets(&scope);
     let chain_assets: Vec<AssetResponse> = all_assets.iter()
         .filter(|asset| asset.deployments.contains_key(&chain))
         .map(|asset| AssetResponse {
@@ -665,6 +773,15 @@ fn parse_supported_chain(s: &str) -> Result<crate::services::multi_chain_asset_s
     }
 }
 
+fn parse_chain_list(s: Option<&str>) -> Result<Vec<crate::services::multi_chain_asset_service::SupportedChain>, String> {
+    s.map(|list| list.split(',')
+            .map(str::trim)
+            .filter(|chain| !chain.is_empty())
+            .map(parse_supported_chain)
+            .collect())
+        .unwrap_or_else(|| Ok(Vec::new()))
+}
+
 fn parse_investor_type(s: &str) -> Result<InvestorType, String> {
     match s.to_lowercase().as_str() {
         "retail" => Ok(InvestorType::Retail),
@@ -678,26 +795,11 @@ fn parse_investor_type(s: &str) -> Result<InvestorType, String> {
 }
 
 fn parse_kyc_status(s: &str) -> Result<KYCStatus, String> {
-    match s.to_lowercase().as_str() {
-        "not_started" | "notstarted" => Ok(KYCStatus::NotStarted),
-        "in_progress" | "inprogress" => Ok(KYCStatus::InProgress),
-        "completed" => Ok(KYCStatus::Completed),
-        "expired" => Ok(KYCStatus::Expired),
-        "rejected" => Ok(KYCStatus::Rejected),
-        "under_review" | "underreview" => Ok(KYCStatus::UnderReview),
-        _ => Err(format!("Invalid KYC status: {}", s)),
-    }
+    s.parse().map_err(|_| format!("Invalid KYC status: {}", s))
 }
 
 fn parse_aml_status(s: &str) -> Result<AMLStatus, String> {
-    match s.to_lowercase().as_str() {
-        "clear" => Ok(AMLStatus::Clear),
-        "under_review" | "underreview" => Ok(AMLStatus::UnderReview),
-        "flagged" => Ok(AMLStatus::Flagged),
-        "blocked" => Ok(AMLStatus::Blocked),
-        "requires_enhanced_due_diligence" | "requiresenhancedduediligence" => Ok(AMLStatus::RequiresEnhancedDueDiligence),
-        _ => Err(format!("Invalid AML status: {}", s)),
-    }
+    s.parse().map_err(|_| format!("Invalid AML status: {}", s))
 }
 
 fn parse_accreditation_status(s: &str) -> Result<AccreditationStatus, String> {
@@ -721,5 +823,38 @@ fn parse_risk_rating(s: &str) -> Result<RiskRating, String> {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::multi_chain_asset_service::{CrossChainLiquidity, SupportedChain};
+    
+    #[test]
+    fn test_partial_liquidity_response_shape() {
+        let mut liquidity = AssetLiquidity::default();
+        liquidity.chains.insert(SupportedChain::Ethereum, CrossChainLiquidity {
+            chain: SupportedChain::Ethereum,
+            total_liquidity_usd: 1_500_000.0,
+            available_liquidity_usd: 1_200_000.0,
+            pools: Vec::new(),
+            bridge_liquidity: 0.0,
+        });
+        liquidity.errors.insert(SupportedChain::Avalanche, ChainLiquidityError {
+            error: "No response within 3000ms".to_string(),
+            timed_out: true,
+            attempts: 2,
+        });
+        
+        let response = serde_json::to_value(LiquidityResponse::from_liquidity("asset-1".to_string(), liquidity)).unwrap();
+        assert_eq!(response["partial"], true);
+        assert_eq!(response["total_liquidity_usd"], 1_500_000.0);
+        assert_eq!(response["chain_liquidity"]["Ethereum"]["pool_count"], 0);
+        assert_eq!(response["errors"]["Avalanche"], serde_json::json!({
+            "error": "No response within 3000ms",
+            "timed_out": true,
+            "attempts": 2,
+        }));
+    }
+}
+
 // API module for RESTful endpoints
 // This will be expanded in future phases 
\ No newline at end of file
@@ -11,6 +11,9 @@ use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn, error};
 use jsonwebtoken::{decode, DecodingKey, Validation, Algorithm};
+use price_oracle::{FxRateService, OracleAggregator};
+use quantera_types::{Currency, Pii, WalletAddress};
+use quantera_secrets::RotatingSecret;
 
 use crate::services::portfolio_service::{
     PortfolioService, PortfolioSummary, AssetHolding,
@@ -36,13 +39,22 @@ pub struct PortfolioJwtClaims {
 #[derive(Clone)]
 pub struct PortfolioApiState {
     pub db: Arc<PgPool>,
-    pub jwt_secret: String,
+    pub jwt_secret: RotatingSecret,
+    pub prices: Arc<OracleAggregator>,
+    pub fx_rates: Arc<FxRateService>,
 }
 
 // ============================================================================
 // Query Parameter Structs
 // ============================================================================
 
+#[derive(Debug, Deserialize)]
+pub struct PortfolioQuery {
+    /// ISO 4217 code totals are reported in; defaults to USD
+    #[serde(default)]
+    pub reporting_currency: Currency,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct HoldingsQuery {
     pub category: Option<String>,
@@ -121,8 +133,9 @@ fn validate_portfolio_access(
 
     if token_wallet != requested_wallet_lower {
         warn!(
-            "Portfolio access denied: token wallet {} does not match requested wallet {}",
-            token_wallet, requested_wallet_lower
+            token_wallet = %Pii::new(&token_wallet),
+            requested_wallet = %Pii::new(&requested_wallet_lower),
+            "Portfolio access denied: token wallet does not match requested wallet"
         );
         return Err((
             StatusCode::FORBIDDEN,
@@ -135,17 +148,9 @@ fn validate_portfolio_access(
 
 /// Validate wallet address format
 fn validate_wallet_address(wallet: &str) -> Result<(), (StatusCode, String)> {
-    if !wallet.starts_with("0x") {
-        return Err((StatusCode::BAD_REQUEST, "Wallet address must start with 0x".to_string()));
-    }
-    if wallet.len() != 42 {
-        return Err((StatusCode::BAD_REQUEST, "Wallet address must be 42 characters".to_string()));
-    }
-    // Validate hex characters
-    if !wallet[2..].chars().all(|c| c.is_ascii_hexdigit()) {
-        return Err((StatusCode::BAD_REQUEST, "Wallet address contains invalid characters".to_string()));
-    }
-    Ok(())
+    wallet.parse::<WalletAddress>()
+        .map(|_| ())
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))
 }
 
 // ============================================================================
@@ -157,20 +162,21 @@ fn validate_wallet_address(wallet: &str) -> Result<(), (StatusCode, String)> {
 async fn get_portfolio_handler(
     State(state): State<PortfolioApiState>,
     Path(wallet_address): Path<String>,
+    Query(query): Query<PortfolioQuery>,
     headers: HeaderMap,
 ) -> Result<Json<PortfolioSummary>, (StatusCode, String)> {
     // Validate wallet address format
     validate_wallet_address(&wallet_address)?;
 
     // Authenticate and authorize
-    let claims = validate_portfolio_access(&headers, &wallet_address, &state.jwt_secret)?;
-    info!("Authenticated portfolio access for wallet: {}", claims.sub);
+    let claims = validate_portfolio_access(&headers, &wallet_address, state.jwt_secret.current().expose())?;
+    info!(wallet = %Pii::new(&claims.sub), "Authenticated portfolio access");
 
-    let service = PortfolioService::new(state.db);
-    let portfolio = service.get_portfolio(&wallet_address)
+    let service = PortfolioService::new(state.db).with_prices(state.prices).with_fx_rates(state.fx_rates);
+    let portfolio = service.get_portfolio(&wallet_address, query.reporting_currency)
         .await
         .map_err(|e| {
-            error!("Failed to fetch portfolio for {}: {}", wallet_address, e);
+            error!(wallet = %Pii::new(&wallet_address), error = %e, "Failed to fetch portfolio");
             (StatusCode::INTERNAL_SERVER_ERROR, "Failed to fetch portfolio".to_string())
         })?;
 
@@ -189,8 +195,8 @@ async fn get_holdings_handler(
     validate_wallet_address(&wallet_address)?;
 
     // Authenticate and authorize
-    let claims = validate_portfolio_access(&headers, &wallet_address, &state.jwt_secret)?;
-    info!("Authenticated holdings access for wallet: {}", claims.sub);
+    let claims = validate_portfolio_access(&headers, &wallet_address, state.jwt_secret.current().expose())?;
+    info!(wallet = %Pii::new(&claims.sub), "Authenticated holdings access");
 
     // Validate query parameters
     if let Some(limit) = query.limit {
@@ -204,7 +210,7 @@ async fn get_holdings_handler(
         }
     }
 
-    let service = PortfolioService::new(state.db);
+    let service = PortfolioService::new(state.db).with_prices(state.prices);
     let holdings = service.get_holdings(
         &wallet_address,
         query.category.as_deref(),
@@ -215,7 +221,7 @@ async fn get_holdings_handler(
     )
     .await
     .map_err(|e| {
-        error!("Failed to fetch holdings for {}: {}", wallet_address, e);
+        error!(wallet = %Pii::new(&wallet_address), error = %e, "Failed to fetch holdings");
         (StatusCode::INTERNAL_SERVER_ERROR, "Failed to fetch holdings".to_string())
     })?;
 
@@ -237,8 +243,8 @@ async fn get_transactions_handler(
     validate_wallet_address(&wallet_address)?;
 
     // Authenticate and authorize
-    let claims = validate_portfolio_access(&headers, &wallet_address, &state.jwt_secret)?;
-    info!("Authenticated transactions access for wallet: {}", claims.sub);
+    let claims = validate_portfolio_access(&headers, &wallet_address, state.jwt_secret.current().expose())?;
+    info!(wallet = %Pii::new(&claims.sub), "Authenticated transactions access");
 
     // Validate query parameters
     if let Some(limit) = query.limit {
@@ -247,7 +253,7 @@ async fn get_transactions_handler(
         }
     }
 
-    let service = PortfolioService::new(state.db);
+    let service = PortfolioService::new(state.db).with_prices(state.prices);
     let transactions = service.get_transactions(
         &wallet_address,
         query.transaction_type.as_deref(),
@@ -257,7 +263,7 @@ async fn get_transactions_handler(
     )
     .await
     .map_err(|e| {
-        error!("Failed to fetch transactions for {}: {}", wallet_address, e);
+        error!(wallet = %Pii::new(&wallet_address), error = %e, "Failed to fetch transactions");
         (StatusCode::INTERNAL_SERVER_ERROR, "Failed to fetch transactions".to_string())
     })?;
 
@@ -279,8 +285,8 @@ async fn get_performance_handler(
     validate_wallet_address(&wallet_address)?;
 
     // Authenticate and authorize
-    let claims = validate_portfolio_access(&headers, &wallet_address, &state.jwt_secret)?;
-    info!("Authenticated performance access for wallet: {}", claims.sub);
+    let claims = validate_portfolio_access(&headers, &wallet_address, state.jwt_secret.current().expose())?;
+    info!(wallet = %Pii::new(&claims.sub), "Authenticated performance access");
 
     // Validate period parameter
     if let Some(ref period) = query.period {
@@ -293,14 +299,14 @@ async fn get_performance_handler(
         }
     }
 
-    let service = PortfolioService::new(state.db);
+    let service = PortfolioService::new(state.db).with_prices(state.prices);
     let performance = service.calculate_performance(
         &wallet_address,
         query.period.as_deref(),
     )
     .await
     .map_err(|e| {
-        error!("Failed to calculate performance for {}: {}", wallet_address, e);
+        error!(wallet = %Pii::new(&wallet_address), error = %e, "Failed to calculate performance");
         (StatusCode::INTERNAL_SERVER_ERROR, "Failed to calculate performance".to_string())
     })?;
 
@@ -319,8 +325,8 @@ async fn get_yield_handler(
     validate_wallet_address(&wallet_address)?;
 
     // Authenticate and authorize
-    let claims = validate_portfolio_access(&headers, &wallet_address, &state.jwt_secret)?;
-    info!("Authenticated yield access for wallet: {}", claims.sub);
+    let claims = validate_portfolio_access(&headers, &wallet_address, state.jwt_secret.current().expose())?;
+    info!(wallet = %Pii::new(&claims.sub), "Authenticated yield access");
 
     // Validate status parameter
     if let Some(ref status) = query.status {
@@ -333,14 +339,14 @@ async fn get_yield_handler(
         }
     }
 
-    let service = PortfolioService::new(state.db);
+    let service = PortfolioService::new(state.db).with_prices(state.prices);
     let distributions = service.get_yield_distributions(
         &wallet_address,
         query.status.as_deref(),
     )
     .await
     .map_err(|e| {
-        error!("Failed to fetch yield distributions for {}: {}", wallet_address, e);
+        error!(wallet = %Pii::new(&wallet_address), error = %e, "Failed to fetch yield distributions");
         (StatusCode::INTERNAL_SERVER_ERROR, "Failed to fetch yield distributions".to_string())
     })?;
 
@@ -360,14 +366,14 @@ async fn get_impact_handler(
     validate_wallet_address(&wallet_address)?;
 
     // Authenticate and authorize
-    let claims = validate_portfolio_access(&headers, &wallet_address, &state.jwt_secret)?;
-    info!("Authenticated impact access for wallet: {}", claims.sub);
+    let claims = validate_portfolio_access(&headers, &wallet_address, state.jwt_secret.current().expose())?;
+    info!(wallet = %Pii::new(&claims.sub), "Authenticated impact access");
 
-    let service = PortfolioService::new(state.db);
+    let service = PortfolioService::new(state.db).with_prices(state.prices);
     let impact = service.calculate_impact(&wallet_address)
         .await
         .map_err(|e| {
-            error!("Failed to calculate impact for {}: {}", wallet_address, e);
+            error!(wallet = %Pii::new(&wallet_address), error = %e, "Failed to calculate impact");
             (StatusCode::INTERNAL_SERVER_ERROR, "Failed to calculate impact".to_string())
         })?;
 
@@ -380,14 +386,17 @@ async fn get_impact_handler(
 
 /// Create portfolio router with authenticated endpoints
 /// All endpoints require valid JWT token and wallet ownership verification
-pub fn create_portfolio_router(db: Arc<PgPool>) -> Router {
-    // Load JWT secret from environment
-    let jwt_secret = std::env::var("JWT_SECRET")
-        .expect("JWT_SECRET must be set for portfolio API authentication");
-
+pub fn create_portfolio_router(
+    db: Arc<PgPool>,
+    prices: Arc<OracleAggregator>,
+    fx_rates: Arc<FxRateService>,
+    jwt_secret: RotatingSecret,
+) -> Router {
     let state = PortfolioApiState {
         db,
         jwt_secret,
+        prices,
+        fx_rates,
     };
 
     Router::new()
@@ -0,0 +1,322 @@
+// Rate limit backends: per-process, or shared across replicas through Redis
+use chrono::Utc;
+use redis::aio::ConnectionManager;
+use redis::Script;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use super::secure_api::{AtomicRateLimiter, RateLimitResult, RateLimitType};
+
+const DEFAULT_KEY_PREFIX: &str = "quantera:ratelimit";
+const DEFAULT_REDIS_TIMEOUT_MS: u64 = 200;
+
+/// Increment every window key, setting its expiry when the window opens.
+/// Returns count and remaining TTL in milliseconds for each key.
+const WINDOW_SCRIPT: &str = r#"
+local results = {}
+for _, key in ipairs(KEYS) do
+    local count = redis.call('INCR', key)
+    if count == 1 then
+        redis.call('PEXPIRE', key, ARGV[1])
+    end
+    table.insert(results, count)
+    table.insert(results, redis.call('PTTL', key))
+end
+return results
+"#;
+
+/// What a request is told when the shared window cannot be read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedisFailurePolicy {
+    /// Allow the request; only the local limiter applies
+    Open,
+    /// Reject the request
+    Closed,
+}
+
+/// Counters for shared-window failures
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RedisLimiterStats {
+    pub redis_failures: u64,
+    pub failed_open: u64,
+    pub failed_closed: u64,
+}
+
+/// Limits shared across replicas through Redis, with the local limiter as a pre-filter.
+///
+/// A request the local limiter rejects is already over the shared limit, so it is shed
+/// without a Redis round trip.
+pub struct RedisRateLimiter {
+    local: AtomicRateLimiter,
+    client: redis::Client,
+    connection: Mutex<Option<ConnectionManager>>,
+    script: Script,
+    key_prefix: String,
+    failure_policy: RedisFailurePolicy,
+    timeout: Duration,
+    failed_open: AtomicU64,
+    failed_closed: AtomicU64,
+}
+
+impl RedisRateLimiter {
+    /// Connects lazily; an unreachable Redis is handled per request by the failure policy
+    pub fn new(
+        local: AtomicRateLimiter,
+        redis_url: &str,
+        key_prefix: impl Into<String>,
+        failure_policy: RedisFailurePolicy,
+    ) -> Result<Self, String> {
+        let client = redis::Client::open(redis_url)
+            .map_err(|e| format!("Invalid rate limit Redis URL: {}", e))?;
+
+        Ok(Self {
+            local,
+            client,
+            connection: Mutex::new(None),
+            script: Script::new(WINDOW_SCRIPT),
+            key_prefix: key_prefix.into(),
+            failure_policy,
+            timeout: Duration::from_millis(DEFAULT_REDIS_TIMEOUT_MS),
+            failed_open: AtomicU64::new(0),
+            failed_closed: AtomicU64::new(0),
+        })
+    }
+
+    /// Time allowed for the Redis round trip before the failure policy applies
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn stats(&self) -> RedisLimiterStats {
+        let failed_open = self.failed_open.load(Ordering::Relaxed);
+        let failed_closed = self.failed_closed.load(Ordering::Relaxed);
+        RedisLimiterStats {
+            redis_failures: failed_open + failed_closed,
+            failed_open,
+            failed_closed,
+        }
+    }
+
+    pub async fn check_combined(&self, user_id: Option<&str>, ip: Option<&str>) -> RateLimitResult {
+        let local = self.local.check_combined(user_id, ip);
+        if !local.allowed {
+            return local;
+        }
+
+        let user_id_str = user_id.unwrap_or("anonymous");
+        let is_authenticated = user_id.is_some() && user_id != Some("anonymous");
+
+        let mut keys = vec![format!("{}:user:{}", self.key_prefix, user_id_str)];
+        if let Some(ip_addr) = ip {
+            keys.push(format!("{}:ip:{}", self.key_prefix, ip_addr));
+        }
+
+        let windows = match tokio::time::timeout(self.timeout, self.increment(&keys)).await {
+            Ok(Ok(windows)) => windows,
+            Ok(Err(e)) => return self.on_failure(local, &e.to_string()),
+            Err(_) => return self.on_failure(local, "timed out"),
+        };
+
+        let now_ms = Utc::now().timestamp_millis() as u64;
+        let mut limits = vec![(self.local.user_limit(is_authenticated), RateLimitType::User)];
+        if ip.is_some() {
+            limits.push((self.local.ip_limit(), RateLimitType::Ip));
+        }
+
+        let mut result: Option<RateLimitResult> = None;
+        for ((count, ttl_ms), (limit, limit_type)) in windows.into_iter().zip(limits) {
+            let ttl_ms = if ttl_ms > 0 { ttl_ms as u64 } else { self.local.window_duration_ms() };
+            let reset_at = now_ms + ttl_ms;
+            let count = count.max(0) as u64;
+
+            if count > limit {
+                return RateLimitResult { allowed: false, remaining: 0, reset_at, limit_type };
+            }
+
+            let remaining = limit - count;
+            result = Some(match result {
+                Some(current) if current.remaining < remaining => RateLimitResult {
+                    reset_at: current.reset_at.max(reset_at),
+                    ..current
+                },
+                Some(current) => RateLimitResult {
+                    allowed: true,
+                    remaining,
+                    reset_at: current.reset_at.max(reset_at),
+                    limit_type,
+                },
+                None => RateLimitResult { allowed: true, remaining, reset_at, limit_type },
+            });
+        }
+
+        result.unwrap_or(local)
+    }
+
+    async fn increment(&self, keys: &[String]) -> redis::RedisResult<Vec<(i64, i64)>> {
+        let mut connection = self.connection().await?;
+
+        let mut invocation = self.script.prepare_invoke();
+        for key in keys {
+            invocation.key(key);
+        }
+        invocation.arg(self.local.window_duration_ms());
+
+        let flat: Vec<i64> = match invocation.invoke_async(&mut connection).await {
+            Ok(flat) => flat,
+            Err(e) => {
+                // Reconnect on the next request
+                if e.is_io_error() || e.is_connection_dropped() {
+                    *self.connection.lock().await = None;
+                }
+                return Err(e);
+            }
+        };
+
+        Ok(flat.chunks(2)
+            .map(|window| (window[0], window.get(1).copied().unwrap_or(-1)))
+            .collect())
+    }
+
+    async fn connection(&self) -> redis::RedisResult<ConnectionManager> {
+        let mut connection = self.connection.lock().await;
+        if let Some(existing) = connection.as_ref() {
+            return Ok(existing.clone());
+        }
+
+        let manager = ConnectionManager::new(self.client.clone()).await?;
+        *connection = Some(manager.clone());
+        Ok(manager)
+    }
+
+    fn on_failure(&self, local: RateLimitResult, error: &str) -> RateLimitResult {
+        match self.failure_policy {
+            RedisFailurePolicy::Open => {
+                let failures = self.failed_open.fetch_add(1, Ordering::Relaxed) + 1;
+                warn!("Rate limit Redis unavailable ({}), failing open [failed_open={}]", error, failures);
+                local
+            }
+            RedisFailurePolicy::Closed => {
+                let failures = self.failed_closed.fetch_add(1, Ordering::Relaxed) + 1;
+                warn!("Rate limit Redis unavailable ({}), failing closed [failed_closed={}]", error, failures);
+                RateLimitResult { allowed: false, remaining: 0, ..local }
+            }
+        }
+    }
+}
+
+/// Rate limiter selected by `RATE_LIMIT_MODE`
+pub enum RateLimitBackend {
+    /// Per-process limits
+    Local(AtomicRateLimiter),
+    /// Limits shared by every replica using the same Redis
+    Redis(RedisRateLimiter),
+}
+
+impl RateLimitBackend {
+    /// Read `RATE_LIMIT_MODE` (local or redis). Redis mode uses `RATE_LIMIT_REDIS_URL`
+    /// (falling back to `REDIS_URL`), `RATE_LIMIT_REDIS_FAILURE_POLICY` (open or closed)
+    /// and `RATE_LIMIT_REDIS_TIMEOUT_MS`.
+    pub fn from_env() -> Result<Self, String> {
+        let local = AtomicRateLimiter::new();
+
+        match std::env::var("RATE_LIMIT_MODE").ok().as_deref() {
+            None | Some("") | Some("local") => Ok(Self::Local(local)),
+            Some("redis") => {
+                let redis_url = std::env::var("RATE_LIMIT_REDIS_URL")
+                    .or_else(|_| std::env::var("REDIS_URL"))
+                    .map_err(|_| "RATE_LIMIT_MODE=redis requires RATE_LIMIT_REDIS_URL or REDIS_URL".to_string())?;
+
+                let failure_policy = match std::env::var("RATE_LIMIT_REDIS_FAILURE_POLICY").ok().as_deref() {
+                    None | Some("") | Some("open") => RedisFailurePolicy::Open,
+                    Some("closed") => RedisFailurePolicy::Closed,
+                    Some(other) => return Err(format!(
+                        "RATE_LIMIT_REDIS_FAILURE_POLICY must be open or closed, got {}", other
+                    )),
+                };
+
+                let timeout_ms = match std::env::var("RATE_LIMIT_REDIS_TIMEOUT_MS") {
+                    Ok(ms) => ms.parse::<u64>()
+                        .ok()
+                        .filter(|ms| *ms > 0)
+                        .ok_or_else(|| "RATE_LIMIT_REDIS_TIMEOUT_MS must be a positive integer".to_string())?,
+                    Err(_) => DEFAULT_REDIS_TIMEOUT_MS,
+                };
+
+                info!("Rate limiter using shared Redis windows, failing {:?} when Redis is unavailable", failure_policy);
+                Ok(Self::Redis(
+                    RedisRateLimiter::new(local, &redis_url, DEFAULT_KEY_PREFIX, failure_policy)?
+                        .with_timeout(Duration::from_millis(timeout_ms)),
+                ))
+            }
+            Some(other) => Err(format!("RATE_LIMIT_MODE must be local or redis, got {}", other)),
+        }
+    }
+
+    pub async fn check_combined(&self, user_id: Option<&str>, ip: Option<&str>) -> RateLimitResult {
+        match self {
+            Self::Local(limiter) => limiter.check_combined(user_id, ip),
+            Self::Redis(limiter) => limiter.check_combined(user_id, ip).await,
+        }
+    }
+
+    pub fn authenticated_limit(&self) -> u64 {
+        match self {
+            Self::Local(limiter) => limiter.authenticated_limit(),
+            Self::Redis(limiter) => limiter.local.authenticated_limit(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(redis_url: &str, prefix: &str, policy: RedisFailurePolicy) -> RedisRateLimiter {
+        RedisRateLimiter::new(AtomicRateLimiter::with_limits(100, 4, 0), redis_url, prefix, policy).unwrap()
+    }
+
+    /// Runs only when TEST_REDIS_URL points at a disposable Redis
+    #[tokio::test]
+    async fn test_window_shared_across_instances() {
+        let Ok(redis_url) = std::env::var("TEST_REDIS_URL") else {
+            eprintln!("TEST_REDIS_URL not set, skipping shared window test");
+            return;
+        };
+        let prefix = format!("test:ratelimit:{}", uuid::Uuid::new_v4());
+        let first = limiter(&redis_url, &prefix, RedisFailurePolicy::Closed);
+        let second = limiter(&redis_url, &prefix, RedisFailurePolicy::Closed);
+
+        // Anonymous limit of 4 per window, split across replicas; neither local
+        // limiter sees more than 2 requests
+        for (i, instance) in [&first, &second, &first, &second].into_iter().enumerate() {
+            let result = instance.check_combined(None, Some("10.0.0.1")).await;
+            assert!(result.allowed, "request {} should be allowed", i + 1);
+            assert_eq!(result.remaining, 3 - i as u64);
+        }
+
+        let rejected = second.check_combined(None, Some("10.0.0.1")).await;
+        assert!(!rejected.allowed);
+        assert!(matches!(rejected.limit_type, RateLimitType::User));
+        assert!(!first.check_combined(None, Some("10.0.0.1")).await.allowed);
+
+        assert_eq!(first.stats(), RedisLimiterStats::default());
+        assert_eq!(second.stats(), RedisLimiterStats::default());
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_redis_follows_failure_policy() {
+        let unreachable = "redis://127.0.0.1:1/";
+
+        let open = limiter(unreachable, "test", RedisFailurePolicy::Open);
+        assert!(open.check_combined(Some("0xabc"), None).await.allowed);
+        assert_eq!(open.stats(), RedisLimiterStats { redis_failures: 1, failed_open: 1, failed_closed: 0 });
+
+        let closed = limiter(unreachable, "test", RedisFailurePolicy::Closed);
+        assert!(!closed.check_combined(Some("0xabc"), None).await.allowed);
+        assert!(!closed.check_combined(Some("0xabc"), None).await.allowed);
+        assert_eq!(closed.stats(), RedisLimiterStats { redis_failures: 2, failed_open: 0, failed_closed: 2 });
+    }
+}
@@ -1,8 +1,8 @@
 use axum::{
     extract::{Path, Query, State, ConnectInfo},
-    http::{StatusCode, HeaderMap},
+    http::{header, StatusCode, HeaderMap},
     response::{Json, IntoResponse},
-    routing::{get, post, put},
+    routing::{get, post, put, delete},
     Router,
     middleware,
 };
@@ -12,18 +12,47 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::net::SocketAddr;
 use tokio::sync::RwLock;
 use uuid::Uuid;
+use price_oracle::FxRateService;
+use quantera_types::{Currency, ErrorEnvelope, FinalityDowngrade, FinalityError, Pagination, Pii, SortSpec, WalletAddress};
 use chrono::{DateTime, Utc, Duration};
 use sha2::{Sha256, Digest};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation, Algorithm};
 use tracing::{info, warn, error};
 use sqlx::PgPool;
 use dashmap::DashMap;
+use quantera_secrets::RotatingSecret;
+use quantera_service_auth::{IssuedKey, Scope, ServiceAuthError, ServiceKey, ServiceKeys, DEFAULT_RATE_LIMIT_PER_MINUTE};
 
-use crate::services::multi_chain_asset_service::{MultiChainAssetService, AssetType, ComplianceStandard};
+use crate::services::multi_chain_asset_service::{
+    MultiChainAssetService, AssetType, ComplianceStandard, DeploymentCostReport,
+    DistributionRules, CatalogViewer, AssetConfigUpdate, AssetReviewItem, ChainLiquidityStats,
+};
+use crate::services::asset_review::{ApprovalStatus, AssetReview, ReviewError};
+use crate::services::symbol_registry::{RenameRequest, SymbolError, SymbolReservation, DEFAULT_RESERVATION_HOURS};
 use crate::compliance::enhanced_compliance_engine::{
     EnhancedComplianceEngine, InvestorProfile, InvestorType, KYCStatus, AMLStatus,
-    AccreditationStatus, RiskRating, SanctionsStatus, AccessLevel
+    AccreditationStatus, RiskRating, SanctionsStatus, AccessLevel, ComplianceError, ComplianceResult
 };
+use crate::compliance::jurisdiction_risk::JurisdictionTier;
+use crate::compliance::appropriateness::{AppropriatenessProfile, QuestionSet, QuestionnaireAnswers};
+use crate::compliance::check_cache::CheckCacheStats;
+use crate::compliance::eligibility::EligibilityReport;
+use crate::compliance::messages::TranslationGap;
+use crate::services::prime_brokerage_service::{MarginRatioChange, MarginRatioError, MarginRatios, PrimeBrokerageMetrics, PrimeBrokerageService};
+use crate::services::statements::{self, Statement, StatementError, StatementPeriod};
+use crate::audit_sink::{AuditEvent, AuditStream, SinkStats};
+use crate::task_health::TaskHealth;
+use crate::consistency::{self, ConsistencyChecker, ConsistencyReport, RunSummary, RunTrigger};
+use crate::db_routing::DbRouter;
+use crate::feature_flags::{self, FeatureFlags, FlagError, FlagScope, FlagStatus, LEGACY_LOGIN};
+use crate::tenant::{TenantId, TenantScope, DEFAULT_TENANT, tenant_for_api_key};
+use super::auth_challenge::{ChallengeLimits, client_ip, request_fingerprint, fingerprint_matches};
+use super::auth_sessions::{self, ActiveSession, DeviceInfo, SessionLimits};
+use super::audit_log::{self, AuditLogPage, AuditLogQuery, AuditLogQueryError};
+use super::compliance_reports::{self, ComplianceReportDetail, ComplianceReportPage, ComplianceReportQuery, ReportQueryError};
+use super::admin_summary::{self, AdminSummary, AdminSummaryCache};
+use super::rate_limit::RateLimitBackend;
+use super::{compare_assets, pagination_error, PaginatedResponse, ASSET_SORT_FIELDS, DEFAULT_ASSET_SORT};
 
 // Security Configuration - loaded from environment with defaults
 const MAX_REQUEST_SIZE: usize = 1024 * 1024; // 1MB
@@ -34,15 +63,6 @@ const DEFAULT_RATE_LIMIT_REQUESTS: u64 = 100; // per minute for authenticated us
 const DEFAULT_RATE_LIMIT_ANONYMOUS: u64 = 20; // per minute for anonymous users
 const DEFAULT_RATE_LIMIT_BURST: u64 = 10; // burst allowance
 
-// SECURITY FIX: JWT secret must be loaded from environment variables
-fn get_jwt_secret() -> String {
-    std::env::var("JWT_SECRET")
-        .unwrap_or_else(|_| {
-            error!("CRITICAL SECURITY ERROR: JWT_SECRET environment variable not set!");
-            panic!("JWT_SECRET environment variable is required for security");
-        })
-}
-
 // Authentication & Authorization
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JwtClaims {
@@ -52,10 +72,30 @@ pub struct JwtClaims {
     pub exp: usize,
     pub iat: usize,
     pub permissions: Vec<Permission>,
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+}
+
+impl JwtClaims {
+    /// Tenant this token may see. Tokens without a tenant claim fall back to the
+    /// tenant bound to the request's API key, then to the default tenant.
+    pub fn tenant_scope(&self, api_key: Option<&str>) -> TenantScope {
+        if self.role == UserRole::PlatformAdmin {
+            return TenantScope::AllTenants;
+        }
+
+        let tenant = self.tenant_id.clone()
+            .map(TenantId::new)
+            .or_else(|| api_key.and_then(tenant_for_api_key))
+            .unwrap_or_default();
+
+        TenantScope::Tenant(tenant)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum UserRole {
+    PlatformAdmin, // Operator of the white-label platform; sees every tenant
     Admin,
     AssetManager,
     ComplianceOfficer,
@@ -73,6 +113,9 @@ pub enum Permission {
     ManageInvestors,
     ViewInvestors,
     SystemAdmin,
+    ViewDashboard,
+    /// Checker role of asset issuance; never granted for assets the holder created or edited
+    ApproveAsset,
 }
 
 // Secure API State with encryption
@@ -80,10 +123,24 @@ pub enum Permission {
 pub struct SecureApiState {
     pub asset_service: Arc<RwLock<MultiChainAssetService>>,
     pub compliance_engine: Arc<RwLock<EnhancedComplianceEngine>>,
-    pub jwt_secret: String,
-    pub rate_limiter: Arc<AtomicRateLimiter>,
+    /// Resolved through the secrets provider at startup and re-fetched while running
+    pub jwt_secret: RotatingSecret,
+    pub rate_limiter: Arc<RateLimitBackend>,
     pub audit_logger: Arc<RwLock<AuditLogger>>,
     pub db: Arc<PgPool>, // Phase 3: Database pool for auth
+    /// Replica-aware routing for read-only stats, history and export queries
+    pub db_router: Arc<DbRouter>,
+    pub challenge_limits: ChallengeLimits,
+    pub session_limits: SessionLimits,
+    pub prime_brokerage: Arc<RwLock<PrimeBrokerageService>>,
+    pub task_health: Arc<TaskHealth>,
+    pub summary_cache: Arc<AdminSummaryCache>,
+    pub feature_flags: Arc<FeatureFlags>,
+    /// API keys the other services present on their calls
+    pub service_keys: Arc<ServiceKeys>,
+    /// Exchange rates for totals requested in a reporting currency
+    pub fx_rates: Arc<FxRateService>,
+    pub consistency: Arc<ConsistencyChecker>,
 }
 
 // ============================================================================
@@ -149,6 +206,11 @@ impl AtomicRateLimiter {
             authenticated_limit, anonymous_limit, burst_allowance
         );
 
+        Self::with_limits(authenticated_limit, anonymous_limit, burst_allowance)
+    }
+
+    /// Create a rate limiter with explicit per-minute limits
+    pub fn with_limits(authenticated_limit: u64, anonymous_limit: u64, burst_allowance: u64) -> Self {
         Self {
             user_limits: DashMap::new(),
             ip_limits: DashMap::new(),
@@ -159,24 +221,40 @@ impl AtomicRateLimiter {
         }
     }
 
-    /// Check rate limit for a user (lock-free atomic operation)
-    /// Returns (allowed, remaining_requests, reset_time_ms)
-    pub fn check_user_limit(&self, user_id: &str, is_authenticated: bool) -> (bool, u64, u64) {
-        let limit = if is_authenticated {
+    /// Maximum requests per window for a user
+    pub fn user_limit(&self, is_authenticated: bool) -> u64 {
+        if is_authenticated {
             self.authenticated_limit + self.burst_allowance
         } else {
             self.anonymous_limit
-        };
+        }
+    }
+
+    /// Maximum requests per window for an IP address
+    pub fn ip_limit(&self) -> u64 {
+        // IP limit is more restrictive to prevent DDoS
+        self.anonymous_limit * 5 // Allow 5x anonymous limit per IP
+    }
+
+    /// Advertised per-minute limit for authenticated users
+    pub fn authenticated_limit(&self) -> u64 {
+        self.authenticated_limit
+    }
+
+    pub fn window_duration_ms(&self) -> u64 {
+        self.window_duration_ms
+    }
 
-        self.check_limit_internal(&self.user_limits, user_id, limit)
+    /// Check rate limit for a user (lock-free atomic operation)
+    /// Returns (allowed, remaining_requests, reset_time_ms)
+    pub fn check_user_limit(&self, user_id: &str, is_authenticated: bool) -> (bool, u64, u64) {
+        self.check_limit_internal(&self.user_limits, user_id, self.user_limit(is_authenticated))
     }
 
     /// Check rate limit for an IP address (lock-free atomic operation)
     /// Returns (allowed, remaining_requests, reset_time_ms)
     pub fn check_ip_limit(&self, ip: &str) -> (bool, u64, u64) {
-        // IP limit is more restrictive to prevent DDoS
-        let ip_limit = self.anonymous_limit * 5; // Allow 5x anonymous limit per IP
-        self.check_limit_internal(&self.ip_limits, ip, ip_limit)
+        self.check_limit_internal(&self.ip_limits, ip, self.ip_limit())
     }
 
     /// Internal lock-free rate limit check with atomic operations
@@ -353,7 +431,9 @@ impl RateLimiter {
 // Audit Logging
 #[derive(Debug, Clone, Serialize)]
 pub struct AuditLogEntry {
+    pub id: Uuid,
     pub timestamp: DateTime<Utc>,
+    pub tenant_id: TenantId,
     pub user_id: String,
     pub action: String,
     pub resource: String,
@@ -363,22 +443,87 @@ pub struct AuditLogEntry {
     pub details: serde_json::Value,
 }
 
+/// Audit trail; entries go to the audit_log table when a database is attached, else stay in memory
 #[derive(Debug)]
 pub struct AuditLogger {
     entries: Vec<AuditLogEntry>,
+    db: Option<Arc<PgPool>>,
+    reads: Option<Arc<DbRouter>>,
+    retention: Duration,
+    stream: Option<Arc<AuditStream>>,
 }
 
 impl AuditLogger {
     pub fn new() -> Self {
         Self {
             entries: Vec::new(),
+            db: None,
+            reads: None,
+            retention: audit_log::retention_from_env(),
+            stream: None,
         }
     }
 
+    /// Persist entries to the audit_log table instead of keeping them in memory
+    pub fn with_db(mut self, db: Arc<PgPool>) -> Self {
+        self.db = Some(db);
+        self
+    }
+
+    /// Serve audit log queries through `router`, from the read replica when one is healthy
+    pub fn with_read_router(mut self, router: Arc<DbRouter>) -> Self {
+        self.reads = Some(router);
+        self
+    }
+
+    /// Also stream entries to the configured SIEM sinks
+    pub fn with_stream(mut self, stream: Arc<AuditStream>) -> Self {
+        self.stream = Some(stream);
+        self
+    }
+
     pub fn log(&mut self, entry: AuditLogEntry) {
         info!("AUDIT: {} - {} - {} - {}", 
             entry.user_id, entry.action, entry.resource, entry.success);
-        self.entries.push(entry);
+
+        if let Some(stream) = &self.stream {
+            stream.publish(AuditEvent::from(&entry));
+        }
+
+        match &self.db {
+            Some(db) => {
+                let db = db.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = audit_log::persist_entry(db.as_ref(), &entry).await {
+                        error!("Failed to persist audit entry {}: {}", entry.id, e);
+                    }
+                });
+            }
+            None => {
+                let oldest = Utc::now() - self.retention;
+                self.entries.retain(|existing| existing.timestamp >= oldest);
+                self.entries.push(entry);
+            }
+        }
+    }
+
+    /// One page of entries visible in `scope`, newest first
+    pub async fn page(&self, scope: &TenantScope, query: &AuditLogQuery) -> Result<AuditLogPage, AuditLogQueryError> {
+        match &self.db {
+            Some(db) => {
+                let db = match &self.reads {
+                    Some(router) => router.read().await,
+                    None => db.as_ref(),
+                };
+                audit_log::page_persisted(db, scope, query, Utc::now(), self.retention).await
+            }
+            None => audit_log::page_entries(&self.entries, scope, query, Utc::now(), self.retention),
+        }
+    }
+
+    /// Delivery state of each SIEM sink; empty when none are configured
+    pub fn sink_stats(&self) -> Vec<SinkStats> {
+        self.stream.as_ref().map_or_else(Vec::new, |stream| stream.stats())
     }
 }
 
@@ -396,9 +541,77 @@ pub struct SecureCreateAssetRequest {
     pub jurisdiction: String,
     #[serde(deserialize_with = "validate_total_supply")]
     pub total_supply: u128,
+    /// ISO 4217 code; defaults to USD
+    #[serde(default)]
+    pub currency: Currency,
     pub description: Option<String>,
+    #[serde(default)]
+    pub distribution: Option<DistributionRules>,
+}
+
+/// Changes to a rejected asset; omitted fields are unchanged
+#[derive(Debug, Default, Deserialize)]
+pub struct UpdateAssetRequest {
+    #[serde(default, deserialize_with = "validate_optional_asset_name")]
+    pub name: Option<String>,
+    pub asset_type: Option<String>,
+    pub compliance_standard: Option<String>,
+    pub regulatory_framework: Option<String>,
+    #[serde(default, deserialize_with = "validate_optional_jurisdiction")]
+    pub jurisdiction: Option<String>,
+    #[serde(default, deserialize_with = "validate_optional_total_supply")]
+    pub total_supply: Option<u128>,
+    pub distribution: Option<DistributionRules>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ReviewDecisionRequest {
+    #[serde(default)]
+    pub comments: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FinalityDowngradeRequest {
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReserveSymbolRequest {
+    #[serde(deserialize_with = "validate_symbol")]
+    pub symbol: String,
+    /// Defaults to seven days
+    pub hold_hours: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RenameSymbolRequest {
+    #[serde(deserialize_with = "validate_symbol")]
+    pub symbol: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AssetSearchQuery {
+    pub q: Option<String>,
+    pub asset_type: Option<String>,
+    pub jurisdiction: Option<String>,
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+    pub cursor: Option<String>,
+    pub sort: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct InvestorListQuery {
+    pub jurisdiction: Option<String>,
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+    pub cursor: Option<String>,
+    pub sort: Option<String>,
+}
+
+/// Fields the investor listing can be sorted by
+const INVESTOR_SORT_FIELDS: &[&str] = &["investor_id", "jurisdiction", "compliance_score", "last_updated"];
+
 // Challenge-Response Authentication Structures (Phase 3)
 #[derive(Debug, Deserialize)]
 pub struct ChallengeRequest {
@@ -442,42 +655,8 @@ pub struct LoginResponse {
     pub permissions: Vec<Permission>,
 }
 
-#[derive(Debug, Serialize)]
-pub struct SecureApiError {
-    pub error: String,
-    pub message: String,
-    pub code: u16,
-    pub timestamp: DateTime<Utc>,
-    pub request_id: String,
-}
-
-impl SecureApiError {
-    pub fn new(error: &str, message: &str, code: u16) -> Self {
-        Self {
-            error: error.to_string(),
-            message: message.to_string(),
-            code,
-            timestamp: Utc::now(),
-            request_id: Uuid::new_v4().to_string(),
-        }
-    }
-
-    pub fn unauthorized() -> Self {
-        Self::new("UNAUTHORIZED", "Authentication required", 401)
-    }
-
-    pub fn forbidden() -> Self {
-        Self::new("FORBIDDEN", "Insufficient permissions", 403)
-    }
-
-    pub fn rate_limited() -> Self {
-        Self::new("RATE_LIMITED", "Too many requests", 429)
-    }
-
-    pub fn validation_error(message: &str) -> Self {
-        Self::new("VALIDATION_ERROR", message, 400)
-    }
-}
+/// Error body of the secure API, shared with the other stacks
+pub type SecureApiError = ErrorEnvelope;
 
 // Input Validation Functions
 fn validate_asset_name<'de, D>(deserializer: D) -> Result<String, D::Error>
@@ -531,8 +710,36 @@ where
     Ok(supply)
 }
 
+fn validate_optional_asset_name<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Name(#[serde(deserialize_with = "validate_asset_name")] String);
+    Ok(Option::<Name>::deserialize(deserializer)?.map(|Name(name)| name))
+}
+
+fn validate_optional_jurisdiction<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Jurisdiction(#[serde(deserialize_with = "validate_jurisdiction")] String);
+    Ok(Option::<Jurisdiction>::deserialize(deserializer)?.map(|Jurisdiction(j)| j))
+}
+
+fn validate_optional_total_supply<'de, D>(deserializer: D) -> Result<Option<u128>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Supply(#[serde(deserialize_with = "validate_total_supply")] u128);
+    Ok(Option::<Supply>::deserialize(deserializer)?.map(|Supply(supply)| supply))
+}
+
 // Authentication Middleware
 pub async fn auth_middleware(
+    State(state): State<SecureApiState>,
     headers: HeaderMap,
     mut req: axum::extract::Request,
     next: axum::middleware::Next,
@@ -545,7 +752,7 @@ pub async fn auth_middleware(
 
     let claims = decode::<JwtClaims>(
         token,
-        &DecodingKey::from_secret(get_jwt_secret().as_ref()),
+        &DecodingKey::from_secret(state.jwt_secret.current().expose().as_bytes()),
         &Validation::new(Algorithm::HS256),
     )
     .map_err(|_| (StatusCode::UNAUTHORIZED, Json(SecureApiError::unauthorized())))?
@@ -557,8 +764,12 @@ pub async fn auth_middleware(
         return Err((StatusCode::UNAUTHORIZED, Json(SecureApiError::unauthorized())));
     }
 
-    // Add claims to request extensions
+    let api_key = headers.get("X-API-Key").and_then(|header| header.to_str().ok());
+    let scope = claims.tenant_scope(api_key);
+
+    // Add claims and tenant scope to request extensions
     req.extensions_mut().insert(claims);
+    req.extensions_mut().insert(scope);
     
     Ok(next.run(req).await)
 }
@@ -583,18 +794,10 @@ pub async fn rate_limit_middleware(
         });
 
     // Extract client IP from headers (check forwarded headers for proxies)
-    let client_ip = headers
-        .get("X-Forwarded-For")
-        .and_then(|h| h.to_str().ok())
-        .and_then(|s| s.split(',').next())
-        .map(|s| s.trim())
-        .or_else(|| {
-            headers.get("X-Real-IP")
-                .and_then(|h| h.to_str().ok())
-        });
+    let client_ip = client_ip(&headers);
 
     // Perform atomic rate limit check (no locks required)
-    let result = state.rate_limiter.check_combined(user_id, client_ip);
+    let result = state.rate_limiter.check_combined(user_id, client_ip).await;
 
     if !result.allowed {
         warn!(
@@ -610,7 +813,7 @@ pub async fn rate_limit_middleware(
         let headers = response.headers_mut();
         headers.insert(
             "X-RateLimit-Limit",
-            format!("{}", state.rate_limiter.authenticated_limit)
+            format!("{}", state.rate_limiter.authenticated_limit())
                 .parse()
                 .unwrap_or_default(),
         );
@@ -641,7 +844,7 @@ pub async fn rate_limit_middleware(
     let headers = response.headers_mut();
     headers.insert(
         "X-RateLimit-Limit",
-        format!("{}", state.rate_limiter.authenticated_limit)
+        format!("{}", state.rate_limiter.authenticated_limit())
             .parse()
             .unwrap_or_default(),
     );
@@ -664,7 +867,8 @@ pub async fn rate_limit_middleware(
 // Permission Checking
 fn check_permission(claims: &JwtClaims, required_permission: Permission) -> bool {
     claims.permissions.contains(&required_permission) || 
-    claims.role == UserRole::Admin
+    claims.role == UserRole::Admin ||
+    claims.role == UserRole::PlatformAdmin
 }
 
 // Secure Router with Authentication
@@ -675,36 +879,114 @@ pub fn create_secure_router(state: SecureApiState) -> Router {
         .route("/api/v1/auth/verify", post(verify_signature))
         .route("/api/v1/auth/logout", post(logout))
         .route("/api/v1/auth/validate", get(validate_token))
-        // .route("/api/v1/auth/login", post(login)) // TODO: Fix error type mismatch - disabled for Phase 3A
+        .route("/api/v1/auth/sessions", get(list_sessions))
+        .route("/api/v1/auth/sessions/:id", delete(revoke_session))
+        .route("/api/v1/auth/login", post(login).route_layer(
+            middleware::from_fn_with_state((state.feature_flags.clone(), LEGACY_LOGIN), feature_flags::require_flag),
+        ))
         .route("/api/v1/health", get(health_check))
         
         // Protected routes (auth required)
         .route("/api/v1/assets", post(secure_create_asset))
         .route("/api/v1/assets", get(secure_list_assets))
-        .route("/api/v1/assets/:asset_id", get(secure_get_asset))
+        .route("/api/v1/assets/:asset_id", get(secure_get_asset).put(secure_update_asset))
+        .route("/api/v1/assets/:asset_id/review", get(secure_get_asset_review))
+        .route("/api/v1/assets/:asset_id/resubmit", post(secure_resubmit_asset))
         .route("/api/v1/assets/:asset_id/deploy", post(secure_deploy_asset))
+        .route("/api/v1/assets/:asset_id/deployment-costs", get(secure_get_deployment_costs))
+        .route("/api/v1/assets/:asset_id/symbol-rename", post(secure_request_symbol_rename))
+        .route("/api/v1/assets/symbol-reservations", post(secure_reserve_symbol))
+        .route("/api/v1/assets/symbol-reservations/:symbol", delete(secure_release_symbol))
+        .route("/api/v1/treasuries/:asset_id/eligibility", get(secure_get_treasury_eligibility))
         .route("/api/v1/compliance/check", post(secure_check_compliance))
-        .route("/api/v1/compliance/investors", post(secure_create_investor))
+        .route("/api/v1/compliance/jurisdiction-risk", get(secure_get_jurisdiction_risk))
+        .route("/api/v1/compliance/reports", get(secure_list_compliance_reports))
+        .route("/api/v1/compliance/reports/:report_id", get(secure_get_compliance_report))
+        .route("/api/v1/compliance/investors", get(secure_list_investors).post(secure_create_investor))
         .route("/api/v1/compliance/investors/:investor_id", get(secure_get_investor))
+        .route("/api/v1/compliance/investors/:investor_id/questionnaire", post(secure_submit_questionnaire))
+        .route("/api/v1/compliance/questionnaires/:jurisdiction", get(secure_get_question_set))
+        .route("/api/v1/compliance/questionnaires/:jurisdiction", put(secure_publish_question_set))
         .route("/api/v1/admin/audit-log", get(get_audit_log))
+        .route("/api/v1/admin/summary", get(get_admin_summary))
+        .route("/api/v1/admin/feature-flags", get(list_feature_flags))
+        .route("/api/v1/admin/feature-flags/:flag", put(set_feature_flag))
+        .route("/api/v1/admin/compliance-check-cache", get(get_compliance_check_cache_stats))
+        .route("/api/v1/admin/liquidity-sources", get(get_liquidity_source_stats))
+        .route("/api/v1/admin/compliance-translation-gaps", get(get_translation_gaps))
+        .route("/api/v1/admin/audit-sinks", get(get_audit_sink_stats))
+        .route("/api/v1/admin/symbol-renames", get(list_symbol_renames))
+        .route("/api/v1/admin/symbol-renames/:request_id/approve", post(approve_symbol_rename))
+        .route("/api/v1/admin/symbol-renames/:request_id/reject", post(reject_symbol_rename))
+        .route("/api/v1/admin/assets/:asset_id/distribution", put(secure_update_distribution_rules))
+        .route("/api/v1/admin/assets/:asset_id/deployments/:chain/downgrade", post(downgrade_deployment_finality))
+        .route("/api/v1/admin/asset-reviews", get(list_asset_reviews))
+        .route("/api/v1/admin/asset-reviews/:asset_id/approve", post(approve_asset))
+        .route("/api/v1/admin/asset-reviews/:asset_id/reject", post(reject_asset))
+        .route("/api/v1/admin/prime-accounts/:institution/margin-ratios", get(list_margin_ratio_changes).put(set_margin_ratios))
+        .route("/api/v1/admin/prime-brokerage/metrics", get(get_prime_brokerage_metrics))
+        .route("/api/v1/institutions/:institution/statements", get(list_statements).post(generate_statement))
+        .route("/api/v1/institutions/:institution/statements/:statement_id/pdf", get(get_statement_pdf))
+        .route("/api/v1/admin/service-keys", get(list_service_keys).post(issue_service_key))
+        .route("/api/v1/admin/service-keys/:service/rotate", post(rotate_service_key))
+        .route("/api/v1/admin/service-keys/:key_id", delete(revoke_service_key))
+        .route("/api/v1/admin/consistency-checks", get(list_consistency_runs).post(run_consistency_checks))
+        .route("/api/v1/admin/consistency-checks/:run_id", get(get_consistency_run))
         
         // Apply middleware
         .layer(middleware::from_fn_with_state(state.clone(), rate_limit_middleware))
-        .route_layer(middleware::from_fn(auth_middleware))
+        .route_layer(middleware::from_fn_with_state(state.clone(), auth_middleware))
         
         .with_state(state)
 }
 
 // Phase 3 Authentication Handlers (Challenge-Response Pattern)
 
+/// Generic failure for challenge verification so callers cannot tell which check failed
+fn challenge_auth_failed() -> (StatusCode, String) {
+    (StatusCode::UNAUTHORIZED, "Authentication failed".to_string())
+}
+
 /// Generate authentication challenge for wallet signing
+///
+/// The response has the same shape whether or not the wallet has authenticated before.
+/// Each wallet and client IP may hold a limited number of outstanding challenges, and the
+/// challenge is bound to the requesting origin and user agent.
 async fn create_challenge(
     State(state): State<SecureApiState>,
+    headers: HeaderMap,
     Json(req): Json<ChallengeRequest>,
 ) -> Result<Json<ChallengeResponse>, (StatusCode, String)> {
     // Validate wallet address format
-    if !req.wallet_address.starts_with("0x") || req.wallet_address.len() != 42 {
-        return Err((StatusCode::BAD_REQUEST, "Invalid wallet address format".to_string()));
+    let wallet_address = req.wallet_address.parse::<WalletAddress>()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid wallet address format".to_string()))?
+        .to_string();
+    let ip = client_ip(&headers).map(str::to_string);
+    
+    // Cap outstanding challenges per wallet and per IP
+    use sqlx::Row;
+    let outstanding = sqlx::query(
+        "SELECT COUNT(*) FILTER (WHERE wallet_address = $1) AS wallet_count,
+                COUNT(*) FILTER (WHERE client_ip = $2) AS ip_count
+         FROM auth_challenges
+         WHERE used = false AND expires_at > NOW()
+           AND (wallet_address = $1 OR client_ip = $2)"
+    )
+    .bind(&wallet_address)
+    .bind(ip.as_deref())
+    .fetch_one(state.db.as_ref())
+    .await
+    .map_err(|e| {
+        error!("Failed to count outstanding challenges: {}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, "Internal error".to_string())
+    })?;
+    
+    let wallet_count: i64 = outstanding.get("wallet_count");
+    let ip_count: i64 = outstanding.get("ip_count");
+    
+    if !state.challenge_limits.allows(wallet_count, ip_count) {
+        warn!("Outstanding challenge cap reached: ip={:?}", ip);
+        return Err((StatusCode::TOO_MANY_REQUESTS, "Too many outstanding challenges".to_string()));
     }
     
     // Generate challenge message
@@ -718,19 +1000,23 @@ async fn create_challenge(
     
     // Store challenge in database
     sqlx::query(
-        "INSERT INTO auth_challenges (wallet_address, challenge, expires_at) VALUES ($1, $2, $3)"
+        "INSERT INTO auth_challenges (wallet_address, challenge, expires_at, fingerprint, client_ip)
+         VALUES ($1, $2, $3, $4, $5)"
     )
-    .bind(req.wallet_address.to_lowercase())
+    .bind(&wallet_address)
     .bind(&challenge)
     .bind(expires_at)
+    .bind(request_fingerprint(&headers))
+    .bind(ip.as_deref())
     .execute(state.db.as_ref())
     .await
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e)))?;
-    
-    info!("Challenge generated for wallet: {}", req.wallet_address);
+    .map_err(|e| {
+        error!("Failed to store challenge: {}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, "Internal error".to_string())
+    })?;
     
     Ok(Json(ChallengeResponse {
-        wallet_address: req.wallet_address,
+        wallet_address,
         challenge,
         expires_at: expires_at.timestamp(),
     }))
@@ -739,11 +1025,12 @@ async fn create_challenge(
 /// Verify wallet signature and issue JWT token
 async fn verify_signature(
     State(state): State<SecureApiState>,
+    headers: HeaderMap,
     Json(req): Json<VerifyRequest>,
 ) -> Result<Json<VerifyResponse>, (StatusCode, String)> {
     // Fetch challenge from database
     let challenge_record = sqlx::query(
-        "SELECT challenge, expires_at, used FROM auth_challenges 
+        "SELECT challenge, expires_at, used, fingerprint FROM auth_challenges 
          WHERE wallet_address = $1 
          ORDER BY created_at DESC 
          LIMIT 1"
@@ -751,23 +1038,27 @@ async fn verify_signature(
     .bind(req.wallet_address.to_lowercase())
     .fetch_optional(state.db.as_ref())
     .await
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e)))?
-    .ok_or((StatusCode::UNAUTHORIZED, "No challenge found for this wallet".to_string()))?;
+    .map_err(|e| {
+        error!("Failed to load challenge: {}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, "Internal error".to_string())
+    })?
+    .ok_or_else(challenge_auth_failed)?;
     
     // Extract values from row
     use sqlx::Row;
     let challenge: String = challenge_record.get("challenge");
     let expires_at: DateTime<Utc> = challenge_record.get("expires_at");
     let used: bool = challenge_record.get("used");
+    let fingerprint: Option<String> = challenge_record.get("fingerprint");
     
-    // Check if challenge expired
-    if expires_at < Utc::now() {
-        return Err((StatusCode::UNAUTHORIZED, "Challenge expired".to_string()));
+    // Expired, used and foreign-fingerprint challenges all fail the same way
+    if expires_at < Utc::now() || used {
+        return Err(challenge_auth_failed());
     }
     
-    // Check if already used
-    if used {
-        return Err((StatusCode::UNAUTHORIZED, "Challenge already used".to_string()));
+    if !fingerprint_matches(fingerprint.as_deref().unwrap_or(""), &request_fingerprint(&headers)) {
+        warn!(wallet = %Pii::new(&req.wallet_address), "Challenge fingerprint mismatch");
+        return Err(challenge_auth_failed());
     }
     
     // PHASE 3B: Real ECDSA signature verification using ethers-rs
@@ -777,7 +1068,7 @@ async fn verify_signature(
     // Parse the signature
     let signature = req.signature.parse::<Signature>()
         .map_err(|e| {
-            warn!("Invalid signature format from {}: {}", req.wallet_address, e);
+            warn!(wallet = %Pii::new(&req.wallet_address), error = %e, "Invalid signature format");
             (StatusCode::BAD_REQUEST, "Invalid signature format".to_string())
         })?;
     
@@ -787,8 +1078,8 @@ async fn verify_signature(
     // Recover the address that signed this message
     let recovered_address = signature.recover(message_hash)
         .map_err(|e| {
-            warn!("Signature recovery failed for {}: {}", req.wallet_address, e);
-            (StatusCode::UNAUTHORIZED, "Invalid signature".to_string())
+            warn!(wallet = %Pii::new(&req.wallet_address), error = %e, "Signature recovery failed");
+            challenge_auth_failed()
         })?;
     
     // Compare recovered address with claimed address (case-insensitive)
@@ -801,13 +1092,15 @@ async fn verify_signature(
             expected_address, 
             recovered_address_hex
         );
-        return Err((
-            StatusCode::UNAUTHORIZED, 
-            "Signature verification failed - address mismatch".to_string()
-        ));
+        return Err(challenge_auth_failed());
     }
     
-    info!("Signature verified successfully for {}", req.wallet_address);
+    info!(wallet = %Pii::new(&req.wallet_address), "Signature verified successfully");
+    
+    // Challenge consumption, user upsert, session and audit entry commit together,
+    // so a failed session insert leaves the challenge usable
+    let db_error = |e: sqlx::Error| (StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e));
+    let mut tx = state.db.begin().await.map_err(db_error)?;
     
     // Mark challenge as used
     sqlx::query(
@@ -815,23 +1108,24 @@ async fn verify_signature(
     )
     .bind(req.wallet_address.to_lowercase())
     .bind(&challenge)
-    .execute(state.db.as_ref())
+    .execute(&mut *tx)
     .await
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e)))?;
+    .map_err(db_error)?;
     
     // Create or update user
     let user_record = sqlx::query(
         "INSERT INTO users (wallet_address) VALUES ($1) 
          ON CONFLICT (wallet_address) DO UPDATE SET last_login = NOW()
-         RETURNING id, wallet_address"
+         RETURNING id, wallet_address, tenant_id"
     )
     .bind(req.wallet_address.to_lowercase())
-    .fetch_one(state.db.as_ref())
+    .fetch_one(&mut *tx)
     .await
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e)))?;
+    .map_err(db_error)?;
     
     let user_id: Uuid = user_record.get("id");
     let wallet_address: String = user_record.get("wallet_address");
+    let tenant_id: String = user_record.get("tenant_id");
     
     // Generate JWT token
     let exp = (Utc::now() + Duration::hours(24)).timestamp() as i64;
@@ -844,6 +1138,7 @@ async fn verify_signature(
         exp: i64,          // expiration timestamp
         iat: i64,          // issued at timestamp
         role: String,      // user role
+        tenant_id: String, // issuer tenant the user belongs to
     }
     
     let claims = SimpleClaims {
@@ -851,29 +1146,54 @@ async fn verify_signature(
         exp,
         iat,
         role: "user".to_string(), // Default role for Phase 3
+        tenant_id: tenant_id.clone(),
     };
     
     let token = encode(
         &Header::default(),
         &claims,
-        &EncodingKey::from_secret(state.jwt_secret.as_bytes()),
+        &EncodingKey::from_secret(state.jwt_secret.current().expose().as_bytes()),
     )
     .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Token generation failed: {}", e)))?;
     
-    // Store session
+    // Store session with its device, evicting the oldest sessions beyond the per-user cap
     let token_hash = format!("{:x}", Sha256::digest(token.as_bytes()));
+    let device = DeviceInfo::from_headers(&headers);
     
-    sqlx::query(
-        "INSERT INTO auth_sessions (user_id, token_hash, expires_at) VALUES ($1, $2, $3)"
+    let (session_id, evicted) = auth_sessions::create_session(
+        &mut tx,
+        user_id,
+        &token_hash,
+        chrono::DateTime::from_timestamp(exp, 0).unwrap(),
+        &device,
+        state.session_limits,
     )
-    .bind(user_id)
-    .bind(&token_hash)
-    .bind(chrono::DateTime::from_timestamp(exp, 0).unwrap())
-    .execute(state.db.as_ref())
     .await
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e)))?;
+    .map_err(db_error)?;
+    
+    audit_log::persist_entry(&mut *tx, &AuditLogEntry {
+        id: Uuid::new_v4(),
+        timestamp: Utc::now(),
+        tenant_id: TenantId::new(&tenant_id),
+        user_id: wallet_address.clone(),
+        action: "SESSION_CREATED".to_string(),
+        resource: "AUTH".to_string(),
+        ip_address: None,
+        user_agent: None,
+        success: true,
+        details: serde_json::json!({
+            "session_id": session_id,
+            "expires_at": exp,
+            "device": device.label,
+            "evicted_sessions": evicted,
+        }),
+    })
+    .await
+    .map_err(db_error)?;
     
-    info!("Authentication successful for wallet: {}", wallet_address);
+    tx.commit().await.map_err(db_error)?;
+    
+    info!(wallet = %Pii::new(&wallet_address), "Authentication successful");
     
     Ok(Json(VerifyResponse {
         token,
@@ -916,7 +1236,7 @@ async fn validate_token(
     
     let token_data = decode::<SimpleClaims>(
         token,
-        &DecodingKey::from_secret(state.jwt_secret.as_bytes()),
+        &DecodingKey::from_secret(state.jwt_secret.current().expose().as_bytes()),
         &Validation::default(),
     );
     
@@ -1010,6 +1330,61 @@ async fn logout(
     })))
 }
 
+/// Hash of the bearer token and the user whose active session it is
+async fn session_caller(state: &SecureApiState, headers: &HeaderMap) -> Result<(String, Uuid), (StatusCode, String)> {
+    let token = headers
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .ok_or((StatusCode::UNAUTHORIZED, "Missing authorization header".to_string()))?;
+    
+    let token_hash = format!("{:x}", Sha256::digest(token.as_bytes()));
+    let user_id = auth_sessions::session_user(state.db.as_ref(), &token_hash)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e)))?
+        .ok_or((StatusCode::UNAUTHORIZED, "Session is not active".to_string()))?;
+    
+    Ok((token_hash, user_id))
+}
+
+/// List the caller's active sessions, marking the one making the request
+async fn list_sessions(
+    State(state): State<SecureApiState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<ActiveSession>>, (StatusCode, String)> {
+    let (token_hash, user_id) = session_caller(&state, &headers).await?;
+    
+    let sessions = auth_sessions::list_active_sessions(state.db.as_ref(), user_id, &token_hash)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e)))?;
+    
+    Ok(Json(sessions))
+}
+
+/// Revoke one of the caller's own sessions
+async fn revoke_session(
+    State(state): State<SecureApiState>,
+    headers: HeaderMap,
+    Path(session_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let (_, user_id) = session_caller(&state, &headers).await?;
+    
+    // Other users' sessions are reported as missing rather than forbidden
+    let revoked = auth_sessions::revoke_session(state.db.as_ref(), user_id, session_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e)))?;
+    if !revoked {
+        return Err((StatusCode::NOT_FOUND, "Session not found".to_string()));
+    }
+    
+    info!(session_id = %session_id, user = %Pii::new(&user_id), "Session revoked by its owner");
+    
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "session_id": session_id
+    })))
+}
+
 // Legacy Authentication Handler (v1.3.0 compatibility)
 async fn login(
     State(state): State<SecureApiState>,
@@ -1037,19 +1412,22 @@ async fn login(
         exp,
         iat: now as usize,
         permissions: permissions.clone(),
+        tenant_id: Some(DEFAULT_TENANT.to_string()),
     };
 
     let token = encode(
         &Header::default(),
         &claims,
-        &EncodingKey::from_secret(get_jwt_secret().as_ref()),
+        &EncodingKey::from_secret(state.jwt_secret.current().expose().as_bytes()),
     )
     .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(SecureApiError::new("TOKEN_GENERATION_FAILED", "Failed to generate token", 500))))?;
 
     // Log successful login
     let mut audit_logger = state.audit_logger.write().await;
     audit_logger.log(AuditLogEntry {
+        id: Uuid::new_v4(),
         timestamp: Utc::now(),
+        tenant_id: TenantId::default(),
         user_id: request.wallet_address.clone(),
         action: "LOGIN".to_string(),
         resource: "AUTH".to_string(),
@@ -1071,6 +1449,7 @@ async fn login(
 async fn secure_create_asset(
     State(state): State<SecureApiState>,
     claims: axum::Extension<JwtClaims>,
+    scope: TenantScope,
     Json(request): Json<SecureCreateAssetRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<SecureApiError>)> {
     // Check permissions
@@ -1091,7 +1470,10 @@ async fn secure_create_asset(
     let compliance_standard = parse_compliance_standard(&request.compliance_standard)
         .map_err(|e| (StatusCode::BAD_REQUEST, Json(SecureApiError::validation_error(&e))))?;
 
+    let tenant_id = issuing_tenant(&scope, &claims);
+
     let asset_id = service.create_asset(
+        tenant_id.clone(),
         request.name.clone(),
         request.symbol.clone(),
         asset_type,
@@ -1099,13 +1481,22 @@ async fn secure_create_asset(
         request.regulatory_framework.clone(),
         request.jurisdiction.clone(),
         request.total_supply,
+        request.currency,
+        &claims.sub,
     ).await
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(SecureApiError::new("CREATION_FAILED", &e.to_string(), 500))))?;
+    .map_err(|e| symbol_error(e, "CREATION_FAILED"))?;
+
+    if let Some(rules) = request.distribution.clone() {
+        service.set_distribution_rules(&TenantScope::Tenant(tenant_id.clone()), &asset_id, rules)
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(SecureApiError::new("CREATION_FAILED", &e.to_string(), 500))))?;
+    }
 
     // Log asset creation
     let mut audit_logger = state.audit_logger.write().await;
     audit_logger.log(AuditLogEntry {
+        id: Uuid::new_v4(),
         timestamp: Utc::now(),
+        tenant_id,
         user_id: claims.sub.clone(),
         action: "CREATE_ASSET".to_string(),
         resource: asset_id.clone(),
@@ -1116,14 +1507,16 @@ async fn secure_create_asset(
             "name": request.name,
             "symbol": request.symbol,
             "asset_type": request.asset_type,
-            "jurisdiction": request.jurisdiction
+            "jurisdiction": request.jurisdiction,
+            "distribution": request.distribution,
+            "approval_status": "pending_approval"
         }),
     });
 
     Ok(Json(serde_json::json!({
         "asset_id": asset_id,
-        "status": "created",
-        "message": "Asset created successfully"
+        "status": "pending_approval",
+        "message": "Asset created; it can be deployed once another user approves it"
     })))
 }
 
@@ -1149,6 +1542,8 @@ fn determine_user_permissions(wallet_address: &str) -> (UserRole, Vec<Permission
                 Permission::ManageInvestors,
                 Permission::ViewInvestors,
                 Permission::SystemAdmin,
+                Permission::ViewDashboard,
+                Permission::ApproveAsset,
             ]
         ),
         addr if addr.starts_with("0xasset") => (
@@ -1168,6 +1563,7 @@ fn determine_user_permissions(wallet_address: &str) -> (UserRole, Vec<Permission
                 Permission::ManageInvestors,
                 Permission::ViewInvestors,
                 Permission::ViewAsset,
+                Permission::ApproveAsset,
             ]
         ),
         _ => (
@@ -1180,98 +1576,1404 @@ fn determine_user_permissions(wallet_address: &str) -> (UserRole, Vec<Permission
     }
 }
 
-// Additional secure handlers would be implemented here...
-async fn secure_list_assets(
-    State(state): State<SecureApiState>,
-    claims: axum::Extension<JwtClaims>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<SecureApiError>)> {
-    if !check_permission(&claims, Permission::ViewAsset) {
-        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+/// Restricted assets are only visible to compliance and admin roles in full; investors
+/// see what their profile's jurisdiction and investor type admit them to.
+async fn catalog_viewer(state: &SecureApiState, claims: &JwtClaims, scope: &TenantScope) -> CatalogViewer {
+    match claims.role {
+        UserRole::PlatformAdmin | UserRole::Admin | UserRole::ComplianceOfficer | UserRole::AssetManager => {
+            CatalogViewer::Unrestricted
+        }
+        UserRole::Investor | UserRole::ReadOnly => {
+            let engine = state.compliance_engine.read().await;
+            match engine.investor_classification(scope, &claims.sub) {
+                Some((jurisdiction, investor_type)) => CatalogViewer::Investor { jurisdiction, investor_type },
+                None => CatalogViewer::Unclassified,
+            }
+        }
     }
+}
 
-    // Implementation here...
-    Ok(Json(serde_json::json!({"message": "Secure list assets implementation"})))
+/// Tenant new assets and symbol reservations belong to: the caller's own, or for platform
+/// admins their tenant claim or the default tenant
+fn issuing_tenant(scope: &TenantScope, claims: &JwtClaims) -> TenantId {
+    scope.tenant().cloned()
+        .or_else(|| claims.tenant_id.clone().map(TenantId::new))
+        .unwrap_or_default()
 }
 
-async fn secure_get_asset(
+/// Symbol collisions are 409s naming the asset that holds the symbol
+fn symbol_error(e: anyhow::Error, code: &str) -> (StatusCode, Json<SecureApiError>) {
+    match e.downcast_ref::<SymbolError>() {
+        Some(SymbolError::Taken { symbol, asset_id }) => (
+            StatusCode::CONFLICT,
+            Json(SecureApiError::new("SYMBOL_TAKEN", &e.to_string(), 409)
+                .with_details(serde_json::json!({ "symbol": symbol, "asset_id": asset_id }))),
+        ),
+        Some(SymbolError::Reserved { symbol, expires_at }) => (
+            StatusCode::CONFLICT,
+            Json(SecureApiError::new("SYMBOL_RESERVED", &e.to_string(), 409)
+                .with_details(serde_json::json!({ "symbol": symbol, "reserved_until": expires_at }))),
+        ),
+        Some(SymbolError::Invalid(_) | SymbolError::SelfApproval) => {
+            (StatusCode::BAD_REQUEST, Json(SecureApiError::validation_error(&e.to_string())))
+        }
+        Some(SymbolError::ReservationNotFound | SymbolError::RenameNotFound) => {
+            (StatusCode::NOT_FOUND, Json(SecureApiError::new("NOT_FOUND", &e.to_string(), 404)))
+        }
+        None => (StatusCode::INTERNAL_SERVER_ERROR, Json(SecureApiError::new(code, &e.to_string(), 500))),
+    }
+}
+
+/// Hold a symbol for the caller's tenant while its asset is onboarded
+async fn secure_reserve_symbol(
     State(state): State<SecureApiState>,
     claims: axum::Extension<JwtClaims>,
-    Path(asset_id): Path<String>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<SecureApiError>)> {
-    if !check_permission(&claims, Permission::ViewAsset) {
+    scope: TenantScope,
+    Json(request): Json<ReserveSymbolRequest>,
+) -> Result<(StatusCode, Json<SymbolReservation>), (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::CreateAsset) {
         return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
     }
 
-    // Implementation here...
-    Ok(Json(serde_json::json!({"asset_id": asset_id, "message": "Secure get asset implementation"})))
+    let hold = Duration::hours(request.hold_hours.unwrap_or(DEFAULT_RESERVATION_HOURS));
+    let reservation = state.asset_service.write().await
+        .reserve_symbol(&issuing_tenant(&scope, &claims), &request.symbol, hold, &claims.sub)
+        .map_err(|e| symbol_error(e, "RESERVATION_FAILED"))?;
+
+    Ok((StatusCode::CREATED, Json(reservation)))
 }
 
-async fn secure_deploy_asset(
+async fn secure_release_symbol(
     State(state): State<SecureApiState>,
     claims: axum::Extension<JwtClaims>,
-    Path(asset_id): Path<String>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<SecureApiError>)> {
-    if !check_permission(&claims, Permission::DeployAsset) {
+    scope: TenantScope,
+    Path(symbol): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::CreateAsset) {
         return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
     }
 
-    // Implementation here...
-    Ok(Json(serde_json::json!({"asset_id": asset_id, "message": "Secure deploy asset implementation"})))
+    state.asset_service.write().await
+        .release_symbol(&issuing_tenant(&scope, &claims), &symbol)
+        .map_err(|e| symbol_error(e, "RELEASE_FAILED"))?;
+
+    Ok(StatusCode::NO_CONTENT)
 }
 
-async fn secure_check_compliance(
+/// Ask for an asset's symbol to change; an admin must approve it before it applies
+async fn secure_request_symbol_rename(
     State(state): State<SecureApiState>,
     claims: axum::Extension<JwtClaims>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<SecureApiError>)> {
-    if !check_permission(&claims, Permission::ViewCompliance) {
+    scope: TenantScope,
+    Path(asset_id): Path<String>,
+    Json(request): Json<RenameSymbolRequest>,
+) -> Result<(StatusCode, Json<RenameRequest>), (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::CreateAsset) {
         return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
     }
 
-    // Implementation here...
-    Ok(Json(serde_json::json!({"message": "Secure compliance check implementation"})))
+    let mut service = state.asset_service.write().await;
+    if service.get_asset(&scope, &asset_id).is_none() {
+        return Err((StatusCode::NOT_FOUND, Json(SecureApiError::new("ASSET_NOT_FOUND", "Asset not found", 404))));
+    }
+    let rename = service.request_symbol_rename(&scope, &asset_id, &request.symbol, &claims.sub)
+        .map_err(|e| symbol_error(e, "RENAME_FAILED"))?;
+    drop(service);
+
+    state.audit_logger.write().await.log(AuditLogEntry {
+        id: Uuid::new_v4(),
+        timestamp: Utc::now(),
+        tenant_id: rename.tenant_id.clone(),
+        user_id: claims.sub.clone(),
+        action: "REQUEST_SYMBOL_RENAME".to_string(),
+        resource: asset_id,
+        ip_address: None,
+        user_agent: None,
+        success: true,
+        details: serde_json::json!(rename),
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(rename)))
 }
 
-async fn secure_create_investor(
+async fn list_symbol_renames(
     State(state): State<SecureApiState>,
     claims: axum::Extension<JwtClaims>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<SecureApiError>)> {
-    if !check_permission(&claims, Permission::ManageInvestors) {
+) -> Result<Json<Vec<RenameRequest>>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::SystemAdmin) {
         return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
     }
 
-    // Implementation here...
-    Ok(Json(serde_json::json!({"message": "Secure create investor implementation"})))
+    let service = state.asset_service.read().await;
+    Ok(Json(service.pending_symbol_renames().into_iter().cloned().collect()))
 }
 
-async fn secure_get_investor(
+/// Apply a pending rename; the previous symbol is kept as a searchable alias
+async fn approve_symbol_rename(
     State(state): State<SecureApiState>,
     claims: axum::Extension<JwtClaims>,
-    Path(investor_id): Path<String>,
+    Path(request_id): Path<Uuid>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<SecureApiError>)> {
-    if !check_permission(&claims, Permission::ViewInvestors) {
+    if !check_permission(&claims, Permission::SystemAdmin) {
         return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
     }
 
-    // Implementation here...
-    Ok(Json(serde_json::json!({"investor_id": investor_id, "message": "Secure get investor implementation"})))
+    let mut service = state.asset_service.write().await;
+    let asset = service.approve_symbol_rename(request_id, &claims.sub)
+        .map_err(|e| symbol_error(e, "RENAME_FAILED"))?;
+    let (tenant_id, asset_id, symbol) = (asset.tenant_id.clone(), asset.asset_id.clone(), asset.symbol.clone());
+    let aliases = service.symbol_aliases(&asset_id).to_vec();
+
+    state.audit_logger.write().await.log(AuditLogEntry {
+        id: Uuid::new_v4(),
+        timestamp: Utc::now(),
+        tenant_id,
+        user_id: claims.sub.clone(),
+        action: "APPROVE_SYMBOL_RENAME".to_string(),
+        resource: asset_id.clone(),
+        ip_address: None,
+        user_agent: None,
+        success: true,
+        details: serde_json::json!({ "request_id": request_id, "symbol": symbol, "aliases": aliases }),
+    });
+
+    Ok(Json(serde_json::json!({ "asset_id": asset_id, "symbol": symbol, "aliases": aliases })))
 }
 
-async fn get_audit_log(
+async fn reject_symbol_rename(
     State(state): State<SecureApiState>,
     claims: axum::Extension<JwtClaims>,
-) -> Result<Json<Vec<AuditLogEntry>>, (StatusCode, Json<SecureApiError>)> {
+    Path(request_id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, Json<SecureApiError>)> {
     if !check_permission(&claims, Permission::SystemAdmin) {
         return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
     }
 
-    let audit_logger = state.audit_logger.read().await;
-    Ok(Json(audit_logger.entries.clone()))
+    state.asset_service.write().await.reject_symbol_rename(request_id)
+        .map_err(|e| symbol_error(e, "RENAME_FAILED"))?;
+
+    Ok(StatusCode::NO_CONTENT)
 }
 
-async fn health_check() -> Json<serde_json::Value> {
-    Json(serde_json::json!({
-        "status": "healthy",
-        "timestamp": Utc::now().to_rfc3339(),
+#[derive(Debug, Deserialize)]
+pub struct SetMarginRatiosRequest {
+    pub maintenance_margin_ratio: u32,
+    pub initial_margin_ratio: u32,
+    /// Defaults to now; a future date schedules the change
+    pub effective_at: Option<DateTime<Utc>>,
+    pub reason: String,
+}
+
+fn margin_ratio_error(e: anyhow::Error) -> (StatusCode, Json<SecureApiError>) {
+    match e.downcast_ref::<MarginRatioError>() {
+        Some(MarginRatioError::Invalid(_)) => (StatusCode::BAD_REQUEST, Json(SecureApiError::validation_error(&e.to_string()))),
+        Some(MarginRatioError::AccountNotFound(_)) => {
+            (StatusCode::NOT_FOUND, Json(SecureApiError::new("ACCOUNT_NOT_FOUND", &e.to_string(), 404)))
+        }
+        None => (StatusCode::INTERNAL_SERVER_ERROR, Json(SecureApiError::new("MARGIN_RATIO_CHANGE_FAILED", &e.to_string(), 500))),
+    }
+}
+
+/// Change a prime account's margin ratios; changes in force re-evaluate the account at once
+async fn set_margin_ratios(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    Path(institution): Path<String>,
+    Json(request): Json<SetMarginRatiosRequest>,
+) -> Result<Json<MarginRatioChange>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::SystemAdmin) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+    if request.reason.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, Json(SecureApiError::validation_error("A reason is required"))));
+    }
+
+    let ratios = MarginRatios::new(request.maintenance_margin_ratio, request.initial_margin_ratio);
+    let change = state.prime_brokerage.write().await
+        .set_margin_ratios(&institution, ratios, request.effective_at, &claims.sub, request.reason.trim())
+        .await
+        .map_err(margin_ratio_error)?;
+
+    state.audit_logger.write().await.log(AuditLogEntry {
+        id: Uuid::new_v4(),
+        timestamp: Utc::now(),
+        tenant_id: TenantId::default(),
+        user_id: claims.sub.clone(),
+        action: "SET_MARGIN_RATIOS".to_string(),
+        resource: institution.clone(),
+        ip_address: None,
+        user_agent: None,
+        success: true,
+        details: serde_json::json!({
+            "change_id": change.change_id,
+            "previous": change.previous,
+            "ratios": change.ratios,
+            "effective_at": change.effective_at,
+            "reason": change.reason,
+            "margin_call": change.margin_call.as_ref().map(|call| call.shortfall.to_string()),
+        }),
+    });
+
+    Ok(Json(change))
+}
+
+async fn list_margin_ratio_changes(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    Path(institution): Path<String>,
+) -> Result<Json<Vec<MarginRatioChange>>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::SystemAdmin) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    Ok(Json(state.prime_brokerage.read().await.get_margin_ratio_changes(&institution).to_vec()))
+}
+
+/// Exposure, margin calls and risk spread across prime accounts, including accounts
+/// whose credit is frozen on a stale collateral price
+async fn get_prime_brokerage_metrics(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+) -> Result<Json<PrimeBrokerageMetrics>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::SystemAdmin) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    Ok(Json(state.prime_brokerage.read().await.get_prime_brokerage_metrics()))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatementQuery {
+    /// `YYYY-MM`
+    pub month: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GenerateStatementRequest {
+    /// `YYYY-MM`
+    pub month: String,
+}
+
+fn parse_statement_month(month: &str) -> Result<StatementPeriod, (StatusCode, Json<SecureApiError>)> {
+    StatementPeriod::parse(month)
+        .ok_or_else(|| (StatusCode::BAD_REQUEST, Json(SecureApiError::validation_error("month must be YYYY-MM"))))
+}
+
+fn statement_error(e: StatementError) -> (StatusCode, Json<SecureApiError>) {
+    match e {
+        StatementError::PeriodOpen(_) => (StatusCode::CONFLICT, Json(SecureApiError::new("PERIOD_OPEN", &e.to_string(), 409))),
+        StatementError::NotFound => (StatusCode::NOT_FOUND, Json(SecureApiError::new("STATEMENT_NOT_FOUND", "Statement not found", 404))),
+        e => {
+            error!("Statement request failed: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(SecureApiError::new("STATEMENT_FAILED", "Failed to read statements", 500)))
+        }
+    }
+}
+
+/// Stored statements of an institution, every version, newest month first
+async fn list_statements(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    Path(institution): Path<String>,
+    Query(query): Query<StatementQuery>,
+) -> Result<Json<Vec<Statement>>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::ViewInvestors) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+    let period = query.month.as_deref().map(parse_statement_month).transpose()?;
+
+    let statements = statements::list_statements(state.db_router.read().await, &institution, period).await
+        .map_err(statement_error)?;
+    Ok(Json(statements))
+}
+
+/// Statement of a closed month; returns the stored one unless late ledger entries call for an amendment
+async fn generate_statement(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    Path(institution): Path<String>,
+    Json(request): Json<GenerateStatementRequest>,
+) -> Result<Json<Statement>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::SystemAdmin) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+    let period = parse_statement_month(&request.month)?;
+
+    let margin_calls = state.prime_brokerage.read().await
+        .get_margin_calls(&institution)
+        .cloned()
+        .unwrap_or_default();
+    let statement = statements::generate_statement(state.db.as_ref(), &institution, period, &margin_calls, Utc::now()).await
+        .map_err(statement_error)?;
+
+    state.audit_logger.write().await.log(AuditLogEntry {
+        id: Uuid::new_v4(),
+        timestamp: Utc::now(),
+        tenant_id: TenantId::default(),
+        user_id: claims.sub.clone(),
+        action: "GENERATE_STATEMENT".to_string(),
+        resource: institution.clone(),
+        ip_address: None,
+        user_agent: None,
+        success: true,
+        details: serde_json::json!({
+            "statement_id": statement.statement_id,
+            "period": statement.period,
+            "version": statement.version,
+            "amends": statement.amends,
+        }),
+    });
+
+    Ok(Json(statement))
+}
+
+async fn get_statement_pdf(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    Path((institution, statement_id)): Path<(String, Uuid)>,
+) -> Result<impl IntoResponse, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::ViewInvestors) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    let pdf = statements::statement_pdf(state.db_router.read().await, &institution, statement_id).await
+        .map_err(statement_error)?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"statement-{}.pdf\"", statement_id)),
+        ],
+        pdf,
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ServiceKeyQuery {
+    pub service: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IssueServiceKeyRequest {
+    pub service: String,
+    pub scopes: Vec<Scope>,
+    pub rate_limit_per_minute: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RotateServiceKeyRequest {
+    /// How long the current key keeps working; one hour when omitted
+    pub grace_secs: Option<i64>,
+}
+
+fn service_key_error(e: ServiceAuthError) -> (StatusCode, Json<SecureApiError>) {
+    match e {
+        ServiceAuthError::InvalidRequest(message) => (StatusCode::BAD_REQUEST, Json(SecureApiError::validation_error(&message))),
+        ServiceAuthError::NotFound(_) => (StatusCode::NOT_FOUND, Json(SecureApiError::new("SERVICE_KEY_NOT_FOUND", &e.to_string(), 404))),
+        e => {
+            error!("Service key request failed: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(SecureApiError::new("SERVICE_KEY_FAILED", "Failed to manage service keys", 500)))
+        }
+    }
+}
+
+async fn audit_service_key(state: &SecureApiState, claims: &JwtClaims, action: &str, key: &ServiceKey) {
+    state.audit_logger.write().await.log(AuditLogEntry {
+        id: Uuid::new_v4(),
+        timestamp: Utc::now(),
+        tenant_id: TenantId::default(),
+        user_id: claims.sub.clone(),
+        action: action.to_string(),
+        resource: key.service.clone(),
+        ip_address: None,
+        user_agent: None,
+        success: true,
+        details: serde_json::json!({
+            "key_id": key.key_id,
+            "scopes": key.scopes,
+            "rate_limit_per_minute": key.rate_limit_per_minute,
+        }),
+    });
+}
+
+/// Every service key, live or not; secrets are never returned after issuance
+async fn list_service_keys(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    Query(query): Query<ServiceKeyQuery>,
+) -> Result<Json<Vec<ServiceKey>>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::SystemAdmin) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+    let keys = state.service_keys.list(query.service.as_deref()).await.map_err(service_key_error)?;
+    Ok(Json(keys))
+}
+
+/// First key of a service; the returned `api_key` is shown only once
+async fn issue_service_key(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    Json(request): Json<IssueServiceKeyRequest>,
+) -> Result<(StatusCode, Json<IssuedKey>), (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::SystemAdmin) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+    let issued = state.service_keys.issue(
+        &request.service,
+        request.scopes,
+        request.rate_limit_per_minute.unwrap_or(DEFAULT_RATE_LIMIT_PER_MINUTE),
+        &claims.sub,
+        Utc::now(),
+    ).await.map_err(service_key_error)?;
+
+    audit_service_key(&state, &claims, "ISSUE_SERVICE_KEY", &issued.key).await;
+    Ok((StatusCode::CREATED, Json(issued)))
+}
+
+/// Replacement key for a service; its current key keeps working through the grace period
+async fn rotate_service_key(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    Path(service): Path<String>,
+    Json(request): Json<RotateServiceKeyRequest>,
+) -> Result<(StatusCode, Json<IssuedKey>), (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::SystemAdmin) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+    let grace_secs = request.grace_secs.unwrap_or(3600);
+    if !(0..=7 * 24 * 3600).contains(&grace_secs) {
+        return Err((StatusCode::BAD_REQUEST, Json(SecureApiError::validation_error("grace_secs must be between 0 and 7 days"))));
+    }
+    let issued = state.service_keys.rotate(&service, Duration::seconds(grace_secs), &claims.sub, Utc::now()).await
+        .map_err(service_key_error)?;
+
+    audit_service_key(&state, &claims, "ROTATE_SERVICE_KEY", &issued.key).await;
+    Ok((StatusCode::CREATED, Json(issued)))
+}
+
+async fn revoke_service_key(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    Path(key_id): Path<Uuid>,
+) -> Result<Json<ServiceKey>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::SystemAdmin) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+    let key = state.service_keys.revoke(key_id, Utc::now()).await.map_err(service_key_error)?;
+
+    audit_service_key(&state, &claims, "REVOKE_SERVICE_KEY", &key).await;
+    Ok(Json(key))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConsistencyRunQuery {
+    pub limit: Option<i64>,
+}
+
+fn consistency_error(e: sqlx::Error) -> (StatusCode, Json<SecureApiError>) {
+    error!("Consistency run history failed: {}", e);
+    (StatusCode::INTERNAL_SERVER_ERROR, Json(SecureApiError::new("CONSISTENCY_HISTORY_FAILED", "Failed to read or store consistency runs", 500)))
+}
+
+/// Run every consistency check now, e.g. after an incident; the report is kept in the history
+async fn run_consistency_checks(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+) -> Result<Json<ConsistencyReport>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::SystemAdmin) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+    let report = state.consistency.run_and_store(RunTrigger::Admin).await.map_err(consistency_error)?;
+
+    state.audit_logger.write().await.log(AuditLogEntry {
+        id: Uuid::new_v4(),
+        timestamp: Utc::now(),
+        tenant_id: TenantId::default(),
+        user_id: claims.sub.clone(),
+        action: "RUN_CONSISTENCY_CHECKS".to_string(),
+        resource: report.run_id.to_string(),
+        ip_address: None,
+        user_agent: None,
+        success: true,
+        details: serde_json::json!({ "findings": report.findings().count() }),
+    });
+    Ok(Json(report))
+}
+
+/// Recent runs with finding counts per check, newest first
+async fn list_consistency_runs(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    Query(query): Query<ConsistencyRunQuery>,
+) -> Result<Json<Vec<RunSummary>>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::SystemAdmin) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+    let limit = query.limit.unwrap_or(30).clamp(1, 500);
+    let runs = consistency::recent_runs(state.db_router.read().await, limit).await.map_err(consistency_error)?;
+    Ok(Json(runs))
+}
+
+async fn get_consistency_run(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    Path(run_id): Path<Uuid>,
+) -> Result<Json<ConsistencyReport>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::SystemAdmin) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+    consistency::stored_report(&state.db, run_id).await
+        .map_err(consistency_error)?
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, Json(SecureApiError::new("RUN_NOT_FOUND", "Consistency run not found", 404))))
+}
+
+// Additional secure handlers would be implemented here...
+async fn secure_list_assets(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    scope: TenantScope,
+    Query(query): Query<AssetSearchQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::ViewAsset) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    let asset_type = query.asset_type.as_deref()
+        .map(parse_asset_type)
+        .transpose()
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(SecureApiError::validation_error(&e))))?;
+    let pagination = Pagination::new(query.page, query.per_page, query.cursor.as_deref()).map_err(pagination_error)?;
+    let sort = SortSpec::parse(query.sort.as_deref(), DEFAULT_ASSET_SORT, ASSET_SORT_FIELDS).map_err(pagination_error)?;
+    let term = query.q.as_deref().map(str::to_lowercase);
+
+    let viewer = catalog_viewer(&state, &claims, &scope).await;
+    let service = state.asset_service.read().await;
+    let mut assets: Vec<_> = service.get_all_assets(&scope)
+        .into_iter()
+        .filter(|asset| viewer.can_see(asset))
+        .filter(|asset| asset_type.as_ref()
+            .map_or(true, |t| std::mem::discriminant(t) == std::mem::discriminant(&asset.asset_type)))
+        .filter(|asset| query.jurisdiction.as_ref().map_or(true, |j| &asset.jurisdiction == j))
+        .filter(|asset| term.as_ref().map_or(true, |term| {
+            asset.name.to_lowercase().contains(term)
+                || asset.symbol.to_lowercase().contains(term)
+                || service.symbol_aliases(&asset.asset_id).iter().any(|alias| alias.to_lowercase().contains(term))
+        }))
+        .collect();
+    sort.sort(&mut assets, |field, a, b| compare_assets(field, a, b));
+    let page = pagination.paginate(assets);
+
+    Ok(Json(serde_json::json!({
+        "assets": page.data,
+        "total_count": page.total_count,
+        "page": page.page,
+        "per_page": page.per_page,
+        "total_pages": page.total_pages,
+        "next_cursor": page.next_cursor,
+    })))
+}
+
+async fn secure_get_asset(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    scope: TenantScope,
+    Path(asset_id): Path<String>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::ViewAsset) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    let viewer = catalog_viewer(&state, &claims, &scope).await;
+    let service = state.asset_service.read().await;
+
+    // Assets of other tenants, or outside the investor's distribution rules, 404 so their
+    // existence is not revealed
+    let asset = service.get_asset(&scope, &asset_id)
+        .filter(|asset| viewer.can_see(asset))
+        .ok_or_else(|| (StatusCode::NOT_FOUND, Json(SecureApiError::new("ASSET_NOT_FOUND", "Asset not found", 404))))?;
+
+    Ok(Json(serde_json::json!(asset)))
+}
+
+/// The caller's eligibility for a treasury, criterion by criterion, for gating the invest action
+async fn secure_get_treasury_eligibility(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    scope: TenantScope,
+    Path(asset_id): Path<String>,
+    Query(locale): Query<super::LocaleQuery>,
+    headers: HeaderMap,
+) -> Result<Json<EligibilityReport>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::ViewAsset) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    let viewer = catalog_viewer(&state, &claims, &scope).await;
+    let (asset_type, distribution) = {
+        let service = state.asset_service.read().await;
+        // Private placements stay hidden from investors they are not offered to; other
+        // restrictions are reported so the page can explain them
+        service.get_asset(&scope, &asset_id)
+            .filter(|asset| matches!(asset.asset_type, AssetType::TreasuryNotes))
+            .filter(|asset| !asset.distribution.private_placement || viewer.can_see(asset))
+            .map(|asset| (asset.asset_type.compliance_asset_type(), asset.distribution.clone()))
+            .ok_or_else(|| (StatusCode::NOT_FOUND, Json(SecureApiError::new("TREASURY_NOT_FOUND", "Treasury not found", 404))))?
+    };
+
+    let engine = state.compliance_engine.read().await;
+    let messages = engine.messages().clone();
+    let locale = messages.negotiate(
+        locale.locale.as_deref(),
+        headers.get(header::ACCEPT_LANGUAGE).and_then(|value| value.to_str().ok()),
+    );
+    let report = engine.eligibility(&scope, &claims.sub, &asset_id, asset_type, &distribution);
+
+    Ok(Json(report.localized(&messages, &locale)))
+}
+
+async fn secure_update_distribution_rules(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    scope: TenantScope,
+    Path(asset_id): Path<String>,
+    Json(rules): Json<DistributionRules>,
+) -> Result<Json<DistributionRules>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::ManageCompliance) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    let mut service = state.asset_service.write().await;
+    let asset = service.set_distribution_rules(&scope, &asset_id, rules)
+        .map_err(|_| (StatusCode::NOT_FOUND, Json(SecureApiError::new("ASSET_NOT_FOUND", "Asset not found", 404))))?;
+    let tenant_id = asset.tenant_id.clone();
+    let rules = asset.distribution.clone();
+
+    let mut audit_logger = state.audit_logger.write().await;
+    audit_logger.log(AuditLogEntry {
+        id: Uuid::new_v4(),
+        timestamp: Utc::now(),
+        tenant_id,
+        user_id: claims.sub.clone(),
+        action: "UPDATE_DISTRIBUTION_RULES".to_string(),
+        resource: asset_id,
+        ip_address: None,
+        user_agent: None,
+        success: true,
+        details: serde_json::json!(rules),
+    });
+
+    Ok(Json(rules))
+}
+
+/// Withdraw a deployment's Confirmed status after its block was reorged out
+async fn downgrade_deployment_finality(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    scope: TenantScope,
+    Path((asset_id, chain)): Path<(String, String)>,
+    Json(request): Json<FinalityDowngradeRequest>,
+) -> Result<Json<FinalityDowngrade>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::DeployAsset) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+    if request.reason.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, Json(SecureApiError::validation_error("A downgrade reason is required"))));
+    }
+    let chain = super::parse_supported_chain(&chain)
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(SecureApiError::validation_error(&e))))?;
+
+    let mut service = state.asset_service.write().await;
+    let downgrade = service.downgrade_deployment_finality(&scope, &asset_id, &chain, request.reason.trim(), &claims.sub)
+        .map_err(|e| match e.downcast_ref::<FinalityError>() {
+            Some(FinalityError::NotConfirmed(_) | FinalityError::AlreadyFinal) => {
+                (StatusCode::CONFLICT, Json(SecureApiError::new("INVALID_FINALITY_STATE", &e.to_string(), 409)))
+            }
+            _ => (StatusCode::NOT_FOUND, Json(SecureApiError::new("DEPLOYMENT_NOT_FOUND", &e.to_string(), 404))),
+        })?;
+    let tenant_id = service.get_asset(&scope, &asset_id).map(|asset| asset.tenant_id.clone()).unwrap_or_default();
+    drop(service);
+
+    state.audit_logger.write().await.log(AuditLogEntry {
+        id: Uuid::new_v4(),
+        timestamp: Utc::now(),
+        tenant_id,
+        user_id: claims.sub.clone(),
+        action: "DOWNGRADE_DEPLOYMENT_FINALITY".to_string(),
+        resource: format!("{}/{}", asset_id, chain.name()),
+        ip_address: None,
+        user_agent: None,
+        success: true,
+        details: serde_json::json!(downgrade),
+    });
+
+    Ok(Json(downgrade))
+}
+
+/// Review decisions that conflict with the asset's state are 409s; self-approval is 403
+fn review_error(e: anyhow::Error, code: &str) -> (StatusCode, Json<SecureApiError>) {
+    match e.downcast_ref::<ReviewError>() {
+        Some(ReviewError::NotFound) => {
+            (StatusCode::NOT_FOUND, Json(SecureApiError::new("ASSET_NOT_FOUND", "Asset not found", 404)))
+        }
+        Some(ReviewError::SelfApproval) => {
+            (StatusCode::FORBIDDEN, Json(SecureApiError::new("SELF_APPROVAL", &e.to_string(), 403)))
+        }
+        Some(ReviewError::NotPending(status) | ReviewError::NotEditable(status) | ReviewError::NotApproved(status)) => (
+            StatusCode::CONFLICT,
+            Json(SecureApiError::new("INVALID_APPROVAL_STATE", &e.to_string(), 409)
+                .with_details(serde_json::json!({ "approval_status": status }))),
+        ),
+        Some(ReviewError::CommentsRequired) => {
+            (StatusCode::BAD_REQUEST, Json(SecureApiError::validation_error(&e.to_string())))
+        }
+        None => (StatusCode::INTERNAL_SERVER_ERROR, Json(SecureApiError::new(code, &e.to_string(), 500))),
+    }
+}
+
+fn review_audit_entry(review: &AssetReview, user_id: &str, action: &str) -> AuditLogEntry {
+    AuditLogEntry {
+        id: Uuid::new_v4(),
+        timestamp: Utc::now(),
+        tenant_id: review.tenant_id.clone(),
+        user_id: user_id.to_string(),
+        action: action.to_string(),
+        resource: review.asset_id.clone(),
+        ip_address: None,
+        user_agent: None,
+        success: true,
+        details: serde_json::json!({
+            "status": review.status,
+            "revision": review.revision,
+            "decision": review.latest_decision(),
+        }),
+    }
+}
+
+/// Change a rejected asset's configuration ahead of resubmitting it
+async fn secure_update_asset(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    scope: TenantScope,
+    Path(asset_id): Path<String>,
+    Json(request): Json<UpdateAssetRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::CreateAsset) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    let update = AssetConfigUpdate {
+        name: request.name,
+        asset_type: request.asset_type.as_deref().map(parse_asset_type).transpose()
+            .map_err(|e| (StatusCode::BAD_REQUEST, Json(SecureApiError::validation_error(&e))))?,
+        compliance_standard: request.compliance_standard.as_deref().map(parse_compliance_standard).transpose()
+            .map_err(|e| (StatusCode::BAD_REQUEST, Json(SecureApiError::validation_error(&e))))?,
+        regulatory_framework: request.regulatory_framework,
+        jurisdiction: request.jurisdiction,
+        total_supply: request.total_supply,
+        distribution: request.distribution,
+    };
+
+    let mut service = state.asset_service.write().await;
+    let asset = service.update_asset_config(&scope, &asset_id, update, &claims.sub)
+        .map_err(|e| review_error(e, "UPDATE_FAILED"))?
+        .clone();
+    drop(service);
+
+    state.audit_logger.write().await.log(AuditLogEntry {
+        id: Uuid::new_v4(),
+        timestamp: Utc::now(),
+        tenant_id: asset.tenant_id.clone(),
+        user_id: claims.sub.clone(),
+        action: "UPDATE_ASSET".to_string(),
+        resource: asset_id,
+        ip_address: None,
+        user_agent: None,
+        success: true,
+        details: serde_json::json!(asset),
+    });
+
+    Ok(Json(serde_json::json!(asset)))
+}
+
+async fn secure_resubmit_asset(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    scope: TenantScope,
+    Path(asset_id): Path<String>,
+) -> Result<Json<AssetReview>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::CreateAsset) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    let review = state.asset_service.write().await
+        .resubmit_asset(&scope, &asset_id, &claims.sub)
+        .map_err(|e| review_error(e, "RESUBMIT_FAILED"))?
+        .clone();
+
+    state.audit_logger.write().await.log(review_audit_entry(&review, &claims.sub, "RESUBMIT_ASSET"));
+    Ok(Json(review))
+}
+
+async fn secure_get_asset_review(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    scope: TenantScope,
+    Path(asset_id): Path<String>,
+) -> Result<Json<AssetReview>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::CreateAsset) && !check_permission(&claims, Permission::ApproveAsset) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    let service = state.asset_service.read().await;
+    service.asset_review(&scope, &asset_id)
+        .cloned()
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, Json(SecureApiError::new("ASSET_NOT_FOUND", "Asset not found", 404))))
+}
+
+/// Review queue: pending assets with their full configuration, oldest first
+async fn list_asset_reviews(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    scope: TenantScope,
+) -> Result<Json<Vec<AssetReviewItem>>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::ApproveAsset) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    Ok(Json(state.asset_service.read().await.review_queue(&scope)))
+}
+
+async fn approve_asset(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    scope: TenantScope,
+    Path(asset_id): Path<String>,
+    Json(request): Json<ReviewDecisionRequest>,
+) -> Result<Json<AssetReview>, (StatusCode, Json<SecureApiError>)> {
+    decide_asset(state, claims, scope, asset_id, request, true).await
+}
+
+async fn reject_asset(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    scope: TenantScope,
+    Path(asset_id): Path<String>,
+    Json(request): Json<ReviewDecisionRequest>,
+) -> Result<Json<AssetReview>, (StatusCode, Json<SecureApiError>)> {
+    decide_asset(state, claims, scope, asset_id, request, false).await
+}
+
+/// Record a checker's decision; refused decisions are audited too
+async fn decide_asset(
+    state: SecureApiState,
+    claims: axum::Extension<JwtClaims>,
+    scope: TenantScope,
+    asset_id: String,
+    request: ReviewDecisionRequest,
+    approve: bool,
+) -> Result<Json<AssetReview>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::ApproveAsset) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    let action = if approve { "APPROVE_ASSET" } else { "REJECT_ASSET" };
+    let mut service = state.asset_service.write().await;
+    let result = if approve {
+        service.approve_asset(&scope, &asset_id, &claims.sub, request.comments.clone())
+    } else {
+        service.reject_asset(&scope, &asset_id, &claims.sub, request.comments.as_deref().unwrap_or_default())
+    }
+    .cloned();
+    let tenant_id = service.get_asset(&scope, &asset_id).map(|asset| asset.tenant_id.clone());
+    drop(service);
+
+    match result {
+        Ok(review) => {
+            state.audit_logger.write().await.log(review_audit_entry(&review, &claims.sub, action));
+            Ok(Json(review))
+        }
+        Err(e) => {
+            if let Some(tenant_id) = tenant_id {
+                state.audit_logger.write().await.log(AuditLogEntry {
+                    id: Uuid::new_v4(),
+                    timestamp: Utc::now(),
+                    tenant_id,
+                    user_id: claims.sub.clone(),
+                    action: action.to_string(),
+                    resource: asset_id,
+                    ip_address: None,
+                    user_agent: None,
+                    success: false,
+                    details: serde_json::json!({ "error": e.to_string(), "comments": request.comments }),
+                });
+            }
+            Err(review_error(e, "REVIEW_FAILED"))
+        }
+    }
+}
+
+async fn secure_deploy_asset(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    scope: TenantScope,
+    Path(asset_id): Path<String>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::DeployAsset) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    let service = state.asset_service.read().await;
+    let asset = service.get_asset(&scope, &asset_id)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, Json(SecureApiError::new("ASSET_NOT_FOUND", "Asset not found", 404))))?;
+    if asset.approval_status != ApprovalStatus::Approved {
+        return Err(review_error(ReviewError::NotApproved(asset.approval_status).into(), "DEPLOYMENT_FAILED"));
+    }
+    drop(service);
+
+    // Implementation here...
+    Ok(Json(serde_json::json!({"asset_id": asset_id, "message": "Secure deploy asset implementation"})))
+}
+
+async fn secure_get_deployment_costs(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    scope: TenantScope,
+    Path(asset_id): Path<String>,
+    Query(query): Query<super::DeploymentCostQuery>,
+) -> Result<Json<DeploymentCostReport>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::ViewAsset) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+    
+    let chains = super::parse_chain_list(query.chains.as_deref())
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(SecureApiError::new("INVALID_CHAIN", &e, 400))))?;
+    
+    let viewer = catalog_viewer(&state, &claims, &scope).await;
+    let service = state.asset_service.read().await;
+    
+    if !service.get_asset(&scope, &asset_id).map_or(false, |asset| viewer.can_see(asset)) {
+        return Err((StatusCode::NOT_FOUND, Json(SecureApiError::new("ASSET_NOT_FOUND", "Asset not found", 404))));
+    }
+    
+    let report = service.estimate_deployment_costs(&scope, &asset_id, chains).await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(SecureApiError::new("COST_ESTIMATION_FAILED", &e.to_string(), 500))))?;
+    
+    Ok(Json(report))
+}
+
+/// Run a compliance check, with messages in the requested locale or the Accept-Language one
+async fn secure_check_compliance(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    scope: TenantScope,
+    Query(locale): Query<super::LocaleQuery>,
+    headers: HeaderMap,
+    Json(request): Json<super::ComplianceCheckRequest>,
+) -> Result<Json<ComplianceResult>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::ViewCompliance) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+    let investment_amount: u128 = request.investment_amount.parse()
+        .map_err(|_| (StatusCode::BAD_REQUEST, Json(SecureApiError::validation_error("Invalid investment amount"))))?;
+
+    let mut engine = state.compliance_engine.write().await;
+    let messages = engine.messages().clone();
+    let locale = messages.negotiate(
+        locale.locale.as_deref(),
+        headers.get(header::ACCEPT_LANGUAGE).and_then(|value| value.to_str().ok()),
+    );
+    let result = engine.comprehensive_compliance_check_for(
+        &scope,
+        &request.investor_id,
+        &request.target(),
+        investment_amount,
+        &request.jurisdiction,
+        &claims.sub,
+    ).await
+    .map_err(|e| match e {
+        ComplianceError::AccessDenied => (StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())),
+        ComplianceError::InvestorNotFound => (StatusCode::NOT_FOUND, Json(SecureApiError::new("INVESTOR_NOT_FOUND", "Investor profile not found", 404))),
+        ComplianceError::InvalidInput(msg) => (StatusCode::BAD_REQUEST, Json(SecureApiError::validation_error(&msg))),
+        e => (StatusCode::INTERNAL_SERVER_ERROR, Json(SecureApiError::new("COMPLIANCE_CHECK_FAILED", &e.to_string(), 500))),
+    })?;
+
+    Ok(Json(result.localized(&messages, &locale)))
+}
+
+async fn secure_get_jurisdiction_risk(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+) -> Result<Json<Vec<JurisdictionTier>>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::ViewCompliance) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    let engine = state.compliance_engine.read().await;
+    Ok(Json(engine.jurisdiction_risk().tiers()))
+}
+
+/// Stored compliance reports; investors only see reports about their own address
+async fn secure_list_compliance_reports(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    scope: TenantScope,
+    Query(query): Query<ComplianceReportQuery>,
+) -> Result<Json<ComplianceReportPage>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::ViewCompliance) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    let investor = compliance_reports::investor_filter(&claims.role, &claims.sub, query.investor.as_deref())
+        .map_err(report_query_error)?;
+    let page = compliance_reports::page_reports(state.db_router.read().await, &scope, investor.as_deref(), &query).await
+        .map_err(report_query_error)?;
+
+    Ok(Json(page))
+}
+
+/// Full compliance report with its IPFS hash
+async fn secure_get_compliance_report(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    scope: TenantScope,
+    Path(report_id): Path<Uuid>,
+) -> Result<Json<ComplianceReportDetail>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::ViewCompliance) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    let investor = compliance_reports::investor_filter(&claims.role, &claims.sub, None)
+        .map_err(report_query_error)?;
+    let report = compliance_reports::get_report(state.db_router.read().await, &scope, investor.as_deref(), report_id).await
+        .map_err(report_query_error)?;
+
+    Ok(Json(report))
+}
+
+fn report_query_error(e: ReportQueryError) -> (StatusCode, Json<SecureApiError>) {
+    match e {
+        ReportQueryError::Forbidden => (StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())),
+        ReportQueryError::NotFound => (StatusCode::NOT_FOUND, Json(SecureApiError::new("REPORT_NOT_FOUND", "Compliance report not found", 404))),
+        ReportQueryError::InvalidCursor => (StatusCode::BAD_REQUEST, Json(SecureApiError::new("INVALID_CURSOR", "Invalid cursor", 400))),
+        ReportQueryError::InvalidFilter(msg) => (StatusCode::BAD_REQUEST, Json(SecureApiError::validation_error(&msg))),
+        e => {
+            error!("Compliance report query failed: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(SecureApiError::new("REPORT_QUERY_FAILED", "Failed to read compliance reports", 500)))
+        }
+    }
+}
+
+async fn secure_list_investors(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    scope: TenantScope,
+    Query(query): Query<InvestorListQuery>,
+) -> Result<Json<PaginatedResponse<InvestorProfile>>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::ViewInvestors) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+    let pagination = Pagination::new(query.page, query.per_page, query.cursor.as_deref()).map_err(pagination_error)?;
+    let sort = SortSpec::parse(query.sort.as_deref(), "investor_id", INVESTOR_SORT_FIELDS).map_err(pagination_error)?;
+
+    let engine = state.compliance_engine.read().await;
+    let mut profiles: Vec<_> = engine.list_investor_profiles(&scope, &claims.sub)
+        .map_err(|e| match e {
+            ComplianceError::AccessDenied => (StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())),
+            e => (StatusCode::INTERNAL_SERVER_ERROR, Json(SecureApiError::new("PROFILE_FETCH_FAILED", &e.to_string(), 500))),
+        })?
+        .into_iter()
+        .filter(|profile| query.jurisdiction.as_ref().map_or(true, |j| &profile.jurisdiction == j))
+        .collect();
+    sort.sort(&mut profiles, |field, a, b| match field {
+        "jurisdiction" => a.jurisdiction.cmp(&b.jurisdiction),
+        "compliance_score" => a.compliance_score.cmp(&b.compliance_score),
+        "last_updated" => a.last_updated.cmp(&b.last_updated),
+        _ => a.investor_id.cmp(&b.investor_id),
+    });
+
+    Ok(Json(pagination.paginate(profiles).map(Clone::clone)))
+}
+
+async fn secure_create_investor(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::ManageInvestors) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    // Implementation here...
+    Ok(Json(serde_json::json!({"message": "Secure create investor implementation"})))
+}
+
+async fn secure_get_investor(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    scope: TenantScope,
+    Path(investor_id): Path<String>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::ViewInvestors) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    let mut engine = state.compliance_engine.write().await;
+
+    let profile = engine.get_investor_profile(&scope, &investor_id, &claims.sub).await
+        .map_err(|e| match e {
+            ComplianceError::AccessDenied => (StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())),
+            e => (StatusCode::INTERNAL_SERVER_ERROR, Json(SecureApiError::new("PROFILE_FETCH_FAILED", &e.to_string(), 500))),
+        })?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, Json(SecureApiError::new("INVESTOR_NOT_FOUND", "Investor profile not found", 404))))?;
+
+    Ok(Json(serde_json::json!(profile)))
+}
+
+async fn secure_get_question_set(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    Path(jurisdiction): Path<String>,
+) -> Result<Json<QuestionSet>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::ViewCompliance) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    let engine = state.compliance_engine.read().await;
+    engine.question_set(&jurisdiction)
+        .cloned()
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, Json(SecureApiError::new("QUESTIONNAIRE_NOT_FOUND", "No questionnaire for jurisdiction", 404))))
+}
+
+async fn secure_publish_question_set(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    Path(jurisdiction): Path<String>,
+    Json(mut set): Json<QuestionSet>,
+) -> Result<Json<QuestionSet>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::ManageCompliance) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    set.jurisdiction = jurisdiction;
+    let mut engine = state.compliance_engine.write().await;
+    let published = engine.publish_question_set(set, &claims.sub)
+        .map_err(questionnaire_error)?;
+
+    Ok(Json(published.clone()))
+}
+
+/// Investors submit their own answers; submitting for someone else needs ManageInvestors
+async fn secure_submit_questionnaire(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    scope: TenantScope,
+    Path(investor_id): Path<String>,
+    Json(answers): Json<QuestionnaireAnswers>,
+) -> Result<Json<AppropriatenessProfile>, (StatusCode, Json<SecureApiError>)> {
+    if claims.sub != investor_id && !check_permission(&claims, Permission::ManageInvestors) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    let mut engine = state.compliance_engine.write().await;
+    let assessment = engine.submit_questionnaire(&scope, &investor_id, answers, &claims.sub).await
+        .map_err(questionnaire_error)?;
+
+    Ok(Json(assessment))
+}
+
+fn questionnaire_error(e: ComplianceError) -> (StatusCode, Json<SecureApiError>) {
+    match e {
+        ComplianceError::AccessDenied => (StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())),
+        ComplianceError::InvestorNotFound => (StatusCode::NOT_FOUND, Json(SecureApiError::new("INVESTOR_NOT_FOUND", "Investor profile not found", 404))),
+        ComplianceError::JurisdictionNotSupported => (StatusCode::NOT_FOUND, Json(SecureApiError::new("QUESTIONNAIRE_NOT_FOUND", "No questionnaire for jurisdiction", 404))),
+        ComplianceError::InvalidInput(msg) => (StatusCode::BAD_REQUEST, Json(SecureApiError::new("INVALID_QUESTIONNAIRE", &msg, 400))),
+        e => (StatusCode::INTERNAL_SERVER_ERROR, Json(SecureApiError::new("QUESTIONNAIRE_FAILED", &e.to_string(), 500))),
+    }
+}
+
+async fn get_audit_log(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    scope: TenantScope,
+    Query(query): Query<AuditLogQuery>,
+) -> Result<Json<AuditLogPage>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::SystemAdmin) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    let audit_logger = state.audit_logger.read().await;
+    let page = audit_logger.page(&scope, &query).await
+        .map_err(|e| match e {
+            AuditLogQueryError::InvalidCursor => (StatusCode::BAD_REQUEST, Json(SecureApiError::new("INVALID_CURSOR", "Invalid cursor", 400))),
+            e => {
+                error!("Audit log query failed: {}", e);
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(SecureApiError::new("AUDIT_LOG_FAILED", "Failed to read audit log", 500)))
+            }
+        })?;
+
+    Ok(Json(page))
+}
+
+/// Hit rate of the compliance check result cache
+async fn get_compliance_check_cache_stats(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+) -> Result<Json<CheckCacheStats>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::SystemAdmin) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    Ok(Json(state.compliance_engine.read().await.check_cache_stats()))
+}
+
+/// Per-chain failure, timeout and retry counts of asset liquidity queries
+async fn get_liquidity_source_stats(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+) -> Result<Json<Vec<ChainLiquidityStats>>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::SystemAdmin) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    Ok(Json(state.asset_service.read().await.liquidity_stats()))
+}
+
+/// Compliance messages rendered in English because their locale had no translation
+async fn get_translation_gaps(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+) -> Result<Json<Vec<TranslationGap>>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::SystemAdmin) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    Ok(Json(state.compliance_engine.read().await.messages().gaps()))
+}
+
+/// Lag, backlog and dropped-event counts of the SIEM audit sinks
+async fn get_audit_sink_stats(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+) -> Result<Json<Vec<SinkStats>>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::SystemAdmin) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    Ok(Json(state.audit_logger.read().await.sink_stats()))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AdminSummaryQuery {
+    /// ISO 4217 code totals are reported in; defaults to USD
+    #[serde(default)]
+    pub reporting_currency: Currency,
+}
+
+/// Dashboard header KPIs. Each section carries its own status so a slow or failing
+/// source does not fail the whole response; results are cached for 30 seconds.
+async fn get_admin_summary(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    scope: TenantScope,
+    Query(query): Query<AdminSummaryQuery>,
+) -> Result<Json<AdminSummary>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::SystemAdmin) && !check_permission(&claims, Permission::ViewDashboard) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    let reads = state.db_router.read().await;
+    let reporting = query.reporting_currency;
+    let summary = state.summary_cache.get_or_build(&scope, reporting, admin_summary::build_summary(
+        admin_summary::asset_summary(&state.asset_service, &scope, &state.fx_rates, reporting),
+        admin_summary::compliance_today(&state.compliance_engine, &scope),
+        admin_summary::open_risk_alerts(reads),
+        admin_summary::margin_summary(&state.prime_brokerage),
+        &state.task_health,
+        &state.feature_flags,
+        admin_summary::SOURCE_TIMEOUT,
+    )).await;
+
+    Ok(Json(summary))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetFeatureFlagRequest {
+    pub enabled: bool,
+    /// Override for one tenant instead of the default
+    pub tenant: Option<String>,
+    /// Override for one role, e.g. `AssetManager`, instead of the default
+    pub role: Option<String>,
+}
+
+fn feature_flag_error(e: FlagError) -> (StatusCode, Json<SecureApiError>) {
+    match e {
+        FlagError::UnknownFlag(_) => (StatusCode::NOT_FOUND, Json(SecureApiError::new("FLAG_NOT_FOUND", &e.to_string(), 404))),
+        FlagError::Database(_) => {
+            error!("{}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(SecureApiError::new("FLAG_UPDATE_FAILED", "Failed to persist feature flag", 500)))
+        }
+    }
+}
+
+async fn list_feature_flags(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+) -> Result<Json<Vec<FlagStatus>>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::SystemAdmin) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+    Ok(Json(state.feature_flags.snapshot()))
+}
+
+/// Toggle a flag at runtime, globally or for one tenant or role
+async fn set_feature_flag(
+    State(state): State<SecureApiState>,
+    claims: axum::Extension<JwtClaims>,
+    Path(flag): Path<String>,
+    Json(request): Json<SetFeatureFlagRequest>,
+) -> Result<Json<FlagStatus>, (StatusCode, Json<SecureApiError>)> {
+    if !check_permission(&claims, Permission::SystemAdmin) {
+        return Err((StatusCode::FORBIDDEN, Json(SecureApiError::forbidden())));
+    }
+
+    let scope = match (request.tenant, request.role) {
+        (None, None) => FlagScope::Global,
+        (Some(tenant), None) => FlagScope::Tenant(tenant),
+        (None, Some(role)) => FlagScope::Role(role),
+        (Some(_), Some(_)) => {
+            return Err((StatusCode::BAD_REQUEST, Json(SecureApiError::validation_error("Override either a tenant or a role, not both"))));
+        }
+    };
+
+    let status = state.feature_flags
+        .set(&flag, scope.clone(), request.enabled, &claims.sub)
+        .await
+        .map_err(feature_flag_error)?;
+
+    state.audit_logger.write().await.log(AuditLogEntry {
+        id: Uuid::new_v4(),
+        timestamp: Utc::now(),
+        tenant_id: TenantId::default(),
+        user_id: claims.sub.clone(),
+        action: "SET_FEATURE_FLAG".to_string(),
+        resource: flag,
+        ip_address: None,
+        user_agent: None,
+        success: true,
+        details: serde_json::json!({
+            "enabled": request.enabled,
+            "scope": format!("{:?}", scope),
+        }),
+    });
+
+    Ok(Json(status))
+}
+
+async fn health_check() -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "status": "healthy",
+        "timestamp": Utc::now().to_rfc3339(),
         "version": "1.3.0-secure"
     }))
 }
@@ -1298,4 +3000,478 @@ fn parse_compliance_standard(s: &str) -> Result<ComplianceStandard, String> {
         "ERC1404" => Ok(ComplianceStandard::ERC1404),
         _ => Ok(ComplianceStandard::Custom(s.to_string())),
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compliance::enhanced_compliance_engine::{ComplianceRequirement, RegulatoryFramework, VerificationMethod};
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    const TEST_SECRET: &str = "tenant-isolation-test-secret";
+
+    fn token(role: UserRole, tenant_id: Option<&str>) -> String {
+        let claims = JwtClaims {
+            sub: "0xtest".to_string(),
+            role,
+            access_level: AccessLevel::Standard,
+            exp: (Utc::now() + Duration::hours(1)).timestamp() as usize,
+            iat: Utc::now().timestamp() as usize,
+            permissions: vec![Permission::ViewAsset],
+            tenant_id: tenant_id.map(str::to_string),
+        };
+        encode(&Header::default(), &claims, &EncodingKey::from_secret(TEST_SECRET.as_ref())).unwrap()
+    }
+
+    async fn test_state() -> (SecureApiState, String, String) {
+        let mut service = MultiChainAssetService::new();
+        let mut asset_ids = Vec::new();
+        for tenant in ["tenant-a", "tenant-b"] {
+            let asset_id = service.create_asset(
+                TenantId::new(tenant),
+                format!("{} Fund", tenant),
+                "FUND".to_string(),
+                AssetType::Securities,
+                ComplianceStandard::ERC3643,
+                "Reg D".to_string(),
+                "US".to_string(),
+                1_000_000,
+                Currency::USD,
+                "issuer",
+            ).await.unwrap();
+            asset_ids.push(asset_id);
+        }
+        let asset_b = asset_ids.pop().unwrap();
+        let asset_a = asset_ids.pop().unwrap();
+
+        let db = Arc::new(PgPool::connect_lazy("postgres://localhost/unused").unwrap());
+        let state = SecureApiState {
+            asset_service: Arc::new(RwLock::new(service)),
+            compliance_engine: Arc::new(RwLock::new(EnhancedComplianceEngine::new())),
+            jwt_secret: RotatingSecret::fixed(quantera_secrets::SecretValue::new(TEST_SECRET)),
+            rate_limiter: Arc::new(RateLimitBackend::Local(AtomicRateLimiter::new())),
+            audit_logger: Arc::new(RwLock::new(AuditLogger::new())),
+            db_router: Arc::new(DbRouter::new(db.clone())),
+            consistency: Arc::new(ConsistencyChecker::new(db.clone())),
+            db,
+            challenge_limits: ChallengeLimits::default(),
+            session_limits: SessionLimits::default(),
+            prime_brokerage: Arc::new(RwLock::new(PrimeBrokerageService::new())),
+            task_health: Arc::new(TaskHealth::new()),
+            summary_cache: Arc::new(AdminSummaryCache::default()),
+            feature_flags: Arc::new(FeatureFlags::new(std::collections::HashMap::new())),
+            service_keys: Arc::new(ServiceKeys::new(Arc::new(quantera_service_auth::InMemoryServiceKeyStore::default()))),
+            fx_rates: Arc::new(FxRateService::new(
+                Arc::new(price_oracle::OracleAggregator::new(Vec::new(), Default::default())),
+                chrono::Duration::seconds(60),
+                chrono::Duration::hours(1),
+            )),
+        };
+
+        (state, asset_a, asset_b)
+    }
+
+    async fn get(state: &SecureApiState, uri: &str, token: &str) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder()
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+
+        let response = create_secure_router(state.clone()).oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null))
+    }
+
+    #[tokio::test]
+    async fn test_tenant_isolation_on_list_and_get() {
+        let (state, asset_a, asset_b) = test_state().await;
+        let tenant_a = token(UserRole::Investor, Some("tenant-a"));
+
+        let (status, body) = get(&state, "/api/v1/assets", &tenant_a).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["total_count"], 1);
+        assert_eq!(body["assets"][0]["asset_id"], asset_a.as_str());
+
+        let (status, _) = get(&state, &format!("/api/v1/assets/{}", asset_a), &tenant_a).await;
+        assert_eq!(status, StatusCode::OK);
+
+        // Another tenant's asset looks exactly like a missing one
+        let (status, body) = get(&state, &format!("/api/v1/assets/{}", asset_b), &tenant_a).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"], "ASSET_NOT_FOUND");
+    }
+
+    async fn put_json(state: &SecureApiState, uri: &str, token: &str, body: serde_json::Value) -> StatusCode {
+        let request = Request::builder()
+            .method("PUT")
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+
+        create_secure_router(state.clone()).oneshot(request).await.unwrap().status()
+    }
+
+    fn user_token(sub: &str, role: UserRole, permissions: Vec<Permission>) -> String {
+        let claims = JwtClaims {
+            sub: sub.to_string(),
+            role,
+            access_level: AccessLevel::Standard,
+            exp: (Utc::now() + Duration::hours(1)).timestamp() as usize,
+            iat: Utc::now().timestamp() as usize,
+            permissions,
+            tenant_id: Some("tenant-a".to_string()),
+        };
+        encode(&Header::default(), &claims, &EncodingKey::from_secret(TEST_SECRET.as_ref())).unwrap()
+    }
+
+    async fn post_json(state: &SecureApiState, uri: &str, token: &str, body: serde_json::Value) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+
+        let response = create_secure_router(state.clone()).oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null))
+    }
+
+    #[tokio::test]
+    async fn test_asset_needs_second_user_approval_and_resubmits_after_rejection() {
+        let (state, _, _) = test_state().await;
+        let admin = user_token("0xadmin-maker", UserRole::Admin, vec![
+            Permission::CreateAsset, Permission::DeployAsset, Permission::ViewAsset, Permission::ApproveAsset,
+        ]);
+        let officer = user_token("0xcomp-checker", UserRole::ComplianceOfficer, vec![
+            Permission::ViewAsset, Permission::ApproveAsset,
+        ]);
+        let asset_id = state.asset_service.write().await.create_asset(
+            TenantId::new("tenant-a"),
+            "Harbour Notes".to_string(),
+            "HBN".to_string(),
+            AssetType::CorporateBonds,
+            ComplianceStandard::ERC3643,
+            "Reg D".to_string(),
+            "US".to_string(),
+            1_000_000,
+            Currency::USD,
+            "0xadmin-maker",
+        ).await.unwrap();
+        let review = |action: &str| format!("/api/v1/admin/asset-reviews/{}/{}", asset_id, action);
+
+        let (status, body) = post_json(&state, &review("approve"), &admin, serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["error"], "SELF_APPROVAL");
+
+        let (status, _) = post_json(&state, &format!("/api/v1/assets/{}/deploy", asset_id), &admin, serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let (status, _) = post_json(&state, &review("reject"), &officer, serde_json::json!({ "comments": "Supply should be 500k" })).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let edit = put_json(&state, &format!("/api/v1/assets/{}", asset_id), &admin, serde_json::json!({ "total_supply": 500_000 })).await;
+        assert_eq!(edit, StatusCode::OK);
+        let (status, body) = post_json(&state, &format!("/api/v1/assets/{}/resubmit", asset_id), &admin, serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["revision"], 2);
+
+        let (_, queue) = get(&state, "/api/v1/admin/asset-reviews", &officer).await;
+        assert_eq!(queue[0]["asset"]["total_supply"], 500_000);
+
+        let (status, body) = post_json(&state, &review("approve"), &officer, serde_json::json!({ "comments": "OK" })).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "approved");
+        let (status, _) = post_json(&state, &format!("/api/v1/assets/{}/deploy", asset_id), &admin, serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::OK);
+        // Create happened outside the API; the refused self-approval is audited as a failure
+        let audit = state.audit_logger.read().await;
+        let actions: Vec<(&str, bool)> = audit.entries.iter().map(|e| (e.action.as_str(), e.success)).collect();
+        assert_eq!(actions, vec![
+            ("APPROVE_ASSET", false),
+            ("REJECT_ASSET", true),
+            ("UPDATE_ASSET", true),
+            ("RESUBMIT_ASSET", true),
+            ("APPROVE_ASSET", true),
+        ]);
+    }
+
+    fn investor_profile(jurisdiction: &str, investor_type: InvestorType) -> InvestorProfile {
+        InvestorProfile {
+            investor_id: "0xtest".to_string(),
+            tenant_id: TenantId::new("tenant-a"),
+            jurisdiction: jurisdiction.to_string(),
+            tax_residency: vec![jurisdiction.to_string()],
+            investor_type,
+            kyc_status: KYCStatus::Completed,
+            aml_status: AMLStatus::Clear,
+            accreditation_status: AccreditationStatus::NotApplicable,
+            investment_limits: std::collections::HashMap::new(),
+            last_updated: Utc::now(),
+            compliance_score: 90,
+            risk_rating: RiskRating::Low,
+            sanctions_status: SanctionsStatus::Clear,
+            cooling_periods: std::collections::HashMap::new(),
+            appropriateness: None,
+            data_hash: String::new(),
+            access_level: AccessLevel::Standard,
+            created_by: "officer".to_string(),
+            last_accessed: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_private_placement_hidden_from_ineligible_investor() {
+        let (state, asset_a, _) = test_state().await;
+        let tenant = TenantScope::Tenant(TenantId::new("tenant-a"));
+
+        let placement = state.asset_service.write().await.create_asset(
+            TenantId::new("tenant-a"),
+            "Reg D Placement".to_string(),
+            "REGD".to_string(),
+            AssetType::PrivateEquity,
+            ComplianceStandard::ERC3643,
+            "Reg D".to_string(),
+            "US".to_string(),
+            1_000_000,
+            Currency::USD,
+            "issuer",
+        ).await.unwrap();
+
+        {
+            let mut engine = state.compliance_engine.write().await;
+            engine.grant_access("officer".to_string(), AccessLevel::Standard);
+            engine.update_investor_profile(&tenant, "0xtest".to_string(), investor_profile("EU", InvestorType::Retail), "officer")
+                .await.unwrap();
+        }
+
+        let admin = token(UserRole::Admin, Some("tenant-a"));
+        let status = put_json(&state, &format!("/api/v1/admin/assets/{}/distribution", placement), &admin, serde_json::json!({
+            "allowed_jurisdictions": ["US"],
+            "allowed_investor_types": ["AccreditedInvestor"],
+            "private_placement": true
+        })).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let investor = token(UserRole::Investor, Some("tenant-a"));
+        let (status, body) = get(&state, "/api/v1/assets", &investor).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["total_count"], 1);
+        assert_eq!(body["assets"][0]["asset_id"], asset_a.as_str());
+
+        let (status, body) = get(&state, "/api/v1/assets?q=reg", &investor).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["total_count"], 0);
+
+        let (status, body) = get(&state, &format!("/api/v1/assets/{}", placement), &investor).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"], "ASSET_NOT_FOUND");
+
+        // Investors cannot loosen the rules themselves
+        let status = put_json(&state, &format!("/api/v1/admin/assets/{}/distribution", placement), &investor, serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, body) = get(&state, "/api/v1/assets?q=reg", &admin).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["total_count"], 1);
+
+        let (status, body) = get(&state, &format!("/api/v1/assets/{}", placement), &admin).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["distribution"]["private_placement"], true);
+    }
+
+    /// A USD treasury in tenant-a offered to US and UK investors, with the investor's profile
+    async fn treasury_with_investor(state: &SecureApiState, profile: InvestorProfile) -> String {
+        let tenant = TenantScope::Tenant(TenantId::new("tenant-a"));
+        let mut service = state.asset_service.write().await;
+        let treasury = service.create_asset(
+            TenantId::new("tenant-a"),
+            "Six Month Bills".to_string(),
+            "TB6M".to_string(),
+            AssetType::TreasuryNotes,
+            ComplianceStandard::ERC3643,
+            "Reg S".to_string(),
+            "US".to_string(),
+            1_000_000,
+            Currency::USD,
+            "issuer",
+        ).await.unwrap();
+        service.set_distribution_rules(&tenant, &treasury, DistributionRules {
+            allowed_jurisdictions: vec!["US".to_string(), "UK".to_string()],
+            minimum_investment: Some(rust_decimal::Decimal::from(10_000)),
+            ..DistributionRules::default()
+        }).unwrap();
+
+        let mut engine = state.compliance_engine.write().await;
+        engine.grant_access("officer".to_string(), AccessLevel::Elevated);
+        engine.update_investor_profile(&tenant, "0xtest".to_string(), profile, "officer").await.unwrap();
+        treasury
+    }
+
+    #[tokio::test]
+    async fn test_eligible_investor_passes_every_treasury_criterion() {
+        let (state, asset_a, _) = test_state().await;
+        let treasury = treasury_with_investor(&state, investor_profile("US", InvestorType::Retail)).await;
+        let investor = token(UserRole::Investor, Some("tenant-a"));
+
+        let (status, body) = get(&state, &format!("/api/v1/treasuries/{}/eligibility", treasury), &investor).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["eligible"], true);
+        assert_eq!(body["kyc_tier"], "basic");
+        assert_eq!(body["criteria"].as_array().unwrap().len(), 5);
+        assert_eq!(body["onboarding"], serde_json::json!([]));
+        assert_eq!(body["criteria"][2]["message"], "KYC tier Basic permits investments up to 50000; the minimum investment is 10000");
+
+        // Only treasuries have an eligibility matrix
+        let (status, body) = get(&state, &format!("/api/v1/treasuries/{}/eligibility", asset_a), &investor).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"], "TREASURY_NOT_FOUND");
+    }
+
+    #[tokio::test]
+    async fn test_recent_investment_blocks_treasury_during_cooling_period() {
+        let (state, _, _) = test_state().await;
+        let mut profile = investor_profile("US", InvestorType::Retail);
+        profile.cooling_periods.insert("treasuries".to_string(), Utc::now() - Duration::days(2));
+        let treasury = treasury_with_investor(&state, profile).await;
+        state.compliance_engine.write().await.set_framework_requirements("US", vec![ComplianceRequirement {
+            requirement_id: "SEC_COOL_002".to_string(),
+            framework: RegulatoryFramework::SECRegulation,
+            description: "Cooling period between treasury subscriptions".to_string(),
+            is_mandatory: true,
+            verification_method: VerificationMethod::CoolingPeriodCheck,
+            applicable_asset_types: vec!["treasuries".to_string()],
+            minimum_investment_threshold: None,
+            maximum_investment_threshold: None,
+            cooling_period_days: Some(7),
+        }], "officer").unwrap();
+
+        let investor = token(UserRole::Investor, Some("tenant-a"));
+        let (status, body) = get(&state, &format!("/api/v1/treasuries/{}/eligibility?locale=en", treasury), &investor).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["eligible"], false);
+        let failed: Vec<&serde_json::Value> = body["criteria"].as_array().unwrap().iter().filter(|c| c["passed"] == false).collect();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0]["criterion"], "cooling_period");
+        assert_eq!(failed[0]["remediation"], "Wait 5 more days before next investment");
+    }
+
+    #[tokio::test]
+    async fn test_investor_outside_offered_jurisdictions_is_told_where_treasury_is_offered() {
+        let (state, _, _) = test_state().await;
+        let treasury = treasury_with_investor(&state, investor_profile("SG", InvestorType::Professional)).await;
+        let investor = token(UserRole::Investor, Some("tenant-a"));
+
+        let (status, body) = get(&state, &format!("/api/v1/treasuries/{}/eligibility", treasury), &investor).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["eligible"], false);
+        assert_eq!(body["kyc_tier"], "enhanced");
+        assert_eq!(body["criteria"][0]["criterion"], "jurisdiction");
+        assert_eq!(body["criteria"][0]["passed"], false);
+        assert_eq!(body["criteria"][0]["message"], "Not offered to investors in SG");
+        assert_eq!(body["criteria"][0]["remediation"], "Offered only to investors in: US, UK");
+        assert!(body["criteria"].as_array().unwrap()[1..].iter().all(|c| c["passed"] == true));
+    }
+
+    #[tokio::test]
+    async fn test_platform_admin_sees_all_tenants() {
+        let (state, _, asset_b) = test_state().await;
+        let admin = token(UserRole::PlatformAdmin, None);
+
+        let (status, body) = get(&state, "/api/v1/assets", &admin).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["total_count"], 2);
+
+        let (status, _) = get(&state, &format!("/api/v1/assets/{}", asset_b), &admin).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_asset_list_pages_and_sort_are_validated() {
+        let (state, asset_a, _) = test_state().await;
+        let admin = token(UserRole::PlatformAdmin, None);
+
+        let (status, body) = get(&state, "/api/v1/assets?per_page=1&sort=name", &admin).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["assets"][0]["asset_id"], asset_a.as_str());
+        assert_eq!(body["total_pages"], 2);
+        let cursor = body["next_cursor"].as_str().unwrap().to_string();
+        let (_, body) = get(&state, &format!("/api/v1/assets?per_page=1&sort=name&cursor={}", cursor), &admin).await;
+        assert_eq!((body["page"].clone(), body["next_cursor"].clone()), (serde_json::json!(2), serde_json::Value::Null));
+
+        // A page past the end is empty rather than an error
+        let (status, body) = get(&state, "/api/v1/assets?page=7", &admin).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["assets"], serde_json::json!([]));
+        assert_eq!(body["total_count"], 2);
+
+        let (status, body) = get(&state, "/api/v1/assets?sort=total_supply", &admin).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "INVALID_SORT");
+        let (status, body) = get(&state, "/api/v1/assets?page=0", &admin).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "INVALID_PAGINATION");
+    }
+    #[tokio::test]
+    async fn test_search_finds_asset_by_former_symbol() {
+        let (state, _, _) = test_state().await;
+        let tenant = TenantScope::Tenant(TenantId::new("tenant-a"));
+        {
+            let mut service = state.asset_service.write().await;
+            let asset_id = service.create_asset(
+                TenantId::new("tenant-a"),
+                "Harbour Notes".to_string(),
+                "HBR".to_string(),
+                AssetType::Securities,
+                ComplianceStandard::ERC3643,
+                "Reg D".to_string(),
+                "US".to_string(),
+                1_000_000,
+                Currency::USD,
+                "issuer",
+            ).await.unwrap();
+            let rename = service.request_symbol_rename(&tenant, &asset_id, "HBN", "issuer").unwrap();
+            service.approve_symbol_rename(rename.request_id, "admin").unwrap();
+        }
+
+        let investor = token(UserRole::Investor, Some("tenant-a"));
+        let (status, body) = get(&state, "/api/v1/assets?q=hbr", &investor).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["total_count"], 1);
+        assert_eq!(body["assets"][0]["symbol"], "HBN");
+    }
+
+    #[tokio::test]
+    async fn test_feature_flag_toggle_exposes_guarded_route_without_restart() {
+        let (state, _, _) = test_state().await;
+        let admin = user_token("0xops", UserRole::Admin, vec![Permission::SystemAdmin]);
+        let investor = token(UserRole::Investor, Some("tenant-a"));
+        // A stale timestamp is rejected by the login handler itself, so a 400 means the route is reachable
+        let login = serde_json::json!({ "wallet_address": "0xabc", "signature": "0x", "message": "login", "timestamp": 0 });
+
+        let (status, _) = post_json(&state, "/api/v1/auth/login", &investor, login.clone()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let toggle = put_json(&state, "/api/v1/admin/feature-flags/legacy_login", &investor, serde_json::json!({ "enabled": true })).await;
+        assert_eq!(toggle, StatusCode::FORBIDDEN);
+        let toggle = put_json(&state, "/api/v1/admin/feature-flags/legacy_login", &admin, serde_json::json!({ "enabled": true, "tenant": "tenant-a" })).await;
+        assert_eq!(toggle, StatusCode::OK);
+
+        let (status, _) = post_json(&state, "/api/v1/auth/login", &investor, login.clone()).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let other_tenant = token(UserRole::Investor, Some("tenant-b"));
+        let (status, _) = post_json(&state, "/api/v1/auth/login", &other_tenant, login).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (_, summary) = get(&state, "/api/v1/admin/summary", &admin).await;
+        assert_eq!(summary["feature_flags"][0]["tenants"]["tenant-a"], true);
+    }
+}
@@ -11,6 +11,8 @@ use serde::{Deserialize, Serialize};
 use rust_decimal::Decimal;
 use tracing::{info, warn, error};
 use jsonwebtoken::{decode, DecodingKey, Validation, Algorithm};
+use quantera_types::{Pii, WalletAddress};
+use quantera_secrets::RotatingSecret;
 
 use crate::services::tradefinance_service::{
     TradeFinanceService, TradeFinanceAsset, TradeFinancePosition,
@@ -36,7 +38,7 @@ pub struct TradeFinanceJwtClaims {
 #[derive(Clone)]
 pub struct TradeFinanceApiState {
     pub db: Arc<PgPool>,
-    pub jwt_secret: String,
+    pub jwt_secret: RotatingSecret,
 }
 
 // ============================================================================
@@ -116,8 +118,9 @@ fn validate_position_access(
 
     if token_wallet != requested_wallet_lower {
         warn!(
-            "Position access denied: token wallet {} does not match requested wallet {}",
-            token_wallet, requested_wallet_lower
+            token_wallet = %Pii::new(&token_wallet),
+            requested_wallet = %Pii::new(&requested_wallet_lower),
+            "Position access denied: token wallet does not match requested wallet"
         );
         return Err((
             StatusCode::FORBIDDEN,
@@ -130,16 +133,9 @@ fn validate_position_access(
 
 /// Validate wallet address format
 fn validate_wallet_address(wallet: &str) -> Result<(), (StatusCode, String)> {
-    if !wallet.starts_with("0x") {
-        return Err((StatusCode::BAD_REQUEST, "Wallet address must start with 0x".to_string()));
-    }
-    if wallet.len() != 42 {
-        return Err((StatusCode::BAD_REQUEST, "Wallet address must be 42 characters".to_string()));
-    }
-    if !wallet[2..].chars().all(|c| c.is_ascii_hexdigit()) {
-        return Err((StatusCode::BAD_REQUEST, "Wallet address contains invalid characters".to_string()));
-    }
-    Ok(())
+    wallet.parse::<WalletAddress>()
+        .map(|_| ())
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))
 }
 
 // ============================================================================
@@ -240,14 +236,14 @@ async fn get_positions_handler(
     validate_wallet_address(&wallet_address)?;
 
     // Authenticate and verify wallet ownership
-    let claims = validate_position_access(&headers, &wallet_address, &state.jwt_secret)?;
-    info!("Authenticated position access for wallet: {}", claims.sub);
+    let claims = validate_position_access(&headers, &wallet_address, state.jwt_secret.current().expose())?;
+    info!(wallet = %Pii::new(&claims.sub), "Authenticated position access");
 
     let service = TradeFinanceService::new(state.db);
     let positions = service.get_positions(&wallet_address)
         .await
         .map_err(|e| {
-            error!("Failed to fetch positions for {}: {}", wallet_address, e);
+            error!(wallet = %Pii::new(&wallet_address), error = %e, "Failed to fetch positions");
             (StatusCode::INTERNAL_SERVER_ERROR, "Failed to fetch positions".to_string())
         })?;
 
@@ -279,11 +275,10 @@ async fn purchase_asset_handler(
     Json(req): Json<PurchaseRequest>,
 ) -> Result<Json<PurchaseResult>, (StatusCode, String)> {
     // Authenticate user
-    let claims = validate_jwt_token(&headers, &state.jwt_secret)?;
+    let claims = validate_jwt_token(&headers, state.jwt_secret.current().expose())?;
     let wallet_address = claims.sub.clone();
 
-    info!("Processing purchase: asset={}, wallet={}, units={}",
-          req.asset_id, wallet_address, req.units);
+    info!(asset_id = %req.asset_id, wallet = %Pii::new(&wallet_address), units = %req.units, "Processing purchase");
 
     // Validate wallet address from token
     validate_wallet_address(&wallet_address)?;
@@ -338,7 +333,7 @@ async fn purchase_asset_handler(
         } else if error_msg.contains("KYC") || error_msg.contains("compliance") {
             (StatusCode::FORBIDDEN, "KYC verification required for this purchase".to_string())
         } else {
-            error!("Purchase failed for {}: {}", wallet_address, error_msg);
+            error!(wallet = %Pii::new(&wallet_address), error = %error_msg, "Purchase failed");
             (StatusCode::INTERNAL_SERVER_ERROR, "Purchase failed. Please try again.".to_string())
         }
     })?;
@@ -374,11 +369,7 @@ async fn get_analytics_handler(
 /// Create trade finance router
 /// - Public endpoints: asset listing, asset details, analytics
 /// - Authenticated endpoints: positions (wallet ownership), purchase
-pub fn create_tradefinance_router(db: Arc<PgPool>) -> Router {
-    // Load JWT secret from environment
-    let jwt_secret = std::env::var("JWT_SECRET")
-        .expect("JWT_SECRET must be set for trade finance API authentication");
-
+pub fn create_tradefinance_router(db: Arc<PgPool>, jwt_secret: RotatingSecret) -> Router {
     let state = TradeFinanceApiState {
         db,
         jwt_secret,
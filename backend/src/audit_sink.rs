@@ -0,0 +1,749 @@
+// Streaming of audit events to external SIEM sinks. Local persistence stays the source of
+// truth; sinks receive a best-effort, ordered copy.
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::api::secure_api::AuditLogEntry as ApiAuditEntry;
+use crate::compliance::enhanced_compliance_engine::AuditLogEntry as ComplianceAuditEntry;
+use crate::task_health::TaskHealth;
+use crate::tenant::TenantId;
+
+/// Source of AuditLogger entries
+pub const SECURE_API_SOURCE: &str = "secure_api";
+
+/// Source of the compliance engine's audit trail
+pub const COMPLIANCE_ENGINE_SOURCE: &str = "compliance_engine";
+
+/// An audit entry as shipped to a sink
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEvent {
+    pub source: String,
+    /// Per-source publish order since the process started; gaps mean dropped events
+    pub sequence: u64,
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    pub tenant_id: TenantId,
+    pub actor: String,
+    pub action: String,
+    pub resource: String,
+    pub success: Option<bool>,
+    pub details: serde_json::Value,
+}
+
+impl From<&ApiAuditEntry> for AuditEvent {
+    fn from(entry: &ApiAuditEntry) -> Self {
+        Self {
+            source: SECURE_API_SOURCE.to_string(),
+            sequence: 0,
+            id: entry.id.to_string(),
+            timestamp: entry.timestamp,
+            tenant_id: entry.tenant_id.clone(),
+            actor: entry.user_id.clone(),
+            action: entry.action.clone(),
+            resource: entry.resource.clone(),
+            success: Some(entry.success),
+            details: serde_json::json!({
+                "ip_address": entry.ip_address,
+                "user_agent": entry.user_agent,
+                "details": entry.details,
+            }),
+        }
+    }
+}
+
+impl From<&ComplianceAuditEntry> for AuditEvent {
+    fn from(entry: &ComplianceAuditEntry) -> Self {
+        Self {
+            source: COMPLIANCE_ENGINE_SOURCE.to_string(),
+            sequence: 0,
+            id: entry.entry_id.clone(),
+            timestamp: entry.timestamp,
+            tenant_id: entry.tenant_id.clone(),
+            actor: entry.performed_by.clone(),
+            action: entry.action.clone(),
+            resource: format!("investor/{}", entry.investor_id),
+            success: entry.compliance_result,
+            details: serde_json::json!({
+                "risk_level": entry.risk_level,
+                "details": entry.details,
+            }),
+        }
+    }
+}
+
+// ============ Sinks ============
+
+/// Destination for batches of audit events, delivered in order
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    /// Stable name used for the spill file, metrics and task health
+    fn name(&self) -> &str;
+
+    /// Deliver a whole batch or fail; a failed batch is retried as-is
+    async fn deliver(&self, batch: &[AuditEvent]) -> Result<(), String>;
+}
+
+/// POSTs batches as JSON lines to an HTTPS endpoint
+pub struct HttpSink {
+    name: String,
+    url: String,
+    token: Option<String>,
+    client: reqwest::Client,
+}
+
+impl HttpSink {
+    pub fn new(url: &str, token: Option<String>) -> Result<Self, String> {
+        if !url.starts_with("https://") {
+            return Err(format!("Audit sink {} must use https", url));
+        }
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Self { name: sink_name(url), url: url.to_string(), token, client })
+    }
+}
+
+#[async_trait]
+impl AuditSink for HttpSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn deliver(&self, batch: &[AuditEvent]) -> Result<(), String> {
+        let mut body = String::new();
+        for event in batch {
+            body.push_str(&serde_json::to_string(event).map_err(|e| e.to_string())?);
+            body.push('\n');
+        }
+
+        let mut request = self.client.post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/x-ndjson")
+            .body(body);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.send().await.map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("{} responded {}", self.url, response.status()));
+        }
+        Ok(())
+    }
+}
+
+type SyslogStream = Box<dyn AsyncWrite + Send + Unpin>;
+
+/// RFC 5424 messages over TCP, optionally TLS, with octet-counting framing (RFC 6587)
+pub struct SyslogSink {
+    name: String,
+    host: String,
+    port: u16,
+    tls: bool,
+    hostname: String,
+    connection: tokio::sync::Mutex<Option<SyslogStream>>,
+}
+
+impl SyslogSink {
+    pub fn new(host: &str, port: u16, tls: bool) -> Self {
+        let scheme = if tls { "syslog+tls" } else { "syslog" };
+        Self {
+            name: sink_name(&format!("{}://{}:{}", scheme, host, port)),
+            host: host.to_string(),
+            port,
+            tls,
+            hostname: std::env::var("HOSTNAME").unwrap_or_else(|_| "-".to_string()),
+            connection: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    async fn connect(&self) -> Result<SyslogStream, String> {
+        let tcp = tokio::net::TcpStream::connect((self.host.as_str(), self.port)).await
+            .map_err(|e| format!("connect {}:{}: {}", self.host, self.port, e))?;
+        if !self.tls {
+            return Ok(Box::new(tcp));
+        }
+        let connector = native_tls::TlsConnector::new().map_err(|e| e.to_string())?;
+        let stream = tokio_native_tls::TlsConnector::from(connector)
+            .connect(&self.host, tcp).await
+            .map_err(|e| format!("TLS handshake with {}: {}", self.host, e))?;
+        Ok(Box::new(stream))
+    }
+}
+
+#[async_trait]
+impl AuditSink for SyslogSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn deliver(&self, batch: &[AuditEvent]) -> Result<(), String> {
+        let mut frames = Vec::new();
+        for event in batch {
+            let message = format_rfc5424(event, &self.hostname)?;
+            frames.extend_from_slice(format!("{} {}", message.len(), message).as_bytes());
+        }
+
+        let mut connection = self.connection.lock().await;
+        if connection.is_none() {
+            *connection = Some(self.connect().await?);
+        }
+        let stream = connection.as_mut().expect("connection was just established");
+        let written = async {
+            stream.write_all(&frames).await?;
+            stream.flush().await
+        }.await;
+
+        if let Err(e) = written {
+            // Reconnect on the retry; the receiver may see a partial batch twice
+            *connection = None;
+            return Err(format!("write to {}:{}: {}", self.host, self.port, e));
+        }
+        Ok(())
+    }
+}
+
+/// One RFC 5424 message: facility log audit (13), notice for successes and warning for failures
+pub fn format_rfc5424(event: &AuditEvent, hostname: &str) -> Result<String, String> {
+    let severity = if event.success == Some(false) { 4 } else { 5 };
+    let structured = format!(
+        "[quantera@32473 id=\"{}\" seq=\"{}\" tenant=\"{}\" actor=\"{}\" action=\"{}\" resource=\"{}\"]",
+        sd_escape(&event.id),
+        event.sequence,
+        sd_escape(event.tenant_id.as_str()),
+        sd_escape(&event.actor),
+        sd_escape(&event.action),
+        sd_escape(&event.resource),
+    );
+    let message = serde_json::to_string(event).map_err(|e| e.to_string())?;
+
+    Ok(format!(
+        "<{}>1 {} {} quantera - {} {} {}",
+        13 * 8 + severity,
+        event.timestamp.to_rfc3339_opts(SecondsFormat::Micros, true),
+        header_field(hostname, 255),
+        header_field(&event.source, 32),
+        structured,
+        message,
+    ))
+}
+
+/// SD-PARAM values escape `"`, `\` and `]`
+fn sd_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace(']', "\\]")
+}
+
+/// Header fields are printable ASCII without spaces, or `-` when empty
+fn header_field(value: &str, max_len: usize) -> String {
+    let field: String = value.chars().filter(|c| c.is_ascii_graphic()).take(max_len).collect();
+    if field.is_empty() { "-".to_string() } else { field }
+}
+
+fn sink_name(target: &str) -> String {
+    target.chars().map(|c| if c.is_ascii_alphanumeric() || c == '.' { c } else { '_' }).collect()
+}
+
+// ============ Buffering and Delivery ============
+
+#[derive(Debug, Clone)]
+pub struct StreamSettings {
+    pub batch_size: usize,
+    pub flush_interval: Duration,
+    /// Events held in memory per sink before new ones spill to disk
+    pub max_buffered: usize,
+    /// Directory for spill files; without one, events beyond `max_buffered` are dropped
+    pub spill_dir: Option<PathBuf>,
+    pub max_spill_bytes: u64,
+    pub retry_base: Duration,
+    pub retry_max: Duration,
+}
+
+impl Default for StreamSettings {
+    fn default() -> Self {
+        Self {
+            batch_size: 100,
+            flush_interval: Duration::from_secs(1),
+            max_buffered: 10_000,
+            spill_dir: Some(PathBuf::from("./audit-spill")),
+            max_spill_bytes: 512 * 1024 * 1024,
+            retry_base: Duration::from_secs(1),
+            retry_max: Duration::from_secs(300),
+        }
+    }
+}
+
+impl StreamSettings {
+    /// Wait before the next retry after `failures` consecutive failures
+    pub fn retry_delay(&self, failures: u32) -> Duration {
+        let factor = 2u32.saturating_pow(failures.saturating_sub(1).min(16));
+        self.retry_base.saturating_mul(factor).min(self.retry_max)
+    }
+}
+
+/// Delivery state of one sink, for the admin endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct SinkStats {
+    pub name: String,
+    pub buffered: usize,
+    pub spilled: usize,
+    pub delivered: u64,
+    /// Events lost because the spill file was full or unwritable
+    pub dropped: u64,
+    /// Age of the oldest undelivered event
+    pub lag_seconds: i64,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    pub last_delivery: Option<DateTime<Utc>>,
+}
+
+/// Pending events of one sink: a memory queue followed by the spill file, oldest first.
+/// Once anything has spilled, new events also spill until the file drains, keeping order.
+struct SinkState {
+    memory: VecDeque<AuditEvent>,
+    spill_path: Option<PathBuf>,
+    spilled: usize,
+    spill_bytes: u64,
+    oldest_spilled: Option<DateTime<Utc>>,
+    delivered: u64,
+    dropped: u64,
+    consecutive_failures: u32,
+    last_error: Option<String>,
+    last_delivery: Option<DateTime<Utc>>,
+}
+
+impl SinkState {
+    /// Pick up events left in the spill file by a previous run
+    fn open(spill_path: Option<PathBuf>) -> Self {
+        let mut state = Self {
+            memory: VecDeque::new(),
+            spill_path,
+            spilled: 0,
+            spill_bytes: 0,
+            oldest_spilled: None,
+            delivered: 0,
+            dropped: 0,
+            consecutive_failures: 0,
+            last_error: None,
+            last_delivery: None,
+        };
+        if let Some(contents) = state.spill_path.as_ref().and_then(|path| std::fs::read_to_string(path).ok()) {
+            let lines: Vec<&str> = contents.lines().filter(|line| !line.is_empty()).collect();
+            state.spilled = lines.len();
+            state.spill_bytes = contents.len() as u64;
+            state.oldest_spilled = lines.first()
+                .and_then(|line| serde_json::from_str::<AuditEvent>(line).ok())
+                .map(|event| event.timestamp);
+        }
+        state
+    }
+
+    fn push(&mut self, event: AuditEvent, settings: &StreamSettings) {
+        if self.spilled == 0 && self.memory.len() < settings.max_buffered {
+            self.memory.push_back(event);
+            return;
+        }
+        if let Err(e) = self.spill(&event, settings.max_spill_bytes) {
+            self.dropped += 1;
+            warn!("Dropped audit event {} from {}: {}", event.id, event.source, e);
+        }
+    }
+
+    fn spill(&mut self, event: &AuditEvent, max_bytes: u64) -> Result<(), String> {
+        let path = self.spill_path.as_ref().ok_or("no spill directory configured")?;
+        let mut line = serde_json::to_string(event).map_err(|e| e.to_string())?;
+        line.push('\n');
+        if self.spill_bytes + line.len() as u64 > max_bytes {
+            return Err("spill file is full".to_string());
+        }
+
+        std::fs::OpenOptions::new().create(true).append(true).open(path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|e| e.to_string())?;
+        self.spilled += 1;
+        self.spill_bytes += line.len() as u64;
+        self.oldest_spilled.get_or_insert(event.timestamp);
+        Ok(())
+    }
+
+    /// Move the oldest spilled events back into memory once it has drained
+    fn refill(&mut self, max_buffered: usize) {
+        if !self.memory.is_empty() || self.spilled == 0 {
+            return;
+        }
+        let Some(path) = self.spill_path.clone() else { return };
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                error!("Cannot read audit spill file {}: {}", path.display(), e);
+                return;
+            }
+        };
+
+        let mut lines = contents.lines().filter(|line| !line.is_empty());
+        for line in lines.by_ref().take(max_buffered) {
+            match serde_json::from_str::<AuditEvent>(line) {
+                Ok(event) => self.memory.push_back(event),
+                Err(_) => self.dropped += 1,
+            }
+        }
+        let rest: Vec<&str> = lines.collect();
+
+        let rewritten = if rest.is_empty() {
+            std::fs::remove_file(&path)
+        } else {
+            std::fs::write(&path, rest.iter().map(|line| format!("{}\n", line)).collect::<String>())
+        };
+        if let Err(e) = rewritten {
+            error!("Cannot rewrite audit spill file {}: {}", path.display(), e);
+        }
+
+        self.spilled = rest.len();
+        self.spill_bytes = rest.iter().map(|line| line.len() as u64 + 1).sum();
+        self.oldest_spilled = rest.first()
+            .and_then(|line| serde_json::from_str::<AuditEvent>(line).ok())
+            .map(|event| event.timestamp);
+    }
+
+    fn stats(&self, name: &str, now: DateTime<Utc>) -> SinkStats {
+        let oldest = self.memory.front().map(|event| event.timestamp).or(self.oldest_spilled);
+        SinkStats {
+            name: name.to_string(),
+            buffered: self.memory.len(),
+            spilled: self.spilled,
+            delivered: self.delivered,
+            dropped: self.dropped,
+            lag_seconds: oldest.map_or(0, |oldest| (now - oldest).num_seconds().max(0)),
+            consecutive_failures: self.consecutive_failures,
+            last_error: self.last_error.clone(),
+            last_delivery: self.last_delivery,
+        }
+    }
+}
+
+/// Queue and delivery loop of one sink
+pub struct SinkWorker {
+    sink: Arc<dyn AuditSink>,
+    settings: StreamSettings,
+    state: Mutex<SinkState>,
+}
+
+impl SinkWorker {
+    pub fn new(sink: Arc<dyn AuditSink>, settings: StreamSettings) -> Self {
+        let spill_path = settings.spill_dir.as_ref().map(|dir| dir.join(format!("{}.jsonl", sink.name())));
+        Self { state: Mutex::new(SinkState::open(spill_path)), sink, settings }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, SinkState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn push(&self, event: AuditEvent) {
+        self.lock().push(event, &self.settings);
+    }
+
+    /// Try to deliver the oldest pending batch; `Ok(false)` when nothing is pending.
+    /// A failed batch stays at the head of the queue, so delivery stays in order.
+    pub async fn deliver_next(&self) -> Result<bool, String> {
+        let batch: Vec<AuditEvent> = {
+            let mut state = self.lock();
+            state.refill(self.settings.max_buffered);
+            state.memory.iter().take(self.settings.batch_size).cloned().collect()
+        };
+        if batch.is_empty() {
+            return Ok(false);
+        }
+
+        let result = self.sink.deliver(&batch).await;
+        let mut state = self.lock();
+        match result {
+            Ok(()) => {
+                state.memory.drain(..batch.len());
+                state.delivered += batch.len() as u64;
+                state.consecutive_failures = 0;
+                state.last_error = None;
+                state.last_delivery = Some(Utc::now());
+                Ok(true)
+            }
+            Err(e) => {
+                state.consecutive_failures += 1;
+                state.last_error = Some(e.clone());
+                Err(e)
+            }
+        }
+    }
+
+    pub fn stats(&self) -> SinkStats {
+        self.lock().stats(self.sink.name(), Utc::now())
+    }
+
+    /// Deliver on every flush interval, backing off while the sink is failing
+    async fn run(self: Arc<Self>, health: Arc<TaskHealth>) {
+        let task = format!("audit_sink:{}", self.sink.name());
+        let mut ticker = tokio::time::interval(self.settings.flush_interval);
+        loop {
+            ticker.tick().await;
+            loop {
+                match self.deliver_next().await {
+                    Ok(true) => continue,
+                    Ok(false) => {
+                        health.record_success(&task);
+                        break;
+                    }
+                    Err(e) => {
+                        let failures = self.lock().consecutive_failures;
+                        warn!("Audit sink {} failed ({} in a row): {}", self.sink.name(), failures, e);
+                        health.record_failure(&task, e);
+                        tokio::time::sleep(self.settings.retry_delay(failures)).await;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Fans audit events out to every configured sink
+pub struct AuditStream {
+    workers: Vec<Arc<SinkWorker>>,
+    sequences: Mutex<HashMap<String, u64>>,
+}
+
+impl AuditStream {
+    pub fn new(sinks: Vec<Arc<dyn AuditSink>>, settings: StreamSettings) -> Self {
+        Self {
+            workers: sinks.into_iter().map(|sink| Arc::new(SinkWorker::new(sink, settings.clone()))).collect(),
+            sequences: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sinks from `AUDIT_SINKS`, a comma-separated list of `https://...`,
+    /// `syslog://host:port` and `syslog+tls://host:port` targets; empty means none
+    pub fn from_env() -> Result<Self, String> {
+        let env_parse = |name: &str, default: u64| -> Result<u64, String> {
+            match std::env::var(name) {
+                Ok(value) => value.parse().map_err(|_| format!("Invalid {}", name)),
+                Err(_) => Ok(default),
+            }
+        };
+        let defaults = StreamSettings::default();
+        let settings = StreamSettings {
+            batch_size: env_parse("AUDIT_SINK_BATCH_SIZE", defaults.batch_size as u64)?.max(1) as usize,
+            flush_interval: Duration::from_millis(env_parse("AUDIT_SINK_FLUSH_INTERVAL_MS", 1000)?.max(1)),
+            max_buffered: env_parse("AUDIT_SINK_BUFFER_EVENTS", defaults.max_buffered as u64)?.max(1) as usize,
+            spill_dir: match std::env::var("AUDIT_SINK_SPILL_DIR") {
+                Ok(dir) if dir.is_empty() => None,
+                Ok(dir) => Some(PathBuf::from(dir)),
+                Err(_) => defaults.spill_dir,
+            },
+            max_spill_bytes: env_parse("AUDIT_SINK_MAX_SPILL_MB", 512)? * 1024 * 1024,
+            ..defaults
+        };
+
+        let token = std::env::var("AUDIT_SINK_HTTP_TOKEN").ok().filter(|token| !token.is_empty());
+        let mut sinks: Vec<Arc<dyn AuditSink>> = Vec::new();
+        for target in std::env::var("AUDIT_SINKS").unwrap_or_default().split(',').map(str::trim).filter(|t| !t.is_empty()) {
+            if target.starts_with("https://") {
+                sinks.push(Arc::new(HttpSink::new(target, token.clone())?));
+                continue;
+            }
+            let (tls, address) = if let Some(address) = target.strip_prefix("syslog+tls://") {
+                (true, address)
+            } else if let Some(address) = target.strip_prefix("syslog://") {
+                (false, address)
+            } else {
+                return Err(format!("Unsupported audit sink {}", target));
+            };
+            let (host, port) = address.rsplit_once(':')
+                .and_then(|(host, port)| Some((host, port.parse::<u16>().ok()?)))
+                .ok_or_else(|| format!("Audit sink {} needs host:port", target))?;
+            sinks.push(Arc::new(SyslogSink::new(host, port, tls)));
+        }
+
+        if !sinks.is_empty() {
+            if let Some(dir) = &settings.spill_dir {
+                std::fs::create_dir_all(dir).map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+            }
+        }
+        Ok(Self::new(sinks, settings))
+    }
+
+    /// Queue an event for every sink, stamping its per-source sequence number
+    pub fn publish(&self, mut event: AuditEvent) {
+        if self.workers.is_empty() {
+            return;
+        }
+        {
+            let mut sequences = self.sequences.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            let sequence = sequences.entry(event.source.clone()).or_insert(0);
+            *sequence += 1;
+            event.sequence = *sequence;
+        }
+        for worker in &self.workers {
+            worker.push(event.clone());
+        }
+    }
+
+    pub fn stats(&self) -> Vec<SinkStats> {
+        self.workers.iter().map(|worker| worker.stats()).collect()
+    }
+
+    /// Start one delivery loop per sink
+    pub fn spawn_delivery(&self, health: Arc<TaskHealth>) -> Vec<tokio::task::JoinHandle<()>> {
+        self.workers.iter()
+            .map(|worker| tokio::spawn(worker.clone().run(health.clone())))
+            .collect()
+    }
+}
+
+impl Default for AuditStream {
+    fn default() -> Self {
+        Self::new(Vec::new(), StreamSettings::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// Sink that records what it receives and fails while `down` is set
+    #[derive(Default)]
+    struct FlakySink {
+        down: AtomicBool,
+        received: Mutex<Vec<AuditEvent>>,
+    }
+
+    #[async_trait]
+    impl AuditSink for FlakySink {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        async fn deliver(&self, batch: &[AuditEvent]) -> Result<(), String> {
+            if self.down.load(Ordering::SeqCst) {
+                return Err("connection refused".to_string());
+            }
+            self.received.lock().unwrap().extend_from_slice(batch);
+            Ok(())
+        }
+    }
+
+    fn event(source: &str, action: &str) -> AuditEvent {
+        AuditEvent {
+            source: source.to_string(),
+            sequence: 0,
+            id: Uuid::new_v4().to_string(),
+            timestamp: Utc::now() - chrono::Duration::seconds(30),
+            tenant_id: TenantId::default(),
+            actor: "0xabc".to_string(),
+            action: action.to_string(),
+            resource: "asset-1".to_string(),
+            success: Some(true),
+            details: serde_json::Value::Null,
+        }
+    }
+
+    fn settings(spill_dir: Option<PathBuf>, max_spill_bytes: u64) -> StreamSettings {
+        StreamSettings { batch_size: 3, max_buffered: 4, spill_dir, max_spill_bytes, ..StreamSettings::default() }
+    }
+
+    fn spill_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("audit-spill-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_outage_spills_to_disk_and_replays_in_order() {
+        let sink = Arc::new(FlakySink::default());
+        sink.down.store(true, Ordering::SeqCst);
+        let dir = spill_dir();
+        let stream = AuditStream::new(vec![sink.clone()], settings(Some(dir.clone()), u64::MAX));
+
+        for i in 0..10 {
+            let source = if i % 2 == 0 { SECURE_API_SOURCE } else { COMPLIANCE_ENGINE_SOURCE };
+            stream.publish(event(source, &format!("action-{}", i)));
+        }
+
+        let worker = &stream.workers[0];
+        assert!(worker.deliver_next().await.is_err());
+        let stats = stream.stats().remove(0);
+        assert_eq!((stats.buffered, stats.spilled, stats.dropped), (4, 6, 0));
+        assert_eq!(stats.consecutive_failures, 1);
+        assert!(stats.lag_seconds >= 30);
+
+        sink.down.store(false, Ordering::SeqCst);
+        while worker.deliver_next().await.unwrap() {}
+
+        let received = sink.received.lock().unwrap().clone();
+        let actions: Vec<String> = received.iter().map(|e| e.action.clone()).collect();
+        assert_eq!(actions, (0..10).map(|i| format!("action-{}", i)).collect::<Vec<_>>());
+        for source in [SECURE_API_SOURCE, COMPLIANCE_ENGINE_SOURCE] {
+            let sequences: Vec<u64> = received.iter().filter(|e| e.source == source).map(|e| e.sequence).collect();
+            assert_eq!(sequences, vec![1, 2, 3, 4, 5]);
+        }
+
+        let stats = stream.stats().remove(0);
+        assert_eq!((stats.buffered, stats.spilled, stats.delivered, stats.lag_seconds), (0, 0, 10, 0));
+        assert!(!dir.join("flaky.jsonl").exists());
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_full_spill_counts_dropped_events_and_survives_restart() {
+        let sink = Arc::new(FlakySink::default());
+        sink.down.store(true, Ordering::SeqCst);
+        let dir = spill_dir();
+        let line_len = serde_json::to_string(&event(SECURE_API_SOURCE, "x")).unwrap().len() as u64 + 16;
+        let stream = AuditStream::new(vec![sink.clone()], settings(Some(dir.clone()), line_len * 2));
+
+        for _ in 0..8 {
+            stream.publish(event(SECURE_API_SOURCE, "x"));
+        }
+        let stats = stream.stats().remove(0);
+        assert_eq!((stats.buffered, stats.spilled, stats.dropped), (4, 2, 2));
+
+        // A new process picks the spilled events back up
+        let restarted = SinkWorker::new(sink.clone(), settings(Some(dir.clone()), line_len * 2));
+        sink.down.store(false, Ordering::SeqCst);
+        assert_eq!(restarted.stats().spilled, 2);
+        while restarted.deliver_next().await.unwrap() {}
+        let replayed: Vec<u64> = sink.received.lock().unwrap().iter().map(|e| e.sequence).collect();
+        assert_eq!(replayed, vec![5, 6]);
+
+        // Without a spill directory the overflow is dropped outright
+        let memory_only = AuditStream::new(vec![Arc::new(FlakySink::default())], settings(None, u64::MAX));
+        for _ in 0..6 {
+            memory_only.publish(event(SECURE_API_SOURCE, "x"));
+        }
+        assert_eq!(memory_only.stats()[0].dropped, 2);
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_rfc5424_format_and_backoff() {
+        let mut failed = event(COMPLIANCE_ENGINE_SOURCE, "compliance_check");
+        failed.success = Some(false);
+        failed.sequence = 7;
+        failed.actor = "officer \"x\"]".to_string();
+
+        let message = format_rfc5424(&failed, "api 1").unwrap();
+        assert!(message.starts_with("<108>1 "));
+        assert!(message.contains(" api1 quantera - compliance_engine [quantera@32473 "));
+        assert!(message.contains("seq=\"7\""));
+        assert!(message.contains("actor=\"officer \\\"x\\\"\\]\""));
+
+        let settings = StreamSettings::default();
+        assert_eq!(settings.retry_delay(1), Duration::from_secs(1));
+        assert_eq!(settings.retry_delay(4), Duration::from_secs(8));
+        assert_eq!(settings.retry_delay(40), Duration::from_secs(300));
+    }
+}
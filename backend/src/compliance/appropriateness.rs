@@ -0,0 +1,332 @@
+// MiFID II appropriateness questionnaires, scoring and product complexity tiers
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use chrono::{DateTime, Utc, Duration};
+
+use super::enhanced_compliance_engine::ComplianceError;
+
+/// Questionnaires older than this no longer support an appropriateness decision
+pub const QUESTIONNAIRE_VALIDITY_DAYS: i64 = 365;
+
+/// Knowledge and experience score (0-100) from which complex products are appropriate
+pub const COMPLEX_PRODUCT_THRESHOLD: u8 = 40;
+
+/// Knowledge and experience score (0-100) from which highly complex products are appropriate
+pub const HIGHLY_COMPLEX_PRODUCT_THRESHOLD: u8 = 70;
+
+/// Financial situation score below which the investor cannot bear losses beyond non-complex products
+pub const LOSS_CAPACITY_FLOOR: u8 = 30;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestionCategory {
+    Knowledge,
+    Experience,
+    FinancialSituation,
+    Objectives,
+}
+
+impl QuestionCategory {
+    pub const ALL: [QuestionCategory; 4] = [
+        QuestionCategory::Knowledge,
+        QuestionCategory::Experience,
+        QuestionCategory::FinancialSituation,
+        QuestionCategory::Objectives,
+    ];
+}
+
+/// Product complexity under MiFID II; ordered from least to most complex
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum ComplexityTier {
+    NonComplex,
+    Complex,
+    HighlyComplex,
+}
+
+/// Complexity tier of an asset type; unknown asset types are treated as complex
+pub fn product_complexity(asset_type: &str) -> ComplexityTier {
+    match asset_type {
+        "securities" | "real_estate" | "commodities" | "treasuries" => ComplexityTier::NonComplex,
+        "private_equity" | "structured_products" | "complex_instruments" | "institutional_securities" => ComplexityTier::Complex,
+        "derivatives" | "high_risk" => ComplexityTier::HighlyComplex,
+        _ => ComplexityTier::Complex,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnswerOption {
+    pub option_id: String,
+    pub text: String,
+    pub points: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Question {
+    pub question_id: String,
+    pub category: QuestionCategory,
+    pub text: String,
+    pub options: Vec<AnswerOption>,
+}
+
+impl Question {
+    fn max_points(&self) -> u32 {
+        self.options.iter().map(|option| option.points as u32).max().unwrap_or(0)
+    }
+}
+
+/// Versioned question set of a jurisdiction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestionSet {
+    #[serde(default)]
+    pub jurisdiction: String,
+    /// Assigned on publish
+    #[serde(default)]
+    pub version: u32,
+    pub questions: Vec<Question>,
+    #[serde(default = "Utc::now")]
+    pub published_at: DateTime<Utc>,
+}
+
+impl QuestionSet {
+    /// Every category needs a question, and question and option ids must be unique
+    pub fn validate(&self) -> Result<(), ComplianceError> {
+        let mut question_ids = HashSet::new();
+        for question in &self.questions {
+            if !question_ids.insert(question.question_id.as_str()) {
+                return Err(ComplianceError::InvalidInput(format!("Duplicate question {}", question.question_id)));
+            }
+            if question.options.is_empty() {
+                return Err(ComplianceError::InvalidInput(format!("Question {} has no options", question.question_id)));
+            }
+            let mut option_ids = HashSet::new();
+            if !question.options.iter().all(|option| option_ids.insert(option.option_id.as_str())) {
+                return Err(ComplianceError::InvalidInput(format!("Duplicate option in question {}", question.question_id)));
+            }
+        }
+
+        for category in QuestionCategory::ALL {
+            if !self.questions.iter().any(|q| q.category == category && q.max_points() > 0) {
+                return Err(ComplianceError::InvalidInput(format!("No scored question for category {:?}", category)));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Built-in question set used for MiFID II jurisdictions until a version is published
+    pub fn builtin(jurisdiction: &str) -> Self {
+        let question = |question_id: &str, category, text: &str, options: &[(&str, &str, u8)]| Question {
+            question_id: question_id.to_string(),
+            category,
+            text: text.to_string(),
+            options: options.iter()
+                .map(|(option_id, text, points)| AnswerOption {
+                    option_id: option_id.to_string(),
+                    text: text.to_string(),
+                    points: *points,
+                })
+                .collect(),
+        };
+
+        Self {
+            jurisdiction: jurisdiction.to_string(),
+            version: 1,
+            questions: vec![
+                question("knowledge_leverage", QuestionCategory::Knowledge,
+                    "What happens to your position if a leveraged product moves against you?",
+                    &[("unsure", "I am not sure", 0), ("capped", "I can lose at most my investment", 5), ("amplified", "Losses are amplified and may exceed my investment", 10)]),
+                question("knowledge_liquidity", QuestionCategory::Knowledge,
+                    "Can tokenized assets always be sold at their quoted price?",
+                    &[("yes", "Yes", 0), ("unsure", "I am not sure", 2), ("no", "No, thin markets can force a discount", 10)]),
+                question("experience_years", QuestionCategory::Experience,
+                    "How long have you invested in securities or digital assets?",
+                    &[("none", "Never", 0), ("under_3", "Less than 3 years", 5), ("over_3", "3 years or more", 10)]),
+                question("experience_complex", QuestionCategory::Experience,
+                    "How many trades in derivatives or structured products have you made in the last 12 months?",
+                    &[("none", "None", 0), ("few", "1 to 9", 5), ("many", "10 or more", 10)]),
+                question("financial_loss_capacity", QuestionCategory::FinancialSituation,
+                    "What share of your liquid assets could you lose without affecting your standard of living?",
+                    &[("none", "None", 0), ("some", "Up to 10%", 5), ("substantial", "More than 10%", 10)]),
+                question("financial_income", QuestionCategory::FinancialSituation,
+                    "How stable is your income?",
+                    &[("unstable", "Irregular or none", 0), ("stable", "Stable", 10)]),
+                question("objectives_horizon", QuestionCategory::Objectives,
+                    "How long do you plan to hold this investment?",
+                    &[("short", "Less than 1 year", 0), ("medium", "1 to 5 years", 5), ("long", "More than 5 years", 10)]),
+                question("objectives_risk", QuestionCategory::Objectives,
+                    "Which best describes your investment objective?",
+                    &[("preserve", "Preserve capital", 0), ("income", "Steady income", 5), ("growth", "Growth, accepting losses", 10)]),
+            ],
+            published_at: Utc::now(),
+        }
+    }
+}
+
+/// Answers to a question set, keyed by question id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestionnaireAnswers {
+    pub question_set_version: u32,
+    pub answers: HashMap<String, String>,
+}
+
+/// Outcome of a scored questionnaire, stored on the investor profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppropriatenessProfile {
+    pub jurisdiction: String,
+    pub question_set_version: u32,
+    /// Percentage of the attainable points per category
+    pub category_scores: HashMap<QuestionCategory, u8>,
+    pub knowledge_experience_score: u8,
+    /// Most complex product tier that is appropriate for the investor
+    pub max_complexity: ComplexityTier,
+    pub assessed_at: DateTime<Utc>,
+}
+
+impl AppropriatenessProfile {
+    /// Older than the validity period and due for a refresh
+    pub fn is_stale(&self, now: DateTime<Utc>) -> bool {
+        now.signed_duration_since(self.assessed_at) > Duration::days(QUESTIONNAIRE_VALIDITY_DAYS)
+    }
+
+    pub fn permits(&self, complexity: ComplexityTier) -> bool {
+        complexity <= self.max_complexity
+    }
+}
+
+/// Highest appropriate complexity for the knowledge and experience and financial situation scores
+pub fn appropriate_complexity(knowledge_experience: u8, financial_situation: u8) -> ComplexityTier {
+    let tier = if knowledge_experience >= HIGHLY_COMPLEX_PRODUCT_THRESHOLD {
+        ComplexityTier::HighlyComplex
+    } else if knowledge_experience >= COMPLEX_PRODUCT_THRESHOLD {
+        ComplexityTier::Complex
+    } else {
+        ComplexityTier::NonComplex
+    };
+
+    if financial_situation < LOSS_CAPACITY_FLOOR {
+        ComplexityTier::NonComplex
+    } else {
+        tier
+    }
+}
+
+/// Score answers against the question set they were given for; every question must be answered
+pub fn score(
+    set: &QuestionSet,
+    answers: &QuestionnaireAnswers,
+    now: DateTime<Utc>,
+) -> Result<AppropriatenessProfile, ComplianceError> {
+    if answers.question_set_version != set.version {
+        return Err(ComplianceError::InvalidInput(format!(
+            "Answers are for question set version {}, current version is {}",
+            answers.question_set_version, set.version
+        )));
+    }
+    if let Some(unknown) = answers.answers.keys().find(|id| !set.questions.iter().any(|q| &q.question_id == *id)) {
+        return Err(ComplianceError::InvalidInput(format!("Unknown question {}", unknown)));
+    }
+
+    let mut points: HashMap<QuestionCategory, (u32, u32)> = HashMap::new();
+    for question in &set.questions {
+        let option_id = answers.answers.get(&question.question_id)
+            .ok_or_else(|| ComplianceError::InvalidInput(format!("Question {} is unanswered", question.question_id)))?;
+        let option = question.options.iter()
+            .find(|option| &option.option_id == option_id)
+            .ok_or_else(|| ComplianceError::InvalidInput(format!("Invalid answer {} to question {}", option_id, question.question_id)))?;
+
+        let entry = points.entry(question.category).or_insert((0, 0));
+        entry.0 += option.points as u32;
+        entry.1 += question.max_points();
+    }
+
+    let percentage = |(earned, attainable): (u32, u32)| -> u8 {
+        if attainable == 0 { 0 } else { (earned * 100 / attainable) as u8 }
+    };
+    let category_scores: HashMap<QuestionCategory, u8> = points.iter()
+        .map(|(category, totals)| (*category, percentage(*totals)))
+        .collect();
+
+    // Knowledge and experience are pooled so strength in one can offset the other
+    let pooled = [QuestionCategory::Knowledge, QuestionCategory::Experience].iter()
+        .filter_map(|category| points.get(category))
+        .fold((0, 0), |acc, (earned, attainable)| (acc.0 + earned, acc.1 + attainable));
+    let knowledge_experience_score = percentage(pooled);
+    let financial_situation = category_scores.get(&QuestionCategory::FinancialSituation).copied().unwrap_or(0);
+
+    Ok(AppropriatenessProfile {
+        jurisdiction: set.jurisdiction.clone(),
+        question_set_version: set.version,
+        category_scores,
+        knowledge_experience_score,
+        max_complexity: appropriate_complexity(knowledge_experience_score, financial_situation),
+        assessed_at: now,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answers(set: &QuestionSet, pick: impl Fn(&Question) -> usize) -> QuestionnaireAnswers {
+        QuestionnaireAnswers {
+            question_set_version: set.version,
+            answers: set.questions.iter()
+                .map(|q| (q.question_id.clone(), q.options[pick(q)].option_id.clone()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_complexity_boundaries() {
+        assert_eq!(appropriate_complexity(COMPLEX_PRODUCT_THRESHOLD - 1, 100), ComplexityTier::NonComplex);
+        assert_eq!(appropriate_complexity(COMPLEX_PRODUCT_THRESHOLD, 100), ComplexityTier::Complex);
+        assert_eq!(appropriate_complexity(HIGHLY_COMPLEX_PRODUCT_THRESHOLD - 1, 100), ComplexityTier::Complex);
+        assert_eq!(appropriate_complexity(HIGHLY_COMPLEX_PRODUCT_THRESHOLD, 100), ComplexityTier::HighlyComplex);
+
+        // Low loss capacity caps at non-complex regardless of expertise
+        assert_eq!(appropriate_complexity(100, LOSS_CAPACITY_FLOOR - 1), ComplexityTier::NonComplex);
+        assert_eq!(appropriate_complexity(100, LOSS_CAPACITY_FLOOR), ComplexityTier::HighlyComplex);
+    }
+
+    #[test]
+    fn test_scoring() {
+        let set = QuestionSet::builtin("EU");
+        set.validate().unwrap();
+        let now = Utc::now();
+
+        let expert = score(&set, &answers(&set, |q| q.options.len() - 1), now).unwrap();
+        assert_eq!(expert.knowledge_experience_score, 100);
+        assert_eq!(expert.max_complexity, ComplexityTier::HighlyComplex);
+        assert!(expert.permits(product_complexity("derivatives")));
+
+        let novice = score(&set, &answers(&set, |_| 0), now).unwrap();
+        assert_eq!(novice.knowledge_experience_score, 0);
+        assert_eq!(novice.max_complexity, ComplexityTier::NonComplex);
+        assert!(novice.permits(product_complexity("real_estate")));
+        assert!(!novice.permits(product_complexity("structured_products")));
+
+        // Middle options: 5 + 2 + 5 + 5 of 40 knowledge and experience points
+        let intermediate = score(&set, &answers(&set, |q| if q.options.len() > 2 { 1 } else { 0 }), now).unwrap();
+        assert_eq!(intermediate.knowledge_experience_score, 42);
+        assert_eq!(intermediate.category_scores[&QuestionCategory::FinancialSituation], 25);
+        assert_eq!(intermediate.max_complexity, ComplexityTier::NonComplex);
+
+        let mut incomplete = answers(&set, |_| 0);
+        incomplete.answers.remove("objectives_risk");
+        assert!(matches!(score(&set, &incomplete, now), Err(ComplianceError::InvalidInput(_))));
+
+        let mut outdated = answers(&set, |_| 0);
+        outdated.question_set_version = 0;
+        assert!(matches!(score(&set, &outdated, now), Err(ComplianceError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_staleness() {
+        let set = QuestionSet::builtin("EU");
+        let assessed = Utc::now() - Duration::days(QUESTIONNAIRE_VALIDITY_DAYS);
+        let profile = score(&set, &answers(&set, |_| 0), assessed).unwrap();
+        assert!(!profile.is_stale(assessed + Duration::days(QUESTIONNAIRE_VALIDITY_DAYS)));
+        assert!(profile.is_stale(assessed + Duration::days(QUESTIONNAIRE_VALIDITY_DAYS) + Duration::seconds(1)));
+    }
+}
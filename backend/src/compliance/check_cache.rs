@@ -0,0 +1,198 @@
+// Short-lived cache of compliance check results, invalidated by profile and rule versions
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::tenant::TenantId;
+use super::enhanced_compliance_engine::ComplianceResult;
+
+/// How long a cached result is served; time-dependent checks (KYC expiry, cooling
+/// periods, questionnaire validity) are never more stale than this
+pub const CHECK_CACHE_TTL_SECS: i64 = 60;
+
+/// Most results held at once
+pub const CHECK_CACHE_CAPACITY: usize = 10_000;
+
+/// Identifies the inputs a check result depends on
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CheckCacheKey {
+    pub tenant_id: TenantId,
+    pub investor_id: String,
+    pub asset_type: String,
+    pub jurisdiction: String,
+    pub amount_bucket: AmountBucket,
+    pub profile_version: u64,
+    pub rules_version: u64,
+}
+
+/// Coarse amount range. Amounts in one bucket have the same order of magnitude and
+/// sit on the same side of every fixed amount threshold the checks compare against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AmountBucket {
+    magnitude: u32,
+    thresholds_exceeded: usize,
+}
+
+impl AmountBucket {
+    pub fn new(amount: u128, thresholds: &[u128]) -> Self {
+        Self {
+            magnitude: u128::BITS - amount.leading_zeros(),
+            thresholds_exceeded: thresholds.iter().filter(|threshold| amount > **threshold).count(),
+        }
+    }
+}
+
+struct CachedCheck {
+    amount: u128,
+    result: ComplianceResult,
+    expires_at: DateTime<Utc>,
+}
+
+impl CachedCheck {
+    /// A pass covers smaller amounts in its bucket, since every amount-dependent check
+    /// only gets stricter as the amount grows; anything else must match exactly
+    fn covers(&self, amount: u128) -> bool {
+        amount == self.amount || (self.result.is_compliant && amount < self.amount)
+    }
+}
+
+/// Hit and miss counts since the engine started
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct CheckCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+    pub hit_rate: f64,
+}
+
+pub struct CheckCache {
+    entries: HashMap<CheckCacheKey, CachedCheck>,
+    ttl: Duration,
+    capacity: usize,
+    hits: u64,
+    misses: u64,
+}
+
+impl Default for CheckCache {
+    fn default() -> Self {
+        Self::new(Duration::seconds(CHECK_CACHE_TTL_SECS), CHECK_CACHE_CAPACITY)
+    }
+}
+
+impl CheckCache {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self { entries: HashMap::new(), ttl, capacity, hits: 0, misses: 0 }
+    }
+
+    /// Cached result for `amount`, if one is live and covers it
+    pub fn get(&mut self, key: &CheckCacheKey, amount: u128, now: DateTime<Utc>) -> Option<&ComplianceResult> {
+        let hit = self.entries.get(key)
+            .map_or(false, |cached| cached.expires_at > now && cached.covers(amount));
+
+        if hit {
+            self.hits += 1;
+            self.entries.get(key).map(|cached| &cached.result)
+        } else {
+            self.misses += 1;
+            None
+        }
+    }
+
+    pub fn insert(&mut self, key: CheckCacheKey, amount: u128, result: ComplianceResult, now: DateTime<Utc>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&key) {
+            self.entries.retain(|_, cached| cached.expires_at > now);
+        }
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&key) {
+            let oldest = self.entries.iter()
+                .min_by_key(|(_, cached)| cached.expires_at)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+
+        self.entries.insert(key, CachedCheck { amount, result, expires_at: now + self.ttl });
+    }
+
+    pub fn stats(&self) -> CheckCacheStats {
+        let lookups = self.hits + self.misses;
+        CheckCacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.entries.len(),
+            hit_rate: if lookups == 0 { 0.0 } else { self.hits as f64 / lookups as f64 },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(is_compliant: bool) -> ComplianceResult {
+        ComplianceResult {
+            is_compliant,
+            overall_score: if is_compliant { 100 } else { 70 },
+            checks: Vec::new(),
+            recommendations: Vec::new(),
+            required_actions: Vec::new(),
+            estimated_completion_time: None,
+            audit_trail_id: "audit".to_string(),
+            locale: "en".to_string(),
+            recommendation_refs: Vec::new(),
+        }
+    }
+
+    fn key(amount: u128) -> CheckCacheKey {
+        CheckCacheKey {
+            tenant_id: TenantId::default(),
+            investor_id: "investor-1".to_string(),
+            asset_type: "real_estate".to_string(),
+            jurisdiction: "EU".to_string(),
+            amount_bucket: AmountBucket::new(amount, &[1_000]),
+            profile_version: 1,
+            rules_version: 1,
+        }
+    }
+
+    #[test]
+    fn test_pass_never_covers_a_larger_amount() {
+        let now = Utc::now();
+        let mut cache = CheckCache::default();
+
+        cache.insert(key(600), 600, result(true), now);
+        assert_eq!(key(600), key(700));
+        assert!(cache.get(&key(700), 700, now).is_none());
+        assert!(cache.get(&key(600), 600, now).is_some());
+        assert!(cache.get(&key(550), 550, now).is_some());
+
+        // A failure only answers the exact amount it was computed for
+        cache.insert(key(600), 600, result(false), now);
+        assert!(cache.get(&key(550), 550, now).is_none());
+
+        // Crossing a threshold changes the bucket
+        assert_ne!(key(1_000), key(1_001));
+
+        assert!(cache.get(&key(600), 600, now + Duration::seconds(CHECK_CACHE_TTL_SECS)).is_none());
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (2, 3));
+    }
+
+    #[test]
+    fn test_capacity_drops_the_oldest_entry() {
+        let now = Utc::now();
+        let mut cache = CheckCache::new(Duration::seconds(60), 2);
+        let keyed = |investor: &str| CheckCacheKey { investor_id: investor.to_string(), ..key(10) };
+
+        cache.insert(keyed("a"), 10, result(true), now);
+        cache.insert(keyed("b"), 10, result(true), now + Duration::seconds(1));
+        cache.insert(keyed("c"), 10, result(true), now + Duration::seconds(2));
+
+        assert_eq!(cache.stats().entries, 2);
+        assert!(cache.get(&keyed("a"), 10, now).is_none());
+        assert!(cache.get(&keyed("c"), 10, now).is_some());
+    }
+}
@@ -0,0 +1,327 @@
+// Investment limits per asset type, issuer, asset class and portfolio, checked against holdings
+use async_trait::async_trait;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::services::portfolio_service::PortfolioService;
+use super::enhanced_compliance_engine::InvestmentLimit;
+use super::thresholds::{ConversionRates, FiatCurrency, ThresholdConfig};
+
+/// What an investment limit caps
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LimitScope {
+    /// One asset type, counted by the engine as investments are made
+    AssetType(String),
+    /// Everything issued by one issuer
+    Issuer(String),
+    AssetClass(String),
+    /// The whole portfolio
+    Portfolio,
+}
+
+impl LimitScope {
+    /// Exposure under the scope comes from portfolio holdings rather than the limit's counter
+    pub fn uses_holdings(&self) -> bool {
+        !matches!(self, LimitScope::AssetType(_))
+    }
+
+    /// The scope covers an investment in `target`
+    pub fn covers(&self, target: &InvestmentTarget) -> bool {
+        match self {
+            LimitScope::AssetType(asset_type) => *asset_type == target.asset_type,
+            LimitScope::Issuer(issuer) => target.issuer.as_ref() == Some(issuer),
+            LimitScope::AssetClass(class) => target.asset_class.as_ref() == Some(class),
+            LimitScope::Portfolio => true,
+        }
+    }
+
+    fn holds(&self, holding: &Holding) -> bool {
+        match self {
+            LimitScope::AssetType(asset_type) => holding.asset_type.as_ref() == Some(asset_type),
+            LimitScope::Issuer(issuer) => holding.issuer.as_ref() == Some(issuer),
+            LimitScope::AssetClass(class) => holding.asset_class.as_ref() == Some(class),
+            LimitScope::Portfolio => true,
+        }
+    }
+}
+
+impl std::fmt::Display for LimitScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LimitScope::AssetType(asset_type) => write!(f, "asset type {}", asset_type),
+            LimitScope::Issuer(issuer) => write!(f, "issuer {}", issuer),
+            LimitScope::AssetClass(class) => write!(f, "asset class {}", class),
+            LimitScope::Portfolio => f.write_str("portfolio"),
+        }
+    }
+}
+
+/// The asset an investment is made in. Issuer and asset class limits only apply when the
+/// target names its issuer and class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvestmentTarget {
+    pub asset_type: String,
+    pub issuer: Option<String>,
+    pub asset_class: Option<String>,
+}
+
+impl InvestmentTarget {
+    pub fn new(asset_type: &str) -> Self {
+        Self { asset_type: asset_type.to_string(), issuer: None, asset_class: None }
+    }
+
+    pub fn issued_by(mut self, issuer: &str) -> Self {
+        self.issuer = Some(issuer.to_string());
+        self
+    }
+
+    pub fn in_class(mut self, asset_class: &str) -> Self {
+        self.asset_class = Some(asset_class.to_string());
+        self
+    }
+}
+
+/// A synced portfolio position, valued in settlement asset base units like investment amounts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Holding {
+    pub asset_id: String,
+    pub asset_type: Option<String>,
+    pub issuer: Option<String>,
+    pub asset_class: Option<String>,
+    pub value: u128,
+}
+
+/// Current holdings of investors, for limits whose exposure spans the portfolio
+#[async_trait]
+pub trait HoldingsSource: Send + Sync {
+    async fn holdings(&self, investor_id: &str) -> Result<Vec<Holding>, String>;
+}
+
+/// Holdings from the synced portfolio tables, with the investor id as the wallet address.
+/// Values are converted from each holding's currency at the compliance conversion rates.
+pub struct PortfolioHoldings {
+    portfolio: Arc<PortfolioService>,
+    rates: Arc<dyn ConversionRates>,
+    config: ThresholdConfig,
+}
+
+impl PortfolioHoldings {
+    pub fn new(portfolio: Arc<PortfolioService>, rates: Arc<dyn ConversionRates>, config: ThresholdConfig) -> Self {
+        Self { portfolio, rates, config }
+    }
+
+    /// Settlement base units per unit of `currency`
+    async fn settlement_units_per(&self, currency: &str) -> Result<Decimal, String> {
+        let fiat = match currency {
+            "USD" => FiatCurrency::USD,
+            "EUR" => FiatCurrency::EUR,
+            "SGD" => FiatCurrency::SGD,
+            other => return Err(format!("no FX rate for {}", other)),
+        };
+        let usd_per_unit = match fiat {
+            FiatCurrency::USD => Decimal::ONE,
+            fiat => {
+                let rate = self.rates.usd_fx_rate(fiat).await?.rate;
+                Decimal::ONE.checked_div(rate).ok_or_else(|| format!("invalid USD/{} rate {}", fiat, rate))?
+            }
+        };
+        let asset_usd = self.rates.asset_price_usd(&self.config.settlement_asset).await?.rate;
+        let scale = 10u64.checked_pow(self.config.settlement_decimals)
+            .ok_or_else(|| "settlement decimals out of range".to_string())?;
+        usd_per_unit.checked_div(asset_usd)
+            .and_then(|units| units.checked_mul(Decimal::from(scale)))
+            .ok_or_else(|| format!("invalid {}/USD price {}", self.config.settlement_asset, asset_usd))
+    }
+}
+
+#[async_trait]
+impl HoldingsSource for PortfolioHoldings {
+    async fn holdings(&self, investor_id: &str) -> Result<Vec<Holding>, String> {
+        let holdings = self.portfolio.get_holdings(investor_id, None, None, None, None, None).await
+            .map_err(|e| e.to_string())?;
+
+        let mut rates: HashMap<String, Decimal> = HashMap::new();
+        let mut valued = Vec::with_capacity(holdings.len());
+        for holding in holdings {
+            let currency = holding.currency.to_string();
+            let units_per = match rates.get(&currency) {
+                Some(units_per) => *units_per,
+                None => {
+                    let units_per = self.settlement_units_per(&currency).await?;
+                    rates.insert(currency, units_per);
+                    units_per
+                }
+            };
+            let value: Decimal = holding.value.parse().map_err(|_| format!("invalid value of {}", holding.asset_id))?;
+            let value = value.checked_mul(units_per)
+                .and_then(|units| units.floor().to_u128())
+                .ok_or_else(|| format!("value of {} is out of range", holding.asset_id))?;
+            valued.push(Holding {
+                asset_id: holding.asset_id,
+                asset_type: holding.asset_type,
+                issuer: holding.issuer,
+                asset_class: holding.asset_class,
+                value,
+            });
+        }
+        Ok(valued)
+    }
+}
+
+/// An applicable limit with the exposure already counted against it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopedLimit {
+    pub scope: LimitScope,
+    pub maximum_amount: u128,
+    pub current_exposure: u128,
+}
+
+impl ScopedLimit {
+    pub fn remaining(&self) -> u128 {
+        self.maximum_amount.saturating_sub(self.current_exposure)
+    }
+}
+
+/// Every limit applicable to an investment, evaluated
+#[derive(Debug, Clone, Default)]
+pub struct LimitReport {
+    pub evaluated: Vec<ScopedLimit>,
+    /// Scopes whose exposure could not be established, with the reason
+    pub unavailable: Vec<(LimitScope, String)>,
+}
+
+impl LimitReport {
+    pub fn is_empty(&self) -> bool {
+        self.evaluated.is_empty() && self.unavailable.is_empty()
+    }
+
+    /// The most restrictive limit: the one leaving the least room
+    pub fn binding(&self) -> Option<&ScopedLimit> {
+        self.evaluated.iter().min_by_key(|limit| limit.remaining())
+    }
+
+    /// Whether every evaluated limit has room for `amount`
+    pub fn permits(&self, amount: u128) -> bool {
+        self.binding().map_or(true, |limit| amount <= limit.remaining())
+    }
+}
+
+/// Evaluate every limit covering `target`. Holdings are only loaded when a limit needs them,
+/// and when they cannot be, those limits are reported unavailable instead of passing.
+pub async fn evaluate_limits(
+    limits: &HashMap<String, InvestmentLimit>,
+    target: &InvestmentTarget,
+    investor_id: &str,
+    holdings: Option<&dyn HoldingsSource>,
+) -> LimitReport {
+    let applicable: Vec<&InvestmentLimit> = limits.values()
+        .filter(|limit| limit.scope().covers(target))
+        .collect();
+
+    let loaded = if applicable.iter().any(|limit| limit.scope().uses_holdings()) {
+        match holdings {
+            Some(source) => source.holdings(investor_id).await,
+            None => Err("no holdings source is configured".to_string()),
+        }
+    } else {
+        Ok(Vec::new())
+    };
+
+    let mut report = LimitReport::default();
+    for limit in applicable {
+        let scope = limit.scope();
+        let current_exposure = if !scope.uses_holdings() {
+            limit.current_exposure
+        } else {
+            match &loaded {
+                Ok(holdings) => holdings.iter()
+                    .filter(|holding| scope.holds(holding))
+                    .fold(0u128, |total, holding| total.saturating_add(holding.value)),
+                Err(reason) => {
+                    report.unavailable.push((scope, reason.clone()));
+                    continue;
+                }
+            }
+        };
+        report.evaluated.push(ScopedLimit { scope, maximum_amount: limit.maximum_amount, current_exposure });
+    }
+    // Stable order for reporting
+    report.evaluated.sort_by(|a, b| a.scope.to_string().cmp(&b.scope.to_string()));
+    report.unavailable.sort_by(|a, b| a.0.to_string().cmp(&b.0.to_string()));
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    struct FixedHoldings(Result<Vec<Holding>, String>);
+
+    #[async_trait]
+    impl HoldingsSource for FixedHoldings {
+        async fn holdings(&self, _investor_id: &str) -> Result<Vec<Holding>, String> {
+            self.0.clone()
+        }
+    }
+
+    fn limit(asset_type: &str, scope: Option<LimitScope>, maximum_amount: u128, current_exposure: u128) -> InvestmentLimit {
+        InvestmentLimit {
+            asset_type: asset_type.to_string(),
+            maximum_amount,
+            current_exposure,
+            reset_period: Duration::days(365),
+            last_reset: Utc::now(),
+            scope,
+        }
+    }
+
+    fn holding(asset_id: &str, issuer: &str, asset_class: &str, value: u128) -> Holding {
+        Holding {
+            asset_id: asset_id.to_string(),
+            asset_type: Some("securities".to_string()),
+            issuer: Some(issuer.to_string()),
+            asset_class: Some(asset_class.to_string()),
+            value,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_overlapping_scopes_and_unavailable_holdings() {
+        let limits = HashMap::from([
+            ("securities".to_string(), limit("securities", None, 1_000, 100)),
+            ("issuer:acme".to_string(), limit("securities", Some(LimitScope::Issuer("Acme".to_string())), 500, 0)),
+            ("class:equity".to_string(), limit("securities", Some(LimitScope::AssetClass("equity".to_string())), 2_000, 0)),
+            ("portfolio".to_string(), limit("securities", Some(LimitScope::Portfolio), 5_000, 0)),
+            ("issuer:other".to_string(), limit("securities", Some(LimitScope::Issuer("Other".to_string())), 1, 0)),
+        ]);
+        let target = InvestmentTarget::new("securities").issued_by("Acme").in_class("equity");
+        let source = FixedHoldings(Ok(vec![
+            holding("acme-1", "Acme", "equity", 300),
+            holding("acme-2", "Acme", "debt", 150),
+            holding("beta-1", "Beta", "equity", 900),
+        ]));
+
+        let report = evaluate_limits(&limits, &target, "investor-1", Some(&source)).await;
+        assert_eq!(report.evaluated.len(), 4, "the other issuer's limit does not apply");
+        let exposure = |scope: LimitScope| report.evaluated.iter().find(|limit| limit.scope == scope).unwrap().current_exposure;
+        assert_eq!(exposure(LimitScope::AssetType("securities".to_string())), 100);
+        assert_eq!(exposure(LimitScope::Issuer("Acme".to_string())), 450);
+        assert_eq!(exposure(LimitScope::AssetClass("equity".to_string())), 1_200);
+        assert_eq!(exposure(LimitScope::Portfolio), 1_350);
+        assert_eq!(report.binding().unwrap().scope, LimitScope::Issuer("Acme".to_string()));
+        assert!(report.permits(50) && !report.permits(51));
+
+        // Without holdings only the asset type limit can be evaluated
+        let report = evaluate_limits(&limits, &target, "investor-1", Some(&FixedHoldings(Err("sync lagging".to_string())))).await;
+        assert_eq!(report.evaluated.len(), 1);
+        assert_eq!(report.unavailable.iter().map(|(scope, _)| scope.to_string()).collect::<Vec<_>>(), vec![
+            "asset class equity", "issuer Acme", "portfolio",
+        ]);
+        let report = evaluate_limits(&limits, &InvestmentTarget::new("securities"), "investor-1", None).await;
+        assert_eq!(report.unavailable.len(), 1, "only the portfolio limit covers an unattributed asset");
+    }
+}
@@ -0,0 +1,332 @@
+//! Whether an investor may invest in a specific instrument, for gating the invest action.
+//!
+//! Evaluated from the profile the engine already holds, without running or recording a
+//! compliance check, so the frontend can ask on page load. Each criterion reports on its
+//! own with a remediation hint; investors without a complete profile get the onboarding
+//! tasks that stand between them and an evaluation.
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use crate::services::multi_chain_asset_service::DistributionRules;
+use super::enhanced_compliance_engine::{
+    AMLStatus, AccreditationStatus, InvestorProfile, InvestorType, KYCStatus, SanctionsStatus,
+};
+use super::messages::{MessageCatalog, MessageRef, DEFAULT_LOCALE};
+
+/// Largest single investment at the Basic tier, in the instrument's currency
+pub const BASIC_TIER_CAP: u64 = 50_000;
+/// Largest single investment at the Enhanced tier, in the instrument's currency
+pub const ENHANCED_TIER_CAP: u64 = 1_000_000;
+
+/// Verification depth of an investor, which caps what they may invest in one go
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KycTier {
+    Unverified,
+    Basic,
+    Enhanced,
+    Institutional,
+}
+
+impl KycTier {
+    pub fn for_profile(profile: &InvestorProfile) -> Self {
+        if profile.kyc_status != KYCStatus::Completed {
+            return KycTier::Unverified;
+        }
+        match profile.investor_type {
+            InvestorType::Institutional | InvestorType::EligibleCounterparty => KycTier::Institutional,
+            InvestorType::Professional | InvestorType::QualifiedInvestor | InvestorType::AccreditedInvestor => KycTier::Enhanced,
+            InvestorType::Retail if matches!(profile.accreditation_status, AccreditationStatus::Verified) => KycTier::Enhanced,
+            InvestorType::Retail => KycTier::Basic,
+        }
+    }
+
+    /// Largest single investment; None when uncapped
+    pub fn cap(&self) -> Option<Decimal> {
+        match self {
+            KycTier::Unverified => Some(Decimal::ZERO),
+            KycTier::Basic => Some(Decimal::from(BASIC_TIER_CAP)),
+            KycTier::Enhanced => Some(Decimal::from(ENHANCED_TIER_CAP)),
+            KycTier::Institutional => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Criterion {
+    Jurisdiction,
+    InvestorType,
+    KycTier,
+    CoolingPeriod,
+    Sanctions,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CriterionResult {
+    pub criterion: Criterion,
+    pub passed: bool,
+    /// Rendered from `message_ref`, in the locale of the report
+    pub message: String,
+    /// What would make the criterion pass; None once it does
+    pub remediation: Option<String>,
+    pub message_ref: MessageRef,
+    pub remediation_ref: Option<MessageRef>,
+}
+
+impl CriterionResult {
+    fn new(criterion: Criterion, passed: bool, message: MessageRef, remediation: MessageRef) -> Self {
+        Self {
+            criterion,
+            passed,
+            message: String::new(),
+            remediation: None,
+            message_ref: message,
+            remediation_ref: (!passed).then_some(remediation),
+        }
+    }
+}
+
+/// Steps an investor must take before they can be evaluated in full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingTask {
+    CreateProfile,
+    CompleteKyc,
+    CompleteAml,
+    ProvideTaxResidency,
+}
+
+impl OnboardingTask {
+    fn message(&self) -> MessageRef {
+        match self {
+            OnboardingTask::CreateProfile => MessageRef::new("eligibility.onboarding.create_profile"),
+            OnboardingTask::CompleteKyc => MessageRef::new("kyc.remediation"),
+            OnboardingTask::CompleteAml => MessageRef::new("aml.remediation"),
+            OnboardingTask::ProvideTaxResidency => MessageRef::new("tax_residency.remediation"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OnboardingStep {
+    pub task: OnboardingTask,
+    pub description: String,
+}
+
+/// What the evaluation needs to know about the instrument
+#[derive(Debug, Clone)]
+pub struct Instrument<'a> {
+    pub asset_id: &'a str,
+    /// Asset type as compliance requirements name it, e.g. `treasuries`
+    pub asset_type: &'a str,
+    pub distribution: &'a DistributionRules,
+    /// Longest cooling period the investor's jurisdiction imposes on the asset type
+    pub cooling_period_days: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EligibilityReport {
+    pub asset_id: String,
+    pub investor_id: String,
+    /// True only when every criterion passes and nothing is left to onboard
+    pub eligible: bool,
+    pub kyc_tier: Option<KycTier>,
+    pub criteria: Vec<CriterionResult>,
+    pub onboarding: Vec<OnboardingStep>,
+    pub locale: String,
+    pub evaluated_at: DateTime<Utc>,
+}
+
+impl EligibilityReport {
+    /// Render every message in a locale
+    pub fn localized(mut self, catalog: &MessageCatalog, locale: &str) -> Self {
+        for result in &mut self.criteria {
+            result.message = catalog.render(&result.message_ref, locale);
+            result.remediation = result.remediation_ref.as_ref().map(|step| catalog.render(step, locale));
+        }
+        for step in &mut self.onboarding {
+            step.description = catalog.render(&step.task.message(), locale);
+        }
+        self.locale = locale.to_string();
+        self
+    }
+}
+
+fn onboarding_tasks(profile: Option<&InvestorProfile>) -> Vec<OnboardingTask> {
+    let Some(profile) = profile else {
+        return vec![OnboardingTask::CreateProfile, OnboardingTask::CompleteKyc, OnboardingTask::CompleteAml];
+    };
+    let mut tasks = Vec::new();
+    if profile.kyc_status != KYCStatus::Completed {
+        tasks.push(OnboardingTask::CompleteKyc);
+    }
+    if profile.aml_status != AMLStatus::Clear {
+        tasks.push(OnboardingTask::CompleteAml);
+    }
+    if profile.tax_residency.is_empty() {
+        tasks.push(OnboardingTask::ProvideTaxResidency);
+    }
+    tasks
+}
+
+fn criteria(profile: &InvestorProfile, instrument: &Instrument, now: DateTime<Utc>) -> Vec<CriterionResult> {
+    let rules = instrument.distribution;
+    let mut results = Vec::new();
+
+    let passed = rules.allowed_jurisdictions.is_empty()
+        || rules.allowed_jurisdictions.iter().any(|jurisdiction| *jurisdiction == profile.jurisdiction);
+    results.push(CriterionResult::new(
+        Criterion::Jurisdiction,
+        passed,
+        MessageRef::new(if passed { "eligibility.jurisdiction.permitted" } else { "eligibility.jurisdiction.not_permitted" })
+            .param("jurisdiction", &profile.jurisdiction),
+        MessageRef::new("eligibility.jurisdiction.remediation").param("allowed", rules.allowed_jurisdictions.join(", ")),
+    ));
+
+    let private_placement_blocked = rules.private_placement && profile.investor_type == InvestorType::Retail;
+    let passed = !private_placement_blocked
+        && (rules.allowed_investor_types.is_empty() || rules.allowed_investor_types.contains(&profile.investor_type));
+    let allowed = if rules.allowed_investor_types.is_empty() {
+        "Professional, QualifiedInvestor, AccreditedInvestor, Institutional, EligibleCounterparty".to_string()
+    } else {
+        rules.allowed_investor_types.iter().map(|investor_type| format!("{:?}", investor_type)).collect::<Vec<_>>().join(", ")
+    };
+    results.push(CriterionResult::new(
+        Criterion::InvestorType,
+        passed,
+        MessageRef::new(if passed { "eligibility.investor_type.permitted" } else { "eligibility.investor_type.not_permitted" })
+            .param("investor_type", format!("{:?}", profile.investor_type)),
+        MessageRef::new("eligibility.investor_type.remediation").param("allowed", allowed),
+    ));
+
+    let tier = KycTier::for_profile(profile);
+    let minimum = rules.minimum_investment.unwrap_or(Decimal::ZERO);
+    let passed = tier != KycTier::Unverified && tier.cap().map_or(true, |cap| cap >= minimum);
+    let message = match tier.cap() {
+        _ if tier == KycTier::Unverified => MessageRef::new("eligibility.kyc_tier.unverified"),
+        None => MessageRef::new("eligibility.kyc_tier.uncapped"),
+        Some(cap) if passed => MessageRef::new("eligibility.kyc_tier.within_cap").param("cap", cap),
+        Some(cap) => MessageRef::new("eligibility.kyc_tier.below_minimum").param("cap", cap),
+    };
+    results.push(CriterionResult::new(
+        Criterion::KycTier,
+        passed,
+        message.param("tier", format!("{:?}", tier)).param("minimum", minimum),
+        if tier == KycTier::Unverified {
+            MessageRef::new("kyc.remediation")
+        } else {
+            MessageRef::new("eligibility.kyc_tier.remediation").param("minimum", minimum)
+        },
+    ));
+
+    let last_investment = profile.cooling_periods.get(instrument.asset_type);
+    let (passed, message, remediation) = match (instrument.cooling_period_days, last_investment) {
+        (None, _) => (true, MessageRef::new("cooling_period.not_required"), MessageRef::default()),
+        (Some(_), None) => (true, MessageRef::new("cooling_period.first_investment"), MessageRef::default()),
+        (Some(days), Some(last)) => {
+            let cooling_period = Duration::days(days as i64);
+            let elapsed = now.signed_duration_since(*last);
+            // Round the wait up so a partial day is not reported as zero days remaining
+            let remaining_days = ((cooling_period - elapsed).num_hours() + 23).div_euclid(24);
+            (
+                elapsed >= cooling_period,
+                MessageRef::new("cooling_period.elapsed").param("days", elapsed.num_days()),
+                MessageRef::new("cooling_period.remediation").param("days_remaining", remaining_days),
+            )
+        }
+    };
+    results.push(CriterionResult::new(Criterion::CoolingPeriod, passed, message, remediation));
+
+    let passed = matches!(profile.sanctions_status, SanctionsStatus::Clear);
+    results.push(CriterionResult::new(
+        Criterion::Sanctions,
+        passed,
+        MessageRef::new("sanctions.status").param("status", format!("{:?}", profile.sanctions_status)),
+        MessageRef::new("sanctions.remediation"),
+    ));
+
+    results
+}
+
+/// Evaluate an investor against an instrument; messages are rendered by `localized`
+pub fn evaluate(
+    investor_id: &str,
+    profile: Option<&InvestorProfile>,
+    instrument: &Instrument,
+    now: DateTime<Utc>,
+) -> EligibilityReport {
+    let criteria = profile.map(|profile| criteria(profile, instrument, now)).unwrap_or_default();
+    let onboarding: Vec<OnboardingStep> = onboarding_tasks(profile).into_iter()
+        .map(|task| OnboardingStep { task, description: String::new() })
+        .collect();
+    EligibilityReport {
+        asset_id: instrument.asset_id.to_string(),
+        investor_id: investor_id.to_string(),
+        eligible: profile.is_some() && onboarding.is_empty() && criteria.iter().all(|result| result.passed),
+        kyc_tier: profile.map(KycTier::for_profile),
+        criteria,
+        onboarding,
+        locale: DEFAULT_LOCALE.to_string(),
+        evaluated_at: now,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compliance::enhanced_compliance_engine::{AccessLevel, RiskRating};
+    use crate::tenant::TenantId;
+    use std::collections::HashMap;
+
+    fn retail_profile() -> InvestorProfile {
+        InvestorProfile {
+            investor_id: "0xinvestor".to_string(),
+            tenant_id: TenantId::default(),
+            jurisdiction: "US".to_string(),
+            tax_residency: vec!["US".to_string()],
+            investor_type: InvestorType::Retail,
+            kyc_status: KYCStatus::Completed,
+            aml_status: AMLStatus::Clear,
+            accreditation_status: AccreditationStatus::NotApplicable,
+            investment_limits: HashMap::new(),
+            last_updated: Utc::now(),
+            compliance_score: 90,
+            risk_rating: RiskRating::Low,
+            sanctions_status: SanctionsStatus::Clear,
+            cooling_periods: HashMap::new(),
+            appropriateness: None,
+            data_hash: String::new(),
+            access_level: AccessLevel::Standard,
+            created_by: "officer".to_string(),
+            last_accessed: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_tier_follows_verification_and_investor_type() {
+        let mut profile = retail_profile();
+        assert_eq!(KycTier::for_profile(&profile), KycTier::Basic);
+        profile.accreditation_status = AccreditationStatus::Verified;
+        assert_eq!(KycTier::for_profile(&profile), KycTier::Enhanced);
+        profile.investor_type = InvestorType::Institutional;
+        assert_eq!(KycTier::for_profile(&profile).cap(), None);
+        profile.kyc_status = KYCStatus::InProgress;
+        assert_eq!(KycTier::for_profile(&profile), KycTier::Unverified);
+    }
+
+    #[test]
+    fn test_missing_profile_lists_onboarding_tasks() {
+        let rules = DistributionRules::default();
+        let instrument = Instrument { asset_id: "t-1", asset_type: "treasuries", distribution: &rules, cooling_period_days: None };
+        let report = evaluate("0xnew", None, &instrument, Utc::now()).localized(&MessageCatalog::builtin(), "en");
+
+        assert!(!report.eligible);
+        assert!(report.criteria.is_empty());
+        let tasks: Vec<OnboardingTask> = report.onboarding.iter().map(|step| step.task).collect();
+        assert_eq!(tasks, vec![OnboardingTask::CreateProfile, OnboardingTask::CompleteKyc, OnboardingTask::CompleteAml]);
+        assert_eq!(report.onboarding[1].description, "Complete KYC verification process");
+    }
+}
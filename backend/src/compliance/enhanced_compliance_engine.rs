@@ -1,11 +1,27 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
 use tokio;
 use anyhow::{Result, anyhow};
 use chrono::{DateTime, Utc, Duration};
 use sha2::{Sha256, Digest};
 use uuid::Uuid;
 use tracing::{info, warn, error};
+use quantera_types::Pii;
+
+use crate::audit_sink::{AuditEvent, AuditStream};
+use crate::services::multi_chain_asset_service::DistributionRules;
+use crate::tenant::{TenantId, TenantScope};
+use super::jurisdiction_risk::{JurisdictionRiskTable, JurisdictionRiskTier};
+use super::appropriateness::{self, AppropriatenessProfile, QuestionSet, QuestionnaireAnswers};
+use super::check_cache::{AmountBucket, CheckCache, CheckCacheKey, CheckCacheStats};
+use super::concentration::{self, HoldingsSource, InvestmentTarget, LimitReport, LimitScope};
+use super::eligibility::{self, EligibilityReport, Instrument};
+use super::messages::{MessageCatalog, MessageRef, DEFAULT_LOCALE};
+use super::thresholds::{AppliedRate, ConversionRates, FiatCurrency, MonetaryThreshold, ThresholdConfig, ThresholdOutcome, Valuation};
+
+/// Amounts above this need institutional or accredited investor status
+const HIGH_VALUE_THRESHOLD: u128 = 1_000_000_000_000_000_000_000; // 1000 ETH equivalent
 
 /// Security-enhanced compliance engine with comprehensive access control
 /// and data protection measures for institutional-grade compliance management
@@ -29,11 +45,22 @@ pub struct ComplianceRequirement {
     pub is_mandatory: bool,
     pub verification_method: VerificationMethod,
     pub applicable_asset_types: Vec<String>,
-    pub minimum_investment_threshold: Option<u128>,
-    pub maximum_investment_threshold: Option<u128>,
+    /// Statutory amount from which the requirement applies, in the framework's currency
+    pub minimum_investment_threshold: Option<MonetaryThreshold>,
+    /// Statutory amount up to which the requirement applies
+    pub maximum_investment_threshold: Option<MonetaryThreshold>,
     pub cooling_period_days: Option<u32>,
 }
 
+impl ComplianceRequirement {
+    fn thresholds(&self) -> Vec<&MonetaryThreshold> {
+        [&self.minimum_investment_threshold, &self.maximum_investment_threshold]
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum VerificationMethod {
     KYC,
@@ -53,6 +80,8 @@ pub enum VerificationMethod {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InvestorProfile {
     pub investor_id: String,
+    #[serde(default)]
+    pub tenant_id: TenantId,
     pub jurisdiction: String,
     pub tax_residency: Vec<String>,
     pub investor_type: InvestorType,
@@ -65,6 +94,9 @@ pub struct InvestorProfile {
     pub risk_rating: RiskRating,
     pub sanctions_status: SanctionsStatus,
     pub cooling_periods: HashMap<String, DateTime<Utc>>, // Asset type -> last investment date
+    /// Result of the latest appropriateness questionnaire
+    #[serde(default)]
+    pub appropriateness: Option<AppropriatenessProfile>,
     // Security fields
     pub data_hash: String, // For integrity verification
     pub access_level: AccessLevel,
@@ -72,7 +104,7 @@ pub struct InvestorProfile {
     pub last_accessed: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum InvestorType {
     Retail,
     Professional,
@@ -82,24 +114,8 @@ pub enum InvestorType {
     EligibleCounterparty,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum KYCStatus {
-    NotStarted,
-    InProgress,
-    Completed,
-    Expired,
-    Rejected,
-    UnderReview,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum AMLStatus {
-    Clear,
-    UnderReview,
-    Flagged,
-    Blocked,
-    RequiresEnhancedDueDiligence,
-}
+/// KYC and AML states are shared with the compliance service so profiles read the same in both
+pub use quantera_types::{KycStatus as KYCStatus, AmlStatus as AMLStatus};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AccreditationStatus {
@@ -138,9 +154,19 @@ pub enum AccessLevel {
 pub struct InvestmentLimit {
     pub asset_type: String,
     pub maximum_amount: u128,
+    /// Counted by the engine; only used for asset type limits
     pub current_exposure: u128,
     pub reset_period: Duration,
     pub last_reset: DateTime<Utc>,
+    /// What the limit caps; a limit on `asset_type` when unset
+    #[serde(default)]
+    pub scope: Option<LimitScope>,
+}
+
+impl InvestmentLimit {
+    pub fn scope(&self) -> LimitScope {
+        self.scope.clone().unwrap_or_else(|| LimitScope::AssetType(self.asset_type.clone()))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -148,11 +174,20 @@ pub struct ComplianceCheck {
     pub requirement_id: String,
     pub framework: RegulatoryFramework,
     pub passed: bool,
+    /// Rendered from `message_ref`, in the locale of the enclosing result
     pub message: String,
     pub severity: ComplianceSeverity,
     pub remediation_steps: Vec<String>,
     pub check_timestamp: DateTime<Utc>,
     pub check_id: String,
+    /// Canonical message id and parameters the text is rendered from
+    #[serde(default)]
+    pub message_ref: MessageRef,
+    #[serde(default)]
+    pub remediation_refs: Vec<MessageRef>,
+    /// Price and FX rates the investment was valued with against fiat thresholds
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rates: Vec<AppliedRate>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -163,7 +198,7 @@ pub enum ComplianceSeverity {
     Critical,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ComplianceResult {
     pub is_compliant: bool,
     pub overall_score: u8,
@@ -172,11 +207,57 @@ pub struct ComplianceResult {
     pub required_actions: Vec<String>,
     pub estimated_completion_time: Option<Duration>,
     pub audit_trail_id: String,
+    /// Locale the messages are rendered in
+    pub locale: String,
+    pub recommendation_refs: Vec<MessageRef>,
+}
+
+impl ComplianceResult {
+    /// Re-render every message in another locale from the stored message ids
+    pub fn localized(mut self, catalog: &MessageCatalog, locale: &str) -> Self {
+        for check in &mut self.checks {
+            check.message = catalog.render(&check.message_ref, locale);
+            check.remediation_steps = check.remediation_refs.iter()
+                .map(|step| catalog.render(step, locale))
+                .collect();
+        }
+        self.recommendations = self.recommendation_refs.iter()
+            .map(|recommendation| catalog.render(recommendation, locale))
+            .collect();
+        self.required_actions = required_actions(&self.checks);
+        self.locale = locale.to_string();
+        self
+    }
+}
+
+/// Remediation steps of failed checks that block compliance
+fn required_actions(checks: &[ComplianceCheck]) -> Vec<String> {
+    checks.iter()
+        .filter(|check| !check.passed && matches!(check.severity, ComplianceSeverity::Critical | ComplianceSeverity::Error))
+        .flat_map(|check| check.remediation_steps.iter())
+        .cloned()
+        .collect()
+}
+
+/// Points a failed check of this severity takes off the overall score
+fn score_penalty(severity: &ComplianceSeverity) -> u8 {
+    match severity {
+        ComplianceSeverity::Critical => 30,
+        ComplianceSeverity::Error => 20,
+        ComplianceSeverity::Warning => 10,
+        ComplianceSeverity::Info => 5,
+    }
+}
+
+/// Remediation that applies only while the check fails
+fn unless_passed(passed: bool, step: MessageRef) -> Vec<MessageRef> {
+    if passed { Vec::new() } else { vec![step] }
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct AuditLogEntry {
     pub entry_id: String,
+    pub tenant_id: TenantId,
     pub timestamp: DateTime<Utc>,
     pub action: String,
     pub investor_id: String,
@@ -186,6 +267,13 @@ pub struct AuditLogEntry {
     pub risk_level: RiskRating,
 }
 
+/// Compliance checks run and failed over a period
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ComplianceCheckCounts {
+    pub checks: usize,
+    pub violations: usize,
+}
+
 #[derive(Debug)]
 pub enum ComplianceError {
     InvestorNotFound,
@@ -221,13 +309,23 @@ impl std::error::Error for ComplianceError {}
 
 pub struct EnhancedComplianceEngine {
     frameworks: HashMap<String, Vec<ComplianceRequirement>>,
-    investor_profiles: HashMap<String, InvestorProfile>,
+    investor_profiles: HashMap<(TenantId, String), InvestorProfile>,
     jurisdiction_mappings: HashMap<String, Vec<RegulatoryFramework>>,
     asset_type_requirements: HashMap<String, Vec<String>>, // Asset type -> requirement IDs
     sanctions_lists: HashMap<String, Vec<String>>, // Jurisdiction -> sanctioned entities
     audit_log: Vec<AuditLogEntry>,
     encryption_key: String, // In production, this would be properly managed
     access_control: HashMap<String, AccessLevel>, // User ID -> Access Level
+    jurisdiction_risk: JurisdictionRiskTable,
+    question_sets: HashMap<String, Vec<QuestionSet>>, // Jurisdiction -> versions, oldest first
+    profile_versions: HashMap<(TenantId, String), u64>, // Bumped on every profile mutation
+    rules_versions: HashMap<String, u64>, // Jurisdiction -> bumped on every requirement change
+    check_cache: CheckCache,
+    audit_stream: Option<Arc<AuditStream>>,
+    messages: Arc<MessageCatalog>,
+    conversion_rates: Option<Arc<dyn ConversionRates>>,
+    threshold_config: ThresholdConfig,
+    holdings: Option<Arc<dyn HoldingsSource>>,
 }
 
 impl EnhancedComplianceEngine {
@@ -241,16 +339,80 @@ impl EnhancedComplianceEngine {
             audit_log: Vec::new(),
             encryption_key: "secure_key_placeholder".to_string(), // Would be from secure key management
             access_control: HashMap::new(),
+            jurisdiction_risk: JurisdictionRiskTable::builtin(),
+            question_sets: HashMap::new(),
+            profile_versions: HashMap::new(),
+            rules_versions: HashMap::new(),
+            check_cache: CheckCache::default(),
+            audit_stream: None,
+            messages: Arc::new(MessageCatalog::builtin()),
+            conversion_rates: None,
+            threshold_config: ThresholdConfig::default(),
+            holdings: None,
         };
         
         engine.initialize_frameworks();
         engine.initialize_jurisdiction_mappings();
         engine.initialize_asset_type_requirements();
         engine.initialize_sanctions_lists();
+        engine.initialize_question_sets();
         
         engine
     }
 
+    /// Also stream the audit trail to the configured SIEM sinks
+    pub fn with_audit_stream(mut self, stream: Arc<AuditStream>) -> Self {
+        self.audit_stream = Some(stream);
+        self
+    }
+
+    /// Value investments against fiat thresholds with these price and FX rates. Without
+    /// them, requirements with thresholds can only be checked as warnings.
+    pub fn with_conversion_rates(mut self, rates: Arc<dyn ConversionRates>, config: ThresholdConfig) -> Self {
+        self.conversion_rates = Some(rates);
+        self.threshold_config = config;
+        self
+    }
+
+    /// Count issuer, asset class and portfolio limits against holdings from this source.
+    /// Without it, those limits are reported as unevaluated warnings.
+    pub fn with_holdings(mut self, holdings: Arc<dyn HoldingsSource>) -> Self {
+        self.holdings = Some(holdings);
+        self
+    }
+
+    /// Message templates check results are rendered with
+    pub fn messages(&self) -> &Arc<MessageCatalog> {
+        &self.messages
+    }
+
+    /// A check with its messages rendered in the default locale
+    fn check(
+        &self,
+        requirement_id: &str,
+        framework: RegulatoryFramework,
+        passed: bool,
+        severity: ComplianceSeverity,
+        message_ref: MessageRef,
+        remediation_refs: Vec<MessageRef>,
+    ) -> ComplianceCheck {
+        ComplianceCheck {
+            requirement_id: requirement_id.to_string(),
+            framework,
+            passed,
+            message: self.messages.render(&message_ref, DEFAULT_LOCALE),
+            severity,
+            remediation_steps: remediation_refs.iter()
+                .map(|step| self.messages.render(step, DEFAULT_LOCALE))
+                .collect(),
+            check_timestamp: Utc::now(),
+            check_id: Uuid::new_v4().to_string(),
+            message_ref,
+            remediation_refs,
+            rates: Vec::new(),
+        }
+    }
+
     /// Validate input parameters for security
     fn validate_inputs(
         &self,
@@ -320,16 +482,91 @@ impl EnhancedComplianceEngine {
         let expected_hash = self.generate_data_hash(&profile_data);
         
         if profile.data_hash != expected_hash {
-            error!("Data integrity check failed for investor: {}", profile.investor_id);
+            error!(investor_id = %Pii::new(&profile.investor_id), "Data integrity check failed");
             return Err(ComplianceError::DataIntegrityError);
         }
 
         Ok(())
     }
 
+    /// Profiles whose data no longer matches their integrity hash
+    pub fn integrity_failures(&self) -> Vec<(TenantId, String)> {
+        self.investor_profiles.iter()
+            .filter(|(_, profile)| self.verify_data_integrity(profile).is_err())
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    /// Find a profile visible in the scope; profiles of other tenants are treated as missing
+    fn find_profile(&self, scope: &TenantScope, investor_id: &str) -> Option<&InvestorProfile> {
+        match scope {
+            TenantScope::Tenant(tenant) => self.investor_profiles.get(&(tenant.clone(), investor_id.to_string())),
+            TenantScope::AllTenants => self.investor_profiles.values()
+                .find(|profile| profile.investor_id == investor_id),
+        }
+    }
+
+    fn bump_profile_version(&mut self, key: &(TenantId, String)) {
+        *self.profile_versions.entry(key.clone()).or_default() += 1;
+    }
+
+    /// Invalidate cached checks for one jurisdiction, or for all of them
+    fn bump_rules_version(&mut self, jurisdiction: Option<&str>) {
+        match jurisdiction {
+            Some(jurisdiction) => *self.rules_versions.entry(jurisdiction.to_string()).or_default() += 1,
+            None => {
+                let jurisdictions: Vec<String> = self.jurisdiction_mappings.keys()
+                    .chain(self.frameworks.keys())
+                    .chain(self.question_sets.keys())
+                    .cloned()
+                    .collect();
+                for jurisdiction in jurisdictions {
+                    *self.rules_versions.entry(jurisdiction).or_default() += 1;
+                }
+            }
+        }
+    }
+
+    /// Amounts the requirements of a jurisdiction compare against at the valuation's rates;
+    /// a cache bucket never spans one
+    fn amount_thresholds(&self, jurisdiction: &str, valuation: &Valuation) -> Vec<u128> {
+        self.frameworks.get(jurisdiction).into_iter()
+            .flatten()
+            .flat_map(ComplianceRequirement::thresholds)
+            .filter_map(|threshold| valuation.settlement_amount(threshold))
+            .chain(std::iter::once(HIGH_VALUE_THRESHOLD))
+            .collect()
+    }
+
+    /// Whether a requirement covers an asset type
+    fn covers_asset_type(&self, requirement: &ComplianceRequirement, asset_type: &str) -> bool {
+        requirement.applicable_asset_types.iter().any(|applicable| applicable == "*" || applicable == asset_type)
+            || self.asset_type_requirements.get(asset_type)
+                .map_or(false, |ids| ids.contains(&requirement.requirement_id))
+    }
+
+    /// Value an investment in every currency the thresholds of the jurisdiction's
+    /// requirements for the asset type are set in
+    async fn value_investment(&self, jurisdiction: &str, asset_type: &str, investment_amount: u128) -> Valuation {
+        let currencies: BTreeSet<FiatCurrency> = self.frameworks.get(jurisdiction).into_iter()
+            .flatten()
+            .filter(|requirement| self.covers_asset_type(requirement, asset_type))
+            .flat_map(ComplianceRequirement::thresholds)
+            .map(|threshold| threshold.currency)
+            .collect();
+        Valuation::at_current_rates(
+            self.conversion_rates.as_deref(),
+            &self.threshold_config,
+            investment_amount,
+            currencies,
+            Utc::now(),
+        ).await
+    }
+
     /// Log audit entry
     fn log_audit_entry(
         &mut self,
+        tenant_id: TenantId,
         action: String,
         investor_id: String,
         performed_by: String,
@@ -341,6 +578,7 @@ impl EnhancedComplianceEngine {
         
         let entry = AuditLogEntry {
             entry_id: entry_id.clone(),
+            tenant_id,
             timestamp: Utc::now(),
             action,
             investor_id,
@@ -350,6 +588,9 @@ impl EnhancedComplianceEngine {
             risk_level,
         };
 
+        if let Some(stream) = &self.audit_stream {
+            stream.publish(AuditEvent::from(&entry));
+        }
         self.audit_log.push(entry);
         
         // In production, this would be written to a secure audit database
@@ -360,12 +601,30 @@ impl EnhancedComplianceEngine {
 
     pub async fn comprehensive_compliance_check(
         &mut self,
+        scope: &TenantScope,
         investor_id: &str,
         asset_type: &str,
         investment_amount: u128,
         jurisdiction: &str,
         performed_by: &str,
     ) -> Result<ComplianceResult, ComplianceError> {
+        let target = InvestmentTarget::new(asset_type);
+        self.comprehensive_compliance_check_for(scope, investor_id, &target, investment_amount, jurisdiction, performed_by).await
+    }
+
+    /// Compliance check of an investment in `target`, whose issuer and asset class select
+    /// the concentration limits that apply
+    pub async fn comprehensive_compliance_check_for(
+        &mut self,
+        scope: &TenantScope,
+        investor_id: &str,
+        target: &InvestmentTarget,
+        investment_amount: u128,
+        jurisdiction: &str,
+        performed_by: &str,
+    ) -> Result<ComplianceResult, ComplianceError> {
+        let asset_type = target.asset_type.as_str();
+
         // Check access permissions
         self.check_access(performed_by, AccessLevel::Standard)?;
 
@@ -373,23 +632,52 @@ impl EnhancedComplianceEngine {
         self.validate_inputs(investor_id, asset_type, investment_amount, jurisdiction)?;
 
         // Get investor profile
-        let profile = self.investor_profiles.get(investor_id)
+        let profile = self.find_profile(scope, investor_id)
             .ok_or(ComplianceError::InvestorNotFound)?;
 
         // Verify data integrity
         self.verify_data_integrity(profile)?;
 
+        // Serve a recent result computed from the same profile and rule versions
+        let profile_key = (profile.tenant_id.clone(), profile.investor_id.clone());
+        let valuation = self.value_investment(jurisdiction, asset_type, investment_amount).await;
+        let cache_key = CheckCacheKey {
+            tenant_id: profile_key.0.clone(),
+            investor_id: profile_key.1.clone(),
+            asset_type: asset_type.to_string(),
+            jurisdiction: jurisdiction.to_string(),
+            amount_bucket: AmountBucket::new(investment_amount, &self.amount_thresholds(jurisdiction, &valuation)),
+            profile_version: self.profile_versions.get(&profile_key).copied().unwrap_or(0),
+            rules_version: self.rules_versions.get(jurisdiction).copied().unwrap_or(0),
+        };
+        // A result degraded by missing rates is recomputed once rates are back, and holdings
+        // change without a profile update, so results counting them are never cached
+        let cacheable = valuation.is_complete() && !profile.investment_limits.values()
+            .map(InvestmentLimit::scope)
+            .any(|limit| limit.uses_holdings() && limit.covers(target));
+        if cacheable {
+            if let Some(cached) = self.check_cache.get(&cache_key, investment_amount, Utc::now()).cloned() {
+                return self.audit_cached_check(cache_key, cached, investment_amount, performed_by);
+            }
+        }
+        let profile = self.investor_profiles.get(&profile_key)
+            .ok_or(ComplianceError::InvestorNotFound)?;
+
         // Get applicable frameworks for jurisdiction
         let frameworks = self.jurisdiction_mappings.get(jurisdiction)
             .ok_or(ComplianceError::JurisdictionNotSupported)?;
 
-        // Get applicable requirements for asset type
-        let empty_vec = vec![];
-        let asset_requirements = self.asset_type_requirements.get(asset_type)
-            .unwrap_or(&empty_vec);
+        // Every asset type, issuer, asset class and portfolio limit covering the investment
+        let limits = concentration::evaluate_limits(
+            &profile.investment_limits,
+            target,
+            &profile.investor_id,
+            self.holdings.as_deref(),
+        ).await;
 
         let mut compliance_checks = Vec::new();
         let mut overall_score = 100u8;
+        let mut limits_checked = false;
 
         // Perform framework-specific checks
         for framework in frameworks {
@@ -397,25 +685,20 @@ impl EnhancedComplianceEngine {
                 .ok_or(ComplianceError::FrameworkNotSupported)?;
 
             for requirement in framework_requirements {
-                if requirement.framework == *framework &&
-                   (requirement.applicable_asset_types.contains(&"*".to_string()) ||
-                    requirement.applicable_asset_types.contains(&asset_type.to_string()) ||
-                    asset_requirements.contains(&requirement.requirement_id)) {
-                    
-                    let check_result = self.perform_compliance_check(
+                if requirement.framework == *framework && self.covers_asset_type(requirement, asset_type) {
+                    limits_checked |= matches!(requirement.verification_method, VerificationMethod::InvestmentLimitCheck);
+
+                    let check_result = self.threshold_check(
                         profile,
                         requirement,
                         asset_type,
                         investment_amount,
+                        &valuation,
+                        &limits,
                     ).await?;
                     
                     if !check_result.passed {
-                        match check_result.severity {
-                            ComplianceSeverity::Critical => overall_score = overall_score.saturating_sub(30),
-                            ComplianceSeverity::Error => overall_score = overall_score.saturating_sub(20),
-                            ComplianceSeverity::Warning => overall_score = overall_score.saturating_sub(10),
-                            ComplianceSeverity::Info => overall_score = overall_score.saturating_sub(5),
-                        }
+                        overall_score = overall_score.saturating_sub(score_penalty(&check_result.severity));
                     }
                     
                     compliance_checks.push(check_result);
@@ -423,6 +706,16 @@ impl EnhancedComplianceEngine {
             }
         }
 
+        // Limits are checked even where no framework requires it, once any are configured
+        if !limits.is_empty() && !limits_checked {
+            let framework = frameworks.first().cloned().unwrap_or(RegulatoryFramework::MiCA);
+            let check_result = self.limit_check("INVESTMENT_LIMITS", framework, &limits, investment_amount);
+            if !check_result.passed {
+                overall_score = overall_score.saturating_sub(score_penalty(&check_result.severity));
+            }
+            compliance_checks.push(check_result);
+        }
+
         // Perform additional risk-based checks
         self.perform_risk_based_checks(profile, asset_type, investment_amount, &mut compliance_checks).await?;
 
@@ -431,8 +724,11 @@ impl EnhancedComplianceEngine {
             check.passed || !matches!(check.severity, ComplianceSeverity::Critical | ComplianceSeverity::Error)
         );
 
-        let recommendations = self.generate_recommendations(&compliance_checks);
-        let required_actions = self.generate_required_actions(&compliance_checks);
+        let recommendation_refs = self.generate_recommendations(&compliance_checks);
+        let recommendations = recommendation_refs.iter()
+            .map(|recommendation| self.messages.render(recommendation, DEFAULT_LOCALE))
+            .collect();
+        let required_actions = required_actions(&compliance_checks);
         let estimated_completion_time = self.estimate_completion_time(&compliance_checks);
 
         // Create audit log entry
@@ -442,16 +738,18 @@ impl EnhancedComplianceEngine {
         audit_details.insert("jurisdiction".to_string(), jurisdiction.to_string());
         audit_details.insert("overall_score".to_string(), overall_score.to_string());
 
+        let tenant_id = profile.tenant_id.clone();
         let audit_trail_id = self.log_audit_entry(
+            tenant_id,
             "comprehensive_compliance_check".to_string(),
             investor_id.to_string(),
             performed_by.to_string(),
             audit_details,
             Some(is_compliant),
-            profile.risk_rating.clone(),
+            self.effective_risk_rating(profile),
         )?;
 
-        Ok(ComplianceResult {
+        let result = ComplianceResult {
             is_compliant,
             overall_score,
             checks: compliance_checks,
@@ -459,7 +757,100 @@ impl EnhancedComplianceEngine {
             required_actions,
             estimated_completion_time,
             audit_trail_id,
-        })
+            locale: DEFAULT_LOCALE.to_string(),
+            recommendation_refs,
+        };
+        if cacheable {
+            self.check_cache.insert(cache_key, investment_amount, result.clone(), Utc::now());
+        }
+
+        Ok(result)
+    }
+
+    /// Check a requirement if the investment's fiat value falls within its thresholds.
+    ///
+    /// When the value cannot be established, a failing check is degraded to a warning
+    /// instead of being skipped or blocking the investment.
+    async fn threshold_check(
+        &self,
+        profile: &InvestorProfile,
+        requirement: &ComplianceRequirement,
+        asset_type: &str,
+        investment_amount: u128,
+        valuation: &Valuation,
+        limits: &LimitReport,
+    ) -> Result<ComplianceCheck, ComplianceError> {
+        let outcome = valuation.applicability(
+            requirement.minimum_investment_threshold.as_ref(),
+            requirement.maximum_investment_threshold.as_ref(),
+        );
+
+        let mut check = match outcome {
+            ThresholdOutcome::Applies => {
+                self.perform_compliance_check(profile, requirement, asset_type, investment_amount, limits).await?
+            }
+            ThresholdOutcome::NotApplicable => self.check(
+                &requirement.requirement_id,
+                requirement.framework.clone(),
+                true,
+                ComplianceSeverity::Info,
+                MessageRef::new("threshold.not_applicable"),
+                vec![],
+            ),
+            ThresholdOutcome::RatesUnavailable { currency, reason } => {
+                let check = self.perform_compliance_check(profile, requirement, asset_type, investment_amount, limits).await?;
+                if check.passed {
+                    check
+                } else {
+                    let mut remediation = vec![MessageRef::new("threshold.rates_remediation")];
+                    remediation.extend(check.remediation_refs);
+                    warn!("Requirement {} checked without {} rates: {}", requirement.requirement_id, currency, reason);
+                    self.check(
+                        &requirement.requirement_id,
+                        requirement.framework.clone(),
+                        false,
+                        ComplianceSeverity::Warning,
+                        MessageRef::new("threshold.rates_unavailable")
+                            .param("currency", currency)
+                            .param("reason", reason),
+                        remediation,
+                    )
+                }
+            }
+        };
+        check.rates = valuation.rates_used(&requirement.thresholds());
+        Ok(check)
+    }
+
+    /// Serve a cached result; the check is still audited, under its own audit trail id
+    fn audit_cached_check(
+        &mut self,
+        key: CheckCacheKey,
+        mut result: ComplianceResult,
+        investment_amount: u128,
+        performed_by: &str,
+    ) -> Result<ComplianceResult, ComplianceError> {
+        let risk_level = self.investor_profiles.get(&(key.tenant_id.clone(), key.investor_id.clone()))
+            .map_or(RiskRating::Low, |profile| self.effective_risk_rating(profile));
+
+        let mut audit_details = HashMap::new();
+        audit_details.insert("asset_type".to_string(), key.asset_type);
+        audit_details.insert("investment_amount".to_string(), investment_amount.to_string());
+        audit_details.insert("jurisdiction".to_string(), key.jurisdiction);
+        audit_details.insert("overall_score".to_string(), result.overall_score.to_string());
+        audit_details.insert("cached".to_string(), "true".to_string());
+
+        result.audit_trail_id = self.log_audit_entry(
+            key.tenant_id,
+            "comprehensive_compliance_check".to_string(),
+            key.investor_id,
+            performed_by.to_string(),
+            audit_details,
+            Some(result.is_compliant),
+            risk_level,
+        )?;
+
+        Ok(result)
     }
 
     async fn perform_compliance_check(
@@ -468,9 +859,12 @@ impl EnhancedComplianceEngine {
         requirement: &ComplianceRequirement,
         asset_type: &str,
         investment_amount: u128,
+        limits: &LimitReport,
     ) -> Result<ComplianceCheck, ComplianceError> {
-        let check_id = Uuid::new_v4().to_string();
         let check_timestamp = Utc::now();
+        let check = |passed: bool, severity: ComplianceSeverity, message: MessageRef, remediation: Vec<MessageRef>| {
+            self.check(&requirement.requirement_id, requirement.framework.clone(), passed, severity, message, remediation)
+        };
 
         match requirement.verification_method {
             VerificationMethod::KYC => {
@@ -483,20 +877,12 @@ impl EnhancedComplianceEngine {
                     ComplianceSeverity::Info
                 };
 
-                Ok(ComplianceCheck {
-                    requirement_id: requirement.requirement_id.clone(),
-                    framework: requirement.framework.clone(),
+                Ok(check(
                     passed,
-                    message: format!("KYC verification status: {:?}", profile.kyc_status),
                     severity,
-                    remediation_steps: if !passed {
-                        vec!["Complete KYC verification process".to_string()]
-                    } else {
-                        vec![]
-                    },
-                    check_timestamp,
-                    check_id,
-                })
+                    MessageRef::new("kyc.status").param("status", format!("{:?}", profile.kyc_status)),
+                    unless_passed(passed, MessageRef::new("kyc.remediation")),
+                ))
             },
 
             VerificationMethod::AML => {
@@ -509,74 +895,28 @@ impl EnhancedComplianceEngine {
                     ComplianceSeverity::Info
                 };
 
-                Ok(ComplianceCheck {
-                    requirement_id: requirement.requirement_id.clone(),
-                    framework: requirement.framework.clone(),
+                Ok(check(
                     passed,
-                    message: format!("AML screening status: {:?}", profile.aml_status),
                     severity,
-                    remediation_steps: if !passed {
-                        vec!["Complete AML screening process".to_string()]
-                    } else {
-                        vec![]
-                    },
-                    check_timestamp,
-                    check_id,
-                })
+                    MessageRef::new("aml.status").param("status", format!("{:?}", profile.aml_status)),
+                    unless_passed(passed, MessageRef::new("aml.remediation")),
+                ))
             },
 
             VerificationMethod::AccreditedInvestorCheck => {
                 let passed = matches!(profile.accreditation_status, AccreditationStatus::Verified) ||
                            matches!(profile.investor_type, InvestorType::AccreditedInvestor | InvestorType::Institutional);
                 
-                Ok(ComplianceCheck {
-                    requirement_id: requirement.requirement_id.clone(),
-                    framework: requirement.framework.clone(),
+                Ok(check(
                     passed,
-                    message: format!("Accredited investor status: {:?}", profile.accreditation_status),
-                    severity: if !passed { ComplianceSeverity::Error } else { ComplianceSeverity::Info },
-                    remediation_steps: if !passed {
-                        vec!["Provide accredited investor documentation".to_string()]
-                    } else {
-                        vec![]
-                    },
-                    check_timestamp,
-                    check_id,
-                })
+                    if !passed { ComplianceSeverity::Error } else { ComplianceSeverity::Info },
+                    MessageRef::new("accreditation.status").param("status", format!("{:?}", profile.accreditation_status)),
+                    unless_passed(passed, MessageRef::new("accreditation.remediation")),
+                ))
             },
 
             VerificationMethod::InvestmentLimitCheck => {
-                if let Some(limit) = profile.investment_limits.get(asset_type) {
-                    let remaining_capacity = limit.maximum_amount.saturating_sub(limit.current_exposure);
-                    let passed = investment_amount <= remaining_capacity;
-                    
-                    Ok(ComplianceCheck {
-                        requirement_id: requirement.requirement_id.clone(),
-                        framework: requirement.framework.clone(),
-                        passed,
-                        message: format!("Investment limit check: {} / {} remaining", 
-                                       remaining_capacity, limit.maximum_amount),
-                        severity: if !passed { ComplianceSeverity::Error } else { ComplianceSeverity::Info },
-                        remediation_steps: if !passed {
-                            vec!["Reduce investment amount or wait for limit reset".to_string()]
-                        } else {
-                            vec![]
-                        },
-                        check_timestamp,
-                        check_id,
-                    })
-                } else {
-                    Ok(ComplianceCheck {
-                        requirement_id: requirement.requirement_id.clone(),
-                        framework: requirement.framework.clone(),
-                        passed: false,
-                        message: "No investment limit configured for asset type".to_string(),
-                        severity: ComplianceSeverity::Warning,
-                        remediation_steps: vec!["Configure investment limits".to_string()],
-                        check_timestamp,
-                        check_id,
-                    })
-                }
+                Ok(self.limit_check(&requirement.requirement_id, requirement.framework.clone(), limits, investment_amount))
             },
 
             VerificationMethod::CoolingPeriodCheck => {
@@ -586,46 +926,19 @@ impl EnhancedComplianceEngine {
                         let time_since_last = Utc::now().signed_duration_since(*last_investment);
                         let passed = time_since_last >= cooling_period;
                         
-                        Ok(ComplianceCheck {
-                            requirement_id: requirement.requirement_id.clone(),
-                            framework: requirement.framework.clone(),
+                        Ok(check(
                             passed,
-                            message: format!("Cooling period check: {} days since last investment", 
-                                           time_since_last.num_days()),
-                            severity: if !passed { ComplianceSeverity::Warning } else { ComplianceSeverity::Info },
-                            remediation_steps: if !passed {
-                                vec![format!("Wait {} more days before next investment", 
-                                           (cooling_period - time_since_last).num_days())]
-                            } else {
-                                vec![]
-                            },
-                            check_timestamp,
-                            check_id,
-                        })
+                            if !passed { ComplianceSeverity::Warning } else { ComplianceSeverity::Info },
+                            MessageRef::new("cooling_period.elapsed").param("days", time_since_last.num_days()),
+                            unless_passed(passed, MessageRef::new("cooling_period.remediation")
+                                .param("days_remaining", (cooling_period - time_since_last).num_days())),
+                        ))
                     } else {
                         // First investment, no cooling period required
-                        Ok(ComplianceCheck {
-                            requirement_id: requirement.requirement_id.clone(),
-                            framework: requirement.framework.clone(),
-                            passed: true,
-                            message: "First investment in asset type".to_string(),
-                            severity: ComplianceSeverity::Info,
-                            remediation_steps: vec![],
-                            check_timestamp,
-                            check_id,
-                        })
+                        Ok(check(true, ComplianceSeverity::Info, MessageRef::new("cooling_period.first_investment"), vec![]))
                     }
                 } else {
-                    Ok(ComplianceCheck {
-                        requirement_id: requirement.requirement_id.clone(),
-                        framework: requirement.framework.clone(),
-                        passed: true,
-                        message: "No cooling period required".to_string(),
-                        severity: ComplianceSeverity::Info,
-                        remediation_steps: vec![],
-                        check_timestamp,
-                        check_id,
-                    })
+                    Ok(check(true, ComplianceSeverity::Info, MessageRef::new("cooling_period.not_required"), vec![]))
                 }
             },
 
@@ -637,20 +950,12 @@ impl EnhancedComplianceEngine {
                     ComplianceSeverity::Info
                 };
 
-                Ok(ComplianceCheck {
-                    requirement_id: requirement.requirement_id.clone(),
-                    framework: requirement.framework.clone(),
+                Ok(check(
                     passed,
-                    message: format!("Sanctions screening status: {:?}", profile.sanctions_status),
                     severity,
-                    remediation_steps: if !passed {
-                        vec!["Complete sanctions screening process".to_string()]
-                    } else {
-                        vec![]
-                    },
-                    check_timestamp,
-                    check_id,
-                })
+                    MessageRef::new("sanctions.status").param("status", format!("{:?}", profile.sanctions_status)),
+                    unless_passed(passed, MessageRef::new("sanctions.remediation")),
+                ))
             },
 
             VerificationMethod::QualifiedInvestorStatus => {
@@ -660,20 +965,12 @@ impl EnhancedComplianceEngine {
                     InvestorType::EligibleCounterparty
                 );
                 
-                Ok(ComplianceCheck {
-                    requirement_id: requirement.requirement_id.clone(),
-                    framework: requirement.framework.clone(),
+                Ok(check(
                     passed,
-                    message: format!("Qualified investor status: {:?}", profile.investor_type),
-                    severity: if !passed { ComplianceSeverity::Error } else { ComplianceSeverity::Info },
-                    remediation_steps: if !passed {
-                        vec!["Obtain qualified investor certification".to_string()]
-                    } else {
-                        vec![]
-                    },
-                    check_timestamp,
-                    check_id,
-                })
+                    if !passed { ComplianceSeverity::Error } else { ComplianceSeverity::Info },
+                    MessageRef::new("qualified_investor.status").param("investor_type", format!("{:?}", profile.investor_type)),
+                    unless_passed(passed, MessageRef::new("qualified_investor.remediation")),
+                ))
             },
 
             VerificationMethod::ProfessionalInvestorVerification => {
@@ -683,109 +980,155 @@ impl EnhancedComplianceEngine {
                     InvestorType::EligibleCounterparty
                 );
                 
-                Ok(ComplianceCheck {
-                    requirement_id: requirement.requirement_id.clone(),
-                    framework: requirement.framework.clone(),
+                Ok(check(
                     passed,
-                    message: format!("Professional investor status: {:?}", profile.investor_type),
-                    severity: if !passed { ComplianceSeverity::Warning } else { ComplianceSeverity::Info },
-                    remediation_steps: if !passed {
-                        vec!["Obtain professional investor classification".to_string()]
-                    } else {
-                        vec![]
-                    },
-                    check_timestamp,
-                    check_id,
-                })
+                    if !passed { ComplianceSeverity::Warning } else { ComplianceSeverity::Info },
+                    MessageRef::new("professional_investor.status").param("investor_type", format!("{:?}", profile.investor_type)),
+                    unless_passed(passed, MessageRef::new("professional_investor.remediation")),
+                ))
             },
 
             VerificationMethod::InstitutionalInvestorCheck => {
                 let passed = matches!(profile.investor_type, InvestorType::Institutional);
                 
-                Ok(ComplianceCheck {
-                    requirement_id: requirement.requirement_id.clone(),
-                    framework: requirement.framework.clone(),
+                Ok(check(
                     passed,
-                    message: format!("Institutional investor status: {:?}", profile.investor_type),
-                    severity: if !passed { ComplianceSeverity::Error } else { ComplianceSeverity::Info },
-                    remediation_steps: if !passed {
-                        vec!["Provide institutional investor documentation".to_string()]
-                    } else {
-                        vec![]
-                    },
-                    check_timestamp,
-                    check_id,
-                })
+                    if !passed { ComplianceSeverity::Error } else { ComplianceSeverity::Info },
+                    MessageRef::new("institutional_investor.status").param("investor_type", format!("{:?}", profile.investor_type)),
+                    unless_passed(passed, MessageRef::new("institutional_investor.remediation")),
+                ))
             },
 
             VerificationMethod::TaxResidencyVerification => {
                 let passed = !profile.tax_residency.is_empty();
                 
-                Ok(ComplianceCheck {
-                    requirement_id: requirement.requirement_id.clone(),
-                    framework: requirement.framework.clone(),
+                Ok(check(
                     passed,
-                    message: format!("Tax residency verification: {} jurisdictions", profile.tax_residency.len()),
-                    severity: if !passed { ComplianceSeverity::Warning } else { ComplianceSeverity::Info },
-                    remediation_steps: if !passed {
-                        vec!["Provide tax residency documentation".to_string()]
-                    } else {
-                        vec![]
-                    },
-                    check_timestamp,
-                    check_id,
-                })
+                    if !passed { ComplianceSeverity::Warning } else { ComplianceSeverity::Info },
+                    MessageRef::new("tax_residency.status").param("count", profile.tax_residency.len()),
+                    unless_passed(passed, MessageRef::new("tax_residency.remediation")),
+                ))
             },
 
             VerificationMethod::SuitabilityAssessment => {
-                // Check if investor has appropriate risk rating for the asset
-                let passed = match profile.risk_rating {
-                    RiskRating::Prohibited => false,
-                    RiskRating::High => asset_type != "high_risk",
-                    RiskRating::Medium => !["high_risk", "derivatives"].contains(&asset_type),
-                    RiskRating::Low => ["securities", "real_estate", "commodities"].contains(&asset_type),
+                let risk_rating = self.effective_risk_rating(profile);
+                let complexity = appropriateness::product_complexity(asset_type);
+                let questionnaire = profile.appropriateness.as_ref()
+                    .filter(|assessment| !assessment.is_stale(check_timestamp));
+
+                let (passed, message) = match (&risk_rating, questionnaire) {
+                    (RiskRating::Prohibited, _) => (
+                        false,
+                        MessageRef::new("suitability.prohibited").param("risk_rating", format!("{:?}", risk_rating)),
+                    ),
+                    (_, Some(assessment)) => (
+                        assessment.permits(complexity),
+                        MessageRef::new("suitability.appropriateness")
+                            .param("max_complexity", format!("{:?}", assessment.max_complexity))
+                            .param("asset_type", asset_type)
+                            .param("complexity", format!("{:?}", complexity)),
+                    ),
+                    // Without a current questionnaire, fall back to the risk rating
+                    (_, None) => (
+                        Self::legacy_suitability(&risk_rating, asset_type),
+                        MessageRef::new("suitability.risk_rating")
+                            .param("risk_rating", format!("{:?}", risk_rating))
+                            .param("asset_type", asset_type),
+                    ),
                 };
+
+                if passed && questionnaire.is_none() {
+                    let id = if profile.appropriateness.is_some() {
+                        "suitability.questionnaire_stale"
+                    } else {
+                        "suitability.questionnaire_missing"
+                    };
+                    return Ok(check(
+                        false,
+                        ComplianceSeverity::Warning,
+                        MessageRef { id: id.to_string(), params: message.params },
+                        vec![MessageRef::new("suitability.questionnaire_remediation")],
+                    ));
+                }
                 
-                Ok(ComplianceCheck {
-                    requirement_id: requirement.requirement_id.clone(),
-                    framework: requirement.framework.clone(),
+                Ok(check(
                     passed,
-                    message: format!("Suitability assessment: {:?} risk rating for {} asset", 
-                                   profile.risk_rating, asset_type),
-                    severity: if !passed { ComplianceSeverity::Error } else { ComplianceSeverity::Info },
-                    remediation_steps: if !passed {
-                        vec!["Complete suitability assessment or choose appropriate asset type".to_string()]
-                    } else {
-                        vec![]
-                    },
-                    check_timestamp,
-                    check_id,
-                })
+                    if !passed { ComplianceSeverity::Error } else { ComplianceSeverity::Info },
+                    message,
+                    unless_passed(passed, MessageRef::new("suitability.remediation")),
+                ))
             },
 
             VerificationMethod::GeographicRestriction => {
                 // Check if jurisdiction allows investment in this asset type
-                let restricted_jurisdictions = vec!["CN", "KP", "IR"]; // Example restricted jurisdictions
-                let passed = !restricted_jurisdictions.contains(&profile.jurisdiction.as_str());
+                let passed = self.jurisdiction_risk.tier(&profile.jurisdiction) != JurisdictionRiskTier::Blacklisted;
                 
-                Ok(ComplianceCheck {
-                    requirement_id: requirement.requirement_id.clone(),
-                    framework: requirement.framework.clone(),
+                Ok(check(
                     passed,
-                    message: format!("Geographic restriction check for jurisdiction: {}", profile.jurisdiction),
-                    severity: if !passed { ComplianceSeverity::Critical } else { ComplianceSeverity::Info },
-                    remediation_steps: if !passed {
-                        vec!["Investment not permitted from this jurisdiction".to_string()]
-                    } else {
-                        vec![]
-                    },
-                    check_timestamp,
-                    check_id,
-                })
+                    if !passed { ComplianceSeverity::Critical } else { ComplianceSeverity::Info },
+                    MessageRef::new("geographic.status").param("jurisdiction", &profile.jurisdiction),
+                    unless_passed(passed, MessageRef::new("jurisdiction.not_permitted")),
+                ))
             },
         }
     }
 
+    /// One check over every applicable investment limit, reporting the binding one. A breach
+    /// of any limit fails the check; limits whose exposure is unknown only warn.
+    fn limit_check(
+        &self,
+        requirement_id: &str,
+        framework: RegulatoryFramework,
+        limits: &LimitReport,
+        investment_amount: u128,
+    ) -> ComplianceCheck {
+        let check = |passed: bool, severity: ComplianceSeverity, message: MessageRef, remediation: Vec<MessageRef>| {
+            self.check(requirement_id, framework.clone(), passed, severity, message, remediation)
+        };
+
+        let binding = limits.binding();
+        if let Some(limit) = binding.filter(|limit| investment_amount > limit.remaining()) {
+            return check(
+                false,
+                ComplianceSeverity::Error,
+                MessageRef::new("investment_limit.exceeded")
+                    .param("scope", &limit.scope)
+                    .param("remaining", limit.remaining())
+                    .param("limit", limit.maximum_amount),
+                vec![MessageRef::new("investment_limit.remediation")],
+            );
+        }
+        if !limits.unavailable.is_empty() {
+            for (scope, reason) in &limits.unavailable {
+                warn!("Investment limit on {} not evaluated: {}", scope, reason);
+            }
+            let scopes: Vec<String> = limits.unavailable.iter().map(|(scope, _)| scope.to_string()).collect();
+            return check(
+                false,
+                ComplianceSeverity::Warning,
+                MessageRef::new("investment_limit.exposure_unavailable").param("scopes", scopes.join(", ")),
+                vec![MessageRef::new("investment_limit.sync_holdings")],
+            );
+        }
+        match binding {
+            Some(limit) => check(
+                true,
+                ComplianceSeverity::Info,
+                MessageRef::new("investment_limit.remaining")
+                    .param("remaining", limit.remaining())
+                    .param("limit", limit.maximum_amount)
+                    .param("scope", &limit.scope),
+                vec![],
+            ),
+            None => check(
+                false,
+                ComplianceSeverity::Warning,
+                MessageRef::new("investment_limit.missing"),
+                vec![MessageRef::new("investment_limit.configure")],
+            ),
+        }
+    }
+
     async fn perform_risk_based_checks(
         &self,
         profile: &InvestorProfile,
@@ -793,55 +1136,87 @@ impl EnhancedComplianceEngine {
         investment_amount: u128,
         checks: &mut Vec<ComplianceCheck>,
     ) -> Result<(), ComplianceError> {
-        let check_timestamp = Utc::now();
+        let check = |requirement_id: &str, passed: bool, severity: ComplianceSeverity, message: MessageRef, remediation: &str| {
+            self.check(requirement_id, RegulatoryFramework::MiCA, passed, severity, message, vec![MessageRef::new(remediation)])
+        };
+
+        // Jurisdiction risk tier check
+        match self.jurisdiction_risk.tier(&profile.jurisdiction) {
+            JurisdictionRiskTier::Blacklisted => checks.push(check(
+                "RISK_JURISDICTION_BLOCKED",
+                false,
+                ComplianceSeverity::Critical,
+                MessageRef::new("risk.jurisdiction_blocked").param("jurisdiction", &profile.jurisdiction),
+                "jurisdiction.not_permitted",
+            )),
+            JurisdictionRiskTier::High => checks.push(check(
+                "RISK_JURISDICTION_EDD",
+                // Screening only clears once enhanced due diligence has been completed
+                matches!(profile.aml_status, AMLStatus::Clear),
+                ComplianceSeverity::Error,
+                MessageRef::new("risk.jurisdiction_edd").param("jurisdiction", &profile.jurisdiction),
+                "risk.jurisdiction_edd_remediation",
+            )),
+            JurisdictionRiskTier::Standard => {}
+        }
 
         // High-value transaction check
-        if investment_amount > 1_000_000_000_000_000_000_000 { // > 1000 ETH equivalent
-            checks.push(ComplianceCheck {
-                requirement_id: "RISK_HIGH_VALUE".to_string(),
-                framework: RegulatoryFramework::MiCA, // Default framework
-                passed: matches!(profile.investor_type, InvestorType::Institutional | InvestorType::AccreditedInvestor),
-                message: "High-value transaction requires institutional or accredited investor status".to_string(),
-                severity: ComplianceSeverity::Warning,
-                remediation_steps: vec!["Verify institutional or accredited investor status".to_string()],
-                check_timestamp,
-                check_id: Uuid::new_v4().to_string(),
-            });
+        if investment_amount > HIGH_VALUE_THRESHOLD {
+            checks.push(check(
+                "RISK_HIGH_VALUE",
+                matches!(profile.investor_type, InvestorType::Institutional | InvestorType::AccreditedInvestor),
+                ComplianceSeverity::Warning,
+                MessageRef::new("risk.high_value"),
+                "risk.high_value_remediation",
+            ));
         }
 
         // Compliance score check
         if profile.compliance_score < 70 {
-            checks.push(ComplianceCheck {
-                requirement_id: "RISK_LOW_SCORE".to_string(),
-                framework: RegulatoryFramework::MiCA,
-                passed: false,
-                message: format!("Low compliance score: {}/100", profile.compliance_score),
-                severity: ComplianceSeverity::Warning,
-                remediation_steps: vec!["Improve compliance score through additional verification".to_string()],
-                check_timestamp,
-                check_id: Uuid::new_v4().to_string(),
-            });
+            checks.push(check(
+                "RISK_LOW_SCORE",
+                false,
+                ComplianceSeverity::Warning,
+                MessageRef::new("risk.low_score").param("score", profile.compliance_score),
+                "risk.low_score_remediation",
+            ));
         }
 
         // Profile freshness check
         let profile_age = Utc::now().signed_duration_since(profile.last_updated);
         if profile_age > Duration::days(90) {
-            checks.push(ComplianceCheck {
-                requirement_id: "RISK_STALE_PROFILE".to_string(),
-                framework: RegulatoryFramework::MiCA,
-                passed: false,
-                message: format!("Profile last updated {} days ago", profile_age.num_days()),
-                severity: ComplianceSeverity::Warning,
-                remediation_steps: vec!["Update investor profile information".to_string()],
-                check_timestamp,
-                check_id: Uuid::new_v4().to_string(),
-            });
+            checks.push(check(
+                "RISK_STALE_PROFILE",
+                false,
+                ComplianceSeverity::Warning,
+                MessageRef::new("risk.stale_profile").param("days", profile_age.num_days()),
+                "risk.stale_profile_remediation",
+            ));
         }
 
         Ok(())
     }
 
-    fn generate_recommendations(&self, checks: &[ComplianceCheck]) -> Vec<String> {
+    /// Investor risk rating raised to at least what the jurisdiction's risk tier implies
+    fn effective_risk_rating(&self, profile: &InvestorProfile) -> RiskRating {
+        match (self.jurisdiction_risk.tier(&profile.jurisdiction), &profile.risk_rating) {
+            (JurisdictionRiskTier::Blacklisted, _) => RiskRating::Prohibited,
+            (JurisdictionRiskTier::High, RiskRating::Low | RiskRating::Medium) => RiskRating::High,
+            (_, rating) => rating.clone(),
+        }
+    }
+
+    /// Risk rating based suitability used when no current questionnaire exists
+    fn legacy_suitability(risk_rating: &RiskRating, asset_type: &str) -> bool {
+        match risk_rating {
+            RiskRating::Prohibited => false,
+            RiskRating::High => asset_type != "high_risk",
+            RiskRating::Medium => !["high_risk", "derivatives"].contains(&asset_type),
+            RiskRating::Low => ["securities", "real_estate", "commodities"].contains(&asset_type),
+        }
+    }
+
+    fn generate_recommendations(&self, checks: &[ComplianceCheck]) -> Vec<MessageRef> {
         let mut recommendations = Vec::new();
         
         let failed_checks = checks.iter().filter(|check| !check.passed).count();
@@ -850,32 +1225,24 @@ impl EnhancedComplianceEngine {
         ).count();
 
         if critical_failures > 0 {
-            recommendations.push("Address critical compliance failures immediately".to_string());
+            recommendations.push(MessageRef::new("recommendation.critical_failures"));
         }
 
         if failed_checks > 3 {
-            recommendations.push("Consider comprehensive compliance review".to_string());
+            recommendations.push(MessageRef::new("recommendation.comprehensive_review"));
         }
 
         if checks.iter().any(|check| check.requirement_id.contains("KYC") && !check.passed) {
-            recommendations.push("Complete KYC verification to improve compliance standing".to_string());
+            recommendations.push(MessageRef::new("recommendation.complete_kyc"));
         }
 
         if checks.iter().any(|check| check.requirement_id.contains("AML") && !check.passed) {
-            recommendations.push("Complete AML screening to ensure regulatory compliance".to_string());
+            recommendations.push(MessageRef::new("recommendation.complete_aml"));
         }
 
         recommendations
     }
 
-    fn generate_required_actions(&self, checks: &[ComplianceCheck]) -> Vec<String> {
-        checks.iter()
-            .filter(|check| !check.passed && matches!(check.severity, ComplianceSeverity::Critical | ComplianceSeverity::Error))
-            .flat_map(|check| check.remediation_steps.iter())
-            .cloned()
-            .collect()
-    }
-
     fn estimate_completion_time(&self, checks: &[ComplianceCheck]) -> Option<Duration> {
         let failed_checks = checks.iter().filter(|check| !check.passed).count();
         
@@ -896,6 +1263,7 @@ impl EnhancedComplianceEngine {
 
     pub async fn update_investor_profile(
         &mut self,
+        scope: &TenantScope,
         investor_id: String,
         mut profile: InvestorProfile,
         performed_by: &str,
@@ -908,7 +1276,15 @@ impl EnhancedComplianceEngine {
             return Err(ComplianceError::InvalidInput("Invalid investor ID".to_string()));
         }
 
-        // Generate data hash for integrity
+        // Profiles always belong to the caller's tenant; platform admins keep the profile's own
+        if let Some(tenant) = scope.tenant() {
+            profile.tenant_id = tenant.clone();
+        }
+        let tenant_id = profile.tenant_id.clone();
+
+        // Generate data hash for integrity over the final last_updated
+        profile.last_updated = Utc::now();
+        profile.last_accessed = Utc::now();
         let profile_data = format!("{}{}{:?}{:?}", 
             profile.investor_id, 
             profile.jurisdiction, 
@@ -916,17 +1292,18 @@ impl EnhancedComplianceEngine {
             profile.last_updated
         );
         profile.data_hash = self.generate_data_hash(&profile_data);
-        profile.last_updated = Utc::now();
-        profile.last_accessed = Utc::now();
 
         // Store profile
-        self.investor_profiles.insert(investor_id.clone(), profile);
+        let key = (tenant_id.clone(), investor_id.clone());
+        self.investor_profiles.insert(key.clone(), profile);
+        self.bump_profile_version(&key);
 
         // Create audit log entry
         let mut audit_details = HashMap::new();
         audit_details.insert("action".to_string(), "profile_update".to_string());
         
         self.log_audit_entry(
+            tenant_id,
             "update_investor_profile".to_string(),
             investor_id,
             performed_by.to_string(),
@@ -940,6 +1317,7 @@ impl EnhancedComplianceEngine {
 
     pub async fn get_investor_profile(
         &mut self,
+        scope: &TenantScope,
         investor_id: &str,
         requested_by: &str,
     ) -> Result<Option<&InvestorProfile>, ComplianceError> {
@@ -952,14 +1330,15 @@ impl EnhancedComplianceEngine {
         }
 
         // First verify data integrity with immutable borrow
-        if let Some(profile) = self.investor_profiles.get(investor_id) {
+        let key = if let Some(profile) = self.find_profile(scope, investor_id) {
             self.verify_data_integrity(profile)?;
+            (profile.tenant_id.clone(), profile.investor_id.clone())
         } else {
             return Ok(None);
-        }
+        };
         
         // Then update with mutable borrow
-        if let Some(profile) = self.investor_profiles.get_mut(investor_id) {
+        if let Some(profile) = self.investor_profiles.get_mut(&key) {
             profile.last_accessed = Utc::now();
             Ok(Some(profile))
         } else {
@@ -967,6 +1346,50 @@ impl EnhancedComplianceEngine {
         }
     }
 
+    /// Profiles visible in the scope, for compliance staff reviewing investors.
+    /// Profiles failing the integrity check are left out, as `get_investor_profile` refuses them.
+    pub fn list_investor_profiles(
+        &self,
+        scope: &TenantScope,
+        requested_by: &str,
+    ) -> Result<Vec<&InvestorProfile>, ComplianceError> {
+        self.check_access(requested_by, AccessLevel::ReadOnly)?;
+        Ok(self.investor_profiles.values()
+            .filter(|profile| scope.allows(&profile.tenant_id))
+            .filter(|profile| self.verify_data_integrity(profile).is_ok())
+            .collect())
+    }
+
+    /// Jurisdiction and investor type of a profile, used to gate the product catalog.
+    /// Investors read their own classification, so no access level is required.
+    pub fn investor_classification(&self, scope: &TenantScope, investor_id: &str) -> Option<(String, InvestorType)> {
+        self.find_profile(scope, investor_id)
+            .map(|profile| (profile.jurisdiction.clone(), profile.investor_type.clone()))
+    }
+
+    /// Eligibility of an investor for one instrument, from the profile held in memory.
+    /// Nothing is checked against the cache or written to the audit log.
+    pub fn eligibility(
+        &self,
+        scope: &TenantScope,
+        investor_id: &str,
+        asset_id: &str,
+        asset_type: &str,
+        distribution: &DistributionRules,
+    ) -> EligibilityReport {
+        let profile = self.find_profile(scope, investor_id);
+        let cooling_period_days = profile
+            .and_then(|profile| self.frameworks.get(&profile.jurisdiction))
+            .into_iter()
+            .flatten()
+            .filter(|requirement| matches!(requirement.verification_method, VerificationMethod::CoolingPeriodCheck))
+            .filter(|requirement| self.covers_asset_type(requirement, asset_type))
+            .filter_map(|requirement| requirement.cooling_period_days)
+            .max();
+        let instrument = Instrument { asset_id, asset_type, distribution, cooling_period_days };
+        eligibility::evaluate(investor_id, profile, &instrument, Utc::now())
+    }
+
     pub async fn get_supported_jurisdictions(&self) -> Vec<String> {
         self.jurisdiction_mappings.keys().cloned().collect()
     }
@@ -986,13 +1409,155 @@ impl EnhancedComplianceEngine {
         self.access_control.insert(user_id, access_level);
     }
 
+    /// Install a refreshed country risk dataset; it applies from the next check on
+    pub fn set_jurisdiction_risk(&mut self, table: JurisdictionRiskTable) {
+        self.jurisdiction_risk = table;
+        self.bump_rules_version(None);
+    }
+
+    /// Replace a jurisdiction's requirements; cached checks for it are invalidated
+    pub fn set_framework_requirements(
+        &mut self,
+        jurisdiction: &str,
+        requirements: Vec<ComplianceRequirement>,
+        performed_by: &str,
+    ) -> Result<(), ComplianceError> {
+        self.check_access(performed_by, AccessLevel::Elevated)?;
+
+        let mut audit_details = HashMap::new();
+        audit_details.insert("jurisdiction".to_string(), jurisdiction.to_string());
+        audit_details.insert("requirements".to_string(), requirements.len().to_string());
+        self.log_audit_entry(
+            TenantId::default(),
+            "set_framework_requirements".to_string(),
+            String::new(),
+            performed_by.to_string(),
+            audit_details,
+            None,
+            RiskRating::Low,
+        )?;
+
+        self.frameworks.insert(jurisdiction.to_string(), requirements);
+        self.bump_rules_version(Some(jurisdiction));
+        Ok(())
+    }
+
+    /// Hit rate of the check result cache
+    pub fn check_cache_stats(&self) -> CheckCacheStats {
+        self.check_cache.stats()
+    }
+
+    pub fn jurisdiction_risk(&self) -> &JurisdictionRiskTable {
+        &self.jurisdiction_risk
+    }
+
+    /// Current question set of a jurisdiction
+    pub fn question_set(&self, jurisdiction: &str) -> Option<&QuestionSet> {
+        self.question_sets.get(jurisdiction).and_then(|versions| versions.last())
+    }
+
+    /// Publish a new question set version; answers to older versions are no longer accepted
+    pub fn publish_question_set(
+        &mut self,
+        mut set: QuestionSet,
+        performed_by: &str,
+    ) -> Result<&QuestionSet, ComplianceError> {
+        self.check_access(performed_by, AccessLevel::Elevated)?;
+        set.validate()?;
+
+        let next_version = self.question_set(&set.jurisdiction).map_or(1, |current| current.version + 1);
+        set.version = next_version;
+        set.published_at = Utc::now();
+
+        let mut audit_details = HashMap::new();
+        audit_details.insert("jurisdiction".to_string(), set.jurisdiction.clone());
+        audit_details.insert("version".to_string(), next_version.to_string());
+        self.log_audit_entry(
+            TenantId::default(),
+            "publish_question_set".to_string(),
+            String::new(),
+            performed_by.to_string(),
+            audit_details,
+            None,
+            RiskRating::Low,
+        )?;
+
+        self.bump_rules_version(Some(&set.jurisdiction));
+        let versions = self.question_sets.entry(set.jurisdiction.clone()).or_default();
+        versions.push(set);
+        Ok(versions.last().expect("version just pushed"))
+    }
+
+    /// Score an investor's answers against their jurisdiction's question set and store the result.
+    ///
+    /// Investors may submit their own answers; anyone else needs Standard access.
+    pub async fn submit_questionnaire(
+        &mut self,
+        scope: &TenantScope,
+        investor_id: &str,
+        answers: QuestionnaireAnswers,
+        performed_by: &str,
+    ) -> Result<AppropriatenessProfile, ComplianceError> {
+        if performed_by != investor_id {
+            self.check_access(performed_by, AccessLevel::Standard)?;
+        }
+
+        let (key, jurisdiction) = {
+            let profile = self.find_profile(scope, investor_id)
+                .ok_or(ComplianceError::InvestorNotFound)?;
+            self.verify_data_integrity(profile)?;
+            ((profile.tenant_id.clone(), profile.investor_id.clone()), profile.jurisdiction.clone())
+        };
+
+        let set = self.question_set(&jurisdiction)
+            .ok_or(ComplianceError::JurisdictionNotSupported)?;
+        let assessment = appropriateness::score(set, &answers, Utc::now())?;
+
+        if let Some(profile) = self.investor_profiles.get_mut(&key) {
+            profile.appropriateness = Some(assessment.clone());
+        }
+        self.bump_profile_version(&key);
+
+        let mut audit_details = HashMap::new();
+        audit_details.insert("question_set_version".to_string(), assessment.question_set_version.to_string());
+        audit_details.insert("knowledge_experience_score".to_string(), assessment.knowledge_experience_score.to_string());
+        audit_details.insert("max_complexity".to_string(), format!("{:?}", assessment.max_complexity));
+        self.log_audit_entry(
+            key.0,
+            "submit_questionnaire".to_string(),
+            investor_id.to_string(),
+            performed_by.to_string(),
+            audit_details,
+            None,
+            RiskRating::Low,
+        )?;
+
+        Ok(assessment)
+    }
+
     pub fn revoke_access(&mut self, user_id: &str) {
         self.access_control.remove(user_id);
     }
 
-    pub fn get_audit_log(&self, requested_by: &str) -> Result<&Vec<AuditLogEntry>, ComplianceError> {
+    pub fn get_audit_log(&self, scope: &TenantScope, requested_by: &str) -> Result<Vec<&AuditLogEntry>, ComplianceError> {
         self.check_access(requested_by, AccessLevel::Elevated)?;
-        Ok(&self.audit_log)
+        Ok(self.audit_log.iter()
+            .filter(|entry| scope.allows(&entry.tenant_id))
+            .collect())
+    }
+
+    /// Count compliance checks in the audit log since `since`; failed checks are violations
+    pub fn check_counts_since(&self, scope: &TenantScope, since: DateTime<Utc>) -> ComplianceCheckCounts {
+        self.audit_log.iter()
+            .filter(|entry| scope.allows(&entry.tenant_id) && entry.timestamp >= since)
+            .filter_map(|entry| entry.compliance_result)
+            .fold(ComplianceCheckCounts::default(), |mut counts, compliant| {
+                counts.checks += 1;
+                if !compliant {
+                    counts.violations += 1;
+                }
+                counts
+            })
     }
 
     fn initialize_frameworks(&mut self) {
@@ -1027,7 +1592,7 @@ impl EnhancedComplianceEngine {
                 is_mandatory: false,
                 verification_method: VerificationMethod::ProfessionalInvestorVerification,
                 applicable_asset_types: vec!["complex_instruments".to_string()],
-                minimum_investment_threshold: Some(100_000_000_000_000_000_000), // 100 ETH equivalent
+                minimum_investment_threshold: Some(MonetaryThreshold::new(500_000, FiatCurrency::EUR)), // MiFID II elective professional portfolio test
                 maximum_investment_threshold: None,
                 cooling_period_days: None,
             },
@@ -1042,7 +1607,7 @@ impl EnhancedComplianceEngine {
                 is_mandatory: true,
                 verification_method: VerificationMethod::AccreditedInvestorCheck,
                 applicable_asset_types: vec!["securities".to_string(), "private_equity".to_string()],
-                minimum_investment_threshold: Some(1_000_000_000_000_000_000), // 1 ETH equivalent
+                minimum_investment_threshold: Some(MonetaryThreshold::new(1_000_000, FiatCurrency::USD)), // Rule 501(a)(5) net worth test
                 maximum_investment_threshold: None,
                 cooling_period_days: None,
             },
@@ -1053,7 +1618,7 @@ impl EnhancedComplianceEngine {
                 is_mandatory: false,
                 verification_method: VerificationMethod::QualifiedInvestorStatus,
                 applicable_asset_types: vec!["institutional_securities".to_string()],
-                minimum_investment_threshold: Some(100_000_000_000_000_000_000_000), // 100,000 ETH equivalent
+                minimum_investment_threshold: Some(MonetaryThreshold::new(100_000_000, FiatCurrency::USD)), // Rule 144A QIB securities owned
                 maximum_investment_threshold: None,
                 cooling_period_days: None,
             },
@@ -1065,7 +1630,7 @@ impl EnhancedComplianceEngine {
                 verification_method: VerificationMethod::CoolingPeriodCheck,
                 applicable_asset_types: vec!["high_risk".to_string()],
                 minimum_investment_threshold: None,
-                maximum_investment_threshold: Some(10_000_000_000_000_000_000), // 10 ETH equivalent
+                maximum_investment_threshold: Some(MonetaryThreshold::new(10_000, FiatCurrency::USD)),
                 cooling_period_days: Some(7),
             },
         ]);
@@ -1079,7 +1644,7 @@ impl EnhancedComplianceEngine {
                 is_mandatory: true,
                 verification_method: VerificationMethod::AccreditedInvestorCheck,
                 applicable_asset_types: vec!["securities".to_string()],
-                minimum_investment_threshold: Some(200_000_000_000_000_000_000), // 200 SGD equivalent
+                minimum_investment_threshold: Some(MonetaryThreshold::new(200_000, FiatCurrency::SGD)), // SFA s305(2)(c) minimum consideration
                 maximum_investment_threshold: None,
                 cooling_period_days: None,
             },
@@ -1131,6 +1696,13 @@ impl EnhancedComplianceEngine {
         ]);
     }
 
+    fn initialize_question_sets(&mut self) {
+        // MiFID II appropriateness applies in the EU; the UK retained it after Brexit
+        for jurisdiction in ["EU", "DE", "FR", "UK"] {
+            self.question_sets.insert(jurisdiction.to_string(), vec![QuestionSet::builtin(jurisdiction)]);
+        }
+    }
+
     fn initialize_sanctions_lists(&mut self) {
         // Initialize with example sanctioned entities (in production, this would be from official sources)
         self.sanctions_lists.insert("GLOBAL".to_string(), vec![
@@ -1146,4 +1718,300 @@ impl EnhancedComplianceEngine {
             "eu_sanctioned_entity".to_string(),
         ]);
     }
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compliance::jurisdiction_risk::{FatfStatus, JurisdictionRisk};
+    use crate::compliance::thresholds::RateObservation;
+    use rust_decimal::Decimal;
+    use std::sync::Mutex;
+
+    fn profile(jurisdiction: &str) -> InvestorProfile {
+        InvestorProfile {
+            investor_id: "investor-1".to_string(),
+            tenant_id: TenantId::default(),
+            jurisdiction: jurisdiction.to_string(),
+            tax_residency: vec![jurisdiction.to_string()],
+            investor_type: InvestorType::Professional,
+            kyc_status: KYCStatus::Completed,
+            aml_status: AMLStatus::RequiresEnhancedDueDiligence,
+            accreditation_status: AccreditationStatus::NotApplicable,
+            investment_limits: HashMap::new(),
+            last_updated: Utc::now(),
+            compliance_score: 90,
+            risk_rating: RiskRating::Medium,
+            sanctions_status: SanctionsStatus::Clear,
+            cooling_periods: HashMap::new(),
+            appropriateness: None,
+            data_hash: String::new(),
+            access_level: AccessLevel::Standard,
+            created_by: "officer".to_string(),
+            last_accessed: Utc::now(),
+        }
+    }
+
+    fn listing(fatf_status: FatfStatus) -> JurisdictionRiskTable {
+        JurisdictionRiskTable::new(vec![JurisdictionRisk {
+            country_code: "AE".to_string(),
+            fatf_status,
+            basel_aml_score: None,
+            tier_override: None,
+            updated_at: Utc::now(),
+        }])
+    }
+
+    fn jurisdiction_check<'a>(result: &'a ComplianceResult, requirement_id: &str) -> Option<&'a ComplianceCheck> {
+        result.checks.iter().find(|check| check.requirement_id == requirement_id)
+    }
+
+    #[tokio::test]
+    async fn test_tier_change_applies_to_next_check() {
+        let scope = TenantScope::Tenant(TenantId::default());
+        let mut engine = EnhancedComplianceEngine::new();
+        engine.grant_access("officer".to_string(), AccessLevel::Standard);
+        engine.update_investor_profile(&scope, "investor-1".to_string(), profile("AE"), "officer").await.unwrap();
+
+        let result = engine.comprehensive_compliance_check(&scope, "investor-1", "real_estate", 1_000, "EU", "officer").await.unwrap();
+        assert!(jurisdiction_check(&result, "RISK_JURISDICTION_EDD").is_none());
+        assert!(jurisdiction_check(&result, "RISK_JURISDICTION_BLOCKED").is_none());
+
+        // Grey listing requires enhanced due diligence
+        engine.set_jurisdiction_risk(listing(FatfStatus::IncreasedMonitoring));
+        let result = engine.comprehensive_compliance_check(&scope, "investor-1", "real_estate", 1_000, "EU", "officer").await.unwrap();
+        let edd = jurisdiction_check(&result, "RISK_JURISDICTION_EDD").unwrap();
+        assert!(!edd.passed);
+        assert!(matches!(edd.severity, ComplianceSeverity::Error));
+        assert!(!result.is_compliant);
+
+        // Black listing blocks outright
+        engine.set_jurisdiction_risk(listing(FatfStatus::CallForAction));
+        let result = engine.comprehensive_compliance_check(&scope, "investor-1", "real_estate", 1_000, "EU", "officer").await.unwrap();
+        let blocked = jurisdiction_check(&result, "RISK_JURISDICTION_BLOCKED").unwrap();
+        assert!(!blocked.passed);
+        assert!(matches!(blocked.severity, ComplianceSeverity::Critical));
+        assert!(jurisdiction_check(&result, "RISK_JURISDICTION_EDD").is_none());
+        assert!(!result.is_compliant);
+    }
+
+    #[tokio::test]
+    async fn test_suitability_uses_questionnaire_and_falls_back_to_risk_rating() {
+        let scope = TenantScope::Tenant(TenantId::default());
+        let mut engine = EnhancedComplianceEngine::new();
+        engine.grant_access("officer".to_string(), AccessLevel::Standard);
+        engine.update_investor_profile(&scope, "investor-1".to_string(), profile("EU"), "officer").await.unwrap();
+
+        // No questionnaire: the Medium risk rating still rejects derivatives and only warns otherwise
+        let result = engine.comprehensive_compliance_check(&scope, "investor-1", "derivatives", 1_000, "SG", "officer").await.unwrap();
+        let suitability = jurisdiction_check(&result, "MAS_SUIT_001").unwrap();
+        assert!(!suitability.passed);
+        assert!(matches!(suitability.severity, ComplianceSeverity::Error));
+
+        let result = engine.comprehensive_compliance_check(&scope, "investor-1", "structured_products", 1_000, "SG", "officer").await.unwrap();
+        let suitability = jurisdiction_check(&result, "MAS_SUIT_001").unwrap();
+        assert!(!suitability.passed);
+        assert!(matches!(suitability.severity, ComplianceSeverity::Warning));
+
+        // Investors answer for themselves; top answers make derivatives appropriate
+        let set = engine.question_set("EU").unwrap().clone();
+        let answers = QuestionnaireAnswers {
+            question_set_version: set.version,
+            answers: set.questions.iter()
+                .map(|q| (q.question_id.clone(), q.options.last().unwrap().option_id.clone()))
+                .collect(),
+        };
+        engine.submit_questionnaire(&scope, "investor-1", answers, "investor-1").await.unwrap();
+
+        let result = engine.comprehensive_compliance_check(&scope, "investor-1", "derivatives", 1_000, "SG", "officer").await.unwrap();
+        let suitability = jurisdiction_check(&result, "MAS_SUIT_001").unwrap();
+        assert!(suitability.passed);
+
+        // A questionnaire older than 12 months needs a refresh
+        let key = (TenantId::default(), "investor-1".to_string());
+        engine.investor_profiles.get_mut(&key).unwrap().appropriateness.as_mut().unwrap().assessed_at =
+            Utc::now() - Duration::days(appropriateness::QUESTIONNAIRE_VALIDITY_DAYS + 1);
+        let result = engine.comprehensive_compliance_check(&scope, "investor-1", "structured_products", 1_000, "SG", "officer").await.unwrap();
+        let suitability = jurisdiction_check(&result, "MAS_SUIT_001").unwrap();
+        assert!(!suitability.passed);
+        assert!(matches!(suitability.severity, ComplianceSeverity::Warning));
+        assert!(suitability.message.contains("out of date"));
+    }
+
+    #[tokio::test]
+    async fn test_profile_update_invalidates_cached_check() {
+        let scope = TenantScope::Tenant(TenantId::default());
+        let mut engine = EnhancedComplianceEngine::new();
+        engine.grant_access("officer".to_string(), AccessLevel::Standard);
+        engine.update_investor_profile(&scope, "investor-1".to_string(), profile("EU"), "officer").await.unwrap();
+
+        let first = engine.comprehensive_compliance_check(&scope, "investor-1", "real_estate", 1_000, "EU", "officer").await.unwrap();
+        let second = engine.comprehensive_compliance_check(&scope, "investor-1", "real_estate", 1_000, "EU", "officer").await.unwrap();
+        assert_eq!(engine.check_cache_stats().hits, 1);
+        assert_eq!(first.is_compliant, second.is_compliant);
+        // Cached answers are still audited, each under its own trail id
+        assert_ne!(first.audit_trail_id, second.audit_trail_id);
+        assert_eq!(engine.check_counts_since(&scope, Utc::now() - Duration::minutes(1)).checks, 2);
+
+        let mut rejected = profile("EU");
+        rejected.kyc_status = KYCStatus::Rejected;
+        engine.update_investor_profile(&scope, "investor-1".to_string(), rejected, "officer").await.unwrap();
+
+        let result = engine.comprehensive_compliance_check(&scope, "investor-1", "real_estate", 1_000, "EU", "officer").await.unwrap();
+        assert_eq!(engine.check_cache_stats().hits, 1);
+        assert!(!jurisdiction_check(&result, "MICA_KYC_001").unwrap().passed);
+        assert!(!result.is_compliant);
+    }
+
+    #[tokio::test]
+    async fn test_result_renders_in_each_locale_from_stored_ids() {
+        let scope = TenantScope::Tenant(TenantId::default());
+        let mut engine = EnhancedComplianceEngine::new();
+        engine.grant_access("officer".to_string(), AccessLevel::Standard);
+        let mut investor = profile("EU");
+        investor.kyc_status = KYCStatus::Rejected;
+        investor.compliance_score = 55;
+        engine.update_investor_profile(&scope, "investor-1".to_string(), investor, "officer").await.unwrap();
+
+        let result = engine.comprehensive_compliance_check(&scope, "investor-1", "real_estate", 1_000, "EU", "officer").await.unwrap();
+        let low_score = jurisdiction_check(&result, "RISK_LOW_SCORE").unwrap();
+        assert_eq!(low_score.message, "Low compliance score: 55/100");
+        assert_eq!(low_score.message_ref.params["score"], "55");
+
+        // Only ids and parameters are needed to render a stored report again
+        let stored: Vec<ComplianceCheck> = serde_json::from_value(serde_json::to_value(&result.checks).unwrap()).unwrap();
+        let messages = engine.messages().clone();
+        let german = ComplianceResult { checks: stored.clone(), ..result.clone() }.localized(&messages, "de");
+        let french = ComplianceResult { checks: stored, ..result }.localized(&messages, "fr");
+
+        assert_eq!(jurisdiction_check(&german, "RISK_LOW_SCORE").unwrap().message, "Niedriger Compliance-Wert: 55/100");
+        assert_eq!(jurisdiction_check(&french, "RISK_LOW_SCORE").unwrap().message, "Score de conformité faible : 55/100");
+        let kyc = jurisdiction_check(&german, "MICA_KYC_001").unwrap();
+        assert_eq!(kyc.message, "Status der KYC-Prüfung: Rejected");
+        assert_eq!(kyc.remediation_steps, vec!["KYC-Prüfung abschließen"]);
+        assert!(german.required_actions.contains(&"KYC-Prüfung abschließen".to_string()));
+        assert!(french.recommendations.contains(&"Finaliser la vérification KYC pour améliorer la situation de conformité".to_string()));
+        assert_eq!(french.locale, "fr");
+        assert!(messages.gaps().is_empty());
+    }
+
+    /// 2,000 USD per ETH and 1.25 SGD per USD, observed at a settable time
+    struct TestRates {
+        observed_at: Mutex<DateTime<Utc>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ConversionRates for TestRates {
+        async fn asset_price_usd(&self, _asset: &str) -> Result<RateObservation, String> {
+            Ok(RateObservation { rate: Decimal::from(2_000), observed_at: *self.observed_at.lock().unwrap(), source: "test".to_string() })
+        }
+
+        async fn usd_fx_rate(&self, currency: FiatCurrency) -> Result<RateObservation, String> {
+            let rate = match currency {
+                FiatCurrency::USD => Decimal::ONE,
+                FiatCurrency::SGD => Decimal::new(125, 2),
+                FiatCurrency::EUR => return Err("no EUR feed".to_string()),
+            };
+            Ok(RateObservation { rate, observed_at: *self.observed_at.lock().unwrap(), source: "test".to_string() })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fiat_thresholds_apply_from_exact_statutory_amounts() {
+        const ETH: u128 = 1_000_000_000_000_000_000;
+        let scope = TenantScope::Tenant(TenantId::default());
+        let rates = Arc::new(TestRates { observed_at: Mutex::new(Utc::now()) });
+        let mut engine = EnhancedComplianceEngine::new()
+            .with_conversion_rates(rates.clone(), ThresholdConfig::default());
+        engine.grant_access("officer".to_string(), AccessLevel::Standard);
+        let mut investor = profile("SG");
+        investor.cooling_periods.insert("high_risk".to_string(), Utc::now());
+        engine.update_investor_profile(&scope, "investor-1".to_string(), investor, "officer").await.unwrap();
+
+        // SGD 200,000 is exactly 80 ETH; the unaccredited investor is checked from there on
+        let result = engine.comprehensive_compliance_check(&scope, "investor-1", "securities", 80 * ETH - 1, "SG", "officer").await.unwrap();
+        let accreditation = jurisdiction_check(&result, "MAS_AI_001").unwrap();
+        assert!(accreditation.passed);
+        assert_eq!(accreditation.message_ref.id, "threshold.not_applicable");
+        assert_eq!(accreditation.rates.iter().map(|rate| rate.pair.as_str()).collect::<Vec<_>>(), vec!["ETH/USD", "USD/SGD"]);
+        assert_eq!(accreditation.rates[1].rate, Decimal::new(125, 2));
+
+        let result = engine.comprehensive_compliance_check(&scope, "investor-1", "securities", 80 * ETH, "SG", "officer").await.unwrap();
+        let accreditation = jurisdiction_check(&result, "MAS_AI_001").unwrap();
+        assert!(!accreditation.passed);
+        assert!(matches!(accreditation.severity, ComplianceSeverity::Error));
+        assert!(!result.is_compliant);
+
+        // The USD 10,000 cooling period ceiling is exactly 5 ETH and includes it
+        let result = engine.comprehensive_compliance_check(&scope, "investor-1", "high_risk", 5 * ETH, "US", "officer").await.unwrap();
+        assert!(!jurisdiction_check(&result, "SEC_COOL_001").unwrap().passed);
+        let result = engine.comprehensive_compliance_check(&scope, "investor-1", "high_risk", 5 * ETH + 1, "US", "officer").await.unwrap();
+        assert_eq!(jurisdiction_check(&result, "SEC_COOL_001").unwrap().message_ref.id, "threshold.not_applicable");
+
+        // Stale rates neither pass nor block the investment
+        *rates.observed_at.lock().unwrap() = Utc::now() - Duration::hours(1);
+        let result = engine.comprehensive_compliance_check(&scope, "investor-1", "securities", 80 * ETH - 1, "SG", "officer").await.unwrap();
+        let accreditation = jurisdiction_check(&result, "MAS_AI_001").unwrap();
+        assert!(!accreditation.passed);
+        assert!(matches!(accreditation.severity, ComplianceSeverity::Warning));
+        assert_eq!(accreditation.message_ref.id, "threshold.rates_unavailable");
+        assert!(accreditation.rates.is_empty());
+        assert!(result.is_compliant);
+    }
+
+    struct FixedHoldings(Vec<concentration::Holding>);
+
+    #[async_trait::async_trait]
+    impl HoldingsSource for FixedHoldings {
+        async fn holdings(&self, _investor_id: &str) -> Result<Vec<concentration::Holding>, String> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_issuer_limit_binds_below_asset_type_limit() {
+        let scope = TenantScope::Tenant(TenantId::default());
+        let holdings = FixedHoldings(vec![concentration::Holding {
+            asset_id: "acme-bond".to_string(),
+            asset_type: Some("securities".to_string()),
+            issuer: Some("Acme".to_string()),
+            asset_class: Some("fixed_income".to_string()),
+            value: 800,
+        }]);
+        let mut engine = EnhancedComplianceEngine::new().with_holdings(Arc::new(holdings));
+        engine.grant_access("officer".to_string(), AccessLevel::Standard);
+        let limit = |scope: Option<LimitScope>, maximum_amount: u128| InvestmentLimit {
+            asset_type: "securities".to_string(),
+            maximum_amount,
+            current_exposure: 0,
+            reset_period: Duration::days(365),
+            last_reset: Utc::now(),
+            scope,
+        };
+        let mut investor = profile("EU");
+        investor.investment_limits = HashMap::from([
+            ("securities".to_string(), limit(None, 10_000)),
+            ("issuer:acme".to_string(), limit(Some(LimitScope::Issuer("Acme".to_string())), 1_000)),
+        ]);
+        engine.update_investor_profile(&scope, "investor-1".to_string(), investor, "officer").await.unwrap();
+
+        // 500 fits the asset type limit, but only 200 of the Acme limit is left
+        let acme = InvestmentTarget::new("securities").issued_by("Acme");
+        let result = engine.comprehensive_compliance_check_for(&scope, "investor-1", &acme, 500, "EU", "officer").await.unwrap();
+        let limits = jurisdiction_check(&result, "INVESTMENT_LIMITS").unwrap();
+        assert!(!limits.passed);
+        assert!(matches!(limits.severity, ComplianceSeverity::Error));
+        assert_eq!(limits.message_ref.params["scope"], "issuer Acme");
+        assert_eq!(limits.message_ref.params["remaining"], "200");
+        assert!(!result.is_compliant);
+
+        // Another issuer is only held to the asset type limit, and holdings results are not cached
+        let other = InvestmentTarget::new("securities").issued_by("Other");
+        let result = engine.comprehensive_compliance_check_for(&scope, "investor-1", &other, 500, "EU", "officer").await.unwrap();
+        let limits = jurisdiction_check(&result, "INVESTMENT_LIMITS").unwrap();
+        assert!(limits.passed);
+        assert_eq!(limits.message_ref.params["remaining"], "10000");
+        engine.comprehensive_compliance_check_for(&scope, "investor-1", &acme, 500, "EU", "officer").await.unwrap();
+        assert_eq!(engine.check_cache_stats().hits, 0);
+    }
+}
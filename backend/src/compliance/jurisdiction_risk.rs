@@ -0,0 +1,352 @@
+// Country risk tiers from FATF listings and the Basel AML Index
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tokio::sync::RwLock;
+use tracing::{info, error};
+
+use super::enhanced_compliance_engine::EnhancedComplianceEngine;
+use crate::task_health::TaskHealth;
+
+/// Basel AML Index score from which a jurisdiction counts as high risk (0-10 scale)
+pub const BASEL_HIGH_RISK_THRESHOLD: f64 = 6.5;
+
+/// Name the refresh task reports its health under
+pub const JURISDICTION_RISK_REFRESH_TASK: &str = "jurisdiction_risk_refresh";
+
+/// FATF listing of a jurisdiction
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FatfStatus {
+    NotListed,
+    /// Grey list
+    IncreasedMonitoring,
+    /// Black list
+    CallForAction,
+}
+
+impl FatfStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FatfStatus::NotListed => "not_listed",
+            FatfStatus::IncreasedMonitoring => "increased_monitoring",
+            FatfStatus::CallForAction => "call_for_action",
+        }
+    }
+}
+
+impl std::str::FromStr for FatfStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "not_listed" => Ok(FatfStatus::NotListed),
+            "increased_monitoring" => Ok(FatfStatus::IncreasedMonitoring),
+            "call_for_action" => Ok(FatfStatus::CallForAction),
+            other => Err(format!("Unknown FATF status: {}", other)),
+        }
+    }
+}
+
+/// Graduated compliance treatment of investors from a jurisdiction
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum JurisdictionRiskTier {
+    Standard,
+    /// Enhanced due diligence required
+    High,
+    /// Investment blocked
+    Blacklisted,
+}
+
+impl JurisdictionRiskTier {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JurisdictionRiskTier::Standard => "standard",
+            JurisdictionRiskTier::High => "high",
+            JurisdictionRiskTier::Blacklisted => "blacklisted",
+        }
+    }
+}
+
+impl std::str::FromStr for JurisdictionRiskTier {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "standard" => Ok(JurisdictionRiskTier::Standard),
+            "high" => Ok(JurisdictionRiskTier::High),
+            "blacklisted" => Ok(JurisdictionRiskTier::Blacklisted),
+            other => Err(format!("Unknown jurisdiction risk tier: {}", other)),
+        }
+    }
+}
+
+/// Risk data of one jurisdiction as provided by the configured source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JurisdictionRisk {
+    /// ISO 3166-1 alpha-2 code
+    pub country_code: String,
+    pub fatf_status: FatfStatus,
+    pub basel_aml_score: Option<f64>,
+    /// Platform policy that takes precedence over the FATF and Basel data
+    #[serde(default)]
+    pub tier_override: Option<JurisdictionRiskTier>,
+    #[serde(default = "Utc::now")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl JurisdictionRisk {
+    pub fn tier(&self) -> JurisdictionRiskTier {
+        if let Some(tier) = self.tier_override {
+            return tier;
+        }
+
+        match self.fatf_status {
+            FatfStatus::CallForAction => JurisdictionRiskTier::Blacklisted,
+            FatfStatus::IncreasedMonitoring => JurisdictionRiskTier::High,
+            FatfStatus::NotListed if self.basel_aml_score.map_or(false, |score| score >= BASEL_HIGH_RISK_THRESHOLD) => {
+                JurisdictionRiskTier::High
+            }
+            FatfStatus::NotListed => JurisdictionRiskTier::Standard,
+        }
+    }
+}
+
+/// Current tier of a jurisdiction, as exposed over the API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JurisdictionTier {
+    pub country_code: String,
+    pub tier: JurisdictionRiskTier,
+    pub fatf_status: FatfStatus,
+    pub basel_aml_score: Option<f64>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Country risk dataset keyed by country code; unlisted jurisdictions are Standard
+#[derive(Debug, Clone, Default)]
+pub struct JurisdictionRiskTable {
+    entries: HashMap<String, JurisdictionRisk>,
+}
+
+impl JurisdictionRiskTable {
+    pub fn new(entries: Vec<JurisdictionRisk>) -> Self {
+        Self {
+            entries: entries.into_iter()
+                .map(|entry| (entry.country_code.to_uppercase(), entry))
+                .collect(),
+        }
+    }
+
+    /// Dataset used until the configured source has been loaded
+    pub fn builtin() -> Self {
+        let entry = |code: &str, fatf_status, basel_aml_score, tier_override| JurisdictionRisk {
+            country_code: code.to_string(),
+            fatf_status,
+            basel_aml_score,
+            tier_override,
+            updated_at: Utc::now(),
+        };
+
+        Self::new(vec![
+            entry("KP", FatfStatus::CallForAction, None, None),
+            entry("IR", FatfStatus::CallForAction, None, None),
+            entry("MM", FatfStatus::CallForAction, Some(8.0), None),
+            // Restricted by platform policy regardless of AML listings
+            entry("CN", FatfStatus::NotListed, Some(5.0), Some(JurisdictionRiskTier::Blacklisted)),
+            entry("NG", FatfStatus::IncreasedMonitoring, Some(6.7), None),
+            entry("VN", FatfStatus::IncreasedMonitoring, Some(6.4), None),
+            entry("ZA", FatfStatus::IncreasedMonitoring, Some(5.9), None),
+            entry("HT", FatfStatus::IncreasedMonitoring, Some(8.2), None),
+            entry("MZ", FatfStatus::NotListed, Some(7.0), None),
+        ])
+    }
+
+    pub fn tier(&self, country_code: &str) -> JurisdictionRiskTier {
+        self.entries.get(&country_code.to_uppercase())
+            .map(|entry| entry.tier())
+            .unwrap_or(JurisdictionRiskTier::Standard)
+    }
+
+    pub fn get(&self, country_code: &str) -> Option<&JurisdictionRisk> {
+        self.entries.get(&country_code.to_uppercase())
+    }
+
+    /// Jurisdictions above the Standard tier, sorted by country code
+    pub fn tiers(&self) -> Vec<JurisdictionTier> {
+        let mut tiers: Vec<JurisdictionTier> = self.entries.values()
+            .filter(|entry| entry.tier() != JurisdictionRiskTier::Standard)
+            .map(|entry| JurisdictionTier {
+                country_code: entry.country_code.clone(),
+                tier: entry.tier(),
+                fatf_status: entry.fatf_status,
+                basel_aml_score: entry.basel_aml_score,
+                updated_at: entry.updated_at,
+            })
+            .collect();
+        tiers.sort_by(|a, b| a.country_code.cmp(&b.country_code));
+        tiers
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &JurisdictionRisk> {
+        self.entries.values()
+    }
+}
+
+// ============ Source & Refresh ============
+
+/// Load the dataset from `JURISDICTION_RISK_SOURCE`.
+///
+/// The source is an HTTP(S) URL or a file path serving a JSON array of `JurisdictionRisk`;
+/// without it the built-in dataset is used.
+pub async fn load_from_source() -> Result<JurisdictionRiskTable> {
+    let source = match std::env::var("JURISDICTION_RISK_SOURCE") {
+        Ok(source) if !source.trim().is_empty() => source,
+        _ => return Ok(JurisdictionRiskTable::builtin()),
+    };
+
+    let body = if source.starts_with("http://") || source.starts_with("https://") {
+        reqwest::get(&source).await?
+            .error_for_status()?
+            .text().await?
+    } else {
+        tokio::fs::read_to_string(&source).await?
+    };
+
+    let entries: Vec<JurisdictionRisk> = serde_json::from_str(&body)
+        .map_err(|e| anyhow!("Invalid jurisdiction risk data from {}: {}", source, e))?;
+    if entries.is_empty() {
+        return Err(anyhow!("Jurisdiction risk source {} returned no entries", source));
+    }
+
+    Ok(JurisdictionRiskTable::new(entries))
+}
+
+/// Replace the contents of the jurisdiction_risk table with the dataset
+pub async fn persist(db: &PgPool, table: &JurisdictionRiskTable) -> Result<()> {
+    let mut tx = db.begin().await?;
+
+    sqlx::query("DELETE FROM jurisdiction_risk")
+        .execute(&mut *tx)
+        .await?;
+
+    for entry in table.entries() {
+        sqlx::query(
+            r#"
+            INSERT INTO jurisdiction_risk (
+                country_code, fatf_status, basel_aml_score, tier_override, tier, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6)
+            "#
+        )
+        .bind(&entry.country_code)
+        .bind(entry.fatf_status.as_str())
+        .bind(entry.basel_aml_score)
+        .bind(entry.tier_override.map(|tier| tier.as_str()))
+        .bind(entry.tier().as_str())
+        .bind(entry.updated_at)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Read the last persisted dataset, e.g. to start from it when the source is unreachable
+pub async fn load_persisted(db: &PgPool) -> Result<JurisdictionRiskTable> {
+    let rows: Vec<(String, String, Option<f64>, Option<String>, DateTime<Utc>)> = sqlx::query_as(
+        "SELECT country_code, fatf_status, basel_aml_score, tier_override, updated_at FROM jurisdiction_risk"
+    )
+    .fetch_all(db)
+    .await?;
+
+    let entries = rows.into_iter()
+        .map(|(country_code, fatf_status, basel_aml_score, tier_override, updated_at)| {
+            Ok(JurisdictionRisk {
+                country_code,
+                fatf_status: fatf_status.parse().map_err(|e: String| anyhow!(e))?,
+                basel_aml_score,
+                tier_override: tier_override.map(|tier| tier.parse()).transpose().map_err(|e: String| anyhow!(e))?,
+                updated_at,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(JurisdictionRiskTable::new(entries))
+}
+
+/// Load the source, persist it and install it in the engine; checks use it from then on
+pub async fn refresh(db: &PgPool, engine: &RwLock<EnhancedComplianceEngine>) -> Result<usize> {
+    let table = load_from_source().await?;
+    persist(db, &table).await?;
+
+    let count = table.entries().count();
+    engine.write().await.set_jurisdiction_risk(table);
+    Ok(count)
+}
+
+/// Refresh the dataset now and then every `every`, keeping the last good data on failure
+pub fn spawn_jurisdiction_risk_refresh(
+    db: Arc<PgPool>,
+    engine: Arc<RwLock<EnhancedComplianceEngine>>,
+    health: Arc<TaskHealth>,
+    every: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        match load_persisted(db.as_ref()).await {
+            Ok(table) if table.entries().next().is_some() => engine.write().await.set_jurisdiction_risk(table),
+            Ok(_) => {}
+            Err(e) => error!("Failed to load persisted jurisdiction risk data: {}", e),
+        }
+
+        let mut ticker = tokio::time::interval(every);
+        loop {
+            ticker.tick().await;
+            match refresh(db.as_ref(), engine.as_ref()).await {
+                Ok(count) => {
+                    info!("Refreshed jurisdiction risk data for {} jurisdictions", count);
+                    health.record_success(JURISDICTION_RISK_REFRESH_TASK);
+                }
+                Err(e) => {
+                    error!("Jurisdiction risk refresh failed: {}", e);
+                    health.record_failure(JURISDICTION_RISK_REFRESH_TASK, e);
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(code: &str, fatf_status: FatfStatus, basel_aml_score: Option<f64>) -> JurisdictionRisk {
+        JurisdictionRisk {
+            country_code: code.to_string(),
+            fatf_status,
+            basel_aml_score,
+            tier_override: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_tier_classification() {
+        assert_eq!(entry("KP", FatfStatus::CallForAction, None).tier(), JurisdictionRiskTier::Blacklisted);
+        assert_eq!(entry("NG", FatfStatus::IncreasedMonitoring, Some(4.0)).tier(), JurisdictionRiskTier::High);
+        assert_eq!(entry("MZ", FatfStatus::NotListed, Some(BASEL_HIGH_RISK_THRESHOLD)).tier(), JurisdictionRiskTier::High);
+        assert_eq!(entry("FI", FatfStatus::NotListed, Some(3.1)).tier(), JurisdictionRiskTier::Standard);
+
+        let mut overridden = entry("CN", FatfStatus::NotListed, Some(5.0));
+        overridden.tier_override = Some(JurisdictionRiskTier::Blacklisted);
+        assert_eq!(overridden.tier(), JurisdictionRiskTier::Blacklisted);
+
+        let table = JurisdictionRiskTable::new(vec![entry("kp", FatfStatus::CallForAction, None)]);
+        assert_eq!(table.tier("KP"), JurisdictionRiskTier::Blacklisted);
+        assert_eq!(table.tier("DE"), JurisdictionRiskTier::Standard);
+    }
+}
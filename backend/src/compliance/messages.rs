@@ -0,0 +1,394 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use tracing::warn;
+
+/// Locale every message has a template in; anything missing elsewhere falls back to it
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// A message by catalog id with its parameters. Check results store these rather than text
+/// so a historical report can be rendered again in any locale.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageRef {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, String>,
+}
+
+impl MessageRef {
+    pub fn new(id: &str) -> Self {
+        Self { id: id.to_string(), params: BTreeMap::new() }
+    }
+
+    pub fn param(mut self, name: &str, value: impl ToString) -> Self {
+        self.params.insert(name.to_string(), value.to_string());
+        self
+    }
+}
+
+/// Renders of a message in a locale that had to fall back to English
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TranslationGap {
+    pub locale: String,
+    pub message_id: String,
+    pub misses: u64,
+}
+
+/// Per-locale message templates. Templates name parameters in braces, e.g. `{days}`.
+pub struct MessageCatalog {
+    templates: HashMap<String, HashMap<String, String>>,
+    gaps: Mutex<HashMap<(String, String), u64>>,
+}
+
+impl MessageCatalog {
+    pub fn new() -> Self {
+        Self { templates: HashMap::new(), gaps: Mutex::new(HashMap::new()) }
+    }
+
+    /// English, German and French templates for every compliance check message
+    pub fn builtin() -> Self {
+        let mut catalog = Self::new();
+        catalog.add_locale("en", EN.iter().copied());
+        catalog.add_locale("de", DE.iter().copied());
+        catalog.add_locale("fr", FR.iter().copied());
+        catalog
+    }
+
+    /// Add templates to a locale, replacing any with the same id
+    pub fn add_locale<'a>(&mut self, locale: &str, templates: impl IntoIterator<Item = (&'a str, &'a str)>) {
+        let entry = self.templates.entry(locale.to_lowercase()).or_default();
+        for (id, template) in templates {
+            entry.insert(id.to_string(), template.to_string());
+        }
+    }
+
+    /// Catalog locale for a requested tag: an exact match, else its primary language
+    pub fn resolve_locale(&self, requested: &str) -> Option<&str> {
+        let requested = requested.trim().to_lowercase();
+        let language = requested.split(['-', '_']).next().unwrap_or_default();
+        [requested.as_str(), language].into_iter()
+            .find_map(|tag| self.templates.get_key_value(tag).map(|(locale, _)| locale.as_str()))
+    }
+
+    /// Locale to render in: an explicit locale parameter wins, then the best supported entry
+    /// of an Accept-Language header by quality, then English
+    pub fn negotiate(&self, requested: Option<&str>, accept_language: Option<&str>) -> String {
+        if let Some(locale) = requested.and_then(|requested| self.resolve_locale(requested)) {
+            return locale.to_string();
+        }
+
+        let mut ranges: Vec<(&str, f32)> = accept_language.unwrap_or_default()
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|part| part.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+                (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+            })
+            .collect();
+        // Stable, so equally weighted ranges keep the client's order
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        ranges.into_iter()
+            .find_map(|(tag, _)| self.resolve_locale(tag))
+            .unwrap_or(DEFAULT_LOCALE)
+            .to_string()
+    }
+
+    /// Render a message in a locale, falling back to English and counting the gap when the
+    /// locale has no template for it. Unknown ids render as the id itself.
+    pub fn render(&self, message: &MessageRef, locale: &str) -> String {
+        let locale = self.resolve_locale(locale).unwrap_or(locale).to_string();
+        let template = self.template(&locale, &message.id).or_else(|| {
+            self.record_gap(&locale, &message.id);
+            if locale == DEFAULT_LOCALE { None } else { self.template(DEFAULT_LOCALE, &message.id) }
+        });
+        match template {
+            Some(template) => interpolate(template, &message.params),
+            None => message.id.clone(),
+        }
+    }
+
+    /// Messages rendered in English because their locale lacked a template, most missed first
+    pub fn gaps(&self) -> Vec<TranslationGap> {
+        let gaps = self.gaps.lock().unwrap_or_else(|e| e.into_inner());
+        let mut gaps: Vec<TranslationGap> = gaps.iter()
+            .map(|((locale, message_id), misses)| TranslationGap {
+                locale: locale.clone(),
+                message_id: message_id.clone(),
+                misses: *misses,
+            })
+            .collect();
+        gaps.sort_by(|a, b| b.misses.cmp(&a.misses).then_with(|| (&a.locale, &a.message_id).cmp(&(&b.locale, &b.message_id))));
+        gaps
+    }
+
+    fn template(&self, locale: &str, id: &str) -> Option<&str> {
+        self.templates.get(locale).and_then(|templates| templates.get(id)).map(String::as_str)
+    }
+
+    fn record_gap(&self, locale: &str, id: &str) {
+        let mut gaps = self.gaps.lock().unwrap_or_else(|e| e.into_inner());
+        let misses = gaps.entry((locale.to_string(), id.to_string())).or_default();
+        if *misses == 0 {
+            warn!("No {} translation for compliance message {}", locale, id);
+        }
+        *misses += 1;
+    }
+}
+
+impl Default for MessageCatalog {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+/// Substitute `{name}` placeholders; unknown placeholders are left as written
+fn interpolate(template: &str, params: &BTreeMap<String, String>) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else { break };
+        let name = &rest[start + 1..start + end];
+        match params.get(name) {
+            Some(value) => rendered.push_str(value),
+            None => rendered.push_str(&rest[start..=start + end]),
+        }
+        rest = &rest[start + end + 1..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+const EN: &[(&str, &str)] = &[
+    ("kyc.status", "KYC verification status: {status}"),
+    ("kyc.remediation", "Complete KYC verification process"),
+    ("aml.status", "AML screening status: {status}"),
+    ("aml.remediation", "Complete AML screening process"),
+    ("accreditation.status", "Accredited investor status: {status}"),
+    ("accreditation.remediation", "Provide accredited investor documentation"),
+    ("investment_limit.remaining", "Investment limit check: {remaining} / {limit} remaining"),
+    ("investment_limit.remediation", "Reduce investment amount or wait for limit reset"),
+    ("investment_limit.missing", "No investment limit configured for asset type"),
+    ("investment_limit.configure", "Configure investment limits"),
+    ("investment_limit.exceeded", "Investment exceeds the {scope} limit: {remaining} / {limit} remaining"),
+    ("investment_limit.exposure_unavailable", "Exposure could not be determined for limits on: {scopes}"),
+    ("investment_limit.sync_holdings", "Sync portfolio holdings and repeat the check"),
+    ("cooling_period.elapsed", "Cooling period check: {days} days since last investment"),
+    ("cooling_period.remediation", "Wait {days_remaining} more days before next investment"),
+    ("cooling_period.first_investment", "First investment in asset type"),
+    ("cooling_period.not_required", "No cooling period required"),
+    ("sanctions.status", "Sanctions screening status: {status}"),
+    ("sanctions.remediation", "Complete sanctions screening process"),
+    ("qualified_investor.status", "Qualified investor status: {investor_type}"),
+    ("qualified_investor.remediation", "Obtain qualified investor certification"),
+    ("professional_investor.status", "Professional investor status: {investor_type}"),
+    ("professional_investor.remediation", "Obtain professional investor classification"),
+    ("institutional_investor.status", "Institutional investor status: {investor_type}"),
+    ("institutional_investor.remediation", "Provide institutional investor documentation"),
+    ("tax_residency.status", "Tax residency verification: {count} jurisdictions"),
+    ("tax_residency.remediation", "Provide tax residency documentation"),
+    ("suitability.prohibited", "Suitability assessment: {risk_rating} risk rating"),
+    ("suitability.risk_rating", "Suitability assessment: {risk_rating} risk rating for {asset_type} asset"),
+    ("suitability.appropriateness", "Appropriateness assessment: {max_complexity} products appropriate, {asset_type} asset is {complexity}"),
+    ("suitability.questionnaire_missing", "Suitability assessment: {risk_rating} risk rating for {asset_type} asset; appropriateness questionnaire has not been completed"),
+    ("suitability.questionnaire_stale", "Suitability assessment: {risk_rating} risk rating for {asset_type} asset; appropriateness questionnaire is out of date"),
+    ("suitability.questionnaire_remediation", "Complete the appropriateness questionnaire"),
+    ("suitability.remediation", "Complete suitability assessment or choose appropriate asset type"),
+    ("geographic.status", "Geographic restriction check for jurisdiction: {jurisdiction}"),
+    ("jurisdiction.not_permitted", "Investment not permitted from this jurisdiction"),
+    ("risk.jurisdiction_blocked", "Jurisdiction {jurisdiction} is blacklisted"),
+    ("risk.jurisdiction_edd", "High-risk jurisdiction {jurisdiction} requires enhanced due diligence"),
+    ("risk.jurisdiction_edd_remediation", "Complete enhanced due diligence for high-risk jurisdiction"),
+    ("risk.high_value", "High-value transaction requires institutional or accredited investor status"),
+    ("risk.high_value_remediation", "Verify institutional or accredited investor status"),
+    ("risk.low_score", "Low compliance score: {score}/100"),
+    ("risk.low_score_remediation", "Improve compliance score through additional verification"),
+    ("risk.stale_profile", "Profile last updated {days} days ago"),
+    ("risk.stale_profile_remediation", "Update investor profile information"),
+    ("threshold.not_applicable", "Investment amount is outside the statutory threshold; requirement does not apply"),
+    ("threshold.rates_unavailable", "Investment could not be valued in {currency} for the statutory threshold: {reason}"),
+    ("threshold.rates_remediation", "Repeat the check once current price and exchange rates are available"),
+    ("recommendation.critical_failures", "Address critical compliance failures immediately"),
+    ("recommendation.comprehensive_review", "Consider comprehensive compliance review"),
+    ("recommendation.complete_kyc", "Complete KYC verification to improve compliance standing"),
+    ("recommendation.complete_aml", "Complete AML screening to ensure regulatory compliance"),
+    ("eligibility.jurisdiction.permitted", "Offered to investors in {jurisdiction}"),
+    ("eligibility.jurisdiction.not_permitted", "Not offered to investors in {jurisdiction}"),
+    ("eligibility.jurisdiction.remediation", "Offered only to investors in: {allowed}"),
+    ("eligibility.investor_type.permitted", "Open to {investor_type} investors"),
+    ("eligibility.investor_type.not_permitted", "Not open to {investor_type} investors"),
+    ("eligibility.investor_type.remediation", "Obtain classification as one of: {allowed}"),
+    ("eligibility.kyc_tier.within_cap", "KYC tier {tier} permits investments up to {cap}; the minimum investment is {minimum}"),
+    ("eligibility.kyc_tier.below_minimum", "KYC tier {tier} caps investments at {cap}, below the minimum investment of {minimum}"),
+    ("eligibility.kyc_tier.uncapped", "KYC tier {tier} has no investment cap; the minimum investment is {minimum}"),
+    ("eligibility.kyc_tier.unverified", "No investment is permitted until KYC verification is complete"),
+    ("eligibility.kyc_tier.remediation", "Upgrade verification to a tier permitting investments of {minimum}"),
+    ("eligibility.onboarding.create_profile", "Create an investor profile"),
+];
+
+const DE: &[(&str, &str)] = &[
+    ("kyc.status", "Status der KYC-Prüfung: {status}"),
+    ("kyc.remediation", "KYC-Prüfung abschließen"),
+    ("aml.status", "Status der Geldwäscheprüfung: {status}"),
+    ("aml.remediation", "Geldwäscheprüfung abschließen"),
+    ("accreditation.status", "Status als akkreditierter Anleger: {status}"),
+    ("accreditation.remediation", "Nachweise als akkreditierter Anleger einreichen"),
+    ("investment_limit.remaining", "Anlagegrenze: {remaining} von {limit} verfügbar"),
+    ("investment_limit.remediation", "Anlagebetrag verringern oder Zurücksetzen der Anlagegrenze abwarten"),
+    ("investment_limit.missing", "Für diese Anlageklasse ist keine Anlagegrenze konfiguriert"),
+    ("investment_limit.configure", "Anlagegrenzen konfigurieren"),
+    ("investment_limit.exceeded", "Anlage überschreitet die Grenze für {scope}: {remaining} von {limit} verfügbar"),
+    ("investment_limit.exposure_unavailable", "Bestand konnte nicht ermittelt werden für Grenzen auf: {scopes}"),
+    ("investment_limit.sync_holdings", "Portfoliobestände synchronisieren und Prüfung wiederholen"),
+    ("cooling_period.elapsed", "Karenzzeit: {days} Tage seit der letzten Anlage"),
+    ("cooling_period.remediation", "Noch {days_remaining} Tage bis zur nächsten Anlage warten"),
+    ("cooling_period.first_investment", "Erste Anlage in dieser Anlageklasse"),
+    ("cooling_period.not_required", "Keine Karenzzeit erforderlich"),
+    ("sanctions.status", "Status der Sanktionsprüfung: {status}"),
+    ("sanctions.remediation", "Sanktionsprüfung abschließen"),
+    ("qualified_investor.status", "Status als qualifizierter Anleger: {investor_type}"),
+    ("qualified_investor.remediation", "Zertifizierung als qualifizierter Anleger einholen"),
+    ("professional_investor.status", "Status als professioneller Kunde: {investor_type}"),
+    ("professional_investor.remediation", "Einstufung als professioneller Kunde beantragen"),
+    ("institutional_investor.status", "Status als institutioneller Anleger: {investor_type}"),
+    ("institutional_investor.remediation", "Nachweise als institutioneller Anleger einreichen"),
+    ("tax_residency.status", "Prüfung der steuerlichen Ansässigkeit: {count} Rechtsordnungen"),
+    ("tax_residency.remediation", "Nachweise zur steuerlichen Ansässigkeit einreichen"),
+    ("suitability.prohibited", "Eignungsprüfung: Risikoeinstufung {risk_rating}"),
+    ("suitability.risk_rating", "Eignungsprüfung: Risikoeinstufung {risk_rating} für Anlageklasse {asset_type}"),
+    ("suitability.appropriateness", "Angemessenheitsprüfung: Produkte bis {max_complexity} angemessen, Anlageklasse {asset_type} ist {complexity}"),
+    ("suitability.questionnaire_missing", "Eignungsprüfung: Risikoeinstufung {risk_rating} für Anlageklasse {asset_type}; Angemessenheitsfragebogen wurde nicht ausgefüllt"),
+    ("suitability.questionnaire_stale", "Eignungsprüfung: Risikoeinstufung {risk_rating} für Anlageklasse {asset_type}; Angemessenheitsfragebogen ist veraltet"),
+    ("suitability.questionnaire_remediation", "Angemessenheitsfragebogen ausfüllen"),
+    ("suitability.remediation", "Eignungsprüfung abschließen oder eine geeignete Anlageklasse wählen"),
+    ("geographic.status", "Prüfung geografischer Beschränkungen für Rechtsordnung: {jurisdiction}"),
+    ("jurisdiction.not_permitted", "Anlagen aus dieser Rechtsordnung sind nicht zulässig"),
+    ("risk.jurisdiction_blocked", "Rechtsordnung {jurisdiction} steht auf der schwarzen Liste"),
+    ("risk.jurisdiction_edd", "Hochrisiko-Rechtsordnung {jurisdiction} erfordert verstärkte Sorgfaltspflichten"),
+    ("risk.jurisdiction_edd_remediation", "Verstärkte Sorgfaltsprüfung für Hochrisiko-Rechtsordnung abschließen"),
+    ("risk.high_value", "Transaktionen mit hohem Wert erfordern den Status als institutioneller oder akkreditierter Anleger"),
+    ("risk.high_value_remediation", "Status als institutioneller oder akkreditierter Anleger nachweisen"),
+    ("risk.low_score", "Niedriger Compliance-Wert: {score}/100"),
+    ("risk.low_score_remediation", "Compliance-Wert durch zusätzliche Verifizierung verbessern"),
+    ("risk.stale_profile", "Profil zuletzt vor {days} Tagen aktualisiert"),
+    ("risk.stale_profile_remediation", "Anlegerprofil aktualisieren"),
+    ("threshold.not_applicable", "Anlagebetrag liegt außerhalb des gesetzlichen Schwellenwerts; Anforderung gilt nicht"),
+    ("threshold.rates_unavailable", "Anlage konnte für den gesetzlichen Schwellenwert nicht in {currency} bewertet werden: {reason}"),
+    ("threshold.rates_remediation", "Prüfung wiederholen, sobald aktuelle Preise und Wechselkurse vorliegen"),
+    ("recommendation.critical_failures", "Kritische Compliance-Verstöße umgehend beheben"),
+    ("recommendation.comprehensive_review", "Umfassende Compliance-Überprüfung erwägen"),
+    ("recommendation.complete_kyc", "KYC-Prüfung abschließen, um den Compliance-Status zu verbessern"),
+    ("recommendation.complete_aml", "Geldwäscheprüfung abschließen, um die regulatorischen Anforderungen zu erfüllen"),
+    ("eligibility.jurisdiction.permitted", "Angeboten für Anleger in {jurisdiction}"),
+    ("eligibility.jurisdiction.not_permitted", "Nicht angeboten für Anleger in {jurisdiction}"),
+    ("eligibility.jurisdiction.remediation", "Nur angeboten für Anleger in: {allowed}"),
+    ("eligibility.investor_type.permitted", "Offen für Anleger der Kategorie {investor_type}"),
+    ("eligibility.investor_type.not_permitted", "Nicht offen für Anleger der Kategorie {investor_type}"),
+    ("eligibility.investor_type.remediation", "Einstufung in eine dieser Kategorien beantragen: {allowed}"),
+    ("eligibility.kyc_tier.within_cap", "KYC-Stufe {tier} erlaubt Anlagen bis {cap}; die Mindestanlage beträgt {minimum}"),
+    ("eligibility.kyc_tier.below_minimum", "KYC-Stufe {tier} begrenzt Anlagen auf {cap}, unter der Mindestanlage von {minimum}"),
+    ("eligibility.kyc_tier.uncapped", "KYC-Stufe {tier} hat keine Anlagegrenze; die Mindestanlage beträgt {minimum}"),
+    ("eligibility.kyc_tier.unverified", "Anlagen sind erst nach abgeschlossener KYC-Prüfung zulässig"),
+    ("eligibility.kyc_tier.remediation", "Verifizierung auf eine Stufe erweitern, die Anlagen von {minimum} erlaubt"),
+    ("eligibility.onboarding.create_profile", "Anlegerprofil anlegen"),
+];
+
+const FR: &[(&str, &str)] = &[
+    ("kyc.status", "Statut de la vérification KYC : {status}"),
+    ("kyc.remediation", "Finaliser la vérification KYC"),
+    ("aml.status", "Statut du contrôle LCB-FT : {status}"),
+    ("aml.remediation", "Finaliser le contrôle LCB-FT"),
+    ("accreditation.status", "Statut d'investisseur accrédité : {status}"),
+    ("accreditation.remediation", "Fournir les justificatifs d'investisseur accrédité"),
+    ("investment_limit.remaining", "Plafond d'investissement : {remaining} disponible sur {limit}"),
+    ("investment_limit.remediation", "Réduire le montant investi ou attendre la réinitialisation du plafond"),
+    ("investment_limit.missing", "Aucun plafond d'investissement configuré pour ce type d'actif"),
+    ("investment_limit.configure", "Configurer les plafonds d'investissement"),
+    ("investment_limit.exceeded", "L'investissement dépasse le plafond {scope} : {remaining} disponible sur {limit}"),
+    ("investment_limit.exposure_unavailable", "Exposition indéterminée pour les plafonds : {scopes}"),
+    ("investment_limit.sync_holdings", "Synchroniser les positions du portefeuille et relancer la vérification"),
+    ("cooling_period.elapsed", "Délai de réflexion : {days} jours depuis le dernier investissement"),
+    ("cooling_period.remediation", "Attendre encore {days_remaining} jours avant le prochain investissement"),
+    ("cooling_period.first_investment", "Premier investissement dans ce type d'actif"),
+    ("cooling_period.not_required", "Aucun délai de réflexion requis"),
+    ("sanctions.status", "Statut du filtrage des sanctions : {status}"),
+    ("sanctions.remediation", "Finaliser le filtrage des sanctions"),
+    ("qualified_investor.status", "Statut d'investisseur qualifié : {investor_type}"),
+    ("qualified_investor.remediation", "Obtenir la certification d'investisseur qualifié"),
+    ("professional_investor.status", "Statut de client professionnel : {investor_type}"),
+    ("professional_investor.remediation", "Obtenir la classification de client professionnel"),
+    ("institutional_investor.status", "Statut d'investisseur institutionnel : {investor_type}"),
+    ("institutional_investor.remediation", "Fournir les justificatifs d'investisseur institutionnel"),
+    ("tax_residency.status", "Vérification de la résidence fiscale : {count} juridictions"),
+    ("tax_residency.remediation", "Fournir les justificatifs de résidence fiscale"),
+    ("suitability.prohibited", "Évaluation d'adéquation : notation de risque {risk_rating}"),
+    ("suitability.risk_rating", "Évaluation d'adéquation : notation de risque {risk_rating} pour l'actif {asset_type}"),
+    ("suitability.appropriateness", "Évaluation du caractère approprié : produits jusqu'à {max_complexity} appropriés, l'actif {asset_type} est {complexity}"),
+    ("suitability.questionnaire_missing", "Évaluation d'adéquation : notation de risque {risk_rating} pour l'actif {asset_type} ; le questionnaire de caractère approprié n'a pas été rempli"),
+    ("suitability.questionnaire_stale", "Évaluation d'adéquation : notation de risque {risk_rating} pour l'actif {asset_type} ; le questionnaire de caractère approprié n'est plus à jour"),
+    ("suitability.questionnaire_remediation", "Remplir le questionnaire de caractère approprié"),
+    ("suitability.remediation", "Finaliser l'évaluation d'adéquation ou choisir un type d'actif approprié"),
+    ("geographic.status", "Contrôle des restrictions géographiques pour la juridiction : {jurisdiction}"),
+    ("jurisdiction.not_permitted", "Investissement non autorisé depuis cette juridiction"),
+    ("risk.jurisdiction_blocked", "La juridiction {jurisdiction} figure sur la liste noire"),
+    ("risk.jurisdiction_edd", "La juridiction à haut risque {jurisdiction} exige des mesures de vigilance renforcées"),
+    ("risk.jurisdiction_edd_remediation", "Finaliser les mesures de vigilance renforcées pour la juridiction à haut risque"),
+    ("risk.high_value", "Les transactions de montant élevé exigent le statut d'investisseur institutionnel ou accrédité"),
+    ("risk.high_value_remediation", "Justifier du statut d'investisseur institutionnel ou accrédité"),
+    ("risk.low_score", "Score de conformité faible : {score}/100"),
+    ("risk.low_score_remediation", "Améliorer le score de conformité par des vérifications complémentaires"),
+    ("risk.stale_profile", "Profil mis à jour il y a {days} jours"),
+    ("risk.stale_profile_remediation", "Mettre à jour le profil investisseur"),
+    ("threshold.not_applicable", "Le montant investi est hors du seuil réglementaire ; l'exigence ne s'applique pas"),
+    ("threshold.rates_unavailable", "L'investissement n'a pas pu être évalué en {currency} pour le seuil réglementaire : {reason}"),
+    ("threshold.rates_remediation", "Relancer la vérification lorsque les prix et taux de change actuels sont disponibles"),
+    ("recommendation.critical_failures", "Traiter immédiatement les manquements critiques à la conformité"),
+    ("recommendation.comprehensive_review", "Envisager une revue complète de la conformité"),
+    ("recommendation.complete_kyc", "Finaliser la vérification KYC pour améliorer la situation de conformité"),
+    ("recommendation.complete_aml", "Finaliser le contrôle LCB-FT pour respecter les exigences réglementaires"),
+    ("eligibility.jurisdiction.permitted", "Proposé aux investisseurs de {jurisdiction}"),
+    ("eligibility.jurisdiction.not_permitted", "Non proposé aux investisseurs de {jurisdiction}"),
+    ("eligibility.jurisdiction.remediation", "Proposé uniquement aux investisseurs de : {allowed}"),
+    ("eligibility.investor_type.permitted", "Ouvert aux investisseurs de catégorie {investor_type}"),
+    ("eligibility.investor_type.not_permitted", "Non ouvert aux investisseurs de catégorie {investor_type}"),
+    ("eligibility.investor_type.remediation", "Obtenir une classification parmi : {allowed}"),
+    ("eligibility.kyc_tier.within_cap", "Le niveau KYC {tier} permet d'investir jusqu'à {cap} ; l'investissement minimum est de {minimum}"),
+    ("eligibility.kyc_tier.below_minimum", "Le niveau KYC {tier} plafonne les investissements à {cap}, en dessous de l'investissement minimum de {minimum}"),
+    ("eligibility.kyc_tier.uncapped", "Le niveau KYC {tier} n'a pas de plafond ; l'investissement minimum est de {minimum}"),
+    ("eligibility.kyc_tier.unverified", "Aucun investissement n'est possible avant la fin de la vérification KYC"),
+    ("eligibility.kyc_tier.remediation", "Passer à un niveau de vérification permettant d'investir {minimum}"),
+    ("eligibility.onboarding.create_profile", "Créer un profil investisseur"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiation_and_fallback_gaps() {
+        let mut catalog = MessageCatalog::builtin();
+        catalog.add_locale("es", [("kyc.status", "Estado de verificación KYC: {status}")]);
+
+        assert_eq!(catalog.negotiate(Some("fr-CA"), Some("de")), "fr");
+        assert_eq!(catalog.negotiate(None, Some("ja, de-AT;q=0.8, fr;q=0.9")), "fr");
+        assert_eq!(catalog.negotiate(Some("xx"), Some("ja, fr;q=0")), DEFAULT_LOCALE);
+
+        let remediation = MessageRef::new("kyc.remediation");
+        assert_eq!(catalog.render(&remediation, "es"), "Complete KYC verification process");
+        catalog.render(&remediation, "es");
+        assert_eq!(catalog.render(&MessageRef::new("kyc.status").param("status", "Pending"), "es"), "Estado de verificación KYC: Pending");
+        assert_eq!(catalog.gaps(), vec![TranslationGap { locale: "es".to_string(), message_id: "kyc.remediation".to_string(), misses: 2 }]);
+    }
+
+    #[test]
+    fn test_every_message_is_translated() {
+        for locale in [DE, FR] {
+            let ids: Vec<&str> = locale.iter().map(|(id, _)| *id).collect();
+            assert_eq!(ids, EN.iter().map(|(id, _)| *id).collect::<Vec<_>>());
+        }
+    }
+}
@@ -1 +1,8 @@
-pub mod enhanced_compliance_engine; 
\ No newline at end of file
+pub mod enhanced_compliance_engine; 
+pub mod jurisdiction_risk;
+pub mod appropriateness;
+pub mod check_cache;
+pub mod concentration;
+pub mod eligibility;
+pub mod messages;
+pub mod thresholds;
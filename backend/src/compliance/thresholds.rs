@@ -0,0 +1,351 @@
+// Fiat-denominated investment thresholds, evaluated at check-time price and FX rates
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use price_oracle::OracleAggregator;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Currencies statutory thresholds are expressed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum FiatCurrency {
+    USD,
+    EUR,
+    SGD,
+}
+
+impl std::fmt::Display for FiatCurrency {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// A statutory amount in the currency the framework defines it in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MonetaryThreshold {
+    pub amount: Decimal,
+    pub currency: FiatCurrency,
+}
+
+impl MonetaryThreshold {
+    pub fn new(amount: i64, currency: FiatCurrency) -> Self {
+        Self { amount: Decimal::from(amount), currency }
+    }
+}
+
+/// A rate as reported by its source
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateObservation {
+    pub rate: Decimal,
+    pub observed_at: DateTime<Utc>,
+    pub source: String,
+}
+
+/// A rate a check was evaluated with, kept in the check result for auditability
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppliedRate {
+    /// `ETH/USD` for the asset price, `USD/SGD` for FX
+    pub pair: String,
+    pub rate: Decimal,
+    pub observed_at: DateTime<Utc>,
+    pub source: String,
+}
+
+/// Price and FX rates used to value investments in fiat
+#[async_trait]
+pub trait ConversionRates: Send + Sync {
+    /// USD per whole unit of `asset`
+    async fn asset_price_usd(&self, asset: &str) -> Result<RateObservation, String>;
+
+    /// Units of `currency` per USD
+    async fn usd_fx_rate(&self, currency: FiatCurrency) -> Result<RateObservation, String>;
+}
+
+/// Rates from the price aggregator; FX rates are quoted under keys like `USDSGD`
+pub struct OracleConversionRates {
+    prices: Arc<OracleAggregator>,
+}
+
+impl OracleConversionRates {
+    pub fn new(prices: Arc<OracleAggregator>) -> Self {
+        Self { prices }
+    }
+
+    async fn aggregated(&self, key: &str) -> Result<RateObservation, String> {
+        let price = self.prices.price(key).await.map_err(|e| e.to_string())?;
+        if price.disputed {
+            return Err(format!("price sources disagree on {}", key));
+        }
+        // A consensus price is only as fresh as its oldest input
+        let observed_at = price.sources.iter()
+            .map(|quote| quote.observed_at)
+            .min()
+            .unwrap_or(price.aggregated_at);
+        let source = price.sources.iter().map(|quote| quote.source.as_str()).collect::<Vec<_>>().join(",");
+        Ok(RateObservation { rate: price.price, observed_at, source })
+    }
+}
+
+#[async_trait]
+impl ConversionRates for OracleConversionRates {
+    async fn asset_price_usd(&self, asset: &str) -> Result<RateObservation, String> {
+        self.aggregated(asset).await
+    }
+
+    async fn usd_fx_rate(&self, currency: FiatCurrency) -> Result<RateObservation, String> {
+        match currency {
+            FiatCurrency::USD => Ok(RateObservation { rate: Decimal::ONE, observed_at: Utc::now(), source: "identity".to_string() }),
+            currency => self.aggregated(&format!("USD{}", currency)).await,
+        }
+    }
+}
+
+/// What investment amounts are denominated in and how old a rate may be
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThresholdConfig {
+    /// Asset investment amounts are settled in, as keyed in the price aggregator
+    pub settlement_asset: String,
+    /// Decimals of the settlement asset's base unit
+    pub settlement_decimals: u32,
+    /// Rates observed longer ago than this are not used
+    pub max_rate_age: Duration,
+}
+
+impl Default for ThresholdConfig {
+    fn default() -> Self {
+        Self {
+            settlement_asset: "ETH".to_string(),
+            settlement_decimals: 18,
+            max_rate_age: Duration::minutes(15),
+        }
+    }
+}
+
+impl ThresholdConfig {
+    /// Read from COMPLIANCE_SETTLEMENT_ASSET, COMPLIANCE_SETTLEMENT_DECIMALS and
+    /// COMPLIANCE_RATE_MAX_AGE_SECS, defaulting to ETH, 18 and 900
+    pub fn from_env() -> Result<Self, String> {
+        let defaults = Self::default();
+        let number = |name: &str, default: i64| -> Result<i64, String> {
+            match std::env::var(name) {
+                Ok(value) if !value.is_empty() => value.parse().map_err(|_| format!("Invalid {}: {}", name, value)),
+                _ => Ok(default),
+            }
+        };
+        Ok(Self {
+            settlement_asset: std::env::var("COMPLIANCE_SETTLEMENT_ASSET")
+                .ok()
+                .filter(|asset| !asset.is_empty())
+                .unwrap_or(defaults.settlement_asset),
+            settlement_decimals: number("COMPLIANCE_SETTLEMENT_DECIMALS", defaults.settlement_decimals as i64)? as u32,
+            max_rate_age: Duration::seconds(number("COMPLIANCE_RATE_MAX_AGE_SECS", defaults.max_rate_age.num_seconds())?),
+        })
+    }
+}
+
+/// Whether a requirement applies to an investment of a given value
+#[derive(Debug, Clone, PartialEq)]
+pub enum ThresholdOutcome {
+    Applies,
+    NotApplicable,
+    /// The investment could not be valued in the threshold's currency
+    RatesUnavailable { currency: FiatCurrency, reason: String },
+}
+
+/// Rates for one currency: the settlement asset price and the USD FX rate
+#[derive(Debug, Clone)]
+struct CurrencyRates {
+    asset_price: AppliedRate,
+    fx: AppliedRate,
+}
+
+impl CurrencyRates {
+    /// Currency units per whole settlement asset unit
+    fn price(&self) -> Decimal {
+        self.asset_price.rate * self.fx.rate
+    }
+}
+
+/// An investment amount valued in every currency a jurisdiction's thresholds use
+#[derive(Debug, Clone)]
+pub struct Valuation {
+    amount: u128,
+    decimals: u32,
+    rates: BTreeMap<FiatCurrency, Result<CurrencyRates, String>>,
+}
+
+impl Valuation {
+    /// Look up the rates for `currencies`; stale or missing rates are recorded per currency
+    pub async fn at_current_rates(
+        rates: Option<&dyn ConversionRates>,
+        config: &ThresholdConfig,
+        amount: u128,
+        currencies: impl IntoIterator<Item = FiatCurrency>,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let fresh = |pair: String, observation: Result<RateObservation, String>| -> Result<AppliedRate, String> {
+            let observation = observation?;
+            if now - observation.observed_at > config.max_rate_age {
+                return Err(format!("{} rate from {} is stale", pair, observation.observed_at));
+            }
+            Ok(AppliedRate { pair, rate: observation.rate, observed_at: observation.observed_at, source: observation.source })
+        };
+
+        let mut valuation = Self { amount, decimals: config.settlement_decimals, rates: BTreeMap::new() };
+        let currencies: Vec<FiatCurrency> = currencies.into_iter().collect();
+        if currencies.is_empty() {
+            return valuation;
+        }
+        let Some(rates) = rates else {
+            for currency in currencies {
+                valuation.rates.insert(currency, Err("no price or FX rate source is configured".to_string()));
+            }
+            return valuation;
+        };
+
+        let asset = &config.settlement_asset;
+        let asset_price = fresh(format!("{}/USD", asset), rates.asset_price_usd(asset).await);
+        for currency in currencies {
+            let entry = match &asset_price {
+                Ok(asset_price) => fresh(format!("USD/{}", currency), rates.usd_fx_rate(currency).await)
+                    .map(|fx| CurrencyRates { asset_price: asset_price.clone(), fx }),
+                Err(reason) => Err(reason.clone()),
+            };
+            valuation.rates.insert(currency, entry);
+        }
+        valuation
+    }
+
+    /// Investment value in `currency`
+    fn value_in(&self, currency: FiatCurrency) -> Result<Decimal, String> {
+        let rates = self.rates.get(&currency)
+            .ok_or_else(|| format!("no {} rate was requested", currency))?
+            .as_ref()
+            .map_err(Clone::clone)?;
+        let units = i128::try_from(self.amount).ok()
+            .and_then(|amount| Decimal::try_from_i128_with_scale(amount, self.decimals).ok())
+            .ok_or_else(|| "investment amount is out of range".to_string())?;
+        units.checked_mul(rates.price()).ok_or_else(|| "investment value overflows".to_string())
+    }
+
+    /// Requirements apply from the minimum up to and including the maximum
+    pub fn applicability(&self, minimum: Option<&MonetaryThreshold>, maximum: Option<&MonetaryThreshold>) -> ThresholdOutcome {
+        for (threshold, is_minimum) in [(minimum, true), (maximum, false)] {
+            let Some(threshold) = threshold else { continue };
+            let value = match self.value_in(threshold.currency) {
+                Ok(value) => value,
+                Err(reason) => return ThresholdOutcome::RatesUnavailable { currency: threshold.currency, reason },
+            };
+            let within = if is_minimum { value >= threshold.amount } else { value <= threshold.amount };
+            if !within {
+                return ThresholdOutcome::NotApplicable;
+            }
+        }
+        ThresholdOutcome::Applies
+    }
+
+    /// Rates a check against these thresholds was evaluated with
+    pub fn rates_used(&self, thresholds: &[&MonetaryThreshold]) -> Vec<AppliedRate> {
+        let mut used = Vec::new();
+        for threshold in thresholds {
+            if let Some(Ok(rates)) = self.rates.get(&threshold.currency) {
+                for rate in [&rates.asset_price, &rates.fx] {
+                    if !used.contains(rate) {
+                        used.push(rate.clone());
+                    }
+                }
+            }
+        }
+        used
+    }
+
+    /// A threshold in settlement asset base units at the current rates, rounded down
+    pub fn settlement_amount(&self, threshold: &MonetaryThreshold) -> Option<u128> {
+        let Some(Ok(rates)) = self.rates.get(&threshold.currency) else { return None };
+        let price = rates.price();
+        if price.is_zero() {
+            return None;
+        }
+        (threshold.amount / price)
+            .checked_mul(Decimal::from(10u64.checked_pow(self.decimals)?))?
+            .floor()
+            .to_u128()
+    }
+
+    /// Every requested currency could be valued
+    pub fn is_complete(&self) -> bool {
+        self.rates.values().all(Result::is_ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Fixed rates observed at a settable time
+    struct FixedRates {
+        eth_usd: Decimal,
+        usd_sgd: Decimal,
+        observed_at: Mutex<DateTime<Utc>>,
+    }
+
+    #[async_trait]
+    impl ConversionRates for FixedRates {
+        async fn asset_price_usd(&self, _asset: &str) -> Result<RateObservation, String> {
+            Ok(RateObservation { rate: self.eth_usd, observed_at: *self.observed_at.lock().unwrap(), source: "test".to_string() })
+        }
+
+        async fn usd_fx_rate(&self, currency: FiatCurrency) -> Result<RateObservation, String> {
+            match currency {
+                FiatCurrency::SGD => Ok(RateObservation { rate: self.usd_sgd, observed_at: *self.observed_at.lock().unwrap(), source: "test".to_string() }),
+                FiatCurrency::USD => Ok(RateObservation { rate: Decimal::ONE, observed_at: Utc::now(), source: "identity".to_string() }),
+                FiatCurrency::EUR => Err("no EUR feed".to_string()),
+            }
+        }
+    }
+
+    fn eth(whole: u128) -> u128 {
+        whole * 10u128.pow(18)
+    }
+
+    #[tokio::test]
+    async fn test_stale_and_missing_rates_are_unavailable() {
+        let now = Utc::now();
+        let rates = FixedRates { eth_usd: Decimal::from(2_000), usd_sgd: Decimal::new(135, 2), observed_at: Mutex::new(now - Duration::hours(1)) };
+        let config = ThresholdConfig::default();
+        let sgd = MonetaryThreshold::new(200_000, FiatCurrency::SGD);
+        let eur = MonetaryThreshold::new(500_000, FiatCurrency::EUR);
+
+        let valuation = Valuation::at_current_rates(Some(&rates), &config, eth(100), [FiatCurrency::SGD, FiatCurrency::EUR], now).await;
+        assert!(!valuation.is_complete());
+        assert!(matches!(valuation.applicability(Some(&sgd), None), ThresholdOutcome::RatesUnavailable { currency: FiatCurrency::SGD, .. }));
+        assert!(valuation.rates_used(&[&sgd]).is_empty());
+
+        *rates.observed_at.lock().unwrap() = now;
+        let valuation = Valuation::at_current_rates(Some(&rates), &config, eth(100), [FiatCurrency::SGD, FiatCurrency::EUR], now).await;
+        // 100 ETH at 2,000 USD and 1.35 SGD per USD is SGD 270,000
+        assert_eq!(valuation.applicability(Some(&sgd), None), ThresholdOutcome::Applies);
+        assert!(matches!(valuation.applicability(Some(&eur), None), ThresholdOutcome::RatesUnavailable { currency: FiatCurrency::EUR, .. }));
+        assert_eq!(valuation.rates_used(&[&sgd]).iter().map(|rate| rate.pair.as_str()).collect::<Vec<_>>(), vec!["ETH/USD", "USD/SGD"]);
+
+        let unconfigured = Valuation::at_current_rates(None, &config, eth(100), [FiatCurrency::USD], now).await;
+        assert!(matches!(
+            unconfigured.applicability(Some(&MonetaryThreshold::new(1, FiatCurrency::USD)), None),
+            ThresholdOutcome::RatesUnavailable { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_settlement_amount_of_threshold() {
+        let now = Utc::now();
+        let rates = FixedRates { eth_usd: Decimal::from(2_000), usd_sgd: Decimal::new(125, 2), observed_at: Mutex::new(now) };
+        let valuation = Valuation::at_current_rates(Some(&rates), &ThresholdConfig::default(), 1, [FiatCurrency::SGD], now).await;
+
+        // SGD 200,000 is 80 ETH at SGD 2,500 per ETH
+        assert_eq!(valuation.settlement_amount(&MonetaryThreshold::new(200_000, FiatCurrency::SGD)), Some(eth(80)));
+        assert_eq!(valuation.settlement_amount(&MonetaryThreshold::new(1, FiatCurrency::USD)), None);
+    }
+}
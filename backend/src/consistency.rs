@@ -0,0 +1,996 @@
+// Cross-store consistency checks: Postgres against Redis, the treasury registry and IPFS,
+// run after incidents and on a schedule. Checks only read the stores they compare.
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use ethers::abi::{self, ParamType, Token};
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::types::{Address, TransactionRequest, U256};
+use redis::aio::ConnectionManager;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::compliance::enhanced_compliance_engine::EnhancedComplianceEngine;
+use crate::task_health::TaskHealth;
+
+pub const CONSISTENCY_TASK: &str = "consistency_checks";
+
+/// Time each check may take unless `CONSISTENCY_CHECK_TIMEOUT_SECS` says otherwise
+pub const DEFAULT_CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Rows, keys or treasuries each check samples unless `CONSISTENCY_SAMPLE_SIZE` says otherwise
+pub const DEFAULT_SAMPLE_SIZE: i64 = 200;
+
+/// Key prefix of the risk service's cached assessments
+const RISK_CACHE_PREFIX: &str = "risk:portfolio:";
+
+/// A cached assessment within this of the stored row is the same calculation
+const SAME_CALCULATION_MS: i64 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsistencyCheck {
+    /// Cached risk metrics against the latest stored row per portfolio
+    RiskMetricsCache,
+    /// Held treasuries against the TreasuryRegistry contract
+    TreasuryRegistry,
+    /// Sessions whose user no longer exists
+    OrphanedSessions,
+    /// Compliance reports whose IPFS content is not pinned
+    ComplianceReportContent,
+    /// Investor profiles whose data hash does not verify
+    ProfileIntegrity,
+}
+
+impl ConsistencyCheck {
+    pub const ALL: [ConsistencyCheck; 5] = [
+        ConsistencyCheck::RiskMetricsCache,
+        ConsistencyCheck::TreasuryRegistry,
+        ConsistencyCheck::OrphanedSessions,
+        ConsistencyCheck::ComplianceReportContent,
+        ConsistencyCheck::ProfileIntegrity,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConsistencyCheck::RiskMetricsCache => "risk_metrics_cache",
+            ConsistencyCheck::TreasuryRegistry => "treasury_registry",
+            ConsistencyCheck::OrphanedSessions => "orphaned_sessions",
+            ConsistencyCheck::ComplianceReportContent => "compliance_report_content",
+            ConsistencyCheck::ProfileIntegrity => "profile_integrity",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl FindingSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FindingSeverity::Info => "info",
+            FindingSeverity::Warning => "warning",
+            FindingSeverity::Critical => "critical",
+        }
+    }
+}
+
+/// One inconsistency. `code` and `subject` are stable so remediation scripts can act on them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Finding {
+    pub check: ConsistencyCheck,
+    /// e.g. `risk_cache.stale` or `session.orphaned`
+    pub code: String,
+    pub severity: FindingSeverity,
+    /// Identifier of the inconsistent record: a portfolio address, token id, session id, ...
+    pub subject: String,
+    pub detail: String,
+}
+
+impl Finding {
+    fn new(check: ConsistencyCheck, code: &str, severity: FindingSeverity, subject: impl ToString, detail: impl ToString) -> Self {
+        Self { check, code: code.to_string(), severity, subject: subject.to_string(), detail: detail.to_string() }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Consistent,
+    Inconsistent,
+    Error,
+    Timeout,
+    /// The store the check compares against is not configured in this process
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckResult {
+    pub check: ConsistencyCheck,
+    pub status: CheckStatus,
+    pub findings: Vec<Finding>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunTrigger {
+    Schedule,
+    Admin,
+    Cli,
+}
+
+impl RunTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunTrigger::Schedule => "schedule",
+            RunTrigger::Admin => "admin",
+            RunTrigger::Cli => "cli",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsistencyReport {
+    pub run_id: Uuid,
+    pub trigger: RunTrigger,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub checks: Vec<CheckResult>,
+}
+
+impl ConsistencyReport {
+    pub fn findings(&self) -> impl Iterator<Item = &Finding> {
+        self.checks.iter().flat_map(|check| check.findings.iter())
+    }
+
+    pub fn worst_severity(&self) -> Option<FindingSeverity> {
+        self.findings().map(|finding| finding.severity).max()
+    }
+
+    /// Checks that could not complete, so their stores are unverified
+    pub fn incomplete(&self) -> Vec<ConsistencyCheck> {
+        self.checks.iter()
+            .filter(|check| matches!(check.status, CheckStatus::Error | CheckStatus::Timeout))
+            .map(|check| check.check)
+            .collect()
+    }
+}
+
+/// A risk assessment as the risk service caches it
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedRiskMetrics {
+    pub portfolio_address: String,
+    pub timestamp: DateTime<Utc>,
+    pub var_95: Decimal,
+    pub risk_grade: String,
+}
+
+/// The latest risk assessment stored for a portfolio
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredRiskMetrics {
+    pub timestamp: DateTime<Utc>,
+    pub var_95: Option<Decimal>,
+    pub risk_grade: Option<String>,
+}
+
+#[async_trait]
+pub trait RiskMetricsCache: Send + Sync {
+    /// Up to `limit` cached assessments
+    async fn cached_metrics(&self, limit: usize) -> Result<Vec<CachedRiskMetrics>, String>;
+}
+
+/// The risk service's Redis cache
+pub struct RedisRiskMetricsCache {
+    client: redis::Client,
+}
+
+impl RedisRiskMetricsCache {
+    pub fn new(redis_url: &str) -> Result<Self, String> {
+        let client = redis::Client::open(redis_url).map_err(|e| format!("Invalid risk cache Redis URL: {}", e))?;
+        Ok(Self { client })
+    }
+}
+
+#[derive(Deserialize)]
+struct CachedEntry {
+    portfolio_address: String,
+    timestamp: DateTime<Utc>,
+    var_95: serde_json::Value,
+    risk_grade: String,
+}
+
+#[async_trait]
+impl RiskMetricsCache for RedisRiskMetricsCache {
+    async fn cached_metrics(&self, limit: usize) -> Result<Vec<CachedRiskMetrics>, String> {
+        let mut connection = ConnectionManager::new(self.client.clone()).await.map_err(|e| e.to_string())?;
+
+        let mut keys = Vec::new();
+        let mut cursor = 0u64;
+        loop {
+            let (next, batch): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(format!("{}*", RISK_CACHE_PREFIX))
+                .arg("COUNT")
+                .arg(100)
+                .query_async(&mut connection)
+                .await
+                .map_err(|e| e.to_string())?;
+            keys.extend(batch);
+            cursor = next;
+            if cursor == 0 || keys.len() >= limit {
+                break;
+            }
+        }
+        keys.truncate(limit);
+
+        let mut cached = Vec::new();
+        for key in keys {
+            // Entries expire between SCAN and GET
+            let value: Option<String> = redis::cmd("GET").arg(&key).query_async(&mut connection).await.map_err(|e| e.to_string())?;
+            let Some(value) = value else { continue };
+            let entry: CachedEntry = serde_json::from_str(&value).map_err(|e| format!("Unreadable cache entry {}: {}", key, e))?;
+            let var_95 = match &entry.var_95 {
+                serde_json::Value::String(value) => value.clone(),
+                other => other.to_string(),
+            };
+            cached.push(CachedRiskMetrics {
+                portfolio_address: entry.portfolio_address.to_lowercase(),
+                timestamp: entry.timestamp,
+                var_95: Decimal::from_str(&var_95).map_err(|e| format!("Unreadable VaR in {}: {}", key, e))?,
+                risk_grade: entry.risk_grade,
+            });
+        }
+        Ok(cached)
+    }
+}
+
+/// A treasury as the TreasuryRegistry contract records it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegistryTreasury {
+    pub token_address: String,
+    /// 0 active, 1 matured, 2 redeemed
+    pub status: u8,
+    /// Unix seconds
+    pub maturity_date: u64,
+}
+
+#[async_trait]
+pub trait TreasuryRegistryReader: Send + Sync {
+    /// The registry entry for a token id; `None` when it was never registered
+    async fn treasury(&self, token_id: [u8; 32]) -> Result<Option<RegistryTreasury>, String>;
+}
+
+/// Reads `treasuries(bytes32)` over JSON-RPC
+pub struct RpcTreasuryRegistry {
+    provider: Provider<Http>,
+    address: Address,
+}
+
+impl RpcTreasuryRegistry {
+    pub fn new(rpc_url: &str, address: &str) -> Result<Self, String> {
+        let provider = Provider::<Http>::try_from(rpc_url).map_err(|e| format!("Invalid RPC URL {}: {}", rpc_url, e))?;
+        let address = address.parse().map_err(|_| format!("Invalid registry address {}", address))?;
+        Ok(Self { provider, address })
+    }
+}
+
+#[async_trait]
+impl TreasuryRegistryReader for RpcTreasuryRegistry {
+    async fn treasury(&self, token_id: [u8; 32]) -> Result<Option<RegistryTreasury>, String> {
+        let mut calldata = ethers::utils::id("treasuries(bytes32)").to_vec();
+        calldata.extend(abi::encode(&[Token::FixedBytes(token_id.to_vec())]));
+
+        let tx = TransactionRequest::new().to(self.address).data(calldata);
+        let output = self.provider.call(&tx.into(), None).await.map_err(|e| e.to_string())?;
+        let tokens = abi::decode(&[
+            ParamType::Address,
+            ParamType::String,
+            ParamType::Uint(8),
+            ParamType::Uint(256),
+            ParamType::Uint(256),
+            ParamType::Uint(256),
+            ParamType::Uint(256),
+            ParamType::Address,
+            ParamType::FixedBytes(32),
+        ], &output).map_err(|e| format!("Unexpected treasuries() response: {}", e))?;
+
+        let token_address = tokens[0].clone().into_address().unwrap_or_default();
+        if token_address.is_zero() {
+            return Ok(None);
+        }
+        Ok(Some(RegistryTreasury {
+            token_address: format!("{:?}", token_address),
+            status: tokens[2].clone().into_uint().unwrap_or_default().low_u32() as u8,
+            maturity_date: tokens[5].clone().into_uint().unwrap_or_default().low_u64(),
+        }))
+    }
+}
+
+#[async_trait]
+pub trait ContentStore: Send + Sync {
+    /// Whether the content is pinned and retrievable
+    async fn contains(&self, cid: &str) -> Result<bool, String>;
+}
+
+/// Pins on an IPFS node's HTTP API
+pub struct IpfsPinStore {
+    http: reqwest::Client,
+    api_url: String,
+}
+
+impl IpfsPinStore {
+    pub fn new(api_url: &str) -> Self {
+        Self { http: reqwest::Client::new(), api_url: api_url.trim_end_matches('/').to_string() }
+    }
+}
+
+#[async_trait]
+impl ContentStore for IpfsPinStore {
+    async fn contains(&self, cid: &str) -> Result<bool, String> {
+        let response = self.http
+            .post(format!("{}/api/v0/pin/ls", self.api_url))
+            .query(&[("arg", cid), ("type", "recursive")])
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if response.status().is_success() {
+            return Ok(true);
+        }
+
+        // The node answers 500 with "not pinned" for unknown content
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        if body.contains("not pinned") || body.contains("invalid path") {
+            Ok(false)
+        } else {
+            Err(format!("IPFS node returned {}: {}", status, body))
+        }
+    }
+}
+
+#[async_trait]
+pub trait ProfileIntegrity: Send + Sync {
+    /// `tenant/investor` of every profile whose data hash does not verify
+    async fn failing_profiles(&self) -> Vec<String>;
+}
+
+#[async_trait]
+impl ProfileIntegrity for RwLock<EnhancedComplianceEngine> {
+    async fn failing_profiles(&self) -> Vec<String> {
+        self.read().await.integrity_failures().into_iter()
+            .map(|(tenant, investor_id)| format!("{}/{}", tenant, investor_id))
+            .collect()
+    }
+}
+
+/// Stores to compare and how long each check may take
+#[derive(Debug, Clone)]
+pub struct ConsistencyConfig {
+    pub check_timeout: Duration,
+    pub sample_size: i64,
+    /// Scheduled runs are disabled when unset
+    pub interval: Option<Duration>,
+    pub risk_cache_redis_url: Option<String>,
+    pub eth_rpc_url: Option<String>,
+    pub registry_address: Option<String>,
+    pub ipfs_api_url: Option<String>,
+}
+
+impl ConsistencyConfig {
+    /// Read `CONSISTENCY_CHECK_TIMEOUT_SECS`, `CONSISTENCY_SAMPLE_SIZE`, `CONSISTENCY_CHECK_INTERVAL_SECS`
+    /// and the store locations: `RISK_CACHE_REDIS_URL` (falling back to `REDIS_URL`), `ETH_RPC_URL`,
+    /// `REGISTRY_ADDRESS` and `IPFS_API_URL`
+    pub fn from_env() -> Result<Self, String> {
+        let env_parse = |name: &str| -> Result<Option<u64>, String> {
+            match std::env::var(name) {
+                Ok(value) if !value.is_empty() => value.parse().map(Some).map_err(|_| format!("Invalid {}", name)),
+                _ => Ok(None),
+            }
+        };
+        let env_url = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+
+        Ok(Self {
+            check_timeout: env_parse("CONSISTENCY_CHECK_TIMEOUT_SECS")?
+                .map(|secs| Duration::from_secs(secs.max(1)))
+                .unwrap_or(DEFAULT_CHECK_TIMEOUT),
+            sample_size: env_parse("CONSISTENCY_SAMPLE_SIZE")?.map(|size| size.max(1) as i64).unwrap_or(DEFAULT_SAMPLE_SIZE),
+            interval: env_parse("CONSISTENCY_CHECK_INTERVAL_SECS")?.filter(|secs| *secs > 0).map(Duration::from_secs),
+            risk_cache_redis_url: env_url("RISK_CACHE_REDIS_URL").or_else(|| env_url("REDIS_URL")),
+            eth_rpc_url: env_url("ETH_RPC_URL"),
+            registry_address: env_url("REGISTRY_ADDRESS"),
+            ipfs_api_url: env_url("IPFS_API_URL"),
+        })
+    }
+}
+
+/// Runs the battery of cross-store checks and keeps their history
+pub struct ConsistencyChecker {
+    db: Arc<PgPool>,
+    risk_cache: Option<Arc<dyn RiskMetricsCache>>,
+    registry: Option<Arc<dyn TreasuryRegistryReader>>,
+    content: Option<Arc<dyn ContentStore>>,
+    profiles: Option<Arc<dyn ProfileIntegrity>>,
+    check_timeout: Duration,
+    sample_size: i64,
+}
+
+impl ConsistencyChecker {
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self {
+            db,
+            risk_cache: None,
+            registry: None,
+            content: None,
+            profiles: None,
+            check_timeout: DEFAULT_CHECK_TIMEOUT,
+            sample_size: DEFAULT_SAMPLE_SIZE,
+        }
+    }
+
+    /// Checker over every store `config` locates; checks of the others are skipped
+    pub fn from_config(db: Arc<PgPool>, config: &ConsistencyConfig) -> Result<Self, String> {
+        let mut checker = Self::new(db).with_limits(config.check_timeout, config.sample_size);
+        if let Some(url) = &config.risk_cache_redis_url {
+            checker = checker.with_risk_cache(Arc::new(RedisRiskMetricsCache::new(url)?));
+        }
+        if let (Some(rpc_url), Some(address)) = (&config.eth_rpc_url, &config.registry_address) {
+            checker = checker.with_registry(Arc::new(RpcTreasuryRegistry::new(rpc_url, address)?));
+        }
+        if let Some(url) = &config.ipfs_api_url {
+            checker = checker.with_content_store(Arc::new(IpfsPinStore::new(url)));
+        }
+        Ok(checker)
+    }
+
+    pub fn with_limits(mut self, check_timeout: Duration, sample_size: i64) -> Self {
+        self.check_timeout = check_timeout;
+        self.sample_size = sample_size;
+        self
+    }
+
+    pub fn with_risk_cache(mut self, cache: Arc<dyn RiskMetricsCache>) -> Self {
+        self.risk_cache = Some(cache);
+        self
+    }
+
+    pub fn with_registry(mut self, registry: Arc<dyn TreasuryRegistryReader>) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    pub fn with_content_store(mut self, content: Arc<dyn ContentStore>) -> Self {
+        self.content = Some(content);
+        self
+    }
+
+    /// Investor profiles are held in memory, so only a checker inside the server can verify them
+    pub fn with_profiles(mut self, profiles: Arc<dyn ProfileIntegrity>) -> Self {
+        self.profiles = Some(profiles);
+        self
+    }
+
+    /// Run every check concurrently, each bounded by the check timeout
+    pub async fn run(&self, trigger: RunTrigger) -> ConsistencyReport {
+        let started_at = Utc::now();
+        let (risk, treasury, sessions, reports, profiles) = tokio::join!(
+            bounded(ConsistencyCheck::RiskMetricsCache, self.check_timeout, self.risk_metrics_cache()),
+            bounded(ConsistencyCheck::TreasuryRegistry, self.check_timeout, self.treasury_registry()),
+            bounded(ConsistencyCheck::OrphanedSessions, self.check_timeout, self.orphaned_sessions()),
+            bounded(ConsistencyCheck::ComplianceReportContent, self.check_timeout, self.compliance_report_content()),
+            bounded(ConsistencyCheck::ProfileIntegrity, self.check_timeout, self.profile_integrity()),
+        );
+
+        ConsistencyReport {
+            run_id: Uuid::new_v4(),
+            trigger,
+            started_at,
+            finished_at: Utc::now(),
+            checks: vec![risk, treasury, sessions, reports, profiles],
+        }
+    }
+
+    /// Run and record the report in the history tables
+    pub async fn run_and_store(&self, trigger: RunTrigger) -> Result<ConsistencyReport, sqlx::Error> {
+        let report = self.run(trigger).await;
+        store_report(&self.db, &report).await?;
+        Ok(report)
+    }
+
+    async fn risk_metrics_cache(&self) -> Result<Option<Vec<Finding>>, String> {
+        let Some(cache) = &self.risk_cache else { return Ok(None) };
+        let cached = cache.cached_metrics(self.sample_size as usize).await?;
+        if cached.is_empty() {
+            return Ok(Some(Vec::new()));
+        }
+
+        let portfolios: Vec<String> = cached.iter().map(|entry| entry.portfolio_address.clone()).collect();
+        let rows: Vec<(String, DateTime<Utc>, Option<String>, Option<String>)> = sqlx::query_as(
+            "SELECT DISTINCT ON (portfolio_address) lower(portfolio_address), timestamp, var_95::text, risk_grade
+             FROM risk_metrics
+             WHERE lower(portfolio_address) = ANY($1)
+             ORDER BY portfolio_address, timestamp DESC"
+        )
+            .bind(&portfolios)
+            .fetch_all(self.db.as_ref())
+            .await
+            .map_err(|e| e.to_string())?;
+        let stored = rows.into_iter()
+            .map(|(portfolio, timestamp, var_95, risk_grade)| (portfolio, StoredRiskMetrics {
+                timestamp,
+                var_95: var_95.and_then(|value| Decimal::from_str(&value).ok()),
+                risk_grade,
+            }))
+            .collect();
+
+        Ok(Some(risk_cache_findings(&cached, &stored)))
+    }
+
+    async fn treasury_registry(&self) -> Result<Option<Vec<Finding>>, String> {
+        let Some(registry) = &self.registry else { return Ok(None) };
+        let held: Vec<(String, Option<NaiveDate>)> = sqlx::query_as(
+            "SELECT asset_id, MIN(maturity_date)
+             FROM portfolio_holdings
+             WHERE asset_category = 'treasury' AND quantity > 0
+             GROUP BY asset_id
+             ORDER BY random()
+             LIMIT $1"
+        )
+            .bind(self.sample_size)
+            .fetch_all(self.db.as_ref())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let check = ConsistencyCheck::TreasuryRegistry;
+        let mut findings = Vec::new();
+        for (asset_id, maturity) in held {
+            let Some(token_id) = parse_token_id(&asset_id) else {
+                findings.push(Finding::new(check, "treasury.invalid_token_id", FindingSeverity::Warning, &asset_id,
+                    "Held treasury's asset id is not a registry token id"));
+                continue;
+            };
+            match registry.treasury(token_id).await? {
+                None => findings.push(Finding::new(check, "treasury.unregistered", FindingSeverity::Critical, &asset_id,
+                    "Held treasury is not in the registry")),
+                Some(entry) => findings.extend(treasury_findings(&asset_id, maturity, &entry)),
+            }
+        }
+        Ok(Some(findings))
+    }
+
+    async fn orphaned_sessions(&self) -> Result<Option<Vec<Finding>>, String> {
+        let rows: Vec<(Uuid, Option<Uuid>, bool)> = sqlx::query_as(
+            "SELECT s.id, s.user_id, (NOT COALESCE(s.is_revoked, false) AND s.expires_at > NOW())
+             FROM auth_sessions s
+             LEFT JOIN users u ON u.id = s.user_id
+             WHERE u.id IS NULL
+             ORDER BY s.created_at DESC
+             LIMIT $1"
+        )
+            .bind(self.sample_size)
+            .fetch_all(self.db.as_ref())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(Some(rows.into_iter().map(|(session_id, user_id, active)| {
+            let user = user_id.map(|id| id.to_string()).unwrap_or_else(|| "none".to_string());
+            // A usable session without a user bypasses account deactivation
+            let (severity, state) = if active { (FindingSeverity::Critical, "active") } else { (FindingSeverity::Warning, "inactive") };
+            Finding::new(ConsistencyCheck::OrphanedSessions, "session.orphaned", severity, session_id,
+                format!("{} session references missing user {}", state, user))
+        }).collect()))
+    }
+
+    async fn compliance_report_content(&self) -> Result<Option<Vec<Finding>>, String> {
+        let Some(content) = &self.content else { return Ok(None) };
+        let reports: Vec<(Uuid, String)> = sqlx::query_as(
+            "SELECT report_id, ipfs_hash FROM compliance_reports
+             WHERE ipfs_hash IS NOT NULL
+             ORDER BY generated_at DESC
+             LIMIT $1"
+        )
+            .bind(self.sample_size)
+            .fetch_all(self.db.as_ref())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut findings = Vec::new();
+        for (report_id, cid) in reports {
+            if cid.trim().is_empty() || !content.contains(&cid).await? {
+                findings.push(Finding::new(ConsistencyCheck::ComplianceReportContent, "report.content_missing", FindingSeverity::Critical,
+                    report_id, format!("Report content {} is not pinned", cid)));
+            }
+        }
+        Ok(Some(findings))
+    }
+
+    async fn profile_integrity(&self) -> Result<Option<Vec<Finding>>, String> {
+        let Some(profiles) = &self.profiles else { return Ok(None) };
+        Ok(Some(profiles.failing_profiles().await.into_iter()
+            .map(|profile| Finding::new(ConsistencyCheck::ProfileIntegrity, "profile.hash_mismatch", FindingSeverity::Critical,
+                profile, "Profile data does not match its integrity hash"))
+            .collect()))
+    }
+}
+
+/// Run one check with the timeout; `Ok(None)` from a check means its store is not configured
+async fn bounded<F>(check: ConsistencyCheck, timeout: Duration, run: F) -> CheckResult
+where
+    F: Future<Output = Result<Option<Vec<Finding>>, String>>,
+{
+    let started = Instant::now();
+    let (status, findings, error) = match tokio::time::timeout(timeout, run).await {
+        Ok(Ok(Some(findings))) if findings.is_empty() => (CheckStatus::Consistent, findings, None),
+        Ok(Ok(Some(findings))) => (CheckStatus::Inconsistent, findings, None),
+        Ok(Ok(None)) => (CheckStatus::Skipped, Vec::new(), None),
+        Ok(Err(e)) => (CheckStatus::Error, Vec::new(), Some(e)),
+        Err(_) => (CheckStatus::Timeout, Vec::new(), Some(format!("No result within {}s", timeout.as_secs()))),
+    };
+    CheckResult { check, status, findings, error, duration_ms: started.elapsed().as_millis() as u64 }
+}
+
+/// Compare cached assessments with the latest stored one per portfolio
+pub fn risk_cache_findings(cached: &[CachedRiskMetrics], stored: &HashMap<String, StoredRiskMetrics>) -> Vec<Finding> {
+    let check = ConsistencyCheck::RiskMetricsCache;
+    let mut findings = Vec::new();
+    for entry in cached {
+        let portfolio = &entry.portfolio_address;
+        let Some(row) = stored.get(portfolio) else {
+            findings.push(Finding::new(check, "risk_cache.unpersisted", FindingSeverity::Warning, portfolio,
+                "Cached assessment has no stored row"));
+            continue;
+        };
+
+        let drift = (entry.timestamp - row.timestamp).num_milliseconds();
+        if drift < -SAME_CALCULATION_MS {
+            findings.push(Finding::new(check, "risk_cache.stale", FindingSeverity::Warning, portfolio,
+                format!("Cache from {} predates the stored assessment from {}", entry.timestamp, row.timestamp)));
+        } else if drift > SAME_CALCULATION_MS {
+            findings.push(Finding::new(check, "risk_cache.unpersisted", FindingSeverity::Warning, portfolio,
+                format!("Cache from {} is newer than the stored assessment from {}", entry.timestamp, row.timestamp)));
+        } else {
+            // Stored values went through f64, so only differences beyond that precision count
+            let var_matches = row.var_95.map_or(false, |stored| {
+                let tolerance = (stored.abs() * Decimal::new(1, 9)).max(Decimal::new(1, 8));
+                (stored - entry.var_95).abs() <= tolerance
+            });
+            let grade_matches = row.risk_grade.as_deref() == Some(entry.risk_grade.as_str());
+            if !var_matches || !grade_matches {
+                findings.push(Finding::new(check, "risk_cache.mismatch", FindingSeverity::Critical, portfolio,
+                    format!(
+                        "Same assessment differs: cached VaR95 {} grade {}, stored VaR95 {} grade {}",
+                        entry.var_95,
+                        entry.risk_grade,
+                        row.var_95.map(|v| v.to_string()).unwrap_or_else(|| "none".to_string()),
+                        row.risk_grade.as_deref().unwrap_or("none"),
+                    )));
+            }
+        }
+    }
+    findings
+}
+
+/// Compare a held treasury with its registry entry
+fn treasury_findings(asset_id: &str, maturity: Option<NaiveDate>, entry: &RegistryTreasury) -> Vec<Finding> {
+    let check = ConsistencyCheck::TreasuryRegistry;
+    let mut findings = Vec::new();
+    if entry.status == 2 {
+        findings.push(Finding::new(check, "treasury.redeemed_but_held", FindingSeverity::Warning, asset_id,
+            format!("Registry marks {} redeemed but holdings remain", entry.token_address)));
+    }
+    let registry_maturity = Utc.timestamp_opt(entry.maturity_date as i64, 0).single().map(|at| at.date_naive());
+    if let (Some(held), Some(registered)) = (maturity, registry_maturity) {
+        if held != registered {
+            findings.push(Finding::new(check, "treasury.maturity_mismatch", FindingSeverity::Warning, asset_id,
+                format!("Holdings mature {}, registry {}", held, registered)));
+        }
+    }
+    findings
+}
+
+/// Hex token id, left-padded to 32 bytes
+fn parse_token_id(asset_id: &str) -> Option<[u8; 32]> {
+    let hex = asset_id.strip_prefix("0x")?;
+    let value = U256::from_str_radix(hex, 16).ok()?;
+    let mut token_id = [0u8; 32];
+    value.to_big_endian(&mut token_id);
+    Some(token_id)
+}
+
+async fn store_report(db: &PgPool, report: &ConsistencyReport) -> Result<(), sqlx::Error> {
+    let mut tx = db.begin().await?;
+    sqlx::query(
+        "INSERT INTO consistency_runs (run_id, trigger, started_at, finished_at, report)
+         VALUES ($1, $2, $3, $4, $5::jsonb)"
+    )
+        .bind(report.run_id)
+        .bind(report.trigger.as_str())
+        .bind(report.started_at)
+        .bind(report.finished_at)
+        .bind(serde_json::to_string(report).unwrap_or_default())
+        .execute(&mut *tx)
+        .await?;
+
+    for finding in report.findings() {
+        sqlx::query(
+            "INSERT INTO consistency_findings (run_id, check_name, code, severity, subject, detail, found_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)"
+        )
+            .bind(report.run_id)
+            .bind(finding.check.as_str())
+            .bind(&finding.code)
+            .bind(finding.severity.as_str())
+            .bind(&finding.subject)
+            .bind(&finding.detail)
+            .bind(report.finished_at)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await
+}
+
+/// Finding counts of one stored run, for drift trends
+#[derive(Debug, Clone, Serialize)]
+pub struct RunSummary {
+    pub run_id: Uuid,
+    pub trigger: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    /// Findings per check name; checks without findings are absent
+    pub findings: BTreeMap<String, i64>,
+    pub critical: i64,
+}
+
+/// Most recent runs first
+pub async fn recent_runs(db: &PgPool, limit: i64) -> Result<Vec<RunSummary>, sqlx::Error> {
+    let runs: Vec<(Uuid, String, DateTime<Utc>, DateTime<Utc>)> = sqlx::query_as(
+        "SELECT run_id, trigger, started_at, finished_at FROM consistency_runs ORDER BY started_at DESC LIMIT $1"
+    )
+        .bind(limit)
+        .fetch_all(db)
+        .await?;
+    let run_ids: Vec<Uuid> = runs.iter().map(|(run_id, ..)| *run_id).collect();
+    let counts: Vec<(Uuid, String, String, i64)> = sqlx::query_as(
+        "SELECT run_id, check_name, severity, COUNT(*) FROM consistency_findings
+         WHERE run_id = ANY($1)
+         GROUP BY run_id, check_name, severity"
+    )
+        .bind(&run_ids)
+        .fetch_all(db)
+        .await?;
+
+    Ok(runs.into_iter().map(|(run_id, trigger, started_at, finished_at)| {
+        let mut findings = BTreeMap::new();
+        let mut critical = 0;
+        for (_, check, severity, count) in counts.iter().filter(|(id, ..)| *id == run_id) {
+            *findings.entry(check.clone()).or_insert(0) += count;
+            if severity == FindingSeverity::Critical.as_str() {
+                critical += count;
+            }
+        }
+        RunSummary { run_id, trigger, started_at, finished_at, findings, critical }
+    }).collect())
+}
+
+pub async fn stored_report(db: &PgPool, run_id: Uuid) -> Result<Option<ConsistencyReport>, sqlx::Error> {
+    let report: Option<(String,)> = sqlx::query_as("SELECT report::text FROM consistency_runs WHERE run_id = $1")
+        .bind(run_id)
+        .fetch_optional(db)
+        .await?;
+    Ok(report.and_then(|(report,)| serde_json::from_str(&report).ok()))
+}
+
+/// Run the checks every `every` and store the results
+pub fn spawn_consistency_checks(checker: Arc<ConsistencyChecker>, health: Arc<TaskHealth>, every: Duration) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(every);
+        loop {
+            ticker.tick().await;
+            match checker.run_and_store(RunTrigger::Schedule).await {
+                Ok(report) => {
+                    let findings = report.findings().count();
+                    if report.worst_severity() == Some(FindingSeverity::Critical) {
+                        warn!("Consistency run {} found {} inconsistencies, some critical", report.run_id, findings);
+                    } else if findings > 0 {
+                        info!("Consistency run {} found {} inconsistencies", report.run_id, findings);
+                    }
+                    let incomplete = report.incomplete();
+                    if incomplete.is_empty() {
+                        health.record_success(CONSISTENCY_TASK);
+                    } else {
+                        let names: Vec<&str> = incomplete.iter().map(ConsistencyCheck::as_str).collect();
+                        health.record_failure(CONSISTENCY_TASK, format!("Checks did not complete: {}", names.join(", ")));
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to store consistency run: {}", e);
+                    health.record_failure(CONSISTENCY_TASK, e);
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration as ChronoDuration;
+
+    fn cached(portfolio: &str, timestamp: DateTime<Utc>, var_95: i64, risk_grade: &str) -> CachedRiskMetrics {
+        CachedRiskMetrics { portfolio_address: portfolio.to_string(), timestamp, var_95: Decimal::from(var_95), risk_grade: risk_grade.to_string() }
+    }
+
+    fn stored(timestamp: DateTime<Utc>, var_95: i64, risk_grade: &str) -> StoredRiskMetrics {
+        StoredRiskMetrics { timestamp, var_95: Some(Decimal::from(var_95)), risk_grade: Some(risk_grade.to_string()) }
+    }
+
+    #[tokio::test]
+    async fn test_risk_cache_classification_and_timeouts() {
+        let at = Utc::now();
+        let rows = HashMap::from([
+            ("0xa".to_string(), stored(at, 100, "B")),
+            ("0xb".to_string(), stored(at, 100, "B")),
+            ("0xc".to_string(), stored(at + ChronoDuration::minutes(5), 100, "B")),
+        ]);
+        let entries = [
+            cached("0xa", at, 100, "B"),
+            cached("0xb", at, 250, "B"),
+            cached("0xc", at, 100, "B"),
+            cached("0xd", at, 100, "B"),
+        ];
+
+        let codes: Vec<(String, String)> = risk_cache_findings(&entries, &rows).into_iter()
+            .map(|finding| (finding.subject, finding.code))
+            .collect();
+        assert_eq!(codes, vec![
+            ("0xb".to_string(), "risk_cache.mismatch".to_string()),
+            ("0xc".to_string(), "risk_cache.stale".to_string()),
+            ("0xd".to_string(), "risk_cache.unpersisted".to_string()),
+        ]);
+
+        // A hanging store only fails its own check
+        let slow = bounded(ConsistencyCheck::ComplianceReportContent, Duration::from_millis(20), async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(Some(Vec::new()))
+        }).await;
+        assert_eq!(slow.status, CheckStatus::Timeout);
+        let skipped = bounded(ConsistencyCheck::ProfileIntegrity, Duration::from_millis(20), async { Ok(None) }).await;
+        assert_eq!(skipped.status, CheckStatus::Skipped);
+    }
+
+    struct FixedCache(Vec<CachedRiskMetrics>);
+
+    #[async_trait]
+    impl RiskMetricsCache for FixedCache {
+        async fn cached_metrics(&self, _limit: usize) -> Result<Vec<CachedRiskMetrics>, String> {
+            Ok(self.0.clone())
+        }
+    }
+
+    /// Registers only the token ids it was given, maturing at the unix seconds given
+    struct FixedRegistry(HashMap<[u8; 32], u64>);
+
+    #[async_trait]
+    impl TreasuryRegistryReader for FixedRegistry {
+        async fn treasury(&self, token_id: [u8; 32]) -> Result<Option<RegistryTreasury>, String> {
+            Ok(self.0.get(&token_id).map(|maturity_date| RegistryTreasury {
+                token_address: "0x7070707070707070707070707070707070707070".to_string(),
+                status: 0,
+                maturity_date: *maturity_date,
+            }))
+        }
+    }
+
+    struct NothingPinned;
+
+    #[async_trait]
+    impl ContentStore for NothingPinned {
+        async fn contains(&self, _cid: &str) -> Result<bool, String> {
+            Ok(false)
+        }
+    }
+
+    struct TamperedProfile;
+
+    #[async_trait]
+    impl ProfileIntegrity for TamperedProfile {
+        async fn failing_profiles(&self) -> Vec<String> {
+            vec!["default/investor-1".to_string()]
+        }
+    }
+
+    /// Migrated database from TEST_DATABASE_URL; skipped without one
+    async fn test_pool() -> Option<PgPool> {
+        let url = std::env::var("TEST_DATABASE_URL").ok()?;
+        Some(PgPool::connect(&url).await.expect("TEST_DATABASE_URL is unreachable"))
+    }
+
+    #[tokio::test]
+    async fn test_seeded_inconsistency_of_each_class_is_found() {
+        let Some(db) = test_pool().await else { return };
+        let seed = Uuid::new_v4().simple().to_string();
+
+        // Cached and stored assessments of the same calculation disagree
+        let portfolio = format!("0x{}", &seed[..32]).to_lowercase() + "00000000";
+        let calculated_at = Utc.with_ymd_and_hms(2024, 11, 15, 12, 0, 0).unwrap();
+        sqlx::query("INSERT INTO risk_metrics (portfolio_address, timestamp, var_95, risk_grade) VALUES ($1, $2, 100, 'B')")
+            .bind(&portfolio)
+            .bind(calculated_at)
+            .execute(&db)
+            .await
+            .unwrap();
+
+        // A held treasury the registry has never seen
+        let token_id = format!("0x{}", &seed[..16]);
+        sqlx::query(
+            "INSERT INTO portfolio_holdings (wallet_address, asset_id, asset_name, asset_symbol, quantity, acquisition_price,
+                                             acquisition_date, asset_category, maturity_date)
+             VALUES ($1, $2, 'Unregistered T-Bill', 'UTB', 10, 98, NOW(), 'treasury', '2030-01-01')"
+        )
+            .bind(&portfolio)
+            .bind(&token_id)
+            .execute(&db)
+            .await
+            .unwrap();
+
+        // An active session with no user
+        let session_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO auth_sessions (user_id, token_hash, expires_at) VALUES (NULL, $1, NOW() + INTERVAL '1 hour') RETURNING id"
+        )
+            .bind(format!("{}{}", seed, seed))
+            .fetch_one(&db)
+            .await
+            .unwrap();
+
+        // A report whose content was never pinned
+        let report_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO compliance_reports (report_id, investor_address, jurisdiction, ipfs_hash) VALUES ($1, $2, 'US', $3)"
+        )
+            .bind(report_id)
+            .bind(seed.as_bytes())
+            .bind(format!("Qm{}", seed))
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let checker = ConsistencyChecker::new(Arc::new(db.clone()))
+            .with_limits(Duration::from_secs(10), 10_000)
+            .with_risk_cache(Arc::new(FixedCache(vec![cached(&portfolio, calculated_at, 250, "B")])))
+            .with_registry(Arc::new(FixedRegistry(HashMap::new())))
+            .with_content_store(Arc::new(NothingPinned))
+            .with_profiles(Arc::new(TamperedProfile));
+        let report = checker.run_and_store(RunTrigger::Cli).await.unwrap();
+
+        let found = |code: &str, subject: &str| report.findings().any(|f| f.code == code && f.subject == subject);
+        assert!(found("risk_cache.mismatch", &portfolio));
+        assert!(found("treasury.unregistered", &token_id));
+        assert!(found("session.orphaned", &session_id.to_string()));
+        assert!(found("report.content_missing", &report_id.to_string()));
+        assert!(found("profile.hash_mismatch", "default/investor-1"));
+        assert!(report.checks.iter().all(|check| check.status == CheckStatus::Inconsistent), "{:?}", report.checks);
+        assert_eq!(report.worst_severity(), Some(FindingSeverity::Critical));
+
+        // The run is kept for trends
+        let runs = recent_runs(&db, 50).await.unwrap();
+        let run = runs.iter().find(|run| run.run_id == report.run_id).unwrap();
+        assert!(ConsistencyCheck::ALL.iter().all(|check| run.findings.contains_key(check.as_str())));
+        assert!(stored_report(&db, report.run_id).await.unwrap().is_some());
+    }
+}
@@ -0,0 +1,160 @@
+// Read-replica routing for heavy read-only queries
+use sqlx::{PgPool, Postgres, Transaction};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Replay lag tolerated by default before reads go back to the primary
+pub const DEFAULT_MAX_REPLICA_LAG_BYTES: u64 = 16 * 1024 * 1024;
+
+/// How long a replica health check is trusted before it is repeated
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Routes explicitly read-only queries to a replica, everything else to the primary.
+///
+/// Callers opt in per query with [`DbRouter::read`]; writes and transactions always
+/// use the primary. Reads fall back to the primary while the replica is unreachable
+/// or has replayed less WAL than the primary by more than `max_lag_bytes`.
+#[derive(Debug)]
+pub struct DbRouter {
+    primary: Arc<PgPool>,
+    replica: Option<Arc<PgPool>>,
+    max_lag_bytes: u64,
+    /// Result of the last health check and when it was made
+    replica_health: Mutex<Option<(Instant, bool)>>,
+}
+
+impl DbRouter {
+    pub fn new(primary: Arc<PgPool>) -> Self {
+        Self {
+            primary,
+            replica: None,
+            max_lag_bytes: DEFAULT_MAX_REPLICA_LAG_BYTES,
+            replica_health: Mutex::new(None),
+        }
+    }
+
+    /// Send read-only queries to `replica` while it is within `max_lag_bytes` of the primary
+    pub fn with_replica(mut self, replica: Arc<PgPool>, max_lag_bytes: u64) -> Self {
+        self.replica = Some(replica);
+        self.max_lag_bytes = max_lag_bytes;
+        self
+    }
+
+    /// Pool for writes and anything that must see its own writes
+    pub fn primary(&self) -> &PgPool {
+        &self.primary
+    }
+
+    /// Transactions always run on the primary
+    pub async fn begin(&self) -> Result<Transaction<'static, Postgres>, sqlx::Error> {
+        self.primary.begin().await
+    }
+
+    /// Pool for a read-only query: the replica when healthy, else the primary
+    pub async fn read(&self) -> &PgPool {
+        match &self.replica {
+            Some(replica) if self.replica_healthy(replica).await => replica,
+            _ => &self.primary,
+        }
+    }
+
+    async fn replica_healthy(&self, replica: &PgPool) -> bool {
+        if let Some((checked_at, healthy)) = *self.replica_health.lock().expect("replica health lock poisoned") {
+            if checked_at.elapsed() < HEALTH_CHECK_INTERVAL {
+                return healthy;
+            }
+        }
+
+        let healthy = match replica_lag_bytes(&self.primary, replica).await {
+            Ok(lag) => {
+                let usable = within_lag(lag, self.max_lag_bytes);
+                if !usable {
+                    warn!("Read replica is {:?} bytes behind the primary, reading from the primary", lag);
+                }
+                usable
+            }
+            Err(e) => {
+                warn!("Read replica unavailable, reading from the primary: {}", e);
+                false
+            }
+        };
+        *self.replica_health.lock().expect("replica health lock poisoned") = Some((Instant::now(), healthy));
+        healthy
+    }
+}
+
+/// WAL the replica has yet to replay, in bytes; `None` when it is not in recovery
+async fn replica_lag_bytes(primary: &PgPool, replica: &PgPool) -> Result<Option<f64>, sqlx::Error> {
+    let primary_lsn: String = sqlx::query_scalar("SELECT pg_current_wal_lsn()::text")
+        .fetch_one(primary)
+        .await?;
+    sqlx::query_scalar("SELECT pg_wal_lsn_diff($1::pg_lsn, pg_last_wal_replay_lsn())::float8")
+        .bind(primary_lsn)
+        .fetch_one(replica)
+        .await
+}
+
+/// A server that is not replaying WAL (e.g. a logical replica) has no replay lag
+fn within_lag(lag_bytes: Option<f64>, max_lag_bytes: u64) -> bool {
+    lag_bytes.map_or(true, |lag| lag <= max_lag_bytes as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::postgres::PgPoolOptions;
+
+    #[test]
+    fn test_lag_threshold() {
+        assert!(within_lag(None, 0));
+        assert!(within_lag(Some(0.0), 0));
+        assert!(within_lag(Some(1024.0), 1024));
+        assert!(!within_lag(Some(1025.0), 1024));
+    }
+
+    /// Pools from TEST_DATABASE_URL and TEST_REPLICA_DATABASE_URL, which must name
+    /// different databases; skipped without both
+    async fn test_pools() -> Option<(PgPool, PgPool)> {
+        let primary = std::env::var("TEST_DATABASE_URL").ok()?;
+        let replica = std::env::var("TEST_REPLICA_DATABASE_URL").ok()?;
+        Some((
+            PgPool::connect(&primary).await.expect("TEST_DATABASE_URL is unreachable"),
+            PgPool::connect(&replica).await.expect("TEST_REPLICA_DATABASE_URL is unreachable"),
+        ))
+    }
+
+    async fn database(db: &PgPool) -> String {
+        sqlx::query_scalar("SELECT current_database()::text").fetch_one(db).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_reads_go_to_replica_and_transactions_to_primary() {
+        let Some((primary, replica)) = test_pools().await else { return };
+        let (primary_name, replica_name) = (database(&primary).await, database(&replica).await);
+        let router = DbRouter::new(Arc::new(primary)).with_replica(Arc::new(replica), DEFAULT_MAX_REPLICA_LAG_BYTES);
+
+        assert_eq!(database(router.read().await).await, replica_name);
+        assert_eq!(database(router.primary()).await, primary_name);
+
+        let mut tx = router.begin().await.unwrap();
+        let in_tx: String = sqlx::query_scalar("SELECT current_database()::text").fetch_one(&mut *tx).await.unwrap();
+        tx.rollback().await.unwrap();
+        assert_eq!(in_tx, primary_name);
+    }
+
+    #[tokio::test]
+    async fn test_reads_fall_back_to_primary_when_replica_is_down() {
+        let Some((primary, _)) = test_pools().await else { return };
+        let primary_name = database(&primary).await;
+        let unreachable = PgPoolOptions::new()
+            .acquire_timeout(Duration::from_millis(500))
+            .connect_lazy("postgres://quantera@127.0.0.1:1/replica")
+            .unwrap();
+        let router = DbRouter::new(Arc::new(primary)).with_replica(Arc::new(unreachable), DEFAULT_MAX_REPLICA_LAG_BYTES);
+
+        assert_eq!(database(router.read().await).await, primary_name);
+        // The failed check is cached rather than retried on every read
+        assert_eq!(database(router.read().await).await, primary_name);
+    }
+}
@@ -0,0 +1,305 @@
+// Feature flags for experimental endpoints, configured per environment with runtime overrides
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::{error, info};
+
+use crate::api::secure_api::{JwtClaims, UserRole};
+use crate::task_health::TaskHealth;
+use crate::tenant::{TenantId, TenantScope};
+
+/// Wallet login with the legacy simplified signature check
+pub const LEGACY_LOGIN: &str = "legacy_login";
+
+pub const FLAG_REFRESH_TASK: &str = "feature_flag_refresh";
+
+/// A flag as configured: the default plus tenant and role overrides.
+///
+/// A tenant override wins over a role override, which wins over the default.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FlagDefinition {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub description: String,
+    /// Keyed by tenant id
+    #[serde(default)]
+    pub tenants: BTreeMap<String, bool>,
+    /// Keyed by role name, e.g. `Admin`
+    #[serde(default)]
+    pub roles: BTreeMap<String, bool>,
+}
+
+impl FlagDefinition {
+    fn new(enabled: bool, description: &str) -> Self {
+        Self { enabled, description: description.to_string(), ..Default::default() }
+    }
+
+    fn evaluate(&self, tenant: Option<&TenantId>, role: Option<&UserRole>) -> bool {
+        tenant.and_then(|tenant| self.tenants.get(tenant.as_str()))
+            .or_else(|| role.and_then(|role| self.roles.get(&role_key(role))))
+            .copied()
+            .unwrap_or(self.enabled)
+    }
+
+    fn apply(&mut self, scope: &FlagScope, enabled: bool) {
+        match scope {
+            FlagScope::Global => self.enabled = enabled,
+            FlagScope::Tenant(tenant) => { self.tenants.insert(tenant.clone(), enabled); }
+            FlagScope::Role(role) => { self.roles.insert(role.clone(), enabled); }
+        }
+    }
+}
+
+/// What a runtime override applies to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FlagScope {
+    Global,
+    Tenant(String),
+    Role(String),
+}
+
+impl FlagScope {
+    fn columns(&self) -> (&'static str, &str) {
+        match self {
+            FlagScope::Global => ("global", ""),
+            FlagScope::Tenant(tenant) => ("tenant", tenant),
+            FlagScope::Role(role) => ("role", role),
+        }
+    }
+
+    fn from_columns(scope_type: &str, scope_value: String) -> Option<Self> {
+        match scope_type {
+            "global" => Some(FlagScope::Global),
+            "tenant" => Some(FlagScope::Tenant(scope_value)),
+            "role" => Some(FlagScope::Role(scope_value)),
+            _ => None,
+        }
+    }
+}
+
+/// Current state of a flag, as reported to admins
+#[derive(Debug, Clone, Serialize)]
+pub struct FlagStatus {
+    pub name: String,
+    #[serde(flatten)]
+    pub definition: FlagDefinition,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FlagError {
+    #[error("Unknown feature flag: {0}")]
+    UnknownFlag(String),
+    #[error("Failed to persist feature flag: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+fn role_key(role: &UserRole) -> String {
+    format!("{:?}", role)
+}
+
+/// Flags known to the backend when the configuration does not mention them
+fn builtin_flags() -> HashMap<String, FlagDefinition> {
+    HashMap::from([
+        (LEGACY_LOGIN.to_string(), FlagDefinition::new(false, "Wallet login without the challenge-response flow")),
+    ])
+}
+
+/// Flag registry; configured defaults with overrides toggled at runtime and kept in the database
+pub struct FeatureFlags {
+    configured: HashMap<String, FlagDefinition>,
+    flags: RwLock<HashMap<String, FlagDefinition>>,
+    db: Option<Arc<PgPool>>,
+}
+
+impl FeatureFlags {
+    pub fn new(definitions: HashMap<String, FlagDefinition>) -> Self {
+        let mut configured = builtin_flags();
+        configured.extend(definitions);
+        Self {
+            flags: RwLock::new(configured.clone()),
+            configured,
+            db: None,
+        }
+    }
+
+    /// Read the environment's flag definitions from the JSON file at `FEATURE_FLAGS_PATH`
+    pub fn from_env() -> Result<Self, String> {
+        let definitions = match std::env::var("FEATURE_FLAGS_PATH") {
+            Ok(path) if !path.is_empty() => {
+                let contents = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("Failed to read FEATURE_FLAGS_PATH {}: {}", path, e))?;
+                serde_json::from_str(&contents)
+                    .map_err(|e| format!("Invalid feature flags in {}: {}", path, e))?
+            }
+            _ => HashMap::new(),
+        };
+        Ok(Self::new(definitions))
+    }
+
+    /// Persist runtime overrides to `db`
+    pub fn with_db(mut self, db: Arc<PgPool>) -> Self {
+        self.db = Some(db);
+        self
+    }
+
+    /// Whether `flag` is on for a tenant and role; undefined flags are off
+    pub fn is_enabled(&self, flag: &str, tenant: Option<&TenantId>, role: Option<&UserRole>) -> bool {
+        self.flags.read().expect("feature flags lock poisoned")
+            .get(flag)
+            .map(|definition| definition.evaluate(tenant, role))
+            .unwrap_or(false)
+    }
+
+    /// Toggle a flag for `scope`; takes effect immediately and is written through to the database
+    pub async fn set(&self, flag: &str, scope: FlagScope, enabled: bool, updated_by: &str) -> Result<FlagStatus, FlagError> {
+        if !self.configured.contains_key(flag) {
+            return Err(FlagError::UnknownFlag(flag.to_string()));
+        }
+
+        if let Some(db) = &self.db {
+            let (scope_type, scope_value) = scope.columns();
+            sqlx::query(
+                "INSERT INTO feature_flag_overrides (flag, scope_type, scope_value, enabled, updated_by, updated_at)
+                 VALUES ($1, $2, $3, $4, $5, NOW())
+                 ON CONFLICT (flag, scope_type, scope_value)
+                 DO UPDATE SET enabled = EXCLUDED.enabled, updated_by = EXCLUDED.updated_by, updated_at = NOW()"
+            )
+            .bind(flag)
+            .bind(scope_type)
+            .bind(scope_value)
+            .bind(enabled)
+            .bind(updated_by)
+            .execute(db.as_ref())
+            .await?;
+        }
+
+        let mut flags = self.flags.write().expect("feature flags lock poisoned");
+        let definition = flags.entry(flag.to_string()).or_default();
+        definition.apply(&scope, enabled);
+        info!("Feature flag {} set to {} for {:?} by {}", flag, enabled, scope, updated_by);
+        Ok(FlagStatus { name: flag.to_string(), definition: definition.clone() })
+    }
+
+    /// Rebuild the flags from configuration and the stored overrides, picking up
+    /// toggles made through other replicas
+    pub async fn reload_overrides(&self) -> Result<usize, sqlx::Error> {
+        let Some(db) = &self.db else { return Ok(0) };
+        let rows: Vec<(String, String, String, bool)> = sqlx::query_as(
+            "SELECT flag, scope_type, scope_value, enabled FROM feature_flag_overrides"
+        )
+        .fetch_all(db.as_ref())
+        .await?;
+
+        let mut flags = self.configured.clone();
+        let mut applied = 0;
+        for (flag, scope_type, scope_value, enabled) in rows {
+            let (Some(definition), Some(scope)) = (flags.get_mut(&flag), FlagScope::from_columns(&scope_type, scope_value)) else {
+                continue;
+            };
+            definition.apply(&scope, enabled);
+            applied += 1;
+        }
+        *self.flags.write().expect("feature flags lock poisoned") = flags;
+        Ok(applied)
+    }
+
+    /// Every flag with its current overrides, by name
+    pub fn snapshot(&self) -> Vec<FlagStatus> {
+        let mut flags: Vec<FlagStatus> = self.flags.read().expect("feature flags lock poisoned")
+            .iter()
+            .map(|(name, definition)| FlagStatus { name: name.clone(), definition: definition.clone() })
+            .collect();
+        flags.sort_by(|a, b| a.name.cmp(&b.name));
+        flags
+    }
+}
+
+/// Reload stored overrides every `every` so toggles reach all replicas
+pub fn spawn_flag_refresh(flags: Arc<FeatureFlags>, health: Arc<TaskHealth>, every: Duration) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(every);
+        loop {
+            ticker.tick().await;
+            match flags.reload_overrides().await {
+                Ok(_) => health.record_success(FLAG_REFRESH_TASK),
+                Err(e) => {
+                    error!("Feature flag refresh failed: {}", e);
+                    health.record_failure(FLAG_REFRESH_TASK, e);
+                }
+            }
+        }
+    })
+}
+
+/// Route middleware hiding a route while its flag is off for the caller.
+///
+/// Runs after authentication so tenant and role overrides apply. A disabled route
+/// answers 404 like one that does not exist.
+pub async fn require_flag(
+    State((flags, flag)): State<(Arc<FeatureFlags>, &'static str)>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let tenant = req.extensions().get::<TenantScope>().and_then(TenantScope::tenant);
+    let role = req.extensions().get::<JwtClaims>().map(|claims| &claims.role);
+    if !flags.is_enabled(flag, tenant, role) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_tenant_override_wins_over_role_and_default() {
+        let flags = FeatureFlags::new(HashMap::from([("beta_reports".to_string(), FlagDefinition {
+            enabled: false,
+            description: String::new(),
+            tenants: BTreeMap::from([("tenant-a".to_string(), false), ("tenant-b".to_string(), true)]),
+            roles: BTreeMap::from([("Admin".to_string(), true)]),
+        })]));
+        let (tenant_a, tenant_b, tenant_c) = (TenantId::new("tenant-a"), TenantId::new("tenant-b"), TenantId::new("tenant-c"));
+
+        assert!(!flags.is_enabled("beta_reports", Some(&tenant_a), Some(&UserRole::Admin)));
+        assert!(flags.is_enabled("beta_reports", Some(&tenant_b), Some(&UserRole::Investor)));
+        assert!(flags.is_enabled("beta_reports", Some(&tenant_c), Some(&UserRole::Admin)));
+        assert!(!flags.is_enabled("beta_reports", Some(&tenant_c), Some(&UserRole::Investor)));
+        assert!(!flags.is_enabled("never_defined", None, None));
+    }
+
+    #[tokio::test]
+    async fn test_runtime_toggle_applies_to_guarded_route() {
+        let flags = Arc::new(FeatureFlags::new(HashMap::new()));
+        let app = Router::new().route(
+            "/experimental",
+            get(|| async { "ok" }).route_layer(middleware::from_fn_with_state((flags.clone(), LEGACY_LOGIN), require_flag)),
+        );
+        let status = |app: Router| async move {
+            app.oneshot(Request::builder().uri("/experimental").body(Body::empty()).unwrap()).await.unwrap().status()
+        };
+
+        assert_eq!(status(app.clone()).await, StatusCode::NOT_FOUND);
+        flags.set(LEGACY_LOGIN, FlagScope::Global, true, "ops").await.unwrap();
+        assert_eq!(status(app.clone()).await, StatusCode::OK);
+        flags.set(LEGACY_LOGIN, FlagScope::Global, false, "ops").await.unwrap();
+        assert_eq!(status(app).await, StatusCode::NOT_FOUND);
+
+        assert!(matches!(
+            flags.set("never_defined", FlagScope::Global, true, "ops").await,
+            Err(FlagError::UnknownFlag(_))
+        ));
+    }
+}
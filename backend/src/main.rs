@@ -20,10 +20,17 @@ use sqlx::postgres::PgPool;
 mod services;
 mod compliance;
 mod api;
+mod tenant;
+mod task_health;
+mod audit_sink;
+mod feature_flags;
+mod db_routing;
+mod consistency;
 
 use services::market_maker_service::MarketMakerService;
 use compliance::enhanced_compliance_engine::EnhancedComplianceEngine;
-use api::secure_api::{SecureApiState, AtomicRateLimiter, AuditLogger};
+use api::secure_api::{SecureApiState, AuditLogger};
+use api::rate_limit::RateLimitBackend;
 
 // Security constants
 const MAX_REQUEST_BODY_SIZE: usize = 1024 * 1024; // 1MB max request body
@@ -43,7 +50,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .with_thread_ids(true)
         .init();
 
+    // `quantera-backend run_consistency_checks` runs the checks once and exits, for incident runbooks
+    if std::env::args().nth(1).as_deref() == Some("run_consistency_checks") {
+        std::process::exit(run_consistency_checks_once().await);
+    }
+
     tracing::info!("Starting Quantera Backend v2.0.0");
+    if !quantera_types::redaction_salt_from_env() {
+        tracing::warn!("{} is not set; redacted log fields are hashed without a salt", quantera_types::REDACTION_SALT_ENV);
+    }
 
     // Load configuration with validation
     let port = std::env::var("API_PORT")
@@ -99,6 +114,27 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .expect("Failed to connect to database");
 
     tracing::info!("Database connection pool established with {} max connections", max_connections);
+
+    // Read-only stats, history and export queries go to DATABASE_REPLICA_URL when set.
+    // The replica pool connects lazily so an unavailable replica does not block startup.
+    let mut db_router = db_routing::DbRouter::new(Arc::new(db_pool.clone()));
+    if let Ok(replica_url) = std::env::var("DATABASE_REPLICA_URL") {
+        let max_lag_bytes: u64 = std::env::var("DATABASE_REPLICA_MAX_LAG_BYTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(db_routing::DEFAULT_MAX_REPLICA_LAG_BYTES);
+        let replica_pool = sqlx::postgres::PgPoolOptions::new()
+            .max_connections(max_connections)
+            .acquire_timeout(Duration::from_secs(5))
+            .max_lifetime(Duration::from_secs(max_lifetime))
+            .idle_timeout(Duration::from_secs(600))
+            .test_before_acquire(true)
+            .connect_lazy(&replica_url)
+            .expect("Invalid DATABASE_REPLICA_URL");
+        tracing::info!("Read replica configured, max replay lag {} bytes", max_lag_bytes);
+        db_router = db_router.with_replica(Arc::new(replica_pool), max_lag_bytes);
+    }
+    let db_router = Arc::new(db_router);
     
     // NOTE: Migrations must be applied manually for Phase 3
     // sqlx::migrate! requires integer-prefixed filenames (e.g., 001_auth.sql)
@@ -108,22 +144,112 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize services
     use services::multi_chain_asset_service::MultiChainAssetService;
     let asset_service = Arc::new(RwLock::new(MultiChainAssetService::new()));
-    let compliance_engine = Arc::new(RwLock::new(EnhancedComplianceEngine::new()));
+    let task_health = Arc::new(task_health::TaskHealth::new());
+    let audit_stream = Arc::new(audit_sink::AuditStream::from_env().expect("Invalid audit sink configuration"));
+    // Holdings are valued at aggregated prices from PRICE_API_SOURCES and PRICE_OVERRIDES_PATH
+    let price_aggregator = Arc::new(
+        price_oracle::OracleAggregator::from_env(None).expect("Invalid price oracle configuration")
+    );
+    // Reporting-currency totals convert at the same aggregator's <base><quote> FX quotes, e.g. EURUSD
+    let fx_rates = Arc::new(
+        price_oracle::FxRateService::from_env(price_aggregator.clone()).expect("Invalid FX rate configuration")
+    );
+    // Fiat accreditation thresholds are checked at the aggregated settlement asset price and USD<currency> FX quotes
+    let conversion_rates: Arc<dyn compliance::thresholds::ConversionRates> =
+        Arc::new(compliance::thresholds::OracleConversionRates::new(price_aggregator.clone()));
+    let threshold_config = compliance::thresholds::ThresholdConfig::from_env().expect("Invalid compliance threshold configuration");
+    // Issuer, asset class and portfolio limits are counted against synced holdings valued at the same rates
+    let limit_holdings = compliance::concentration::PortfolioHoldings::new(
+        Arc::new(services::portfolio_service::PortfolioService::new(Arc::new(db_pool.clone())).with_prices(price_aggregator.clone())),
+        conversion_rates.clone(),
+        threshold_config.clone(),
+    );
+    let compliance_engine = Arc::new(RwLock::new(
+        EnhancedComplianceEngine::new()
+            .with_audit_stream(audit_stream.clone())
+            .with_conversion_rates(conversion_rates.clone(), threshold_config)
+            .with_holdings(Arc::new(limit_holdings)),
+    ));
+    // Collateral is valued at the same aggregated prices, converted to each account's base currency
+    let prime_brokerage = Arc::new(RwLock::new(services::prime_brokerage_service::PrimeBrokerageService::with_config(
+        services::prime_brokerage_service::MarginConfig::from_env().expect("Invalid prime brokerage margin configuration"),
+    ).with_prices(conversion_rates)));
+    
+    // Credentials come from SECRETS_PROVIDER (environment variables unless configured otherwise)
+    let secrets = quantera_secrets::SecretsConfig::from_env()
+        .expect("Invalid secrets provider configuration")
+        .build()
+        .await
+        .expect("Failed to initialize secrets provider");
+    let jwt_secret = secrets.rotating(quantera_secrets::SecretRef::new("JWT_SECRET"))
+        .await
+        .expect("JWT_SECRET must be available from the secrets provider");
     
-    // Get JWT secret
-    let jwt_secret = std::env::var("JWT_SECRET")
-        .expect("JWT_SECRET must be set in .env");
+    // Flags come from FEATURE_FLAGS_PATH; overrides toggled through the admin API are stored in the database
+    let feature_flags = Arc::new(
+        feature_flags::FeatureFlags::from_env()
+            .expect("Invalid feature flag configuration")
+            .with_db(Arc::new(db_pool.clone())),
+    );
+    if let Err(e) = feature_flags.reload_overrides().await {
+        tracing::warn!("Failed to load stored feature flag overrides: {}", e);
+    }
+    
+    // Postgres is compared with the risk cache, registry and IPFS stores in CONSISTENCY_* / store settings
+    let consistency_config = consistency::ConsistencyConfig::from_env().expect("Invalid consistency check configuration");
+    let consistency_checker = Arc::new(
+        consistency::ConsistencyChecker::from_config(Arc::new(db_pool.clone()), &consistency_config)
+            .expect("Invalid consistency check store configuration")
+            .with_profiles(compliance_engine.clone()),
+    );
     
     // Create secure API state with atomic rate limiter
     let secure_state = SecureApiState {
         asset_service: asset_service.clone(),
         compliance_engine: compliance_engine.clone(),
         jwt_secret: jwt_secret.clone(),
-        rate_limiter: Arc::new(AtomicRateLimiter::new()),
-        audit_logger: Arc::new(RwLock::new(AuditLogger::new())),
+        rate_limiter: Arc::new(RateLimitBackend::from_env().expect("Invalid rate limit configuration")),
+        audit_logger: Arc::new(RwLock::new(AuditLogger::new().with_db(Arc::new(db_pool.clone())).with_read_router(db_router.clone()).with_stream(audit_stream.clone()))),
         db: Arc::new(db_pool.clone()),
+        db_router,
+        challenge_limits: api::auth_challenge::ChallengeLimits::from_env(),
+        session_limits: api::auth_sessions::SessionLimits::from_env(),
+        prime_brokerage: prime_brokerage.clone(),
+        task_health: task_health.clone(),
+        summary_cache: Arc::new(api::admin_summary::AdminSummaryCache::default()),
+        feature_flags: feature_flags.clone(),
+        service_keys: Arc::new(quantera_service_auth::ServiceKeys::new(Arc::new(
+            quantera_service_auth::PgServiceKeyStore::new(db_pool.clone()),
+        ))),
+        fx_rates: fx_rates.clone(),
+        consistency: consistency_checker.clone(),
     };
     
+    // Expired and used auth challenges are deleted every 10 minutes
+    api::auth_challenge::spawn_challenge_cleanup(secure_state.db.clone(), task_health.clone(), std::time::Duration::from_secs(600));
+    
+    // Future-dated margin ratio changes take effect within a minute of their effective date
+    services::prime_brokerage_service::spawn_margin_ratio_activation(prime_brokerage, task_health.clone(), std::time::Duration::from_secs(60));
+    
+    // Flag overrides made through other replicas are picked up within 30 seconds
+    feature_flags::spawn_flag_refresh(feature_flags, task_health.clone(), std::time::Duration::from_secs(30));
+    
+    // Scheduled consistency runs only when CONSISTENCY_CHECK_INTERVAL_SECS is set
+    if let Some(every) = consistency_config.interval {
+        consistency::spawn_consistency_checks(consistency_checker, task_health.clone(), every);
+    }
+    
+    // Audit events are shipped to the SIEM sinks in AUDIT_SINKS, if any
+    audit_stream.spawn_delivery(task_health.clone());
+    
+    // Country risk tiers are reloaded from JURISDICTION_RISK_SOURCE daily
+    compliance::jurisdiction_risk::spawn_jurisdiction_risk_refresh(
+        secure_state.db.clone(),
+        compliance_engine.clone(),
+        task_health.clone(),
+        std::time::Duration::from_secs(86400),
+    );
+    
     // Keep db_pool Arc for other routers
     let db_arc = Arc::new(db_pool);
 
@@ -144,8 +270,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .route("/", get(|| async { "Quantera Backend API v2.0.0" }))
         .route("/health", get(health_check))
         .merge(api::secure_api::create_secure_router(secure_state))
-        .merge(api::portfolio_api::create_portfolio_router(db_arc.clone()))
-        .merge(api::tradefinance_api::create_tradefinance_router(db_arc.clone()))
+        .merge(api::portfolio_api::create_portfolio_router(db_arc.clone(), price_aggregator, fx_rates, jwt_secret.clone()))
+        .merge(api::tradefinance_api::create_tradefinance_router(db_arc.clone(), jwt_secret))
         // Security layers
         .layer(DefaultBodyLimit::max(MAX_REQUEST_BODY_SIZE))
         .layer(cors);
@@ -163,10 +289,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
 /// Validate that all required environment variables are set
 fn validate_required_env_vars() {
-    let required_vars = [
+    let mut required_vars = vec![
         ("DATABASE_URL", "Database connection string"),
-        ("JWT_SECRET", "JWT signing secret (min 64 chars recommended)"),
     ];
+    // With Vault or AWS Secrets Manager the JWT secret is not in the environment
+    let env_secrets = std::env::var("SECRETS_PROVIDER").map(|p| p.eq_ignore_ascii_case("env")).unwrap_or(true);
+    if env_secrets {
+        required_vars.push(("JWT_SECRET", "JWT signing secret (min 64 chars recommended)"));
+    }
 
     let mut missing = Vec::new();
     let mut warnings = Vec::new();
@@ -213,6 +343,47 @@ fn validate_required_env_vars() {
     tracing::info!("Environment validation passed");
 }
 
+/// One consistency run against the stores in the environment; prints the report as JSON.
+/// Investor profiles live in the server's memory, so that check is skipped here and runs through
+/// POST /api/v1/admin/consistency-checks instead. Exits 2 on critical findings, 1 if the run failed.
+async fn run_consistency_checks_once() -> i32 {
+    let (database_url, config) = match (
+        std::env::var("DATABASE_URL").map_err(|_| "DATABASE_URL must be set".to_string()),
+        consistency::ConsistencyConfig::from_env(),
+    ) {
+        (Ok(url), Ok(config)) => (url, config),
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::error!("{}", e);
+            return 1;
+        }
+    };
+    let db = match PgPool::connect(&database_url).await {
+        Ok(db) => Arc::new(db),
+        Err(e) => {
+            tracing::error!("Failed to connect to database: {}", e);
+            return 1;
+        }
+    };
+    let checker = match consistency::ConsistencyChecker::from_config(db, &config) {
+        Ok(checker) => checker,
+        Err(e) => {
+            tracing::error!("{}", e);
+            return 1;
+        }
+    };
+
+    match checker.run_and_store(consistency::RunTrigger::Cli).await {
+        Ok(report) => {
+            println!("{}", serde_json::to_string_pretty(&report).unwrap_or_default());
+            if report.worst_severity() == Some(consistency::FindingSeverity::Critical) { 2 } else { 0 }
+        }
+        Err(e) => {
+            tracing::error!("Failed to store consistency run: {}", e);
+            1
+        }
+    }
+}
+
 async fn health_check() -> impl IntoResponse {
     Json(json!({
         "status": "healthy",
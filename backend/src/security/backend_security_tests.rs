@@ -36,7 +36,7 @@ impl BackendSecurityTestSuite {
         let state = SecureApiState {
             asset_service,
             compliance_engine: Arc::new(RwLock::new(compliance_engine)),
-            jwt_secret: "test-secret-key".to_string(),
+            jwt_secret: quantera_secrets::RotatingSecret::fixed(quantera_secrets::SecretValue::new("test-secret-key")),
             rate_limiter: Arc::new(RwLock::new(RateLimiter::new())),
             audit_logger: Arc::new(RwLock::new(AuditLogger::new())),
         };
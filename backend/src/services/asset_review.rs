@@ -0,0 +1,271 @@
+// Maker-checker review of new assets: nothing is deployable until a second person approves it
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::info;
+
+use crate::tenant::{TenantId, TenantScope};
+
+/// Where an asset stands in review
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalStatus {
+    PendingApproval,
+    Approved,
+    /// Sent back with comments; editable and resubmittable
+    Rejected,
+}
+
+impl Default for ApprovalStatus {
+    /// Assets recorded before reviews existed were already deployable
+    fn default() -> Self {
+        ApprovalStatus::Approved
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewOutcome {
+    Approved,
+    Rejected,
+}
+
+/// One reviewer's decision on one revision of an asset
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewDecision {
+    pub reviewer: String,
+    pub outcome: ReviewOutcome,
+    pub comments: Option<String>,
+    pub revision: u32,
+    pub decided_at: DateTime<Utc>,
+}
+
+/// Review history of an asset
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetReview {
+    pub asset_id: String,
+    pub tenant_id: TenantId,
+    pub status: ApprovalStatus,
+    /// Starts at 1 and increases on every resubmission
+    pub revision: u32,
+    pub created_by: String,
+    pub submitted_by: String,
+    pub submitted_at: DateTime<Utc>,
+    /// Everyone who created, edited or resubmitted the asset; none of them may approve it
+    pub makers: Vec<String>,
+    pub decisions: Vec<ReviewDecision>,
+}
+
+impl AssetReview {
+    pub fn latest_decision(&self) -> Option<&ReviewDecision> {
+        self.decisions.last()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReviewError {
+    NotFound,
+    /// The reviewer created or changed the asset
+    SelfApproval,
+    /// Only pending assets can be approved or rejected
+    NotPending(ApprovalStatus),
+    /// Only rejected assets can be edited or resubmitted
+    NotEditable(ApprovalStatus),
+    NotApproved(ApprovalStatus),
+    CommentsRequired,
+}
+
+impl std::fmt::Display for ReviewError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ReviewError::NotFound => write!(f, "Asset review not found"),
+            ReviewError::SelfApproval => write!(f, "An asset cannot be reviewed by anyone who created or changed it"),
+            ReviewError::NotPending(status) => write!(f, "Asset is not awaiting approval (status {:?})", status),
+            ReviewError::NotEditable(status) => write!(f, "Only rejected assets can be changed (status {:?})", status),
+            ReviewError::NotApproved(status) => write!(f, "Asset has not been approved for deployment (status {:?})", status),
+            ReviewError::CommentsRequired => write!(f, "A rejection must say what needs to change"),
+        }
+    }
+}
+
+impl std::error::Error for ReviewError {}
+
+/// Told about every submission and decision, e.g. to alert reviewers or the issuer
+pub trait ReviewNotifier: Send + Sync {
+    fn notify(&self, review: &AssetReview, actor: &str);
+}
+
+/// Default notifier: a log line per transition
+pub struct LogReviewNotifier;
+
+impl ReviewNotifier for LogReviewNotifier {
+    fn notify(&self, review: &AssetReview, actor: &str) {
+        info!(
+            "Asset {} revision {} is {:?} (by {})",
+            review.asset_id, review.revision, review.status, actor
+        );
+    }
+}
+
+/// Reviews keyed by asset id
+pub struct ReviewRegistry {
+    reviews: HashMap<String, AssetReview>,
+}
+
+impl ReviewRegistry {
+    pub fn new() -> Self {
+        Self { reviews: HashMap::new() }
+    }
+
+    /// Queue a newly created asset for approval
+    pub fn submit_new(&mut self, asset_id: &str, tenant_id: &TenantId, created_by: &str, now: DateTime<Utc>) -> &AssetReview {
+        let review = AssetReview {
+            asset_id: asset_id.to_string(),
+            tenant_id: tenant_id.clone(),
+            status: ApprovalStatus::PendingApproval,
+            revision: 1,
+            created_by: created_by.to_string(),
+            submitted_by: created_by.to_string(),
+            submitted_at: now,
+            makers: vec![created_by.to_string()],
+            decisions: Vec::new(),
+        };
+        self.reviews.insert(asset_id.to_string(), review);
+        &self.reviews[asset_id]
+    }
+
+    pub fn get(&self, asset_id: &str) -> Option<&AssetReview> {
+        self.reviews.get(asset_id)
+    }
+
+    /// Pending reviews in the scope, oldest submission first
+    pub fn pending(&self, scope: &TenantScope) -> Vec<&AssetReview> {
+        let mut pending: Vec<_> = self.reviews.values()
+            .filter(|review| review.status == ApprovalStatus::PendingApproval && scope.allows(&review.tenant_id))
+            .collect();
+        pending.sort_by_key(|review| review.submitted_at);
+        pending
+    }
+
+    pub fn approve(
+        &mut self,
+        asset_id: &str,
+        reviewer: &str,
+        comments: Option<String>,
+        now: DateTime<Utc>,
+    ) -> Result<&AssetReview, ReviewError> {
+        self.decide(asset_id, reviewer, ReviewOutcome::Approved, comments, now)
+    }
+
+    pub fn reject(&mut self, asset_id: &str, reviewer: &str, comments: &str, now: DateTime<Utc>) -> Result<&AssetReview, ReviewError> {
+        if comments.trim().is_empty() {
+            return Err(ReviewError::CommentsRequired);
+        }
+        self.decide(asset_id, reviewer, ReviewOutcome::Rejected, Some(comments.trim().to_string()), now)
+    }
+
+    /// Check a rejected asset may be edited, recording the editor as one of its makers
+    pub fn record_edit(&mut self, asset_id: &str, editor: &str) -> Result<(), ReviewError> {
+        let review = self.editable(asset_id)?;
+        if !review.makers.iter().any(|maker| maker == editor) {
+            review.makers.push(editor.to_string());
+        }
+        Ok(())
+    }
+
+    /// Send a rejected asset back for approval as a new revision
+    pub fn resubmit(&mut self, asset_id: &str, submitted_by: &str, now: DateTime<Utc>) -> Result<&AssetReview, ReviewError> {
+        let review = self.editable(asset_id)?;
+        review.status = ApprovalStatus::PendingApproval;
+        review.revision += 1;
+        review.submitted_by = submitted_by.to_string();
+        review.submitted_at = now;
+        if !review.makers.iter().any(|maker| maker == submitted_by) {
+            review.makers.push(submitted_by.to_string());
+        }
+        Ok(review)
+    }
+
+    fn editable(&mut self, asset_id: &str) -> Result<&mut AssetReview, ReviewError> {
+        let review = self.reviews.get_mut(asset_id).ok_or(ReviewError::NotFound)?;
+        match review.status {
+            ApprovalStatus::Rejected => Ok(review),
+            status => Err(ReviewError::NotEditable(status)),
+        }
+    }
+
+    fn decide(
+        &mut self,
+        asset_id: &str,
+        reviewer: &str,
+        outcome: ReviewOutcome,
+        comments: Option<String>,
+        now: DateTime<Utc>,
+    ) -> Result<&AssetReview, ReviewError> {
+        let review = self.reviews.get_mut(asset_id).ok_or(ReviewError::NotFound)?;
+        if review.status != ApprovalStatus::PendingApproval {
+            return Err(ReviewError::NotPending(review.status));
+        }
+        if review.makers.iter().any(|maker| maker == reviewer) {
+            return Err(ReviewError::SelfApproval);
+        }
+
+        review.status = match outcome {
+            ReviewOutcome::Approved => ApprovalStatus::Approved,
+            ReviewOutcome::Rejected => ApprovalStatus::Rejected,
+        };
+        review.decisions.push(ReviewDecision {
+            reviewer: reviewer.to_string(),
+            outcome,
+            comments,
+            revision: review.revision,
+            decided_at: now,
+        });
+        Ok(review)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_makers_cannot_approve_their_own_asset() {
+        let now = Utc::now();
+        let mut reviews = ReviewRegistry::new();
+        reviews.submit_new("asset-1", &TenantId::default(), "manager", now);
+
+        assert_eq!(reviews.approve("asset-1", "manager", None, now).unwrap_err(), ReviewError::SelfApproval);
+        assert_eq!(reviews.reject("asset-1", "checker", "  ", now).unwrap_err(), ReviewError::CommentsRequired);
+
+        let review = reviews.approve("asset-1", "checker", Some("Looks right".to_string()), now).unwrap();
+        assert_eq!(review.status, ApprovalStatus::Approved);
+        assert_eq!(review.latest_decision().unwrap().reviewer, "checker");
+        assert_eq!(
+            reviews.approve("asset-1", "other", None, now).unwrap_err(),
+            ReviewError::NotPending(ApprovalStatus::Approved),
+        );
+    }
+
+    #[test]
+    fn test_rejected_asset_is_edited_and_resubmitted_as_new_revision() {
+        let now = Utc::now();
+        let mut reviews = ReviewRegistry::new();
+        reviews.submit_new("asset-1", &TenantId::default(), "manager", now);
+
+        assert!(matches!(reviews.record_edit("asset-1", "manager"), Err(ReviewError::NotEditable(_))));
+        reviews.reject("asset-1", "checker", "Jurisdiction should be DE", now).unwrap();
+
+        reviews.record_edit("asset-1", "manager").unwrap();
+        reviews.record_edit("asset-1", "checker").unwrap();
+        let review = reviews.resubmit("asset-1", "manager", now).unwrap();
+        assert_eq!(review.status, ApprovalStatus::PendingApproval);
+        assert_eq!(review.revision, 2);
+        assert_eq!(review.decisions[0].revision, 1);
+
+        // Having edited the asset, the first reviewer is now a maker too
+        assert_eq!(reviews.approve("asset-1", "checker", None, now).unwrap_err(), ReviewError::SelfApproval);
+        assert_eq!(reviews.approve("asset-1", "second-checker", None, now).unwrap().revision, 2);
+        assert!(reviews.pending(&TenantScope::AllTenants).is_empty());
+    }
+}
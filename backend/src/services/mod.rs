@@ -1,8 +1,11 @@
 pub mod market_maker_service;
 pub mod multi_chain_asset_service;
+pub mod symbol_registry;
+pub mod asset_review;
 pub mod cross_exchange_service;
 pub mod institutional_custody_service;
 pub mod prime_brokerage_service;
 pub mod liquidity_analytics_service;
 pub mod portfolio_service; // Phase 5
-pub mod tradefinance_service; // Phase 5 
\ No newline at end of file
+pub mod tradefinance_service; // Phase 5 
+pub mod statements;
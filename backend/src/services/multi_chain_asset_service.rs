@@ -1,10 +1,26 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio;
 use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use ethers::abi::{self, Token};
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::types::{Address, Bytes, TransactionRequest, U256};
 use uuid::Uuid;
+use rust_decimal::Decimal;
+use quantera_types::{Currency, Finality, FinalityDowngrade, FinalityPolicy, FinalityStatus};
 use rand;
 
+use crate::compliance::enhanced_compliance_engine::InvestorType;
+use crate::tenant::{TenantId, TenantScope};
+use super::symbol_registry::{RenameRequest, SymbolRegistry, SymbolReservation, SymbolScope};
+use super::asset_review::{ApprovalStatus, AssetReview, LogReviewNotifier, ReviewError, ReviewNotifier, ReviewRegistry};
+
+/// OP Stack GasPriceOracle predeploy, used for L1 data fees on Optimism and Base
+pub const OP_STACK_GAS_ORACLE: &str = "0x420000000000000000000000000000000000000F";
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum SupportedChain {
     Ethereum,
@@ -52,7 +68,9 @@ pub struct ChainConfig {
     pub supports_blobs: bool,   // EIP-4844 blob support
     pub gas_token: String,
     pub average_block_time: u64, // in seconds
-    pub finality_blocks: u64,
+    /// When a deployment counts as confirmed and finalized on this chain
+    pub finality: FinalityPolicy,
+    pub l1_gas_oracle: Option<String>, // Rollup oracle charging L1 data fees, if any
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -78,18 +96,100 @@ pub enum SettlementAssetType {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CrossChainAsset {
     pub asset_id: String,
+    #[serde(default)]
+    pub tenant_id: TenantId,
     pub name: String,
     pub symbol: String,
     pub asset_type: AssetType,
     pub deployments: HashMap<SupportedChain, AssetDeployment>,
     pub total_supply: u128,
+    /// ISO 4217 currency the asset is valued in; assets stored before currencies were tracked are in USD
+    #[serde(default)]
+    pub currency: Currency,
     pub compliance_standard: ComplianceStandard,
     pub regulatory_framework: String,
     pub jurisdiction: String,
+    #[serde(default)]
+    pub distribution: DistributionRules,
+    /// Only approved assets may be deployed
+    #[serde(default)]
+    pub approval_status: ApprovalStatus,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// Changes to a rejected asset's configuration; unset fields are left as they are.
+/// The symbol is not editable here, it changes through the rename flow.
+#[derive(Debug, Clone, Default)]
+pub struct AssetConfigUpdate {
+    pub name: Option<String>,
+    pub asset_type: Option<AssetType>,
+    pub compliance_standard: Option<ComplianceStandard>,
+    pub regulatory_framework: Option<String>,
+    pub jurisdiction: Option<String>,
+    pub total_supply: Option<u128>,
+    pub distribution: Option<DistributionRules>,
+}
+
+/// A pending asset with everything a reviewer needs to decide on it
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetReviewItem {
+    pub asset: CrossChainAsset,
+    pub review: AssetReview,
+}
+
+/// Who an asset may be offered to. Empty lists place no restriction on that dimension.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DistributionRules {
+    #[serde(default)]
+    pub allowed_jurisdictions: Vec<String>,
+    #[serde(default)]
+    pub allowed_investor_types: Vec<InvestorType>,
+    /// Private placements (e.g. Reg D) are never offered to retail investors
+    #[serde(default)]
+    pub private_placement: bool,
+    /// Smallest subscription accepted, in the asset's currency
+    #[serde(default)]
+    pub minimum_investment: Option<Decimal>,
+}
+
+impl DistributionRules {
+    pub fn is_restricted(&self) -> bool {
+        self.private_placement
+            || !self.allowed_jurisdictions.is_empty()
+            || !self.allowed_investor_types.is_empty()
+    }
+
+    pub fn permits(&self, jurisdiction: &str, investor_type: &InvestorType) -> bool {
+        if self.private_placement && *investor_type == InvestorType::Retail {
+            return false;
+        }
+        (self.allowed_jurisdictions.is_empty() || self.allowed_jurisdictions.iter().any(|j| j == jurisdiction))
+            && (self.allowed_investor_types.is_empty() || self.allowed_investor_types.contains(investor_type))
+    }
+}
+
+/// Whose view of the asset catalog is being built
+#[derive(Debug, Clone)]
+pub enum CatalogViewer {
+    /// Compliance and admin roles see every asset in their scope
+    Unrestricted,
+    /// Investors only see assets whose distribution rules admit them
+    Investor { jurisdiction: String, investor_type: InvestorType },
+    /// Callers without an investor profile only see unrestricted assets
+    Unclassified,
+}
+
+impl CatalogViewer {
+    pub fn can_see(&self, asset: &CrossChainAsset) -> bool {
+        match self {
+            CatalogViewer::Unrestricted => true,
+            CatalogViewer::Investor { jurisdiction, investor_type } => asset.distribution.permits(jurisdiction, investor_type),
+            CatalogViewer::Unclassified => !asset.distribution.is_restricted(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AssetDeployment {
     pub contract_address: String,
@@ -97,6 +197,11 @@ pub struct AssetDeployment {
     pub deployment_block: u64,
     pub is_active: bool,
     pub liquidity_pools: Vec<LiquidityPool>,
+    #[serde(default)]
+    pub finality: Finality,
+    /// Confirmed statuses withdrawn after a reorg, kept for audit
+    #[serde(default)]
+    pub finality_downgrades: Vec<FinalityDowngrade>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -121,6 +226,22 @@ pub enum AssetType {
     ArtAndCollectibles,
 }
 
+impl AssetType {
+    /// Asset type as compliance requirements and cooling periods are keyed
+    pub fn compliance_asset_type(&self) -> &'static str {
+        match self {
+            AssetType::RealEstate => "real_estate",
+            AssetType::Commodities => "commodities",
+            AssetType::Securities => "securities",
+            AssetType::TreasuryNotes => "treasuries",
+            AssetType::CorporateBonds => "corporate_bonds",
+            AssetType::PrivateEquity => "private_equity",
+            AssetType::Infrastructure => "infrastructure",
+            AssetType::ArtAndCollectibles => "art_and_collectibles",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ComplianceStandard {
     ERC3643,  // T-REX Protocol
@@ -131,6 +252,7 @@ pub enum ComplianceStandard {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AssetMetrics {
+    /// In the asset's currency
     pub total_value_locked: f64,
     pub market_cap: f64,
     pub trading_volume_24h: f64,
@@ -149,12 +271,263 @@ pub struct CrossChainLiquidity {
     pub bridge_liquidity: f64,
 }
 
+/// Why a chain's liquidity could not be read; only transient failures are retried
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum LiquiditySourceError {
+    #[error("{0}")]
+    Transient(String),
+    #[error("{0}")]
+    Permanent(String),
+}
+
+/// Per-chain source of DEX and bridge liquidity for a deployed asset
+#[async_trait]
+pub trait ChainLiquiditySource: Send + Sync {
+    async fn liquidity(&self, chain: &SupportedChain, contract_address: &str) -> std::result::Result<CrossChainLiquidity, LiquiditySourceError>;
+}
+
+/// Fixed pools standing in for the DEX queries each chain's RPC endpoint would serve
+pub struct SimulatedLiquiditySource;
+
+#[async_trait]
+impl ChainLiquiditySource for SimulatedLiquiditySource {
+    async fn liquidity(&self, chain: &SupportedChain, _contract_address: &str) -> std::result::Result<CrossChainLiquidity, LiquiditySourceError> {
+        let pools = vec![
+            LiquidityPool {
+                pool_address: format!("0x{:040x}", rand::random::<u64>()),
+                dex_name: "Uniswap V3".to_string(),
+                pair_token: "USDC".to_string(),
+                liquidity_usd: 1_000_000.0,
+                volume_24h_usd: 50_000.0,
+                apr: 8.5,
+            },
+            LiquidityPool {
+                pool_address: format!("0x{:040x}", rand::random::<u64>()),
+                dex_name: "SushiSwap".to_string(),
+                pair_token: "USDT".to_string(),
+                liquidity_usd: 500_000.0,
+                volume_24h_usd: 25_000.0,
+                apr: 7.2,
+            },
+        ];
+        
+        let total_liquidity = pools.iter().map(|p| p.liquidity_usd).sum();
+        
+        Ok(CrossChainLiquidity {
+            chain: chain.clone(),
+            total_liquidity_usd: total_liquidity,
+            available_liquidity_usd: total_liquidity * 0.8, // 80% available
+            pools,
+            bridge_liquidity: 200_000.0,
+        })
+    }
+}
+
+/// A chain whose liquidity could not be read, after any retry
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChainLiquidityError {
+    pub error: String,
+    pub timed_out: bool,
+    pub attempts: u32,
+}
+
+/// Liquidity of the chains that answered, and why the others did not
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AssetLiquidity {
+    pub chains: HashMap<SupportedChain, CrossChainLiquidity>,
+    pub errors: HashMap<SupportedChain, ChainLiquidityError>,
+}
+
+impl AssetLiquidity {
+    pub fn is_partial(&self) -> bool {
+        !self.errors.is_empty()
+    }
+}
+
+/// Liquidity query outcomes for one chain since the service started
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ChainLiquidityStats {
+    pub chain: String,
+    pub queries: u64,
+    pub failures: u64,
+    /// Failures whose last attempt timed out
+    pub timeouts: u64,
+    /// Second attempts after a transient failure or timeout
+    pub retries: u64,
+    pub failure_rate: f64,
+}
+
+#[derive(Default)]
+struct LiquidityMetrics {
+    chains: Mutex<HashMap<SupportedChain, ChainLiquidityStats>>,
+}
+
+impl LiquidityMetrics {
+    fn update(&self, chain: &SupportedChain, change: impl FnOnce(&mut ChainLiquidityStats)) {
+        let mut chains = self.chains.lock().expect("liquidity metrics lock poisoned");
+        change(chains.entry(chain.clone()).or_insert_with(|| ChainLiquidityStats {
+            chain: format!("{:?}", chain),
+            ..Default::default()
+        }));
+    }
+    
+    fn record(&self, chain: &SupportedChain, failure: Option<&ChainLiquidityError>) {
+        self.update(chain, |stats| {
+            stats.queries += 1;
+            if let Some(failure) = failure {
+                stats.failures += 1;
+                stats.timeouts += u64::from(failure.timed_out);
+            }
+            stats.failure_rate = stats.failures as f64 / stats.queries as f64;
+        });
+    }
+    
+    fn retried(&self, chain: &SupportedChain) {
+        self.update(chain, |stats| stats.retries += 1);
+    }
+    
+    fn snapshot(&self) -> Vec<ChainLiquidityStats> {
+        let mut stats: Vec<_> = self.chains.lock().expect("liquidity metrics lock poisoned").values().cloned().collect();
+        stats.sort_by(|a, b| a.chain.cmp(&b.chain));
+        stats
+    }
+}
+
+/// Per-chain access to the gas data needed to price a deployment
+#[async_trait]
+pub trait ChainGasClient: Send + Sync {
+    /// Gas units to deploy the given creation bytecode
+    async fn estimate_deployment_gas(&self, bytecode: &[u8]) -> Result<u64>;
+    
+    /// Current gas price in wei
+    async fn gas_price(&self) -> Result<u128>;
+    
+    /// L1 data fee in wei charged by a rollup gas oracle for posting `data`
+    async fn l1_data_fee(&self, oracle: Address, data: &[u8]) -> Result<u128>;
+}
+
+/// JSON-RPC backed gas client
+pub struct RpcGasClient {
+    provider: Provider<Http>,
+}
+
+impl RpcGasClient {
+    pub fn new(rpc_url: &str) -> Result<Self> {
+        let provider = Provider::<Http>::try_from(rpc_url)
+            .map_err(|e| anyhow!("Invalid RPC URL {}: {}", rpc_url, e))?;
+        Ok(Self { provider })
+    }
+}
+
+#[async_trait]
+impl ChainGasClient for RpcGasClient {
+    async fn estimate_deployment_gas(&self, bytecode: &[u8]) -> Result<u64> {
+        let tx = TransactionRequest::new().data(Bytes::from(bytecode.to_vec()));
+        let gas = self.provider.estimate_gas(&tx.into(), None).await?;
+        Ok(gas.as_u64())
+    }
+    
+    async fn gas_price(&self) -> Result<u128> {
+        Ok(self.provider.get_gas_price().await?.as_u128())
+    }
+    
+    async fn l1_data_fee(&self, oracle: Address, data: &[u8]) -> Result<u128> {
+        // getL1Fee(bytes)
+        let mut calldata = vec![0x49, 0x94, 0x8e, 0x0e];
+        calldata.extend(abi::encode(&[Token::Bytes(data.to_vec())]));
+        
+        let tx = TransactionRequest::new().to(oracle).data(calldata);
+        let result = self.provider.call(&tx.into(), None).await?;
+        if result.len() < 32 {
+            return Err(anyhow!("Unexpected getL1Fee response from {:?}", oracle));
+        }
+        
+        Ok(U256::from_big_endian(&result[..32]).as_u128())
+    }
+}
+
+/// USD price of a chain's gas token
+#[async_trait]
+pub trait GasTokenPriceSource: Send + Sync {
+    async fn usd_price(&self, gas_token: &str) -> Result<f64>;
+}
+
+/// Fixed gas token prices, configured via `GAS_TOKEN_PRICES_USD` (e.g. `ETH=3000,MATIC=0.7`)
+pub struct StaticPriceSource {
+    prices: HashMap<String, f64>,
+}
+
+impl StaticPriceSource {
+    pub fn new(prices: HashMap<String, f64>) -> Self {
+        Self { prices }
+    }
+    
+    pub fn from_env() -> Self {
+        let prices = std::env::var("GAS_TOKEN_PRICES_USD")
+            .unwrap_or_else(|_| "ETH=3000,MATIC=0.7,AVAX=35,BNB=600".to_string())
+            .split(',')
+            .filter_map(|entry| {
+                let (symbol, price) = entry.split_once('=')?;
+                Some((symbol.trim().to_uppercase(), price.trim().parse().ok()?))
+            })
+            .collect();
+        
+        Self { prices }
+    }
+}
+
+#[async_trait]
+impl GasTokenPriceSource for StaticPriceSource {
+    async fn usd_price(&self, gas_token: &str) -> Result<f64> {
+        self.prices.get(&gas_token.to_uppercase())
+            .copied()
+            .ok_or_else(|| anyhow!("No USD price configured for {}", gas_token))
+    }
+}
+
+/// Estimated cost of deploying an asset on one chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeploymentCostEstimate {
+    pub chain: SupportedChain,
+    pub rank: Option<usize>,
+    pub gas_units: Option<u64>,
+    pub gas_price_wei: Option<u128>,
+    pub l1_data_fee_wei: Option<u128>,
+    pub total_cost_native: Option<f64>,
+    pub gas_token: Option<String>,
+    pub total_cost_usd: Option<f64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeploymentCostReport {
+    pub asset_id: String,
+    pub recommended_chain: Option<SupportedChain>,
+    /// Cheapest first; chains that could not be estimated come last with an error
+    pub estimates: Vec<DeploymentCostEstimate>,
+    pub generated_at: chrono::DateTime<chrono::Utc>,
+}
+
 pub struct MultiChainAssetService {
     chain_configs: HashMap<SupportedChain, ChainConfig>,
     supported_assets: HashMap<String, CrossChainAsset>,
     asset_metrics: HashMap<String, AssetMetrics>,
+    gas_clients: HashMap<SupportedChain, Arc<dyn ChainGasClient>>,
+    price_source: Arc<dyn GasTokenPriceSource>,
+    symbols: SymbolRegistry,
+    reviews: ReviewRegistry,
+    review_notifier: Arc<dyn ReviewNotifier>,
+    liquidity_sources: HashMap<SupportedChain, Arc<dyn ChainLiquiditySource>>,
+    liquidity_timeout: Duration,
+    liquidity_metrics: Arc<LiquidityMetrics>,
 }
 
+/// Time one chain's liquidity query may take, per attempt, unless `LIQUIDITY_QUERY_TIMEOUT_MS` says otherwise
+pub const DEFAULT_LIQUIDITY_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Pause before the single retry of a transient liquidity failure
+const LIQUIDITY_RETRY_DELAY: Duration = Duration::from_millis(200);
+
 impl MultiChainAssetService {
     pub fn new() -> Self {
         let mut chain_configs = HashMap::new();
@@ -188,7 +561,8 @@ impl MultiChainAssetService {
             supports_blobs: true,
             gas_token: "ETH".to_string(),
             average_block_time: 12,
-            finality_blocks: 32,
+            finality: FinalityPolicy::for_chain(1),
+            l1_gas_oracle: None,
         });
         
         // Initialize Polygon configuration
@@ -220,7 +594,8 @@ impl MultiChainAssetService {
             supports_blobs: false,
             gas_token: "MATIC".to_string(),
             average_block_time: 2,
-            finality_blocks: 128,
+            finality: FinalityPolicy::for_chain(137),
+            l1_gas_oracle: None,
         });
         
         // Initialize other chains...
@@ -230,9 +605,57 @@ impl MultiChainAssetService {
             chain_configs,
             supported_assets: HashMap::new(),
             asset_metrics: HashMap::new(),
+            gas_clients: HashMap::new(),
+            price_source: Arc::new(StaticPriceSource::from_env()),
+            symbols: SymbolRegistry::new(SymbolScope::from_env()),
+            reviews: ReviewRegistry::new(),
+            review_notifier: Arc::new(LogReviewNotifier),
+            liquidity_sources: HashMap::new(),
+            liquidity_timeout: std::env::var("LIQUIDITY_QUERY_TIMEOUT_MS").ok()
+                .and_then(|ms| ms.parse().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(DEFAULT_LIQUIDITY_TIMEOUT),
+            liquidity_metrics: Arc::new(LiquidityMetrics::default()),
         }
     }
     
+    /// Use a specific gas client for a chain instead of its configured RPC endpoint
+    pub fn with_gas_client(mut self, chain: SupportedChain, client: Arc<dyn ChainGasClient>) -> Self {
+        self.gas_clients.insert(chain, client);
+        self
+    }
+    
+    pub fn with_price_source(mut self, price_source: Arc<dyn GasTokenPriceSource>) -> Self {
+        self.price_source = price_source;
+        self
+    }
+    
+    pub fn with_symbol_scope(mut self, scope: SymbolScope) -> Self {
+        self.symbols = SymbolRegistry::new(scope);
+        self
+    }
+    
+    pub fn with_review_notifier(mut self, notifier: Arc<dyn ReviewNotifier>) -> Self {
+        self.review_notifier = notifier;
+        self
+    }
+    
+    /// Use a specific liquidity source for a chain instead of the simulated pools
+    pub fn with_liquidity_source(mut self, chain: SupportedChain, source: Arc<dyn ChainLiquiditySource>) -> Self {
+        self.liquidity_sources.insert(chain, source);
+        self
+    }
+    
+    pub fn with_liquidity_timeout(mut self, timeout: Duration) -> Self {
+        self.liquidity_timeout = timeout;
+        self
+    }
+    
+    /// Per-chain liquidity query failure rates
+    pub fn liquidity_stats(&self) -> Vec<ChainLiquidityStats> {
+        self.liquidity_metrics.snapshot()
+    }
+    
     fn init_other_chains(chain_configs: &mut HashMap<SupportedChain, ChainConfig>) {
         // Avalanche
         chain_configs.insert(SupportedChain::Avalanche, ChainConfig {
@@ -254,7 +677,8 @@ impl MultiChainAssetService {
             supports_blobs: false,
             gas_token: "AVAX".to_string(),
             average_block_time: 2,
-            finality_blocks: 1,
+            finality: FinalityPolicy::for_chain(43114),
+            l1_gas_oracle: None,
         });
         
         // Arbitrum
@@ -277,7 +701,57 @@ impl MultiChainAssetService {
             supports_blobs: false,
             gas_token: "ETH".to_string(),
             average_block_time: 1,
-            finality_blocks: 1,
+            finality: FinalityPolicy::for_chain(42161),
+            // Arbitrum folds the L1 component into eth_estimateGas
+            l1_gas_oracle: None,
+        });
+        
+        // Optimism
+        chain_configs.insert(SupportedChain::Optimism, ChainConfig {
+            chain_id: 10,
+            rpc_url: "https://mainnet.optimism.io".to_string(),
+            block_explorer: "https://optimistic.etherscan.io".to_string(),
+            settlement_assets: vec![
+                SettlementAsset {
+                    symbol: "USDC".to_string(),
+                    name: "USD Coin".to_string(),
+                    contract_address: "0x0b2C639c533813f4Aa9D7837CAf62653d097Ff85".to_string(),
+                    decimals: 6,
+                    asset_type: SettlementAssetType::STABLECOIN,
+                    is_preferred: true,
+                    risk_weight: 10,
+                },
+            ],
+            supports_eip7702: false,
+            supports_blobs: false,
+            gas_token: "ETH".to_string(),
+            average_block_time: 2,
+            finality: FinalityPolicy::for_chain(10),
+            l1_gas_oracle: Some(OP_STACK_GAS_ORACLE.to_string()),
+        });
+        
+        // Base
+        chain_configs.insert(SupportedChain::Base, ChainConfig {
+            chain_id: 8453,
+            rpc_url: "https://mainnet.base.org".to_string(),
+            block_explorer: "https://basescan.org".to_string(),
+            settlement_assets: vec![
+                SettlementAsset {
+                    symbol: "USDC".to_string(),
+                    name: "USD Coin".to_string(),
+                    contract_address: "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913".to_string(),
+                    decimals: 6,
+                    asset_type: SettlementAssetType::STABLECOIN,
+                    is_preferred: true,
+                    risk_weight: 10,
+                },
+            ],
+            supports_eip7702: false,
+            supports_blobs: false,
+            gas_token: "ETH".to_string(),
+            average_block_time: 2,
+            finality: FinalityPolicy::for_chain(8453),
+            l1_gas_oracle: Some(OP_STACK_GAS_ORACLE.to_string()),
         });
     }
     
@@ -286,6 +760,12 @@ impl MultiChainAssetService {
         asset: &CrossChainAsset,
         target_chains: Vec<SupportedChain>,
     ) -> Result<HashMap<SupportedChain, String>> {
+        let status = self.supported_assets.get(&asset.asset_id)
+            .map_or(asset.approval_status, |stored| stored.approval_status);
+        if status != ApprovalStatus::Approved {
+            return Err(ReviewError::NotApproved(status).into());
+        }
+        
         let mut deployment_addresses = HashMap::new();
         
         for chain in target_chains {
@@ -310,12 +790,7 @@ impl MultiChainAssetService {
         // This would use alloy-rs for Ethereum-compatible chains
         
         // Choose appropriate token standard based on compliance requirements
-        let contract_bytecode = match asset.compliance_standard {
-            ComplianceStandard::ERC3643 => self.get_erc3643_bytecode(),
-            ComplianceStandard::ERC1400 => self.get_erc1400_bytecode(),
-            ComplianceStandard::ERC1404 => self.get_erc1404_bytecode(),
-            ComplianceStandard::Custom(_) => self.get_standard_erc20_bytecode(),
-        };
+        let contract_bytecode = self.get_bytecode(&asset.compliance_standard);
         
         // Simulate deployment (in real implementation, this would use alloy-rs)
         let contract_address = format!("0x{:040x}", rand::random::<u64>());
@@ -326,66 +801,71 @@ impl MultiChainAssetService {
         Ok(contract_address)
     }
     
+    /// Liquidity on every chain the asset is deployed to, queried concurrently.
+    ///
+    /// A chain that errors or exceeds the timeout is reported in `errors` instead of failing
+    /// the whole request; transient failures, timeouts included, are retried once.
     pub async fn get_asset_liquidity_across_chains(
         &self,
+        scope: &TenantScope,
         asset_id: &str,
-    ) -> Result<HashMap<SupportedChain, CrossChainLiquidity>> {
-        let mut liquidity_map = HashMap::new();
-        
+    ) -> Result<AssetLiquidity> {
         // Find asset
-        let asset = self.supported_assets.get(asset_id)
+        let asset = self.get_asset(scope, asset_id)
             .ok_or_else(|| anyhow!("Asset not found: {}", asset_id))?;
         
-        // Query liquidity on each chain where asset is deployed
-        for (chain, deployment) in &asset.deployments {
-            let liquidity = self.query_chain_liquidity(chain, &deployment.contract_address).await?;
-            liquidity_map.insert(chain.clone(), liquidity);
+        let queries = asset.deployments.iter().map(|(chain, deployment)| async move {
+            let outcome = self.query_chain_liquidity(chain, &deployment.contract_address).await;
+            self.liquidity_metrics.record(chain, outcome.as_ref().err());
+            (chain.clone(), outcome)
+        });
+        
+        let mut liquidity = AssetLiquidity::default();
+        for (chain, outcome) in futures::future::join_all(queries).await {
+            match outcome {
+                Ok(chain_liquidity) => {
+                    liquidity.chains.insert(chain, chain_liquidity);
+                }
+                Err(e) => {
+                    tracing::warn!("Liquidity query for {} on {:?} failed after {} attempt(s): {}", asset_id, chain, e.attempts, e.error);
+                    liquidity.errors.insert(chain, e);
+                }
+            }
         }
         
-        Ok(liquidity_map)
+        Ok(liquidity)
     }
     
     async fn query_chain_liquidity(
         &self,
         chain: &SupportedChain,
         contract_address: &str,
-    ) -> Result<CrossChainLiquidity> {
-        // Implementation for querying liquidity on specific chain
-        // This would use the chain's RPC endpoint to query DEX contracts
-        
-        // Simulate liquidity data
-        let pools = vec![
-            LiquidityPool {
-                pool_address: format!("0x{:040x}", rand::random::<u64>()),
-                dex_name: "Uniswap V3".to_string(),
-                pair_token: "USDC".to_string(),
-                liquidity_usd: 1_000_000.0,
-                volume_24h_usd: 50_000.0,
-                apr: 8.5,
-            },
-            LiquidityPool {
-                pool_address: format!("0x{:040x}", rand::random::<u64>()),
-                dex_name: "SushiSwap".to_string(),
-                pair_token: "USDT".to_string(),
-                liquidity_usd: 500_000.0,
-                volume_24h_usd: 25_000.0,
-                apr: 7.2,
-            },
-        ];
-        
-        let total_liquidity = pools.iter().map(|p| p.liquidity_usd).sum();
+    ) -> std::result::Result<CrossChainLiquidity, ChainLiquidityError> {
+        let source: Arc<dyn ChainLiquiditySource> = match self.liquidity_sources.get(chain) {
+            Some(source) => source.clone(),
+            None => Arc::new(SimulatedLiquiditySource),
+        };
         
-        Ok(CrossChainLiquidity {
-            chain: chain.clone(),
-            total_liquidity_usd: total_liquidity,
-            available_liquidity_usd: total_liquidity * 0.8, // 80% available
-            pools,
-            bridge_liquidity: 200_000.0,
-        })
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let (error, timed_out, transient) = match tokio::time::timeout(self.liquidity_timeout, source.liquidity(chain, contract_address)).await {
+                Ok(Ok(liquidity)) => return Ok(liquidity),
+                Ok(Err(LiquiditySourceError::Transient(e))) => (e, false, true),
+                Ok(Err(LiquiditySourceError::Permanent(e))) => (e, false, false),
+                Err(_) => (format!("No response within {}ms", self.liquidity_timeout.as_millis()), true, true),
+            };
+            if !transient || attempts > 1 {
+                return Err(ChainLiquidityError { error, timed_out, attempts });
+            }
+            self.liquidity_metrics.retried(chain);
+            tokio::time::sleep(LIQUIDITY_RETRY_DELAY).await;
+        }
     }
     
     pub async fn create_asset(
         &mut self,
+        tenant_id: TenantId,
         name: String,
         symbol: String,
         asset_type: AssetType,
@@ -393,24 +873,35 @@ impl MultiChainAssetService {
         regulatory_framework: String,
         jurisdiction: String,
         total_supply: u128,
+        currency: Currency,
+        created_by: &str,
     ) -> Result<String> {
         let asset_id = Uuid::new_v4().to_string();
         let now = chrono::Utc::now();
         
+        // Symbols are unique per tenant, or platform-wide, ignoring case
+        self.symbols.assign(&tenant_id, &symbol, &asset_id, now)?;
+        
         let asset = CrossChainAsset {
             asset_id: asset_id.clone(),
+            tenant_id,
             name,
             symbol,
             asset_type,
             deployments: HashMap::new(),
             total_supply,
+            currency,
             compliance_standard,
             regulatory_framework,
             jurisdiction,
+            distribution: DistributionRules::default(),
+            approval_status: ApprovalStatus::PendingApproval,
             created_at: now,
             updated_at: now,
         };
         
+        let review = self.reviews.submit_new(&asset_id, &asset.tenant_id, created_by, now);
+        self.review_notifier.notify(review, created_by);
         self.supported_assets.insert(asset_id.clone(), asset);
         
         // Initialize metrics
@@ -427,32 +918,231 @@ impl MultiChainAssetService {
         Ok(asset_id)
     }
     
-    pub fn get_asset(&self, asset_id: &str) -> Option<&CrossChainAsset> {
+    /// Hold a symbol for a tenant while its issuer onboards
+    pub fn reserve_symbol(
+        &mut self,
+        tenant_id: &TenantId,
+        symbol: &str,
+        hold: chrono::Duration,
+        reserved_by: &str,
+    ) -> Result<SymbolReservation> {
+        Ok(self.symbols.reserve(tenant_id, symbol, hold, reserved_by, chrono::Utc::now())?)
+    }
+    
+    pub fn release_symbol(&mut self, tenant_id: &TenantId, symbol: &str) -> Result<()> {
+        Ok(self.symbols.release(tenant_id, symbol)?)
+    }
+    
+    /// Ask to change an asset's symbol; nothing changes until an admin approves it
+    pub fn request_symbol_rename(
+        &mut self,
+        scope: &TenantScope,
+        asset_id: &str,
+        new_symbol: &str,
+        requested_by: &str,
+    ) -> Result<RenameRequest> {
+        let asset = self.get_asset(scope, asset_id)
+            .ok_or_else(|| anyhow!("Asset not found"))?;
+        let (tenant_id, old_symbol) = (asset.tenant_id.clone(), asset.symbol.clone());
+        Ok(self.symbols.request_rename(&tenant_id, asset_id, &old_symbol, new_symbol, requested_by, chrono::Utc::now())?)
+    }
+    
+    pub fn pending_symbol_renames(&self) -> Vec<&RenameRequest> {
+        self.symbols.pending_renames()
+    }
+    
+    /// Apply an approved rename; the old symbol remains searchable as an alias
+    pub fn approve_symbol_rename(&mut self, request_id: Uuid, approved_by: &str) -> Result<&CrossChainAsset> {
+        let request = self.symbols.approve_rename(request_id, approved_by, chrono::Utc::now())?;
+        let asset = self.supported_assets.get_mut(&request.asset_id)
+            .ok_or_else(|| anyhow!("Asset not found"))?;
+        asset.symbol = request.new_symbol;
+        asset.updated_at = chrono::Utc::now();
+        Ok(asset)
+    }
+    
+    pub fn reject_symbol_rename(&mut self, request_id: Uuid) -> Result<RenameRequest> {
+        Ok(self.symbols.reject_rename(request_id)?)
+    }
+    
+    /// Former symbols of an asset, for search
+    pub fn symbol_aliases(&self, asset_id: &str) -> &[String] {
+        self.symbols.aliases(asset_id)
+    }
+    
+    /// Assets outside the scope are reported as missing, never as forbidden
+    pub fn get_asset(&self, scope: &TenantScope, asset_id: &str) -> Option<&CrossChainAsset> {
         self.supported_assets.get(asset_id)
+            .filter(|asset| scope.allows(&asset.tenant_id))
     }
     
-    pub fn get_asset_metrics(&self, asset_id: &str) -> Option<&AssetMetrics> {
+    /// Replace an asset's distribution rules
+    pub fn set_distribution_rules(
+        &mut self,
+        scope: &TenantScope,
+        asset_id: &str,
+        rules: DistributionRules,
+    ) -> Result<&CrossChainAsset> {
+        let asset = self.supported_assets.get_mut(asset_id)
+            .filter(|asset| scope.allows(&asset.tenant_id))
+            .ok_or_else(|| anyhow!("Asset not found"))?;
+        asset.distribution = rules;
+        asset.updated_at = chrono::Utc::now();
+        Ok(asset)
+    }
+    
+    /// Record the finality observed for an asset's deployment on a chain.
+    /// Returns whether the recorded status advanced.
+    pub fn record_deployment_finality(
+        &mut self,
+        scope: &TenantScope,
+        asset_id: &str,
+        chain: &SupportedChain,
+        status: FinalityStatus,
+        block_number: u64,
+        block_hash: &str,
+    ) -> Result<bool> {
+        let deployment = self.deployment_mut(scope, asset_id, chain)?;
+        let advanced = deployment.finality.observe(status, block_number, block_hash)?;
+        if advanced {
+            deployment.deployment_block = block_number;
+        }
+        Ok(advanced)
+    }
+    
+    /// Withdraw a Confirmed deployment status after a reorg, keeping the downgrade for audit
+    pub fn downgrade_deployment_finality(
+        &mut self,
+        scope: &TenantScope,
+        asset_id: &str,
+        chain: &SupportedChain,
+        reason: &str,
+        actor: &str,
+    ) -> Result<FinalityDowngrade> {
+        let deployment = self.deployment_mut(scope, asset_id, chain)?;
+        let downgrade = deployment.finality.downgrade(reason, actor)?;
+        deployment.finality_downgrades.push(downgrade.clone());
+        Ok(downgrade)
+    }
+    
+    fn deployment_mut(&mut self, scope: &TenantScope, asset_id: &str, chain: &SupportedChain) -> Result<&mut AssetDeployment> {
+        self.supported_assets.get_mut(asset_id)
+            .filter(|asset| scope.allows(&asset.tenant_id))
+            .ok_or_else(|| anyhow!("Asset not found"))?
+            .deployments.get_mut(chain)
+            .ok_or_else(|| anyhow!("Asset is not deployed on {}", chain.name()))
+    }
+    
+    /// Change a rejected asset before resubmitting it; the editor can no longer approve it
+    pub fn update_asset_config(
+        &mut self,
+        scope: &TenantScope,
+        asset_id: &str,
+        update: AssetConfigUpdate,
+        edited_by: &str,
+    ) -> Result<&CrossChainAsset> {
+        if self.get_asset(scope, asset_id).is_none() {
+            return Err(ReviewError::NotFound.into());
+        }
+        self.reviews.record_edit(asset_id, edited_by)?;
+        
+        let asset = self.supported_assets.get_mut(asset_id).ok_or(ReviewError::NotFound)?;
+        if let Some(name) = update.name { asset.name = name; }
+        if let Some(asset_type) = update.asset_type { asset.asset_type = asset_type; }
+        if let Some(standard) = update.compliance_standard { asset.compliance_standard = standard; }
+        if let Some(framework) = update.regulatory_framework { asset.regulatory_framework = framework; }
+        if let Some(jurisdiction) = update.jurisdiction { asset.jurisdiction = jurisdiction; }
+        if let Some(total_supply) = update.total_supply { asset.total_supply = total_supply; }
+        if let Some(rules) = update.distribution { asset.distribution = rules; }
+        asset.updated_at = chrono::Utc::now();
+        Ok(asset)
+    }
+    
+    /// Send a rejected asset back to the review queue
+    pub fn resubmit_asset(&mut self, scope: &TenantScope, asset_id: &str, submitted_by: &str) -> Result<&AssetReview> {
+        if self.get_asset(scope, asset_id).is_none() {
+            return Err(ReviewError::NotFound.into());
+        }
+        self.reviews.resubmit(asset_id, submitted_by, chrono::Utc::now())?;
+        self.sync_review(asset_id, submitted_by)
+    }
+    
+    pub fn approve_asset(
+        &mut self,
+        scope: &TenantScope,
+        asset_id: &str,
+        reviewer: &str,
+        comments: Option<String>,
+    ) -> Result<&AssetReview> {
+        if self.get_asset(scope, asset_id).is_none() {
+            return Err(ReviewError::NotFound.into());
+        }
+        self.reviews.approve(asset_id, reviewer, comments, chrono::Utc::now())?;
+        self.sync_review(asset_id, reviewer)
+    }
+    
+    pub fn reject_asset(&mut self, scope: &TenantScope, asset_id: &str, reviewer: &str, comments: &str) -> Result<&AssetReview> {
+        if self.get_asset(scope, asset_id).is_none() {
+            return Err(ReviewError::NotFound.into());
+        }
+        self.reviews.reject(asset_id, reviewer, comments, chrono::Utc::now())?;
+        self.sync_review(asset_id, reviewer)
+    }
+    
+    /// Assets awaiting approval in the scope, oldest submission first
+    pub fn review_queue(&self, scope: &TenantScope) -> Vec<AssetReviewItem> {
+        self.reviews.pending(scope)
+            .into_iter()
+            .filter_map(|review| Some(AssetReviewItem {
+                asset: self.supported_assets.get(&review.asset_id)?.clone(),
+                review: review.clone(),
+            }))
+            .collect()
+    }
+    
+    pub fn asset_review(&self, scope: &TenantScope, asset_id: &str) -> Option<&AssetReview> {
+        self.get_asset(scope, asset_id)?;
+        self.reviews.get(asset_id)
+    }
+    
+    /// Mirror a review transition onto the asset and tell the notifier
+    fn sync_review(&mut self, asset_id: &str, actor: &str) -> Result<&AssetReview> {
+        let review = self.reviews.get(asset_id).ok_or(ReviewError::NotFound)?;
+        if let Some(asset) = self.supported_assets.get_mut(asset_id) {
+            asset.approval_status = review.status;
+            asset.updated_at = chrono::Utc::now();
+        }
+        self.review_notifier.notify(review, actor);
+        Ok(review)
+    }
+    
+    pub fn get_asset_metrics(&self, scope: &TenantScope, asset_id: &str) -> Option<&AssetMetrics> {
+        self.get_asset(scope, asset_id)?;
         self.asset_metrics.get(asset_id)
     }
     
-    pub fn get_all_assets(&self) -> Vec<&CrossChainAsset> {
-        self.supported_assets.values().collect()
+    pub fn get_all_assets(&self, scope: &TenantScope) -> Vec<&CrossChainAsset> {
+        self.scoped_assets(scope).collect()
     }
     
-    pub fn get_assets_by_type(&self, asset_type: &AssetType) -> Vec<&CrossChainAsset> {
-        self.supported_assets
-            .values()
+    pub fn get_assets_by_type(&self, scope: &TenantScope, asset_type: &AssetType) -> Vec<&CrossChainAsset> {
+        self.scoped_assets(scope)
             .filter(|asset| std::mem::discriminant(&asset.asset_type) == std::mem::discriminant(asset_type))
             .collect()
     }
     
-    pub fn get_assets_by_jurisdiction(&self, jurisdiction: &str) -> Vec<&CrossChainAsset> {
-        self.supported_assets
-            .values()
+    pub fn get_assets_by_jurisdiction(&self, scope: &TenantScope, jurisdiction: &str) -> Vec<&CrossChainAsset> {
+        self.scoped_assets(scope)
             .filter(|asset| asset.jurisdiction == jurisdiction)
             .collect()
     }
     
+    fn scoped_assets<'a>(&'a self, scope: &'a TenantScope) -> impl Iterator<Item = &'a CrossChainAsset> + 'a {
+        self.supported_assets
+            .values()
+            .filter(move |asset| scope.allows(&asset.tenant_id))
+    }
+    
     pub fn get_supported_chains(&self) -> Vec<SupportedChain> {
         self.chain_configs.keys().cloned().collect()
     }
@@ -512,6 +1202,113 @@ impl MultiChainAssetService {
         Ok(base_fee + bridge_fee + destination_fee)
     }
     
+    /// Estimate what deploying an asset would cost on each chain, cheapest first.
+    ///
+    /// An empty `chains` list estimates every configured chain. Chains that cannot be
+    /// reached are reported with an error rather than dropped.
+    pub async fn estimate_deployment_costs(
+        &self,
+        scope: &TenantScope,
+        asset_id: &str,
+        chains: Vec<SupportedChain>,
+    ) -> Result<DeploymentCostReport> {
+        let asset = self.get_asset(scope, asset_id)
+            .ok_or_else(|| anyhow!("Asset not found: {}", asset_id))?;
+        
+        let bytecode = self.get_bytecode(&asset.compliance_standard);
+        let chains = if chains.is_empty() { self.get_supported_chains() } else { chains };
+        
+        let estimates = futures::future::join_all(chains.into_iter().map(|chain| {
+            let bytecode = &bytecode;
+            async move {
+                match self.estimate_chain_deployment_cost(&chain, bytecode).await {
+                    Ok(estimate) => estimate,
+                    Err(e) => DeploymentCostEstimate {
+                        chain,
+                        rank: None,
+                        gas_units: None,
+                        gas_price_wei: None,
+                        l1_data_fee_wei: None,
+                        total_cost_native: None,
+                        gas_token: None,
+                        total_cost_usd: None,
+                        error: Some(e.to_string()),
+                    },
+                }
+            }
+        })).await;
+        
+        let (mut ranked, failed): (Vec<_>, Vec<_>) = estimates.into_iter()
+            .partition(|estimate| estimate.error.is_none());
+        
+        ranked.sort_by(|a, b| a.total_cost_usd.partial_cmp(&b.total_cost_usd)
+            .unwrap_or(std::cmp::Ordering::Equal));
+        for (idx, estimate) in ranked.iter_mut().enumerate() {
+            estimate.rank = Some(idx + 1);
+        }
+        
+        let recommended_chain = ranked.first().map(|estimate| estimate.chain.clone());
+        ranked.extend(failed);
+        
+        Ok(DeploymentCostReport {
+            asset_id: asset_id.to_string(),
+            recommended_chain,
+            estimates: ranked,
+            generated_at: chrono::Utc::now(),
+        })
+    }
+    
+    async fn estimate_chain_deployment_cost(
+        &self,
+        chain: &SupportedChain,
+        bytecode: &[u8],
+    ) -> Result<DeploymentCostEstimate> {
+        let config = self.chain_configs.get(chain)
+            .ok_or_else(|| anyhow!("Chain {:?} not supported", chain))?;
+        
+        let client: Arc<dyn ChainGasClient> = match self.gas_clients.get(chain) {
+            Some(client) => client.clone(),
+            None => Arc::new(RpcGasClient::new(&config.rpc_url)?),
+        };
+        
+        let gas_units = client.estimate_deployment_gas(bytecode).await?;
+        let gas_price = client.gas_price().await?;
+        
+        let l1_data_fee = match &config.l1_gas_oracle {
+            Some(oracle) => {
+                let oracle = oracle.parse::<Address>()
+                    .map_err(|e| anyhow!("Invalid gas oracle address for {:?}: {}", chain, e))?;
+                client.l1_data_fee(oracle, bytecode).await?
+            }
+            None => 0,
+        };
+        
+        let total_wei = (gas_units as u128) * gas_price + l1_data_fee;
+        let total_cost_native = total_wei as f64 / 1e18;
+        let token_price = self.price_source.usd_price(&config.gas_token).await?;
+        
+        Ok(DeploymentCostEstimate {
+            chain: chain.clone(),
+            rank: None,
+            gas_units: Some(gas_units),
+            gas_price_wei: Some(gas_price),
+            l1_data_fee_wei: config.l1_gas_oracle.as_ref().map(|_| l1_data_fee),
+            total_cost_native: Some(total_cost_native),
+            gas_token: Some(config.gas_token.clone()),
+            total_cost_usd: Some(total_cost_native * token_price),
+            error: None,
+        })
+    }
+    
+    fn get_bytecode(&self, standard: &ComplianceStandard) -> Vec<u8> {
+        match standard {
+            ComplianceStandard::ERC3643 => self.get_erc3643_bytecode(),
+            ComplianceStandard::ERC1400 => self.get_erc1400_bytecode(),
+            ComplianceStandard::ERC1404 => self.get_erc1404_bytecode(),
+            ComplianceStandard::Custom(_) => self.get_standard_erc20_bytecode(),
+        }
+    }
+    
     fn get_erc3643_bytecode(&self) -> Vec<u8> {
         // Return compiled ERC-3643 contract bytecode
         // In real implementation, this would be the actual bytecode
@@ -555,32 +1352,37 @@ pub struct AssetDetailResponse {
     pub asset: CrossChainAsset,
     pub metrics: AssetMetrics,
     pub liquidity: HashMap<SupportedChain, CrossChainLiquidity>,
+    /// Chains whose liquidity could not be read
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub liquidity_errors: HashMap<SupportedChain, ChainLiquidityError>,
 }
 
 // API endpoint implementations
 pub async fn get_supported_chains(
     service: &MultiChainAssetService,
+    scope: &TenantScope,
 ) -> Result<ChainSupportResponse> {
     Ok(ChainSupportResponse {
         supported_chains: service.get_supported_chains(),
         chain_configs: service.chain_configs.clone(),
-        total_assets: service.supported_assets.len(),
+        total_assets: service.get_all_assets(scope).len(),
     })
 }
 
 pub async fn get_assets(
     service: &MultiChainAssetService,
+    scope: &TenantScope,
     page: u32,
     per_page: u32,
     asset_type: Option<AssetType>,
     jurisdiction: Option<String>,
 ) -> Result<AssetListResponse> {
     let mut assets: Vec<CrossChainAsset> = if let Some(asset_type) = asset_type {
-        service.get_assets_by_type(&asset_type).into_iter().cloned().collect()
+        service.get_assets_by_type(scope, &asset_type).into_iter().cloned().collect()
     } else if let Some(jurisdiction) = jurisdiction {
-        service.get_assets_by_jurisdiction(&jurisdiction).into_iter().cloned().collect()
+        service.get_assets_by_jurisdiction(scope, &jurisdiction).into_iter().cloned().collect()
     } else {
-        service.get_all_assets().into_iter().cloned().collect()
+        service.get_all_assets(scope).into_iter().cloned().collect()
     };
     
     // Sort by creation date (newest first)
@@ -606,21 +1408,288 @@ pub async fn get_assets(
 
 pub async fn get_asset_detail(
     service: &MultiChainAssetService,
+    scope: &TenantScope,
     asset_id: &str,
 ) -> Result<AssetDetailResponse> {
-    let asset = service.get_asset(asset_id)
+    let asset = service.get_asset(scope, asset_id)
         .ok_or_else(|| anyhow!("Asset not found"))?
         .clone();
     
-    let metrics = service.get_asset_metrics(asset_id)
+    let metrics = service.get_asset_metrics(scope, asset_id)
         .ok_or_else(|| anyhow!("Asset metrics not found"))?
         .clone();
     
-    let liquidity = service.get_asset_liquidity_across_chains(asset_id).await?;
+    let liquidity = service.get_asset_liquidity_across_chains(scope, asset_id).await?;
     
     Ok(AssetDetailResponse {
         asset,
         metrics,
-        liquidity,
+        liquidity: liquidity.chains,
+        liquidity_errors: liquidity.errors,
     })
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    struct MockGasClient {
+        gas_units: u64,
+        gas_price: u128,
+        l1_fee: u128,
+        oracle_calls: Mutex<Vec<Address>>,
+    }
+    
+    #[async_trait]
+    impl ChainGasClient for MockGasClient {
+        async fn estimate_deployment_gas(&self, _bytecode: &[u8]) -> Result<u64> {
+            Ok(self.gas_units)
+        }
+        
+        async fn gas_price(&self) -> Result<u128> {
+            Ok(self.gas_price)
+        }
+        
+        async fn l1_data_fee(&self, oracle: Address, _data: &[u8]) -> Result<u128> {
+            self.oracle_calls.lock().unwrap().push(oracle);
+            Ok(self.l1_fee)
+        }
+    }
+    
+    struct UnreachableGasClient;
+    
+    #[async_trait]
+    impl ChainGasClient for UnreachableGasClient {
+        async fn estimate_deployment_gas(&self, _bytecode: &[u8]) -> Result<u64> {
+            Err(anyhow!("connection refused"))
+        }
+        
+        async fn gas_price(&self) -> Result<u128> {
+            Err(anyhow!("connection refused"))
+        }
+        
+        async fn l1_data_fee(&self, _oracle: Address, _data: &[u8]) -> Result<u128> {
+            Err(anyhow!("connection refused"))
+        }
+    }
+    
+    fn mock(gas_units: u64, gas_price: u128, l1_fee: u128) -> Arc<MockGasClient> {
+        Arc::new(MockGasClient { gas_units, gas_price, l1_fee, oracle_calls: Mutex::new(Vec::new()) })
+    }
+    
+    #[tokio::test]
+    async fn test_rollup_deployment_cost_includes_l1_fee() {
+        let ethereum = mock(2_000_000, 20_000_000_000, 0);
+        let optimism = mock(2_000_000, 1_000_000, 500_000_000_000_000);
+        
+        let mut prices = HashMap::new();
+        prices.insert("ETH".to_string(), 2000.0);
+        
+        let mut service = MultiChainAssetService::new()
+            .with_gas_client(SupportedChain::Ethereum, ethereum.clone())
+            .with_gas_client(SupportedChain::Optimism, optimism.clone())
+            .with_gas_client(SupportedChain::Base, Arc::new(UnreachableGasClient))
+            .with_price_source(Arc::new(StaticPriceSource::new(prices)));
+        
+        let asset_id = service.create_asset(
+            TenantId::default(),
+            "Test Bond".to_string(),
+            "TBND".to_string(),
+            AssetType::CorporateBonds,
+            ComplianceStandard::ERC3643,
+            "Reg D".to_string(),
+            "US".to_string(),
+            1_000_000,
+            Currency::USD,
+            "manager",
+        ).await.unwrap();
+        
+        let report = service.estimate_deployment_costs(
+            &TenantScope::Tenant(TenantId::default()),
+            &asset_id,
+            vec![SupportedChain::Ethereum, SupportedChain::Optimism, SupportedChain::Base],
+        ).await.unwrap();
+        
+        assert_eq!(report.recommended_chain, Some(SupportedChain::Optimism));
+        assert_eq!(report.estimates.len(), 3);
+        
+        // 2M gas * 0.001 gwei + 0.0005 ETH L1 fee = 0.000502 ETH
+        let op = &report.estimates[0];
+        assert_eq!(op.rank, Some(1));
+        assert_eq!(op.l1_data_fee_wei, Some(500_000_000_000_000));
+        assert!((op.total_cost_usd.unwrap() - 1.004).abs() < 1e-9);
+        assert_eq!(*optimism.oracle_calls.lock().unwrap(), vec![OP_STACK_GAS_ORACLE.parse::<Address>().unwrap()]);
+        
+        // L1 has no oracle
+        let eth = &report.estimates[1];
+        assert_eq!(eth.chain, SupportedChain::Ethereum);
+        assert_eq!(eth.l1_data_fee_wei, None);
+        assert!(ethereum.oracle_calls.lock().unwrap().is_empty());
+        
+        let base = &report.estimates[2];
+        assert_eq!(base.chain, SupportedChain::Base);
+        assert_eq!(base.rank, None);
+        assert!(base.error.as_ref().unwrap().contains("connection refused"));
+    }
+    
+    #[tokio::test]
+    async fn test_only_assets_approved_by_a_second_user_deploy() {
+        let mut service = MultiChainAssetService::new();
+        let scope = TenantScope::Tenant(TenantId::default());
+        let asset_id = service.create_asset(
+            TenantId::default(),
+            "Harbour Notes".to_string(),
+            "HBN".to_string(),
+            AssetType::CorporateBonds,
+            ComplianceStandard::ERC3643,
+            "Reg D".to_string(),
+            "US".to_string(),
+            1_000_000,
+            Currency::USD,
+            "manager",
+        ).await.unwrap();
+        
+        let asset = service.get_asset(&scope, &asset_id).unwrap().clone();
+        assert_eq!(asset.approval_status, ApprovalStatus::PendingApproval);
+        assert!(service.deploy_asset_cross_chain(&asset, vec![SupportedChain::Ethereum]).await.is_err());
+        
+        let err = service.approve_asset(&scope, &asset_id, "manager", None).unwrap_err();
+        assert_eq!(err.downcast_ref::<ReviewError>(), Some(&ReviewError::SelfApproval));
+        
+        service.reject_asset(&scope, &asset_id, "checker", "Supply should be 500k").unwrap();
+        service.update_asset_config(&scope, &asset_id, AssetConfigUpdate {
+            total_supply: Some(500_000),
+            ..Default::default()
+        }, "manager").unwrap();
+        service.resubmit_asset(&scope, &asset_id, "manager").unwrap();
+        assert_eq!(service.review_queue(&scope)[0].asset.total_supply, 500_000);
+        
+        service.approve_asset(&scope, &asset_id, "checker", None).unwrap();
+        let asset = service.get_asset(&scope, &asset_id).unwrap().clone();
+        assert_eq!(asset.approval_status, ApprovalStatus::Approved);
+        assert!(service.deploy_asset_cross_chain(&asset, vec![SupportedChain::Ethereum]).await.is_ok());
+    }
+    
+    #[tokio::test]
+    async fn test_reorged_deployment_is_downgraded_before_new_block() {
+        let mut service = MultiChainAssetService::new();
+        let scope = TenantScope::Tenant(TenantId::default());
+        let asset_id = service.create_asset(
+            TenantId::default(),
+            "Harbour Notes".to_string(),
+            "HBN".to_string(),
+            AssetType::CorporateBonds,
+            ComplianceStandard::ERC3643,
+            "Reg D".to_string(),
+            "US".to_string(),
+            1_000_000,
+            Currency::USD,
+            "manager",
+        ).await.unwrap();
+        service.supported_assets.get_mut(&asset_id).unwrap().deployments.insert(SupportedChain::Polygon, AssetDeployment {
+            contract_address: "0x01".to_string(),
+            deployment_tx: "0x02".to_string(),
+            deployment_block: 0,
+            is_active: true,
+            liquidity_pools: Vec::new(),
+            finality: Finality::default(),
+            finality_downgrades: Vec::new(),
+        });
+        
+        let chain = SupportedChain::Polygon;
+        assert!(service.record_deployment_finality(&scope, &asset_id, &chain, FinalityStatus::Confirmed, 500, "0xaa").unwrap());
+        
+        // The same transaction turning up in another block is refused until the old one is withdrawn
+        assert!(service.record_deployment_finality(&scope, &asset_id, &chain, FinalityStatus::Confirmed, 501, "0xbb").is_err());
+        let downgrade = service.downgrade_deployment_finality(&scope, &asset_id, &chain, "block 500 reorged", "ops").unwrap();
+        assert_eq!(downgrade.from, FinalityStatus::Confirmed);
+        assert_eq!(downgrade.block_number, Some(500));
+        assert!(service.record_deployment_finality(&scope, &asset_id, &chain, FinalityStatus::Confirmed, 501, "0xbb").unwrap());
+        
+        let deployment = &service.get_asset(&scope, &asset_id).unwrap().deployments[&chain];
+        assert_eq!(deployment.deployment_block, 501);
+        assert_eq!(deployment.finality_downgrades.len(), 1);
+    }
+    
+    /// Fails transiently a set number of times before answering
+    struct FlakyLiquiditySource {
+        failures_left: Mutex<u32>,
+    }
+    
+    #[async_trait]
+    impl ChainLiquiditySource for FlakyLiquiditySource {
+        async fn liquidity(&self, chain: &SupportedChain, address: &str) -> std::result::Result<CrossChainLiquidity, LiquiditySourceError> {
+            let mut failures_left = self.failures_left.lock().unwrap();
+            if *failures_left > 0 {
+                *failures_left -= 1;
+                return Err(LiquiditySourceError::Transient("rpc connection reset".to_string()));
+            }
+            SimulatedLiquiditySource.liquidity(chain, address).await
+        }
+    }
+    
+    /// Never answers within any reasonable timeout
+    struct StalledLiquiditySource;
+    
+    #[async_trait]
+    impl ChainLiquiditySource for StalledLiquiditySource {
+        async fn liquidity(&self, _chain: &SupportedChain, _address: &str) -> std::result::Result<CrossChainLiquidity, LiquiditySourceError> {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Err(LiquiditySourceError::Permanent("unreachable".to_string()))
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_failed_chains_are_reported_beside_answering_ones() {
+        let mut service = MultiChainAssetService::new()
+            .with_liquidity_timeout(Duration::from_millis(50))
+            .with_liquidity_source(SupportedChain::Polygon, Arc::new(FlakyLiquiditySource { failures_left: Mutex::new(1) }))
+            .with_liquidity_source(SupportedChain::Base, Arc::new(FlakyLiquiditySource { failures_left: Mutex::new(2) }))
+            .with_liquidity_source(SupportedChain::Avalanche, Arc::new(StalledLiquiditySource));
+        let scope = TenantScope::Tenant(TenantId::default());
+        let asset_id = service.create_asset(
+            TenantId::default(),
+            "Harbour Notes".to_string(),
+            "HBN".to_string(),
+            AssetType::CorporateBonds,
+            ComplianceStandard::ERC3643,
+            "Reg D".to_string(),
+            "US".to_string(),
+            1_000_000,
+            Currency::USD,
+            "manager",
+        ).await.unwrap();
+        for chain in [SupportedChain::Ethereum, SupportedChain::Polygon, SupportedChain::Base, SupportedChain::Avalanche] {
+            service.supported_assets.get_mut(&asset_id).unwrap().deployments.insert(chain, AssetDeployment {
+                contract_address: "0x01".to_string(),
+                deployment_tx: "0x02".to_string(),
+                deployment_block: 0,
+                is_active: true,
+                liquidity_pools: Vec::new(),
+                finality: Finality::default(),
+                finality_downgrades: Vec::new(),
+            });
+        }
+        
+        // One failure is absorbed by the retry; a second, or a chain timing out twice, is reported
+        let liquidity = service.get_asset_liquidity_across_chains(&scope, &asset_id).await.unwrap();
+        assert!(liquidity.is_partial());
+        assert_eq!(liquidity.chains.len(), 2);
+        assert!(liquidity.chains.contains_key(&SupportedChain::Ethereum));
+        assert!(liquidity.chains.contains_key(&SupportedChain::Polygon));
+        let stalled = &liquidity.errors[&SupportedChain::Avalanche];
+        assert!(stalled.timed_out);
+        assert_eq!(stalled.attempts, 2);
+        assert_eq!(liquidity.errors[&SupportedChain::Base], ChainLiquidityError {
+            error: "rpc connection reset".to_string(),
+            timed_out: false,
+            attempts: 2,
+        });
+        
+        let stats = service.liquidity_stats();
+        let base = stats.iter().find(|stats| stats.chain == "Base").unwrap();
+        assert_eq!((base.queries, base.failures, base.retries, base.failure_rate), (1, 1, 1, 1.0));
+        let polygon = stats.iter().find(|stats| stats.chain == "Polygon").unwrap();
+        assert_eq!((polygon.failures, polygon.retries), (0, 1));
+    }
+}
@@ -5,6 +5,9 @@ use std::collections::HashMap;
 use chrono::{DateTime, Utc, Duration};
 use rust_decimal::Decimal;
 use anyhow::Result;
+use price_oracle::{FxRate, FxRateService, OracleAggregator, ReportingTotal, UnconvertedAmount};
+use quantera_types::Currency;
+use tracing::warn;
 
 // ============================================================================
 // Data Types
@@ -17,6 +20,9 @@ pub struct AssetHolding {
     pub name: String,
     pub symbol: String,
     pub quantity: String,
+    /// `price`, `value` and the yield amount are in this currency
+    #[serde(default)]
+    pub currency: Currency,
     pub price: String,
     pub value: String,
     pub yield_rate: Option<String>,
@@ -25,11 +31,20 @@ pub struct AssetHolding {
     pub asset_type: Option<String>,
     pub category: Option<String>,
     pub asset_class: Option<String>,
+    /// Issuer concentration limits are counted by this
+    #[serde(default)]
+    pub issuer: Option<String>,
     pub acquisition_date: Option<DateTime<Utc>>,
     pub acquisition_price: Option<String>,
     pub unrealized_gain: Option<String>,
     pub unrealized_gain_percent: Option<String>,
     pub allocation: Option<i32>,
+    /// Oracle confidence in `price`; absent when valued at the acquisition price
+    #[serde(default)]
+    pub price_confidence: Option<String>,
+    /// Price sources disagree, so `price` is a reference value only
+    #[serde(default)]
+    pub price_disputed: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -65,9 +80,15 @@ pub struct YieldDistribution {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PortfolioSummary {
     pub wallet_address: String,
+    /// Currency of `total_value` and `total_yield`
+    pub reporting_currency: Currency,
     pub total_value: String,
     pub total_yield: String,
     pub yield_rate: String,
+    /// Rates the holdings in other currencies were converted at
+    pub fx_rates: Vec<FxRate>,
+    /// Holding values in currencies with no rate, left out of the totals
+    pub unconverted: Vec<UnconvertedAmount>,
     pub impact_score: Option<i32>,
     pub carbon_offset: Option<String>,
     pub asset_allocation: HashMap<String, i32>,
@@ -112,57 +133,32 @@ pub struct ImpactMetrics {
 
 pub struct PortfolioService {
     db: Arc<PgPool>,
+    prices: Option<Arc<OracleAggregator>>,
+    fx: Option<Arc<FxRateService>>,
 }
 
 impl PortfolioService {
     pub fn new(db: Arc<PgPool>) -> Self {
-        Self { db }
+        Self { db, prices: None, fx: None }
     }
     
-    /// Get complete portfolio for a wallet address
-    pub async fn get_portfolio(&self, wallet_address: &str) -> Result<PortfolioSummary> {
+    /// Value holdings at aggregated oracle prices instead of their acquisition price
+    pub fn with_prices(mut self, prices: Arc<OracleAggregator>) -> Self {
+        self.prices = Some(prices);
+        self
+    }
+    
+    /// Convert holdings in other currencies into the reporting currency
+    pub fn with_fx_rates(mut self, fx: Arc<FxRateService>) -> Self {
+        self.fx = Some(fx);
+        self
+    }
+    
+    /// Get complete portfolio for a wallet address, totalled in `reporting`
+    pub async fn get_portfolio(&self, wallet_address: &str, reporting: Currency) -> Result<PortfolioSummary> {
         // Fetch all holdings
         let holdings = self.get_holdings(wallet_address, None, None, None, None, None).await?;
-        
-        // Calculate totals
-        let mut total_value = Decimal::ZERO;
-        let mut total_yield = Decimal::ZERO;
-        let mut asset_allocation: HashMap<String, i32> = HashMap::new();
-        
-        for holding in &holdings {
-            // Parse values
-            let value = holding.value.parse::<Decimal>().unwrap_or(Decimal::ZERO);
-            let yield_amount = holding.yield_amount.as_ref()
-                .and_then(|y| y.parse::<Decimal>().ok())
-                .unwrap_or(Decimal::ZERO);
-            
-            total_value += value;
-            total_yield += yield_amount;
-            
-            // Calculate allocation
-            if let Some(category) = &holding.category {
-                *asset_allocation.entry(category.clone()).or_insert(0) += holding.allocation.unwrap_or(0);
-            }
-        }
-        
-        // Calculate yield rate
-        let yield_rate = if total_value > Decimal::ZERO {
-            (total_yield / total_value * Decimal::from(100)).to_string()
-        } else {
-            "0.00".to_string()
-        };
-        
-        Ok(PortfolioSummary {
-            wallet_address: wallet_address.to_string(),
-            total_value: total_value.to_string(),
-            total_yield: total_yield.to_string(),
-            yield_rate,
-            impact_score: Some(85), // TODO: Calculate from environmental holdings
-            carbon_offset: Some("72.5".to_string()), // TODO: Aggregate from holdings
-            asset_allocation,
-            holdings,
-            last_updated: Utc::now(),
-        })
+        Ok(summarize(wallet_address, holdings, reporting, self.fx.as_deref()).await)
     }
     
     /// Get holdings with optional filtering
@@ -179,8 +175,8 @@ impl PortfolioService {
         
         let mut query = String::from(
             "SELECT id, wallet_address, asset_id, asset_name, asset_symbol, 
-                    quantity, acquisition_price, acquisition_date, asset_type,
-                    asset_category, asset_class, maturity_date
+                    quantity, currency, acquisition_price, acquisition_date, asset_type,
+                    asset_category, asset_class, issuer, maturity_date
              FROM portfolio_holdings
              WHERE wallet_address = $1"
         );
@@ -215,10 +211,10 @@ impl PortfolioService {
         for row in rows {
             let quantity: Decimal = row.get("quantity");
             let acquisition_price: Decimal = row.get("acquisition_price");
+            let asset_id: String = row.get("asset_id");
+            let currency: Currency = row.get::<String, _>("currency").parse().map_err(anyhow::Error::msg)?;
             
-            // TODO: Fetch current price from oracle/on-chain
-            // For now, use acquisition price + small random variation
-            let current_price = acquisition_price * Decimal::new(102, 2); // 2% gain
+            let (current_price, price_confidence, price_disputed) = self.current_price(&asset_id, acquisition_price).await;
             let value = quantity * current_price;
             let unrealized_gain = value - (quantity * acquisition_price);
             let unrealized_gain_percent = if acquisition_price > Decimal::ZERO {
@@ -229,10 +225,11 @@ impl PortfolioService {
             
             holdings.push(AssetHolding {
                 id: row.get::<uuid::Uuid, _>("id").to_string(),
-                asset_id: row.get("asset_id"),
+                asset_id,
                 name: row.get("asset_name"),
                 symbol: row.get("asset_symbol"),
                 quantity: quantity.to_string(),
+                currency,
                 price: current_price.to_string(),
                 value: value.to_string(),
                 yield_rate: Some("4.50".to_string()), // TODO: Calculate
@@ -242,17 +239,36 @@ impl PortfolioService {
                 asset_type: row.get("asset_type"),
                 category: row.get("asset_category"),
                 asset_class: row.get("asset_class"),
+                issuer: row.get("issuer"),
                 acquisition_date: row.get("acquisition_date"),
                 acquisition_price: Some(acquisition_price.to_string()),
                 unrealized_gain: Some(unrealized_gain.to_string()),
                 unrealized_gain_percent: Some(unrealized_gain_percent.to_string()),
                 allocation: Some(12), // TODO: Calculate percentage
+                price_confidence,
+                price_disputed,
             });
         }
         
         Ok(holdings)
     }
     
+    /// Aggregated oracle price of an asset with its confidence and dispute flag,
+    /// falling back to the acquisition price when no source quotes it
+    async fn current_price(&self, asset_id: &str, acquisition_price: Decimal) -> (Decimal, Option<String>, bool) {
+        let Some(prices) = &self.prices else {
+            return (acquisition_price, None, false);
+        };
+        
+        match prices.price(asset_id).await {
+            Ok(price) => (price.price, Some(price.confidence.to_string()), price.disputed),
+            Err(e) => {
+                warn!("Valuing {} at acquisition price: {}", asset_id, e);
+                (acquisition_price, None, false)
+            }
+        }
+    }
+    
     /// Get transaction history
     pub async fn get_transactions(
         &self,
@@ -426,3 +442,149 @@ impl PortfolioService {
         })
     }
 }
+
+/// Total `amounts` in `reporting`; without an FX service only amounts already in it count
+async fn reporting_total(fx: Option<&FxRateService>, amounts: Vec<(Currency, Decimal)>, reporting: Currency) -> ReportingTotal {
+    if let Some(fx) = fx {
+        return fx.total(amounts, reporting).await;
+    }
+    let mut total = ReportingTotal { currency: reporting, total: Decimal::ZERO, rates: Vec::new(), unconverted: Vec::new() };
+    for (currency, amount) in amounts {
+        if currency == reporting {
+            total.total += amount;
+        } else {
+            total.unconverted.push(UnconvertedAmount { currency, amount, reason: "No FX rates configured".to_string() });
+        }
+    }
+    total
+}
+
+/// Portfolio totals in `reporting`; holdings with no rate into it are listed as unconverted
+async fn summarize(
+    wallet_address: &str,
+    holdings: Vec<AssetHolding>,
+    reporting: Currency,
+    fx: Option<&FxRateService>,
+) -> PortfolioSummary {
+    let mut values = Vec::new();
+    let mut yields = Vec::new();
+    let mut asset_allocation: HashMap<String, i32> = HashMap::new();
+    
+    for holding in &holdings {
+        // Parse values
+        let value = holding.value.parse::<Decimal>().unwrap_or(Decimal::ZERO);
+        let yield_amount = holding.yield_amount.as_ref()
+            .and_then(|y| y.parse::<Decimal>().ok())
+            .unwrap_or(Decimal::ZERO);
+        
+        values.push((holding.currency, value));
+        yields.push((holding.currency, yield_amount));
+        
+        // Calculate allocation
+        if let Some(category) = &holding.category {
+            *asset_allocation.entry(category.clone()).or_insert(0) += holding.allocation.unwrap_or(0);
+        }
+    }
+    
+    // Both totals leave out the same currencies, so the yield rate covers the converted holdings
+    let value = reporting_total(fx, values, reporting).await;
+    let total_yield = reporting_total(fx, yields, reporting).await.total;
+    let yield_rate = if value.total > Decimal::ZERO {
+        (total_yield / value.total * Decimal::from(100)).to_string()
+    } else {
+        "0.00".to_string()
+    };
+    
+    PortfolioSummary {
+        wallet_address: wallet_address.to_string(),
+        reporting_currency: reporting,
+        total_value: value.total.to_string(),
+        total_yield: total_yield.to_string(),
+        yield_rate,
+        fx_rates: value.rates,
+        unconverted: value.unconverted,
+        impact_score: Some(85), // TODO: Calculate from environmental holdings
+        carbon_offset: Some("72.5".to_string()), // TODO: Aggregate from holdings
+        asset_allocation,
+        holdings,
+        last_updated: Utc::now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use price_oracle::{FxRateProvider, OracleError};
+
+    /// Quotes EUR/USD at 1.10 only
+    struct EurUsd;
+
+    #[async_trait]
+    impl FxRateProvider for EurUsd {
+        async fn rate(&self, base: Currency, quote: Currency) -> Result<Option<FxRate>, OracleError> {
+            Ok((base == Currency::EUR && quote == Currency::USD).then(|| FxRate {
+                base,
+                quote,
+                rate: Decimal::new(110, 2),
+                source: "test".to_string(),
+                observed_at: Utc::now(),
+            }))
+        }
+    }
+
+    fn holding(asset_id: &str, currency: Currency, value: i64) -> AssetHolding {
+        AssetHolding {
+            id: asset_id.to_string(),
+            asset_id: asset_id.to_string(),
+            name: asset_id.to_string(),
+            symbol: asset_id.to_uppercase(),
+            quantity: "1".to_string(),
+            currency,
+            price: value.to_string(),
+            value: value.to_string(),
+            yield_rate: Some("4.00".to_string()),
+            yield_amount: Some((Decimal::from(value) * Decimal::new(4, 2)).to_string()),
+            maturity: None,
+            asset_type: None,
+            category: None,
+            asset_class: None,
+            issuer: None,
+            acquisition_date: None,
+            acquisition_price: None,
+            unrealized_gain: None,
+            unrealized_gain_percent: None,
+            allocation: None,
+            price_confidence: None,
+            price_disputed: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mixed_usd_eur_portfolio_valuation() {
+        let fx = FxRateService::new(Arc::new(EurUsd), Duration::seconds(60), Duration::hours(1));
+        let gbp: Currency = "GBP".parse().unwrap();
+        let holdings = vec![
+            holding("ust", Currency::USD, 10_000),
+            holding("bund", Currency::EUR, 5_000),
+            holding("gilt", gbp, 2_000),
+        ];
+
+        let in_usd = summarize("0xabc", holdings.clone(), Currency::USD, Some(&fx)).await;
+        assert_eq!(in_usd.reporting_currency, Currency::USD);
+        assert_eq!(in_usd.total_value.parse::<Decimal>().unwrap(), Decimal::from(15_500));
+        assert_eq!(in_usd.total_yield.parse::<Decimal>().unwrap(), Decimal::from(620));
+        assert_eq!(in_usd.fx_rates.iter().map(FxRate::pair).collect::<Vec<_>>(), vec!["EUR/USD"]);
+        // The gilt has no rate, so it is disclosed instead of counted at par
+        assert_eq!(in_usd.unconverted.len(), 1);
+        assert_eq!((in_usd.unconverted[0].currency, in_usd.unconverted[0].amount), (gbp, Decimal::from(2_000)));
+
+        // 10,000 USD / 1.10 + 5,000 EUR
+        let in_eur = summarize("0xabc", holdings.clone(), Currency::EUR, Some(&fx)).await;
+        assert_eq!(in_eur.total_value.parse::<Decimal>().unwrap().round_dp(2), Decimal::new(1409091, 2));
+
+        let without_fx = summarize("0xabc", holdings, Currency::USD, None).await;
+        assert_eq!(without_fx.total_value.parse::<Decimal>().unwrap(), Decimal::from(10_000));
+        assert_eq!(without_fx.unconverted.len(), 2);
+    }
+}
@@ -1,8 +1,20 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
 use tokio;
 use anyhow::{Result, anyhow};
 use chrono::{DateTime, Utc, Duration};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+use crate::compliance::thresholds::{ConversionRates, FiatCurrency};
+
+/// Margin ratios outside this range, in basis points, are rejected
+pub const MIN_MARGIN_RATIO_BPS: u32 = 100;
+pub const MAX_MARGIN_RATIO_BPS: u32 = 10_000;
+
+/// Decimals of collateral amounts, exposure and valuations
+const UNIT_DECIMALS: u32 = 18;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum AccountType {
@@ -37,6 +49,14 @@ pub enum MarginMethod {
     Span,              // Standard Portfolio Analysis of Risk
 }
 
+/// Conditions that hold an account back from normal operation until they clear
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AccountFlag {
+    /// A material collateral asset has no price newer than the staleness limit, so
+    /// the collateral cannot be valued; credit and margin are frozen until it can
+    StaleCollateralPrice,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PrimeAccount {
     pub institution: String,
@@ -56,6 +76,37 @@ pub struct PrimeAccount {
     pub jurisdiction: String,
     pub authorized_traders: Vec<String>,
     pub risk_score: u32,
+    /// Currency collateral is valued and credit extended in
+    pub base_currency: FiatCurrency,
+    /// Latest accepted valuation of the collateral
+    pub collateral_valuation: Option<CollateralValuation>,
+    pub flags: BTreeSet<AccountFlag>,
+}
+
+/// One collateral asset valued in the account's base currency
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollateralAssetValue {
+    pub asset: String,
+    pub amount: u128,
+    /// Base currency per whole unit of the asset
+    pub price: Decimal,
+    pub price_observed_at: DateTime<Utc>,
+    /// Priced too long ago; left out of the valuation as an immaterial holding
+    pub stale: bool,
+    pub haircut_bps: u32,
+    pub market_value: u128,
+    pub lending_value: u128,
+}
+
+/// An account's collateral at current prices, before and after haircuts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollateralValuation {
+    pub currency: FiatCurrency,
+    pub market_value: u128,
+    /// Value after haircuts; what credit and margin are extended against
+    pub lending_value: u128,
+    pub assets: Vec<CollateralAssetValue>,
+    pub valued_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -141,6 +192,8 @@ pub struct PrimeBrokerageMetrics {
     pub active_positions: u32,
     pub portfolio_margin_accounts: u32,
     pub risk_distribution: HashMap<RiskLevel, u32>,
+    /// Institutions whose credit is frozen on a stale collateral price
+    pub stale_price_accounts: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -154,6 +207,222 @@ pub struct MarginCallAlert {
     pub created_at: DateTime<Utc>,
 }
 
+/// Maintenance and initial margin of an account, in basis points of exposure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarginRatios {
+    pub maintenance_margin_ratio: u32,
+    pub initial_margin_ratio: u32,
+}
+
+impl MarginRatios {
+    pub fn new(maintenance_margin_ratio: u32, initial_margin_ratio: u32) -> Self {
+        Self { maintenance_margin_ratio, initial_margin_ratio }
+    }
+
+    pub fn validate(&self) -> std::result::Result<(), MarginRatioError> {
+        for ratio in [self.maintenance_margin_ratio, self.initial_margin_ratio] {
+            if !(MIN_MARGIN_RATIO_BPS..=MAX_MARGIN_RATIO_BPS).contains(&ratio) {
+                return Err(MarginRatioError::Invalid(format!(
+                    "ratios must be between {} and {} bps, got {}", MIN_MARGIN_RATIO_BPS, MAX_MARGIN_RATIO_BPS, ratio
+                )));
+            }
+        }
+        if self.maintenance_margin_ratio >= self.initial_margin_ratio {
+            return Err(MarginRatioError::Invalid(format!(
+                "maintenance ratio {} must be below initial ratio {}",
+                self.maintenance_margin_ratio, self.initial_margin_ratio
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Upper bounds of the Low, Medium and High risk buckets; higher scores are Critical
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RiskLevelBounds {
+    pub low: u32,
+    pub medium: u32,
+    pub high: u32,
+}
+
+impl Default for RiskLevelBounds {
+    fn default() -> Self {
+        Self { low: 25, medium: 50, high: 75 }
+    }
+}
+
+impl RiskLevelBounds {
+    pub fn level(&self, score: u32) -> RiskLevel {
+        if score <= self.low {
+            RiskLevel::Low
+        } else if score <= self.medium {
+            RiskLevel::Medium
+        } else if score <= self.high {
+            RiskLevel::High
+        } else {
+            RiskLevel::Critical
+        }
+    }
+}
+
+/// Default margin ratios per account type, risk bucket boundaries and collateral valuation rules
+#[derive(Debug, Clone)]
+pub struct MarginConfig {
+    pub default_ratios: HashMap<AccountType, MarginRatios>,
+    pub risk_level_bounds: RiskLevelBounds,
+    /// Haircut per collateral asset in bps of market value
+    pub collateral_haircuts: HashMap<String, u32>,
+    /// Haircut of assets missing from `collateral_haircuts`
+    pub default_haircut_bps: u32,
+    /// Collateral prices observed longer ago than this are stale
+    pub max_price_age: Duration,
+    /// Share of collateral market value, in bps, from which a stale asset blocks valuation
+    pub material_collateral_bps: u32,
+}
+
+impl Default for MarginConfig {
+    fn default() -> Self {
+        let ratios = MarginRatios::new(1250, 1500); // 12.5% maintenance, 15% initial
+        Self {
+            default_ratios: [AccountType::Individual, AccountType::Omnibus, AccountType::Segregated, AccountType::PrimeServices]
+                .into_iter()
+                .map(|account_type| (account_type, ratios))
+                .collect(),
+            risk_level_bounds: RiskLevelBounds::default(),
+            collateral_haircuts: HashMap::new(),
+            default_haircut_bps: 2000,
+            max_price_age: Duration::minutes(15),
+            material_collateral_bps: 500,
+        }
+    }
+}
+
+impl MarginConfig {
+    /// Read `PRIME_MARGIN_RATIOS` (e.g. `Omnibus=1500:2000,PrimeServices=1000:1250`,
+    /// maintenance:initial in bps; unlisted types keep 1250:1500), `PRIME_RISK_LEVEL_BOUNDS`
+    /// (low,medium,high upper bounds of the risk score, default 25,50,75),
+    /// `PRIME_COLLATERAL_HAIRCUTS` (e.g. `USDC=200,ETH=2500` in bps),
+    /// `PRIME_DEFAULT_HAIRCUT_BPS` (default 2000), `PRIME_COLLATERAL_MAX_PRICE_AGE_SECS`
+    /// (default 900) and `PRIME_MATERIAL_COLLATERAL_BPS` (default 500)
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self::default();
+
+        for entry in std::env::var("PRIME_MARGIN_RATIOS").unwrap_or_default().split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let invalid = || anyhow!("Invalid PRIME_MARGIN_RATIOS entry {}", entry);
+            let (account_type, ratios) = entry.split_once('=').ok_or_else(invalid)?;
+            let account_type = match account_type.trim() {
+                "Individual" => AccountType::Individual,
+                "Omnibus" => AccountType::Omnibus,
+                "Segregated" => AccountType::Segregated,
+                "PrimeServices" => AccountType::PrimeServices,
+                _ => return Err(invalid()),
+            };
+            let (maintenance, initial) = ratios.split_once(':').ok_or_else(invalid)?;
+            let ratios = MarginRatios::new(
+                maintenance.trim().parse().map_err(|_| invalid())?,
+                initial.trim().parse().map_err(|_| invalid())?,
+            );
+            ratios.validate().map_err(|e| anyhow!("PRIME_MARGIN_RATIOS {}: {}", entry, e))?;
+            config.default_ratios.insert(account_type, ratios);
+        }
+
+        if let Ok(bounds) = std::env::var("PRIME_RISK_LEVEL_BOUNDS") {
+            let parsed: Vec<u32> = bounds.split(',')
+                .map(|bound| bound.trim().parse::<u32>())
+                .collect::<std::result::Result<_, _>>()
+                .map_err(|_| anyhow!("Invalid PRIME_RISK_LEVEL_BOUNDS"))?;
+            match parsed[..] {
+                [low, medium, high] if low < medium && medium < high && high <= 100 => {
+                    config.risk_level_bounds = RiskLevelBounds { low, medium, high };
+                }
+                _ => return Err(anyhow!("PRIME_RISK_LEVEL_BOUNDS must be three increasing scores up to 100")),
+            }
+        }
+
+        for entry in std::env::var("PRIME_COLLATERAL_HAIRCUTS").unwrap_or_default().split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let invalid = || anyhow!("Invalid PRIME_COLLATERAL_HAIRCUTS entry {}", entry);
+            let (asset, haircut) = entry.split_once('=').ok_or_else(invalid)?;
+            let haircut: u32 = haircut.trim().parse().map_err(|_| invalid())?;
+            if haircut > 10_000 {
+                return Err(invalid());
+            }
+            config.collateral_haircuts.insert(asset.trim().to_string(), haircut);
+        }
+
+        let number = |name: &str, default: u32| -> Result<u32> {
+            match std::env::var(name) {
+                Ok(value) if !value.is_empty() => value.trim().parse().map_err(|_| anyhow!("Invalid {}: {}", name, value)),
+                _ => Ok(default),
+            }
+        };
+        config.default_haircut_bps = number("PRIME_DEFAULT_HAIRCUT_BPS", config.default_haircut_bps)?;
+        config.max_price_age = Duration::seconds(number("PRIME_COLLATERAL_MAX_PRICE_AGE_SECS", config.max_price_age.num_seconds() as u32)? as i64);
+        config.material_collateral_bps = number("PRIME_MATERIAL_COLLATERAL_BPS", config.material_collateral_bps)?;
+        if config.default_haircut_bps > 10_000 || config.material_collateral_bps > 10_000 {
+            return Err(anyhow!("PRIME_DEFAULT_HAIRCUT_BPS and PRIME_MATERIAL_COLLATERAL_BPS must be at most 10000"));
+        }
+
+        Ok(config)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarginRatioError {
+    Invalid(String),
+    AccountNotFound(String),
+}
+
+impl std::fmt::Display for MarginRatioError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MarginRatioError::Invalid(msg) => write!(f, "Invalid margin ratios: {}", msg),
+            MarginRatioError::AccountNotFound(institution) => write!(f, "Institution {} not found", institution),
+        }
+    }
+}
+
+impl std::error::Error for MarginRatioError {}
+
+/// Why an account's collateral could not be valued
+#[derive(Debug, Clone, PartialEq)]
+pub enum CollateralValuationError {
+    /// A material asset's latest price, or the FX rate to the base currency, is too old
+    StalePrice { asset: String, observed_at: DateTime<Utc> },
+    /// No price could be found for an asset or for the FX rate
+    PriceUnavailable { asset: String, reason: String },
+    /// The value does not fit the account's units
+    Overflow(String),
+}
+
+impl std::fmt::Display for CollateralValuationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CollateralValuationError::StalePrice { asset, observed_at } => write!(f, "Price of {} is stale (observed {})", asset, observed_at),
+            CollateralValuationError::PriceUnavailable { asset, reason } => write!(f, "No price for {}: {}", asset, reason),
+            CollateralValuationError::Overflow(asset) => write!(f, "Value of {} collateral overflows", asset),
+        }
+    }
+}
+
+impl std::error::Error for CollateralValuationError {}
+
+/// An admin change to an account's margin ratios
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarginRatioChange {
+    pub change_id: Uuid,
+    pub institution: String,
+    pub previous: MarginRatios,
+    pub ratios: MarginRatios,
+    pub effective_at: DateTime<Utc>,
+    pub changed_by: String,
+    pub reason: String,
+    pub requested_at: DateTime<Utc>,
+    /// Set once the ratios are in force; future-dated changes wait until `effective_at`
+    pub applied_at: Option<DateTime<Utc>>,
+    /// Margin call raised by re-evaluating the account under the new ratios
+    pub margin_call: Option<MarginCallAlert>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StressTestScenario {
     pub scenario_name: String,
@@ -173,10 +442,17 @@ pub struct PrimeBrokerageService {
     asset_prices: HashMap<String, u128>,
     asset_volatilities: HashMap<String, u32>,
     correlation_matrix: HashMap<String, HashMap<String, u32>>,
+    margin_config: MarginConfig,
+    ratio_changes: HashMap<String, Vec<MarginRatioChange>>, // Institution -> changes, oldest first
+    prices: Option<Arc<dyn ConversionRates>>,
 }
 
 impl PrimeBrokerageService {
     pub fn new() -> Self {
+        Self::with_config(MarginConfig::default())
+    }
+
+    pub fn with_config(margin_config: MarginConfig) -> Self {
         Self {
             prime_accounts: HashMap::new(),
             portfolio_margin_accounts: HashMap::new(),
@@ -187,9 +463,19 @@ impl PrimeBrokerageService {
             asset_prices: HashMap::new(),
             asset_volatilities: HashMap::new(),
             correlation_matrix: HashMap::new(),
+            margin_config,
+            ratio_changes: HashMap::new(),
+            prices: None,
         }
     }
 
+    /// Value collateral at the aggregator's USD prices and FX rates; without it no
+    /// collateral can be valued and accounts holding any stay frozen
+    pub fn with_prices(mut self, prices: Arc<dyn ConversionRates>) -> Self {
+        self.prices = Some(prices);
+        self
+    }
+
     pub async fn create_prime_account(
         &mut self,
         institution: String,
@@ -207,15 +493,17 @@ impl PrimeBrokerageService {
             return Err(anyhow!("At least one authorized trader required"));
         }
 
+        let ratios = self.margin_config.default_ratios.get(&account_type).copied()
+            .unwrap_or_else(|| MarginRatios::new(1250, 1500));
         let account = PrimeAccount {
             institution: institution.clone(),
             institution_name,
             account_type,
             credit_limit,
             current_exposure: 0,
-            available_credit: credit_limit,
-            maintenance_margin_ratio: 1250, // 12.5%
-            initial_margin_ratio: 1500,     // 15%
+            available_credit: 0, // Extended once collateral is deposited and valued
+            maintenance_margin_ratio: ratios.maintenance_margin_ratio,
+            initial_margin_ratio: ratios.initial_margin_ratio,
             collateral_balances: HashMap::new(),
             positions: HashMap::new(),
             credit_facilities: HashMap::new(),
@@ -225,6 +513,9 @@ impl PrimeBrokerageService {
             jurisdiction,
             authorized_traders,
             risk_score: 50, // Default medium risk
+            base_currency: FiatCurrency::USD,
+            collateral_valuation: None,
+            flags: BTreeSet::new(),
         };
 
         self.prime_accounts.insert(institution.clone(), account);
@@ -291,6 +582,8 @@ impl PrimeBrokerageService {
         position: i128,
         entry_price: u128,
     ) -> Result<()> {
+        // Margin is checked against collateral at current prices
+        self.update_available_credit(&institution).await?;
         let account = self.prime_accounts.get(&institution)
             .ok_or_else(|| anyhow!("Institution {} not found", institution))?;
 
@@ -373,9 +666,17 @@ impl PrimeBrokerageService {
         facility_type: CreditType,
         amount: u128,
     ) -> Result<()> {
+        self.update_available_credit(&institution).await?;
         let account = self.prime_accounts.get_mut(&institution)
             .ok_or_else(|| anyhow!("Institution {} not found", institution))?;
 
+        if account.flags.contains(&AccountFlag::StaleCollateralPrice) {
+            return Err(anyhow!("Credit is frozen: collateral of {} has a stale price", institution));
+        }
+        if amount > account.available_credit {
+            return Err(anyhow!("Exceeds available credit against collateral"));
+        }
+
         let facility = account.credit_facilities.get_mut(&facility_type)
             .ok_or_else(|| anyhow!("Credit facility {:?} not found", facility_type))?;
 
@@ -388,6 +689,7 @@ impl PrimeBrokerageService {
         }
 
         facility.utilized += amount;
+        account.available_credit -= amount;
         account.last_activity = Utc::now();
 
         println!("Utilized {} from {:?} facility for institution {}", amount, facility_type, institution);
@@ -465,7 +767,90 @@ impl PrimeBrokerageService {
         }
     }
 
+    /// Change an account's margin ratios from `effective_at` (now when absent). Changes in
+    /// force immediately re-evaluate the account and may raise a margin call.
+    pub async fn set_margin_ratios(
+        &mut self,
+        institution: &str,
+        ratios: MarginRatios,
+        effective_at: Option<DateTime<Utc>>,
+        changed_by: &str,
+        reason: &str,
+    ) -> Result<MarginRatioChange> {
+        ratios.validate()?;
+        let account = self.prime_accounts.get(institution)
+            .ok_or_else(|| MarginRatioError::AccountNotFound(institution.to_string()))?;
+
+        let now = Utc::now();
+        let change = MarginRatioChange {
+            change_id: Uuid::new_v4(),
+            institution: institution.to_string(),
+            previous: MarginRatios::new(account.maintenance_margin_ratio, account.initial_margin_ratio),
+            ratios,
+            effective_at: effective_at.unwrap_or(now).max(now),
+            changed_by: changed_by.to_string(),
+            reason: reason.to_string(),
+            requested_at: now,
+            applied_at: None,
+            margin_call: None,
+        };
+        let change_id = change.change_id;
+        self.ratio_changes.entry(institution.to_string()).or_default().push(change);
+
+        self.apply_due_ratio_changes(now).await?;
+        self.ratio_changes.get(institution)
+            .and_then(|changes| changes.iter().find(|change| change.change_id == change_id))
+            .cloned()
+            .ok_or_else(|| anyhow!("Margin ratio change {} not found", change_id))
+    }
+
+    /// Put changes whose effective date has passed into force, oldest first, and
+    /// re-evaluate each affected account
+    pub async fn apply_due_ratio_changes(&mut self, now: DateTime<Utc>) -> Result<Vec<MarginRatioChange>> {
+        let mut due: Vec<(String, Uuid, DateTime<Utc>)> = self.ratio_changes.iter()
+            .flat_map(|(institution, changes)| changes.iter()
+                .filter(|change| change.applied_at.is_none() && change.effective_at <= now)
+                .map(move |change| (institution.clone(), change.change_id, change.effective_at)))
+            .collect();
+        due.sort_by_key(|(_, _, effective_at)| *effective_at);
+
+        let mut applied = Vec::new();
+        for (institution, change_id, _) in due {
+            let Some(change) = self.ratio_change_mut(&institution, change_id) else { continue };
+            let ratios = change.ratios;
+            change.applied_at = Some(now);
+
+            if let Some(account) = self.prime_accounts.get_mut(&institution) {
+                account.maintenance_margin_ratio = ratios.maintenance_margin_ratio;
+                account.initial_margin_ratio = ratios.initial_margin_ratio;
+            }
+            let calls_before = self.margin_calls.get(&institution).map_or(0, Vec::len);
+            self.check_margin_requirements(&institution).await?;
+            let margin_call = self.margin_calls.get(&institution)
+                .filter(|calls| calls.len() > calls_before)
+                .and_then(|calls| calls.last().cloned());
+
+            if let Some(change) = self.ratio_change_mut(&institution, change_id) {
+                change.margin_call = margin_call;
+                applied.push(change.clone());
+            }
+        }
+        Ok(applied)
+    }
+
+    /// Ratio changes of an account, oldest first
+    pub fn get_margin_ratio_changes(&self, institution: &str) -> &[MarginRatioChange] {
+        self.ratio_changes.get(institution).map_or(&[], Vec::as_slice)
+    }
+
+    fn ratio_change_mut(&mut self, institution: &str, change_id: Uuid) -> Option<&mut MarginRatioChange> {
+        self.ratio_changes.get_mut(institution)?.iter_mut().find(|change| change.change_id == change_id)
+    }
+
+    /// Raise a margin call when collateral at current prices no longer covers maintenance
+    /// margin. Fails without a call while the collateral cannot be valued.
     pub async fn check_margin_requirements(&mut self, institution: &str) -> Result<bool> {
+        self.update_available_credit(institution).await?;
         let total_exposure = self.calculate_total_exposure(institution).await?;
         let available_margin = self.calculate_available_margin(institution).await?;
         
@@ -528,7 +913,7 @@ impl PrimeBrokerageService {
             
             // Calculate leverage
             if account.current_exposure > 0 {
-                let collateral_value = self.calculate_total_collateral_value_sync(&account.institution);
+                let collateral_value = self.collateral_market_value(&account.institution);
                 if collateral_value > 0 {
                     total_leverage += (account.current_exposure as f64) / (collateral_value as f64);
                 }
@@ -545,12 +930,7 @@ impl PrimeBrokerageService {
 
         // Calculate risk distribution
         for metrics in self.risk_metrics.values() {
-            let risk_level = match metrics.overall_risk_score {
-                0..=25 => RiskLevel::Low,
-                26..=50 => RiskLevel::Medium,
-                51..=75 => RiskLevel::High,
-                _ => RiskLevel::Critical,
-            };
+            let risk_level = self.margin_config.risk_level_bounds.level(metrics.overall_risk_score);
             *risk_distribution.entry(risk_level).or_insert(0) += 1;
         }
 
@@ -569,6 +949,14 @@ impl PrimeBrokerageService {
             active_positions: self.cross_margin_positions.values().map(|v| v.len() as u32).sum(),
             portfolio_margin_accounts: self.portfolio_margin_accounts.len() as u32,
             risk_distribution,
+            stale_price_accounts: {
+                let mut stale: Vec<String> = self.prime_accounts.values()
+                    .filter(|account| account.flags.contains(&AccountFlag::StaleCollateralPrice))
+                    .map(|account| account.institution.clone())
+                    .collect();
+                stale.sort();
+                stale
+            },
         }
     }
 
@@ -584,30 +972,132 @@ impl PrimeBrokerageService {
         self.margin_calls.get(institution)
     }
 
+    /// Margin calls across all institutions whose deadline has not yet passed
+    pub fn open_margin_call_count(&self) -> usize {
+        let now = Utc::now();
+        self.margin_calls.values()
+            .flat_map(|calls| calls.iter())
+            .filter(|call| call.deadline > now)
+            .count()
+    }
+
     pub fn get_all_institutions(&self) -> Vec<&PrimeAccount> {
         self.prime_accounts.values().collect()
     }
 
+    /// Value an account's collateral in its base currency at current prices, after haircuts.
+    ///
+    /// Assets priced longer ago than the staleness limit are left out while they make up
+    /// less than the materiality share of the collateral, and fail the valuation otherwise.
+    pub async fn value_collateral(&self, institution: &str) -> Result<CollateralValuation> {
+        let account = self.prime_accounts.get(institution)
+            .ok_or_else(|| anyhow!("Institution {} not found", institution))?;
+        let config = &self.margin_config;
+        let now = Utc::now();
+        let currency = account.base_currency;
+
+        let mut balances: Vec<(&String, u128)> = account.collateral_balances.iter()
+            .filter(|(_, amount)| **amount > 0)
+            .map(|(asset, amount)| (asset, *amount))
+            .collect();
+        balances.sort();
+
+        let mut assets = Vec::new();
+        if let Some((first, _)) = balances.first() {
+            let prices = self.prices.as_ref().ok_or_else(|| CollateralValuationError::PriceUnavailable {
+                asset: first.to_string(),
+                reason: "no price source configured".to_string(),
+            })?;
+            let pair = format!("USD/{}", currency);
+            let fx = prices.usd_fx_rate(currency).await
+                .map_err(|reason| CollateralValuationError::PriceUnavailable { asset: pair.clone(), reason })?;
+            if now - fx.observed_at > config.max_price_age {
+                return Err(CollateralValuationError::StalePrice { asset: pair, observed_at: fx.observed_at }.into());
+            }
+
+            for (asset, amount) in balances {
+                let usd = prices.asset_price_usd(asset).await
+                    .map_err(|reason| CollateralValuationError::PriceUnavailable { asset: asset.clone(), reason })?;
+                let price = usd.rate * fx.rate;
+                let market_value = value_in_units(amount, price)
+                    .ok_or_else(|| CollateralValuationError::Overflow(asset.clone()))?;
+                let haircut_bps = config.collateral_haircuts.get(asset).copied().unwrap_or(config.default_haircut_bps);
+                let stale = now - usd.observed_at > config.max_price_age;
+                assets.push(CollateralAssetValue {
+                    asset: asset.clone(),
+                    amount,
+                    price,
+                    price_observed_at: usd.observed_at,
+                    stale,
+                    haircut_bps,
+                    market_value,
+                    lending_value: if stale { 0 } else { market_value * (10_000 - haircut_bps as u128) / 10_000 },
+                });
+            }
+        }
+
+        let total: u128 = assets.iter().map(|asset| asset.market_value).sum();
+        if let Some(stale) = assets.iter().find(|asset| {
+            asset.stale && asset.market_value * 10_000 >= total * config.material_collateral_bps as u128
+        }) {
+            return Err(CollateralValuationError::StalePrice { asset: stale.asset.clone(), observed_at: stale.price_observed_at }.into());
+        }
+
+        Ok(CollateralValuation {
+            currency,
+            market_value: assets.iter().filter(|asset| !asset.stale).map(|asset| asset.market_value).sum(),
+            lending_value: assets.iter().map(|asset| asset.lending_value).sum(),
+            assets,
+            valued_at: now,
+        })
+    }
+
     // Private helper methods
 
+    /// Revalue the collateral and recompute available credit. Collateral that cannot be
+    /// valued flags the account and freezes its credit until a valuation succeeds.
     async fn update_available_credit(&mut self, institution: &str) -> Result<()> {
-        let total_collateral_value = self.calculate_total_collateral_value(institution).await?;
-        
-        if let Some(account) = self.prime_accounts.get_mut(institution) {
-            let used_credit = account.credit_limit - account.available_credit;
-            
-            // Available credit = (collateral value * haircut) - used credit
-            let available_from_collateral = (total_collateral_value * 8000) / 10000; // 80% haircut
-            account.available_credit = if available_from_collateral > used_credit {
-                available_from_collateral - used_credit
-            } else {
-                0
-            };
+        let valuation = self.value_collateral(institution).await;
+        let account = self.prime_accounts.get_mut(institution)
+            .ok_or_else(|| anyhow!("Institution {} not found", institution))?;
+
+        match valuation {
+            Ok(valuation) => {
+                // Available credit = lending value of collateral, capped at the limit, less credit drawn
+                let used_credit: u128 = account.credit_facilities.values().map(|facility| facility.utilized).sum();
+                account.available_credit = valuation.lending_value.min(account.credit_limit).saturating_sub(used_credit);
+                account.collateral_valuation = Some(valuation);
+                account.flags.remove(&AccountFlag::StaleCollateralPrice);
+            }
+            Err(e) => {
+                let error = e.downcast::<CollateralValuationError>()?;
+                if account.flags.insert(AccountFlag::StaleCollateralPrice) {
+                    println!("Credit frozen for institution {}: {}", institution, error);
+                }
+                account.available_credit = 0;
+            }
         }
 
         Ok(())
     }
 
+    /// Lending value of the last accepted valuation; fails while the account is frozen
+    fn collateral_lending_value(&self, institution: &str) -> Result<u128> {
+        let account = self.prime_accounts.get(institution)
+            .ok_or_else(|| anyhow!("Institution {} not found", institution))?;
+        if account.flags.contains(&AccountFlag::StaleCollateralPrice) {
+            return Err(anyhow!("Collateral of {} has a stale price and cannot be valued", institution));
+        }
+        Ok(account.collateral_valuation.as_ref().map_or(0, |valuation| valuation.lending_value))
+    }
+
+    /// Market value of the last accepted valuation
+    fn collateral_market_value(&self, institution: &str) -> u128 {
+        self.prime_accounts.get(institution)
+            .and_then(|account| account.collateral_valuation.as_ref())
+            .map_or(0, |valuation| valuation.market_value)
+    }
+
     async fn can_withdraw_collateral(&self, institution: &str, asset: &str, amount: u128) -> Result<bool> {
         // Simplified check - in reality would calculate impact on margin requirements
         let account = self.prime_accounts.get(institution)
@@ -623,7 +1113,7 @@ impl PrimeBrokerageService {
     }
 
     async fn calculate_available_margin(&self, institution: &str) -> Result<u128> {
-        let total_collateral_value = self.calculate_total_collateral_value(institution).await?;
+        let total_collateral_value = self.collateral_lending_value(institution)?;
         let current_exposure = self.calculate_total_exposure(institution).await?;
         
         let account = self.prime_accounts.get(institution)
@@ -645,18 +1135,8 @@ impl PrimeBrokerageService {
         Ok(account.current_exposure)
     }
 
-    async fn calculate_total_collateral_value(&self, institution: &str) -> Result<u128> {
-        // Simplified - in reality would iterate through all collateral assets and apply current prices
-        Ok(1_000_000 * 1_000_000_000_000_000_000) // Placeholder: 1M tokens
-    }
-
-    fn calculate_total_collateral_value_sync(&self, institution: &str) -> u128 {
-        // Simplified synchronous version for metrics calculation
-        1_000_000 * 1_000_000_000_000_000_000 // Placeholder: 1M tokens
-    }
-
     async fn calculate_position_risk(&self, position_value: u128, institution: &str) -> Result<RiskLevel> {
-        let portfolio_value = self.calculate_total_collateral_value(institution).await?;
+        let portfolio_value = self.collateral_market_value(institution);
         if portfolio_value == 0 {
             return Ok(RiskLevel::Critical);
         }
@@ -682,7 +1162,7 @@ impl PrimeBrokerageService {
     }
 
     async fn update_risk_metrics(&mut self, institution: &str) -> Result<()> {
-        let portfolio_value = self.calculate_total_collateral_value(institution).await?;
+        let portfolio_value = self.collateral_market_value(institution);
         let total_exposure = self.calculate_total_exposure(institution).await?;
         let leverage_ratio = if portfolio_value > 0 {
             ((total_exposure * 100) / portfolio_value) as u32
@@ -731,7 +1211,7 @@ impl PrimeBrokerageService {
     }
 
     async fn calculate_risk_based_margin(&self, institution: &str) -> Result<MarginCalculationResult> {
-        let portfolio_value = self.calculate_total_collateral_value(institution).await?;
+        let portfolio_value = self.collateral_market_value(institution);
         let portfolio_volatility = 1500u32; // 15% portfolio volatility
         let volatility_multiplier = 200u32; // 2x volatility multiplier
         
@@ -805,4 +1285,194 @@ impl PrimeBrokerageService {
         self.stress_test_scenarios.insert(scenario_name, scenario);
         Ok(())
     }
-} 
\ No newline at end of file
+} 
+/// Value of `amount` base units at `price` per whole unit, in base units
+fn value_in_units(amount: u128, price: Decimal) -> Option<u128> {
+    let whole = Decimal::try_from_i128_with_scale(i128::try_from(amount).ok()?, UNIT_DECIMALS).ok()?;
+    whole.checked_mul(price)?
+        .checked_mul(Decimal::from(10u64.pow(UNIT_DECIMALS)))?
+        .trunc()
+        .to_u128()
+}
+
+/// Task name reported to TaskHealth by the ratio activation loop
+pub const MARGIN_RATIO_ACTIVATION_TASK: &str = "margin_ratio_activation";
+
+/// Put future-dated margin ratio changes into force once their effective date passes
+pub fn spawn_margin_ratio_activation(
+    service: std::sync::Arc<tokio::sync::RwLock<PrimeBrokerageService>>,
+    health: std::sync::Arc<crate::task_health::TaskHealth>,
+    every: std::time::Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(every);
+        loop {
+            ticker.tick().await;
+            match service.write().await.apply_due_ratio_changes(Utc::now()).await {
+                Ok(applied) => {
+                    for change in applied.iter().filter(|change| change.margin_call.is_some()) {
+                        println!("Margin call raised for {} after ratio change {}", change.institution, change.change_id);
+                    }
+                    health.record_success(MARGIN_RATIO_ACTIVATION_TASK);
+                }
+                Err(e) => health.record_failure(MARGIN_RATIO_ACTIVATION_TASK, e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compliance::thresholds::RateObservation;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    const ONE_TOKEN: u128 = 1_000_000_000_000_000_000;
+
+    /// USD prices per asset and when they were observed
+    #[derive(Default)]
+    struct StubPrices {
+        prices: Mutex<HashMap<String, (Decimal, DateTime<Utc>)>>,
+    }
+
+    impl StubPrices {
+        fn set(&self, asset: &str, price: i64, observed_at: DateTime<Utc>) {
+            self.prices.lock().unwrap().insert(asset.to_string(), (Decimal::from(price), observed_at));
+        }
+    }
+
+    #[async_trait]
+    impl ConversionRates for StubPrices {
+        async fn asset_price_usd(&self, asset: &str) -> Result<RateObservation, String> {
+            let (rate, observed_at) = *self.prices.lock().unwrap().get(asset).ok_or_else(|| format!("no {} feed", asset))?;
+            Ok(RateObservation { rate, observed_at, source: "test".to_string() })
+        }
+
+        async fn usd_fx_rate(&self, _currency: FiatCurrency) -> Result<RateObservation, String> {
+            Ok(RateObservation { rate: Decimal::ONE, observed_at: Utc::now(), source: "identity".to_string() })
+        }
+    }
+
+    async fn priced_service(prices: Arc<StubPrices>) -> PrimeBrokerageService {
+        let mut config = MarginConfig::default();
+        config.collateral_haircuts.insert("USDC".to_string(), 0);
+        let mut service = PrimeBrokerageService::with_config(config).with_prices(prices);
+        service.create_prime_account(
+            "inst-1".to_string(),
+            "Institution One".to_string(),
+            AccountType::PrimeServices,
+            10_000_000 * ONE_TOKEN,
+            "US".to_string(),
+            vec!["trader-1".to_string()],
+        ).await.unwrap();
+        service
+    }
+
+    /// Account with 3M tokens of exposure against 1M USDC of collateral at par, no haircut
+    async fn leveraged_account() -> PrimeBrokerageService {
+        let prices = Arc::new(StubPrices::default());
+        prices.set("USDC", 1, Utc::now());
+        let mut service = priced_service(prices).await;
+        service.deposit_collateral("inst-1".to_string(), "USDC".to_string(), 1_000_000 * ONE_TOKEN).await.unwrap();
+        service.prime_accounts.get_mut("inst-1").unwrap().current_exposure = 3_000_000 * ONE_TOKEN;
+        service
+    }
+
+    #[tokio::test]
+    async fn test_tightened_ratio_raises_margin_call() {
+        let mut service = leveraged_account().await;
+        assert!(service.check_margin_requirements("inst-1").await.unwrap());
+        assert!(service.get_margin_calls("inst-1").is_none());
+
+        let change = service.set_margin_ratios("inst-1", MarginRatios::new(2500, 3000), None, "admin", "Concentrated book").await.unwrap();
+
+        assert_eq!(change.previous, MarginRatios::new(1250, 1500));
+        assert!(change.applied_at.is_some());
+        let call = change.margin_call.expect("tightened ratio should breach maintenance margin");
+        // 25% of 3M is 750k required against 1M - 750k = 250k available
+        assert_eq!(call.required_margin, 750_000 * ONE_TOKEN);
+        assert_eq!(call.shortfall, 500_000 * ONE_TOKEN);
+        assert_eq!(service.get_margin_calls("inst-1").unwrap().len(), 1);
+        assert_eq!(service.prime_accounts["inst-1"].maintenance_margin_ratio, 2500);
+    }
+
+    #[tokio::test]
+    async fn test_future_change_waits_for_effective_date() {
+        let mut service = leveraged_account().await;
+        let effective_at = Utc::now() + Duration::days(1);
+        let change = service.set_margin_ratios("inst-1", MarginRatios::new(2500, 3000), Some(effective_at), "admin", "Scheduled review").await.unwrap();
+
+        assert!(change.applied_at.is_none());
+        assert_eq!(service.prime_accounts["inst-1"].maintenance_margin_ratio, 1250);
+        assert!(service.apply_due_ratio_changes(Utc::now()).await.unwrap().is_empty());
+
+        let applied = service.apply_due_ratio_changes(effective_at).await.unwrap();
+        assert_eq!(applied.len(), 1);
+        assert!(applied[0].margin_call.is_some());
+        assert_eq!(service.get_margin_ratio_changes("inst-1").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_ratio_bounds_are_validated() {
+        let mut service = leveraged_account().await;
+        for ratios in [MarginRatios::new(1500, 1500), MarginRatios::new(50, 1500), MarginRatios::new(1250, 12_000)] {
+            let err = service.set_margin_ratios("inst-1", ratios, None, "admin", "bad").await.unwrap_err();
+            assert!(matches!(err.downcast_ref::<MarginRatioError>(), Some(MarginRatioError::Invalid(_))));
+        }
+        let missing = service.set_margin_ratios("inst-2", MarginRatios::new(1000, 1200), None, "admin", "x").await.unwrap_err();
+        assert!(matches!(missing.downcast_ref::<MarginRatioError>(), Some(MarginRatioError::AccountNotFound(_))));
+        assert!(service.get_margin_ratio_changes("inst-1").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stale_price_freezes_credit() {
+        let prices = Arc::new(StubPrices::default());
+        prices.set("USDC", 1, Utc::now());
+        prices.set("ETH", 2_000, Utc::now());
+        let mut service = priced_service(prices.clone()).await;
+        service.deposit_collateral("inst-1".to_string(), "USDC".to_string(), 100_000 * ONE_TOKEN).await.unwrap();
+        service.deposit_collateral("inst-1".to_string(), "ETH".to_string(), 100 * ONE_TOKEN).await.unwrap();
+        // 100k USDC at par plus 200k of ETH after a 20% haircut
+        assert_eq!(service.prime_accounts["inst-1"].available_credit, 260_000 * ONE_TOKEN);
+
+        prices.set("ETH", 2_000, Utc::now() - Duration::hours(1));
+        service.deposit_collateral("inst-1".to_string(), "USDC".to_string(), 10_000 * ONE_TOKEN).await.unwrap();
+        let account = &service.prime_accounts["inst-1"];
+        assert!(account.flags.contains(&AccountFlag::StaleCollateralPrice));
+        assert_eq!(account.available_credit, 0);
+        let err = service.utilize_credit_facility("inst-1".to_string(), CreditType::MarginLending, ONE_TOKEN).await.unwrap_err();
+        assert!(err.to_string().contains("frozen"), "{}", err);
+        assert!(service.check_margin_requirements("inst-1").await.is_err());
+        assert_eq!(service.get_prime_brokerage_metrics().stale_price_accounts, vec!["inst-1".to_string()]);
+
+        // A fresh price lifts the freeze
+        prices.set("ETH", 2_000, Utc::now());
+        assert!(service.check_margin_requirements("inst-1").await.unwrap());
+        assert!(service.prime_accounts["inst-1"].flags.is_empty());
+        assert_eq!(service.prime_accounts["inst-1"].available_credit, 270_000 * ONE_TOKEN);
+        assert!(service.get_prime_brokerage_metrics().stale_price_accounts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_price_drop_below_exposure_raises_margin_call() {
+        let prices = Arc::new(StubPrices::default());
+        prices.set("ETH", 2_000, Utc::now());
+        let mut service = priced_service(prices.clone()).await;
+        service.deposit_collateral("inst-1".to_string(), "ETH".to_string(), 500 * ONE_TOKEN).await.unwrap();
+        service.prime_accounts.get_mut("inst-1").unwrap().current_exposure = 1_000_000 * ONE_TOKEN;
+        assert!(service.check_margin_requirements("inst-1").await.unwrap());
+        assert_eq!(service.prime_accounts["inst-1"].available_credit, 800_000 * ONE_TOKEN);
+
+        prices.set("ETH", 500, Utc::now());
+        assert!(!service.check_margin_requirements("inst-1").await.unwrap());
+        let account = &service.prime_accounts["inst-1"];
+        // 500 ETH at 500 is 250k, lending 200k after the haircut, under the 1M exposure
+        assert_eq!(account.available_credit, 200_000 * ONE_TOKEN);
+        assert!(account.available_credit < account.current_exposure);
+        let call = &service.get_margin_calls("inst-1").unwrap()[0];
+        // 12.5% of 1M is 125k required against 200k - 125k = 75k available
+        assert_eq!(call.required_margin, 125_000 * ONE_TOKEN);
+        assert_eq!(call.shortfall, 50_000 * ONE_TOKEN);
+    }
+}
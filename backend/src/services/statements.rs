@@ -0,0 +1,822 @@
+// Monthly statements per institution, built from the position ledger and stored immutably
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::collections::{BTreeMap, HashMap};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::services::prime_brokerage_service::MarginCallAlert;
+
+/// Kind of a position ledger entry; `amount` is always the positive cash value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LedgerEntryType {
+    Deposit,
+    Withdrawal,
+    Buy,
+    Sell,
+    Fee,
+    /// Financing interest charged on margin borrowing
+    Interest,
+    /// Yield distribution received on a holding
+    Yield,
+}
+
+impl LedgerEntryType {
+    fn parse(entry_type: &str) -> Option<Self> {
+        Some(match entry_type {
+            "deposit" => LedgerEntryType::Deposit,
+            "withdrawal" => LedgerEntryType::Withdrawal,
+            "buy" => LedgerEntryType::Buy,
+            "sell" => LedgerEntryType::Sell,
+            "fee" => LedgerEntryType::Fee,
+            "interest" => LedgerEntryType::Interest,
+            "yield" => LedgerEntryType::Yield,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub entry_type: LedgerEntryType,
+    /// Set on buys, sells and yield distributions
+    pub asset_id: Option<String>,
+    pub quantity: Decimal,
+    pub price: Decimal,
+    pub amount: Decimal,
+    /// When the entry takes effect on the account
+    pub effective_at: DateTime<Utc>,
+    /// When the platform learned of it; corrections arrive after the fact
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// A calendar month, identified by its first day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct StatementPeriod(NaiveDate);
+
+impl StatementPeriod {
+    pub fn new(year: i32, month: u32) -> Option<Self> {
+        NaiveDate::from_ymd_opt(year, month, 1).map(Self)
+    }
+
+    /// Parse `YYYY-MM`
+    pub fn parse(month: &str) -> Option<Self> {
+        let (year, month) = month.split_once('-')?;
+        Self::new(year.parse().ok()?, month.parse().ok()?)
+    }
+
+    pub fn first_day(&self) -> NaiveDate {
+        self.0
+    }
+
+    pub fn start(&self) -> DateTime<Utc> {
+        Utc.from_utc_datetime(&self.0.and_hms_opt(0, 0, 0).expect("midnight is valid"))
+    }
+
+    /// Start of the following month; the period excludes it
+    pub fn end(&self) -> DateTime<Utc> {
+        let next = if self.0.month() == 12 {
+            NaiveDate::from_ymd_opt(self.0.year() + 1, 1, 1)
+        } else {
+            NaiveDate::from_ymd_opt(self.0.year(), self.0.month() + 1, 1)
+        };
+        Utc.from_utc_datetime(&next.expect("next month is valid").and_hms_opt(0, 0, 0).expect("midnight is valid"))
+    }
+
+    /// Day whose closing prices value the opening balances
+    pub fn opening_price_date(&self) -> NaiveDate {
+        self.0 - Duration::days(1)
+    }
+
+    pub fn closing_price_date(&self) -> NaiveDate {
+        (self.end() - Duration::days(1)).date_naive()
+    }
+}
+
+impl std::fmt::Display for StatementPeriod {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}", self.0.year(), self.0.month())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Balances {
+    pub cash: Decimal,
+    pub positions_value: Decimal,
+    pub total: Decimal,
+}
+
+/// Movement of one asset over the month, at average cost
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PositionChange {
+    pub asset_id: String,
+    pub opening_quantity: Decimal,
+    pub bought: Decimal,
+    pub sold: Decimal,
+    pub closing_quantity: Decimal,
+    pub opening_price: Decimal,
+    pub closing_price: Decimal,
+    pub closing_cost: Decimal,
+    pub closing_value: Decimal,
+    pub realized_pnl: Decimal,
+    pub unrealized_pnl: Decimal,
+    pub yield_received: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatementBody {
+    pub institution_id: String,
+    pub period: String,
+    pub opening: Balances,
+    pub closing: Balances,
+    pub positions: Vec<PositionChange>,
+    pub deposits: Decimal,
+    pub withdrawals: Decimal,
+    pub fees: Decimal,
+    pub interest: Decimal,
+    pub yield_distributions: Decimal,
+    pub realized_pnl: Decimal,
+    pub opening_unrealized_pnl: Decimal,
+    pub closing_unrealized_pnl: Decimal,
+    /// Margin calls raised during the month
+    pub margin_calls: Vec<MarginCallAlert>,
+    /// Ledger entries effective in the month
+    pub entry_count: usize,
+}
+
+/// A stored statement; later versions amend earlier ones and never replace them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Statement {
+    pub statement_id: Uuid,
+    pub institution_id: String,
+    pub period: String,
+    pub version: i32,
+    /// Statement this one amends
+    pub amends: Option<Uuid>,
+    /// Ledger entries recorded after this are not reflected
+    pub ledger_cutoff: DateTime<Utc>,
+    /// SHA-256 of the JSON body
+    pub content_hash: String,
+    pub generated_at: DateTime<Utc>,
+    pub body: StatementBody,
+}
+
+#[derive(Debug)]
+pub enum StatementError {
+    /// Statements are only generated for months that have ended
+    PeriodOpen(StatementPeriod),
+    NotFound,
+    Database(sqlx::Error),
+    Corrupt(String),
+}
+
+impl std::fmt::Display for StatementError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            StatementError::PeriodOpen(period) => write!(f, "{} has not ended yet", period),
+            StatementError::NotFound => write!(f, "Statement not found"),
+            StatementError::Database(e) => write!(f, "Database error: {}", e),
+            StatementError::Corrupt(msg) => write!(f, "Stored statement is unreadable: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for StatementError {}
+
+impl From<sqlx::Error> for StatementError {
+    fn from(e: sqlx::Error) -> Self {
+        StatementError::Database(e)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Holding {
+    quantity: Decimal,
+    cost: Decimal,
+    /// Price of the latest trade, the fallback when no market price is known
+    last_price: Decimal,
+}
+
+impl Holding {
+    fn value(&self, price: Option<&Decimal>) -> Decimal {
+        self.quantity * price.copied().unwrap_or(self.last_price)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Book {
+    cash: Decimal,
+    holdings: BTreeMap<String, Holding>,
+}
+
+impl Book {
+    /// Applies an entry and returns the realized P&L of a sale
+    fn apply(&mut self, entry: &LedgerEntry) -> Decimal {
+        match entry.entry_type {
+            LedgerEntryType::Deposit | LedgerEntryType::Yield => self.cash += entry.amount,
+            LedgerEntryType::Withdrawal | LedgerEntryType::Fee | LedgerEntryType::Interest => self.cash -= entry.amount,
+            LedgerEntryType::Buy => {
+                self.cash -= entry.amount;
+                let holding = self.holdings.entry(entry.asset_id.clone().unwrap_or_default()).or_default();
+                holding.quantity += entry.quantity;
+                holding.cost += entry.amount;
+                holding.last_price = entry.price;
+            }
+            LedgerEntryType::Sell => {
+                self.cash += entry.amount;
+                let holding = self.holdings.entry(entry.asset_id.clone().unwrap_or_default()).or_default();
+                let cost_removed = if holding.quantity.is_zero() {
+                    Decimal::ZERO
+                } else {
+                    holding.cost * entry.quantity / holding.quantity
+                };
+                holding.quantity -= entry.quantity;
+                holding.cost -= cost_removed;
+                holding.last_price = entry.price;
+                return entry.amount - cost_removed;
+            }
+        }
+        Decimal::ZERO
+    }
+
+    fn balances(&self, prices: &HashMap<String, Decimal>) -> Balances {
+        let positions_value = self.holdings.iter()
+            .map(|(asset, holding)| holding.value(prices.get(asset)))
+            .sum();
+        Balances { cash: self.cash, positions_value, total: self.cash + positions_value }
+    }
+
+    fn unrealized_pnl(&self, prices: &HashMap<String, Decimal>) -> Decimal {
+        self.holdings.iter()
+            .map(|(asset, holding)| holding.value(prices.get(asset)) - holding.cost)
+            .sum()
+    }
+}
+
+/// Builds a month's statement from every ledger entry effective before the month ends.
+///
+/// Prices are the closing prices before and at the end of the month; assets without one
+/// are valued at their latest trade price. Balances tie out to the flows:
+/// `closing.total - opening.total = deposits - withdrawals - fees - interest + yield
+/// + realized + (closing unrealized - opening unrealized)`.
+pub fn build_statement(
+    institution_id: &str,
+    period: StatementPeriod,
+    entries: &[LedgerEntry],
+    opening_prices: &HashMap<String, Decimal>,
+    closing_prices: &HashMap<String, Decimal>,
+    margin_calls: &[MarginCallAlert],
+) -> StatementBody {
+    let (start, end) = (period.start(), period.end());
+    let mut entries: Vec<&LedgerEntry> = entries.iter().filter(|entry| entry.effective_at < end).collect();
+    entries.sort_by_key(|entry| (entry.effective_at, entry.recorded_at));
+
+    let opening_count = entries.iter().take_while(|entry| entry.effective_at < start).count();
+    let mut book = Book::default();
+    for entry in &entries[..opening_count] {
+        book.apply(entry);
+    }
+    let opening = book.balances(opening_prices);
+    let opening_unrealized_pnl = book.unrealized_pnl(opening_prices);
+    let opening_book = book.clone();
+
+    let mut body = StatementBody {
+        institution_id: institution_id.to_string(),
+        period: period.to_string(),
+        opening,
+        closing: Balances::default(),
+        positions: Vec::new(),
+        deposits: Decimal::ZERO,
+        withdrawals: Decimal::ZERO,
+        fees: Decimal::ZERO,
+        interest: Decimal::ZERO,
+        yield_distributions: Decimal::ZERO,
+        realized_pnl: Decimal::ZERO,
+        opening_unrealized_pnl,
+        closing_unrealized_pnl: Decimal::ZERO,
+        margin_calls: margin_calls.iter()
+            .filter(|call| call.created_at >= start && call.created_at < end)
+            .cloned()
+            .collect(),
+        entry_count: 0,
+    };
+
+    let mut changes: BTreeMap<String, PositionChange> = BTreeMap::new();
+    for entry in &entries[opening_count..] {
+        body.entry_count += 1;
+        let realized = book.apply(entry);
+        match entry.entry_type {
+            LedgerEntryType::Deposit => body.deposits += entry.amount,
+            LedgerEntryType::Withdrawal => body.withdrawals += entry.amount,
+            LedgerEntryType::Fee => body.fees += entry.amount,
+            LedgerEntryType::Interest => body.interest += entry.amount,
+            LedgerEntryType::Yield => body.yield_distributions += entry.amount,
+            LedgerEntryType::Buy | LedgerEntryType::Sell => body.realized_pnl += realized,
+        }
+        if let Some(asset) = &entry.asset_id {
+            let change = changes.entry(asset.clone()).or_insert_with(|| blank_change(asset));
+            match entry.entry_type {
+                LedgerEntryType::Buy => change.bought += entry.quantity,
+                LedgerEntryType::Sell => {
+                    change.sold += entry.quantity;
+                    change.realized_pnl += realized;
+                }
+                LedgerEntryType::Yield => change.yield_received += entry.amount,
+                _ => {}
+            }
+        }
+    }
+
+    body.closing = book.balances(closing_prices);
+    body.closing_unrealized_pnl = book.unrealized_pnl(closing_prices);
+
+    for (asset, holding) in &book.holdings {
+        let opening_holding = opening_book.holdings.get(asset);
+        if holding.quantity.is_zero() && opening_holding.map_or(true, |h| h.quantity.is_zero()) && !changes.contains_key(asset) {
+            continue;
+        }
+        let mut change = changes.remove(asset).unwrap_or_else(|| blank_change(asset));
+        if let Some(opening_holding) = opening_holding {
+            change.opening_quantity = opening_holding.quantity;
+            change.opening_price = opening_prices.get(asset).copied().unwrap_or(opening_holding.last_price);
+        }
+        change.closing_quantity = holding.quantity;
+        change.closing_price = closing_prices.get(asset).copied().unwrap_or(holding.last_price);
+        change.closing_cost = holding.cost;
+        change.closing_value = holding.value(closing_prices.get(asset));
+        change.unrealized_pnl = change.closing_value - holding.cost;
+        body.positions.push(change);
+    }
+    // Yield on assets never traded through the ledger
+    body.positions.extend(changes.into_values());
+
+    body
+}
+
+fn blank_change(asset: &str) -> PositionChange {
+    PositionChange {
+        asset_id: asset.to_string(),
+        opening_quantity: Decimal::ZERO,
+        bought: Decimal::ZERO,
+        sold: Decimal::ZERO,
+        closing_quantity: Decimal::ZERO,
+        opening_price: Decimal::ZERO,
+        closing_price: Decimal::ZERO,
+        closing_cost: Decimal::ZERO,
+        closing_value: Decimal::ZERO,
+        realized_pnl: Decimal::ZERO,
+        unrealized_pnl: Decimal::ZERO,
+        yield_received: Decimal::ZERO,
+    }
+}
+
+fn content_hash(body: &StatementBody) -> String {
+    let json = serde_json::to_vec(body).expect("statement body serializes");
+    hex::encode(Sha256::digest(&json))
+}
+
+const STATEMENT_COLUMNS: &str =
+    "statement_id, institution_id, period, version, amends, ledger_cutoff, content_hash, generated_at, statement::text";
+
+type StatementRow = (Uuid, String, NaiveDate, i32, Option<Uuid>, DateTime<Utc>, String, DateTime<Utc>, String);
+
+fn from_row(row: StatementRow) -> Result<Statement, StatementError> {
+    let (statement_id, institution_id, period, version, amends, ledger_cutoff, content_hash, generated_at, body) = row;
+    let body = serde_json::from_str(&body).map_err(|e| StatementError::Corrupt(e.to_string()))?;
+    Ok(Statement {
+        statement_id,
+        institution_id,
+        period: StatementPeriod(period).to_string(),
+        version,
+        amends,
+        ledger_cutoff,
+        content_hash,
+        generated_at,
+        body,
+    })
+}
+
+async fn latest_statement(db: &PgPool, institution_id: &str, period: StatementPeriod) -> Result<Option<Statement>, StatementError> {
+    let row: Option<StatementRow> = sqlx::query_as(&format!(
+        "SELECT {} FROM institution_statements
+         WHERE institution_id = $1 AND period = $2
+         ORDER BY version DESC LIMIT 1",
+        STATEMENT_COLUMNS
+    ))
+    .bind(institution_id)
+    .bind(period.first_day())
+    .fetch_optional(db)
+    .await?;
+    row.map(from_row).transpose()
+}
+
+/// Entries effective before the month ended that were recorded after `cutoff`
+async fn has_late_entries(db: &PgPool, institution_id: &str, period: StatementPeriod, cutoff: DateTime<Utc>) -> Result<bool, StatementError> {
+    Ok(sqlx::query_scalar(
+        "SELECT EXISTS(
+            SELECT 1 FROM institution_ledger_entries
+            WHERE institution_id = $1 AND effective_at < $2 AND recorded_at > $3
+         )"
+    )
+    .bind(institution_id)
+    .bind(period.end())
+    .bind(cutoff)
+    .fetch_one(db)
+    .await?)
+}
+
+async fn load_entries(db: &PgPool, institution_id: &str, period: StatementPeriod, cutoff: DateTime<Utc>) -> Result<Vec<LedgerEntry>, StatementError> {
+    let rows: Vec<(String, Option<String>, Decimal, Decimal, Decimal, DateTime<Utc>, DateTime<Utc>)> = sqlx::query_as(
+        "SELECT entry_type, asset_id, quantity, price, amount, effective_at, recorded_at
+         FROM institution_ledger_entries
+         WHERE institution_id = $1 AND effective_at < $2 AND recorded_at <= $3
+         ORDER BY effective_at, recorded_at, id"
+    )
+    .bind(institution_id)
+    .bind(period.end())
+    .bind(cutoff)
+    .fetch_all(db)
+    .await?;
+
+    rows.into_iter()
+        .map(|(entry_type, asset_id, quantity, price, amount, effective_at, recorded_at)| {
+            let entry_type = LedgerEntryType::parse(&entry_type)
+                .ok_or_else(|| StatementError::Corrupt(format!("Unknown ledger entry type {}", entry_type)))?;
+            Ok(LedgerEntry { entry_type, asset_id, quantity, price, amount, effective_at, recorded_at })
+        })
+        .collect()
+}
+
+/// Latest accepted price of each asset on or before `date`; quarantined prices are skipped
+async fn prices_on(db: &PgPool, assets: &[String], date: NaiveDate) -> Result<HashMap<String, Decimal>, StatementError> {
+    let rows: Vec<(String, Decimal)> = sqlx::query_as(
+        "SELECT DISTINCT ON (asset_address) asset_address, price
+         FROM asset_price_history
+         WHERE asset_address = ANY($1) AND price_date <= $2 AND NOT quarantined
+         ORDER BY asset_address, price_date DESC"
+    )
+    .bind(assets)
+    .bind(date)
+    .fetch_all(db)
+    .await?;
+    Ok(rows.into_iter().collect())
+}
+
+/// Statement of `institution_id` for `period`, generating it on first request.
+///
+/// Repeated calls return the stored statement. Once ledger entries effective in or before
+/// the month are recorded after its cutoff, the next call stores an amended version that
+/// references the one it supersedes.
+pub async fn generate_statement(
+    db: &PgPool,
+    institution_id: &str,
+    period: StatementPeriod,
+    margin_calls: &[MarginCallAlert],
+    now: DateTime<Utc>,
+) -> Result<Statement, StatementError> {
+    if period.end() > now {
+        return Err(StatementError::PeriodOpen(period));
+    }
+
+    let previous = latest_statement(db, institution_id, period).await?;
+    if let Some(previous) = &previous {
+        if !has_late_entries(db, institution_id, period, previous.ledger_cutoff).await? {
+            return Ok(previous.clone());
+        }
+    }
+
+    let entries = load_entries(db, institution_id, period, now).await?;
+    let mut assets: Vec<String> = entries.iter().filter_map(|entry| entry.asset_id.clone()).collect();
+    assets.sort();
+    assets.dedup();
+    let opening_prices = prices_on(db, &assets, period.opening_price_date()).await?;
+    let closing_prices = prices_on(db, &assets, period.closing_price_date()).await?;
+
+    let body = build_statement(institution_id, period, &entries, &opening_prices, &closing_prices, margin_calls);
+    let statement = Statement {
+        statement_id: Uuid::new_v4(),
+        institution_id: institution_id.to_string(),
+        period: period.to_string(),
+        version: previous.as_ref().map_or(1, |p| p.version + 1),
+        amends: previous.as_ref().map(|p| p.statement_id),
+        ledger_cutoff: now,
+        content_hash: content_hash(&body),
+        generated_at: now,
+        body,
+    };
+    let pdf = render_pdf(&statement);
+
+    // A concurrent generation of the same version wins; return whatever was stored
+    let inserted = sqlx::query(
+        "INSERT INTO institution_statements
+            (statement_id, institution_id, period, version, amends, ledger_cutoff, content_hash, statement, pdf, generated_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8::jsonb, $9, $10)
+         ON CONFLICT (institution_id, period, version) DO NOTHING"
+    )
+    .bind(statement.statement_id)
+    .bind(institution_id)
+    .bind(period.first_day())
+    .bind(statement.version)
+    .bind(statement.amends)
+    .bind(statement.ledger_cutoff)
+    .bind(&statement.content_hash)
+    .bind(serde_json::to_string(&statement.body).expect("statement body serializes"))
+    .bind(&pdf)
+    .bind(statement.generated_at)
+    .execute(db)
+    .await?
+    .rows_affected();
+
+    if inserted == 0 {
+        return latest_statement(db, institution_id, period).await?.ok_or(StatementError::NotFound);
+    }
+    info!(
+        "Generated statement {} for {} {} (version {})",
+        statement.statement_id, institution_id, period, statement.version
+    );
+    Ok(statement)
+}
+
+/// Every stored statement of an institution, newest month and version first
+pub async fn list_statements(db: &PgPool, institution_id: &str, period: Option<StatementPeriod>) -> Result<Vec<Statement>, StatementError> {
+    let rows: Vec<StatementRow> = sqlx::query_as(&format!(
+        "SELECT {} FROM institution_statements
+         WHERE institution_id = $1 AND ($2::date IS NULL OR period = $2)
+         ORDER BY period DESC, version DESC",
+        STATEMENT_COLUMNS
+    ))
+    .bind(institution_id)
+    .bind(period.map(|p| p.first_day()))
+    .fetch_all(db)
+    .await?;
+    rows.into_iter().map(from_row).collect()
+}
+
+pub async fn statement_pdf(db: &PgPool, institution_id: &str, statement_id: Uuid) -> Result<Vec<u8>, StatementError> {
+    sqlx::query_scalar("SELECT pdf FROM institution_statements WHERE institution_id = $1 AND statement_id = $2")
+        .bind(institution_id)
+        .bind(statement_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or(StatementError::NotFound)
+}
+
+const PDF_LINES_PER_PAGE: usize = 50;
+
+fn statement_lines(statement: &Statement) -> Vec<String> {
+    let body = &statement.body;
+    let mut lines = vec![
+        format!("Quantera statement {} - {}", body.institution_id, body.period),
+        format!("Statement {} (version {})", statement.statement_id, statement.version),
+    ];
+    if let Some(amends) = statement.amends {
+        lines.push(format!("Amends statement {}", amends));
+    }
+    lines.push(format!("Ledger entries recorded up to {}", statement.ledger_cutoff.to_rfc3339()));
+    lines.push(String::new());
+    for (label, balances) in [("Opening", &body.opening), ("Closing", &body.closing)] {
+        lines.push(format!(
+            "{} balance: cash {} + positions {} = {}",
+            label, balances.cash, balances.positions_value, balances.total
+        ));
+    }
+    lines.push(String::new());
+    lines.push(format!("Deposits {}  Withdrawals {}", body.deposits, body.withdrawals));
+    lines.push(format!("Fees {}  Interest {}  Yield received {}", body.fees, body.interest, body.yield_distributions));
+    lines.push(format!(
+        "Realized P&L {}  Unrealized P&L {} -> {}",
+        body.realized_pnl, body.opening_unrealized_pnl, body.closing_unrealized_pnl
+    ));
+    lines.push(String::new());
+    lines.push("Positions".to_string());
+    for position in &body.positions {
+        lines.push(format!(
+            "{}: {} +{} -{} = {} @ {}, value {}, realized {}, unrealized {}, yield {}",
+            position.asset_id, position.opening_quantity, position.bought, position.sold, position.closing_quantity,
+            position.closing_price, position.closing_value, position.realized_pnl, position.unrealized_pnl,
+            position.yield_received
+        ));
+    }
+    lines.push(String::new());
+    lines.push(format!("Margin calls ({})", body.margin_calls.len()));
+    for call in &body.margin_calls {
+        lines.push(format!(
+            "{}: shortfall {} of {} required, {:?}, due {}",
+            call.created_at.to_rfc3339(), call.shortfall, call.required_margin, call.severity, call.deadline.to_rfc3339()
+        ));
+    }
+    lines
+}
+
+fn pdf_escape(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_ascii() && !c.is_ascii_control())
+        .flat_map(|c| match c {
+            '(' | ')' | '\\' => vec!['\\', c],
+            _ => vec![c],
+        })
+        .collect()
+}
+
+/// Renders the statement as a plain text PDF in Helvetica, one line per row
+pub fn render_pdf(statement: &Statement) -> Vec<u8> {
+    let lines = statement_lines(statement);
+    let pages: Vec<&[String]> = lines.chunks(PDF_LINES_PER_PAGE).collect();
+
+    // Objects 1-3 are the catalog, page tree and font; each page adds a page and its content
+    let page_ids: Vec<usize> = (0..pages.len()).map(|i| 4 + 2 * i).collect();
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            page_ids.iter().map(|id| format!("{} 0 R", id)).collect::<Vec<_>>().join(" "),
+            pages.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+    ];
+    for (page, id) in pages.iter().zip(&page_ids) {
+        let mut content = String::from("BT /F1 9 Tf 40 760 Td 14 TL\n");
+        for line in page.iter() {
+            content.push_str(&format!("({}) Tj T*\n", pdf_escape(line)));
+        }
+        content.push_str("ET");
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            id + 1
+        ));
+        objects.push(format!("<< /Length {} >>\nstream\n{}\nendstream", content.len(), content));
+    }
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", i + 1, object).as_bytes());
+    }
+    let xref = pdf.len();
+    pdf.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+    for offset in offsets {
+        pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    pdf.extend_from_slice(
+        format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref).as_bytes(),
+    );
+    pdf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::prime_brokerage_service::RiskLevel;
+    use std::str::FromStr;
+
+    fn d(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    fn at(day: u32, month: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, month, day, 12, 0, 0).unwrap()
+    }
+
+    fn cash(entry_type: LedgerEntryType, amount: &str, effective_at: DateTime<Utc>) -> LedgerEntry {
+        LedgerEntry {
+            entry_type,
+            asset_id: None,
+            quantity: Decimal::ZERO,
+            price: Decimal::ZERO,
+            amount: d(amount),
+            effective_at,
+            recorded_at: effective_at,
+        }
+    }
+
+    fn trade(entry_type: LedgerEntryType, asset: &str, quantity: &str, price: &str, effective_at: DateTime<Utc>) -> LedgerEntry {
+        LedgerEntry {
+            entry_type,
+            asset_id: Some(asset.to_string()),
+            quantity: d(quantity),
+            price: d(price),
+            amount: d(quantity) * d(price),
+            effective_at,
+            recorded_at: effective_at,
+        }
+    }
+
+    fn seeded_ledger() -> Vec<LedgerEntry> {
+        let mut yield_entry = cash(LedgerEntryType::Yield, "120", at(25, 9));
+        yield_entry.asset_id = Some("TBILL".to_string());
+        vec![
+            // August: the opening position
+            cash(LedgerEntryType::Deposit, "100000", at(3, 8)),
+            trade(LedgerEntryType::Buy, "TBILL", "500", "99", at(4, 8)),
+            trade(LedgerEntryType::Buy, "GOLD", "10", "2000", at(20, 8)),
+            // September
+            cash(LedgerEntryType::Deposit, "25000", at(2, 9)),
+            trade(LedgerEntryType::Buy, "TBILL", "500", "101", at(5, 9)),
+            trade(LedgerEntryType::Sell, "TBILL", "400", "102", at(15, 9)),
+            trade(LedgerEntryType::Sell, "GOLD", "10", "2100", at(16, 9)),
+            cash(LedgerEntryType::Withdrawal, "5000", at(18, 9)),
+            cash(LedgerEntryType::Fee, "75.50", at(30, 9)),
+            cash(LedgerEntryType::Interest, "42.25", at(30, 9)),
+            yield_entry,
+            // October, after the statement month
+            cash(LedgerEntryType::Deposit, "1", at(1, 10)),
+        ]
+    }
+
+    #[test]
+    fn test_statement_ties_out_to_ledger() {
+        let period = StatementPeriod::parse("2026-09").unwrap();
+        let opening_prices = HashMap::from([("TBILL".to_string(), d("99.50")), ("GOLD".to_string(), d("2050"))]);
+        let closing_prices = HashMap::from([("TBILL".to_string(), d("100.25"))]);
+        let margin_call = MarginCallAlert {
+            institution: "inst-1".to_string(),
+            required_margin: 10_000,
+            available_margin: 8_000,
+            shortfall: 2_000,
+            severity: RiskLevel::High,
+            deadline: at(11, 9),
+            created_at: at(10, 9),
+        };
+        let earlier_call = MarginCallAlert { created_at: at(10, 8), ..margin_call.clone() };
+
+        let body = build_statement("inst-1", period, &seeded_ledger(), &opening_prices, &closing_prices, &[earlier_call, margin_call]);
+
+        // Opening: 100000 - 49500 - 20000 cash, 500 TBILL @ 99.50 and 10 GOLD @ 2050
+        assert_eq!(body.opening, Balances { cash: d("30500"), positions_value: d("70250"), total: d("100750") });
+        assert_eq!(body.opening_unrealized_pnl, d("750"));
+        assert_eq!(body.entry_count, 8);
+        assert_eq!(body.margin_calls.len(), 1);
+
+        // TBILL average cost 100 after the September buy; 400 sold at 102 realizes 800.
+        // GOLD sold out at 2100 against 2000 realizes 1000.
+        assert_eq!(body.realized_pnl, d("1800"));
+        let tbill = body.positions.iter().find(|p| p.asset_id == "TBILL").unwrap();
+        assert_eq!((tbill.opening_quantity, tbill.bought, tbill.sold, tbill.closing_quantity), (d("500"), d("500"), d("400"), d("600")));
+        assert_eq!((tbill.closing_cost, tbill.closing_value, tbill.unrealized_pnl), (d("60000"), d("60150"), d("150")));
+        assert_eq!(tbill.yield_received, d("120"));
+        let gold = body.positions.iter().find(|p| p.asset_id == "GOLD").unwrap();
+        assert_eq!((gold.closing_quantity, gold.realized_pnl), (Decimal::ZERO, d("1000")));
+
+        // Closing cash is the opening cash plus every cash flow of the month
+        let expected_cash = body.opening.cash + body.deposits - body.withdrawals - d("50500") + d("40800") + d("21000")
+            - body.fees - body.interest + body.yield_distributions;
+        assert_eq!(body.closing.cash, expected_cash);
+        assert_eq!(body.closing.cash, d("61802.25"));
+        assert_eq!(body.closing.total, body.closing.cash + body.closing.positions_value);
+
+        // The change in total equity is explained by flows, realized and unrealized P&L
+        let explained = body.deposits - body.withdrawals - body.fees - body.interest + body.yield_distributions
+            + body.realized_pnl + body.closing_unrealized_pnl - body.opening_unrealized_pnl;
+        assert_eq!(body.closing.total - body.opening.total, explained);
+    }
+
+    #[test]
+    fn test_late_correction_changes_statement() {
+        let period = StatementPeriod::new(2026, 9).unwrap();
+        let prices = HashMap::new();
+        let original = build_statement("inst-1", period, &seeded_ledger(), &prices, &prices, &[]);
+
+        // A fee for September booked in October
+        let mut corrected = seeded_ledger();
+        let mut late_fee = cash(LedgerEntryType::Fee, "10", at(29, 9));
+        late_fee.recorded_at = at(5, 10);
+        corrected.push(late_fee);
+        let amended = build_statement("inst-1", period, &corrected, &prices, &prices, &[]);
+
+        assert_eq!(amended.fees - original.fees, d("10"));
+        assert_eq!(original.closing.cash - amended.closing.cash, d("10"));
+        assert_ne!(content_hash(&original), content_hash(&amended));
+        assert_eq!(period.end(), Utc.with_ymd_and_hms(2026, 10, 1, 0, 0, 0).unwrap());
+        assert_eq!(StatementPeriod::parse("2026-12").unwrap().end(), Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap());
+        assert!(StatementPeriod::parse("2026-13").is_none());
+    }
+
+    #[test]
+    fn test_pdf_is_well_formed() {
+        let period = StatementPeriod::new(2026, 9).unwrap();
+        let body = build_statement("inst-(1)", period, &seeded_ledger(), &HashMap::new(), &HashMap::new(), &[]);
+        let statement = Statement {
+            statement_id: Uuid::new_v4(),
+            institution_id: "inst-(1)".to_string(),
+            period: period.to_string(),
+            version: 2,
+            amends: Some(Uuid::new_v4()),
+            ledger_cutoff: at(2, 10),
+            content_hash: content_hash(&body),
+            generated_at: at(2, 10),
+            body,
+        };
+
+        let pdf = String::from_utf8(render_pdf(&statement)).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4") && pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains("inst-\\(1\\)"));
+        assert!(pdf.contains("Amends statement"));
+        let startxref: usize = pdf.lines().rev().nth(1).unwrap().parse().unwrap();
+        assert!(pdf[startxref..].starts_with("xref"));
+    }
+}
@@ -0,0 +1,291 @@
+// Asset symbol uniqueness, onboarding reservations and admin-approved renames
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::tenant::TenantId;
+
+/// Default hold on a reserved symbol
+pub const DEFAULT_RESERVATION_HOURS: i64 = 7 * 24;
+
+/// Longest a symbol may be reserved without creating the asset
+pub const MAX_RESERVATION_HOURS: i64 = 30 * 24;
+
+/// Whether a symbol must be unique within its tenant or across the platform
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolScope {
+    Tenant,
+    Global,
+}
+
+impl SymbolScope {
+    /// Read `ASSET_SYMBOL_SCOPE` (`tenant` or `global`), defaulting to per-tenant
+    pub fn from_env() -> Self {
+        match std::env::var("ASSET_SYMBOL_SCOPE").as_deref() {
+            Ok("global") => SymbolScope::Global,
+            _ => SymbolScope::Tenant,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SymbolReservation {
+    pub symbol: String,
+    pub tenant_id: TenantId,
+    pub reserved_by: String,
+    pub reserved_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A requested symbol change, applied only once an admin approves it
+#[derive(Debug, Clone, Serialize)]
+pub struct RenameRequest {
+    pub request_id: Uuid,
+    pub asset_id: String,
+    pub tenant_id: TenantId,
+    pub old_symbol: String,
+    pub new_symbol: String,
+    pub requested_by: String,
+    pub requested_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SymbolError {
+    /// Held by an asset, as its symbol or a former symbol
+    Taken { symbol: String, asset_id: String },
+    Reserved { symbol: String, expires_at: DateTime<Utc> },
+    Invalid(String),
+    ReservationNotFound,
+    RenameNotFound,
+    /// Renames need a second person to approve them
+    SelfApproval,
+}
+
+impl std::fmt::Display for SymbolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SymbolError::Taken { symbol, asset_id } => write!(f, "Symbol {} is already used by asset {}", symbol, asset_id),
+            SymbolError::Reserved { symbol, expires_at } => write!(f, "Symbol {} is reserved until {}", symbol, expires_at),
+            SymbolError::Invalid(msg) => write!(f, "Invalid symbol: {}", msg),
+            SymbolError::ReservationNotFound => write!(f, "Reservation not found"),
+            SymbolError::RenameNotFound => write!(f, "Rename request not found"),
+            SymbolError::SelfApproval => write!(f, "A rename cannot be approved by its requester"),
+        }
+    }
+}
+
+impl std::error::Error for SymbolError {}
+
+/// Symbol ownership, keyed by upper-cased symbol and, in tenant scope, the tenant
+pub struct SymbolRegistry {
+    scope: SymbolScope,
+    assigned: HashMap<(Option<TenantId>, String), String>, // -> asset id
+    reservations: HashMap<(Option<TenantId>, String), SymbolReservation>,
+    aliases: HashMap<String, Vec<String>>, // Asset id -> former symbols, oldest first
+    renames: HashMap<Uuid, RenameRequest>,
+}
+
+impl SymbolRegistry {
+    pub fn new(scope: SymbolScope) -> Self {
+        Self {
+            scope,
+            assigned: HashMap::new(),
+            reservations: HashMap::new(),
+            aliases: HashMap::new(),
+            renames: HashMap::new(),
+        }
+    }
+
+    pub fn scope(&self) -> SymbolScope {
+        self.scope
+    }
+
+    /// Hold a symbol for a tenant while it onboards; expired reservations are reclaimable
+    pub fn reserve(
+        &mut self,
+        tenant_id: &TenantId,
+        symbol: &str,
+        hold: Duration,
+        reserved_by: &str,
+        now: DateTime<Utc>,
+    ) -> Result<SymbolReservation, SymbolError> {
+        if hold <= Duration::zero() || hold > Duration::hours(MAX_RESERVATION_HOURS) {
+            return Err(SymbolError::Invalid(format!("reservations last at most {} hours", MAX_RESERVATION_HOURS)));
+        }
+        let key = self.key(tenant_id, symbol)?;
+        self.check_available(&key, tenant_id, now)?;
+
+        let reservation = SymbolReservation {
+            symbol: key.1.clone(),
+            tenant_id: tenant_id.clone(),
+            reserved_by: reserved_by.to_string(),
+            reserved_at: now,
+            expires_at: now + hold,
+        };
+        self.reservations.insert(key, reservation.clone());
+        Ok(reservation)
+    }
+
+    pub fn release(&mut self, tenant_id: &TenantId, symbol: &str) -> Result<(), SymbolError> {
+        let key = self.key(tenant_id, symbol)?;
+        match self.reservations.get(&key) {
+            Some(reservation) if &reservation.tenant_id == tenant_id => {
+                self.reservations.remove(&key);
+                Ok(())
+            }
+            _ => Err(SymbolError::ReservationNotFound),
+        }
+    }
+
+    /// Assign a symbol to a new asset, consuming the tenant's own reservation
+    pub fn assign(&mut self, tenant_id: &TenantId, symbol: &str, asset_id: &str, now: DateTime<Utc>) -> Result<(), SymbolError> {
+        let key = self.key(tenant_id, symbol)?;
+        self.check_available(&key, tenant_id, now)?;
+        self.reservations.remove(&key);
+        self.assigned.insert(key, asset_id.to_string());
+        Ok(())
+    }
+
+    /// Propose a new symbol for an asset. Availability is checked now and again on approval.
+    pub fn request_rename(
+        &mut self,
+        tenant_id: &TenantId,
+        asset_id: &str,
+        old_symbol: &str,
+        new_symbol: &str,
+        requested_by: &str,
+        now: DateTime<Utc>,
+    ) -> Result<RenameRequest, SymbolError> {
+        let key = self.key(tenant_id, new_symbol)?;
+        self.check_available(&key, tenant_id, now)?;
+
+        let request = RenameRequest {
+            request_id: Uuid::new_v4(),
+            asset_id: asset_id.to_string(),
+            tenant_id: tenant_id.clone(),
+            old_symbol: old_symbol.to_string(),
+            new_symbol: key.1,
+            requested_by: requested_by.to_string(),
+            requested_at: now,
+        };
+        self.renames.insert(request.request_id, request.clone());
+        Ok(request)
+    }
+
+    pub fn pending_renames(&self) -> Vec<&RenameRequest> {
+        let mut pending: Vec<_> = self.renames.values().collect();
+        pending.sort_by_key(|request| request.requested_at);
+        pending
+    }
+
+    /// Apply an approved rename. The old symbol stays held by the asset as an alias.
+    pub fn approve_rename(&mut self, request_id: Uuid, approved_by: &str, now: DateTime<Utc>) -> Result<RenameRequest, SymbolError> {
+        let request = self.renames.get(&request_id).cloned().ok_or(SymbolError::RenameNotFound)?;
+        if request.requested_by == approved_by {
+            return Err(SymbolError::SelfApproval);
+        }
+        let key = self.key(&request.tenant_id, &request.new_symbol)?;
+        self.check_available(&key, &request.tenant_id, now)?;
+
+        self.renames.remove(&request_id);
+        self.reservations.remove(&key);
+        self.assigned.insert(key, request.asset_id.clone());
+        self.aliases.entry(request.asset_id.clone()).or_default().push(request.old_symbol.to_uppercase());
+        Ok(request)
+    }
+
+    pub fn reject_rename(&mut self, request_id: Uuid) -> Result<RenameRequest, SymbolError> {
+        self.renames.remove(&request_id).ok_or(SymbolError::RenameNotFound)
+    }
+
+    /// Former symbols of an asset
+    pub fn aliases(&self, asset_id: &str) -> &[String] {
+        self.aliases.get(asset_id).map_or(&[], Vec::as_slice)
+    }
+
+    fn key(&self, tenant_id: &TenantId, symbol: &str) -> Result<(Option<TenantId>, String), SymbolError> {
+        let symbol = symbol.trim();
+        if symbol.is_empty() || symbol.len() > 10 {
+            return Err(SymbolError::Invalid(format!("'{}' must be 1 to 10 characters", symbol)));
+        }
+        let tenant = match self.scope {
+            SymbolScope::Tenant => Some(tenant_id.clone()),
+            SymbolScope::Global => None,
+        };
+        Ok((tenant, symbol.to_uppercase()))
+    }
+
+    fn check_available(&self, key: &(Option<TenantId>, String), tenant_id: &TenantId, now: DateTime<Utc>) -> Result<(), SymbolError> {
+        if let Some(asset_id) = self.assigned.get(key) {
+            return Err(SymbolError::Taken { symbol: key.1.clone(), asset_id: asset_id.clone() });
+        }
+        match self.reservations.get(key) {
+            Some(reservation) if &reservation.tenant_id != tenant_id && reservation.expires_at > now => {
+                Err(SymbolError::Reserved { symbol: key.1.clone(), expires_at: reservation.expires_at })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tenant(id: &str) -> TenantId {
+        TenantId::new(id)
+    }
+
+    #[test]
+    fn test_collision_is_case_insensitive_and_follows_scope() {
+        let now = Utc::now();
+        let mut per_tenant = SymbolRegistry::new(SymbolScope::Tenant);
+        per_tenant.assign(&tenant("a"), "gold", "asset-1", now).unwrap();
+
+        assert_eq!(
+            per_tenant.assign(&tenant("a"), "GOLD", "asset-2", now),
+            Err(SymbolError::Taken { symbol: "GOLD".to_string(), asset_id: "asset-1".to_string() }),
+        );
+        assert!(per_tenant.assign(&tenant("b"), "Gold", "asset-3", now).is_ok());
+
+        let mut global = SymbolRegistry::new(SymbolScope::Global);
+        global.assign(&tenant("a"), "gold", "asset-1", now).unwrap();
+        assert!(matches!(global.assign(&tenant("b"), "GOLD", "asset-3", now), Err(SymbolError::Taken { .. })));
+    }
+
+    #[test]
+    fn test_expired_reservation_is_reclaimable() {
+        let now = Utc::now();
+        let mut registry = SymbolRegistry::new(SymbolScope::Global);
+        registry.reserve(&tenant("a"), "RWA1", Duration::hours(1), "issuer-a", now).unwrap();
+
+        assert!(matches!(registry.assign(&tenant("b"), "rwa1", "asset-b", now), Err(SymbolError::Reserved { .. })));
+        assert!(matches!(
+            registry.reserve(&tenant("b"), "RWA1", Duration::hours(1), "issuer-b", now + Duration::minutes(59)),
+            Err(SymbolError::Reserved { .. })
+        ));
+
+        let later = now + Duration::hours(1);
+        let reclaimed = registry.reserve(&tenant("b"), "RWA1", Duration::hours(1), "issuer-b", later).unwrap();
+        assert_eq!(reclaimed.tenant_id, tenant("b"));
+        assert!(registry.assign(&tenant("b"), "RWA1", "asset-b", later).is_ok());
+    }
+
+    #[test]
+    fn test_approved_rename_keeps_old_symbol_as_alias() {
+        let now = Utc::now();
+        let mut registry = SymbolRegistry::new(SymbolScope::Tenant);
+        registry.assign(&tenant("a"), "OLD", "asset-1", now).unwrap();
+
+        let request = registry.request_rename(&tenant("a"), "asset-1", "OLD", "new", "issuer", now).unwrap();
+        assert_eq!(registry.approve_rename(request.request_id, "issuer", now).unwrap_err(), SymbolError::SelfApproval);
+        registry.approve_rename(request.request_id, "admin", now).unwrap();
+
+        assert_eq!(registry.aliases("asset-1"), ["OLD".to_string()]);
+        // The alias stays with the asset rather than being released
+        assert!(matches!(registry.assign(&tenant("a"), "old", "asset-2", now), Err(SymbolError::Taken { .. })));
+        assert!(matches!(registry.assign(&tenant("a"), "NEW", "asset-2", now), Err(SymbolError::Taken { .. })));
+    }
+}
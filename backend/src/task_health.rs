@@ -0,0 +1,56 @@
+// Health of long-running background tasks, reported by the tasks themselves
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::Serialize;
+
+/// Last known state of one background task
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskStatus {
+    pub name: String,
+    pub healthy: bool,
+    pub last_run: DateTime<Utc>,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+/// Registry background tasks report each run into
+#[derive(Default)]
+pub struct TaskHealth {
+    tasks: DashMap<String, TaskStatus>,
+}
+
+impl TaskHealth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_success(&self, name: &str) {
+        let now = Utc::now();
+        self.tasks.insert(name.to_string(), TaskStatus {
+            name: name.to_string(),
+            healthy: true,
+            last_run: now,
+            last_success: Some(now),
+            last_error: None,
+        });
+    }
+
+    /// A failed run keeps the time of the last successful one
+    pub fn record_failure(&self, name: &str, error: impl ToString) {
+        let last_success = self.tasks.get(name).and_then(|status| status.last_success);
+        self.tasks.insert(name.to_string(), TaskStatus {
+            name: name.to_string(),
+            healthy: false,
+            last_run: Utc::now(),
+            last_success,
+            last_error: Some(error.to_string()),
+        });
+    }
+
+    /// Tasks that have run at least once, by name
+    pub fn snapshot(&self) -> Vec<TaskStatus> {
+        let mut tasks: Vec<TaskStatus> = self.tasks.iter().map(|entry| entry.value().clone()).collect();
+        tasks.sort_by(|a, b| a.name.cmp(&b.name));
+        tasks
+    }
+}
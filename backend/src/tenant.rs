@@ -0,0 +1,87 @@
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{request::Parts, StatusCode},
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Sha256, Digest};
+
+/// Tenant that owns all data created before tenancy was introduced
+pub const DEFAULT_TENANT: &str = "default";
+
+/// Issuer tenant of a white-label deployment
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TenantId(String);
+
+impl TenantId {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for TenantId {
+    fn default() -> Self {
+        Self(DEFAULT_TENANT.to_string())
+    }
+}
+
+impl std::fmt::Display for TenantId {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Data a request may see: one tenant, or every tenant for platform admins
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TenantScope {
+    Tenant(TenantId),
+    AllTenants,
+}
+
+impl TenantScope {
+    /// Whether data owned by `tenant` is visible in this scope
+    pub fn allows(&self, tenant: &TenantId) -> bool {
+        match self {
+            TenantScope::Tenant(own) => own == tenant,
+            TenantScope::AllTenants => true,
+        }
+    }
+
+    /// Tenant new records are written to, if the scope names one
+    pub fn tenant(&self) -> Option<&TenantId> {
+        match self {
+            TenantScope::Tenant(tenant) => Some(tenant),
+            TenantScope::AllTenants => None,
+        }
+    }
+}
+
+/// Scope is inserted into request extensions by the auth middleware
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for TenantScope {
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get::<TenantScope>()
+            .cloned()
+            .ok_or((StatusCode::UNAUTHORIZED, "Tenant could not be determined"))
+    }
+}
+
+/// Resolve the tenant for an API key.
+///
+/// Keys are configured as `TENANT_API_KEYS=<tenant>:<sha256 of key>,...` so raw keys
+/// never sit in the environment.
+pub fn tenant_for_api_key(api_key: &str) -> Option<TenantId> {
+    let key_hash = format!("{:x}", Sha256::digest(api_key.as_bytes()));
+
+    std::env::var("TENANT_API_KEYS").ok()?
+        .split(',')
+        .filter_map(|entry| entry.trim().split_once(':'))
+        .find(|(_, hash)| hash.eq_ignore_ascii_case(&key_hash))
+        .map(|(tenant, _)| TenantId::new(tenant))
+}
@@ -0,0 +1,125 @@
+// Minimal ABI encoding for programming mock contract responses
+use alloy_primitives::{keccak256, Address, B256, U256};
+
+/// A value a mock contract returns or a test encodes as call arguments.
+///
+/// Only what the services' contracts use: 32-byte static words, strings, bytes and arrays
+/// of static words.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AbiValue {
+    Address(Address),
+    Uint(U256),
+    Bool(bool),
+    FixedBytes([u8; 32]),
+    String(String),
+    Bytes(Vec<u8>),
+    Array(Vec<AbiValue>),
+}
+
+impl AbiValue {
+    fn is_dynamic(&self) -> bool {
+        matches!(self, AbiValue::String(_) | AbiValue::Bytes(_) | AbiValue::Array(_))
+    }
+}
+
+/// First four bytes of the keccak hash of a signature like `transfer(address,uint256)`
+pub fn selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Topic 0 of an event such as `Transfer(address,address,uint256)`
+pub fn event_topic(signature: &str) -> B256 {
+    keccak256(signature.as_bytes())
+}
+
+/// An indexed address argument as a log topic
+pub fn address_topic(address: Address) -> B256 {
+    B256::left_padding_from(address.as_slice())
+}
+
+pub fn word(value: U256) -> [u8; 32] {
+    value.to_be_bytes::<32>()
+}
+
+/// ABI encoding of `values` as a tuple, the layout of return data and call arguments
+pub fn encode(values: &[AbiValue]) -> Vec<u8> {
+    let mut head = Vec::with_capacity(values.len() * 32);
+    let mut tail = Vec::new();
+    for value in values {
+        if value.is_dynamic() {
+            head.extend_from_slice(&word(U256::from(values.len() * 32 + tail.len())));
+            tail.extend(encode_dynamic(value));
+        } else {
+            head.extend_from_slice(&encode_static(value));
+        }
+    }
+    head.extend(tail);
+    head
+}
+
+/// Calldata for `signature` with `args`
+pub fn encode_call(signature: &str, args: &[AbiValue]) -> Vec<u8> {
+    let mut calldata = selector(signature).to_vec();
+    calldata.extend(encode(args));
+    calldata
+}
+
+/// Revert data for `Error(string)`
+pub fn revert_data(reason: &str) -> Vec<u8> {
+    encode_call("Error(string)", &[AbiValue::String(reason.to_string())])
+}
+
+fn encode_static(value: &AbiValue) -> [u8; 32] {
+    match value {
+        AbiValue::Address(address) => address_topic(*address).0,
+        AbiValue::Uint(value) => word(*value),
+        AbiValue::Bool(value) => word(U256::from(*value as u8)),
+        AbiValue::FixedBytes(bytes) => *bytes,
+        dynamic => unreachable!("{:?} is not a static value", dynamic),
+    }
+}
+
+fn encode_dynamic(value: &AbiValue) -> Vec<u8> {
+    match value {
+        AbiValue::String(text) => encode_bytes(text.as_bytes()),
+        AbiValue::Bytes(bytes) => encode_bytes(bytes),
+        AbiValue::Array(items) => {
+            let mut encoded = word(U256::from(items.len())).to_vec();
+            encoded.extend(encode(items));
+            encoded
+        }
+        value => encode_static(value).to_vec(),
+    }
+}
+
+fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+    let mut encoded = word(U256::from(bytes.len())).to_vec();
+    encoded.extend_from_slice(bytes);
+    encoded.resize(32 + bytes.len().div_ceil(32) * 32, 0);
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dynamic_values_are_placed_after_the_head() {
+        let encoded = encode(&[
+            AbiValue::Uint(U256::from(7)),
+            AbiValue::String("ipfs://Qm".to_string()),
+            AbiValue::Array(vec![AbiValue::FixedBytes([0xab; 32])]),
+        ]);
+        // Three head words, then the string (length + one padded word), then the array
+        assert_eq!(encoded.len(), 3 * 32 + 2 * 32 + 2 * 32);
+        assert_eq!(U256::from_be_slice(&encoded[32..64]), U256::from(96));
+        assert_eq!(U256::from_be_slice(&encoded[64..96]), U256::from(160));
+        assert_eq!(U256::from_be_slice(&encoded[96..128]), U256::from(9));
+        assert_eq!(&encoded[128..137], b"ipfs://Qm");
+        assert_eq!(encoded[192..224], [0xab; 32]);
+
+        assert_eq!(hex::encode(selector("transfer(address,uint256)")), "a9059cbb");
+        assert_eq!(&revert_data("nope")[..4], &[0x08, 0xc3, 0x79, 0xa0]);
+    }
+}
@@ -0,0 +1,163 @@
+// Throwaway Postgres databases and Redis instances for scenario tests
+use crate::HarnessError;
+use sqlx::postgres::{PgPool, PgPoolOptions};
+use sqlx::{Connection, Executor, PgConnection};
+use std::path::{Path, PathBuf};
+
+/// Postgres server the scenarios create their databases on; needs TimescaleDB
+pub const DATABASE_URL_VAR: &str = "TEST_DATABASE_URL";
+
+/// Redis instance the scenarios may flush at will
+pub const REDIS_URL_VAR: &str = "TEST_REDIS_URL";
+
+/// Data for local development, not part of the schema
+const SKIPPED_MIGRATIONS: &[&str] = &["seed_test_data.sql"];
+
+/// A fresh database with every migration applied, dropped again by `drop`.
+///
+/// Each test gets its own database rather than a schema because the risk tables install
+/// the TimescaleDB extension, which cannot live in more than one schema of a database.
+pub struct TestDatabase {
+    server_url: String,
+    name: String,
+    url: String,
+    pool: PgPool,
+}
+
+impl TestDatabase {
+    /// Create a database on the server at `TEST_DATABASE_URL`, or `None` when it is unset so
+    /// the calling test can skip
+    pub async fn from_env() -> Result<Option<Self>, HarnessError> {
+        match std::env::var(DATABASE_URL_VAR) {
+            Ok(url) if !url.is_empty() => Self::create(&url).await.map(Some),
+            _ => {
+                eprintln!("{} is not set; skipping scenario that needs Postgres", DATABASE_URL_VAR);
+                Ok(None)
+            }
+        }
+    }
+
+    pub async fn create(server_url: &str) -> Result<Self, HarnessError> {
+        let name = format!("e2e_{}", uuid::Uuid::new_v4().simple());
+        let mut admin = PgConnection::connect(server_url).await?;
+        admin.execute(format!("CREATE DATABASE {}", name).as_str()).await?;
+        admin.close().await?;
+
+        let url = with_database(server_url, &name)?;
+        let pool = PgPoolOptions::new().max_connections(5).connect(&url).await?;
+        let database = Self { server_url: server_url.to_string(), name, url, pool };
+        database.migrate().await?;
+        Ok(database)
+    }
+
+    /// Apply the service migrations in file name order, as `psql` would
+    async fn migrate(&self) -> Result<(), HarnessError> {
+        for file in migration_files(&migrations_dir())? {
+            let sql = std::fs::read_to_string(&file)?;
+            let name = file.file_name().unwrap_or_default().to_string_lossy().into_owned();
+            self.pool.execute(sql.as_str()).await
+                .map_err(|e| HarnessError::Migration { file: name, message: e.to_string() })?;
+        }
+        Ok(())
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    /// Connection URL for services under test
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Close connections and drop the database
+    pub async fn drop(self) -> Result<(), HarnessError> {
+        self.pool.close().await;
+        let mut admin = PgConnection::connect(&self.server_url).await?;
+        admin.execute(format!("DROP DATABASE IF EXISTS {} WITH (FORCE)", self.name).as_str()).await?;
+        admin.close().await?;
+        Ok(())
+    }
+}
+
+fn migrations_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../migrations")
+}
+
+fn migration_files(dir: &Path) -> Result<Vec<PathBuf>, HarnessError> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "sql"))
+        .filter(|path| !path.file_name().is_some_and(|name| SKIPPED_MIGRATIONS.iter().any(|skipped| name == *skipped)))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// `url` with its database name replaced by `database`, keeping any query string
+fn with_database(url: &str, database: &str) -> Result<String, HarnessError> {
+    let (base, query) = match url.split_once('?') {
+        Some((base, query)) => (base, Some(query)),
+        None => (url, None),
+    };
+    let authority_start = base.find("://").map(|index| index + 3)
+        .ok_or_else(|| HarnessError::Config(format!("{} is not a postgres URL", DATABASE_URL_VAR)))?;
+    let server = match base[authority_start..].find('/') {
+        Some(index) => &base[..authority_start + index],
+        None => base,
+    };
+    Ok(match query {
+        Some(query) => format!("{}/{}?{}", server, database, query),
+        None => format!("{}/{}", server, database),
+    })
+}
+
+/// The Redis instance at `TEST_REDIS_URL`, emptied before the test starts.
+///
+/// Only point this at an instance kept for tests: connecting flushes its current database.
+pub struct TestRedis {
+    url: String,
+}
+
+impl TestRedis {
+    pub async fn from_env() -> Result<Option<Self>, HarnessError> {
+        let url = match std::env::var(REDIS_URL_VAR) {
+            Ok(url) if !url.is_empty() => url,
+            _ => {
+                eprintln!("{} is not set; skipping scenario that needs Redis", REDIS_URL_VAR);
+                return Ok(None);
+            }
+        };
+        let client = redis::Client::open(url.as_str())?;
+        let mut connection = client.get_multiplexed_async_connection().await?;
+        redis::cmd("FLUSHDB").query_async::<_, ()>(&mut connection).await?;
+        Ok(Some(Self { url }))
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_database_name_swapped_into_url() {
+        assert_eq!(
+            with_database("postgres://postgres:pw@localhost:5433/postgres?sslmode=disable", "e2e_1").unwrap(),
+            "postgres://postgres:pw@localhost:5433/e2e_1?sslmode=disable"
+        );
+        assert_eq!(with_database("postgres://localhost:5433", "e2e_1").unwrap(), "postgres://localhost:5433/e2e_1");
+        assert!(with_database("localhost:5433", "e2e_1").is_err());
+
+        let files = migration_files(&migrations_dir()).unwrap();
+        let names: Vec<_> = files.iter().map(|file| file.file_name().unwrap().to_string_lossy().into_owned()).collect();
+        assert_eq!(names.first().map(String::as_str), Some("001_auth_schema.sql"));
+        assert!(!names.iter().any(|name| name == "seed_test_data.sql"));
+        // Timescale is installed before the v2.1 migrations that build on the risk tables
+        let risk = names.iter().position(|name| name == "v2_0_0_risk_tables.sql").unwrap();
+        assert!(names[risk + 1..].iter().all(|name| name.starts_with("v2_1_0_")));
+    }
+}
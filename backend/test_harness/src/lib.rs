@@ -0,0 +1,76 @@
+// End-to-end test harness
+//
+// Everything a scenario test needs to run the services without external networks:
+// an in-process Ethereum JSON-RPC node, HTTP fixtures standing in for the KYC, sanctions,
+// IPFS and price providers, throwaway Postgres databases and Redis instances, and seeders
+// that produce the same treasuries, assets, investors and portfolios on every run.
+//
+// Scenarios that need Postgres or Redis read TEST_DATABASE_URL and TEST_REDIS_URL and skip
+// when they are unset; `docker compose -f test_harness/docker-compose.yml up -d` starts both.
+use thiserror::Error;
+
+pub mod abi;
+
+mod mock_chain;
+pub use mock_chain::{
+    CallKind,
+    CallOutcome,
+    ContractCall,
+    MockChain,
+    MockChainConfig,
+    MockLog,
+    SentTransaction,
+    BLOCK_TIME_SECS,
+    DEFAULT_CHAIN_ID,
+    GENESIS_TIMESTAMP,
+};
+
+mod rlp;
+
+mod providers;
+pub use providers::{
+    IpfsFixture,
+    KycFixture,
+    PriceFeedFixture,
+    SanctionsFixture,
+    WebhookDelivery,
+    WebhookFixture,
+    raw_cid,
+    KYC_APPLICANT_ID,
+    KYC_CHECK_ID,
+};
+
+mod db;
+pub use db::{
+    TestDatabase,
+    TestRedis,
+    DATABASE_URL_VAR,
+    REDIS_URL_VAR,
+};
+
+mod seed;
+pub use seed::{
+    SeededAsset,
+    SeededInvestor,
+    SeededPortfolio,
+    SeededTreasury,
+    Seeder,
+};
+
+#[derive(Debug, Error)]
+pub enum HarnessError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Migration {file} failed: {message}")]
+    Migration { file: String, message: String },
+
+    #[error("Redis error: {0}")]
+    Redis(#[from] redis::RedisError),
+
+    #[error("Invalid test configuration: {0}")]
+    Config(String),
+}
@@ -0,0 +1,702 @@
+// In-process Ethereum JSON-RPC node with programmable contracts and reorgs
+use crate::{abi, rlp, HarnessError};
+use alloy_primitives::{keccak256, Address, B256, U256};
+use axum::{extract::State, routing::post, Json, Router};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
+
+/// Chain id local development nodes use
+pub const DEFAULT_CHAIN_ID: u64 = 31337;
+
+/// Timestamp of the genesis block, 2026-01-01T00:00:00Z
+pub const GENESIS_TIMESTAMP: u64 = 1_767_225_600;
+
+pub const BLOCK_TIME_SECS: u64 = 12;
+
+/// Gas price and priority fee quoted to clients, 1 gwei
+const GAS_PRICE: u64 = 1_000_000_000;
+
+const GAS_ESTIMATE: u64 = 100_000;
+
+#[derive(Debug, Clone)]
+pub struct MockChainConfig {
+    pub chain_id: u64,
+    /// Blocks behind the head reported for the `safe` tag
+    pub safe_depth: u64,
+    /// Blocks behind the head reported for the `finalized` tag
+    pub finalized_depth: u64,
+    /// Answer to `eth_supportedEIPs`; empty makes clients fall back to pre-Pectra calls
+    pub supported_eips: Vec<String>,
+    /// Mine every accepted transaction into its own block straight away
+    pub auto_mine: bool,
+}
+
+impl Default for MockChainConfig {
+    fn default() -> Self {
+        Self { chain_id: DEFAULT_CHAIN_ID, safe_depth: 2, finalized_depth: 4, supported_eips: Vec::new(), auto_mine: true }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallKind {
+    /// `eth_call` and `eth_estimateGas`; must not change programmed state
+    Call,
+    /// A transaction being mined
+    Transaction,
+}
+
+/// A contract call as the node received it
+#[derive(Debug, Clone)]
+pub struct ContractCall {
+    pub kind: CallKind,
+    /// Absent for signed transactions, whose sender is not recovered
+    pub from: Option<Address>,
+    pub to: Address,
+    pub input: Vec<u8>,
+}
+
+impl ContractCall {
+    pub fn selector(&self) -> Option<[u8; 4]> {
+        self.input.get(..4).map(|selector| [selector[0], selector[1], selector[2], selector[3]])
+    }
+
+    /// 32-byte argument word `index`, counted after the selector
+    pub fn word(&self, index: usize) -> Option<[u8; 32]> {
+        let start = 4 + index * 32;
+        self.input.get(start..start + 32).map(|word| word.try_into().unwrap_or([0; 32]))
+    }
+
+    pub fn uint(&self, index: usize) -> Option<U256> {
+        self.word(index).map(|word| U256::from_be_bytes(word))
+    }
+}
+
+/// What a programmed contract does with a call
+#[derive(Debug, Clone, PartialEq)]
+pub enum CallOutcome {
+    Return(Vec<u8>),
+    /// Revert with `Error(string)`
+    Revert(String),
+}
+
+type CallHandler = Arc<dyn Fn(&ContractCall) -> CallOutcome + Send + Sync>;
+
+/// A log to include in a mined block
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockLog {
+    pub address: Address,
+    pub topics: Vec<B256>,
+    pub data: Vec<u8>,
+}
+
+/// A transaction the node accepted
+#[derive(Debug, Clone, PartialEq)]
+pub struct SentTransaction {
+    pub hash: B256,
+    pub nonce: u64,
+    pub to: Option<Address>,
+    pub input: Vec<u8>,
+    /// None while pending, including after a reorg dropped its block
+    pub block_number: Option<u64>,
+    pub succeeded: bool,
+}
+
+struct MinedLog {
+    log: MockLog,
+    transaction_hash: B256,
+    transaction_index: u64,
+}
+
+struct Block {
+    number: u64,
+    hash: B256,
+    parent_hash: B256,
+    timestamp: u64,
+    transactions: Vec<B256>,
+    logs: Vec<MinedLog>,
+}
+
+struct ChainState {
+    config: MockChainConfig,
+    blocks: Vec<Block>,
+    /// Bumped on every reorg so replacement blocks get new hashes
+    fork: u64,
+    handlers: HashMap<(Address, [u8; 4]), CallHandler>,
+    balances: HashMap<Address, U256>,
+    code: HashMap<Address, Vec<u8>>,
+    transactions: Vec<SentTransaction>,
+    methods: Vec<String>,
+}
+
+impl ChainState {
+    fn new(config: MockChainConfig) -> Self {
+        let genesis = Block {
+            number: 0,
+            hash: keccak256(b"quantera mock chain genesis"),
+            parent_hash: B256::ZERO,
+            timestamp: GENESIS_TIMESTAMP,
+            transactions: Vec::new(),
+            logs: Vec::new(),
+        };
+        Self {
+            config,
+            blocks: vec![genesis],
+            fork: 0,
+            handlers: HashMap::new(),
+            balances: HashMap::new(),
+            code: HashMap::new(),
+            transactions: Vec::new(),
+            methods: Vec::new(),
+        }
+    }
+
+    fn head(&self) -> &Block {
+        self.blocks.last().expect("the genesis block is never removed")
+    }
+
+    /// Mine a block with every pending transaction and the given logs
+    fn mine_block(&mut self, logs: Vec<MockLog>) -> u64 {
+        let parent = self.head();
+        let number = parent.number + 1;
+        let parent_hash = parent.hash;
+        let timestamp = parent.timestamp + BLOCK_TIME_SECS;
+
+        let mut preimage = parent_hash.to_vec();
+        preimage.extend_from_slice(&number.to_be_bytes());
+        preimage.extend_from_slice(&self.fork.to_be_bytes());
+        let hash = keccak256(&preimage);
+
+        let mut transactions = Vec::new();
+        for tx in self.transactions.iter_mut().filter(|tx| tx.block_number.is_none()) {
+            tx.block_number = Some(number);
+            transactions.push(tx.hash);
+        }
+        // Bare logs get a transaction hash of their own, as if emitted by an unseen transaction
+        let logs = logs.into_iter().enumerate()
+            .map(|(index, log)| {
+                let mut preimage = hash.to_vec();
+                preimage.extend_from_slice(&(index as u64).to_be_bytes());
+                MinedLog { log, transaction_hash: keccak256(&preimage), transaction_index: (transactions.len() + index) as u64 }
+            })
+            .collect();
+
+        self.blocks.push(Block { number, hash, parent_hash, timestamp, transactions, logs });
+        number
+    }
+
+    fn block(&self, tag: &Value) -> Option<&Block> {
+        let head = self.head().number;
+        let number = match tag.as_str().unwrap_or("latest") {
+            "latest" | "pending" => head,
+            "earliest" => 0,
+            "safe" => head.saturating_sub(self.config.safe_depth),
+            "finalized" => head.saturating_sub(self.config.finalized_depth),
+            quantity => parse_quantity(quantity)?,
+        };
+        self.blocks.get(number as usize)
+    }
+
+    fn call(&self, call: &ContractCall) -> Result<CallOutcome, RpcError> {
+        let Some(selector) = call.selector() else {
+            return Ok(CallOutcome::Return(Vec::new()));
+        };
+        match self.handlers.get(&(call.to, selector)) {
+            Some(handler) => Ok(handler(call)),
+            None => Err(RpcError::new(-32000, format!(
+                "mock chain has no response programmed for selector 0x{} on {}", hex::encode(selector), hex_bytes(call.to.as_slice())
+            ))),
+        }
+    }
+
+    fn send(&mut self, nonce: u64, to: Option<Address>, input: Vec<u8>, hash: B256) -> B256 {
+        // Unprogrammed calls and plain transfers succeed
+        let succeeded = match to {
+            Some(to) => !matches!(
+                self.call(&ContractCall { kind: CallKind::Transaction, from: None, to, input: input.clone() }),
+                Ok(CallOutcome::Revert(_))
+            ),
+            None => true,
+        };
+        self.transactions.push(SentTransaction { hash, nonce, to, input, block_number: None, succeeded });
+        if self.config.auto_mine {
+            self.mine_block(Vec::new());
+        }
+        hash
+    }
+
+    fn handle(&mut self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        self.methods.push(method.to_string());
+        let param = |index: usize| params.get(index).cloned().unwrap_or(Value::Null);
+
+        match method {
+            "eth_chainId" => Ok(json!(quantity(self.config.chain_id))),
+            "net_version" => Ok(json!(self.config.chain_id.to_string())),
+            "eth_blockNumber" => Ok(json!(quantity(self.head().number))),
+            "eth_gasPrice" | "eth_maxPriorityFeePerGas" => Ok(json!(quantity(GAS_PRICE))),
+            "eth_supportedEIPs" => Ok(json!(self.config.supported_eips.join(","))),
+            "eth_getTransactionCount" => Ok(json!(quantity(self.transactions.len() as u64))),
+            "eth_getBalance" => {
+                let address = parse_address(&param(0)).ok_or_else(|| RpcError::invalid("address"))?;
+                Ok(json!(format!("0x{:x}", self.balances.get(&address).copied().unwrap_or_default())))
+            }
+            "eth_getCode" => {
+                let address = parse_address(&param(0)).ok_or_else(|| RpcError::invalid("address"))?;
+                Ok(json!(hex_bytes(self.code.get(&address).map(Vec::as_slice).unwrap_or_default())))
+            }
+            "eth_getBlockByNumber" => Ok(self.block(&param(0)).map(|block| self.block_json(block)).unwrap_or(Value::Null)),
+            "eth_getBlockByHash" => {
+                let hash = parse_b256(&param(0)).ok_or_else(|| RpcError::invalid("block hash"))?;
+                Ok(self.blocks.iter().find(|block| block.hash == hash).map(|block| self.block_json(block)).unwrap_or(Value::Null))
+            }
+            "eth_getBlockhash" => {
+                let number = param(0).as_u64().or_else(|| param(0).as_str().and_then(parse_quantity)).ok_or_else(|| RpcError::invalid("block number"))?;
+                Ok(self.blocks.get(number as usize).map(|block| json!(hex_bytes(block.hash.as_slice()))).unwrap_or(Value::Null))
+            }
+            "eth_call" | "eth_estimateGas" => {
+                let request = param(0);
+                let to = parse_address(&request["to"]).ok_or_else(|| RpcError::invalid("call recipient"))?;
+                let input = parse_data(request.get("input").or_else(|| request.get("data")).unwrap_or(&Value::Null))
+                    .ok_or_else(|| RpcError::invalid("call data"))?;
+                let call = ContractCall { kind: CallKind::Call, from: parse_address(&request["from"]), to, input };
+                match self.call(&call)? {
+                    CallOutcome::Return(_) if method == "eth_estimateGas" => Ok(json!(quantity(GAS_ESTIMATE))),
+                    CallOutcome::Return(data) => Ok(json!(hex_bytes(&data))),
+                    CallOutcome::Revert(reason) => Err(RpcError::revert(&reason)),
+                }
+            }
+            "eth_sendRawTransaction" => {
+                let raw = parse_data(&param(0)).ok_or_else(|| RpcError::invalid("raw transaction"))?;
+                let tx = rlp::decode_transaction(&raw).map_err(|e| RpcError::invalid(&e))?;
+                let hash = self.send(tx.nonce, tx.to, tx.input, keccak256(&raw));
+                Ok(json!(hex_bytes(hash.as_slice())))
+            }
+            "eth_sendTransaction" => {
+                let request = param(0);
+                let input = parse_data(request.get("input").or_else(|| request.get("data")).unwrap_or(&Value::Null)).unwrap_or_default();
+                let nonce = self.transactions.len() as u64;
+                let mut preimage = request.to_string().into_bytes();
+                preimage.extend_from_slice(&nonce.to_be_bytes());
+                let hash = self.send(nonce, parse_address(&request["to"]), input, keccak256(&preimage));
+                Ok(json!(hex_bytes(hash.as_slice())))
+            }
+            "eth_getTransactionByHash" => {
+                let hash = parse_b256(&param(0)).ok_or_else(|| RpcError::invalid("transaction hash"))?;
+                Ok(self.transactions.iter().find(|tx| tx.hash == hash).map(|tx| self.transaction_json(tx)).unwrap_or(Value::Null))
+            }
+            "eth_getTransactionReceipt" => {
+                let hash = parse_b256(&param(0)).ok_or_else(|| RpcError::invalid("transaction hash"))?;
+                Ok(self.transactions.iter().find(|tx| tx.hash == hash).and_then(|tx| self.receipt_json(tx)).unwrap_or(Value::Null))
+            }
+            "eth_getLogs" => Ok(Value::Array(self.logs(&param(0))?)),
+            "eth_feeHistory" => Ok(json!({
+                "oldestBlock": quantity(self.head().number),
+                "baseFeePerGas": [quantity(GAS_PRICE), quantity(GAS_PRICE)],
+                "gasUsedRatio": [0.5],
+                "reward": [[quantity(GAS_PRICE)]],
+            })),
+            other => Err(RpcError::new(-32601, format!("mock chain does not implement {}", other))),
+        }
+    }
+
+    fn logs(&self, filter: &Value) -> Result<Vec<Value>, RpcError> {
+        let head = self.head().number;
+        let bound = |key: &str, default: u64| match filter.get(key) {
+            None | Some(Value::Null) => Some(default),
+            Some(tag) => self.block(tag).map(|block| block.number),
+        };
+        let (from, to) = match filter.get("blockHash").and_then(parse_b256) {
+            Some(hash) => {
+                let number = self.blocks.iter().find(|block| block.hash == hash).map(|block| block.number)
+                    .ok_or_else(|| RpcError::invalid("unknown block hash"))?;
+                (number, number)
+            }
+            None => (bound("fromBlock", head).unwrap_or(head + 1), bound("toBlock", head).unwrap_or(head)),
+        };
+
+        let addresses: Option<Vec<Address>> = match filter.get("address") {
+            None | Some(Value::Null) => None,
+            Some(Value::Array(addresses)) => Some(addresses.iter().filter_map(parse_address).collect()),
+            Some(address) => Some(parse_address(address).into_iter().collect()),
+        };
+        let topics: Vec<Option<Vec<B256>>> = filter.get("topics").and_then(Value::as_array)
+            .map(|topics| topics.iter().map(|topic| match topic {
+                Value::Null => None,
+                Value::Array(options) => Some(options.iter().filter_map(parse_b256).collect()),
+                topic => Some(parse_b256(topic).into_iter().collect()),
+            }).collect())
+            .unwrap_or_default();
+
+        let mut logs = Vec::new();
+        for block in self.blocks.iter().filter(|block| block.number >= from && block.number <= to) {
+            for (index, mined) in block.logs.iter().enumerate() {
+                if addresses.as_ref().is_some_and(|addresses| !addresses.contains(&mined.log.address)) {
+                    continue;
+                }
+                let matches = topics.iter().enumerate().all(|(position, wanted)| match wanted {
+                    None => true,
+                    Some(options) => mined.log.topics.get(position).is_some_and(|topic| options.contains(topic)),
+                });
+                if matches {
+                    logs.push(log_json(block, mined, index));
+                }
+            }
+        }
+        Ok(logs)
+    }
+
+    fn block_json(&self, block: &Block) -> Value {
+        json!({
+            "number": quantity(block.number),
+            "hash": hex_bytes(block.hash.as_slice()),
+            "parentHash": hex_bytes(block.parent_hash.as_slice()),
+            "timestamp": quantity(block.timestamp),
+            "transactions": block.transactions.iter().map(|hash| hex_bytes(hash.as_slice())).collect::<Vec<_>>(),
+            "nonce": "0x0000000000000000",
+            "mixHash": hex_bytes(&[0; 32]),
+            "sha3Uncles": hex_bytes(&[0; 32]),
+            "logsBloom": hex_bytes(&[0; 256]),
+            "transactionsRoot": hex_bytes(&[0; 32]),
+            "stateRoot": hex_bytes(&[0; 32]),
+            "receiptsRoot": hex_bytes(&[0; 32]),
+            "miner": hex_bytes(&[0; 20]),
+            "difficulty": "0x0",
+            "totalDifficulty": "0x0",
+            "extraData": "0x",
+            "size": "0x0",
+            "gasLimit": quantity(30_000_000),
+            "gasUsed": quantity(block.transactions.len() as u64 * GAS_ESTIMATE),
+            "baseFeePerGas": quantity(GAS_PRICE),
+            "uncles": [],
+        })
+    }
+
+    fn transaction_json(&self, tx: &SentTransaction) -> Value {
+        let block = tx.block_number.and_then(|number| self.blocks.get(number as usize));
+        json!({
+            "hash": hex_bytes(tx.hash.as_slice()),
+            "nonce": quantity(tx.nonce),
+            "blockHash": block.map(|block| hex_bytes(block.hash.as_slice())),
+            "blockNumber": block.map(|block| quantity(block.number)),
+            "transactionIndex": block.map(|block| quantity(transaction_index(block, tx.hash))),
+            "from": hex_bytes(&[0; 20]),
+            "to": tx.to.map(|to| hex_bytes(to.as_slice())),
+            "value": "0x0",
+            "gas": quantity(GAS_ESTIMATE),
+            "gasPrice": quantity(GAS_PRICE),
+            "input": hex_bytes(&tx.input),
+            "v": "0x0",
+            "r": "0x0",
+            "s": "0x0",
+            "type": "0x0",
+        })
+    }
+
+    fn receipt_json(&self, tx: &SentTransaction) -> Option<Value> {
+        let block = self.blocks.get(tx.block_number? as usize)?;
+        let contract_address = match tx.to {
+            None => Some(hex_bytes(&keccak256(tx.hash.as_slice())[12..])),
+            Some(_) => None,
+        };
+        Some(json!({
+            "transactionHash": hex_bytes(tx.hash.as_slice()),
+            "transactionIndex": quantity(transaction_index(block, tx.hash)),
+            "blockHash": hex_bytes(block.hash.as_slice()),
+            "blockNumber": quantity(block.number),
+            "from": hex_bytes(&[0; 20]),
+            "to": tx.to.map(|to| hex_bytes(to.as_slice())),
+            "contractAddress": contract_address,
+            "cumulativeGasUsed": quantity(GAS_ESTIMATE),
+            "gasUsed": quantity(GAS_ESTIMATE),
+            "effectiveGasPrice": quantity(GAS_PRICE),
+            "logs": [],
+            "logsBloom": hex_bytes(&[0; 256]),
+            "status": if tx.succeeded { "0x1" } else { "0x0" },
+            "type": "0x0",
+        }))
+    }
+}
+
+fn transaction_index(block: &Block, hash: B256) -> u64 {
+    block.transactions.iter().position(|tx| *tx == hash).unwrap_or_default() as u64
+}
+
+fn log_json(block: &Block, mined: &MinedLog, index: usize) -> Value {
+    json!({
+        "address": hex_bytes(mined.log.address.as_slice()),
+        "topics": mined.log.topics.iter().map(|topic| hex_bytes(topic.as_slice())).collect::<Vec<_>>(),
+        "data": hex_bytes(&mined.log.data),
+        "blockNumber": quantity(block.number),
+        "blockHash": hex_bytes(block.hash.as_slice()),
+        "transactionHash": hex_bytes(mined.transaction_hash.as_slice()),
+        "transactionIndex": quantity(mined.transaction_index),
+        "logIndex": quantity(index as u64),
+        "removed": false,
+    })
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+    data: Option<String>,
+}
+
+impl RpcError {
+    fn new(code: i64, message: String) -> Self {
+        Self { code, message, data: None }
+    }
+
+    fn invalid(what: &str) -> Self {
+        Self::new(-32602, format!("invalid {}", what))
+    }
+
+    /// The error geth returns for a reverted call, with the revert data attached
+    fn revert(reason: &str) -> Self {
+        Self { code: 3, message: format!("execution reverted: {}", reason), data: Some(hex_bytes(&abi::revert_data(reason))) }
+    }
+}
+
+fn quantity(value: u64) -> String {
+    format!("0x{:x}", value)
+}
+
+fn hex_bytes(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn parse_quantity(value: &str) -> Option<u64> {
+    u64::from_str_radix(value.strip_prefix("0x")?, 16).ok()
+}
+
+fn parse_data(value: &Value) -> Option<Vec<u8>> {
+    hex::decode(value.as_str()?.strip_prefix("0x")?).ok()
+}
+
+fn parse_address(value: &Value) -> Option<Address> {
+    parse_data(value).filter(|bytes| bytes.len() == 20).map(|bytes| Address::from_slice(&bytes))
+}
+
+fn parse_b256(value: &Value) -> Option<B256> {
+    parse_data(value).filter(|bytes| bytes.len() == 32).map(|bytes| B256::from_slice(&bytes))
+}
+
+type SharedState = Arc<Mutex<ChainState>>;
+
+async fn rpc(State(state): State<SharedState>, Json(body): Json<Value>) -> Json<Value> {
+    let mut chain = state.lock().expect("mock chain state poisoned");
+    let mut answer = |request: &Value| {
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let method = request["method"].as_str().unwrap_or_default();
+        match chain.handle(method, &request["params"]) {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err(e) => json!({"jsonrpc": "2.0", "id": id, "error": {"code": e.code, "message": e.message, "data": e.data}}),
+        }
+    };
+    match &body {
+        Value::Array(batch) => Json(Value::Array(batch.iter().map(&mut answer).collect())),
+        request => Json(answer(request)),
+    }
+}
+
+/// An Ethereum JSON-RPC node on a local port, answering the calls the services' clients make.
+///
+/// Contracts are programmed per selector with closures; blocks are only mined when a
+/// transaction arrives (with `auto_mine`) or a test asks, so block numbers, hashes and
+/// timestamps are the same on every run. The server stops when the value is dropped.
+pub struct MockChain {
+    state: SharedState,
+    addr: SocketAddr,
+    server: JoinHandle<()>,
+}
+
+impl MockChain {
+    pub async fn start() -> Result<Self, HarnessError> {
+        Self::start_with(MockChainConfig::default()).await
+    }
+
+    pub async fn start_with(config: MockChainConfig) -> Result<Self, HarnessError> {
+        let state = Arc::new(Mutex::new(ChainState::new(config)));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let app = Router::new().route("/", post(rpc)).with_state(state.clone());
+        let server = tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, app).await {
+                tracing::error!("Mock chain server stopped: {}", e);
+            }
+        });
+        Ok(Self { state, addr, server })
+    }
+
+    /// HTTP endpoint to give the clients under test
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    fn with_state<T>(&self, f: impl FnOnce(&mut ChainState) -> T) -> T {
+        f(&mut self.state.lock().expect("mock chain state poisoned"))
+    }
+
+    pub fn chain_id(&self) -> u64 {
+        self.with_state(|chain| chain.config.chain_id)
+    }
+
+    /// Answer calls and transactions to `signature` on `to` with `handler`, replacing any
+    /// earlier program for it
+    pub fn on_call(&self, to: Address, signature: &str, handler: impl Fn(&ContractCall) -> CallOutcome + Send + Sync + 'static) {
+        let selector = abi::selector(signature);
+        self.with_state(|chain| chain.handlers.insert((to, selector), Arc::new(handler)));
+    }
+
+    /// Always return `values`, ABI-encoded
+    pub fn returns(&self, to: Address, signature: &str, values: Vec<abi::AbiValue>) {
+        let data = abi::encode(&values);
+        self.on_call(to, signature, move |_| CallOutcome::Return(data.clone()));
+    }
+
+    /// Always revert with `reason`
+    pub fn reverts(&self, to: Address, signature: &str, reason: &str) {
+        let reason = reason.to_string();
+        self.on_call(to, signature, move |_| CallOutcome::Revert(reason.clone()));
+    }
+
+    pub fn set_balance(&self, address: Address, balance: U256) {
+        self.with_state(|chain| chain.balances.insert(address, balance));
+    }
+
+    pub fn set_code(&self, address: Address, code: Vec<u8>) {
+        self.with_state(|chain| chain.code.insert(address, code));
+    }
+
+    /// Mine `blocks` blocks, the first with any pending transactions; returns the new head
+    pub fn mine(&self, blocks: u64) -> u64 {
+        self.with_state(|chain| {
+            for _ in 0..blocks {
+                chain.mine_block(Vec::new());
+            }
+            chain.head().number
+        })
+    }
+
+    /// Mine one block containing `logs`, returning its number
+    pub fn mine_logs(&self, logs: Vec<MockLog>) -> u64 {
+        self.with_state(|chain| chain.mine_block(logs))
+    }
+
+    /// Replace the last `depth` blocks with a longer fork of `depth + 1` blocks.
+    ///
+    /// Transactions from the dropped blocks go back to pending and are re-mined into the
+    /// first replacement block, so their receipts name a different block hash; logs mined
+    /// with `mine_logs` in the dropped blocks are gone. Returns the new head.
+    pub fn reorg(&self, depth: u64) -> u64 {
+        self.with_state(|chain| {
+            let keep = chain.blocks.len().saturating_sub(depth as usize).max(1);
+            chain.blocks.truncate(keep);
+            let head = chain.head().number;
+            for tx in chain.transactions.iter_mut().filter(|tx| tx.block_number.is_some_and(|block| block > head)) {
+                tx.block_number = None;
+            }
+            chain.fork += 1;
+            for _ in 0..=depth {
+                chain.mine_block(Vec::new());
+            }
+            chain.head().number
+        })
+    }
+
+    pub fn head(&self) -> u64 {
+        self.with_state(|chain| chain.head().number)
+    }
+
+    pub fn block_hash(&self, number: u64) -> Option<B256> {
+        self.with_state(|chain| chain.blocks.get(number as usize).map(|block| block.hash))
+    }
+
+    /// Every transaction accepted, in the order it arrived
+    pub fn transactions(&self) -> Vec<SentTransaction> {
+        self.with_state(|chain| chain.transactions.clone())
+    }
+
+    /// Transactions calling `signature` on `to`
+    pub fn transactions_to(&self, to: Address, signature: &str) -> Vec<SentTransaction> {
+        let selector = abi::selector(signature);
+        self.transactions().into_iter()
+            .filter(|tx| tx.to == Some(to) && tx.input.starts_with(&selector))
+            .collect()
+    }
+
+    /// JSON-RPC methods received so far, in order
+    pub fn requests(&self) -> Vec<String> {
+        self.with_state(|chain| chain.methods.clone())
+    }
+}
+
+impl Drop for MockChain {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abi::AbiValue;
+
+    async fn rpc_call(chain: &MockChain, method: &str, params: Value) -> Value {
+        reqwest::Client::new()
+            .post(chain.url())
+            .json(&json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params}))
+            .send().await.unwrap()
+            .json().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_programmed_calls_and_revert_errors() {
+        let chain = MockChain::start().await.unwrap();
+        let registry = Address::from([0x42; 20]);
+        chain.returns(registry, "getAllTreasuries()", vec![AbiValue::Array(vec![AbiValue::FixedBytes([7; 32])])]);
+        chain.reverts(registry, "updateTreasuryPrice(bytes32,uint256)", "not an operator");
+
+        let call = |signature: &str| json!([{"to": hex_bytes(registry.as_slice()), "data": hex_bytes(&abi::encode_call(signature, &[]))}, "latest"]);
+        let listed = rpc_call(&chain, "eth_call", call("getAllTreasuries()")).await;
+        assert_eq!(parse_data(&listed["result"]).unwrap(), abi::encode(&[AbiValue::Array(vec![AbiValue::FixedBytes([7; 32])])]));
+
+        let reverted = rpc_call(&chain, "eth_call", call("updateTreasuryPrice(bytes32,uint256)")).await;
+        assert_eq!(reverted["error"]["code"], 3);
+        assert_eq!(parse_data(&reverted["error"]["data"]).unwrap(), abi::revert_data("not an operator"));
+
+        let unprogrammed = rpc_call(&chain, "eth_call", call("owner()")).await;
+        assert!(unprogrammed["error"]["message"].as_str().unwrap().contains("no response programmed"));
+        assert_eq!(rpc_call(&chain, "eth_chainId", json!([])).await["result"], "0x7a69");
+    }
+
+    #[tokio::test]
+    async fn test_reorg_moves_transactions_and_drops_logs() {
+        let chain = MockChain::start().await.unwrap();
+        let token = Address::from([0x11; 20]);
+        let transfer = abi::event_topic("Transfer(address,address,uint256)");
+        chain.mine(3);
+        let log_block = chain.mine_logs(vec![MockLog { address: token, topics: vec![transfer], data: abi::word(U256::from(5)).to_vec() }]);
+
+        let sent = rpc_call(&chain, "eth_sendTransaction", json!([{"to": hex_bytes(token.as_slice()), "data": "0x"}])).await;
+        let tx_hash = sent["result"].clone();
+        let receipt = rpc_call(&chain, "eth_getTransactionReceipt", json!([tx_hash])).await["result"].clone();
+        assert_eq!(receipt["blockNumber"], quantity(log_block + 1));
+        let original_block_hash = receipt["blockHash"].clone();
+
+        let filter = json!([{"fromBlock": "0x0", "toBlock": "latest", "address": hex_bytes(token.as_slice()), "topics": [hex_bytes(transfer.as_slice())]}]);
+        assert_eq!(rpc_call(&chain, "eth_getLogs", filter.clone()).await["result"].as_array().unwrap().len(), 1);
+
+        // Dropping the last two blocks loses the log and re-mines the transaction elsewhere
+        assert_eq!(chain.reorg(2), log_block + 2);
+        assert!(rpc_call(&chain, "eth_getLogs", filter).await["result"].as_array().unwrap().is_empty());
+        let receipt = rpc_call(&chain, "eth_getTransactionReceipt", json!([tx_hash])).await["result"].clone();
+        assert_eq!(receipt["blockNumber"], quantity(log_block));
+        assert_ne!(receipt["blockHash"], original_block_hash);
+
+        let safe = rpc_call(&chain, "eth_getBlockByNumber", json!(["safe", false])).await["result"].clone();
+        assert_eq!(safe["number"], quantity(chain.head() - 2));
+        assert_eq!(safe["timestamp"], quantity(GENESIS_TIMESTAMP + (chain.head() - 2) * BLOCK_TIME_SECS));
+    }
+}
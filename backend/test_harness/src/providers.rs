@@ -0,0 +1,359 @@
+// HTTP fixtures standing in for the KYC, sanctions, IPFS and price providers and webhook receivers
+use alloy_primitives::Address;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use wiremock::matchers::{method, path, path_regex};
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+/// Identifiers the KYC fixture hands out
+pub const KYC_APPLICANT_ID: &str = "e2e-applicant-0001";
+pub const KYC_CHECK_ID: &str = "e2e-check-0001";
+
+/// An Onfido-shaped KYC provider: applicants and checks are created, and every check comes
+/// back with the same result
+pub struct KycFixture {
+    server: MockServer,
+}
+
+impl KycFixture {
+    /// Every check passes
+    pub async fn clear() -> Self {
+        Self::with_result("clear").await
+    }
+
+    /// Every check needs review, which the services treat as a failed verification
+    pub async fn rejecting() -> Self {
+        Self::with_result("consider").await
+    }
+
+    async fn with_result(result: &str) -> Self {
+        let server = MockServer::start().await;
+        let check = json!({
+            "id": KYC_CHECK_ID,
+            "status": "complete",
+            "result": result,
+            "report_ids": ["e2e-report-document", "e2e-report-facial-similarity"],
+        });
+
+        Mock::given(method("POST")).and(path("/applicants"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({ "id": KYC_APPLICANT_ID })))
+            .mount(&server).await;
+        Mock::given(method("POST")).and(path("/checks"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(check.clone()))
+            .mount(&server).await;
+        Mock::given(method("GET")).and(path(format!("/checks/{}", KYC_CHECK_ID)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(check))
+            .mount(&server).await;
+
+        Self { server }
+    }
+
+    /// Base URL to configure as the provider's API
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+
+    /// Applicants created so far
+    pub async fn applicants_created(&self) -> usize {
+        received(&self.server, "POST", "/applicants").await
+    }
+}
+
+/// A PEP screening API flagging the given wallets.
+///
+/// The sanctions lists themselves are compiled into the screener, so this only covers the
+/// external PEP lookups (`GET /v1/wallets/{address}`, and `GET /v1/persons` for beneficial
+/// owners, who are never flagged).
+pub struct SanctionsFixture {
+    server: MockServer,
+}
+
+impl SanctionsFixture {
+    pub async fn start(flagged: &[Address]) -> Self {
+        let server = MockServer::start().await;
+        for wallet in flagged {
+            // Mounted ahead of the catch-all below
+            Mock::given(method("GET")).and(path(format!("/v1/wallets/0x{}", hex::encode(wallet))))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "pep": true,
+                    "details": "Politically exposed person (e2e fixture)",
+                })))
+                .with_priority(1)
+                .mount(&server).await;
+        }
+        Mock::given(method("GET")).and(path_regex(r"^/v1/wallets/0x[0-9a-f]{40}$"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "pep": false })))
+            .mount(&server).await;
+        Mock::given(method("GET")).and(path("/v1/persons"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "pep": false })))
+            .mount(&server).await;
+        Self { server }
+    }
+
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+
+    /// Wallets screened so far, including repeats
+    pub async fn screenings(&self) -> usize {
+        self.server.received_requests().await.unwrap_or_default().iter()
+            .filter(|request| request.url.path().starts_with("/v1/wallets/"))
+            .count()
+    }
+
+    /// Beneficial owners screened so far, including repeats
+    pub async fn person_screenings(&self) -> usize {
+        received(&self.server, "GET", "/v1/persons").await
+    }
+}
+
+/// A Kubo (`/api/v0`) node keeping added content in memory.
+///
+/// Content is addressed by its CIDv1 raw-block hash, which is what the compliance client
+/// asks for and checks on retrieval, so round trips verify like they do against a real node.
+pub struct IpfsFixture {
+    server: MockServer,
+    blocks: Arc<Mutex<HashMap<String, Vec<u8>>>>,
+}
+
+impl IpfsFixture {
+    pub async fn start() -> Self {
+        let server = MockServer::start().await;
+        let blocks = Arc::new(Mutex::new(HashMap::new()));
+
+        Mock::given(method("POST")).and(path("/api/v0/add"))
+            .respond_with(IpfsAdd(blocks.clone()))
+            .mount(&server).await;
+        Mock::given(method("POST")).and(path("/api/v0/pin/add"))
+            .respond_with(IpfsPin(blocks.clone()))
+            .mount(&server).await;
+        Mock::given(method("POST")).and(path("/api/v0/cat"))
+            .respond_with(IpfsCat(blocks.clone()))
+            .mount(&server).await;
+
+        Self { server, blocks }
+    }
+
+    /// API address to configure as the IPFS node, e.g. `http://127.0.0.1:41234`
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+
+    /// Content stored under `cid`, if it was added
+    pub fn content(&self, cid: &str) -> Option<Vec<u8>> {
+        self.blocks.lock().expect("IPFS fixture state poisoned").get(cid).cloned()
+    }
+
+    pub fn stored(&self) -> usize {
+        self.blocks.lock().expect("IPFS fixture state poisoned").len()
+    }
+}
+
+/// CIDv1 of `content` as a single sha2-256 raw block
+pub fn raw_cid(content: &[u8]) -> String {
+    let mut bytes = vec![0x01, 0x55, 0x12, 0x20];
+    bytes.extend_from_slice(&Sha256::digest(content));
+    format!("b{}", data_encoding::BASE32_NOPAD.encode(&bytes).to_lowercase())
+}
+
+struct IpfsAdd(Arc<Mutex<HashMap<String, Vec<u8>>>>);
+
+impl Respond for IpfsAdd {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let Some(content) = multipart_file(request) else {
+            return ResponseTemplate::new(400).set_body_string("expected a multipart file upload");
+        };
+        let cid = raw_cid(&content);
+        let size = content.len();
+        self.0.lock().expect("IPFS fixture state poisoned").insert(cid.clone(), content);
+        ResponseTemplate::new(200).set_body_json(json!({ "Name": cid, "Hash": cid, "Size": size.to_string() }))
+    }
+}
+
+struct IpfsPin(Arc<Mutex<HashMap<String, Vec<u8>>>>);
+
+impl Respond for IpfsPin {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        match query_arg(request).filter(|cid| self.0.lock().expect("IPFS fixture state poisoned").contains_key(cid)) {
+            Some(cid) => ResponseTemplate::new(200).set_body_json(json!({ "Pins": [cid] })),
+            None => ResponseTemplate::new(500).set_body_json(json!({ "Message": "block not found", "Code": 0, "Type": "error" })),
+        }
+    }
+}
+
+struct IpfsCat(Arc<Mutex<HashMap<String, Vec<u8>>>>);
+
+impl Respond for IpfsCat {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let content = query_arg(request).and_then(|cid| self.0.lock().expect("IPFS fixture state poisoned").get(&cid).cloned());
+        match content {
+            Some(content) => ResponseTemplate::new(200).set_body_bytes(content),
+            None => ResponseTemplate::new(500).set_body_json(json!({ "Message": "block not found", "Code": 0, "Type": "error" })),
+        }
+    }
+}
+
+fn query_arg(request: &Request) -> Option<String> {
+    request.url.query_pairs().find(|(key, _)| key == "arg").map(|(_, value)| value.into_owned())
+}
+
+/// Bytes of the first part of a `multipart/form-data` body
+fn multipart_file(request: &Request) -> Option<Vec<u8>> {
+    let content_type = request.headers.get("content-type")?.to_str().ok()?;
+    let boundary = content_type.split(';')
+        .find_map(|param| param.trim().strip_prefix("boundary="))?
+        .trim_matches('"');
+    let delimiter = format!("--{}", boundary);
+
+    let body = &request.body;
+    let part = find(body, delimiter.as_bytes())? + delimiter.len();
+    let headers_end = part + find(&body[part..], b"\r\n\r\n")? + 4;
+    let end = headers_end + find(&body[headers_end..], format!("\r\n{}", delimiter).as_bytes())?;
+    Some(body[headers_end..end].to_vec())
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+/// A price API answering `GET /{asset}` with `{"price": "...", "timestamp": secs}`, the shape
+/// `HttpPriceOracle` reads; unknown assets are 404s.
+pub struct PriceFeedFixture {
+    server: MockServer,
+    prices: Arc<Mutex<HashMap<String, (Decimal, i64)>>>,
+}
+
+impl PriceFeedFixture {
+    pub async fn start() -> Self {
+        let server = MockServer::start().await;
+        let prices = Arc::new(Mutex::new(HashMap::new()));
+        Mock::given(method("GET"))
+            .respond_with(PriceQuote(prices.clone()))
+            .mount(&server).await;
+        Self { server, prices }
+    }
+
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+
+    /// Quote `asset` at `price`, observed now
+    pub fn set_price(&self, asset: &str, price: Decimal) {
+        self.set_price_at(asset, price, Utc::now().timestamp());
+    }
+
+    /// Quote `asset` at `price` observed at `timestamp`, e.g. to serve a stale price
+    pub fn set_price_at(&self, asset: &str, price: Decimal, timestamp: i64) {
+        self.prices.lock().expect("price fixture state poisoned").insert(asset.to_string(), (price, timestamp));
+    }
+
+    pub fn remove_price(&self, asset: &str) {
+        self.prices.lock().expect("price fixture state poisoned").remove(asset);
+    }
+}
+
+struct PriceQuote(Arc<Mutex<HashMap<String, (Decimal, i64)>>>);
+
+impl Respond for PriceQuote {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let asset = request.url.path().trim_start_matches('/');
+        match self.0.lock().expect("price fixture state poisoned").get(asset) {
+            Some((price, timestamp)) => ResponseTemplate::new(200).set_body_json(json!({
+                "price": price.to_string(),
+                "timestamp": timestamp,
+            })),
+            None => ResponseTemplate::new(404),
+        }
+    }
+}
+
+/// A POST received by a webhook fixture
+#[derive(Debug, Clone)]
+pub struct WebhookDelivery {
+    pub signature: Option<String>,
+    pub delivery_id: Option<String>,
+    pub body: Vec<u8>,
+}
+
+/// A webhook endpoint at `/hook` answering 500 to its first `failures` POSTs and 200 after
+pub struct WebhookFixture {
+    server: MockServer,
+}
+
+impl WebhookFixture {
+    pub async fn failing_first(failures: u64) -> Self {
+        let server = MockServer::start().await;
+        if failures > 0 {
+            Mock::given(method("POST")).and(path("/hook"))
+                .respond_with(ResponseTemplate::new(500))
+                .up_to_n_times(failures)
+                .with_priority(1)
+                .mount(&server).await;
+        }
+        Mock::given(method("POST")).and(path("/hook"))
+            .respond_with(ResponseTemplate::new(200))
+            .with_priority(2)
+            .mount(&server).await;
+        Self { server }
+    }
+
+    /// Every POST fails
+    pub async fn down() -> Self {
+        Self::failing_first(u64::MAX).await
+    }
+
+    /// URL to register as the webhook
+    pub fn url(&self) -> String {
+        format!("{}/hook", self.server.uri())
+    }
+
+    /// POSTs received so far, retries included, in arrival order
+    pub async fn deliveries(&self) -> Vec<WebhookDelivery> {
+        let header = |request: &Request, name: &str| request.headers.get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        self.server.received_requests().await.unwrap_or_default().iter()
+            .filter(|request| request.method.as_str() == "POST" && request.url.path() == "/hook")
+            .map(|request| WebhookDelivery {
+                signature: header(request, "X-Signature"),
+                delivery_id: header(request, "X-Delivery-Id"),
+                body: request.body.clone(),
+            })
+            .collect()
+    }
+}
+
+async fn received(server: &MockServer, http_method: &str, request_path: &str) -> usize {
+    server.received_requests().await.unwrap_or_default().iter()
+        .filter(|request| request.method.as_str() == http_method && request.url.path() == request_path)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_ipfs_fixture_addresses_content_by_raw_cid() {
+        let ipfs = IpfsFixture::start().await;
+        let client = reqwest::Client::new();
+        let form = reqwest::multipart::Form::new()
+            .part("file", reqwest::multipart::Part::bytes(b"hello world".to_vec()).file_name("document"));
+        let added: serde_json::Value = client.post(format!("{}/api/v0/add?cid-version=1&raw-leaves=true", ipfs.uri()))
+            .multipart(form)
+            .send().await.unwrap()
+            .json().await.unwrap();
+        assert_eq!(added["Hash"], "bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e");
+
+        let cid = added["Hash"].as_str().unwrap();
+        let pinned = client.post(format!("{}/api/v0/pin/add?arg={}", ipfs.uri(), cid)).send().await.unwrap();
+        assert!(pinned.status().is_success());
+        let body = client.post(format!("{}/api/v0/cat?arg={}", ipfs.uri(), cid)).send().await.unwrap().bytes().await.unwrap();
+        assert_eq!(body.as_ref(), b"hello world");
+        assert_eq!(ipfs.stored(), 1);
+    }
+}
@@ -0,0 +1,126 @@
+// Just enough RLP to read the recipient and calldata of signed transactions
+use alloy_primitives::Address;
+
+/// The parts of a signed transaction the mock node acts on; the sender is not recovered
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DecodedTransaction {
+    pub nonce: u64,
+    pub to: Option<Address>,
+    pub input: Vec<u8>,
+}
+
+/// Decode a legacy, EIP-2930 (0x01) or EIP-1559 (0x02) transaction
+pub(crate) fn decode_transaction(raw: &[u8]) -> Result<DecodedTransaction, String> {
+    // Positions of nonce, to and input in each envelope's field list
+    let (payload, nonce, to, input) = match raw.first() {
+        Some(0x01) => (&raw[1..], 1, 4, 6),
+        Some(0x02) => (&raw[1..], 1, 5, 7),
+        Some(prefix) if *prefix >= 0xc0 => (raw, 0, 3, 5),
+        _ => return Err("unsupported transaction envelope".to_string()),
+    };
+
+    let (is_list, fields, _) = item(payload)?;
+    if !is_list {
+        return Err("transaction is not an RLP list".to_string());
+    }
+    let fields = list_items(fields)?;
+    let field = |index: usize| fields.get(index).copied().ok_or_else(|| format!("transaction has no field {}", index));
+
+    let nonce_bytes = field(nonce)?;
+    if nonce_bytes.len() > 8 {
+        return Err("nonce does not fit in 64 bits".to_string());
+    }
+    let to = match field(to)? {
+        [] => None,
+        bytes if bytes.len() == 20 => Some(Address::from_slice(bytes)),
+        bytes => return Err(format!("recipient is {} bytes", bytes.len())),
+    };
+
+    Ok(DecodedTransaction {
+        nonce: nonce_bytes.iter().fold(0u64, |nonce, byte| (nonce << 8) | *byte as u64),
+        to,
+        input: field(input)?.to_vec(),
+    })
+}
+
+/// Split one item off `data`: whether it is a list, its payload, and what follows it
+fn item(data: &[u8]) -> Result<(bool, &[u8], &[u8]), String> {
+    let prefix = *data.first().ok_or("unexpected end of RLP data")?;
+    let (is_list, offset, length) = match prefix {
+        0x00..=0x7f => return Ok((false, &data[..1], &data[1..])),
+        0x80..=0xb7 => (false, 1, (prefix - 0x80) as usize),
+        0xb8..=0xbf => {
+            let size = (prefix - 0xb7) as usize;
+            (false, 1 + size, be_length(data.get(1..1 + size).ok_or("truncated RLP length")?)?)
+        }
+        0xc0..=0xf7 => (true, 1, (prefix - 0xc0) as usize),
+        0xf8..=0xff => {
+            let size = (prefix - 0xf7) as usize;
+            (true, 1 + size, be_length(data.get(1..1 + size).ok_or("truncated RLP length")?)?)
+        }
+    };
+    let end = offset.checked_add(length).filter(|end| *end <= data.len()).ok_or("truncated RLP item")?;
+    Ok((is_list, &data[offset..end], &data[end..]))
+}
+
+/// Payloads of the items of a list; nested lists (access lists) come back as their payloads
+fn list_items(mut data: &[u8]) -> Result<Vec<&[u8]>, String> {
+    let mut items = Vec::new();
+    while !data.is_empty() {
+        let (_, payload, rest) = item(data)?;
+        items.push(payload);
+        data = rest;
+    }
+    Ok(items)
+}
+
+fn be_length(bytes: &[u8]) -> Result<usize, String> {
+    if bytes.len() > std::mem::size_of::<usize>() {
+        return Err("RLP length too large".to_string());
+    }
+    Ok(bytes.iter().fold(0usize, |length, byte| (length << 8) | *byte as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(bytes: &[u8]) -> Vec<u8> {
+        match bytes {
+            [byte] if *byte < 0x80 => vec![*byte],
+            _ if bytes.len() <= 55 => [vec![0x80 + bytes.len() as u8], bytes.to_vec()].concat(),
+            _ => [vec![0xb8, bytes.len() as u8], bytes.to_vec()].concat(),
+        }
+    }
+
+    fn list(items: &[Vec<u8>]) -> Vec<u8> {
+        let payload = items.concat();
+        let prefix = if payload.len() <= 55 { vec![0xc0 + payload.len() as u8] } else { vec![0xf8, payload.len() as u8] };
+        [prefix, payload].concat()
+    }
+
+    #[test]
+    fn test_recipient_and_input_of_each_envelope() {
+        let to = [0x11u8; 20];
+        let input = vec![0xa9, 0x05, 0x9c, 0xbb, 0x00, 0x01];
+        let expected = DecodedTransaction { nonce: 3, to: Some(Address::from(to)), input: input.clone() };
+
+        // nonce, gasPrice, gas, to, value, data, v, r, s
+        let legacy = list(&[string(&[3]), string(&[1]), string(&[0x52, 0x08]), string(&to), string(&[]), string(&input), string(&[27]), string(&[1; 32]), string(&[2; 32])]);
+        assert_eq!(decode_transaction(&legacy).unwrap(), expected);
+
+        // chainId, nonce, maxPriorityFee, maxFee, gas, to, value, data, accessList, yParity, r, s
+        let eip1559 = [vec![0x02], list(&[
+            string(&[0x7a, 0x69]), string(&[3]), string(&[1]), string(&[2]), string(&[0x52, 0x08]),
+            string(&to), string(&[]), string(&input), list(&[]), string(&[]), string(&[1; 32]), string(&[2; 32]),
+        ])].concat();
+        assert_eq!(decode_transaction(&eip1559).unwrap(), expected);
+
+        // Contract creation has no recipient
+        let create = list(&[string(&[]), string(&[1]), string(&[1]), string(&[]), string(&[]), string(&input), string(&[27]), string(&[1]), string(&[2])]);
+        assert_eq!(decode_transaction(&create).unwrap().to, None);
+
+        assert!(decode_transaction(&[0x03, 0xc0]).is_err());
+        assert!(decode_transaction(&list(&[string(&[3])])).is_err());
+    }
+}
@@ -0,0 +1,232 @@
+// Deterministic treasuries, assets, investors and portfolios for scenarios
+use crate::{abi, HarnessError, MockLog, GENESIS_TIMESTAMP};
+use alloy_primitives::{keccak256, Address, U256};
+use chrono::{DateTime, Duration, NaiveDate};
+use quantera_types::{Currency, TreasuryMetadata, TreasuryType};
+use rust_decimal::{Decimal, RoundingStrategy};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// Transfer amounts are 18-decimal fixed point, as the token contracts use
+const TOKEN_DECIMALS: u32 = 18;
+
+/// Hands out the same addresses, ids and instruments for the same seed and call order
+pub struct Seeder {
+    seed: u64,
+    counter: u64,
+}
+
+impl Seeder {
+    pub fn new(seed: u64) -> Self {
+        Self { seed, counter: 0 }
+    }
+
+    fn next_hash(&mut self, label: &str) -> [u8; 32] {
+        self.counter += 1;
+        let mut preimage = label.as_bytes().to_vec();
+        preimage.extend_from_slice(&self.seed.to_be_bytes());
+        preimage.extend_from_slice(&self.counter.to_be_bytes());
+        keccak256(&preimage).0
+    }
+
+    pub fn address(&mut self) -> Address {
+        Address::from_slice(&self.next_hash("address")[12..])
+    }
+
+    pub fn uuid(&mut self) -> Uuid {
+        let hash = self.next_hash("uuid");
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&hash[..16]);
+        uuid::Builder::from_random_bytes(bytes).into_uuid()
+    }
+
+    /// A treasury issued at genesis with the usual term, face value and yield for its type
+    pub fn treasury(&mut self, treasury_type: TreasuryType) -> SeededTreasury {
+        self.counter += 1;
+        let (label, term_days, yield_rate) = match treasury_type {
+            TreasuryType::TBill => ("26 Week Bill", 182, 450),
+            TreasuryType::TNote => ("5 Year Note", 5 * 365, 425),
+            TreasuryType::TBond => ("30 Year Bond", 30 * 365, 475),
+        };
+        let symbol = format!("E2E{}{}", &format!("{:?}", treasury_type)[1..].to_uppercase(), self.counter);
+        SeededTreasury {
+            name: format!("E2E {} {}", label, self.counter),
+            symbol,
+            treasury_type,
+            face_value: U256::from(1_000u64) * U256::from(10u64).pow(U256::from(TOKEN_DECIMALS)),
+            currency: Currency::USD,
+            yield_rate,
+            issuance_date: GENESIS_TIMESTAMP,
+            maturity_date: GENESIS_TIMESTAMP + term_days * DAY_SECS,
+            issuer: self.address(),
+        }
+    }
+
+    /// An asset token with one daily price per entry of `prices`, the first on the genesis date
+    pub fn asset(&mut self, symbol: &str, prices: &[Decimal]) -> SeededAsset {
+        let first = genesis_date();
+        SeededAsset {
+            address: self.address(),
+            symbol: symbol.to_string(),
+            prices: prices.iter().enumerate()
+                .map(|(day, price)| (first + Duration::days(day as i64), *price))
+                .collect(),
+        }
+    }
+
+    pub fn investor(&mut self, jurisdiction: &str) -> SeededInvestor {
+        let investor_id = self.uuid();
+        let wallet = self.address();
+        SeededInvestor {
+            wallet,
+            investor_id,
+            name: format!("E2E Investor {}", self.counter),
+            jurisdiction: jurisdiction.to_string(),
+        }
+    }
+
+    /// A portfolio holding `amount` of each asset, bought from a seeded market maker
+    pub fn portfolio(&mut self, holdings: &[(&SeededAsset, Decimal)]) -> SeededPortfolio {
+        SeededPortfolio {
+            address: self.address(),
+            counterparty: self.address(),
+            holdings: holdings.iter().map(|(asset, amount)| (asset.address, *amount)).collect(),
+        }
+    }
+}
+
+/// Parameters for `TreasuryService::create_treasury_token`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeededTreasury {
+    pub name: String,
+    pub symbol: String,
+    pub treasury_type: TreasuryType,
+    pub face_value: U256,
+    pub currency: Currency,
+    /// Basis points
+    pub yield_rate: u64,
+    pub issuance_date: u64,
+    pub maturity_date: u64,
+    pub issuer: Address,
+}
+
+impl SeededTreasury {
+    pub fn metadata(&self) -> TreasuryMetadata {
+        TreasuryMetadata {
+            name: self.name.clone(),
+            symbol: self.symbol.clone(),
+            description: format!("{} seeded for end-to-end tests", self.name),
+            issuer_name: "E2E Treasury Issuer".to_string(),
+            treasury_type: self.treasury_type,
+            face_value: self.face_value.to_string(),
+            currency: self.currency,
+            issuance_date: self.issuance_date,
+            maturity_date: self.maturity_date,
+            yield_rate: self.yield_rate,
+            image_uri: None,
+            external_url: None,
+            additional_details: None,
+            tranche: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeededAsset {
+    pub address: Address,
+    pub symbol: String,
+    pub prices: Vec<(NaiveDate, Decimal)>,
+}
+
+impl SeededAsset {
+    /// Write the daily prices to `asset_price_history`, where the risk service reads them
+    pub async fn insert_price_history(&self, db: &PgPool) -> Result<(), HarnessError> {
+        for (date, price) in &self.prices {
+            sqlx::query(r#"
+                INSERT INTO asset_price_history (asset_address, price_date, price, source)
+                VALUES ($1, $2, $3::numeric, 'e2e_seed')
+                ON CONFLICT (asset_address, price_date) DO UPDATE SET price = EXCLUDED.price
+            "#)
+                .bind(format!("0x{}", hex::encode(self.address)))
+                .bind(date)
+                .bind(price.to_string())
+                .execute(db)
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub fn latest_price(&self) -> Option<Decimal> {
+        self.prices.last().map(|(_, price)| *price)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeededInvestor {
+    pub wallet: Address,
+    pub investor_id: Uuid,
+    pub name: String,
+    pub jurisdiction: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeededPortfolio {
+    pub address: Address,
+    /// Sender of the transfers that fund the portfolio
+    pub counterparty: Address,
+    pub holdings: Vec<(Address, Decimal)>,
+}
+
+impl SeededPortfolio {
+    /// `Transfer` logs moving each holding into the portfolio, ready for `MockChain::mine_logs`
+    pub fn transfer_logs(&self) -> Vec<MockLog> {
+        let topic = abi::event_topic("Transfer(address,address,uint256)");
+        self.holdings.iter()
+            .map(|(asset, amount)| MockLog {
+                address: *asset,
+                topics: vec![topic, abi::address_topic(self.counterparty), abi::address_topic(self.address)],
+                data: abi::word(base_units(*amount)).to_vec(),
+            })
+            .collect()
+    }
+}
+
+/// `amount` as an 18-decimal integer; fractions beyond 18 places are dropped
+fn base_units(amount: Decimal) -> U256 {
+    let mut scaled = amount.round_dp_with_strategy(TOKEN_DECIMALS, RoundingStrategy::ToZero);
+    scaled.rescale(TOKEN_DECIMALS);
+    U256::from(scaled.mantissa().unsigned_abs())
+}
+
+fn genesis_date() -> NaiveDate {
+    DateTime::from_timestamp(GENESIS_TIMESTAMP as i64, 0)
+        .expect("genesis timestamp is in range")
+        .date_naive()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_fixtures() {
+        let build = |seed| {
+            let mut seeder = Seeder::new(seed);
+            let asset = seeder.asset("QTB", &[Decimal::new(9950, 2), Decimal::new(9975, 2)]);
+            let portfolio = seeder.portfolio(&[(&asset, Decimal::new(15, 1))]);
+            (seeder.treasury(TreasuryType::TBill), seeder.investor("US"), asset, portfolio)
+        };
+        assert_eq!(build(7), build(7));
+        assert_ne!(build(7).1.wallet, build(8).1.wallet);
+
+        let (treasury, _, asset, portfolio) = build(7);
+        assert_eq!(TreasuryType::for_term(treasury.issuance_date, treasury.maturity_date), TreasuryType::TBill);
+        assert_eq!(asset.prices[1].0, NaiveDate::from_ymd_opt(2026, 1, 2).unwrap());
+
+        let logs = portfolio.transfer_logs();
+        assert_eq!(logs[0].topics[2], abi::address_topic(portfolio.address));
+        assert_eq!(U256::from_be_slice(&logs[0].data), U256::from(1_500_000_000_000_000_000u64));
+    }
+}
@@ -0,0 +1,232 @@
+// Investor onboarding: KYC, profile, compliance check with an encrypted report, PEP re-screening,
+// beneficial owner screening of institutions
+use chrono::{Duration, NaiveDate, Utc};
+use compliance_service::beneficial_owners::{BeneficialOwner, UboStatus};
+use compliance_service::config::Config;
+use compliance_service::kyc::{KycParams, KycStatus};
+use compliance_service::monitoring::{AmlRule, AmlStatus};
+use compliance_service::self_service;
+use compliance_service::{ComplianceService, InvestorProfile};
+use rust_decimal::Decimal;
+use quantera_test_harness::{
+    raw_cid,
+    IpfsFixture,
+    KycFixture,
+    MockChain,
+    SanctionsFixture,
+    Seeder,
+    TestDatabase,
+    TestRedis,
+    KYC_CHECK_ID,
+};
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+
+type EthAddress = ethers::types::Address;
+
+const COMPLIANCE_ENGINE: &str = "0x00000000000000000000000000000000c0ffee01";
+
+/// Service configuration pointing every provider at a fixture
+fn compliance_config(db: &TestDatabase, redis: &TestRedis, chain: &MockChain, kyc: &KycFixture, pep: &SanctionsFixture, ipfs: &IpfsFixture) -> Config {
+    std::env::set_var("DATABASE_URL", db.url());
+    std::env::set_var("REDIS_URL", redis.url());
+    std::env::set_var("ETH_RPC_URL", chain.url());
+    std::env::set_var("COMPLIANCE_ENGINE_ADDRESS", COMPLIANCE_ENGINE);
+    let mut config = Config::from_env().expect("required settings are set");
+
+    // Anything a developer's .env enables beyond the fixtures is switched off
+    config.jumio_api_key = None;
+    config.jumio_api_secret = None;
+    config.onfido_api_token = Some("e2e-token".to_string());
+    config.onfido_api_url = Some(kyc.uri());
+    config.pep_screening_api_url = Some(pep.uri());
+    config.pep_screening_api_key = Some("e2e-key".to_string());
+    config.ipfs_api_url = ipfs.uri();
+    config.ipfs_gateway_urls = Vec::new();
+    config.ipfs_replica_pin_url = None;
+    config.identity_registry_address = None;
+    config.identity_registry_signer_key = None;
+    config.position_service_url = None;
+    config.aml_rules_path = None;
+    config
+}
+
+#[tokio::test]
+async fn test_investor_onboarded_checked_and_rescreened() {
+    let (Some(db), Some(redis)) = (TestDatabase::from_env().await.unwrap(), TestRedis::from_env().await.unwrap()) else {
+        return;
+    };
+    let mut seeder = Seeder::new(1503);
+    let investor = seeder.investor("US");
+    let wallet = EthAddress::from_slice(investor.wallet.as_slice());
+
+    let chain = MockChain::start().await.unwrap();
+    let kyc = KycFixture::clear().await;
+    let pep = SanctionsFixture::start(&[investor.wallet]).await;
+    let ipfs = IpfsFixture::start().await;
+    let config = compliance_config(&db, &redis, &chain, &kyc, &pep, &ipfs);
+    let service = ComplianceService::new(config, db.url(), redis.url(), &chain.url(), COMPLIANCE_ENGINE.parse().unwrap())
+        .await
+        .unwrap();
+
+    // KYC through the provider
+    let kyc_result = service.verify_kyc(KycParams {
+        investor_id: self_service::investor_id(wallet),
+        document_type: "passport".to_string(),
+        country: investor.jurisdiction.clone(),
+        metadata: HashMap::from([("first_name".to_string(), investor.name.clone())]),
+    }).await.unwrap();
+    assert!(kyc_result.verified);
+    assert_eq!(kyc_result.verification_id, KYC_CHECK_ID);
+    assert_eq!(kyc_result.kyc_level, 2);
+
+    // Profile
+    let now = Utc::now();
+    service.update_investor_profile(InvestorProfile {
+        address: wallet,
+        jurisdiction: investor.jurisdiction.clone(),
+        kyc_level: kyc_result.kyc_level,
+        kyc_expiry: kyc_result.expiry,
+        kyc_status: KycStatus::Completed,
+        aml_status: AmlStatus::Clear,
+        investment_blocked: false,
+        accreditation_level: 1,
+        risk_score: 10,
+        total_invested: dec!(0),
+        documents_ipfs: Vec::new(),
+        last_check: now,
+        pep: false,
+        sanctioned: false,
+    }).await.unwrap();
+
+    // Compliance check; the report is stored encrypted under its content hash
+    let report = service.perform_compliance_check(wallet, &investor.jurisdiction, dec!(25000), None).await.unwrap();
+    assert!(report.violations.is_empty(), "{:?}", report.violations);
+    assert!(!report.sanctions_result.is_sanctioned);
+    let cid = report.ipfs_hash.clone().expect("report uploaded");
+    let stored = ipfs.content(&cid).expect("report pinned on the node");
+    assert_eq!(raw_cid(&stored), cid);
+    assert!(!String::from_utf8_lossy(&stored).contains(&investor.jurisdiction), "report is encrypted at rest");
+    assert_eq!(kyc.applicants_created().await, 2);
+
+    // The PEP provider flags the investor at the next scheduled screening
+    let run = service.run_rescreening(now + Duration::days(400)).await.unwrap();
+    assert_eq!(run.screened, 1);
+    assert_eq!(run.hits.len(), 1);
+    assert_eq!(run.hits[0].rule, AmlRule::PepMatch);
+    assert!(run.hits[0].alert_id.is_some());
+    assert_eq!(pep.screenings().await, 1);
+    assert!(service.get_investor_profile(wallet).await.unwrap().unwrap().pep);
+
+    db.drop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_rejected_kyc_blocks_the_check() {
+    let (Some(db), Some(redis)) = (TestDatabase::from_env().await.unwrap(), TestRedis::from_env().await.unwrap()) else {
+        return;
+    };
+    let investor = Seeder::new(1504).investor("GB");
+    let wallet = EthAddress::from_slice(investor.wallet.as_slice());
+
+    let chain = MockChain::start().await.unwrap();
+    let kyc = KycFixture::rejecting().await;
+    let pep = SanctionsFixture::start(&[]).await;
+    let ipfs = IpfsFixture::start().await;
+    let config = compliance_config(&db, &redis, &chain, &kyc, &pep, &ipfs);
+    let service = ComplianceService::new(config, db.url(), redis.url(), &chain.url(), COMPLIANCE_ENGINE.parse().unwrap())
+        .await
+        .unwrap();
+
+    let report = service.perform_compliance_check(wallet, &investor.jurisdiction, dec!(1000), None).await.unwrap();
+    assert!(report.violations.iter().any(|v| v.violation_type == "KYC_FAILED"));
+    assert!(report.recommendations.iter().any(|r| r.contains("blocked")));
+    // A failed check is still reported and stored
+    assert!(report.ipfs_hash.is_some());
+
+    db.drop().await.unwrap();
+}
+
+fn beneficial_owner(name: &str, ownership_percent: Decimal) -> BeneficialOwner {
+    BeneficialOwner {
+        full_name: name.to_string(),
+        date_of_birth: NaiveDate::from_ymd_opt(1968, 3, 9).unwrap(),
+        nationality: "DE".to_string(),
+        ownership_percent,
+    }
+}
+
+#[tokio::test]
+async fn test_clear_institution_with_a_sanctioned_owner_is_flagged() {
+    let (Some(db), Some(redis)) = (TestDatabase::from_env().await.unwrap(), TestRedis::from_env().await.unwrap()) else {
+        return;
+    };
+    let institution = Seeder::new(1507).investor("DE");
+    let wallet = EthAddress::from_slice(institution.wallet.as_slice());
+
+    let chain = MockChain::start().await.unwrap();
+    let kyc = KycFixture::clear().await;
+    let pep = SanctionsFixture::start(&[]).await;
+    let ipfs = IpfsFixture::start().await;
+    let config = compliance_config(&db, &redis, &chain, &kyc, &pep, &ipfs);
+    let service = ComplianceService::new(config, db.url(), redis.url(), &chain.url(), COMPLIANCE_ENGINE.parse().unwrap())
+        .await
+        .unwrap();
+
+    service.update_investor_profile(InvestorProfile {
+        address: wallet,
+        jurisdiction: institution.jurisdiction.clone(),
+        kyc_level: 2,
+        kyc_expiry: Utc::now() + Duration::days(365),
+        kyc_status: KycStatus::Completed,
+        aml_status: AmlStatus::Clear,
+        investment_blocked: false,
+        accreditation_level: 2,
+        risk_score: 10,
+        total_invested: dec!(0),
+        documents_ipfs: Vec::new(),
+        last_check: Utc::now(),
+        pep: false,
+        sanctioned: false,
+    }).await.unwrap();
+
+    // The institution itself screens clear, but one of its owners holds 30% and is listed
+    let owners = service.update_beneficial_owners(wallet, vec![
+        beneficial_owner("Clean Founder", dec!(55)),
+        beneficial_owner("UN Sanctioned Individual", dec!(30)),
+        beneficial_owner("Small Holder", dec!(15)),
+    ], "officer-1").await.unwrap();
+    assert_eq!(owners.len(), 2, "owners below the threshold are not recorded");
+    assert_eq!(owners[1].status, UboStatus::Sanctioned);
+    assert_eq!(owners[0].status, UboStatus::Clear);
+
+    let profile = service.get_investor_profile(wallet).await.unwrap().unwrap();
+    assert!(profile.sanctioned);
+    assert_eq!(profile.aml_status, AmlStatus::UnderReview);
+    let alerts = service.get_aml_alerts(None).await.unwrap();
+    assert!(alerts.iter().any(|alert| alert.investor == wallet && alert.rule == AmlRule::SanctionsMatch));
+
+    let report = service.perform_compliance_check(wallet, &institution.jurisdiction, dec!(1000), None).await.unwrap();
+    assert!(!report.sanctions_result.is_sanctioned);
+    assert_eq!(report.beneficial_owners.len(), 2);
+    let hit = report.violations.iter().find(|v| v.violation_type == "UBO_SANCTIONS_HIT").expect("owner hit reported");
+    assert!(hit.description.contains("UN Sanctioned Individual (30%)"), "{}", hit.description);
+
+    // Unchanged owners keep their screening; only the new one is looked up
+    let before = pep.person_screenings().await;
+    service.update_beneficial_owners(wallet, vec![
+        beneficial_owner("Clean Founder", dec!(40)),
+        beneficial_owner("New Partner", dec!(30)),
+    ], "officer-1").await.unwrap();
+    assert_eq!(pep.person_screenings().await, before + 1);
+    let audit: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM compliance_audit_log WHERE event_type = 'UBO_REGISTER_UPDATED' AND entity_id = $1"
+    )
+        .bind(format!("{:?}", wallet))
+        .fetch_one(db.pool())
+        .await
+        .unwrap();
+    assert_eq!(audit, 2);
+
+    db.drop().await.unwrap();
+}
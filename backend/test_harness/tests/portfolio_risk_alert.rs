@@ -0,0 +1,254 @@
+// Portfolio risk: on-chain acquisitions and price history feed a concentration alert
+use quantera_test_harness::{MockChain, Seeder, TestDatabase, TestRedis, WebhookFixture};
+use risk_service::ethereum_client::EthereumClient;
+use risk_service::webhooks::{sign, WebhookConfig};
+use risk_service::{AlertSeverity, AlertStatus, AlertType, RiskService, RiskServiceError, VaRMethod};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use std::time::Duration;
+
+type EthAddress = ethers::types::Address;
+
+/// Blocks mined on top of the transfers so they clear the default 12-block confirmation depth
+const CONFIRMATIONS: u64 = 12;
+
+#[tokio::test]
+async fn test_single_asset_portfolio_raises_concentration_alert() {
+    let (Some(db), Some(redis)) = (TestDatabase::from_env().await.unwrap(), TestRedis::from_env().await.unwrap()) else {
+        return;
+    };
+    let mut seeder = Seeder::new(1503);
+    let prices: Vec<Decimal> = (0..60).map(|day| dec!(100) + Decimal::from(day % 7) - dec!(3)).collect();
+    let asset = seeder.asset("E2EQT", &prices);
+    let portfolio = seeder.portfolio(&[(&asset, dec!(250))]);
+    asset.insert_price_history(db.pool()).await.unwrap();
+
+    let chain = MockChain::start().await.unwrap();
+    chain.mine_logs(portfolio.transfer_logs());
+    chain.mine(CONFIRMATIONS);
+
+    let client = EthereumClient::new(&chain.url()).await.expect("client connects to the mock chain");
+    let service = RiskService::new(Arc::new(client), db.url(), redis.url(), EthAddress::repeat_byte(0x5e))
+        .await
+        .unwrap();
+    let portfolio_address = EthAddress::from_slice(portfolio.address.as_slice());
+
+    let alerts = service.monitor_risk_limits(portfolio_address).await.unwrap();
+    let concentration = alerts.iter()
+        .find(|alert| alert.alert_type == AlertType::ConcentrationRisk)
+        .expect("single-asset portfolio breaches the concentration limit");
+    assert_eq!(concentration.metric_value, Decimal::ONE);
+    assert_eq!(concentration.severity, AlertSeverity::Warning);
+    assert_eq!(concentration.status, AlertStatus::Open);
+
+    // A second cycle refreshes the open alert instead of opening another
+    service.monitor_risk_limits(portfolio_address).await.unwrap();
+    let tracked = service.get_alerts(portfolio_address, Some(AlertStatus::Open)).await;
+    let tracked: Vec<_> = tracked.iter().filter(|t| t.alert.alert_type == AlertType::ConcentrationRisk).collect();
+    assert_eq!(tracked.len(), 1);
+    assert_eq!(tracked[0].alert.occurrence_count, 2);
+
+    // The refresh falls within the notification cooldown, so the stored row is left alone
+    let (stored_count,): (i32,) = sqlx::query_as("SELECT occurrence_count FROM risk_alerts WHERE id = $1")
+        .bind(concentration.id)
+        .fetch_one(db.pool())
+        .await
+        .unwrap();
+    assert_eq!(stored_count, 1);
+
+    // A restarted service picks the open alert back up instead of opening a duplicate
+    let client = EthereumClient::new(&chain.url()).await.expect("client connects to the mock chain");
+    let restarted = RiskService::new(Arc::new(client), db.url(), redis.url(), EthAddress::repeat_byte(0x5e))
+        .await
+        .unwrap();
+    assert!(restarted.restore_open_alerts().await.unwrap() >= 1);
+    restarted.monitor_risk_limits(portfolio_address).await.unwrap();
+    let active = restarted.list_active_alerts(portfolio_address).await;
+    let restored = active.iter()
+        .find(|alert| alert.alert_type == AlertType::ConcentrationRisk)
+        .unwrap();
+    assert_eq!((restored.id, restored.occurrence_count), (concentration.id, 2));
+
+    // The position was reconstructed from the transfer at the seeded price
+    let (amount, entry_price): (String, String) = sqlx::query_as(
+        "SELECT amount::text, entry_price::text FROM position_cost_basis WHERE portfolio_address = $1",
+    )
+        .bind(format!("{:?}", portfolio_address))
+        .fetch_one(db.pool())
+        .await
+        .unwrap();
+    assert_eq!(amount.parse::<Decimal>().unwrap(), dec!(250));
+    assert_eq!(entry_price.parse::<Decimal>().unwrap(), prices[0]);
+
+    db.drop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_portfolio_limit_overrides_default_and_reverts_on_delete() {
+    let (Some(db), Some(redis)) = (TestDatabase::from_env().await.unwrap(), TestRedis::from_env().await.unwrap()) else {
+        return;
+    };
+    let mut seeder = Seeder::new(1509);
+    let prices: Vec<Decimal> = (0..60).map(|day| dec!(100) + Decimal::from(day % 5) - dec!(2)).collect();
+    let asset = seeder.asset("E2EQL", &prices);
+    let portfolio = seeder.portfolio(&[(&asset, dec!(40))]);
+    asset.insert_price_history(db.pool()).await.unwrap();
+
+    let chain = MockChain::start().await.unwrap();
+    chain.mine_logs(portfolio.transfer_logs());
+    chain.mine(CONFIRMATIONS);
+
+    let client = EthereumClient::new(&chain.url()).await.expect("client connects to the mock chain");
+    let service = RiskService::new(Arc::new(client), db.url(), redis.url(), EthAddress::repeat_byte(0x5e))
+        .await
+        .unwrap();
+    let portfolio_address = EthAddress::from_slice(portfolio.address.as_slice());
+
+    // Read once so the defaults are cached before the write
+    assert_eq!(service.list_risk_limits(portfolio_address).await.unwrap().len(), 3);
+    let err = service.set_risk_limit(portfolio_address, "max_sharpe", dec!(1), None).await.unwrap_err();
+    assert!(matches!(err, RiskServiceError::InvalidInput(_)), "{}", err);
+
+    let limit = service.set_risk_limit(portfolio_address, "max_concentration", dec!(0.5), Some(AlertSeverity::Critical))
+        .await
+        .unwrap();
+    assert!(limit.custom);
+    let limits = service.list_risk_limits(portfolio_address).await.unwrap();
+    let concentration = limits.iter().find(|limit| limit.name == "max_concentration").unwrap();
+    assert_eq!((concentration.value, &concentration.severity), (dec!(0.5), &AlertSeverity::Critical));
+
+    let alerts = service.monitor_risk_limits(portfolio_address).await.unwrap();
+    let alert = alerts.iter()
+        .find(|alert| alert.alert_type == AlertType::ConcentrationRisk)
+        .expect("single-asset portfolio breaches the custom limit");
+    assert_eq!(alert.limit, "max_concentration");
+    assert_eq!((alert.metric_value, alert.threshold), (Decimal::ONE, dec!(0.5)));
+    assert_eq!(alert.severity, AlertSeverity::Critical);
+    assert!(alert.message.starts_with("Concentration risk exceeds limit"), "{}", alert.message);
+
+    assert!(service.delete_risk_limit(portfolio_address, "max_concentration").await.unwrap());
+    assert!(!service.delete_risk_limit(portfolio_address, "max_concentration").await.unwrap());
+    let limits = service.list_risk_limits(portfolio_address).await.unwrap();
+    let concentration = limits.iter().find(|limit| limit.name == "max_concentration").unwrap();
+    assert_eq!((concentration.value, concentration.custom), (dec!(0.4), false));
+
+    db.drop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_alert_webhooks_are_signed_retried_and_dead_lettered() {
+    let (Some(db), Some(redis)) = (TestDatabase::from_env().await.unwrap(), TestRedis::from_env().await.unwrap()) else {
+        return;
+    };
+    let mut seeder = Seeder::new(1511);
+    let prices: Vec<Decimal> = (0..60).map(|day| dec!(100) + Decimal::from(day % 3) - dec!(1)).collect();
+    let asset = seeder.asset("E2EQW", &prices);
+    let portfolio = seeder.portfolio(&[(&asset, dec!(75))]);
+    asset.insert_price_history(db.pool()).await.unwrap();
+
+    let chain = MockChain::start().await.unwrap();
+    chain.mine_logs(portfolio.transfer_logs());
+    chain.mine(CONFIRMATIONS);
+
+    let client = EthereumClient::new(&chain.url()).await.expect("client connects to the mock chain");
+    let service = RiskService::new(Arc::new(client), db.url(), redis.url(), EthAddress::repeat_byte(0x5e))
+        .await
+        .unwrap()
+        .with_webhook_config(WebhookConfig {
+            timeout: Duration::from_secs(2),
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(20),
+            max_backoff: Duration::from_millis(100),
+        })
+        .unwrap();
+    let portfolio_address = EthAddress::from_slice(portfolio.address.as_slice());
+
+    let flaky = WebhookFixture::failing_first(2).await;
+    let down = WebhookFixture::down().await;
+    let info_only = WebhookFixture::failing_first(0).await;
+    let secret = "e2e-webhook-secret-0001";
+    let err = service.register_webhook(portfolio_address, "ftp://example.com", secret, &[AlertSeverity::Warning]).await.unwrap_err();
+    assert!(matches!(err, RiskServiceError::InvalidInput(_)), "{}", err);
+    let flaky_hook = service.register_webhook(portfolio_address, &flaky.url(), secret, &[AlertSeverity::Warning, AlertSeverity::Critical]).await.unwrap();
+    let down_hook = service.register_webhook(portfolio_address, &down.url(), secret, &[AlertSeverity::Warning]).await.unwrap();
+    service.register_webhook(portfolio_address, &info_only.url(), secret, &[AlertSeverity::Info]).await.unwrap();
+    let listed = service.list_webhooks(portfolio_address).await.unwrap();
+    assert_eq!(listed.len(), 3);
+    assert!(serde_json::to_value(&listed[0]).unwrap().get("secret").is_none());
+
+    // Prices within 1% of 100 keep VaR and drawdown in bounds, so concentration is the only alert
+    let alerts = service.monitor_risk_limits(portfolio_address).await.unwrap();
+    assert_eq!(alerts.len(), 1, "{:?}", alerts);
+    let concentration = &alerts[0];
+    assert_eq!((&concentration.alert_type, &concentration.severity), (&AlertType::ConcentrationRisk, &AlertSeverity::Warning));
+
+    // Deliveries run in the background; wait for the retries to play out
+    let mut dead_letters = Vec::new();
+    for _ in 0..100 {
+        dead_letters = service.webhook_dead_letters(portfolio_address).await.unwrap();
+        if !dead_letters.is_empty() && flaky.deliveries().await.len() >= 3 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    // Two 500s, then delivered on the third attempt with the same signed body
+    let deliveries = flaky.deliveries().await;
+    assert_eq!(deliveries.len(), 3);
+    for delivery in &deliveries {
+        assert_eq!(delivery.signature.as_deref(), Some(sign(secret, &delivery.body).as_str()));
+        assert_eq!(delivery.delivery_id, deliveries[0].delivery_id);
+        assert_eq!(delivery.body, deliveries[0].body);
+    }
+    let payload: serde_json::Value = serde_json::from_slice(&deliveries[0].body).unwrap();
+    assert_eq!(payload["webhook_id"], flaky_hook.id.to_string());
+    assert_eq!(payload["alert"]["id"], concentration.id.to_string());
+    assert_eq!(payload["alert"]["alert_type"], "ConcentrationRisk");
+
+    // The endpoint that never recovered is dead-lettered after its last attempt
+    assert_eq!(dead_letters.len(), 1);
+    let dead = &dead_letters[0];
+    assert_eq!((dead.webhook_id, dead.alert_id, dead.attempts, dead.last_status), (down_hook.id, concentration.id, 3, Some(500)));
+    assert_eq!(dead.payload["alert"]["id"], concentration.id.to_string());
+    assert_eq!(down.deliveries().await.len(), 3);
+    assert!(info_only.deliveries().await.is_empty());
+
+    assert!(service.delete_webhook(portfolio_address, down_hook.id).await.unwrap());
+    assert!(!service.delete_webhook(portfolio_address, down_hook.id).await.unwrap());
+    assert_eq!(service.list_webhooks(portfolio_address).await.unwrap().len(), 2);
+
+    db.drop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_position_sync_failures_are_not_an_empty_portfolio() {
+    let (Some(db), Some(redis)) = (TestDatabase::from_env().await.unwrap(), TestRedis::from_env().await.unwrap()) else {
+        return;
+    };
+    let mut seeder = Seeder::new(1504);
+    let asset = seeder.asset("E2EQE", &[dec!(100)]);
+    let portfolio = seeder.portfolio(&[(&asset, dec!(10))]);
+    let empty = EthAddress::repeat_byte(0xe0);
+
+    let chain = MockChain::start().await.unwrap();
+    chain.mine(CONFIRMATIONS);
+    let client = EthereumClient::new(&chain.url()).await.expect("client connects to the mock chain");
+    let service = RiskService::new(Arc::new(client), db.url(), redis.url(), EthAddress::repeat_byte(0x5e))
+        .await
+        .unwrap();
+
+    // Nothing was ever transferred to this address
+    let err = service.calculate_portfolio_risk(empty, VaRMethod::Historical).await.unwrap_err();
+    assert!(matches!(err, RiskServiceError::PortfolioNotFound(_)), "{}", err);
+
+    // With the node gone, the backfill fails loudly, and keeps failing until it succeeds
+    drop(chain);
+    let portfolio_address = EthAddress::from_slice(portfolio.address.as_slice());
+    for _ in 0..2 {
+        let err = service.calculate_portfolio_risk(portfolio_address, VaRMethod::Historical).await.unwrap_err();
+        assert!(matches!(err, RiskServiceError::EthereumError(_)), "{}", err);
+    }
+
+    db.drop().await.unwrap();
+}
@@ -0,0 +1,52 @@
+// Risk price history: bulk import, partial coverage and the Redis cache in front of Postgres
+use chrono::{Duration, TimeZone, Utc};
+use quantera_test_harness::{MockChain, Seeder, TestDatabase, TestRedis};
+use risk_service::ethereum_client::EthereumClient;
+use risk_service::{RiskService, RiskServiceError};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+
+type EthAddress = ethers::types::Address;
+
+#[tokio::test]
+async fn test_history_served_from_cache_until_a_price_is_recorded() {
+    let (Some(db), Some(redis)) = (TestDatabase::from_env().await.unwrap(), TestRedis::from_env().await.unwrap()) else {
+        return;
+    };
+    let mut seeder = Seeder::new(1505);
+    let priced = EthAddress::from_slice(seeder.asset("E2EPH", &[]).address.as_slice());
+    let unpriced = EthAddress::from_slice(seeder.asset("E2ENP", &[]).address.as_slice());
+
+    let chain = MockChain::start().await.unwrap();
+    let client = EthereumClient::new(&chain.url()).await.expect("client connects to the mock chain");
+    let service = RiskService::new(Arc::new(client), db.url(), redis.url(), EthAddress::repeat_byte(0x5e))
+        .await
+        .unwrap();
+
+    let first = Utc.with_ymd_and_hms(2024, 6, 3, 16, 0, 0).unwrap();
+    let prices: Vec<_> = (0..40).map(|day| (first + Duration::days(day), dec!(100) + Decimal::from(day % 5))).collect();
+    assert_eq!(service.backfill_price_history(vec![(priced, prices)]).await.unwrap(), 40);
+
+    // One held asset without history makes the portfolio's history insufficient
+    let err = service.price_history(&[priced, unpriced]).await.unwrap_err();
+    assert!(matches!(err, RiskServiceError::InsufficientData), "{}", err);
+    let history = service.price_history(&[priced]).await.unwrap();
+    assert_eq!(history.len(), 40);
+    assert_eq!(history[0], vec![dec!(100)]);
+
+    // Later calculations are served from Redis, so they agree even if Postgres is busy or changed underneath
+    sqlx::query("DELETE FROM asset_price_history WHERE asset_address = $1")
+        .bind(format!("{:?}", priced))
+        .execute(db.pool())
+        .await
+        .unwrap();
+    assert_eq!(service.price_history(&[priced]).await.unwrap(), history);
+
+    // Recording a price drops the cached history
+    service.record_price(priced, dec!(101), Utc::now()).await.unwrap();
+    let err = service.price_history(&[priced]).await.unwrap_err();
+    assert!(matches!(err, RiskServiceError::InsufficientData), "{}", err);
+
+    db.drop().await.unwrap();
+}
@@ -0,0 +1,74 @@
+// Risk scenarios: price shocks revalue synced positions and stressed markets re-run VaR
+use quantera_test_harness::{MockChain, Seeder, TestDatabase, TestRedis};
+use risk_service::ethereum_client::EthereumClient;
+use risk_service::{MarketScenario, RiskService, RiskServiceError};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+type EthAddress = ethers::types::Address;
+
+/// Blocks mined on top of the transfers so they clear the default 12-block confirmation depth
+const CONFIRMATIONS: u64 = 12;
+
+#[tokio::test]
+async fn test_shock_on_the_only_asset_moves_the_portfolio_by_the_same_share() {
+    let (Some(db), Some(redis)) = (TestDatabase::from_env().await.unwrap(), TestRedis::from_env().await.unwrap()) else {
+        return;
+    };
+    let mut seeder = Seeder::new(1512);
+    let prices: Vec<Decimal> = (0..60).map(|day| dec!(100) + Decimal::from((day * 7) % 9) - dec!(4)).collect();
+    let asset = seeder.asset("E2EQS", &prices);
+    let portfolio = seeder.portfolio(&[(&asset, dec!(120))]);
+    asset.insert_price_history(db.pool()).await.unwrap();
+
+    let chain = MockChain::start().await.unwrap();
+    chain.mine_logs(portfolio.transfer_logs());
+    chain.mine(CONFIRMATIONS);
+
+    let client = EthereumClient::new(&chain.url()).await.expect("client connects to the mock chain");
+    let service = RiskService::new(Arc::new(client), db.url(), redis.url(), EthAddress::repeat_byte(0x5e))
+        .await
+        .unwrap()
+        .with_monte_carlo_seed(1512)
+        .with_monte_carlo_simulations(4_000);
+    let portfolio_address = EthAddress::from_slice(portfolio.address.as_slice());
+    let asset_address = EthAddress::from_slice(asset.address.as_slice());
+
+    let halved = MarketScenario {
+        name: "asset halves".to_string(),
+        price_shocks: HashMap::from([(asset_address, dec!(-0.5))]),
+        default_shock: Decimal::ZERO,
+        volatility_multiplier: Decimal::ONE,
+        correlation_adjustment: Decimal::ZERO,
+    };
+    let builtin = service.list_builtin_scenarios();
+    let spike = builtin.iter().find(|scenario| scenario.name == "volatility spike").unwrap().clone();
+    let crisis = builtin.iter().find(|scenario| scenario.name == "2008 crisis").unwrap().clone();
+
+    let outcomes = service.predict_risk_scenarios(portfolio_address, vec![halved, spike, crisis]).await.unwrap();
+    let outcome = |name: &str| outcomes.iter().find(|outcome| outcome.scenario.name == name).unwrap();
+
+    let halved = outcome("asset halves");
+    assert!((halved.portfolio_value_change - dec!(-0.5)).abs() < dec!(0.0001), "{}", halved.portfolio_value_change);
+    assert_eq!(halved.stressed_value * dec!(2), halved.baseline_value);
+    // One asset keeps its weight, and the market is not stressed, so VaR is unchanged
+    assert_eq!(halved.var_impact, Decimal::ZERO);
+    assert_eq!(halved.probability, Decimal::ZERO);
+
+    let spike = outcome("volatility spike");
+    assert_eq!(spike.portfolio_value_change, Decimal::ZERO);
+    assert!(spike.var_impact > Decimal::ZERO, "{}", spike.var_impact);
+    assert!(spike.stressed_var_95 > spike.baseline_var_95 * dec!(1.5));
+
+    assert_eq!(outcome("2008 crisis").portfolio_value_change, dec!(-0.45));
+    // Best outcome first
+    assert_eq!(outcomes[0].scenario.name, "volatility spike");
+
+    let invalid = MarketScenario { volatility_multiplier: Decimal::ZERO, ..builtin[0].clone() };
+    let err = service.predict_risk_scenarios(portfolio_address, vec![invalid]).await.unwrap_err();
+    assert!(matches!(err, RiskServiceError::InvalidInput(_)), "{}", err);
+
+    db.drop().await.unwrap();
+}
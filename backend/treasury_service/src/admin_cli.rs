@@ -0,0 +1,609 @@
+// Administration commands for the treasury_admin binary
+use crate::{
+    BusinessCalendar,
+    ContractAddressRegistry,
+    ContractName,
+    DistributionPreview,
+    Error,
+    HoldingsSync,
+    HoldingsSyncConfig,
+    IpfsClient,
+    MaturityResult,
+    MulticallBalanceReader,
+    RegistryTokenUniverse,
+    TreasuryInfo,
+    TreasuryOverview,
+    TreasuryRegistration,
+    TreasuryRegistryClient,
+    TreasuryService,
+    TreasuryStatus,
+    TreasuryType,
+    WithholdingTable,
+    YieldDistributionResult,
+    YieldSchedulerService,
+};
+use alloy_primitives::{Address, U256};
+use ethereum_client::{StallPolicy, MIN_FEE_BUMP_PERCENT};
+use quantera_types::FinalityPolicy;
+use quantera_types::wire::{self, format_token_id};
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Every requested change was made
+pub const EXIT_SUCCESS: i32 = 0;
+
+/// The command ran but at least one operation failed
+pub const EXIT_FAILURE: i32 = 1;
+
+/// The command was refused before doing anything: bad input or a missing --yes
+pub const EXIT_USAGE: i32 = 2;
+
+/// Registry and IPFS settings, read from the same environment variables as the server
+#[derive(Debug, Clone)]
+pub struct RegistryConfig {
+    pub ethereum_rpc_url: String,
+    pub registry_address: Address,
+    /// Addresses of every contract for DEPLOYMENT_ENV
+    pub contracts: ContractAddressRegistry,
+    pub ipfs_url: String,
+    /// Speed-up of stalled price updates and yield distributions; None when TX_STALL_TIMEOUT_SECS is 0
+    pub stall_policy: Option<StallPolicy>,
+    /// Confirmations receipts wait for, from CHAIN_ID's defaults and FINALITY_* overrides
+    pub finality: FinalityPolicy,
+}
+
+impl RegistryConfig {
+    pub fn from_env() -> Result<Self, Error> {
+        let ethereum_rpc_url = std::env::var("ETHEREUM_RPC_URL")
+            .unwrap_or_else(|_| "http://localhost:8545".to_string());
+
+        let contracts = ContractAddressRegistry::from_env()?;
+        let registry_address = contracts.get(ContractName::TreasuryRegistry)?;
+
+        let ipfs_url = std::env::var("IPFS_URL")
+            .unwrap_or_else(|_| "http://localhost:5001".to_string());
+
+        let defaults = StallPolicy::default();
+        let stall_timeout_secs = env_number("TX_STALL_TIMEOUT_SECS", defaults.stall_timeout.as_secs())?;
+        let fee_bump_percent = env_number("TX_FEE_BUMP_PERCENT", defaults.fee_bump_percent as u64)? as u32;
+        if fee_bump_percent < MIN_FEE_BUMP_PERCENT {
+            return Err(Error::InvalidParameter(format!("TX_FEE_BUMP_PERCENT must be at least {}", MIN_FEE_BUMP_PERCENT)));
+        }
+        let stall_policy = (stall_timeout_secs > 0).then(|| StallPolicy {
+            stall_timeout: std::time::Duration::from_secs(stall_timeout_secs),
+            fee_bump_percent,
+            ..defaults
+        });
+
+        let finality = FinalityPolicy::from_env(env_number("CHAIN_ID", 1)?)
+            .map_err(|e| Error::InvalidParameter(e.to_string()))?;
+
+        Ok(Self { ethereum_rpc_url, registry_address, contracts, ipfs_url, stall_policy, finality })
+    }
+}
+
+pub(crate) fn env_number(name: &str, default: u64) -> Result<u64, Error> {
+    match std::env::var(name) {
+        Ok(value) => value.parse::<u64>()
+            .map_err(|_| Error::InvalidParameter(format!("{} must be a non-negative integer", name))),
+        Err(_) => Ok(default),
+    }
+}
+
+#[derive(Debug, Parser)]
+#[command(name = "treasury_admin", about = "Administer treasuries in the TreasuryRegistry")]
+pub struct Cli {
+    /// Print JSON instead of tables
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    #[command(subcommand)]
+    pub command: AdminCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AdminCommand {
+    /// Register deployed treasury tokens from a JSON file holding one registration or an array
+    RegisterTreasury {
+        file: PathBuf,
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Set a treasury's price
+    UpdatePrice {
+        token_id: String,
+        price: String,
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Set a treasury's status: active, matured or redeemed
+    UpdateStatus {
+        token_id: String,
+        #[arg(value_parser = parse_status)]
+        status: TreasuryStatus,
+        #[arg(long)]
+        yes: bool,
+    },
+    /// List treasuries in the registry
+    ListTreasuries {
+        #[arg(long, value_parser = parse_status)]
+        status: Option<TreasuryStatus>,
+        #[arg(long = "type", value_parser = parse_type)]
+        treasury_type: Option<TreasuryType>,
+        /// Only treasuries maturing before this unix timestamp
+        #[arg(long)]
+        matures_before: Option<u64>,
+    },
+    /// Process maturity of every active treasury past its maturity date
+    RunMaturities {
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Distribute yield to every treasury that is due
+    RunYieldDistribution {
+        /// List the treasuries that are due and each holder's withholding without distributing
+        #[arg(long)]
+        dry_run: bool,
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Re-read every registry entry and report treasuries that cannot be listed
+    ResyncRegistry,
+}
+
+fn parse_status(value: &str) -> Result<TreasuryStatus, String> {
+    value.parse()
+}
+
+fn parse_type(value: &str) -> Result<TreasuryType, String> {
+    value.parse()
+}
+
+/// Operations behind the admin commands.
+///
+/// ServiceAdmin is the production implementation; tests can substitute a mock.
+#[async_trait]
+pub trait TreasuryAdmin: Send + Sync {
+    async fn register_treasuries(&self, rows: &[TreasuryRegistration]) -> Vec<Result<[u8; 32], Error>>;
+    async fn update_price(&self, token_id: [u8; 32], price: U256) -> Result<(), Error>;
+    async fn update_status(&self, token_id: [u8; 32], status: TreasuryStatus) -> Result<(), Error>;
+    async fn list_treasuries(&self) -> Result<Vec<TreasuryOverview>, Error>;
+    /// Every token id in the registry, including entries that cannot be listed
+    async fn registry_token_ids(&self) -> Result<Vec<[u8; 32]>, Error>;
+    async fn run_maturities(&self) -> Result<Vec<MaturityResult>, Error>;
+    async fn due_yield_distributions(&self) -> Result<Vec<([u8; 32], TreasuryInfo)>, Error>;
+    async fn preview_yield_distribution(&self, token_id: [u8; 32]) -> Result<DistributionPreview, Error>;
+    async fn run_yield_distribution(&self) -> Result<Vec<YieldDistributionResult>, Error>;
+}
+
+/// Admin operations backed by the same service structs as the server
+pub struct ServiceAdmin {
+    pub treasury_service: Arc<TreasuryService>,
+    pub registry_client: Arc<TreasuryRegistryClient>,
+    pub yield_scheduler: Arc<YieldSchedulerService>,
+}
+
+#[async_trait]
+impl TreasuryAdmin for ServiceAdmin {
+    async fn register_treasuries(&self, rows: &[TreasuryRegistration]) -> Vec<Result<[u8; 32], Error>> {
+        self.registry_client.register_treasuries(rows).await
+    }
+
+    async fn update_price(&self, token_id: [u8; 32], price: U256) -> Result<(), Error> {
+        self.treasury_service.update_treasury_price(token_id, price).await
+    }
+
+    async fn update_status(&self, token_id: [u8; 32], status: TreasuryStatus) -> Result<(), Error> {
+        self.registry_client.update_treasury_status(token_id, status).await
+    }
+
+    async fn list_treasuries(&self) -> Result<Vec<TreasuryOverview>, Error> {
+        self.treasury_service.get_all_treasuries().await
+    }
+
+    async fn registry_token_ids(&self) -> Result<Vec<[u8; 32]>, Error> {
+        self.registry_client.get_all_treasuries().await
+    }
+
+    async fn run_maturities(&self) -> Result<Vec<MaturityResult>, Error> {
+        self.yield_scheduler.check_and_process_maturities().await
+    }
+
+    async fn due_yield_distributions(&self) -> Result<Vec<([u8; 32], TreasuryInfo)>, Error> {
+        self.yield_scheduler.due_yield_distributions().await
+    }
+
+    async fn preview_yield_distribution(&self, token_id: [u8; 32]) -> Result<DistributionPreview, Error> {
+        self.yield_scheduler.preview_yield_distribution(token_id).await
+    }
+
+    async fn run_yield_distribution(&self) -> Result<Vec<YieldDistributionResult>, Error> {
+        self.yield_scheduler.check_and_distribute_yields().await
+    }
+}
+
+impl ServiceAdmin {
+    /// Build the services from configuration; the CLI never deploys tokens. Yield is
+    /// withheld for the holders listed in TAX_PROFILES_PATH and dates follow the
+    /// HOLIDAY_CALENDAR_PATH holidays, as the server does.
+    pub async fn connect(
+        config: &RegistryConfig,
+        ethereum_client: Arc<ethereum_client::EthereumClient>,
+        token_deployer: Box<dyn crate::TokenDeployer>,
+        compliance_checker: Box<dyn crate::ComplianceChecker>,
+    ) -> Result<Self, Error> {
+        let registry_client = Arc::new(TreasuryRegistryClient::new(ethereum_client.clone(), config.registry_address).await);
+        let calendar = Arc::new(BusinessCalendar::from_env()?);
+        let treasury_service = Arc::new(TreasuryService::new(
+            (*registry_client).clone(),
+            IpfsClient::new(&config.ipfs_url),
+            token_deployer,
+            compliance_checker,
+        ).await
+        .with_calendar(calendar.clone()));
+
+        let withholding = Arc::new(WithholdingTable::from_env()?);
+        let holdings = HoldingsSync::new(
+            Arc::new(RegistryTokenUniverse::new(registry_client.clone(), ethereum_client.clone(), Vec::new())),
+            Arc::new(MulticallBalanceReader::new(ethereum_client.clone(), config.contracts.get(ContractName::Multicall)?)),
+            HoldingsSyncConfig::from_env()?,
+        );
+        for profile in withholding.profiles() {
+            holdings.track(profile.wallet);
+        }
+        let yield_scheduler = Arc::new(YieldSchedulerService::new(registry_client.clone(), ethereum_client).await
+            .with_withholding(withholding, Arc::new(holdings))
+            .with_calendar(calendar));
+
+        Ok(Self { treasury_service, registry_client, yield_scheduler })
+    }
+}
+
+/// Outcome of one registration row, matching the bulk registration API
+#[derive(Debug, Serialize)]
+struct RegistrationRow {
+    row: usize,
+    token_id: Option<String>,
+    error: Option<String>,
+}
+
+/// Registry entries that `list-treasuries` cannot show in full
+#[derive(Debug, Serialize)]
+struct RegistrySyncReport {
+    registered: usize,
+    listed: usize,
+    /// Listed from on-chain data only because their metadata failed validation
+    quarantined: Vec<QuarantinedTreasury>,
+    /// In the registry but not listed at all, e.g. unreachable details or metadata
+    unreadable: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct QuarantinedTreasury {
+    token_id: String,
+    reason: String,
+}
+
+/// Run a parsed command, writing results to `out` and errors to `err`; returns the exit code
+pub async fn run<O: Write + Send, E: Write + Send>(
+    cli: Cli,
+    admin: &dyn TreasuryAdmin,
+    out: &mut O,
+    err: &mut E,
+) -> i32 {
+    match execute(cli, admin, out).await {
+        Ok(code) => code,
+        Err(CommandError::Refused(message)) => {
+            let _ = writeln!(err, "{}", message);
+            EXIT_USAGE
+        }
+        Err(CommandError::Failed(e)) => {
+            let _ = writeln!(err, "error: {}", e);
+            EXIT_FAILURE
+        }
+    }
+}
+
+enum CommandError {
+    Refused(String),
+    Failed(Error),
+}
+
+impl From<Error> for CommandError {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::InvalidParameter(message) => CommandError::Refused(format!("error: {}", message)),
+            e => CommandError::Failed(e),
+        }
+    }
+}
+
+impl From<std::io::Error> for CommandError {
+    fn from(e: std::io::Error) -> Self {
+        CommandError::Failed(Error::Internal(format!("Failed to write output: {}", e)))
+    }
+}
+
+fn require_yes(yes: bool, action: &str) -> Result<(), CommandError> {
+    if yes {
+        Ok(())
+    } else {
+        Err(CommandError::Refused(format!("Refusing to {} without --yes", action)))
+    }
+}
+
+async fn execute<O: Write + Send>(cli: Cli, admin: &dyn TreasuryAdmin, out: &mut O) -> Result<i32, CommandError> {
+    let json = cli.json;
+
+    match cli.command {
+        AdminCommand::RegisterTreasury { file, yes } => {
+            let rows = read_registrations(&file)?;
+            require_yes(yes, &format!("register {} treasuries", rows.len()))?;
+
+            let results: Vec<RegistrationRow> = admin.register_treasuries(&rows).await
+                .into_iter()
+                .enumerate()
+                .map(|(row, result)| match result {
+                    Ok(token_id) => RegistrationRow { row, token_id: Some(format_token_id(&token_id)), error: None },
+                    Err(e) => RegistrationRow { row, token_id: None, error: Some(e.to_string()) },
+                })
+                .collect();
+            let failed = results.iter().any(|row| row.error.is_some());
+
+            if json {
+                write_json(out, &results)?;
+            } else {
+                write_table(out, &["ROW", "TOKEN ID", "ERROR"], results.iter().map(|row| vec![
+                    row.row.to_string(),
+                    row.token_id.clone().unwrap_or_default(),
+                    row.error.clone().unwrap_or_default(),
+                ]))?;
+            }
+            Ok(if failed { EXIT_FAILURE } else { EXIT_SUCCESS })
+        }
+
+        AdminCommand::UpdatePrice { token_id, price, yes } => {
+            let token_id = parse_token_id(&token_id)?;
+            let price = price.parse::<U256>()
+                .map_err(|_| Error::InvalidParameter(format!("Invalid price: {}", price)))?;
+            require_yes(yes, &format!("set the price of {} to {}", format_token_id(&token_id), price))?;
+
+            admin.update_price(token_id, price).await?;
+            write_done(out, json, &format!("Price of {} set to {}", format_token_id(&token_id), price))?;
+            Ok(EXIT_SUCCESS)
+        }
+
+        AdminCommand::UpdateStatus { token_id, status, yes } => {
+            let token_id = parse_token_id(&token_id)?;
+            require_yes(yes, &format!("set the status of {} to {:?}", format_token_id(&token_id), status))?;
+
+            admin.update_status(token_id, status).await?;
+            write_done(out, json, &format!("Status of {} set to {:?}", format_token_id(&token_id), status))?;
+            Ok(EXIT_SUCCESS)
+        }
+
+        AdminCommand::ListTreasuries { status, treasury_type, matures_before } => {
+            let treasuries: Vec<TreasuryOverview> = admin.list_treasuries().await?
+                .into_iter()
+                .filter(|t| status.map_or(true, |status| t.status == status))
+                .filter(|t| treasury_type.map_or(true, |treasury_type| t.treasury_type == treasury_type))
+                .filter(|t| matures_before.map_or(true, |before| t.maturity_date < before))
+                .collect();
+
+            if json {
+                write_json(out, &treasuries)?;
+            } else {
+                write_table(out, &["TOKEN ID", "SYMBOL", "TYPE", "STATUS", "PRICE", "YIELD (BPS)", "MATURITY"], treasuries.iter().map(|t| vec![
+                    format_token_id(&t.token_id),
+                    if t.validation_error.is_some() { "(quarantined)".to_string() } else { t.symbol.clone() },
+                    format!("{:?}", t.treasury_type),
+                    format!("{:?}", t.status),
+                    t.current_price.to_string(),
+                    t.yield_rate.to_string(),
+                    t.maturity_date.to_string(),
+                ]))?;
+            }
+            Ok(EXIT_SUCCESS)
+        }
+
+        AdminCommand::RunMaturities { yes } => {
+            require_yes(yes, "process maturities")?;
+
+            let results = admin.run_maturities().await?;
+            let failed = results.iter().any(|result| !result.success);
+
+            if json {
+                write_json(out, &results)?;
+            } else {
+                write_table(out, &["TOKEN ID", "MATURITY", "RESULT"], results.iter().map(|result| vec![
+                    format_token_id(&result.treasury_id),
+                    result.maturity_date.to_string(),
+                    outcome(result.success, &result.error_message),
+                ]))?;
+            }
+            Ok(if failed { EXIT_FAILURE } else { EXIT_SUCCESS })
+        }
+
+        AdminCommand::RunYieldDistribution { dry_run: true, .. } => {
+            let due = admin.due_yield_distributions().await?;
+            let mut previews = Vec::with_capacity(due.len());
+            for (token_id, _) in &due {
+                previews.push(admin.preview_yield_distribution(*token_id).await?);
+            }
+
+            if json {
+                let rows: Vec<_> = previews.iter()
+                    .map(|preview| serde_json::json!({
+                        "token_id": format_token_id(&preview.treasury_id),
+                        "token_address": preview.token_address,
+                        "yield_rate": preview.yield_rate,
+                        "amount": preview.amount.to_string(),
+                        "holders": preview.holders.iter().map(|holder| serde_json::json!({
+                            "wallet": holder.wallet,
+                            "jurisdiction": holder.jurisdiction,
+                            "gross": holder.gross.to_string(),
+                            "rate_bps": holder.rate_bps,
+                            "basis": holder.basis,
+                            "withheld": holder.withheld.to_string(),
+                            "net": holder.net.to_string(),
+                            "missing_documents": holder.missing_documents,
+                        })).collect::<Vec<_>>(),
+                    }))
+                    .collect();
+                write_json(out, &rows)?;
+            } else {
+                write_table(out, &["TOKEN ID", "TOKEN ADDRESS", "YIELD (BPS)", "AMOUNT"], previews.iter().map(|preview| vec![
+                    format_token_id(&preview.treasury_id),
+                    format!("{:?}", preview.token_address),
+                    preview.yield_rate.to_string(),
+                    preview.amount.to_string(),
+                ]))?;
+
+                let holders = previews.iter()
+                    .flat_map(|preview| preview.holders.iter().map(move |holder| (preview.treasury_id, holder)));
+                writeln!(out)?;
+                write_table(out, &["TOKEN ID", "HOLDER", "JURISDICTION", "GROSS", "RATE (BPS)", "WITHHELD", "NET", "MISSING"], holders.map(|(token_id, holder)| vec![
+                    format_token_id(&token_id),
+                    format!("{:?}", holder.wallet),
+                    holder.jurisdiction.clone().unwrap_or_else(|| "unknown".to_string()),
+                    holder.gross.to_string(),
+                    holder.rate_bps.to_string(),
+                    holder.withheld.to_string(),
+                    holder.net.to_string(),
+                    holder.missing_documents.iter().map(|document| document.as_str()).collect::<Vec<_>>().join(", "),
+                ]))?;
+            }
+            Ok(EXIT_SUCCESS)
+        }
+
+        AdminCommand::RunYieldDistribution { dry_run: false, yes } => {
+            require_yes(yes, "distribute yield")?;
+
+            let results = admin.run_yield_distribution().await?;
+            let failed = results.iter().any(|result| !result.success);
+
+            if json {
+                write_json(out, &results)?;
+            } else {
+                write_table(out, &["TOKEN ID", "DISTRIBUTION", "AMOUNT", "WITHHELD", "RESULT"], results.iter().map(|result| vec![
+                    format_token_id(&result.treasury_id),
+                    result.distribution_id.to_string(),
+                    result.amount.to_string(),
+                    result.withheld.to_string(),
+                    outcome(result.success, &result.error_message),
+                ]))?;
+            }
+            Ok(if failed { EXIT_FAILURE } else { EXIT_SUCCESS })
+        }
+
+        AdminCommand::ResyncRegistry => {
+            let token_ids = admin.registry_token_ids().await?;
+            let treasuries = admin.list_treasuries().await?;
+
+            let listed: std::collections::HashSet<[u8; 32]> = treasuries.iter().map(|t| t.token_id).collect();
+            let report = RegistrySyncReport {
+                registered: token_ids.len(),
+                listed: treasuries.len(),
+                quarantined: treasuries.iter()
+                    .filter_map(|t| t.validation_error.as_ref().map(|reason| QuarantinedTreasury {
+                        token_id: format_token_id(&t.token_id),
+                        reason: reason.clone(),
+                    }))
+                    .collect(),
+                unreadable: token_ids.iter()
+                    .filter(|token_id| !listed.contains(*token_id))
+                    .map(format_token_id)
+                    .collect(),
+            };
+            let healthy = report.quarantined.is_empty() && report.unreadable.is_empty();
+
+            if json {
+                write_json(out, &report)?;
+            } else {
+                writeln!(out, "{} registered, {} listed", report.registered, report.listed)?;
+                let issues = report.quarantined.iter()
+                    .map(|q| vec![q.token_id.clone(), "quarantined".to_string(), q.reason.clone()])
+                    .chain(report.unreadable.iter().map(|token_id| vec![token_id.clone(), "unreadable".to_string(), String::new()]));
+                write_table(out, &["TOKEN ID", "ISSUE", "REASON"], issues)?;
+            }
+            Ok(if healthy { EXIT_SUCCESS } else { EXIT_FAILURE })
+        }
+    }
+}
+
+/// Registrations from a file holding a single object or an array
+fn read_registrations(file: &PathBuf) -> Result<Vec<TreasuryRegistration>, Error> {
+    let contents = std::fs::read_to_string(file)
+        .map_err(|e| Error::InvalidParameter(format!("Cannot read {}: {}", file.display(), e)))?;
+
+    let value: serde_json::Value = serde_json::from_str(&contents)
+        .map_err(|e| Error::InvalidParameter(format!("Invalid JSON in {}: {}", file.display(), e)))?;
+    let rows = if value.is_array() {
+        serde_json::from_value(value)
+    } else {
+        serde_json::from_value(value).map(|row| vec![row])
+    };
+
+    rows.map_err(|e| Error::InvalidParameter(format!("Invalid registration in {}: {}", file.display(), e)))
+}
+
+fn parse_token_id(token_id: &str) -> Result<[u8; 32], Error> {
+    wire::parse_token_id(token_id)
+        .map_err(|_| Error::InvalidParameter(format!("Invalid token id: {}", token_id)))
+}
+
+fn outcome(success: bool, error_message: &Option<String>) -> String {
+    if success {
+        "ok".to_string()
+    } else {
+        format!("failed: {}", error_message.as_deref().unwrap_or("unknown error"))
+    }
+}
+
+fn write_done<O: Write>(out: &mut O, json: bool, message: &str) -> std::io::Result<()> {
+    if json {
+        writeln!(out, "{}", serde_json::json!({ "success": true, "message": message }))
+    } else {
+        writeln!(out, "{}", message)
+    }
+}
+
+fn write_json<O: Write, T: Serialize + ?Sized>(out: &mut O, value: &T) -> std::io::Result<()> {
+    serde_json::to_writer_pretty(&mut *out, value)?;
+    writeln!(out)
+}
+
+/// Left-aligned columns padded to the widest cell
+fn write_table<O: Write>(
+    out: &mut O,
+    headers: &[&str],
+    rows: impl Iterator<Item = Vec<String>>,
+) -> std::io::Result<()> {
+    let rows: Vec<Vec<String>> = rows.collect();
+    let mut widths: Vec<usize> = headers.iter().map(|header| header.len()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let line = |cells: Vec<&str>| -> String {
+        cells.iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    writeln!(out, "{}", line(headers.to_vec()))?;
+    for row in &rows {
+        writeln!(out, "{}", line(row.iter().map(String::as_str).collect()))?;
+    }
+    Ok(())
+}
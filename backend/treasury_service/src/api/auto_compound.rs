@@ -0,0 +1,130 @@
+use crate::{
+    api::{ApiServices, ApiError, with_services, with_auth},
+    AutomationConfig,
+    Error as ServiceError,
+};
+use alloy_primitives::{Address, U256};
+use quantera_types::wire::parse_token_id;
+use serde::{Serialize, Deserialize};
+use warp::{Filter, Rejection, Reply};
+use std::sync::Arc;
+use tracing::info;
+
+/// Yield auto-compounding routes; each user manages their own automation
+pub fn routes(
+    services: Arc<ApiServices>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    let get_config_route = warp::path!("yield" / "automation")
+        .and(warp::get())
+        .and(with_auth(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(get_config_handler);
+
+    let set_config_route = warp::path!("yield" / "automation")
+        .and(warp::put())
+        .and(warp::body::json::<AutomationConfigRequest>())
+        .and(with_auth(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(set_config_handler);
+
+    let disable_route = warp::path!("yield" / "automation")
+        .and(warp::delete())
+        .and(with_auth(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(disable_handler);
+
+    let get_events_route = warp::path!("yield" / "automation" / "compounds")
+        .and(warp::get())
+        .and(with_auth(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(get_events_handler);
+
+    get_config_route
+        .or(set_config_route)
+        .or(disable_route)
+        .or(get_events_route)
+}
+
+/// Auto-compound settings of the authenticated user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutomationConfigRequest {
+    pub source_token: Address,
+    pub yield_token: Address,
+    pub target_asset: Address,
+    /// Hex pool id
+    pub pool_id: String,
+    /// Hex smart account id
+    pub account_id: String,
+    pub session_key: Address,
+    pub session_valid_until: u64,
+    pub max_slippage_bps: u32,
+    pub max_gas: U256,
+    pub min_compound_amount: U256,
+}
+
+fn wallet(services: &ApiServices, token: &str) -> Result<Address, Rejection> {
+    services.auth_service.validate_token(token)
+        .wallet_address
+        .ok_or_else(|| warp::reject::custom(ApiError(ServiceError::Unauthorized("Token has no wallet".into()))))
+}
+
+fn parse_id(id: &str, name: &str) -> Result<[u8; 32], Rejection> {
+    parse_token_id(id).map_err(|e| warp::reject::custom(ApiError(
+        ServiceError::InvalidParameter(format!("Invalid {}: {}", name, e))
+    )))
+}
+
+async fn get_config_handler(
+    token: String, // From auth middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    let user = wallet(&services, &token)?;
+    let config = services.auto_compound.config(user).await
+        .ok_or_else(|| warp::reject::custom(ApiError(ServiceError::NotFound(format!("No auto-compound configuration for {:?}", user)))))?;
+    Ok(warp::reply::json(&config))
+}
+
+/// Opt in to auto-compounding, or replace the current settings
+async fn set_config_handler(
+    request: AutomationConfigRequest,
+    token: String, // From auth middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    let user = wallet(&services, &token)?;
+    info!("Auto-compound configured by {:?}", user);
+
+    let config = services.auto_compound.set_config(AutomationConfig {
+        user,
+        source_token: request.source_token,
+        yield_token: request.yield_token,
+        target_asset: request.target_asset,
+        pool_id: parse_id(&request.pool_id, "pool id")?,
+        account_id: parse_id(&request.account_id, "account id")?,
+        session_key: request.session_key,
+        session_valid_until: request.session_valid_until,
+        max_slippage_bps: request.max_slippage_bps,
+        max_gas: request.max_gas,
+        min_compound_amount: request.min_compound_amount,
+        active: true,
+    }).await.map_err(|e| warp::reject::custom(ApiError(e)))?;
+    Ok(warp::reply::json(&config))
+}
+
+async fn disable_handler(
+    token: String, // From auth middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    let user = wallet(&services, &token)?;
+    let config = services.auto_compound.disable(user).await
+        .map_err(|e| warp::reject::custom(ApiError(e)))?;
+    Ok(warp::reply::json(&config))
+}
+
+/// Compound attempts of the authenticated user, oldest first
+async fn get_events_handler(
+    token: String, // From auth middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    let user = wallet(&services, &token)?;
+    Ok(warp::reply::json(&services.auto_compound.events_for(user).await))
+}
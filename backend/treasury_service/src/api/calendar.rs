@@ -0,0 +1,61 @@
+use crate::{
+    api::{ApiServices, ApiError, with_services, with_admin},
+    NewHoliday,
+};
+use serde::{Serialize, Deserialize};
+use warp::{Filter, Rejection, Reply};
+use std::sync::Arc;
+use tracing::info;
+
+/// Business-day calendar routes, restricted to platform admins
+pub fn routes(
+    services: Arc<ApiServices>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    let get_holidays_route = warp::path!("admin" / "calendar" / "holidays")
+        .and(warp::get())
+        .and(warp::query::<HolidayQueryParams>())
+        .and(with_admin(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(get_holidays_handler);
+
+    let add_holiday_route = warp::path!("admin" / "calendar" / "holidays")
+        .and(warp::post())
+        .and(warp::body::json::<NewHoliday>())
+        .and(with_admin(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(add_holiday_handler);
+
+    get_holidays_route
+        .or(add_holiday_route)
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct HolidayQueryParams {
+    /// Defaults to the scheduling market
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub market: Option<String>,
+}
+
+/// Holidays of a market, earliest first
+async fn get_holidays_handler(
+    params: HolidayQueryParams,
+    _admin: String, // From admin middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    let calendar = &services.business_calendar;
+    Ok(warp::reply::json(&calendar.holidays(params.market.as_deref().unwrap_or(calendar.market()))))
+}
+
+/// Add an ad-hoc market closure; distribution and maturity dates move around it from the next run
+async fn add_holiday_handler(
+    holiday: NewHoliday,
+    admin: String, // From admin middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    info!("Holiday {} for {} requested by {}", holiday.date, holiday.market, admin);
+
+    let holiday = services.business_calendar.add_holiday(holiday, &admin)
+        .map_err(|e| warp::reject::custom(ApiError(e)))?;
+
+    Ok(warp::reply::json(&holiday))
+}
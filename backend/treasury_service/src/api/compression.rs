@@ -0,0 +1,200 @@
+//! Response compression negotiated from the request's Accept-Encoding.
+//!
+//! JSON and text bodies above a small threshold are sent brotli- or gzip-encoded when the
+//! client accepts it; everything else (WebSocket upgrades, empty bodies) passes through.
+
+use std::io::Write;
+use warp::{
+    http::{header, HeaderValue, Response, StatusCode},
+    hyper::{self, Body},
+    Filter, Rejection, Reply,
+};
+use tracing::warn;
+
+/// Bodies smaller than this gain nothing from compression
+const MIN_COMPRESS_BYTES: usize = 1024;
+
+/// Brotli quality; the top levels are too slow for per-request encoding
+const BROTLI_QUALITY: u32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Brotli,
+    Gzip,
+}
+
+impl Encoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Encoding::Brotli => "br",
+            Encoding::Gzip => "gzip",
+        }
+    }
+}
+
+/// The encoding to respond with: the accepted one with the highest q-value, brotli on ties
+pub fn negotiate(accept_encoding: &str) -> Option<Encoding> {
+    let mut best: Option<(Encoding, f32)> = None;
+    for entry in accept_encoding.split(',') {
+        let mut parts = entry.split(';');
+        let coding = parts.next().unwrap_or_default().trim().to_ascii_lowercase();
+        let quality = parts
+            .filter_map(|param| param.trim().strip_prefix("q="))
+            .find_map(|q| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+        if quality <= 0.0 {
+            continue;
+        }
+
+        let candidates: &[Encoding] = match coding.as_str() {
+            "br" => &[Encoding::Brotli],
+            "gzip" | "x-gzip" => &[Encoding::Gzip],
+            "*" => &[Encoding::Brotli, Encoding::Gzip],
+            _ => &[],
+        };
+        for &encoding in candidates {
+            let better = match best {
+                None => true,
+                Some((current, q)) => quality > q || (quality == q && current == Encoding::Gzip && encoding == Encoding::Brotli),
+            };
+            if better {
+                best = Some((encoding, quality));
+            }
+        }
+    }
+    best.map(|(encoding, _)| encoding)
+}
+
+/// Compress the replies of `filter` when the request allows it
+pub fn negotiate_compression<F, R>(
+    filter: F,
+) -> impl Filter<Extract = (Response<Body>,), Error = Rejection> + Clone
+where
+    F: Filter<Extract = (R,), Error = Rejection> + Clone + Send + Sync + 'static,
+    R: Reply + Send,
+{
+    warp::header::optional::<String>("accept-encoding")
+        .and(filter)
+        .and_then(|accept_encoding: Option<String>, reply: R| async move {
+            let encoding = accept_encoding.as_deref().and_then(negotiate);
+            Ok::<_, Rejection>(compress_response(reply.into_response(), encoding).await)
+        })
+}
+
+/// Whether the response carries a complete JSON or text body that is not already encoded
+fn compressible(response: &Response<Body>) -> bool {
+    if response.status() == StatusCode::SWITCHING_PROTOCOLS
+        || response.headers().contains_key(header::CONTENT_ENCODING)
+    {
+        return false;
+    }
+    response.headers().get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|content_type| {
+            content_type.starts_with("application/json")
+                || (content_type.starts_with("text/") && !content_type.starts_with("text/event-stream"))
+        })
+        .unwrap_or(false)
+}
+
+async fn compress_response(response: Response<Body>, encoding: Option<Encoding>) -> Response<Body> {
+    if !compressible(&response) {
+        return response;
+    }
+    let (mut parts, body) = response.into_parts();
+    // Caches must not serve one client's encoding to another
+    parts.headers.append(header::VARY, HeaderValue::from_static("accept-encoding"));
+
+    let bytes = match hyper::body::to_bytes(body).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!("Failed to buffer response body for compression: {}", e);
+            parts.status = StatusCode::INTERNAL_SERVER_ERROR;
+            parts.headers.remove(header::CONTENT_LENGTH);
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+    let encoding = match encoding {
+        Some(encoding) if bytes.len() >= MIN_COMPRESS_BYTES => encoding,
+        _ => return Response::from_parts(parts, Body::from(bytes)),
+    };
+
+    match encode(encoding, &bytes) {
+        Ok(compressed) => {
+            parts.headers.insert(header::CONTENT_ENCODING, HeaderValue::from_static(encoding.as_str()));
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Response::from_parts(parts, Body::from(compressed))
+        }
+        Err(e) => {
+            warn!("Failed to {}-encode response body: {}", encoding.as_str(), e);
+            Response::from_parts(parts, Body::from(bytes))
+        }
+    }
+}
+
+fn encode(encoding: Encoding, bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    match encoding {
+        Encoding::Gzip => {
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(bytes)?;
+            encoder.finish()
+        }
+        Encoding::Brotli => {
+            let mut compressed = Vec::new();
+            {
+                let mut encoder = brotli::CompressorWriter::new(&mut compressed, 4096, BROTLI_QUALITY, 22);
+                encoder.write_all(bytes)?;
+                encoder.flush()?;
+            }
+            Ok(compressed)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn listing() -> impl Filter<Extract = (Response<Body>,), Error = Rejection> + Clone {
+        let rows: Vec<serde_json::Value> = (0..100)
+            .map(|i| serde_json::json!({ "token_id": format!("0x{:064x}", i), "status": "Active" }))
+            .collect();
+        negotiate_compression(warp::path!("treasuries").map(move || warp::reply::json(&rows)))
+    }
+
+    #[test]
+    fn test_negotiate_prefers_highest_quality() {
+        assert_eq!(negotiate("gzip, deflate, br"), Some(Encoding::Brotli));
+        assert_eq!(negotiate("br;q=0.5, gzip"), Some(Encoding::Gzip));
+        assert_eq!(negotiate("gzip;q=0, br;q=0"), None);
+        assert_eq!(negotiate("*"), Some(Encoding::Brotli));
+        assert_eq!(negotiate("identity"), None);
+    }
+
+    #[tokio::test]
+    async fn test_compressed_when_accept_encoding_allows() {
+        let route = listing();
+
+        let plain = warp::test::request().path("/treasuries").reply(&route).await;
+        assert!(plain.headers().get(header::CONTENT_ENCODING).is_none());
+
+        let gzipped = warp::test::request()
+            .path("/treasuries")
+            .header("accept-encoding", "gzip")
+            .reply(&route)
+            .await;
+        assert_eq!(gzipped.headers()[header::CONTENT_ENCODING], "gzip");
+        assert!(gzipped.body().len() < plain.body().len());
+        let mut decoded = Vec::new();
+        flate2::read::GzDecoder::new(&gzipped.body()[..]).read_to_end(&mut decoded).unwrap();
+        assert_eq!(decoded, plain.body().to_vec());
+
+        let brotli = warp::test::request()
+            .path("/treasuries")
+            .header("accept-encoding", "gzip, br")
+            .reply(&route)
+            .await;
+        assert_eq!(brotli.headers()[header::CONTENT_ENCODING], "br");
+    }
+}
@@ -0,0 +1,212 @@
+use crate::{
+    api::{ApiServices, ApiError, with_services, with_auth, with_admin},
+    DealingTerms,
+    Error as ServiceError,
+};
+use alloy_primitives::{Address, U256};
+use chrono::{DateTime, Utc};
+use quantera_types::wire::{parse_token_id, parse_u256};
+use serde::{Serialize, Deserialize};
+use warp::{Filter, Rejection, Reply};
+use std::sync::Arc;
+use tracing::info;
+
+/// Dealing window routes: terms and acquisitions for platform admins, notice, lock-up status
+/// and transfer pre-checks for investors
+pub fn routes(
+    services: Arc<ApiServices>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    let get_terms_route = warp::path!("admin" / "dealing" / "terms")
+        .and(warp::get())
+        .and(with_admin(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(get_terms_handler);
+
+    let set_terms_route = warp::path!("admin" / "dealing" / "terms")
+        .and(warp::put())
+        .and(warp::body::json::<DealingTerms>())
+        .and(with_admin(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(set_terms_handler);
+
+    let remove_terms_route = warp::path!("admin" / "dealing" / "terms" / String)
+        .and(warp::delete())
+        .and(with_admin(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(remove_terms_handler);
+
+    let record_acquisition_route = warp::path!("admin" / "dealing" / "acquisitions")
+        .and(warp::post())
+        .and(warp::body::json::<AcquisitionRequest>())
+        .and(with_admin(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(record_acquisition_handler);
+
+    let get_lockups_route = warp::path!("dealing" / "lockups")
+        .and(warp::get())
+        .and(with_auth(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(get_lockups_handler);
+
+    let give_notice_route = warp::path!("dealing" / "notices")
+        .and(warp::post())
+        .and(warp::body::json::<NoticeRequest>())
+        .and(with_auth(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(give_notice_handler);
+
+    let check_transfer_route = warp::path!("transfers" / "check")
+        .and(warp::post())
+        .and(warp::body::json::<TransferCheckRequest>())
+        .and(with_auth(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(check_transfer_handler);
+
+    get_terms_route
+        .or(set_terms_route)
+        .or(remove_terms_route)
+        .or(record_acquisition_route)
+        .or(get_lockups_route)
+        .or(give_notice_route)
+        .or(check_transfer_route)
+}
+
+/// Units issued to an investor, or transferred to them off-book when `from` is set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcquisitionRequest {
+    /// Hex treasury id
+    pub treasury_id: String,
+    pub investor: Address,
+    /// Decimal amount in base units
+    pub amount: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<Address>,
+    /// Defaults to now
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acquired_at: Option<DateTime<Utc>>,
+}
+
+/// Notice to redeem in the first window the asset's notice period reaches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoticeRequest {
+    /// Hex treasury id
+    pub treasury_id: String,
+    /// Decimal amount in base units
+    pub amount: String,
+}
+
+/// Transfer from the authenticated user to `to`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferCheckRequest {
+    /// Hex treasury id
+    pub treasury_id: String,
+    pub to: Address,
+    /// Decimal amount in base units
+    pub quantity: String,
+}
+
+fn wallet(services: &ApiServices, token: &str) -> Result<Address, Rejection> {
+    services.auth_service.validate_token(token)
+        .wallet_address
+        .ok_or_else(|| warp::reject::custom(ApiError(ServiceError::Unauthorized("Token has no wallet".into()))))
+}
+
+fn parse_treasury_id(id: &str) -> Result<[u8; 32], Rejection> {
+    parse_token_id(id).map_err(|e| warp::reject::custom(ApiError(
+        ServiceError::InvalidParameter(format!("Invalid treasury id: {}", e))
+    )))
+}
+
+fn parse_amount(amount: &str) -> Result<U256, Rejection> {
+    parse_u256(amount).map_err(|e| warp::reject::custom(ApiError(
+        ServiceError::InvalidParameter(format!("Invalid amount: {}", e))
+    )))
+}
+
+async fn get_terms_handler(
+    _admin: String, // From admin middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    Ok(warp::reply::json(&services.dealing_windows.all_terms()))
+}
+
+/// Add or replace an asset's windows, notice period and lock-up. Notice already given
+/// keeps the window it was given for.
+async fn set_terms_handler(
+    terms: DealingTerms,
+    admin: String, // From admin middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    let terms = services.dealing_windows.set_terms(terms, &admin)
+        .map_err(|e| warp::reject::custom(ApiError(e)))?;
+    Ok(warp::reply::json(&terms))
+}
+
+async fn remove_terms_handler(
+    treasury_id: String,
+    admin: String, // From admin middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    let terms = services.dealing_windows.remove_terms(parse_treasury_id(&treasury_id)?, &admin)
+        .map_err(|e| warp::reject::custom(ApiError(e)))?;
+    Ok(warp::reply::json(&terms))
+}
+
+/// Record an acquisition made outside the order book, starting or inheriting its lock-up
+async fn record_acquisition_handler(
+    request: AcquisitionRequest,
+    admin: String, // From admin middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    let treasury_id = parse_treasury_id(&request.treasury_id)?;
+    let amount = parse_amount(&request.amount)?;
+    let acquired_at = request.acquired_at.unwrap_or_else(Utc::now);
+    info!("[AUDIT] Acquisition of {} by {:?} from {:?} recorded by {}", amount, request.investor, request.from, admin);
+
+    match request.from {
+        Some(from) => services.dealing_windows.record_transfer(from, request.investor, treasury_id, amount, acquired_at),
+        None => services.dealing_windows.record_subscription(request.investor, treasury_id, amount, acquired_at),
+    }.map_err(|e| warp::reject::custom(ApiError(e)))?;
+
+    Ok(warp::reply::json(&services.dealing_windows.lockup_status(request.investor, Utc::now())))
+}
+
+/// Lock-ups, redemption notices and next windows of the authenticated user
+async fn get_lockups_handler(
+    token: String, // From auth middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    let investor = wallet(&services, &token)?;
+    Ok(warp::reply::json(&services.dealing_windows.lockup_status(investor, Utc::now())))
+}
+
+async fn give_notice_handler(
+    request: NoticeRequest,
+    token: String, // From auth middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    let investor = wallet(&services, &token)?;
+    let notice = services.dealing_windows.give_notice(
+        investor,
+        parse_treasury_id(&request.treasury_id)?,
+        parse_amount(&request.amount)?,
+        Utc::now(),
+    ).map_err(|e| warp::reject::custom(ApiError(e)))?;
+    Ok(warp::reply::json(&notice))
+}
+
+/// Dealing window and compliance pre-check of a transfer from the authenticated user
+async fn check_transfer_handler(
+    request: TransferCheckRequest,
+    token: String, // From auth middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    let from = wallet(&services, &token)?;
+    let checks = services.pre_trade_compliance.check_transfer(
+        from,
+        request.to,
+        parse_treasury_id(&request.treasury_id)?,
+        parse_amount(&request.quantity)?,
+    ).await.map_err(|e| warp::reject::custom(ApiError(e)))?;
+    Ok(warp::reply::json(&checks))
+}
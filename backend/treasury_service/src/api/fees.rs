@@ -0,0 +1,137 @@
+use crate::{
+    api::{ApiServices, ApiError, with_services, with_admin},
+    Error as ServiceError,
+    Currency, FeeReportRow, NewFeeRule, ReportPeriod, report_csv, report_fees_in,
+};
+use price_oracle::ReportingTotal;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Serialize, Deserialize};
+use warp::{Filter, Rejection, Reply};
+use std::sync::Arc;
+use tracing::{info, debug};
+
+/// Fee schedule routes, restricted to platform admins
+pub fn routes(
+    services: Arc<ApiServices>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    let get_accruals_route = warp::path!("admin" / "fees" / "accruals")
+        .and(warp::get())
+        .and(warp::query::<AccrualReportParams>())
+        .and(with_admin(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(get_accruals_handler);
+
+    let get_schedule_route = warp::path!("admin" / "fees" / "schedule")
+        .and(warp::get())
+        .and(warp::query::<ScheduleQueryParams>())
+        .and(with_admin(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(get_schedule_handler);
+
+    let add_rule_route = warp::path!("admin" / "fees" / "schedule")
+        .and(warp::post())
+        .and(warp::body::json::<NewFeeRule>())
+        .and(with_admin(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(add_rule_handler);
+
+    get_accruals_route
+        .or(get_schedule_route)
+        .or(add_rule_route)
+}
+
+/// Accrual report query parameters
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct AccrualReportParams {
+    /// Defaults to 30 days before `to`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive; defaults to now
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<DateTime<Utc>>,
+    /// day or month (default)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub period: Option<ReportPeriod>,
+    /// json (default) or csv
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// ISO 4217 code to total the fees in; JSON reports then carry the rows and the total
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reporting_currency: Option<Currency>,
+}
+
+/// Report rows with their fees totalled in a reporting currency
+#[derive(Debug, Serialize)]
+pub struct FeeReport {
+    pub rows: Vec<FeeReportRow>,
+    pub total_fees: ReportingTotal,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct ScheduleQueryParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+}
+
+/// Fees accrued per tenant, period and operation
+async fn get_accruals_handler(
+    params: AccrualReportParams,
+    _admin: String, // From admin middleware
+    services: Arc<ApiServices>,
+) -> Result<Box<dyn Reply>, Rejection> {
+    debug!("Getting fee accrual report: {:?}", params);
+
+    let to = params.to.unwrap_or_else(Utc::now);
+    let from = params.from.unwrap_or(to - Duration::days(30));
+    if from >= to {
+        return Err(warp::reject::custom(ApiError(
+            ServiceError::InvalidParameter("Report start must be before its end".into())
+        )));
+    }
+
+    let rows = services.fee_schedule.report(from, to, params.period.unwrap_or(ReportPeriod::Month));
+    if let Some(reporting) = params.reporting_currency {
+        if !matches!(params.format.as_deref(), None | Some("json")) {
+            return Err(warp::reject::custom(ApiError(
+                ServiceError::InvalidParameter("reporting_currency is only available for JSON reports".into())
+            )));
+        }
+        let total_fees = report_fees_in(&rows, reporting, &services.fx_rates).await
+            .map_err(|e| warp::reject::custom(ApiError(e)))?;
+        return Ok(Box::new(warp::reply::json(&FeeReport { rows, total_fees })));
+    }
+    match params.format.as_deref() {
+        None | Some("json") => Ok(Box::new(warp::reply::json(&rows))),
+        Some("csv") => Ok(Box::new(warp::reply::with_header(
+            warp::reply::with_header(report_csv(&rows), "Content-Type", "text/csv"),
+            "Content-Disposition",
+            "attachment; filename=\"fee-accruals.csv\"",
+        ))),
+        Some(other) => Err(warp::reject::custom(ApiError(
+            ServiceError::InvalidParameter(format!("Unknown report format {}", other))
+        ))),
+    }
+}
+
+/// Fee rules, past and scheduled
+async fn get_schedule_handler(
+    params: ScheduleQueryParams,
+    _admin: String, // From admin middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    Ok(warp::reply::json(&services.fee_schedule.rules(params.tenant.as_deref())))
+}
+
+/// Schedule a fee change, effective now or later
+async fn add_rule_handler(
+    rule: NewFeeRule,
+    admin: String, // From admin middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    info!("Fee change for {} {} requested by {}", rule.tenant, rule.operation.as_str(), admin);
+
+    let rule = services.fee_schedule.add_rule(rule, &admin, Utc::now())
+        .map_err(|e| warp::reject::custom(ApiError(e)))?;
+
+    Ok(warp::reply::json(&rule))
+}
@@ -11,6 +11,7 @@ use crate::clients::l2_bridge_client::{
 };
 use crate::ethereum_client::EthereumClient;
 use crate::Error;
+use quantera_types::FinalityStatus;
 use crate::api::auth::{with_auth, Role, JwtClaims};
 use crate::api::utils::{with_clients, json_response, json_error_response};
 
@@ -103,6 +104,8 @@ pub struct OrderBridgingResponse {
     pub estimated_confirmation_time: u64,
     pub bridging_fee: String,
     pub status: MessageStatus,
+    /// Whether the source transaction can still be reorged out
+    pub finality: FinalityStatus,
 }
 
 #[derive(Debug, Serialize)]
@@ -112,6 +115,8 @@ pub struct TradeSettlementResponse {
     pub estimated_confirmation_time: u64,
     pub settlement_fee: String,
     pub status: MessageStatus,
+    /// Whether the source transaction can still be reorged out
+    pub finality: FinalityStatus,
 }
 
 #[derive(Debug, Serialize)]
@@ -391,12 +396,14 @@ async fn handle_bridge_order(
     // Bridge the order
     match client.bridge_order(order_request).await {
         Ok(result) => {
+            let finality = client.source_finality(result.source_transaction_hash).await.unwrap_or_default();
             let response = OrderBridgingResponse {
                 message_id: format!("0x{}", hex::encode(result.message_id)),
                 source_transaction_hash: format!("{:?}", result.source_transaction_hash),
                 estimated_confirmation_time: result.estimated_confirmation_time,
                 bridging_fee: result.bridging_fee.to_string(),
                 status: result.status,
+                finality,
             };
             json_response(&response)
         },
@@ -468,12 +475,14 @@ async fn handle_settle_trade(
     // Settle the trade
     match client.settle_trade(trade_request).await {
         Ok(result) => {
+            let finality = client.source_finality(result.source_transaction_hash).await.unwrap_or_default();
             let response = TradeSettlementResponse {
                 message_id: format!("0x{}", hex::encode(result.message_id)),
                 source_transaction_hash: format!("{:?}", result.source_transaction_hash),
                 estimated_confirmation_time: result.estimated_confirmation_time,
                 settlement_fee: result.settlement_fee.to_string(),
                 status: result.status,
+                finality,
             };
             json_response(&response)
         },
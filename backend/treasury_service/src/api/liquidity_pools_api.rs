@@ -5,6 +5,7 @@ use std::sync::Arc;
 use std::convert::TryFrom;
 
 use crate::clients::liquidity_pools_client::{LiquidityPoolsClient, PoolConfig, PoolState, Position, AssetClass};
+use crate::{AprWindow, LpAnalytics};
 use crate::ethereum_client::EthereumClient;
 use crate::auth::jwt::with_auth;
 
@@ -92,6 +93,12 @@ pub struct PositionResponse {
     pub created_at: u64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalyticsQuery {
+    /// Comma-separated APR windows, e.g. `7d,30d`
+    pub windows: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiError {
     pub message: String,
@@ -100,6 +107,7 @@ pub struct ApiError {
 pub fn liquidity_pools_routes(
     ethereum_client: Arc<EthereumClient>,
     liquidity_pools_address: Address,
+    analytics: Arc<LpAnalytics>,
 ) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
     let client = Arc::new(move || {
         LiquidityPoolsClient::new(ethereum_client.clone(), liquidity_pools_address)
@@ -155,6 +163,12 @@ pub fn liquidity_pools_routes(
         .and(warp::any().map(move || client.clone()))
         .and_then(get_pool_state_handler);
         
+    let get_position_analytics = warp::path!("liquidity" / "pools" / String / "positions" / String / "analytics")
+        .and(warp::get())
+        .and(warp::query::<AnalyticsQuery>())
+        .and(warp::any().map(move || analytics.clone()))
+        .and_then(get_position_analytics_handler);
+        
     let get_user_positions = warp::path!("liquidity" / "positions" / "user" / String)
         .and(warp::get())
         .and(warp::any().map(move || client.clone()))
@@ -173,6 +187,7 @@ pub fn liquidity_pools_routes(
         .or(get_pools)
         .or(get_pool)
         .or(get_pool_state)
+        .or(get_position_analytics)
         .or(get_user_positions)
         .or(get_position)
 }
@@ -518,6 +533,42 @@ async fn get_pool_state_handler(
     Ok(warp::reply::json(&response))
 }
 
+async fn get_position_analytics_handler(
+    pool_id_hex: String,
+    owner_address: String,
+    query: AnalyticsQuery,
+    analytics: Arc<LpAnalytics>,
+) -> Result<impl Reply, Rejection> {
+    // Parse pool ID and owner
+    let pool_id = parse_bytes32(&pool_id_hex)?;
+    let owner = owner_address.parse::<Address>().map_err(|_| {
+        warp::reject::custom(ApiError {
+            message: "Invalid owner address".to_string(),
+        })
+    })?;
+    
+    let windows = match query.windows.as_deref() {
+        Some(windows) => AprWindow::parse_list(windows).map_err(|e| {
+            warp::reject::custom(ApiError {
+                message: e.to_string(),
+            })
+        })?,
+        None => AprWindow::DEFAULTS.to_vec(),
+    };
+    
+    // Reconstruct the position history and compute fees, IL and APR
+    let response = analytics
+        .position_analytics(pool_id, owner, &windows)
+        .await
+        .map_err(|e| {
+            warp::reject::custom(ApiError {
+                message: format!("Failed to compute position analytics: {}", e),
+            })
+        })?;
+    
+    Ok(warp::reply::json(&response))
+}
+
 async fn get_user_positions_handler(
     user_address: String,
     client_fn: Arc<dyn Fn() -> LiquidityPoolsClient<EthereumClient> + Send + Sync>,
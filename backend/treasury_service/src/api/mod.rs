@@ -16,13 +16,26 @@ use crate::{
         YieldOptimizerClient,
     },
     AssetManagementService,
+    PreTradeCompliance,
+    PreTradeRisk,
+    SettlementEngine,
+    OrderReconciler,
+    OrderReplayer,
+    TreasuryFeed,
+    FeeSchedule,
+    BusinessCalendar,
+    WithholdingTable,
+    DealingWindows,
+    LpAnalytics,
+    AutoCompounder,
+    ErrorEnvelope,
 };
 use warp::{Filter, Rejection, Reply};
 use std::sync::Arc;
 use std::convert::Infallible;
-use serde::{Serialize, Deserialize};
 use tracing::{info, error, debug};
 use http::StatusCode;
+use price_oracle::FxRateService;
 use ethereum_client::EthereumClient;
 use ethereum_client::Address;
 
@@ -38,6 +51,13 @@ mod environmental_assets;
 mod asset_factory_api;
 mod l2_bridge_api;
 mod smart_account_api;
+mod treasury_ws;
+mod fees;
+mod calendar;
+mod withholding;
+mod auto_compound;
+mod dealing;
+mod compression;
 
 // Re-export for easy access
 pub use auth::routes as auth_routes;
@@ -50,6 +70,13 @@ pub use yield_optimizer_api::yield_optimizer_routes;
 pub use environmental_assets::routes as environmental_assets_routes;
 pub use l2_bridge_api::routes as l2_bridge_routes;
 pub use smart_account_api::routes as smart_account_routes;
+pub use treasury_ws::{routes as treasury_ws_routes, TokenValidator};
+pub use fees::routes as fee_routes;
+pub use calendar::routes as calendar_routes;
+pub use withholding::routes as withholding_routes;
+pub use auto_compound::routes as auto_compound_routes;
+pub use dealing::routes as dealing_routes;
+pub use compression::{negotiate_compression, Encoding};
 
 /// Container for token clients
 #[derive(Clone)]
@@ -57,15 +84,6 @@ pub struct TokenClientsContainer {
     pub treasury_token_client: TreasuryTokenClient,
 }
 
-/// API error response
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ErrorResponse {
-    pub code: u16,
-    pub message: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub details: Option<String>,
-}
-
 /// All services required by the API
 pub struct ApiServices {
     pub treasury_service: Arc<TreasuryService>,
@@ -83,6 +101,26 @@ pub struct ApiServices {
     pub asset_factory_client: Arc<AssetFactoryClient<EthereumClient>>,
     pub liquidity_pools_client: Arc<LiquidityPoolsClient<EthereumClient>>,
     pub yield_optimizer_client: Arc<YieldOptimizerClient<EthereumClient>>,
+    pub pre_trade_compliance: Arc<PreTradeCompliance>,
+    /// Risk limit check on order placement; off unless a risk service is configured
+    pub pre_trade_risk: Option<Arc<PreTradeRisk>>,
+    pub treasury_feed: Arc<TreasuryFeed>,
+    pub settlement_engine: Arc<SettlementEngine>,
+    pub reconciler: Arc<OrderReconciler>,
+    /// Rebuilds the order ledger from trading contract history
+    pub order_replayer: Arc<OrderReplayer>,
+    pub fee_schedule: Arc<FeeSchedule>,
+    /// Holidays that move distribution and maturity dates
+    pub business_calendar: Arc<BusinessCalendar>,
+    pub withholding: Arc<WithholdingTable>,
+    /// Subscription and redemption windows, notice and lock-ups of assets that deal periodically
+    pub dealing_windows: Arc<DealingWindows>,
+    pub lp_analytics: Arc<LpAnalytics>,
+    pub auto_compound: Arc<AutoCompounder>,
+    /// Exchange rates for fee totals requested in a reporting currency
+    pub fx_rates: Arc<FxRateService>,
+    /// Fixed-point decimals of on-chain treasury prices
+    pub price_decimals: u32,
 }
 
 /// Create all API routes
@@ -109,7 +147,8 @@ pub fn routes(
     // Liquidity pool routes - use the client from ApiServices
     let liquidity_routes = liquidity_pools_api::liquidity_pools_routes(
         api_services.ethereum_client.clone(),
-        api_services.liquidity_pools_client.address
+        api_services.liquidity_pools_client.address,
+        api_services.lp_analytics.clone(),
     );
     
     // Yield optimizer routes - use the client from ApiServices
@@ -141,7 +180,28 @@ pub fn routes(
         api_services.smart_account_client.address
     );
     
-    // Combine all routes with prefix
+    // Treasury price and status feed
+    let treasury_ws_routes = treasury_ws::routes(
+        api_services.treasury_feed.clone(),
+        api_services.auth_service.clone(),
+    );
+    
+    // Fee schedule administration and accrual reports
+    let fee_routes = fees::routes(api_services.clone());
+    
+    // Market holidays for distribution and maturity scheduling
+    let calendar_routes = calendar::routes(api_services.clone());
+    
+    // Withholding rules, holder tax profiles and remittance reports
+    let withholding_routes = withholding::routes(api_services.clone());
+    
+    // Users' yield auto-compounding settings and compound history
+    let auto_compound_routes = auto_compound::routes(api_services.clone());
+    
+    // Dealing windows and lock-ups: admin terms, investor notices and lock-up status
+    let dealing_routes = dealing::routes(api_services.clone());
+    
+    // Combine all routes with prefix; JSON bodies are compressed when the client accepts it
     let api_routes = health_routes
         .or(auth_routes)
         .or(treasury_routes)
@@ -153,6 +213,13 @@ pub fn routes(
         .or(asset_factory_routes)
         .or(l2_bridge_routes)
         .or(smart_account_routes)
+        .or(treasury_ws_routes)
+        .or(fee_routes)
+        .or(calendar_routes)
+        .or(withholding_routes)
+        .or(auto_compound_routes)
+        .or(dealing_routes);
+    let api_routes = compression::negotiate_compression(api_routes)
         .with(warp::trace::request())
         .recover(handle_rejection);
     
@@ -172,23 +239,43 @@ pub struct ApiError(pub ServiceError);
 impl warp::reject::Reject for ApiError {}
 
 /// Convert ServiceError to API error response
-pub fn error_response(err: &ServiceError) -> (StatusCode, ErrorResponse) {
-    let (code, message) = match err {
-        ServiceError::NotFound(_) => (StatusCode::NOT_FOUND, "Resource not found"),
-        ServiceError::Unauthorized(_) => (StatusCode::UNAUTHORIZED, "Unauthorized"),
-        ServiceError::InvalidParameter(_) => (StatusCode::BAD_REQUEST, "Invalid parameter"),
-        ServiceError::ContractInteraction(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Blockchain interaction error"),
-        ServiceError::EthereumClient(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Ethereum client error"),
-        ServiceError::InvalidState(_) => (StatusCode::CONFLICT, "Invalid state"),
-        ServiceError::Unimplemented(_) => (StatusCode::NOT_IMPLEMENTED, "Feature not implemented"),
-        _ => (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error"),
+pub fn error_response(err: &ServiceError) -> (StatusCode, ErrorEnvelope) {
+    // Status follows the step's underlying error; the attempt id lets clients resume
+    if let ServiceError::CreationFailed { attempt_id, source, .. } = err {
+        let (code, response) = error_response(source);
+        return (code, response.with_details(err.to_string()).with_attempt_id(attempt_id.to_string()));
+    }
+    
+    // Clients get the failing call's index and reason as structured details
+    if let ServiceError::BatchCallFailed(failure) = err {
+        let code = StatusCode::UNPROCESSABLE_ENTITY;
+        let details = serde_json::to_value(failure).unwrap_or_default();
+        return (code, ErrorEnvelope::new("BATCH_CALL_FAILED", "A call in the batch failed", code.as_u16()).with_details(details));
+    }
+    
+    // The next window comes back structured so clients can tell investors when to return
+    if let ServiceError::DealingRejected(rejection) = err {
+        let code = StatusCode::FORBIDDEN;
+        let details = serde_json::to_value(rejection).unwrap_or_default();
+        return (code, ErrorEnvelope::new("DEALING_REJECTED", "Outside the asset's dealing terms", code.as_u16()).with_details(details));
+    }
+    
+    let (code, error, message) = match err {
+        ServiceError::NotFound(_) => (StatusCode::NOT_FOUND, "NOT_FOUND", "Resource not found"),
+        ServiceError::Unauthorized(_) => (StatusCode::UNAUTHORIZED, "UNAUTHORIZED", "Unauthorized"),
+        ServiceError::InvalidParameter(_) => (StatusCode::BAD_REQUEST, "INVALID_PARAMETER", "Invalid parameter"),
+        ServiceError::ContractInteraction(_) => (StatusCode::INTERNAL_SERVER_ERROR, "CONTRACT_INTERACTION", "Blockchain interaction error"),
+        ServiceError::EthereumClient(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ETHEREUM_CLIENT", "Ethereum client error"),
+        ServiceError::InvalidState(_) => (StatusCode::CONFLICT, "INVALID_STATE", "Invalid state"),
+        ServiceError::SymbolTaken { .. } => (StatusCode::CONFLICT, "SYMBOL_TAKEN", "Symbol already in use"),
+        ServiceError::ComplianceRejected(_) => (StatusCode::FORBIDDEN, "COMPLIANCE_REJECTED", "Compliance check failed"),
+        ServiceError::RiskLimitBreached(_) => (StatusCode::FORBIDDEN, "RISK_LIMIT_BREACHED", "Order would breach a risk limit"),
+        ServiceError::InvalidMetadata(_) => (StatusCode::UNPROCESSABLE_ENTITY, "INVALID_METADATA", "Invalid treasury metadata"),
+        ServiceError::Unimplemented(_) => (StatusCode::NOT_IMPLEMENTED, "NOT_IMPLEMENTED", "Feature not implemented"),
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL", "Internal server error"),
     };
     
-    (code, ErrorResponse {
-        code: code.as_u16(),
-        message: message.to_string(),
-        details: Some(err.to_string()),
-    })
+    (code, ErrorEnvelope::new(error, message, code.as_u16()).with_details(err.to_string()))
 }
 
 /// Handle all rejections and convert to error responses
@@ -198,39 +285,24 @@ async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
     } else if let Some(e) = err.find::<warp::filters::body::BodyDeserializeError>() {
         (
             StatusCode::BAD_REQUEST,
-            ErrorResponse {
-                code: StatusCode::BAD_REQUEST.as_u16(),
-                message: "Invalid request body".to_string(),
-                details: Some(e.to_string()),
-            },
+            ErrorEnvelope::new("INVALID_BODY", "Invalid request body", StatusCode::BAD_REQUEST.as_u16())
+                .with_details(e.to_string()),
         )
     } else if err.find::<warp::reject::MethodNotAllowed>().is_some() {
         (
             StatusCode::METHOD_NOT_ALLOWED,
-            ErrorResponse {
-                code: StatusCode::METHOD_NOT_ALLOWED.as_u16(),
-                message: "Method not allowed".to_string(),
-                details: None,
-            },
+            ErrorEnvelope::new("METHOD_NOT_ALLOWED", "Method not allowed", StatusCode::METHOD_NOT_ALLOWED.as_u16()),
         )
     } else if err.find::<warp::reject::MissingHeader>().is_some() {
         (
             StatusCode::BAD_REQUEST,
-            ErrorResponse {
-                code: StatusCode::BAD_REQUEST.as_u16(),
-                message: "Missing required header".to_string(),
-                details: None,
-            },
+            ErrorEnvelope::new("MISSING_HEADER", "Missing required header", StatusCode::BAD_REQUEST.as_u16()),
         )
     } else {
         error!("Unhandled rejection: {:?}", err);
         (
             StatusCode::INTERNAL_SERVER_ERROR,
-            ErrorResponse {
-                code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
-                message: "Unhandled rejection".to_string(),
-                details: None,
-            },
+            ErrorEnvelope::new("UNHANDLED_REJECTION", "Unhandled rejection", StatusCode::INTERNAL_SERVER_ERROR.as_u16()),
         )
     };
     
@@ -269,6 +341,23 @@ pub fn with_auth(auth_service: Arc<AuthenticationService>) -> impl Filter<Extrac
         })
 }
 
+/// Authentication middleware for platform administration; yields the admin's wallet
+pub fn with_admin(auth_service: Arc<AuthenticationService>) -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
+    with_auth(auth_service.clone())
+        .and_then(move |token: String| {
+            let auth_service = auth_service.clone();
+            async move {
+                let validation = auth_service.validate_token(&token);
+                match (validation.role.as_deref(), validation.wallet_address) {
+                    (Some("admin"), Some(wallet)) => Ok(format!("{:?}", wallet)),
+                    _ => Err(warp::reject::custom(ApiError(
+                        ServiceError::Unauthorized("Platform administration requires the admin role".into())
+                    ))),
+                }
+            }
+        })
+}
+
 /// Extract services from context
 pub fn with_services(services: Arc<ApiServices>) -> impl Filter<Extract = (Arc<ApiServices>,), Error = Infallible> + Clone {
     warp::any().map(move || services.clone())
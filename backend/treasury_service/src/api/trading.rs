@@ -1,12 +1,23 @@
 use crate::{
-    api::{ApiServices, ApiError, with_services, with_auth},
+    api::{ApiServices, ApiError, with_services, with_auth, with_admin},
+    clients::trading_client::{self, Error as TradingError, OrderSide, OrderPreview, TradingClient},
+    clients::smart_account_client::{Call, RevertPolicy, BatchResult, BatchFailure},
     Error as ServiceError,
+    SettlementFilter, SettlementStatus,
+    SettlementPrice, settlement_price, default_settlement_date,
+    ProposedTrade, RiskEvaluation,
+    ReplayRequest,
 };
+use rust_decimal::Decimal;
+use chrono::{NaiveDate, Utc};
 use serde::{Serialize, Deserialize};
 use warp::{Filter, Rejection, Reply};
 use std::sync::Arc;
-use tracing::{info, debug, error};
+use tracing::{info, debug, error, warn};
+use alloy_contract::Token;
 use alloy_primitives::{Address, U256};
+use ethereum_client::EthereumClient;
+use quantera_types::wire::{format_token_id, parse_token_id};
 use uuid::Uuid;
 
 /// Order type
@@ -44,6 +55,21 @@ pub struct PlaceOrderRequest {
     pub use_l2: Option<bool>, // Whether to place on L2
     #[serde(skip_serializing_if = "Option::is_none")]
     pub partition: Option<String>, // ERC-1400 partition
+    /// Place the order through the trader's smart account, batched with its token approval
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub smart_account_batch: Option<BatchExecutionRequest>,
+}
+
+/// Opt-in batched execution of an L1 order
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchExecutionRequest {
+    /// Token a buy order pays with; sell orders approve the treasury token instead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_token: Option<String>,
+    /// Continue-on-failure still places the order when the approval fails, e.g. because
+    /// the allowance is already set
+    #[serde(default)]
+    pub revert_policy: RevertPolicy,
 }
 
 /// Cancel order request
@@ -72,6 +98,42 @@ pub struct OrderResponse {
     pub gas_saved: Option<String>,
     pub partition: Option<String>,
     pub tx_hash: Option<String>,
+    /// Pre-trade compliance check the order was accepted under
+    pub compliance_check_id: Option<String>,
+    /// Pre-trade risk evaluation the order was accepted under
+    pub risk_evaluation_id: Option<String>,
+    /// Decision and breached limits of that evaluation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub risk_evaluation: Option<RiskEvaluation>,
+    /// Per-call results when the order was placed as a smart account batch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch: Option<BatchResult>,
+}
+
+/// Order preview request
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrderPreviewRequest {
+    pub side: String, // "buy" or "sell"
+    pub amount: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth: Option<u32>, // Book levels to read, defaults to 50
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settlement_date: Option<NaiveDate>, // Defaults to the next business day
+}
+
+/// Order preview with settlement pricing, so traders see the amount due including accrual
+#[derive(Debug, Serialize)]
+pub struct PricedOrderPreview {
+    #[serde(flatten)]
+    pub preview: OrderPreview,
+    /// Clean and dirty price per 100 of face value; absent when the treasury could not be priced
+    pub settlement: Option<SettlementPrice>,
+    /// Average fill price plus accrued interest, in registry price units
+    #[serde(with = "quantera_types::wire::option_u256_decimal")]
+    pub settlement_price: Option<U256>,
+    /// What the filled amount settles for, including accrued interest
+    #[serde(with = "quantera_types::wire::option_u256_decimal")]
+    pub settlement_amount: Option<U256>,
 }
 
 /// Create trading routes
@@ -105,10 +167,70 @@ pub fn routes(
         .and(with_services(services.clone()))
         .and_then(get_order_handler);
     
+    let preview_order_route = warp::path!("trading" / String / "preview")
+        .and(warp::post())
+        .and(with_auth(services.auth_service.clone()))
+        .and(warp::body::json())
+        .and(with_services(services.clone()))
+        .and_then(preview_order_handler);
+    
+    let get_settlements_route = warp::path!("trading" / "settlements")
+        .and(warp::get())
+        .and(warp::query::<SettlementQueryParams>())
+        .and(with_auth(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(get_settlements_handler);
+    
+    let get_settlement_route = warp::path!("trading" / "settlements" / Uuid)
+        .and(warp::get())
+        .and(with_auth(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(get_settlement_handler);
+    
+    let retry_settlement_route = warp::path!("trading" / "settlements" / Uuid / "retry")
+        .and(warp::post())
+        .and(with_auth(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(retry_settlement_handler);
+    
+    let downgrade_settlement_route = warp::path!("trading" / "settlements" / Uuid / "downgrade")
+        .and(warp::post())
+        .and(with_admin(services.auth_service.clone()))
+        .and(warp::body::json())
+        .and(with_services(services.clone()))
+        .and_then(downgrade_settlement_handler);
+    
+    let reconciliation_report_route = warp::path!("trading" / "reconciliation")
+        .and(warp::get())
+        .and(with_admin(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(reconciliation_report_handler);
+    
+    let run_reconciliation_route = warp::path!("trading" / "reconciliation" / "run")
+        .and(warp::post())
+        .and(with_admin(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(run_reconciliation_handler);
+    
+    let replay_orders_route = warp::path!("trading" / "replay")
+        .and(warp::post())
+        .and(with_admin(services.auth_service.clone()))
+        .and(warp::body::json())
+        .and(with_services(services.clone()))
+        .and_then(replay_orders_handler);
+    
     place_order_route
         .or(cancel_order_route)
         .or(get_orders_route)
         .or(get_order_route)
+        .or(preview_order_route)
+        .or(get_settlements_route)
+        .or(get_settlement_route)
+        .or(retry_settlement_route)
+        .or(downgrade_settlement_route)
+        .or(reconciliation_report_route)
+        .or(run_reconciliation_route)
+        .or(replay_orders_route)
 }
 
 /// Order query parameters
@@ -128,6 +250,24 @@ pub struct OrderQueryParams {
     pub offset: Option<usize>,
 }
 
+/// Settlement query parameters
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct SettlementQueryParams {
+    /// Buyer or seller
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wallet_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub treasury_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trade_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<usize>,
+}
+
 /// Place order handler
 async fn place_order_handler(
     _token: String, // From auth middleware
@@ -159,6 +299,33 @@ async fn place_order_handler(
     // Parse price
     let price = parse_decimal_str(&request.price)?;
     
+    // Subscriptions to assets with dealing terms are only taken while a window is open
+    if order_type == OrderType::Buy {
+        services.dealing_windows.check_subscription(treasury_id, Utc::now())
+            .map_err(|e| warp::reject::custom(ApiError(e)))?;
+    }
+    
+    // Run the transfer compliance pre-check before submitting
+    let side = match order_type {
+        OrderType::Buy => OrderSide::Buy,
+        OrderType::Sell => OrderSide::Sell,
+    };
+    let compliance_check_id = services.pre_trade_compliance.check_order(wallet_address, side, treasury_id, quantity)
+        .await
+        .map_err(|e| warp::reject::custom(ApiError(e)))?
+        .map(|record| record.check_id.to_string());
+    
+    // Evaluate the order against the portfolio's risk limits
+    let risk_evaluation = match &services.pre_trade_risk {
+        Some(pre_trade_risk) => {
+            let trade = proposed_trade(&services, treasury_id, order_type, &request.quantity, &request.price).await?;
+            Some(pre_trade_risk.check_order(wallet_address, trade)
+                .await
+                .map_err(|e| warp::reject::custom(ApiError(e)))?)
+        }
+        None => None,
+    };
+    
     // Check if user is verified
     let user_status = services.user_service.get_user_verification_status(wallet_address)
         .await
@@ -193,10 +360,33 @@ async fn place_order_handler(
                 ServiceError::InvalidState("Insufficient balance".into())
             )));
         }
+        
+        // Redemptions need an open window, notice where required and units out of lock-up
+        services.dealing_windows.check_redemption(wallet_address, treasury_id, quantity, balance, Utc::now())
+            .map_err(|e| warp::reject::custom(ApiError(e)))?;
     }
     
     // Place order on L2 if requested
-    let order_result = if request.use_l2.unwrap_or(false) {
+    let order_result = if let Some(batch) = &request.smart_account_batch {
+        if request.use_l2.unwrap_or(false) {
+            return Err(warp::reject::custom(ApiError(
+                ServiceError::InvalidParameter("Smart account batches are only available for L1 orders".into())
+            )));
+        }
+        place_batched_order(
+            &services,
+            wallet_address,
+            treasury_id,
+            order_type,
+            quantity,
+            price,
+            request.expiration,
+            request.partition.clone(),
+            batch,
+            compliance_check_id,
+            risk_evaluation.clone(),
+        ).await?
+    } else if request.use_l2.unwrap_or(false) {
         // Place order on L2
         place_l2_order(
             &services,
@@ -207,6 +397,8 @@ async fn place_order_handler(
             price,
             request.expiration,
             request.partition.clone(),
+            compliance_check_id,
+            risk_evaluation.clone(),
         ).await?
     } else {
         // Place order on L1
@@ -219,12 +411,46 @@ async fn place_order_handler(
             price,
             request.expiration,
             request.partition.clone(),
+            compliance_check_id,
+            risk_evaluation.clone(),
         ).await?
     };
     
+    if let (Some(pre_trade_risk), Some(evaluation)) = (&services.pre_trade_risk, risk_evaluation) {
+        pre_trade_risk.record_order(&order_result.order_id, evaluation).await;
+    }
+    
     Ok(warp::reply::json(&order_result))
 }
 
+/// The order as a trade in the treasury's token, in the units the risk service prices in
+async fn proposed_trade(
+    services: &Arc<ApiServices>,
+    treasury_id: [u8; 32],
+    order_type: OrderType,
+    quantity: &str,
+    price: &str,
+) -> Result<ProposedTrade, Rejection> {
+    let parse = |value: &str| value.replace(',', "").parse::<Decimal>()
+        .map_err(|_| warp::reject::custom(ApiError(
+            ServiceError::InvalidParameter(format!("Invalid numeric format: {}", value))
+        )));
+    let quantity = parse(quantity)?;
+    
+    let token_info = services.registry_client.get_treasury_details(treasury_id)
+        .await
+        .map_err(|e| warp::reject::custom(ApiError(e)))?;
+    
+    Ok(ProposedTrade {
+        asset: token_info.token_address,
+        quantity: match order_type {
+            OrderType::Buy => quantity,
+            OrderType::Sell => -quantity,
+        },
+        price: parse(price)?,
+    })
+}
+
 /// Place order on L1
 async fn place_l1_order(
     services: &Arc<ApiServices>,
@@ -235,6 +461,8 @@ async fn place_l1_order(
     price: U256,
     expiration: Option<u64>,
     partition: Option<String>,
+    compliance_check_id: Option<String>,
+    risk_evaluation: Option<RiskEvaluation>,
 ) -> Result<OrderResponse, Rejection> {
     // In a real implementation, this would interact with the TradingClient to place an order
     // For this example, we'll just create a mock order response
@@ -252,7 +480,7 @@ async fn place_l1_order(
     let order = OrderResponse {
         order_id,
         wallet_address: wallet_address.to_string(),
-        treasury_id: hex::encode(treasury_id),
+        treasury_id: format_token_id(&treasury_id),
         order_type: match order_type {
             OrderType::Buy => "buy".to_string(),
             OrderType::Sell => "sell".to_string(),
@@ -269,11 +497,127 @@ async fn place_l1_order(
         gas_saved: None,
         partition,
         tx_hash: Some(format!("0x{}", hex::encode(rand::random::<[u8; 32]>()))),
+        compliance_check_id,
+        risk_evaluation_id: risk_evaluation.as_ref()
+            .and_then(|evaluation| evaluation.evaluation_id)
+            .map(|id| id.to_string()),
+        risk_evaluation,
+        batch: None,
     };
     
     Ok(order)
 }
 
+/// Place an L1 order through the trader's smart account in one batch with the approval
+/// that lets the trading contract escrow the order's tokens
+async fn place_batched_order(
+    services: &Arc<ApiServices>,
+    wallet_address: Address,
+    treasury_id: [u8; 32],
+    order_type: OrderType,
+    quantity: U256,
+    price: U256,
+    expiration: Option<u64>,
+    partition: Option<String>,
+    batch: &BatchExecutionRequest,
+    compliance_check_id: Option<String>,
+    risk_evaluation: Option<RiskEvaluation>,
+) -> Result<OrderResponse, Rejection> {
+    let reject = |e: ServiceError| warp::reject::custom(ApiError(e));
+    
+    // Buys escrow the payment token, sells the treasury token itself
+    let (approve_token, approve_amount) = match order_type {
+        OrderType::Buy => {
+            let payment_token = batch.payment_token.as_deref()
+                .ok_or_else(|| reject(ServiceError::InvalidParameter("payment_token is required for batched buy orders".into())))?;
+            let amount = quantity.checked_mul(price)
+                .ok_or_else(|| reject(ServiceError::InvalidParameter("Order value overflows".into())))?;
+            (parse_address(payment_token)?, amount)
+        }
+        OrderType::Sell => {
+            let token_info = services.registry_client.get_treasury_details(treasury_id)
+                .await
+                .map_err(reject)?;
+            (token_info.token_address, quantity)
+        }
+    };
+    
+    let approve = EthereumClient::encode_function_call(
+        "approve(address,uint256)",
+        vec![Token::Address(services.trading_client.address()), Token::from(approve_amount)],
+    ).map_err(|e| reject(ServiceError::Encoding(e)))?;
+    let place = TradingClient::place_order_calldata(
+        treasury_id,
+        match order_type {
+            OrderType::Buy => OrderSide::Buy,
+            OrderType::Sell => OrderSide::Sell,
+        },
+        trading_client::OrderType::Limit,
+        price,
+        quantity,
+        expiration.unwrap_or_default(),
+    ).map_err(|e| reject(ServiceError::Encoding(e.to_string())))?;
+    let calls = vec![
+        Call { target: approve_token, value: U256::ZERO, data: approve },
+        Call { target: services.trading_client.address(), value: U256::ZERO, data: place },
+    ];
+    
+    let result = services.smart_account_client.execute_batch(wallet_address, calls.clone(), batch.revert_policy)
+        .await
+        .map_err(reject)?;
+    
+    // A failed approval is tolerated under continue-on-failure; a failed placement is not
+    let placement = result.results.get(1)
+        .ok_or_else(|| reject(ServiceError::Decoding("Batch returned no result for the order placement".into())))?;
+    if !placement.success {
+        return Err(reject(ServiceError::BatchCallFailed(BatchFailure {
+            index: placement.index,
+            target: calls[placement.index].target,
+            reason: placement.revert_reason.clone().unwrap_or_else(|| "reverted without a reason".to_string()),
+        })));
+    }
+    if let Some(failure) = result.first_failure(&calls) {
+        warn!("Batched order for {:?} placed despite {}", wallet_address, failure);
+    }
+    
+    let order_id = (placement.return_data.len() >= 32)
+        .then(|| U256::from_be_slice(&placement.return_data[..32]).to_string())
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    
+    Ok(OrderResponse {
+        order_id,
+        wallet_address: wallet_address.to_string(),
+        treasury_id: format_token_id(&treasury_id),
+        order_type: match order_type {
+            OrderType::Buy => "buy".to_string(),
+            OrderType::Sell => "sell".to_string(),
+        },
+        quantity: quantity.to_string(),
+        price: price.to_string(),
+        status: "open".to_string(),
+        created_at: now,
+        updated_at: now,
+        filled_quantity: "0".to_string(),
+        remaining_quantity: quantity.to_string(),
+        expiration,
+        is_l2: false,
+        gas_saved: None,
+        partition,
+        tx_hash: None,
+        compliance_check_id,
+        risk_evaluation_id: risk_evaluation.as_ref()
+            .and_then(|evaluation| evaluation.evaluation_id)
+            .map(|id| id.to_string()),
+        risk_evaluation,
+        batch: Some(result),
+    })
+}
+
 /// Place order on L2
 async fn place_l2_order(
     services: &Arc<ApiServices>,
@@ -284,6 +628,8 @@ async fn place_l2_order(
     price: U256,
     expiration: Option<u64>,
     partition: Option<String>,
+    compliance_check_id: Option<String>,
+    risk_evaluation: Option<RiskEvaluation>,
 ) -> Result<OrderResponse, Rejection> {
     // In a real implementation, this would interact with the L2Client to place an order on L2
     // For this example, we'll just create a mock order response
@@ -301,7 +647,7 @@ async fn place_l2_order(
     let order = OrderResponse {
         order_id,
         wallet_address: wallet_address.to_string(),
-        treasury_id: hex::encode(treasury_id),
+        treasury_id: format_token_id(&treasury_id),
         order_type: match order_type {
             OrderType::Buy => "buy".to_string(),
             OrderType::Sell => "sell".to_string(),
@@ -318,6 +664,12 @@ async fn place_l2_order(
         gas_saved: Some("85%".to_string()),  // Mock gas savings from L2
         partition,
         tx_hash: Some(format!("0x{}", hex::encode(rand::random::<[u8; 32]>()))),
+        compliance_check_id,
+        risk_evaluation_id: risk_evaluation.as_ref()
+            .and_then(|evaluation| evaluation.evaluation_id)
+            .map(|id| id.to_string()),
+        risk_evaluation,
+        batch: None,
     };
     
     Ok(order)
@@ -396,7 +748,7 @@ async fn get_orders_handler(
         let order = OrderResponse {
             order_id: Uuid::new_v4().to_string(),
             wallet_address: wallet_address.unwrap_or(Address::ZERO).to_string(),
-            treasury_id: treasury_id.map(hex::encode).unwrap_or_else(|| hex::encode(rand::random::<[u8; 32]>())),
+            treasury_id: format_token_id(&treasury_id.unwrap_or_else(rand::random)),
             order_type: order_type.to_string(),
             quantity: format!("{}", (i + 1) * 1000),
             price: format!("{}", 100 + i * 5),
@@ -422,6 +774,10 @@ async fn get_orders_handler(
             gas_saved: if i % 3 == 0 { Some("82%".to_string()) } else { None },
             partition: if i % 4 == 0 { Some("default".to_string()) } else { None },
             tx_hash: Some(format!("0x{}", hex::encode(rand::random::<[u8; 32]>()))),
+            compliance_check_id: None,
+            risk_evaluation_id: None,
+            risk_evaluation: None,
+            batch: None,
         };
         
         orders.push(order);
@@ -461,11 +817,17 @@ async fn get_order_handler(
     // In a real implementation, this would fetch the order from the TradingClient
     // For this example, we'll just create a mock order
     
+    // Decision of the pre-trade risk evaluation the order was placed under
+    let risk_evaluation = match &services.pre_trade_risk {
+        Some(pre_trade_risk) => pre_trade_risk.evaluation_for_order(&order_id).await,
+        None => None,
+    };
+    
     // Create mock order
     let order = OrderResponse {
         order_id: order_id.clone(),
         wallet_address: Address::ZERO.to_string(),
-        treasury_id: hex::encode(rand::random::<[u8; 32]>()),
+        treasury_id: format_token_id(&rand::random()),
         order_type: "buy".to_string(),
         quantity: "1000".to_string(),
         price: "105".to_string(),
@@ -479,11 +841,78 @@ async fn get_order_handler(
         gas_saved: None,
         partition: None,
         tx_hash: Some(format!("0x{}", hex::encode(rand::random::<[u8; 32]>()))),
+        compliance_check_id: None,
+        risk_evaluation_id: risk_evaluation.as_ref()
+            .and_then(|evaluation| evaluation.evaluation_id)
+            .map(|id| id.to_string()),
+        risk_evaluation,
+        batch: None,
     };
     
     Ok(warp::reply::json(&order))
 }
 
+/// Preview order handler
+///
+/// Returns the expected fill against the current book. The preview is non-binding and
+/// carries the block number of the snapshot it was computed from.
+async fn preview_order_handler(
+    token_id: String,
+    _token: String, // From auth middleware
+    request: OrderPreviewRequest,
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    debug!("Previewing {} order for token: {}", request.side, token_id);
+    
+    let token_id = parse_treasury_id(&token_id)?;
+    
+    let side = match request.side.to_lowercase().as_str() {
+        "buy" => OrderSide::Buy,
+        "sell" => OrderSide::Sell,
+        _ => {
+            return Err(warp::reject::custom(ApiError(
+                ServiceError::InvalidParameter("Invalid order side".into())
+            )));
+        }
+    };
+    
+    let amount = parse_decimal_str(&request.amount)?;
+    let depth = request.depth.unwrap_or(50).clamp(1, 500);
+    
+    let preview = services.trading_client.preview_order(token_id, side, amount, depth)
+        .await
+        .map_err(|e| warp::reject::custom(ApiError(match e {
+            TradingError::Order(msg) => ServiceError::InvalidParameter(msg),
+            e => ServiceError::ContractInteraction(e.to_string()),
+        })))?;
+    
+    // Pricing is informational; a treasury that cannot be priced still gets its fill preview
+    let settlement_date = request.settlement_date
+        .unwrap_or_else(|| default_settlement_date(chrono::Utc::now().date_naive()));
+    let settlement = match services.treasury_service.get_treasury_details(token_id).await {
+        Ok(info) => settlement_price(&info, settlement_date, services.price_decimals)
+            .map_err(|e| warn!("Settlement pricing for order preview failed: {}", e))
+            .ok(),
+        Err(e) => {
+            warn!("Treasury details for order preview unavailable: {}", e);
+            None
+        }
+    };
+    let unit_price = match (&settlement, preview.average_price) {
+        (Some(settlement), Some(average)) => settlement.accrued_fixed_point(services.price_decimals)
+            .ok()
+            .map(|accrued| average + accrued),
+        _ => None,
+    };
+    
+    Ok(warp::reply::json(&PricedOrderPreview {
+        settlement_amount: unit_price.map(|price| price * preview.filled_amount),
+        settlement_price: unit_price,
+        settlement,
+        preview,
+    }))
+}
+
 /// Parse address from string
 fn parse_address(address: &str) -> Result<Address, Rejection> {
     Address::parse_checksummed(address, None)
@@ -492,23 +921,134 @@ fn parse_address(address: &str) -> Result<Address, Rejection> {
         )))
 }
 
-/// Parse treasury ID from hex string
-fn parse_treasury_id(id: &str) -> Result<[u8; 32], Rejection> {
-    let id_cleaned = id.trim_start_matches("0x");
-    let bytes = hex::decode(id_cleaned)
-        .map_err(|_| warp::reject::custom(ApiError(
-            ServiceError::InvalidParameter("Invalid treasury ID format".into())
-        )))?;
+/// List settlements, newest first
+async fn get_settlements_handler(
+    params: SettlementQueryParams,
+    _token: String, // From auth middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    debug!("Getting settlements with filters: {:?}", params);
+    
+    let filter = SettlementFilter {
+        status: params.status.as_deref()
+            .map(str::parse::<SettlementStatus>)
+            .transpose()
+            .map_err(|e| warp::reject::custom(ApiError(e)))?,
+        party: params.wallet_address.as_deref().map(parse_address).transpose()?,
+        token_id: params.treasury_id.as_deref().map(parse_treasury_id).transpose()?,
+        trade_id: params.trade_id,
+    };
+    
+    let settlements: Vec<_> = services.settlement_engine.list(&filter).await
+        .into_iter()
+        .skip(params.offset.unwrap_or(0))
+        .take(params.limit.unwrap_or(50).min(500))
+        .collect();
+    
+    Ok(warp::reply::json(&settlements))
+}
+
+/// One settlement with its attempts and transaction hashes
+async fn get_settlement_handler(
+    settlement_id: Uuid,
+    _token: String, // From auth middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    let settlement = services.settlement_engine.get(settlement_id)
+        .await
+        .map_err(|e| warp::reject::custom(ApiError(e)))?;
+    
+    Ok(warp::reply::json(&settlement))
+}
+
+/// Retry a failed settlement, e.g. once the buyer has approved the payment
+async fn retry_settlement_handler(
+    settlement_id: Uuid,
+    _token: String, // From auth middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    info!("Retrying settlement: {}", settlement_id);
+    
+    let settlement = services.settlement_engine.retry(settlement_id)
+        .await
+        .map_err(|e| warp::reject::custom(ApiError(e)))?;
     
-    if bytes.len() != 32 {
+    Ok(warp::reply::json(&settlement))
+}
+
+/// Why a settlement's swap is no longer considered confirmed
+#[derive(Debug, Deserialize)]
+pub struct DowngradeRequest {
+    pub reason: String,
+}
+
+/// Return a Confirmed settlement to Pending finality after a reorg; admin only, audited
+async fn downgrade_settlement_handler(
+    settlement_id: Uuid,
+    admin: String,
+    request: DowngradeRequest,
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    if request.reason.trim().is_empty() {
         return Err(warp::reject::custom(ApiError(
-            ServiceError::InvalidParameter("Treasury ID must be 32 bytes".into())
+            ServiceError::InvalidParameter("A reason is required to downgrade a settlement".into())
         )));
     }
     
-    let mut result = [0u8; 32];
-    result.copy_from_slice(&bytes);
-    Ok(result)
+    let settlement = services.settlement_engine.downgrade_finality(settlement_id, request.reason.trim(), &admin)
+        .await
+        .map_err(|e| warp::reject::custom(ApiError(e)))?;
+    
+    Ok(warp::reply::json(&settlement))
+}
+
+/// Recent reconciliation runs, newest first; admin only
+async fn reconciliation_report_handler(
+    _admin: String,
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    Ok(warp::reply::json(&services.reconciler.reports().await))
+}
+
+/// Reconcile now instead of waiting for the next scheduled run; admin only, audited
+async fn run_reconciliation_handler(
+    admin: String,
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    info!("[AUDIT] {} started an order reconciliation run", admin);
+    
+    let report = services.reconciler.run()
+        .await
+        .map_err(|e| warp::reject::custom(ApiError(e)))?;
+    
+    Ok(warp::reply::json(&report))
+}
+
+/// Rebuild the order ledger from trading contract events, swapping it in when asked and it
+/// validates against the live ledger; admin only, audited. Rerunning an interrupted replay
+/// with the same request resumes it.
+async fn replay_orders_handler(
+    admin: String,
+    request: ReplayRequest,
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    info!(
+        "[AUDIT] {} started an order ledger replay from block {} (swap: {})",
+        admin, request.from_block, request.swap
+    );
+    
+    let report = services.order_replayer.run(request)
+        .await
+        .map_err(|e| warp::reject::custom(ApiError(e)))?;
+    
+    Ok(warp::reply::json(&report))
+}
+
+/// Parse treasury ID from hex string
+fn parse_treasury_id(id: &str) -> Result<[u8; 32], Rejection> {
+    parse_token_id(id).map_err(|e| warp::reject::custom(ApiError(
+        ServiceError::InvalidParameter(format!("Invalid treasury ID: {}", e))
+    )))
 }
 
 /// Parse decimal string to U256
@@ -1,13 +1,15 @@
 use crate::{
-    api::{ApiServices, ApiError, with_services, with_auth},
+    api::{ApiServices, ApiError, with_services, with_auth, with_admin},
     Error as ServiceError,
-    TreasuryType, TreasuryOverview, TreasuryInfo, TreasuryMetadata,
+    TreasuryType, TreasuryOverview, TreasuryInfo, TreasuryMetadata, TreasuryRegistration,
+    TreasuryStatus, Currency, DEFAULT_RESERVATION_SECS,
 };
 use serde::{Serialize, Deserialize};
 use warp::{Filter, Rejection, Reply};
 use std::sync::Arc;
-use tracing::{info, debug, error};
+use tracing::{info, debug, error, warn};
 use alloy_primitives::{U256, Address};
+use quantera_types::{Pagination, PaginationError, SortSpec};
 
 /// Treasury filter parameters
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
@@ -20,10 +22,84 @@ pub struct TreasuryQueryParams {
     pub min_yield: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_maturity: Option<u64>,
+    /// Legacy paging; the response stays a bare array unless `page`, `per_page` or `cursor` is given
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub offset: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_page: Option<u32>,
+    /// `next_cursor` of the previous page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    /// Sort order over `SORT_FIELDS`, e.g. `-yield_rate,maturity_date`; registry order when absent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    /// Comma-separated fields to return per treasury, e.g. `token_id,current_price`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<String>,
+    /// Omit the fields derived from each treasury's pinned metadata
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<bool>,
+}
+
+/// Fields of a treasury listing row
+const OVERVIEW_FIELDS: &[&str] = &[
+    "token_id", "token_address", "name", "symbol", "treasury_type",
+    "current_price", "yield_rate", "maturity_date", "status", "validation_error",
+];
+
+/// Fields a treasury listing can be sorted by
+const SORT_FIELDS: &[&str] = &["name", "symbol", "current_price", "yield_rate", "maturity_date"];
+
+/// Rows of a bare-array listing when no `limit` is given
+const LEGACY_LIMIT: usize = 10;
+
+/// Listing fields that come from the treasury's metadata rather than the registry
+const METADATA_FIELDS: &[&str] = &["name", "symbol", "validation_error"];
+
+/// Fields to keep per listing row, or None for full rows
+fn listing_fields(params: &TreasuryQueryParams) -> Result<Option<Vec<&'static str>>, ServiceError> {
+    let summary = params.summary.unwrap_or(false);
+    let mut fields = match &params.fields {
+        Some(requested) => {
+            let mut fields = Vec::new();
+            for name in requested.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+                let field = OVERVIEW_FIELDS.iter()
+                    .find(|field| **field == name)
+                    .ok_or_else(|| ServiceError::InvalidParameter(format!("Unknown treasury field: {}", name)))?;
+                if !fields.contains(field) {
+                    fields.push(*field);
+                }
+            }
+            fields
+        }
+        None if summary => OVERVIEW_FIELDS.to_vec(),
+        None => return Ok(None),
+    };
+    if summary {
+        fields.retain(|field| !METADATA_FIELDS.contains(field));
+    }
+    Ok(Some(fields))
+}
+
+/// Project listing rows onto `fields` before they are serialized into the response
+fn project_overviews(
+    treasuries: &[TreasuryOverview],
+    fields: &[&str],
+) -> Result<Vec<serde_json::Map<String, serde_json::Value>>, ServiceError> {
+    treasuries.iter()
+        .map(|treasury| {
+            let mut row = match serde_json::to_value(treasury) {
+                Ok(serde_json::Value::Object(row)) => row,
+                _ => return Err(ServiceError::Internal("Treasury overview did not serialize to an object".into())),
+            };
+            row.retain(|key, _| fields.contains(&key.as_str()));
+            Ok(row)
+        })
+        .collect()
 }
 
 /// Treasury creation request
@@ -35,8 +111,59 @@ pub struct CreateTreasuryRequest {
     pub treasury_type: String,
     pub total_supply: String,
     pub face_value: String,
+    /// ISO 4217 code of the face value; defaults to USD
+    #[serde(default)]
+    pub currency: Currency,
     pub yield_rate: u64,
     pub maturity_date: u64,
+    /// Distinguishes a re-registration of the same instrument and dates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tranche: Option<String>,
+}
+
+/// Created treasury, with anything the issuer should double-check
+#[derive(Debug, Serialize)]
+pub struct CreateTreasuryResponse {
+    #[serde(flatten)]
+    pub overview: TreasuryOverview,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// Symbol reservation request
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReserveSymbolRequest {
+    pub symbol: String,
+    /// How long to hold the symbol; defaults to seven days
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hold_secs: Option<u64>,
+}
+
+/// Batch registration request
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RegisterTreasuriesRequest {
+    pub registrations: Vec<TreasuryRegistration>,
+}
+
+/// Outcome of one batch registration row
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RegistrationRowResult {
+    pub row: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Bulk status change request
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkStatusChangeRequest {
+    /// Hex token ids
+    pub token_ids: Vec<String>,
+    pub new_status: TreasuryStatus,
+    /// Report the outcome per treasury without submitting the change
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
 /// Create treasury routes
@@ -61,15 +188,81 @@ pub fn routes(
         .and(with_services(services.clone()))
         .and_then(create_treasury_handler);
     
+    let register_batch_route = warp::path!("treasuries" / "batch")
+        .and(warp::post())
+        .and(with_auth(services.auth_service.clone()))
+        .and(warp::body::json())
+        .and(with_services(services.clone()))
+        .and_then(register_treasuries_handler);
+    
+    let attempt_route = warp::path!("treasuries" / "attempts" / String)
+        .and(warp::get())
+        .and(with_auth(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(get_creation_attempt_handler);
+    
+    let resume_route = warp::path!("treasuries" / "attempts" / String / "resume")
+        .and(warp::post())
+        .and(with_auth(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(resume_creation_handler);
+    
+    let reserve_symbol_route = warp::path!("treasuries" / "symbols" / "reservations")
+        .and(warp::post())
+        .and(with_auth(services.auth_service.clone()))
+        .and(warp::body::json())
+        .and(with_services(services.clone()))
+        .and_then(reserve_symbol_handler);
+    
+    let release_symbol_route = warp::path!("treasuries" / "symbols" / "reservations" / String)
+        .and(warp::delete())
+        .and(with_auth(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(release_symbol_handler);
+    
     let yield_info_route = warp::path!("treasuries" / String / "yield")
         .and(warp::get())
         .and(with_services(services.clone()))
         .and_then(get_treasury_yield_handler);
     
+    let bulk_status_route = warp::path!("admin" / "treasuries" / "bulk-status")
+        .and(warp::post())
+        .and(warp::body::json::<BulkStatusChangeRequest>())
+        .and(with_admin(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(bulk_status_handler);
+    
+    let get_bulk_status_route = warp::path!("admin" / "treasuries" / "bulk-status" / String)
+        .and(warp::get())
+        .and(with_admin(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(get_bulk_status_handler);
+    
+    let approve_bulk_status_route = warp::path!("admin" / "treasuries" / "bulk-status" / String / "approve")
+        .and(warp::post())
+        .and(with_admin(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(approve_bulk_status_handler);
+    
+    let resume_bulk_status_route = warp::path!("admin" / "treasuries" / "bulk-status" / String / "resume")
+        .and(warp::post())
+        .and(with_admin(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(resume_bulk_status_handler);
+    
     list_route
         .or(detail_route)
         .or(create_route)
+        .or(register_batch_route)
+        .or(attempt_route)
+        .or(resume_route)
+        .or(reserve_symbol_route)
+        .or(release_symbol_route)
         .or(yield_info_route)
+        .or(bulk_status_route)
+        .or(get_bulk_status_route)
+        .or(approve_bulk_status_route)
+        .or(resume_bulk_status_route)
 }
 
 /// List treasuries handler
@@ -78,6 +271,16 @@ async fn list_treasuries_handler(
     services: Arc<ApiServices>,
 ) -> Result<impl Reply, Rejection> {
     info!("Listing treasuries with filters: {:?}", params);
+    let fields = listing_fields(&params).map_err(|e| warp::reject::custom(ApiError(e)))?;
+    let invalid = |e: PaginationError| warp::reject::custom(ApiError(ServiceError::InvalidParameter(e.to_string())));
+    let paged = params.page.is_some() || params.per_page.is_some() || params.cursor.is_some();
+    let pagination = if paged {
+        Pagination::new(params.page, params.per_page, params.cursor.as_deref())
+    } else {
+        Pagination::from_offset(params.offset.unwrap_or(0), params.limit.unwrap_or(LEGACY_LIMIT))
+    }
+        .map_err(invalid)?;
+    let sort = SortSpec::parse(params.sort.as_deref(), "", SORT_FIELDS).map_err(invalid)?;
     
     // Get all treasuries
     let mut treasuries = services.treasury_service
@@ -87,14 +290,7 @@ async fn list_treasuries_handler(
     
     // Apply filters
     if let Some(type_str) = &params.treasury_type {
-        let treasury_type = match type_str.as_str() {
-            "tbill" => Some(TreasuryType::TBill),
-            "tnote" => Some(TreasuryType::TNote),
-            "tbond" => Some(TreasuryType::TBond),
-            _ => None,
-        };
-        
-        if let Some(t_type) = treasury_type {
+        if let Ok(t_type) = type_str.parse::<TreasuryType>() {
             treasuries.retain(|t| t.treasury_type == t_type);
         }
     }
@@ -107,18 +303,32 @@ async fn list_treasuries_handler(
         treasuries.retain(|t| t.maturity_date <= max_maturity);
     }
     
-    // Paginate results
-    let limit = params.limit.unwrap_or(10).min(100);
-    let offset = params.offset.unwrap_or(0);
+    sort.sort(&mut treasuries, |field, a, b| match field {
+        "name" => a.name.cmp(&b.name),
+        "symbol" => a.symbol.cmp(&b.symbol),
+        "current_price" => a.current_price.cmp(&b.current_price),
+        "yield_rate" => a.yield_rate.cmp(&b.yield_rate),
+        _ => a.maturity_date.cmp(&b.maturity_date),
+    });
     
-    let paginated = if offset < treasuries.len() {
-        let end = (offset + limit).min(treasuries.len());
-        treasuries[offset..end].to_vec()
-    } else {
-        vec![]
+    let selected = pagination.slice(&treasuries);
+    let rows: Vec<serde_json::Value> = match fields {
+        Some(fields) => project_overviews(selected, &fields)
+            .map_err(|e| warp::reject::custom(ApiError(e)))?
+            .into_iter()
+            .map(serde_json::Value::Object)
+            .collect(),
+        None => selected.iter()
+            .map(serde_json::to_value)
+            .collect::<Result<_, _>>()
+            .map_err(|e| warp::reject::custom(ApiError(ServiceError::Internal(e.to_string()))))?,
     };
     
-    Ok(warp::reply::json(&paginated))
+    if paged {
+        Ok(warp::reply::json(&pagination.page_of(rows, treasuries.len())))
+    } else {
+        Ok(warp::reply::json(&rows))
+    }
 }
 
 /// Get treasury details handler
@@ -148,12 +358,16 @@ async fn create_treasury_handler(
 ) -> Result<impl Reply, Rejection> {
     info!("Creating new treasury: {}", request.name);
 
+    // A maturity on a weekend or holiday is accepted but settles on an adjusted date
+    let maturity_warning = services.treasury_service.maturity_warning(request.maturity_date);
+    if let Some(warning) = &maturity_warning {
+        warn!("Treasury {}: {}", request.name, warning);
+    }
+
     // Parse treasury type
-    let treasury_type = match request.treasury_type.to_lowercase().as_str() {
-        "tbill" => TreasuryType::TBill,
-        "tnote" => TreasuryType::TNote,
-        "tbond" => TreasuryType::TBond,
-        _ => {
+    let treasury_type: TreasuryType = match request.treasury_type.parse() {
+        Ok(treasury_type) => treasury_type,
+        Err(_) => {
             error!("Invalid treasury type: {}", request.treasury_type);
             return Err(warp::reject::custom(ApiError(
                 ServiceError::InvalidParameter("Invalid treasury type".into())
@@ -217,10 +431,12 @@ async fn create_treasury_handler(
         total_supply,
         treasury_type,
         face_value,
+        request.currency,
         request.yield_rate,
         issuance_date,
         request.maturity_date,
         issuer_address,
+        request.tranche,
     ).await.map_err(|e| {
         error!("Failed to create treasury: {}", e);
         warp::reject::custom(ApiError(e))
@@ -229,9 +445,182 @@ async fn create_treasury_handler(
     info!("Treasury created: {:?}", overview);
     // TODO: Emit event to audit log or event bus
 
+    Ok(warp::reply::json(&CreateTreasuryResponse {
+        overview,
+        warnings: maturity_warning.into_iter().collect(),
+    }))
+}
+
+/// Get a treasury creation attempt and the steps it completed
+async fn get_creation_attempt_handler(
+    id: String,
+    _token: String, // From auth middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    let attempt_id = parse_attempt_id(&id)?;
+    
+    let attempt = services.treasury_service
+        .get_creation_attempt(&attempt_id)
+        .ok_or_else(|| warp::reject::custom(ApiError(ServiceError::NotFound(format!("Creation attempt {}", attempt_id)))))?;
+    
+    Ok(warp::reply::json(&attempt))
+}
+
+/// Resume a failed treasury creation, reusing any contract it already deployed
+async fn resume_creation_handler(
+    id: String,
+    _token: String, // From auth middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    let attempt_id = parse_attempt_id(&id)?;
+    info!("Resuming treasury creation attempt {}", attempt_id);
+    
+    let overview = services.treasury_service
+        .resume_treasury_creation(attempt_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to resume treasury creation {}: {}", attempt_id, e);
+            warp::reject::custom(ApiError(e))
+        })?;
+    
+    info!("Treasury created: {:?}", overview);
     Ok(warp::reply::json(&overview))
 }
 
+/// Hold a symbol for the issuer during onboarding; 409 if another treasury or issuer holds it
+async fn reserve_symbol_handler(
+    _token: String, // From auth middleware
+    request: ReserveSymbolRequest,
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    // Same placeholder issuer as create_treasury_handler until auth carries the address
+    let issuer_address = Address::ZERO;
+    
+    let reservation = services.treasury_service
+        .reserve_symbol(&request.symbol, issuer_address, request.hold_secs.unwrap_or(DEFAULT_RESERVATION_SECS))
+        .map_err(|e| warp::reject::custom(ApiError(e)))?;
+    
+    Ok(warp::reply::with_status(warp::reply::json(&reservation), warp::http::StatusCode::CREATED))
+}
+
+/// Release the issuer's reservation of a symbol
+async fn release_symbol_handler(
+    symbol: String,
+    _token: String, // From auth middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    let issuer_address = Address::ZERO;
+    
+    services.treasury_service
+        .release_symbol(&symbol, issuer_address)
+        .map_err(|e| warp::reject::custom(ApiError(e)))?;
+    
+    Ok(warp::reply::with_status(warp::reply(), warp::http::StatusCode::NO_CONTENT))
+}
+
+fn parse_attempt_id(id: &str) -> Result<uuid::Uuid, Rejection> {
+    id.parse::<uuid::Uuid>()
+        .map_err(|_| warp::reject::custom(ApiError(ServiceError::InvalidParameter(format!("Invalid attempt id: {}", id)))))
+}
+
+/// Register already-deployed treasury tokens in bulk, reporting each row's token id or
+/// collision separately
+async fn register_treasuries_handler(
+    _token: String, // From auth middleware
+    request: RegisterTreasuriesRequest,
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    info!("Registering batch of {} treasuries", request.registrations.len());
+    
+    let results = services.registry_client
+        .register_treasuries(&request.registrations)
+        .await
+        .into_iter()
+        .enumerate()
+        .map(|(row, result)| match result {
+            Ok(token_id) => RegistrationRowResult { row, token_id: Some(format!("0x{}", hex::encode(token_id))), error: None },
+            Err(e) => RegistrationRowResult { row, token_id: None, error: Some(e.to_string()) },
+        })
+        .collect::<Vec<_>>();
+    
+    Ok(warp::reply::json(&results))
+}
+
+/// Preview a bulk status change, or submit it for approval by another admin
+async fn bulk_status_handler(
+    request: BulkStatusChangeRequest,
+    admin: String, // From admin middleware
+    services: Arc<ApiServices>,
+) -> Result<Box<dyn Reply>, Rejection> {
+    let token_ids = request.token_ids.iter()
+        .map(|id| parse_treasury_id(id))
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    if request.dry_run {
+        let report = services.treasury_service
+            .bulk_update_status(&token_ids, request.new_status, true)
+            .await
+            .map_err(|e| warp::reject::custom(ApiError(e)))?;
+        return Ok(Box::new(warp::reply::json(&report)));
+    }
+    
+    info!("Bulk status change of {} treasuries to {:?} requested by {}", token_ids.len(), request.new_status, admin);
+    let pending = services.treasury_service
+        .request_bulk_status(token_ids, request.new_status, &admin)
+        .await
+        .map_err(|e| warp::reject::custom(ApiError(e)))?;
+    
+    Ok(Box::new(warp::reply::with_status(warp::reply::json(&pending), warp::http::StatusCode::ACCEPTED)))
+}
+
+async fn get_bulk_status_handler(
+    id: String,
+    _admin: String, // From admin middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    let request_id = parse_bulk_status_id(&id)?;
+    let request = services.treasury_service
+        .get_bulk_status(&request_id)
+        .ok_or_else(|| warp::reject::custom(ApiError(ServiceError::NotFound(format!("Bulk status request {}", id)))))?;
+    
+    Ok(warp::reply::json(&request))
+}
+
+/// Approve a bulk status change submitted by another admin and run it
+async fn approve_bulk_status_handler(
+    id: String,
+    admin: String, // From admin middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    let request_id = parse_bulk_status_id(&id)?;
+    let request = services.treasury_service
+        .approve_bulk_status(request_id, &admin)
+        .await
+        .map_err(|e| warp::reject::custom(ApiError(e)))?;
+    
+    Ok(warp::reply::json(&request))
+}
+
+/// Retry the treasuries that failed in the last run of a bulk status change
+async fn resume_bulk_status_handler(
+    id: String,
+    _admin: String, // From admin middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    let request_id = parse_bulk_status_id(&id)?;
+    let request = services.treasury_service
+        .resume_bulk_status(request_id)
+        .await
+        .map_err(|e| warp::reject::custom(ApiError(e)))?;
+    
+    Ok(warp::reply::json(&request))
+}
+
+fn parse_bulk_status_id(id: &str) -> Result<uuid::Uuid, Rejection> {
+    id.parse::<uuid::Uuid>()
+        .map_err(|_| warp::reject::custom(ApiError(ServiceError::InvalidParameter(format!("Invalid bulk status request id: {}", id)))))
+}
+
 /// Get treasury yield information
 async fn get_treasury_yield_handler(
     id: String,
@@ -314,4 +703,56 @@ fn parse_decimal_string(value: &str) -> Result<U256, ServiceError> {
         },
         _ => Err(ServiceError::InvalidParameter(format!("Invalid number format: {}", value))),
     }
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TreasuryStatus;
+
+    fn overview() -> TreasuryOverview {
+        TreasuryOverview {
+            token_id: [7u8; 32],
+            token_address: Address::repeat_byte(0x11),
+            name: "13 Week T-Bill".to_string(),
+            symbol: "TB13W".to_string(),
+            treasury_type: TreasuryType::TBill,
+            current_price: U256::from(99_500u64),
+            yield_rate: 525,
+            maturity_date: 1_800_000_000,
+            status: TreasuryStatus::Active,
+            validation_error: None,
+        }
+    }
+
+    fn params(fields: Option<&str>, summary: Option<bool>) -> TreasuryQueryParams {
+        TreasuryQueryParams {
+            fields: fields.map(str::to_string),
+            summary,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_listing_projection_shape() {
+        assert!(listing_fields(&params(None, None)).unwrap().is_none());
+
+        let fields = listing_fields(&params(Some("token_id, current_price,name"), None)).unwrap().unwrap();
+        let rows = project_overviews(&[overview()], &fields).unwrap();
+        let keys: Vec<&str> = rows[0].keys().map(String::as_str).collect();
+        assert_eq!(keys.len(), 3);
+        assert!(keys.contains(&"token_id") && keys.contains(&"current_price") && keys.contains(&"name"));
+        assert_eq!(rows[0]["current_price"], serde_json::to_value(overview()).unwrap()["current_price"]);
+
+        // Summary drops the metadata-derived fields, including explicitly requested ones
+        let fields = listing_fields(&params(Some("token_id,name"), Some(true))).unwrap().unwrap();
+        assert_eq!(fields, vec!["token_id"]);
+        let summary = listing_fields(&params(None, Some(true))).unwrap().unwrap();
+        let row = &project_overviews(&[overview()], &summary).unwrap()[0];
+        assert!(row.contains_key("status") && !row.contains_key("name") && !row.contains_key("symbol"));
+
+        assert!(matches!(
+            listing_fields(&params(Some("token_id,issuer"), None)),
+            Err(ServiceError::InvalidParameter(_))
+        ));
+    }
+}
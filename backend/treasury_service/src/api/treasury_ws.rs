@@ -0,0 +1,234 @@
+use crate::{
+    api::ApiError,
+    AuthenticationService,
+    Error as ServiceError,
+    TokenValidationResult,
+    TreasuryFeed,
+    FeedMessage,
+    FeedRequest,
+};
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tracing::{info, debug, warn};
+use warp::ws::{Message, WebSocket};
+use warp::{Filter, Rejection, Reply};
+
+/// How often the server pings each connection
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Connections silent for this long (no pong or message) are closed
+pub const CLIENT_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Trait for validating the bearer token presented on connect.
+///
+/// AuthenticationService is the production implementation; tests can substitute a mock.
+pub trait TokenValidator: Send + Sync {
+    fn validate_token(&self, token: &str) -> TokenValidationResult;
+}
+
+impl TokenValidator for AuthenticationService {
+    fn validate_token(&self, token: &str) -> TokenValidationResult {
+        AuthenticationService::validate_token(self, token)
+    }
+}
+
+/// Browsers cannot set headers on a WebSocket handshake, so the token may also be a query parameter
+#[derive(Debug, Deserialize)]
+struct WsAuthQuery {
+    token: Option<String>,
+}
+
+/// Create the treasury feed route: GET /ws/treasuries
+pub fn routes(
+    feed: Arc<TreasuryFeed>,
+    validator: Arc<dyn TokenValidator>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("ws" / "treasuries")
+        .and(warp::ws())
+        .and(warp::header::optional::<String>("authorization"))
+        .and(warp::query::<WsAuthQuery>())
+        .and_then(move |ws: warp::ws::Ws, header: Option<String>, query: WsAuthQuery| {
+            let validator = validator.clone();
+            let feed = feed.clone();
+            async move {
+                let token = header.as_deref()
+                    .and_then(|value| value.strip_prefix("Bearer "))
+                    .map(str::to_string)
+                    .or(query.token)
+                    .ok_or_else(|| warp::reject::custom(ApiError(ServiceError::Unauthorized("Missing token".into()))))?;
+
+                let validation = validator.validate_token(&token);
+                if !validation.is_valid {
+                    return Err(warp::reject::custom(ApiError(ServiceError::Unauthorized(
+                        validation.error_message.unwrap_or_else(|| "Invalid token".into())
+                    ))));
+                }
+
+                Ok::<_, Rejection>(ws.on_upgrade(move |socket| {
+                    handle_connection(socket, feed, HEARTBEAT_INTERVAL, CLIENT_TIMEOUT)
+                }))
+            }
+        })
+}
+
+/// Serve one connection until it closes or stops answering heartbeats
+async fn handle_connection(
+    socket: WebSocket,
+    feed: Arc<TreasuryFeed>,
+    heartbeat: Duration,
+    timeout: Duration,
+) {
+    let (mut ws_sender, mut ws_receiver) = socket.split();
+    let (sender, mut events) = mpsc::unbounded_channel();
+    let client_id = feed.connect(sender);
+    info!("Treasury feed client connected: {}", client_id);
+
+    let mut ticker = tokio::time::interval(heartbeat);
+    ticker.tick().await;
+    let mut last_seen = Instant::now();
+
+    loop {
+        let outgoing = tokio::select! {
+            incoming = ws_receiver.next() => {
+                let message = match incoming {
+                    Some(Ok(message)) => message,
+                    Some(Err(e)) => {
+                        debug!("Treasury feed client {} errored: {}", client_id, e);
+                        break;
+                    }
+                    None => break,
+                };
+                last_seen = Instant::now();
+
+                if message.is_close() {
+                    break;
+                }
+                let Ok(text) = message.to_str() else { continue };
+
+                match serde_json::from_str::<FeedRequest>(text) {
+                    Ok(request) => feed.handle_request(&client_id, request),
+                    Err(e) => FeedMessage::Error { message: format!("Invalid request: {}", e) },
+                }
+            }
+            Some(event) = events.recv() => event,
+            _ = ticker.tick() => {
+                if last_seen.elapsed() > timeout {
+                    warn!("Treasury feed client {} timed out", client_id);
+                    break;
+                }
+                if ws_sender.send(Message::ping(Vec::new())).await.is_err() {
+                    break;
+                }
+                continue;
+            }
+        };
+
+        let text = match serde_json::to_string(&outgoing) {
+            Ok(text) => text,
+            Err(e) => {
+                warn!("Failed to encode treasury feed message: {}", e);
+                continue;
+            }
+        };
+        if ws_sender.send(Message::text(text)).await.is_err() {
+            break;
+        }
+    }
+
+    feed.disconnect(&client_id);
+    info!("Treasury feed client disconnected: {}", client_id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TreasuryStatus;
+    use alloy_primitives::U256;
+
+    struct StaticValidator;
+    impl TokenValidator for StaticValidator {
+        fn validate_token(&self, token: &str) -> TokenValidationResult {
+            TokenValidationResult {
+                is_valid: token == "valid",
+                wallet_address: None,
+                role: None,
+                error_message: None,
+            }
+        }
+    }
+
+    fn token_id_hex(byte: u8) -> String {
+        format!("0x{}", hex::encode([byte; 32]))
+    }
+
+    async fn recv(client: &mut warp::test::WsClient) -> FeedMessage {
+        let message = client.recv().await.expect("feed message");
+        serde_json::from_str(message.to_str().expect("text message")).expect("feed message json")
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_receives_snapshot_and_price_update() {
+        let feed = Arc::new(TreasuryFeed::new(2));
+        feed.apply([1u8; 32], U256::from(1000), TreasuryStatus::Active);
+        let route = routes(feed.clone(), Arc::new(StaticValidator));
+
+        let mut client = warp::test::ws()
+            .path("/ws/treasuries")
+            .header("authorization", "Bearer valid")
+            .handshake(route.clone())
+            .await
+            .expect("handshake");
+
+        client.send_text(format!(r#"{{"action":"subscribe","token_ids":["{}"]}}"#, token_id_hex(1))).await;
+        match recv(&mut client).await {
+            FeedMessage::Snapshot { treasuries } => {
+                assert_eq!(treasuries.len(), 1);
+                assert_eq!(treasuries[0].current_price, U256::from(1000));
+            }
+            other => panic!("expected snapshot, got {:?}", other),
+        }
+
+        // Synthetic registry price change
+        feed.apply([1u8; 32], U256::from(1010), TreasuryStatus::Active);
+        match recv(&mut client).await {
+            FeedMessage::TreasuryPriceUpdated { token_id, old_price, new_price, .. } => {
+                assert_eq!(token_id, token_id_hex(1));
+                assert_eq!(old_price, U256::from(1000));
+                assert_eq!(new_price, U256::from(1010));
+            }
+            other => panic!("expected price update, got {:?}", other),
+        }
+
+        // Over the per-connection cap
+        client.send_text(format!(
+            r#"{{"action":"subscribe","token_ids":["{}","{}"]}}"#, token_id_hex(2), token_id_hex(3)
+        )).await;
+        assert!(matches!(recv(&mut client).await, FeedMessage::Error { .. }));
+
+        client.send_text(r#"{"action":"ping"}"#).await;
+        assert_eq!(recv(&mut client).await, FeedMessage::Pong);
+
+        // Disconnecting deregisters the connection
+        drop(client);
+        for _ in 0..50 {
+            if feed.connection_count() == 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(feed.connection_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_handshake_requires_valid_token() {
+        let feed = Arc::new(TreasuryFeed::default());
+        let route = routes(feed.clone(), Arc::new(StaticValidator));
+
+        assert!(warp::test::ws().path("/ws/treasuries").handshake(route.clone()).await.is_err());
+        assert!(warp::test::ws().path("/ws/treasuries?token=expired").handshake(route.clone()).await.is_err());
+        assert!(warp::test::ws().path("/ws/treasuries?token=valid").handshake(route).await.is_ok());
+    }
+}
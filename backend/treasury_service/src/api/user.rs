@@ -2,11 +2,14 @@ use crate::{
     api::{ApiServices, ApiError, with_services, with_auth},
     Error as ServiceError,
     VerificationData, AddressData, IdData, InstitutionalVerificationData, RepresentativeData, UserPortfolio,
+    HoldingKind, SettlementPrice, settlement_price, default_settlement_date,
 };
+use chrono::NaiveDate;
+use std::collections::HashMap;
 use serde::{Serialize, Deserialize};
 use warp::{Filter, Rejection, Reply};
 use std::sync::Arc;
-use tracing::{info, debug, error};
+use tracing::{info, debug, error, warn};
 use alloy_primitives::{Address, U256};
 
 /// User registration request
@@ -29,6 +32,17 @@ pub struct VerificationRequest {
     pub government_id: Option<IdData>,
 }
 
+/// Portfolio query parameters
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PortfolioQuery {
+    /// Re-read on-chain balances instead of serving the cached snapshot
+    #[serde(default)]
+    pub refresh: bool,
+    /// Settlement date for treasury pricing, defaults to the next business day
+    #[serde(default)]
+    pub settlement_date: Option<NaiveDate>,
+}
+
 /// Institutional registration request
 #[derive(Debug, Serialize, Deserialize)]
 pub struct InstitutionalRegistrationRequest {
@@ -73,6 +87,7 @@ pub fn routes(
     
     let portfolio_route = warp::path!("users" / String / "portfolio")
         .and(warp::get())
+        .and(warp::query::<PortfolioQuery>())
         .and(with_auth(services.auth_service.clone()))
         .and(with_services(services.clone()))
         .and_then(get_portfolio_handler);
@@ -202,6 +217,7 @@ async fn register_institutional_handler(
 /// Get user portfolio
 async fn get_portfolio_handler(
     wallet_address_str: String,
+    query: PortfolioQuery,
     _token: String, // From auth middleware
     services: Arc<ApiServices>,
 ) -> Result<impl Reply, Rejection> {
@@ -218,12 +234,28 @@ async fn get_portfolio_handler(
     };
     
     // Get portfolio
-    let portfolio = services.user_service.get_user_portfolio(wallet_address)
+    let portfolio = services.user_service.get_user_portfolio(wallet_address, query.refresh)
         .await
         .map_err(|e| warp::reject::custom(ApiError(e)))?;
     
+    // Clean and dirty prices for treasury holdings; unpriceable holdings are listed without them
+    let settlement_date = query.settlement_date
+        .unwrap_or_else(|| default_settlement_date(chrono::Utc::now().date_naive()));
+    let mut pricing = HashMap::new();
+    for holding in portfolio.holdings.iter().filter(|h| h.kind == HoldingKind::Treasury) {
+        let priced = services.treasury_service.get_treasury_details(holding.treasury_id)
+            .await
+            .and_then(|info| settlement_price(&info, settlement_date, services.price_decimals));
+        match priced {
+            Ok(price) => {
+                pricing.insert(holding.treasury_id, price);
+            }
+            Err(e) => warn!("Settlement pricing for treasury 0x{} failed: {}", hex::encode(holding.treasury_id), e),
+        }
+    }
+    
     // Return enhanced portfolio with market data
-    let enhanced_portfolio = enhance_portfolio_with_market_data(portfolio);
+    let enhanced_portfolio = enhance_portfolio_with_market_data(portfolio, &pricing, services.price_decimals);
     
     Ok(warp::reply::json(&enhanced_portfolio))
 }
@@ -276,11 +308,27 @@ fn generate_smart_account_code(template_type: &str, parameters: &serde_json::Val
 }
 
 /// Enhance portfolio with additional market data
-fn enhance_portfolio_with_market_data(portfolio: UserPortfolio) -> serde_json::Value {
+fn enhance_portfolio_with_market_data(
+    portfolio: UserPortfolio,
+    pricing: &HashMap<[u8; 32], SettlementPrice>,
+    price_decimals: u32,
+) -> serde_json::Value {
     // In a real implementation, this would fetch current market data and enhance the portfolio
     // For this example, we'll just add some mock market data
     
+    let mut total_settlement_value = U256::ZERO;
     let holdings_with_market_data: Vec<serde_json::Value> = portfolio.holdings.iter().map(|holding| {
+        // Settlement value is the holding at its dirty price; without pricing it is the registry value
+        let settlement = pricing.get(&holding.treasury_id).filter(|_| holding.kind == HoldingKind::Treasury);
+        let accrued_value = settlement
+            .and_then(|price| price.accrued_fixed_point(price_decimals).ok())
+            .map(|accrued| holding.balance * accrued);
+        let settlement_value = settlement
+            .and_then(|price| price.dirty_fixed_point(price_decimals).ok())
+            .map(|dirty| holding.balance * dirty)
+            .unwrap_or(holding.value);
+        total_settlement_value += settlement_value;
+        
         serde_json::json!({
             "treasury_id": hex::encode(holding.treasury_id),
             "token_address": holding.token_address.to_string(),
@@ -292,6 +340,13 @@ fn enhance_portfolio_with_market_data(portfolio: UserPortfolio) -> serde_json::V
             "yield_rate": holding.yield_rate,
             "maturity_date": holding.maturity_date,
             "is_restricted": holding.is_restricted,
+            "kind": holding.kind,
+            "is_dust": holding.is_dust,
+            "allocation_bps": holding.allocation_bps,
+            "last_synced_block": holding.last_synced_block,
+            "pricing": settlement,
+            "accrued_interest_value": accrued_value.map(|v| v.to_string()),
+            "settlement_value": settlement_value.to_string(),
             // Mock market data
             "market_data": {
                 "current_price": (holding.value / holding.balance).to_string(),
@@ -306,10 +361,13 @@ fn enhance_portfolio_with_market_data(portfolio: UserPortfolio) -> serde_json::V
         "wallet_address": portfolio.wallet_address.to_string(),
         "holdings": holdings_with_market_data,
         "total_value": portfolio.total_value.to_string(),
+        "total_settlement_value": total_settlement_value.to_string(),
         "total_pending_yield": portfolio.total_pending_yield.to_string(),
         "verification_status": format!("{:?}", portfolio.verification_status),
         "investment_limit": portfolio.investment_limit.map(|v| v.to_string()),
         "smart_account_enabled": portfolio.smart_account_enabled,
+        "last_synced_block": portfolio.last_synced_block,
+        "synced_at": portfolio.synced_at,
         // Mock portfolio analytics
         "analytics": {
             "yield_weighted_average": format!("{:.2}%", portfolio.holdings.iter().map(|h| h.yield_rate as f64 * (h.value.as_u128() as f64 / portfolio.total_value.as_u128() as f64)).sum::<f64>() / 100.0),
@@ -0,0 +1,143 @@
+use crate::{
+    api::{ApiServices, ApiError, with_services, with_admin},
+    Error as ServiceError,
+    ReportPeriod, TaxProfileUpdate, WithholdingRule, remittance_csv,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Serialize, Deserialize};
+use warp::{Filter, Rejection, Reply};
+use std::sync::Arc;
+use tracing::{info, debug};
+
+/// Withholding tax routes, restricted to platform admins
+pub fn routes(
+    services: Arc<ApiServices>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    let get_remittance_route = warp::path!("admin" / "withholding" / "remittance")
+        .and(warp::get())
+        .and(warp::query::<RemittanceReportParams>())
+        .and(with_admin(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(get_remittance_handler);
+
+    let get_rules_route = warp::path!("admin" / "withholding" / "rules")
+        .and(warp::get())
+        .and(with_admin(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(get_rules_handler);
+
+    let set_rule_route = warp::path!("admin" / "withholding" / "rules")
+        .and(warp::put())
+        .and(warp::body::json::<WithholdingRule>())
+        .and(with_admin(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(set_rule_handler);
+
+    let get_profiles_route = warp::path!("admin" / "withholding" / "profiles")
+        .and(warp::get())
+        .and(with_admin(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(get_profiles_handler);
+
+    let set_profile_route = warp::path!("admin" / "withholding" / "profiles")
+        .and(warp::put())
+        .and(warp::body::json::<TaxProfileUpdate>())
+        .and(with_admin(services.auth_service.clone()))
+        .and(with_services(services.clone()))
+        .and_then(set_profile_handler);
+
+    get_remittance_route
+        .or(get_rules_route)
+        .or(set_rule_route)
+        .or(get_profiles_route)
+        .or(set_profile_route)
+}
+
+/// Remittance report query parameters
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct RemittanceReportParams {
+    /// Defaults to 30 days before `to`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive; defaults to now
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<DateTime<Utc>>,
+    /// day or month (default)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub period: Option<ReportPeriod>,
+    /// json (default) or csv
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+}
+
+/// Amounts withheld per jurisdiction and period, for remittance to tax authorities
+async fn get_remittance_handler(
+    params: RemittanceReportParams,
+    _admin: String, // From admin middleware
+    services: Arc<ApiServices>,
+) -> Result<Box<dyn Reply>, Rejection> {
+    debug!("Getting withholding remittance report: {:?}", params);
+
+    let to = params.to.unwrap_or_else(Utc::now);
+    let from = params.from.unwrap_or(to - Duration::days(30));
+    if from >= to {
+        return Err(warp::reject::custom(ApiError(
+            ServiceError::InvalidParameter("Report start must be before its end".into())
+        )));
+    }
+
+    let rows = services.withholding.remittance_report(from, to, params.period.unwrap_or(ReportPeriod::Month));
+    match params.format.as_deref() {
+        None | Some("json") => Ok(Box::new(warp::reply::json(&rows))),
+        Some("csv") => Ok(Box::new(warp::reply::with_header(
+            warp::reply::with_header(remittance_csv(&rows), "Content-Type", "text/csv"),
+            "Content-Disposition",
+            "attachment; filename=\"withholding-remittance.csv\"",
+        ))),
+        Some(other) => Err(warp::reject::custom(ApiError(
+            ServiceError::InvalidParameter(format!("Unknown report format {}", other))
+        ))),
+    }
+}
+
+async fn get_rules_handler(
+    _admin: String, // From admin middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    Ok(warp::reply::json(&services.withholding.rules()))
+}
+
+/// Add or replace a jurisdiction's rule; applies to later distributions only
+async fn set_rule_handler(
+    rule: WithholdingRule,
+    admin: String, // From admin middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    info!("Withholding rule for {} set by {}", rule.jurisdiction, admin);
+
+    let rule = services.withholding.set_rule(rule, &admin)
+        .map_err(|e| warp::reject::custom(ApiError(e)))?;
+
+    Ok(warp::reply::json(&rule))
+}
+
+async fn get_profiles_handler(
+    _admin: String, // From admin middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    Ok(warp::reply::json(&services.withholding.profiles()))
+}
+
+/// Record a holder's jurisdiction and the tax forms received from them
+async fn set_profile_handler(
+    update: TaxProfileUpdate,
+    admin: String, // From admin middleware
+    services: Arc<ApiServices>,
+) -> Result<impl Reply, Rejection> {
+    info!("Tax profile of {:?} updated by {}", update.wallet, admin);
+
+    let profile = services.withholding.set_profile(update, &admin, Utc::now())
+        .map_err(|e| warp::reject::custom(ApiError(e)))?;
+
+    Ok(warp::reply::json(&profile))
+}
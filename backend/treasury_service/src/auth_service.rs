@@ -89,6 +89,7 @@ pub struct AuthenticationService {
     jwt_secret: String,
     challenge_map: tokio::sync::Mutex<HashMap<Address, AuthChallenge>>,
     token_blacklist: tokio::sync::Mutex<HashMap<String, u64>>, // Token -> Expiration time
+    admin_wallets: Vec<Address>,
 }
 
 impl AuthenticationService {
@@ -104,9 +105,16 @@ impl AuthenticationService {
             jwt_secret,
             challenge_map: tokio::sync::Mutex::new(HashMap::new()),
             token_blacklist: tokio::sync::Mutex::new(HashMap::new()),
+            admin_wallets: Vec::new(),
         }
     }
     
+    /// Wallets signed in with the `admin` role, which platform administration endpoints require
+    pub fn with_admin_wallets(mut self, admin_wallets: Vec<Address>) -> Self {
+        self.admin_wallets = admin_wallets;
+        self
+    }
+    
     /// Generate a new authentication challenge for a wallet
     pub async fn generate_challenge(
         &self,
@@ -226,7 +234,9 @@ impl AuthenticationService {
         let user_status = self.user_service.get_user_verification_status(wallet_address).await?;
         
         // Determine the user's role based on status and type
-        let role = if user_status.institutional_details.is_some() {
+        let role = if self.admin_wallets.contains(&wallet_address) {
+            "admin"
+        } else if user_status.institutional_details.is_some() {
             "institution"
         } else if user_status.status == crate::VerificationStatus::Verified {
             "verified_user"
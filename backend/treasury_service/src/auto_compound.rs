@@ -0,0 +1,662 @@
+// Automatic reinvestment of yield distributions through users' smart account session keys
+use alloy_primitives::{Address, U256};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use ethereum_client::EthereumClient;
+use rust_decimal::Decimal;
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+use crate::clients::liquidity_pools_client::LiquidityPoolsClient;
+use crate::clients::smart_account_client::{ExecutionParams, SmartAccountClient};
+use crate::{Error, HolderWithholding};
+
+const SWAP_SIGNATURE: &str = "swap(bytes32,address,bool,int256,uint160)";
+
+/// Slippage bounds above this are refused as a configuration mistake
+pub const MAX_SLIPPAGE_BPS: u32 = 1_000;
+
+const BPS: u64 = 10_000;
+
+/// A user's standing instruction to reinvest the yield of one treasury token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutomationConfig {
+    pub user: Address,
+    /// Treasury token whose distributions are reinvested
+    pub source_token: Address,
+    /// Token the distributions are paid in
+    pub yield_token: Address,
+    pub target_asset: Address,
+    /// Pool swapping the yield token into the target asset
+    pub pool_id: [u8; 32],
+    pub account_id: [u8; 32],
+    pub session_key: Address,
+    /// Unix seconds after which the session key can no longer execute
+    pub session_valid_until: u64,
+    pub max_slippage_bps: u32,
+    /// Gas ceiling for one compound execution
+    pub max_gas: U256,
+    /// Yield below this accumulates until a later distribution
+    pub min_compound_amount: U256,
+    #[serde(default = "default_active")]
+    pub active: bool,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+impl AutomationConfig {
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.max_slippage_bps == 0 || self.max_slippage_bps > MAX_SLIPPAGE_BPS {
+            return Err(Error::InvalidParameter(format!(
+                "max_slippage_bps must be between 1 and {}", MAX_SLIPPAGE_BPS
+            )));
+        }
+        if self.max_gas == U256::ZERO {
+            return Err(Error::InvalidParameter("max_gas must be positive".into()));
+        }
+        if self.yield_token == self.target_asset {
+            return Err(Error::InvalidParameter("Target asset must differ from the yield token".into()));
+        }
+        Ok(())
+    }
+}
+
+/// Output of swapping the yield, at the pool's spot price and as quoted with price impact
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapQuote {
+    pub spot_amount_out: U256,
+    pub amount_out: U256,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapExecution {
+    pub amount_out: U256,
+    pub gas_used: U256,
+}
+
+/// Swaps yield into the target asset through a user's smart account
+#[async_trait]
+pub trait CompoundExecutor: Send + Sync {
+    async fn quote(&self, config: &AutomationConfig, amount_in: U256) -> Result<SwapQuote, Error>;
+
+    async fn estimate_gas(&self, config: &AutomationConfig, amount_in: U256, min_amount_out: U256) -> Result<U256, Error>;
+
+    /// Execute the swap with the session key; reverts on chain below `min_amount_out`
+    async fn execute(&self, config: &AutomationConfig, amount_in: U256, min_amount_out: U256) -> Result<SwapExecution, Error>;
+}
+
+/// Why a compound did not go through; the yield stays accrued for the next cycle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CompoundFailure {
+    SlippageExceeded { spot_amount_out: U256, amount_out: U256, max_slippage_bps: u32 },
+    GasCeilingExceeded { estimate: U256, ceiling: U256 },
+    SessionKeyExpired { expired_at: u64 },
+    ExecutionFailed { reason: String },
+}
+
+impl std::fmt::Display for CompoundFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CompoundFailure::SlippageExceeded { spot_amount_out, amount_out, max_slippage_bps } => write!(
+                f, "Swap would return {} against {} at spot, beyond the {} bps slippage bound",
+                amount_out, spot_amount_out, max_slippage_bps
+            ),
+            CompoundFailure::GasCeilingExceeded { estimate, ceiling } => {
+                write!(f, "Estimated gas {} exceeds the ceiling of {}", estimate, ceiling)
+            }
+            CompoundFailure::SessionKeyExpired { expired_at } => write!(f, "Session key expired at {}", expired_at),
+            CompoundFailure::ExecutionFailed { reason } => write!(f, "Execution failed: {}", reason),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompoundStatus {
+    Compounded,
+    Failed,
+}
+
+/// One compound attempt for a user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompoundEvent {
+    pub event_id: Uuid,
+    pub user: Address,
+    /// Distributions whose yield the attempt reinvested
+    pub references: Vec<String>,
+    pub yield_token: Address,
+    pub target_asset: Address,
+    pub amount_in: U256,
+    /// Zero for failed attempts
+    pub amount_out: U256,
+    /// Target asset base units received per yield token base unit
+    pub effective_price: Option<Decimal>,
+    pub gas_used: U256,
+    pub status: CompoundStatus,
+    pub failure: Option<CompoundFailure>,
+    pub executed_at: DateTime<Utc>,
+}
+
+/// Sent to a user whose yield could not be compounded
+#[derive(Debug, Clone, Serialize)]
+pub struct CompoundFailureNotice {
+    pub user: Address,
+    pub event_id: Uuid,
+    pub amount_in: U256,
+    pub failure: CompoundFailure,
+}
+
+/// Tells users that a compound failed and will be retried
+#[async_trait]
+pub trait CompoundNotifier: Send + Sync {
+    async fn compound_failed(&self, notice: &CompoundFailureNotice);
+}
+
+/// Default notifier: a log line per failure
+pub struct LogCompoundNotifier;
+
+#[async_trait]
+impl CompoundNotifier for LogCompoundNotifier {
+    async fn compound_failed(&self, notice: &CompoundFailureNotice) {
+        warn!("Auto-compound of {} for {:?} failed, retrying next cycle: {}", notice.amount_in, notice.user, notice.failure);
+    }
+}
+
+pub struct WebhookCompoundNotifier {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookCompoundNotifier {
+    pub fn new(url: String) -> Self {
+        Self { client: reqwest::Client::new(), url }
+    }
+}
+
+#[async_trait]
+impl CompoundNotifier for WebhookCompoundNotifier {
+    async fn compound_failed(&self, notice: &CompoundFailureNotice) {
+        if let Err(e) = self.client.post(&self.url).json(notice).send().await.and_then(|r| r.error_for_status()) {
+            warn!("Auto-compound webhook failed for {:?}: {}", notice.user, e);
+        }
+    }
+}
+
+/// Yield credited to a user and not compounded yet
+#[derive(Debug, Default)]
+struct Accrual {
+    amount: U256,
+    references: Vec<String>,
+    /// Cycle of the last failed attempt; the next attempt waits for a later cycle
+    failed_in_cycle: Option<u64>,
+    in_flight: bool,
+}
+
+/// Reinvests distributed yield for users with an active automation config.
+///
+/// Each distribution credits the holders' net yield and compounds it at once. A failed
+/// compound keeps the yield accrued and is retried by the next `run_cycle`, never sooner.
+pub struct AutoCompounder {
+    executor: Arc<dyn CompoundExecutor>,
+    notifier: Arc<dyn CompoundNotifier>,
+    configs: RwLock<HashMap<Address, AutomationConfig>>,
+    accruals: RwLock<HashMap<Address, Accrual>>,
+    events: RwLock<Vec<CompoundEvent>>,
+    cycle: AtomicU64,
+}
+
+impl AutoCompounder {
+    pub fn new(executor: Arc<dyn CompoundExecutor>) -> Self {
+        Self {
+            executor,
+            notifier: Arc::new(LogCompoundNotifier),
+            configs: RwLock::new(HashMap::new()),
+            accruals: RwLock::new(HashMap::new()),
+            events: RwLock::new(Vec::new()),
+            cycle: AtomicU64::new(0),
+        }
+    }
+
+    pub fn with_notifier(mut self, notifier: Arc<dyn CompoundNotifier>) -> Self {
+        self.notifier = notifier;
+        self
+    }
+
+    pub async fn set_config(&self, config: AutomationConfig) -> Result<AutomationConfig, Error> {
+        config.validate()?;
+        info!("Auto-compound for {:?} set to {:?} via pool 0x{}", config.user, config.target_asset, hex::encode(config.pool_id));
+        self.configs.write().await.insert(config.user, config.clone());
+        Ok(config)
+    }
+
+    pub async fn config(&self, user: Address) -> Option<AutomationConfig> {
+        self.configs.read().await.get(&user).cloned()
+    }
+
+    /// Stop compounding for `user`; accrued yield is kept should they opt in again
+    pub async fn disable(&self, user: Address) -> Result<AutomationConfig, Error> {
+        let mut configs = self.configs.write().await;
+        let config = configs.get_mut(&user)
+            .ok_or_else(|| Error::NotFound(format!("No auto-compound configuration for {:?}", user)))?;
+        config.active = false;
+        Ok(config.clone())
+    }
+
+    pub async fn events_for(&self, user: Address) -> Vec<CompoundEvent> {
+        self.events.read().await.iter().filter(|event| event.user == user).cloned().collect()
+    }
+
+    /// Credit the net yield of a paid distribution of `token` and compound it
+    pub async fn on_distribution(&self, reference: &str, token: Address, holders: &[HolderWithholding]) -> Vec<CompoundEvent> {
+        let mut credited = Vec::new();
+        {
+            let configs = self.configs.read().await;
+            let mut accruals = self.accruals.write().await;
+            for holder in holders {
+                let opted_in = configs.get(&holder.wallet)
+                    .map(|config| config.active && config.source_token == token)
+                    .unwrap_or(false);
+                if !opted_in || holder.net == U256::ZERO {
+                    continue;
+                }
+                let accrual = accruals.entry(holder.wallet).or_default();
+                accrual.amount = accrual.amount.saturating_add(holder.net);
+                accrual.references.push(reference.to_string());
+                credited.push(holder.wallet);
+            }
+        }
+
+        let mut events = Vec::new();
+        for user in credited {
+            events.extend(self.attempt(user).await);
+        }
+        events
+    }
+
+    /// Start a new cycle and retry every user whose yield is still accrued
+    pub async fn run_cycle(&self) -> Vec<CompoundEvent> {
+        self.cycle.fetch_add(1, Ordering::SeqCst);
+        let users: Vec<Address> = self.accruals.read().await.keys().copied().collect();
+        let mut events = Vec::new();
+        for user in users {
+            events.extend(self.attempt(user).await);
+        }
+        events
+    }
+
+    async fn attempt(&self, user: Address) -> Option<CompoundEvent> {
+        let config = self.configs.read().await.get(&user).filter(|config| config.active).cloned()?;
+        let cycle = self.cycle.load(Ordering::SeqCst);
+        let (amount_in, references) = {
+            let mut accruals = self.accruals.write().await;
+            let accrual = accruals.get_mut(&user)?;
+            if accrual.in_flight || accrual.failed_in_cycle.is_some_and(|failed| failed >= cycle) {
+                return None;
+            }
+            if accrual.amount < config.min_compound_amount {
+                debug!("Skipping auto-compound of {} for {:?}: below minimum {}", accrual.amount, user, config.min_compound_amount);
+                return None;
+            }
+            accrual.in_flight = true;
+            (accrual.amount, accrual.references.clone())
+        };
+
+        let outcome = self.compound(&config, amount_in).await;
+        let event = CompoundEvent {
+            event_id: Uuid::new_v4(),
+            user,
+            references,
+            yield_token: config.yield_token,
+            target_asset: config.target_asset,
+            amount_in,
+            amount_out: outcome.as_ref().map(|swap| swap.amount_out).unwrap_or(U256::ZERO),
+            effective_price: outcome.as_ref().ok().and_then(|swap| effective_price(amount_in, swap.amount_out)),
+            gas_used: outcome.as_ref().map(|swap| swap.gas_used).unwrap_or(U256::ZERO),
+            status: if outcome.is_ok() { CompoundStatus::Compounded } else { CompoundStatus::Failed },
+            failure: outcome.as_ref().err().cloned(),
+            executed_at: Utc::now(),
+        };
+
+        {
+            let mut accruals = self.accruals.write().await;
+            if let Some(accrual) = accruals.get_mut(&user) {
+                accrual.in_flight = false;
+                if outcome.is_ok() {
+                    // Yield credited while the swap was in flight stays for the next attempt
+                    accrual.amount = accrual.amount.saturating_sub(amount_in);
+                    accrual.references.drain(..event.references.len().min(accrual.references.len()));
+                    accrual.failed_in_cycle = None;
+                    if accrual.amount == U256::ZERO {
+                        accruals.remove(&user);
+                    }
+                } else {
+                    accrual.failed_in_cycle = Some(cycle);
+                }
+            }
+        }
+        self.events.write().await.push(event.clone());
+
+        match &outcome {
+            Ok(swap) => info!("Compounded {} of {:?} into {} of {:?} for {:?}", amount_in, config.yield_token, swap.amount_out, config.target_asset, user),
+            Err(failure) => self.notifier.compound_failed(&CompoundFailureNotice {
+                user,
+                event_id: event.event_id,
+                amount_in,
+                failure: failure.clone(),
+            }).await,
+        }
+        Some(event)
+    }
+
+    async fn compound(&self, config: &AutomationConfig, amount_in: U256) -> Result<SwapExecution, CompoundFailure> {
+        if config.session_valid_until <= Utc::now().timestamp() as u64 {
+            return Err(CompoundFailure::SessionKeyExpired { expired_at: config.session_valid_until });
+        }
+        let failed = |e: Error| CompoundFailure::ExecutionFailed { reason: e.to_string() };
+
+        let quote = self.executor.quote(config, amount_in).await.map_err(failed)?;
+        let min_amount_out = quote.spot_amount_out.saturating_mul(U256::from(BPS - config.max_slippage_bps as u64)) / U256::from(BPS);
+        if quote.amount_out < min_amount_out {
+            return Err(CompoundFailure::SlippageExceeded {
+                spot_amount_out: quote.spot_amount_out,
+                amount_out: quote.amount_out,
+                max_slippage_bps: config.max_slippage_bps,
+            });
+        }
+
+        let estimate = self.executor.estimate_gas(config, amount_in, min_amount_out).await.map_err(failed)?;
+        if estimate > config.max_gas {
+            return Err(CompoundFailure::GasCeilingExceeded { estimate, ceiling: config.max_gas });
+        }
+
+        let swap = self.executor.execute(config, amount_in, min_amount_out).await.map_err(failed)?;
+        if swap.amount_out < min_amount_out {
+            return Err(CompoundFailure::SlippageExceeded {
+                spot_amount_out: quote.spot_amount_out,
+                amount_out: swap.amount_out,
+                max_slippage_bps: config.max_slippage_bps,
+            });
+        }
+        Ok(swap)
+    }
+}
+
+fn effective_price(amount_in: U256, amount_out: U256) -> Option<Decimal> {
+    let amount_in = Decimal::from_str(&amount_in.to_string()).ok()?;
+    let amount_out = Decimal::from_str(&amount_out.to_string()).ok()?;
+    amount_out.checked_div(amount_in)
+}
+
+/// Retry accrued yield every `interval`
+pub fn spawn_auto_compound(compounder: Arc<AutoCompounder>, interval: std::time::Duration) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let events = compounder.run_cycle().await;
+            let failed = events.iter().filter(|event| event.status == CompoundStatus::Failed).count();
+            if !events.is_empty() {
+                debug!("Auto-compound cycle: {} attempts, {} failed", events.len(), failed);
+            }
+        }
+    })
+}
+
+fn pool_error(e: impl std::fmt::Display) -> Error {
+    Error::ContractInteraction(format!("LiquidityPools call failed: {}", e))
+}
+
+fn parse_amount(value: impl ToString) -> Result<U256, Error> {
+    U256::from_str_radix(&value.to_string(), 10)
+        .map_err(|e| Error::Decoding(format!("Invalid amount: {}", e)))
+}
+
+/// Swaps through the LiquidityPools contract, executed by the user's smart account
+pub struct ContractCompoundExecutor {
+    pools: LiquidityPoolsClient<EthereumClient>,
+    accounts: SmartAccountClient,
+}
+
+impl ContractCompoundExecutor {
+    pub fn new(client: Arc<EthereumClient>, pools_contract: Address, smart_account_contract: Address) -> Self {
+        Self {
+            pools: LiquidityPoolsClient::new(client.clone(), pools_contract),
+            accounts: SmartAccountClient::new(client, smart_account_contract),
+        }
+    }
+
+    /// Whether the yield token is the pool's token A, i.e. the swap moves the price down
+    async fn zero_for_one(&self, config: &AutomationConfig) -> Result<bool, Error> {
+        let pool = self.pools.get_pool_config(config.pool_id).await.map_err(pool_error)?;
+        if pool.token_a == config.yield_token && pool.token_b == config.target_asset {
+            Ok(true)
+        } else if pool.token_b == config.yield_token && pool.token_a == config.target_asset {
+            Ok(false)
+        } else {
+            Err(Error::InvalidParameter(format!(
+                "Pool 0x{} does not pair the yield token with the target asset", hex::encode(config.pool_id)
+            )))
+        }
+    }
+
+    async fn sqrt_price_x96(&self, pool_id: [u8; 32]) -> Result<U256, Error> {
+        parse_amount(self.pools.get_pool_state(pool_id).await.map_err(pool_error)?.sqrt_price_x96)
+    }
+
+    /// Calldata for a swap whose price limit stops it short of `min_amount_out`
+    async fn swap_calldata(&self, config: &AutomationConfig, amount_in: U256, min_amount_out: U256) -> Result<Vec<u8>, Error> {
+        let zero_for_one = self.zero_for_one(config).await?;
+        let sqrt_price = self.sqrt_price_x96(config.pool_id).await?;
+
+        // The price may move by at most the slippage bound; sqrt prices move by its square root
+        let bound = ((BPS - config.max_slippage_bps as u64) as f64 / BPS as f64).sqrt();
+        let bound = U256::from((bound * 1e9) as u64);
+        let scale = U256::from(1_000_000_000u64);
+        let price_limit = if zero_for_one { sqrt_price * bound / scale } else { sqrt_price * scale / bound };
+        debug!("Swap of {} with price limit {} (minimum out {})", amount_in, price_limit, min_amount_out);
+
+        EthereumClient::encode_function_call(
+            SWAP_SIGNATURE,
+            vec![
+                config.pool_id.into(),
+                config.user.into(),
+                zero_for_one.into(),
+                amount_in.into(),
+                price_limit.into(),
+            ],
+        ).map_err(Error::InvalidParameter)
+    }
+}
+
+#[async_trait]
+impl CompoundExecutor for ContractCompoundExecutor {
+    async fn quote(&self, config: &AutomationConfig, amount_in: U256) -> Result<SwapQuote, Error> {
+        let zero_for_one = self.zero_for_one(config).await?;
+        let sqrt_price = self.sqrt_price_x96(config.pool_id).await?;
+        let q96 = U256::from(1u64) << 96;
+        // Token B per token A is (sqrtPriceX96 / 2^96)^2; applied in two steps to stay in range
+        let spot_amount_out = if zero_for_one {
+            amount_in * sqrt_price / q96 * sqrt_price / q96
+        } else if sqrt_price == U256::ZERO {
+            U256::ZERO
+        } else {
+            amount_in * q96 / sqrt_price * q96 / sqrt_price
+        };
+
+        let specified = ethers::types::I256::from_dec_str(&amount_in.to_string())
+            .map_err(|e| Error::InvalidParameter(format!("Swap amount out of range: {}", e)))?;
+        let (amount0, amount1, ..) = self.pools.quote_swap(config.pool_id, zero_for_one, specified)
+            .await
+            .map_err(pool_error)?;
+        // The pool reports the output as a negative delta
+        let amount_out = parse_amount(if zero_for_one { amount1 } else { amount0 }.unsigned_abs())?;
+
+        Ok(SwapQuote { spot_amount_out, amount_out })
+    }
+
+    async fn estimate_gas(&self, config: &AutomationConfig, amount_in: U256, min_amount_out: U256) -> Result<U256, Error> {
+        let calldata = self.swap_calldata(config, amount_in, min_amount_out).await?;
+        let simulation = self.accounts.simulate_execution(config.account_id, calldata).await?;
+        if !simulation.success {
+            return Err(Error::ContractInteraction(format!("Compound simulation reverted: {}", simulation.error_message)));
+        }
+        Ok(simulation.gas_used)
+    }
+
+    async fn execute(&self, config: &AutomationConfig, amount_in: U256, min_amount_out: U256) -> Result<SwapExecution, Error> {
+        let zero_for_one = self.zero_for_one(config).await?;
+        let calldata = self.swap_calldata(config, amount_in, min_amount_out).await?;
+        let result = self.accounts.execute_account(config.account_id, calldata, ExecutionParams {
+            gas_limit: config.max_gas,
+            gas_price: U256::ZERO,
+            value: U256::ZERO,
+            delegated: true,
+            delegate: config.session_key,
+            valid_until: config.session_valid_until,
+            nonce: U256::ZERO,
+        }).await?;
+        if !result.success {
+            return Err(Error::ContractInteraction(format!("Compound swap reverted: {}", result.error_message)));
+        }
+
+        // swap returns (int256 amount0, int256 amount1); the output side is negative
+        let word = if zero_for_one { 1 } else { 0 };
+        let delta = result.result_data.get(word * 32..(word + 1) * 32)
+            .map(U256::from_be_slice)
+            .ok_or_else(|| Error::Decoding("Swap returned no amounts".into()))?;
+        Ok(SwapExecution { amount_out: U256::ZERO.wrapping_sub(delta), gas_used: result.gas_used })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Quotes with a fixed price impact and records executed swaps
+    struct PoolStub {
+        impact_bps: u64,
+        executed: Mutex<Vec<U256>>,
+    }
+
+    #[async_trait]
+    impl CompoundExecutor for PoolStub {
+        async fn quote(&self, _config: &AutomationConfig, amount_in: U256) -> Result<SwapQuote, Error> {
+            // Two target units per yield unit at spot
+            let spot_amount_out = amount_in * U256::from(2u64);
+            let amount_out = spot_amount_out * U256::from(BPS - self.impact_bps) / U256::from(BPS);
+            Ok(SwapQuote { spot_amount_out, amount_out })
+        }
+
+        async fn estimate_gas(&self, _config: &AutomationConfig, _amount_in: U256, _min_amount_out: U256) -> Result<U256, Error> {
+            Ok(U256::from(180_000u64))
+        }
+
+        async fn execute(&self, config: &AutomationConfig, amount_in: U256, _min_amount_out: U256) -> Result<SwapExecution, Error> {
+            self.executed.lock().unwrap().push(amount_in);
+            let quote = self.quote(config, amount_in).await?;
+            Ok(SwapExecution { amount_out: quote.amount_out, gas_used: U256::from(150_000u64) })
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingNotifier(Mutex<Vec<CompoundFailureNotice>>);
+
+    #[async_trait]
+    impl CompoundNotifier for RecordingNotifier {
+        async fn compound_failed(&self, notice: &CompoundFailureNotice) {
+            self.0.lock().unwrap().push(notice.clone());
+        }
+    }
+
+    fn user() -> Address {
+        Address::repeat_byte(0x01)
+    }
+
+    fn config() -> AutomationConfig {
+        AutomationConfig {
+            user: user(),
+            source_token: Address::repeat_byte(0x10),
+            yield_token: Address::repeat_byte(0x20),
+            target_asset: Address::repeat_byte(0x30),
+            pool_id: [0x40; 32],
+            account_id: [0x50; 32],
+            session_key: Address::repeat_byte(0x60),
+            session_valid_until: Utc::now().timestamp() as u64 + 86_400,
+            max_slippage_bps: 50,
+            max_gas: U256::from(250_000u64),
+            min_compound_amount: U256::from(100u64),
+            active: true,
+        }
+    }
+
+    fn holders(net: u64) -> Vec<HolderWithholding> {
+        vec![HolderWithholding {
+            wallet: user(),
+            jurisdiction: None,
+            gross: U256::from(net),
+            rate_bps: 0,
+            basis: crate::RateBasis::NotWithheld,
+            withheld: U256::ZERO,
+            net: U256::from(net),
+            missing_documents: Vec::new(),
+        }]
+    }
+
+    async fn compounder(impact_bps: u64) -> (AutoCompounder, Arc<PoolStub>, Arc<RecordingNotifier>) {
+        let pool = Arc::new(PoolStub { impact_bps, executed: Mutex::new(Vec::new()) });
+        let notifier = Arc::new(RecordingNotifier::default());
+        let compounder = AutoCompounder::new(pool.clone()).with_notifier(notifier.clone());
+        compounder.set_config(config()).await.unwrap();
+        (compounder, pool, notifier)
+    }
+
+    #[tokio::test]
+    async fn test_distribution_compounded_at_effective_price() {
+        let (compounder, pool, notifier) = compounder(20).await;
+
+        // Dust stays accrued until a later distribution lifts it over the minimum
+        assert!(compounder.on_distribution("distribution 1", config().source_token, &holders(60)).await.is_empty());
+        let events = compounder.on_distribution("distribution 2", config().source_token, &holders(940)).await;
+
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.status, CompoundStatus::Compounded);
+        assert_eq!(event.amount_in, U256::from(1_000u64));
+        assert_eq!(event.amount_out, U256::from(1_996u64));
+        assert_eq!(event.effective_price, Some(Decimal::from_str("1.996").unwrap()));
+        assert_eq!(event.references, vec!["distribution 1", "distribution 2"]);
+        assert_eq!(*pool.executed.lock().unwrap(), vec![U256::from(1_000u64)]);
+        assert!(notifier.0.lock().unwrap().is_empty());
+        assert!(compounder.run_cycle().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_slippage_bound_rejects_and_retries_next_cycle() {
+        let (compounder, pool, notifier) = compounder(80).await;
+
+        let events = compounder.on_distribution("distribution 1", config().source_token, &holders(1_000)).await;
+        assert_eq!(events[0].status, CompoundStatus::Failed);
+        assert!(matches!(events[0].failure, Some(CompoundFailure::SlippageExceeded { max_slippage_bps: 50, .. })));
+        assert!(pool.executed.lock().unwrap().is_empty());
+        assert_eq!(notifier.0.lock().unwrap().len(), 1);
+
+        // Another distribution in the same cycle adds to the accrual but does not retry
+        assert!(compounder.on_distribution("distribution 2", config().source_token, &holders(500)).await.is_empty());
+
+        let retried = compounder.run_cycle().await;
+        assert_eq!(retried.len(), 1);
+        assert_eq!(retried[0].amount_in, U256::from(1_500u64));
+        assert_eq!(retried[0].status, CompoundStatus::Failed);
+        assert_eq!(notifier.0.lock().unwrap().len(), 2);
+        assert_eq!(compounder.events_for(user()).await.len(), 2);
+    }
+}
@@ -8,13 +8,64 @@ use treasury_service::{
     MockVerificationProvider,
     api::{routes, ApiServices, TokenClientsContainer},
     AssetManagementService,
+    PrometheusMetricsRecorder,
+    PreTradeCompliance,
+    PreTradeComplianceConfig,
+    PreTradeRisk,
+    PreTradeRiskConfig,
+    HttpRiskClient,
+    TreasuryFeed,
+    spawn_registry_sync,
+    admin_cli::RegistryConfig,
+    ChainlinkFeedReader,
+    TreasuryPriceUpdater,
+    spawn_price_updates,
+    HoldingsSync,
+    HoldingsSyncConfig,
+    RegistryTokenUniverse,
+    MulticallBalanceReader,
+    WebhookHoldingHook,
+    spawn_holdings_sync,
+    ContractName,
+    SettlementEngine,
+    SettlementConfig,
+    ContractSettlementChain,
+    WebhookSettlementNotifier,
+    FeeSchedule,
+    BusinessCalendar,
+    WithholdingTable,
+    WebhookDocumentationNotifier,
+    DealingWindows,
+    AutoCompounder,
+    ContractCompoundExecutor,
+    WebhookCompoundNotifier,
+    spawn_auto_compound,
+    spawn_settlement_processing,
+    OrderReconciler,
+    OrderLedger,
+    ContractOrderEventSource,
+    CursorStore,
+    FileCursorStore,
+    MemoryCursorStore,
+    ReconciliationConfig,
+    WebhookDriftAlerter,
+    spawn_reconciliation,
+    OrderReplayer,
+    ReplayConfig,
+    CheckpointStore,
+    FileCheckpointStore,
+    MemoryCheckpointStore,
+    LpAnalytics,
+    ContractLpDataSource,
 };
+use price_oracle::{FeedReader, FxRateService, OracleAggregator};
 use ethereum_client::EthereumClient;
 use alloy_primitives::Address;
 use std::sync::Arc;
 use std::net::SocketAddr;
 use tracing::{info, error};
 use tracing_subscriber::{EnvFilter, fmt};
+use warp::Filter;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -27,16 +78,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     // Load environment variables
     dotenv::dotenv().ok();
+    if !quantera_types::redaction_salt_from_env() {
+        tracing::warn!("{} is not set; redacted log fields are hashed without a salt", quantera_types::REDACTION_SALT_ENV);
+    }
     
     // Get configuration from environment
-    let ethereum_rpc_url = std::env::var("ETHEREUM_RPC_URL")
-        .unwrap_or_else(|_| "http://localhost:8545".to_string());
-    
-    let registry_address = std::env::var("REGISTRY_ADDRESS")
-        .unwrap_or_else(|_| "0x0000000000000000000000000000000000000000".to_string());
-    
-    let ipfs_url = std::env::var("IPFS_URL")
-        .unwrap_or_else(|_| "http://localhost:5001".to_string());
+    let RegistryConfig { ethereum_rpc_url, registry_address, contracts, ipfs_url, stall_policy, finality } = RegistryConfig::from_env()
+        .expect("Invalid registry configuration");
     
     let jwt_secret = std::env::var("JWT_SECRET")
         .unwrap_or_else(|_| {
@@ -49,34 +97,55 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .parse::<u16>()
         .unwrap_or(3030);
     
-    // Contract addresses from environment
-    let l2_bridge_address = std::env::var("L2_BRIDGE_ADDRESS")
-        .unwrap_or_else(|_| "0x0000000000000000000000000000000000000000".to_string());
-    
-    let smart_account_address = std::env::var("SMART_ACCOUNT_ADDRESS")
-        .unwrap_or_else(|_| "0x0000000000000000000000000000000000000000".to_string());
-    
-    let asset_factory_address = std::env::var("ASSET_FACTORY_ADDRESS")
-        .unwrap_or_else(|_| "0x0000000000000000000000000000000000000000".to_string());
-    
-    let liquidity_pools_address = std::env::var("LIQUIDITY_POOLS_ADDRESS")
-        .unwrap_or_else(|_| "0x0000000000000000000000000000000000000000".to_string());
-    
-    let yield_optimizer_address = std::env::var("YIELD_OPTIMIZER_ADDRESS")
-        .unwrap_or_else(|_| "0x0000000000000000000000000000000000000000".to_string());
-    
-    // Create Ethereum client
-    let ethereum_client = Arc::new(EthereumClient::new(&ethereum_rpc_url).await?);
-    
-    // Create registry client
-    let registry_address = Address::parse_checksummed(&registry_address, None)
-        .expect("Invalid registry address format");
+    let feed_sync_interval = std::time::Duration::from_secs(
+        std::env::var("TREASURY_FEED_SYNC_INTERVAL_SECS")
+            .ok()
+            .and_then(|secs| secs.parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(15)
+    );
     
-    let registry_client = Arc::new(TreasuryRegistryClient::new(ethereum_client.clone(), registry_address).await);
+    // Metrics for Ethereum client calls, exposed on /metrics
+    let eth_metrics = Arc::new(PrometheusMetricsRecorder::new(prometheus::Registry::new())?);
+    
+    // Create Ethereum client; stalled price updates and yield distributions are sped up per TX_STALL_TIMEOUT_SECS
+    let mut ethereum_client = EthereumClient::new(&ethereum_rpc_url).await?
+        .with_metrics(eth_metrics.clone())
+        .with_finality_policy(finality);
+    if let Some(policy) = stall_policy {
+        ethereum_client = ethereum_client.with_stall_policy(policy);
+    }
+    let ethereum_client = Arc::new(ethereum_client);
+    
+    // Refuse to start with any contract address that has no code or does not answer
+    // its sentinel view; every bad address is reported at once
+    if let Err(e) = contracts.validate(&*ethereum_client, &ContractName::ALL).await {
+        error!("{}", e);
+        return Err(e.into());
+    }
+    
+    // Create registry client; bulk status changes go out as one multicall when the
+    // registry deployment supports it
+    let batch_status_updates = std::env::var("TREASURY_REGISTRY_MULTICALL")
+        .ok()
+        .and_then(|v| v.parse::<bool>().ok())
+        .unwrap_or(false);
+    let registry_client = Arc::new(
+        TreasuryRegistryClient::new(ethereum_client.clone(), registry_address).await
+            .with_batched_status_updates(batch_status_updates)
+    );
     
     // Create IPFS client
     let ipfs_client = IpfsClient::new(&ipfs_url);
     
+    // Issuance, trading and yield distribution fees accrue against FEE_TENANT; each
+    // operation is refused until a fee (zero included) is configured for it
+    let fee_schedule = Arc::new(FeeSchedule::from_env()?);
+    
+    // Distribution and maturity dates on weekends or HOLIDAY_CALENDAR_PATH holidays move
+    // to a business day of BUSINESS_DAY_MARKET by BUSINESS_DAY_CONVENTION
+    let business_calendar = Arc::new(BusinessCalendar::from_env()?);
+    
     // Create Treasury service
     let token_deployer = Box::new(MockTokenDeployer);
     let compliance_checker = Box::new(MockComplianceChecker);
@@ -85,48 +154,197 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         ipfs_client,
         token_deployer,
         compliance_checker,
-    ).await);
+    ).await
+    .with_fee_schedule(fee_schedule.clone())
+    .with_calendar(business_calendar.clone()));
     
     // Create verification provider
     let verification_provider = Arc::new(MockVerificationProvider);
     
     // Create clients for UserService
-    let compliance_client = treasury_service::clients::compliance_client::ComplianceClient::new(
+    let compliance_client = Arc::new(treasury_service::clients::compliance_client::ComplianceClient::new(
         ethereum_client.clone(),
-        Address::ZERO, // Mock address
-    ).await;
+        contracts.get(ContractName::Compliance)?,
+    ).await);
+    
+    // Assets with terms at DEALING_TERMS_PATH only take subscriptions, redemptions and
+    // transfers in their windows; redemptions also need notice and units out of lock-up
+    let dealing_windows = Arc::new(DealingWindows::from_env()?);
+    
+    // Create pre-trade compliance checks for order placement
+    let pre_trade_compliance = Arc::new(PreTradeCompliance::new(
+        compliance_client.clone(),
+        PreTradeComplianceConfig::from_env()?,
+    ).with_dealing_windows(dealing_windows.clone()));
+    
+    // Orders are checked against the portfolio's risk limits when a risk service is configured
+    let pre_trade_risk = match std::env::var("RISK_SERVICE_URL") {
+        Ok(url) if !url.is_empty() => Some(Arc::new(PreTradeRisk::new(
+            Arc::new(HttpRiskClient::new(url)),
+            PreTradeRiskConfig::from_env()?,
+        ))),
+        _ => None,
+    };
+    
+    // Holdings are read through Multicall3 and cover registered treasuries plus any
+    // platform asset tokens listed in PLATFORM_ASSET_TOKENS
+    let multicall_address = contracts.get(ContractName::Multicall)?;
+    let platform_assets = std::env::var("PLATFORM_ASSET_TOKENS")
+        .unwrap_or_default()
+        .split(',')
+        .filter(|token| !token.trim().is_empty())
+        .map(|token| Address::parse_checksummed(token.trim(), None).expect("Invalid platform asset token address"))
+        .collect();
+    let mut holdings_sync = HoldingsSync::new(
+        Arc::new(RegistryTokenUniverse::new(registry_client.clone(), ethereum_client.clone(), platform_assets)),
+        Arc::new(MulticallBalanceReader::new(ethereum_client.clone(), multicall_address)),
+        HoldingsSyncConfig::from_env()?,
+    );
+    if let Ok(url) = std::env::var("HOLDINGS_WEBHOOK_URL") {
+        holdings_sync = holdings_sync.with_change_hook(Arc::new(WebhookHoldingHook::new(url)));
+    }
+    let holdings_sync = Arc::new(holdings_sync);
     
     // Create UserService
     let user_service = Arc::new(UserService::new(
-        Arc::new(compliance_client),
+        compliance_client,
         registry_client.clone(),
         ethereum_client.clone(),
         verification_provider,
-    ).await);
+    ).await
+    .with_holdings_sync(holdings_sync.clone()));
+    
+    let holdings_sync_interval = std::env::var("HOLDINGS_SYNC_INTERVAL_SECS")
+        .ok()
+        .and_then(|secs| secs.parse::<u64>().ok())
+        .unwrap_or(600);
+    spawn_holdings_sync(user_service.clone(), std::time::Duration::from_secs(holdings_sync_interval));
+    
+    // Yield paid to holders is split into net payout and tax withheld by jurisdiction;
+    // rules come from WITHHOLDING_RULES_PATH and holder tax profiles from TAX_PROFILES_PATH
+    let mut withholding = WithholdingTable::from_env()?;
+    if let Ok(url) = std::env::var("TAX_DOCUMENTATION_WEBHOOK_URL") {
+        withholding = withholding.with_notifier(Arc::new(WebhookDocumentationNotifier::new(url)));
+    }
+    let withholding = Arc::new(withholding);
+    for profile in withholding.profiles() {
+        holdings_sync.track(profile.wallet);
+    }
+    
+    // Opted-in holders' yield is swapped into their target asset on each distribution through
+    // their smart account session key; failed compounds are retried every AUTO_COMPOUND_INTERVAL_SECS
+    let mut auto_compound = AutoCompounder::new(Arc::new(ContractCompoundExecutor::new(
+        ethereum_client.clone(),
+        contracts.get(ContractName::LiquidityPools)?,
+        contracts.get(ContractName::SmartAccount)?,
+    )));
+    if let Ok(url) = std::env::var("AUTO_COMPOUND_WEBHOOK_URL") {
+        auto_compound = auto_compound.with_notifier(Arc::new(WebhookCompoundNotifier::new(url)));
+    }
+    let auto_compound = Arc::new(auto_compound);
+    let auto_compound_interval = std::env::var("AUTO_COMPOUND_INTERVAL_SECS")
+        .ok()
+        .and_then(|secs| secs.parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(3600);
+    spawn_auto_compound(auto_compound.clone(), std::time::Duration::from_secs(auto_compound_interval));
     
     // Create YieldSchedulerService
     let yield_scheduler = Arc::new(YieldSchedulerService::new(
         registry_client.clone(),
         ethereum_client.clone(),
-    ).await);
+    ).await
+    .with_fee_schedule(fee_schedule.clone())
+    .with_withholding(withholding.clone(), holdings_sync.clone())
+    .with_auto_compound(auto_compound.clone())
+    .with_calendar(business_calendar.clone()));
     
     // Create AuthenticationService
+    let admin_wallets = std::env::var("TREASURY_ADMIN_WALLETS")
+        .unwrap_or_default()
+        .split(',')
+        .filter(|wallet| !wallet.trim().is_empty())
+        .map(|wallet| Address::parse_checksummed(wallet.trim(), None).expect("Invalid TREASURY_ADMIN_WALLETS address"))
+        .collect();
     let auth_service = Arc::new(AuthenticationService::new(
         user_service.clone(),
         ethereum_client.clone(),
         jwt_secret,
-    ).await);
+    ).await
+    .with_admin_wallets(admin_wallets));
     
     // Create TradingClient
     let trading_client = treasury_service::clients::trading_client::TradingClient::new(
         ethereum_client.clone(),
-        Address::ZERO, // Mock address
+        contracts.get(ContractName::Trading)?,
     ).await;
     
+    // Fills settle delivery-versus-payment through the trading contract; failed settlements
+    // are retried each run until SETTLEMENT_DEADLINE_SECS, then cancelled
+    let stablecoin_address = std::env::var("SETTLEMENT_STABLECOIN_ADDRESS")
+        .ok()
+        .map(|address| Address::parse_checksummed(address.trim(), None).expect("Invalid SETTLEMENT_STABLECOIN_ADDRESS"))
+        .unwrap_or(Address::ZERO);
+    let mut settlement_engine = SettlementEngine::new(
+        Arc::new(ContractSettlementChain::new(ethereum_client.clone(), contracts.get(ContractName::Trading)?, stablecoin_address)),
+        SettlementConfig::from_env()?,
+    )
+    .with_fee_schedule(fee_schedule.clone())
+    .with_dealing_windows(dealing_windows.clone());
+    if let Ok(url) = std::env::var("SETTLEMENT_WEBHOOK_URL") {
+        settlement_engine = settlement_engine.with_notifier(Arc::new(WebhookSettlementNotifier::new(url)));
+    }
+    let settlement_engine = Arc::new(settlement_engine);
+    let settlement_interval = std::env::var("SETTLEMENT_INTERVAL_SECS")
+        .ok()
+        .and_then(|secs| secs.parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(30);
+    spawn_settlement_processing(settlement_engine.clone(), std::time::Duration::from_secs(settlement_interval));
+    
+    // Local orders are reconciled against trading contract events from the stored cursor;
+    // drift that cannot be repaired is alerted
+    let cursor: Arc<dyn CursorStore> = match std::env::var("RECONCILIATION_CURSOR_PATH") {
+        Ok(path) => Arc::new(FileCursorStore::new(path)),
+        Err(_) => Arc::new(MemoryCursorStore::default()),
+    };
+    let mut reconciler = OrderReconciler::new(
+        Arc::new(ContractOrderEventSource::new(ethereum_client.clone(), contracts.get(ContractName::Trading)?)),
+        Arc::new(OrderLedger::default()),
+        settlement_engine.clone(),
+        cursor,
+        ReconciliationConfig::from_env()?,
+    );
+    if let Ok(url) = std::env::var("RECONCILIATION_WEBHOOK_URL") {
+        reconciler = reconciler.with_alerter(Arc::new(WebhookDriftAlerter::new(url)));
+    }
+    let reconciler = Arc::new(reconciler);
+    let reconciliation_interval = std::env::var("RECONCILIATION_INTERVAL_SECS")
+        .ok()
+        .and_then(|secs| secs.parse::<u64>().ok())
+        .unwrap_or(300);
+    if reconciliation_interval > 0 {
+        spawn_reconciliation(reconciler.clone(), std::time::Duration::from_secs(reconciliation_interval));
+    }
+    
+    // The order ledger can be rebuilt from trading contract history; replay progress is kept
+    // in a file when configured so a long replay survives a restart
+    let replay_checkpoints: Arc<dyn CheckpointStore> = match std::env::var("REPLAY_CHECKPOINT_PATH") {
+        Ok(path) => Arc::new(FileCheckpointStore::new(path)),
+        Err(_) => Arc::new(MemoryCheckpointStore::default()),
+    };
+    let order_replayer = Arc::new(OrderReplayer::new(
+        ethereum_client.clone(),
+        contracts.get(ContractName::Trading)?,
+        reconciler.clone(),
+        replay_checkpoints,
+        ReplayConfig::from_env()?,
+    ));
+    
     // Create L2Client
     let l2_client = treasury_service::clients::l2_client::L2Client::new(
         ethereum_client.clone(),
-        Address::ZERO, // Mock address
+        contracts.get(ContractName::L2)?,
     ).await;
     
     // Create AssetManagementService
@@ -135,49 +353,34 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         registry_client.clone(),
     ).await);
     
-    // Create L2BridgeClient with actual address
-    let l2_bridge_address = Address::parse_checksummed(&l2_bridge_address, None)
-        .expect("Invalid L2 bridge address format");
-    
+    // Create L2BridgeClient
     let l2_bridge_client = treasury_service::clients::l2_bridge_client::L2BridgeClient::new(
         ethereum_client.clone(),
-        l2_bridge_address,
+        contracts.get(ContractName::L2Bridge)?,
     );
     
-    // Create SmartAccountClient with actual address
-    let smart_account_address = Address::parse_checksummed(&smart_account_address, None)
-        .expect("Invalid smart account address format");
-    
+    // Create SmartAccountClient
     let smart_account_client = treasury_service::clients::smart_account_client::SmartAccountClient::new(
         ethereum_client.clone(),
-        smart_account_address,
+        contracts.get(ContractName::SmartAccount)?,
     );
     
-    // Create AssetFactoryClient with actual address
-    let asset_factory_address = Address::parse_checksummed(&asset_factory_address, None)
-        .expect("Invalid asset factory address format");
-    
+    // Create AssetFactoryClient
     let asset_factory_client = treasury_service::clients::asset_factory_client::AssetFactoryClient::new(
         ethereum_client.clone(),
-        asset_factory_address,
+        contracts.get(ContractName::AssetFactory)?,
     );
     
-    // Create LiquidityPoolsClient with actual address
-    let liquidity_pools_address = Address::parse_checksummed(&liquidity_pools_address, None)
-        .expect("Invalid liquidity pools address format");
-    
+    // Create LiquidityPoolsClient
     let liquidity_pools_client = treasury_service::clients::liquidity_pools_client::LiquidityPoolsClient::new(
         ethereum_client.clone(),
-        liquidity_pools_address,
+        contracts.get(ContractName::LiquidityPools)?,
     );
     
-    // Create YieldOptimizerClient with actual address
-    let yield_optimizer_address = Address::parse_checksummed(&yield_optimizer_address, None)
-        .expect("Invalid yield optimizer address format");
-    
+    // Create YieldOptimizerClient
     let yield_optimizer_client = treasury_service::clients::yield_optimizer_client::YieldOptimizerClient::new(
         ethereum_client.clone(),
-        yield_optimizer_address,
+        contracts.get(ContractName::YieldOptimizer)?,
     );
     
     // Create token client
@@ -192,6 +395,42 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     };
     
     // Create API services
+    let price_decimals = std::env::var("TREASURY_PRICE_DECIMALS")
+        .ok()
+        .and_then(|decimals| decimals.parse::<u32>().ok())
+        .unwrap_or(18);
+    
+    let feed_reader: Arc<dyn FeedReader> = Arc::new(ChainlinkFeedReader(ethereum_client.clone()));
+    let aggregator = Arc::new(OracleAggregator::from_env(Some(feed_reader))?);
+    // Fee totals in a reporting currency convert at the aggregator's <base><quote> FX quotes
+    let fx_rates = Arc::new(FxRateService::from_env(aggregator.clone())?);
+    
+    // Oracle prices are pushed to the registry when PRICE_UPDATE_INTERVAL_SECS is set;
+    // disputed prices are never written automatically
+    if let Some(interval) = std::env::var("PRICE_UPDATE_INTERVAL_SECS").ok()
+        .and_then(|secs| secs.parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+    {
+        let updater = Arc::new(TreasuryPriceUpdater::new(aggregator, treasury_service.clone(), price_decimals));
+        spawn_price_updates(updater, std::time::Duration::from_secs(interval));
+    }
+    
+    // Push treasury price and status changes to WebSocket subscribers
+    let treasury_feed = Arc::new(TreasuryFeed::default());
+    spawn_registry_sync(treasury_feed.clone(), treasury_service.clone(), feed_sync_interval);
+    
+    // LP analytics read pool history from the LiquidityPools deployment block
+    let liquidity_pools_client = Arc::new(liquidity_pools_client);
+    let lp_analytics = Arc::new(LpAnalytics::new(Arc::new(ContractLpDataSource::new(
+        ethereum_client.clone(),
+        liquidity_pools_client.clone(),
+        contracts.get(ContractName::LiquidityPools)?,
+        std::env::var("LIQUIDITY_POOLS_FROM_BLOCK")
+            .ok()
+            .and_then(|block| block.parse::<u64>().ok())
+            .unwrap_or(0),
+    ))));
+    
     let api_services = ApiServices {
         treasury_service,
         registry_client,
@@ -206,18 +445,37 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         l2_bridge_client: Arc::new(l2_bridge_client),
         smart_account_client: Arc::new(smart_account_client),
         asset_factory_client: Arc::new(asset_factory_client),
-        liquidity_pools_client: Arc::new(liquidity_pools_client),
+        liquidity_pools_client,
         yield_optimizer_client: Arc::new(yield_optimizer_client),
+        pre_trade_compliance,
+        pre_trade_risk,
+        treasury_feed,
+        settlement_engine,
+        reconciler,
+        order_replayer,
+        fee_schedule,
+        business_calendar,
+        withholding,
+        dealing_windows,
+        lp_analytics,
+        auto_compound,
+        fx_rates,
+        price_decimals,
     };
     
     // Create API routes
     let api_routes = routes(api_services);
     
+    let metrics_route = warp::path("metrics")
+        .and(warp::path::end())
+        .and(warp::get())
+        .map(move || eth_metrics.render());
+    
     // Start the server
     let addr = SocketAddr::from(([0, 0, 0, 0], server_port));
     info!("Listening on {}", addr);
     
-    warp::serve(api_routes)
+    warp::serve(api_routes.or(metrics_route))
         .run(addr)
         .await;
     
@@ -0,0 +1,85 @@
+use treasury_service::{
+    admin_cli::{self, Cli, RegistryConfig, ServiceAdmin, EXIT_FAILURE},
+    ComplianceChecker,
+    ContractName,
+    Error,
+    TokenDeployer,
+};
+use alloy_primitives::Address;
+use clap::Parser;
+use ethereum_client::EthereumClient;
+use std::sync::Arc;
+use tracing_subscriber::EnvFilter;
+
+/// The admin CLI only manages treasuries that already exist; creation goes through the API
+struct NoTokenDeployer;
+
+impl TokenDeployer for NoTokenDeployer {
+    fn deploy_token(&self, _name: &str, _symbol: &str, _total_supply: u64, _issuer: Address) -> Result<Address, Error> {
+        Err(Error::Unimplemented("treasury_admin does not deploy tokens".into()))
+    }
+}
+
+struct NoComplianceChecker;
+
+impl ComplianceChecker for NoComplianceChecker {
+    fn is_compliant(&self, _issuer: Address) -> Result<bool, Error> {
+        Err(Error::Unimplemented("treasury_admin does not check issuer compliance".into()))
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    // Logs go to stderr so stdout stays parseable with --json
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .with_writer(std::io::stderr)
+        .init();
+
+    dotenv::dotenv().ok();
+
+    let cli = Cli::parse();
+
+    let config = match RegistryConfig::from_env() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(EXIT_FAILURE);
+        }
+    };
+
+    let ethereum_client = match EthereumClient::new(&config.ethereum_rpc_url).await {
+        Ok(client) => {
+            let client = client.with_finality_policy(config.finality);
+            match config.stall_policy {
+                Some(policy) => Arc::new(client.with_stall_policy(policy)),
+                None => Arc::new(client),
+            }
+        }
+        Err(e) => {
+            eprintln!("error: cannot connect to {}: {}", config.ethereum_rpc_url, e);
+            std::process::exit(EXIT_FAILURE);
+        }
+    };
+
+    if let Err(e) = config.contracts.validate(&*ethereum_client, &[ContractName::TreasuryRegistry]).await {
+        eprintln!("error: {}", e);
+        std::process::exit(EXIT_FAILURE);
+    }
+
+    let admin = match ServiceAdmin::connect(
+        &config,
+        ethereum_client,
+        Box::new(NoTokenDeployer),
+        Box::new(NoComplianceChecker),
+    ).await {
+        Ok(admin) => admin,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(EXIT_FAILURE);
+        }
+    };
+
+    let code = admin_cli::run(cli, &admin, &mut std::io::stdout(), &mut std::io::stderr()).await;
+    std::process::exit(code);
+}
@@ -0,0 +1,424 @@
+// Bulk treasury status transitions with dry-run, batched registry writes and maker-checker approval
+use async_trait::async_trait;
+use serde::{Serialize, Deserialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use tracing::warn;
+use uuid::Uuid;
+use crate::{Error, TreasuryRegistryClient, TreasuryStatus};
+
+/// Whether the registry lifecycle allows moving a treasury from `from` to `to`
+pub fn transition_allowed(from: TreasuryStatus, to: TreasuryStatus) -> bool {
+    matches!(
+        (from, to),
+        (TreasuryStatus::Active, TreasuryStatus::Matured) | (TreasuryStatus::Matured, TreasuryStatus::Redeemed)
+    )
+}
+
+/// Registry reads and writes used by bulk status transitions
+#[async_trait]
+pub trait StatusRegistry: Send + Sync {
+    async fn current_status(&self, token_id: [u8; 32]) -> Result<TreasuryStatus, Error>;
+
+    /// Whether several updates can be sent as one transaction
+    fn supports_batch(&self) -> bool;
+
+    async fn update_status(&self, token_id: [u8; 32], status: TreasuryStatus) -> Result<(), Error>;
+
+    /// Update every treasury in one transaction; all or nothing
+    async fn update_statuses(&self, token_ids: &[[u8; 32]], status: TreasuryStatus) -> Result<(), Error>;
+}
+
+#[async_trait]
+impl StatusRegistry for TreasuryRegistryClient {
+    async fn current_status(&self, token_id: [u8; 32]) -> Result<TreasuryStatus, Error> {
+        Ok(self.get_treasury_details(token_id).await?.status)
+    }
+
+    fn supports_batch(&self) -> bool {
+        self.batches_status_updates()
+    }
+
+    async fn update_status(&self, token_id: [u8; 32], status: TreasuryStatus) -> Result<(), Error> {
+        self.update_treasury_status(token_id, status).await
+    }
+
+    async fn update_statuses(&self, token_ids: &[[u8; 32]], status: TreasuryStatus) -> Result<(), Error> {
+        self.update_treasury_statuses(token_ids, status).await
+    }
+}
+
+/// What happened, or would happen, to one treasury
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum TransitionOutcome {
+    /// Valid, not sent because the run was a dry run
+    WouldApply,
+    Applied,
+    Failed { reason: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransitionResult {
+    #[serde(with = "quantera_types::wire::token_id")]
+    pub token_id: [u8; 32],
+    /// Status before the run; None when it could not be read
+    pub from: Option<TreasuryStatus>,
+    #[serde(flatten)]
+    pub outcome: TransitionOutcome,
+}
+
+/// Per-treasury outcome of a bulk status run, in request order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkStatusReport {
+    pub new_status: TreasuryStatus,
+    pub dry_run: bool,
+    /// Whether the writes went out as a single registry transaction
+    pub batched: bool,
+    pub results: Vec<TransitionResult>,
+}
+
+impl BulkStatusReport {
+    /// Treasuries that failed validation or whose write failed
+    pub fn failed_ids(&self) -> Vec<[u8; 32]> {
+        self.results.iter()
+            .filter(|result| matches!(result.outcome, TransitionOutcome::Failed { .. }))
+            .map(|result| result.token_id)
+            .collect()
+    }
+}
+
+/// Validate and, unless `dry_run`, apply a status transition to each treasury.
+///
+/// Treasuries are checked independently: a duplicate id, an unreadable status or a
+/// disallowed transition fails that treasury only. Valid writes are batched when the
+/// registry supports it; if the batch fails they are retried one by one so each
+/// treasury gets its own outcome.
+pub async fn apply_bulk_status(
+    registry: &dyn StatusRegistry,
+    token_ids: &[[u8; 32]],
+    new_status: TreasuryStatus,
+    dry_run: bool,
+) -> BulkStatusReport {
+    let mut seen = HashSet::new();
+    let mut results = Vec::with_capacity(token_ids.len());
+    let mut valid = Vec::new();
+
+    for &token_id in token_ids {
+        let failed = |from, reason: String| TransitionResult { token_id, from, outcome: TransitionOutcome::Failed { reason } };
+        if !seen.insert(token_id) {
+            results.push(failed(None, "Duplicate token id in request".into()));
+            continue;
+        }
+        match registry.current_status(token_id).await {
+            Err(e) => results.push(failed(None, e.to_string())),
+            Ok(from) if !transition_allowed(from, new_status) => {
+                results.push(failed(Some(from), format!("Transition from {:?} to {:?} is not allowed", from, new_status)));
+            }
+            Ok(from) => {
+                valid.push(results.len());
+                results.push(TransitionResult { token_id, from: Some(from), outcome: TransitionOutcome::WouldApply });
+            }
+        }
+    }
+
+    let mut report = BulkStatusReport { new_status, dry_run, batched: false, results };
+    if dry_run || valid.is_empty() {
+        return report;
+    }
+
+    if registry.supports_batch() && valid.len() > 1 {
+        let ids: Vec<[u8; 32]> = valid.iter().map(|&i| report.results[i].token_id).collect();
+        match registry.update_statuses(&ids, new_status).await {
+            Ok(()) => {
+                for &i in &valid {
+                    report.results[i].outcome = TransitionOutcome::Applied;
+                }
+                report.batched = true;
+                return report;
+            }
+            Err(e) => warn!("Batched status update of {} treasuries failed, retrying one by one: {}", ids.len(), e),
+        }
+    }
+
+    for &i in &valid {
+        let result = &mut report.results[i];
+        result.outcome = match registry.update_status(result.token_id, new_status).await {
+            Ok(()) => TransitionOutcome::Applied,
+            Err(e) => TransitionOutcome::Failed { reason: e.to_string() },
+        };
+    }
+    report
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum BulkStatusState {
+    /// Previewed, waiting for a second admin
+    PendingApproval,
+    Running,
+    Completed,
+    /// Some treasuries failed; can be resumed for just those
+    PartiallyFailed,
+}
+
+/// A bulk status change and its runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkStatusRequest {
+    pub request_id: Uuid,
+    pub new_status: TreasuryStatus,
+    pub requested_by: String,
+    pub approved_by: Option<String>,
+    pub state: BulkStatusState,
+    /// Dry run made when the request was submitted
+    pub preview: BulkStatusReport,
+    pub runs: Vec<BulkStatusReport>,
+    pub created_at: u64,
+    pub updated_at: u64,
+    /// Treasuries the next run applies to
+    #[serde(skip)]
+    pending: Vec<[u8; 32]>,
+}
+
+impl BulkStatusRequest {
+    pub fn pending_ids(&self) -> &[[u8; 32]] {
+        &self.pending
+    }
+}
+
+/// Bulk status requests awaiting approval or resumption.
+///
+/// A request is submitted by one admin and must be approved by another before any
+/// transaction is sent. Only one run of a request can be in flight.
+#[derive(Debug, Default)]
+pub struct BulkStatusLedger {
+    requests: Mutex<HashMap<Uuid, BulkStatusRequest>>,
+}
+
+impl BulkStatusLedger {
+    /// Record a previewed request pending approval
+    pub fn submit(
+        &self,
+        token_ids: Vec<[u8; 32]>,
+        new_status: TreasuryStatus,
+        requested_by: &str,
+        preview: BulkStatusReport,
+    ) -> Result<BulkStatusRequest, Error> {
+        let now = chrono::Utc::now().timestamp() as u64;
+        let request = BulkStatusRequest {
+            request_id: Uuid::new_v4(),
+            new_status,
+            requested_by: requested_by.to_string(),
+            approved_by: None,
+            state: BulkStatusState::PendingApproval,
+            preview,
+            runs: Vec::new(),
+            created_at: now,
+            updated_at: now,
+            pending: token_ids,
+        };
+
+        self.lock()?.insert(request.request_id, request.clone());
+        Ok(request)
+    }
+
+    /// Approve a pending request and claim it for its first run
+    pub fn approve(&self, request_id: Uuid, approver: &str) -> Result<BulkStatusRequest, Error> {
+        self.update(request_id, |request| {
+            if request.state != BulkStatusState::PendingApproval {
+                return Err(Error::InvalidState(format!(
+                    "Bulk status request {} is {:?}, only pending requests can be approved", request_id, request.state
+                )));
+            }
+            if request.requested_by == approver {
+                return Err(Error::Unauthorized("Bulk status changes must be approved by a different admin".into()));
+            }
+            request.approved_by = Some(approver.to_string());
+            request.state = BulkStatusState::Running;
+            Ok(())
+        })
+    }
+
+    /// Claim a partially failed request to rerun its failed treasuries
+    pub fn begin_resume(&self, request_id: Uuid) -> Result<BulkStatusRequest, Error> {
+        self.update(request_id, |request| {
+            if request.state != BulkStatusState::PartiallyFailed {
+                return Err(Error::InvalidState(format!(
+                    "Bulk status request {} is {:?}, only partially failed requests can be resumed", request_id, request.state
+                )));
+            }
+            request.state = BulkStatusState::Running;
+            Ok(())
+        })
+    }
+
+    /// Record a finished run; its failures become the treasuries a resume applies to
+    pub fn record_run(&self, request_id: Uuid, report: BulkStatusReport) -> Result<BulkStatusRequest, Error> {
+        self.update(request_id, |request| {
+            request.pending = report.failed_ids();
+            request.state = if request.pending.is_empty() {
+                BulkStatusState::Completed
+            } else {
+                BulkStatusState::PartiallyFailed
+            };
+            request.runs.push(report);
+            Ok(())
+        })
+    }
+
+    pub fn get(&self, request_id: &Uuid) -> Option<BulkStatusRequest> {
+        self.requests.lock().ok().and_then(|requests| requests.get(request_id).cloned())
+    }
+
+    fn update<F>(&self, request_id: Uuid, change: F) -> Result<BulkStatusRequest, Error>
+    where
+        F: FnOnce(&mut BulkStatusRequest) -> Result<(), Error>,
+    {
+        let mut requests = self.lock()?;
+        let request = requests.get_mut(&request_id)
+            .ok_or_else(|| Error::NotFound(format!("Bulk status request {}", request_id)))?;
+        change(request)?;
+        request.updated_at = chrono::Utc::now().timestamp() as u64;
+        Ok(request.clone())
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, HashMap<Uuid, BulkStatusRequest>>, Error> {
+        self.requests.lock()
+            .map_err(|_| Error::Internal("Bulk status ledger lock poisoned".into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct MockRegistry {
+        statuses: Mutex<HashMap<[u8; 32], TreasuryStatus>>,
+        batch: bool,
+        /// Writes to these treasuries fail
+        failing: Mutex<HashSet<[u8; 32]>>,
+        batch_calls: AtomicUsize,
+        single_calls: AtomicUsize,
+    }
+
+    impl MockRegistry {
+        fn new(statuses: &[([u8; 32], TreasuryStatus)], batch: bool) -> Self {
+            Self {
+                statuses: Mutex::new(statuses.iter().copied().collect()),
+                batch,
+                failing: Mutex::new(HashSet::new()),
+                batch_calls: AtomicUsize::new(0),
+                single_calls: AtomicUsize::new(0),
+            }
+        }
+
+        fn status(&self, token_id: [u8; 32]) -> TreasuryStatus {
+            self.statuses.lock().unwrap()[&token_id]
+        }
+    }
+
+    #[async_trait]
+    impl StatusRegistry for MockRegistry {
+        async fn current_status(&self, token_id: [u8; 32]) -> Result<TreasuryStatus, Error> {
+            self.statuses.lock().unwrap().get(&token_id).copied()
+                .ok_or_else(|| Error::NotFound("Treasury not registered".into()))
+        }
+
+        fn supports_batch(&self) -> bool {
+            self.batch
+        }
+
+        async fn update_status(&self, token_id: [u8; 32], status: TreasuryStatus) -> Result<(), Error> {
+            self.single_calls.fetch_add(1, Ordering::SeqCst);
+            if self.failing.lock().unwrap().contains(&token_id) {
+                return Err(Error::InvalidState("updateTreasuryStatus would revert".into()));
+            }
+            self.statuses.lock().unwrap().insert(token_id, status);
+            Ok(())
+        }
+
+        async fn update_statuses(&self, token_ids: &[[u8; 32]], status: TreasuryStatus) -> Result<(), Error> {
+            self.batch_calls.fetch_add(1, Ordering::SeqCst);
+            if token_ids.iter().any(|id| self.failing.lock().unwrap().contains(id)) {
+                return Err(Error::InvalidState("multicall would revert".into()));
+            }
+            let mut statuses = self.statuses.lock().unwrap();
+            for id in token_ids {
+                statuses.insert(*id, status);
+            }
+            Ok(())
+        }
+    }
+
+    const ACTIVE_A: [u8; 32] = [1; 32];
+    const ACTIVE_B: [u8; 32] = [2; 32];
+    const REDEEMED: [u8; 32] = [3; 32];
+    const UNKNOWN: [u8; 32] = [4; 32];
+
+    fn outcome(report: &BulkStatusReport, token_id: [u8; 32]) -> &TransitionOutcome {
+        &report.results.iter().find(|r| r.token_id == token_id).unwrap().outcome
+    }
+
+    #[tokio::test]
+    async fn test_mixed_batch_dry_run_then_batched_execute() {
+        let registry = MockRegistry::new(&[
+            (ACTIVE_A, TreasuryStatus::Active),
+            (ACTIVE_B, TreasuryStatus::Active),
+            (REDEEMED, TreasuryStatus::Redeemed),
+        ], true);
+        let ids = [ACTIVE_A, REDEEMED, ACTIVE_B, UNKNOWN, ACTIVE_A];
+
+        let preview = apply_bulk_status(&registry, &ids, TreasuryStatus::Matured, true).await;
+        assert_eq!(registry.batch_calls.load(Ordering::SeqCst) + registry.single_calls.load(Ordering::SeqCst), 0);
+        assert_eq!(registry.status(ACTIVE_A), TreasuryStatus::Active);
+        assert_eq!(preview.results.len(), 5);
+        assert_eq!(outcome(&preview, ACTIVE_A), &TransitionOutcome::WouldApply);
+        assert_eq!(outcome(&preview, ACTIVE_B), &TransitionOutcome::WouldApply);
+        // Redeemed -> Matured, an unregistered id and a repeated id all fail on their own
+        assert_eq!(preview.failed_ids(), vec![REDEEMED, UNKNOWN, ACTIVE_A]);
+
+        let report = apply_bulk_status(&registry, &ids, TreasuryStatus::Matured, false).await;
+        assert!(report.batched);
+        assert_eq!(registry.batch_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(registry.single_calls.load(Ordering::SeqCst), 0);
+        assert_eq!(outcome(&report, ACTIVE_B), &TransitionOutcome::Applied);
+        assert_eq!(registry.status(ACTIVE_A), TreasuryStatus::Matured);
+        assert_eq!(registry.status(ACTIVE_B), TreasuryStatus::Matured);
+        assert_eq!(registry.status(REDEEMED), TreasuryStatus::Redeemed);
+    }
+
+    #[tokio::test]
+    async fn test_partial_failure_resumes_only_failed_ids() {
+        let registry = MockRegistry::new(&[
+            (ACTIVE_A, TreasuryStatus::Active),
+            (ACTIVE_B, TreasuryStatus::Active),
+        ], true);
+        registry.failing.lock().unwrap().insert(ACTIVE_B);
+        let ids = vec![ACTIVE_A, ACTIVE_B];
+        let ledger = BulkStatusLedger::default();
+
+        let preview = apply_bulk_status(&registry, &ids, TreasuryStatus::Matured, true).await;
+        let request = ledger.submit(ids, TreasuryStatus::Matured, "0xmaker", preview).unwrap();
+        assert!(matches!(ledger.approve(request.request_id, "0xmaker"), Err(Error::Unauthorized(_))));
+        let request = ledger.approve(request.request_id, "0xchecker").unwrap();
+
+        // The batch reverts as a whole, so each treasury is retried on its own
+        let report = apply_bulk_status(&registry, request.pending_ids(), request.new_status, false).await;
+        assert!(!report.batched);
+        assert_eq!(outcome(&report, ACTIVE_A), &TransitionOutcome::Applied);
+        assert!(matches!(outcome(&report, ACTIVE_B), TransitionOutcome::Failed { .. }));
+        let request = ledger.record_run(request.request_id, report).unwrap();
+        assert_eq!(request.state, BulkStatusState::PartiallyFailed);
+        assert_eq!(request.pending_ids(), &[ACTIVE_B]);
+
+        registry.failing.lock().unwrap().clear();
+        let request = ledger.begin_resume(request.request_id).unwrap();
+        assert!(matches!(ledger.begin_resume(request.request_id), Err(Error::InvalidState(_))));
+        let report = apply_bulk_status(&registry, request.pending_ids(), request.new_status, false).await;
+        assert_eq!(report.results.len(), 1);
+        let request = ledger.record_run(request.request_id, report).unwrap();
+        assert_eq!(request.state, BulkStatusState::Completed);
+        assert_eq!(request.runs.len(), 2);
+        assert_eq!(registry.status(ACTIVE_B), TreasuryStatus::Matured);
+    }
+}
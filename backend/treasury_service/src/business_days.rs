@@ -0,0 +1,258 @@
+// Business-day calendars for distribution and maturity dates
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc, Weekday};
+use serde::{Serialize, Deserialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::RwLock;
+use tracing::info;
+use crate::Error;
+
+/// Longest run of consecutive non-business days an adjustment will step over
+const MAX_CLOSED_RUN: u32 = 31;
+
+/// How a date falling on a weekend or holiday is moved to a business day
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BusinessDayConvention {
+    /// The next business day
+    Following,
+    /// The next business day, unless that is in the next month; then the previous one
+    ModifiedFollowing,
+    /// The previous business day
+    Preceding,
+}
+
+impl std::str::FromStr for BusinessDayConvention {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "following" => Ok(BusinessDayConvention::Following),
+            "modified_following" => Ok(BusinessDayConvention::ModifiedFollowing),
+            "preceding" => Ok(BusinessDayConvention::Preceding),
+            other => Err(Error::InvalidParameter(format!("Unknown business day convention {}", other))),
+        }
+    }
+}
+
+/// A market holiday
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Holiday {
+    pub date: NaiveDate,
+    pub name: String,
+    /// `configuration` for holidays from the calendar file, otherwise the admin who added it
+    #[serde(default = "configured_by")]
+    pub added_by: String,
+}
+
+fn configured_by() -> String {
+    "configuration".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewHoliday {
+    pub market: String,
+    pub date: NaiveDate,
+    pub name: String,
+}
+
+/// A scheduled date and the business day it was moved to, kept on the resulting record
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DateAdjustment {
+    pub market: String,
+    pub convention: BusinessDayConvention,
+    pub unadjusted: NaiveDate,
+    pub adjusted: NaiveDate,
+}
+
+impl DateAdjustment {
+    /// `timestamp` moved by the same number of days, keeping its time of day
+    pub fn apply(&self, timestamp: u64) -> u64 {
+        let shift = (self.adjusted - self.unadjusted).num_seconds();
+        (timestamp as i64 + shift).max(0) as u64
+    }
+}
+
+/// Holidays per market, plus the market and convention used for scheduling.
+///
+/// Saturdays and Sundays are never business days. Holidays come from the calendar file
+/// at startup; admins can add ad-hoc closures while running.
+pub struct BusinessCalendar {
+    market: String,
+    convention: BusinessDayConvention,
+    holidays: RwLock<HashMap<String, BTreeMap<NaiveDate, Holiday>>>,
+}
+
+impl BusinessCalendar {
+    pub fn new(market: impl Into<String>, convention: BusinessDayConvention) -> Self {
+        Self { market: market.into().to_uppercase(), convention, holidays: RwLock::new(HashMap::new()) }
+    }
+
+    /// Calendar for `BUSINESS_DAY_MARKET` (default `US`) and `BUSINESS_DAY_CONVENTION`
+    /// (default `modified_following`), with the holidays in the JSON file at
+    /// `HOLIDAY_CALENDAR_PATH`: an object of market to `[{"date", "name"}]`
+    pub fn from_env() -> Result<Self, Error> {
+        let convention = match std::env::var("BUSINESS_DAY_CONVENTION") {
+            Ok(convention) => convention.parse()?,
+            Err(_) => BusinessDayConvention::ModifiedFollowing,
+        };
+        let calendar = Self::new(std::env::var("BUSINESS_DAY_MARKET").unwrap_or_else(|_| "US".to_string()), convention);
+        let Ok(path) = std::env::var("HOLIDAY_CALENDAR_PATH") else { return Ok(calendar) };
+
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| Error::InvalidParameter(format!("Cannot read HOLIDAY_CALENDAR_PATH {}: {}", path, e)))?;
+        calendar.load(&contents)
+            .map_err(|e| Error::InvalidParameter(format!("Invalid holiday calendar {}: {}", path, e)))?;
+        Ok(calendar)
+    }
+
+    /// Add the holidays of a calendar file's contents
+    pub fn load(&self, contents: &str) -> Result<(), Error> {
+        let markets: HashMap<String, Vec<Holiday>> = serde_json::from_str(contents)
+            .map_err(|e| Error::InvalidParameter(e.to_string()))?;
+        let mut holidays = self.holidays.write().map_err(|_| Error::Internal("Holiday calendar lock poisoned".into()))?;
+        for (market, days) in markets {
+            let market = holidays.entry(market.to_uppercase()).or_default();
+            for holiday in days {
+                market.insert(holiday.date, holiday);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn market(&self) -> &str {
+        &self.market
+    }
+
+    pub fn convention(&self) -> BusinessDayConvention {
+        self.convention
+    }
+
+    /// Add an ad-hoc closure, such as a national day of mourning
+    pub fn add_holiday(&self, new: NewHoliday, added_by: &str) -> Result<Holiday, Error> {
+        let market = new.market.trim().to_uppercase();
+        if market.is_empty() || new.name.trim().is_empty() {
+            return Err(Error::InvalidParameter("Holidays need a market and a name".into()));
+        }
+        if matches!(new.date.weekday(), Weekday::Sat | Weekday::Sun) {
+            return Err(Error::InvalidParameter(format!("{} is already a weekend day", new.date)));
+        }
+
+        let mut holidays = self.holidays.write().map_err(|_| Error::Internal("Holiday calendar lock poisoned".into()))?;
+        let days = holidays.entry(market.clone()).or_default();
+        if let Some(existing) = days.get(&new.date) {
+            return Err(Error::InvalidParameter(format!("{} is already a {} holiday: {}", new.date, market, existing.name)));
+        }
+        let holiday = Holiday { date: new.date, name: new.name.trim().to_string(), added_by: added_by.to_string() };
+        days.insert(new.date, holiday.clone());
+        info!("[AUDIT] {} holiday {} ({}) added by {}", market, holiday.date, holiday.name, added_by);
+        Ok(holiday)
+    }
+
+    /// Holidays of a market, earliest first
+    pub fn holidays(&self, market: &str) -> Vec<Holiday> {
+        self.holidays.read()
+            .map(|holidays| holidays.get(&market.to_uppercase()).map(|days| days.values().cloned().collect()).unwrap_or_default())
+            .unwrap_or_default()
+    }
+
+    pub fn is_business_day(&self, market: &str, date: NaiveDate) -> bool {
+        if matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+            return false;
+        }
+        self.holidays.read()
+            .map(|holidays| !holidays.get(&market.to_uppercase()).map_or(false, |days| days.contains_key(&date)))
+            .unwrap_or(true)
+    }
+
+    /// Move `date` to a business day of `market`; `None` when it already is one
+    pub fn adjust(&self, market: &str, date: NaiveDate, convention: BusinessDayConvention) -> Result<Option<DateAdjustment>, Error> {
+        if self.is_business_day(market, date) {
+            return Ok(None);
+        }
+        let adjusted = match convention {
+            BusinessDayConvention::Following => self.step(market, date, 1)?,
+            BusinessDayConvention::Preceding => self.step(market, date, -1)?,
+            BusinessDayConvention::ModifiedFollowing => {
+                let following = self.step(market, date, 1)?;
+                if following.month() == date.month() { following } else { self.step(market, date, -1)? }
+            }
+        };
+        Ok(Some(DateAdjustment { market: market.to_uppercase(), convention, unadjusted: date, adjusted }))
+    }
+
+    /// Adjust the UTC date of a unix timestamp in the scheduling market and convention
+    pub fn adjust_timestamp(&self, timestamp: u64) -> Result<Option<DateAdjustment>, Error> {
+        let date = Utc.timestamp_opt(timestamp as i64, 0).single()
+            .map(|at: DateTime<Utc>| at.date_naive())
+            .ok_or_else(|| Error::InvalidParameter(format!("Invalid timestamp {}", timestamp)))?;
+        self.adjust(&self.market, date, self.convention)
+    }
+
+    fn step(&self, market: &str, date: NaiveDate, direction: i64) -> Result<NaiveDate, Error> {
+        let mut day = date;
+        for _ in 0..MAX_CLOSED_RUN {
+            day += chrono::Duration::days(direction);
+            if self.is_business_day(market, day) {
+                return Ok(day);
+            }
+        }
+        Err(Error::InvalidState(format!("No {} business day within {} days of {}", market, MAX_CLOSED_RUN, date)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    /// Good Friday and Easter Monday bridge the weekend of 4-5 April 2026; an ad-hoc
+    /// Friday and the Monday after close the weekend of 31 October-1 November
+    fn calendar() -> BusinessCalendar {
+        let calendar = BusinessCalendar::new("GB", BusinessDayConvention::ModifiedFollowing);
+        calendar.load(r#"{"GB": [
+            {"date": "2026-04-03", "name": "Good Friday"},
+            {"date": "2026-04-06", "name": "Easter Monday"},
+            {"date": "2026-11-02", "name": "Exchange closure"}
+        ]}"#).unwrap();
+        calendar.add_holiday(NewHoliday { market: "gb".to_string(), date: date("2026-10-30"), name: "System migration".to_string() }, "ops").unwrap();
+        calendar
+    }
+
+    #[test]
+    fn test_conventions_around_a_bridged_weekend() {
+        let calendar = calendar();
+        let adjusted = |day: &str, convention| calendar.adjust("GB", date(day), convention).unwrap().map(|a| a.adjusted);
+
+        assert_eq!(adjusted("2026-04-04", BusinessDayConvention::Following), Some(date("2026-04-07")));
+        assert_eq!(adjusted("2026-04-04", BusinessDayConvention::Preceding), Some(date("2026-04-02")));
+        assert_eq!(adjusted("2026-04-04", BusinessDayConvention::ModifiedFollowing), Some(date("2026-04-07")));
+        // The holidays themselves move the same way as the weekend between them
+        assert_eq!(adjusted("2026-04-03", BusinessDayConvention::Following), Some(date("2026-04-07")));
+        assert_eq!(adjusted("2026-04-06", BusinessDayConvention::Preceding), Some(date("2026-04-02")));
+        assert_eq!(adjusted("2026-04-07", BusinessDayConvention::Following), None);
+
+        // Following would leave October, so modified following goes back instead
+        assert_eq!(adjusted("2026-10-31", BusinessDayConvention::Following), Some(date("2026-11-03")));
+        assert_eq!(adjusted("2026-10-31", BusinessDayConvention::ModifiedFollowing), Some(date("2026-10-29")));
+        assert_eq!(adjusted("2026-10-31", BusinessDayConvention::Preceding), Some(date("2026-10-29")));
+
+        // Other markets only close at weekends
+        assert_eq!(calendar.adjust("US", date("2026-04-03"), BusinessDayConvention::Following).unwrap(), None);
+    }
+
+    #[test]
+    fn test_timestamps_keep_their_time_of_day() {
+        let calendar = calendar();
+        let maturity = Utc.with_ymd_and_hms(2026, 10, 31, 16, 30, 0).unwrap().timestamp() as u64;
+        let adjustment = calendar.adjust_timestamp(maturity).unwrap().unwrap();
+        assert_eq!(adjustment.adjusted, date("2026-10-29"));
+        assert_eq!(adjustment.apply(maturity), Utc.with_ymd_and_hms(2026, 10, 29, 16, 30, 0).unwrap().timestamp() as u64);
+
+        assert!(calendar.add_holiday(NewHoliday { market: "GB".to_string(), date: date("2026-04-03"), name: "Again".to_string() }, "ops").is_err());
+        assert_eq!(calendar.holidays("GB").len(), 4);
+        assert_eq!(calendar.holidays("GB")[3].added_by, "configuration");
+    }
+}
@@ -2,15 +2,7 @@ use alloy_primitives::{Address, U256, H256, Bytes};
 use ethereum_client::{EthereumClient, Error as EthError};
 use serde::{Serialize, Deserialize};
 use std::sync::Arc;
-use crate::Error;
-
-/// Treasury Token types
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
-pub enum TreasuryType {
-    TBill,
-    TNote,
-    TBond,
-}
+use crate::{Error, TreasuryType};
 
 /// Treasury Token information
 #[derive(Debug, Clone, Serialize, Deserialize)]
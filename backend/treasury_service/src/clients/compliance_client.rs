@@ -5,6 +5,8 @@ use serde::{Serialize, Deserialize};
 use thiserror::Error;
 use tracing::{info, debug, warn, error};
 
+pub use quantera_types::VerificationStatus;
+
 /// Custom error type for ComplianceClient operations
 #[derive(Debug, Error)]
 pub enum Error {
@@ -27,16 +29,6 @@ pub enum Error {
     Unauthorized(String),
 }
 
-/// Verification status types
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
-pub enum VerificationStatus {
-    Unverified,
-    Pending,
-    Verified,
-    Rejected,
-    Suspended,
-}
-
 /// Entity type for compliance
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum EntityType {
@@ -78,6 +70,21 @@ pub struct RegulatoryRule {
     pub parameters: Vec<u8>,
 }
 
+/// Outcome of the ComplianceModule transfer check
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TransferCheck {
+    pub compliant: bool,
+    /// Rule that rejected the transfer, e.g. "Receiver not verified"
+    pub rule: Option<String>,
+}
+
+/// Decode the ABI-encoded reason string returned alongside a failed check
+fn decode_reason(data: &[u8]) -> Option<String> {
+    let len = u64::from_be_bytes(data.get(56..64)?.try_into().ok()?) as usize;
+    let reason = data.get(64..64usize.checked_add(len)?)?;
+    String::from_utf8(reason.to_vec()).ok()
+}
+
 /// Client for interacting with the ComplianceModule contract
 #[derive(Debug, Clone)]
 pub struct ComplianceClient {
@@ -209,6 +216,74 @@ impl ComplianceClient {
         Ok(())
     }
     
+    /// Register a wallet in the ERC-3643 identity registry
+    ///
+    /// `country` is the ISO 3166-1 numeric code and `claims_hash` commits to the
+    /// off-chain KYC claims the wallet was approved under.
+    pub async fn register_identity(
+        &self,
+        wallet: Address,
+        country: u16,
+        claims_hash: H256,
+    ) -> Result<(), Error> {
+        info!("Registering identity for wallet: {:?}, country: {}", wallet, country);
+
+        self.client.send_transaction(
+            self.contract_address,
+            "registerIdentity(address,uint16,bytes32)",
+            vec![
+                wallet.into(),
+                country.into(),
+                claims_hash.into(),
+            ],
+        ).await.map_err(Error::EthereumClient)?;
+
+        Ok(())
+    }
+
+    /// Replace the country and claims hash of a registered wallet
+    pub async fn update_identity(
+        &self,
+        wallet: Address,
+        country: u16,
+        claims_hash: H256,
+    ) -> Result<(), Error> {
+        info!("Updating identity for wallet: {:?}, country: {}", wallet, country);
+
+        self.client.send_transaction(
+            self.contract_address,
+            "updateIdentity(address,uint16,bytes32)",
+            vec![
+                wallet.into(),
+                country.into(),
+                claims_hash.into(),
+            ],
+        ).await.map_err(Error::EthereumClient)?;
+
+        Ok(())
+    }
+
+    /// Remove a wallet from the identity registry
+    ///
+    /// Only called once the investor has been offboarded; the wallet can no longer
+    /// receive registry-gated tokens afterwards.
+    pub async fn remove_identity(
+        &self,
+        wallet: Address,
+    ) -> Result<(), Error> {
+        warn!("Removing identity for wallet: {:?}", wallet);
+
+        self.client.send_transaction(
+            self.contract_address,
+            "deleteIdentity(address)",
+            vec![
+                wallet.into(),
+            ],
+        ).await.map_err(Error::EthereumClient)?;
+
+        Ok(())
+    }
+
     /// Get verification status for an entity
     pub async fn get_verification_status(
         &self,
@@ -627,6 +702,39 @@ impl ComplianceClient {
         Ok(result)
     }
     
+    /// Check whether a token transfer would pass the ComplianceModule rules.
+    ///
+    /// A zero `from` checks the receiver only, as for an initial issuance.
+    pub async fn check_transfer(
+        &self,
+        from: Address,
+        to: Address,
+        amount: U256,
+        treasury_id: [u8; 32],
+    ) -> Result<TransferCheck, Error> {
+        debug!("Checking transfer compliance from {:?} to {:?}, amount: {}", from, to, amount);
+        
+        let (compliant, reason) = self.client.call_contract::<(bool, Bytes)>(
+            self.contract_address,
+            "checkCompliance(address,address,uint256,bytes32)",
+            vec![
+                from.into(),
+                to.into(),
+                amount.into(),
+                treasury_id.into(),
+            ],
+        ).await.map_err(Error::EthereumClient)?;
+        
+        Ok(TransferCheck {
+            compliant,
+            rule: if compliant {
+                None
+            } else {
+                Some(decode_reason(&reason).unwrap_or_else(|| "Unspecified compliance rule".to_string()))
+            },
+        })
+    }
+    
     /// Register as an institutional validator
     pub async fn register_institutional_validator(
         &self,
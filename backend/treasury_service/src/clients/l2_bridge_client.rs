@@ -1,5 +1,6 @@
 use alloy_primitives::{Address, U256, Bytes, FixedBytes};
-use ethereum_client::{EthereumClient, Error as EthError};
+use ethereum_client::{EthereumClient, Error as EthError, FinalityCheck};
+use quantera_types::{Finality, FinalityStatus};
 use serde::{Serialize, Deserialize};
 use std::sync::Arc;
 use crate::Error;
@@ -230,6 +231,18 @@ impl L2BridgeClient {
         Ok(result)
     }
     
+    /// Finality of the source-chain transaction that sent a message; Pending while it is
+    /// unmined or its block was reorged out
+    pub async fn source_finality(&self, source_transaction_hash: [u8; 32]) -> Result<FinalityStatus, Error> {
+        let check = self.client.check_finality(source_transaction_hash.into(), &Finality::default())
+            .await
+            .map_err(Error::EthereumClient)?;
+        Ok(match check {
+            FinalityCheck::Observed { status, .. } => status,
+            FinalityCheck::Missing | FinalityCheck::Reorged { .. } => FinalityStatus::Pending,
+        })
+    }
+    
     /// Get the status of a message
     pub async fn get_message_status(&self, message_id: [u8; 32]) -> Result<MessageStatus, Error> {
         let status = self.client.call_contract::<MessageStatus>(
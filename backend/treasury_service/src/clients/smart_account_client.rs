@@ -1,10 +1,18 @@
+use alloy_contract::{Token, Tokenize};
 use alloy_primitives::{Address, U256, Bytes};
-use ethereum_client::{EthereumClient, Error as EthError};
+use ethereum_client::{EthereumClient, Error as EthError, SimulationOutcome, decode_revert_reason};
 use serde::{Serialize, Deserialize};
 use std::sync::Arc;
 use std::collections::HashMap;
+use tracing::{info, warn};
 use crate::Error;
 
+/// ERC-7821 batch entry point on an EIP-7702 delegated account
+const EXECUTE_SIGNATURE: &str = "execute(bytes32,bytes)";
+
+/// ERC-7579 call type for a batch of calls
+const CALL_TYPE_BATCH: u8 = 0x01;
+
 /// Type of smart account template
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum TemplateType {
@@ -87,6 +95,136 @@ pub struct SmartAccountOperation {
     pub executed_by: Address,
 }
 
+/// One call in a batched smart account execution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Call {
+    pub target: Address,
+    #[serde(default)]
+    pub value: U256,
+    pub data: Vec<u8>,
+}
+
+/// What a batch does when one of its calls reverts
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RevertPolicy {
+    /// Any failing call reverts the whole batch
+    #[default]
+    RevertAll,
+    /// Failing calls are reported and the remaining calls still run
+    ContinueOnFailure,
+}
+
+impl RevertPolicy {
+    /// ERC-7579 exec type: 0x00 reverts on failure, 0x01 tries each call
+    fn exec_type(&self) -> u8 {
+        match self {
+            RevertPolicy::RevertAll => 0x00,
+            RevertPolicy::ContinueOnFailure => 0x01,
+        }
+    }
+}
+
+/// Outcome of one call in a batch
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CallResult {
+    pub index: usize,
+    pub success: bool,
+    pub return_data: Vec<u8>,
+    /// Decoded from the return data of a failed call when it is a standard revert
+    pub revert_reason: Option<String>,
+}
+
+/// The call that failed a batch and why
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BatchFailure {
+    pub index: usize,
+    pub target: Address,
+    pub reason: String,
+}
+
+impl std::fmt::Display for BatchFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "call {} to {:?} reverted: {}", self.index, self.target, self.reason)
+    }
+}
+
+/// Outcome of a batched smart account execution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchResult {
+    pub policy: RevertPolicy,
+    /// Gas estimated for the whole batch before it was sent
+    pub gas_estimate: U256,
+    pub results: Vec<CallResult>,
+}
+
+impl BatchResult {
+    /// The first call that failed, if any
+    pub fn first_failure(&self, calls: &[Call]) -> Option<BatchFailure> {
+        first_failure(calls, &self.results)
+    }
+}
+
+/// ERC-7579 execution mode for a batch: call type, exec type, then zeroed selector and payload
+pub fn batch_mode(policy: RevertPolicy) -> [u8; 32] {
+    let mut mode = [0u8; 32];
+    mode[0] = CALL_TYPE_BATCH;
+    mode[1] = policy.exec_type();
+    mode
+}
+
+/// Arguments of `execute(bytes32,bytes)`; the execution data is `abi.encode((address,uint256,bytes)[])`
+pub fn encode_batch(calls: &[Call], policy: RevertPolicy) -> Result<Vec<Token>, Error> {
+    let executions = calls.iter()
+        .map(|call| Token::Tuple(vec![
+            Token::Address(call.target),
+            Token::from(call.value),
+            Token::Bytes(call.data.clone()),
+        ]))
+        .collect();
+    let execution_data = Token::encode(&[Token::Array(executions)])
+        .map_err(|e| Error::Encoding(format!("Failed to encode batch calls: {}", e)))?;
+
+    Ok(vec![Token::from(batch_mode(policy)), Token::Bytes(execution_data)])
+}
+
+/// Calldata sent to the delegated account to execute `calls`
+pub fn batch_payload(calls: &[Call], policy: RevertPolicy) -> Result<Vec<u8>, Error> {
+    EthereumClient::encode_function_call(EXECUTE_SIGNATURE, encode_batch(calls, policy)?)
+        .map_err(Error::Encoding)
+}
+
+/// Per-call results from the `(bool,bytes)[]` the account reports for a batch
+pub fn map_results(raw: Vec<(bool, Vec<u8>)>) -> Vec<CallResult> {
+    raw.into_iter()
+        .enumerate()
+        .map(|(index, (success, return_data))| CallResult {
+            index,
+            success,
+            revert_reason: if success { None } else { decode_revert_reason(&return_data) },
+            return_data,
+        })
+        .collect()
+}
+
+fn decode_results(data: &[u8]) -> Result<Vec<CallResult>, Error> {
+    let tokens = Token::decode(data)
+        .map_err(|e| Error::Decoding(format!("Failed to decode batch results: {}", e)))?;
+    let raw = Vec::<(bool, Vec<u8>)>::from_tokens(&tokens)
+        .map_err(|e| Error::Decoding(format!("Failed to convert batch results: {}", e)))?;
+    Ok(map_results(raw))
+}
+
+fn first_failure(calls: &[Call], results: &[CallResult]) -> Option<BatchFailure> {
+    results.iter()
+        .find(|result| !result.success)
+        .map(|result| BatchFailure {
+            index: result.index,
+            target: calls.get(result.index).map(|call| call.target).unwrap_or_default(),
+            reason: result.revert_reason.clone().unwrap_or_else(|| "reverted without a reason".to_string()),
+        })
+}
+
 /// Verification result for a template
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VerificationResult {
@@ -486,6 +624,56 @@ impl SmartAccountClient {
         
         Ok(template_id_bytes)
     }
+    
+    /// Execute `calls` in one transaction through the EIP-7702 delegated `account`.
+    ///
+    /// The batch is first simulated call by call so that under `RevertAll` a failing call is
+    /// reported with its index and reason instead of being sent. Under `ContinueOnFailure`
+    /// failed calls are returned in the results alongside the ones that went through.
+    pub async fn execute_batch(
+        &self,
+        account: Address,
+        calls: Vec<Call>,
+        policy: RevertPolicy,
+    ) -> Result<BatchResult, Error> {
+        if calls.is_empty() {
+            return Err(Error::InvalidParameter("Batch has no calls".to_string()));
+        }
+        
+        // Try mode runs every call, so the simulation shows each call's outcome
+        let preflight = match self.client.simulate_transaction(
+            account,
+            EXECUTE_SIGNATURE,
+            encode_batch(&calls, RevertPolicy::ContinueOnFailure)?,
+        ).await.map_err(Error::EthereumClient)? {
+            SimulationOutcome::Success(data) => decode_results(&data)?,
+            SimulationOutcome::Reverted(reason) => return Err(Error::ContractInteraction(
+                format!("Batch simulation for {:?} reverted: {}", account, reason)
+            )),
+        };
+        if policy == RevertPolicy::RevertAll {
+            if let Some(failure) = first_failure(&calls, &preflight) {
+                return Err(Error::BatchCallFailed(failure));
+            }
+        }
+        
+        let gas_estimate = self.client.estimate_gas(account, EXECUTE_SIGNATURE, encode_batch(&calls, policy)?)
+            .await.map_err(Error::EthereumClient)?;
+        
+        info!("Executing batch of {} calls through {:?} ({:?}, estimated gas {})", calls.len(), account, policy, gas_estimate);
+        let output = self.client.execute_smart_account(account, batch_payload(&calls, policy)?)
+            .await.map_err(Error::EthereumClient)?;
+        
+        // The account logs the per-call results; fall back to the simulation when it does not
+        let results = if output.is_empty() {
+            warn!("Batch through {:?} reported no results, using the simulated ones", account);
+            preflight
+        } else {
+            decode_results(&output)?
+        };
+        
+        Ok(BatchResult { policy, gas_estimate, results })
+    }
 }
 
 #[cfg(test)]
@@ -493,6 +681,49 @@ mod tests {
     use super::*;
     use ethers::signers::Signer;
     
+    fn calls() -> Vec<Call> {
+        vec![
+            Call { target: Address::repeat_byte(0x11), value: U256::ZERO, data: vec![0x09, 0x5e, 0xa7, 0xb3] },
+            Call { target: Address::repeat_byte(0x22), value: U256::from(5u64), data: vec![0xde, 0xad] },
+        ]
+    }
+    
+    #[test]
+    fn test_batch_payload_encoding() {
+        let payload = batch_payload(&calls(), RevertPolicy::ContinueOnFailure).unwrap();
+        let selector = &alloy_primitives::keccak256(EXECUTE_SIGNATURE.as_bytes())[..4];
+        assert_eq!(&payload[..4], selector);
+        
+        // Mode word: batch call type, try exec type
+        assert_eq!(payload[4], CALL_TYPE_BATCH);
+        assert_eq!(payload[5], 0x01);
+        assert!(payload[6..36].iter().all(|byte| *byte == 0));
+        
+        let revert_all = batch_payload(&calls(), RevertPolicy::RevertAll).unwrap();
+        assert_eq!(revert_all[5], 0x00);
+        assert_eq!(revert_all[36..], payload[36..]);
+    }
+    
+    #[test]
+    fn test_continue_on_failure_result_mapping() {
+        // Error("allowance") as a failing call would return it
+        let mut revert = vec![0x08, 0xc3, 0x79, 0xa0];
+        revert.extend_from_slice(&U256::from(32u64).to_be_bytes::<32>());
+        revert.extend_from_slice(&U256::from(9u64).to_be_bytes::<32>());
+        revert.extend_from_slice(&[b"allowance".as_slice(), &[0u8; 23]].concat());
+        
+        let results = map_results(vec![(true, vec![0x01]), (false, revert)]);
+        assert_eq!(results[0], CallResult { index: 0, success: true, return_data: vec![0x01], revert_reason: None });
+        assert!(!results[1].success);
+        assert_eq!(results[1].revert_reason.as_deref(), Some("allowance"));
+        
+        let batch = BatchResult { policy: RevertPolicy::ContinueOnFailure, gas_estimate: U256::from(90_000u64), results };
+        let failure = batch.first_failure(&calls()).unwrap();
+        assert_eq!(failure.index, 1);
+        assert_eq!(failure.target, Address::repeat_byte(0x22));
+        assert_eq!(failure.reason, "allowance");
+    }
+    
     // These tests are commented out as they require a running Ethereum node
     // with the appropriate contracts deployed.
     
@@ -59,11 +59,15 @@ pub enum OrderStatus {
 pub struct Order {
     pub order_id: u64,
     pub trader: Address,
+    #[serde(with = "quantera_types::wire::token_id")]
     pub token_id: [u8; 32],
     pub side: OrderSide,
     pub order_type: OrderType,
+    #[serde(with = "quantera_types::wire::u256_decimal")]
     pub price: U256,
+    #[serde(with = "quantera_types::wire::u256_decimal")]
     pub quantity: U256,
+    #[serde(with = "quantera_types::wire::u256_decimal")]
     pub filled_quantity: U256,
     pub creation_time: u64,
     pub expiration_time: u64,
@@ -77,8 +81,11 @@ pub struct Trade {
     pub trade_id: u64,
     pub buy_order_id: u64,
     pub sell_order_id: u64,
+    #[serde(with = "quantera_types::wire::token_id")]
     pub token_id: [u8; 32],
+    #[serde(with = "quantera_types::wire::u256_decimal")]
     pub price: U256,
+    #[serde(with = "quantera_types::wire::u256_decimal")]
     pub quantity: U256,
     pub buyer: Address,
     pub seller: Address,
@@ -89,7 +96,9 @@ pub struct Trade {
 /// Order book entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderBookEntry {
+    #[serde(with = "quantera_types::wire::u256_decimal")]
     pub price: U256,
+    #[serde(with = "quantera_types::wire::u256_decimal")]
     pub quantity: U256,
     pub order_count: u64,
 }
@@ -97,11 +106,54 @@ pub struct OrderBookEntry {
 /// Order book for a token
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderBook {
+    #[serde(with = "quantera_types::wire::token_id")]
     pub token_id: [u8; 32],
     pub bids: Vec<OrderBookEntry>,
     pub asks: Vec<OrderBookEntry>,
+    #[serde(with = "quantera_types::wire::u256_decimal")]
     pub last_trade_price: U256,
     pub last_update_time: u64,
+    /// Block the snapshot was read at
+    #[serde(default)]
+    pub block_number: u64,
+}
+
+/// How much of a previewed order the book could absorb
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PreviewLiquidity {
+    Full,
+    Partial,
+    NoLiquidity,
+}
+
+/// Expected execution of an order against an order book snapshot.
+///
+/// Previews are non-binding: the book may change before the order is submitted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderPreview {
+    #[serde(with = "quantera_types::wire::token_id")]
+    pub token_id: [u8; 32],
+    pub side: OrderSide,
+    #[serde(with = "quantera_types::wire::u256_decimal")]
+    pub requested_amount: U256,
+    #[serde(with = "quantera_types::wire::u256_decimal")]
+    pub filled_amount: U256,
+    /// Amount the book cannot fill at current depth
+    #[serde(with = "quantera_types::wire::u256_decimal")]
+    pub unfilled_amount: U256,
+    #[serde(with = "quantera_types::wire::option_u256_decimal")]
+    pub average_price: Option<U256>,
+    #[serde(with = "quantera_types::wire::option_u256_decimal")]
+    pub mid_price: Option<U256>,
+    /// Adverse difference between the average fill and mid price, in basis points
+    pub slippage_bps: Option<u64>,
+    pub levels_consumed: usize,
+    pub liquidity: PreviewLiquidity,
+    #[serde(with = "quantera_types::wire::option_u256_decimal")]
+    pub estimated_gas: Option<U256>,
+    pub block_number: u64,
+    pub binding: bool,
 }
 
 /// Client for interacting with the TradingModule contract
@@ -162,6 +214,42 @@ impl TradingClient {
         Ok(order_id)
     }
     
+    /// Address of the TradingModule contract
+    pub fn address(&self) -> Address {
+        self.contract_address
+    }
+    
+    /// Calldata for `placeOrder`, for callers that send it as part of a smart account batch
+    pub fn place_order_calldata(
+        token_id: [u8; 32],
+        side: OrderSide,
+        order_type: OrderType,
+        price: U256,
+        quantity: U256,
+        expiration_time: u64,
+    ) -> Result<Vec<u8>, Error> {
+        let side_value = match side {
+            OrderSide::Buy => 0u8,
+            OrderSide::Sell => 1u8,
+        };
+        let order_type_value = match order_type {
+            OrderType::Limit => 0u8,
+            OrderType::Market => 1u8,
+        };
+        
+        EthereumClient::encode_function_call(
+            "placeOrder(bytes32,uint8,uint8,uint256,uint256,uint256)",
+            vec![
+                token_id.into(),
+                side_value.into(),
+                order_type_value.into(),
+                price.into(),
+                quantity.into(),
+                U256::from(expiration_time).into(),
+            ],
+        ).map_err(Error::Encoding)
+    }
+    
     /// Cancel an order
     pub async fn cancel_order(
         &self,
@@ -354,6 +442,9 @@ impl TradingClient {
     ) -> Result<OrderBook, Error> {
         debug!("Getting order book for token: {:?}, depth: {}", token_id, depth);
         
+        // Pin the snapshot to a block so callers can tell how fresh it is
+        let block_number = self.client.get_block_number().await.map_err(Error::EthereumClient)?;
+        
         // Call the contract for bids
         let bids = self.client.call_contract::<Vec<(U256, U256, u64)>>(
             self.contract_address,
@@ -403,11 +494,62 @@ impl TradingClient {
             asks: ask_entries,
             last_trade_price: last_price,
             last_update_time: last_update,
+            block_number,
         };
         
         Ok(order_book)
     }
     
+    /// Preview an order's expected fill against the current order book
+    ///
+    /// Walks the opposite side of the book and estimates gas for submitting the order as a
+    /// market order. An empty book yields a `NoLiquidity` preview rather than an error.
+    pub async fn preview_order(
+        &self,
+        token_id: [u8; 32],
+        side: OrderSide,
+        amount: U256,
+        depth: u32,
+    ) -> Result<OrderPreview, Error> {
+        debug!("Previewing {:?} order for token: {:?}, amount: {}", side, token_id, amount);
+        
+        if amount.is_zero() {
+            return Err(Error::Order("Preview amount must be positive".to_string()));
+        }
+        
+        let book = self.get_order_book(token_id, depth).await?;
+        let mut preview = preview_fill(&book, side, amount);
+        
+        if preview.liquidity != PreviewLiquidity::NoLiquidity {
+            let side_value = match side {
+                OrderSide::Buy => 0u8,
+                OrderSide::Sell => 1u8,
+            };
+            
+            // Gas is informational only; a failed estimate does not fail the preview
+            preview.estimated_gas = match self.client.estimate_gas(
+                self.contract_address,
+                "placeOrder(bytes32,uint8,uint8,uint256,uint256,uint256)",
+                vec![
+                    token_id.into(),
+                    side_value.into(),
+                    1u8.into(), // Market
+                    preview.average_price.unwrap_or_default().into(),
+                    amount.into(),
+                    U256::ZERO.into(),
+                ],
+            ).await {
+                Ok(gas) => Some(gas),
+                Err(e) => {
+                    warn!("Gas estimate for order preview failed: {}", e);
+                    None
+                }
+            };
+        }
+        
+        Ok(preview)
+    }
+    
     /// Get orders by trader
     pub async fn get_orders_by_trader(
         &self,
@@ -580,4 +722,145 @@ impl TradingClient {
         
         Ok(signature)
     }
-} 
\ No newline at end of file
+} 
+/// Walk the book for an order of `amount` on `side`, without gas estimation
+pub fn preview_fill(book: &OrderBook, side: OrderSide, amount: U256) -> OrderPreview {
+    // Buys consume asks from the lowest price, sells consume bids from the highest
+    let mut levels: Vec<&OrderBookEntry> = match side {
+        OrderSide::Buy => book.asks.iter().collect(),
+        OrderSide::Sell => book.bids.iter().collect(),
+    };
+    levels.retain(|level| !level.quantity.is_zero());
+    match side {
+        OrderSide::Buy => levels.sort_by(|a, b| a.price.cmp(&b.price)),
+        OrderSide::Sell => levels.sort_by(|a, b| b.price.cmp(&a.price)),
+    }
+    
+    let mut remaining = amount;
+    let mut filled = U256::ZERO;
+    let mut notional = U256::ZERO;
+    let mut levels_consumed = 0;
+    
+    for level in levels {
+        if remaining.is_zero() {
+            break;
+        }
+        let take = remaining.min(level.quantity);
+        notional += level.price * take;
+        filled += take;
+        remaining -= take;
+        levels_consumed += 1;
+    }
+    
+    let best_bid = book.bids.iter().filter(|l| !l.quantity.is_zero()).map(|l| l.price).max();
+    let best_ask = book.asks.iter().filter(|l| !l.quantity.is_zero()).map(|l| l.price).min();
+    let mid_price = match (best_bid, best_ask) {
+        (Some(bid), Some(ask)) => Some((bid + ask) / U256::from(2)),
+        (bid, ask) => bid.or(ask),
+    };
+    
+    let average_price = if filled.is_zero() { None } else { Some(notional / filled) };
+    
+    let slippage_bps = match (average_price, mid_price) {
+        (Some(average), Some(mid)) if !mid.is_zero() => {
+            let adverse = match side {
+                OrderSide::Buy => average.saturating_sub(mid),
+                OrderSide::Sell => mid.saturating_sub(average),
+            };
+            u64::try_from(adverse * U256::from(10_000) / mid).ok()
+        }
+        _ => None,
+    };
+    
+    let liquidity = if filled.is_zero() {
+        PreviewLiquidity::NoLiquidity
+    } else if remaining.is_zero() {
+        PreviewLiquidity::Full
+    } else {
+        PreviewLiquidity::Partial
+    };
+    
+    OrderPreview {
+        token_id: book.token_id,
+        side,
+        requested_amount: amount,
+        filled_amount: filled,
+        unfilled_amount: remaining,
+        average_price,
+        mid_price,
+        slippage_bps,
+        levels_consumed,
+        liquidity,
+        estimated_gas: None,
+        block_number: book.block_number,
+        binding: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn level(price: u64, quantity: u64) -> OrderBookEntry {
+        OrderBookEntry {
+            price: U256::from(price),
+            quantity: U256::from(quantity),
+            order_count: 1,
+        }
+    }
+    
+    fn book(bids: Vec<OrderBookEntry>, asks: Vec<OrderBookEntry>) -> OrderBook {
+        OrderBook {
+            token_id: [7u8; 32],
+            bids,
+            asks,
+            last_trade_price: U256::from(100),
+            last_update_time: 0,
+            block_number: 1_234,
+        }
+    }
+    
+    #[test]
+    fn test_preview_walks_asks_for_buy() {
+        // Mid is (98 + 100) / 2 = 99
+        let book = book(vec![level(98, 500)], vec![level(102, 100), level(100, 100)]);
+        let preview = preview_fill(&book, OrderSide::Buy, U256::from(150));
+        
+        assert_eq!(preview.liquidity, PreviewLiquidity::Full);
+        assert_eq!(preview.filled_amount, U256::from(150));
+        assert!(preview.unfilled_amount.is_zero());
+        assert_eq!(preview.levels_consumed, 2);
+        // (100 * 100 + 102 * 50) / 150 = 100
+        assert_eq!(preview.average_price, Some(U256::from(100)));
+        assert_eq!(preview.mid_price, Some(U256::from(99)));
+        assert_eq!(preview.slippage_bps, Some(101));
+        assert_eq!(preview.block_number, 1_234);
+        assert!(!preview.binding);
+    }
+    
+    #[test]
+    fn test_preview_partial_fill_reports_residual() {
+        let book = book(vec![level(100, 60), level(95, 40)], vec![]);
+        let preview = preview_fill(&book, OrderSide::Sell, U256::from(250));
+        
+        assert_eq!(preview.liquidity, PreviewLiquidity::Partial);
+        assert_eq!(preview.filled_amount, U256::from(100));
+        assert_eq!(preview.unfilled_amount, U256::from(150));
+        // (100 * 60 + 95 * 40) / 100 = 98, mid falls back to the best bid
+        assert_eq!(preview.average_price, Some(U256::from(98)));
+        assert_eq!(preview.mid_price, Some(U256::from(100)));
+        assert_eq!(preview.slippage_bps, Some(200));
+    }
+    
+    #[test]
+    fn test_preview_empty_book_is_no_liquidity() {
+        let book = book(vec![level(99, 10)], vec![level(101, 0)]);
+        let preview = preview_fill(&book, OrderSide::Buy, U256::from(10));
+        
+        assert_eq!(preview.liquidity, PreviewLiquidity::NoLiquidity);
+        assert!(preview.filled_amount.is_zero());
+        assert_eq!(preview.unfilled_amount, U256::from(10));
+        assert_eq!(preview.average_price, None);
+        assert_eq!(preview.slippage_bps, None);
+    }
+}
@@ -73,6 +73,23 @@ pub struct YieldDistribution {
     pub distribution_end_time: u64,
 }
 
+/// Function signatures for privileged token writes
+pub const MINT_SIGNATURE: &str = "mint(address,uint256)";
+pub const BURN_SIGNATURE: &str = "burn(address,uint256)";
+pub const PAUSE_SIGNATURE: &str = "pause()";
+pub const UNPAUSE_SIGNATURE: &str = "unpause()";
+
+/// Map a failed privileged write, surfacing AccessControl reverts as Unauthorized
+fn map_write_error(error: EthError) -> Error {
+    let message = error.to_string();
+    if message.contains("AccessControl") || message.contains("missing role") {
+        warn!("Token write rejected by access control: {}", message);
+        Error::Unauthorized(message)
+    } else {
+        Error::EthereumClient(error)
+    }
+}
+
 /// Client for interacting with the TreasuryToken contract
 #[derive(Debug, Clone)]
 pub struct TreasuryTokenClient {
@@ -738,15 +755,55 @@ impl TreasuryTokenClient {
         Ok(pending_yield)
     }
     
+    /// Mint new tokens to an account
+    pub async fn mint(
+        &self,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), Error> {
+        info!("Minting {} tokens to {:?}", amount, to);
+        
+        self.client.send_transaction(
+            self.contract_address,
+            MINT_SIGNATURE,
+            vec![
+                to.into(),
+                amount.into(),
+            ],
+        ).await.map_err(map_write_error)?;
+        
+        Ok(())
+    }
+    
+    /// Burn tokens from an account
+    pub async fn burn(
+        &self,
+        from: Address,
+        amount: U256,
+    ) -> Result<(), Error> {
+        info!("Burning {} tokens from {:?}", amount, from);
+        
+        self.client.send_transaction(
+            self.contract_address,
+            BURN_SIGNATURE,
+            vec![
+                from.into(),
+                amount.into(),
+            ],
+        ).await.map_err(map_write_error)?;
+        
+        Ok(())
+    }
+    
     /// Pause all token transfers
     pub async fn pause(&self) -> Result<(), Error> {
         info!("Pausing token transfers");
         
         self.client.send_transaction(
             self.contract_address,
-            "pause()",
+            PAUSE_SIGNATURE,
             vec![],
-        ).await.map_err(Error::EthereumClient)?;
+        ).await.map_err(map_write_error)?;
         
         Ok(())
     }
@@ -757,9 +814,9 @@ impl TreasuryTokenClient {
         
         self.client.send_transaction(
             self.contract_address,
-            "unpause()",
+            UNPAUSE_SIGNATURE,
             vec![],
-        ).await.map_err(Error::EthereumClient)?;
+        ).await.map_err(map_write_error)?;
         
         Ok(())
     }
@@ -807,4 +864,52 @@ impl TreasuryTokenClient {
         
         Ok(module_address)
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_contract::Token;
+    
+    fn selector(signature: &str) -> [u8; 4] {
+        let hash = alloy_primitives::keccak256(signature.as_bytes());
+        [hash[0], hash[1], hash[2], hash[3]]
+    }
+    
+    #[test]
+    fn test_write_selectors() {
+        assert_eq!(selector(MINT_SIGNATURE), [0x40, 0xc1, 0x0f, 0x19]);
+        assert_eq!(selector(BURN_SIGNATURE), [0x9d, 0xc2, 0x9f, 0xac]);
+        assert_eq!(selector(PAUSE_SIGNATURE), [0x84, 0x56, 0xcb, 0x59]);
+        assert_eq!(selector(UNPAUSE_SIGNATURE), [0x3f, 0x4b, 0xa8, 0x3a]);
+    }
+    
+    #[test]
+    fn test_mint_calldata_encoding() {
+        let to = Address::repeat_byte(0xab);
+        let amount = U256::from(1_000_000u64);
+        
+        let calldata = EthereumClient::encode_function_call(
+            MINT_SIGNATURE,
+            vec![Token::from(to), Token::from(amount)],
+        ).unwrap();
+        
+        assert_eq!(calldata.len(), 4 + 32 + 32);
+        assert_eq!(&calldata[0..4], &selector(MINT_SIGNATURE));
+        // Address is left padded to 32 bytes
+        assert_eq!(&calldata[4..16], &[0u8; 12]);
+        assert_eq!(&calldata[16..36], to.as_slice());
+        assert_eq!(&calldata[36..68], &amount.to_be_bytes::<32>());
+    }
+    
+    #[test]
+    fn test_access_control_revert_maps_to_unauthorized() {
+        let error = map_write_error(EthError::TransactionError(
+            "execution reverted: AccessControl: account 0x01 is missing role 0x9f2d".to_string()
+        ));
+        assert!(matches!(error, Error::Unauthorized(_)));
+        
+        let error = map_write_error(EthError::TransactionError("nonce too low".to_string()));
+        assert!(matches!(error, Error::EthereumClient(_)));
+    }
+}
@@ -0,0 +1,331 @@
+// Contract addresses per deployment environment, checked against the chain at startup
+use crate::{Error, DEFAULT_MULTICALL_ADDRESS};
+use alloy_primitives::Address;
+use async_trait::async_trait;
+use ethereum_client::EthereumClient;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::Path;
+use tracing::{info, warn};
+
+/// Environment used when DEPLOYMENT_ENV is unset
+pub const DEFAULT_DEPLOYMENT_ENV: &str = "local";
+
+/// Contracts the treasury service talks to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContractName {
+    TreasuryRegistry,
+    Compliance,
+    Trading,
+    L2,
+    L2Bridge,
+    SmartAccount,
+    AssetFactory,
+    LiquidityPools,
+    YieldOptimizer,
+    Multicall,
+}
+
+impl ContractName {
+    pub const ALL: [ContractName; 10] = [
+        ContractName::TreasuryRegistry,
+        ContractName::Compliance,
+        ContractName::Trading,
+        ContractName::L2,
+        ContractName::L2Bridge,
+        ContractName::SmartAccount,
+        ContractName::AssetFactory,
+        ContractName::LiquidityPools,
+        ContractName::YieldOptimizer,
+        ContractName::Multicall,
+    ];
+
+    /// Environment variable that overrides the address from the registry file
+    pub fn env_var(&self) -> &'static str {
+        match self {
+            ContractName::TreasuryRegistry => "REGISTRY_ADDRESS",
+            ContractName::Compliance => "COMPLIANCE_ADDRESS",
+            ContractName::Trading => "TRADING_ADDRESS",
+            ContractName::L2 => "L2_ADDRESS",
+            ContractName::L2Bridge => "L2_BRIDGE_ADDRESS",
+            ContractName::SmartAccount => "SMART_ACCOUNT_ADDRESS",
+            ContractName::AssetFactory => "ASSET_FACTORY_ADDRESS",
+            ContractName::LiquidityPools => "LIQUIDITY_POOLS_ADDRESS",
+            ContractName::YieldOptimizer => "YIELD_OPTIMIZER_ADDRESS",
+            ContractName::Multicall => "MULTICALL_ADDRESS",
+        }
+    }
+
+    /// Argument-free view function that any deployment of the contract answers
+    pub fn sentinel(&self) -> Option<&'static str> {
+        match self {
+            ContractName::TreasuryRegistry => Some("getAllTreasuries()"),
+            ContractName::Compliance => Some("getAllRegulatoryRules()"),
+            ContractName::Trading => Some("getActiveOrders()"),
+            ContractName::L2 => Some("getAllL2Chains()"),
+            ContractName::L2Bridge => Some("getSupportedChains()"),
+            ContractName::SmartAccount => Some("getPublicTemplates()"),
+            ContractName::AssetFactory => Some("getAssetCount()"),
+            ContractName::LiquidityPools => None,
+            ContractName::YieldOptimizer => Some("getPublicStrategies()"),
+            ContractName::Multicall => Some("getBlockNumber()"),
+        }
+    }
+}
+
+impl fmt::Display for ContractName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = serde_json::to_value(self).ok()
+            .and_then(|value| value.as_str().map(str::to_string))
+            .unwrap_or_default();
+        write!(f, "{}", name)
+    }
+}
+
+/// One configured address that failed validation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InvalidContract {
+    pub contract: ContractName,
+    pub address: Option<Address>,
+    pub reason: String,
+}
+
+/// Every configured address that failed validation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContractValidationError {
+    pub environment: String,
+    pub invalid: Vec<InvalidContract>,
+}
+
+impl ContractValidationError {
+    /// Whether `contract` is among the invalid addresses
+    pub fn has_contract(&self, contract: ContractName) -> bool {
+        self.invalid.iter().any(|invalid| invalid.contract == contract)
+    }
+}
+
+impl fmt::Display for ContractValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let invalid: Vec<String> = self.invalid.iter()
+            .map(|invalid| match invalid.address {
+                Some(address) => format!("{} ({}): {}", invalid.contract, address, invalid.reason),
+                None => format!("{}: {}", invalid.contract, invalid.reason),
+            })
+            .collect();
+        write!(f, "{} environment: {}", self.environment, invalid.join("; "))
+    }
+}
+
+impl std::error::Error for ContractValidationError {}
+
+/// On-chain checks used to validate configured addresses
+#[async_trait]
+pub trait ContractProbe: Send + Sync {
+    /// Deployed bytecode at `address`; empty when nothing is deployed there
+    async fn code(&self, address: Address) -> Result<Vec<u8>, Error>;
+
+    /// Call an argument-free view function, succeeding if the contract answers
+    async fn call_view(&self, address: Address, function: &str) -> Result<(), Error>;
+}
+
+#[async_trait]
+impl ContractProbe for EthereumClient {
+    async fn code(&self, address: Address) -> Result<Vec<u8>, Error> {
+        Ok(self.get_code(address).await?)
+    }
+
+    async fn call_view(&self, address: Address, function: &str) -> Result<(), Error> {
+        self.call_raw(address, function, vec![]).await?;
+        Ok(())
+    }
+}
+
+/// Contract addresses for one deployment environment.
+///
+/// Addresses come from the CONTRACT_ADDRESSES_PATH file, a JSON object keyed by
+/// environment name, and can be overridden one at a time with each contract's
+/// environment variable.
+#[derive(Debug, Clone)]
+pub struct ContractAddressRegistry {
+    environment: String,
+    addresses: BTreeMap<ContractName, Address>,
+}
+
+impl ContractAddressRegistry {
+    pub fn new(environment: impl Into<String>, addresses: BTreeMap<ContractName, Address>) -> Self {
+        Self { environment: environment.into(), addresses }
+    }
+
+    /// Load the DEPLOYMENT_ENV section of CONTRACT_ADDRESSES_PATH, then apply per-contract overrides
+    pub fn from_env() -> Result<Self, Error> {
+        let environment = std::env::var("DEPLOYMENT_ENV")
+            .unwrap_or_else(|_| DEFAULT_DEPLOYMENT_ENV.to_string());
+
+        let mut registry = match std::env::var("CONTRACT_ADDRESSES_PATH") {
+            Ok(path) => Self::from_file(path, &environment)?,
+            Err(_) => Self::new(environment, BTreeMap::new()),
+        };
+
+        for contract in ContractName::ALL {
+            if let Ok(address) = std::env::var(contract.env_var()) {
+                registry.addresses.insert(contract, parse_address(contract.env_var(), &address)?);
+            }
+        }
+
+        registry.addresses.entry(ContractName::Multicall)
+            .or_insert_with(|| DEFAULT_MULTICALL_ADDRESS.parse().expect("valid multicall address"));
+
+        Ok(registry)
+    }
+
+    /// Load one environment's addresses from a registry file
+    pub fn from_file(path: impl AsRef<Path>, environment: &str) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| Error::InvalidParameter(format!("Cannot read contract registry {}: {}", path.display(), e)))?;
+        Self::from_json(&contents, environment)
+    }
+
+    pub fn from_json(json: &str, environment: &str) -> Result<Self, Error> {
+        let mut environments: HashMap<String, BTreeMap<ContractName, String>> = serde_json::from_str(json)
+            .map_err(|e| Error::InvalidParameter(format!("Invalid contract registry: {}", e)))?;
+        let entries = environments.remove(environment)
+            .ok_or_else(|| Error::NotFound(format!("No contract addresses for environment {}", environment)))?;
+
+        let addresses = entries.into_iter()
+            .map(|(contract, address)| Ok((contract, parse_address(&contract.to_string(), &address)?)))
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Self::new(environment, addresses))
+    }
+
+    pub fn environment(&self) -> &str {
+        &self.environment
+    }
+
+    pub fn get(&self, contract: ContractName) -> Result<Address, Error> {
+        self.addresses.get(&contract).copied()
+            .ok_or_else(|| Error::NotFound(format!(
+                "No {} address for environment {} (set {})",
+                contract, self.environment, contract.env_var()
+            )))
+    }
+
+    /// Check that every contract in `required` is configured, has code and, where it
+    /// has a sentinel, answers it. Reports all invalid addresses at once.
+    pub async fn validate(&self, probe: &dyn ContractProbe, required: &[ContractName]) -> Result<(), Error> {
+        let mut invalid = Vec::new();
+
+        for &contract in required {
+            let address = match self.addresses.get(&contract) {
+                Some(address) if *address != Address::ZERO => *address,
+                Some(_) => {
+                    invalid.push(InvalidContract { contract, address: Some(Address::ZERO), reason: "zero address".into() });
+                    continue;
+                }
+                None => {
+                    invalid.push(InvalidContract {
+                        contract,
+                        address: None,
+                        reason: format!("not configured (set {})", contract.env_var()),
+                    });
+                    continue;
+                }
+            };
+
+            let reason = match probe.code(address).await {
+                Ok(code) if code.is_empty() => Some("no contract code at address".to_string()),
+                Ok(_) => match contract.sentinel() {
+                    Some(sentinel) => probe.call_view(address, sentinel).await.err()
+                        .map(|e| format!("{} did not respond: {}", sentinel, e)),
+                    None => None,
+                },
+                Err(e) => Some(format!("code lookup failed: {}", e)),
+            };
+
+            match reason {
+                Some(reason) => invalid.push(InvalidContract { contract, address: Some(address), reason }),
+                None => info!("{} contract at {} validated", contract, address),
+            }
+        }
+
+        if invalid.is_empty() {
+            return Ok(());
+        }
+
+        for contract in &invalid {
+            warn!("Invalid {} contract address {:?}: {}", contract.contract, contract.address, contract.reason);
+        }
+        Err(Error::InvalidContracts(ContractValidationError {
+            environment: self.environment.clone(),
+            invalid,
+        }))
+    }
+}
+
+fn parse_address(field: &str, address: &str) -> Result<Address, Error> {
+    Address::parse_checksummed(address.trim(), None)
+        .map_err(|e| Error::InvalidParameter(format!("Invalid {} {}: {}", field, address, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// Chain with code at a fixed set of addresses, all of which answer their sentinels
+    struct FakeChain {
+        deployed: HashSet<Address>,
+    }
+
+    #[async_trait]
+    impl ContractProbe for FakeChain {
+        async fn code(&self, address: Address) -> Result<Vec<u8>, Error> {
+            Ok(if self.deployed.contains(&address) { vec![0x60, 0x80] } else { Vec::new() })
+        }
+
+        async fn call_view(&self, _address: Address, _function: &str) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_startup_validation_reports_bad_address() {
+        let registry = ContractAddressRegistry::from_json(r#"{
+            "sepolia": {
+                "treasury_registry": "0x1111111111111111111111111111111111111111",
+                "liquidity_pools": "0x2222222222222222222222222222222222222222",
+                "yield_optimizer": "0x3333333333333333333333333333333333333333"
+            }
+        }"#, "sepolia").unwrap();
+        let chain = FakeChain {
+            deployed: [Address::repeat_byte(0x11), Address::repeat_byte(0x33)].into_iter().collect(),
+        };
+
+        let required = [ContractName::TreasuryRegistry, ContractName::LiquidityPools, ContractName::YieldOptimizer];
+        match registry.validate(&chain, &required).await {
+            Err(Error::InvalidContracts(error)) => {
+                assert_eq!(error.invalid.len(), 1);
+                assert!(error.has_contract(ContractName::LiquidityPools));
+                assert_eq!(error.invalid[0].address, Some(Address::repeat_byte(0x22)));
+            }
+            other => panic!("expected invalid contracts, got {:?}", other),
+        }
+
+        assert!(registry.validate(&chain, &[ContractName::TreasuryRegistry, ContractName::YieldOptimizer]).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_unconfigured_contracts_are_all_reported() {
+        let registry = ContractAddressRegistry::new("local", BTreeMap::new());
+        let chain = FakeChain { deployed: HashSet::new() };
+
+        match registry.validate(&chain, &ContractName::ALL).await {
+            Err(Error::InvalidContracts(error)) => assert_eq!(error.invalid.len(), ContractName::ALL.len()),
+            other => panic!("expected invalid contracts, got {:?}", other),
+        }
+        assert!(matches!(registry.get(ContractName::Trading), Err(Error::NotFound(_))));
+    }
+}
@@ -0,0 +1,203 @@
+use alloy_primitives::{Address, U256};
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use uuid::Uuid;
+use crate::{Currency, Error, TreasuryType};
+
+/// Steps of treasury creation, in the order they run
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CreationStep {
+    UploadMetadata,
+    DeployToken,
+    RegisterTreasury,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CreationAttemptStatus {
+    InProgress,
+    /// Stopped at `failed_step` and rolled back; can be resumed by id
+    Failed,
+    Completed,
+}
+
+/// Inputs of a creation attempt, kept so a failed attempt can be resumed by id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreasuryCreationParams {
+    pub name: String,
+    pub symbol: String,
+    pub total_supply: u64,
+    pub treasury_type: TreasuryType,
+    pub face_value: U256,
+    /// Attempts recorded before currencies were tracked are in US dollars
+    #[serde(default)]
+    pub currency: Currency,
+    pub yield_rate: u64,
+    pub issuance_date: u64,
+    pub maturity_date: u64,
+    pub issuer: Address,
+    pub tranche: Option<String>,
+}
+
+/// One treasury creation and the steps it has completed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreationAttempt {
+    pub attempt_id: Uuid,
+    pub params: TreasuryCreationParams,
+    pub status: CreationAttemptStatus,
+    pub completed_steps: Vec<CreationStep>,
+    pub failed_step: Option<CreationStep>,
+    pub error: Option<String>,
+    pub metadata_uri: Option<String>,
+    /// False once the metadata has been unpinned by a rollback
+    pub metadata_pinned: bool,
+    /// Kept across failures so a resume registers this contract instead of redeploying
+    pub token_address: Option<Address>,
+    /// Deployed but unregistered, pending cleanup or resume
+    pub token_orphaned: bool,
+    pub token_id: Option<[u8; 32]>,
+    pub resume_count: u32,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+impl CreationAttempt {
+    pub fn has_completed(&self, step: CreationStep) -> bool {
+        self.completed_steps.contains(&step)
+    }
+}
+
+/// Record of treasury creation attempts, the service's creation_attempts table.
+///
+/// Steps are recorded as they complete so a failure can be compensated precisely and a
+/// resume skips the work already done. Only one run of an attempt can be in flight.
+#[derive(Debug, Default)]
+pub struct CreationAttemptLedger {
+    attempts: Mutex<HashMap<Uuid, CreationAttempt>>,
+}
+
+impl CreationAttemptLedger {
+    /// Open a new attempt
+    pub fn start(&self, params: TreasuryCreationParams) -> Result<CreationAttempt, Error> {
+        let now = chrono::Utc::now().timestamp() as u64;
+        let attempt = CreationAttempt {
+            attempt_id: Uuid::new_v4(),
+            params,
+            status: CreationAttemptStatus::InProgress,
+            completed_steps: Vec::new(),
+            failed_step: None,
+            error: None,
+            metadata_uri: None,
+            metadata_pinned: false,
+            token_address: None,
+            token_orphaned: false,
+            token_id: None,
+            resume_count: 0,
+            created_at: now,
+            updated_at: now,
+        };
+
+        self.lock()?.insert(attempt.attempt_id, attempt.clone());
+        Ok(attempt)
+    }
+
+    /// Claim a failed attempt for resuming, rejecting attempts that are running or completed
+    pub fn begin_resume(&self, attempt_id: Uuid) -> Result<CreationAttempt, Error> {
+        self.update(attempt_id, |attempt| {
+            if attempt.status != CreationAttemptStatus::Failed {
+                return Err(Error::InvalidState(format!(
+                    "Creation attempt {} is {:?}, only failed attempts can be resumed", attempt_id, attempt.status
+                )));
+            }
+            attempt.status = CreationAttemptStatus::InProgress;
+            attempt.failed_step = None;
+            attempt.error = None;
+            attempt.resume_count += 1;
+            Ok(())
+        })
+    }
+
+    /// Apply a change to an attempt and return the updated record
+    pub fn update<F>(&self, attempt_id: Uuid, change: F) -> Result<CreationAttempt, Error>
+    where
+        F: FnOnce(&mut CreationAttempt) -> Result<(), Error>,
+    {
+        let mut attempts = self.lock()?;
+        let attempt = attempts.get_mut(&attempt_id)
+            .ok_or_else(|| Error::NotFound(format!("Creation attempt {}", attempt_id)))?;
+        change(attempt)?;
+        attempt.updated_at = chrono::Utc::now().timestamp() as u64;
+        Ok(attempt.clone())
+    }
+
+    /// Mark a step as completed
+    pub fn complete_step(&self, attempt_id: Uuid, step: CreationStep) -> Result<CreationAttempt, Error> {
+        self.update(attempt_id, |attempt| {
+            if !attempt.completed_steps.contains(&step) {
+                attempt.completed_steps.push(step);
+            }
+            Ok(())
+        })
+    }
+
+    pub fn get(&self, attempt_id: &Uuid) -> Option<CreationAttempt> {
+        self.attempts.lock().ok().and_then(|attempts| attempts.get(attempt_id).cloned())
+    }
+
+    /// Deployed contracts left unregistered by failed attempts, for cleanup
+    pub fn orphaned_tokens(&self) -> Vec<CreationAttempt> {
+        self.attempts.lock()
+            .map(|attempts| attempts.values().filter(|a| a.token_orphaned).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, HashMap<Uuid, CreationAttempt>>, Error> {
+        self.attempts.lock()
+            .map_err(|_| Error::Internal("Creation attempt ledger lock poisoned".into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> TreasuryCreationParams {
+        TreasuryCreationParams {
+            name: "Test Treasury".to_string(),
+            symbol: "TST".to_string(),
+            total_supply: 1000,
+            treasury_type: TreasuryType::TBill,
+            face_value: U256::from(1000),
+            currency: Currency::USD,
+            yield_rate: 100,
+            issuance_date: 1,
+            maturity_date: 2,
+            issuer: Address::from_slice(&[0x11; 20]),
+            tranche: None,
+        }
+    }
+
+    #[test]
+    fn test_only_failed_attempts_resume() {
+        let ledger = CreationAttemptLedger::default();
+        let attempt = ledger.start(params()).unwrap();
+
+        // Still running
+        assert!(matches!(ledger.begin_resume(attempt.attempt_id), Err(Error::InvalidState(_))));
+
+        ledger.update(attempt.attempt_id, |a| {
+            a.status = CreationAttemptStatus::Failed;
+            a.failed_step = Some(CreationStep::RegisterTreasury);
+            Ok(())
+        }).unwrap();
+
+        let resumed = ledger.begin_resume(attempt.attempt_id).unwrap();
+        assert_eq!(resumed.status, CreationAttemptStatus::InProgress);
+        assert_eq!(resumed.failed_step, None);
+        assert_eq!(resumed.resume_count, 1);
+
+        // A second concurrent resume is rejected
+        assert!(matches!(ledger.begin_resume(attempt.attempt_id), Err(Error::InvalidState(_))));
+        assert!(matches!(ledger.begin_resume(Uuid::new_v4()), Err(Error::NotFound(_))));
+    }
+}
@@ -0,0 +1,667 @@
+// Subscription and redemption windows, redemption notice and lock-ups of tokenized funds
+//
+// Assets with dealing terms (private equity, infrastructure) only deal while a window is
+// open. Buy orders are subscriptions and sell orders redemptions; off-book transfers go
+// through the transfer pre-check. Redemptions also need notice given ahead of the window,
+// and may only sell units whose lock-up, counted from their acquisition, has run out.
+// Units received by transfer keep the lock-up they carried at the sender.
+use alloy_primitives::{Address, U256};
+use chrono::{DateTime, Duration, Months, Utc};
+use serde::{Serialize, Deserialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::sync::RwLock;
+use tracing::info;
+use uuid::Uuid;
+use crate::withholding::read_json;
+use crate::Error;
+
+/// Recurring windows are searched this far ahead, a century of monthly windows
+const MAX_RECURRENCES: u32 = 1_200;
+
+/// A period during which an asset accepts subscriptions and redemptions
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DealingWindow {
+    pub opens_at: DateTime<Utc>,
+    /// Exclusive
+    pub closes_at: DateTime<Utc>,
+}
+
+impl DealingWindow {
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        self.opens_at <= at && at < self.closes_at
+    }
+}
+
+/// Windows repeating every `every_months` from `first_opens_at`, each open for `open_days`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RecurringWindows {
+    pub first_opens_at: DateTime<Utc>,
+    pub every_months: u32,
+    pub open_days: u32,
+}
+
+impl RecurringWindows {
+    fn nth(&self, n: u32) -> Option<DealingWindow> {
+        let opens_at = self.first_opens_at.checked_add_months(Months::new(n.checked_mul(self.every_months)?))?;
+        Some(DealingWindow { opens_at, closes_at: opens_at + Duration::days(self.open_days.into()) })
+    }
+
+    fn first_where(&self, matches: impl Fn(&DealingWindow) -> bool) -> Option<DealingWindow> {
+        (0..MAX_RECURRENCES).map_while(|n| self.nth(n)).find(|window| matches(window))
+    }
+}
+
+/// Dealing windows, notice period and lock-up of one asset
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DealingTerms {
+    #[serde(with = "quantera_types::wire::token_id")]
+    pub treasury_id: [u8; 32],
+    #[serde(default)]
+    pub recurring: Option<RecurringWindows>,
+    /// One-off windows, in addition to any recurring ones
+    #[serde(default)]
+    pub windows: Vec<DealingWindow>,
+    /// Days between giving redemption notice and the opening of the window it is for
+    #[serde(default)]
+    pub notice_days: u32,
+    /// Days after acquisition before units can be redeemed
+    #[serde(default)]
+    pub lockup_days: u32,
+}
+
+impl DealingTerms {
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.recurring.is_none() && self.windows.is_empty() {
+            return Err(Error::InvalidParameter("Dealing terms need recurring or ad-hoc windows".into()));
+        }
+        if let Some(recurring) = &self.recurring {
+            if recurring.every_months == 0 || recurring.open_days == 0 {
+                return Err(Error::InvalidParameter("Recurring windows need a positive interval and length".into()));
+            }
+            // The shortest month sets how long a window can stay open before the next one
+            if recurring.open_days > recurring.every_months.saturating_mul(28) {
+                return Err(Error::InvalidParameter(format!(
+                    "Windows open for {} days overlap when repeating every {} months", recurring.open_days, recurring.every_months
+                )));
+            }
+        }
+        if let Some(window) = self.windows.iter().find(|window| window.opens_at >= window.closes_at) {
+            return Err(Error::InvalidParameter(format!("Window opening {} does not close after it opens", window.opens_at)));
+        }
+        Ok(())
+    }
+
+    /// Earliest-opening window, recurring or ad-hoc, that matches
+    fn window_where(&self, matches: impl Fn(&DealingWindow) -> bool) -> Option<DealingWindow> {
+        let recurring = self.recurring.as_ref().and_then(|recurring| recurring.first_where(&matches));
+        self.windows.iter()
+            .copied()
+            .filter(|window| matches(window))
+            .chain(recurring)
+            .min_by_key(|window| window.opens_at)
+    }
+
+    /// The window open at `at`, if any
+    pub fn open_window(&self, at: DateTime<Utc>) -> Option<DealingWindow> {
+        self.window_where(|window| window.contains(at))
+    }
+
+    /// The window open at `at`, or else the next one to open
+    pub fn next_window(&self, at: DateTime<Utc>) -> Option<DealingWindow> {
+        self.window_where(|window| window.closes_at > at)
+    }
+
+    /// First window that redemption notice given at `given_at` reaches
+    pub fn notice_window(&self, given_at: DateTime<Utc>) -> Option<DealingWindow> {
+        if self.notice_days == 0 {
+            return self.next_window(given_at);
+        }
+        let earliest = given_at + Duration::days(self.notice_days.into());
+        self.window_where(|window| window.opens_at >= earliest)
+    }
+}
+
+/// Redemption notice for one window, fixed when given so later changes to the terms
+/// cannot shorten it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedemptionNotice {
+    pub notice_id: Uuid,
+    #[serde(with = "quantera_types::wire::token_id")]
+    pub treasury_id: [u8; 32],
+    pub investor: Address,
+    #[serde(with = "quantera_types::wire::u256_decimal")]
+    pub amount: U256,
+    /// Not yet used by redemption orders
+    #[serde(with = "quantera_types::wire::u256_decimal")]
+    pub remaining: U256,
+    pub given_at: DateTime<Utc>,
+    pub window: DealingWindow,
+}
+
+/// How units were acquired
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AcquisitionSource {
+    /// Issued to the investor; the full lock-up runs from acquisition
+    Subscription,
+    /// Received from another holder along with the lock-up left on the units
+    SecondaryTransfer { from: Address },
+}
+
+/// Units of one acquisition still in lock-up
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockupLot {
+    #[serde(with = "quantera_types::wire::u256_decimal")]
+    pub amount: U256,
+    pub acquired_at: DateTime<Utc>,
+    pub lockup_expires_at: DateTime<Utc>,
+    pub source: AcquisitionSource,
+}
+
+/// An investor's lock-ups, notices and next window in one asset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockupStatus {
+    #[serde(with = "quantera_types::wire::token_id")]
+    pub treasury_id: [u8; 32],
+    pub investor: Address,
+    #[serde(with = "quantera_types::wire::u256_decimal")]
+    pub locked: U256,
+    /// Locked lots, soonest expiring first
+    pub lots: Vec<LockupLot>,
+    /// Notices for windows that have not closed
+    pub notices: Vec<RedemptionNotice>,
+    pub next_window: Option<DealingWindow>,
+    /// Absent once the asset has no dealing terms
+    pub notice_days: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DealingRejectionReason {
+    OutsideWindow,
+    /// A window is open, but no notice was given for it
+    NoticeRequired,
+    LockedUp,
+}
+
+/// Why an order or transfer was refused under an asset's dealing terms, and when it can
+/// be made
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DealingRejection {
+    #[serde(with = "quantera_types::wire::token_id")]
+    pub treasury_id: [u8; 32],
+    pub reason: DealingRejectionReason,
+    /// Next window the attempt can be made in; for redemptions needing notice, the first
+    /// window notice given now reaches
+    pub next_window: Option<DealingWindow>,
+    /// When enough units come out of lock-up
+    pub unlocks_at: Option<DateTime<Utc>>,
+}
+
+impl fmt::Display for DealingRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self.reason {
+            DealingRejectionReason::OutsideWindow => "outside its dealing window",
+            DealingRejectionReason::NoticeRequired => "without redemption notice for this window",
+            DealingRejectionReason::LockedUp => "with units still locked up",
+        };
+        write!(f, "{} {}", quantera_types::wire::format_token_id(&self.treasury_id), reason)?;
+        if let Some(unlocks_at) = self.unlocks_at {
+            write!(f, "; units unlock {}", unlocks_at)?;
+        }
+        match self.next_window {
+            Some(window) => write!(f, "; next window opens {}", window.opens_at),
+            None => write!(f, "; no further window is scheduled"),
+        }
+    }
+}
+
+/// Dealing terms per asset, redemption notices and per-investor lock-ups.
+///
+/// Assets without terms deal at any time.
+pub struct DealingWindows {
+    terms: RwLock<HashMap<[u8; 32], DealingTerms>>,
+    notices: RwLock<Vec<RedemptionNotice>>,
+    lots: RwLock<HashMap<([u8; 32], Address), Vec<LockupLot>>>,
+}
+
+impl Default for DealingWindows {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DealingWindows {
+    pub fn new() -> Self {
+        Self {
+            terms: RwLock::new(HashMap::new()),
+            notices: RwLock::new(Vec::new()),
+            lots: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Terms from the JSON array at `DEALING_TERMS_PATH`, standing configuration
+    pub fn from_env() -> Result<Self, Error> {
+        let windows = Self::new();
+        if let Ok(path) = std::env::var("DEALING_TERMS_PATH") {
+            let terms: Vec<DealingTerms> = read_json(&path, "DEALING_TERMS_PATH")?;
+            for terms in terms {
+                windows.set_terms(terms, "configuration")
+                    .map_err(|e| Error::InvalidParameter(format!("Invalid dealing terms {}: {}", path, e)))?;
+            }
+        }
+        Ok(windows)
+    }
+
+    /// Add or replace an asset's terms. Notice already given keeps the window it was for.
+    pub fn set_terms(&self, terms: DealingTerms, set_by: &str) -> Result<DealingTerms, Error> {
+        terms.validate()?;
+        self.terms.write().map_err(|_| Error::Internal("Dealing terms lock poisoned".into()))?
+            .insert(terms.treasury_id, terms.clone());
+        info!(
+            "[AUDIT] Dealing terms of {} set by {}: recurring {:?}, {} ad-hoc windows, {} days notice, {} days lock-up",
+            quantera_types::wire::format_token_id(&terms.treasury_id), set_by, terms.recurring,
+            terms.windows.len(), terms.notice_days, terms.lockup_days
+        );
+        Ok(terms)
+    }
+
+    /// Let an asset deal at any time again
+    pub fn remove_terms(&self, treasury_id: [u8; 32], removed_by: &str) -> Result<DealingTerms, Error> {
+        let terms = self.terms.write().map_err(|_| Error::Internal("Dealing terms lock poisoned".into()))?
+            .remove(&treasury_id)
+            .ok_or_else(|| Error::NotFound(format!("Dealing terms of {}", quantera_types::wire::format_token_id(&treasury_id))))?;
+        info!("[AUDIT] Dealing terms of {} removed by {}", quantera_types::wire::format_token_id(&treasury_id), removed_by);
+        Ok(terms)
+    }
+
+    pub fn terms(&self, treasury_id: [u8; 32]) -> Option<DealingTerms> {
+        self.terms.read().ok().and_then(|terms| terms.get(&treasury_id).cloned())
+    }
+
+    /// Terms ordered by treasury id
+    pub fn all_terms(&self) -> Vec<DealingTerms> {
+        let mut terms: Vec<DealingTerms> = self.terms.read().map(|terms| terms.values().cloned().collect()).unwrap_or_default();
+        terms.sort_by_key(|terms| terms.treasury_id);
+        terms
+    }
+
+    /// Buy orders need an open window
+    pub fn check_subscription(&self, treasury_id: [u8; 32], now: DateTime<Utc>) -> Result<(), Error> {
+        self.require_open_window(treasury_id, now)
+    }
+
+    /// Transfers need an open window; the recipient takes over any lock-up on the units
+    pub fn check_transfer(&self, treasury_id: [u8; 32], now: DateTime<Utc>) -> Result<(), Error> {
+        self.require_open_window(treasury_id, now)
+    }
+
+    /// Sell orders may only sell units out of lock-up, and need an open window or, with a
+    /// notice period, notice covering the amount for the window open now. The notice used
+    /// is consumed.
+    ///
+    /// `balance` is the investor's holding, which includes units acquired before any lock-up
+    /// was tracked.
+    pub fn check_redemption(
+        &self,
+        investor: Address,
+        treasury_id: [u8; 32],
+        amount: U256,
+        balance: U256,
+        now: DateTime<Utc>,
+    ) -> Result<(), Error> {
+        let Some(terms) = self.terms(treasury_id) else {
+            return Ok(());
+        };
+
+        let mut locked_lots = self.locked_lots(treasury_id, investor, now);
+        let locked = locked_lots.iter().fold(U256::ZERO, |total, lot| total.saturating_add(lot.amount));
+        let mut free = balance.saturating_sub(locked);
+        if amount > free {
+            locked_lots.sort_by_key(|lot| lot.lockup_expires_at);
+            let unlocks_at = locked_lots.iter()
+                .find(|lot| {
+                    free = free.saturating_add(lot.amount);
+                    free >= amount
+                })
+                .map(|lot| lot.lockup_expires_at);
+            return Err(Error::DealingRejected(DealingRejection {
+                treasury_id,
+                reason: DealingRejectionReason::LockedUp,
+                next_window: terms.next_window(unlocks_at.unwrap_or(now)),
+                unlocks_at,
+            }));
+        }
+
+        if terms.notice_days == 0 {
+            return self.require_open_window(treasury_id, now);
+        }
+
+        let mut notices = self.notices.write().map_err(|_| Error::Internal("Redemption notices lock poisoned".into()))?;
+        let mut covering: Vec<&mut RedemptionNotice> = notices.iter_mut()
+            .filter(|notice| notice.treasury_id == treasury_id && notice.investor == investor)
+            .filter(|notice| notice.window.contains(now) && notice.remaining > U256::ZERO)
+            .collect();
+        let noticed = covering.iter().fold(U256::ZERO, |total, notice| total.saturating_add(notice.remaining));
+        if noticed < amount {
+            let in_window = !covering.is_empty() || terms.open_window(now).is_some();
+            // A notice already given for a later window is sooner than one given now
+            let pending = notices.iter()
+                .filter(|notice| notice.treasury_id == treasury_id && notice.investor == investor)
+                .filter(|notice| notice.window.opens_at > now && notice.remaining > U256::ZERO)
+                .map(|notice| notice.window);
+            let next_window = pending.chain(terms.notice_window(now)).min_by_key(|window| window.opens_at);
+            return Err(Error::DealingRejected(DealingRejection {
+                treasury_id,
+                reason: if in_window { DealingRejectionReason::NoticeRequired } else { DealingRejectionReason::OutsideWindow },
+                next_window,
+                unlocks_at: None,
+            }));
+        }
+
+        covering.sort_by_key(|notice| notice.given_at);
+        let mut outstanding = amount;
+        for notice in covering {
+            let used = notice.remaining.min(outstanding);
+            notice.remaining -= used;
+            outstanding -= used;
+            if outstanding == U256::ZERO {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Give notice to redeem `amount` in the first window the notice period reaches
+    pub fn give_notice(
+        &self,
+        investor: Address,
+        treasury_id: [u8; 32],
+        amount: U256,
+        now: DateTime<Utc>,
+    ) -> Result<RedemptionNotice, Error> {
+        if amount == U256::ZERO {
+            return Err(Error::InvalidParameter("Notice amount must be positive".into()));
+        }
+        let terms = self.terms(treasury_id)
+            .ok_or_else(|| Error::NotFound(format!("Dealing terms of {}", quantera_types::wire::format_token_id(&treasury_id))))?;
+        let window = terms.notice_window(now)
+            .ok_or_else(|| Error::InvalidState(format!("No window is scheduled {} days or more ahead", terms.notice_days)))?;
+
+        let notice = RedemptionNotice {
+            notice_id: Uuid::new_v4(),
+            treasury_id,
+            investor,
+            amount,
+            remaining: amount,
+            given_at: now,
+            window,
+        };
+        self.notices.write().map_err(|_| Error::Internal("Redemption notices lock poisoned".into()))?
+            .push(notice.clone());
+        info!(
+            "{:?} gave notice to redeem {} of {} in the window opening {}",
+            investor, amount, quantera_types::wire::format_token_id(&treasury_id), window.opens_at
+        );
+        Ok(notice)
+    }
+
+    /// Start the asset's lock-up on units issued to an investor
+    pub fn record_subscription(
+        &self,
+        investor: Address,
+        treasury_id: [u8; 32],
+        amount: U256,
+        acquired_at: DateTime<Utc>,
+    ) -> Result<(), Error> {
+        let lockup_days = self.terms(treasury_id).map_or(0, |terms| terms.lockup_days);
+        if lockup_days == 0 || amount == U256::ZERO {
+            return Ok(());
+        }
+        self.lots.write().map_err(|_| Error::Internal("Lock-up lots lock poisoned".into()))?
+            .entry((treasury_id, investor))
+            .or_default()
+            .push(LockupLot {
+                amount,
+                acquired_at,
+                lockup_expires_at: acquired_at + Duration::days(lockup_days.into()),
+                source: AcquisitionSource::Subscription,
+            });
+        Ok(())
+    }
+
+    /// Move units between holders. Locked units move first, soonest expiring first, and
+    /// keep their expiry, so neither holder can shed a lock-up by transferring.
+    pub fn record_transfer(
+        &self,
+        from: Address,
+        to: Address,
+        treasury_id: [u8; 32],
+        amount: U256,
+        transferred_at: DateTime<Utc>,
+    ) -> Result<(), Error> {
+        let mut lots = self.lots.write().map_err(|_| Error::Internal("Lock-up lots lock poisoned".into()))?;
+        let mut sender_lots: Vec<LockupLot> = lots.remove(&(treasury_id, from)).unwrap_or_default()
+            .into_iter()
+            .filter(|lot| lot.lockup_expires_at > transferred_at)
+            .collect();
+        sender_lots.sort_by_key(|lot| lot.lockup_expires_at);
+
+        let mut outstanding = amount;
+        let mut inherited = Vec::new();
+        for lot in sender_lots.iter_mut() {
+            if outstanding == U256::ZERO {
+                break;
+            }
+            let moved = lot.amount.min(outstanding);
+            lot.amount -= moved;
+            outstanding -= moved;
+            inherited.push(LockupLot {
+                amount: moved,
+                acquired_at: transferred_at,
+                lockup_expires_at: lot.lockup_expires_at,
+                source: AcquisitionSource::SecondaryTransfer { from },
+            });
+        }
+        sender_lots.retain(|lot| lot.amount > U256::ZERO);
+
+        if !sender_lots.is_empty() {
+            lots.insert((treasury_id, from), sender_lots);
+        }
+        if !inherited.is_empty() {
+            info!(
+                "{:?} inherited {} locked lots of {} from {:?}",
+                to, inherited.len(), quantera_types::wire::format_token_id(&treasury_id), from
+            );
+            lots.entry((treasury_id, to)).or_default().extend(inherited);
+        }
+        Ok(())
+    }
+
+    /// Lock-ups and notices of an investor in every asset they have either in
+    pub fn lockup_status(&self, investor: Address, now: DateTime<Utc>) -> Vec<LockupStatus> {
+        let notices: Vec<RedemptionNotice> = self.notices.read()
+            .map(|notices| notices.iter()
+                .filter(|notice| notice.investor == investor && notice.window.closes_at > now)
+                .cloned()
+                .collect())
+            .unwrap_or_default();
+        let mut treasury_ids: BTreeSet<[u8; 32]> = notices.iter().map(|notice| notice.treasury_id).collect();
+        if let Ok(lots) = self.lots.read() {
+            treasury_ids.extend(lots.keys().filter(|(_, holder)| *holder == investor).map(|(treasury_id, _)| *treasury_id));
+        }
+
+        treasury_ids.into_iter()
+            .filter_map(|treasury_id| {
+                let mut lots = self.locked_lots(treasury_id, investor, now);
+                let notices: Vec<RedemptionNotice> = notices.iter().filter(|notice| notice.treasury_id == treasury_id).cloned().collect();
+                if lots.is_empty() && notices.is_empty() {
+                    return None;
+                }
+                lots.sort_by_key(|lot| lot.lockup_expires_at);
+                let terms = self.terms(treasury_id);
+                Some(LockupStatus {
+                    treasury_id,
+                    investor,
+                    locked: lots.iter().fold(U256::ZERO, |total, lot| total.saturating_add(lot.amount)),
+                    lots,
+                    notices,
+                    next_window: terms.as_ref().and_then(|terms| terms.next_window(now)),
+                    notice_days: terms.map(|terms| terms.notice_days),
+                })
+            })
+            .collect()
+    }
+
+    fn locked_lots(&self, treasury_id: [u8; 32], investor: Address, now: DateTime<Utc>) -> Vec<LockupLot> {
+        self.lots.read()
+            .ok()
+            .and_then(|lots| lots.get(&(treasury_id, investor)).cloned())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|lot| lot.lockup_expires_at > now)
+            .collect()
+    }
+
+    fn require_open_window(&self, treasury_id: [u8; 32], now: DateTime<Utc>) -> Result<(), Error> {
+        match self.terms(treasury_id) {
+            Some(terms) if terms.open_window(now).is_none() => Err(Error::DealingRejected(DealingRejection {
+                treasury_id,
+                reason: DealingRejectionReason::OutsideWindow,
+                next_window: terms.next_window(now),
+                unlocks_at: None,
+            })),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const FUND: [u8; 32] = [0x5e; 32];
+
+    fn at(month: u32, day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, month, day, 12, 0, 0).unwrap()
+    }
+
+    /// Quarterly windows open for the first 10 days of Jan, Apr, Jul and Oct
+    fn quarterly(notice_days: u32, lockup_days: u32) -> DealingTerms {
+        DealingTerms {
+            treasury_id: FUND,
+            recurring: Some(RecurringWindows {
+                first_opens_at: Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap(),
+                every_months: 3,
+                open_days: 10,
+            }),
+            windows: Vec::new(),
+            notice_days,
+            lockup_days,
+        }
+    }
+
+    fn rejection(result: Result<(), Error>) -> DealingRejection {
+        match result {
+            Err(Error::DealingRejected(rejection)) => rejection,
+            other => panic!("expected a dealing rejection, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_orders_and_transfers_outside_window_get_the_next_window() {
+        let windows = DealingWindows::new();
+        windows.set_terms(quarterly(0, 0), "admin").unwrap();
+        let investor = Address::repeat_byte(1);
+
+        windows.check_subscription(FUND, at(4, 5)).unwrap();
+        windows.check_transfer(FUND, at(4, 5)).unwrap();
+        windows.check_redemption(investor, FUND, U256::from(10u64), U256::from(10u64), at(4, 5)).unwrap();
+
+        let subscription = rejection(windows.check_subscription(FUND, at(5, 20)));
+        assert_eq!(subscription.reason, DealingRejectionReason::OutsideWindow);
+        assert_eq!(subscription.next_window.unwrap().opens_at, Utc.with_ymd_and_hms(2026, 7, 1, 0, 0, 0).unwrap());
+        assert!(Error::DealingRejected(subscription).to_string().contains("next window opens 2026-07-01"));
+        let transfer = rejection(windows.check_transfer(FUND, at(5, 20)));
+        assert_eq!(transfer.reason, DealingRejectionReason::OutsideWindow);
+        let redemption = rejection(windows.check_redemption(investor, FUND, U256::from(10u64), U256::from(10u64), at(5, 20)));
+        assert_eq!(redemption.reason, DealingRejectionReason::OutsideWindow);
+
+        // An ad-hoc window opens dealing in between, and is the next window before it opens
+        let mut terms = quarterly(0, 0);
+        terms.windows.push(DealingWindow { opens_at: at(5, 18), closes_at: at(5, 25) });
+        windows.set_terms(terms, "admin").unwrap();
+        windows.check_subscription(FUND, at(5, 20)).unwrap();
+        assert_eq!(rejection(windows.check_subscription(FUND, at(5, 12))).next_window.unwrap().opens_at, at(5, 18));
+
+        // Assets without terms deal at any time
+        windows.check_subscription([1u8; 32], at(5, 20)).unwrap();
+    }
+
+    #[test]
+    fn test_redemptions_need_notice_that_terms_changes_do_not_shorten() {
+        let windows = DealingWindows::new();
+        windows.set_terms(quarterly(30, 0), "admin").unwrap();
+        let investor = Address::repeat_byte(2);
+        let (amount, balance) = (U256::from(40u64), U256::from(100u64));
+
+        // In the window, but without notice
+        let rejected = rejection(windows.check_redemption(investor, FUND, amount, balance, at(4, 5)));
+        assert_eq!(rejected.reason, DealingRejectionReason::NoticeRequired);
+        assert_eq!(rejected.next_window.unwrap().opens_at, Utc.with_ymd_and_hms(2026, 7, 1, 0, 0, 0).unwrap());
+
+        // Notice given mid-June is too late for July's window
+        let notice = windows.give_notice(investor, FUND, amount, at(6, 15)).unwrap();
+        assert_eq!(notice.window.opens_at, Utc.with_ymd_and_hms(2026, 10, 1, 0, 0, 0).unwrap());
+        let notice = windows.give_notice(investor, FUND, amount, at(5, 15)).unwrap();
+        assert_eq!(notice.window.opens_at, Utc.with_ymd_and_hms(2026, 7, 1, 0, 0, 0).unwrap());
+
+        // The windows move to the middle of the month with a longer notice period; the notice
+        // keeps its window
+        let mut moved = quarterly(60, 0);
+        moved.recurring.as_mut().unwrap().first_opens_at = Utc.with_ymd_and_hms(2026, 1, 15, 0, 0, 0).unwrap();
+        windows.set_terms(moved, "admin").unwrap();
+        windows.check_redemption(investor, FUND, U256::from(30u64), balance, at(7, 5)).unwrap();
+        // Only 10 of the notice is left
+        let rejected = rejection(windows.check_redemption(investor, FUND, U256::from(30u64), balance, at(7, 6)));
+        assert_eq!(rejected.reason, DealingRejectionReason::NoticeRequired);
+        windows.check_redemption(investor, FUND, U256::from(10u64), balance, at(7, 6)).unwrap();
+
+        // Outside any window, the next one is October's, already noticed
+        let rejected = rejection(windows.check_redemption(investor, FUND, amount, balance, at(8, 1)));
+        assert_eq!(rejected.reason, DealingRejectionReason::OutsideWindow);
+        assert_eq!(rejected.next_window.unwrap().opens_at, Utc.with_ymd_and_hms(2026, 10, 1, 0, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_lockup_blocks_redemption_and_follows_transferred_units() {
+        let windows = DealingWindows::new();
+        windows.set_terms(quarterly(0, 180), "admin").unwrap();
+        let (investor, recipient) = (Address::repeat_byte(3), Address::repeat_byte(4));
+
+        // 50 held from before lock-ups were tracked, 100 subscribed in January
+        windows.record_subscription(investor, FUND, U256::from(100u64), at(1, 5)).unwrap();
+        let balance = U256::from(150u64);
+        windows.check_redemption(investor, FUND, U256::from(50u64), balance, at(4, 5)).unwrap();
+        let rejected = rejection(windows.check_redemption(investor, FUND, U256::from(60u64), balance, at(4, 5)));
+        assert_eq!(rejected.reason, DealingRejectionReason::LockedUp);
+        assert_eq!(rejected.unlocks_at, Some(at(7, 4)));
+        assert_eq!(rejected.next_window.unwrap().opens_at, Utc.with_ymd_and_hms(2026, 7, 1, 0, 0, 0).unwrap());
+
+        // Transferred units keep their lock-up at the recipient and free nothing at the sender
+        windows.record_transfer(investor, recipient, FUND, U256::from(30u64), at(4, 6)).unwrap();
+        let status = windows.lockup_status(recipient, at(4, 6));
+        assert_eq!(status[0].locked, U256::from(30u64));
+        assert_eq!(status[0].lots[0].lockup_expires_at, at(7, 4));
+        assert_eq!(status[0].lots[0].source, AcquisitionSource::SecondaryTransfer { from: investor });
+        assert_eq!(windows.lockup_status(investor, at(4, 6))[0].locked, U256::from(70u64));
+        let rejected = rejection(windows.check_redemption(recipient, FUND, U256::from(10u64), U256::from(30u64), at(4, 7)));
+        assert_eq!(rejected.reason, DealingRejectionReason::LockedUp);
+
+        // Past the expiry the units are free, in the next window
+        windows.check_redemption(recipient, FUND, U256::from(30u64), U256::from(30u64), at(7, 5)).unwrap();
+        assert!(windows.lockup_status(recipient, at(7, 5)).is_empty());
+    }
+}
@@ -0,0 +1,466 @@
+// Platform fee schedule and fee accrual ledger
+use alloy_primitives::U256;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use price_oracle::{FxRateService, ReportingTotal};
+use rust_decimal::Decimal;
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+use tracing::info;
+use uuid::Uuid;
+use crate::{Currency, Error};
+
+const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Platform operations that carry a fee
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeOperation {
+    /// Treasury creation; notional is face value times supply
+    Issuance,
+    /// Trade settlement; notional is the payment amount
+    Trading,
+    /// Yield distribution; notional is the amount distributed
+    YieldDistribution,
+}
+
+impl FeeOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeeOperation::Issuance => "issuance",
+            FeeOperation::Trading => "trading",
+            FeeOperation::YieldDistribution => "yield_distribution",
+        }
+    }
+}
+
+impl std::str::FromStr for FeeOperation {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "issuance" => Ok(FeeOperation::Issuance),
+            "trading" => Ok(FeeOperation::Trading),
+            "yield_distribution" => Ok(FeeOperation::YieldDistribution),
+            other => Err(Error::InvalidParameter(format!("Unknown fee operation {}", other))),
+        }
+    }
+}
+
+/// Rate applying once the tenant's volume for the month reaches `from_volume`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FeeTier {
+    pub from_volume: U256,
+    pub bps: u32,
+}
+
+/// How a fee is charged. A zero fee is configured explicitly, e.g. as `Bps { bps: 0 }`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FeeModel {
+    Flat { amount: U256 },
+    Bps { bps: u32 },
+    /// Rate chosen by the tenant's notional for the operation earlier in the calendar month,
+    /// counting only operations in the same currency
+    Tiered { tiers: Vec<FeeTier> },
+}
+
+impl FeeModel {
+    pub fn validate(&self) -> Result<(), Error> {
+        let valid_bps = |bps: u32| u64::from(bps) <= BPS_DENOMINATOR;
+        match self {
+            FeeModel::Flat { .. } => Ok(()),
+            FeeModel::Bps { bps } if valid_bps(*bps) => Ok(()),
+            FeeModel::Tiered { tiers } => {
+                if tiers.first().map(|tier| tier.from_volume) != Some(U256::ZERO) {
+                    return Err(Error::InvalidParameter("The first fee tier must start at zero volume".into()));
+                }
+                if tiers.windows(2).any(|pair| pair[1].from_volume <= pair[0].from_volume) {
+                    return Err(Error::InvalidParameter("Fee tiers must be in increasing volume order".into()));
+                }
+                if !tiers.iter().all(|tier| valid_bps(tier.bps)) {
+                    return Err(Error::InvalidParameter("Fee tier rates cannot exceed 10000 bps".into()));
+                }
+                Ok(())
+            }
+            FeeModel::Bps { .. } => Err(Error::InvalidParameter("Fee rate cannot exceed 10000 bps".into())),
+        }
+    }
+
+    /// Fee on `notional` for a tenant that has already done `prior_volume` this month.
+    /// The whole operation is charged at the tier its starting volume falls in.
+    pub fn fee(&self, notional: U256, prior_volume: U256) -> U256 {
+        let bps = match self {
+            FeeModel::Flat { amount } => return *amount,
+            FeeModel::Bps { bps } => *bps,
+            FeeModel::Tiered { tiers } => tiers.iter()
+                .take_while(|tier| tier.from_volume <= prior_volume)
+                .last()
+                .map(|tier| tier.bps)
+                .unwrap_or(0),
+        };
+        notional.saturating_mul(U256::from(bps)) / U256::from(BPS_DENOMINATOR)
+    }
+}
+
+/// A fee for one tenant and operation, in force from `effective_from` until superseded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeRule {
+    pub rule_id: Uuid,
+    pub tenant: String,
+    pub operation: FeeOperation,
+    pub model: FeeModel,
+    pub effective_from: DateTime<Utc>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewFeeRule {
+    pub tenant: String,
+    pub operation: FeeOperation,
+    pub model: FeeModel,
+    /// Defaults to now; never earlier
+    pub effective_from: Option<DateTime<Utc>>,
+}
+
+/// A fee owed to the platform for one operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeAccrual {
+    pub accrual_id: Uuid,
+    pub tenant: String,
+    pub operation: FeeOperation,
+    /// Treasury id, settlement id or distribution the fee was charged on
+    pub reference: String,
+    pub notional: U256,
+    pub fee: U256,
+    /// Currency of the notional and fee; accruals recorded before currencies were tracked are in USD
+    #[serde(default)]
+    pub currency: Currency,
+    pub rule_id: Uuid,
+    pub accrued_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportPeriod {
+    Day,
+    Month,
+}
+
+impl ReportPeriod {
+    pub(crate) fn start(&self, at: DateTime<Utc>) -> NaiveDate {
+        let date = at.date_naive();
+        match self {
+            ReportPeriod::Day => date,
+            ReportPeriod::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+}
+
+/// Accruals of one tenant, operation and currency within one period
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FeeReportRow {
+    pub tenant: String,
+    pub period_start: NaiveDate,
+    pub operation: FeeOperation,
+    pub currency: Currency,
+    pub accruals: usize,
+    pub notional: U256,
+    pub fees: U256,
+}
+
+/// Report rows as CSV with a header line; amounts in base units
+pub fn report_csv(rows: &[FeeReportRow]) -> String {
+    let mut csv = String::from("tenant,period_start,operation,currency,accruals,notional,fees\n");
+    for row in rows {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            row.tenant.replace(',', " "), row.period_start, row.operation.as_str(), row.currency, row.accruals, row.notional, row.fees
+        ));
+    }
+    csv
+}
+
+/// Fees of the report rows totalled in `reporting`, in base units, with the rates applied
+pub async fn report_fees_in(rows: &[FeeReportRow], reporting: Currency, fx: &FxRateService) -> Result<ReportingTotal, Error> {
+    let fees = rows.iter()
+        .map(|row| row.fees.to_string().parse::<Decimal>()
+            .map(|fees| (row.currency, fees))
+            .map_err(|_| Error::InvalidState(format!("{} fees of {} are too large to convert", row.currency, row.tenant))))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(fx.total(fees, reporting).await)
+}
+
+/// Effective-dated fee rules per tenant and operation, and the fees accrued under them.
+///
+/// Every operation needs a rule in force: a tenant without one is refused rather than
+/// charged nothing.
+pub struct FeeSchedule {
+    /// Tenant this service's own operations are charged to
+    tenant: String,
+    /// Currency trades settle and yield is paid in
+    payment_currency: Currency,
+    rules: RwLock<Vec<FeeRule>>,
+    accruals: RwLock<Vec<FeeAccrual>>,
+}
+
+impl FeeSchedule {
+    pub fn new(tenant: impl Into<String>) -> Self {
+        Self {
+            tenant: tenant.into(),
+            payment_currency: Currency::USD,
+            rules: RwLock::new(Vec::new()),
+            accruals: RwLock::new(Vec::new()),
+        }
+    }
+
+    pub fn with_payment_currency(mut self, currency: Currency) -> Self {
+        self.payment_currency = currency;
+        self
+    }
+
+    pub fn tenant(&self) -> &str {
+        &self.tenant
+    }
+
+    /// Currency of trading and yield distribution notionals
+    pub fn payment_currency(&self) -> Currency {
+        self.payment_currency
+    }
+
+    /// Schedule for `FEE_TENANT` (default `default`) and `PAYMENT_CURRENCY` (default `USD`),
+    /// with the rules listed in the JSON file at `FEE_SCHEDULE_PATH`. The file is the
+    /// standing configuration and is applied as written on every start, including
+    /// effective dates already past.
+    pub fn from_env() -> Result<Self, Error> {
+        let payment_currency = match std::env::var("PAYMENT_CURRENCY") {
+            Ok(currency) => currency.parse().map_err(|e| Error::InvalidParameter(format!("Invalid PAYMENT_CURRENCY: {}", e)))?,
+            Err(_) => Currency::USD,
+        };
+        let schedule = Self::new(std::env::var("FEE_TENANT").unwrap_or_else(|_| "default".to_string()))
+            .with_payment_currency(payment_currency);
+        let Ok(path) = std::env::var("FEE_SCHEDULE_PATH") else { return Ok(schedule) };
+
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| Error::InvalidParameter(format!("Cannot read FEE_SCHEDULE_PATH {}: {}", path, e)))?;
+        let configured: Vec<NewFeeRule> = serde_json::from_str(&contents)
+            .map_err(|e| Error::InvalidParameter(format!("Invalid fee schedule {}: {}", path, e)))?;
+        let now = Utc::now();
+        for new in configured {
+            let effective_from = new.effective_from.unwrap_or(now);
+            schedule.add_rule(new, "configuration", effective_from.min(now))
+                .map_err(|e| Error::InvalidParameter(format!("Invalid fee schedule {}: {}", path, e)))?;
+        }
+        Ok(schedule)
+    }
+
+    /// Schedule a fee change. Changes take effect now or later, never in the past.
+    pub fn add_rule(&self, new: NewFeeRule, created_by: &str, now: DateTime<Utc>) -> Result<FeeRule, Error> {
+        new.model.validate()?;
+        if new.tenant.trim().is_empty() {
+            return Err(Error::InvalidParameter("Fee rules need a tenant".into()));
+        }
+        let effective_from = new.effective_from.unwrap_or(now);
+        if effective_from < now {
+            return Err(Error::InvalidParameter(format!(
+                "Fee changes cannot be retroactive: {} is before {}", effective_from, now
+            )));
+        }
+
+        let mut rules = self.rules.write().map_err(|_| Error::Internal("Fee rules lock poisoned".into()))?;
+        if rules.iter().any(|r| r.tenant == new.tenant && r.operation == new.operation && r.effective_from == effective_from) {
+            return Err(Error::InvalidParameter(format!(
+                "A {} fee for {} already takes effect at {}", new.operation.as_str(), new.tenant, effective_from
+            )));
+        }
+        let rule = FeeRule {
+            rule_id: Uuid::new_v4(),
+            tenant: new.tenant,
+            operation: new.operation,
+            model: new.model,
+            effective_from,
+            created_by: created_by.to_string(),
+            created_at: now,
+        };
+        rules.push(rule.clone());
+        info!(
+            "[AUDIT] {} fee for {} set by {}, effective {}: {:?}",
+            rule.operation.as_str(), rule.tenant, created_by, effective_from, rule.model
+        );
+        Ok(rule)
+    }
+
+    /// Rules, optionally for one tenant, ordered by tenant, operation and effective date
+    pub fn rules(&self, tenant: Option<&str>) -> Vec<FeeRule> {
+        let mut rules: Vec<FeeRule> = self.rules.read().map(|rules| rules.clone()).unwrap_or_default()
+            .into_iter()
+            .filter(|rule| tenant.map_or(true, |tenant| rule.tenant == tenant))
+            .collect();
+        rules.sort_by(|a, b| (&a.tenant, a.operation, a.effective_from).cmp(&(&b.tenant, b.operation, b.effective_from)));
+        rules
+    }
+
+    /// Rule in force for this service's tenant at `at`
+    pub fn rule_at(&self, operation: FeeOperation, at: DateTime<Utc>) -> Result<FeeRule, Error> {
+        self.rules.read().map_err(|_| Error::Internal("Fee rules lock poisoned".into()))?
+            .iter()
+            .filter(|r| r.tenant == self.tenant && r.operation == operation && r.effective_from <= at)
+            .max_by_key(|r| r.effective_from)
+            .cloned()
+            .ok_or_else(|| Error::InvalidState(format!(
+                "No {} fee configured for tenant {}", operation.as_str(), self.tenant
+            )))
+    }
+
+    /// Fee an operation of `notional` in `currency` would be charged at `at`, and the rule
+    /// charging it. Flat amounts and tier volumes are read in the operation's currency.
+    pub fn quote(&self, operation: FeeOperation, notional: U256, currency: Currency, at: DateTime<Utc>) -> Result<(FeeRule, U256), Error> {
+        let rule = self.rule_at(operation, at)?;
+        let fee = rule.model.fee(notional, self.volume_before(operation, currency, at)?);
+        Ok((rule, fee))
+    }
+
+    /// Record the fee on a completed operation
+    pub fn accrue(
+        &self,
+        operation: FeeOperation,
+        reference: impl Into<String>,
+        notional: U256,
+        currency: Currency,
+        at: DateTime<Utc>,
+    ) -> Result<FeeAccrual, Error> {
+        let (rule, fee) = self.quote(operation, notional, currency, at)?;
+        let accrual = FeeAccrual {
+            accrual_id: Uuid::new_v4(),
+            tenant: self.tenant.clone(),
+            operation,
+            reference: reference.into(),
+            notional,
+            fee,
+            currency,
+            rule_id: rule.rule_id,
+            accrued_at: at,
+        };
+        self.accruals.write().map_err(|_| Error::Internal("Fee accruals lock poisoned".into()))?
+            .push(accrual.clone());
+        info!(
+            "[AUDIT] {} fee of {} {} accrued for {} on {} (notional {})",
+            operation.as_str(), fee, currency, accrual.tenant, accrual.reference, notional
+        );
+        Ok(accrual)
+    }
+
+    /// Accruals in `[from, to)` totalled per tenant, period, operation and currency
+    pub fn report(&self, from: DateTime<Utc>, to: DateTime<Utc>, period: ReportPeriod) -> Vec<FeeReportRow> {
+        let accruals = self.accruals.read().map(|a| a.clone()).unwrap_or_default();
+        let mut totals: BTreeMap<(String, NaiveDate, FeeOperation, Currency), FeeReportRow> = BTreeMap::new();
+        for accrual in accruals.into_iter().filter(|a| a.accrued_at >= from && a.accrued_at < to) {
+            let period_start = period.start(accrual.accrued_at);
+            let row = totals.entry((accrual.tenant.clone(), period_start, accrual.operation, accrual.currency))
+                .or_insert_with(|| FeeReportRow {
+                    tenant: accrual.tenant.clone(),
+                    period_start,
+                    operation: accrual.operation,
+                    currency: accrual.currency,
+                    accruals: 0,
+                    notional: U256::ZERO,
+                    fees: U256::ZERO,
+                });
+            row.accruals += 1;
+            row.notional = row.notional.saturating_add(accrual.notional);
+            row.fees = row.fees.saturating_add(accrual.fee);
+        }
+        totals.into_values().collect()
+    }
+
+    /// Notional in `currency` this service's tenant accrued for `operation` earlier in the month of `at`
+    fn volume_before(&self, operation: FeeOperation, currency: Currency, at: DateTime<Utc>) -> Result<U256, Error> {
+        let month = ReportPeriod::Month.start(at);
+        Ok(self.accruals.read().map_err(|_| Error::Internal("Fee accruals lock poisoned".into()))?
+            .iter()
+            .filter(|a| a.tenant == self.tenant && a.operation == operation && a.currency == currency)
+            .filter(|a| a.accrued_at <= at && ReportPeriod::Month.start(a.accrued_at) == month)
+            .fold(U256::ZERO, |total, a| total.saturating_add(a.notional)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn rule(tenant: &str, operation: FeeOperation, model: FeeModel, effective_from: Option<DateTime<Utc>>) -> NewFeeRule {
+        NewFeeRule { tenant: tenant.to_string(), operation, model, effective_from }
+    }
+
+    fn tiered() -> FeeModel {
+        FeeModel::Tiered { tiers: vec![
+            FeeTier { from_volume: U256::ZERO, bps: 30 },
+            FeeTier { from_volume: U256::from(1_000_000u64), bps: 20 },
+            FeeTier { from_volume: U256::from(5_000_000u64), bps: 10 },
+        ] }
+    }
+
+    #[test]
+    fn test_tier_boundaries() {
+        let model = tiered();
+        let notional = U256::from(100_000u64);
+        assert_eq!(model.fee(notional, U256::ZERO), U256::from(300u64));
+        assert_eq!(model.fee(notional, U256::from(999_999u64)), U256::from(300u64));
+        // Reaching a boundary exactly moves to the next tier
+        assert_eq!(model.fee(notional, U256::from(1_000_000u64)), U256::from(200u64));
+        assert_eq!(model.fee(notional, U256::from(5_000_000u64)), U256::from(100u64));
+
+        let schedule = FeeSchedule::new("acme");
+        let start = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+        schedule.add_rule(rule("acme", FeeOperation::Trading, model, None), "admin", start).unwrap();
+        let first = schedule.accrue(FeeOperation::Trading, "trade-1", U256::from(1_000_000u64), Currency::USD, start + Duration::hours(1)).unwrap();
+        let second = schedule.accrue(FeeOperation::Trading, "trade-2", notional, Currency::USD, start + Duration::hours(2)).unwrap();
+        assert_eq!(first.fee, U256::from(3_000u64));
+        assert_eq!(second.fee, U256::from(200u64));
+        // Euro volume is tiered on its own
+        let in_euros = schedule.accrue(FeeOperation::Trading, "trade-eur", notional, Currency::EUR, start + Duration::hours(3)).unwrap();
+        assert_eq!(in_euros.fee, U256::from(300u64));
+        // Volume resets with the month
+        let next_month = schedule.accrue(FeeOperation::Trading, "trade-3", notional, Currency::USD, start + Duration::days(31)).unwrap();
+        assert_eq!(next_month.fee, U256::from(300u64));
+
+        let rows = schedule.report(start, start + Duration::days(62), ReportPeriod::Month);
+        assert_eq!(rows.len(), 3);
+        assert_eq!((rows[0].currency, rows[0].accruals, rows[0].fees), (Currency::EUR, 1, U256::from(300u64)));
+        assert_eq!((rows[1].currency, rows[1].accruals, rows[1].fees), (Currency::USD, 2, U256::from(3_200u64)));
+        assert!(report_csv(&rows).starts_with(
+            "tenant,period_start,operation,currency,accruals,notional,fees\nacme,2026-03-01,trading,EUR,1,100000,300\nacme,2026-03-01,trading,USD,2,1100000,3200\n"
+        ));
+    }
+
+    #[test]
+    fn test_effective_date_transitions() {
+        let schedule = FeeSchedule::new("acme");
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        let notional = U256::from(1_000_000u64);
+
+        // Nothing configured is an error, not a free operation
+        assert!(matches!(schedule.quote(FeeOperation::Issuance, notional, Currency::USD, now), Err(Error::InvalidState(_))));
+
+        schedule.add_rule(rule("acme", FeeOperation::Issuance, FeeModel::Bps { bps: 50 }, None), "admin", now).unwrap();
+        schedule.add_rule(
+            rule("acme", FeeOperation::Issuance, FeeModel::Flat { amount: U256::ZERO }, Some(now + Duration::days(1))),
+            "admin",
+            now,
+        ).unwrap();
+        assert!(schedule.add_rule(
+            rule("acme", FeeOperation::Issuance, FeeModel::Bps { bps: 10 }, Some(now - Duration::seconds(1))),
+            "admin",
+            now,
+        ).is_err());
+
+        assert_eq!(schedule.quote(FeeOperation::Issuance, notional, Currency::USD, now).unwrap().1, U256::from(5_000u64));
+        assert_eq!(schedule.quote(FeeOperation::Issuance, notional, Currency::USD, now + Duration::days(1) - Duration::seconds(1)).unwrap().1, U256::from(5_000u64));
+        assert_eq!(schedule.quote(FeeOperation::Issuance, notional, Currency::USD, now + Duration::days(1)).unwrap().1, U256::ZERO);
+        // Other tenants' rules do not apply
+        schedule.add_rule(rule("globex", FeeOperation::Trading, FeeModel::Bps { bps: 5 }, None), "admin", now).unwrap();
+        assert!(schedule.quote(FeeOperation::Trading, notional, Currency::USD, now).is_err());
+    }
+}
@@ -0,0 +1,599 @@
+use alloy_contract::Token;
+use alloy_primitives::{Address, U256};
+use async_trait::async_trait;
+use chrono::Utc;
+use ethereum_client::EthereumClient;
+use serde::{Serialize, Deserialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info, warn};
+use crate::{
+    admin_cli::env_number,
+    user_service::{PortfolioHolding, UserService},
+    withholding::HolderBalances,
+    Error,
+    TreasuryRegistryClient,
+};
+
+/// Multicall3, deployed at the same address on every supported chain
+pub const DEFAULT_MULTICALL_ADDRESS: &str = "0xcA11bde05977b3631167028862bE2a173976CA11";
+
+/// What kind of token a holding is in
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum HoldingKind {
+    Treasury,
+    PlatformAsset,
+}
+
+/// A token whose balances are tracked in user portfolios
+#[derive(Debug, Clone)]
+pub struct TrackedToken {
+    pub kind: HoldingKind,
+    /// Treasury id, or asset id for platform assets
+    pub id: [u8; 32],
+    pub token_address: Address,
+    pub name: String,
+    pub symbol: String,
+    /// Zero when the token has no registry price
+    pub price: U256,
+    pub yield_rate: u64,
+    pub maturity_date: u64,
+}
+
+/// Balances of one wallet read at a single block
+#[derive(Debug, Clone)]
+pub struct BalanceSnapshot {
+    pub block: u64,
+    /// One entry per requested token; `None` where that balance call failed
+    pub balances: Vec<Option<U256>>,
+}
+
+/// Source of the tokens to enumerate for each wallet
+#[async_trait]
+pub trait TokenUniverse: Send + Sync {
+    async fn tracked_tokens(&self) -> Result<Vec<TrackedToken>, Error>;
+}
+
+/// Batched balance lookups
+#[async_trait]
+pub trait BalanceReader: Send + Sync {
+    async fn balances(&self, wallet: Address, tokens: &[Address]) -> Result<BalanceSnapshot, Error>;
+}
+
+/// Notified when a holding moves by more than the configured threshold
+#[async_trait]
+pub trait HoldingChangeHook: Send + Sync {
+    async fn holding_changed(&self, change: &HoldingChange);
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct HoldingChange {
+    pub wallet_address: Address,
+    pub token_address: Address,
+    pub previous_balance: U256,
+    pub balance: U256,
+    pub block: u64,
+}
+
+/// Holdings of one wallet as of the last sync
+#[derive(Debug, Clone, Serialize)]
+pub struct HoldingsSnapshot {
+    pub wallet_address: Address,
+    pub holdings: Vec<PortfolioHolding>,
+    /// Value of every listed holding, dust included
+    pub total_value: U256,
+    pub last_synced_block: u64,
+    pub synced_at: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct HoldingsSyncConfig {
+    /// Cached snapshots older than this are refreshed on read
+    pub max_age_secs: u64,
+    /// Balances below this are listed but left out of allocations
+    pub dust_threshold: U256,
+    /// Relative balance change that triggers the change hook
+    pub change_threshold_bps: u32,
+}
+
+impl Default for HoldingsSyncConfig {
+    fn default() -> Self {
+        Self {
+            max_age_secs: 300,
+            dust_threshold: U256::ZERO,
+            change_threshold_bps: 100,
+        }
+    }
+}
+
+impl HoldingsSyncConfig {
+    pub fn from_env() -> Result<Self, Error> {
+        let defaults = Self::default();
+        Ok(Self {
+            max_age_secs: env_number("HOLDINGS_MAX_AGE_SECS", defaults.max_age_secs)?,
+            dust_threshold: U256::from(env_number("HOLDINGS_DUST_THRESHOLD", 0)?),
+            change_threshold_bps: env_number("HOLDINGS_CHANGE_THRESHOLD_BPS", defaults.change_threshold_bps as u64)? as u32,
+        })
+    }
+}
+
+/// Syncs wallet holdings from on-chain balances and caches the result per wallet
+pub struct HoldingsSync {
+    universe: Arc<dyn TokenUniverse>,
+    reader: Arc<dyn BalanceReader>,
+    hook: Option<Arc<dyn HoldingChangeHook>>,
+    config: HoldingsSyncConfig,
+    snapshots: Mutex<HashMap<Address, HoldingsSnapshot>>,
+    wallets: Mutex<HashSet<Address>>,
+}
+
+impl HoldingsSync {
+    pub fn new(universe: Arc<dyn TokenUniverse>, reader: Arc<dyn BalanceReader>, config: HoldingsSyncConfig) -> Self {
+        Self {
+            universe,
+            reader,
+            hook: None,
+            config,
+            snapshots: Mutex::new(HashMap::new()),
+            wallets: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub fn with_change_hook(mut self, hook: Arc<dyn HoldingChangeHook>) -> Self {
+        self.hook = Some(hook);
+        self
+    }
+
+    /// Include a wallet in background syncs
+    pub fn track(&self, wallet: Address) {
+        self.wallets.lock().unwrap().insert(wallet);
+    }
+
+    pub fn tracked_wallets(&self) -> Vec<Address> {
+        self.wallets.lock().unwrap().iter().copied().collect()
+    }
+
+    /// Cached holdings unless they are stale or a refresh is forced
+    pub async fn snapshot(&self, wallet: Address, force_refresh: bool) -> Result<HoldingsSnapshot, Error> {
+        if !force_refresh {
+            let cached = self.snapshots.lock().unwrap().get(&wallet).cloned();
+            if let Some(snapshot) = cached {
+                let age = (Utc::now().timestamp() as u64).saturating_sub(snapshot.synced_at);
+                if age < self.config.max_age_secs {
+                    return Ok(snapshot);
+                }
+            }
+        }
+        self.sync_wallet(wallet).await
+    }
+
+    /// Read current balances of every tracked token and replace the wallet's snapshot
+    pub async fn sync_wallet(&self, wallet: Address) -> Result<HoldingsSnapshot, Error> {
+        self.track(wallet);
+
+        let tokens = self.universe.tracked_tokens().await?;
+        let addresses: Vec<Address> = tokens.iter().map(|t| t.token_address).collect();
+        let read = self.reader.balances(wallet, &addresses).await?;
+        if read.balances.len() != tokens.len() {
+            return Err(Error::ContractInteraction(format!(
+                "Expected {} balances, got {}", tokens.len(), read.balances.len()
+            )));
+        }
+
+        let previous = self.snapshots.lock().unwrap().get(&wallet).cloned();
+        let previous_holding = |token: Address| previous.as_ref()
+            .and_then(|snapshot| snapshot.holdings.iter().find(|h| h.token_address == token).cloned());
+
+        let mut holdings = Vec::new();
+        for (token, balance) in tokens.iter().zip(read.balances) {
+            let Some(balance) = balance else {
+                // Keep the last known holding rather than reporting it as sold
+                warn!("Balance of {:?} in {:?} unavailable, keeping previous value", wallet, token.token_address);
+                holdings.extend(previous_holding(token.token_address));
+                continue;
+            };
+            if balance == U256::ZERO {
+                continue;
+            }
+            holdings.push(PortfolioHolding {
+                treasury_id: token.id,
+                token_address: token.token_address,
+                kind: token.kind,
+                name: token.name.clone(),
+                symbol: token.symbol.clone(),
+                balance,
+                value: balance * token.price,
+                pending_yield: U256::ZERO,
+                yield_rate: token.yield_rate,
+                maturity_date: token.maturity_date,
+                is_restricted: false,
+                is_dust: balance < self.config.dust_threshold,
+                allocation_bps: None,
+                last_synced_block: read.block,
+            });
+        }
+
+        let total_value = holdings.iter().fold(U256::ZERO, |sum, h| sum + h.value);
+        apply_allocations(&mut holdings);
+
+        let snapshot = HoldingsSnapshot {
+            wallet_address: wallet,
+            holdings,
+            total_value,
+            last_synced_block: read.block,
+            synced_at: Utc::now().timestamp() as u64,
+        };
+
+        let changes = detect_changes(previous.as_ref(), &snapshot, self.config.change_threshold_bps);
+        self.snapshots.lock().unwrap().insert(wallet, snapshot.clone());
+
+        if let Some(hook) = &self.hook {
+            for change in &changes {
+                hook.holding_changed(change).await;
+            }
+        }
+        debug!("Synced {} holdings of {:?} at block {}", snapshot.holdings.len(), wallet, read.block);
+
+        Ok(snapshot)
+    }
+}
+
+/// Holders among the tracked wallets; a wallet whose holdings cannot be read fails the
+/// lookup rather than being left out of a distribution
+#[async_trait]
+impl HolderBalances for HoldingsSync {
+    async fn holder_balances(&self, token: Address) -> Result<Vec<(Address, U256)>, Error> {
+        let mut wallets = self.tracked_wallets();
+        wallets.sort();
+
+        let mut balances = Vec::new();
+        for wallet in wallets {
+            let snapshot = self.snapshot(wallet, false).await?;
+            let balance = snapshot.holdings.iter()
+                .filter(|h| h.token_address == token)
+                .fold(U256::ZERO, |sum, h| sum + h.balance);
+            if balance > U256::ZERO {
+                balances.push((wallet, balance));
+            }
+        }
+        Ok(balances)
+    }
+}
+
+/// Share of non-dust value held in each non-dust holding
+fn apply_allocations(holdings: &mut [PortfolioHolding]) {
+    let allocatable = holdings.iter()
+        .filter(|h| !h.is_dust)
+        .fold(U256::ZERO, |sum, h| sum + h.value);
+
+    for holding in holdings.iter_mut().filter(|h| !h.is_dust) {
+        holding.allocation_bps = Some(if allocatable == U256::ZERO {
+            0
+        } else {
+            (holding.value * U256::from(10_000u64) / allocatable).to::<u64>() as u32
+        });
+    }
+}
+
+fn detect_changes(previous: Option<&HoldingsSnapshot>, current: &HoldingsSnapshot, threshold_bps: u32) -> Vec<HoldingChange> {
+    // Without a previous sync there is nothing to compare against
+    let Some(previous) = previous else {
+        return Vec::new();
+    };
+
+    let before: HashMap<Address, U256> = previous.holdings.iter().map(|h| (h.token_address, h.balance)).collect();
+    let after: HashMap<Address, U256> = current.holdings.iter().map(|h| (h.token_address, h.balance)).collect();
+
+    let mut tokens: Vec<Address> = before.keys().chain(after.keys()).copied().collect::<HashSet<_>>().into_iter().collect();
+    tokens.sort();
+
+    tokens.into_iter()
+        .filter_map(|token| {
+            let previous_balance = before.get(&token).copied().unwrap_or(U256::ZERO);
+            let balance = after.get(&token).copied().unwrap_or(U256::ZERO);
+            exceeds_threshold(previous_balance, balance, threshold_bps).then(|| HoldingChange {
+                wallet_address: current.wallet_address,
+                token_address: token,
+                previous_balance,
+                balance,
+                block: current.last_synced_block,
+            })
+        })
+        .collect()
+}
+
+fn exceeds_threshold(previous: U256, current: U256, threshold_bps: u32) -> bool {
+    if previous == current {
+        return false;
+    }
+    if previous == U256::ZERO || current == U256::ZERO {
+        return true;
+    }
+    let diff = if current > previous { current - previous } else { previous - current };
+    diff * U256::from(10_000u64) >= previous * U256::from(threshold_bps)
+}
+
+/// Registered treasuries plus configured platform asset tokens
+pub struct RegistryTokenUniverse {
+    registry: Arc<TreasuryRegistryClient>,
+    ethereum_client: Arc<EthereumClient>,
+    platform_assets: Vec<Address>,
+    /// Token names and symbols never change, so they are read once
+    names: Mutex<HashMap<Address, (String, String)>>,
+}
+
+impl RegistryTokenUniverse {
+    pub fn new(registry: Arc<TreasuryRegistryClient>, ethereum_client: Arc<EthereumClient>, platform_assets: Vec<Address>) -> Self {
+        Self { registry, ethereum_client, platform_assets, names: Mutex::new(HashMap::new()) }
+    }
+
+    async fn name_and_symbol(&self, token: Address) -> Result<(String, String), Error> {
+        if let Some(names) = self.names.lock().unwrap().get(&token).cloned() {
+            return Ok(names);
+        }
+        let name = self.ethereum_client.call_contract::<String>(token, "name()", vec![]).await?;
+        let symbol = self.ethereum_client.call_contract::<String>(token, "symbol()", vec![]).await?;
+        self.names.lock().unwrap().insert(token, (name.clone(), symbol.clone()));
+        Ok((name, symbol))
+    }
+}
+
+#[async_trait]
+impl TokenUniverse for RegistryTokenUniverse {
+    async fn tracked_tokens(&self) -> Result<Vec<TrackedToken>, Error> {
+        let mut tokens = Vec::new();
+
+        for treasury_id in self.registry.get_all_treasuries().await? {
+            let info = match self.registry.get_treasury_details(treasury_id).await {
+                Ok(info) => info,
+                Err(e) => {
+                    warn!("Failed to get details for treasury {:?}: {}", treasury_id, e);
+                    continue;
+                }
+            };
+            let (name, symbol) = match self.name_and_symbol(info.token_address).await {
+                Ok(names) => names,
+                Err(e) => {
+                    warn!("Failed to get token info for treasury {:?}: {}", treasury_id, e);
+                    continue;
+                }
+            };
+            tokens.push(TrackedToken {
+                kind: HoldingKind::Treasury,
+                id: treasury_id,
+                token_address: info.token_address,
+                name,
+                symbol,
+                price: info.current_price,
+                yield_rate: info.yield_rate,
+                maturity_date: info.maturity_date,
+            });
+        }
+
+        for &token_address in &self.platform_assets {
+            match self.name_and_symbol(token_address).await {
+                Ok((name, symbol)) => tokens.push(TrackedToken {
+                    kind: HoldingKind::PlatformAsset,
+                    id: [0u8; 32],
+                    token_address,
+                    name,
+                    symbol,
+                    price: U256::ZERO,
+                    yield_rate: 0,
+                    maturity_date: 0,
+                }),
+                Err(e) => warn!("Failed to get token info for platform asset {:?}: {}", token_address, e),
+            }
+        }
+
+        Ok(tokens)
+    }
+}
+
+/// Reads all balances of a wallet in one `tryBlockAndAggregate` call per batch
+pub struct MulticallBalanceReader {
+    client: Arc<EthereumClient>,
+    multicall: Address,
+    batch_size: usize,
+}
+
+impl MulticallBalanceReader {
+    pub fn new(client: Arc<EthereumClient>, multicall: Address) -> Self {
+        Self { client, multicall, batch_size: 100 }
+    }
+}
+
+#[async_trait]
+impl BalanceReader for MulticallBalanceReader {
+    async fn balances(&self, wallet: Address, tokens: &[Address]) -> Result<BalanceSnapshot, Error> {
+        let calldata = EthereumClient::encode_function_call("balanceOf(address)", vec![Token::Address(wallet)])
+            .map_err(Error::Encoding)?;
+
+        let mut block = 0;
+        let mut balances = Vec::with_capacity(tokens.len());
+        for batch in tokens.chunks(self.batch_size) {
+            let calls = batch.iter()
+                .map(|token| Token::Tuple(vec![Token::Address(*token), Token::Bytes(calldata.clone())]))
+                .collect();
+
+            // Failed calls come back unsuccessful instead of reverting the whole batch
+            let (block_number, _, results) = self.client.call_contract::<(U256, [u8; 32], Vec<(bool, Vec<u8>)>)>(
+                self.multicall,
+                "tryBlockAndAggregate(bool,(address,bytes)[])",
+                vec![Token::Bool(false), Token::Array(calls)],
+            ).await?;
+
+            block = block.max(block_number.to::<u64>());
+            balances.extend(results.into_iter().map(|(success, data)| {
+                (success && data.len() >= 32).then(|| U256::from_be_slice(&data[..32]))
+            }));
+        }
+
+        Ok(BalanceSnapshot { block, balances })
+    }
+}
+
+/// Posts holding changes as JSON to a webhook
+pub struct WebhookHoldingHook {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookHoldingHook {
+    pub fn new(url: String) -> Self {
+        Self { client: reqwest::Client::new(), url }
+    }
+}
+
+#[async_trait]
+impl HoldingChangeHook for WebhookHoldingHook {
+    async fn holding_changed(&self, change: &HoldingChange) {
+        info!("Holding of {:?} in {:?} changed from {} to {}", change.wallet_address, change.token_address, change.previous_balance, change.balance);
+        if let Err(e) = self.client.post(&self.url).json(change).send().await.and_then(|r| r.error_for_status()) {
+            warn!("Holding change webhook failed: {}", e);
+        }
+    }
+}
+
+/// Periodically re-sync holdings of every verified wallet
+pub fn spawn_holdings_sync(user_service: Arc<UserService>, interval: std::time::Duration) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match user_service.sync_verified_holdings().await {
+                Ok(synced) => debug!("Synced holdings of {} verified wallets", synced),
+                Err(e) => warn!("Holdings sync failed: {}", e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct StaticUniverse(Vec<TrackedToken>);
+
+    #[async_trait]
+    impl TokenUniverse for StaticUniverse {
+        async fn tracked_tokens(&self) -> Result<Vec<TrackedToken>, Error> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockBalances {
+        balances: Mutex<HashMap<Address, U256>>,
+        reads: AtomicUsize,
+    }
+
+    impl MockBalances {
+        fn set(&self, token: Address, balance: u64) {
+            self.balances.lock().unwrap().insert(token, U256::from(balance));
+        }
+    }
+
+    #[async_trait]
+    impl BalanceReader for MockBalances {
+        async fn balances(&self, _wallet: Address, tokens: &[Address]) -> Result<BalanceSnapshot, Error> {
+            let reads = self.reads.fetch_add(1, Ordering::SeqCst) as u64;
+            let balances = self.balances.lock().unwrap();
+            Ok(BalanceSnapshot {
+                block: 100 + reads,
+                balances: tokens.iter().map(|t| Some(balances.get(t).copied().unwrap_or(U256::ZERO))).collect(),
+            })
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingHook(Mutex<Vec<HoldingChange>>);
+
+    #[async_trait]
+    impl HoldingChangeHook for RecordingHook {
+        async fn holding_changed(&self, change: &HoldingChange) {
+            self.0.lock().unwrap().push(change.clone());
+        }
+    }
+
+    fn token(byte: u8, price: u64) -> TrackedToken {
+        TrackedToken {
+            kind: HoldingKind::Treasury,
+            id: [byte; 32],
+            token_address: Address::repeat_byte(byte),
+            name: format!("Treasury {}", byte),
+            symbol: format!("T{}", byte),
+            price: U256::from(price),
+            yield_rate: 450,
+            maturity_date: 1_900_000_000,
+        }
+    }
+
+    fn sync(balances: Arc<MockBalances>, config: HoldingsSyncConfig) -> HoldingsSync {
+        let universe = Arc::new(StaticUniverse(vec![token(1, 1), token(2, 3), token(3, 1)]));
+        HoldingsSync::new(universe, balances, config)
+    }
+
+    #[tokio::test]
+    async fn test_allocations_exclude_dust() {
+        let balances = Arc::new(MockBalances::default());
+        balances.set(Address::repeat_byte(1), 1_000);
+        balances.set(Address::repeat_byte(2), 1_000);
+        balances.set(Address::repeat_byte(3), 5);
+
+        let sync = sync(balances, HoldingsSyncConfig { dust_threshold: U256::from(10u64), ..Default::default() });
+        let snapshot = sync.snapshot(Address::repeat_byte(9), false).await.unwrap();
+
+        assert_eq!(snapshot.holdings.len(), 3);
+        assert_eq!(snapshot.total_value, U256::from(4_005u64));
+        assert_eq!(snapshot.last_synced_block, 100);
+
+        let allocation = |byte: u8| snapshot.holdings.iter()
+            .find(|h| h.token_address == Address::repeat_byte(byte))
+            .map(|h| (h.allocation_bps, h.is_dust))
+            .unwrap();
+        assert_eq!(allocation(1), (Some(2_500), false));
+        assert_eq!(allocation(2), (Some(7_500), false));
+        // Dust is still listed, just not allocated
+        assert_eq!(allocation(3), (None, true));
+    }
+
+    #[tokio::test]
+    async fn test_cached_snapshot_and_change_hook() {
+        let balances = Arc::new(MockBalances::default());
+        balances.set(Address::repeat_byte(1), 10_000);
+        balances.set(Address::repeat_byte(2), 500);
+        let hook = Arc::new(RecordingHook::default());
+        let sync = sync(balances.clone(), HoldingsSyncConfig::default()).with_change_hook(hook.clone());
+        let wallet = Address::repeat_byte(9);
+
+        sync.snapshot(wallet, false).await.unwrap();
+        balances.set(Address::repeat_byte(1), 9_950);
+        balances.set(Address::repeat_byte(3), 7);
+
+        // Fresh snapshots are served from the cache
+        let cached = sync.snapshot(wallet, false).await.unwrap();
+        assert_eq!(balances.reads.load(Ordering::SeqCst), 1);
+        assert_eq!(cached.holdings.len(), 2);
+
+        // A 0.5% move stays under the 1% threshold; a new holding always notifies
+        let refreshed = sync.snapshot(wallet, true).await.unwrap();
+        assert_eq!(balances.reads.load(Ordering::SeqCst), 2);
+        assert_eq!(refreshed.holdings.len(), 3);
+        assert_eq!(*hook.0.lock().unwrap(), vec![HoldingChange {
+            wallet_address: wallet,
+            token_address: Address::repeat_byte(3),
+            previous_balance: U256::ZERO,
+            balance: U256::from(7u64),
+            block: 101,
+        }]);
+
+        balances.set(Address::repeat_byte(2), 0);
+        sync.sync_wallet(wallet).await.unwrap();
+        let changes = hook.0.lock().unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[1].token_address, Address::repeat_byte(2));
+        assert_eq!(changes[1].balance, U256::ZERO);
+    }
+}
@@ -1,14 +1,29 @@
 use alloy_primitives::{Address, U256, H256, Bytes};
-use ethereum_client::{EthereumClient, Error as EthError};
+use alloy_contract::Token;
+use ethereum_client::{EthereumClient, Error as EthError, SimulationOutcome};
 use serde::{Serialize, Deserialize};
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
 use async_trait::async_trait;
 use thiserror::Error;
+use uuid::Uuid;
 
 // Create and export clients module
 mod clients;
 pub use clients::*;
 
+// Wire types shared with the backend and compliance service
+pub use quantera_types::{
+    TreasuryType,
+    TreasuryStatus,
+    TreasuryOverview,
+    TreasuryMetadata,
+    VerificationStatus,
+    ErrorEnvelope,
+    Currency,
+};
+
 // Create and export yield scheduler
 mod yield_scheduler;
 pub use yield_scheduler::{
@@ -16,6 +31,7 @@ pub use yield_scheduler::{
     YieldDistributionResult,
     MaturityResult,
     TreasurySnapshot,
+    DistributionPreview,
 };
 
 // Create and export user service
@@ -35,10 +51,29 @@ pub use user_service::{
     InstitutionalRegistrationResult,
     PortfolioHolding,
     UserPortfolio,
-    VerificationStatus,
     SmartAccountSetupResult,
 };
 
+// Create and export on-chain holdings sync
+mod holdings_sync;
+pub use holdings_sync::{
+    HoldingsSync,
+    HoldingsSyncConfig,
+    HoldingsSnapshot,
+    HoldingKind,
+    HoldingChange,
+    HoldingChangeHook,
+    TrackedToken,
+    TokenUniverse,
+    BalanceReader,
+    BalanceSnapshot,
+    RegistryTokenUniverse,
+    MulticallBalanceReader,
+    WebhookHoldingHook,
+    spawn_holdings_sync,
+    DEFAULT_MULTICALL_ADDRESS,
+};
+
 // Create and export authentication service
 mod auth_service;
 pub use auth_service::{
@@ -51,9 +86,275 @@ pub use auth_service::{
     TwoFactorSetupResult,
 };
 
+// Create and export pre-trade compliance checks
+mod order_compliance;
+pub use order_compliance::{
+    PreTradeCompliance,
+    PreTradeComplianceConfig,
+    PreTradeMode,
+    TransferPreCheck,
+    ComplianceCheckRecord,
+    PartyRole,
+};
+
+// Create and export pre-trade risk limit checks
+mod order_risk;
+pub use order_risk::{
+    PreTradeRisk,
+    PreTradeRiskConfig,
+    PreTradeRiskCheck,
+    HttpRiskClient,
+    ProposedTrade,
+    RiskDecision,
+    RiskEvaluation,
+    BreachedRiskLimit,
+};
+
+// Create and export metadata validation
+mod metadata_validation;
+pub use metadata_validation::{
+    parse_metadata,
+    MetadataValidationError,
+    MetadataViolation,
+};
+
+// Create and export treasury creation attempt tracking
+mod creation_attempts;
+pub use creation_attempts::{
+    CreationAttempt,
+    CreationAttemptLedger,
+    CreationAttemptStatus,
+    CreationStep,
+    TreasuryCreationParams,
+};
+
+// Create and export bulk treasury status transitions
+mod bulk_status;
+pub use bulk_status::{
+    StatusRegistry,
+    TransitionOutcome,
+    TransitionResult,
+    BulkStatusReport,
+    BulkStatusRequest,
+    BulkStatusState,
+    BulkStatusLedger,
+    apply_bulk_status,
+    transition_allowed,
+};
+
+// Create and export treasury price and status feed
+mod treasury_feed;
+pub use treasury_feed::{
+    TreasuryFeed,
+    TreasuryQuote,
+    FeedMessage,
+    FeedRequest,
+    spawn_registry_sync,
+    DEFAULT_MAX_SUBSCRIPTIONS,
+};
+
+// Create and export oracle-driven treasury price updates
+mod price_updater;
+pub use price_updater::{
+    ChainlinkFeedReader,
+    TreasuryPriceUpdater,
+    PriceUpdateOutcome,
+    PriceUpdateResult,
+    spawn_price_updates,
+};
+
+// Create and export settlement pricing
+mod pricing;
+pub use pricing::{
+    SettlementPrice,
+    DayCount,
+    Accrual,
+    settlement_price,
+    accrued_interest,
+    bill_price,
+    default_settlement_date,
+};
+
+// Create and export treasury symbol uniqueness and reservations
+mod symbol_registry;
+pub use symbol_registry::{
+    SymbolRegistry,
+    SymbolReservation,
+    DEFAULT_RESERVATION_SECS,
+    MAX_RESERVATION_SECS,
+};
+
+// Create and export the contract address registry
+mod contract_registry;
+pub use contract_registry::{
+    ContractAddressRegistry,
+    ContractName,
+    ContractProbe,
+    ContractValidationError,
+    InvalidContract,
+    DEFAULT_DEPLOYMENT_ENV,
+};
+
+// Create and export delivery-versus-payment trade settlement
+mod settlement;
+pub use settlement::{
+    SettlementEngine,
+    Settlement,
+    SettlementAttempt,
+    SettlementStatus,
+    SettlementFilter,
+    SettlementConfig,
+    SettlementChain,
+    ContractSettlementChain,
+    SettlementNotifier,
+    WebhookSettlementNotifier,
+    spawn_settlement_processing,
+};
+
+// Create and export order reconciliation against trading contract events
+mod reconciliation;
+pub use reconciliation::{
+    OrderReconciler,
+    OrderLedger,
+    LocalOrder,
+    LocalOrderStatus,
+    OrderEvent,
+    OrderEventKind,
+    OrderEventSource,
+    ContractOrderEventSource,
+    CursorStore,
+    MemoryCursorStore,
+    FileCursorStore,
+    Discrepancy,
+    DiscrepancyKind,
+    Resolution,
+    ReconciliationReport,
+    ReconciliationConfig,
+    DriftAlerter,
+    LogDriftAlerter,
+    WebhookDriftAlerter,
+    spawn_reconciliation,
+};
+
+// Create and export order ledger replay from trading contract history
+mod replay;
+pub use replay::{
+    OrderReplayer,
+    ReplayConfig,
+    ReplayRequest,
+    ReplayReport,
+    ReplayTarget,
+    ReplayCheckpoint,
+    CheckpointStore,
+    MemoryCheckpointStore,
+    FileCheckpointStore,
+    ShadowLedger,
+    ValidationReport,
+    SpotMismatch,
+};
+
+// Create and export the platform fee schedule
+mod business_days;
+pub use business_days::{
+    BusinessCalendar,
+    BusinessDayConvention,
+    DateAdjustment,
+    Holiday,
+    NewHoliday,
+};
+
+mod fees;
+pub use fees::{
+    FeeSchedule,
+    FeeOperation,
+    FeeModel,
+    FeeTier,
+    FeeRule,
+    NewFeeRule,
+    FeeAccrual,
+    FeeReportRow,
+    ReportPeriod,
+    report_csv,
+    report_fees_in,
+};
+
+// Create and export yield withholding tax
+mod withholding;
+pub use withholding::{
+    WithholdingTable,
+    WithholdingRule,
+    WithholdingEntry,
+    TaxDocument,
+    TaxProfile,
+    TaxProfileUpdate,
+    RateBasis,
+    HolderWithholding,
+    HolderBalances,
+    DocumentationNotice,
+    DocumentationNotifier,
+    LogDocumentationNotifier,
+    WebhookDocumentationNotifier,
+    RemittanceRow,
+    remittance_csv,
+};
+
+// Create and export yield auto-compounding
+mod auto_compound;
+pub use auto_compound::{
+    AutoCompounder,
+    AutomationConfig,
+    CompoundExecutor,
+    ContractCompoundExecutor,
+    SwapQuote,
+    SwapExecution,
+    CompoundEvent,
+    CompoundStatus,
+    CompoundFailure,
+    CompoundFailureNotice,
+    CompoundNotifier,
+    LogCompoundNotifier,
+    WebhookCompoundNotifier,
+    spawn_auto_compound,
+};
+
+// Create and export subscription and redemption windows
+mod dealing_windows;
+pub use dealing_windows::{
+    DealingWindows,
+    DealingTerms,
+    DealingWindow,
+    RecurringWindows,
+    RedemptionNotice,
+    AcquisitionSource,
+    LockupLot,
+    LockupStatus,
+    DealingRejection,
+    DealingRejectionReason,
+};
+
+// Create and export liquidity pool position analytics
+mod lp_analytics;
+pub use lp_analytics::{
+    LpAnalytics,
+    LpDataSource,
+    ContractLpDataSource,
+    PositionAnalytics,
+    ImpermanentLoss,
+    FeeApr,
+    AprWindow,
+    TokenAmounts,
+};
+
 // Create and export API module
 pub mod api;
 
+// Prometheus adapter for Ethereum client metrics
+pub mod metrics;
+pub use metrics::PrometheusMetricsRecorder;
+
+// Commands behind the treasury_admin binary
+pub mod admin_cli;
+
 /// Custom error type for Treasury service operations
 #[derive(Debug, Error)]
 pub enum Error {
@@ -84,9 +385,40 @@ pub enum Error {
     #[error("Invalid parameter: {0}")]
     InvalidParameter(String),
     
+    #[error("Compliance check failed: {0}")]
+    ComplianceRejected(String),
+    
+    #[error("Risk limit breached: {0}")]
+    RiskLimitBreached(String),
+    
+    #[error("Dealing rejected: {0}")]
+    DealingRejected(DealingRejection),
+    
+    #[error("Batch failed: {0}")]
+    BatchCallFailed(clients::smart_account_client::BatchFailure),
+    
     #[error("Unauthorized: {0}")]
     Unauthorized(String),
     
+    #[error("Invalid treasury metadata: {0}")]
+    InvalidMetadata(MetadataValidationError),
+    
+    #[error("Treasury creation attempt {attempt_id} failed at {step:?}: {source}")]
+    CreationFailed {
+        attempt_id: Uuid,
+        step: CreationStep,
+        source: Box<Error>,
+    },
+    
+    #[error("Symbol {symbol} is already held by {holder}")]
+    SymbolTaken {
+        symbol: String,
+        holder: String,
+    },
+    
+    #[error("Invalid contract addresses for {0}")]
+    InvalidContracts(ContractValidationError),
+    
     #[error("Internal error: {0}")]
     Internal(String),
     
@@ -94,28 +426,13 @@ pub enum Error {
     Unimplemented(String),
 }
 
-/// Treasury types
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
-pub enum TreasuryType {
-    TBill,
-    TNote,
-    TBond,
-}
-
-/// Treasury status
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
-pub enum TreasuryStatus {
-    Active,
-    Matured,
-    Redeemed,
-}
-
 /// Treasury information structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TreasuryInfo {
     pub token_address: Address,
     pub metadata_uri: String,
     pub status: TreasuryStatus,
+    #[serde(with = "quantera_types::wire::u256_decimal")]
     pub current_price: U256,
     pub issuance_date: u64,
     pub maturity_date: u64,
@@ -124,35 +441,79 @@ pub struct TreasuryInfo {
     pub historical_data_hash: H256,
 }
 
-/// Treasury overview for listing
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TreasuryOverview {
-    pub token_id: [u8; 32],
-    pub token_address: Address,
-    pub name: String,
-    pub symbol: String,
-    pub treasury_type: TreasuryType,
-    pub current_price: U256,
-    pub yield_rate: u64,
-    pub maturity_date: u64,
-    pub status: TreasuryStatus,
+/// Trait for simulating contract writes before they are broadcast.
+///
+/// The registry client runs every write through a simulator first so transactions that
+/// would revert on stale state are rejected without spending gas. EthereumClient is the
+/// production implementation; tests can substitute a mock.
+#[async_trait]
+pub trait TransactionSimulator: Send + Sync + std::fmt::Debug {
+    async fn simulate(
+        &self,
+        to: Address,
+        function: &str,
+        args: Vec<Token>,
+    ) -> Result<SimulationOutcome, Error>;
 }
 
-/// Treasury metadata
+#[async_trait]
+impl TransactionSimulator for EthereumClient {
+    async fn simulate(
+        &self,
+        to: Address,
+        function: &str,
+        args: Vec<Token>,
+    ) -> Result<SimulationOutcome, Error> {
+        self.simulate_transaction(to, function, args).await.map_err(Error::EthereumClient)
+    }
+}
+
+/// Trait for checking whether a token id is already taken in the registry.
+///
+/// Registration checks every generated id against the registry before sending it, since
+/// the contract only rejects a duplicate with an opaque revert. EthereumClient reads the
+/// registry's `treasuries` mapping; tests can substitute a mock.
+#[async_trait]
+pub trait TokenIdLookup: Send + Sync + std::fmt::Debug {
+    async fn is_registered(&self, registry: Address, token_id: [u8; 32]) -> Result<bool, Error>;
+}
+
+#[async_trait]
+impl TokenIdLookup for EthereumClient {
+    async fn is_registered(&self, registry: Address, token_id: [u8; 32]) -> Result<bool, Error> {
+        let entry = self.call_contract::<(Address, String, u8, U256, u64, u64, u64, Address, H256)>(
+            registry,
+            "treasuries(bytes32)",
+            vec![token_id.into()],
+        ).await.map_err(Error::EthereumClient)?;
+        
+        Ok(entry.0 != Address::ZERO)
+    }
+}
+
+/// One row of a batch registration
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TreasuryMetadata {
-    pub name: String,
-    pub symbol: String,
-    pub description: String,
-    pub issuer_name: String,
+pub struct TreasuryRegistration {
+    pub token_address: Address,
+    pub metadata_uri: String,
     pub treasury_type: TreasuryType,
-    pub face_value: String,
     pub issuance_date: u64,
     pub maturity_date: u64,
     pub yield_rate: u64,
-    pub image_uri: Option<String>,
-    pub external_url: Option<String>,
-    pub additional_details: Option<serde_json::Value>,
+    #[serde(default)]
+    pub tranche: Option<String>,
+}
+
+impl TreasuryRegistration {
+    pub fn token_id(&self) -> [u8; 32] {
+        TreasuryRegistryClient::generate_token_id(
+            self.token_address,
+            self.treasury_type,
+            self.issuance_date,
+            self.maturity_date,
+            self.tranche.as_deref(),
+        )
+    }
 }
 
 /// Client for interacting with the TreasuryRegistry contract
@@ -160,18 +521,98 @@ pub struct TreasuryMetadata {
 pub struct TreasuryRegistryClient {
     client: Arc<EthereumClient>,
     contract_address: Address,
+    simulator: Arc<dyn TransactionSimulator>,
+    token_id_lookup: Arc<dyn TokenIdLookup>,
+    force: bool,
+    prevented_reverts: Arc<AtomicU64>,
+    batch_status_updates: bool,
 }
 
 impl TreasuryRegistryClient {
     /// Create a new TreasuryRegistryClient
     pub async fn new(client: Arc<EthereumClient>, address: Address) -> Self {
         Self {
+            simulator: client.clone(),
+            token_id_lookup: client.clone(),
             client,
             contract_address: address,
+            force: false,
+            prevented_reverts: Arc::new(AtomicU64::new(0)),
+            batch_status_updates: false,
         }
     }
     
+    /// Replace the simulator used for pre-flight checks
+    pub fn with_simulator(mut self, simulator: Arc<dyn TransactionSimulator>) -> Self {
+        self.simulator = simulator;
+        self
+    }
+    
+    /// Replace the lookup used to detect token id collisions
+    pub fn with_token_id_lookup(mut self, lookup: Arc<dyn TokenIdLookup>) -> Self {
+        self.token_id_lookup = lookup;
+        self
+    }
+    
+    /// Send status updates of several treasuries as one `multicall(bytes[])` transaction
+    ///
+    /// Only enable this for registry deployments exposing a self-multicall that keeps
+    /// `msg.sender`; Multicall3 cannot be used because status updates are issuer- or admin-only.
+    pub fn with_batched_status_updates(mut self, enabled: bool) -> Self {
+        self.batch_status_updates = enabled;
+        self
+    }
+    
+    /// Whether status updates can be batched
+    pub fn batches_status_updates(&self) -> bool {
+        self.batch_status_updates
+    }
+    
+    /// Get a handle that skips (or re-enables) pre-flight simulation
+    ///
+    /// Use `with_force(true)` when simulation is known to be unreliable, e.g. when the
+    /// write depends on state changed earlier in the same block.
+    pub fn with_force(&self, force: bool) -> Self {
+        let mut client = self.clone();
+        client.force = force;
+        client
+    }
+    
+    /// Number of writes rejected because their simulation reverted
+    pub fn prevented_reverts(&self) -> u64 {
+        self.prevented_reverts.load(Ordering::Relaxed)
+    }
+    
+    /// Simulate a write and broadcast it only if the simulation succeeds
+    async fn send_simulated(&self, function: &str, args: Vec<Token>) -> Result<(), Error> {
+        if !self.force {
+            let outcome = self.simulator.simulate(
+                self.contract_address,
+                function,
+                args.clone(),
+            ).await?;
+            
+            if let SimulationOutcome::Reverted(reason) = outcome {
+                self.prevented_reverts.fetch_add(1, Ordering::Relaxed);
+                tracing::warn!("Simulation of {} reverted, not broadcasting: {}", function, reason);
+                return Err(Error::InvalidState(format!("{} would revert: {}", function, reason)));
+            }
+        }
+        
+        self.client.send_transaction(
+            self.contract_address,
+            function,
+            args,
+        ).await.map_err(Error::EthereumClient)?;
+        
+        Ok(())
+    }
+    
     /// Register a new treasury
+    ///
+    /// The token id is derived from the token address, type and dates, plus `tranche` when
+    /// given. Registering an id that already exists fails with `Error::InvalidState` naming
+    /// the conflicting id; pass a distinct tranche to register the same instrument again.
     pub async fn register_treasury(
         &self,
         token_address: Address,
@@ -180,9 +621,11 @@ impl TreasuryRegistryClient {
         issuance_date: u64,
         maturity_date: u64,
         yield_rate: u64,
+        tranche: Option<&str>,
     ) -> Result<[u8; 32], Error> {
         // Generate a unique token ID
-        let token_id = Self::generate_token_id(token_address, treasury_type, issuance_date, maturity_date);
+        let token_id = Self::generate_token_id(token_address, treasury_type, issuance_date, maturity_date, tranche);
+        self.ensure_token_id_available(token_id).await?;
         
         // Convert treasury type to uint8
         let treasury_type_value = match treasury_type {
@@ -209,30 +652,109 @@ impl TreasuryRegistryClient {
         Ok(result)
     }
     
+    /// Check each row of a batch for token id collisions, against the registry and against
+    /// earlier rows of the same batch. Returns the row's token id or the collision error.
+    pub async fn check_registrations(&self, rows: &[TreasuryRegistration]) -> Vec<Result<[u8; 32], Error>> {
+        let mut seen = std::collections::HashSet::new();
+        let mut results = Vec::with_capacity(rows.len());
+        
+        for row in rows {
+            let token_id = row.token_id();
+            let result = if !seen.insert(token_id) {
+                Err(Error::InvalidState(format!(
+                    "token id already registered: 0x{} (duplicate within batch)", hex::encode(token_id)
+                )))
+            } else {
+                self.ensure_token_id_available(token_id).await.map(|_| token_id)
+            };
+            results.push(result);
+        }
+        
+        results
+    }
+    
+    /// Register a batch of treasuries. Rows that collide are reported and skipped; the
+    /// remaining rows are still registered.
+    pub async fn register_treasuries(&self, rows: &[TreasuryRegistration]) -> Vec<Result<[u8; 32], Error>> {
+        let checks = self.check_registrations(rows).await;
+        let mut results = Vec::with_capacity(rows.len());
+        
+        for (row, check) in rows.iter().zip(checks) {
+            let result = match check {
+                Ok(_) => self.register_treasury(
+                    row.token_address,
+                    &row.metadata_uri,
+                    row.treasury_type,
+                    row.issuance_date,
+                    row.maturity_date,
+                    row.yield_rate,
+                    row.tranche.as_deref(),
+                ).await,
+                Err(e) => Err(e),
+            };
+            results.push(result);
+        }
+        
+        results
+    }
+    
+    async fn ensure_token_id_available(&self, token_id: [u8; 32]) -> Result<(), Error> {
+        if self.token_id_lookup.is_registered(self.contract_address, token_id).await? {
+            tracing::warn!("Token id collision on registration: 0x{}", hex::encode(token_id));
+            return Err(Error::InvalidState(format!("token id already registered: 0x{}", hex::encode(token_id))));
+        }
+        Ok(())
+    }
+    
     /// Update treasury status
     pub async fn update_treasury_status(
         &self,
         token_id: [u8; 32],
         status: TreasuryStatus,
     ) -> Result<(), Error> {
-        // Convert status to uint8
-        let status_value = match status {
-            TreasuryStatus::Active => 0u8,
-            TreasuryStatus::Matured => 1u8,
-            TreasuryStatus::Redeemed => 2u8,
-        };
-        
-        // Call the contract
-        self.client.send_transaction(
-            self.contract_address,
+        // Simulate, then call the contract
+        self.send_simulated(
             "updateTreasuryStatus(bytes32,uint8)",
             vec![
                 token_id.into(),
-                status_value.into(),
+                Self::status_value(status).into(),
             ],
-        ).await.map_err(Error::EthereumClient)?;
+        ).await
+    }
+    
+    /// Update the status of several treasuries in one transaction
+    ///
+    /// Requires `with_batched_status_updates`; the whole batch reverts if any update would.
+    pub async fn update_treasury_statuses(
+        &self,
+        token_ids: &[[u8; 32]],
+        status: TreasuryStatus,
+    ) -> Result<(), Error> {
+        if !self.batch_status_updates {
+            return Err(Error::InvalidState("Batched status updates are not enabled for this registry".into()));
+        }
         
-        Ok(())
+        let calls = token_ids.iter()
+            .map(|token_id| {
+                EthereumClient::encode_function_call(
+                    "updateTreasuryStatus(bytes32,uint8)",
+                    vec![(*token_id).into(), Self::status_value(status).into()],
+                )
+                .map(Token::Bytes)
+                .map_err(Error::Encoding)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        
+        self.send_simulated("multicall(bytes[])", vec![Token::Array(calls)]).await
+    }
+    
+    /// Status as the registry's uint8 enum
+    fn status_value(status: TreasuryStatus) -> u8 {
+        match status {
+            TreasuryStatus::Active => 0u8,
+            TreasuryStatus::Matured => 1u8,
+            TreasuryStatus::Redeemed => 2u8,
+        }
     }
     
     /// Update treasury price
@@ -241,17 +763,14 @@ impl TreasuryRegistryClient {
         token_id: [u8; 32],
         new_price: U256,
     ) -> Result<(), Error> {
-        // Call the contract
-        self.client.send_transaction(
-            self.contract_address,
+        // Simulate, then call the contract
+        self.send_simulated(
             "updateTreasuryPrice(bytes32,uint256)",
             vec![
                 token_id.into(),
                 new_price.into(),
             ],
-        ).await.map_err(Error::EthereumClient)?;
-        
-        Ok(())
+        ).await
     }
     
     /// Delegate operator permissions
@@ -261,17 +780,14 @@ impl TreasuryRegistryClient {
         operator_address: Address,
         approved: bool,
     ) -> Result<(), Error> {
-        // Call the contract
-        self.client.send_transaction(
-            self.contract_address,
+        // Simulate, then call the contract
+        self.send_simulated(
             "delegateOperator(address,bool)",
             vec![
                 operator_address.into(),
                 approved.into(),
             ],
-        ).await.map_err(Error::EthereumClient)?;
-        
-        Ok(())
+        ).await
     }
     
     /// Execute as delegated operator
@@ -430,11 +946,15 @@ impl TreasuryRegistryClient {
     }
     
     /// Generate a token ID
-    fn generate_token_id(
+    ///
+    /// Ids without a tranche are unchanged from earlier releases, so existing treasuries
+    /// keep their ids.
+    pub fn generate_token_id(
         token_address: Address,
         treasury_type: TreasuryType,
         issuance_date: u64,
         maturity_date: u64,
+        tranche: Option<&str>,
     ) -> [u8; 32] {
         let treasury_type_value = match treasury_type {
             TreasuryType::TBill => 0u8,
@@ -443,13 +963,19 @@ impl TreasuryRegistryClient {
         };
         
         // Combine elements to create a unique ID
-        let data = [
+        let mut data = [
             token_address.as_bytes(),
             &[treasury_type_value],
             &issuance_date.to_be_bytes(),
             &maturity_date.to_be_bytes(),
         ].concat();
         
+        // Length-prefixed so the salt cannot be confused with the fixed-width fields
+        if let Some(tranche) = tranche.filter(|t| !t.is_empty()) {
+            data.extend_from_slice(&(tranche.len() as u32).to_be_bytes());
+            data.extend_from_slice(tranche.as_bytes());
+        }
+        
         // Hash the data to get the token ID
         let hash = alloy_primitives::keccak256(&data);
         hash
@@ -472,6 +998,9 @@ impl IpfsClient {
     
     /// Upload metadata to IPFS
     pub async fn upload_metadata(&self, metadata: &TreasuryMetadata) -> Result<String, Error> {
+        // Reject invalid documents before pinning
+        metadata.validate().map_err(Error::InvalidMetadata)?;
+        
         // Serialize metadata to JSON
         let json = serde_json::to_string(metadata)
             .map_err(|e| Error::Encoding(format!("Failed to serialize metadata: {}", e)))?;
@@ -483,10 +1012,26 @@ impl IpfsClient {
         Ok(mock_ipfs_hash)
     }
     
-    /// Get metadata from IPFS
+    /// Unpin metadata that is no longer referenced
+    pub async fn unpin_metadata(&self, uri: &str) -> Result<(), Error> {
+        // In a real implementation, this would remove the pin from the IPFS node
+        if !uri.starts_with("ipfs://") {
+            return Err(Error::Ipfs(format!("Invalid IPFS URI: {}", uri)));
+        }
+        
+        Ok(())
+    }
+    
+    /// Get metadata from IPFS, rejecting documents that fail validation
     pub async fn get_metadata(&self, uri: &str) -> Result<TreasuryMetadata, Error> {
+        let document = self.fetch_document(uri).await?;
+        parse_metadata(&document).map_err(Error::InvalidMetadata)
+    }
+    
+    /// Fetch the raw metadata document
+    async fn fetch_document(&self, uri: &str) -> Result<Vec<u8>, Error> {
         // In a real implementation, this would fetch the JSON from IPFS
-        // For now, we'll just return a mock metadata object
+        // For now, we'll just return a mock metadata document
         
         // Check if the URI is an IPFS URI
         if !uri.starts_with("ipfs://") {
@@ -501,15 +1046,96 @@ impl IpfsClient {
             issuer_name: "U.S. Department of the Treasury".to_string(),
             treasury_type: TreasuryType::TNote,
             face_value: "1000.00".to_string(),
+            currency: Currency::USD,
             issuance_date: chrono::Utc::now().timestamp() as u64,
             maturity_date: chrono::Utc::now().timestamp() as u64 + 10 * 365 * 24 * 60 * 60, // 10 years
             yield_rate: 300, // 3.00% (in basis points)
             image_uri: Some("https://example.com/treasury.png".to_string()),
             external_url: Some("https://www.treasurydirect.gov/".to_string()),
             additional_details: None,
+            tranche: None,
         };
         
-        Ok(metadata)
+        serde_json::to_vec(&metadata)
+            .map_err(|e| Error::Encoding(format!("Failed to serialize metadata: {}", e)))
+    }
+}
+
+/// Burn recorded against a redemption settlement
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RedemptionBurn {
+    pub settlement_id: [u8; 32],
+    pub token_id: [u8; 32],
+    pub from: Address,
+    pub amount: U256,
+    pub completed: bool,
+    pub recorded_at: u64,
+}
+
+/// Ledger of redemption settlements that have been burned (or are being burned).
+///
+/// A settlement can only be burned once; a failed burn releases the reservation so it
+/// can be retried.
+#[derive(Debug, Default)]
+pub struct RedemptionBurnLedger {
+    burns: Mutex<HashMap<[u8; 32], RedemptionBurn>>,
+}
+
+impl RedemptionBurnLedger {
+    /// Reserve a settlement for burning, rejecting settlements already burned or in flight
+    pub fn reserve(
+        &self,
+        settlement_id: [u8; 32],
+        token_id: [u8; 32],
+        from: Address,
+        amount: U256,
+    ) -> Result<(), Error> {
+        let mut burns = self.burns.lock()
+            .map_err(|_| Error::Internal("Redemption ledger lock poisoned".into()))?;
+        
+        if let Some(existing) = burns.get(&settlement_id) {
+            return Err(Error::InvalidState(format!(
+                "Redemption settlement {} already {}",
+                hex::encode(settlement_id),
+                if existing.completed { "burned" } else { "being burned" },
+            )));
+        }
+        
+        burns.insert(settlement_id, RedemptionBurn {
+            settlement_id,
+            token_id,
+            from,
+            amount,
+            completed: false,
+            recorded_at: chrono::Utc::now().timestamp() as u64,
+        });
+        
+        Ok(())
+    }
+    
+    /// Mark a reserved settlement as burned
+    pub fn complete(&self, settlement_id: [u8; 32]) -> Result<RedemptionBurn, Error> {
+        let mut burns = self.burns.lock()
+            .map_err(|_| Error::Internal("Redemption ledger lock poisoned".into()))?;
+        
+        let burn = burns.get_mut(&settlement_id)
+            .ok_or_else(|| Error::NotFound(format!("Redemption settlement {}", hex::encode(settlement_id))))?;
+        burn.completed = true;
+        burn.recorded_at = chrono::Utc::now().timestamp() as u64;
+        
+        Ok(burn.clone())
+    }
+    
+    /// Release a reservation after a failed burn
+    pub fn release(&self, settlement_id: [u8; 32]) {
+        if let Ok(mut burns) = self.burns.lock() {
+            burns.remove(&settlement_id);
+        }
+    }
+    
+    /// Get the burn recorded for a settlement
+    pub fn get(&self, settlement_id: &[u8; 32]) -> Option<RedemptionBurn> {
+        self.burns.lock().ok().and_then(|burns| burns.get(settlement_id).cloned())
     }
 }
 
@@ -551,6 +1177,8 @@ pub trait ComplianceChecker: Send + Sync {
 ///   - Deploying the treasury token contract (via TokenDeployer)
 ///   - Registering the treasury in the registry contract
 ///   - Enforcing compliance checks (via ComplianceChecker)
+///   - Rolling back and resuming failed creations (via CreationAttemptLedger)
+///   - Accruing issuance fees (via FeeSchedule, when attached)
 ///
 /// The service is constructed with concrete implementations of TokenDeployer and ComplianceChecker,
 /// which can be swapped for mocks in tests or real implementations in production.
@@ -559,6 +1187,12 @@ pub struct TreasuryService {
     ipfs_client: IpfsClient,
     token_deployer: Box<dyn TokenDeployer>,
     compliance_checker: Box<dyn ComplianceChecker>,
+    redemption_burns: RedemptionBurnLedger,
+    creation_attempts: CreationAttemptLedger,
+    bulk_status: BulkStatusLedger,
+    symbols: SymbolRegistry,
+    fees: Option<Arc<FeeSchedule>>,
+    calendar: Option<Arc<BusinessCalendar>>,
 }
 
 impl TreasuryService {
@@ -574,10 +1208,46 @@ impl TreasuryService {
             ipfs_client,
             token_deployer,
             compliance_checker,
+            redemption_burns: RedemptionBurnLedger::default(),
+            creation_attempts: CreationAttemptLedger::default(),
+            bulk_status: BulkStatusLedger::default(),
+            symbols: SymbolRegistry::default(),
+            fees: None,
+            calendar: None,
+        }
+    }
+    
+    /// Accrue an issuance fee on every treasury created; creation is refused while no
+    /// issuance fee is configured
+    pub fn with_fee_schedule(mut self, fees: Arc<FeeSchedule>) -> Self {
+        self.fees = Some(fees);
+        self
+    }
+    
+    /// Warn when a new treasury's maturity date is not a business day
+    pub fn with_calendar(mut self, calendar: Arc<BusinessCalendar>) -> Self {
+        self.calendar = Some(calendar);
+        self
+    }
+    
+    /// Why a maturity date will settle on a different day, if it will
+    pub fn maturity_warning(&self, maturity_date: u64) -> Option<String> {
+        let calendar = self.calendar.as_ref()?;
+        match calendar.adjust_timestamp(maturity_date) {
+            Ok(Some(adjustment)) => Some(format!(
+                "Maturity date {} is not a {} business day; it settles on {} ({:?})",
+                adjustment.unadjusted, adjustment.market, adjustment.adjusted, adjustment.convention
+            )),
+            Ok(None) => None,
+            Err(e) => Some(format!("Maturity date could not be checked against the {} calendar: {}", calendar.market(), e)),
         }
     }
     
     /// Create a new treasury token
+    ///
+    /// Each step is recorded in a creation attempt. If a step fails, completed steps are
+    /// compensated (metadata unpinned, a deployed contract marked orphaned) and the error
+    /// carries the attempt id so the creation can be resumed with `resume_treasury_creation`.
     pub async fn create_treasury_token(
         &self,
         name: String,
@@ -585,10 +1255,12 @@ impl TreasuryService {
         total_supply: u64,
         treasury_type: TreasuryType,
         face_value: U256,
+        currency: Currency,
         yield_rate: u64,
         issuance_date: u64,
         maturity_date: u64,
         issuer: Address,
+        tranche: Option<String>,
     ) -> Result<TreasuryOverview, Error> {
         // Compliance check: ensure issuer passes KYC/AML
         if !self.compliance_checker.is_compliant(issuer)? {
@@ -596,54 +1268,233 @@ impl TreasuryService {
             return Err(Error::Unauthorized("Issuer failed compliance checks".into()));
         }
         
-        // Create metadata
-        let metadata = TreasuryMetadata {
-            name: name.clone(),
+        if let Some(fees) = &self.fees {
+            fees.rule_at(FeeOperation::Issuance, chrono::Utc::now())?;
+        }
+        
+        // Hold the symbol for the attempt so concurrent creations cannot take it
+        let now = chrono::Utc::now().timestamp() as u64;
+        self.symbols.claim(&symbol, issuer, "pending creation", now)?;
+        
+        let attempt = self.creation_attempts.start(TreasuryCreationParams {
+            name,
             symbol: symbol.clone(),
-            description: format!("{} {}", name, match treasury_type {
-                TreasuryType::TBill => "Bill",
-                TreasuryType::TNote => "Note",
-                TreasuryType::TBond => "Bond",
-            }),
-            issuer_name: "U.S. Department of the Treasury".to_string(),
+            total_supply,
             treasury_type,
-            face_value: face_value.to_string(),
+            face_value,
+            currency,
+            yield_rate,
             issuance_date,
             maturity_date,
-            yield_rate,
-            image_uri: Some("https://example.com/treasury.png".to_string()),
-            external_url: Some("https://www.treasurydirect.gov/".to_string()),
-            additional_details: None,
-        };
+            issuer,
+            tranche,
+        })?;
+        self.symbols.reassign(&symbol, &format!("creation attempt {}", attempt.attempt_id))?;
         
-        // Upload metadata to IPFS
-        let metadata_uri = self.ipfs_client.upload_metadata(&metadata).await?;
+        self.run_creation_attempt(attempt).await
+    }
+    
+    /// Resume a failed creation attempt, reusing a token contract it already deployed
+    pub async fn resume_treasury_creation(&self, attempt_id: Uuid) -> Result<TreasuryOverview, Error> {
+        let attempt = self.creation_attempts.get(&attempt_id)
+            .ok_or_else(|| Error::NotFound(format!("Creation attempt {}", attempt_id)))?;
+        
+        // The issuer may have lost compliance since the attempt failed
+        if !self.compliance_checker.is_compliant(attempt.params.issuer)? {
+            tracing::error!("Issuer failed compliance checks: {}", attempt.params.issuer);
+            return Err(Error::Unauthorized("Issuer failed compliance checks".into()));
+        }
+        
+        if let Some(fees) = &self.fees {
+            fees.rule_at(FeeOperation::Issuance, chrono::Utc::now())?;
+        }
         
-        // Deploy the actual treasury token contract and get its address
-        let token_address = self.token_deployer.deploy_token(&name, &symbol, total_supply, issuer)?;
-        tracing::info!("[AUDIT] Treasury token deployed for {} ({}), supply: {} at {:?}", name, symbol, total_supply, token_address);
+        let attempt = self.creation_attempts.begin_resume(attempt_id)?;
+        tracing::info!("[AUDIT] Resuming treasury creation attempt {} (resume {})", attempt_id, attempt.resume_count);
+        
+        self.run_creation_attempt(attempt).await
+    }
+    
+    /// Hold a symbol for an issuer while it onboards
+    pub fn reserve_symbol(&self, symbol: &str, issuer: Address, hold_secs: u64) -> Result<SymbolReservation, Error> {
+        let reservation = self.symbols.reserve(symbol, issuer, hold_secs, chrono::Utc::now().timestamp() as u64)?;
+        tracing::info!("[AUDIT] Symbol {} reserved by {} until {}", reservation.symbol, issuer, reservation.expires_at);
+        Ok(reservation)
+    }
+    
+    /// Release an issuer's reservation before it expires
+    pub fn release_symbol(&self, symbol: &str, issuer: Address) -> Result<(), Error> {
+        self.symbols.release(symbol, issuer)
+    }
+    
+    /// Get a creation attempt and the steps it has completed
+    pub fn get_creation_attempt(&self, attempt_id: &Uuid) -> Option<CreationAttempt> {
+        self.creation_attempts.get(attempt_id)
+    }
+    
+    /// Deployed contracts left unregistered by failed creation attempts
+    pub fn orphaned_tokens(&self) -> Vec<CreationAttempt> {
+        self.creation_attempts.orphaned_tokens()
+    }
+    
+    /// Move treasuries to `new_status` (Active to Matured, or Matured to Redeemed)
+    ///
+    /// With `dry_run` only the per-treasury outcome is reported and nothing is sent.
+    /// Invalid transitions and failed writes are reported per treasury without stopping
+    /// the rest; `BulkStatusReport::failed_ids` lists the ones to retry.
+    pub async fn bulk_update_status(
+        &self,
+        token_ids: &[[u8; 32]],
+        new_status: TreasuryStatus,
+        dry_run: bool,
+    ) -> Result<BulkStatusReport, Error> {
+        if token_ids.is_empty() {
+            return Err(Error::InvalidParameter("No treasuries given".into()));
+        }
+        if new_status == TreasuryStatus::Active {
+            return Err(Error::InvalidParameter("Treasuries cannot be moved back to Active".into()));
+        }
+        
+        let report = apply_bulk_status(&self.registry_client, token_ids, new_status, dry_run).await;
+        if !dry_run {
+            tracing::info!(
+                "[AUDIT] Bulk status change to {:?}: {} of {} treasuries failed",
+                new_status, report.failed_ids().len(), token_ids.len()
+            );
+        }
+        Ok(report)
+    }
+    
+    /// Preview a bulk status change and hold it until another admin approves it
+    pub async fn request_bulk_status(
+        &self,
+        token_ids: Vec<[u8; 32]>,
+        new_status: TreasuryStatus,
+        requested_by: &str,
+    ) -> Result<BulkStatusRequest, Error> {
+        let preview = self.bulk_update_status(&token_ids, new_status, true).await?;
+        let request = self.bulk_status.submit(token_ids, new_status, requested_by, preview)?;
+        tracing::info!("[AUDIT] Bulk status request {} to {:?} submitted by {}", request.request_id, new_status, requested_by);
+        Ok(request)
+    }
+    
+    /// Approve a pending bulk status change and run it
+    pub async fn approve_bulk_status(&self, request_id: Uuid, approver: &str) -> Result<BulkStatusRequest, Error> {
+        let request = self.bulk_status.approve(request_id, approver)?;
+        tracing::info!("[AUDIT] Bulk status request {} approved by {}", request_id, approver);
+        self.run_bulk_status(request).await
+    }
+    
+    /// Rerun a partially failed bulk status change for its failed treasuries only
+    pub async fn resume_bulk_status(&self, request_id: Uuid) -> Result<BulkStatusRequest, Error> {
+        let request = self.bulk_status.begin_resume(request_id)?;
+        tracing::info!("[AUDIT] Resuming bulk status request {} for {} treasuries", request_id, request.pending_ids().len());
+        self.run_bulk_status(request).await
+    }
+    
+    pub fn get_bulk_status(&self, request_id: &Uuid) -> Option<BulkStatusRequest> {
+        self.bulk_status.get(request_id)
+    }
+    
+    async fn run_bulk_status(&self, request: BulkStatusRequest) -> Result<BulkStatusRequest, Error> {
+        let report = self.bulk_update_status(request.pending_ids(), request.new_status, false).await?;
+        self.bulk_status.record_run(request.request_id, report)
+    }
+    
+    async fn run_creation_attempt(&self, attempt: CreationAttempt) -> Result<TreasuryOverview, Error> {
+        let attempt_id = attempt.attempt_id;
+        
+        match self.run_creation_steps(&attempt).await {
+            Ok(overview) => {
+                if let Some(fees) = &self.fees {
+                    let notional = attempt.params.face_value.saturating_mul(U256::from(attempt.params.total_supply));
+                    let reference = format!("treasury 0x{}", hex::encode(overview.token_id));
+                    // The treasury exists by now; a missing accrual is for finance to reconcile
+                    if let Err(e) = fees.accrue(FeeOperation::Issuance, reference, notional, attempt.params.currency, chrono::Utc::now()) {
+                        tracing::error!("Issuance fee not accrued for {:?}: {}", overview.token_id, e);
+                    }
+                }
+                Ok(overview)
+            }
+            Err((step, e)) => {
+                tracing::error!("Treasury creation attempt {} failed at {:?}: {}", attempt_id, step, e);
+                self.roll_back_creation(attempt_id, step, &e).await;
+                Err(Error::CreationFailed { attempt_id, step, source: Box::new(e) })
+            }
+        }
+    }
+    
+    async fn run_creation_steps(&self, attempt: &CreationAttempt) -> Result<TreasuryOverview, (CreationStep, Error)> {
+        let attempt_id = attempt.attempt_id;
+        let params = &attempt.params;
+        
+        // Upload metadata to IPFS. Always re-pinned on resume since a rollback unpins it;
+        // the URI is content-addressed so it does not change.
+        let step = CreationStep::UploadMetadata;
+        let metadata_uri = self.ipfs_client.upload_metadata(&creation_metadata(params)).await
+            .map_err(|e| (step, e))?;
+        self.creation_attempts.update(attempt_id, |a| {
+            a.metadata_uri = Some(metadata_uri.clone());
+            a.metadata_pinned = true;
+            Ok(())
+        }).and_then(|_| self.creation_attempts.complete_step(attempt_id, step))
+            .map_err(|e| (step, e))?;
+        
+        // Deploy the actual treasury token contract, unless an earlier run already did
+        let step = CreationStep::DeployToken;
+        let token_address = match attempt.token_address {
+            Some(token_address) => {
+                tracing::info!("[AUDIT] Reusing token {:?} deployed by creation attempt {}", token_address, attempt_id);
+                token_address
+            }
+            None => {
+                let token_address = self.token_deployer
+                    .deploy_token(&params.name, &params.symbol, params.total_supply, params.issuer)
+                    .map_err(|e| (step, e))?;
+                tracing::info!("[AUDIT] Treasury token deployed for {} ({}), supply: {} at {:?}", params.name, params.symbol, params.total_supply, token_address);
+                
+                self.creation_attempts.update(attempt_id, |a| {
+                    a.token_address = Some(token_address);
+                    Ok(())
+                }).and_then(|_| self.creation_attempts.complete_step(attempt_id, step))
+                    .map_err(|e| (step, e))?;
+                token_address
+            }
+        };
         
         // Register treasury in the registry
+        let step = CreationStep::RegisterTreasury;
         let token_id = self.registry_client.register_treasury(
             token_address,
             &metadata_uri,
-            treasury_type,
-            issuance_date,
-            maturity_date,
-            yield_rate,
-        ).await?;
+            params.treasury_type,
+            params.issuance_date,
+            params.maturity_date,
+            params.yield_rate,
+            params.tranche.as_deref(),
+        ).await.map_err(|e| (step, e))?;
+        
+        self.creation_attempts.update(attempt_id, |a| {
+            a.token_id = Some(token_id);
+            a.token_orphaned = false;
+            a.status = CreationAttemptStatus::Completed;
+            Ok(())
+        }).and_then(|_| self.creation_attempts.complete_step(attempt_id, step))
+            .and_then(|_| self.symbols.reassign(&params.symbol, &format!("treasury 0x{}", hex::encode(token_id))))
+            .map_err(|e| (step, e))?;
         
         // Create overview
         let overview = TreasuryOverview {
             token_id,
             token_address,
-            name,
-            symbol,
-            treasury_type,
-            current_price: face_value,
-            yield_rate,
-            maturity_date,
+            name: params.name.clone(),
+            symbol: params.symbol.clone(),
+            treasury_type: params.treasury_type,
+            current_price: params.face_value,
+            yield_rate: params.yield_rate,
+            maturity_date: params.maturity_date,
             status: TreasuryStatus::Active,
+            validation_error: None,
         };
         
         // Log event for auditability
@@ -652,6 +1503,38 @@ impl TreasuryService {
         Ok(overview)
     }
     
+    /// Compensate the completed steps of a failed attempt and record the failure
+    async fn roll_back_creation(&self, attempt_id: Uuid, step: CreationStep, error: &Error) {
+        let Some(attempt) = self.creation_attempts.get(&attempt_id) else { return };
+        
+        // A failed unpin leaves the metadata marked pinned for later cleanup
+        let mut metadata_pinned = attempt.metadata_pinned;
+        if let (true, Some(uri)) = (attempt.metadata_pinned, &attempt.metadata_uri) {
+            match self.ipfs_client.unpin_metadata(uri).await {
+                Ok(()) => metadata_pinned = false,
+                Err(e) => tracing::warn!("Failed to unpin {} for creation attempt {}: {}", uri, attempt_id, e),
+            }
+        }
+        
+        // A deployed contract cannot be undone; keep it for resume or later cleanup
+        let token_orphaned = attempt.token_address.is_some();
+        if let Some(token_address) = attempt.token_address {
+            tracing::warn!("[AUDIT] Token {:?} orphaned by failed creation attempt {}", token_address, attempt_id);
+        }
+        
+        let recorded = self.creation_attempts.update(attempt_id, |a| {
+            a.status = CreationAttemptStatus::Failed;
+            a.failed_step = Some(step);
+            a.error = Some(error.to_string());
+            a.metadata_pinned = metadata_pinned;
+            a.token_orphaned = token_orphaned;
+            Ok(())
+        });
+        if let Err(e) = recorded {
+            tracing::error!("Failed to record rollback of creation attempt {}: {}", attempt_id, e);
+        }
+    }
+    
     /// Get treasury details
     pub async fn get_treasury_details(&self, token_id: [u8; 32]) -> Result<TreasuryInfo, Error> {
         self.registry_client.get_treasury_details(token_id).await
@@ -666,9 +1549,9 @@ impl TreasuryService {
         let mut treasuries = Vec::new();
         for token_id in token_ids {
             if let Ok(info) = self.registry_client.get_treasury_details(token_id).await {
-                // Get metadata
-                if let Ok(metadata) = self.ipfs_client.get_metadata(&info.metadata_uri).await {
-                    let overview = TreasuryOverview {
+                // Get metadata; treasuries with invalid metadata are listed from on-chain data only
+                let overview = match self.ipfs_client.get_metadata(&info.metadata_uri).await {
+                    Ok(metadata) => TreasuryOverview {
                         token_id,
                         token_address: info.token_address,
                         name: metadata.name,
@@ -678,10 +1561,27 @@ impl TreasuryService {
                         yield_rate: info.yield_rate,
                         maturity_date: info.maturity_date,
                         status: info.status,
-                    };
-                    
-                    treasuries.push(overview);
-                }
+                        validation_error: None,
+                    },
+                    Err(Error::InvalidMetadata(e)) => {
+                        tracing::warn!("Quarantining treasury 0x{} with invalid metadata at {}: {}", hex::encode(token_id), info.metadata_uri, e);
+                        TreasuryOverview {
+                            token_id,
+                            token_address: info.token_address,
+                            name: String::new(),
+                            symbol: String::new(),
+                            treasury_type: TreasuryType::for_term(info.issuance_date, info.maturity_date),
+                            current_price: info.current_price,
+                            yield_rate: info.yield_rate,
+                            maturity_date: info.maturity_date,
+                            status: info.status,
+                            validation_error: Some(e.to_string()),
+                        }
+                    }
+                    Err(_) => continue,
+                };
+                
+                treasuries.push(overview);
             }
         }
         
@@ -692,6 +1592,140 @@ impl TreasuryService {
     pub async fn update_treasury_price(&self, token_id: [u8; 32], new_price: U256) -> Result<(), Error> {
         self.registry_client.update_treasury_price(token_id, new_price).await
     }
+    
+    /// Mint treasury tokens; only the issuer or its delegated operator, and only while Active
+    pub async fn mint_treasury_tokens(
+        &self,
+        token_id: [u8; 32],
+        caller: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), Error> {
+        let info = self.authorize_token_operator(token_id, caller).await?;
+        ensure_mintable(&info)?;
+        
+        self.token_client(&info).await
+            .mint(to, amount)
+            .await
+            .map_err(map_token_error)?;
+        
+        tracing::info!("[AUDIT] Minted {} of treasury {} to {:?} by {:?}", amount, hex::encode(token_id), to, caller);
+        Ok(())
+    }
+    
+    /// Burn tokens for a redemption settlement, at most once per settlement
+    pub async fn burn_for_redemption(
+        &self,
+        token_id: [u8; 32],
+        caller: Address,
+        settlement_id: [u8; 32],
+        from: Address,
+        amount: U256,
+    ) -> Result<RedemptionBurn, Error> {
+        let info = self.authorize_token_operator(token_id, caller).await?;
+        
+        self.redemption_burns.reserve(settlement_id, token_id, from, amount)?;
+        
+        if let Err(e) = self.token_client(&info).await.burn(from, amount).await {
+            self.redemption_burns.release(settlement_id);
+            return Err(map_token_error(e));
+        }
+        
+        let burn = self.redemption_burns.complete(settlement_id)?;
+        tracing::info!("[AUDIT] Burned {} of treasury {} from {:?} for settlement {}", amount, hex::encode(token_id), from, hex::encode(settlement_id));
+        Ok(burn)
+    }
+    
+    /// Get the burn recorded against a redemption settlement
+    pub fn get_redemption_burn(&self, settlement_id: &[u8; 32]) -> Option<RedemptionBurn> {
+        self.redemption_burns.get(settlement_id)
+    }
+    
+    /// Pause transfers of a treasury token
+    pub async fn pause_treasury_token(&self, token_id: [u8; 32], caller: Address) -> Result<(), Error> {
+        let info = self.authorize_token_operator(token_id, caller).await?;
+        
+        self.token_client(&info).await
+            .pause()
+            .await
+            .map_err(map_token_error)?;
+        
+        tracing::info!("[AUDIT] Treasury {} paused by {:?}", hex::encode(token_id), caller);
+        Ok(())
+    }
+    
+    /// Unpause transfers of a treasury token
+    pub async fn unpause_treasury_token(&self, token_id: [u8; 32], caller: Address) -> Result<(), Error> {
+        let info = self.authorize_token_operator(token_id, caller).await?;
+        
+        self.token_client(&info).await
+            .unpause()
+            .await
+            .map_err(map_token_error)?;
+        
+        tracing::info!("[AUDIT] Treasury {} unpaused by {:?}", hex::encode(token_id), caller);
+        Ok(())
+    }
+    
+    /// Check the caller is the registered issuer or a delegated operator of the issuer
+    async fn authorize_token_operator(&self, token_id: [u8; 32], caller: Address) -> Result<TreasuryInfo, Error> {
+        let info = self.registry_client.get_treasury_details(token_id).await?;
+        
+        if caller != info.issuer
+            && !self.registry_client.is_delegated_operator(info.issuer, caller).await?
+        {
+            tracing::warn!("Unauthorized token operation on {} by {:?}", hex::encode(token_id), caller);
+            return Err(Error::Unauthorized(format!("{:?} is not the issuer or a delegated operator", caller)));
+        }
+        
+        Ok(info)
+    }
+    
+    async fn token_client(&self, info: &TreasuryInfo) -> TreasuryTokenClient {
+        TreasuryTokenClient::new(self.registry_client.client.clone(), info.token_address).await
+    }
+}
+
+/// Metadata document pinned for a treasury creation
+fn creation_metadata(params: &TreasuryCreationParams) -> TreasuryMetadata {
+    TreasuryMetadata {
+        name: params.name.clone(),
+        symbol: params.symbol.clone(),
+        description: format!("{} {}", params.name, match params.treasury_type {
+            TreasuryType::TBill => "Bill",
+            TreasuryType::TNote => "Note",
+            TreasuryType::TBond => "Bond",
+        }),
+        issuer_name: "U.S. Department of the Treasury".to_string(),
+        treasury_type: params.treasury_type,
+        face_value: params.face_value.to_string(),
+        currency: params.currency,
+        issuance_date: params.issuance_date,
+        maturity_date: params.maturity_date,
+        yield_rate: params.yield_rate,
+        image_uri: Some("https://example.com/treasury.png".to_string()),
+        external_url: Some("https://www.treasurydirect.gov/".to_string()),
+        additional_details: None,
+        tranche: params.tranche.clone(),
+    }
+}
+
+/// Minting is only allowed while the treasury is Active
+fn ensure_mintable(info: &TreasuryInfo) -> Result<(), Error> {
+    if info.status != TreasuryStatus::Active {
+        return Err(Error::InvalidState(format!("Cannot mint while treasury is {:?}", info.status)));
+    }
+    Ok(())
+}
+
+/// Convert token client errors into service errors, preserving authorization failures
+fn map_token_error(error: treasury_token_client::Error) -> Error {
+    match error {
+        treasury_token_client::Error::Unauthorized(msg) => Error::Unauthorized(msg),
+        treasury_token_client::Error::InvalidParameter(msg) => Error::InvalidParameter(msg),
+        treasury_token_client::Error::EthereumClient(e) => Error::EthereumClient(e),
+        e => Error::ContractInteraction(e.to_string()),
+    }
 }
 
 #[cfg(test)]
@@ -727,6 +1761,161 @@ mod tests {
         }
     }
 
+    #[derive(Debug)]
+    struct RevertingSimulator {
+        calls: std::sync::Mutex<Vec<String>>,
+    }
+    #[async_trait]
+    impl TransactionSimulator for RevertingSimulator {
+        async fn simulate(
+            &self,
+            _to: Address,
+            function: &str,
+            _args: Vec<Token>,
+        ) -> Result<SimulationOutcome, Error> {
+            self.calls.lock().unwrap().push(function.to_string());
+            Ok(SimulationOutcome::Reverted("Treasury already matured".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registry_write_blocked_by_reverting_simulation() {
+        let simulator = Arc::new(RevertingSimulator { calls: std::sync::Mutex::new(Vec::new()) });
+        let registry_client = TreasuryRegistryClient::new(Arc::new(EthereumClient::new("http://localhost:8545").await.unwrap()), Address::ZERO).await
+            .with_simulator(simulator.clone());
+        
+        let result = registry_client.update_treasury_status([1u8; 32], TreasuryStatus::Matured).await;
+        
+        match result {
+            Err(Error::InvalidState(message)) => assert!(message.contains("Treasury already matured")),
+            other => panic!("expected InvalidState, got {:?}", other),
+        }
+        assert_eq!(registry_client.prevented_reverts(), 1);
+        assert_eq!(
+            simulator.calls.lock().unwrap().as_slice(),
+            &["updateTreasuryStatus(bytes32,uint8)".to_string()]
+        );
+        
+        // Clones made for forced sends share the same counter
+        let _ = registry_client.update_treasury_price([1u8; 32], U256::from(100)).await;
+        assert_eq!(registry_client.with_force(true).prevented_reverts(), 2);
+    }
+
+    /// Lookup that reports a fixed set of token ids as registered
+    #[derive(Debug)]
+    struct RegisteredIds(std::collections::HashSet<[u8; 32]>);
+    
+    #[async_trait]
+    impl TokenIdLookup for RegisteredIds {
+        async fn is_registered(&self, _registry: Address, token_id: [u8; 32]) -> Result<bool, Error> {
+            Ok(self.0.contains(&token_id))
+        }
+    }
+    
+    fn registration(tranche: Option<&str>) -> TreasuryRegistration {
+        TreasuryRegistration {
+            token_address: Address::from_slice(&[0x22; 20]),
+            metadata_uri: "ipfs://QmTest".to_string(),
+            treasury_type: TreasuryType::TNote,
+            issuance_date: 1_700_000_000,
+            maturity_date: 2_000_000_000,
+            yield_rate: 425,
+            tranche: tranche.map(str::to_string),
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_duplicate_token_id_detected() {
+        let existing = registration(None).token_id();
+        let registry_client = TreasuryRegistryClient::new(Arc::new(EthereumClient::new("http://localhost:8545").await.unwrap()), Address::ZERO).await
+            .with_token_id_lookup(Arc::new(RegisteredIds([existing].into_iter().collect())));
+        
+        let row = registration(None);
+        let result = registry_client.register_treasury(
+            row.token_address, &row.metadata_uri, row.treasury_type, row.issuance_date, row.maturity_date, row.yield_rate, None,
+        ).await;
+        match result {
+            Err(Error::InvalidState(message)) => {
+                assert!(message.contains("token id already registered"));
+                assert!(message.contains(&hex::encode(existing)));
+            }
+            other => panic!("expected InvalidState, got {:?}", other),
+        }
+        
+        // Batch rows report collisions individually, including duplicates within the batch
+        let results = registry_client.check_registrations(&[
+            registration(None),
+            registration(Some("B")),
+            registration(Some("B")),
+        ]).await;
+        assert!(matches!(&results[0], Err(Error::InvalidState(_))));
+        assert_eq!(results[1].as_ref().unwrap(), &registration(Some("B")).token_id());
+        match &results[2] {
+            Err(Error::InvalidState(message)) => assert!(message.contains("within batch")),
+            other => panic!("expected InvalidState, got {:?}", other),
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_salted_re_registration() {
+        let unsalted = registration(None).token_id();
+        let salted = registration(Some("tranche-2")).token_id();
+        
+        // Unsalted ids are unchanged; an empty tranche is the same as none
+        assert_eq!(unsalted, TreasuryRegistryClient::generate_token_id(
+            Address::from_slice(&[0x22; 20]), TreasuryType::TNote, 1_700_000_000, 2_000_000_000, None,
+        ));
+        assert_eq!(registration(Some("")).token_id(), unsalted);
+        assert_ne!(salted, unsalted);
+        assert_eq!(salted, registration(Some("tranche-2")).token_id());
+        
+        let registry_client = TreasuryRegistryClient::new(Arc::new(EthereumClient::new("http://localhost:8545").await.unwrap()), Address::ZERO).await
+            .with_token_id_lookup(Arc::new(RegisteredIds([unsalted].into_iter().collect())));
+        let results = registry_client.check_registrations(&[registration(Some("tranche-2"))]).await;
+        assert_eq!(results[0].as_ref().unwrap(), &salted);
+    }
+    
+    fn treasury_info(status: TreasuryStatus) -> TreasuryInfo {
+        TreasuryInfo {
+            token_address: Address::from_slice(&[0x22; 20]),
+            metadata_uri: "ipfs://QmTest".to_string(),
+            status,
+            current_price: U256::from(1000),
+            issuance_date: 1,
+            maturity_date: 2,
+            yield_rate: 100,
+            issuer: Address::from_slice(&[0x11; 20]),
+            historical_data_hash: H256::zero(),
+        }
+    }
+
+    #[test]
+    fn test_mint_blocked_unless_active() {
+        assert!(ensure_mintable(&treasury_info(TreasuryStatus::Active)).is_ok());
+        assert!(matches!(ensure_mintable(&treasury_info(TreasuryStatus::Matured)), Err(Error::InvalidState(_))));
+        assert!(matches!(ensure_mintable(&treasury_info(TreasuryStatus::Redeemed)), Err(Error::InvalidState(_))));
+    }
+
+    #[test]
+    fn test_redemption_burn_ledger_prevents_double_burn() {
+        let ledger = RedemptionBurnLedger::default();
+        let settlement_id = [7u8; 32];
+        let from = Address::from_slice(&[0x33; 20]);
+        
+        ledger.reserve(settlement_id, [1u8; 32], from, U256::from(500)).unwrap();
+        assert!(matches!(ledger.reserve(settlement_id, [1u8; 32], from, U256::from(500)), Err(Error::InvalidState(_))));
+        
+        let burn = ledger.complete(settlement_id).unwrap();
+        assert!(burn.completed);
+        assert!(matches!(ledger.reserve(settlement_id, [1u8; 32], from, U256::from(500)), Err(Error::InvalidState(_))));
+        
+        // A failed burn releases the settlement for retry
+        let retry_id = [8u8; 32];
+        ledger.reserve(retry_id, [1u8; 32], from, U256::from(10)).unwrap();
+        ledger.release(retry_id);
+        assert!(ledger.reserve(retry_id, [1u8; 32], from, U256::from(10)).is_ok());
+    }
+
     #[tokio::test]
     async fn test_treasury_service_compliance_check_fail() {
         let registry_client = TreasuryRegistryClient::new(Arc::new(EthereumClient::new("http://localhost:8545").await.unwrap()), Address::ZERO).await;
@@ -740,10 +1929,12 @@ mod tests {
             1000,
             TreasuryType::TBill,
             U256::from(1000),
+            Currency::USD,
             100,
             1,
             2,
             Address::ZERO,
+            None,
         ).await;
         assert!(matches!(result, Err(Error::Unauthorized(_))));
     }
@@ -761,10 +1952,12 @@ mod tests {
             1000,
             TreasuryType::TBill,
             U256::from(1000),
+            Currency::USD,
             100,
             1,
             2,
             Address::from_slice(&[0x11; 20]),
+            None,
         ).await;
         // Should succeed and use the TestTokenDeployer logic
         assert!(result.is_ok());
@@ -772,4 +1965,152 @@ mod tests {
         assert_eq!(overview.token_address.as_bytes()[0], "Test Treasury".len() as u8);
         assert_eq!(overview.token_address.as_bytes()[1], "TST".len() as u8);
     }
-} 
\ No newline at end of file
+
+    /// Deployer that fails while `fail` is set and counts deployments
+    struct FlakyDeployer {
+        fail: Arc<std::sync::atomic::AtomicBool>,
+        deployed: Arc<AtomicU64>,
+    }
+    impl TokenDeployer for FlakyDeployer {
+        fn deploy_token(&self, name: &str, symbol: &str, total_supply: u64, issuer: Address) -> Result<Address, Error> {
+            if self.fail.load(Ordering::SeqCst) {
+                return Err(Error::ContractInteraction("deployment reverted".into()));
+            }
+            self.deployed.fetch_add(1, Ordering::SeqCst);
+            TestTokenDeployer.deploy_token(name, symbol, total_supply, issuer)
+        }
+    }
+    
+    /// Registry lookup that errors while `unavailable` is set, as in a registry outage
+    #[derive(Debug, Default)]
+    struct FlakyRegistry {
+        unavailable: std::sync::atomic::AtomicBool,
+    }
+    #[async_trait]
+    impl TokenIdLookup for FlakyRegistry {
+        async fn is_registered(&self, _registry: Address, _token_id: [u8; 32]) -> Result<bool, Error> {
+            if self.unavailable.load(Ordering::SeqCst) {
+                return Err(Error::RegistryOperation("registry unavailable".into()));
+            }
+            Ok(false)
+        }
+    }
+    
+    struct CreationHarness {
+        service: TreasuryService,
+        deploy_fails: Arc<std::sync::atomic::AtomicBool>,
+        deployed: Arc<AtomicU64>,
+        registry: Arc<FlakyRegistry>,
+    }
+    
+    async fn creation_harness() -> CreationHarness {
+        let registry = Arc::new(FlakyRegistry::default());
+        let registry_client = TreasuryRegistryClient::new(Arc::new(EthereumClient::new("http://localhost:8545").await.unwrap()), Address::ZERO).await
+            .with_token_id_lookup(registry.clone());
+        let deploy_fails = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let deployed = Arc::new(AtomicU64::new(0));
+        let token_deployer = Box::new(FlakyDeployer { fail: deploy_fails.clone(), deployed: deployed.clone() });
+        let compliance_checker = Box::new(TestComplianceChecker { should_pass: true });
+        let service = TreasuryService::new(registry_client, IpfsClient::new("http://localhost:5001"), token_deployer, compliance_checker).await;
+        CreationHarness { service, deploy_fails, deployed, registry }
+    }
+    
+    async fn create(service: &TreasuryService, name: &str) -> Result<TreasuryOverview, Error> {
+        service.create_treasury_token(
+            name.to_string(),
+            "TST".to_string(),
+            1000,
+            TreasuryType::TBill,
+            U256::from(1000),
+            Currency::USD,
+            100,
+            1,
+            2,
+            Address::from_slice(&[0x11; 20]),
+            None,
+        ).await
+    }
+    
+    fn failed_attempt(result: Result<TreasuryOverview, Error>, expected: CreationStep) -> Uuid {
+        match result {
+            Err(Error::CreationFailed { attempt_id, step, .. }) => {
+                assert_eq!(step, expected);
+                attempt_id
+            }
+            other => panic!("expected CreationFailed at {:?}, got {:?}", expected, other),
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_creation_failure_at_upload_recorded() {
+        let harness = creation_harness().await;
+        
+        // Invalid metadata is rejected before anything is pinned or deployed
+        let attempt_id = failed_attempt(create(&harness.service, "").await, CreationStep::UploadMetadata);
+        let attempt = harness.service.get_creation_attempt(&attempt_id).unwrap();
+        assert_eq!(attempt.status, CreationAttemptStatus::Failed);
+        assert!(attempt.completed_steps.is_empty());
+        assert_eq!(attempt.metadata_uri, None);
+        assert_eq!(attempt.token_address, None);
+        assert!(!attempt.token_orphaned);
+        assert_eq!(harness.deployed.load(Ordering::SeqCst), 0);
+    }
+    
+    #[tokio::test]
+    async fn test_creation_failure_at_deploy_unpins_and_resumes() {
+        let harness = creation_harness().await;
+        harness.deploy_fails.store(true, Ordering::SeqCst);
+        
+        let attempt_id = failed_attempt(create(&harness.service, "Test Treasury").await, CreationStep::DeployToken);
+        let attempt = harness.service.get_creation_attempt(&attempt_id).unwrap();
+        assert_eq!(attempt.status, CreationAttemptStatus::Failed);
+        assert_eq!(attempt.completed_steps, vec![CreationStep::UploadMetadata]);
+        assert!(attempt.metadata_uri.is_some());
+        assert!(!attempt.metadata_pinned);
+        assert_eq!(attempt.token_address, None);
+        assert!(!attempt.token_orphaned);
+        
+        harness.deploy_fails.store(false, Ordering::SeqCst);
+        let overview = harness.service.resume_treasury_creation(attempt_id).await.unwrap();
+        let attempt = harness.service.get_creation_attempt(&attempt_id).unwrap();
+        assert_eq!(attempt.status, CreationAttemptStatus::Completed);
+        assert_eq!(attempt.token_address, Some(overview.token_address));
+        assert!(attempt.metadata_pinned);
+        assert_eq!(harness.deployed.load(Ordering::SeqCst), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_creation_failure_at_register_orphans_token_and_resumes_without_redeploy() {
+        let harness = creation_harness().await;
+        harness.registry.unavailable.store(true, Ordering::SeqCst);
+        
+        let attempt_id = failed_attempt(create(&harness.service, "Test Treasury").await, CreationStep::RegisterTreasury);
+        let attempt = harness.service.get_creation_attempt(&attempt_id).unwrap();
+        assert_eq!(attempt.status, CreationAttemptStatus::Failed);
+        assert_eq!(attempt.completed_steps, vec![CreationStep::UploadMetadata, CreationStep::DeployToken]);
+        assert!(!attempt.metadata_pinned);
+        assert!(attempt.token_orphaned);
+        assert!(attempt.error.unwrap().contains("registry unavailable"));
+        assert_eq!(harness.service.orphaned_tokens().len(), 1);
+        let token_address = attempt.token_address.unwrap();
+        
+        // Resuming during the outage fails again without redeploying
+        failed_attempt(harness.service.resume_treasury_creation(attempt_id).await, CreationStep::RegisterTreasury);
+        assert_eq!(harness.deployed.load(Ordering::SeqCst), 1);
+        
+        harness.registry.unavailable.store(false, Ordering::SeqCst);
+        let overview = harness.service.resume_treasury_creation(attempt_id).await.unwrap();
+        assert_eq!(overview.token_address, token_address);
+        assert_eq!(harness.deployed.load(Ordering::SeqCst), 1);
+        
+        let attempt = harness.service.get_creation_attempt(&attempt_id).unwrap();
+        assert_eq!(attempt.status, CreationAttemptStatus::Completed);
+        assert_eq!(attempt.resume_count, 2);
+        assert!(attempt.metadata_pinned);
+        assert!(!attempt.token_orphaned);
+        assert!(harness.service.orphaned_tokens().is_empty());
+        
+        // Completed attempts cannot be resumed
+        assert!(matches!(harness.service.resume_treasury_creation(attempt_id).await, Err(Error::InvalidState(_))));
+    }
+}
@@ -0,0 +1,583 @@
+// Liquidity pool position analytics: fees earned, impermanent loss and fee APR
+use alloy_primitives::{Address, U256};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use ethereum_client::{EthereumClient, Log};
+use rust_decimal::prelude::*;
+use rust_decimal::Decimal;
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::debug;
+use crate::clients::liquidity_pools_client::LiquidityPoolsClient;
+use crate::Error;
+
+const LIQUIDITY_ADDED: &str = "LiquidityAdded(bytes32,bytes32,address,uint128,uint256,uint256)";
+const LIQUIDITY_REMOVED: &str = "LiquidityRemoved(bytes32,bytes32,address,uint128,uint256,uint256)";
+const FEES_COLLECTED: &str = "FeesCollected(bytes32,bytes32,address,uint256,uint256)";
+const SWAP: &str = "Swap(bytes32,address,address,int256,int256,uint160,uint128,int24,uint256,uint256)";
+
+const SECONDS_PER_DAY: u64 = 86_400;
+const SECONDS_PER_YEAR: u64 = 365 * SECONDS_PER_DAY;
+
+/// Windows with fewer swaps than this report their APR as low confidence
+const MIN_SWAPS_FOR_CONFIDENCE: usize = 20;
+
+/// APRs are never annualized from less than a day of history
+const MIN_APR_SPAN_SECS: u64 = SECONDS_PER_DAY;
+
+/// Token amounts of a pool's pair, in whole tokens
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenAmounts {
+    pub token_a: Decimal,
+    pub token_b: Decimal,
+}
+
+impl TokenAmounts {
+    pub fn new(token_a: Decimal, token_b: Decimal) -> Self {
+        Self { token_a, token_b }
+    }
+
+    /// Value in token B at `price` (token B per token A)
+    pub fn value(&self, price: Decimal) -> Decimal {
+        self.token_a * price + self.token_b
+    }
+}
+
+impl std::ops::Add for TokenAmounts {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.token_a + other.token_a, self.token_b + other.token_b)
+    }
+}
+
+impl std::iter::Sum for TokenAmounts {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |total, amounts| total + amounts)
+    }
+}
+
+/// Pool price and token scaling at the current block
+#[derive(Debug, Clone)]
+pub struct PoolSnapshot {
+    /// Token B per token A
+    pub price: Decimal,
+    pub decimals_a: u32,
+    pub decimals_b: u32,
+}
+
+/// An LP position as it stands now
+#[derive(Debug, Clone)]
+pub struct PositionSnapshot {
+    pub position_id: [u8; 32],
+    pub lower_tick: i32,
+    pub upper_tick: i32,
+    pub liquidity: u128,
+    /// Tokens the position's liquidity is worth at the current price
+    pub amounts: TokenAmounts,
+    /// Fees accrued through the pool's fee-growth accumulators but not yet collected
+    pub fees_owed: TokenAmounts,
+    pub created_at: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LiquidityEventKind {
+    Deposit,
+    Withdrawal,
+    FeeCollection,
+}
+
+/// A deposit, withdrawal or fee collection by the owner, decoded
+#[derive(Debug, Clone)]
+pub struct LiquidityEvent {
+    pub kind: LiquidityEventKind,
+    pub position_id: [u8; 32],
+    pub block_number: u64,
+    pub amounts: TokenAmounts,
+}
+
+/// Fees paid by one swap and the liquidity that shared them
+#[derive(Debug, Clone)]
+pub struct SwapFee {
+    pub timestamp: u64,
+    pub tick: i32,
+    /// In-range liquidity at the time of the swap
+    pub active_liquidity: u128,
+    pub fees: TokenAmounts,
+}
+
+/// Pool and position data the analytics are computed from
+#[async_trait]
+pub trait LpDataSource: Send + Sync {
+    async fn head(&self) -> Result<u64, Error>;
+
+    async fn pool(&self, pool_id: [u8; 32]) -> Result<PoolSnapshot, Error>;
+
+    /// The owner's positions in the pool
+    async fn positions(&self, pool: &PoolSnapshot, pool_id: [u8; 32], owner: Address) -> Result<Vec<PositionSnapshot>, Error>;
+
+    /// The owner's deposits, withdrawals and fee collections up to `to_block`, in chain order
+    async fn liquidity_events(&self, pool: &PoolSnapshot, pool_id: [u8; 32], owner: Address, to_block: u64) -> Result<Vec<LiquidityEvent>, Error>;
+
+    /// Swaps in the pool at or after `since` (unix seconds) up to `to_block`
+    async fn swaps(&self, pool: &PoolSnapshot, pool_id: [u8; 32], since: u64, to_block: u64) -> Result<Vec<SwapFee>, Error>;
+}
+
+/// Reads pool state through the LiquidityPools client and history from its event logs
+pub struct ContractLpDataSource {
+    client: Arc<EthereumClient>,
+    pools: Arc<LiquidityPoolsClient<EthereumClient>>,
+    pools_contract: Address,
+    /// Block the LiquidityPools contract was deployed at; logs are read from here
+    from_block: u64,
+    block_times: RwLock<HashMap<u64, u64>>,
+}
+
+impl ContractLpDataSource {
+    pub fn new(
+        client: Arc<EthereumClient>,
+        pools: Arc<LiquidityPoolsClient<EthereumClient>>,
+        pools_contract: Address,
+        from_block: u64,
+    ) -> Self {
+        Self { client, pools, pools_contract, from_block, block_times: RwLock::new(HashMap::new()) }
+    }
+
+    async fn block_time(&self, block_number: u64) -> Result<u64, Error> {
+        if let Some(timestamp) = self.block_times.read().await.get(&block_number) {
+            return Ok(*timestamp);
+        }
+        let timestamp = self.client.get_block_timestamp(block_number).await?;
+        self.block_times.write().await.insert(block_number, timestamp);
+        Ok(timestamp)
+    }
+
+    async fn decimals(&self, token: Address) -> Result<u32, Error> {
+        Ok(self.client.call_contract::<u8>(token, "decimals()", vec![]).await? as u32)
+    }
+}
+
+fn pool_error(e: impl std::fmt::Display) -> Error {
+    Error::ContractInteraction(format!("LiquidityPools call failed: {}", e))
+}
+
+#[async_trait]
+impl LpDataSource for ContractLpDataSource {
+    async fn head(&self) -> Result<u64, Error> {
+        Ok(self.client.get_block_number().await?)
+    }
+
+    async fn pool(&self, pool_id: [u8; 32]) -> Result<PoolSnapshot, Error> {
+        let config = self.pools.get_pool_config(pool_id).await.map_err(pool_error)?;
+        let state = self.pools.get_pool_state(pool_id).await.map_err(pool_error)?;
+        let decimals_a = self.decimals(config.token_a).await?;
+        let decimals_b = self.decimals(config.token_b).await?;
+
+        Ok(PoolSnapshot {
+            price: price_from_sqrt_x96(&state.sqrt_price_x96.to_string(), decimals_a, decimals_b)?,
+            decimals_a,
+            decimals_b,
+        })
+    }
+
+    async fn positions(&self, pool: &PoolSnapshot, pool_id: [u8; 32], owner: Address) -> Result<Vec<PositionSnapshot>, Error> {
+        let mut snapshots = Vec::new();
+        for (position_id, position) in self.pools.get_user_positions_with_details(owner).await.map_err(pool_error)? {
+            if position.pool_id != pool_id {
+                continue;
+            }
+            let (amount_a, amount_b) = self.pools
+                .calculate_amounts(pool_id, position.lower_tick, position.upper_tick, position.liquidity)
+                .await
+                .map_err(pool_error)?;
+            snapshots.push(PositionSnapshot {
+                position_id,
+                lower_tick: position.lower_tick,
+                upper_tick: position.upper_tick,
+                liquidity: position.liquidity,
+                amounts: TokenAmounts::new(
+                    scale(&amount_a.to_string(), pool.decimals_a)?,
+                    scale(&amount_b.to_string(), pool.decimals_b)?,
+                ),
+                fees_owed: TokenAmounts::new(
+                    scale(&position.tokens_owed_a.to_string(), pool.decimals_a)?,
+                    scale(&position.tokens_owed_b.to_string(), pool.decimals_b)?,
+                ),
+                created_at: position.created_at,
+            });
+        }
+        Ok(snapshots)
+    }
+
+    async fn liquidity_events(&self, pool: &PoolSnapshot, pool_id: [u8; 32], owner: Address, to_block: u64) -> Result<Vec<LiquidityEvent>, Error> {
+        let owner_topic = address_topic(owner);
+        let mut events = Vec::new();
+        for (event, kind) in [
+            (LIQUIDITY_ADDED, LiquidityEventKind::Deposit),
+            (LIQUIDITY_REMOVED, LiquidityEventKind::Withdrawal),
+            (FEES_COLLECTED, LiquidityEventKind::FeeCollection),
+        ] {
+            // Deposits and withdrawals carry the liquidity delta before the amounts
+            let first_amount = if kind == LiquidityEventKind::FeeCollection { 0 } else { 1 };
+            for log in self.client.get_logs(self.pools_contract, event, self.from_block, to_block).await? {
+                if topic(&log, 1)? != pool_id || topic(&log, 3)? != owner_topic {
+                    continue;
+                }
+                events.push((log.block_number, log.log_index, LiquidityEvent {
+                    kind,
+                    position_id: topic(&log, 2)?,
+                    block_number: log.block_number,
+                    amounts: TokenAmounts::new(
+                        scale(&data_word(&log, first_amount)?.to_string(), pool.decimals_a)?,
+                        scale(&data_word(&log, first_amount + 1)?.to_string(), pool.decimals_b)?,
+                    ),
+                }));
+            }
+        }
+        events.sort_by_key(|(block, index, _)| (*block, *index));
+        Ok(events.into_iter().map(|(_, _, event)| event).collect())
+    }
+
+    async fn swaps(&self, pool: &PoolSnapshot, pool_id: [u8; 32], since: u64, to_block: u64) -> Result<Vec<SwapFee>, Error> {
+        let mut swaps = Vec::new();
+        for log in self.client.get_logs(self.pools_contract, SWAP, self.from_block, to_block).await? {
+            if topic(&log, 1)? != pool_id {
+                continue;
+            }
+            let timestamp = self.block_time(log.block_number).await?;
+            if timestamp < since {
+                continue;
+            }
+            // Data: amount0, amount1, sqrtPriceX96, liquidity, tick, fee0, fee1
+            let tick_word = data_word(&log, 4)?.to_be_bytes::<32>();
+            swaps.push(SwapFee {
+                timestamp,
+                tick: i32::from_be_bytes(tick_word[28..].try_into().expect("4 bytes")),
+                active_liquidity: data_word(&log, 3)?.to::<u128>(),
+                fees: TokenAmounts::new(
+                    scale(&data_word(&log, 5)?.to_string(), pool.decimals_a)?,
+                    scale(&data_word(&log, 6)?.to_string(), pool.decimals_b)?,
+                ),
+            });
+        }
+        Ok(swaps)
+    }
+}
+
+fn topic(log: &Log, index: usize) -> Result<[u8; 32], Error> {
+    log.topics.get(index)
+        .map(|topic| topic.0)
+        .ok_or_else(|| Error::Decoding(format!("Log {:?} is missing topic {}", log.transaction_hash, index)))
+}
+
+fn data_word(log: &Log, index: usize) -> Result<U256, Error> {
+    log.data.get(index * 32..(index + 1) * 32)
+        .map(U256::from_be_slice)
+        .ok_or_else(|| Error::Decoding(format!("Log {:?} is missing data word {}", log.transaction_hash, index)))
+}
+
+fn address_topic(address: Address) -> [u8; 32] {
+    let mut topic = [0u8; 32];
+    topic[12..].copy_from_slice(address.as_slice());
+    topic
+}
+
+/// An integer token amount in whole tokens
+fn scale(raw: &str, decimals: u32) -> Result<Decimal, Error> {
+    let amount = Decimal::from_str(raw)
+        .map_err(|_| Error::Decoding(format!("Token amount {} does not fit a decimal", raw)))?;
+    Ok(amount / Decimal::from(10u64.pow(decimals.min(19))))
+}
+
+/// Token B per token A from a Q64.96 square root price
+fn price_from_sqrt_x96(sqrt_price_x96: &str, decimals_a: u32, decimals_b: u32) -> Result<Decimal, Error> {
+    let sqrt_price = f64::from_str(sqrt_price_x96)
+        .map_err(|_| Error::Decoding(format!("Invalid sqrt price {}", sqrt_price_x96)))?
+        / 2f64.powi(96);
+    let price = sqrt_price * sqrt_price * 10f64.powi(decimals_a as i32 - decimals_b as i32);
+    Decimal::from_f64(price)
+        .ok_or_else(|| Error::Decoding(format!("Pool price {} is out of range", price)))
+}
+
+/// Position value against simply holding what was put in, excluding fees
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImpermanentLoss {
+    /// Deposited tokens valued at the current price
+    pub hold_value: Decimal,
+    /// Current position plus withdrawn tokens valued at the current price
+    pub lp_value: Decimal,
+    /// `lp_value - hold_value`; negative is a loss
+    pub value: Decimal,
+    /// `lp_value / hold_value - 1`; absent when nothing was deposited
+    pub ratio: Option<Decimal>,
+}
+
+/// Compare the position with holding the deposited tokens, all at `price`.
+///
+/// Withdrawn tokens count toward the position at today's price so that partial exits
+/// do not show up as a loss.
+pub fn impermanent_loss(deposited: TokenAmounts, withdrawn: TokenAmounts, current: TokenAmounts, price: Decimal) -> ImpermanentLoss {
+    let hold_value = deposited.value(price);
+    let lp_value = (current + withdrawn).value(price);
+    ImpermanentLoss {
+        hold_value,
+        lp_value,
+        value: lp_value - hold_value,
+        ratio: (!hold_value.is_zero()).then(|| lp_value / hold_value - Decimal::ONE),
+    }
+}
+
+/// Trailing period fee APR is annualized over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AprWindow {
+    pub days: u32,
+}
+
+impl AprWindow {
+    pub const DEFAULTS: [AprWindow; 2] = [AprWindow { days: 7 }, AprWindow { days: 30 }];
+
+    /// Comma-separated windows such as `7d,30d`, each between 1 and 365 days
+    pub fn parse_list(windows: &str) -> Result<Vec<AprWindow>, Error> {
+        let mut parsed = Vec::new();
+        for window in windows.split(',').map(str::trim).filter(|window| !window.is_empty()) {
+            let days = window.strip_suffix('d')
+                .and_then(|days| days.parse::<u32>().ok())
+                .filter(|days| (1..=365).contains(days))
+                .ok_or_else(|| Error::InvalidParameter(format!("Invalid APR window {}, expected e.g. 7d", window)))?;
+            if !parsed.contains(&AprWindow { days }) {
+                parsed.push(AprWindow { days });
+            }
+        }
+        if parsed.is_empty() {
+            return Err(Error::InvalidParameter("At least one APR window is required".into()));
+        }
+        Ok(parsed)
+    }
+
+    fn secs(&self) -> u64 {
+        self.days as u64 * SECONDS_PER_DAY
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeApr {
+    pub window: String,
+    /// Seconds of the window the position existed for; the APR is annualized over this
+    pub covered_secs: u64,
+    pub swap_count: usize,
+    /// The position's share of swap fees in the window, valued in token B
+    pub fees_value: Decimal,
+    pub apr: Option<Decimal>,
+    /// Too few swaps or too little history for the APR to be representative
+    pub low_confidence: bool,
+}
+
+/// Annualized fee yield over `window` ending at `now`.
+///
+/// Fees are the position's pro-rata share of each in-range swap. The rate is annualized
+/// over the covered span, never less than a day, rather than over the time between swaps
+/// so a pool with a handful of trades does not extrapolate to absurd yields.
+pub fn fee_apr(
+    window: AprWindow,
+    now: u64,
+    positions: &[PositionSnapshot],
+    swaps: &[SwapFee],
+    price: Decimal,
+    position_value: Decimal,
+) -> FeeApr {
+    let start = now.saturating_sub(window.secs());
+    let opened = positions.iter().map(|position| position.created_at).min().unwrap_or(now);
+    let covered_secs = now.saturating_sub(start.max(opened));
+
+    let in_window: Vec<&SwapFee> = swaps.iter().filter(|swap| swap.timestamp >= start && swap.timestamp <= now).collect();
+    let fees_value: Decimal = in_window.iter()
+        .map(|swap| {
+            let share: Decimal = positions.iter()
+                .filter(|position| swap.timestamp >= position.created_at)
+                .filter(|position| position.lower_tick <= swap.tick && swap.tick < position.upper_tick)
+                .filter(|_| swap.active_liquidity > 0)
+                .filter_map(|position| Some(
+                    Decimal::from_u128(position.liquidity)? / Decimal::from_u128(swap.active_liquidity)?
+                ))
+                .sum();
+            swap.fees.value(price) * share.min(Decimal::ONE)
+        })
+        .sum();
+
+    let apr = (!position_value.is_zero()).then(|| {
+        let span = Decimal::from(covered_secs.max(MIN_APR_SPAN_SECS));
+        fees_value / position_value * Decimal::from(SECONDS_PER_YEAR) / span
+    });
+
+    FeeApr {
+        window: format!("{}d", window.days),
+        covered_secs,
+        swap_count: in_window.len(),
+        fees_value,
+        apr,
+        low_confidence: in_window.len() < MIN_SWAPS_FOR_CONFIDENCE || covered_secs < MIN_APR_SPAN_SECS,
+    }
+}
+
+/// Performance of an owner's positions in one pool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionAnalytics {
+    #[serde(with = "quantera_types::wire::token_id")]
+    pub pool_id: [u8; 32],
+    pub owner: Address,
+    /// Block the analytics were computed at
+    pub block_number: u64,
+    /// Token B per token A
+    pub price: Decimal,
+    pub position_count: usize,
+    pub current: TokenAmounts,
+    pub position_value: Decimal,
+    pub deposited: TokenAmounts,
+    pub withdrawn: TokenAmounts,
+    pub fees_collected: TokenAmounts,
+    pub fees_owed: TokenAmounts,
+    /// Collected and owed fees valued in token B
+    pub fees_value: Decimal,
+    pub impermanent_loss: ImpermanentLoss,
+    pub fee_apr: Vec<FeeApr>,
+    pub computed_at: DateTime<Utc>,
+}
+
+type CacheKey = ([u8; 32], Address, Vec<AprWindow>);
+
+/// Computes LP position analytics, cached per block
+pub struct LpAnalytics {
+    source: Arc<dyn LpDataSource>,
+    cache: RwLock<HashMap<CacheKey, PositionAnalytics>>,
+}
+
+impl LpAnalytics {
+    pub fn new(source: Arc<dyn LpDataSource>) -> Self {
+        Self { source, cache: RwLock::new(HashMap::new()) }
+    }
+
+    pub async fn position_analytics(&self, pool_id: [u8; 32], owner: Address, windows: &[AprWindow]) -> Result<PositionAnalytics, Error> {
+        let block_number = self.source.head().await?;
+        let key = (pool_id, owner, windows.to_vec());
+        if let Some(cached) = self.cache.read().await.get(&key).filter(|cached| cached.block_number == block_number) {
+            return Ok(cached.clone());
+        }
+
+        let pool = self.source.pool(pool_id).await?;
+        let positions = self.source.positions(&pool, pool_id, owner).await?;
+        if positions.is_empty() {
+            return Err(Error::NotFound(format!("{:?} has no positions in pool 0x{}", owner, hex::encode(pool_id))));
+        }
+        let events = self.source.liquidity_events(&pool, pool_id, owner, block_number).await?;
+
+        let total = |kind: LiquidityEventKind| -> TokenAmounts {
+            events.iter().filter(|event| event.kind == kind).map(|event| event.amounts).sum()
+        };
+        let deposited = total(LiquidityEventKind::Deposit);
+        let withdrawn = total(LiquidityEventKind::Withdrawal);
+        let fees_collected = total(LiquidityEventKind::FeeCollection);
+        let current: TokenAmounts = positions.iter().map(|position| position.amounts).sum();
+        let fees_owed: TokenAmounts = positions.iter().map(|position| position.fees_owed).sum();
+        let position_value = current.value(pool.price);
+
+        let now = Utc::now();
+        let longest = windows.iter().map(AprWindow::secs).max().unwrap_or_default();
+        let since = (now.timestamp() as u64).saturating_sub(longest);
+        let swaps = self.source.swaps(&pool, pool_id, since, block_number).await?;
+        let fee_apr = windows.iter()
+            .map(|window| fee_apr(*window, now.timestamp() as u64, &positions, &swaps, pool.price, position_value))
+            .collect();
+
+        let analytics = PositionAnalytics {
+            pool_id,
+            owner,
+            block_number,
+            price: pool.price,
+            position_count: positions.len(),
+            current,
+            position_value,
+            deposited,
+            withdrawn,
+            fees_collected,
+            fees_owed,
+            fees_value: (fees_collected + fees_owed).value(pool.price),
+            impermanent_loss: impermanent_loss(deposited, withdrawn, current, pool.price),
+            fee_apr,
+            computed_at: now,
+        };
+        debug!("Computed LP analytics for {:?} in pool 0x{} at block {}", owner, hex::encode(pool_id), block_number);
+
+        // Entries from older blocks are superseded as soon as a newer one is computed
+        let mut cache = self.cache.write().await;
+        cache.retain(|_, cached| cached.block_number >= block_number);
+        cache.insert(key, analytics.clone());
+        Ok(analytics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_impermanent_loss_two_price_example() {
+        // Full-range position: deposit 1 A + 100 B at 100 B/A, so k = 100.
+        // At 400 B/A the pool holds sqrt(100 / 400) = 0.5 A and sqrt(100 * 400) = 200 B:
+        // worth 0.5 * 400 + 200 = 400 B against 1 * 400 + 100 = 500 B held, a 20% loss,
+        // matching 2 * sqrt(4) / (1 + 4) - 1 = -0.2 for a 4x price move.
+        let loss = impermanent_loss(
+            TokenAmounts::new(dec!(1), dec!(100)),
+            TokenAmounts::default(),
+            TokenAmounts::new(dec!(0.5), dec!(200)),
+            dec!(400),
+        );
+        assert_eq!(loss.hold_value, dec!(500));
+        assert_eq!(loss.lp_value, dec!(400));
+        assert_eq!(loss.value, dec!(-100));
+        assert_eq!(loss.ratio, Some(dec!(-0.2)));
+
+        // Withdrawing half before the move does not change the ratio
+        let partial = impermanent_loss(
+            TokenAmounts::new(dec!(1), dec!(100)),
+            TokenAmounts::new(dec!(0.5), dec!(50)),
+            TokenAmounts::new(dec!(0.25), dec!(100)),
+            dec!(400),
+        );
+        assert_eq!(partial.lp_value, dec!(450));
+        assert_eq!(partial.ratio, Some(dec!(-0.1)));
+    }
+
+    #[test]
+    fn test_sparse_swaps_are_low_confidence() {
+        let now = 1_700_000_000;
+        let position = PositionSnapshot {
+            position_id: [1; 32],
+            lower_tick: -100,
+            upper_tick: 100,
+            liquidity: 1_000,
+            amounts: TokenAmounts::new(dec!(10), dec!(1000)),
+            fees_owed: TokenAmounts::default(),
+            created_at: now - 60 * SECONDS_PER_DAY,
+        };
+        // One swap paying 2 B, a quarter of which goes to the position
+        let swap = SwapFee { timestamp: now - 3600, tick: 0, active_liquidity: 4_000, fees: TokenAmounts::new(dec!(0), dec!(2)) };
+        let apr = fee_apr(AprWindow { days: 7 }, now, &[position], &[swap], dec!(100), dec!(2000));
+
+        assert_eq!(apr.swap_count, 1);
+        assert_eq!(apr.fees_value, dec!(0.5));
+        assert!(apr.low_confidence);
+        // Annualized over the whole week, not the hour since the swap
+        assert_eq!(apr.apr.unwrap().round_dp(6), (dec!(0.5) / dec!(2000) * dec!(365) / dec!(7)).round_dp(6));
+    }
+
+    #[test]
+    fn test_parse_windows() {
+        assert_eq!(AprWindow::parse_list("7d, 30d,7d").unwrap(), vec![AprWindow { days: 7 }, AprWindow { days: 30 }]);
+        assert!(AprWindow::parse_list("0d").is_err());
+        assert!(AprWindow::parse_list("1w").is_err());
+    }
+}
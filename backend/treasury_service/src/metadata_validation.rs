@@ -0,0 +1,274 @@
+use serde::Serialize;
+use std::fmt;
+use crate::TreasuryMetadata;
+
+/// Largest metadata document accepted from IPFS, before parsing
+pub const MAX_METADATA_BYTES: usize = 64 * 1024;
+
+/// Largest serialized `additional_details` object
+pub const MAX_ADDITIONAL_DETAILS_BYTES: usize = 16 * 1024;
+
+pub const MAX_NAME_LEN: usize = 128;
+pub const MAX_SYMBOL_LEN: usize = 16;
+pub const MAX_DESCRIPTION_LEN: usize = 4096;
+pub const MAX_URI_LEN: usize = 2048;
+
+/// Upper bound on yield_rate in basis points (25.00%)
+pub const MAX_YIELD_RATE_BPS: u64 = 2_500;
+
+/// One rule a metadata document breaks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MetadataViolation {
+    pub field: &'static str,
+    pub message: String,
+}
+
+/// Every rule a metadata document breaks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MetadataValidationError {
+    pub violations: Vec<MetadataViolation>,
+}
+
+impl MetadataValidationError {
+    fn single(field: &'static str, message: impl Into<String>) -> Self {
+        Self { violations: vec![MetadataViolation { field, message: message.into() }] }
+    }
+
+    /// Whether a rule on `field` was broken
+    pub fn has_field(&self, field: &str) -> bool {
+        self.violations.iter().any(|violation| violation.field == field)
+    }
+}
+
+impl fmt::Display for MetadataValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let violations: Vec<String> = self.violations.iter()
+            .map(|violation| format!("{}: {}", violation.field, violation.message))
+            .collect();
+        write!(f, "{}", violations.join("; "))
+    }
+}
+
+impl std::error::Error for MetadataValidationError {}
+
+impl TreasuryMetadata {
+    /// Check the document against the metadata rules, collecting every violation
+    pub fn validate(&self) -> Result<(), MetadataValidationError> {
+        let mut violations = Vec::new();
+        let mut violation = |field: &'static str, message: String| {
+            violations.push(MetadataViolation { field, message });
+        };
+
+        if let Err(message) = check_text(&self.name, MAX_NAME_LEN) {
+            violation("name", message);
+        }
+
+        if let Err(message) = check_text(&self.symbol, MAX_SYMBOL_LEN) {
+            violation("symbol", message);
+        } else if !self.symbol.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.') {
+            violation("symbol", "may only contain letters, digits, '-' and '.'".to_string());
+        }
+
+        if self.description.chars().count() > MAX_DESCRIPTION_LEN {
+            violation("description", format!("longer than {} characters", MAX_DESCRIPTION_LEN));
+        }
+
+        if !is_positive_decimal(&self.face_value) {
+            violation("face_value", format!("{:?} is not a positive decimal amount", self.face_value));
+        }
+
+        if self.maturity_date <= self.issuance_date {
+            violation("maturity_date", "must be after issuance_date".to_string());
+        }
+
+        if self.yield_rate > MAX_YIELD_RATE_BPS {
+            violation("yield_rate", format!("{} bps exceeds the {} bps maximum", self.yield_rate, MAX_YIELD_RATE_BPS));
+        }
+
+        if let Some(uri) = &self.image_uri {
+            if let Err(message) = check_uri(uri, &["https", "ipfs"]) {
+                violation("image_uri", message);
+            }
+        }
+
+        if let Some(url) = &self.external_url {
+            if let Err(message) = check_uri(url, &["https"]) {
+                violation("external_url", message);
+            }
+        }
+
+        if let Some(details) = &self.additional_details {
+            let size = serde_json::to_vec(details).map(|bytes| bytes.len()).unwrap_or(usize::MAX);
+            if size > MAX_ADDITIONAL_DETAILS_BYTES {
+                violation("additional_details", format!("{} bytes exceeds the {} byte maximum", size, MAX_ADDITIONAL_DETAILS_BYTES));
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(MetadataValidationError { violations })
+        }
+    }
+}
+
+/// Parse and validate a metadata document fetched from IPFS
+pub fn parse_metadata(document: &[u8]) -> Result<TreasuryMetadata, MetadataValidationError> {
+    if document.len() > MAX_METADATA_BYTES {
+        return Err(MetadataValidationError::single(
+            "document",
+            format!("{} bytes exceeds the {} byte maximum", document.len(), MAX_METADATA_BYTES),
+        ));
+    }
+
+    let metadata: TreasuryMetadata = serde_json::from_slice(document)
+        .map_err(|e| MetadataValidationError::single("document", e.to_string()))?;
+    metadata.validate()?;
+    Ok(metadata)
+}
+
+fn check_text(value: &str, max_len: usize) -> Result<(), String> {
+    if value.trim().is_empty() {
+        return Err("must not be empty".to_string());
+    }
+    if value.chars().count() > max_len {
+        return Err(format!("longer than {} characters", max_len));
+    }
+    if value.chars().any(char::is_control) {
+        return Err("contains control characters".to_string());
+    }
+    Ok(())
+}
+
+fn is_positive_decimal(value: &str) -> bool {
+    let mut parts = value.splitn(2, '.');
+    let whole = parts.next().unwrap_or_default();
+    let fraction = parts.next();
+
+    let digits = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+    digits(whole)
+        && fraction.map_or(true, digits)
+        && value.chars().any(|c| c.is_ascii_digit() && c != '0')
+}
+
+fn check_uri(uri: &str, allowed_schemes: &[&str]) -> Result<(), String> {
+    if uri.len() > MAX_URI_LEN {
+        return Err(format!("longer than {} characters", MAX_URI_LEN));
+    }
+    if uri.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err("contains whitespace or control characters".to_string());
+    }
+
+    let (scheme, rest) = uri.split_once("://")
+        .ok_or_else(|| format!("{:?} is not an absolute URI", uri))?;
+    if !allowed_schemes.contains(&scheme.to_ascii_lowercase().as_str()) {
+        return Err(format!("scheme {:?} not allowed, expected one of {}", scheme, allowed_schemes.join(", ")));
+    }
+
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    if authority.is_empty() || authority.contains('@') {
+        return Err(format!("{:?} has no valid host", uri));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TreasuryType;
+
+    fn valid() -> TreasuryMetadata {
+        TreasuryMetadata {
+            name: "10-Year Treasury Note".to_string(),
+            symbol: "TNOTE-10Y".to_string(),
+            description: "U.S. Treasury 10-Year Note".to_string(),
+            issuer_name: "U.S. Department of the Treasury".to_string(),
+            treasury_type: TreasuryType::TNote,
+            face_value: "1000.00".to_string(),
+            currency: crate::Currency::USD,
+            issuance_date: 1_700_000_000,
+            maturity_date: 2_015_360_000,
+            yield_rate: 425,
+            image_uri: Some("ipfs://QmTreasuryImage".to_string()),
+            external_url: Some("https://www.treasurydirect.gov/".to_string()),
+            additional_details: Some(serde_json::json!({ "cusip": "91282CJL6" })),
+            tranche: None,
+        }
+    }
+
+    #[test]
+    fn test_valid_document_passes() {
+        assert_eq!(valid().validate(), Ok(()));
+
+        let document = serde_json::to_vec(&valid()).unwrap();
+        assert_eq!(parse_metadata(&document).unwrap().symbol, "TNOTE-10Y");
+    }
+
+    #[test]
+    fn test_invalid_documents_rejected_per_rule() {
+        let corpus: Vec<(&str, fn(&mut TreasuryMetadata))> = vec![
+            ("name", |m| m.name = "   ".to_string()),
+            ("name", |m| m.name = "N".repeat(MAX_NAME_LEN + 1)),
+            ("name", |m| m.name = "Note\u{0007}".to_string()),
+            ("symbol", |m| m.symbol = String::new()),
+            ("symbol", |m| m.symbol = "S".repeat(MAX_SYMBOL_LEN + 1)),
+            ("symbol", |m| m.symbol = "<script>".to_string()),
+            ("description", |m| m.description = "d".repeat(MAX_DESCRIPTION_LEN + 1)),
+            ("face_value", |m| m.face_value = "-1000".to_string()),
+            ("face_value", |m| m.face_value = "0.00".to_string()),
+            ("face_value", |m| m.face_value = "1e3".to_string()),
+            ("maturity_date", |m| m.maturity_date = m.issuance_date),
+            ("maturity_date", |m| m.maturity_date = m.issuance_date - 1),
+            ("yield_rate", |m| m.yield_rate = MAX_YIELD_RATE_BPS + 1),
+            ("image_uri", |m| m.image_uri = Some("javascript:alert(1)".to_string())),
+            ("image_uri", |m| m.image_uri = Some("http://example.com/treasury.png".to_string())),
+            ("image_uri", |m| m.image_uri = Some("https:///treasury.png".to_string())),
+            ("image_uri", |m| m.image_uri = Some("https://example.com/a b.png".to_string())),
+            ("external_url", |m| m.external_url = Some("ipfs://QmTreasury".to_string())),
+            ("external_url", |m| m.external_url = Some("https://user@evil.example".to_string())),
+            ("external_url", |m| m.external_url = Some(format!("https://example.com/{}", "a".repeat(MAX_URI_LEN)))),
+            ("additional_details", |m| m.additional_details = Some(serde_json::json!({ "blob": "x".repeat(MAX_ADDITIONAL_DETAILS_BYTES) }))),
+        ];
+
+        for (field, mutate) in corpus {
+            let mut metadata = valid();
+            mutate(&mut metadata);
+            let err = metadata.validate().expect_err(field);
+            assert_eq!(err.violations.len(), 1, "{}: {}", field, err);
+            assert!(err.has_field(field), "expected {} violation, got {}", field, err);
+        }
+    }
+
+    #[test]
+    fn test_all_violations_reported() {
+        let mut metadata = valid();
+        metadata.name = String::new();
+        metadata.yield_rate = 10_000;
+        metadata.external_url = Some("ftp://example.com".to_string());
+
+        let err = metadata.validate().unwrap_err();
+        let fields: Vec<&str> = err.violations.iter().map(|v| v.field).collect();
+        assert_eq!(fields, vec!["name", "yield_rate", "external_url"]);
+    }
+
+    #[test]
+    fn test_malformed_documents_rejected_on_fetch() {
+        let corpus: Vec<Vec<u8>> = vec![
+            b"not json".to_vec(),
+            br#"{"name": "Note"}"#.to_vec(),
+            serde_json::to_vec(&serde_json::json!({ "name": 42 })).unwrap(),
+            vec![b' '; MAX_METADATA_BYTES + 1],
+        ];
+
+        for document in corpus {
+            let err = parse_metadata(&document).unwrap_err();
+            assert!(err.has_field("document"), "{}", err);
+        }
+
+        // Well-formed JSON that breaks a rule is rejected by the same checks as upload
+        let mut metadata = valid();
+        metadata.maturity_date = 0;
+        let err = parse_metadata(&serde_json::to_vec(&metadata).unwrap()).unwrap_err();
+        assert!(err.has_field("maturity_date"));
+    }
+}
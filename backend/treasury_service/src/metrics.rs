@@ -0,0 +1,78 @@
+use ethereum_client::MetricsRecorder;
+use prometheus::{Encoder, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder};
+use std::time::Duration;
+
+/// Prometheus adapter for Ethereum client request metrics
+pub struct PrometheusMetricsRecorder {
+    registry: Registry,
+    requests: IntCounterVec,
+    errors: IntCounterVec,
+    latency: HistogramVec,
+}
+
+impl PrometheusMetricsRecorder {
+    /// Create the recorder and register its collectors in `registry`
+    pub fn new(registry: Registry) -> Result<Self, prometheus::Error> {
+        let requests = IntCounterVec::new(
+            Opts::new("eth_client_requests_total", "Ethereum RPC requests by client method"),
+            &["method", "endpoint"],
+        )?;
+        let errors = IntCounterVec::new(
+            Opts::new("eth_client_errors_total", "Failed Ethereum RPC requests by client method and error kind"),
+            &["method", "endpoint", "error"],
+        )?;
+        let latency = HistogramVec::new(
+            HistogramOpts::new("eth_client_request_duration_seconds", "Ethereum RPC request latency")
+                .buckets(vec![0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.0, 5.0, 10.0, 30.0]),
+            &["method", "endpoint"],
+        )?;
+
+        registry.register(Box::new(requests.clone()))?;
+        registry.register(Box::new(errors.clone()))?;
+        registry.register(Box::new(latency.clone()))?;
+
+        Ok(Self { registry, requests, errors, latency })
+    }
+
+    /// Render all metrics in the registry in the Prometheus text format
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buffer) {
+            tracing::error!("Failed to encode metrics: {}", e);
+        }
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+}
+
+impl MetricsRecorder for PrometheusMetricsRecorder {
+    fn record_request(&self, method: &str, endpoint: &str) {
+        self.requests.with_label_values(&[method, endpoint]).inc();
+    }
+
+    fn record_error(&self, method: &str, endpoint: &str, error_kind: &str) {
+        self.errors.with_label_values(&[method, endpoint, error_kind]).inc();
+    }
+
+    fn record_latency(&self, method: &str, endpoint: &str, latency: Duration) {
+        self.latency.with_label_values(&[method, endpoint]).observe(latency.as_secs_f64());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prometheus_recorder_exports_series() {
+        let recorder = PrometheusMetricsRecorder::new(Registry::new()).unwrap();
+
+        recorder.record_request("get_balance", "https://rpc.example.com");
+        recorder.record_error("get_balance", "https://rpc.example.com", "provider");
+        recorder.record_latency("get_balance", "https://rpc.example.com", Duration::from_millis(120));
+
+        let output = recorder.render();
+        assert!(output.contains("eth_client_requests_total{endpoint=\"https://rpc.example.com\",method=\"get_balance\"} 1"));
+        assert!(output.contains("error=\"provider\""));
+        assert!(output.contains("eth_client_request_duration_seconds_count"));
+    }
+}
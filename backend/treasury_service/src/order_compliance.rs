@@ -0,0 +1,442 @@
+use alloy_primitives::{Address, U256};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Serialize, Deserialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+use uuid::Uuid;
+use crate::clients::compliance_client::{ComplianceClient, TransferCheck, VerificationStatus};
+use crate::clients::trading_client::{OrderSide, TradingClient};
+use crate::{DealingWindows, Error};
+
+/// ComplianceModule restriction type for transfer restrictions
+const TRANSFER_RESTRICTION: u8 = 0;
+
+/// Which side of a trade a party is checked for
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PartyRole {
+    Buyer,
+    Seller,
+}
+
+impl PartyRole {
+    fn for_side(side: OrderSide) -> Self {
+        match side {
+            OrderSide::Buy => PartyRole::Buyer,
+            OrderSide::Sell => PartyRole::Seller,
+        }
+    }
+}
+
+/// Transfer compliance check run for one party of an order
+#[async_trait]
+pub trait TransferPreCheck: Send + Sync {
+    async fn check_party(
+        &self,
+        party: Address,
+        role: PartyRole,
+        treasury_id: [u8; 32],
+        amount: U256,
+    ) -> Result<TransferCheck, Error>;
+}
+
+#[async_trait]
+impl TransferPreCheck for ComplianceClient {
+    async fn check_party(
+        &self,
+        party: Address,
+        role: PartyRole,
+        treasury_id: [u8; 32],
+        amount: U256,
+    ) -> Result<TransferCheck, Error> {
+        let contract_error = |e: crate::clients::compliance_client::Error| Error::ContractInteraction(e.to_string());
+
+        match role {
+            // Receiving side of the transfer, checked like an issuance to the buyer
+            PartyRole::Buyer => self.check_transfer(Address::ZERO, party, amount, treasury_id)
+                .await
+                .map_err(contract_error),
+            PartyRole::Seller => {
+                if self.get_verification_status(party).await.map_err(contract_error)? != VerificationStatus::Verified {
+                    return Ok(TransferCheck { compliant: false, rule: Some("Sender not verified".to_string()) });
+                }
+                if self.is_entity_restricted(party, TRANSFER_RESTRICTION, Some(treasury_id)).await.map_err(contract_error)? {
+                    return Ok(TransferCheck { compliant: false, rule: Some("Sender transfer restricted".to_string()) });
+                }
+                Ok(TransferCheck { compliant: true, rule: None })
+            }
+        }
+    }
+}
+
+/// How failed pre-trade checks are handled
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PreTradeMode {
+    /// Reject the order or settlement
+    Enforce,
+    /// Log the failure and let the order through, for rollout
+    WarnOnly,
+}
+
+#[derive(Debug, Clone)]
+pub struct PreTradeComplianceConfig {
+    pub mode: PreTradeMode,
+    /// Age after which a check must be repeated before settlement
+    pub check_ttl: Duration,
+    /// Assets whose sellers are checked as well as buyers
+    pub restricted_assets: HashSet<[u8; 32]>,
+}
+
+impl Default for PreTradeComplianceConfig {
+    fn default() -> Self {
+        Self {
+            mode: PreTradeMode::Enforce,
+            check_ttl: Duration::minutes(15),
+            restricted_assets: HashSet::new(),
+        }
+    }
+}
+
+impl PreTradeComplianceConfig {
+    /// Read `PRE_TRADE_COMPLIANCE_MODE` (enforce or warn_only), `PRE_TRADE_CHECK_TTL_SECS`
+    /// and `PRE_TRADE_RESTRICTED_ASSETS` (comma-separated treasury ids)
+    pub fn from_env() -> Result<Self, Error> {
+        let defaults = Self::default();
+
+        let mode = match std::env::var("PRE_TRADE_COMPLIANCE_MODE").ok().as_deref() {
+            None | Some("") | Some("enforce") => PreTradeMode::Enforce,
+            Some("warn_only") => PreTradeMode::WarnOnly,
+            Some(other) => return Err(Error::InvalidParameter(format!(
+                "PRE_TRADE_COMPLIANCE_MODE must be enforce or warn_only, got {}", other
+            ))),
+        };
+
+        let check_ttl = match std::env::var("PRE_TRADE_CHECK_TTL_SECS") {
+            Ok(secs) => Duration::seconds(secs.parse::<i64>()
+                .ok()
+                .filter(|secs| *secs > 0)
+                .ok_or_else(|| Error::InvalidParameter("PRE_TRADE_CHECK_TTL_SECS must be a positive integer".into()))?),
+            Err(_) => defaults.check_ttl,
+        };
+
+        let restricted_assets = std::env::var("PRE_TRADE_RESTRICTED_ASSETS").unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(|id| {
+                hex::decode(id.trim_start_matches("0x")).ok()
+                    .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+                    .ok_or_else(|| Error::InvalidParameter(format!("Invalid treasury id in PRE_TRADE_RESTRICTED_ASSETS: {}", id)))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { mode, check_ttl, restricted_assets })
+    }
+}
+
+/// Result of the pre-trade check attached to an order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplianceCheckRecord {
+    pub check_id: Uuid,
+    pub party: Address,
+    pub role: PartyRole,
+    pub treasury_id: [u8; 32],
+    pub amount: U256,
+    pub passed: bool,
+    /// Rule that failed, if any
+    pub failed_rule: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// Transfer compliance pre-check for order placement, re-validated at settlement
+pub struct PreTradeCompliance {
+    checker: Arc<dyn TransferPreCheck>,
+    config: PreTradeComplianceConfig,
+    dealing_windows: Option<Arc<DealingWindows>>,
+    checks: RwLock<HashMap<Uuid, ComplianceCheckRecord>>,
+}
+
+impl PreTradeCompliance {
+    pub fn new(checker: Arc<dyn TransferPreCheck>, config: PreTradeComplianceConfig) -> Self {
+        Self {
+            checker,
+            config,
+            dealing_windows: None,
+            checks: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Refuse transfers of assets with dealing terms outside their windows
+    pub fn with_dealing_windows(mut self, dealing_windows: Arc<DealingWindows>) -> Self {
+        self.dealing_windows = Some(dealing_windows);
+        self
+    }
+
+    pub fn mode(&self) -> PreTradeMode {
+        self.config.mode
+    }
+
+    /// Check the party placing an order. Buyers are always checked; sellers only for
+    /// restricted assets, otherwise `Ok(None)` is returned.
+    ///
+    /// Fails with `Error::ComplianceRejected` naming the rule unless running warn-only.
+    pub async fn check_order(
+        &self,
+        party: Address,
+        side: OrderSide,
+        treasury_id: [u8; 32],
+        amount: U256,
+    ) -> Result<Option<ComplianceCheckRecord>, Error> {
+        let role = PartyRole::for_side(side);
+        if role == PartyRole::Seller && !self.config.restricted_assets.contains(&treasury_id) {
+            return Ok(None);
+        }
+
+        self.run_check(party, role, treasury_id, amount).await.map(Some)
+    }
+
+    /// Pre-check an off-book transfer: the asset's dealing window, then both parties, whatever
+    /// the asset. Fails with `Error::DealingRejected` outside the window, before any
+    /// compliance check runs.
+    pub async fn check_transfer(
+        &self,
+        from: Address,
+        to: Address,
+        treasury_id: [u8; 32],
+        amount: U256,
+    ) -> Result<Vec<ComplianceCheckRecord>, Error> {
+        if let Some(dealing_windows) = &self.dealing_windows {
+            dealing_windows.check_transfer(treasury_id, Utc::now())?;
+        }
+
+        Ok(vec![
+            self.run_check(from, PartyRole::Seller, treasury_id, amount).await?,
+            self.run_check(to, PartyRole::Buyer, treasury_id, amount).await?,
+        ])
+    }
+
+    /// Verify the check attached to an order before it settles.
+    ///
+    /// Checks older than the TTL are repeated; the returned record is the one settlement
+    /// relies on and replaces the order's check id when it differs.
+    pub async fn verify_for_settlement(&self, check_id: Uuid) -> Result<ComplianceCheckRecord, Error> {
+        let record = self.checks.read().await.get(&check_id).cloned()
+            .ok_or_else(|| Error::NotFound(format!("Compliance check {}", check_id)))?;
+
+        if Utc::now() - record.checked_at <= self.config.check_ttl {
+            return self.enforce(record);
+        }
+
+        info!("Compliance check {} is stale, re-checking {:?} before settlement", check_id, record.party);
+        self.run_check(record.party, record.role, record.treasury_id, record.amount).await
+    }
+
+    /// Execute a fill through the TradingClient once both orders' checks are verified.
+    ///
+    /// Orders placed without a check (unrestricted sells) settle without one. Returns the
+    /// trade id and the checks settlement relied on.
+    pub async fn execute_trade(
+        &self,
+        trading_client: &TradingClient,
+        buy_order: (u64, Option<Uuid>),
+        sell_order: (u64, Option<Uuid>),
+        quantity: U256,
+    ) -> Result<(u64, Vec<ComplianceCheckRecord>), Error> {
+        let mut checks = Vec::new();
+        for check_id in [buy_order.1, sell_order.1].into_iter().flatten() {
+            checks.push(self.verify_for_settlement(check_id).await?);
+        }
+
+        let trade_id = trading_client.execute_trade(buy_order.0, sell_order.0, quantity)
+            .await
+            .map_err(|e| Error::ContractInteraction(e.to_string()))?;
+
+        Ok((trade_id, checks))
+    }
+
+    async fn run_check(
+        &self,
+        party: Address,
+        role: PartyRole,
+        treasury_id: [u8; 32],
+        amount: U256,
+    ) -> Result<ComplianceCheckRecord, Error> {
+        let outcome = self.checker.check_party(party, role, treasury_id, amount).await?;
+
+        let record = ComplianceCheckRecord {
+            check_id: Uuid::new_v4(),
+            party,
+            role,
+            treasury_id,
+            amount,
+            passed: outcome.compliant,
+            failed_rule: outcome.rule,
+            checked_at: Utc::now(),
+        };
+        self.checks.write().await.insert(record.check_id, record.clone());
+
+        self.enforce(record)
+    }
+
+    fn enforce(&self, record: ComplianceCheckRecord) -> Result<ComplianceCheckRecord, Error> {
+        if record.passed {
+            return Ok(record);
+        }
+
+        let rule = record.failed_rule.clone().unwrap_or_else(|| "Unspecified compliance rule".to_string());
+        match self.config.mode {
+            PreTradeMode::Enforce => Err(Error::ComplianceRejected(format!(
+                "{:?} {:?} failed transfer compliance: {}", record.role, record.party, rule
+            ))),
+            PreTradeMode::WarnOnly => {
+                warn!("[warn-only] {:?} {:?} failed transfer compliance: {} (check {})",
+                    record.role, record.party, rule, record.check_id);
+                Ok(record)
+            }
+        }
+    }
+
+    #[cfg(test)]
+    async fn backdate(&self, check_id: Uuid, by: Duration) {
+        if let Some(record) = self.checks.write().await.get_mut(&check_id) {
+            record.checked_at = record.checked_at - by;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    /// Fails every check with the given rule while `failing` is set
+    struct MockPreCheck {
+        failing: AtomicBool,
+        calls: AtomicUsize,
+    }
+
+    impl MockPreCheck {
+        fn new(failing: bool) -> Arc<Self> {
+            Arc::new(Self { failing: AtomicBool::new(failing), calls: AtomicUsize::new(0) })
+        }
+    }
+
+    #[async_trait]
+    impl TransferPreCheck for MockPreCheck {
+        async fn check_party(&self, _party: Address, _role: PartyRole, _treasury_id: [u8; 32], _amount: U256) -> Result<TransferCheck, Error> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(if self.failing.load(Ordering::SeqCst) {
+                TransferCheck { compliant: false, rule: Some("Receiver not verified".to_string()) }
+            } else {
+                TransferCheck { compliant: true, rule: None }
+            })
+        }
+    }
+
+    fn config(mode: PreTradeMode) -> PreTradeComplianceConfig {
+        PreTradeComplianceConfig { mode, ..Default::default() }
+    }
+
+    const TREASURY: [u8; 32] = [7u8; 32];
+
+    #[tokio::test]
+    async fn test_failed_buyer_check_rejects_with_rule() {
+        let compliance = PreTradeCompliance::new(MockPreCheck::new(true), config(PreTradeMode::Enforce));
+
+        let err = compliance.check_order(Address::repeat_byte(1), OrderSide::Buy, TREASURY, U256::from(100u64))
+            .await
+            .unwrap_err();
+        match err {
+            Error::ComplianceRejected(msg) => assert!(msg.contains("Receiver not verified")),
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        // Warn-only lets the order through but records the failure
+        let compliance = PreTradeCompliance::new(MockPreCheck::new(true), config(PreTradeMode::WarnOnly));
+        let record = compliance.check_order(Address::repeat_byte(1), OrderSide::Buy, TREASURY, U256::from(100u64))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!record.passed);
+        assert_eq!(record.failed_rule.as_deref(), Some("Receiver not verified"));
+    }
+
+    #[tokio::test]
+    async fn test_sellers_checked_only_for_restricted_assets() {
+        let checker = MockPreCheck::new(false);
+        let mut restricted = config(PreTradeMode::Enforce);
+        restricted.restricted_assets.insert(TREASURY);
+        let compliance = PreTradeCompliance::new(checker.clone(), restricted);
+
+        let unrestricted = compliance.check_order(Address::repeat_byte(2), OrderSide::Sell, [9u8; 32], U256::from(5u64)).await.unwrap();
+        assert!(unrestricted.is_none());
+        assert_eq!(checker.calls.load(Ordering::SeqCst), 0);
+
+        let record = compliance.check_order(Address::repeat_byte(2), OrderSide::Sell, TREASURY, U256::from(5u64)).await.unwrap().unwrap();
+        assert_eq!(record.role, PartyRole::Seller);
+        assert_eq!(checker.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_stale_check_revalidated_before_settlement() {
+        let checker = MockPreCheck::new(false);
+        let compliance = PreTradeCompliance::new(checker.clone(), config(PreTradeMode::Enforce));
+
+        let record = compliance.check_order(Address::repeat_byte(3), OrderSide::Buy, TREASURY, U256::from(10u64)).await.unwrap().unwrap();
+        assert_eq!(checker.calls.load(Ordering::SeqCst), 1);
+
+        // Fresh check is reused as-is
+        let settled = compliance.verify_for_settlement(record.check_id).await.unwrap();
+        assert_eq!(settled.check_id, record.check_id);
+        assert_eq!(checker.calls.load(Ordering::SeqCst), 1);
+
+        // Stale check is repeated and replaced
+        compliance.backdate(record.check_id, Duration::minutes(16)).await;
+        let rechecked = compliance.verify_for_settlement(record.check_id).await.unwrap();
+        assert_ne!(rechecked.check_id, record.check_id);
+        assert_eq!(checker.calls.load(Ordering::SeqCst), 2);
+
+        // A stale check whose party has since become non-compliant blocks settlement
+        compliance.backdate(rechecked.check_id, Duration::minutes(16)).await;
+        checker.failing.store(true, Ordering::SeqCst);
+        assert!(matches!(
+            compliance.verify_for_settlement(rechecked.check_id).await,
+            Err(Error::ComplianceRejected(_))
+        ));
+        assert_eq!(checker.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_transfer_outside_dealing_window_rejected_before_checks() {
+        let now = Utc::now();
+        let dealing_windows = Arc::new(DealingWindows::new());
+        dealing_windows.set_terms(crate::DealingTerms {
+            treasury_id: TREASURY,
+            recurring: None,
+            windows: vec![crate::DealingWindow { opens_at: now + Duration::days(30), closes_at: now + Duration::days(40) }],
+            notice_days: 0,
+            lockup_days: 0,
+        }, "admin").unwrap();
+        let checker = MockPreCheck::new(false);
+        let compliance = PreTradeCompliance::new(checker.clone(), config(PreTradeMode::WarnOnly))
+            .with_dealing_windows(dealing_windows.clone());
+
+        // Warn-only mode does not apply to dealing terms
+        match compliance.check_transfer(Address::repeat_byte(4), Address::repeat_byte(5), TREASURY, U256::from(1u64)).await {
+            Err(Error::DealingRejected(rejection)) => {
+                assert_eq!(rejection.next_window.unwrap().opens_at, now + Duration::days(30));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(checker.calls.load(Ordering::SeqCst), 0);
+
+        // Both parties are checked on an unrestricted asset without terms
+        let records = compliance.check_transfer(Address::repeat_byte(4), Address::repeat_byte(5), [9u8; 32], U256::from(1u64)).await.unwrap();
+        assert_eq!(records.iter().map(|record| record.role).collect::<Vec<_>>(), vec![PartyRole::Seller, PartyRole::Buyer]);
+        assert_eq!(checker.calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,309 @@
+use alloy_primitives::Address;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Serialize, Deserialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+use uuid::Uuid;
+use crate::order_compliance::PreTradeMode;
+use crate::Error;
+
+/// Risk service decision for an order
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskDecision {
+    Allow,
+    Warn,
+    Block,
+}
+
+/// A risk limit the portfolio would be over after the trade
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreachedRiskLimit {
+    pub limit: String,
+    pub before: Decimal,
+    pub after: Decimal,
+    pub threshold: Decimal,
+    pub blocking: bool,
+}
+
+/// Trade overlaid on the portfolio; positive quantities buy, negative sell
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposedTrade {
+    /// Treasury token address
+    pub asset: Address,
+    pub quantity: Decimal,
+    pub price: Decimal,
+}
+
+/// Pre-trade risk evaluation attached to an order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskEvaluation {
+    /// Absent when the risk service could not be reached in time
+    pub evaluation_id: Option<Uuid>,
+    pub decision: RiskDecision,
+    #[serde(default)]
+    pub breached_limits: Vec<BreachedRiskLimit>,
+    /// The evaluation overran its latency budget and degraded to Warn
+    #[serde(default)]
+    pub timed_out: bool,
+    /// A Block let through because the portfolio is warn-only
+    #[serde(default)]
+    pub overridden: bool,
+    #[serde(default = "Utc::now")]
+    pub evaluated_at: DateTime<Utc>,
+}
+
+impl RiskEvaluation {
+    fn degraded(timed_out: bool) -> Self {
+        Self {
+            evaluation_id: None,
+            decision: RiskDecision::Warn,
+            breached_limits: Vec::new(),
+            timed_out,
+            overridden: false,
+            evaluated_at: Utc::now(),
+        }
+    }
+}
+
+/// Source of pre-trade risk evaluations
+#[async_trait]
+pub trait PreTradeRiskCheck: Send + Sync {
+    async fn evaluate(&self, portfolio: Address, trade: &ProposedTrade) -> Result<RiskEvaluation, Error>;
+}
+
+#[derive(Deserialize)]
+struct RiskServiceResponse {
+    data: Option<RiskEvaluation>,
+    error: Option<String>,
+}
+
+/// Risk service reached over HTTP
+pub struct HttpRiskClient {
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl HttpRiskClient {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl PreTradeRiskCheck for HttpRiskClient {
+    async fn evaluate(&self, portfolio: Address, trade: &ProposedTrade) -> Result<RiskEvaluation, Error> {
+        let response: RiskServiceResponse = self.client
+            .post(format!("{}/api/v2/risk/portfolios/{:?}/pre-trade", self.base_url, portfolio))
+            .json(trade)
+            .send()
+            .await
+            .map_err(|e| Error::Internal(format!("Risk service request failed: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| Error::Decoding(format!("Risk service response: {}", e)))?;
+
+        response.data.ok_or_else(|| Error::Internal(format!(
+            "Risk service rejected the evaluation: {}", response.error.unwrap_or_default()
+        )))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PreTradeRiskConfig {
+    pub mode: PreTradeMode,
+    /// Portfolios whose blocks are only logged while enforcement rolls out
+    pub warn_only_portfolios: HashSet<Address>,
+    /// Time allowed for an evaluation before the order proceeds with a Warn
+    pub latency_budget: Duration,
+}
+
+impl Default for PreTradeRiskConfig {
+    fn default() -> Self {
+        Self {
+            mode: PreTradeMode::Enforce,
+            warn_only_portfolios: HashSet::new(),
+            latency_budget: Duration::from_millis(500),
+        }
+    }
+}
+
+impl PreTradeRiskConfig {
+    /// Read `PRE_TRADE_RISK_MODE` (enforce or warn_only), `PRE_TRADE_RISK_WARN_ONLY_PORTFOLIOS`
+    /// (comma-separated addresses) and `PRE_TRADE_RISK_BUDGET_MS`
+    pub fn from_env() -> Result<Self, Error> {
+        let defaults = Self::default();
+
+        let mode = match std::env::var("PRE_TRADE_RISK_MODE").ok().as_deref() {
+            None | Some("") | Some("enforce") => PreTradeMode::Enforce,
+            Some("warn_only") => PreTradeMode::WarnOnly,
+            Some(other) => return Err(Error::InvalidParameter(format!(
+                "PRE_TRADE_RISK_MODE must be enforce or warn_only, got {}", other
+            ))),
+        };
+
+        let warn_only_portfolios = std::env::var("PRE_TRADE_RISK_WARN_ONLY_PORTFOLIOS").unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|address| !address.is_empty())
+            .map(|address| address.parse::<Address>()
+                .map_err(|_| Error::InvalidParameter(format!("Invalid address in PRE_TRADE_RISK_WARN_ONLY_PORTFOLIOS: {}", address))))
+            .collect::<Result<_, _>>()?;
+
+        let latency_budget = match std::env::var("PRE_TRADE_RISK_BUDGET_MS") {
+            Ok(ms) => Duration::from_millis(ms.parse::<u64>()
+                .ok()
+                .filter(|ms| *ms > 0)
+                .ok_or_else(|| Error::InvalidParameter("PRE_TRADE_RISK_BUDGET_MS must be a positive integer".into()))?),
+            Err(_) => defaults.latency_budget,
+        };
+
+        Ok(Self { mode, warn_only_portfolios, latency_budget })
+    }
+
+    fn enforced_for(&self, portfolio: Address) -> bool {
+        self.mode == PreTradeMode::Enforce && !self.warn_only_portfolios.contains(&portfolio)
+    }
+}
+
+/// Risk limit check for order placement; evaluations are kept with the orders they admitted
+pub struct PreTradeRisk {
+    checker: Arc<dyn PreTradeRiskCheck>,
+    config: PreTradeRiskConfig,
+    orders: RwLock<HashMap<String, RiskEvaluation>>,
+}
+
+impl PreTradeRisk {
+    pub fn new(checker: Arc<dyn PreTradeRiskCheck>, config: PreTradeRiskConfig) -> Self {
+        Self {
+            checker,
+            config,
+            orders: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Evaluate an order for `portfolio`.
+    ///
+    /// Fails with `Error::RiskLimitBreached` when the risk service blocks the trade and the
+    /// portfolio is enforced. A slow or unreachable risk service lets the order through
+    /// with a Warn evaluation rather than holding up placement.
+    pub async fn check_order(&self, portfolio: Address, trade: ProposedTrade) -> Result<RiskEvaluation, Error> {
+        let mut evaluation = match tokio::time::timeout(self.config.latency_budget, self.checker.evaluate(portfolio, &trade)).await {
+            Ok(Ok(evaluation)) => evaluation,
+            Ok(Err(e)) => {
+                warn!("Pre-trade risk evaluation for {:?} failed, proceeding with warn: {}", portfolio, e);
+                RiskEvaluation::degraded(false)
+            }
+            Err(_) => {
+                warn!("Pre-trade risk evaluation for {:?} exceeded {:?}, proceeding with warn", portfolio, self.config.latency_budget);
+                RiskEvaluation::degraded(true)
+            }
+        };
+
+        if evaluation.decision == RiskDecision::Block {
+            let limits: Vec<&str> = evaluation.breached_limits.iter()
+                .filter(|limit| limit.blocking)
+                .map(|limit| limit.limit.as_str())
+                .collect();
+            if self.config.enforced_for(portfolio) {
+                return Err(Error::RiskLimitBreached(format!(
+                    "Order for {:?} would breach {}", portfolio, limits.join(", ")
+                )));
+            }
+            warn!("[warn-only] Order for {:?} would breach {} (evaluation {:?})",
+                portfolio, limits.join(", "), evaluation.evaluation_id);
+            evaluation.overridden = true;
+        } else if evaluation.decision == RiskDecision::Warn {
+            info!("Order for {:?} admitted with risk warning (evaluation {:?})", portfolio, evaluation.evaluation_id);
+        }
+
+        Ok(evaluation)
+    }
+
+    /// Attach an evaluation to the order it admitted
+    pub async fn record_order(&self, order_id: &str, evaluation: RiskEvaluation) {
+        self.orders.write().await.insert(order_id.to_string(), evaluation);
+    }
+
+    pub async fn evaluation_for_order(&self, order_id: &str) -> Option<RiskEvaluation> {
+        self.orders.read().await.get(order_id).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    /// Blocks every trade on VaR after an optional delay
+    struct BlockingCheck {
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl PreTradeRiskCheck for BlockingCheck {
+        async fn evaluate(&self, _portfolio: Address, _trade: &ProposedTrade) -> Result<RiskEvaluation, Error> {
+            tokio::time::sleep(self.delay).await;
+            Ok(RiskEvaluation {
+                evaluation_id: Some(Uuid::new_v4()),
+                decision: RiskDecision::Block,
+                breached_limits: vec![BreachedRiskLimit {
+                    limit: "max_var_95".to_string(),
+                    before: dec!(0.05),
+                    after: dec!(0.073),
+                    threshold: dec!(0.06),
+                    blocking: true,
+                }],
+                timed_out: false,
+                overridden: false,
+                evaluated_at: Utc::now(),
+            })
+        }
+    }
+
+    fn trade() -> ProposedTrade {
+        ProposedTrade { asset: Address::repeat_byte(0x11), quantity: dec!(100), price: dec!(99.5) }
+    }
+
+    #[tokio::test]
+    async fn test_var_breach_blocks_unless_portfolio_is_warn_only() {
+        let portfolio = Address::repeat_byte(0x01);
+        let rolling_out = Address::repeat_byte(0x02);
+        let risk = PreTradeRisk::new(Arc::new(BlockingCheck { delay: Duration::ZERO }), PreTradeRiskConfig {
+            warn_only_portfolios: HashSet::from([rolling_out]),
+            ..Default::default()
+        });
+
+        match risk.check_order(portfolio, trade()).await {
+            Err(Error::RiskLimitBreached(message)) => assert!(message.contains("max_var_95")),
+            other => panic!("expected a block, got {:?}", other),
+        }
+
+        let evaluation = risk.check_order(rolling_out, trade()).await.unwrap();
+        assert_eq!(evaluation.decision, RiskDecision::Block);
+        assert!(evaluation.overridden);
+        risk.record_order("order-1", evaluation.clone()).await;
+        assert_eq!(risk.evaluation_for_order("order-1").await.unwrap().evaluation_id, evaluation.evaluation_id);
+    }
+
+    #[tokio::test]
+    async fn test_slow_evaluation_degrades_to_warn() {
+        let risk = PreTradeRisk::new(Arc::new(BlockingCheck { delay: Duration::from_millis(200) }), PreTradeRiskConfig {
+            latency_budget: Duration::from_millis(10),
+            ..Default::default()
+        });
+
+        let evaluation = risk.check_order(Address::repeat_byte(0x01), trade()).await.unwrap();
+        assert_eq!(evaluation.decision, RiskDecision::Warn);
+        assert!(evaluation.timed_out);
+        assert!(evaluation.evaluation_id.is_none());
+    }
+}
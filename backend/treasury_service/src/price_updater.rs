@@ -0,0 +1,189 @@
+use alloy_primitives::{Address, I256, U256};
+use async_trait::async_trait;
+use ethereum_client::EthereumClient;
+use price_oracle::{AggregatedPrice, FeedReader, OracleAggregator, RoundData};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+use crate::{Error, TreasuryService, TreasuryStatus};
+
+/// Reads Chainlink feeds through the service's Ethereum client
+pub struct ChainlinkFeedReader(pub Arc<EthereumClient>);
+
+#[async_trait]
+impl FeedReader for ChainlinkFeedReader {
+    async fn latest_round(&self, feed: &str) -> Result<RoundData, String> {
+        let feed = feed.parse::<Address>().map_err(|e| format!("Invalid feed address {}: {}", feed, e))?;
+
+        let (_, answer, _, updated_at, _) = self.0
+            .call_contract::<(U256, I256, U256, U256, U256)>(feed, "latestRoundData()", vec![])
+            .await
+            .map_err(|e| e.to_string())?;
+        let decimals = self.0.call_contract::<u8>(feed, "decimals()", vec![])
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(RoundData {
+            answer: i128::try_from(answer).map_err(|_| "Answer out of range".to_string())?,
+            decimals,
+            updated_at: updated_at.to::<u64>(),
+        })
+    }
+}
+
+/// What happened to one treasury's price in an update run
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum PriceUpdateOutcome {
+    Updated { old_price: U256, new_price: U256 },
+    Unchanged,
+    /// Sources disagree; the on-chain price is left alone until they converge or are overridden
+    Disputed { reference_price: U256 },
+    NoPrice { error: String },
+    Failed { error: String },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PriceUpdateResult {
+    pub token_id: String,
+    pub outcome: PriceUpdateOutcome,
+    pub price: Option<AggregatedPrice>,
+}
+
+/// Pushes aggregated oracle prices for active treasuries to the registry
+pub struct TreasuryPriceUpdater {
+    aggregator: Arc<OracleAggregator>,
+    treasury_service: Arc<TreasuryService>,
+    /// Fixed-point decimals of on-chain treasury prices
+    price_decimals: u32,
+}
+
+impl TreasuryPriceUpdater {
+    pub fn new(aggregator: Arc<OracleAggregator>, treasury_service: Arc<TreasuryService>, price_decimals: u32) -> Self {
+        Self { aggregator, treasury_service, price_decimals }
+    }
+
+    /// Aggregate and, where the sources agree, write the price of every active treasury
+    pub async fn update_all(&self) -> Result<Vec<PriceUpdateResult>, Error> {
+        let treasuries = self.treasury_service.get_all_treasuries().await?;
+
+        let mut results = Vec::new();
+        for treasury in treasuries.into_iter().filter(|t| t.status == TreasuryStatus::Active) {
+            let token_id = format!("0x{}", hex::encode(treasury.token_id));
+
+            let price = match self.aggregator.price(&token_id).await {
+                Ok(price) => price,
+                Err(e) => {
+                    results.push(PriceUpdateResult { token_id, outcome: PriceUpdateOutcome::NoPrice { error: e.to_string() }, price: None });
+                    continue;
+                }
+            };
+
+            let outcome = match plan_update(treasury.current_price, &price, self.price_decimals) {
+                Ok(PriceUpdateOutcome::Updated { old_price, new_price }) => {
+                    match self.treasury_service.update_treasury_price(treasury.token_id, new_price).await {
+                        Ok(()) => {
+                            info!("Updated price of {} from {} to {}", token_id, old_price, new_price);
+                            PriceUpdateOutcome::Updated { old_price, new_price }
+                        }
+                        Err(e) => PriceUpdateOutcome::Failed { error: e.to_string() },
+                    }
+                }
+                Ok(PriceUpdateOutcome::Disputed { reference_price }) => {
+                    warn!("Not updating {}: oracle price {} is disputed", token_id, price.price);
+                    PriceUpdateOutcome::Disputed { reference_price }
+                }
+                Ok(outcome) => outcome,
+                Err(e) => PriceUpdateOutcome::Failed { error: e.to_string() },
+            };
+
+            results.push(PriceUpdateResult { token_id, outcome, price: Some(price) });
+        }
+
+        Ok(results)
+    }
+}
+
+/// Decide whether an aggregated price should be written over the current on-chain price
+pub fn plan_update(current_price: U256, price: &AggregatedPrice, price_decimals: u32) -> Result<PriceUpdateOutcome, Error> {
+    let new_price = to_fixed_point(price.price, price_decimals)?;
+
+    if !price.allows_automatic_update() {
+        return Ok(PriceUpdateOutcome::Disputed { reference_price: new_price });
+    }
+    if new_price == current_price {
+        return Ok(PriceUpdateOutcome::Unchanged);
+    }
+    Ok(PriceUpdateOutcome::Updated { old_price: current_price, new_price })
+}
+
+/// Convert a decimal price to on-chain fixed point, rounding to `decimals` places
+pub(crate) fn to_fixed_point(price: Decimal, decimals: u32) -> Result<U256, Error> {
+    if price.is_sign_negative() || decimals > 28 {
+        return Err(Error::InvalidParameter(format!("Cannot express price {} with {} decimals", price, decimals)));
+    }
+
+    let rounded = price.round_dp(decimals);
+    let mantissa = U256::from(rounded.mantissa() as u128);
+    Ok(mantissa * U256::from(10u64).pow(U256::from(decimals - rounded.scale())))
+}
+
+/// Periodically push oracle prices to the registry
+pub fn spawn_price_updates(updater: Arc<TreasuryPriceUpdater>, interval: Duration) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match updater.update_all().await {
+                Ok(results) => {
+                    let disputed = results.iter().filter(|r| matches!(r.outcome, PriceUpdateOutcome::Disputed { .. })).count();
+                    if disputed > 0 {
+                        warn!("{} treasury prices are disputed and were not updated", disputed);
+                    }
+                }
+                Err(e) => tracing::error!("Treasury price update failed: {}", e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use price_oracle::PriceQuote;
+
+    fn aggregated(price: Decimal, disputed: bool) -> AggregatedPrice {
+        AggregatedPrice {
+            asset: "0x01".into(),
+            price,
+            confidence: if disputed { Decimal::ZERO } else { Decimal::ONE },
+            sources: vec![PriceQuote { source: "chainlink".into(), price, observed_at: Utc::now() }],
+            discarded: Vec::new(),
+            failed: Vec::new(),
+            disputed,
+            overridden: false,
+            aggregated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_disputed_price_blocks_update() {
+        let current = U256::from(995_000u64);
+
+        assert_eq!(
+            plan_update(current, &aggregated(Decimal::new(9960, 1), false), 3).unwrap(),
+            PriceUpdateOutcome::Updated { old_price: current, new_price: U256::from(996_000u64) }
+        );
+        assert_eq!(
+            plan_update(current, &aggregated(Decimal::new(995, 0), false), 3).unwrap(),
+            PriceUpdateOutcome::Unchanged
+        );
+        assert_eq!(
+            plan_update(current, &aggregated(Decimal::new(1200, 0), true), 3).unwrap(),
+            PriceUpdateOutcome::Disputed { reference_price: U256::from(1_200_000u64) }
+        );
+    }
+}
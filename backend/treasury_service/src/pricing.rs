@@ -0,0 +1,270 @@
+use alloy_primitives::U256;
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, Weekday};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Serialize, Deserialize};
+use crate::{price_updater::to_fixed_point, Error, TreasuryInfo, TreasuryType};
+
+/// Notes and bonds pay semi-annual coupons
+pub const COUPONS_PER_YEAR: u32 = 2;
+
+/// Prices are quoted per this much face value
+const PAR: Decimal = dec!(100);
+
+/// Day-count convention used for accrual or discounting
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DayCount {
+    /// Actual days over actual days in the coupon period (notes and bonds)
+    ActualActual,
+    /// Actual days over a 360-day year (bills)
+    Actual360,
+}
+
+/// Clean and dirty price of a treasury for one settlement date, per 100 of face value
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SettlementPrice {
+    pub treasury_type: TreasuryType,
+    pub settlement_date: NaiveDate,
+    pub day_count: DayCount,
+    pub clean_price: Decimal,
+    pub accrued_interest: Decimal,
+    pub dirty_price: Decimal,
+    /// Days accrued since the last coupon; zero for bills
+    pub accrued_days: i64,
+    /// Days in the current coupon period, or to maturity for bills
+    pub period_days: i64,
+    pub previous_coupon: Option<NaiveDate>,
+    pub next_coupon: Option<NaiveDate>,
+}
+
+impl SettlementPrice {
+    /// Accrued interest in the registry's fixed-point price units
+    pub fn accrued_fixed_point(&self, price_decimals: u32) -> Result<U256, Error> {
+        to_fixed_point(self.accrued_interest, price_decimals)
+    }
+
+    /// Dirty price in the registry's fixed-point price units
+    pub fn dirty_fixed_point(&self, price_decimals: u32) -> Result<U256, Error> {
+        to_fixed_point(self.dirty_price, price_decimals)
+    }
+}
+
+/// Next business day after `trade_date`, the standard T+1 treasury settlement
+pub fn default_settlement_date(trade_date: NaiveDate) -> NaiveDate {
+    let mut date = trade_date + Duration::days(1);
+    while matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+        date = date + Duration::days(1);
+    }
+    date
+}
+
+/// Price a registry treasury for settlement on `settlement`.
+///
+/// Notes and bonds take their clean price from the registry and accrue the coupon
+/// implied by `yield_rate`; bills are priced from `yield_rate` as a discount yield.
+pub fn settlement_price(info: &TreasuryInfo, settlement: NaiveDate, price_decimals: u32) -> Result<SettlementPrice, Error> {
+    let issue = unix_date(info.issuance_date)?;
+    let maturity = unix_date(info.maturity_date)?;
+    let rate = Decimal::from(info.yield_rate) / dec!(10000);
+
+    match TreasuryType::for_term(info.issuance_date, info.maturity_date) {
+        TreasuryType::TBill => {
+            let price = bill_price(rate, settlement, maturity)?;
+            Ok(SettlementPrice {
+                treasury_type: TreasuryType::TBill,
+                settlement_date: settlement,
+                day_count: DayCount::Actual360,
+                clean_price: price,
+                accrued_interest: Decimal::ZERO,
+                dirty_price: price,
+                accrued_days: 0,
+                period_days: (maturity - settlement).num_days(),
+                previous_coupon: None,
+                next_coupon: None,
+            })
+        }
+        treasury_type => {
+            let clean_price = from_fixed_point(info.current_price, price_decimals)?;
+            let accrual = accrued_interest(rate, issue, maturity, settlement)?;
+            Ok(SettlementPrice {
+                treasury_type,
+                settlement_date: settlement,
+                day_count: DayCount::ActualActual,
+                clean_price,
+                accrued_interest: accrual.amount,
+                dirty_price: clean_price + accrual.amount,
+                accrued_days: accrual.accrued_days,
+                period_days: accrual.period_days,
+                previous_coupon: Some(accrual.previous_coupon),
+                next_coupon: Some(accrual.next_coupon),
+            })
+        }
+    }
+}
+
+/// Interest accrued in the current coupon period
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Accrual {
+    /// Per 100 of face value
+    pub amount: Decimal,
+    pub accrued_days: i64,
+    pub period_days: i64,
+    pub previous_coupon: NaiveDate,
+    pub next_coupon: NaiveDate,
+}
+
+/// Actual/actual accrued interest on a semi-annual coupon of `coupon_rate` (e.g. 0.08).
+///
+/// Coupon dates run back from maturity in six-month steps, staying on the last day of
+/// the month when maturity does. In a short first period interest accrues from issue.
+pub fn accrued_interest(
+    coupon_rate: Decimal,
+    issue: NaiveDate,
+    maturity: NaiveDate,
+    settlement: NaiveDate,
+) -> Result<Accrual, Error> {
+    if settlement < issue || settlement >= maturity {
+        return Err(Error::InvalidParameter(format!(
+            "Settlement {} is outside the life of a security issued {} maturing {}", settlement, issue, maturity
+        )));
+    }
+
+    let mut periods_back = 0;
+    let (previous_coupon, next_coupon) = loop {
+        let next = coupon_date(maturity, periods_back)?;
+        let previous = coupon_date(maturity, periods_back + 1)?;
+        if previous <= settlement {
+            break (previous, next);
+        }
+        periods_back += 1;
+    };
+
+    let accrued_days = (settlement - previous_coupon.max(issue)).num_days();
+    let period_days = (next_coupon - previous_coupon).num_days();
+    let coupon = PAR * coupon_rate / Decimal::from(COUPONS_PER_YEAR);
+
+    Ok(Accrual {
+        amount: coupon * Decimal::from(accrued_days) / Decimal::from(period_days),
+        accrued_days,
+        period_days,
+        previous_coupon,
+        next_coupon,
+    })
+}
+
+/// Bill price per 100 from its discount yield, actual/360
+pub fn bill_price(discount_rate: Decimal, settlement: NaiveDate, maturity: NaiveDate) -> Result<Decimal, Error> {
+    if settlement >= maturity {
+        return Err(Error::InvalidParameter(format!("Settlement {} is not before maturity {}", settlement, maturity)));
+    }
+    let days = Decimal::from((maturity - settlement).num_days());
+    Ok(PAR * (Decimal::ONE - discount_rate * days / dec!(360)))
+}
+
+/// Coupon date `periods_back` semi-annual periods before maturity
+fn coupon_date(maturity: NaiveDate, periods_back: u32) -> Result<NaiveDate, Error> {
+    let months = Months::new(12 / COUPONS_PER_YEAR * periods_back);
+    let date = maturity.checked_sub_months(months)
+        .ok_or_else(|| Error::InvalidParameter(format!("No coupon date {} periods before {}", periods_back, maturity)))?;
+    Ok(if is_month_end(maturity) { month_end(date) } else { date })
+}
+
+fn is_month_end(date: NaiveDate) -> bool {
+    date.succ_opt().map_or(true, |next| next.month() != date.month())
+}
+
+fn month_end(date: NaiveDate) -> NaiveDate {
+    let mut end = date;
+    while !is_month_end(end) {
+        end = end.succ_opt().unwrap_or(end);
+    }
+    end
+}
+
+fn unix_date(timestamp: u64) -> Result<NaiveDate, Error> {
+    i64::try_from(timestamp).ok()
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+        .map(|datetime| datetime.date_naive())
+        .ok_or_else(|| Error::InvalidParameter(format!("Invalid timestamp {}", timestamp)))
+}
+
+fn from_fixed_point(value: U256, decimals: u32) -> Result<Decimal, Error> {
+    let mantissa = i128::try_from(value)
+        .map_err(|_| Error::InvalidParameter(format!("Price {} out of range", value)))?;
+    Decimal::try_from_i128_with_scale(mantissa, decimals)
+        .map_err(|e| Error::InvalidParameter(format!("Price {} with {} decimals: {}", value, decimals, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_accrued_interest_matches_textbook_example() {
+        // Fabozzi: 8% coupon, 138 of 184 days accrued between 1 March and 1 September
+        let accrual = accrued_interest(dec!(0.08), date(2002, 3, 1), date(2012, 9, 1), date(2002, 7, 17)).unwrap();
+        assert_eq!(accrual.accrued_days, 138);
+        assert_eq!(accrual.period_days, 184);
+        assert_eq!(accrual.amount, dec!(3));
+        assert_eq!((accrual.previous_coupon, accrual.next_coupon), (date(2002, 3, 1), date(2002, 9, 1)));
+
+        // Month-end maturities keep month-end coupons through leap years
+        let accrual = accrued_interest(dec!(0.04), date(2021, 8, 31), date(2031, 8, 31), date(2024, 3, 15)).unwrap();
+        assert_eq!((accrual.previous_coupon, accrual.next_coupon), (date(2024, 2, 29), date(2024, 8, 31)));
+        assert_eq!((accrual.accrued_days, accrual.period_days), (15, 184));
+    }
+
+    #[test]
+    fn test_bill_uses_discount_yield_actual_360() {
+        // 26-week bill at a 0.150% discount rate prices at 99.924167
+        let price = bill_price(dec!(0.0015), date(2021, 1, 5), date(2021, 7, 6)).unwrap();
+        assert_eq!(price.round_dp(6), dec!(99.924167));
+
+        let info = TreasuryInfo {
+            token_address: Default::default(),
+            metadata_uri: String::new(),
+            status: crate::TreasuryStatus::Active,
+            current_price: U256::ZERO,
+            issuance_date: 1_609_804_800, // 2021-01-05
+            maturity_date: 1_625_529_600, // 2021-07-06
+            yield_rate: 15,
+            issuer: Default::default(),
+            historical_data_hash: Default::default(),
+        };
+        let priced = settlement_price(&info, date(2021, 1, 5), 18).unwrap();
+        assert_eq!(priced.day_count, DayCount::Actual360);
+        assert_eq!(priced.accrued_interest, Decimal::ZERO);
+        assert_eq!(priced.dirty_price, priced.clean_price);
+    }
+
+    #[test]
+    fn test_note_dirty_price_adds_accrual_to_registry_price() {
+        let info = TreasuryInfo {
+            token_address: Default::default(),
+            metadata_uri: String::new(),
+            status: crate::TreasuryStatus::Active,
+            current_price: U256::from(99_500_000u64), // 99.5 at 6 decimals
+            issuance_date: 1_014_940_800, // 2002-03-01
+            maturity_date: 1_346_457_600, // 2012-09-01
+            yield_rate: 800,
+            issuer: Default::default(),
+            historical_data_hash: Default::default(),
+        };
+        let priced = settlement_price(&info, date(2002, 7, 17), 6).unwrap();
+        assert_eq!(priced.treasury_type, TreasuryType::TBond);
+        assert_eq!(priced.clean_price, dec!(99.5));
+        assert_eq!(priced.dirty_price, dec!(102.5));
+        assert_eq!(priced.accrued_fixed_point(6).unwrap(), U256::from(3_000_000u64));
+    }
+
+    #[test]
+    fn test_settlement_skips_weekends() {
+        assert_eq!(default_settlement_date(date(2024, 3, 15)), date(2024, 3, 18)); // Friday
+        assert_eq!(default_settlement_date(date(2024, 3, 12)), date(2024, 3, 13));
+    }
+}
@@ -0,0 +1,828 @@
+// Reconciliation of local order and settlement records against trading contract events
+use alloy_primitives::{Address, U256};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use ethereum_client::{EthereumClient, Log};
+use serde::{Serialize, Deserialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+use tracing::{error, info, warn};
+use crate::admin_cli::env_number;
+use crate::{Error, SettlementEngine, SettlementFilter};
+
+const ORDER_PLACED: &str = "OrderPlaced(uint256,address,bytes32,uint256)";
+const ORDER_FILLED: &str = "OrderFilled(uint256,uint256,uint256)";
+const ORDER_CANCELLED: &str = "OrderCancelled(uint256)";
+
+/// Reports kept for the report endpoint
+const REPORT_HISTORY: usize = 50;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LocalOrderStatus {
+    /// Submitted; the placement transaction has not been seen on chain yet
+    Pending,
+    Open,
+    PartiallyFilled,
+    Filled,
+    Cancelled,
+}
+
+/// The service's own record of an order it submitted to the trading contract
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalOrder {
+    pub order_id: u64,
+    pub trader: Address,
+    #[serde(with = "quantera_types::wire::token_id")]
+    pub token_id: [u8; 32],
+    #[serde(with = "quantera_types::wire::u256_decimal")]
+    pub quantity: U256,
+    #[serde(with = "quantera_types::wire::u256_decimal")]
+    pub filled_quantity: U256,
+    pub status: LocalOrderStatus,
+    /// Trades that filled the order, each settled separately
+    pub trade_ids: Vec<u64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl LocalOrder {
+    pub fn pending(order_id: u64, trader: Address, token_id: [u8; 32], quantity: U256) -> Self {
+        let now = Utc::now();
+        Self {
+            order_id,
+            trader,
+            token_id,
+            quantity,
+            filled_quantity: U256::ZERO,
+            status: LocalOrderStatus::Pending,
+            trade_ids: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// An order as its placement event describes it, for rebuilding the ledger from chain history
+    pub fn placed(event: &OrderEvent) -> Option<Self> {
+        let OrderEventKind::Placed { trader, token_id, quantity } = &event.kind else { return None };
+        Some(Self {
+            status: LocalOrderStatus::Open,
+            ..Self::pending(event.order_id, *trader, *token_id, *quantity)
+        })
+    }
+}
+
+/// Orders submitted by this service, keyed by contract order id
+#[derive(Default)]
+pub struct OrderLedger {
+    orders: RwLock<HashMap<u64, LocalOrder>>,
+}
+
+impl OrderLedger {
+    pub async fn record(&self, order: LocalOrder) {
+        self.orders.write().await.insert(order.order_id, order);
+    }
+
+    pub async fn get(&self, order_id: u64) -> Option<LocalOrder> {
+        self.orders.read().await.get(&order_id).cloned()
+    }
+
+    /// All orders, oldest first
+    pub async fn list(&self) -> Vec<LocalOrder> {
+        let mut orders: Vec<_> = self.orders.read().await.values().cloned().collect();
+        orders.sort_by_key(|order| (order.created_at, order.order_id));
+        orders
+    }
+
+    /// Replace every order at once with a rebuilt set, returning how many pending orders were
+    /// carried over. Pending orders have no chain events to rebuild them from, so they are kept,
+    /// and rebuilt orders keep the creation time of the record they replace.
+    pub(crate) async fn replace(&self, mut rebuilt: HashMap<u64, LocalOrder>) -> usize {
+        let mut orders = self.orders.write().await;
+        let mut carried = 0;
+        for (order_id, order) in orders.iter() {
+            match rebuilt.get_mut(order_id) {
+                Some(replacement) => replacement.created_at = order.created_at,
+                None if order.status == LocalOrderStatus::Pending => {
+                    rebuilt.insert(*order_id, order.clone());
+                    carried += 1;
+                }
+                None => {}
+            }
+        }
+        *orders = rebuilt;
+        carried
+    }
+}
+
+/// A trading contract event, decoded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderEventKind {
+    Placed { trader: Address, token_id: [u8; 32], quantity: U256 },
+    Filled { trade_id: u64, filled_quantity: U256 },
+    Cancelled,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderEvent {
+    pub order_id: u64,
+    pub block_number: u64,
+    pub log_index: u32,
+    pub kind: OrderEventKind,
+}
+
+/// Order lifecycle events as read from the chain
+#[async_trait]
+pub trait OrderEventSource: Send + Sync {
+    async fn head(&self) -> Result<u64, Error>;
+
+    /// Events in `from_block..=to_block`, in chain order
+    async fn events(&self, from_block: u64, to_block: u64) -> Result<Vec<OrderEvent>, Error>;
+}
+
+/// Reads OrderPlaced, OrderFilled and OrderCancelled from the trading contract
+pub struct ContractOrderEventSource {
+    client: Arc<EthereumClient>,
+    trading_contracts: Vec<Address>,
+}
+
+impl ContractOrderEventSource {
+    pub fn new(client: Arc<EthereumClient>, trading_contract: Address) -> Self {
+        Self::for_contracts(client, vec![trading_contract])
+    }
+
+    /// Events of several trading contracts merged in chain order, e.g. a contract and the one
+    /// it replaced; their order ids must not overlap
+    pub fn for_contracts(client: Arc<EthereumClient>, trading_contracts: Vec<Address>) -> Self {
+        Self { client, trading_contracts }
+    }
+}
+
+#[async_trait]
+impl OrderEventSource for ContractOrderEventSource {
+    async fn head(&self) -> Result<u64, Error> {
+        Ok(self.client.get_block_number().await?)
+    }
+
+    async fn events(&self, from_block: u64, to_block: u64) -> Result<Vec<OrderEvent>, Error> {
+        let mut events = Vec::new();
+        for contract in &self.trading_contracts {
+            for event in [ORDER_PLACED, ORDER_FILLED, ORDER_CANCELLED] {
+                for log in self.client.get_logs(*contract, event, from_block, to_block).await? {
+                    events.push(decode_order_event(event, &log)?);
+                }
+            }
+        }
+        events.sort_by_key(|event| (event.block_number, event.log_index));
+        Ok(events)
+    }
+}
+
+fn decode_order_event(event: &str, log: &Log) -> Result<OrderEvent, Error> {
+    let topic = |index: usize| log.topics.get(index)
+        .map(|topic| topic.0)
+        .ok_or_else(|| Error::Decoding(format!("{} log {:?} is missing topic {}", event, log.transaction_hash, index)));
+    let data_word = |index: usize| log.data.get(index * 32..(index + 1) * 32)
+        .map(U256::from_be_slice)
+        .ok_or_else(|| Error::Decoding(format!("{} log {:?} is missing data word {}", event, log.transaction_hash, index)));
+
+    let kind = match event {
+        ORDER_PLACED => OrderEventKind::Placed {
+            trader: Address::from_slice(&topic(2)?[12..]),
+            token_id: topic(3)?,
+            quantity: data_word(0)?,
+        },
+        ORDER_FILLED => OrderEventKind::Filled {
+            trade_id: word_u64(&topic(2)?)?,
+            filled_quantity: data_word(0)?,
+        },
+        _ => OrderEventKind::Cancelled,
+    };
+    Ok(OrderEvent {
+        order_id: word_u64(&topic(1)?)?,
+        block_number: log.block_number,
+        log_index: log.log_index,
+        kind,
+    })
+}
+
+/// A uint256 word that must fit the backend's u64 ids
+fn word_u64(word: &[u8; 32]) -> Result<u64, Error> {
+    if word[..24].iter().any(|byte| *byte != 0) {
+        return Err(Error::Decoding(format!("Id 0x{} does not fit in 64 bits", hex::encode(word))));
+    }
+    Ok(u64::from_be_bytes(word[24..].try_into().expect("8 bytes")))
+}
+
+/// Last block whose events have been reconciled
+pub trait CursorStore: Send + Sync {
+    fn load(&self) -> Result<Option<u64>, Error>;
+    fn save(&self, block: u64) -> Result<(), Error>;
+}
+
+/// Cursor that is lost on restart; the next run starts from the configured start block
+#[derive(Default)]
+pub struct MemoryCursorStore(std::sync::Mutex<Option<u64>>);
+
+impl CursorStore for MemoryCursorStore {
+    fn load(&self) -> Result<Option<u64>, Error> {
+        Ok(*self.0.lock().unwrap())
+    }
+
+    fn save(&self, block: u64) -> Result<(), Error> {
+        *self.0.lock().unwrap() = Some(block);
+        Ok(())
+    }
+}
+
+/// Cursor kept as a block number in a file, written via a temporary file so a crash
+/// mid-write leaves the previous cursor
+pub struct FileCursorStore {
+    path: PathBuf,
+}
+
+impl FileCursorStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl CursorStore for FileCursorStore {
+    fn load(&self) -> Result<Option<u64>, Error> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents.trim().parse::<u64>()
+                .map(Some)
+                .map_err(|_| Error::InvalidState(format!("Reconciliation cursor {} is not a block number", self.path.display()))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(Error::InvalidState(format!("Cannot read reconciliation cursor {}: {}", self.path.display(), e))),
+        }
+    }
+
+    fn save(&self, block: u64) -> Result<(), Error> {
+        let temp = self.path.with_extension("tmp");
+        std::fs::write(&temp, block.to_string())
+            .and_then(|_| std::fs::rename(&temp, &self.path))
+            .map_err(|e| Error::InvalidState(format!("Cannot write reconciliation cursor {}: {}", self.path.display(), e)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscrepancyKind {
+    /// A pending order's placement was mined
+    PlacementConfirmed,
+    /// The chain filled an order recorded as pending or open
+    MissedFill,
+    /// The chain cancelled an order recorded as pending or open
+    MissedCancellation,
+    /// An order on chain that this service has no record of
+    UnknownOrder,
+    /// The chain and the local record disagree in a way that cannot be repaired
+    StatusConflict,
+    /// A fill with no settlement for its trade
+    FillWithoutSettlement,
+    /// A pending order whose placement has not been seen within the grace period; the
+    /// transaction was likely dropped
+    PlacementNotSeen,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Resolution {
+    /// The local record was updated to match the chain
+    Repaired,
+    /// Left for an operator
+    Alerted,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Discrepancy {
+    pub kind: DiscrepancyKind,
+    pub resolution: Resolution,
+    pub order_id: u64,
+    pub trade_id: Option<u64>,
+    /// Block of the event that exposed it; None for placements not seen on chain
+    pub block_number: Option<u64>,
+    pub local_status: Option<LocalOrderStatus>,
+    pub detail: String,
+}
+
+/// Outcome of one reconciliation run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconciliationReport {
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+    /// Blocks read this run; None when the cursor was already at the head
+    pub from_block: Option<u64>,
+    pub to_block: Option<u64>,
+    pub events_read: usize,
+    pub repaired: usize,
+    pub alerted: usize,
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+/// Told about drift reconciliation could not repair
+#[async_trait]
+pub trait DriftAlerter: Send + Sync {
+    async fn drift_detected(&self, discrepancy: &Discrepancy);
+}
+
+/// Logs drift as an alert for log-based alerting
+pub struct LogDriftAlerter;
+
+#[async_trait]
+impl DriftAlerter for LogDriftAlerter {
+    async fn drift_detected(&self, discrepancy: &Discrepancy) {
+        error!("[ALERT] Order drift {:?} on order {}: {}", discrepancy.kind, discrepancy.order_id, discrepancy.detail);
+    }
+}
+
+/// POSTs unrepaired drift as JSON
+pub struct WebhookDriftAlerter {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookDriftAlerter {
+    pub fn new(url: String) -> Self {
+        Self { client: reqwest::Client::new(), url }
+    }
+}
+
+#[async_trait]
+impl DriftAlerter for WebhookDriftAlerter {
+    async fn drift_detected(&self, discrepancy: &Discrepancy) {
+        let body = serde_json::json!({
+            "event": "order_drift",
+            "discrepancy": discrepancy,
+        });
+        if let Err(e) = self.client.post(&self.url).json(&body).send().await {
+            warn!("Order drift webhook for order {} failed: {}", discrepancy.order_id, e);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReconciliationConfig {
+    /// First block read when there is no stored cursor, normally the trading contract's
+    /// deployment block
+    pub start_block: u64,
+    /// Most blocks read per log query
+    pub max_block_range: u64,
+    /// How long a pending order may go unseen on chain before it is reported
+    pub placement_grace: Duration,
+}
+
+impl Default for ReconciliationConfig {
+    fn default() -> Self {
+        Self { start_block: 0, max_block_range: 2_000, placement_grace: Duration::hours(1) }
+    }
+}
+
+impl ReconciliationConfig {
+    /// Read `RECONCILIATION_START_BLOCK`, `RECONCILIATION_BLOCK_RANGE` and
+    /// `RECONCILIATION_PLACEMENT_GRACE_SECS`
+    pub fn from_env() -> Result<Self, Error> {
+        let defaults = Self::default();
+        let max_block_range = env_number("RECONCILIATION_BLOCK_RANGE", defaults.max_block_range)?;
+        if max_block_range == 0 {
+            return Err(Error::InvalidParameter("RECONCILIATION_BLOCK_RANGE must be positive".into()));
+        }
+        Ok(Self {
+            start_block: env_number("RECONCILIATION_START_BLOCK", defaults.start_block)?,
+            max_block_range,
+            placement_grace: Duration::seconds(env_number(
+                "RECONCILIATION_PLACEMENT_GRACE_SECS",
+                defaults.placement_grace.num_seconds() as u64,
+            )? as i64),
+        })
+    }
+}
+
+/// Compares the local order ledger and settlements with trading contract events since the
+/// stored cursor. Drift with only one explanation is repaired; the rest is alerted.
+pub struct OrderReconciler {
+    source: Arc<dyn OrderEventSource>,
+    ledger: Arc<OrderLedger>,
+    settlements: Arc<SettlementEngine>,
+    cursor: Arc<dyn CursorStore>,
+    alerter: Arc<dyn DriftAlerter>,
+    config: ReconciliationConfig,
+    reports: RwLock<VecDeque<ReconciliationReport>>,
+    /// Pending orders already reported as not seen, so each is alerted once
+    unseen_reported: Mutex<HashSet<u64>>,
+    running: Mutex<()>,
+}
+
+impl OrderReconciler {
+    pub fn new(
+        source: Arc<dyn OrderEventSource>,
+        ledger: Arc<OrderLedger>,
+        settlements: Arc<SettlementEngine>,
+        cursor: Arc<dyn CursorStore>,
+        config: ReconciliationConfig,
+    ) -> Self {
+        Self {
+            source,
+            ledger,
+            settlements,
+            cursor,
+            alerter: Arc::new(LogDriftAlerter),
+            config,
+            reports: RwLock::new(VecDeque::new()),
+            unseen_reported: Mutex::new(HashSet::new()),
+            running: Mutex::new(()),
+        }
+    }
+
+    pub fn with_alerter(mut self, alerter: Arc<dyn DriftAlerter>) -> Self {
+        self.alerter = alerter;
+        self
+    }
+
+    pub fn ledger(&self) -> &Arc<OrderLedger> {
+        &self.ledger
+    }
+
+    /// Swap a rebuilt ledger in as of `to_block`, between runs so no event is applied to the
+    /// old ledger after the swap. Returns how many pending orders were carried over.
+    pub(crate) async fn swap_ledger(&self, rebuilt: HashMap<u64, LocalOrder>, to_block: u64) -> Result<usize, Error> {
+        let _running = self.running.lock().await;
+        let carried = self.ledger.replace(rebuilt).await;
+        self.cursor.save(to_block)?;
+        Ok(carried)
+    }
+
+    /// Most recent run, if any
+    pub async fn latest_report(&self) -> Option<ReconciliationReport> {
+        self.reports.read().await.back().cloned()
+    }
+
+    /// Recent runs, newest first
+    pub async fn reports(&self) -> Vec<ReconciliationReport> {
+        self.reports.read().await.iter().rev().cloned().collect()
+    }
+
+    /// Reconcile every block from the cursor to the head. The cursor is saved after each
+    /// range, so a run interrupted part way resumes from the last completed range.
+    pub async fn run(&self) -> Result<ReconciliationReport, Error> {
+        let _running = self.running.lock().await;
+        let started_at = Utc::now();
+        let mut report = ReconciliationReport {
+            started_at,
+            completed_at: started_at,
+            from_block: None,
+            to_block: None,
+            events_read: 0,
+            repaired: 0,
+            alerted: 0,
+            discrepancies: Vec::new(),
+        };
+
+        let head = self.source.head().await?;
+        let mut from = match self.cursor.load()? {
+            Some(block) => block + 1,
+            None => self.config.start_block,
+        };
+        while from <= head {
+            let to = head.min(from + self.config.max_block_range - 1);
+            let events = self.source.events(from, to).await?;
+            report.events_read += events.len();
+            for event in &events {
+                self.reconcile_event(event, &mut report.discrepancies).await;
+            }
+            self.cursor.save(to)?;
+            report.from_block.get_or_insert(from);
+            report.to_block = Some(to);
+            from = to + 1;
+        }
+        self.check_unseen_placements(&mut report.discrepancies).await;
+
+        for discrepancy in &report.discrepancies {
+            match discrepancy.resolution {
+                Resolution::Repaired => {
+                    report.repaired += 1;
+                    info!("[AUDIT] Reconciliation repaired order {}: {}", discrepancy.order_id, discrepancy.detail);
+                }
+                Resolution::Alerted => {
+                    report.alerted += 1;
+                    self.alerter.drift_detected(discrepancy).await;
+                }
+            }
+        }
+        report.completed_at = Utc::now();
+
+        let mut reports = self.reports.write().await;
+        if reports.len() == REPORT_HISTORY {
+            reports.pop_front();
+        }
+        reports.push_back(report.clone());
+        Ok(report)
+    }
+
+    async fn reconcile_event(&self, event: &OrderEvent, found: &mut Vec<Discrepancy>) {
+        let mut orders = self.ledger.orders.write().await;
+        let Some(order) = orders.get_mut(&event.order_id) else {
+            // Fills and cancellations of unknown orders follow an already reported placement
+            if let OrderEventKind::Placed { trader, .. } = &event.kind {
+                found.push(discrepancy(DiscrepancyKind::UnknownOrder, Resolution::Alerted, event, None,
+                    format!("Order placed on chain by {} has no local record", trader)));
+            }
+            return;
+        };
+        let local_status = order.status;
+        // Replayed ranges after a crash see the same fill again; it changes nothing and is not re-checked
+        let already_applied = matches!(&event.kind, OrderEventKind::Filled { trade_id, .. } if order.trade_ids.contains(trade_id));
+        if let Some((kind, resolution, detail)) = apply_order_event(order, event) {
+            found.push(discrepancy(kind, resolution, event, Some(local_status), detail));
+        }
+        drop(orders);
+
+        if let OrderEventKind::Filled { trade_id, .. } = &event.kind {
+            let filter = SettlementFilter { trade_id: Some(*trade_id), ..Default::default() };
+            if !already_applied && self.settlements.list(&filter).await.is_empty() {
+                found.push(discrepancy(DiscrepancyKind::FillWithoutSettlement, Resolution::Alerted, event, Some(local_status),
+                    format!("Trade {} has no settlement", trade_id)));
+            }
+        }
+    }
+
+    async fn check_unseen_placements(&self, found: &mut Vec<Discrepancy>) {
+        let cutoff = Utc::now() - self.config.placement_grace;
+        let mut reported = self.unseen_reported.lock().await;
+        for order in self.ledger.orders.read().await.values() {
+            if order.status == LocalOrderStatus::Pending && order.created_at <= cutoff && reported.insert(order.order_id) {
+                found.push(Discrepancy {
+                    kind: DiscrepancyKind::PlacementNotSeen,
+                    resolution: Resolution::Alerted,
+                    order_id: order.order_id,
+                    trade_id: None,
+                    block_number: None,
+                    local_status: Some(order.status),
+                    detail: format!("Pending since {} with no OrderPlaced event", order.created_at),
+                });
+            }
+        }
+    }
+}
+
+/// Bring an order up to date with one of its chain events, returning the repair made or the
+/// conflict left for an operator. Live reconciliation and ledger replay both apply events
+/// here; applying the same event twice changes nothing the second time.
+pub(crate) fn apply_order_event(order: &mut LocalOrder, event: &OrderEvent) -> Option<(DiscrepancyKind, Resolution, String)> {
+    let local_status = order.status;
+    match &event.kind {
+        OrderEventKind::Placed { .. } => {
+            if local_status != LocalOrderStatus::Pending {
+                return None;
+            }
+            order.status = LocalOrderStatus::Open;
+            order.updated_at = Utc::now();
+            Some((DiscrepancyKind::PlacementConfirmed, Resolution::Repaired, "Pending order confirmed on chain; marked open".to_string()))
+        }
+        OrderEventKind::Filled { trade_id, filled_quantity } => {
+            if order.trade_ids.contains(trade_id) {
+                return None;
+            }
+            match local_status {
+                LocalOrderStatus::Pending | LocalOrderStatus::Open | LocalOrderStatus::PartiallyFilled
+                | LocalOrderStatus::Filled => {
+                    order.trade_ids.push(*trade_id);
+                    order.filled_quantity = order.filled_quantity.saturating_add(*filled_quantity);
+                    order.status = if order.filled_quantity >= order.quantity {
+                        LocalOrderStatus::Filled
+                    } else {
+                        LocalOrderStatus::PartiallyFilled
+                    };
+                    order.updated_at = Utc::now();
+                    let detail = format!("Trade {} filled {} on chain; marked {:?}", trade_id, filled_quantity, order.status);
+                    Some((DiscrepancyKind::MissedFill, Resolution::Repaired, detail))
+                }
+                LocalOrderStatus::Cancelled => Some((DiscrepancyKind::StatusConflict, Resolution::Alerted,
+                    format!("Trade {} filled an order recorded as cancelled", trade_id))),
+            }
+        }
+        OrderEventKind::Cancelled => match local_status {
+            LocalOrderStatus::Cancelled => None,
+            LocalOrderStatus::Pending | LocalOrderStatus::Open | LocalOrderStatus::PartiallyFilled => {
+                order.status = LocalOrderStatus::Cancelled;
+                order.updated_at = Utc::now();
+                Some((DiscrepancyKind::MissedCancellation, Resolution::Repaired, "Order cancelled on chain; marked cancelled".to_string()))
+            }
+            LocalOrderStatus::Filled => Some((DiscrepancyKind::StatusConflict, Resolution::Alerted,
+                "Order recorded as filled was cancelled on chain".to_string())),
+        },
+    }
+}
+
+fn discrepancy(
+    kind: DiscrepancyKind,
+    resolution: Resolution,
+    event: &OrderEvent,
+    local_status: Option<LocalOrderStatus>,
+    detail: String,
+) -> Discrepancy {
+    let trade_id = match event.kind {
+        OrderEventKind::Filled { trade_id, .. } => Some(trade_id),
+        _ => None,
+    };
+    Discrepancy { kind, resolution, order_id: event.order_id, trade_id, block_number: Some(event.block_number), local_status, detail }
+}
+
+/// Reconcile on an interval
+pub fn spawn_reconciliation(reconciler: Arc<OrderReconciler>, interval: std::time::Duration) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match reconciler.run().await {
+                Ok(report) if report.alerted > 0 => {
+                    warn!("Reconciliation found {} unrepaired discrepancies", report.alerted);
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Order reconciliation failed: {}", e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::H256;
+    use ethereum_client::FinalityCheck;
+    use quantera_types::Finality;
+    use crate::clients::trading_client::Trade;
+    use crate::{Settlement, SettlementChain, SettlementConfig};
+
+    struct MockSource {
+        head: u64,
+        events: Vec<OrderEvent>,
+        ranges: std::sync::Mutex<Vec<(u64, u64)>>,
+    }
+
+    #[async_trait]
+    impl OrderEventSource for MockSource {
+        async fn head(&self) -> Result<u64, Error> {
+            Ok(self.head)
+        }
+
+        async fn events(&self, from_block: u64, to_block: u64) -> Result<Vec<OrderEvent>, Error> {
+            self.ranges.lock().unwrap().push((from_block, to_block));
+            Ok(self.events.iter()
+                .filter(|event| (from_block..=to_block).contains(&event.block_number))
+                .cloned()
+                .collect())
+        }
+    }
+
+    /// Settlements are only recorded in these tests, never settled
+    struct NoChain;
+
+    #[async_trait]
+    impl SettlementChain for NoChain {
+        async fn stablecoin_balance(&self, _owner: Address) -> Result<U256, Error> { unimplemented!() }
+        async fn stablecoin_allowance(&self, _owner: Address) -> Result<U256, Error> { unimplemented!() }
+        async fn token_balance(&self, _token: Address, _owner: Address) -> Result<U256, Error> { unimplemented!() }
+        async fn execute_swap(&self, _settlement: &Settlement) -> Result<H256, Error> { unimplemented!() }
+        async fn check_finality(&self, _tx_hash: H256, _recorded: &Finality) -> Result<FinalityCheck, Error> { unimplemented!() }
+    }
+
+    #[derive(Default)]
+    struct RecordingAlerter(std::sync::Mutex<Vec<DiscrepancyKind>>);
+
+    #[async_trait]
+    impl DriftAlerter for RecordingAlerter {
+        async fn drift_detected(&self, discrepancy: &Discrepancy) {
+            self.0.lock().unwrap().push(discrepancy.kind);
+        }
+    }
+
+    fn event(order_id: u64, block_number: u64, kind: OrderEventKind) -> OrderEvent {
+        OrderEvent { order_id, block_number, log_index: 0, kind }
+    }
+
+    fn placed(order_id: u64, block_number: u64) -> OrderEvent {
+        event(order_id, block_number, OrderEventKind::Placed {
+            trader: Address::repeat_byte(1),
+            token_id: [7u8; 32],
+            quantity: U256::from(10u64),
+        })
+    }
+
+    fn filled(order_id: u64, block_number: u64, trade_id: u64) -> OrderEvent {
+        event(order_id, block_number, OrderEventKind::Filled { trade_id, filled_quantity: U256::from(10u64) })
+    }
+
+    fn order(order_id: u64, status: LocalOrderStatus) -> LocalOrder {
+        LocalOrder { status, ..LocalOrder::pending(order_id, Address::repeat_byte(1), [7u8; 32], U256::from(10u64)) }
+    }
+
+    async fn settlements_for(trade_ids: &[u64]) -> Arc<SettlementEngine> {
+        let engine = SettlementEngine::new(Arc::new(NoChain), SettlementConfig::default());
+        for trade_id in trade_ids {
+            let trade = Trade {
+                trade_id: *trade_id,
+                buy_order_id: 1,
+                sell_order_id: 2,
+                token_id: [7u8; 32],
+                price: U256::from(100u64),
+                quantity: U256::from(10u64),
+                buyer: Address::repeat_byte(1),
+                seller: Address::repeat_byte(2),
+                timestamp: 0,
+                l2_hash: None,
+            };
+            engine.record_fill(&trade, Address::repeat_byte(0x70)).await.unwrap();
+        }
+        Arc::new(engine)
+    }
+
+    #[tokio::test]
+    async fn test_repairs_explained_drift_and_alerts_the_rest() {
+        let ledger = Arc::new(OrderLedger::default());
+        ledger.record(order(1, LocalOrderStatus::Pending)).await;
+        ledger.record(order(2, LocalOrderStatus::Open)).await;
+        ledger.record(order(3, LocalOrderStatus::Open)).await;
+        ledger.record(order(4, LocalOrderStatus::Cancelled)).await;
+        ledger.record(order(5, LocalOrderStatus::Open)).await;
+        let mut stale = order(6, LocalOrderStatus::Pending);
+        stale.created_at = Utc::now() - Duration::hours(2);
+        ledger.record(stale).await;
+
+        let source = Arc::new(MockSource {
+            head: 20,
+            events: vec![
+                placed(1, 10),
+                filled(2, 11, 100),
+                event(3, 12, OrderEventKind::Cancelled),
+                filled(4, 13, 101),
+                placed(9, 14),
+                filled(5, 15, 102),
+            ],
+            ranges: Default::default(),
+        });
+        let alerter = Arc::new(RecordingAlerter::default());
+        let reconciler = OrderReconciler::new(
+            source,
+            ledger.clone(),
+            settlements_for(&[100, 101]).await,
+            Arc::new(MemoryCursorStore::default()),
+            ReconciliationConfig::default(),
+        ).with_alerter(alerter.clone());
+
+        let report = reconciler.run().await.unwrap();
+        let resolution = |kind| report.discrepancies.iter().find(|d| d.kind == kind).map(|d| d.resolution);
+        assert_eq!(resolution(DiscrepancyKind::PlacementConfirmed), Some(Resolution::Repaired));
+        assert_eq!(resolution(DiscrepancyKind::MissedFill), Some(Resolution::Repaired));
+        assert_eq!(resolution(DiscrepancyKind::MissedCancellation), Some(Resolution::Repaired));
+        assert_eq!(resolution(DiscrepancyKind::StatusConflict), Some(Resolution::Alerted));
+        assert_eq!(resolution(DiscrepancyKind::UnknownOrder), Some(Resolution::Alerted));
+        assert_eq!(resolution(DiscrepancyKind::FillWithoutSettlement), Some(Resolution::Alerted));
+        assert_eq!(resolution(DiscrepancyKind::PlacementNotSeen), Some(Resolution::Alerted));
+
+        assert_eq!(ledger.get(1).await.unwrap().status, LocalOrderStatus::Open);
+        assert_eq!(ledger.get(2).await.unwrap().status, LocalOrderStatus::Filled);
+        assert_eq!(ledger.get(3).await.unwrap().status, LocalOrderStatus::Cancelled);
+        // Conflicting records are left for an operator
+        assert_eq!(ledger.get(4).await.unwrap().status, LocalOrderStatus::Cancelled);
+        assert!(ledger.get(9).await.is_none());
+
+        assert_eq!(report.repaired, 4);
+        assert_eq!(report.alerted, 4);
+        assert_eq!(alerter.0.lock().unwrap().len(), 4);
+        assert_eq!(reconciler.latest_report().await.unwrap().to_block, Some(20));
+
+        // Unseen placements are alerted once, not every run
+        let report = reconciler.run().await.unwrap();
+        assert!(report.discrepancies.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_resumes_from_stored_cursor() {
+        let path = std::env::temp_dir().join(format!("reconciliation-cursor-{}", uuid::Uuid::new_v4()));
+        let cursor = Arc::new(FileCursorStore::new(&path));
+        cursor.save(50).unwrap();
+
+        let ledger = Arc::new(OrderLedger::default());
+        ledger.record(order(1, LocalOrderStatus::Open)).await;
+        let source = Arc::new(MockSource {
+            head: 120,
+            events: vec![filled(1, 40, 100), filled(1, 90, 101)],
+            ranges: Default::default(),
+        });
+        let config = ReconciliationConfig { max_block_range: 50, ..Default::default() };
+        let reconciler = OrderReconciler::new(source.clone(), ledger.clone(), settlements_for(&[100, 101]).await, cursor.clone(), config);
+
+        let report = reconciler.run().await.unwrap();
+        // The fill at block 40 was reconciled before the restart and is not read again
+        assert_eq!(*source.ranges.lock().unwrap(), vec![(51, 100), (101, 120)]);
+        assert_eq!(report.events_read, 1);
+        assert_eq!(ledger.get(1).await.unwrap().trade_ids, vec![101]);
+        assert_eq!(FileCursorStore::new(&path).load().unwrap(), Some(120));
+
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,571 @@
+// Rebuilding the local order ledger from trading contract history
+use alloy_primitives::Address;
+use chrono::{DateTime, Utc};
+use ethereum_client::EthereumClient;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+use uuid::Uuid;
+use crate::admin_cli::env_number;
+use crate::reconciliation::apply_order_event;
+use crate::{
+    ContractOrderEventSource,
+    DiscrepancyKind,
+    Error,
+    LocalOrder,
+    LocalOrderStatus,
+    OrderEvent,
+    OrderEventSource,
+    OrderReconciler,
+};
+
+#[derive(Debug, Clone)]
+pub struct ReplayConfig {
+    /// Most blocks read per log query
+    pub max_block_range: u64,
+    /// Largest difference, in percent, between the rebuilt and live ledgers that is swapped in
+    pub max_delta_percent: f64,
+    /// Orders compared field by field against the live ledger
+    pub spot_checks: usize,
+}
+
+impl Default for ReplayConfig {
+    fn default() -> Self {
+        Self { max_block_range: 2_000, max_delta_percent: 1.0, spot_checks: 100 }
+    }
+}
+
+impl ReplayConfig {
+    /// Read `RECONCILIATION_BLOCK_RANGE`, `REPLAY_MAX_DELTA_PERCENT` and `REPLAY_SPOT_CHECKS`
+    pub fn from_env() -> Result<Self, Error> {
+        let defaults = Self::default();
+        let max_block_range = env_number("RECONCILIATION_BLOCK_RANGE", defaults.max_block_range)?;
+        if max_block_range == 0 {
+            return Err(Error::InvalidParameter("RECONCILIATION_BLOCK_RANGE must be positive".into()));
+        }
+        let max_delta_percent = match std::env::var("REPLAY_MAX_DELTA_PERCENT") {
+            Ok(value) => value.parse::<f64>().ok()
+                .filter(|percent| (0.0..=100.0).contains(percent))
+                .ok_or_else(|| Error::InvalidParameter("REPLAY_MAX_DELTA_PERCENT must be between 0 and 100".into()))?,
+            Err(_) => defaults.max_delta_percent,
+        };
+        Ok(Self {
+            max_block_range,
+            max_delta_percent,
+            spot_checks: env_number("REPLAY_SPOT_CHECKS", defaults.spot_checks as u64)? as usize,
+        })
+    }
+}
+
+/// The ledger as rebuilt so far, held apart from the live ledger until it is swapped in
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShadowLedger {
+    orders: HashMap<u64, LocalOrder>,
+    /// Events that contradicted earlier ones, e.g. a fill after a cancellation
+    pub conflicts: usize,
+}
+
+impl ShadowLedger {
+    /// Apply one event through the same handler live reconciliation uses
+    pub fn apply(&mut self, event: &OrderEvent) -> Result<(), Error> {
+        if let Some(placed) = LocalOrder::placed(event) {
+            match self.orders.get(&event.order_id) {
+                Some(existing) if (existing.trader, existing.token_id, existing.quantity) != (placed.trader, placed.token_id, placed.quantity) => {
+                    return Err(Error::InvalidState(format!(
+                        "Order {} was placed twice with different details; the replayed contracts share order ids",
+                        event.order_id
+                    )));
+                }
+                Some(_) => {}
+                None => {
+                    self.orders.insert(event.order_id, placed);
+                }
+            }
+        }
+        // Fills and cancellations before the replayed range's placements have nothing to update
+        let Some(order) = self.orders.get_mut(&event.order_id) else { return Ok(()) };
+        if let Some((DiscrepancyKind::StatusConflict, _, detail)) = apply_order_event(order, event) {
+            warn!("Replay conflict on order {}: {}", event.order_id, detail);
+            self.conflicts += 1;
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, order_id: u64) -> Option<&LocalOrder> {
+        self.orders.get(&order_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    fn into_orders(self) -> HashMap<u64, LocalOrder> {
+        self.orders
+    }
+}
+
+/// Contracts and blocks of a replay; a checkpoint only resumes the replay it was saved for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayTarget {
+    pub contracts: Vec<Address>,
+    pub from_block: u64,
+    pub to_block: u64,
+}
+
+/// Progress of a replay, saved after every block range
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayCheckpoint {
+    pub replay_id: Uuid,
+    pub target: ReplayTarget,
+    /// First block not yet replayed
+    pub next_block: u64,
+    pub events_applied: usize,
+    pub shadow: ShadowLedger,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Where replay progress survives a restart
+pub trait CheckpointStore: Send + Sync {
+    fn load(&self) -> Result<Option<ReplayCheckpoint>, Error>;
+    fn save(&self, checkpoint: &ReplayCheckpoint) -> Result<(), Error>;
+    fn clear(&self) -> Result<(), Error>;
+}
+
+/// Checkpoint that is lost on restart
+#[derive(Default)]
+pub struct MemoryCheckpointStore(std::sync::Mutex<Option<ReplayCheckpoint>>);
+
+impl CheckpointStore for MemoryCheckpointStore {
+    fn load(&self) -> Result<Option<ReplayCheckpoint>, Error> {
+        Ok(self.0.lock().unwrap().clone())
+    }
+
+    fn save(&self, checkpoint: &ReplayCheckpoint) -> Result<(), Error> {
+        *self.0.lock().unwrap() = Some(checkpoint.clone());
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<(), Error> {
+        *self.0.lock().unwrap() = None;
+        Ok(())
+    }
+}
+
+/// Checkpoint kept as JSON in a file, written via a temporary file so a crash mid-write
+/// leaves the previous checkpoint
+pub struct FileCheckpointStore {
+    path: PathBuf,
+}
+
+impl FileCheckpointStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl CheckpointStore for FileCheckpointStore {
+    fn load(&self) -> Result<Option<ReplayCheckpoint>, Error> {
+        match std::fs::read(&self.path) {
+            Ok(contents) => serde_json::from_slice(&contents)
+                .map(Some)
+                .map_err(|e| Error::InvalidState(format!("Replay checkpoint {} is unreadable: {}", self.path.display(), e))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(Error::InvalidState(format!("Cannot read replay checkpoint {}: {}", self.path.display(), e))),
+        }
+    }
+
+    fn save(&self, checkpoint: &ReplayCheckpoint) -> Result<(), Error> {
+        let contents = serde_json::to_vec(checkpoint)
+            .map_err(|e| Error::Encoding(format!("Replay checkpoint: {}", e)))?;
+        let temp = self.path.with_extension("tmp");
+        std::fs::write(&temp, contents)
+            .and_then(|_| std::fs::rename(&temp, &self.path))
+            .map_err(|e| Error::InvalidState(format!("Cannot write replay checkpoint {}: {}", self.path.display(), e)))
+    }
+
+    fn clear(&self) -> Result<(), Error> {
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(Error::InvalidState(format!("Cannot remove replay checkpoint {}: {}", self.path.display(), e)))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Replay `target` from its checkpoint, or from its first block when there is none, saving
+/// progress after each range. Checkpoints of another target are discarded.
+pub async fn rebuild(
+    source: &dyn OrderEventSource,
+    checkpoints: &dyn CheckpointStore,
+    target: &ReplayTarget,
+    max_block_range: u64,
+) -> Result<ReplayCheckpoint, Error> {
+    let now = Utc::now();
+    let mut checkpoint = match checkpoints.load()? {
+        Some(checkpoint) if checkpoint.target == *target => {
+            info!("Resuming replay {} at block {}", checkpoint.replay_id, checkpoint.next_block);
+            checkpoint
+        }
+        stale => {
+            if let Some(stale) = stale {
+                warn!("Discarding checkpoint of replay {} over blocks {}..={}", stale.replay_id, stale.target.from_block, stale.target.to_block);
+            }
+            ReplayCheckpoint {
+                replay_id: Uuid::new_v4(),
+                target: target.clone(),
+                next_block: target.from_block,
+                events_applied: 0,
+                shadow: ShadowLedger::default(),
+                started_at: now,
+                updated_at: now,
+            }
+        }
+    };
+
+    while checkpoint.next_block <= target.to_block {
+        let to = target.to_block.min(checkpoint.next_block.saturating_add(max_block_range - 1));
+        for event in source.events(checkpoint.next_block, to).await? {
+            checkpoint.shadow.apply(&event)?;
+            checkpoint.events_applied += 1;
+        }
+        checkpoint.next_block = to + 1;
+        checkpoint.updated_at = Utc::now();
+        checkpoints.save(&checkpoint)?;
+    }
+    Ok(checkpoint)
+}
+
+/// An order the rebuilt and live ledgers disagree on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpotMismatch {
+    pub order_id: u64,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub live_orders: usize,
+    /// Pending live orders are not on chain yet, so they are left out of both counts
+    pub rebuilt_orders: usize,
+    pub count_delta_percent: f64,
+    pub spot_checked: usize,
+    pub mismatches: Vec<SpotMismatch>,
+    pub mismatch_percent: f64,
+    pub max_delta_percent: f64,
+    pub passed: bool,
+}
+
+/// Compare a rebuilt ledger with the live one by order count and by spot checks of orders
+/// spread evenly over both ledgers' ids. An empty live ledger, as in a new environment, has
+/// nothing to compare against and always passes.
+pub fn validate(shadow: &ShadowLedger, live: &[LocalOrder], spot_checks: usize, max_delta_percent: f64) -> ValidationReport {
+    let live: HashMap<u64, &LocalOrder> = live.iter()
+        .filter(|order| order.status != LocalOrderStatus::Pending)
+        .map(|order| (order.order_id, order))
+        .collect();
+    let percent = |part: usize, whole: usize| if whole == 0 { 0.0 } else { part as f64 * 100.0 / whole as f64 };
+    let count_delta_percent = percent(live.len().abs_diff(shadow.len()), live.len());
+
+    let ids: Vec<u64> = live.keys().chain(shadow.orders.keys()).copied().collect::<BTreeSet<_>>().into_iter().collect();
+    let sampled: Vec<u64> = if live.is_empty() || spot_checks == 0 {
+        Vec::new()
+    } else if ids.len() <= spot_checks {
+        ids
+    } else {
+        (0..spot_checks).map(|i| ids[i * ids.len() / spot_checks]).collect()
+    };
+    let mismatches: Vec<SpotMismatch> = sampled.iter()
+        .filter_map(|order_id| {
+            let detail = match (live.get(order_id), shadow.get(*order_id)) {
+                (Some(_), None) => "missing from chain history".to_string(),
+                (None, Some(_)) => "missing from the live ledger".to_string(),
+                (Some(live), Some(rebuilt)) => order_difference(live, rebuilt)?,
+                (None, None) => return None,
+            };
+            Some(SpotMismatch { order_id: *order_id, detail })
+        })
+        .collect();
+    let mismatch_percent = percent(mismatches.len(), sampled.len());
+
+    ValidationReport {
+        live_orders: live.len(),
+        rebuilt_orders: shadow.len(),
+        count_delta_percent,
+        spot_checked: sampled.len(),
+        mismatches,
+        mismatch_percent,
+        max_delta_percent,
+        passed: live.is_empty() || (count_delta_percent <= max_delta_percent && mismatch_percent <= max_delta_percent),
+    }
+}
+
+/// Fields the chain determines that differ, if any
+fn order_difference(live: &LocalOrder, rebuilt: &LocalOrder) -> Option<String> {
+    let mut live_trades = live.trade_ids.clone();
+    live_trades.sort_unstable();
+    let mut rebuilt_trades = rebuilt.trade_ids.clone();
+    rebuilt_trades.sort_unstable();
+
+    let mut differences = Vec::new();
+    if (live.trader, live.token_id, live.quantity) != (rebuilt.trader, rebuilt.token_id, rebuilt.quantity) {
+        differences.push("placement".to_string());
+    }
+    if live.status != rebuilt.status {
+        differences.push(format!("status {:?} on chain is {:?}", live.status, rebuilt.status));
+    }
+    if live.filled_quantity != rebuilt.filled_quantity || live_trades != rebuilt_trades {
+        differences.push(format!("filled {} on chain is {}", live.filled_quantity, rebuilt.filled_quantity));
+    }
+    (!differences.is_empty()).then(|| differences.join("; "))
+}
+
+/// A replay requested by an administrator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayRequest {
+    /// Trading contracts to replay; the configured trading contract when empty
+    #[serde(default)]
+    pub contracts: Vec<Address>,
+    pub from_block: u64,
+    /// The chain head when unset
+    #[serde(default)]
+    pub to_block: Option<u64>,
+    /// Swap the rebuilt ledger in when validation passes
+    #[serde(default)]
+    pub swap: bool,
+    /// Overrides REPLAY_MAX_DELTA_PERCENT for this replay
+    #[serde(default)]
+    pub max_delta_percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayReport {
+    pub replay_id: Uuid,
+    pub target: ReplayTarget,
+    /// Set when an earlier, interrupted run of the same replay was continued
+    pub resumed_from: Option<u64>,
+    pub events_applied: usize,
+    pub conflicts: usize,
+    pub validation: ValidationReport,
+    pub swapped: bool,
+    /// Pending live orders kept through the swap
+    pub pending_carried: usize,
+    pub completed_at: DateTime<Utc>,
+}
+
+/// Rebuilds the order ledger from trading contract events into a shadow ledger and swaps it
+/// in once it validates against the live one
+pub struct OrderReplayer {
+    client: Arc<EthereumClient>,
+    trading_contract: Address,
+    reconciler: Arc<OrderReconciler>,
+    checkpoints: Arc<dyn CheckpointStore>,
+    config: ReplayConfig,
+    running: Mutex<()>,
+}
+
+impl OrderReplayer {
+    pub fn new(
+        client: Arc<EthereumClient>,
+        trading_contract: Address,
+        reconciler: Arc<OrderReconciler>,
+        checkpoints: Arc<dyn CheckpointStore>,
+        config: ReplayConfig,
+    ) -> Self {
+        Self { client, trading_contract, reconciler, checkpoints, config, running: Mutex::new(()) }
+    }
+
+    /// Replay, validate and, when asked and validation passes, swap. Only a replay that reaches
+    /// the chain head can be swapped in, since live reconciliation continues from its last block.
+    pub async fn run(&self, request: ReplayRequest) -> Result<ReplayReport, Error> {
+        let _running = self.running.try_lock()
+            .map_err(|_| Error::InvalidState("A replay is already running".into()))?;
+        let max_delta_percent = request.max_delta_percent.unwrap_or(self.config.max_delta_percent);
+        if !(0.0..=100.0).contains(&max_delta_percent) {
+            return Err(Error::InvalidParameter("max_delta_percent must be between 0 and 100".into()));
+        }
+        let contracts = if request.contracts.is_empty() { vec![self.trading_contract] } else { request.contracts };
+        let source = ContractOrderEventSource::for_contracts(self.client.clone(), contracts.clone());
+
+        let head = source.head().await?;
+        let to_block = request.to_block.unwrap_or(head);
+        if request.from_block > to_block {
+            return Err(Error::InvalidParameter(format!("from_block {} is after to_block {}", request.from_block, to_block)));
+        }
+        if request.swap && to_block < head {
+            return Err(Error::InvalidParameter(format!("Only a replay up to the chain head ({}) can be swapped in", head)));
+        }
+
+        let target = ReplayTarget { contracts, from_block: request.from_block, to_block };
+        let resumed_from = self.checkpoints.load()?
+            .filter(|checkpoint| checkpoint.target == target)
+            .map(|checkpoint| checkpoint.next_block);
+        let checkpoint = rebuild(&source, self.checkpoints.as_ref(), &target, self.config.max_block_range).await?;
+
+        let live = self.reconciler.ledger().list().await;
+        let validation = validate(&checkpoint.shadow, &live, self.config.spot_checks, max_delta_percent);
+        let conflicts = checkpoint.shadow.conflicts;
+        let replay_id = checkpoint.replay_id;
+        let events_applied = checkpoint.events_applied;
+
+        let mut pending_carried = 0;
+        let swapped = request.swap && validation.passed;
+        if request.swap && !validation.passed {
+            warn!(
+                "Replay {} not swapped in: {:.2}% count delta and {:.2}% spot mismatches exceed {:.2}%",
+                replay_id, validation.count_delta_percent, validation.mismatch_percent, max_delta_percent
+            );
+        }
+        if swapped {
+            pending_carried = self.reconciler.swap_ledger(checkpoint.shadow.into_orders(), to_block).await?;
+            self.checkpoints.clear()?;
+            info!("[AUDIT] Replay {} swapped in {} orders as of block {}", replay_id, validation.rebuilt_orders, to_block);
+        }
+
+        Ok(ReplayReport {
+            replay_id,
+            target,
+            resumed_from,
+            events_applied,
+            conflicts,
+            validation,
+            swapped,
+            pending_carried,
+            completed_at: Utc::now(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+    use async_trait::async_trait;
+    use crate::OrderEventKind;
+
+    /// A seeded history: every order placed, most filled in one or two trades, some cancelled
+    fn history(orders: u64) -> Vec<OrderEvent> {
+        let mut events = Vec::new();
+        for order_id in 1..=orders {
+            let block = order_id * 10;
+            events.push(OrderEvent {
+                order_id,
+                block_number: block,
+                log_index: 0,
+                kind: OrderEventKind::Placed { trader: Address::repeat_byte(order_id as u8), token_id: [7u8; 32], quantity: U256::from(10u64) },
+            });
+            let kind = match order_id % 3 {
+                0 => OrderEventKind::Cancelled,
+                1 => OrderEventKind::Filled { trade_id: order_id * 100, filled_quantity: U256::from(10u64) },
+                _ => OrderEventKind::Filled { trade_id: order_id * 100, filled_quantity: U256::from(4u64) },
+            };
+            events.push(OrderEvent { order_id, block_number: block + 3, log_index: 1, kind });
+        }
+        events
+    }
+
+    struct MockSource {
+        events: Vec<OrderEvent>,
+        /// Range calls answered before the source starts failing, to simulate a crash
+        fail_after: Option<usize>,
+        calls: std::sync::Mutex<usize>,
+    }
+
+    #[async_trait]
+    impl OrderEventSource for MockSource {
+        async fn head(&self) -> Result<u64, Error> {
+            Ok(self.events.last().map_or(0, |event| event.block_number))
+        }
+
+        async fn events(&self, from_block: u64, to_block: u64) -> Result<Vec<OrderEvent>, Error> {
+            let mut calls = self.calls.lock().unwrap();
+            if self.fail_after.is_some_and(|limit| *calls >= limit) {
+                return Err(Error::ContractInteraction("node went away".into()));
+            }
+            *calls += 1;
+            Ok(self.events.iter().filter(|event| (from_block..=to_block).contains(&event.block_number)).cloned().collect())
+        }
+    }
+
+    fn target() -> ReplayTarget {
+        ReplayTarget { contracts: vec![Address::repeat_byte(0x7a)], from_block: 0, to_block: 1_000 }
+    }
+
+    /// The live ledger as the service records it: orders submitted as pending, then brought up
+    /// to date by live reconciliation
+    fn live_ledger(events: &[OrderEvent]) -> Vec<LocalOrder> {
+        let mut orders: HashMap<u64, LocalOrder> = HashMap::new();
+        for event in events {
+            if let OrderEventKind::Placed { trader, token_id, quantity } = &event.kind {
+                orders.insert(event.order_id, LocalOrder::pending(event.order_id, *trader, *token_id, *quantity));
+            }
+            apply_order_event(orders.get_mut(&event.order_id).unwrap(), event);
+        }
+        orders.into_values().collect()
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_replay_resumes_and_matches_the_live_ledger() {
+        let events = history(60);
+        let checkpoints = MemoryCheckpointStore::default();
+
+        let crashing = MockSource { events: events.clone(), fail_after: Some(3), calls: Default::default() };
+        assert!(rebuild(&crashing, &checkpoints, &target(), 100).await.is_err());
+        let saved = checkpoints.load().unwrap().unwrap();
+        assert_eq!(saved.next_block, 300);
+
+        let source = MockSource { events: events.clone(), fail_after: None, calls: Default::default() };
+        let rebuilt = rebuild(&source, &checkpoints, &target(), 100).await.unwrap();
+        assert_eq!(rebuilt.replay_id, saved.replay_id);
+        // Only the ranges after the checkpoint were read again
+        assert_eq!(*source.calls.lock().unwrap(), 8);
+        assert_eq!(rebuilt.events_applied, events.len());
+        assert_eq!(rebuilt.shadow.get(1).unwrap().status, LocalOrderStatus::Filled);
+        assert_eq!(rebuilt.shadow.get(2).unwrap().status, LocalOrderStatus::PartiallyFilled);
+        assert_eq!(rebuilt.shadow.get(3).unwrap().status, LocalOrderStatus::Cancelled);
+
+        let report = validate(&rebuilt.shadow, &live_ledger(&events), 20, 1.0);
+        assert!(report.passed, "{:?}", report);
+        assert_eq!((report.spot_checked, report.count_delta_percent), (20, 0.0));
+
+        // Applying the history again changes nothing
+        let mut twice = rebuilt.shadow.clone();
+        for event in &events {
+            twice.apply(event).unwrap();
+        }
+        assert_eq!(twice.get(2).unwrap().filled_quantity, U256::from(4u64));
+    }
+
+    #[test]
+    fn test_validation_refuses_a_ledger_far_from_the_live_one() {
+        let events = history(50);
+        let mut shadow = ShadowLedger::default();
+        for event in &events {
+            shadow.apply(event).unwrap();
+        }
+
+        // Five live orders lost and one recorded with the wrong status
+        let mut live: Vec<LocalOrder> = live_ledger(&events).into_iter().filter(|order| order.order_id > 5).collect();
+        live.iter_mut().find(|order| order.order_id == 10).unwrap().status = LocalOrderStatus::Open;
+        let report = validate(&shadow, &live, 50, 1.0);
+        assert!(!report.passed);
+        assert_eq!(report.mismatches.len(), 6);
+        assert!(validate(&shadow, &live, 50, 15.0).passed);
+
+        // A new environment has no live ledger to compare against
+        assert!(validate(&shadow, &[], 50, 0.0).passed);
+
+        // Contracts with overlapping order ids cannot share a ledger
+        let mut clash = events[0].clone();
+        clash.kind = OrderEventKind::Placed { trader: Address::repeat_byte(0xee), token_id: [7u8; 32], quantity: U256::from(1u64) };
+        assert!(shadow.apply(&clash).is_err());
+    }
+}
@@ -0,0 +1,717 @@
+// Delivery-versus-payment settlement of matched trades
+use alloy_primitives::{Address, H256, U256};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use ethereum_client::{EthereumClient, FinalityCheck};
+use quantera_types::{Finality, FinalityDowngrade, FinalityStatus};
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+use uuid::Uuid;
+use crate::clients::trading_client::{Trade, TradingClient};
+use crate::{DealingWindows, Error, FeeOperation, FeeSchedule, TreasuryRegistryClient};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SettlementStatus {
+    Pending,
+    Settled,
+    /// Checks or the swap failed; retried until the deadline
+    Failed,
+    /// Not settled by the deadline; both counterparties are notified
+    Cancelled,
+}
+
+impl std::str::FromStr for SettlementStatus {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(SettlementStatus::Pending),
+            "settled" => Ok(SettlementStatus::Settled),
+            "failed" => Ok(SettlementStatus::Failed),
+            "cancelled" => Ok(SettlementStatus::Cancelled),
+            other => Err(Error::InvalidParameter(format!("Unknown settlement status {}", other))),
+        }
+    }
+}
+
+/// One try at settling, with the swap transaction if it got that far
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettlementAttempt {
+    pub attempted_at: DateTime<Utc>,
+    pub tx_hash: Option<H256>,
+    /// Why the attempt failed; None when it settled
+    pub failure_reason: Option<String>,
+}
+
+/// Settlement of one matched trade
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Settlement {
+    pub settlement_id: Uuid,
+    pub trade_id: u64,
+    #[serde(with = "quantera_types::wire::token_id")]
+    pub token_id: [u8; 32],
+    pub token_address: Address,
+    pub buyer: Address,
+    pub seller: Address,
+    #[serde(with = "quantera_types::wire::u256_decimal")]
+    pub quantity: U256,
+    #[serde(with = "quantera_types::wire::u256_decimal")]
+    pub price: U256,
+    /// Stablecoin the buyer pays, price times quantity
+    #[serde(with = "quantera_types::wire::u256_decimal")]
+    pub payment_amount: U256,
+    pub status: SettlementStatus,
+    pub failure_reason: Option<String>,
+    pub attempts: Vec<SettlementAttempt>,
+    /// Failed settlements still unsettled at this time are cancelled
+    pub deadline: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub settled_tx_hash: Option<H256>,
+    /// Whether the swap can still be reorged out
+    #[serde(default)]
+    pub finality: Finality,
+    #[serde(default)]
+    pub finality_downgrades: Vec<FinalityDowngrade>,
+}
+
+/// Balances and the atomic swap, as seen by settlement
+#[async_trait]
+pub trait SettlementChain: Send + Sync {
+    async fn stablecoin_balance(&self, owner: Address) -> Result<U256, Error>;
+
+    /// Stablecoin the settlement contract may pull from `owner`
+    async fn stablecoin_allowance(&self, owner: Address) -> Result<U256, Error>;
+
+    async fn token_balance(&self, token: Address, owner: Address) -> Result<U256, Error>;
+
+    /// Swap payment for tokens in one transaction; either both legs move or neither does
+    async fn execute_swap(&self, settlement: &Settlement) -> Result<H256, Error>;
+
+    /// Where the swap transaction is now and how final that is
+    async fn check_finality(&self, tx_hash: H256, recorded: &Finality) -> Result<FinalityCheck, Error>;
+}
+
+/// Settles through the trading contract, which pulls the buyer's stablecoin and the seller's
+/// tokens in one transaction
+pub struct ContractSettlementChain {
+    client: Arc<EthereumClient>,
+    settlement_contract: Address,
+    stablecoin: Address,
+}
+
+impl ContractSettlementChain {
+    pub fn new(client: Arc<EthereumClient>, settlement_contract: Address, stablecoin: Address) -> Self {
+        Self { client, settlement_contract, stablecoin }
+    }
+}
+
+#[async_trait]
+impl SettlementChain for ContractSettlementChain {
+    async fn stablecoin_balance(&self, owner: Address) -> Result<U256, Error> {
+        Ok(self.client.call_contract::<U256>(self.stablecoin, "balanceOf(address)", vec![owner.into()]).await?)
+    }
+
+    async fn stablecoin_allowance(&self, owner: Address) -> Result<U256, Error> {
+        Ok(self.client.call_contract::<U256>(
+            self.stablecoin,
+            "allowance(address,address)",
+            vec![owner.into(), self.settlement_contract.into()],
+        ).await?)
+    }
+
+    async fn token_balance(&self, token: Address, owner: Address) -> Result<U256, Error> {
+        Ok(self.client.call_contract::<U256>(token, "balanceOf(address)", vec![owner.into()]).await?)
+    }
+
+    async fn execute_swap(&self, settlement: &Settlement) -> Result<H256, Error> {
+        let receipt = self.client.send_transaction(
+            self.settlement_contract,
+            "settleTrade(uint256,address,uint256)",
+            vec![
+                U256::from(settlement.trade_id).into(),
+                self.stablecoin.into(),
+                settlement.payment_amount.into(),
+            ],
+        ).await?;
+
+        if !receipt.status {
+            return Err(Error::ContractInteraction(format!(
+                "Settlement transaction {:?} reverted", receipt.transaction_hash
+            )));
+        }
+        Ok(receipt.transaction_hash)
+    }
+
+    async fn check_finality(&self, tx_hash: H256, recorded: &Finality) -> Result<FinalityCheck, Error> {
+        Ok(self.client.check_finality(tx_hash, recorded).await?)
+    }
+}
+
+/// Told about settlements cancelled at their deadline
+#[async_trait]
+pub trait SettlementNotifier: Send + Sync {
+    async fn settlement_cancelled(&self, settlement: &Settlement);
+}
+
+/// POSTs cancelled settlements as JSON; the receiver notifies buyer and seller
+pub struct WebhookSettlementNotifier {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookSettlementNotifier {
+    pub fn new(url: String) -> Self {
+        Self { client: reqwest::Client::new(), url }
+    }
+}
+
+#[async_trait]
+impl SettlementNotifier for WebhookSettlementNotifier {
+    async fn settlement_cancelled(&self, settlement: &Settlement) {
+        let body = serde_json::json!({
+            "event": "settlement_cancelled",
+            "counterparties": [settlement.buyer, settlement.seller],
+            "settlement": settlement,
+        });
+        if let Err(e) = self.client.post(&self.url).json(&body).send().await {
+            warn!("Settlement cancellation webhook for {} failed: {}", settlement.settlement_id, e);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SettlementConfig {
+    /// Time from the fill after which an unsettled trade is cancelled
+    pub deadline: Duration,
+}
+
+impl Default for SettlementConfig {
+    fn default() -> Self {
+        Self { deadline: Duration::hours(24) }
+    }
+}
+
+impl SettlementConfig {
+    /// Read `SETTLEMENT_DEADLINE_SECS`
+    pub fn from_env() -> Result<Self, Error> {
+        let deadline = match std::env::var("SETTLEMENT_DEADLINE_SECS") {
+            Ok(secs) => Duration::seconds(secs.parse::<i64>()
+                .ok()
+                .filter(|secs| *secs > 0)
+                .ok_or_else(|| Error::InvalidParameter("SETTLEMENT_DEADLINE_SECS must be a positive integer".into()))?),
+            Err(_) => Self::default().deadline,
+        };
+        Ok(Self { deadline })
+    }
+}
+
+/// Filters for listing settlements; a party matches as buyer or seller
+#[derive(Debug, Clone, Default)]
+pub struct SettlementFilter {
+    pub status: Option<SettlementStatus>,
+    pub party: Option<Address>,
+    pub token_id: Option<[u8; 32]>,
+    pub trade_id: Option<u64>,
+}
+
+/// Settlement records of filled trades and their Pending → Settled / Failed → Cancelled lifecycle
+pub struct SettlementEngine {
+    chain: Arc<dyn SettlementChain>,
+    notifier: Option<Arc<dyn SettlementNotifier>>,
+    fees: Option<Arc<FeeSchedule>>,
+    dealing_windows: Option<Arc<DealingWindows>>,
+    config: SettlementConfig,
+    settlements: RwLock<HashMap<Uuid, Settlement>>,
+}
+
+impl SettlementEngine {
+    pub fn new(chain: Arc<dyn SettlementChain>, config: SettlementConfig) -> Self {
+        Self {
+            chain,
+            notifier: None,
+            fees: None,
+            dealing_windows: None,
+            config,
+            settlements: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_notifier(mut self, notifier: Arc<dyn SettlementNotifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Accrue a trading fee on each settlement; settlements fail, and are retried, while
+    /// no trading fee is configured
+    pub fn with_fee_schedule(mut self, fees: Arc<FeeSchedule>) -> Self {
+        self.fees = Some(fees);
+        self
+    }
+
+    /// Start the buyer's lock-up on units of assets with dealing terms once a fill settles
+    pub fn with_dealing_windows(mut self, dealing_windows: Arc<DealingWindows>) -> Self {
+        self.dealing_windows = Some(dealing_windows);
+        self
+    }
+
+    /// Open a pending settlement for a fill. A trade reported twice keeps its first record.
+    pub async fn record_fill(&self, trade: &Trade, token_address: Address) -> Result<Settlement, Error> {
+        let payment_amount = trade.price.checked_mul(trade.quantity)
+            .ok_or_else(|| Error::InvalidParameter(format!("Payment for trade {} overflows", trade.trade_id)))?;
+
+        let mut settlements = self.settlements.write().await;
+        if let Some(existing) = settlements.values().find(|s| s.trade_id == trade.trade_id) {
+            return Ok(existing.clone());
+        }
+
+        let now = Utc::now();
+        let settlement = Settlement {
+            settlement_id: Uuid::new_v4(),
+            trade_id: trade.trade_id,
+            token_id: trade.token_id,
+            token_address,
+            buyer: trade.buyer,
+            seller: trade.seller,
+            quantity: trade.quantity,
+            price: trade.price,
+            payment_amount,
+            status: SettlementStatus::Pending,
+            failure_reason: None,
+            attempts: Vec::new(),
+            deadline: now + self.config.deadline,
+            created_at: now,
+            updated_at: now,
+            settled_tx_hash: None,
+            finality: Finality::default(),
+            finality_downgrades: Vec::new(),
+        };
+        settlements.insert(settlement.settlement_id, settlement.clone());
+        info!("Opened settlement {} for trade {}", settlement.settlement_id, trade.trade_id);
+        Ok(settlement)
+    }
+
+    /// Fetch a fill reported by the TradingClient, resolve its token and open its settlement
+    pub async fn record_trade(
+        &self,
+        trading_client: &TradingClient,
+        registry: &TreasuryRegistryClient,
+        trade_id: u64,
+    ) -> Result<Settlement, Error> {
+        let trade = trading_client.get_trade(trade_id)
+            .await
+            .map_err(|e| Error::ContractInteraction(e.to_string()))?;
+        let token_address = registry.get_treasury_details(trade.token_id).await?.token_address;
+        self.record_fill(&trade, token_address).await
+    }
+
+    /// Verify both legs and swap. Pending and failed settlements can be settled; past the
+    /// deadline the settlement is cancelled instead.
+    pub async fn settle(&self, settlement_id: Uuid) -> Result<Settlement, Error> {
+        let settlement = self.get(settlement_id).await?;
+        match settlement.status {
+            SettlementStatus::Pending | SettlementStatus::Failed => {}
+            status => return Err(Error::InvalidState(format!(
+                "Settlement {} is {:?} and cannot be settled", settlement_id, status
+            ))),
+        }
+        if Utc::now() >= settlement.deadline {
+            return self.cancel(settlement_id).await;
+        }
+
+        let fee_check = match &self.fees {
+            Some(fees) => fees.rule_at(FeeOperation::Trading, Utc::now()).map(|_| ()).map_err(|e| e.to_string()),
+            None => Ok(()),
+        };
+        let outcome = match fee_check {
+            Ok(()) => match self.verify_legs(&settlement).await {
+                Ok(()) => self.chain.execute_swap(&settlement).await.map_err(|e| e.to_string()),
+                Err(reason) => Err(reason),
+            },
+            Err(reason) => Err(reason),
+        };
+        if let (Ok(_), Some(dealing_windows)) = (&outcome, &self.dealing_windows) {
+            if let Err(e) = dealing_windows.record_subscription(settlement.buyer, settlement.token_id, settlement.quantity, Utc::now()) {
+                warn!("Lock-up not recorded for settlement {}: {}", settlement_id, e);
+            }
+        }
+        if let (Ok(_), Some(fees)) = (&outcome, &self.fees) {
+            let reference = format!("settlement {}", settlement_id);
+            if let Err(e) = fees.accrue(FeeOperation::Trading, reference, settlement.payment_amount, fees.payment_currency(), Utc::now()) {
+                warn!("Trading fee not accrued for settlement {}: {}", settlement_id, e);
+            }
+        }
+
+        self.update(settlement_id, |s| {
+            let now = Utc::now();
+            match &outcome {
+                Ok(tx_hash) => {
+                    s.status = SettlementStatus::Settled;
+                    s.failure_reason = None;
+                    s.settled_tx_hash = Some(*tx_hash);
+                    s.attempts.push(SettlementAttempt { attempted_at: now, tx_hash: Some(*tx_hash), failure_reason: None });
+                }
+                Err(reason) => {
+                    s.status = SettlementStatus::Failed;
+                    s.failure_reason = Some(reason.clone());
+                    s.attempts.push(SettlementAttempt { attempted_at: now, tx_hash: None, failure_reason: Some(reason.clone()) });
+                }
+            }
+        }).await
+    }
+
+    /// Settle a failed settlement again, e.g. after the buyer raised their allowance
+    pub async fn retry(&self, settlement_id: Uuid) -> Result<Settlement, Error> {
+        let settlement = self.get(settlement_id).await?;
+        if settlement.status != SettlementStatus::Failed {
+            return Err(Error::InvalidState(format!(
+                "Settlement {} is {:?}, only failed settlements can be retried", settlement_id, settlement.status
+            )));
+        }
+        self.settle(settlement_id).await
+    }
+
+    /// Settle pending settlements, retry failed ones and cancel those past their deadline
+    pub async fn process_due(&self) -> Vec<Settlement> {
+        let due: Vec<Uuid> = self.settlements.read().await.values()
+            .filter(|s| matches!(s.status, SettlementStatus::Pending | SettlementStatus::Failed))
+            .map(|s| s.settlement_id)
+            .collect();
+
+        let mut processed = Vec::new();
+        for settlement_id in due {
+            match self.settle(settlement_id).await {
+                Ok(settlement) => processed.push(settlement),
+                Err(e) => warn!("Settlement {} could not be processed: {}", settlement_id, e),
+            }
+        }
+        processed
+    }
+
+    /// Advance the finality of settled swaps, downgrading Confirmed ones whose block was
+    /// reorged out. Returns the settlements that were downgraded.
+    pub async fn refresh_finality(&self) -> Vec<Settlement> {
+        let unsettled: Vec<(Uuid, H256, Finality)> = self.settlements.read().await.values()
+            .filter(|s| s.status == SettlementStatus::Settled && s.finality.status.is_reorgable())
+            .filter_map(|s| s.settled_tx_hash.map(|tx| (s.settlement_id, tx, s.finality.clone())))
+            .collect();
+
+        let mut downgraded = Vec::new();
+        for (settlement_id, tx_hash, recorded) in unsettled {
+            let reason = match self.chain.check_finality(tx_hash, &recorded).await {
+                Ok(FinalityCheck::Observed { status, block_number, block_hash }) => {
+                    let result = self.update(settlement_id, |s| {
+                        if let Err(e) = s.finality.observe(status, block_number, &format!("{:?}", block_hash)) {
+                            warn!("Settlement {} finality not updated: {}", settlement_id, e);
+                        }
+                    }).await;
+                    if let Err(e) = result {
+                        warn!("Settlement {} finality not updated: {}", settlement_id, e);
+                    }
+                    continue;
+                }
+                Ok(FinalityCheck::Missing) => format!("Swap {:?} no longer has a receipt", tx_hash),
+                Ok(FinalityCheck::Reorged { recorded, observed }) => format!("Swap {:?} moved from block {} to {:?}", tx_hash, recorded, observed),
+                Err(e) => {
+                    warn!("Finality of settlement {} could not be checked: {}", settlement_id, e);
+                    continue;
+                }
+            };
+            if recorded.status == FinalityStatus::Confirmed {
+                match self.downgrade_finality(settlement_id, &reason, FINALITY_MONITOR).await {
+                    Ok(settlement) => downgraded.push(settlement),
+                    Err(e) => warn!("Settlement {} could not be downgraded: {}", settlement_id, e),
+                }
+            }
+        }
+        downgraded
+    }
+
+    /// Return a Confirmed settlement to Pending finality, e.g. after an operator saw its
+    /// block reorged out. Finalized settlements cannot be downgraded.
+    pub async fn downgrade_finality(&self, settlement_id: Uuid, reason: &str, actor: &str) -> Result<Settlement, Error> {
+        let mut settlements = self.settlements.write().await;
+        let settlement = settlements.get_mut(&settlement_id)
+            .ok_or_else(|| Error::NotFound(format!("Settlement {}", settlement_id)))?;
+        let downgrade = settlement.finality.downgrade(reason, actor)
+            .map_err(|e| Error::InvalidState(format!("Settlement {}: {}", settlement_id, e)))?;
+
+        warn!(
+            "[AUDIT] Settlement {} downgraded from {:?} in block {:?} to Pending by {}: {}",
+            settlement_id, downgrade.from, downgrade.block_number, actor, reason
+        );
+        settlement.finality_downgrades.push(downgrade);
+        settlement.updated_at = Utc::now();
+        Ok(settlement.clone())
+    }
+
+    pub async fn get(&self, settlement_id: Uuid) -> Result<Settlement, Error> {
+        self.settlements.read().await.get(&settlement_id).cloned()
+            .ok_or_else(|| Error::NotFound(format!("Settlement {}", settlement_id)))
+    }
+
+    /// Matching settlements, newest first
+    pub async fn list(&self, filter: &SettlementFilter) -> Vec<Settlement> {
+        let mut settlements: Vec<Settlement> = self.settlements.read().await.values()
+            .filter(|s| filter.status.map_or(true, |status| s.status == status))
+            .filter(|s| filter.party.map_or(true, |party| s.buyer == party || s.seller == party))
+            .filter(|s| filter.token_id.map_or(true, |token_id| s.token_id == token_id))
+            .filter(|s| filter.trade_id.map_or(true, |trade_id| s.trade_id == trade_id))
+            .cloned()
+            .collect();
+        settlements.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        settlements
+    }
+
+    /// Buyer must hold and have approved the payment; seller must hold the tokens
+    async fn verify_legs(&self, settlement: &Settlement) -> Result<(), String> {
+        let balance = self.chain.stablecoin_balance(settlement.buyer).await.map_err(|e| e.to_string())?;
+        if balance < settlement.payment_amount {
+            return Err(format!("Buyer stablecoin balance {} is below payment {}", balance, settlement.payment_amount));
+        }
+        let allowance = self.chain.stablecoin_allowance(settlement.buyer).await.map_err(|e| e.to_string())?;
+        if allowance < settlement.payment_amount {
+            return Err(format!("Buyer stablecoin allowance {} is below payment {}", allowance, settlement.payment_amount));
+        }
+        let tokens = self.chain.token_balance(settlement.token_address, settlement.seller).await.map_err(|e| e.to_string())?;
+        if tokens < settlement.quantity {
+            return Err(format!("Seller token balance {} is below quantity {}", tokens, settlement.quantity));
+        }
+        Ok(())
+    }
+
+    async fn cancel(&self, settlement_id: Uuid) -> Result<Settlement, Error> {
+        let settlement = self.update(settlement_id, |s| {
+            s.status = SettlementStatus::Cancelled;
+            if s.failure_reason.is_none() {
+                s.failure_reason = Some("Not settled before the deadline".to_string());
+            }
+        }).await?;
+
+        warn!("Settlement {} for trade {} cancelled at its deadline", settlement_id, settlement.trade_id);
+        if let Some(notifier) = &self.notifier {
+            notifier.settlement_cancelled(&settlement).await;
+        }
+        Ok(settlement)
+    }
+
+    async fn update<F>(&self, settlement_id: Uuid, change: F) -> Result<Settlement, Error>
+    where
+        F: FnOnce(&mut Settlement),
+    {
+        let mut settlements = self.settlements.write().await;
+        let settlement = settlements.get_mut(&settlement_id)
+            .ok_or_else(|| Error::NotFound(format!("Settlement {}", settlement_id)))?;
+        change(settlement);
+        settlement.updated_at = Utc::now();
+        Ok(settlement.clone())
+    }
+
+    #[cfg(test)]
+    async fn expire(&self, settlement_id: Uuid) {
+        if let Some(settlement) = self.settlements.write().await.get_mut(&settlement_id) {
+            settlement.deadline = Utc::now() - Duration::seconds(1);
+        }
+    }
+}
+
+/// Actor recorded on downgrades made by the periodic finality check
+const FINALITY_MONITOR: &str = "finality-monitor";
+
+/// Periodically settle pending trades, retry failed ones, cancel expired ones and track
+/// the finality of settled swaps
+pub fn spawn_settlement_processing(engine: Arc<SettlementEngine>, interval: std::time::Duration) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let processed = engine.process_due().await;
+            let failed = processed.iter().filter(|s| s.status == SettlementStatus::Failed).count();
+            if failed > 0 {
+                warn!("{} settlements failed and will be retried", failed);
+            }
+            let reorged = engine.refresh_finality().await;
+            if !reorged.is_empty() {
+                warn!("{} settled swaps were reorged out and are Pending again", reorged.len());
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const TOKEN: Address = Address::repeat_byte(0x70);
+
+    /// Chain with settable balances; swaps succeed and are counted
+    #[derive(Default)]
+    struct MockChain {
+        stablecoin: Mutex<HashMap<Address, U256>>,
+        allowances: Mutex<HashMap<Address, U256>>,
+        tokens: Mutex<HashMap<Address, U256>>,
+        swaps: AtomicUsize,
+        /// Block the swap is reported in; None once it is reorged out
+        swap_block: Mutex<Option<(u64, FinalityStatus)>>,
+    }
+
+    #[async_trait]
+    impl SettlementChain for MockChain {
+        async fn stablecoin_balance(&self, owner: Address) -> Result<U256, Error> {
+            Ok(self.stablecoin.lock().unwrap().get(&owner).copied().unwrap_or_default())
+        }
+
+        async fn stablecoin_allowance(&self, owner: Address) -> Result<U256, Error> {
+            Ok(self.allowances.lock().unwrap().get(&owner).copied().unwrap_or_default())
+        }
+
+        async fn token_balance(&self, _token: Address, owner: Address) -> Result<U256, Error> {
+            Ok(self.tokens.lock().unwrap().get(&owner).copied().unwrap_or_default())
+        }
+
+        async fn execute_swap(&self, _settlement: &Settlement) -> Result<H256, Error> {
+            self.swaps.fetch_add(1, Ordering::SeqCst);
+            Ok(H256::repeat_byte(0xab))
+        }
+
+        async fn check_finality(&self, _tx_hash: H256, _recorded: &Finality) -> Result<FinalityCheck, Error> {
+            Ok(match *self.swap_block.lock().unwrap() {
+                Some((block_number, status)) => FinalityCheck::Observed { status, block_number, block_hash: H256::repeat_byte(block_number as u8) },
+                None => FinalityCheck::Missing,
+            })
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingNotifier {
+        cancelled: Mutex<Vec<Uuid>>,
+    }
+
+    #[async_trait]
+    impl SettlementNotifier for RecordingNotifier {
+        async fn settlement_cancelled(&self, settlement: &Settlement) {
+            self.cancelled.lock().unwrap().push(settlement.settlement_id);
+        }
+    }
+
+    fn trade(trade_id: u64) -> Trade {
+        Trade {
+            trade_id,
+            buy_order_id: 1,
+            sell_order_id: 2,
+            token_id: [7u8; 32],
+            price: U256::from(100u64),
+            quantity: U256::from(10u64),
+            buyer: Address::repeat_byte(1),
+            seller: Address::repeat_byte(2),
+            timestamp: 0,
+            l2_hash: None,
+        }
+    }
+
+    /// Buyer funded for 1000 with `allowance` approved, seller holding 10 tokens
+    fn chain(allowance: u64) -> Arc<MockChain> {
+        let chain = Arc::new(MockChain::default());
+        chain.stablecoin.lock().unwrap().insert(Address::repeat_byte(1), U256::from(1_000u64));
+        chain.allowances.lock().unwrap().insert(Address::repeat_byte(1), U256::from(allowance));
+        chain.tokens.lock().unwrap().insert(Address::repeat_byte(2), U256::from(10u64));
+        chain
+    }
+
+    #[tokio::test]
+    async fn test_insufficient_allowance_fails_then_settles_after_approval() {
+        let chain = chain(400);
+        let engine = SettlementEngine::new(chain.clone(), SettlementConfig::default());
+        let settlement = engine.record_fill(&trade(1), TOKEN).await.unwrap();
+        assert_eq!(settlement.payment_amount, U256::from(1_000u64));
+        assert_eq!(settlement.status, SettlementStatus::Pending);
+
+        let failed = engine.settle(settlement.settlement_id).await.unwrap();
+        assert_eq!(failed.status, SettlementStatus::Failed);
+        assert!(failed.failure_reason.as_deref().unwrap().contains("allowance 400"));
+        assert_eq!(chain.swaps.load(Ordering::SeqCst), 0);
+
+        // Still short, the retry fails again and keeps both attempts
+        let failed = engine.retry(settlement.settlement_id).await.unwrap();
+        assert_eq!(failed.status, SettlementStatus::Failed);
+
+        chain.allowances.lock().unwrap().insert(Address::repeat_byte(1), U256::from(1_000u64));
+        let settled = engine.retry(settlement.settlement_id).await.unwrap();
+        assert_eq!(settled.status, SettlementStatus::Settled);
+        assert_eq!(settled.settled_tx_hash, Some(H256::repeat_byte(0xab)));
+        assert_eq!(settled.failure_reason, None);
+        assert_eq!(settled.attempts.len(), 3);
+        assert_eq!(chain.swaps.load(Ordering::SeqCst), 1);
+
+        // Settled trades cannot be retried or settled twice
+        assert!(matches!(engine.retry(settlement.settlement_id).await, Err(Error::InvalidState(_))));
+        assert!(matches!(engine.settle(settlement.settlement_id).await, Err(Error::InvalidState(_))));
+    }
+
+    #[tokio::test]
+    async fn test_failed_settlement_cancelled_at_deadline_and_counterparties_notified() {
+        let notifier = Arc::new(RecordingNotifier::default());
+        let engine = SettlementEngine::new(chain(0), SettlementConfig::default()).with_notifier(notifier.clone());
+        let settlement = engine.record_fill(&trade(2), TOKEN).await.unwrap();
+
+        engine.process_due().await;
+        assert_eq!(engine.get(settlement.settlement_id).await.unwrap().status, SettlementStatus::Failed);
+
+        engine.expire(settlement.settlement_id).await;
+        let processed = engine.process_due().await;
+        assert_eq!(processed[0].status, SettlementStatus::Cancelled);
+        // The last failure is kept as the reason
+        assert!(processed[0].failure_reason.as_deref().unwrap().contains("allowance"));
+        assert_eq!(*notifier.cancelled.lock().unwrap(), vec![settlement.settlement_id]);
+        assert!(engine.process_due().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fill_recorded_once_and_listed_by_party() {
+        let engine = SettlementEngine::new(chain(1_000), SettlementConfig::default());
+        let first = engine.record_fill(&trade(3), TOKEN).await.unwrap();
+        let again = engine.record_fill(&trade(3), TOKEN).await.unwrap();
+        assert_eq!(first.settlement_id, again.settlement_id);
+
+        let by_seller = SettlementFilter { party: Some(Address::repeat_byte(2)), ..Default::default() };
+        assert_eq!(engine.list(&by_seller).await.len(), 1);
+        let settled = SettlementFilter { status: Some(SettlementStatus::Settled), ..Default::default() };
+        assert!(engine.list(&settled).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reorged_swap_is_downgraded_and_audited() {
+        let chain = chain(1_000);
+        let engine = SettlementEngine::new(chain.clone(), SettlementConfig::default());
+        let settlement = engine.record_fill(&trade(4), TOKEN).await.unwrap();
+        engine.settle(settlement.settlement_id).await.unwrap();
+
+        *chain.swap_block.lock().unwrap() = Some((40, FinalityStatus::Confirmed));
+        assert!(engine.refresh_finality().await.is_empty());
+        let confirmed = engine.get(settlement.settlement_id).await.unwrap();
+        assert_eq!((confirmed.finality.status, confirmed.finality.block_number), (FinalityStatus::Confirmed, Some(40)));
+
+        // The block is reorged out: the settlement is Pending again with the downgrade recorded
+        *chain.swap_block.lock().unwrap() = None;
+        let downgraded = engine.refresh_finality().await;
+        assert_eq!(downgraded.len(), 1);
+        assert_eq!(downgraded[0].finality.status, FinalityStatus::Pending);
+        assert_eq!(downgraded[0].finality_downgrades[0].block_number, Some(40));
+        assert_eq!(downgraded[0].finality_downgrades[0].actor, FINALITY_MONITOR);
+
+        // Re-included and finalized, it can no longer be downgraded
+        *chain.swap_block.lock().unwrap() = Some((41, FinalityStatus::Finalized));
+        engine.refresh_finality().await;
+        assert_eq!(engine.get(settlement.settlement_id).await.unwrap().finality.status, FinalityStatus::Finalized);
+        assert!(matches!(
+            engine.downgrade_finality(settlement.settlement_id, "manual", "0xadmin").await,
+            Err(Error::InvalidState(_))
+        ));
+    }
+}
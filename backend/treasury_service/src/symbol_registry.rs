@@ -0,0 +1,160 @@
+use alloy_primitives::Address;
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use crate::Error;
+
+/// Default hold on a reserved symbol while an issuer onboards
+pub const DEFAULT_RESERVATION_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Longest an issuer may hold a symbol without creating the treasury
+pub const MAX_RESERVATION_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// A symbol held for an issuer until `expires_at`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SymbolReservation {
+    pub symbol: String,
+    pub issuer: Address,
+    pub reserved_at: u64,
+    pub expires_at: u64,
+}
+
+#[derive(Debug, Clone)]
+enum SymbolEntry {
+    /// Claimed by a treasury, or by the creation attempt building it
+    Claimed { holder: String },
+    Reserved(SymbolReservation),
+}
+
+/// Treasury symbols in use or reserved, compared case-insensitively.
+///
+/// Token symbols are fixed once deployed, so treasuries cannot be renamed; a symbol is
+/// only freed by an expired reservation or an explicit release.
+#[derive(Debug, Default)]
+pub struct SymbolRegistry {
+    symbols: Mutex<HashMap<String, SymbolEntry>>,
+}
+
+impl SymbolRegistry {
+    /// Hold a symbol for an issuer; an expired reservation by anyone may be reclaimed
+    pub fn reserve(&self, symbol: &str, issuer: Address, hold_secs: u64, now: u64) -> Result<SymbolReservation, Error> {
+        if hold_secs == 0 || hold_secs > MAX_RESERVATION_SECS {
+            return Err(Error::InvalidParameter(format!(
+                "Reservations last between 1 and {} seconds", MAX_RESERVATION_SECS
+            )));
+        }
+
+        let key = normalize(symbol)?;
+        let mut symbols = self.lock()?;
+        Self::check_available(&symbols, &key, issuer, now)?;
+
+        let reservation = SymbolReservation {
+            symbol: key.clone(),
+            issuer,
+            reserved_at: now,
+            expires_at: now + hold_secs,
+        };
+        symbols.insert(key, SymbolEntry::Reserved(reservation.clone()));
+        Ok(reservation)
+    }
+
+    /// Drop an issuer's own reservation
+    pub fn release(&self, symbol: &str, issuer: Address) -> Result<(), Error> {
+        let key = normalize(symbol)?;
+        let mut symbols = self.lock()?;
+        match symbols.get(&key) {
+            Some(SymbolEntry::Reserved(reservation)) if reservation.issuer == issuer => {
+                symbols.remove(&key);
+                Ok(())
+            }
+            _ => Err(Error::NotFound(format!("Reservation of {} by {}", key, issuer))),
+        }
+    }
+
+    /// Claim a symbol for `holder`, consuming the issuer's reservation if it has one
+    pub fn claim(&self, symbol: &str, issuer: Address, holder: &str, now: u64) -> Result<(), Error> {
+        let key = normalize(symbol)?;
+        let mut symbols = self.lock()?;
+        Self::check_available(&symbols, &key, issuer, now)?;
+        symbols.insert(key, SymbolEntry::Claimed { holder: holder.to_string() });
+        Ok(())
+    }
+
+    /// Move a claim to a new holder, e.g. from the creation attempt to the registered treasury
+    pub fn reassign(&self, symbol: &str, holder: &str) -> Result<(), Error> {
+        let key = normalize(symbol)?;
+        self.lock()?.insert(key, SymbolEntry::Claimed { holder: holder.to_string() });
+        Ok(())
+    }
+
+    /// Current reservation of a symbol, ignoring expired ones
+    pub fn reservation(&self, symbol: &str, now: u64) -> Result<Option<SymbolReservation>, Error> {
+        let key = normalize(symbol)?;
+        Ok(match self.lock()?.get(&key) {
+            Some(SymbolEntry::Reserved(reservation)) if reservation.expires_at > now => Some(reservation.clone()),
+            _ => None,
+        })
+    }
+
+    fn check_available(symbols: &HashMap<String, SymbolEntry>, key: &str, issuer: Address, now: u64) -> Result<(), Error> {
+        match symbols.get(key) {
+            Some(SymbolEntry::Claimed { holder }) => Err(Error::SymbolTaken {
+                symbol: key.to_string(),
+                holder: holder.clone(),
+            }),
+            Some(SymbolEntry::Reserved(reservation)) if reservation.issuer != issuer && reservation.expires_at > now => {
+                Err(Error::SymbolTaken {
+                    symbol: key.to_string(),
+                    holder: format!("reservation by {} until {}", reservation.issuer, reservation.expires_at),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, HashMap<String, SymbolEntry>>, Error> {
+        self.symbols.lock()
+            .map_err(|_| Error::Internal("Symbol registry lock poisoned".into()))
+    }
+}
+
+fn normalize(symbol: &str) -> Result<String, Error> {
+    let symbol = symbol.trim();
+    if symbol.is_empty() || symbol.len() > 11 || !symbol.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(Error::InvalidParameter(format!("Invalid symbol '{}'", symbol)));
+    }
+    Ok(symbol.to_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claimed_symbol_conflicts_case_insensitively() {
+        let registry = SymbolRegistry::default();
+        registry.claim("tb26", Address::ZERO, "0xabc", 0).unwrap();
+
+        match registry.claim("TB26", Address::repeat_byte(1), "attempt", 0) {
+            Err(Error::SymbolTaken { symbol, holder }) => assert_eq!((symbol.as_str(), holder.as_str()), ("TB26", "0xabc")),
+            other => panic!("expected a conflict, got {:?}", other),
+        }
+        assert!(registry.reserve("Tb26", Address::ZERO, 60, 0).is_err());
+    }
+
+    #[test]
+    fn test_expired_reservation_can_be_reclaimed() {
+        let registry = SymbolRegistry::default();
+        let (holder, other) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        registry.reserve("TN30", holder, 60, 1_000).unwrap();
+
+        assert!(matches!(registry.claim("TN30", other, "attempt", 1_059), Err(Error::SymbolTaken { .. })));
+        // The holder's own claim consumes the reservation
+        assert!(registry.claim("tn30", holder, "attempt", 1_059).is_ok());
+
+        registry.reserve("TB27", holder, 60, 1_000).unwrap();
+        assert_eq!(registry.reservation("TB27", 1_060).unwrap(), None);
+        let reclaimed = registry.reserve("TB27", other, 60, 1_060).unwrap();
+        assert_eq!(reclaimed.issuer, other);
+    }
+}
@@ -0,0 +1,348 @@
+use alloy_primitives::U256;
+use quantera_types::wire::{self, format_token_id};
+use serde::{Serialize, Deserialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+use crate::{Error, TreasuryService, TreasuryStatus};
+
+/// Default cap on token ids a single connection may follow
+pub const DEFAULT_MAX_SUBSCRIPTIONS: usize = 50;
+
+/// Current on-chain values of a treasury as pushed to subscribers
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TreasuryQuote {
+    pub token_id: String,
+    #[serde(with = "quantera_types::wire::u256_decimal")]
+    pub current_price: U256,
+    pub status: TreasuryStatus,
+    pub updated_at: u64,
+}
+
+/// Messages sent to feed subscribers
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", content = "payload")]
+pub enum FeedMessage {
+    /// Current values of the subscribed treasuries, sent on every subscribe
+    Snapshot {
+        treasuries: Vec<TreasuryQuote>,
+    },
+    TreasuryPriceUpdated {
+        token_id: String,
+        #[serde(with = "quantera_types::wire::u256_decimal")]
+        old_price: U256,
+        #[serde(with = "quantera_types::wire::u256_decimal")]
+        new_price: U256,
+        timestamp: u64,
+    },
+    TreasuryStatusUpdated {
+        token_id: String,
+        old_status: TreasuryStatus,
+        new_status: TreasuryStatus,
+        timestamp: u64,
+    },
+    /// The connection's subscriptions after an unsubscribe
+    Subscriptions {
+        all: bool,
+        token_ids: Vec<String>,
+    },
+    Pong,
+    Error {
+        message: String,
+    },
+}
+
+/// Requests accepted from feed subscribers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum FeedRequest {
+    /// Follow the given token ids, or every treasury when omitted
+    Subscribe {
+        #[serde(default)]
+        token_ids: Option<Vec<String>>,
+    },
+    /// Stop following the given token ids, or everything when omitted
+    Unsubscribe {
+        #[serde(default)]
+        token_ids: Option<Vec<String>>,
+    },
+    Ping,
+}
+
+#[derive(Debug)]
+struct FeedClient {
+    sender: mpsc::UnboundedSender<FeedMessage>,
+    all: bool,
+    token_ids: HashSet<[u8; 32]>,
+}
+
+impl FeedClient {
+    fn follows(&self, token_id: &[u8; 32]) -> bool {
+        self.all || self.token_ids.contains(token_id)
+    }
+}
+
+/// Fan-out of treasury price and status changes to connected subscribers.
+///
+/// Holds the latest quote per treasury so subscribers get a snapshot without a REST call.
+/// Changes are fed in by the registry sync with `apply`.
+#[derive(Debug)]
+pub struct TreasuryFeed {
+    clients: RwLock<HashMap<Uuid, FeedClient>>,
+    quotes: RwLock<HashMap<[u8; 32], TreasuryQuote>>,
+    max_subscriptions: usize,
+}
+
+impl Default for TreasuryFeed {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_SUBSCRIPTIONS)
+    }
+}
+
+impl TreasuryFeed {
+    pub fn new(max_subscriptions: usize) -> Self {
+        Self {
+            clients: RwLock::new(HashMap::new()),
+            quotes: RwLock::new(HashMap::new()),
+            max_subscriptions,
+        }
+    }
+
+    /// Register a connection with no subscriptions
+    pub fn connect(&self, sender: mpsc::UnboundedSender<FeedMessage>) -> Uuid {
+        let client_id = Uuid::new_v4();
+        self.clients.write().unwrap().insert(client_id, FeedClient {
+            sender,
+            all: false,
+            token_ids: HashSet::new(),
+        });
+        client_id
+    }
+
+    /// Remove a connection and all of its subscriptions
+    pub fn disconnect(&self, client_id: &Uuid) {
+        self.clients.write().unwrap().remove(client_id);
+    }
+
+    pub fn connection_count(&self) -> usize {
+        self.clients.read().unwrap().len()
+    }
+
+    /// Handle a subscriber request, returning the reply for that subscriber
+    pub fn handle_request(&self, client_id: &Uuid, request: FeedRequest) -> FeedMessage {
+        let result = match request {
+            FeedRequest::Subscribe { token_ids } => self.subscribe(client_id, token_ids),
+            FeedRequest::Unsubscribe { token_ids } => self.unsubscribe(client_id, token_ids),
+            FeedRequest::Ping => Ok(FeedMessage::Pong),
+        };
+        result.unwrap_or_else(|e| FeedMessage::Error { message: e.to_string() })
+    }
+
+    /// Add subscriptions and return a snapshot of the treasuries now followed
+    pub fn subscribe(&self, client_id: &Uuid, token_ids: Option<Vec<String>>) -> Result<FeedMessage, Error> {
+        let token_ids = token_ids.map(|ids| ids.iter().map(|id| parse_token_id(id)).collect::<Result<Vec<_>, _>>())
+            .transpose()?;
+
+        let mut clients = self.clients.write().unwrap();
+        let client = clients.get_mut(client_id)
+            .ok_or_else(|| Error::NotFound(format!("Feed connection {}", client_id)))?;
+
+        match token_ids {
+            None => client.all = true,
+            Some(token_ids) => {
+                let added = token_ids.iter().filter(|id| !client.token_ids.contains(*id)).count();
+                if client.token_ids.len() + added > self.max_subscriptions {
+                    return Err(Error::InvalidParameter(format!(
+                        "Subscription limit of {} token ids per connection exceeded", self.max_subscriptions
+                    )));
+                }
+                client.token_ids.extend(token_ids);
+            }
+        }
+
+        let quotes = self.quotes.read().unwrap();
+        let mut treasuries: Vec<TreasuryQuote> = quotes.iter()
+            .filter(|(token_id, _)| client.follows(token_id))
+            .map(|(_, quote)| quote.clone())
+            .collect();
+        treasuries.sort_by(|a, b| a.token_id.cmp(&b.token_id));
+
+        Ok(FeedMessage::Snapshot { treasuries })
+    }
+
+    /// Remove subscriptions and return what the connection still follows
+    pub fn unsubscribe(&self, client_id: &Uuid, token_ids: Option<Vec<String>>) -> Result<FeedMessage, Error> {
+        let token_ids = token_ids.map(|ids| ids.iter().map(|id| parse_token_id(id)).collect::<Result<Vec<_>, _>>())
+            .transpose()?;
+
+        let mut clients = self.clients.write().unwrap();
+        let client = clients.get_mut(client_id)
+            .ok_or_else(|| Error::NotFound(format!("Feed connection {}", client_id)))?;
+
+        match token_ids {
+            None => {
+                client.all = false;
+                client.token_ids.clear();
+            }
+            Some(token_ids) => {
+                for token_id in &token_ids {
+                    client.token_ids.remove(token_id);
+                }
+            }
+        }
+
+        let mut token_ids: Vec<String> = client.token_ids.iter().map(format_token_id).collect();
+        token_ids.sort();
+        Ok(FeedMessage::Subscriptions { all: client.all, token_ids })
+    }
+
+    /// Record the latest values of a treasury and push any price or status change.
+    ///
+    /// The first quote seen for a treasury only seeds the snapshot.
+    pub fn apply(&self, token_id: [u8; 32], current_price: U256, status: TreasuryStatus) -> Vec<FeedMessage> {
+        let timestamp = chrono::Utc::now().timestamp() as u64;
+        let quote = TreasuryQuote {
+            token_id: format_token_id(&token_id),
+            current_price,
+            status,
+            updated_at: timestamp,
+        };
+
+        let previous = self.quotes.write().unwrap().insert(token_id, quote.clone());
+        let Some(previous) = previous else { return Vec::new() };
+
+        let mut events = Vec::new();
+        if previous.current_price != current_price {
+            events.push(FeedMessage::TreasuryPriceUpdated {
+                token_id: quote.token_id.clone(),
+                old_price: previous.current_price,
+                new_price: current_price,
+                timestamp,
+            });
+        }
+        if previous.status != status {
+            events.push(FeedMessage::TreasuryStatusUpdated {
+                token_id: quote.token_id.clone(),
+                old_status: previous.status,
+                new_status: status,
+                timestamp,
+            });
+        }
+
+        for event in &events {
+            self.publish(&token_id, event);
+        }
+        events
+    }
+
+    /// Send an event to every connection following the treasury
+    fn publish(&self, token_id: &[u8; 32], event: &FeedMessage) {
+        let mut closed = Vec::new();
+        {
+            let clients = self.clients.read().unwrap();
+            for (client_id, client) in clients.iter().filter(|(_, client)| client.follows(token_id)) {
+                if client.sender.send(event.clone()).is_err() {
+                    closed.push(*client_id);
+                }
+            }
+        }
+
+        // Receivers are dropped when a connection ends without deregistering
+        for client_id in closed {
+            tracing::debug!("Dropping closed feed connection {}", client_id);
+            self.disconnect(&client_id);
+        }
+    }
+
+    /// Pull current values from the registry and push any changes
+    pub async fn sync(&self, treasury_service: &TreasuryService) -> Result<usize, Error> {
+        let treasuries = treasury_service.get_all_treasuries().await?;
+        let mut events = 0;
+        for treasury in &treasuries {
+            events += self.apply(treasury.token_id, treasury.current_price, treasury.status).len();
+        }
+        Ok(events)
+    }
+}
+
+/// Sync the feed from the registry on an interval
+pub fn spawn_registry_sync(
+    feed: Arc<TreasuryFeed>,
+    treasury_service: Arc<TreasuryService>,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match feed.sync(&treasury_service).await {
+                Ok(0) => {}
+                Ok(events) => tracing::debug!("Treasury feed sync pushed {} updates", events),
+                Err(e) => tracing::warn!("Treasury feed sync failed: {}", e),
+            }
+        }
+    })
+}
+
+fn parse_token_id(token_id: &str) -> Result<[u8; 32], Error> {
+    wire::parse_token_id(token_id)
+        .map_err(|_| Error::InvalidParameter(format!("Invalid token id: {}", token_id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_updates_reach_only_followers() {
+        let feed = TreasuryFeed::new(2);
+        let (followed_tx, mut followed_rx) = mpsc::unbounded_channel();
+        let (other_tx, mut other_rx) = mpsc::unbounded_channel();
+        let followed = feed.connect(followed_tx);
+        let other = feed.connect(other_tx);
+
+        // First sighting seeds the snapshot without an event
+        assert!(feed.apply([1u8; 32], U256::from(100), TreasuryStatus::Active).is_empty());
+        feed.apply([2u8; 32], U256::from(200), TreasuryStatus::Active);
+
+        match feed.subscribe(&followed, Some(vec![format_token_id(&[1u8; 32])])).unwrap() {
+            FeedMessage::Snapshot { treasuries } => {
+                assert_eq!(treasuries.len(), 1);
+                assert_eq!(treasuries[0].current_price, U256::from(100));
+            }
+            other => panic!("expected snapshot, got {:?}", other),
+        }
+        feed.subscribe(&other, Some(vec![format_token_id(&[2u8; 32])])).unwrap();
+
+        let events = feed.apply([1u8; 32], U256::from(101), TreasuryStatus::Matured);
+        assert_eq!(events.len(), 2);
+        assert!(matches!(followed_rx.try_recv().unwrap(), FeedMessage::TreasuryPriceUpdated { .. }));
+        assert!(matches!(followed_rx.try_recv().unwrap(), FeedMessage::TreasuryStatusUpdated { new_status: TreasuryStatus::Matured, .. }));
+        assert!(other_rx.try_recv().is_err());
+
+        // Unchanged values push nothing
+        assert!(feed.apply([1u8; 32], U256::from(101), TreasuryStatus::Matured).is_empty());
+    }
+
+    #[test]
+    fn test_subscription_cap_and_cleanup() {
+        let feed = TreasuryFeed::new(2);
+        let (tx, rx) = mpsc::unbounded_channel();
+        let client_id = feed.connect(tx);
+
+        let ids: Vec<String> = (1..=3u8).map(|b| format_token_id(&[b; 32])).collect();
+        feed.subscribe(&client_id, Some(ids[..2].to_vec())).unwrap();
+        // Re-subscribing to followed ids does not count against the cap
+        feed.subscribe(&client_id, Some(ids[..1].to_vec())).unwrap();
+        assert!(matches!(feed.subscribe(&client_id, Some(ids[2..].to_vec())), Err(Error::InvalidParameter(_))));
+        assert!(matches!(feed.subscribe(&client_id, Some(vec!["0x12".to_string()])), Err(Error::InvalidParameter(_))));
+
+        // A connection whose receiver is gone is dropped on the next publish
+        feed.apply([1u8; 32], U256::from(1), TreasuryStatus::Active);
+        drop(rx);
+        feed.apply([1u8; 32], U256::from(2), TreasuryStatus::Active);
+        assert_eq!(feed.connection_count(), 0);
+    }
+}
@@ -1,7 +1,12 @@
 use crate::{
     clients::{ComplianceClient, TreasuryTokenClient, TreasuryRegistryClient},
+    holdings_sync::{
+        HoldingKind, HoldingsSync, HoldingsSyncConfig, MulticallBalanceReader, RegistryTokenUniverse,
+        DEFAULT_MULTICALL_ADDRESS,
+    },
     TreasuryInfo, 
     TreasuryStatus,
+    VerificationStatus,
     Error as ServiceError
 };
 use alloy_primitives::{Address, U256, H256, Bytes};
@@ -12,6 +17,7 @@ use serde::{Serialize, Deserialize};
 use async_trait::async_trait;
 use chrono::{Utc, TimeZone};
 use tracing::{info, debug, warn, error};
+use quantera_types::Pii;
 
 /// Verification data for user registration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -112,8 +118,10 @@ pub struct InstitutionalRegistrationResult {
 /// User portfolio holding
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PortfolioHolding {
+    /// Treasury id, or asset id for platform assets
     pub treasury_id: [u8; 32],
     pub token_address: Address,
+    pub kind: HoldingKind,
     pub name: String,
     pub symbol: String,
     pub balance: U256,
@@ -122,6 +130,11 @@ pub struct PortfolioHolding {
     pub yield_rate: u64,
     pub maturity_date: u64,
     pub is_restricted: bool,
+    /// Below the dust threshold; listed but not allocated
+    pub is_dust: bool,
+    /// Share of the non-dust portfolio value in basis points
+    pub allocation_bps: Option<u32>,
+    pub last_synced_block: u64,
 }
 
 /// Complete user portfolio
@@ -134,16 +147,8 @@ pub struct UserPortfolio {
     pub verification_status: VerificationStatus,
     pub investment_limit: Option<U256>,
     pub smart_account_enabled: bool,
-}
-
-/// Verification status
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
-pub enum VerificationStatus {
-    Unverified,
-    Pending,
-    Verified,
-    Rejected,
-    Suspended,
+    pub last_synced_block: u64,
+    pub synced_at: u64,
 }
 
 /// Smart account setup result
@@ -215,6 +220,7 @@ pub struct UserService {
     ethereum_client: Arc<EthereumClient>,
     token_clients: Arc<tokio::sync::Mutex<HashMap<Address, TreasuryTokenClient>>>,
     verification_provider: Arc<dyn VerificationProvider>,
+    holdings: Arc<HoldingsSync>,
 }
 
 impl UserService {
@@ -225,15 +231,35 @@ impl UserService {
         ethereum_client: Arc<EthereumClient>,
         verification_provider: Arc<dyn VerificationProvider>,
     ) -> Self {
+        let multicall = Address::parse_checksummed(DEFAULT_MULTICALL_ADDRESS, None)
+            .expect("Multicall3 address is valid");
+        let holdings = Arc::new(HoldingsSync::new(
+            Arc::new(RegistryTokenUniverse::new(registry_client.clone(), ethereum_client.clone(), Vec::new())),
+            Arc::new(MulticallBalanceReader::new(ethereum_client.clone(), multicall)),
+            HoldingsSyncConfig::default(),
+        ));
+        
         Self {
             compliance_client,
             registry_client,
             ethereum_client,
             token_clients: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
             verification_provider,
+            holdings,
         }
     }
     
+    /// Replace the holdings sync, e.g. to track platform assets or notify on changes
+    pub fn with_holdings_sync(mut self, holdings: Arc<HoldingsSync>) -> Self {
+        self.holdings = holdings;
+        self
+    }
+    
+    /// Holdings sync shared with the background refresh
+    pub fn holdings_sync(&self) -> Arc<HoldingsSync> {
+        self.holdings.clone()
+    }
+    
     /// Get or create token client for a token address
     async fn get_token_client(&self, token_address: Address) -> Result<TreasuryTokenClient, ServiceError> {
         let mut clients = self.token_clients.lock().await;
@@ -255,7 +281,7 @@ impl UserService {
         wallet_address: Address,
         email: String,
     ) -> Result<UserData, ServiceError> {
-        info!("Registering new user: {:?}, email: {}", wallet_address, email);
+        info!(wallet = %Pii::debug(wallet_address), email = %Pii::new(&email), "Registering new user");
         
         // Create metadata URI for storing user data
         // In a real implementation, we would store this in a secure database or IPFS
@@ -270,6 +296,8 @@ impl UserService {
             &metadata_uri,
         ).await.map_err(|e| ServiceError::ContractInteraction(format!("Failed to request verification: {}", e)))?;
         
+        self.holdings.track(wallet_address);
+        
         // Return user data
         let user_data = UserData {
             wallet_address,
@@ -289,7 +317,7 @@ impl UserService {
         wallet_address: Address,
         verification_data: VerificationData,
     ) -> Result<VerificationStatus, ServiceError> {
-        info!("Verifying user identity: {:?}", wallet_address);
+        info!(wallet = %Pii::debug(wallet_address), "Verifying user identity");
         
         // Perform identity verification using the verification provider
         let verification_result = self.verification_provider.verify_identity(&verification_data).await?;
@@ -314,6 +342,8 @@ impl UserService {
             jurisdiction_bytes,
         ).await.map_err(|e| ServiceError::ContractInteraction(format!("Failed to update verification status: {}", e)))?;
         
+        self.holdings.track(wallet_address);
+        
         // Get updated verification status
         let status = self.get_user_verification_status(wallet_address).await?;
         
@@ -333,7 +363,7 @@ impl UserService {
         verification_data: InstitutionalVerificationData,
         stake_amount: U256,
     ) -> Result<InstitutionalRegistrationResult, ServiceError> {
-        info!("Registering institutional user: {:?}, name: {}", wallet_address, verification_data.institution_name);
+        info!(wallet = %Pii::debug(wallet_address), "Registering institutional user");
         
         // Validate BLS public key
         let bls_key_valid = self.verification_provider.validate_bls_key(&verification_data.bls_public_key).await?;
@@ -391,107 +421,56 @@ impl UserService {
         Ok(result)
     }
     
-    /// Get a user's portfolio
+    /// Get a user's portfolio from the cached holdings snapshot, re-syncing it when
+    /// stale or when `force_refresh` is set
     pub async fn get_user_portfolio(
         &self,
         wallet_address: Address,
+        force_refresh: bool,
     ) -> Result<UserPortfolio, ServiceError> {
-        info!("Getting portfolio for user: {:?}", wallet_address);
+        info!(wallet = %Pii::debug(wallet_address), "Getting portfolio");
         
         // Get user verification status
         let verification_details = self.get_user_verification_status(wallet_address).await?;
         
-        // Get all treasury tokens
-        let all_treasuries = self.registry_client.get_all_treasuries().await?;
+        let snapshot = self.holdings.snapshot(wallet_address, force_refresh).await?;
         
-        let mut holdings = Vec::new();
-        let mut total_value = U256::from(0);
+        let mut holdings = snapshot.holdings;
         let mut total_pending_yield = U256::from(0);
         
-        // Check balance for each treasury token
-        for treasury_id in all_treasuries {
-            let treasury_info = match self.registry_client.get_treasury_details(treasury_id).await {
-                Ok(info) => info,
-                Err(e) => {
-                    warn!("Failed to get details for treasury {:?}: {}", treasury_id, e);
-                    continue;
-                }
-            };
-            
-            // Get token client
-            let token_client = match self.get_token_client(treasury_info.token_address).await {
+        // Yield and restrictions only apply to treasury holdings
+        for holding in holdings.iter_mut().filter(|h| h.kind == HoldingKind::Treasury) {
+            let token_client = match self.get_token_client(holding.token_address).await {
                 Ok(client) => client,
                 Err(e) => {
-                    warn!("Failed to get token client for treasury {:?}: {}", treasury_id, e);
+                    warn!("Failed to get token client for treasury {:?}: {}", holding.treasury_id, e);
                     continue;
                 }
             };
             
-            // Get token info
-            let token_info = match token_client.get_token_info().await {
-                Ok(info) => info,
-                Err(e) => {
-                    warn!("Failed to get token info for treasury {:?}: {}", treasury_id, e);
-                    continue;
-                }
-            };
-            
-            // Check user balance
-            let balance = match token_client.balance_of(wallet_address).await {
-                Ok(balance) => balance,
-                Err(e) => {
-                    warn!("Failed to get balance for user {:?} in treasury {:?}: {}", wallet_address, treasury_id, e);
-                    continue;
-                }
-            };
-            
-            // Skip if zero balance
-            if balance == U256::from(0) {
-                continue;
-            }
-            
             // Get pending yield
-            let pending_yield = match token_client.get_pending_yield(wallet_address).await {
+            holding.pending_yield = match token_client.get_pending_yield(wallet_address).await {
                 Ok(yield_amount) => yield_amount,
                 Err(e) => {
-                    warn!("Failed to get pending yield for user {:?} in treasury {:?}: {}", wallet_address, treasury_id, e);
+                    warn!(wallet = %Pii::debug(wallet_address), treasury_id = ?holding.treasury_id, error = %e, "Failed to get pending yield");
                     U256::from(0)
                 }
             };
             
-            // Calculate value (balance * price)
-            let value = balance * treasury_info.current_price;
-            
             // Check if holding is restricted
-            let is_restricted = match self.compliance_client.is_entity_restricted(
+            holding.is_restricted = match self.compliance_client.is_entity_restricted(
                 wallet_address, 
                 0, // Restriction type 0 = trading restriction
-                Some(treasury_id),
+                Some(holding.treasury_id),
             ).await {
                 Ok(restricted) => restricted,
                 Err(e) => {
-                    warn!("Failed to check restrictions for user {:?} in treasury {:?}: {}", wallet_address, treasury_id, e);
+                    warn!(wallet = %Pii::debug(wallet_address), treasury_id = ?holding.treasury_id, error = %e, "Failed to check restrictions");
                     false
                 }
             };
             
-            // Add to holdings
-            holdings.push(PortfolioHolding {
-                treasury_id,
-                token_address: treasury_info.token_address,
-                name: token_info.0,
-                symbol: token_info.1,
-                balance,
-                value,
-                pending_yield,
-                yield_rate: treasury_info.yield_rate,
-                maturity_date: treasury_info.maturity_date,
-                is_restricted,
-            });
-            
-            // Update totals
-            total_value += value;
-            total_pending_yield += pending_yield;
+            total_pending_yield += holding.pending_yield;
         }
         
         // Check if smart account is enabled
@@ -501,22 +480,44 @@ impl UserService {
         let portfolio = UserPortfolio {
             wallet_address,
             holdings,
-            total_value,
+            total_value: snapshot.total_value,
             total_pending_yield,
             verification_status: verification_details.status,
             investment_limit: verification_details.investment_limit,
             smart_account_enabled,
+            last_synced_block: snapshot.last_synced_block,
+            synced_at: snapshot.synced_at,
         };
         
         Ok(portfolio)
     }
     
+    /// Re-sync holdings of every tracked wallet that is currently verified
+    pub async fn sync_verified_holdings(&self) -> Result<usize, ServiceError> {
+        let mut synced = 0;
+        for wallet in self.holdings.tracked_wallets() {
+            match self.get_user_verification_status(wallet).await {
+                Ok(details) if details.status == VerificationStatus::Verified => {}
+                Ok(_) => continue,
+                Err(e) => {
+                    warn!(wallet = %Pii::debug(wallet), error = %e, "Skipping holdings sync");
+                    continue;
+                }
+            }
+            match self.holdings.sync_wallet(wallet).await {
+                Ok(_) => synced += 1,
+                Err(e) => warn!(wallet = %Pii::debug(wallet), error = %e, "Holdings sync failed"),
+            }
+        }
+        Ok(synced)
+    }
+    
     /// Get user verification status
     pub async fn get_user_verification_status(
         &self,
         wallet_address: Address,
     ) -> Result<VerificationDetails, ServiceError> {
-        info!("Getting verification status for user: {:?}", wallet_address);
+        info!(wallet = %Pii::debug(wallet_address), "Getting verification status");
         
         // Get verification data from compliance client
         let data = self.compliance_client.get_verification_data(wallet_address).await
@@ -569,7 +570,7 @@ impl UserService {
                     })
                 },
                 Err(e) => {
-                    warn!("Failed to get institutional details for user {:?}: {}", wallet_address, e);
+                    warn!(wallet = %Pii::debug(wallet_address), error = %e, "Failed to get institutional details");
                     None
                 }
             }
@@ -596,10 +597,10 @@ impl UserService {
         &self,
         wallet_address: Address,
     ) -> Result<U256, ServiceError> {
-        info!("Calculating total yield for user: {:?}", wallet_address);
+        info!(wallet = %Pii::debug(wallet_address), "Calculating total yield");
         
         // Get user portfolio
-        let portfolio = self.get_user_portfolio(wallet_address).await?;
+        let portfolio = self.get_user_portfolio(wallet_address, false).await?;
         
         // Return total pending yield
         Ok(portfolio.total_pending_yield)
@@ -610,7 +611,7 @@ impl UserService {
         &self,
         wallet_address: Address,
     ) -> Result<U256, ServiceError> {
-        info!("Getting investment limits for user: {:?}", wallet_address);
+        info!(wallet = %Pii::debug(wallet_address), "Getting investment limits");
         
         // Get user verification status
         let verification_details = self.get_user_verification_status(wallet_address).await?;
@@ -625,7 +626,7 @@ impl UserService {
         wallet_address: Address,
         account_code: Vec<u8>,
     ) -> Result<SmartAccountSetupResult, ServiceError> {
-        info!("Setting up smart account for user: {:?}", wallet_address);
+        info!(wallet = %Pii::debug(wallet_address), "Setting up smart account");
         
         // Get any token client to access the token_client interface
         let treasuries = self.registry_client.get_all_treasuries().await?;
@@ -685,7 +686,7 @@ impl UserService {
         wallet_address: Address,
         operation_data: Vec<u8>,
     ) -> Result<Vec<u8>, ServiceError> {
-        info!("Executing smart account operation for user: {:?}", wallet_address);
+        info!(wallet = %Pii::debug(wallet_address), "Executing smart account operation");
         
         // Check if smart account is enabled
         if !self.is_smart_account_enabled(wallet_address).await? {
@@ -706,7 +707,7 @@ impl UserService {
         operator_address: Address,
         approved: bool,
     ) -> Result<bool, ServiceError> {
-        info!("Setting delegated operator {:?} for user {:?}, approved: {}", operator_address, user_address, approved);
+        info!(operator = ?operator_address, wallet = %Pii::debug(user_address), approved, "Setting delegated operator");
         
         // Use the registry client to set delegation
         self.registry_client.delegate_operator(user_address, operator_address, approved).await?;
@@ -719,7 +720,7 @@ impl UserService {
         &self,
         user_address: Address,
     ) -> Result<Vec<Address>, ServiceError> {
-        info!("Getting delegated operators for user: {:?}", user_address);
+        info!(wallet = %Pii::debug(user_address), "Getting delegated operators");
         
         // In a real implementation, we would query the contract for all delegated operators
         // For now, we'll return an empty list since we don't have that functionality in the client
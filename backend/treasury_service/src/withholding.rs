@@ -0,0 +1,539 @@
+// Withholding tax on yield distributions, by holder jurisdiction and documentation
+use alloy_primitives::{Address, U256};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Serialize, Deserialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+use tracing::{info, warn};
+use uuid::Uuid;
+use crate::{Error, ReportPeriod};
+
+const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Tax forms a holder can have on file
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum TaxDocument {
+    /// IRS W-8BEN, foreign individual
+    W8Ben,
+    /// IRS W-8BEN-E, foreign entity
+    W8BenE,
+    /// IRS W-9, US person
+    W9,
+    /// Certificate of tax residence from the holder's home authority
+    ResidencyCertificate,
+}
+
+impl TaxDocument {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaxDocument::W8Ben => "W-8BEN",
+            TaxDocument::W8BenE => "W-8BEN-E",
+            TaxDocument::W9 => "W-9",
+            TaxDocument::ResidencyCertificate => "residency certificate",
+        }
+    }
+}
+
+/// Withholding on yield paid to holders resident in one jurisdiction
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WithholdingRule {
+    /// ISO 3166-1 alpha-2 code
+    pub jurisdiction: String,
+    /// Rate once every required document is on file
+    pub treaty_rate_bps: u32,
+    /// Rate while any required document is missing
+    pub default_rate_bps: u32,
+    pub required_documents: Vec<TaxDocument>,
+}
+
+impl WithholdingRule {
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.jurisdiction.len() != 2 || !self.jurisdiction.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(Error::InvalidParameter(format!(
+                "Jurisdiction must be a two-letter upper-case code, got {}", self.jurisdiction
+            )));
+        }
+        if u64::from(self.default_rate_bps) > BPS_DENOMINATOR {
+            return Err(Error::InvalidParameter("Withholding rates cannot exceed 10000 bps".into()));
+        }
+        if self.treaty_rate_bps > self.default_rate_bps {
+            return Err(Error::InvalidParameter(format!(
+                "Treaty rate for {} is above its default rate", self.jurisdiction
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A holder's tax residence and the forms on file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxProfile {
+    pub wallet: Address,
+    pub jurisdiction: String,
+    pub documents: Vec<TaxDocument>,
+    pub updated_by: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxProfileUpdate {
+    pub wallet: Address,
+    pub jurisdiction: String,
+    pub documents: Vec<TaxDocument>,
+}
+
+/// Why a holder was withheld at the rate they were
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RateBasis {
+    /// Every required document is on file
+    Treaty,
+    /// A required document is missing
+    Default,
+    /// No rule for the holder's jurisdiction
+    NotWithheld,
+    /// No tax profile; withheld at the highest default rate in the table
+    NoProfile,
+}
+
+/// One holder's share of a distribution split into net payout and withheld amount
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HolderWithholding {
+    pub wallet: Address,
+    /// None when the holder has no tax profile
+    pub jurisdiction: Option<String>,
+    pub gross: U256,
+    pub rate_bps: u32,
+    pub basis: RateBasis,
+    pub withheld: U256,
+    pub net: U256,
+    /// Required documents that are not on file
+    pub missing_documents: Vec<TaxDocument>,
+}
+
+/// Amount withheld from one holder on one distribution, owed to the holder's tax authority
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WithholdingEntry {
+    pub entry_id: Uuid,
+    /// Distribution the yield was paid on
+    pub reference: String,
+    #[serde(with = "quantera_types::wire::token_id")]
+    pub treasury_id: [u8; 32],
+    pub wallet: Address,
+    pub jurisdiction: Option<String>,
+    pub gross: U256,
+    pub rate_bps: u32,
+    pub basis: RateBasis,
+    pub withheld: U256,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Sent to a holder withheld at a higher rate because forms are missing
+#[derive(Debug, Clone, Serialize)]
+pub struct DocumentationNotice {
+    pub wallet: Address,
+    pub jurisdiction: Option<String>,
+    pub missing_documents: Vec<TaxDocument>,
+    pub reference: String,
+    pub rate_bps: u32,
+    pub withheld: U256,
+}
+
+/// Asks holders to submit missing tax forms
+#[async_trait]
+pub trait DocumentationNotifier: Send + Sync {
+    async fn documents_missing(&self, notice: &DocumentationNotice);
+}
+
+/// Default notifier: a log line per holder
+pub struct LogDocumentationNotifier;
+
+#[async_trait]
+impl DocumentationNotifier for LogDocumentationNotifier {
+    async fn documents_missing(&self, notice: &DocumentationNotice) {
+        let missing: Vec<&str> = notice.missing_documents.iter().map(TaxDocument::as_str).collect();
+        info!(
+            "{:?} withheld at {} bps on {}; missing documents: {}",
+            notice.wallet, notice.rate_bps, notice.reference,
+            if missing.is_empty() { "tax profile".to_string() } else { missing.join(", ") }
+        );
+    }
+}
+
+pub struct WebhookDocumentationNotifier {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookDocumentationNotifier {
+    pub fn new(url: String) -> Self {
+        Self { client: reqwest::Client::new(), url }
+    }
+}
+
+#[async_trait]
+impl DocumentationNotifier for WebhookDocumentationNotifier {
+    async fn documents_missing(&self, notice: &DocumentationNotice) {
+        if let Err(e) = self.client.post(&self.url).json(notice).send().await.and_then(|r| r.error_for_status()) {
+            warn!("Tax documentation webhook failed for {:?}: {}", notice.wallet, e);
+        }
+    }
+}
+
+/// Balances of every known holder of a token
+#[async_trait]
+pub trait HolderBalances: Send + Sync {
+    async fn holder_balances(&self, token: Address) -> Result<Vec<(Address, U256)>, Error>;
+}
+
+/// Withheld amounts of one jurisdiction within one period
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RemittanceRow {
+    /// None for holders without a tax profile
+    pub jurisdiction: Option<String>,
+    pub period_start: NaiveDate,
+    pub entries: usize,
+    pub gross: U256,
+    pub withheld: U256,
+}
+
+/// Remittance rows as CSV with a header line; amounts in base units
+pub fn remittance_csv(rows: &[RemittanceRow]) -> String {
+    let mut csv = String::from("jurisdiction,period_start,entries,gross,withheld\n");
+    for row in rows {
+        csv.push_str(&format!(
+            "{},{},{},{},{}\n",
+            row.jurisdiction.as_deref().unwrap_or("unknown"), row.period_start, row.entries, row.gross, row.withheld
+        ));
+    }
+    csv
+}
+
+/// Withholding rules per jurisdiction, holders' tax profiles, and the amounts withheld.
+///
+/// Jurisdictions without a rule are paid gross.
+pub struct WithholdingTable {
+    rules: RwLock<HashMap<String, WithholdingRule>>,
+    profiles: RwLock<HashMap<Address, TaxProfile>>,
+    entries: RwLock<Vec<WithholdingEntry>>,
+    notifier: Arc<dyn DocumentationNotifier>,
+}
+
+impl Default for WithholdingTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WithholdingTable {
+    pub fn new() -> Self {
+        Self {
+            rules: RwLock::new(HashMap::new()),
+            profiles: RwLock::new(HashMap::new()),
+            entries: RwLock::new(Vec::new()),
+            notifier: Arc::new(LogDocumentationNotifier),
+        }
+    }
+
+    pub fn with_notifier(mut self, notifier: Arc<dyn DocumentationNotifier>) -> Self {
+        self.notifier = notifier;
+        self
+    }
+
+    /// Table with the rules in the JSON file at `WITHHOLDING_RULES_PATH` and the tax
+    /// profiles at `TAX_PROFILES_PATH`; both files are standing configuration
+    pub fn from_env() -> Result<Self, Error> {
+        let table = Self::new();
+        let now = Utc::now();
+        if let Ok(path) = std::env::var("WITHHOLDING_RULES_PATH") {
+            let rules: Vec<WithholdingRule> = read_json(&path, "WITHHOLDING_RULES_PATH")?;
+            for rule in rules {
+                table.set_rule(rule, "configuration")
+                    .map_err(|e| Error::InvalidParameter(format!("Invalid withholding rules {}: {}", path, e)))?;
+            }
+        }
+        if let Ok(path) = std::env::var("TAX_PROFILES_PATH") {
+            let profiles: Vec<TaxProfileUpdate> = read_json(&path, "TAX_PROFILES_PATH")?;
+            for profile in profiles {
+                table.set_profile(profile, "configuration", now)
+                    .map_err(|e| Error::InvalidParameter(format!("Invalid tax profiles {}: {}", path, e)))?;
+            }
+        }
+        Ok(table)
+    }
+
+    /// Add or replace the rule of a jurisdiction; applies to distributions from now on
+    pub fn set_rule(&self, rule: WithholdingRule, set_by: &str) -> Result<WithholdingRule, Error> {
+        rule.validate()?;
+        self.rules.write().map_err(|_| Error::Internal("Withholding rules lock poisoned".into()))?
+            .insert(rule.jurisdiction.clone(), rule.clone());
+        info!(
+            "[AUDIT] Withholding for {} set by {}: treaty {} bps, default {} bps, requires {:?}",
+            rule.jurisdiction, set_by, rule.treaty_rate_bps, rule.default_rate_bps, rule.required_documents
+        );
+        Ok(rule)
+    }
+
+    /// Rules ordered by jurisdiction
+    pub fn rules(&self) -> Vec<WithholdingRule> {
+        let mut rules: Vec<WithholdingRule> = self.rules.read().map(|rules| rules.values().cloned().collect()).unwrap_or_default();
+        rules.sort_by(|a, b| a.jurisdiction.cmp(&b.jurisdiction));
+        rules
+    }
+
+    /// Record a holder's jurisdiction and the forms on file, replacing what was there
+    pub fn set_profile(&self, update: TaxProfileUpdate, updated_by: &str, now: DateTime<Utc>) -> Result<TaxProfile, Error> {
+        let jurisdiction = update.jurisdiction.trim().to_ascii_uppercase();
+        if jurisdiction.len() != 2 {
+            return Err(Error::InvalidParameter(format!("Invalid jurisdiction {}", update.jurisdiction)));
+        }
+        let mut documents = update.documents;
+        documents.sort();
+        documents.dedup();
+
+        let profile = TaxProfile { wallet: update.wallet, jurisdiction, documents, updated_by: updated_by.to_string(), updated_at: now };
+        self.profiles.write().map_err(|_| Error::Internal("Tax profiles lock poisoned".into()))?
+            .insert(profile.wallet, profile.clone());
+        info!("[AUDIT] Tax profile of {:?} set by {}: {} with {:?}", profile.wallet, updated_by, profile.jurisdiction, profile.documents);
+        Ok(profile)
+    }
+
+    /// Profiles ordered by wallet
+    pub fn profiles(&self) -> Vec<TaxProfile> {
+        let mut profiles: Vec<TaxProfile> = self.profiles.read().map(|profiles| profiles.values().cloned().collect()).unwrap_or_default();
+        profiles.sort_by_key(|profile| profile.wallet);
+        profiles
+    }
+
+    pub fn profile(&self, wallet: Address) -> Option<TaxProfile> {
+        self.profiles.read().ok()?.get(&wallet).cloned()
+    }
+
+    /// Split a holder's gross yield by their current profile. Withheld amounts round down.
+    pub fn split(&self, wallet: Address, gross: U256) -> Result<HolderWithholding, Error> {
+        let rules = self.rules.read().map_err(|_| Error::Internal("Withholding rules lock poisoned".into()))?;
+        let profile = self.profile(wallet);
+
+        let (jurisdiction, rate_bps, basis, missing_documents) = match &profile {
+            None => {
+                let fallback = rules.values().map(|rule| rule.default_rate_bps).max().unwrap_or(0);
+                (None, fallback, RateBasis::NoProfile, Vec::new())
+            }
+            Some(profile) => match rules.get(&profile.jurisdiction) {
+                None => (Some(profile.jurisdiction.clone()), 0, RateBasis::NotWithheld, Vec::new()),
+                Some(rule) => {
+                    let missing: Vec<TaxDocument> = rule.required_documents.iter()
+                        .filter(|document| !profile.documents.contains(document))
+                        .copied()
+                        .collect();
+                    if missing.is_empty() {
+                        (Some(profile.jurisdiction.clone()), rule.treaty_rate_bps, RateBasis::Treaty, missing)
+                    } else {
+                        (Some(profile.jurisdiction.clone()), rule.default_rate_bps, RateBasis::Default, missing)
+                    }
+                }
+            },
+        };
+
+        let withheld = gross.saturating_mul(U256::from(rate_bps)) / U256::from(BPS_DENOMINATOR);
+        Ok(HolderWithholding {
+            wallet,
+            jurisdiction,
+            gross,
+            rate_bps,
+            basis,
+            withheld,
+            net: gross - withheld,
+            missing_documents,
+        })
+    }
+
+    /// Allocate `amount` pro rata to balances out of `total_supply` and split each share.
+    /// Holders with a zero share are left out.
+    pub fn allocate(&self, amount: U256, total_supply: U256, balances: &[(Address, U256)]) -> Result<Vec<HolderWithholding>, Error> {
+        if total_supply == U256::ZERO {
+            return Ok(Vec::new());
+        }
+        balances.iter()
+            .map(|(wallet, balance)| (*wallet, amount.saturating_mul(*balance) / total_supply))
+            .filter(|(_, gross)| *gross > U256::ZERO)
+            .map(|(wallet, gross)| self.split(wallet, gross))
+            .collect()
+    }
+
+    /// Record the withholding of a paid distribution and ask holders withheld at a higher
+    /// rate for their missing forms
+    pub async fn record(
+        &self,
+        reference: &str,
+        treasury_id: [u8; 32],
+        holders: &[HolderWithholding],
+        at: DateTime<Utc>,
+    ) -> Result<Vec<WithholdingEntry>, Error> {
+        let entries: Vec<WithholdingEntry> = holders.iter()
+            .map(|holder| WithholdingEntry {
+                entry_id: Uuid::new_v4(),
+                reference: reference.to_string(),
+                treasury_id,
+                wallet: holder.wallet,
+                jurisdiction: holder.jurisdiction.clone(),
+                gross: holder.gross,
+                rate_bps: holder.rate_bps,
+                basis: holder.basis,
+                withheld: holder.withheld,
+                recorded_at: at,
+            })
+            .collect();
+        self.entries.write().map_err(|_| Error::Internal("Withholding entries lock poisoned".into()))?
+            .extend(entries.iter().cloned());
+
+        let withheld = entries.iter().fold(U256::ZERO, |total, entry| total.saturating_add(entry.withheld));
+        info!("[AUDIT] {} withheld from {} holders on {}", withheld, entries.len(), reference);
+
+        for holder in holders.iter().filter(|h| matches!(h.basis, RateBasis::Default | RateBasis::NoProfile)) {
+            self.notifier.documents_missing(&DocumentationNotice {
+                wallet: holder.wallet,
+                jurisdiction: holder.jurisdiction.clone(),
+                missing_documents: holder.missing_documents.clone(),
+                reference: reference.to_string(),
+                rate_bps: holder.rate_bps,
+                withheld: holder.withheld,
+            }).await;
+        }
+        Ok(entries)
+    }
+
+    /// Entries recorded in `[from, to)` totalled per jurisdiction and period
+    pub fn remittance_report(&self, from: DateTime<Utc>, to: DateTime<Utc>, period: ReportPeriod) -> Vec<RemittanceRow> {
+        let entries = self.entries.read().map(|entries| entries.clone()).unwrap_or_default();
+        let mut totals: BTreeMap<(Option<String>, NaiveDate), RemittanceRow> = BTreeMap::new();
+        for entry in entries.into_iter().filter(|e| e.recorded_at >= from && e.recorded_at < to) {
+            let period_start = period.start(entry.recorded_at);
+            let row = totals.entry((entry.jurisdiction.clone(), period_start))
+                .or_insert_with(|| RemittanceRow {
+                    jurisdiction: entry.jurisdiction.clone(),
+                    period_start,
+                    entries: 0,
+                    gross: U256::ZERO,
+                    withheld: U256::ZERO,
+                });
+            row.entries += 1;
+            row.gross = row.gross.saturating_add(entry.gross);
+            row.withheld = row.withheld.saturating_add(entry.withheld);
+        }
+        totals.into_values().collect()
+    }
+}
+
+pub(crate) fn read_json<T: serde::de::DeserializeOwned>(path: &str, variable: &str) -> Result<T, Error> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| Error::InvalidParameter(format!("Cannot read {} {}: {}", variable, path, e)))?;
+    serde_json::from_str(&contents)
+        .map_err(|e| Error::InvalidParameter(format!("Invalid {} {}: {}", variable, path, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingNotifier(Mutex<Vec<DocumentationNotice>>);
+
+    #[async_trait]
+    impl DocumentationNotifier for RecordingNotifier {
+        async fn documents_missing(&self, notice: &DocumentationNotice) {
+            self.0.lock().unwrap().push(notice.clone());
+        }
+    }
+
+    fn table(notifier: Arc<RecordingNotifier>) -> WithholdingTable {
+        let table = WithholdingTable::new().with_notifier(notifier);
+        table.set_rule(WithholdingRule {
+            jurisdiction: "DE".into(),
+            treaty_rate_bps: 1_500,
+            default_rate_bps: 3_000,
+            required_documents: vec![TaxDocument::W8Ben],
+        }, "admin").unwrap();
+        table.set_rule(WithholdingRule {
+            jurisdiction: "SG".into(),
+            treaty_rate_bps: 0,
+            default_rate_bps: 2_400,
+            required_documents: vec![TaxDocument::W8BenE, TaxDocument::ResidencyCertificate],
+        }, "admin").unwrap();
+        table
+    }
+
+    fn profile(wallet: Address, jurisdiction: &str, documents: Vec<TaxDocument>) -> TaxProfileUpdate {
+        TaxProfileUpdate { wallet, jurisdiction: jurisdiction.into(), documents }
+    }
+
+    #[test]
+    fn test_treaty_and_default_rates() {
+        let table = table(Arc::default());
+        let now = Utc::now();
+        let (documented, undocumented, partial, domestic, unknown) =
+            (Address::repeat_byte(1), Address::repeat_byte(2), Address::repeat_byte(3), Address::repeat_byte(4), Address::repeat_byte(5));
+        table.set_profile(profile(documented, "de", vec![TaxDocument::W8Ben]), "ops", now).unwrap();
+        table.set_profile(profile(undocumented, "DE", vec![]), "ops", now).unwrap();
+        table.set_profile(profile(partial, "SG", vec![TaxDocument::W8BenE]), "ops", now).unwrap();
+        table.set_profile(profile(domestic, "US", vec![TaxDocument::W9]), "ops", now).unwrap();
+
+        // 10,000 of yield over a supply of 1,000: each 100 tokens earns 1,000
+        let balances: Vec<(Address, U256)> = [documented, undocumented, partial, domestic, unknown].iter()
+            .map(|wallet| (*wallet, U256::from(100u64)))
+            .collect();
+        let split = table.allocate(U256::from(10_000u64), U256::from(1_000u64), &balances).unwrap();
+
+        let rates: Vec<(RateBasis, u32, u64, u64)> = split.iter()
+            .map(|h| (h.basis, h.rate_bps, h.withheld.to::<u64>(), h.net.to::<u64>()))
+            .collect();
+        assert_eq!(rates, vec![
+            (RateBasis::Treaty, 1_500, 150, 850),
+            (RateBasis::Default, 3_000, 300, 700),
+            (RateBasis::Default, 2_400, 240, 760),
+            (RateBasis::NotWithheld, 0, 0, 1_000),
+            // Without a profile the highest default rate applies
+            (RateBasis::NoProfile, 3_000, 300, 700),
+        ]);
+        assert_eq!(split[0].jurisdiction.as_deref(), Some("DE"));
+        assert_eq!(split[2].missing_documents, vec![TaxDocument::ResidencyCertificate]);
+
+        assert!(WithholdingRule { jurisdiction: "FR".into(), treaty_rate_bps: 3_000, default_rate_bps: 1_000, required_documents: vec![] }
+            .validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_documentation_change_between_periods() {
+        let notifier = Arc::new(RecordingNotifier::default());
+        let table = table(notifier.clone());
+        let holder = Address::repeat_byte(7);
+        let march = Utc.with_ymd_and_hms(2026, 3, 31, 12, 0, 0).unwrap();
+        let april = march + Duration::days(30);
+        let gross = U256::from(2_000u64);
+
+        table.set_profile(profile(holder, "DE", vec![]), "ops", march).unwrap();
+        let first = table.split(holder, gross).unwrap();
+        table.record("distribution 1", [1u8; 32], &[first], march).await.unwrap();
+
+        // Forms arrive after the March distribution; only later distributions get the treaty rate
+        table.set_profile(profile(holder, "DE", vec![TaxDocument::W8Ben]), "ops", march + Duration::days(1)).unwrap();
+        let second = table.split(holder, gross).unwrap();
+        table.record("distribution 2", [1u8; 32], &[second], april).await.unwrap();
+
+        let notices = notifier.0.lock().unwrap().clone();
+        assert_eq!(notices.len(), 1);
+        assert_eq!((notices[0].reference.as_str(), notices[0].missing_documents.clone()), ("distribution 1", vec![TaxDocument::W8Ben]));
+
+        let rows = table.remittance_report(march - Duration::days(30), april + Duration::days(1), ReportPeriod::Month);
+        let withheld: Vec<(NaiveDate, u64)> = rows.iter().map(|r| (r.period_start, r.withheld.to::<u64>())).collect();
+        assert_eq!(withheld, vec![
+            (NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(), 600),
+            (NaiveDate::from_ymd_opt(2026, 4, 1).unwrap(), 300),
+        ]);
+        assert!(remittance_csv(&rows).starts_with("jurisdiction,period_start,entries,gross,withheld\nDE,2026-03-01,1,2000,600\n"));
+    }
+}
@@ -3,6 +3,14 @@ use crate::{
     TreasuryTokenClient, 
     TreasuryInfo, 
     TreasuryStatus,
+    FeeOperation,
+    FeeSchedule,
+    HolderBalances,
+    HolderWithholding,
+    WithholdingTable,
+    AutoCompounder,
+    BusinessCalendar,
+    DateAdjustment,
     Error as ServiceError
 };
 use alloy_primitives::{Address, U256, H256};
@@ -12,26 +20,50 @@ use std::collections::HashMap;
 use tokio::task::JoinHandle;
 use tokio::time::{self, Duration};
 use chrono::{Utc, TimeZone};
+use serde::Serialize;
 use tracing::{info, debug, warn, error};
 
+/// Yield is distributed for 30-day periods
+const DISTRIBUTION_PERIOD: u64 = 30 * 24 * 60 * 60;
+
 /// Result of a yield distribution operation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct YieldDistributionResult {
     pub treasury_id: [u8; 32],
     pub token_address: Address,
     pub distribution_id: u64,
     pub amount: U256,
+    /// Total withheld from holders for tax; zero when withholding is not configured
+    pub withheld: U256,
     pub distribution_time: u64,
     pub success: bool,
     pub error_message: Option<String>,
+    /// Set when the scheduled date was not a business day and the run was moved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule_adjustment: Option<DateAdjustment>,
+}
+
+/// What a yield distribution would pay, per holder, without distributing
+#[derive(Debug, Clone, Serialize)]
+pub struct DistributionPreview {
+    pub treasury_id: [u8; 32],
+    pub token_address: Address,
+    pub yield_rate: u64,
+    pub amount: U256,
+    /// Empty when withholding is not configured
+    pub holders: Vec<HolderWithholding>,
 }
 
 /// Result of maturity processing
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct MaturityResult {
     pub treasury_id: [u8; 32],
     pub token_address: Address,
     pub maturity_date: u64,
+    /// Maturity date moved to a business day; equal to it when no calendar applies
+    pub settlement_date: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settlement_adjustment: Option<DateAdjustment>,
     pub processed_at: u64,
     pub success: bool,
     pub error_message: Option<String>,
@@ -56,6 +88,17 @@ pub struct YieldSchedulerService {
     ethereum_client: Arc<EthereumClient>,
     scheduler_handle: Option<JoinHandle<()>>,
     running: bool,
+    fees: Option<Arc<FeeSchedule>>,
+    withholding: Option<(Arc<WithholdingTable>, Arc<dyn HolderBalances>)>,
+    auto_compound: Option<Arc<AutoCompounder>>,
+    calendar: Option<Arc<BusinessCalendar>>,
+}
+
+/// A treasury whose distribution is due, and how its due date was moved
+struct DueDistribution {
+    treasury_id: [u8; 32],
+    treasury_info: TreasuryInfo,
+    adjustment: Option<DateAdjustment>,
 }
 
 impl YieldSchedulerService {
@@ -70,9 +113,49 @@ impl YieldSchedulerService {
             ethereum_client,
             scheduler_handle: None,
             running: false,
+            fees: None,
+            withholding: None,
+            auto_compound: None,
+            calendar: None,
         }
     }
     
+    /// Accrue a fee on each yield distribution; distributions are refused while no
+    /// yield distribution fee is configured
+    pub fn with_fee_schedule(mut self, fees: Arc<FeeSchedule>) -> Self {
+        self.fees = Some(fees);
+        self
+    }
+    
+    /// Split each holder's share of a distribution into net payout and tax withheld;
+    /// distributions are refused while holder balances cannot be read
+    pub fn with_withholding(mut self, table: Arc<WithholdingTable>, holders: Arc<dyn HolderBalances>) -> Self {
+        self.withholding = Some((table, holders));
+        self
+    }
+    
+    /// Reinvest each holder's net yield for holders with an active automation config;
+    /// holders' shares come from the withholding split, so this needs `with_withholding`
+    pub fn with_auto_compound(mut self, compounder: Arc<AutoCompounder>) -> Self {
+        self.auto_compound = Some(compounder);
+        self
+    }
+    
+    /// Move distribution runs and maturity settlement off weekends and market holidays
+    pub fn with_calendar(mut self, calendar: Arc<BusinessCalendar>) -> Self {
+        self.calendar = Some(calendar);
+        self
+    }
+    
+    /// `timestamp` moved to a business day, and the adjustment when it was moved
+    fn business_day(&self, timestamp: u64) -> Result<(u64, Option<DateAdjustment>), ServiceError> {
+        let Some(calendar) = &self.calendar else {
+            return Ok((timestamp, None));
+        };
+        let adjustment = calendar.adjust_timestamp(timestamp)?;
+        Ok((adjustment.as_ref().map_or(timestamp, |adjustment| adjustment.apply(timestamp)), adjustment))
+    }
+    
     /// Get or create token client for a token address
     async fn get_token_client(&self, token_address: Address) -> Result<TreasuryTokenClient, ServiceError> {
         let mut clients = self.token_clients.lock().await;
@@ -105,35 +188,48 @@ impl YieldSchedulerService {
             ));
         }
         
-        // Get token client
-        let token_client = self.get_token_client(treasury_info.token_address).await?;
+        let (token_client, total_supply, yield_amount) = self.next_yield(&treasury_info).await?;
+        let now = Utc::now().timestamp() as u64;
         
-        // Calculate yield amount - in a real implementation, this would use more complex logic
-        // For example, calculating based on time since last distribution, current yield rate, etc.
-        let total_supply = token_client.get_token_info().await
-            .map_err(|e| ServiceError::ContractInteraction(format!("Failed to get token info: {}", e)))?
-            .2;
+        if let Some(fees) = &self.fees {
+            fees.rule_at(FeeOperation::YieldDistribution, Utc::now())?;
+        }
         
-        // Calculate yield for a period (e.g., 30 days from now)
-        let now = Utc::now().timestamp() as u64;
-        let distribution_period = 30 * 24 * 60 * 60; // 30 days in seconds
-        let yield_amount = calculate_yield_amount(total_supply, treasury_info.yield_rate, distribution_period)?;
+        let holders = self.holder_withholding(treasury_info.token_address, yield_amount, total_supply).await?;
+        let withheld = holders.iter().fold(U256::ZERO, |total, holder| total.saturating_add(holder.withheld));
         
         // Distribute yield
         let result = match token_client.distribute_yield(
             yield_amount, 
             None, // All partitions
-            now + distribution_period, // End time (30 days from now)
+            now + DISTRIBUTION_PERIOD, // End time (30 days from now)
         ).await {
             Ok(distribution_id) => {
+                let reference = format!("distribution {} of 0x{}", distribution_id, hex::encode(treasury_id));
+                if let Some(fees) = &self.fees {
+                    if let Err(e) = fees.accrue(FeeOperation::YieldDistribution, reference.clone(), yield_amount, fees.payment_currency(), Utc::now()) {
+                        error!("Yield distribution fee not accrued for {:?}: {}", treasury_id, e);
+                    }
+                }
+                if let Some((table, _)) = &self.withholding {
+                    if let Err(e) = table.record(&reference, treasury_id, &holders, Utc::now()).await {
+                        error!("Withholding not recorded for {:?}: {}", treasury_id, e);
+                    }
+                }
+                if let Some(compounder) = &self.auto_compound {
+                    compounder.on_distribution(&reference, treasury_info.token_address, &holders).await;
+                }
+                
                 YieldDistributionResult {
                     treasury_id,
                     token_address: treasury_info.token_address,
                     distribution_id,
                     amount: yield_amount,
+                    withheld,
                     distribution_time: now,
                     success: true,
                     error_message: None,
+                    schedule_adjustment: None,
                 }
             },
             Err(e) => {
@@ -145,9 +241,11 @@ impl YieldSchedulerService {
                     token_address: treasury_info.token_address,
                     distribution_id: 0,
                     amount: yield_amount,
+                    withheld: U256::ZERO,
                     distribution_time: now,
                     success: false,
                     error_message: Some(error_msg),
+                    schedule_adjustment: None,
                 }
             }
         };
@@ -155,6 +253,52 @@ impl YieldSchedulerService {
         Ok(result)
     }
     
+    /// Amount the next distribution of a treasury would pay, split per holder
+    pub async fn preview_yield_distribution(
+        &self,
+        treasury_id: [u8; 32],
+    ) -> Result<DistributionPreview, ServiceError> {
+        let treasury_info = self.registry_client.get_treasury_details(treasury_id).await?;
+        let (_, total_supply, yield_amount) = self.next_yield(&treasury_info).await?;
+        let holders = self.holder_withholding(treasury_info.token_address, yield_amount, total_supply).await?;
+        
+        Ok(DistributionPreview {
+            treasury_id,
+            token_address: treasury_info.token_address,
+            yield_rate: treasury_info.yield_rate,
+            amount: yield_amount,
+            holders,
+        })
+    }
+    
+    /// Token client, total supply and yield for the next distribution period
+    async fn next_yield(&self, treasury_info: &TreasuryInfo) -> Result<(TreasuryTokenClient, U256, U256), ServiceError> {
+        let token_client = self.get_token_client(treasury_info.token_address).await?;
+        
+        // Calculate yield amount - in a real implementation, this would use more complex logic
+        // For example, calculating based on time since last distribution, current yield rate, etc.
+        let total_supply = token_client.get_token_info().await
+            .map_err(|e| ServiceError::ContractInteraction(format!("Failed to get token info: {}", e)))?
+            .2;
+        
+        let yield_amount = calculate_yield_amount(total_supply, treasury_info.yield_rate, DISTRIBUTION_PERIOD)?;
+        Ok((token_client, total_supply, yield_amount))
+    }
+    
+    /// Each holder's share of `yield_amount`, split by the withholding table
+    async fn holder_withholding(
+        &self,
+        token_address: Address,
+        yield_amount: U256,
+        total_supply: U256,
+    ) -> Result<Vec<HolderWithholding>, ServiceError> {
+        let Some((table, holders)) = &self.withholding else {
+            return Ok(Vec::new());
+        };
+        let balances = holders.holder_balances(token_address).await?;
+        table.allocate(yield_amount, total_supply, &balances)
+    }
+    
     /// Process maturity for a specific treasury
     pub async fn process_maturity(
         &self,
@@ -175,12 +319,17 @@ impl YieldSchedulerService {
         // Get token client
         let token_client = self.get_token_client(treasury_info.token_address).await?;
         
-        // Check if matured
+        // Check if matured; a maturity on a non-business day settles on the adjusted date
         let now = Utc::now().timestamp() as u64;
-        if now < treasury_info.maturity_date {
-            return Err(ServiceError::InvalidState(
-                format!("Treasury {:?} has not matured yet, maturity date: {}", treasury_id, treasury_info.maturity_date)
-            ));
+        let (settlement_date, settlement_adjustment) = self.business_day(treasury_info.maturity_date)?;
+        if now < settlement_date {
+            return Err(ServiceError::InvalidState(format!(
+                "Treasury {:?} has not reached its settlement date {} (maturity date {})",
+                treasury_id, settlement_date, treasury_info.maturity_date
+            )));
+        }
+        if let Some(adjustment) = &settlement_adjustment {
+            info!("Maturity of treasury {:?} on {} settles on {}", treasury_id, adjustment.unadjusted, adjustment.adjusted);
         }
         
         // Process maturity
@@ -193,6 +342,8 @@ impl YieldSchedulerService {
                             treasury_id,
                             token_address: treasury_info.token_address,
                             maturity_date: treasury_info.maturity_date,
+                            settlement_date,
+                            settlement_adjustment: settlement_adjustment.clone(),
                             processed_at: now,
                             success: true,
                             error_message: None,
@@ -206,6 +357,8 @@ impl YieldSchedulerService {
                             treasury_id,
                             token_address: treasury_info.token_address,
                             maturity_date: treasury_info.maturity_date,
+                            settlement_date,
+                            settlement_adjustment: settlement_adjustment.clone(),
                             processed_at: now,
                             success: false,
                             error_message: Some(error_msg),
@@ -221,6 +374,8 @@ impl YieldSchedulerService {
                     treasury_id,
                     token_address: treasury_info.token_address,
                     maturity_date: treasury_info.maturity_date,
+                    settlement_date,
+                    settlement_adjustment,
                     processed_at: now,
                     success: false,
                     error_message: Some(error_msg),
@@ -231,12 +386,21 @@ impl YieldSchedulerService {
         Ok(result)
     }
     
-    /// Check and distribute yields for all eligible treasuries
-    pub async fn check_and_distribute_yields(&self) -> Result<Vec<YieldDistributionResult>, ServiceError> {
-        info!("Checking and distributing yields for eligible treasuries");
+    /// Active treasuries whose next yield distribution is due, without distributing
+    pub async fn due_yield_distributions(&self) -> Result<Vec<([u8; 32], TreasuryInfo)>, ServiceError> {
+        Ok(self.due_distributions().await?
+            .into_iter()
+            .map(|due| (due.treasury_id, due.treasury_info))
+            .collect())
+    }
+    
+    /// Due treasuries; a due date on a non-business day moves by the calendar's convention,
+    /// so a run can fall due early (preceding) or wait for the next business day
+    async fn due_distributions(&self) -> Result<Vec<DueDistribution>, ServiceError> {
+        const DISTRIBUTION_INTERVAL: u64 = 30 * 24 * 60 * 60; // 30 days in seconds
         
         let now = Utc::now().timestamp() as u64;
-        let mut results = Vec::new();
+        let mut due = Vec::new();
         
         // Get all active treasuries
         let active_treasuries = self.registry_client.get_treasuries_by_status(TreasuryStatus::Active).await?;
@@ -251,10 +415,6 @@ impl YieldSchedulerService {
                 }
             };
             
-            // Process treasuries that need yield distribution
-            // In a real implementation, we would check the last distribution time and yield schedule
-            // For now, we'll use a simplified approach for demonstration
-            
             // Get token client
             let token_client = match self.get_token_client(treasury_info.token_address).await {
                 Ok(client) => client,
@@ -274,33 +434,59 @@ impl YieldSchedulerService {
                 }
             };
             
-            const DISTRIBUTION_INTERVAL: u64 = 30 * 24 * 60 * 60; // 30 days in seconds
-            
-            if last_distribution == 0 || now - last_distribution >= DISTRIBUTION_INTERVAL {
-                // Distribute yield
-                match self.distribute_yield(treasury_id).await {
-                    Ok(result) => {
-                        if result.success {
-                            info!("Successfully distributed yield for treasury {:?}", treasury_id);
-                        } else {
-                            warn!("Failed to distribute yield for treasury {:?}: {:?}", 
-                                 treasury_id, result.error_message);
-                        }
-                        results.push(result);
-                    },
-                    Err(e) => {
-                        warn!("Error distributing yield for treasury {:?}: {}", treasury_id, e);
-                        // Add failed result
-                        results.push(YieldDistributionResult {
-                            treasury_id,
-                            token_address: treasury_info.token_address,
-                            distribution_id: 0,
-                            amount: U256::from(0),
-                            distribution_time: now,
-                            success: false,
-                            error_message: Some(format!("Failed to distribute yield: {}", e)),
-                        });
+            if last_distribution == 0 {
+                due.push(DueDistribution { treasury_id, treasury_info, adjustment: None });
+                continue;
+            }
+            let (due_at, adjustment) = match self.business_day(last_distribution + DISTRIBUTION_INTERVAL) {
+                Ok(due_at) => due_at,
+                Err(e) => {
+                    warn!("Failed to adjust distribution date for treasury {:?}: {}", treasury_id, e);
+                    continue;
+                }
+            };
+            if now >= due_at {
+                due.push(DueDistribution { treasury_id, treasury_info, adjustment });
+            }
+        }
+        
+        Ok(due)
+    }
+    
+    /// Check and distribute yields for all eligible treasuries
+    pub async fn check_and_distribute_yields(&self) -> Result<Vec<YieldDistributionResult>, ServiceError> {
+        info!("Checking and distributing yields for eligible treasuries");
+        
+        let now = Utc::now().timestamp() as u64;
+        let mut results = Vec::new();
+        
+        for DueDistribution { treasury_id, treasury_info, adjustment } in self.due_distributions().await? {
+            // Distribute yield
+            match self.distribute_yield(treasury_id).await {
+                Ok(mut result) => {
+                    result.schedule_adjustment = adjustment;
+                    if result.success {
+                        info!("Successfully distributed yield for treasury {:?}", treasury_id);
+                    } else {
+                        warn!("Failed to distribute yield for treasury {:?}: {:?}", 
+                             treasury_id, result.error_message);
                     }
+                    results.push(result);
+                },
+                Err(e) => {
+                    warn!("Error distributing yield for treasury {:?}: {}", treasury_id, e);
+                    // Add failed result
+                    results.push(YieldDistributionResult {
+                        treasury_id,
+                        token_address: treasury_info.token_address,
+                        distribution_id: 0,
+                        amount: U256::from(0),
+                        withheld: U256::ZERO,
+                        distribution_time: now,
+                        success: false,
+                        error_message: Some(format!("Failed to distribute yield: {}", e)),
+                        schedule_adjustment: adjustment,
+                    });
                 }
             }
         }
@@ -328,8 +514,16 @@ impl YieldSchedulerService {
                 }
             };
             
+            let (settlement_date, settlement_adjustment) = match self.business_day(treasury_info.maturity_date) {
+                Ok(settlement) => settlement,
+                Err(e) => {
+                    warn!("Failed to adjust maturity date of treasury {:?}: {}", treasury_id, e);
+                    continue;
+                }
+            };
+            
             // Check if matured
-            if now >= treasury_info.maturity_date {
+            if now >= settlement_date {
                 // Process maturity
                 match self.process_maturity(treasury_id).await {
                     Ok(result) => {
@@ -348,6 +542,8 @@ impl YieldSchedulerService {
                             treasury_id,
                             token_address: treasury_info.token_address,
                             maturity_date: treasury_info.maturity_date,
+                            settlement_date,
+                            settlement_adjustment,
                             processed_at: now,
                             success: false,
                             error_message: Some(format!("Failed to process maturity: {}", e)),
@@ -453,6 +649,10 @@ impl YieldSchedulerService {
             ethereum_client,
             scheduler_handle: None,
             running: true,
+            fees: self.fees.clone(),
+            withholding: self.withholding.clone(),
+            auto_compound: self.auto_compound.clone(),
+            calendar: self.calendar.clone(),
         };
         
         // Spawn the scheduler task
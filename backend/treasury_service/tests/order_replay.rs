@@ -0,0 +1,195 @@
+// Order ledger replay against a seeded harness chain history
+use alloy_primitives::{Address, U256};
+use ethereum_client::EthereumClient;
+use quantera_secrets::{EnvProvider, SecretRef};
+use quantera_test_harness::abi;
+use quantera_test_harness::{MockChain, MockLog};
+use quantera_types::{FinalityPolicy, FinalityRule};
+use std::sync::Arc;
+use treasury_service::{
+    ContractOrderEventSource,
+    ContractSettlementChain,
+    Error,
+    LocalOrder,
+    LocalOrderStatus,
+    MemoryCheckpointStore,
+    MemoryCursorStore,
+    OrderLedger,
+    OrderReconciler,
+    OrderReplayer,
+    ReconciliationConfig,
+    ReplayConfig,
+    ReplayRequest,
+    SettlementConfig,
+    SettlementEngine,
+};
+
+/// The trading contract and the one it replaced, which issued the lower order ids
+const TRADING: Address = Address::new([0x7d; 20]);
+const PREVIOUS_TRADING: Address = Address::new([0x7c; 20]);
+const TOKEN_ID: [u8; 32] = [7u8; 32];
+
+async fn ethereum_client(chain: &MockChain) -> Arc<EthereumClient> {
+    std::env::set_var("E2E_SIGNER_KEY", "0x0000000000000000000000000000000000000000000000000000000000000001");
+    let client = EthereumClient::new(&chain.url(), &SecretRef::new("E2E_SIGNER_KEY"), &EnvProvider, chain.chain_id())
+        .await
+        .expect("client connects to the mock chain");
+    Arc::new(client.with_finality_policy(FinalityPolicy {
+        confirmed: FinalityRule::Depth(1),
+        finalized: FinalityRule::Depth(1),
+    }))
+}
+
+fn trader(order_id: u64) -> Address {
+    Address::repeat_byte(order_id as u8)
+}
+
+fn placed(contract: Address, order_id: u64) -> MockLog {
+    MockLog {
+        address: contract,
+        topics: vec![
+            abi::event_topic("OrderPlaced(uint256,address,bytes32,uint256)"),
+            abi::word(U256::from(order_id)).into(),
+            abi::address_topic(trader(order_id)),
+            TOKEN_ID.into(),
+        ],
+        data: abi::word(U256::from(10u64)).to_vec(),
+    }
+}
+
+fn filled(contract: Address, order_id: u64, trade_id: u64, quantity: u64) -> MockLog {
+    MockLog {
+        address: contract,
+        topics: vec![
+            abi::event_topic("OrderFilled(uint256,uint256,uint256)"),
+            abi::word(U256::from(order_id)).into(),
+            abi::word(U256::from(trade_id)).into(),
+        ],
+        data: abi::word(U256::from(quantity)).to_vec(),
+    }
+}
+
+fn cancelled(contract: Address, order_id: u64) -> MockLog {
+    MockLog {
+        address: contract,
+        topics: vec![abi::event_topic("OrderCancelled(uint256)"), abi::word(U256::from(order_id)).into()],
+        data: Vec::new(),
+    }
+}
+
+/// Twelve orders across both contracts: every third cancelled, the rest filled in full or in part
+fn seed_history(chain: &MockChain) {
+    for order_id in 1..=12u64 {
+        let contract = if order_id <= 6 { PREVIOUS_TRADING } else { TRADING };
+        chain.mine_logs(vec![placed(contract, order_id)]);
+        chain.mine(2);
+        match order_id % 3 {
+            0 => chain.mine_logs(vec![cancelled(contract, order_id)]),
+            1 => chain.mine_logs(vec![filled(contract, order_id, order_id * 100, 10)]),
+            _ => chain.mine_logs(vec![filled(contract, order_id, order_id * 100, 4)]),
+        };
+    }
+}
+
+#[tokio::test]
+async fn test_replayed_ledger_matches_live_sync_and_is_swapped_in() {
+    let chain = MockChain::start().await.unwrap();
+    let client = ethereum_client(&chain).await;
+    seed_history(&chain);
+
+    // The live ledger as the service keeps it: orders recorded on submission, then reconciled
+    let ledger = Arc::new(OrderLedger::default());
+    for order_id in 1..=12u64 {
+        ledger.record(LocalOrder::pending(order_id, trader(order_id), TOKEN_ID, U256::from(10u64))).await;
+    }
+    let settlements = Arc::new(SettlementEngine::new(
+        Arc::new(ContractSettlementChain::new(client.clone(), Address::repeat_byte(0x5e), Address::repeat_byte(0x5c))),
+        SettlementConfig::default(),
+    ));
+    let reconciler = Arc::new(OrderReconciler::new(
+        Arc::new(ContractOrderEventSource::for_contracts(client.clone(), vec![PREVIOUS_TRADING, TRADING])),
+        ledger.clone(),
+        settlements,
+        Arc::new(MemoryCursorStore::default()),
+        ReconciliationConfig::default(),
+    ));
+    reconciler.run().await.unwrap();
+    let live = ledger.list().await;
+    // Submitted but not yet on chain, so nothing in the history rebuilds it
+    ledger.record(LocalOrder::pending(13, trader(13), TOKEN_ID, U256::from(10u64))).await;
+
+    let replayer = OrderReplayer::new(
+        client,
+        TRADING,
+        reconciler.clone(),
+        Arc::new(MemoryCheckpointStore::default()),
+        ReplayConfig { max_block_range: 5, ..ReplayConfig::default() },
+    );
+    let request = ReplayRequest {
+        contracts: vec![PREVIOUS_TRADING, TRADING],
+        from_block: 0,
+        to_block: Some(chain.head() - 1),
+        swap: true,
+        max_delta_percent: None,
+    };
+    let err = replayer.run(request.clone()).await.unwrap_err();
+    assert!(matches!(err, Error::InvalidParameter(_)), "{:?}", err);
+
+    let report = replayer.run(ReplayRequest { to_block: None, ..request }).await.unwrap();
+    assert!(report.validation.passed, "{:?}", report.validation);
+    assert!(report.swapped);
+    assert_eq!((report.events_applied, report.conflicts, report.pending_carried), (24, 0, 1));
+    assert_eq!(report.validation.mismatches.len(), 0);
+
+    for order in &live {
+        let rebuilt = ledger.get(order.order_id).await.unwrap();
+        assert_eq!(
+            (rebuilt.status, rebuilt.filled_quantity, &rebuilt.trade_ids, rebuilt.created_at),
+            (order.status, order.filled_quantity, &order.trade_ids, order.created_at),
+        );
+    }
+    assert_eq!(ledger.get(3).await.unwrap().status, LocalOrderStatus::Cancelled);
+    assert_eq!(ledger.get(8).await.unwrap().status, LocalOrderStatus::PartiallyFilled);
+    assert_eq!(ledger.get(13).await.unwrap().status, LocalOrderStatus::Pending);
+
+    // Reconciliation continues after the replayed range instead of reading it again
+    assert_eq!(reconciler.run().await.unwrap().events_read, 0);
+}
+
+#[tokio::test]
+async fn test_replay_is_not_swapped_over_a_diverging_ledger() {
+    let chain = MockChain::start().await.unwrap();
+    let client = ethereum_client(&chain).await;
+    seed_history(&chain);
+
+    // A live ledger that lost half its orders and disagrees on the rest
+    let ledger = Arc::new(OrderLedger::default());
+    for order_id in 7..=12u64 {
+        let order = LocalOrder::pending(order_id, trader(order_id), TOKEN_ID, U256::from(10u64));
+        ledger.record(LocalOrder { status: LocalOrderStatus::Open, ..order }).await;
+    }
+    let settlements = Arc::new(SettlementEngine::new(
+        Arc::new(ContractSettlementChain::new(client.clone(), Address::repeat_byte(0x5e), Address::repeat_byte(0x5c))),
+        SettlementConfig::default(),
+    ));
+    let reconciler = Arc::new(OrderReconciler::new(
+        Arc::new(ContractOrderEventSource::new(client.clone(), TRADING)),
+        ledger.clone(),
+        settlements,
+        Arc::new(MemoryCursorStore::default()),
+        ReconciliationConfig::default(),
+    ));
+    let replayer = OrderReplayer::new(client, TRADING, reconciler, Arc::new(MemoryCheckpointStore::default()), ReplayConfig::default());
+
+    let report = replayer.run(ReplayRequest {
+        contracts: vec![PREVIOUS_TRADING, TRADING],
+        from_block: 0,
+        to_block: None,
+        swap: true,
+        max_delta_percent: None,
+    }).await.unwrap();
+    assert!(!report.validation.passed);
+    assert!(!report.swapped);
+    assert_eq!(ledger.list().await.len(), 6);
+    assert_eq!(ledger.get(7).await.unwrap().status, LocalOrderStatus::Open);
+}
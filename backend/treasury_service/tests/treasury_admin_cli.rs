@@ -0,0 +1,290 @@
+use alloy_primitives::{Address, U256};
+use async_trait::async_trait;
+use clap::Parser;
+use std::sync::Mutex;
+use treasury_service::{
+    admin_cli::{run, Cli, TreasuryAdmin, EXIT_FAILURE, EXIT_SUCCESS, EXIT_USAGE},
+    DistributionPreview,
+    Error,
+    HolderWithholding,
+    MaturityResult,
+    RateBasis,
+    TaxDocument,
+    TreasuryInfo,
+    TreasuryOverview,
+    TreasuryRegistration,
+    TreasuryStatus,
+    TreasuryType,
+    YieldDistributionResult,
+};
+
+/// Records every operation and answers from canned state
+#[derive(Default)]
+struct MockAdmin {
+    calls: Mutex<Vec<String>>,
+    treasuries: Vec<TreasuryOverview>,
+    registry_ids: Vec<[u8; 32]>,
+    taken_ids: Vec<[u8; 32]>,
+    failing_distribution: bool,
+}
+
+impl MockAdmin {
+    fn record(&self, call: &str) {
+        self.calls.lock().unwrap().push(call.to_string());
+    }
+
+    fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl TreasuryAdmin for MockAdmin {
+    async fn register_treasuries(&self, rows: &[TreasuryRegistration]) -> Vec<Result<[u8; 32], Error>> {
+        self.record("register_treasuries");
+        rows.iter()
+            .map(|row| {
+                let token_id = row.token_id();
+                if self.taken_ids.contains(&token_id) {
+                    Err(Error::InvalidState("token id already registered".into()))
+                } else {
+                    Ok(token_id)
+                }
+            })
+            .collect()
+    }
+
+    async fn update_price(&self, _token_id: [u8; 32], price: U256) -> Result<(), Error> {
+        self.record(&format!("update_price {}", price));
+        Ok(())
+    }
+
+    async fn update_status(&self, _token_id: [u8; 32], status: TreasuryStatus) -> Result<(), Error> {
+        self.record(&format!("update_status {:?}", status));
+        Ok(())
+    }
+
+    async fn list_treasuries(&self) -> Result<Vec<TreasuryOverview>, Error> {
+        Ok(self.treasuries.clone())
+    }
+
+    async fn registry_token_ids(&self) -> Result<Vec<[u8; 32]>, Error> {
+        Ok(self.registry_ids.clone())
+    }
+
+    async fn run_maturities(&self) -> Result<Vec<MaturityResult>, Error> {
+        self.record("run_maturities");
+        Ok(Vec::new())
+    }
+
+    async fn due_yield_distributions(&self) -> Result<Vec<([u8; 32], TreasuryInfo)>, Error> {
+        Ok(vec![([1u8; 32], info(TreasuryStatus::Active))])
+    }
+
+    async fn preview_yield_distribution(&self, token_id: [u8; 32]) -> Result<DistributionPreview, Error> {
+        let holder = |byte: u8, rate_bps: u32, basis: RateBasis, missing_documents: Vec<TaxDocument>| HolderWithholding {
+            wallet: Address::repeat_byte(byte),
+            jurisdiction: Some("DE".into()),
+            gross: U256::from(1_000u64),
+            rate_bps,
+            basis,
+            withheld: U256::from(rate_bps / 10),
+            net: U256::from(1_000 - rate_bps / 10),
+            missing_documents,
+        };
+        Ok(DistributionPreview {
+            treasury_id: token_id,
+            token_address: Address::ZERO,
+            yield_rate: 450,
+            amount: U256::from(2_000u64),
+            holders: vec![
+                holder(0xaa, 1_500, RateBasis::Treaty, Vec::new()),
+                holder(0xbb, 3_000, RateBasis::Default, vec![TaxDocument::W8Ben]),
+            ],
+        })
+    }
+
+    async fn run_yield_distribution(&self) -> Result<Vec<YieldDistributionResult>, Error> {
+        self.record("run_yield_distribution");
+        Ok(vec![YieldDistributionResult {
+            treasury_id: [1u8; 32],
+            token_address: Address::ZERO,
+            distribution_id: 0,
+            amount: U256::ZERO,
+            withheld: U256::ZERO,
+            distribution_time: 1_700_000_000,
+            success: !self.failing_distribution,
+            error_message: self.failing_distribution.then(|| "transfer reverted".to_string()),
+            schedule_adjustment: None,
+        }])
+    }
+}
+
+fn info(status: TreasuryStatus) -> TreasuryInfo {
+    TreasuryInfo {
+        token_address: Address::ZERO,
+        metadata_uri: "ipfs://metadata".into(),
+        status,
+        current_price: U256::from(1000),
+        issuance_date: 1_700_000_000,
+        maturity_date: 1_800_000_000,
+        yield_rate: 450,
+        issuer: Address::ZERO,
+        historical_data_hash: Default::default(),
+    }
+}
+
+fn overview(byte: u8, status: TreasuryStatus, treasury_type: TreasuryType) -> TreasuryOverview {
+    TreasuryOverview {
+        token_id: [byte; 32],
+        token_address: Address::ZERO,
+        name: format!("Treasury {}", byte),
+        symbol: format!("T{}", byte),
+        treasury_type,
+        current_price: U256::from(1000),
+        yield_rate: 450,
+        maturity_date: 1_800_000_000,
+        status,
+        validation_error: None,
+    }
+}
+
+fn token_id_hex(byte: u8) -> String {
+    format!("0x{}", hex::encode([byte; 32]))
+}
+
+async fn invoke(admin: &MockAdmin, args: &[&str]) -> (i32, String, String) {
+    let cli = Cli::try_parse_from(std::iter::once("treasury_admin").chain(args.iter().copied()))
+        .expect("valid arguments");
+    let mut out = Vec::new();
+    let mut err = Vec::new();
+    let code = run(cli, admin, &mut out, &mut err).await;
+    (code, String::from_utf8(out).unwrap(), String::from_utf8(err).unwrap())
+}
+
+#[tokio::test]
+async fn test_destructive_commands_require_yes() {
+    let admin = MockAdmin::default();
+    let token_id = token_id_hex(1);
+
+    let (code, _, err) = invoke(&admin, &["update-price", &token_id, "1010"]).await;
+    assert_eq!(code, EXIT_USAGE);
+    assert!(err.contains("--yes"));
+
+    let (code, _, _) = invoke(&admin, &["update-status", &token_id, "matured"]).await;
+    assert_eq!(code, EXIT_USAGE);
+
+    let (code, _, _) = invoke(&admin, &["run-maturities"]).await;
+    assert_eq!(code, EXIT_USAGE);
+
+    let (code, _, _) = invoke(&admin, &["run-yield-distribution"]).await;
+    assert_eq!(code, EXIT_USAGE);
+    assert!(admin.calls().is_empty());
+
+    let (code, out, _) = invoke(&admin, &["update-price", &token_id, "1010", "--yes"]).await;
+    assert_eq!(code, EXIT_SUCCESS);
+    assert!(out.contains("1010"));
+    assert_eq!(admin.calls(), vec!["update_price 1010"]);
+
+    // Invalid input is rejected before the confirmation check
+    let (code, _, err) = invoke(&admin, &["update-price", "0x1234", "1010", "--yes"]).await;
+    assert_eq!(code, EXIT_USAGE);
+    assert!(err.contains("Invalid token id"));
+}
+
+#[tokio::test]
+async fn test_list_treasuries_filters_and_json() {
+    let admin = MockAdmin {
+        treasuries: vec![
+            overview(1, TreasuryStatus::Active, TreasuryType::TBill),
+            overview(2, TreasuryStatus::Matured, TreasuryType::TBill),
+            overview(3, TreasuryStatus::Matured, TreasuryType::TBond),
+        ],
+        ..Default::default()
+    };
+
+    let (code, out, _) = invoke(&admin, &["list-treasuries", "--status", "matured", "--type", "tbill", "--json"]).await;
+    assert_eq!(code, EXIT_SUCCESS);
+    let listed: Vec<TreasuryOverview> = serde_json::from_str(&out).expect("json output");
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].token_id, [2u8; 32]);
+
+    let (code, out, _) = invoke(&admin, &["list-treasuries"]).await;
+    assert_eq!(code, EXIT_SUCCESS);
+    assert!(out.starts_with("TOKEN ID"));
+    assert_eq!(out.lines().count(), 4);
+}
+
+#[tokio::test]
+async fn test_yield_distribution_dry_run_and_failure_exit_code() {
+    let admin = MockAdmin { failing_distribution: true, ..Default::default() };
+
+    let (code, out, _) = invoke(&admin, &["run-yield-distribution", "--dry-run"]).await;
+    assert_eq!(code, EXIT_SUCCESS);
+    assert!(out.contains(&token_id_hex(1)));
+    assert!(out.contains("WITHHELD") && out.contains("W-8BEN"));
+    assert!(admin.calls().is_empty());
+
+    let (_, out, _) = invoke(&admin, &["--json", "run-yield-distribution", "--dry-run"]).await;
+    let due: serde_json::Value = serde_json::from_str(&out).expect("json output");
+    assert_eq!(due[0]["holders"][1]["withheld"], "300");
+    assert_eq!(due[0]["holders"][1]["net"], "700");
+    assert_eq!(due[0]["holders"][1]["basis"], "default");
+
+    let (code, out, _) = invoke(&admin, &["--json", "run-yield-distribution", "--yes"]).await;
+    assert_eq!(code, EXIT_FAILURE);
+    let results: serde_json::Value = serde_json::from_str(&out).expect("json output");
+    assert_eq!(results[0]["success"], false);
+    assert_eq!(admin.calls(), vec!["run_yield_distribution"]);
+}
+
+#[tokio::test]
+async fn test_register_treasury_from_file() {
+    let rows: Vec<TreasuryRegistration> = (1..=2u8)
+        .map(|n| TreasuryRegistration {
+            token_address: Address::repeat_byte(n),
+            metadata_uri: format!("ipfs://metadata-{}", n),
+            treasury_type: TreasuryType::TNote,
+            issuance_date: 1_700_000_000,
+            maturity_date: 1_900_000_000,
+            yield_rate: 400,
+            tranche: None,
+        })
+        .collect();
+    let admin = MockAdmin { taken_ids: vec![rows[1].token_id()], ..Default::default() };
+
+    let path = std::env::temp_dir().join(format!("treasury_admin_{}.json", uuid::Uuid::new_v4()));
+    std::fs::write(&path, serde_json::to_string(&rows).unwrap()).unwrap();
+    let file = path.to_str().unwrap();
+
+    let (code, _, _) = invoke(&admin, &["register-treasury", file]).await;
+    assert_eq!(code, EXIT_USAGE);
+
+    let (code, out, _) = invoke(&admin, &["register-treasury", file, "--yes", "--json"]).await;
+    std::fs::remove_file(&path).ok();
+
+    // One row collided, so the command fails while still reporting the row that succeeded
+    assert_eq!(code, EXIT_FAILURE);
+    let results: serde_json::Value = serde_json::from_str(&out).expect("json output");
+    assert_eq!(results[0]["token_id"], format!("0x{}", hex::encode(rows[0].token_id())));
+    assert!(results[1]["error"].is_string());
+}
+
+#[tokio::test]
+async fn test_resync_registry_reports_unlisted_entries() {
+    let mut quarantined = overview(2, TreasuryStatus::Active, TreasuryType::TBill);
+    quarantined.validation_error = Some("symbol is empty".into());
+    let admin = MockAdmin {
+        treasuries: vec![overview(1, TreasuryStatus::Active, TreasuryType::TBill), quarantined],
+        registry_ids: vec![[1u8; 32], [2u8; 32], [3u8; 32]],
+        ..Default::default()
+    };
+
+    let (code, out, _) = invoke(&admin, &["resync-registry", "--json"]).await;
+    assert_eq!(code, EXIT_FAILURE);
+    let report: serde_json::Value = serde_json::from_str(&out).expect("json output");
+    assert_eq!(report["registered"], 3);
+    assert_eq!(report["listed"], 2);
+    assert_eq!(report["quarantined"][0]["token_id"], token_id_hex(2));
+    assert_eq!(report["unreadable"][0], token_id_hex(3));
+}
@@ -0,0 +1,281 @@
+// Treasury lifecycle against the harness chain: create, list, price from an oracle, mature
+use alloy_primitives::{Address, U256};
+use ethereum_client::EthereumClient;
+use price_oracle::{AggregatorConfig, HttpPriceOracle, OracleAggregator};
+use quantera_secrets::{EnvProvider, SecretRef};
+use quantera_test_harness::abi::{self, AbiValue};
+use quantera_test_harness::{CallKind, CallOutcome, ContractCall, MockChain, PriceFeedFixture, Seeder};
+use quantera_types::{FinalityPolicy, FinalityRule};
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use treasury_service::{
+    ComplianceChecker,
+    Error,
+    IpfsClient,
+    TokenDeployer,
+    TreasuryPriceUpdater,
+    PriceUpdateOutcome,
+    TreasuryRegistryClient,
+    TreasuryService,
+    TreasuryStatus,
+    TreasuryType,
+};
+
+const REGISTRY: Address = Address::new([0x7e; 20]);
+const TOKEN: Address = Address::new([0x70; 20]);
+
+/// Registry entries as the contract stores them
+#[derive(Clone)]
+struct Entry {
+    token_address: Address,
+    metadata_uri: String,
+    treasury_type: u8,
+    status: u8,
+    price: U256,
+    issuance_date: U256,
+    maturity_date: U256,
+    yield_rate: U256,
+    issuer: Address,
+}
+
+#[derive(Default)]
+struct Registry {
+    entries: HashMap<[u8; 32], Entry>,
+    order: Vec<[u8; 32]>,
+}
+
+/// The `(address,string,uint8,uint256,uint64,uint64,uint64,address,bytes32)` tuple of an entry
+fn details(entry: Option<&Entry>) -> Vec<u8> {
+    let entry = entry.cloned().unwrap_or(Entry {
+        token_address: Address::ZERO,
+        metadata_uri: String::new(),
+        treasury_type: 0,
+        status: 0,
+        price: U256::ZERO,
+        issuance_date: U256::ZERO,
+        maturity_date: U256::ZERO,
+        yield_rate: U256::ZERO,
+        issuer: Address::ZERO,
+    });
+    abi::encode(&[
+        AbiValue::Address(entry.token_address),
+        AbiValue::String(entry.metadata_uri),
+        AbiValue::Uint(U256::from(entry.status)),
+        AbiValue::Uint(entry.price),
+        AbiValue::Uint(entry.issuance_date),
+        AbiValue::Uint(entry.maturity_date),
+        AbiValue::Uint(entry.yield_rate),
+        AbiValue::Address(entry.issuer),
+        AbiValue::FixedBytes([0; 32]),
+    ])
+}
+
+/// A string argument whose offset is in word `index`
+fn string_arg(call: &ContractCall, index: usize) -> String {
+    let offset = call.uint(index).unwrap_or_default().to::<usize>();
+    let start = 4 + offset + 32;
+    let length = U256::from_be_slice(&call.input[4 + offset..start]).to::<usize>();
+    String::from_utf8_lossy(&call.input[start..start + length]).into_owned()
+}
+
+fn address_arg(call: &ContractCall, index: usize) -> Address {
+    Address::from_slice(&call.word(index).unwrap_or_default()[12..])
+}
+
+/// Program the TreasuryRegistry contract; writes only take effect when mined
+fn deploy_registry(chain: &MockChain) -> Arc<Mutex<Registry>> {
+    let registry = Arc::new(Mutex::new(Registry::default()));
+
+    let state = registry.clone();
+    chain.on_call(REGISTRY, "treasuries(bytes32)", move |call| {
+        CallOutcome::Return(details(state.lock().unwrap().entries.get(&call.word(0).unwrap_or_default())))
+    });
+    let state = registry.clone();
+    chain.on_call(REGISTRY, "getTreasuryDetails(bytes32)", move |call| {
+        match state.lock().unwrap().entries.get(&call.word(0).unwrap_or_default()) {
+            Some(entry) => CallOutcome::Return(details(Some(entry))),
+            None => CallOutcome::Revert("unknown treasury".to_string()),
+        }
+    });
+    let state = registry.clone();
+    chain.on_call(REGISTRY, "getAllTreasuries()", move |_| {
+        let ids = state.lock().unwrap().order.iter().map(|id| AbiValue::FixedBytes(*id)).collect();
+        CallOutcome::Return(abi::encode(&[AbiValue::Array(ids)]))
+    });
+
+    // The service registers through call_contract, so record the entry however it arrives
+    let state = registry.clone();
+    chain.on_call(REGISTRY, "registerTreasury(address,bytes32,string,uint8,uint256,uint256,uint256)", move |call| {
+        let token_id = call.word(1).unwrap_or_default();
+        let mut registry = state.lock().unwrap();
+        if registry.entries.contains_key(&token_id) {
+            return CallOutcome::Revert("treasury already registered".to_string());
+        }
+        registry.entries.insert(token_id, Entry {
+            token_address: address_arg(call, 0),
+            metadata_uri: string_arg(call, 2),
+            treasury_type: call.uint(3).unwrap_or_default().to::<u8>(),
+            status: 0,
+            price: U256::ZERO,
+            issuance_date: call.uint(4).unwrap_or_default(),
+            maturity_date: call.uint(5).unwrap_or_default(),
+            yield_rate: call.uint(6).unwrap_or_default(),
+            issuer: call.from.unwrap_or_default(),
+        });
+        registry.order.push(token_id);
+        CallOutcome::Return(token_id.to_vec())
+    });
+
+    let state = registry.clone();
+    chain.on_call(REGISTRY, "updateTreasuryPrice(bytes32,uint256)", move |call| {
+        let mut registry = state.lock().unwrap();
+        let Some(entry) = registry.entries.get_mut(&call.word(0).unwrap_or_default()) else {
+            return CallOutcome::Revert("unknown treasury".to_string());
+        };
+        if entry.status != 0 {
+            return CallOutcome::Revert("treasury not active".to_string());
+        }
+        if call.kind == CallKind::Transaction {
+            entry.price = call.uint(1).unwrap_or_default();
+        }
+        CallOutcome::Return(Vec::new())
+    });
+    let state = registry.clone();
+    chain.on_call(REGISTRY, "updateTreasuryStatus(bytes32,uint8)", move |call| {
+        let mut registry = state.lock().unwrap();
+        let Some(entry) = registry.entries.get_mut(&call.word(0).unwrap_or_default()) else {
+            return CallOutcome::Revert("unknown treasury".to_string());
+        };
+        let status = call.uint(1).unwrap_or_default().to::<u8>();
+        if status != entry.status + 1 {
+            return CallOutcome::Revert("invalid status transition".to_string());
+        }
+        if call.kind == CallKind::Transaction {
+            entry.status = status;
+        }
+        CallOutcome::Return(Vec::new())
+    });
+
+    registry
+}
+
+struct FixedDeployer;
+
+impl TokenDeployer for FixedDeployer {
+    fn deploy_token(&self, _name: &str, _symbol: &str, _total_supply: u64, _issuer: Address) -> Result<Address, Error> {
+        Ok(TOKEN)
+    }
+}
+
+struct AllowAll;
+
+impl ComplianceChecker for AllowAll {
+    fn is_compliant(&self, _issuer: Address) -> Result<bool, Error> {
+        Ok(true)
+    }
+}
+
+async fn ethereum_client(chain: &MockChain) -> Arc<EthereumClient> {
+    std::env::set_var("E2E_SIGNER_KEY", "0x0000000000000000000000000000000000000000000000000000000000000001");
+    let client = EthereumClient::new(&chain.url(), &SecretRef::new("E2E_SIGNER_KEY"), &EnvProvider, chain.chain_id())
+        .await
+        .expect("client connects to the mock chain");
+    // Blocks are only mined on demand, so one confirmation is all a test can wait for
+    Arc::new(client.with_finality_policy(FinalityPolicy {
+        confirmed: FinalityRule::Depth(1),
+        finalized: FinalityRule::Depth(1),
+    }))
+}
+
+#[tokio::test]
+async fn test_treasury_created_priced_and_matured() {
+    let chain = MockChain::start().await.unwrap();
+    let registry = deploy_registry(&chain);
+    let feed = PriceFeedFixture::start().await;
+    let treasury = Seeder::new(1503).treasury(TreasuryType::TBill);
+
+    let client = ethereum_client(&chain).await;
+    let registry_client = TreasuryRegistryClient::new(client, REGISTRY).await;
+    let service = Arc::new(TreasuryService::new(
+        registry_client,
+        IpfsClient::new("http://127.0.0.1:5001"),
+        Box::new(FixedDeployer),
+        Box::new(AllowAll),
+    ).await);
+
+    // Create
+    let created = service.create_treasury_token(
+        treasury.name.clone(),
+        treasury.symbol.clone(),
+        1_000_000,
+        treasury.treasury_type,
+        treasury.face_value,
+        treasury.currency,
+        treasury.yield_rate,
+        treasury.issuance_date,
+        treasury.maturity_date,
+        treasury.issuer,
+        None,
+    ).await.unwrap();
+    assert_eq!(created.token_address, TOKEN);
+    assert_eq!(registry.lock().unwrap().order, vec![created.token_id]);
+
+    // List
+    let listed = service.get_all_treasuries().await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].token_id, created.token_id);
+    assert_eq!(listed[0].status, TreasuryStatus::Active);
+    assert_eq!(listed[0].maturity_date, treasury.maturity_date);
+
+    // Price from the oracle, keyed by token id
+    feed.set_price(&format!("0x{}", hex::encode(created.token_id)), dec!(987.65));
+    let oracle = HttpPriceOracle::new("e2e-feed", &feed.uri(), Duration::from_secs(5)).unwrap();
+    let aggregator = Arc::new(OracleAggregator::new(vec![Arc::new(oracle)], AggregatorConfig::default()));
+    let results = TreasuryPriceUpdater::new(aggregator, service.clone(), 2).update_all().await.unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].outcome, PriceUpdateOutcome::Updated { old_price: U256::ZERO, new_price: U256::from(98_765) });
+    assert_eq!(chain.transactions_to(REGISTRY, "updateTreasuryPrice(bytes32,uint256)").len(), 1);
+    assert_eq!(service.get_treasury_details(created.token_id).await.unwrap().current_price, U256::from(98_765));
+
+    // Mature; matured treasuries are no longer priced
+    let report = service.bulk_update_status(&[created.token_id], TreasuryStatus::Matured, false).await.unwrap();
+    assert!(report.failed_ids().is_empty());
+    let listed = service.get_all_treasuries().await.unwrap();
+    assert_eq!(listed[0].status, TreasuryStatus::Matured);
+    let results = TreasuryPriceUpdater::new(
+        Arc::new(OracleAggregator::new(Vec::new(), AggregatorConfig::default())),
+        service.clone(),
+        2,
+    ).update_all().await.unwrap();
+    assert!(results.is_empty());
+
+    // Every broadcast was simulated first and mined successfully
+    assert!(chain.transactions().iter().all(|tx| tx.succeeded && tx.block_number.is_some()));
+}
+
+#[tokio::test]
+async fn test_reverting_status_change_is_not_broadcast() {
+    let chain = MockChain::start().await.unwrap();
+    let registry = deploy_registry(&chain);
+    let treasury = Seeder::new(1503).treasury(TreasuryType::TNote);
+
+    let registry_client = TreasuryRegistryClient::new(ethereum_client(&chain).await, REGISTRY).await;
+    let token_id = registry_client.register_treasury(
+        TOKEN,
+        "ipfs://Qme2e",
+        treasury.treasury_type,
+        treasury.issuance_date,
+        treasury.maturity_date,
+        treasury.yield_rate,
+        None,
+    ).await.unwrap();
+    assert_eq!(registry.lock().unwrap().entries[&token_id].treasury_type, 1);
+
+    // Active cannot jump straight to Redeemed; the simulation catches it
+    let err = registry_client.update_treasury_status(token_id, TreasuryStatus::Redeemed).await.unwrap_err();
+    assert!(err.to_string().contains("invalid status transition"), "{}", err);
+    assert_eq!(registry_client.prevented_reverts(), 1);
+    assert!(chain.transactions().is_empty());
+}
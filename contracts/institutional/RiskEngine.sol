@@ -56,8 +56,17 @@ contract RiskEngine is AccessControl, Pausable, ReentrancyGuard {
         uint256 lastUpdate;         // Last update timestamp
     }
     
+    // Off-chain risk assessment published by the risk service
+    struct RiskAttestation {
+        uint8 grade;                // 0 = A ... 4 = F
+        uint32 var95Bps;            // VaR at 95% (basis points)
+        uint64 calculatedAt;        // When the off-chain calculation ran
+        uint64 publishedAt;
+    }
+    
     // State variables
     mapping(address => RiskMetrics) public portfolioRiskMetrics;
+    mapping(address => RiskAttestation) public riskAttestations;
     mapping(address => RiskLimits) public portfolioRiskLimits;
     mapping(address => HistoricalData) private historicalData;
     mapping(address => mapping(address => uint256)) public assetPrices; // portfolio => asset => price
@@ -105,6 +114,13 @@ contract RiskEngine is AccessControl, Pausable, ReentrancyGuard {
         address indexed priceFeed
     );
     
+    event RiskAttestationPublished(
+        address indexed portfolio,
+        uint8 grade,
+        uint32 var95Bps,
+        uint64 calculatedAt
+    );
+    
     // Custom errors for gas efficiency
     error InsufficientDataPoints();
     error StalePrice(address asset);
@@ -112,6 +128,8 @@ contract RiskEngine is AccessControl, Pausable, ReentrancyGuard {
     error InvalidConfidenceLevel();
     error PortfolioShutdown();
     error InvalidPriceFeed();
+    error InvalidRiskGrade(uint8 grade);
+    error StaleAttestation(uint64 calculatedAt, uint64 latest);
     
     constructor() {
         _grantRole(DEFAULT_ADMIN_ROLE, msg.sender);
@@ -317,6 +335,34 @@ contract RiskEngine is AccessControl, Pausable, ReentrancyGuard {
         emit PriceFeedUpdated(asset, priceFeed);
     }
     
+    /**
+     * @dev Publish the off-chain risk grade of a portfolio
+     * @param portfolio Portfolio address
+     * @param grade Risk grade, 0 (A) to 4 (F)
+     * @param var95Bps VaR at 95% confidence in basis points
+     * @param calculatedAt Time of the off-chain calculation; must be newer than the current attestation
+     */
+    function publishRiskAttestation(
+        address portfolio,
+        uint8 grade,
+        uint32 var95Bps,
+        uint64 calculatedAt
+    ) external onlyRole(RISK_MANAGER_ROLE) whenNotPaused {
+        if (grade > 4) revert InvalidRiskGrade(grade);
+        
+        RiskAttestation storage attestation = riskAttestations[portfolio];
+        if (calculatedAt <= attestation.calculatedAt) {
+            revert StaleAttestation(calculatedAt, attestation.calculatedAt);
+        }
+        
+        attestation.grade = grade;
+        attestation.var95Bps = var95Bps;
+        attestation.calculatedAt = calculatedAt;
+        attestation.publishedAt = uint64(block.timestamp);
+        
+        emit RiskAttestationPublished(portfolio, grade, var95Bps, calculatedAt);
+    }
+    
     /**
      * @dev Emergency shutdown for a portfolio
      * @param portfolio Portfolio address
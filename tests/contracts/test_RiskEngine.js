@@ -190,6 +190,38 @@ describe("RiskEngine Contract Tests", function () {
     });
   });
   
+  describe("Risk Attestations", function () {
+    it("Should publish and read back a risk grade", async function () {
+      await expect(
+        riskEngine.connect(riskManager).publishRiskAttestation(testPortfolio, 2, 450, 1700000000)
+      ).to.emit(riskEngine, "RiskAttestationPublished")
+        .withArgs(testPortfolio, 2, 450, 1700000000);
+      
+      const attestation = await riskEngine.riskAttestations(testPortfolio);
+      expect(attestation.grade).to.equal(2);
+      expect(attestation.var95Bps).to.equal(450);
+      expect(attestation.calculatedAt).to.equal(1700000000);
+      expect(attestation.publishedAt).to.be.gt(0);
+    });
+    
+    it("Should reject stale attestations and invalid grades", async function () {
+      await riskEngine.connect(riskManager).publishRiskAttestation(testPortfolio, 1, 300, 1700000000);
+      
+      await expect(
+        riskEngine.connect(riskManager).publishRiskAttestation(testPortfolio, 0, 100, 1700000000)
+      ).to.be.revertedWithCustomError(riskEngine, "StaleAttestation");
+      await expect(
+        riskEngine.connect(riskManager).publishRiskAttestation(testPortfolio, 5, 100, 1700000001)
+      ).to.be.revertedWithCustomError(riskEngine, "InvalidRiskGrade");
+    });
+    
+    it("Should reject publishing without role", async function () {
+      await expect(
+        riskEngine.connect(user).publishRiskAttestation(testPortfolio, 0, 100, 1700000000)
+      ).to.be.revertedWith("AccessControl");
+    });
+  });
+  
   describe("Gas Optimization", function () {
     it("Should use custom errors for gas efficiency", async function () {
       // Custom errors use less gas than require statements with strings